@@ -54,21 +54,22 @@ impl RepositoryUrl {
     }
 
     fn cleaned_path(&self) -> String {
-        let path = self.inner.path.to_str_lossy().into_owned();
-        path.strip_suffix(".git").map(ToOwned::to_owned).unwrap_or(path)
+        let path = self.inner.path.to_str_lossy();
+        let path = path.strip_suffix(".git").unwrap_or(&path);
+        // paths of scp-like URLs like `git@github.com:org/repo.git` are relative
+        if path.starts_with('/') {
+            path.to_owned()
+        } else {
+            format!("/{}", path)
+        }
     }
 
     pub fn github_https(&self) -> Option<String> {
         match &self.inner.host {
             Some(host) if host == "github.com" => match self.inner.scheme {
-                Scheme::Http | Scheme::Https | Scheme::Git => {
+                Scheme::Http | Scheme::Https | Scheme::Git | Scheme::Ssh => {
                     format!("https://github.com{}", self.cleaned_path()).into()
                 }
-                Scheme::Ssh => self
-                    .inner
-                    .user
-                    .as_ref()
-                    .map(|user| format!("https://github.com{}/{}", user, self.cleaned_path())),
                 Scheme::Radicle | Scheme::File => None,
             },
             None | Some(_) => None,
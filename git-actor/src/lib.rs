@@ -44,6 +44,24 @@ pub struct SignatureRef<'a> {
     pub time: Time,
 }
 
+/// An immutable signature as found in an object, which isn't necessarily valid.
+///
+/// It keeps the raw bytes of the signature to serialize them exactly as they were found, and provides all parts
+/// that could be parsed. Use [`to_strict()`][LenientSignatureRef::to_strict()] to obtain a [`SignatureRef`] if it is valid.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct LenientSignatureRef<'a> {
+    /// The signature exactly as it was found, without trailing newline.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub raw: &'a BStr,
+    /// The actor's name, which is everything before the email without trailing whitespace.
+    pub name: &'a BStr,
+    /// The actor's email, or `None` if it isn't enclosed in `<>`.
+    pub email: Option<&'a BStr>,
+    /// The time stamp at which the signature was performed, or `None` if either the time or the timezone can't be parsed.
+    pub time: Option<Time>,
+}
+
 /// Indicates if a number is positive or negative for use in [`Time`].
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
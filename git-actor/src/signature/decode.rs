@@ -1,5 +1,5 @@
 use bstr::ByteSlice;
-use btoi::btoi;
+use btoi::{btoi, btou};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take, take_until, take_while_m_n},
@@ -9,7 +9,7 @@ use nom::{
     IResult,
 };
 
-use crate::{Sign, SignatureRef, Time};
+use crate::{LenientSignatureRef, Sign, SignatureRef, Time};
 
 const SPACE: &[u8] = b" ";
 
@@ -65,6 +65,67 @@ pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
     ))
 }
 
+/// Parse a signature from the bytes input `i` up to the next newline, accepting everything that can be found in
+/// real-world repositories like missing emails or bogus timezones like `+051800`.
+///
+/// This never fails, and what can't be parsed is absent in the returned signature whose raw bytes are kept
+/// to allow serializing it exactly as it was.
+pub fn decode_lenient<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], LenientSignatureRef<'a>, E> {
+    let end = i.find_byte(b'\n').unwrap_or(i.len());
+    let (raw, remaining) = i.split_at(end);
+
+    let (name, email, time) = match raw.find_byte(b'<') {
+        None => (raw.trim_end(), None, None),
+        Some(email_start) => {
+            let name = raw[..email_start].trim_end();
+            match raw[email_start + 1..].find_byte(b'>') {
+                None => (name, None, None),
+                Some(email_len) => {
+                    let email = &raw[email_start + 1..][..email_len];
+                    let time = lenient_time(&raw[email_start + 1 + email_len + 1..]);
+                    (name, Some(email.as_bstr()), time)
+                }
+            }
+        }
+    };
+
+    Ok((
+        remaining,
+        LenientSignatureRef {
+            raw: raw.as_bstr(),
+            name: name.as_bstr(),
+            email,
+            time,
+        },
+    ))
+}
+
+/// Parse `<timestamp> <+|-><HHMM>` preceded by any amount of spaces, or return `None` if it doesn't match exactly.
+fn lenient_time(i: &[u8]) -> Option<Time> {
+    let mut tokens = i.split_str(SPACE).filter(|t| !t.is_empty());
+    let seconds = btou::<u32>(tokens.next()?).ok()?;
+    let tz = tokens.next()?;
+    if tokens.next().is_some() || tz.len() != 5 || !tz[1..].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let sign = match tz[0] {
+        b'-' => Sign::Minus,
+        b'+' => Sign::Plus,
+        _ => return None,
+    };
+    let hours = btou::<i32>(&tz[1..3]).ok()?;
+    let minutes = btou::<i32>(&tz[3..]).ok()?;
+    if hours > 24 || minutes > 59 {
+        return None;
+    }
+    let offset = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+    Some(Time {
+        seconds_since_unix_epoch: seconds,
+        offset_in_seconds: offset,
+        sign,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     mod parse_signature {
@@ -1,7 +1,10 @@
 mod _ref {
     use bstr::ByteSlice;
 
-    use crate::{signature::decode, Signature, SignatureRef};
+    use crate::{
+        signature::{decode, decode_lenient},
+        LenientSignatureRef, Signature, SignatureRef,
+    };
 
     impl<'a> SignatureRef<'a> {
         /// Deserialize a signature from the given `data`.
@@ -12,6 +15,13 @@ mod _ref {
             decode(data).map(|(_, t)| t)
         }
 
+        /// Deserialize a signature from the given `data` without ever failing, see [`decode_lenient()`].
+        pub fn from_bytes_lenient(data: &'a [u8]) -> LenientSignatureRef<'a> {
+            decode_lenient::<()>(data)
+                .map(|(_, t)| t)
+                .expect("lenient decoding never fails")
+        }
+
         /// Create an owned instance from this shared one.
         pub fn to_owned(&self) -> Signature {
             Signature {
@@ -32,6 +42,37 @@ mod _ref {
     }
 }
 
+mod lenient {
+    use std::io;
+
+    use crate::{signature::decode, LenientSignatureRef, SignatureRef};
+
+    impl<'a> LenientSignatureRef<'a> {
+        /// Return the signature as [`SignatureRef`] if it would be accepted by the strict [`decode()`] as well.
+        pub fn to_strict(&self) -> Option<SignatureRef<'a>> {
+            match decode::<()>(self.raw) {
+                Ok((remaining, signature)) if remaining.is_empty() => Some(signature),
+                _ => None,
+            }
+        }
+
+        /// Return true if this signature would be accepted when parsing strictly.
+        pub fn is_valid(&self) -> bool {
+            self.to_strict().is_some()
+        }
+
+        /// Serialize this instance to `out` exactly as it was parsed.
+        pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+            out.write_all(self.raw)
+        }
+
+        /// Computes the number of bytes necessary to serialize this signature.
+        pub fn size(&self) -> usize {
+            self.raw.len()
+        }
+    }
+}
+
 mod convert {
     use crate::{Signature, SignatureRef};
 
@@ -198,4 +239,4 @@ mod init {
 
 ///
 mod decode;
-pub use decode::decode;
+pub use decode::{decode, decode_lenient};
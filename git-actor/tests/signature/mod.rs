@@ -77,3 +77,100 @@ fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+mod lenient {
+    use bstr::ByteSlice;
+    use git_actor::{Sign, SignatureRef, Time};
+
+    #[test]
+    fn valid_signatures_are_parsed_like_in_strict_mode() {
+        let input = b"Sebastian Thiel <byronimo@gmail.com> 1528473343 -0230";
+        let sig = SignatureRef::from_bytes_lenient(input);
+        let strict = SignatureRef::from_bytes::<()>(input).unwrap();
+        assert_eq!(sig.name, strict.name);
+        assert_eq!(sig.email, Some(strict.email));
+        assert_eq!(sig.time, Some(strict.time));
+        assert_eq!(sig.to_strict(), Some(strict));
+    }
+
+    #[test]
+    fn parsing_stops_at_the_newline() {
+        let (remaining, sig) = git_actor::signature::decode_lenient::<()>(b"name <email> 1 +0000\nnext").unwrap();
+        assert_eq!(remaining, b"\nnext");
+        assert_eq!(sig.raw, "name <email> 1 +0000");
+        assert!(sig.is_valid());
+    }
+
+    #[test]
+    fn bogus_timezone() {
+        let sig = SignatureRef::from_bytes_lenient(b"name <email> 1234 +051800");
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, Some("email".as_bytes().as_bstr()));
+        assert_eq!(sig.time, None, "the timezone can't be represented");
+        assert!(!sig.is_valid());
+        assert!(
+            SignatureRef::from_bytes::<()>(b"name <email> 1234 +051800")
+                .map(|_| ())
+                .is_ok(),
+            "strict parsing only looks at the first four digits of the timezone"
+        );
+    }
+
+    #[test]
+    fn missing_email() {
+        let sig = SignatureRef::from_bytes_lenient(b"name 1234 +0000");
+        assert_eq!(sig.name, "name 1234 +0000");
+        assert_eq!(sig.email, None);
+        assert_eq!(sig.time, None);
+        assert!(!sig.is_valid());
+        assert!(SignatureRef::from_bytes::<()>(b"name 1234 +0000").is_err());
+    }
+
+    #[test]
+    fn unterminated_email() {
+        let sig = SignatureRef::from_bytes_lenient(b"name <email 1234 +0000");
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, None);
+        assert_eq!(sig.time, None);
+    }
+
+    #[test]
+    fn missing_date() {
+        let sig = SignatureRef::from_bytes_lenient(b"name <email>");
+        assert_eq!(sig.email, Some("email".as_bytes().as_bstr()));
+        assert_eq!(sig.time, None);
+        assert!(SignatureRef::from_bytes::<()>(b"name <email>").is_err());
+    }
+
+    #[test]
+    fn extra_spaces_before_the_date() {
+        let sig = SignatureRef::from_bytes_lenient(b"name <email>  1234  -0100");
+        assert_eq!(
+            sig.time,
+            Some(Time {
+                seconds_since_unix_epoch: 1234,
+                offset_in_seconds: -3600,
+                sign: Sign::Minus
+            })
+        );
+        assert!(!sig.is_valid());
+    }
+
+    #[test]
+    fn non_utf8_names_and_broken_lines_round_trip() -> std::io::Result<()> {
+        for input in &[
+            &b"na\xffme <email> 1234 +0000"[..],
+            b"name <email> 1234 +051800",
+            b"name 1234 +0000",
+            b"  <> ",
+            b"",
+        ] {
+            let sig = SignatureRef::from_bytes_lenient(input);
+            let mut out = Vec::new();
+            sig.write_to(&mut out)?;
+            assert_eq!(out.as_bstr(), input.as_bstr());
+            assert_eq!(sig.size(), input.len());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! Decide whether content is binary, in the same way for diffing, merging and end-of-line conversion.
+//!
+//! Attributes take precedence over the content, which is binary if it is larger than a threshold or if it contains a null byte
+//! close to its beginning.
+use std::collections::BTreeMap;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::State;
+
+/// The amount of bytes at the beginning of content that are searched for null bytes, like git does.
+pub const FIRST_FEW_BYTES: usize = 8000;
+
+/// The size in bytes above which content is binary without looking at it, the default of `core.bigFileThreshold`.
+pub const DEFAULT_BIG_FILE_THRESHOLD: u64 = 512 * 1024 * 1024;
+
+/// Whether content is to be treated as text or as binary.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Class {
+    /// The content is text and can be diffed, merged and converted line by line.
+    Text,
+    /// The content is binary and must be treated as a whole.
+    Binary,
+}
+
+/// The operation content is classified for, each of which consults its own attribute first.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Purpose {
+    /// Diffing content, which consults the `diff` attribute before the `text` attribute.
+    Diff,
+    /// Merging content, which consults the `merge` attribute before the `text` attribute.
+    Merge,
+    /// Converting line endings, which only consults the `text` attribute.
+    Eol,
+}
+
+impl Purpose {
+    fn attribute(&self) -> Option<&'static str> {
+        match self {
+            Purpose::Diff => Some("diff"),
+            Purpose::Merge => Some("merge"),
+            Purpose::Eol => None,
+        }
+    }
+}
+
+/// Options for use in [`classify()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Options {
+    /// Content larger than this amount of bytes is binary no matter what it contains, like `core.bigFileThreshold` configures it.
+    pub big_file_threshold: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            big_file_threshold: DEFAULT_BIG_FILE_THRESHOLD,
+        }
+    }
+}
+
+/// Return true if `data` contains a null byte within its first [`FIRST_FEW_BYTES`], which is what git considers binary.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(FIRST_FEW_BYTES)].contains(&0)
+}
+
+/// Return the class of content for `purpose` as decided by `attributes` alone, or `None` if the content has to decide.
+///
+/// The attribute of `purpose` is consulted first, where `diff` and `merge` force text if set and binary if unset.
+/// A `merge` value of `text` or `binary` names the respective built-in driver. Otherwise the `text` attribute decides,
+/// with `-text` forcing binary and `text` forcing text, while `text=auto` leaves the decision to the content.
+///
+/// Note that the `binary` macro unsets all of these attributes.
+pub fn from_attributes(attributes: &BTreeMap<&BStr, State<'_>>, purpose: Purpose) -> Option<Class> {
+    let state = |name: &str| attributes.get(name.as_bytes().as_bstr());
+    let purpose_class = purpose.attribute().and_then(|name| match state(name)? {
+        State::Set => Some(Class::Text),
+        State::Unset => Some(Class::Binary),
+        State::Value(driver) if purpose == Purpose::Merge && *driver == "text" => Some(Class::Text),
+        State::Value(driver) if purpose == Purpose::Merge && *driver == "binary" => Some(Class::Binary),
+        State::Value(_) | State::Unspecified => None,
+    });
+    purpose_class.or_else(|| match state("text")? {
+        State::Set => Some(Class::Text),
+        State::Unset => Some(Class::Binary),
+        State::Value(_) | State::Unspecified => None,
+    })
+}
+
+/// Return the class of `data` for `purpose`, letting `attributes` of its path decide first, typically obtained
+/// with [`Stack::attributes()`][crate::Stack::attributes()], before falling back to its size and content.
+pub fn classify(data: &[u8], attributes: &BTreeMap<&BStr, State<'_>>, purpose: Purpose, options: Options) -> Class {
+    from_attributes(attributes, purpose).unwrap_or_else(|| {
+        if data.len() as u64 > options.big_file_threshold || is_binary(data) {
+            Class::Binary
+        } else {
+            Class::Text
+        }
+    })
+}
@@ -18,8 +18,13 @@ pub enum State<'a> {
     Unspecified,
 }
 
+pub mod binary;
+
 pub mod parse;
 
+mod stack;
+pub use stack::{List, Stack};
+
 pub fn parse(buf: &[u8]) -> parse::Lines<'_> {
     parse::Lines::new(buf)
 }
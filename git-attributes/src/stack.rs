@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{parse, State};
+
+/// The state of an attribute as stored in the stack, owning its value.
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum OwnedState {
+    Set,
+    Unset,
+    Value(BString),
+    Unspecified,
+}
+
+impl OwnedState {
+    fn as_state(&self) -> State<'_> {
+        match self {
+            OwnedState::Set => State::Set,
+            OwnedState::Unset => State::Unset,
+            OwnedState::Value(value) => State::Value(value.as_bstr()),
+            OwnedState::Unspecified => State::Unspecified,
+        }
+    }
+}
+
+impl<'a> From<State<'a>> for OwnedState {
+    fn from(state: State<'a>) -> Self {
+        match state {
+            State::Set => OwnedState::Set,
+            State::Unset => OwnedState::Unset,
+            State::Value(value) => OwnedState::Value(value.to_owned()),
+            State::Unspecified => OwnedState::Unspecified,
+        }
+    }
+}
+
+type Assignments = Vec<(BString, OwnedState)>;
+
+/// The parsed content of an attribute file, which can be pushed onto a [`Stack`] at any directory, useful to parse
+/// files only once if they are used in multiple places.
+#[derive(Default, Debug, Clone)]
+pub struct List {
+    /// The directory containing the attribute file relative to the root of the work tree, with a trailing slash,
+    /// or empty for top-level files.
+    base: BString,
+    rules: Vec<(git_glob::Pattern, Assignments)>,
+    macros: Vec<(BString, Assignments)>,
+}
+
+impl List {
+    /// Parse the attribute file in `buf`.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, parse::Error> {
+        let mut list = List::default();
+        for line in crate::parse(buf) {
+            let (kind, attrs, _line_number) = line?;
+            let assignments = attrs
+                .map(|attr| attr.map(|(name, state)| (name.to_owned(), OwnedState::from(state))))
+                .collect::<Result<Assignments, _>>()?;
+            match kind {
+                parse::Kind::Pattern(pattern) => list.rules.push((pattern, assignments)),
+                parse::Kind::Macro(name) => list.macros.push((name, assignments)),
+            }
+        }
+        Ok(list)
+    }
+}
+
+/// A stack of attribute files to find the attributes of paths, with those pushed later taking precedence over those
+/// pushed earlier.
+///
+/// Like `git`, attribute macros like `[attr]binary -diff -merge -text` are expanded when they are set for a path,
+/// and the `binary` macro is always defined. Macro definitions are only honored in top-level attribute files.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    builtin_macros: Vec<(BString, Assignments)>,
+    lists: Vec<List>,
+    case: git_glob::pattern::Case,
+}
+
+impl Default for Stack {
+    fn default() -> Self {
+        Stack {
+            builtin_macros: vec![(
+                "binary".into(),
+                vec![
+                    ("diff".into(), OwnedState::Unset),
+                    ("merge".into(), OwnedState::Unset),
+                    ("text".into(), OwnedState::Unset),
+                ],
+            )],
+            lists: Vec::new(),
+            case: git_glob::pattern::Case::Sensitive,
+        }
+    }
+}
+
+impl Stack {
+    /// Match paths according to `case`, which typically is [`Case::Fold`][git_glob::pattern::Case::Fold] if
+    /// `core.ignoreCase` is true.
+    pub fn with_case(mut self, case: git_glob::pattern::Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Parse the attribute file in `buf` and push it onto the stack, which is located in the directory `base`
+    /// relative to the root of the work tree.
+    ///
+    /// `base` is empty for top-level attribute files like the `.gitattributes` file at the root of the work tree, or
+    /// `info/attributes`, and has a trailing slash otherwise, like `dir/sub/`. Macros defined in files that aren't
+    /// top-level are ignored, like `git` does.
+    ///
+    /// Nothing is pushed if `buf` can't be parsed.
+    pub fn push(&mut self, buf: &[u8], base: impl Into<BString>) -> Result<(), parse::Error> {
+        self.push_list(List::from_bytes(buf)?, base);
+        Ok(())
+    }
+
+    /// Push the already parsed `list` onto the stack, which is located in the directory `base` just like with
+    /// [`push()`][Stack::push()].
+    pub fn push_list(&mut self, mut list: List, base: impl Into<BString>) {
+        let base = base.into();
+        if !base.is_empty() {
+            for (pattern, _) in &mut list.rules {
+                pattern.base_path = Some(base.clone());
+            }
+            list.macros.clear();
+        }
+        list.base = base;
+        self.lists.push(list);
+    }
+
+    /// Remove the attribute file pushed last, if there is one.
+    pub fn pop(&mut self) {
+        self.lists.pop();
+    }
+
+    /// Return the attributes of the file or directory at `relative_path`, depending on `is_dir`, which is relative
+    /// to the root of the work tree and uses slashes as separators.
+    ///
+    /// Set macros are expanded into the attributes they define, and all attributes that aren't returned are unspecified.
+    pub fn attributes(&self, relative_path: &BStr, is_dir: bool) -> BTreeMap<&BStr, State<'_>> {
+        let basename_start_pos = relative_path.rfind_byte(b'/').map(|p| p + 1);
+        let mut out = BTreeMap::new();
+        for list in self
+            .lists
+            .iter()
+            .rev()
+            .filter(|list| relative_path.starts_with(&list.base))
+        {
+            for (_, assignments) in list.rules.iter().rev().filter(|(pattern, _)| {
+                pattern.matches_repo_relative_path(relative_path, basename_start_pos, is_dir, self.case)
+            }) {
+                self.assign(assignments, &mut out);
+            }
+        }
+        out.retain(|_, state| *state != State::Unspecified);
+        out
+    }
+
+    /// Assign all `assignments` to attributes that weren't assigned yet, with later ones taking precedence, and
+    /// expand macros that are set.
+    fn assign<'a>(&'a self, assignments: &'a [(BString, OwnedState)], out: &mut BTreeMap<&'a BStr, State<'a>>) {
+        for (name, value) in assignments.iter().rev() {
+            if out.contains_key(name.as_bstr()) {
+                continue;
+            }
+            out.insert(name.as_bstr(), value.as_state());
+            if *value == OwnedState::Set {
+                if let Some(macro_assignments) = self.find_macro(name.as_bstr()) {
+                    self.assign(macro_assignments, out);
+                }
+            }
+        }
+    }
+
+    /// Find the assignments of the macro `name` which was defined last.
+    fn find_macro(&self, name: &BStr) -> Option<&[(BString, OwnedState)]> {
+        self.lists
+            .iter()
+            .rev()
+            .flat_map(|list| list.macros.iter().rev())
+            .chain(self.builtin_macros.iter())
+            .find(|(macro_name, _)| macro_name == name)
+            .map(|(_, assignments)| assignments.as_slice())
+    }
+}
@@ -1 +1,5 @@
+type Result = std::result::Result<(), Box<dyn std::error::Error>>;
+
+mod binary;
 mod parse;
+mod stack;
@@ -0,0 +1,73 @@
+use git_attributes::{
+    binary::{classify, from_attributes, Class, Options, Purpose},
+    Stack,
+};
+
+const TEXT: &[u8] = b"hello\nworld\n";
+const BINARY: &[u8] = b"hello\0world\n";
+
+fn classes(stack: &Stack, path: &str, data: &[u8]) -> [Class; 3] {
+    let attributes = stack.attributes(path.into(), false);
+    [Purpose::Diff, Purpose::Merge, Purpose::Eol]
+        .map(|purpose| classify(data, &attributes, purpose, Options::default()))
+}
+
+#[test]
+fn content_decides_without_attributes() {
+    let stack = Stack::default();
+    assert_eq!(classes(&stack, "file", TEXT), [Class::Text; 3]);
+    assert_eq!(classes(&stack, "file", BINARY), [Class::Binary; 3]);
+
+    let mut late_null = vec![b'a'; git_attributes::binary::FIRST_FEW_BYTES];
+    late_null.push(0);
+    assert_eq!(
+        classes(&stack, "file", &late_null),
+        [Class::Text; 3],
+        "only the first few bytes are searched for null bytes"
+    );
+}
+
+#[test]
+fn content_larger_than_the_threshold_is_binary() {
+    let attributes = Default::default();
+    let options = Options { big_file_threshold: 5 };
+    assert_eq!(classify(TEXT, &attributes, Purpose::Diff, options), Class::Binary);
+    assert_eq!(classify(b"short", &attributes, Purpose::Diff, options), Class::Text);
+}
+
+#[test]
+fn the_binary_macro_and_text_attribute_apply_to_all_purposes() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(b"*.png binary\n*.bin -text\n*.txt text\n*.auto text=auto\n", "")?;
+    assert_eq!(classes(&stack, "image.png", TEXT), [Class::Binary; 3]);
+    assert_eq!(classes(&stack, "data.bin", TEXT), [Class::Binary; 3]);
+    assert_eq!(classes(&stack, "file.txt", BINARY), [Class::Text; 3]);
+    assert_eq!(classes(&stack, "file.auto", BINARY), [Class::Binary; 3]);
+    assert_eq!(classes(&stack, "file.auto", TEXT), [Class::Text; 3]);
+    Ok(())
+}
+
+#[test]
+fn the_attribute_of_the_purpose_takes_precedence() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(
+        b"*.a -text diff\n*.b -diff\n*.c merge=binary\n*.d -text merge=text\n*.e diff=custom\n",
+        "",
+    )?;
+    assert_eq!(
+        classes(&stack, "f.a", BINARY),
+        [Class::Text, Class::Binary, Class::Binary]
+    );
+    assert_eq!(classes(&stack, "f.b", TEXT), [Class::Binary, Class::Text, Class::Text]);
+    assert_eq!(classes(&stack, "f.c", TEXT), [Class::Text, Class::Binary, Class::Text]);
+    assert_eq!(
+        classes(&stack, "f.d", TEXT),
+        [Class::Binary, Class::Text, Class::Binary]
+    );
+    assert_eq!(
+        from_attributes(&stack.attributes("f.e".into(), false), Purpose::Diff),
+        None,
+        "diff drivers leave the decision to their configuration or the content"
+    );
+    Ok(())
+}
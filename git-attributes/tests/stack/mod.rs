@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use bstr::{BStr, ByteSlice};
+use git_attributes::{Stack, State};
+
+fn attributes<'a>(stack: &'a Stack, path: &str) -> BTreeMap<&'a BStr, State<'a>> {
+    stack.attributes(path.into(), false)
+}
+
+fn expected<'a>(attrs: &[(&'a str, State<'a>)]) -> BTreeMap<&'a BStr, State<'a>> {
+    attrs
+        .iter()
+        .map(|(name, state)| (name.as_bytes().as_bstr(), state.clone()))
+        .collect()
+}
+
+#[test]
+fn later_lines_and_deeper_files_take_precedence() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(b"* a b=1 c\n*.txt b=2 -c\n", "")?;
+    stack.push(b"*.txt b=3\nfile.txt !a\n", "dir/")?;
+
+    assert_eq!(
+        attributes(&stack, "file.txt"),
+        expected(&[("a", State::Set), ("b", State::Value("2".into())), ("c", State::Unset)])
+    );
+    assert_eq!(
+        attributes(&stack, "dir/file.txt"),
+        expected(&[("b", State::Value("3".into())), ("c", State::Unset)]),
+        "unspecified attributes override those of lower precedence, but aren't returned"
+    );
+    assert_eq!(
+        attributes(&stack, "dir/file.rs"),
+        expected(&[("a", State::Set), ("b", State::Value("1".into())), ("c", State::Set)])
+    );
+
+    stack.pop();
+    assert_eq!(
+        attributes(&stack, "dir/file.txt"),
+        expected(&[("a", State::Set), ("b", State::Value("2".into())), ("c", State::Unset)])
+    );
+    Ok(())
+}
+
+#[test]
+fn the_binary_macro_is_built_in() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(b"*.png binary\n*.jpg -binary\n", "")?;
+    assert_eq!(
+        attributes(&stack, "image.png"),
+        expected(&[
+            ("binary", State::Set),
+            ("diff", State::Unset),
+            ("merge", State::Unset),
+            ("text", State::Unset),
+        ])
+    );
+    assert_eq!(
+        attributes(&stack, "image.jpg"),
+        expected(&[("binary", State::Unset)]),
+        "macros are only expanded if they are set"
+    );
+    Ok(())
+}
+
+#[test]
+fn macros_are_expanded_recursively_without_overriding_more_specific_attributes() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(
+        b"[attr]generated binary linguist-generated\n[attr]cycle cycle generated\n*.gen generated diff\n*.cycle cycle\n",
+        "",
+    )?;
+    assert_eq!(
+        attributes(&stack, "a.gen"),
+        expected(&[
+            ("binary", State::Set),
+            ("diff", State::Set),
+            ("generated", State::Set),
+            ("linguist-generated", State::Set),
+            ("merge", State::Unset),
+            ("text", State::Unset),
+        ]),
+        "attributes listed after the macro take precedence over those it expands to"
+    );
+    assert_eq!(
+        attributes(&stack, "a.cycle").len(),
+        7,
+        "macros referring to themselves are expanded only once"
+    );
+    Ok(())
+}
+
+#[test]
+fn macros_can_be_redefined_but_only_in_top_level_files() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(b"[attr]binary -text\n* binary\n", "")?;
+    stack.push(b"[attr]binary -diff\n", "dir/")?;
+    assert_eq!(
+        attributes(&stack, "dir/file"),
+        expected(&[("binary", State::Set), ("text", State::Unset)])
+    );
+    Ok(())
+}
+
+#[test]
+fn case_is_ignored_if_configured() -> crate::Result {
+    let mut stack = Stack::default();
+    stack.push(b"*.TXT text\n", "")?;
+    assert!(attributes(&stack, "file.txt").is_empty());
+
+    let mut stack = stack.with_case(git_glob::pattern::Case::Fold);
+    assert_eq!(attributes(&stack, "file.txt"), expected(&[("text", State::Set)]));
+    stack.pop();
+    assert!(attributes(&stack, "file.txt").is_empty());
+    Ok(())
+}
+
+#[test]
+fn invalid_files_are_not_pushed() {
+    let mut stack = Stack::default();
+    assert!(stack.push(b"* -bad-\xffname\n", "").is_err());
+    assert!(attributes(&stack, "file").is_empty());
+}
@@ -0,0 +1,220 @@
+//! Changed-path Bloom filters, which allow to learn if a commit changed a path compared to its first parent without
+//! diffing trees.
+//!
+//! Each commit in a commit-graph file may have a filter which contains all paths changed by the commit, along with all their
+//! leading directories. As with all Bloom filters, a negative answer is definite while a positive answer might be a false positive.
+use bstr::ByteSlice;
+
+/// The hash version whose murmur3 implementation mishandles bytes larger than `0x7f`, like older versions of git.
+const HASH_VERSION_SIGNED_BYTES: u32 = 1;
+/// The hash version with a correct murmur3 implementation.
+const HASH_VERSION_UNSIGNED_BYTES: u32 = 2;
+
+/// The amount of changed paths above which a filter is written to match all paths instead, like git does.
+pub const MAX_CHANGED_PATHS: usize = 512;
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+
+/// The settings stored in the header of the Bloom filter data chunk, shared by all filters of a file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the hash function used to compute the keys, either 1 or 2.
+    pub hash_version: u32,
+    /// The amount of bits set in the filter for each key.
+    pub num_hashes: u32,
+    /// The amount of bits used per changed path, which determines the size of the filter.
+    pub bits_per_entry: u32,
+}
+
+impl Default for Settings {
+    /// The settings git uses when writing Bloom filters.
+    fn default() -> Self {
+        Settings {
+            hash_version: HASH_VERSION_SIGNED_BYTES,
+            num_hashes: 7,
+            bits_per_entry: 10,
+        }
+    }
+}
+
+impl Settings {
+    pub(crate) fn to_bytes(self) -> [u8; 12] {
+        let mut out = [0; 12];
+        out[..4].copy_from_slice(&self.hash_version.to_be_bytes());
+        out[4..8].copy_from_slice(&self.num_hashes.to_be_bytes());
+        out[8..].copy_from_slice(&self.bits_per_entry.to_be_bytes());
+        out
+    }
+
+    pub(crate) fn from_bytes(header: &[u8]) -> Option<Self> {
+        let read = |pos: usize| u32::from_be_bytes([header[pos], header[pos + 1], header[pos + 2], header[pos + 3]]);
+        let settings = Settings {
+            hash_version: read(0),
+            num_hashes: read(4),
+            bits_per_entry: read(8),
+        };
+        matches!(
+            settings.hash_version,
+            HASH_VERSION_SIGNED_BYTES | HASH_VERSION_UNSIGNED_BYTES
+        )
+        .then(|| settings)
+    }
+}
+
+/// The hashes of a single path, computed once for all hash versions to check them against any number of filters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Key {
+    signed_bytes: [u32; 2],
+    unsigned_bytes: [u32; 2],
+}
+
+impl Key {
+    /// Compute the key for `path`, which is relative to the root of the repository and uses slashes as separators.
+    pub fn new(path: &[u8]) -> Self {
+        let hashes = |version| [murmur3(SEED0, path, version), murmur3(SEED1, path, version)];
+        Key {
+            signed_bytes: hashes(HASH_VERSION_SIGNED_BYTES),
+            unsigned_bytes: hashes(HASH_VERSION_UNSIGNED_BYTES),
+        }
+    }
+
+    /// Return the positions of the bits this key sets in a filter with the given `settings`.
+    fn hashes(&self, settings: &Settings) -> impl Iterator<Item = u32> {
+        let [h0, h1] = if settings.hash_version == HASH_VERSION_SIGNED_BYTES {
+            self.signed_bytes
+        } else {
+            self.unsigned_bytes
+        };
+        (0..settings.num_hashes).map(move |i| h0.wrapping_add(i.wrapping_mul(h1)))
+    }
+}
+
+/// The keys of a path along with all of its leading directories, which all have to be contained in a filter
+/// for the path to possibly be changed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Query {
+    keys: Vec<Key>,
+}
+
+impl Query {
+    /// Prepare a query for `path`, which is relative to the root of the repository and uses slashes as separators.
+    ///
+    /// Returns `None` if `path` is empty after removing leading and trailing slashes, as every commit changes the root.
+    pub fn new(path: &[u8]) -> Option<Self> {
+        let path = path.trim_with(|c| c == '/');
+        if path.is_empty() {
+            return None;
+        }
+        let keys = path
+            .find_iter(b"/")
+            .map(|slash| Key::new(&path[..slash]))
+            .chain(Some(Key::new(path)))
+            .collect();
+        Some(Query { keys })
+    }
+}
+
+/// The Bloom filter of a single commit, obtained by [`Commit::bloom_filter()`][crate::file::Commit::bloom_filter()].
+#[derive(Clone, Copy, Debug)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: &'a Settings,
+}
+
+impl<'a> Filter<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: &'a Settings) -> Option<Self> {
+        (!data.is_empty()).then(|| Filter { data, settings })
+    }
+
+    /// Return `false` if `key` is definitely not contained in this filter, or `true` if it might be.
+    pub fn contains(&self, key: &Key) -> bool {
+        let num_bits = self.data.len() as u64 * 8;
+        key.hashes(self.settings).all(|hash| {
+            let bit = u64::from(hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+
+    /// Return `false` if the path of `query` was definitely not changed by the commit owning this filter
+    /// compared to its first parent, or `true` if it might have been.
+    pub fn maybe_changed(&self, query: &Query) -> bool {
+        query.keys.iter().all(|key| self.contains(key))
+    }
+
+    /// Return the settings used to create this filter.
+    pub fn settings(&self) -> &'a Settings {
+        self.settings
+    }
+}
+
+/// Compute the data of the filter of a commit which changed `paths` compared to its first parent, as stored in commit-graph files.
+///
+/// Each path is relative to the root of the repository and uses slashes as separators. All of their leading directories are
+/// added to the filter as well. If more than [`MAX_CHANGED_PATHS`] paths are given, a filter that matches all paths is returned.
+pub fn filter_data<'a>(paths: impl IntoIterator<Item = &'a [u8]>, settings: &Settings) -> Vec<u8> {
+    let mut num_paths = 0;
+    let mut entries = std::collections::BTreeSet::new();
+    for path in paths {
+        num_paths += 1;
+        let path = path.trim_with(|c| c == '/');
+        if path.is_empty() {
+            continue;
+        }
+        entries.extend(path.find_iter(b"/").map(|slash| &path[..slash]));
+        entries.insert(path);
+    }
+    if num_paths > MAX_CHANGED_PATHS {
+        return vec![0xff];
+    }
+
+    let num_bytes = ((entries.len() as u64 * u64::from(settings.bits_per_entry) + 7) / 8).max(1);
+    let mut data = vec![0; num_bytes as usize];
+    let num_bits = num_bytes * 8;
+    for entry in entries {
+        for hash in Key::new(entry).hashes(settings) {
+            let bit = u64::from(hash) % num_bits;
+            data[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+    }
+    data
+}
+
+/// Compute the 32 bit murmur3 hash of `data`, with `version` [`HASH_VERSION_SIGNED_BYTES`] reproducing
+/// the sign extension of bytes larger than `0x7f` that git used to perform.
+fn murmur3(seed: u32, data: &[u8], version: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| {
+        if version == HASH_VERSION_SIGNED_BYTES {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+    let scramble = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+    let mut hash = seed;
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let k = byte(block[0]) | byte(block[1]) << 8 | byte(block[2]) << 16 | byte(block[3]) << 24;
+        hash ^= scramble(k);
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0u32, |k, (shift, b)| k ^ byte(*b) << (shift * 8));
+        hash ^= scramble(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ hash >> 16
+}
@@ -4,7 +4,10 @@ use std::{
     path::Path,
 };
 
-use crate::file::{self, commit::Commit, File, COMMIT_DATA_ENTRY_SIZE_SANS_HASH};
+use crate::{
+    bloom,
+    file::{self, commit::Commit, File, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+};
 
 /// Access
 impl File {
@@ -98,6 +101,11 @@ impl File {
         self.fan[255]
     }
 
+    /// Returns the settings of the changed-path Bloom filters stored in this file, or `None` if there are none.
+    pub fn bloom_filter_settings(&self) -> Option<&bloom::Settings> {
+        self.bloom_filters.as_ref().map(|bloom| &bloom.settings)
+    }
+
     /// Returns the path to this file.
     pub fn path(&self) -> &Path {
         &self.path
@@ -122,6 +130,24 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the data of the changed-path Bloom filter of the commit at `pos` along with the settings shared by all filters,
+    /// or `None` if this file has no Bloom filters or the index entry of `pos` is invalid.
+    pub(crate) fn bloom_filter_data(&self, pos: file::Position) -> Option<(&[u8], &bloom::Settings)> {
+        let bloom = self.bloom_filters.as_ref()?;
+        let pos: usize = pos
+            .0
+            .try_into()
+            .expect("an architecture able to hold 32 bits of integer");
+        let end_at = |pos: usize| -> usize {
+            let start = bloom.index_offset + pos * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().unwrap()) as usize
+        };
+        let start = if pos == 0 { 0 } else { end_at(pos - 1) };
+        let end = end_at(pos);
+        let data = &self.data[bloom.data_range.clone()];
+        Some((data.get(start..end)?, &bloom.settings))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
@@ -6,6 +6,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, File, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
     graph,
 };
@@ -56,6 +57,15 @@ impl<'a> Commit<'a> {
         }
     }
 
+    /// Returns the changed-path Bloom filter of this commit, or `None` if the file has no Bloom filters or the filter
+    /// of this commit wasn't computed.
+    ///
+    /// The filter contains the paths changed compared to the first parent, or all paths if the commit has no parent.
+    pub fn bloom_filter(&self) -> Option<bloom::Filter<'a>> {
+        let (data, settings) = self.file.bloom_filter_data(self.pos)?;
+        bloom::Filter::new(data, settings)
+    }
+
     /// Returns the committer timestamp of this commit.
     ///
     /// The value is the number of seconds since 1970-01-01 00:00:00 UTC.
@@ -7,8 +7,9 @@ use bstr::ByteSlice;
 use memmap2::Mmap;
 
 use crate::file::{
-    ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-    EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    BloomFilters, ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+    BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID,
+    FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
 };
 
 /// The error used in [`File::at()`].
@@ -196,6 +197,26 @@ impl TryFrom<&Path> for File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        // Like git, ignore Bloom filters that are incomplete, malformed or of an unknown version instead of failing,
+        // as they are merely an optimization.
+        let bloom_filters = chunks
+            .usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID)
+            .ok()
+            .zip(chunks.usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).ok())
+            .and_then(|(index_range, data_range)| {
+                if index_range.len() != commit_data_count as usize * 4
+                    || data_range.len() < BLOOM_FILTER_DATA_HEADER_LEN
+                {
+                    return None;
+                }
+                let settings = crate::bloom::Settings::from_bytes(&data[data_range.start..])?;
+                Some(BloomFilters {
+                    index_offset: index_range.start,
+                    data_range: data_range.start + BLOOM_FILTER_DATA_HEADER_LEN..data_range.end,
+                    settings,
+                })
+            });
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -232,6 +253,7 @@ impl TryFrom<&Path> for File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filters,
             commit_data_offset,
             data,
             extra_edges_list_range,
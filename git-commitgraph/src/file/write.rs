@@ -0,0 +1,319 @@
+//! Write commit-graph files.
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    io::{self, Write},
+};
+
+use bstr::BString;
+use git_hash::ObjectId;
+
+use crate::{
+    bloom,
+    file::{
+        File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, HEADER_LEN, LAST_EXTENDED_EDGE_MASK, NO_PARENT,
+        OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    Graph, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// The error returned by [`File::write_to()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Parent {parent} of commit {id} is neither contained in the base graph nor among the commits to write")]
+    MissingParent { id: ObjectId, parent: ObjectId },
+    #[error("Commit-graph files can hold at most {} commits, but {0} were given", MAX_COMMITS)]
+    TooManyCommits(u64),
+    #[error("Commit-graph files can build on at most 255 base graphs, but {0} were given")]
+    TooManyBaseGraphs(usize),
+}
+
+/// A commit to write into a commit-graph file with [`File::write_to()`].
+pub struct Commit {
+    /// The id of the commit itself.
+    pub id: ObjectId,
+    /// The id of the tree the commit points to.
+    pub root_tree_id: ObjectId,
+    /// The ids of all parents in the order they are stored in the commit.
+    pub parent_ids: Vec<ObjectId>,
+    /// The committer timestamp in seconds since 1970-01-01 00:00:00 UTC.
+    pub committer_timestamp: u64,
+    /// The paths changed compared to the first parent, or all paths if there is no parent, used to compute the changed-path
+    /// Bloom filter. If `None`, no filter will be written for this commit even if filters are enabled.
+    pub changed_paths: Option<Vec<BString>>,
+}
+
+/// Options for use in [`File::write_to()`].
+pub struct Options {
+    /// The kind of hash used by all object ids.
+    pub object_hash: git_hash::Kind,
+    /// If set, write changed-path Bloom filters with the given settings.
+    pub bloom_filter_settings: Option<bloom::Settings>,
+}
+
+/// The result of [`File::write_to()`].
+pub struct Outcome {
+    /// The checksum of the written file, which is used to name it if it is part of a chain of files.
+    pub checksum: ObjectId,
+    /// The amount of commits written, excluding those which were already contained in the base graph.
+    pub num_commits: u32,
+    /// The amount of commits for which a changed-path Bloom filter was written.
+    pub num_bloom_filters: u32,
+}
+
+/// A commit ready to be written, with all of its parents resolved to graph positions.
+struct Entry {
+    commit: Commit,
+    parents: Vec<u32>,
+    generation: u32,
+}
+
+/// Writing
+impl File {
+    /// Write a commit-graph file containing `commits` to `out`, building on top of the optional `base` graph as part of a chain.
+    ///
+    /// Commits which are already contained in `base` are skipped, and all parents of the remaining commits must either be
+    /// part of `base` or of `commits`.
+    pub fn write_to(
+        commits: impl IntoIterator<Item = Commit>,
+        base: Option<&Graph>,
+        out: impl io::Write,
+        Options {
+            object_hash,
+            bloom_filter_settings,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let mut commits: Vec<_> = commits
+            .into_iter()
+            .filter(|c| base.map_or(true, |base| base.lookup(c.id).is_none()))
+            .collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+
+        let num_base_commits = base.map_or(0, |base| base.num_commits());
+        if u64::from(num_base_commits) + commits.len() as u64 > u64::from(MAX_COMMITS) {
+            return Err(Error::TooManyCommits(
+                u64::from(num_base_commits) + commits.len() as u64,
+            ));
+        }
+        let base_graph_checksums: Vec<_> = base
+            .map(|base| base.files().iter().map(|f| f.checksum().to_owned()).collect())
+            .unwrap_or_default();
+        let num_base_graphs: u8 = base_graph_checksums
+            .len()
+            .try_into()
+            .map_err(|_| Error::TooManyBaseGraphs(base_graph_checksums.len()))?;
+
+        let entries = resolve_parents_and_generations(commits, base, num_base_commits)?;
+
+        let mut extra_edges = Vec::new();
+        let parent_edges: Vec<_> = entries
+            .iter()
+            .map(|e| match e.parents.as_slice() {
+                [] => (NO_PARENT, NO_PARENT),
+                [first] => (*first, NO_PARENT),
+                [first, second] => (*first, *second),
+                [first, rest @ ..] => {
+                    let edge_index = extra_edges.len() as u32 | EXTENDED_EDGES_MASK;
+                    extra_edges.extend_from_slice(rest);
+                    *extra_edges.last_mut().expect("at least two more parents") |= LAST_EXTENDED_EDGE_MASK;
+                    (*first, edge_index)
+                }
+            })
+            .collect();
+
+        let bloom_filters = bloom_filter_settings.map(|settings| {
+            let filters: Vec<_> = entries
+                .iter()
+                .map(|e| {
+                    e.commit
+                        .changed_paths
+                        .as_ref()
+                        .map(|paths| bloom::filter_data(paths.iter().map(|p| p.as_slice()), &settings))
+                        .unwrap_or_default()
+                })
+                .collect();
+            (settings, filters)
+        });
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = git_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (entries.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (entries.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if !extra_edges.is_empty() {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (extra_edges.len() * 4) as u64);
+        }
+        if let Some((_, filters)) = &bloom_filters {
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (filters.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + filters.iter().map(Vec::len).sum::<usize>()) as u64,
+            );
+        }
+        if num_base_graphs != 0 {
+            cf.plan_chunk(
+                BASE_GRAPHS_LIST_CHUNK_ID,
+                (base_graph_checksums.len() * hash_len) as u64,
+            );
+        }
+
+        let mut out = git_features::hash::Write::new(out, object_hash);
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1 /* version */, object_hash as u8])?;
+        out.write_all(&[
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            num_base_graphs,
+        ])?;
+
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            match chunk_to_write {
+                OID_FAN_CHUNK_ID => {
+                    let mut fan = [0u32; FAN_LEN];
+                    for entry in &entries {
+                        fan[entry.commit.id.first_byte() as usize] += 1;
+                    }
+                    let mut count = 0;
+                    for num_commits_with_byte in fan {
+                        count += num_commits_with_byte;
+                        chunk_write.write_all(&count.to_be_bytes())?;
+                    }
+                }
+                OID_LOOKUP_CHUNK_ID => {
+                    for entry in &entries {
+                        chunk_write.write_all(entry.commit.id.as_slice())?;
+                    }
+                }
+                COMMIT_DATA_CHUNK_ID => {
+                    for (entry, (parent1, parent2)) in entries.iter().zip(&parent_edges) {
+                        let time = entry.commit.committer_timestamp;
+                        chunk_write.write_all(entry.commit.root_tree_id.as_slice())?;
+                        chunk_write.write_all(&parent1.to_be_bytes())?;
+                        chunk_write.write_all(&parent2.to_be_bytes())?;
+                        chunk_write
+                            .write_all(&((entry.generation << 2) | ((time >> 32) as u32 & 0x3)).to_be_bytes())?;
+                        chunk_write.write_all(&(time as u32).to_be_bytes())?;
+                    }
+                }
+                EXTENDED_EDGES_LIST_CHUNK_ID => {
+                    for edge in &extra_edges {
+                        chunk_write.write_all(&edge.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_INDEX_CHUNK_ID => {
+                    let (_, filters) = bloom_filters.as_ref().expect("planned only with filters");
+                    let mut end = 0u32;
+                    for filter in filters {
+                        end += filter.len() as u32;
+                        chunk_write.write_all(&end.to_be_bytes())?;
+                    }
+                }
+                BLOOM_FILTER_DATA_CHUNK_ID => {
+                    let (settings, filters) = bloom_filters.as_ref().expect("planned only with filters");
+                    chunk_write.write_all(&settings.to_bytes())?;
+                    for filter in filters {
+                        chunk_write.write_all(filter)?;
+                    }
+                }
+                BASE_GRAPHS_LIST_CHUNK_ID => {
+                    for checksum in &base_graph_checksums {
+                        chunk_write.write_all(checksum.as_slice())?;
+                    }
+                }
+                unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+            }
+        }
+
+        let checksum: ObjectId = out.hash.digest().into();
+        out.inner.write_all(checksum.as_slice())?;
+
+        Ok(Outcome {
+            checksum,
+            num_commits: entries.len() as u32,
+            num_bloom_filters: bloom_filters.map_or(0, |(_, filters)| {
+                filters.iter().filter(|f| !f.is_empty()).count() as u32
+            }),
+        })
+    }
+}
+
+/// Translate the parent ids of all `commits`, which are sorted by id, into graph positions and compute their generation numbers.
+fn resolve_parents_and_generations(
+    commits: Vec<Commit>,
+    base: Option<&Graph>,
+    num_base_commits: u32,
+) -> Result<Vec<Entry>, Error> {
+    let position_by_id: HashMap<_, _> = commits
+        .iter()
+        .enumerate()
+        .map(|(idx, c)| (c.id, num_base_commits + idx as u32))
+        .collect();
+    let mut entries = commits
+        .into_iter()
+        .map(|commit| {
+            let parents = commit
+                .parent_ids
+                .iter()
+                .map(|parent| {
+                    position_by_id
+                        .get(parent)
+                        .copied()
+                        .or_else(|| base.and_then(|base| base.lookup(parent)).map(|pos| pos.0))
+                        .ok_or(Error::MissingParent {
+                            id: commit.id,
+                            parent: *parent,
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Entry {
+                commit,
+                parents,
+                generation: 0,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    // Compute generations without recursion, as histories can be arbitrarily deep.
+    let mut stack = Vec::new();
+    for idx in 0..entries.len() {
+        if entries[idx].generation != 0 {
+            continue;
+        }
+        stack.push(idx);
+        while let Some(&current) = stack.last() {
+            let mut max_parent_generation = 0;
+            let mut has_unknown_parents = false;
+            for &parent in &entries[current].parents {
+                let generation = match parent.checked_sub(num_base_commits) {
+                    Some(parent_idx) => {
+                        let generation = entries[parent_idx as usize].generation;
+                        if generation == 0 {
+                            stack.push(parent_idx as usize);
+                            has_unknown_parents = true;
+                        }
+                        generation
+                    }
+                    None => base
+                        .expect("base positions exist only with a base")
+                        .commit_at(crate::graph::Position(parent))
+                        .generation(),
+                };
+                max_parent_generation = max_parent_generation.max(generation);
+            }
+            if !has_unknown_parents {
+                entries[current].generation = (max_parent_generation + 1).min(GENERATION_NUMBER_MAX);
+                stack.pop();
+            }
+        }
+    }
+    Ok(entries)
+}
@@ -1,4 +1,5 @@
 use crate::{
+    bloom,
     file::{self, Commit, File},
     graph::{self, Graph},
 };
@@ -20,6 +21,13 @@ impl Graph {
         Some(r.file.commit_at(r.file_pos))
     }
 
+    /// Returns `Some(false)` if the commit with `id` definitely didn't change the path of `query` compared to its
+    /// first parent, `Some(true)` if it might have, or `None` if the commit or its changed-path Bloom filter isn't known.
+    pub fn maybe_changed_path(&self, id: impl AsRef<git_hash::oid>, query: &bloom::Query) -> Option<bool> {
+        let filter = self.commit_by_id(id)?.bloom_filter()?;
+        Some(filter.maybe_changed(query))
+    }
+
     /// Returns the `hash` at the given position `pos`.
     ///
     /// # Panics
@@ -29,6 +37,11 @@ impl Graph {
         r.file.id_at(r.pos)
     }
 
+    /// Returns all files this graph consists of, with the base of all other files first.
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
     /// Iterate over commits in unsorted order.
     pub fn iter_commits(&self) -> impl Iterator<Item = Commit<'_>> {
         self.files.iter().flat_map(|file| file.iter_commits())
@@ -69,3 +69,66 @@ fn two_parents() -> crate::Result {
 
     Ok(())
 }
+
+mod bloom {
+    use git_commitgraph::{bloom::Query, Graph};
+
+    use crate::{check_common, inspect_refs, make_readonly_repo};
+
+    #[test]
+    fn changed_paths() -> crate::Result {
+        let repo_dir = make_readonly_repo("bloom.sh");
+        let refs = inspect_refs(&repo_dir, &["root", "top_changed", "nested_changed"]);
+        let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+        check_common(&cg, &refs);
+
+        let settings = cg
+            .commit_at(refs["root"].pos())
+            .bloom_filter()
+            .expect("filters were written")
+            .settings();
+        assert_eq!((settings.num_hashes, settings.bits_per_entry), (7, 10));
+
+        let top = Query::new(b"top").expect("non-empty");
+        let file = Query::new(b"/dir/sub/file").expect("non-empty, slashes are trimmed");
+        let dir = Query::new(b"dir/").expect("non-empty");
+        let missing = Query::new(b"dir/sub/missing").expect("non-empty");
+
+        let changed = |name: &str, query: &Query| cg.maybe_changed_path(refs[name].id(), query);
+        for query in [&top, &file, &dir] {
+            assert_eq!(
+                changed("root", query),
+                Some(true),
+                "all paths are changed in root commits"
+            );
+        }
+        assert_eq!(changed("top_changed", &top), Some(true));
+        assert_eq!(changed("top_changed", &file), Some(false));
+        assert_eq!(changed("top_changed", &dir), Some(false));
+        assert_eq!(changed("nested_changed", &top), Some(false));
+        assert_eq!(changed("nested_changed", &file), Some(true));
+        assert_eq!(
+            changed("nested_changed", &dir),
+            Some(true),
+            "leading directories are contained as well"
+        );
+        assert_eq!(changed("nested_changed", &missing), Some(false));
+
+        assert_eq!(Query::new(b"/"), None, "the root is always changed");
+        Ok(())
+    }
+
+    #[test]
+    fn graphs_without_filters() -> crate::Result {
+        let repo_dir = make_readonly_repo("single_parent.sh");
+        let refs = inspect_refs(&repo_dir, &["parent", "child"]);
+        let cg = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+
+        assert!(cg.commit_at(refs["child"].pos()).bloom_filter().is_none());
+        assert_eq!(
+            cg.maybe_changed_path(refs["child"].id(), &Query::new(b"file").expect("non-empty")),
+            None
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b root
+mkdir -p dir/sub
+echo root > dir/sub/file
+echo root > top
+git add -A
+git commit -q -m root
+
+git checkout -q -b top_changed root
+echo top_changed > top
+git commit -q -am top_changed
+
+git checkout -q -b nested_changed top_changed
+echo nested_changed > dir/sub/file
+git commit -q -am nested_changed
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
@@ -18,7 +18,7 @@ run() {
     trap - EXIT
 }
 
-#run bloom
+run bloom
 #run bloom_too_large
 run octopus_merges
 run single_commit
@@ -0,0 +1,174 @@
+use git_commitgraph::{
+    bloom::Query,
+    file::{self, write},
+    Graph,
+};
+
+use crate::{check_common, inspect_refs, make_readonly_repo};
+
+fn commits_of(
+    cg: &Graph,
+    with_changed_paths: impl Fn(&git_hash::oid) -> Option<Vec<&'static str>>,
+) -> Vec<write::Commit> {
+    cg.iter_commits()
+        .map(|c| write::Commit {
+            id: c.id().to_owned(),
+            root_tree_id: c.root_tree_id().to_owned(),
+            parent_ids: c
+                .iter_parents()
+                .map(|pos| cg.id_at(pos.expect("valid parent")).to_owned())
+                .collect(),
+            committer_timestamp: c.committer_timestamp(),
+            changed_paths: with_changed_paths(c.id()).map(|paths| paths.into_iter().map(Into::into).collect()),
+        })
+        .collect()
+}
+
+fn write_file(
+    commits: Vec<write::Commit>,
+    base: Option<&Graph>,
+    bloom_filter_settings: Option<git_commitgraph::bloom::Settings>,
+    dir: &std::path::Path,
+) -> Result<(file::File, write::Outcome), Box<dyn std::error::Error>> {
+    let mut buf = Vec::new();
+    let outcome = file::File::write_to(
+        commits,
+        base,
+        &mut buf,
+        write::Options {
+            object_hash: git_hash::Kind::Sha1,
+            bloom_filter_settings,
+        },
+    )?;
+    let path = dir.join(format!("graph-{}.graph", outcome.checksum));
+    std::fs::write(&path, buf)?;
+    Ok((file::File::at(path)?, outcome))
+}
+
+#[test]
+fn octopus_merges_roundtrip_with_extra_edges() -> crate::Result {
+    let repo_dir = make_readonly_repo("octopus_merges.sh");
+    let refs = inspect_refs(
+        &repo_dir,
+        &[
+            "root",
+            "parent1",
+            "parent2",
+            "parent3",
+            "parent4",
+            "three_parents",
+            "four_parents",
+        ],
+    );
+    let expected = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+
+    let tmp = git_testtools::tempfile::tempdir()?;
+    let (file, outcome) = write_file(commits_of(&expected, |_| None), None, None, tmp.path())?;
+    assert_eq!(outcome.num_commits, 7);
+    assert_eq!(outcome.num_bloom_filters, 0);
+    assert_eq!(outcome.checksum, file.checksum());
+
+    let actual = Graph::new(vec![file])?;
+    check_common(&actual, &refs);
+    for info in refs.values() {
+        let (actual, expected) = (actual.commit_at(info.pos()), expected.commit_at(info.pos()));
+        assert_eq!(actual.generation(), expected.generation());
+        assert_eq!(actual.committer_timestamp(), expected.committer_timestamp());
+    }
+    actual.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    Ok(())
+}
+
+#[test]
+fn split_chain_on_top_of_base() -> crate::Result {
+    let repo_dir = make_readonly_repo("single_parent.sh");
+    let refs = inspect_refs(&repo_dir, &["parent", "child"]);
+    let full = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+    let parent_id = refs["parent"].id().to_owned();
+
+    let tmp = git_testtools::tempfile::tempdir()?;
+    let (base_file, _) = write_file(
+        commits_of(&full, |_| None)
+            .into_iter()
+            .filter(|c| c.id == parent_id)
+            .collect(),
+        None,
+        None,
+        tmp.path(),
+    )?;
+    let base = Graph::new(vec![base_file])?;
+
+    let missing_parent = file::File::write_to(
+        commits_of(&full, |_| None).into_iter().filter(|c| c.id != parent_id),
+        None,
+        Vec::new(),
+        write::Options {
+            object_hash: git_hash::Kind::Sha1,
+            bloom_filter_settings: None,
+        },
+    );
+    assert!(
+        matches!(missing_parent, Err(write::Error::MissingParent { .. })),
+        "parents must be known"
+    );
+
+    let (top_file, outcome) = write_file(commits_of(&full, |_| None), Some(&base), None, tmp.path())?;
+    assert_eq!(outcome.num_commits, 1, "commits in the base are skipped");
+    assert_eq!(top_file.base_graph_count(), 1);
+    assert_eq!(
+        top_file.iter_base_graph_ids().collect::<Vec<_>>(),
+        vec![base.files()[0].checksum()]
+    );
+
+    let base_file = file::File::at(base.files()[0].path())?;
+    let chain = Graph::new(vec![base_file, top_file])?;
+    assert_eq!(chain.num_commits(), 2);
+    let child = chain.commit_by_id(refs["child"].id()).expect("present in top file");
+    assert_eq!(child.generation(), 2);
+    assert_eq!(
+        chain.id_at(child.parent1()?.expect("one parent")),
+        refs["parent"].id(),
+        "parents can be in the base graph"
+    );
+    Ok(())
+}
+
+#[test]
+fn changed_path_bloom_filters() -> crate::Result {
+    let repo_dir = make_readonly_repo("bloom.sh");
+    let refs = inspect_refs(&repo_dir, &["root", "top_changed", "nested_changed"]);
+    let expected = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+
+    let changed_paths = |id: &git_hash::oid| {
+        Some(if id == refs["root"].id() {
+            vec!["dir/sub/file", "top"]
+        } else if id == refs["top_changed"].id() {
+            vec!["top"]
+        } else {
+            vec!["dir/sub/file"]
+        })
+    };
+    let tmp = git_testtools::tempfile::tempdir()?;
+    let (file, outcome) = write_file(
+        commits_of(&expected, changed_paths),
+        None,
+        Some(Default::default()),
+        tmp.path(),
+    )?;
+    assert_eq!(outcome.num_bloom_filters, 3);
+    let actual = Graph::new(vec![file])?;
+    check_common(&actual, &refs);
+
+    for path in ["top", "dir", "dir/sub", "dir/sub/file", "dir/sub/missing", "missing"] {
+        let query = Query::new(path.as_bytes()).expect("non-empty");
+        for info in refs.values() {
+            assert_eq!(
+                actual.maybe_changed_path(info.id(), &query),
+                expected.maybe_changed_path(info.id(), &query),
+                "our filters are the same as the ones written by git for '{}'",
+                path
+            );
+        }
+    }
+    Ok(())
+}
@@ -3,12 +3,14 @@ use std::{
     collections::{HashMap, VecDeque},
     convert::TryFrom,
     fmt::Display,
+    io::Write,
     path::{Path, PathBuf},
 };
 
 use crate::{
     file::{
         error::GitConfigError,
+        resolve_includes,
         section::{MutableSection, SectionBody},
         value::{EntryData, MutableMultiValue, MutableValue},
         Index, Size,
@@ -18,7 +20,7 @@ use crate::{
         parse_from_bytes, parse_from_path, parse_from_str, Error, Event, Key, ParsedSectionHeader, Parser,
         SectionHeaderName,
     },
-    values,
+    urlmatch,
 };
 
 /// The section ID is a monotonically increasing ID used to refer to sections.
@@ -100,7 +102,7 @@ pub struct GitConfig<'event> {
     /// The list of events that occur before an actual section. Since a
     /// `git-config` file prohibits global values, this vec is limited to only
     /// comment, newline, and whitespace events.
-    frontmatter_events: SectionBody<'event>,
+    pub(super) frontmatter_events: SectionBody<'event>,
     /// Section name and subsection name to section id lookup tree. This is
     /// effectively a n-tree (opposed to a binary tree) that can have a height
     /// of at most three (including an implicit root node).
@@ -111,16 +113,21 @@ pub struct GitConfig<'event> {
     /// This indirection with the SectionId as the key is critical to flexibly
     /// supporting `git-config` sections, as duplicated keys are permitted.
     pub(super) sections: HashMap<SectionId, SectionBody<'event>>,
-    section_headers: HashMap<SectionId, ParsedSectionHeader<'event>>,
+    pub(super) section_headers: HashMap<SectionId, ParsedSectionHeader<'event>>,
     /// Internal monotonically increasing counter for section ids.
     section_id_counter: usize,
     /// Section order for output ordering.
-    section_order: VecDeque<SectionId>,
+    pub(super) section_order: VecDeque<SectionId>,
+    /// The path of the file each section was read from, for sections of files assembled along with their includes.
+    pub(super) section_paths: HashMap<SectionId, PathBuf>,
+    /// The paths of all files that were to be included, whether they existed or not.
+    pub(super) included_paths: Vec<PathBuf>,
 }
 
 pub mod from_paths {
     use std::borrow::Cow;
 
+    use bstr::BStr;
     use quick_error::quick_error;
 
     use crate::{parser, values::path::interpolate};
@@ -146,6 +153,14 @@ pub mod from_paths {
             MissingConfigPath {
                 display("Include paths from environment variables must not be relative.")
             }
+            RemoteUrlInConditionalInclude { path: Option<std::path::PathBuf> } {
+                display("Remote urls cannot be configured in files included by includeIf.hasconfig:remote.*.url{}", path.as_ref().map(|path| format!(", but {:?} does", path)).unwrap_or_default())
+            }
+            Io(err: std::io::Error) {
+                display("A path required to evaluate an include condition could not be obtained")
+                source(err)
+                from()
+            }
         }
     }
 
@@ -161,6 +176,12 @@ pub mod from_paths {
         ///
         /// Setting this value to false allows to read configuration with cycles, which otherwise always results in an error.
         pub error_on_max_depth_exceeded: bool,
+        /// The git directory of the repository the configuration belongs to, used to evaluate `includeIf.gitdir:` and
+        /// `includeIf.gitdir/i:` conditions, which never match if it is unset.
+        pub git_dir: Option<&'a std::path::Path>,
+        /// The full name of the reference `HEAD` points to, like `refs/heads/main`, used to evaluate `includeIf.onbranch:`
+        /// conditions, which never match if it is unset or not a branch.
+        pub branch_name: Option<&'a BStr>,
     }
 
     impl<'a> Default for Options<'a> {
@@ -169,6 +190,8 @@ pub mod from_paths {
                 git_install_dir: None,
                 max_depth: 10,
                 error_on_max_depth_exceeded: true,
+                git_dir: None,
+                branch_name: None,
             }
         }
     }
@@ -211,6 +234,28 @@ pub mod from_env {
     }
 }
 
+pub mod write_to_path {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        /// The error returned by [`GitConfig::write_to_path()`][super::GitConfig::write_to_path()].
+        #[allow(missing_docs)]
+        pub enum Error {
+            Lock(err: git_lock::acquire::Error) {
+                display("The lock for the config file could not be obtained")
+                source(err)
+                from()
+            }
+            Io(err: std::io::Error) {
+                display("The config file could not be written")
+                source(err)
+                from()
+            }
+        }
+    }
+}
+
 impl<'event> GitConfig<'event> {
     /// Constructs an empty `git-config` file.
     #[inline]
@@ -230,94 +275,25 @@ impl<'event> GitConfig<'event> {
         parse_from_path(path).map(Self::from)
     }
 
-    /// Constructs a `git-config` file from the provided paths in the order provided.
+    /// Constructs a `git-config` file from the provided paths in the order provided, with all `include` and
+    /// `includeIf` sections resolved according to `options`.
     /// This is neither zero-copy nor zero-alloc.
     ///
+    /// Each included file is placed right after the section including it, just like git does, so its values take
+    /// precedence over the ones before the include section but not over the ones after it.
+    ///
     /// # Errors
     ///
-    /// Returns an error if there was an IO error or if a file wasn't a valid
-    /// git-config file.
+    /// Returns an error if there was an IO error, if a file wasn't a valid
+    /// git-config file or if an include couldn't be resolved.
     ///
     /// [`git-config`'s documentation]: https://git-scm.com/docs/git-config#Documentation/git-config.txt-FILES
-    #[inline]
     pub fn from_paths(paths: Vec<PathBuf>, options: &from_paths::Options) -> Result<Self, from_paths::Error> {
-        let mut target = Self::new();
-        for path in paths {
-            let mut config = Self::open(&path)?;
-            config.resolve_includes(Some(&path), options)?;
-            target.append(config);
-        }
-        Ok(target)
-    }
-
-    // TODO: add note indicating that probably a lot if not all information about the original files is currently lost,
-    //       so can't be written back. This will probably change a lot during refactor, so it's not too important now.
-    fn append(&mut self, mut other: Self) {
-        let mut section_indices: Vec<_> = other.section_headers.keys().cloned().collect();
-        // header keys are numeric and ascend in insertion order, hence sorting them gives the order
-        // in which they appear in the config file.
-        section_indices.sort();
-        for section_index in section_indices {
-            let section_header = other.section_headers.remove(&section_index).expect("present");
-            self.push_section(
-                section_header.name.0,
-                section_header.subsection_name,
-                other.sections.remove(&section_index).expect("present"),
-            );
-        }
-    }
-
-    pub(crate) fn resolve_includes(
-        &mut self,
-        config_path: Option<&std::path::Path>,
-        options: &from_paths::Options,
-    ) -> Result<(), from_paths::Error> {
-        fn resolve_includes_recursive(
-            target_config: &mut GitConfig,
-            target_config_path: Option<&Path>,
-            depth: u8,
-            options: &from_paths::Options,
-        ) -> Result<(), from_paths::Error> {
-            if depth == options.max_depth {
-                return if options.error_on_max_depth_exceeded {
-                    Err(from_paths::Error::IncludeDepthExceeded {
-                        max_depth: options.max_depth,
-                    })
-                } else {
-                    Ok(())
-                };
-            }
-
-            let mut paths_to_include = Vec::new();
-            for path in target_config
-                .multi_value::<values::Path>("include", None, "path")
-                .unwrap_or_default()
-            {
-                let path = path.interpolate(options.git_install_dir.as_deref())?;
-                let path: PathBuf = if path.is_relative() {
-                    target_config_path
-                        .ok_or(from_paths::Error::MissingConfigPath)?
-                        .parent()
-                        .expect("path is a config file which naturally lives in a directory")
-                        .join(path)
-                } else {
-                    path.into()
-                };
-
-                if path.is_file() {
-                    paths_to_include.push(path);
-                }
-            }
-
-            for config_path in paths_to_include {
-                let mut include_config = GitConfig::open(&config_path)?;
-                resolve_includes_recursive(&mut include_config, Some(&config_path), depth + 1, options)?;
-                target_config.append(include_config);
-            }
-            Ok(())
-        }
-
-        resolve_includes_recursive(self, config_path, 0, options)
+        let configs = paths
+            .iter()
+            .map(|path| Self::open(path).map(|config| (config, Some(path.as_path()))))
+            .collect::<Result<Vec<_>, _>>()?;
+        resolve_includes::resolve(configs, options)
     }
 
     /// Constructs a `git-config` from the default cascading sequence.
@@ -383,30 +359,8 @@ impl<'event> GitConfig<'event> {
         for i in 0..count {
             let key = env::var(format!("GIT_CONFIG_KEY_{}", i)).map_err(|_| from_env::Error::InvalidKeyId(i))?;
             let value = env::var(format!("GIT_CONFIG_VALUE_{}", i)).map_err(|_| from_env::Error::InvalidValueId(i))?;
-            if let Some((section_name, maybe_subsection)) = key.split_once('.') {
-                let (subsection, key) = if let Some((subsection, key)) = maybe_subsection.rsplit_once('.') {
-                    (Some(subsection), key)
-                } else {
-                    (None, maybe_subsection)
-                };
-
-                let mut section = if let Ok(section) = config.section_mut(section_name, subsection) {
-                    section
-                } else {
-                    // Need to have config own the section and subsection names
-                    // else they get dropped at the end of the loop.
-                    config.new_section(
-                        section_name.to_string(),
-                        subsection.map(|subsection| Cow::Owned(subsection.to_string())),
-                    )
-                };
-
-                section.push(
-                    Cow::<str>::Owned(key.to_string()).into(),
-                    Cow::Owned(value.into_bytes()),
-                );
-            } else {
-                return Err(from_env::Error::InvalidKeyValue(i, key.to_string()));
+            if !config.push_value_by_key(&key, Cow::Owned(value.into_bytes())) {
+                return Err(from_env::Error::InvalidKeyValue(i, key));
             }
         }
 
@@ -414,8 +368,7 @@ impl<'event> GitConfig<'event> {
         if config.is_empty() {
             Ok(None)
         } else {
-            config.resolve_includes(None, options)?;
-            Ok(Some(config))
+            Ok(Some(resolve_includes::resolve(vec![(config, None)], options)?))
         }
     }
 
@@ -619,7 +572,8 @@ impl<'event> GitConfig<'event> {
             .collect()
     }
 
-    /// Get all sections that match the `section_name`, returning all matching section header along with their body.
+    /// Get all sections that match the `section_name`, returning all matching section header along with their body
+    /// in the order they appear in the configuration.
     ///
     /// An empty `Vec` is returned if there is no section with `section_name`.
     ///
@@ -669,8 +623,9 @@ impl<'event> GitConfig<'event> {
         &self,
         section_name: &'lookup str,
     ) -> Vec<(&ParsedSectionHeader<'event>, &SectionBody<'event>)> {
-        self.get_section_ids_by_name(section_name)
-            .unwrap_or_default()
+        let mut section_ids = self.get_section_ids_by_name(section_name).unwrap_or_default();
+        section_ids.sort();
+        section_ids
             .into_iter()
             .map(|id| {
                 (
@@ -773,6 +728,9 @@ impl<'event> GitConfig<'event> {
                 .position(|v| *v == id)
                 .expect("Section order does not contain section that we were trying to remove"),
         );
+        self.remove_from_lookup_tree(id);
+        self.section_headers.remove(&id);
+        self.section_paths.remove(&id);
         self.sections.remove(&id)
     }
 
@@ -784,6 +742,7 @@ impl<'event> GitConfig<'event> {
         subsection_name: impl Into<Option<Cow<'event, str>>>,
         section: SectionBody<'event>,
     ) -> MutableSection<'_, 'event> {
+        self.ensure_trailing_newline();
         let subsection_name = subsection_name.into();
         if subsection_name.is_some() {
             self.push_section_internal(
@@ -806,7 +765,20 @@ impl<'event> GitConfig<'event> {
         }
     }
 
-    /// Renames a section, modifying the last matching section.
+    /// Renames a section, modifying the last matching section. The section
+    /// keeps its position, and can be looked up by its new name afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::{GitConfig, GitConfigError};
+    /// # use std::convert::TryFrom;
+    /// let mut git_config = GitConfig::try_from("[remote \"origin\"]\n\turl = a\n").unwrap();
+    /// git_config.rename_section("remote", "origin", "remote", Some("upstream".into()))?;
+    /// assert_eq!(git_config.to_string(), "[remote \"upstream\"]\n\turl = a\n");
+    /// assert!(git_config.get_raw_value("remote", Some("upstream"), "url").is_ok());
+    /// # Ok::<(), GitConfigError>(())
+    /// ```
     ///
     /// # Errors
     ///
@@ -819,19 +791,101 @@ impl<'event> GitConfig<'event> {
         new_subsection_name: impl Into<Option<Cow<'event, str>>>,
     ) -> Result<(), GitConfigError<'lookup>> {
         let id = self.get_section_ids_by_name_and_subname(section_name, subsection_name.into())?;
-        let id = id
+        let id = *id
             .last()
             .expect("list of sections were empty, which violates invariant");
+        self.remove_from_lookup_tree(id);
         let header = self
             .section_headers
-            .get_mut(id)
+            .get_mut(&id)
             .expect("sections does not have section id from section ids");
         header.name = new_section_name.into();
         header.subsection_name = new_subsection_name.into();
+        header.separator = match (&header.subsection_name, header.separator.take()) {
+            (None, _) => None,
+            (Some(_), None) => Some(" ".into()),
+            (Some(_), Some(separator)) => Some(separator),
+        };
+        let header = header.clone();
+        self.insert_into_lookup_tree(&header, id);
 
         Ok(())
     }
 
+    /// Returns all entries of the config in the order they appear, each with
+    /// the header of its section, its key and its normalized value, which is
+    /// `None` for keys without value. This is what `git config --list` shows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::GitConfig;
+    /// # use std::borrow::Cow;
+    /// # use std::convert::TryFrom;
+    /// let git_config = GitConfig::try_from("[core]\n\ta = b\n\tc\n[remote \"origin\"]\n\turl = d\n").unwrap();
+    /// let entries: Vec<_> = git_config
+    ///     .entries()
+    ///     .into_iter()
+    ///     .map(|(header, key, value)| (header.subsection_name.clone(), key.0.clone(), value))
+    ///     .collect();
+    /// assert_eq!(
+    ///     entries,
+    ///     vec![
+    ///         (None, "a".into(), Some(Cow::<[u8]>::Borrowed(b"b"))),
+    ///         (None, "c".into(), None),
+    ///         (Some("origin".into()), "url".into(), Some(Cow::<[u8]>::Borrowed(b"d"))),
+    ///     ]
+    /// );
+    /// ```
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn entries(&self) -> Vec<(&ParsedSectionHeader<'event>, &Key<'event>, Option<Cow<'event, [u8]>>)> {
+        self.entries_with_path()
+            .into_iter()
+            .map(|(header, key, value, _path)| (header, key, value))
+            .collect()
+    }
+
+    /// Like [`entries()`][Self::entries()], but also returns the path of the
+    /// file each entry was read from if the config was assembled with
+    /// [`from_paths()`][Self::from_paths()], which is the path of an included
+    /// file for entries defined there.
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn entries_with_path(
+        &self,
+    ) -> Vec<(
+        &ParsedSectionHeader<'event>,
+        &Key<'event>,
+        Option<Cow<'event, [u8]>>,
+        Option<&Path>,
+    )> {
+        self.section_order
+            .iter()
+            .flat_map(|section_id| {
+                let header = self
+                    .section_headers
+                    .get(section_id)
+                    .expect("section_header does not contain section id from section_order");
+                let path = self.section_paths.get(section_id).map(PathBuf::as_path);
+                self.sections
+                    .get(section_id)
+                    .expect("sections does not contain section id from section_order")
+                    .entries()
+                    .into_iter()
+                    .map(move |(key, value)| (header, key, value, path))
+            })
+            .collect()
+    }
+
+    /// Returns the paths of all files that were to be included while assembling this instance with
+    /// [`from_paths()`][Self::from_paths()], in the order they were encountered and including the ones that didn't
+    /// exist, which is useful to learn which files affect the configuration.
+    #[must_use]
+    pub fn included_paths(&self) -> &[PathBuf] {
+        &self.included_paths
+    }
+
     /// Returns the number of entries in the config. This ignores any comments.
     /// For example, a config with multiple empty sections will return 0.
     #[must_use]
@@ -845,6 +899,64 @@ impl<'event> GitConfig<'event> {
     pub fn is_empty(&self) -> bool {
         self.sections.values().all(SectionBody::is_empty)
     }
+
+    /// Serializes the config to `out`. Everything that wasn't changed is written
+    /// exactly as it was read, including comments and whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        for event in self.frontmatter_events.as_ref() {
+            out.write_all(&event.to_vec())?;
+        }
+
+        for section_id in &self.section_order {
+            out.write_all(
+                &self
+                    .section_headers
+                    .get(section_id)
+                    .expect("section_header does not contain section id from section_order")
+                    .to_vec(),
+            )?;
+
+            for event in self
+                .sections
+                .get(section_id)
+                .expect("sections does not contain section id from section_order")
+                .as_ref()
+            {
+                out.write_all(&event.to_vec())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the config to the file at `path` atomically, like `git config`
+    /// does. It is written to a lock file next to `path` first, which then
+    /// replaces the file at `path`, creating it if it doesn't exist.
+    ///
+    /// Use `lock_mode` to control what happens if the lock is held by someone
+    /// else already.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock couldn't be obtained or if writing the file
+    /// failed, leaving the file at `path` untouched.
+    pub fn write_to_path(
+        &self,
+        path: impl AsRef<Path>,
+        lock_mode: git_lock::acquire::Fail,
+    ) -> Result<(), write_to_path::Error> {
+        let mut lock = git_lock::File::acquire_to_update_resource(path, lock_mode, None)?;
+        lock.with_mut(|file| {
+            let mut out = std::io::BufWriter::new(file);
+            self.write_to(&mut out)?;
+            out.flush()
+        })?;
+        lock.commit().map_err(|err| err.error)?;
+        Ok(())
+    }
 }
 
 /// # Raw value API
@@ -890,6 +1002,113 @@ impl<'event> GitConfig<'event> {
         Err(GitConfigError::KeyDoesNotExist)
     }
 
+    /// Returns an uninterpreted value of `key` in a section named `section_name`
+    /// whose subsection is the URL best matching `url`, or in a section without
+    /// subsection if there is no matching URL, the same way as
+    /// `git config --get-urlmatch` does.
+    ///
+    /// See [`urlmatch`][crate::urlmatch] for details on how URLs are matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::GitConfig;
+    /// # use std::borrow::Cow;
+    /// # use std::convert::TryFrom;
+    /// let config = r#"
+    ///     [http "https://example.com/org"]
+    ///         sslVerify = false
+    ///     [http "https://example.com"]
+    ///         sslVerify = true
+    ///     [http]
+    ///         sslVerify = maybe
+    /// "#;
+    /// let git_config = GitConfig::try_from(config).unwrap();
+    /// let value = |url| git_config.get_raw_value_for_url("http", url, "sslVerify");
+    /// assert_eq!(value("https://example.com/org/repo"), Ok(Cow::Borrowed(&b"false"[..])));
+    /// assert_eq!(value("https://example.com/other"), Ok(Cow::Borrowed(&b"true"[..])));
+    /// assert_eq!(value("https://example.org"), Ok(Cow::Borrowed(&b"maybe"[..])));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no section named `section_name`
+    /// exists, or if no matching section contains the key.
+    pub fn get_raw_value_for_url<'lookup>(
+        &self,
+        section_name: &'lookup str,
+        url: &str,
+        key: &'lookup str,
+    ) -> Result<Cow<'_, [u8]>, GitConfigError<'lookup>> {
+        let url = urlmatch::Url::parse(url);
+        self.best_url_match(section_name, url.as_ref(), key)?
+            .map(|(_, value)| value)
+            .ok_or(GitConfigError::KeyDoesNotExist)
+    }
+
+    /// Returns an interpreted value of `key` in a section named `section_name`
+    /// whose subsection is the URL best matching `url`. See
+    /// [`get_raw_value_for_url`][Self::get_raw_value_for_url()] for details.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the key wasn't found or if the
+    /// conversion failed.
+    pub fn value_for_url<'lookup, T: TryFrom<Cow<'event, [u8]>>>(
+        &'event self,
+        section_name: &'lookup str,
+        url: &str,
+        key: &'lookup str,
+    ) -> Result<T, GitConfigError<'lookup>> {
+        T::try_from(self.get_raw_value_for_url(section_name, url, key)?).map_err(|_| GitConfigError::FailedConversion)
+    }
+
+    /// Returns the value of `key` in the sections named `section_name` best
+    /// matching `url` along with the quality of the match, or `None` if no
+    /// matching section has `key`. Only sections without subsection match if
+    /// `url` is `None`.
+    pub(crate) fn best_url_match<'lookup>(
+        &self,
+        section_name: &'lookup str,
+        url: Option<&urlmatch::Url>,
+        key: &'lookup str,
+    ) -> Result<Option<(urlmatch::Match, Cow<'_, [u8]>)>, GitConfigError<'lookup>> {
+        let key = Key(key.into());
+        let mut section_ids = self.get_section_ids_by_name(section_name)?;
+        section_ids.sort();
+
+        let mut best = None;
+        for section_id in section_ids {
+            let header = self
+                .section_headers
+                .get(&section_id)
+                .expect("sections does not have section id from section ids");
+            let url_match = match &header.subsection_name {
+                None => urlmatch::Match::default(),
+                Some(subsection) => match url.zip(urlmatch::Url::parse(subsection)) {
+                    Some((url, pattern)) => match url.matches(&pattern) {
+                        Some(url_match) => url_match,
+                        None => continue,
+                    },
+                    None => continue,
+                },
+            };
+            // Like in git, later values win over earlier ones that match equally well.
+            if best.as_ref().map_or(false, |(best_match, _)| url_match < *best_match) {
+                continue;
+            }
+            if let Some(value) = self
+                .sections
+                .get(&section_id)
+                .expect("sections does not have section id from section ids")
+                .value(&key)
+            {
+                best = Some((url_match, value));
+            }
+        }
+        Ok(best)
+    }
+
     /// Returns a mutable reference to an uninterpreted value given a section,
     /// an optional subsection and key.
     ///
@@ -1023,6 +1242,43 @@ impl<'event> GitConfig<'event> {
         }
     }
 
+    /// Like [`get_raw_multi_value()`][Self::get_raw_multi_value()], but also
+    /// returns the path of the file each value was read from if the config was
+    /// assembled with [`from_paths()`][Self::from_paths()], which is the path
+    /// of an included file for values defined there.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the key is not in any requested
+    /// section and subsection, or if no instance of the section and subsections
+    /// exist.
+    #[allow(clippy::type_complexity)]
+    pub fn get_raw_multi_value_with_path<'lookup>(
+        &self,
+        section_name: &'lookup str,
+        subsection_name: Option<&'lookup str>,
+        key: &'lookup str,
+    ) -> Result<Vec<(Cow<'_, [u8]>, Option<&Path>)>, GitConfigError<'lookup>> {
+        let mut values = vec![];
+        for section_id in self.get_section_ids_by_name_and_subname(section_name, subsection_name)? {
+            let path = self.section_paths.get(&section_id).map(PathBuf::as_path);
+            values.extend(
+                self.sections
+                    .get(&section_id)
+                    .expect("sections does not have section id from section ids")
+                    .values(&Key(key.into()))
+                    .into_iter()
+                    .map(|v| (Cow::Owned(v.to_vec()), path)),
+            );
+        }
+
+        if values.is_empty() {
+            Err(GitConfigError::KeyDoesNotExist)
+        } else {
+            Ok(values)
+        }
+    }
+
     /// Returns mutable references to all uninterpreted values given a section,
     /// an optional subsection and key.
     ///
@@ -1268,12 +1524,185 @@ impl<'event> GitConfig<'event> {
         self.get_raw_multi_value_mut(section_name, subsection_name, key)
             .map(|mut v| v.set_values(new_values))
     }
+
+    /// Adds `key` with `value` to the last section matching the section and
+    /// optional subsection name, creating the section if it doesn't exist,
+    /// similar to `git config --add`. Existing values of `key` are kept, making
+    /// it a multivar.
+    ///
+    /// The new entry is placed on its own line after the last entry of the
+    /// section, using the same indentation and spacing as that entry. The value
+    /// is written as-is and needs to be quoted or escaped by the caller if
+    /// necessary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::{GitConfig, GitConfigError};
+    /// # use std::borrow::Cow;
+    /// # use std::convert::TryFrom;
+    /// let mut git_config = GitConfig::try_from("[core]\n    a = b # comment\n\n").unwrap();
+    /// git_config.append_raw_value("core", None, "a", Cow::Borrowed(b"c"));
+    /// assert_eq!(git_config.to_string(), "[core]\n    a = b # comment\n    a = c\n\n");
+    ///
+    /// git_config.append_raw_value("remote", Some("origin"), "url", Cow::Borrowed(b"https://example.com"));
+    /// assert_eq!(
+    ///     git_config.to_string(),
+    ///     "[core]\n    a = b # comment\n    a = c\n\n[remote \"origin\"]\n\turl = https://example.com\n"
+    /// );
+    /// ```
+    pub fn append_raw_value(
+        &mut self,
+        section_name: &str,
+        subsection_name: Option<&str>,
+        key: &str,
+        value: Cow<'event, [u8]>,
+    ) {
+        let key = Key(Cow::Owned(key.to_owned()));
+        let last_section_id = self
+            .get_section_ids_by_name_and_subname(section_name, subsection_name)
+            .ok()
+            .and_then(|ids| ids.last().copied());
+        match last_section_id {
+            Some(id) => self
+                .sections
+                .get_mut(&id)
+                .expect("sections does not have section id from section ids")
+                .append_entry(key, value),
+            None => {
+                self.new_section(
+                    section_name.to_owned(),
+                    subsection_name.map(|name| Cow::Owned(name.to_owned())),
+                );
+                self.sections
+                    .get_mut(self.section_order.back().expect("section was just added"))
+                    .expect("sections does not contain section id from section_order")
+                    .append_entry(key, value);
+            }
+        }
+    }
+
+    /// Removes the last value of `key` in the given section and optional
+    /// subsection, similar to `git config --unset`. Unlike
+    /// [`MutableValue::delete()`], the entire line of the entry is removed,
+    /// including its indentation and comment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::{GitConfig, GitConfigError};
+    /// # use std::convert::TryFrom;
+    /// let mut git_config = GitConfig::try_from("[core]\n\ta = b\n\ta = c\n\td = e\n").unwrap();
+    /// git_config.remove_raw_value("core", None, "a")?;
+    /// assert_eq!(git_config.to_string(), "[core]\n\ta = b\n\td = e\n");
+    /// # Ok::<(), GitConfigError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This errors if any lookup input (section, subsection, and key value) fails.
+    pub fn remove_raw_value<'lookup>(
+        &mut self,
+        section_name: &'lookup str,
+        subsection_name: Option<&'lookup str>,
+        key: &'lookup str,
+    ) -> Result<(), GitConfigError<'lookup>> {
+        let key = Key(key.into());
+        for section_id in self
+            .get_section_ids_by_name_and_subname(section_name, subsection_name)?
+            .iter()
+            .rev()
+        {
+            let section = self
+                .sections
+                .get_mut(section_id)
+                .expect("sections does not have section id from section ids");
+            let last_key_index = section.key_indices(&key).next_back();
+            if let Some(key_index) = last_key_index {
+                section.remove_entry_line(key_index);
+                return Ok(());
+            }
+        }
+        Err(GitConfigError::KeyDoesNotExist)
+    }
+
+    /// Removes all values of `key` in all sections matching the section and
+    /// optional subsection name, similar to `git config --unset-all`. Like
+    /// [`Self::remove_raw_value()`], entire lines are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use git_config::file::{GitConfig, GitConfigError};
+    /// # use std::convert::TryFrom;
+    /// let mut git_config = GitConfig::try_from("[core]\n\ta = b\n\td = e\n[core]\n\ta = c\n").unwrap();
+    /// git_config.remove_raw_multi_value("core", None, "a")?;
+    /// assert_eq!(git_config.to_string(), "[core]\n\td = e\n[core]\n");
+    /// # Ok::<(), GitConfigError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This errors if any lookup input (section, subsection, and key value) fails.
+    pub fn remove_raw_multi_value<'lookup>(
+        &mut self,
+        section_name: &'lookup str,
+        subsection_name: Option<&'lookup str>,
+        key: &'lookup str,
+    ) -> Result<(), GitConfigError<'lookup>> {
+        let key = Key(key.into());
+        let mut found_key = false;
+        for section_id in self.get_section_ids_by_name_and_subname(section_name, subsection_name)? {
+            let section = self
+                .sections
+                .get_mut(&section_id)
+                .expect("sections does not have section id from section ids");
+            // Remove from the back so that the indices of earlier entries stay valid.
+            let key_indices: Vec<_> = section.key_indices(&key).rev().collect();
+            for key_index in key_indices {
+                section.remove_entry_line(key_index);
+                found_key = true;
+            }
+        }
+        if found_key {
+            Ok(())
+        } else {
+            Err(GitConfigError::KeyDoesNotExist)
+        }
+    }
 }
 
 /// Private helper functions
 impl<'event> GitConfig<'event> {
+    /// Adds `value` for `key` of the form `section[.subsection].name` to the last section it belongs to, creating
+    /// the section if it doesn't exist, or returns false if `key` doesn't name a section.
+    pub(crate) fn push_value_by_key(&mut self, key: &str, value: Cow<'event, [u8]>) -> bool {
+        let (section_name, maybe_subsection) = match key.split_once('.') {
+            Some(split) => split,
+            None => return false,
+        };
+        let (subsection, key) = if let Some((subsection, key)) = maybe_subsection.rsplit_once('.') {
+            (Some(subsection), key)
+        } else {
+            (None, maybe_subsection)
+        };
+
+        let mut section = if let Ok(section) = self.section_mut(section_name, subsection) {
+            section
+        } else {
+            // Need to have config own the section and subsection names
+            // as they are only borrowed from `key`.
+            self.new_section(
+                section_name.to_string(),
+                subsection.map(|subsection| Cow::Owned(subsection.to_string())),
+            )
+        };
+        section.push(Cow::<str>::Owned(key.to_string()).into(), value);
+        true
+    }
+
     /// Adds a new section to the config file.
-    fn push_section_internal(
+    pub(super) fn push_section_internal(
         &mut self,
         // current_section_name: Option<SectionHeaderName<'event>>,
         // current_subsection_name: Option<Cow<'event, str>>,
@@ -1281,43 +1710,102 @@ impl<'event> GitConfig<'event> {
         section: SectionBody<'event>,
     ) -> MutableSection<'_, 'event> {
         let new_section_id = SectionId(self.section_id_counter);
-        self.section_headers.insert(new_section_id, header.clone());
+        self.insert_into_lookup_tree(&header, new_section_id);
+        self.section_headers.insert(new_section_id, header);
         self.sections.insert(new_section_id, section);
-        let lookup = self.section_lookup_tree.entry(header.name).or_default();
+        self.section_order.push_back(new_section_id);
+        self.section_id_counter += 1;
+        self.sections.get_mut(&new_section_id).map(MutableSection::new).unwrap()
+    }
 
+    /// Makes `id` findable by the name and subsection name of `header`, keeping
+    /// the section ids of each node ordered by precedence.
+    fn insert_into_lookup_tree(&mut self, header: &ParsedSectionHeader<'event>, id: SectionId) {
+        // Section ids are handed out in order, so sorting by id is sorting by
+        // precedence.
+        fn insert_sorted(ids: &mut Vec<SectionId>, id: SectionId) {
+            let pos = ids.binary_search(&id).unwrap_or_else(|pos| pos);
+            ids.insert(pos, id);
+        }
+
+        let lookup = self.section_lookup_tree.entry(header.name.clone()).or_default();
         let mut found_node = false;
-        if let Some(subsection_name) = header.subsection_name {
+        if let Some(subsection_name) = &header.subsection_name {
             for node in lookup.iter_mut() {
                 if let LookupTreeNode::NonTerminal(subsection) = node {
                     found_node = true;
-                    subsection
-                        // Clones the cow, not the inner borrowed str.
-                        .entry(subsection_name.clone())
-                        .or_default()
-                        .push(new_section_id);
+                    // Clones the cow, not the inner borrowed str.
+                    insert_sorted(subsection.entry(subsection_name.clone()).or_default(), id);
                     break;
                 }
             }
             if !found_node {
                 let mut map = HashMap::new();
-                map.insert(subsection_name, vec![new_section_id]);
+                map.insert(subsection_name.clone(), vec![id]);
                 lookup.push(LookupTreeNode::NonTerminal(map));
             }
         } else {
             for node in lookup.iter_mut() {
                 if let LookupTreeNode::Terminal(vec) = node {
                     found_node = true;
-                    vec.push(new_section_id);
+                    insert_sorted(vec, id);
                     break;
                 }
             }
             if !found_node {
-                lookup.push(LookupTreeNode::Terminal(vec![new_section_id]));
+                lookup.push(LookupTreeNode::Terminal(vec![id]));
             }
         }
-        self.section_order.push_back(new_section_id);
-        self.section_id_counter += 1;
-        self.sections.get_mut(&new_section_id).map(MutableSection::new).unwrap()
+    }
+
+    /// Removes `id` from the lookup tree, along with all nodes that become empty
+    /// so that lookups don't find sections that don't exist anymore.
+    fn remove_from_lookup_tree(&mut self, id: SectionId) {
+        let header = self
+            .section_headers
+            .get(&id)
+            .expect("section_headers does not contain section id to remove");
+        let lookup = match self.section_lookup_tree.get_mut(&header.name) {
+            Some(lookup) => lookup,
+            None => return,
+        };
+        for node in lookup.iter_mut() {
+            match (node, &header.subsection_name) {
+                (LookupTreeNode::Terminal(ids), None) => ids.retain(|v| *v != id),
+                (LookupTreeNode::NonTerminal(subsections), Some(subsection_name)) => {
+                    if let Some(ids) = subsections.get_mut(subsection_name) {
+                        ids.retain(|v| *v != id);
+                        if ids.is_empty() {
+                            subsections.remove(subsection_name);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        lookup.retain(|node| match node {
+            LookupTreeNode::Terminal(ids) => !ids.is_empty(),
+            LookupTreeNode::NonTerminal(subsections) => !subsections.is_empty(),
+        });
+        if lookup.is_empty() {
+            self.section_lookup_tree.remove(&header.name);
+        }
+    }
+
+    /// Adds a newline to the end of the config unless it is empty or already
+    /// ends with one, so that new sections start on their own line.
+    fn ensure_trailing_newline(&mut self) {
+        let body = match self.section_order.back() {
+            Some(id) => self
+                .sections
+                .get_mut(id)
+                .expect("sections does not contain section id from section_order"),
+            None if self.frontmatter_events.is_empty() => return,
+            None => &mut self.frontmatter_events,
+        };
+        if !matches!(body.as_ref().last(), Some(Event::Newline(_))) {
+            body.as_mut().push(Event::Newline("\n".into()));
+        }
     }
 
     /// Returns the mapping between section and subsection name to section ids.
@@ -1467,30 +1955,7 @@ impl From<GitConfig<'_>> for Vec<u8> {
 impl From<&GitConfig<'_>> for Vec<u8> {
     fn from(config: &GitConfig) -> Self {
         let mut value = Self::new();
-
-        for events in config.frontmatter_events.as_ref() {
-            value.extend(events.to_vec());
-        }
-
-        for section_id in &config.section_order {
-            value.extend(
-                config
-                    .section_headers
-                    .get(section_id)
-                    .expect("section_header does not contain section id from section_order")
-                    .to_vec(),
-            );
-
-            for event in config
-                .sections
-                .get(section_id)
-                .expect("sections does not contain section id from section_order")
-                .as_ref()
-            {
-                value.extend(event.to_vec());
-            }
-        }
-
+        config.write_to(&mut value).expect("writing to a vec never fails");
         value
     }
 }
@@ -2,6 +2,7 @@
 
 mod error;
 mod git_config;
+mod resolve_includes;
 mod resolved;
 mod section;
 mod value;
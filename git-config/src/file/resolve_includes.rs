@@ -0,0 +1,295 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{
+    file::{from_paths, GitConfig, SectionBody},
+    parser::{Key, ParsedSectionHeader},
+    values,
+};
+
+/// State shared by all files that are resolved together.
+struct Context<'a> {
+    options: &'a from_paths::Options<'a>,
+    /// The urls of all remotes, or `None` if they are not yet known in the first pass.
+    remote_urls: Option<&'a [BString]>,
+    /// Set if a `hasconfig:remote.*.url:` condition was encountered.
+    uses_remote_urls: bool,
+}
+
+/// Assemble all `configs`, each along with the path it was read from, into a single configuration in order, replacing
+/// each `include` and matching `includeIf` section with itself followed by the files it includes, the same way git does.
+///
+/// As `includeIf.hasconfig:remote.*.url:` conditions depend on all remote urls, including the ones defined after them,
+/// a second pass is made with all remote urls known if any of these conditions were encountered.
+pub(crate) fn resolve<'event>(
+    configs: Vec<(GitConfig<'event>, Option<&Path>)>,
+    options: &from_paths::Options<'_>,
+) -> Result<GitConfig<'event>, from_paths::Error> {
+    let mut remote_urls = None;
+    loop {
+        let mut ctx = Context {
+            options,
+            remote_urls: remote_urls.as_deref(),
+            uses_remote_urls: false,
+        };
+        let mut target = GitConfig::new();
+        for (config, config_path) in configs.iter().cloned() {
+            resolve_recursive(&mut target, config, config_path, 0, false, &mut ctx)?;
+        }
+        if ctx.uses_remote_urls && remote_urls.is_none() {
+            remote_urls = Some(all_remote_urls(&target));
+            continue;
+        }
+        return Ok(target);
+    }
+}
+
+fn resolve_recursive<'event>(
+    target: &mut GitConfig<'event>,
+    mut config: GitConfig<'event>,
+    config_path: Option<&Path>,
+    depth: u8,
+    included_by_remote_url: bool,
+    ctx: &mut Context<'_>,
+) -> Result<(), from_paths::Error> {
+    if target.section_order.is_empty() && target.frontmatter_events.is_empty() {
+        target.frontmatter_events = std::mem::take(&mut config.frontmatter_events);
+    }
+
+    for section_id in config.section_order {
+        let header = config.section_headers.remove(&section_id).expect("present");
+        let body = config.sections.remove(&section_id).expect("present");
+        if included_by_remote_url && is_remote_section_with_url(&header, &body) {
+            return Err(from_paths::Error::RemoteUrlInConditionalInclude {
+                path: config_path.map(ToOwned::to_owned),
+            });
+        }
+
+        let condition = include_condition(&header, config_path, ctx)?;
+        let paths = match condition {
+            Condition::Unmet => Vec::new(),
+            Condition::Met | Condition::MetByRemoteUrl => include_paths(&body, config_path, ctx.options)?,
+        };
+        target.push_section_internal(header, body);
+        if let Some(path) = config_path {
+            let id = *target.section_order.back().expect("section was just added");
+            target.section_paths.insert(id, path.to_owned());
+        }
+
+        for path in paths {
+            target.included_paths.push(path.clone());
+            if !path.is_file() {
+                continue;
+            }
+            if depth == ctx.options.max_depth {
+                if ctx.options.error_on_max_depth_exceeded {
+                    return Err(from_paths::Error::IncludeDepthExceeded {
+                        max_depth: ctx.options.max_depth,
+                    });
+                }
+                continue;
+            }
+            let include_config = GitConfig::open(&path)?;
+            resolve_recursive(
+                target,
+                include_config,
+                Some(path.as_path()),
+                depth + 1,
+                included_by_remote_url || condition == Condition::MetByRemoteUrl,
+                ctx,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// What to do with the files mentioned in a section.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Condition {
+    /// The section isn't an include section, or its condition is false.
+    Unmet,
+    /// The files are to be included.
+    Met,
+    /// The files are to be included due to a `hasconfig:remote.*.url:` condition, which means they must not define
+    /// remote urls themselves.
+    MetByRemoteUrl,
+}
+
+/// Evaluate the condition of the section with `header`, which is always met for `include` sections.
+fn include_condition(
+    header: &ParsedSectionHeader<'_>,
+    config_path: Option<&Path>,
+    ctx: &mut Context<'_>,
+) -> Result<Condition, from_paths::Error> {
+    let name = &header.name.0;
+    let condition = match &header.subsection_name {
+        None if name.eq_ignore_ascii_case("include") => return Ok(Condition::Met),
+        Some(condition) if name.eq_ignore_ascii_case("includeIf") => condition,
+        _ => return Ok(Condition::Unmet),
+    };
+
+    let is_met = if let Some(pattern) = condition.strip_prefix("gitdir:") {
+        gitdir_matches(pattern, false, config_path, ctx.options)?
+    } else if let Some(pattern) = condition.strip_prefix("gitdir/i:") {
+        gitdir_matches(pattern, true, config_path, ctx.options)?
+    } else if let Some(pattern) = condition.strip_prefix("onbranch:") {
+        onbranch_matches(pattern, ctx.options)
+    } else if let Some(pattern) = condition.strip_prefix("hasconfig:remote.*.url:") {
+        ctx.uses_remote_urls = true;
+        let is_met = ctx.remote_urls.unwrap_or_default().iter().any(|url| {
+            git_glob::wildmatch(
+                pattern.as_bytes().as_bstr(),
+                url.as_bstr(),
+                git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+            )
+        });
+        return Ok(if is_met {
+            Condition::MetByRemoteUrl
+        } else {
+            Condition::Unmet
+        });
+    } else {
+        // Like git, ignore conditions we don't know.
+        false
+    };
+    Ok(if is_met { Condition::Met } else { Condition::Unmet })
+}
+
+/// Return all paths of `include.path` values in `body` that point to existing files, making relative paths relative to
+/// the directory containing the config file at `config_path`.
+fn include_paths(
+    body: &SectionBody<'_>,
+    config_path: Option<&Path>,
+    options: &from_paths::Options<'_>,
+) -> Result<Vec<PathBuf>, from_paths::Error> {
+    let mut paths = Vec::new();
+    for path in body.values(&Key::from("path")) {
+        let path = values::Path::from(path).interpolate(options.git_install_dir.as_deref())?;
+        let path: PathBuf = if path.is_relative() {
+            config_path
+                .ok_or(from_paths::Error::MissingConfigPath)?
+                .parent()
+                .expect("path is a config file which naturally lives in a directory")
+                .join(path)
+        } else {
+            path.into()
+        };
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Match `pattern` against the git directory the same way as `include_by_gitdir()` in git does.
+fn gitdir_matches(
+    pattern: &str,
+    ignore_case: bool,
+    config_path: Option<&Path>,
+    options: &from_paths::Options<'_>,
+) -> Result<bool, from_paths::Error> {
+    let git_dir = match options.git_dir {
+        Some(git_dir) => git_dir,
+        None => return Ok(false),
+    };
+
+    let mut pattern: BString = if pattern.is_empty() {
+        BString::default()
+    } else {
+        let pattern =
+            values::Path::from(Cow::Borrowed(pattern.as_bytes())).interpolate(options.git_install_dir.as_deref())?;
+        to_unix_bytes(&pattern)
+    };
+    let mut literal_prefix_len = 0;
+    if pattern.starts_with(b"./") {
+        let config_dir = config_path
+            .ok_or(from_paths::Error::MissingConfigPath)?
+            .parent()
+            .expect("path is a config file which naturally lives in a directory");
+        let config_dir = to_unix_bytes(&config_dir.canonicalize()?);
+        literal_prefix_len = config_dir.len() + 1;
+        let mut absolute_pattern = config_dir;
+        absolute_pattern.push_str(&pattern[1..]);
+        pattern = absolute_pattern;
+    } else if !pattern.starts_with(b"/") && !Path::new(pattern.to_str_lossy().as_ref()).is_absolute() {
+        pattern.insert_str(0, "**/");
+    }
+    if pattern.ends_with(b"/") {
+        pattern.push_str("**");
+    }
+
+    let mut mode = git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL;
+    if ignore_case {
+        mode |= git_glob::wildmatch::Mode::IGNORE_CASE;
+    }
+    let is_match = |git_dir: &BStr| {
+        if git_dir.len() < literal_prefix_len {
+            return false;
+        }
+        let (prefix, git_dir_prefix) = (&pattern[..literal_prefix_len], &git_dir[..literal_prefix_len]);
+        let prefix_matches = if ignore_case {
+            prefix.eq_ignore_ascii_case(git_dir_prefix)
+        } else {
+            prefix == git_dir_prefix
+        };
+        prefix_matches
+            && git_glob::wildmatch(
+                pattern[literal_prefix_len..].as_bstr(),
+                git_dir[literal_prefix_len..].as_bstr(),
+                mode,
+            )
+    };
+
+    // Like git, try the real path first, and the path as given in case it contains symlinks the pattern refers to.
+    let real_git_dir = git_dir.canonicalize().ok().map(|dir| to_unix_bytes(&dir));
+    if real_git_dir.as_ref().map_or(false, |dir| is_match(dir.as_bstr())) {
+        return Ok(true);
+    }
+    let absolute_git_dir = if git_dir.is_absolute() {
+        git_dir.to_owned()
+    } else {
+        std::env::current_dir()?.join(git_dir)
+    };
+    Ok(is_match(to_unix_bytes(&absolute_git_dir).as_bstr()))
+}
+
+/// Match `pattern` against the short name of the branch `HEAD` points to, like `include_by_branch()` in git does.
+fn onbranch_matches(pattern: &str, options: &from_paths::Options<'_>) -> bool {
+    let branch_name = match options.branch_name.and_then(|name| name.strip_prefix(b"refs/heads/")) {
+        Some(name) => name,
+        None => return false,
+    };
+    let mut pattern = BString::from(pattern);
+    if pattern.ends_with(b"/") {
+        pattern.push_str("**");
+    }
+    git_glob::wildmatch(
+        pattern.as_bstr(),
+        branch_name.as_bstr(),
+        git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+    )
+}
+
+fn is_remote_section_with_url(header: &ParsedSectionHeader<'_>, body: &SectionBody<'_>) -> bool {
+    header.name.0.eq_ignore_ascii_case("remote")
+        && header.subsection_name.is_some()
+        && body.contains_key(&Key::from("url"))
+}
+
+fn all_remote_urls(config: &GitConfig<'_>) -> Vec<BString> {
+    config
+        .sections_by_name_with_header("remote")
+        .into_iter()
+        .filter(|(header, _)| header.subsection_name.is_some())
+        .flat_map(|(_, body)| body.values(&Key::from("url")))
+        .map(|url| url.into_owned().into())
+        .collect()
+}
+
+fn to_unix_bytes(path: &Path) -> BString {
+    git_features::path::convert::to_unix_separators(git_features::path::into_bytes_or_panic_on_windows(path))
+        .into_owned()
+        .into()
+}
@@ -334,6 +334,48 @@ impl<'event> SectionBody<'event> {
             .map_err(|_| GitConfigError::FailedConversion)
     }
 
+    /// Retrieves all entries of this section in order, each with its key and
+    /// normalized value, which is `None` for keys without value like `[core] bare`
+    /// that are considered `true`. Note that keys with an empty value like
+    /// `[core] bare =` have an empty value, which is considered `false`.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(&Key<'event>, Option<Cow<'event, [u8]>>)> {
+        let mut entries = vec![];
+        let mut current_key = None;
+        let mut has_separator = false;
+        let mut partial_value = None;
+
+        for event in &self.0 {
+            match event {
+                Event::Key(key) => {
+                    current_key = Some(key);
+                    has_separator = false;
+                }
+                Event::KeyValueSeparator => has_separator = true,
+                Event::Value(v) => {
+                    if let Some(key) = current_key.take() {
+                        entries.push((key, has_separator.then(|| normalize_cow(v.clone()))));
+                    }
+                }
+                Event::ValueNotDone(v) if current_key.is_some() => {
+                    partial_value.get_or_insert_with(Vec::new).extend_from_slice(v.as_ref());
+                }
+                Event::ValueDone(v) => {
+                    if let Some(key) = current_key.take() {
+                        let mut value = partial_value
+                            .take()
+                            .expect("ValueDone event called before ValueNotDone");
+                        value.extend(&**v);
+                        entries.push((key, Some(normalize_vec(value))));
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        entries
+    }
+
     /// Returns an iterator visiting all keys in order.
     #[inline]
     pub fn keys(&self) -> impl Iterator<Item = &Key<'event>> {
@@ -401,6 +443,141 @@ impl<'event> SectionBody<'event> {
     }
 }
 
+// Internal methods to edit entire lines while keeping the formatting of all other lines intact.
+impl<'event> SectionBody<'event> {
+    /// Returns the indices of all key events matching `key`, in order of appearance.
+    pub(super) fn key_indices<'a>(&'a self, key: &'a Key<'a>) -> impl DoubleEndedIterator<Item = usize> + 'a {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, e)| matches!(e, Event::Key(k) if k == key).then(|| i))
+    }
+
+    /// Returns the range of events making up the line of the entry whose key is at `key_index`, including the
+    /// whitespace before the key, whitespace and comments after the value, continuation lines and the trailing
+    /// newline, if present.
+    fn entry_line_range(&self, key_index: usize) -> Range<usize> {
+        let mut start = key_index;
+        while start > 0 && matches!(self.0[start - 1], Event::Whitespace(_)) {
+            start -= 1;
+        }
+
+        let mut end = key_index + 1;
+        let mut value_continues = false;
+        while let Some(event) = self.0.get(end) {
+            match event {
+                Event::Newline(_) if !value_continues => {
+                    end += 1;
+                    break;
+                }
+                Event::Key(_) => break,
+                Event::ValueNotDone(_) => value_continues = true,
+                Event::ValueDone(_) => value_continues = false,
+                _ => (),
+            }
+            end += 1;
+        }
+        start..end
+    }
+
+    /// Removes the line of the entry whose key is at `key_index`, see [`Self::entry_line_range()`].
+    pub(super) fn remove_entry_line(&mut self, key_index: usize) {
+        let line = self.entry_line_range(key_index);
+        self.split_newline(line.end - 1);
+        self.0.drain(line);
+    }
+
+    /// Splits the newline event at `index`, if it is one, into an event for its first line ending and one for all
+    /// others, as the parser merges consecutive newlines into a single event.
+    fn split_newline(&mut self, index: usize) {
+        let newlines = match &self.0[index] {
+            Event::Newline(newlines) => newlines.clone(),
+            _ => return,
+        };
+        let first_len = if newlines.starts_with("\r\n") { 2 } else { 1 };
+        if newlines.len() <= first_len {
+            return;
+        }
+        let (first, rest) = match newlines {
+            Cow::Borrowed(newlines) => (
+                Cow::Borrowed(&newlines[..first_len]),
+                Cow::Borrowed(&newlines[first_len..]),
+            ),
+            Cow::Owned(newlines) => (
+                Cow::Owned(newlines[..first_len].to_owned()),
+                Cow::Owned(newlines[first_len..].to_owned()),
+            ),
+        };
+        self.0[index] = Event::Newline(first);
+        self.0.insert(index + 1, Event::Newline(rest));
+    }
+
+    /// Adds `key` with `value` on a new line after the last entry, or right after the section header if there is
+    /// none. The indentation, the spacing around the separator and the newline style are copied from the last entry,
+    /// defaulting to what `git` uses.
+    pub(super) fn append_entry(&mut self, key: Key<'event>, value: Cow<'event, [u8]>) {
+        let mut indentation = Some(Cow::Borrowed("\t"));
+        let mut whitespace_around_separator = (Some(Cow::Borrowed(" ")), Some(Cow::Borrowed(" ")));
+        let mut newline = Cow::Borrowed("\n");
+        let mut needs_newline_before = false;
+
+        let insert_at = match self.0.iter().rposition(|e| matches!(e, Event::Key(_))) {
+            Some(key_index) => {
+                let line = self.entry_line_range(key_index);
+                indentation = match self.0[line.start..key_index].first() {
+                    Some(Event::Whitespace(ws)) => Some(ws.clone()),
+                    _ => None,
+                };
+                if let Some(separator) = self.0[key_index..line.end]
+                    .iter()
+                    .position(|e| matches!(e, Event::KeyValueSeparator))
+                    .map(|pos| key_index + pos)
+                {
+                    let whitespace_at = |idx: usize| match &self.0[idx] {
+                        Event::Whitespace(ws) => Some(ws.clone()),
+                        _ => None,
+                    };
+                    whitespace_around_separator = (whitespace_at(separator - 1), whitespace_at(separator + 1));
+                }
+                self.split_newline(line.end - 1);
+                match &self.0[line.end - 1] {
+                    Event::Newline(nl) => newline = nl.clone(),
+                    _ => needs_newline_before = true,
+                }
+                line.end
+            }
+            None => match self.0.iter().position(|e| matches!(e, Event::Newline(_))) {
+                Some(pos) => {
+                    self.split_newline(pos);
+                    if let Event::Newline(nl) = &self.0[pos] {
+                        newline = nl.clone();
+                    }
+                    pos + 1
+                }
+                None => {
+                    needs_newline_before = true;
+                    self.0.len()
+                }
+            },
+        };
+
+        let mut entry = Vec::with_capacity(8);
+        if needs_newline_before {
+            entry.push(Event::Newline(newline.clone()));
+        }
+        entry.extend(indentation.map(Event::Whitespace));
+        entry.push(Event::Key(key));
+        entry.extend(whitespace_around_separator.0.map(Event::Whitespace));
+        entry.push(Event::KeyValueSeparator);
+        entry.extend(whitespace_around_separator.1.map(Event::Whitespace));
+        entry.push(Event::Value(value));
+        if !needs_newline_before || insert_at < self.0.len() {
+            entry.push(Event::Newline(newline));
+        }
+        self.0.splice(insert_at..insert_at, entry);
+    }
+}
+
 impl<'event> IntoIterator for SectionBody<'event> {
     type Item = (Key<'event>, Cow<'event, [u8]>);
 
@@ -1,13 +1,21 @@
 #![allow(unused)]
-#![allow(clippy::result_unit_err)]
 
 use std::{
     borrow::Cow,
+    collections::HashMap,
     convert::TryFrom,
+    env,
     path::{Path, PathBuf},
 };
 
-use crate::file::{from_paths, GitConfig, GitConfigError};
+use bstr::ByteSlice;
+
+use crate::{
+    file::{from_paths, GitConfig, GitConfigError},
+    parser::{Key, ParsedSectionHeader},
+    urlmatch,
+    values::Boolean,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum ConfigSource {
@@ -21,14 +29,84 @@ pub enum ConfigSource {
     /// variable set in this file will be overridden by whatever is in the
     /// Global configuration file.
     User,
-
+    /// The configuration of the repository, usually `.git/config`, which is shared by all of its worktrees.
     Repository,
-    // Worktree(&'a Path),
+    /// The configuration specific to a worktree, `config.worktree` in its git directory, which is only read if
+    /// `extensions.worktreeConfig` is enabled.
+    Worktree,
     /// Config values parsed from the environment.
     Env,
+    /// Config values passed on the command-line, like `git -c section.key=value`.
     Cli,
 }
 
+impl ConfigSource {
+    /// Returns the path of the file git reads the configuration of this source from and writes it to, for the
+    /// repository at `git_dir` if set. This is `None` if the source isn't a file or if its location is unknown.
+    ///
+    /// Environment variables like `GIT_CONFIG_SYSTEM` and `GIT_CONFIG_GLOBAL` are respected, and the user
+    /// configuration file is used for the global configuration if it exists but `~/.gitconfig` doesn't, just like git does.
+    #[must_use]
+    pub fn storage_location(self, git_dir: Option<&Path>) -> Option<PathBuf> {
+        let xdg_config = || {
+            env::var_os("XDG_CONFIG_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+                .map(|dir| dir.join("git").join("config"))
+        };
+        match self {
+            ConfigSource::System => {
+                Some(env::var_os("GIT_CONFIG_SYSTEM").map_or_else(|| "/etc/gitconfig".into(), PathBuf::from))
+            }
+            ConfigSource::Global => env::var_os("GIT_CONFIG_GLOBAL").map(PathBuf::from).or_else(|| {
+                let global = PathBuf::from(env::var_os("HOME")?).join(".gitconfig");
+                match xdg_config() {
+                    Some(user) if !global.is_file() && user.is_file() => Some(user),
+                    _ => Some(global),
+                }
+            }),
+            ConfigSource::User => xdg_config(),
+            ConfigSource::Repository => git_dir.map(|git_dir| common_dir(git_dir).join("config")),
+            ConfigSource::Worktree => git_dir.map(|git_dir| git_dir.join("config.worktree")),
+            ConfigSource::Env | ConfigSource::Cli => None,
+        }
+    }
+}
+
+pub mod build {
+    use std::path::PathBuf;
+
+    use quick_error::quick_error;
+
+    use crate::file::{from_env, from_paths};
+
+    quick_error! {
+        #[derive(Debug)]
+        /// The error returned by [`ConfigBuilder::try_build()`][super::ConfigBuilder::try_build()] and
+        /// [`Config::from_environment_and_repo()`][super::Config::from_environment_and_repo()].
+        #[allow(missing_docs)]
+        pub enum Error {
+            FromPaths(err: from_paths::Error) {
+                display("Could not load a configuration file")
+                source(err)
+                from()
+            }
+            FromEnv(err: from_env::Error) {
+                display("Could not load configuration from the environment")
+                source(err)
+                from()
+            }
+            MissingOverride { path: PathBuf } {
+                display("The configuration file at {:?} does not exist", path)
+            }
+            InvalidCliOverride { input: String } {
+                display("Configuration override '{}' is not of the form <section>[.<subsection>].<key>[=<value>]", input)
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Eq, Hash, Default)]
 pub struct ConfigBuilder {
     no_system: bool,
@@ -36,6 +114,8 @@ pub struct ConfigBuilder {
     override_system_config: Option<PathBuf>,
     override_global_config: Option<PathBuf>,
     override_repo_config: Option<PathBuf>,
+    git_dir: Option<PathBuf>,
+    cli_overrides: Vec<String>,
 }
 
 impl ConfigBuilder {
@@ -84,57 +164,172 @@ impl ConfigBuilder {
 
     /// Sets where to read the repository-specific configuration file. This
     /// is equivalent to setting `GIT_CONFIG`. If none is provided, then the
-    /// builder will look in the default location, `.git/config`.
+    /// builder will look in the default location, `config` in the
+    /// [git directory][Self::git_dir()].
     #[must_use]
     pub fn repository_config_path(&mut self, path: Option<PathBuf>) -> &mut Self {
         self.override_repo_config = path;
         self
     }
 
+    /// Sets the git directory of the repository whose configuration files to read,
+    /// which is also used to evaluate `includeIf` conditions. If none is provided,
+    /// only configuration outside of a repository is read.
+    #[must_use]
+    pub fn git_dir(&mut self, git_dir: Option<PathBuf>) -> &mut Self {
+        self.git_dir = git_dir;
+        self
+    }
+
+    /// Sets configuration overrides as passed to `git -c`, each of the form
+    /// `section[.subsection].key=value`, or `section[.subsection].key` to set it to `true`.
+    /// Later overrides take precedence over earlier ones.
+    #[must_use]
+    pub fn cli_overrides(&mut self, overrides: Vec<String>) -> &mut Self {
+        self.cli_overrides = overrides;
+        self
+    }
+
     /// Builds a config, ignoring any failed configuration files.
     #[must_use]
-    pub fn build(&self) -> Config {
-        let system_conf = if self.no_system { None } else { todo!() };
+    pub fn build(&self) -> Config<'static> {
+        self.build_inner(true).expect("errors are ignored")
+    }
 
-        let global_conf = {
-            let path = self
-                .override_global_config
-                .as_ref()
-                .map_or_else(|| Path::new(".git/config"), PathBuf::as_path);
+    /// Attempts to build a config, returning error if the environment variable
+    /// is invalid, if a config file is invalid, or if an overridden config file
+    /// does not exist. This is only recommended when you have a very controlled
+    /// system state. Otherwise, this will likely fail more often than you'd
+    /// like.
+    pub fn try_build(&self) -> Result<Config<'static>, build::Error> {
+        self.build_inner(false)
+    }
 
-            GitConfig::open(path).ok()
+    fn build_inner(&self, ignore_errors: bool) -> Result<Config<'static>, build::Error> {
+        let branch_name = self.git_dir.as_ref().and_then(|git_dir| head_branch_name(git_dir));
+        let options = from_paths::Options {
+            git_dir: self.git_dir.as_deref(),
+            branch_name: branch_name.as_ref().map(|name| name.as_bstr()),
+            ..Default::default()
         };
 
-        let env_conf = if self.load_env_conf {
-            // TODO: when bringing up the system, make sure options can be passed. Have to review this entire module first though.
-            GitConfig::from_env(&from_paths::Options::default()).ok().flatten()
-        } else {
-            None
-        };
+        let mut config = Config::default();
+        if !self.no_system && !env_bool("GIT_CONFIG_NOSYSTEM") {
+            match &self.override_system_config {
+                Some(path) => config.load(ConfigSource::System, path, true, ignore_errors, &options)?,
+                None => {
+                    let path = env::var_os("GIT_CONFIG_SYSTEM").map_or_else(|| "/etc/gitconfig".into(), PathBuf::from);
+                    config.load(ConfigSource::System, &path, false, ignore_errors, &options)?;
+                }
+            }
+        }
 
-        // let user_conf = if self.
+        match &self.override_global_config {
+            Some(path) => config.load(ConfigSource::Global, path, true, ignore_errors, &options)?,
+            None => {
+                if let Some(path) = env::var_os("GIT_CONFIG_GLOBAL") {
+                    config.load(ConfigSource::Global, Path::new(&path), false, ignore_errors, &options)?;
+                } else {
+                    let home = env::var_os("HOME").map(PathBuf::from);
+                    let xdg_config_home = env::var_os("XDG_CONFIG_HOME")
+                        .filter(|dir| !dir.is_empty())
+                        .map(PathBuf::from)
+                        .or_else(|| home.as_ref().map(|home| home.join(".config")));
+                    if let Some(dir) = xdg_config_home {
+                        let path = dir.join("git").join("config");
+                        config.load(ConfigSource::User, &path, false, ignore_errors, &options)?;
+                    }
+                    if let Some(home) = home {
+                        config.load(
+                            ConfigSource::Global,
+                            &home.join(".gitconfig"),
+                            false,
+                            ignore_errors,
+                            &options,
+                        )?;
+                    }
+                }
+            }
+        }
+
+        match (&self.override_repo_config, &self.git_dir) {
+            (Some(path), _) => config.load(ConfigSource::Repository, path, true, ignore_errors, &options)?,
+            (None, Some(git_dir)) => {
+                let path = common_dir(git_dir).join("config");
+                config.load(ConfigSource::Repository, &path, false, ignore_errors, &options)?;
+            }
+            (None, None) => {}
+        }
+        if let Some(git_dir) = &self.git_dir {
+            let uses_worktree_config = config.repository_conf.as_ref().map_or(false, |repo| {
+                repo.value::<Boolean<'_>>("extensions", None, "worktreeConfig")
+                    .map_or(false, |value| value.to_bool())
+            });
+            if uses_worktree_config {
+                let path = git_dir.join("config.worktree");
+                config.load(ConfigSource::Worktree, &path, false, ignore_errors, &options)?;
+            }
+        }
+
+        if self.load_env_conf {
+            match GitConfig::from_env(&options) {
+                Ok(env_conf) => config.env_conf = env_conf,
+                Err(_) if ignore_errors => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
 
-        Config {
-            system_conf,
-            global_conf,
-            user_conf: todo!(),
-            repository_conf: todo!(),
-            worktree_conf: todo!(),
-            env_conf,
-            cli_conf: todo!(),
+        let mut cli_conf = GitConfig::new();
+        for input in &self.cli_overrides {
+            let (key, value) = match input.split_once('=') {
+                Some((key, value)) => (key, value),
+                None => (input.as_str(), "true"),
+            };
+            if !key.contains('.') || !cli_conf.push_value_by_key(key, Cow::Owned(value.as_bytes().to_owned())) {
+                if ignore_errors {
+                    continue;
+                }
+                return Err(build::Error::InvalidCliOverride { input: input.clone() });
+            }
         }
+        if !cli_conf.is_empty() {
+            config.cli_conf = Some(cli_conf);
+        }
+
+        Ok(config)
     }
+}
 
-    /// Attempts to build a config, returning error if the environment variable
-    /// is invalid, if a config file is invalid, or if an overridden config file
-    /// does not exist. This is only recommended when you have a very controlled
-    /// system state. Otherwise, this will likely fail more often than you'd
-    /// like.
-    pub fn try_build(&self) -> Result<Config, ()> {
-        todo!()
+/// Return the directory containing the configuration shared by all worktrees, which differs from `git_dir` for linked
+/// worktrees.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir.to_owned(),
     }
 }
 
+/// Return the full name of the branch `HEAD` of `git_dir` points to for use in `includeIf.onbranch:` conditions, or `None`
+/// if `HEAD` is detached or can't be read.
+fn head_branch_name(git_dir: &Path) -> Option<git_ref::FullName> {
+    let store = git_ref::file::Store::at(git_dir, git_ref::store::WriteReflog::Disable, git_hash::Kind::Sha1);
+    match store.try_find("HEAD").ok()??.target {
+        git_ref::Target::Symbolic(name) => Some(name),
+        git_ref::Target::Peeled(_) => None,
+    }
+}
+
+/// Read the environment variable `name` as boolean like git does, which also accepts integers as `true` unless they are `0`.
+fn env_bool(name: &str) -> bool {
+    env::var(name).map_or(false, |value| match value.parse::<i64>() {
+        Ok(number) => number != 0,
+        Err(_) => Boolean::try_from(value.as_bytes()).map_or(false, |value| value.to_bool()),
+    })
+}
+
+/// A configuration assembled from all files and overrides git would read, each kept separately along with its
+/// [source][ConfigSource].
+#[derive(Default)]
 pub struct Config<'config> {
     system_conf: Option<GitConfig<'config>>,
     global_conf: Option<GitConfig<'config>>,
@@ -143,6 +338,23 @@ pub struct Config<'config> {
     worktree_conf: Option<GitConfig<'config>>,
     env_conf: Option<GitConfig<'config>>,
     cli_conf: Option<GitConfig<'config>>,
+    paths: HashMap<ConfigSource, PathBuf>,
+}
+
+impl Config<'static> {
+    /// Read all configuration git would use for the repository at `git_dir`, or only the configuration outside of
+    /// repositories if `None`, in the system, user, global, repository and worktree configuration files along with the
+    /// values set by `GIT_CONFIG_COUNT`, `GIT_CONFIG_KEY_<n>` and `GIT_CONFIG_VALUE_<n>` environment variables and the
+    /// `cli_overrides` as passed to `git -c`.
+    ///
+    /// Configuration files that don't exist are skipped, which is also the case for all configuration files of a
+    /// repository that doesn't exist. For more control, use a [`ConfigBuilder`].
+    pub fn from_environment_and_repo(git_dir: Option<&Path>, cli_overrides: &[&str]) -> Result<Self, build::Error> {
+        ConfigBuilder::new()
+            .git_dir(git_dir.map(ToOwned::to_owned))
+            .cli_overrides(cli_overrides.iter().map(|s| s.to_string()).collect())
+            .try_build()
+    }
 }
 
 impl<'config> Config<'config> {
@@ -158,7 +370,9 @@ impl<'config> Config<'config> {
             .map(|(value, _)| value)
     }
 
-    fn value_with_source<T: TryFrom<Cow<'config, [u8]>>>(
+    /// Retrieves the value with the highest precedence along with its source, or `None` if it isn't set or if it
+    /// can't be converted.
+    pub fn value_with_source<T: TryFrom<Cow<'config, [u8]>>>(
         &'config self,
         section_name: &str,
         subsection_name: Option<&str>,
@@ -202,24 +416,159 @@ impl<'config> Config<'config> {
 
         for (conf, source) in mapping.iter() {
             if let Some(conf) = conf {
-                return Ok(Some((conf.value(section_name, subsection_name, key)?, *source)));
+                match conf.value(section_name, subsection_name, key) {
+                    Ok(value) => return Ok(Some((value, *source))),
+                    Err(GitConfigError::FailedConversion) => return Err(GitConfigError::FailedConversion),
+                    Err(_) => continue,
+                }
             }
         }
 
         Ok(None)
     }
 
+    /// Returns all values of `key` in `section_name` and `subsection_name`
+    /// along with their source and the path of the file defining them, in the
+    /// order git would report them with `git config --show-origin --get-all`,
+    /// that is the value with the highest precedence comes last.
+    ///
+    /// The path is the one of the included file for values defined in files
+    /// included by the configuration of the source, and `None` for values
+    /// that weren't read from a file.
+    #[must_use]
+    pub fn raw_multi_value_with_source(
+        &self,
+        section_name: &str,
+        subsection_name: Option<&str>,
+        key: &str,
+    ) -> Vec<(Cow<'_, [u8]>, ConfigSource, Option<&Path>)> {
+        self.mapping()
+            .iter()
+            .rev()
+            .filter_map(|&(conf, source)| conf.as_ref().map(|conf| (conf, source)))
+            .flat_map(|(conf, source)| {
+                conf.get_raw_multi_value_with_path(section_name, subsection_name, key)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |(value, path)| (value, source, path))
+            })
+            .collect()
+    }
+
+    /// Returns the value of `key` in a section named `section_name` whose
+    /// subsection is the URL best matching `url` across all configuration
+    /// files along with its source, like `git config --get-urlmatch` does.
+    /// See [`GitConfig::get_raw_value_for_url()`] for details.
+    #[must_use]
+    pub fn raw_value_for_url_with_source(
+        &self,
+        section_name: &str,
+        url: &str,
+        key: &str,
+    ) -> Option<(Cow<'_, [u8]>, ConfigSource)> {
+        let url = urlmatch::Url::parse(url);
+        let mut best = None;
+        for &(conf, source) in self.mapping().iter().rev() {
+            if let Some(Ok(Some((url_match, value)))) = conf
+                .as_ref()
+                .map(|conf| conf.best_url_match(section_name, url.as_ref(), key))
+            {
+                if best
+                    .as_ref()
+                    .map_or(true, |(best_match, _, _)| url_match >= *best_match)
+                {
+                    best = Some((url_match, value, source));
+                }
+            }
+        }
+        best.map(|(_, value, source)| (value, source))
+    }
+
+    /// Returns all entries of all configurations along with their source, each
+    /// with the header of its section, its key, its normalized value which is
+    /// `None` for keys without value, its source and the path of the file
+    /// defining it like [`raw_multi_value_with_source()`][Self::raw_multi_value_with_source()].
+    /// Like `git config --list`, entries with lower precedence come first.
+    #[must_use]
+    pub fn entries_with_source(
+        &self,
+    ) -> Vec<(
+        &ParsedSectionHeader<'_>,
+        &Key<'_>,
+        Option<Cow<'_, [u8]>>,
+        ConfigSource,
+        Option<&Path>,
+    )> {
+        self.mapping()
+            .iter()
+            .rev()
+            .filter_map(|&(conf, source)| conf.as_ref().map(|conf| (conf, source)))
+            .flat_map(|(conf, source)| {
+                conf.entries_with_path()
+                    .into_iter()
+                    .map(move |(header, key, value, path)| (header, key, value, source, path))
+            })
+            .collect()
+    }
+
+    /// Returns the path of the file the configuration of `source` was read
+    /// from, or `None` if there is no such file or if the source isn't a file.
+    #[must_use]
+    pub fn path(&self, source: ConfigSource) -> Option<&Path> {
+        self.paths.get(&source).map(PathBuf::as_path)
+    }
+
     /// Returns a mapping from [`GitConfig`] to [`ConfigSource`]
-    const fn mapping(&self) -> [(&Option<GitConfig>, ConfigSource); 6] {
+    const fn mapping(&self) -> [(&Option<GitConfig>, ConfigSource); 7] {
         [
             (&self.cli_conf, ConfigSource::Cli),
             (&self.env_conf, ConfigSource::Env),
+            (&self.worktree_conf, ConfigSource::Worktree),
             (&self.repository_conf, ConfigSource::Repository),
-            (&self.user_conf, ConfigSource::User),
             (&self.global_conf, ConfigSource::Global),
+            (&self.user_conf, ConfigSource::User),
             (&self.system_conf, ConfigSource::System),
         ]
     }
+
+    fn slot_mut(&mut self, source: ConfigSource) -> &mut Option<GitConfig<'config>> {
+        match source {
+            ConfigSource::System => &mut self.system_conf,
+            ConfigSource::Global => &mut self.global_conf,
+            ConfigSource::User => &mut self.user_conf,
+            ConfigSource::Repository => &mut self.repository_conf,
+            ConfigSource::Worktree => &mut self.worktree_conf,
+            ConfigSource::Env => &mut self.env_conf,
+            ConfigSource::Cli => &mut self.cli_conf,
+        }
+    }
+
+    /// Read the file at `path` with all of its includes resolved as the configuration of `source`, if it exists.
+    fn load(
+        &mut self,
+        source: ConfigSource,
+        path: &Path,
+        is_override: bool,
+        ignore_errors: bool,
+        options: &from_paths::Options<'_>,
+    ) -> Result<(), build::Error> {
+        if !path.is_file() {
+            return if is_override && !ignore_errors {
+                Err(build::Error::MissingOverride { path: path.to_owned() })
+            } else {
+                Ok(())
+            };
+        }
+        match GitConfig::from_paths(vec![path.to_owned()], options) {
+            Ok(config) => {
+                *self.slot_mut(source) = Some(config);
+                self.paths.insert(source, path.to_owned());
+            }
+            Err(_) if ignore_errors => {}
+            Err(err) => return Err(err.into()),
+        }
+        Ok(())
+    }
 }
 
 /// Lower-level interface for directly accessing a
@@ -233,6 +582,7 @@ impl<'config> Config<'config> {
             ConfigSource::Global => self.global_conf.as_ref(),
             ConfigSource::User => self.user_conf.as_ref(),
             ConfigSource::Repository => self.repository_conf.as_ref(),
+            ConfigSource::Worktree => self.worktree_conf.as_ref(),
             ConfigSource::Env => self.env_conf.as_ref(),
             ConfigSource::Cli => self.cli_conf.as_ref(),
         }
@@ -242,13 +592,6 @@ impl<'config> Config<'config> {
     /// if one was found during initialization.
     #[must_use]
     pub fn get_config_mut(&mut self, source: ConfigSource) -> Option<&mut GitConfig<'config>> {
-        match source {
-            ConfigSource::System => self.system_conf.as_mut(),
-            ConfigSource::Global => self.global_conf.as_mut(),
-            ConfigSource::User => self.user_conf.as_mut(),
-            ConfigSource::Repository => self.repository_conf.as_mut(),
-            ConfigSource::Env => self.env_conf.as_mut(),
-            ConfigSource::Cli => self.cli_conf.as_mut(),
-        }
+        self.slot_mut(source).as_mut()
     }
 }
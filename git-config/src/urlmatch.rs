@@ -0,0 +1,192 @@
+//! Match URLs against the URLs used as subsections of configuration like `http.<url>.*`, the same way as
+//! `git config --get-urlmatch` does.
+
+/// A URL normalized for matching, with a lower-case scheme and host, without default port, query and fragment,
+/// and with a path free of `.` and `..` components and unnecessary percent-encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    scheme: String,
+    user: Option<String>,
+    host: String,
+    port: Option<u16>,
+    path: String,
+}
+
+/// Describes how well a [`Url`] matched, with better matches comparing greater.
+///
+/// Matches are ranked by the length of the matching host first, then by the length of the matching path, and finally by
+/// whether the user name matched. Values without URL in their subsection match with the lowest possible rank, the default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match {
+    /// The length of the host of the URL that matched, which is longer for more specific hosts.
+    pub host_len: usize,
+    /// The length of the matching path, including its implicit trailing slash.
+    pub path_len: usize,
+    /// True if the URL that matched had a user name, which then matched as well.
+    pub user_matched: bool,
+}
+
+impl Url {
+    /// Parse and normalize `url`, or return `None` if it isn't a URL with a scheme and a host.
+    pub fn parse(url: &str) -> Option<Self> {
+        let (scheme, rest) = url.split_once("://")?;
+        let is_valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !is_valid_scheme {
+            return None;
+        }
+        let scheme = scheme.to_ascii_lowercase();
+
+        let rest = rest.split(|c: char| c == '?' || c == '#').next().unwrap_or_default();
+        let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let (user, host_and_port) = match authority.rfind('@') {
+            Some(pos) => {
+                let user = authority[..pos].split(':').next().unwrap_or_default();
+                (Some(normalize_escapes(user)), &authority[pos + 1..])
+            }
+            None => (None, authority),
+        };
+        let (host, port) = if host_and_port.starts_with('[') {
+            let end = host_and_port.find(']')?;
+            let (host, port) = host_and_port.split_at(end + 1);
+            if !port.is_empty() && !port.starts_with(':') {
+                return None;
+            }
+            (host, port.get(1..))
+        } else {
+            match host_and_port.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (host_and_port, None),
+            }
+        };
+        if host.is_empty() && scheme != "file" {
+            return None;
+        }
+
+        let port = match port.filter(|port| !port.is_empty()) {
+            Some(port) => {
+                if !port.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                match port.parse::<u16>().ok()? {
+                    0 => return None,
+                    80 if scheme == "http" => None,
+                    443 if scheme == "https" => None,
+                    port => Some(port),
+                }
+            }
+            None => None,
+        };
+
+        Some(Url {
+            host: normalize_escapes(host).to_ascii_lowercase(),
+            path: normalize_path(&normalize_escapes(path))?,
+            scheme,
+            user,
+            port,
+        })
+    }
+
+    /// Return how well this URL is matched by `pattern`, the URL of a configuration subsection, or `None` if it doesn't
+    /// match at all.
+    ///
+    /// For a match, the scheme and port must be the same, and so must be the host, except for host components of `pattern`
+    /// that are `*`, which match any single component. The path of `pattern` must be a prefix of the path of this URL ending
+    /// at a component boundary, and if `pattern` has a user name it must be the same as well.
+    pub fn matches(&self, pattern: &Url) -> Option<Match> {
+        if self.scheme != pattern.scheme || self.port != pattern.port || !host_matches(&self.host, &pattern.host) {
+            return None;
+        }
+        let user_matched = match &pattern.user {
+            Some(user) if self.user.as_ref() == Some(user) => true,
+            Some(_) => return None,
+            None => false,
+        };
+        Some(Match {
+            host_len: pattern.host.len(),
+            path_len: path_match_len(&self.path, &pattern.path)?,
+            user_matched,
+        })
+    }
+}
+
+fn host_matches(host: &str, pattern: &str) -> bool {
+    host.split('.').count() == pattern.split('.').count()
+        && host
+            .split('.')
+            .zip(pattern.split('.'))
+            .all(|(component, pattern)| pattern == "*" || component == pattern)
+}
+
+/// Return the length of the match of `prefix` with `path`, including the implicit trailing slash, if it is a prefix ending
+/// at a path component boundary.
+fn path_match_len(path: &str, prefix: &str) -> Option<usize> {
+    if prefix == "/" {
+        return Some(1);
+    }
+    let prefix = prefix.strip_suffix('/').unwrap_or(prefix);
+    let rest = path.strip_prefix(prefix)?;
+    if rest.is_empty() || rest.starts_with('/') {
+        Some(prefix.len() + 1)
+    } else {
+        None
+    }
+}
+
+/// Decode percent-encoded characters that don't need encoding and make the hex digits of all others upper-case.
+fn normalize_escapes(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = if bytes[i] == b'%' {
+            bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match decoded {
+            Some(byte) if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') => {
+                out.push(byte);
+                i += 3;
+            }
+            Some(_) => {
+                out.push(b'%');
+                out.extend(bytes[i + 1..i + 3].iter().map(u8::to_ascii_uppercase));
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).expect("only ASCII characters were changed")
+}
+
+/// Resolve `.` and `..` components of `path`, or return `None` if `..` would leave the root.
+fn normalize_path(path: &str) -> Option<String> {
+    let parts: Vec<_> = path.split('/').skip(1).collect();
+    let mut components = Vec::new();
+    for (index, part) in parts.iter().enumerate() {
+        match *part {
+            "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            "" if index + 1 == parts.len() => {}
+            component => components.push(component),
+        }
+    }
+    let ends_with_slash = parts.last().map_or(true, |last| matches!(*last, "" | "." | ".."));
+    let mut out = format!("/{}", components.join("/"));
+    if ends_with_slash && !components.is_empty() {
+        out.push('/');
+    }
+    Some(out)
+}
@@ -1,6 +1,8 @@
 type Result = std::result::Result<(), Box<dyn std::error::Error>>;
 
+mod fs;
 mod git_config;
 mod parser;
+mod urlmatch;
 mod value;
 mod values;
@@ -0,0 +1,406 @@
+use std::{borrow::Cow, env, ffi::OsString, fs, path::Path};
+
+use git_config::fs::{build, Config, ConfigBuilder, ConfigSource};
+use serial_test::serial;
+use tempfile::tempdir;
+
+struct Env<'a> {
+    altered_vars: Vec<(&'a str, Option<OsString>)>,
+}
+
+impl<'a> Env<'a> {
+    fn new() -> Self {
+        Env {
+            altered_vars: Vec::new(),
+        }
+    }
+
+    fn set(mut self, var: &'a str, value: impl AsRef<std::ffi::OsStr>) -> Self {
+        self.altered_vars.push((var, env::var_os(var)));
+        env::set_var(var, value);
+        self
+    }
+}
+
+impl<'a> Drop for Env<'a> {
+    fn drop(&mut self) {
+        for (var, previous_value) in self.altered_vars.iter().rev() {
+            match previous_value {
+                Some(value) => env::set_var(var, value),
+                None => env::remove_var(var),
+            }
+        }
+    }
+}
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+#[serial]
+fn values_are_layered_by_precedence_and_keep_their_source() {
+    let dir = tempdir().unwrap();
+    let home = dir.path().join("home");
+    let git_dir = dir.path().join("repo").join(".git");
+    let _env = Env::new()
+        .set("GIT_CONFIG_NOSYSTEM", "1")
+        .set("HOME", &home)
+        .set("XDG_CONFIG_HOME", dir.path().join("xdg"))
+        .set("GIT_CONFIG_COUNT", "1")
+        .set("GIT_CONFIG_KEY_0", "core.env")
+        .set("GIT_CONFIG_VALUE_0", "env");
+
+    write(
+        &dir.path().join("xdg").join("git").join("config"),
+        "[core]\n  key = user\n  user = user\n",
+    );
+    write(&home.join(".gitconfig"), "[core]\n  key = global\n  global = global\n");
+    write(
+        &git_dir.join("config"),
+        "[core]\n  key = repo\n[extensions]\n  worktreeConfig = true\n",
+    );
+    write(
+        &git_dir.join("config.worktree"),
+        "[core]\n  worktree = worktree\n  env = worktree\n",
+    );
+
+    let config = Config::from_environment_and_repo(Some(&git_dir), &["core.cli=cli", "core.flag"]).unwrap();
+
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "key"),
+        Some((Cow::Borrowed(&b"repo"[..]), ConfigSource::Repository))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "user"),
+        Some((Cow::Borrowed(&b"user"[..]), ConfigSource::User))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "global"),
+        Some((Cow::Borrowed(&b"global"[..]), ConfigSource::Global))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "worktree"),
+        Some((Cow::Borrowed(&b"worktree"[..]), ConfigSource::Worktree))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "env"),
+        Some((Cow::Borrowed(&b"env"[..]), ConfigSource::Env))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "cli"),
+        Some((Cow::Borrowed(&b"cli"[..]), ConfigSource::Cli))
+    );
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "flag"),
+        Some((Cow::Borrowed(&b"true"[..]), ConfigSource::Cli))
+    );
+    assert_eq!(config.value_with_source::<Cow<'_, [u8]>>("core", None, "missing"), None);
+
+    let user_config = dir.path().join("xdg").join("git").join("config");
+    let global_config = home.join(".gitconfig");
+    let repo_config = git_dir.join("config");
+    assert_eq!(
+        config.raw_multi_value_with_source("core", None, "key"),
+        vec![
+            (
+                Cow::Borrowed(&b"user"[..]),
+                ConfigSource::User,
+                Some(user_config.as_path())
+            ),
+            (
+                Cow::Borrowed(&b"global"[..]),
+                ConfigSource::Global,
+                Some(global_config.as_path())
+            ),
+            (
+                Cow::Borrowed(&b"repo"[..]),
+                ConfigSource::Repository,
+                Some(repo_config.as_path())
+            ),
+        ]
+    );
+    assert_eq!(
+        config.raw_multi_value_with_source("core", None, "env"),
+        vec![
+            (
+                Cow::Borrowed(&b"worktree"[..]),
+                ConfigSource::Worktree,
+                Some(git_dir.join("config.worktree").as_path())
+            ),
+            (Cow::Borrowed(&b"env"[..]), ConfigSource::Env, None),
+        ],
+        "values that weren't read from a file have no path"
+    );
+
+    assert_eq!(
+        config.path(ConfigSource::User),
+        Some(dir.path().join("xdg").join("git").join("config").as_path())
+    );
+    assert_eq!(
+        config.path(ConfigSource::Global),
+        Some(home.join(".gitconfig").as_path())
+    );
+    assert_eq!(
+        config.path(ConfigSource::Repository),
+        Some(git_dir.join("config").as_path())
+    );
+    assert_eq!(
+        config.path(ConfigSource::Worktree),
+        Some(git_dir.join("config.worktree").as_path())
+    );
+    assert_eq!(config.path(ConfigSource::System), None);
+    assert_eq!(config.path(ConfigSource::Cli), None);
+}
+
+#[test]
+#[serial]
+fn worktree_config_is_ignored_unless_enabled() {
+    let dir = tempdir().unwrap();
+    let _env = Env::new()
+        .set("GIT_CONFIG_NOSYSTEM", "1")
+        .set("HOME", dir.path())
+        .set("XDG_CONFIG_HOME", dir.path());
+    write(&dir.path().join("config"), "[core]\n  key = repo\n");
+    write(&dir.path().join("config.worktree"), "[core]\n  key = worktree\n");
+
+    let config = Config::from_environment_and_repo(Some(dir.path()), &[]).unwrap();
+    assert_eq!(
+        config.value_with_source::<Cow<'_, [u8]>>("core", None, "key"),
+        Some((Cow::Borrowed(&b"repo"[..]), ConfigSource::Repository))
+    );
+    assert!(config.get_config(ConfigSource::Worktree).is_none());
+}
+
+#[test]
+#[serial]
+fn cli_overrides_must_name_a_section() {
+    assert!(matches!(
+        Config::from_environment_and_repo(None, &["key=value"]),
+        Err(build::Error::InvalidCliOverride { input }) if input == "key=value"
+    ));
+
+    let config = ConfigBuilder::new()
+        .no_system(true)
+        .cli_overrides(vec!["key=value".into(), "a.b=c".into()])
+        .build();
+    assert_eq!(
+        config.value::<Cow<'_, [u8]>>("a", None, "b"),
+        Some(Cow::Borrowed(&b"c"[..]))
+    );
+}
+
+#[test]
+#[serial]
+fn overridden_files_must_exist() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("missing");
+    let res = ConfigBuilder::new()
+        .no_system(true)
+        .global_config_path(Some(path.clone()))
+        .try_build();
+    assert!(matches!(res, Err(build::Error::MissingOverride { path: missing }) if missing == path));
+
+    let config = ConfigBuilder::new()
+        .no_system(true)
+        .global_config_path(Some(path))
+        .build();
+    assert!(config.get_config(ConfigSource::Global).is_none());
+}
+
+#[test]
+fn url_matches_are_ranked_across_files() {
+    let dir = tempdir().unwrap();
+    let global = dir.path().join("global");
+    let repo = dir.path().join("repo");
+    write(
+        &global,
+        "[http \"https://example.com/org\"]\n  sslVerify = global\n[http]\n  proxy = global\n",
+    );
+    write(
+        &repo,
+        "[http]\n  sslVerify = repo\n[http \"https://example.com\"]\n  proxy = repo\n",
+    );
+
+    let config = ConfigBuilder::new()
+        .no_system(true)
+        .load_environment_entries(false)
+        .global_config_path(Some(global))
+        .repository_config_path(Some(repo))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(
+        config.raw_value_for_url_with_source("http", "https://example.com/org/repo", "sslVerify"),
+        Some((Cow::Borrowed(&b"global"[..]), ConfigSource::Global)),
+        "the more specific url wins even though it has a lower precedence"
+    );
+    assert_eq!(
+        config.raw_value_for_url_with_source("http", "https://example.com/other", "sslVerify"),
+        Some((Cow::Borrowed(&b"repo"[..]), ConfigSource::Repository))
+    );
+    assert_eq!(
+        config.raw_value_for_url_with_source("http", "https://example.com/org/repo", "proxy"),
+        Some((Cow::Borrowed(&b"repo"[..]), ConfigSource::Repository))
+    );
+    assert_eq!(
+        config.raw_value_for_url_with_source("http", "https://example.org", "proxy"),
+        Some((Cow::Borrowed(&b"global"[..]), ConfigSource::Global))
+    );
+    assert_eq!(
+        config.raw_value_for_url_with_source("http", "https://example.org", "other"),
+        None
+    );
+}
+
+#[test]
+#[serial]
+fn entries_of_all_sources_are_listed_with_lowest_precedence_first() {
+    let dir = tempdir().unwrap();
+    let git_dir = dir.path().join("repo");
+    let _env = Env::new()
+        .set("GIT_CONFIG_NOSYSTEM", "1")
+        .set("HOME", dir.path())
+        .set("XDG_CONFIG_HOME", dir.path().join("xdg"));
+    write(&dir.path().join(".gitconfig"), "[user]\n  name = global\n");
+    write(
+        &git_dir.join("config"),
+        "[user]\n  name = repo\n[include]\n  path = included\n[remote \"origin\"]\n  url = u\n",
+    );
+    write(&git_dir.join("included"), "[core]\n  bare\n  empty =\n");
+
+    let config = Config::from_environment_and_repo(Some(&git_dir), &["core.cli=cli"]).unwrap();
+    let entries: Vec<_> = config
+        .entries_with_source()
+        .into_iter()
+        .map(|(header, key, value, source, path)| {
+            (
+                format!(
+                    "{}{}.{}",
+                    header.name.0,
+                    header
+                        .subsection_name
+                        .as_ref()
+                        .map(|name| format!(".{}", name))
+                        .unwrap_or_default(),
+                    key.0
+                ),
+                value.map(|value| value.into_owned()),
+                source,
+                path.map(ToOwned::to_owned),
+            )
+        })
+        .collect();
+    let global_config = Some(dir.path().join(".gitconfig"));
+    let repo_config = Some(git_dir.join("config"));
+    let included_config = Some(git_dir.join("included"));
+    assert_eq!(
+        entries,
+        vec![
+            (
+                "user.name".into(),
+                Some(b"global".to_vec()),
+                ConfigSource::Global,
+                global_config
+            ),
+            (
+                "user.name".into(),
+                Some(b"repo".to_vec()),
+                ConfigSource::Repository,
+                repo_config.clone()
+            ),
+            (
+                "include.path".into(),
+                Some(b"included".to_vec()),
+                ConfigSource::Repository,
+                repo_config.clone()
+            ),
+            (
+                "core.bare".into(),
+                None,
+                ConfigSource::Repository,
+                included_config.clone()
+            ),
+            (
+                "core.empty".into(),
+                Some(Vec::new()),
+                ConfigSource::Repository,
+                included_config
+            ),
+            (
+                "remote.origin.url".into(),
+                Some(b"u".to_vec()),
+                ConfigSource::Repository,
+                repo_config
+            ),
+            ("core.cli".into(), Some(b"cli".to_vec()), ConfigSource::Cli, None),
+        ]
+    );
+}
+
+#[test]
+#[serial]
+fn onbranch_conditions_match_the_branch_head_points_to() {
+    let dir = tempdir().unwrap();
+    let _env = Env::new()
+        .set("GIT_CONFIG_NOSYSTEM", "1")
+        .set("HOME", dir.path())
+        .set("XDG_CONFIG_HOME", dir.path());
+    write(
+        &dir.path().join("config"),
+        "[core]\n  key = repo\n[includeIf \"onbranch:main\"]\n  path = included\n",
+    );
+    write(&dir.path().join("included"), "[core]\n  key = included\n");
+    let key = || {
+        let config = Config::from_environment_and_repo(Some(dir.path()), &[]).unwrap();
+        config.value::<Cow<'_, [u8]>>("core", None, "key").unwrap().into_owned()
+    };
+
+    write(&dir.path().join("HEAD"), "ref: refs/heads/main\n");
+    assert_eq!(key(), b"included");
+
+    write(&dir.path().join("HEAD"), "ref: refs/heads/other\n");
+    assert_eq!(key(), b"repo");
+
+    write(&dir.path().join("HEAD"), "0000000000000000000000000000000000000000\n");
+    assert_eq!(key(), b"repo", "a detached HEAD is on no branch");
+}
+
+#[test]
+#[serial]
+fn storage_locations_follow_git() {
+    let dir = tempdir().unwrap();
+    let git_dir = dir.path().join("repo");
+    let xdg_config = dir.path().join("xdg").join("git").join("config");
+    let _env = Env::new()
+        .set("HOME", dir.path())
+        .set("XDG_CONFIG_HOME", dir.path().join("xdg"))
+        .set("GIT_CONFIG_SYSTEM", dir.path().join("system"));
+
+    assert_eq!(
+        ConfigSource::System.storage_location(None),
+        Some(dir.path().join("system"))
+    );
+    assert_eq!(
+        ConfigSource::Global.storage_location(None),
+        Some(dir.path().join(".gitconfig"))
+    );
+    write(&xdg_config, "");
+    assert_eq!(
+        ConfigSource::Global.storage_location(None),
+        Some(xdg_config.clone()),
+        "the user configuration is used if it exists but ~/.gitconfig doesn't"
+    );
+    assert_eq!(ConfigSource::User.storage_location(None), Some(xdg_config));
+    assert_eq!(
+        ConfigSource::Repository.storage_location(Some(&git_dir)),
+        Some(git_dir.join("config"))
+    );
+    assert_eq!(
+        ConfigSource::Worktree.storage_location(Some(&git_dir)),
+        Some(git_dir.join("config.worktree"))
+    );
+    assert_eq!(ConfigSource::Repository.storage_location(None), None);
+    assert_eq!(ConfigSource::Cli.storage_location(Some(&git_dir)), None);
+}
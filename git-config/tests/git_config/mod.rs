@@ -748,6 +748,256 @@ mod from_paths_tests {
     }
 }
 
+#[cfg(test)]
+mod conditional_includes {
+    use std::{borrow::Cow, fs, path::Path};
+
+    use bstr::ByteSlice;
+    use git_config::file::{from_paths, GitConfig};
+    use tempfile::tempdir;
+
+    fn escape_backslashes(path: &Path) -> String {
+        path.to_str().unwrap().replace('\\', "\\\\")
+    }
+
+    fn write_included_file(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("included");
+        fs::write(&path, "[core]\n  value = included\n").unwrap();
+        path
+    }
+
+    #[test]
+    fn included_files_are_placed_after_the_include_section() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let config_path = dir.path().join("config");
+        fs::write(
+            &config_path,
+            format!(
+                "[core]\n  value = before\n[include]\n  path = {}\n[core]\n  value = after\n",
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+
+        let config = GitConfig::from_paths(vec![config_path], &Default::default()).unwrap();
+        assert_eq!(
+            config.get_raw_multi_value("core", None, "value").unwrap(),
+            vec![
+                Cow::<[u8]>::Borrowed(b"before"),
+                Cow::Borrowed(b"included"),
+                Cow::Borrowed(b"after")
+            ]
+        );
+    }
+
+    #[test]
+    fn included_paths_are_recorded_whether_they_exist_or_not() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let missing = dir.path().join("missing");
+        let config_path = dir.path().join("config");
+        fs::write(
+            &config_path,
+            format!(
+                "[include]\n  path = {}\n  path = {}\n",
+                escape_backslashes(&missing),
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+
+        let config = GitConfig::from_paths(vec![config_path], &Default::default()).unwrap();
+        assert_eq!(config.included_paths(), &[missing, included]);
+        assert_eq!(config.get_raw_value("core", None, "value").unwrap().as_ref(), b"included");
+    }
+
+    #[test]
+    fn gitdir() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let git_dir = dir.path().join("repo").join(".git");
+        fs::create_dir_all(&git_dir).unwrap();
+        let config_path = dir.path().join("config");
+
+        let value_with_condition = |condition: &str| {
+            fs::write(
+                &config_path,
+                format!(
+                    "[core]\n  value = base\n[includeIf \"{}\"]\n  path = {}\n",
+                    condition,
+                    escape_backslashes(&included)
+                ),
+            )
+            .unwrap();
+            let options = from_paths::Options {
+                git_dir: Some(&git_dir),
+                ..Default::default()
+            };
+            let config = GitConfig::from_paths(vec![config_path.clone()], &options).unwrap();
+            config.get_raw_value("core", None, "value").unwrap().into_owned()
+        };
+
+        let real_dir = dir.path().canonicalize().unwrap().to_str().unwrap().replace('\\', "/");
+        for (condition, expected) in [
+            (format!("gitdir:{}/repo/.git", real_dir), "included"),
+            (format!("gitdir:{}/repo/", real_dir), "included"),
+            (format!("gitdir:{}/repo", real_dir), "base"),
+            ("gitdir:repo/.git".to_string(), "included"),
+            ("gitdir:repo/".to_string(), "included"),
+            ("gitdir:./repo/.git".to_string(), "included"),
+            ("gitdir:./".to_string(), "included"),
+            ("gitdir:./other/".to_string(), "base"),
+            ("gitdir:REPO/".to_string(), "base"),
+            ("gitdir/i:REPO/".to_string(), "included"),
+            ("gitdir/i:OTHER/".to_string(), "base"),
+        ] {
+            assert_eq!(
+                value_with_condition(&condition).as_bstr(),
+                expected.as_bytes().as_bstr(),
+                "{}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn gitdir_never_matches_without_git_dir() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let config_path = dir.path().join("config");
+        fs::write(
+            &config_path,
+            format!(
+                "[core]\n  value = base\n[includeIf \"gitdir:**\"]\n  path = {}\n",
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+
+        let config = GitConfig::from_paths(vec![config_path], &Default::default()).unwrap();
+        assert_eq!(
+            config.get_raw_value("core", None, "value"),
+            Ok(Cow::<[u8]>::Borrowed(b"base"))
+        );
+    }
+
+    #[test]
+    fn onbranch() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let config_path = dir.path().join("config");
+
+        let value_with_condition = |condition: &str, branch_name: Option<&str>| {
+            fs::write(
+                &config_path,
+                format!(
+                    "[core]\n  value = base\n[includeIf \"onbranch:{}\"]\n  path = {}\n",
+                    condition,
+                    escape_backslashes(&included)
+                ),
+            )
+            .unwrap();
+            let options = from_paths::Options {
+                branch_name: branch_name.map(|name| name.as_bytes().as_bstr()),
+                ..Default::default()
+            };
+            let config = GitConfig::from_paths(vec![config_path.clone()], &options).unwrap();
+            config.get_raw_value("core", None, "value").unwrap().into_owned()
+        };
+
+        for (condition, branch_name, expected) in [
+            ("main", Some("refs/heads/main"), "included"),
+            ("main", Some("refs/heads/other"), "base"),
+            ("main", None, "base"),
+            ("main", Some("refs/tags/main"), "base"),
+            ("feature/", Some("refs/heads/feature/a/b"), "included"),
+            ("feature/*", Some("refs/heads/feature/a/b"), "base"),
+            ("feature/*", Some("refs/heads/feature/a"), "included"),
+            ("**/b", Some("refs/heads/feature/a/b"), "included"),
+        ] {
+            assert_eq!(
+                value_with_condition(condition, branch_name).as_bstr(),
+                expected.as_bytes().as_bstr(),
+                "{} {:?}",
+                condition,
+                branch_name
+            );
+        }
+    }
+
+    #[test]
+    fn hasconfig_remote_url_sees_remotes_defined_in_any_file() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let a_path = dir.path().join("a");
+        fs::write(
+            &a_path,
+            format!(
+                "[core]\n  value = base\n[includeIf \"hasconfig:remote.*.url:https://example.com/**\"]\n  path = {}\n",
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+        let b_path = dir.path().join("b");
+        fs::write(&b_path, "[remote \"origin\"]\n  url = https://example.com/org/repo\n").unwrap();
+
+        let config = GitConfig::from_paths(vec![a_path.clone(), b_path], &Default::default()).unwrap();
+        assert_eq!(
+            config.get_raw_value("core", None, "value"),
+            Ok(Cow::<[u8]>::Borrowed(b"included"))
+        );
+
+        let config = GitConfig::from_paths(vec![a_path], &Default::default()).unwrap();
+        assert_eq!(
+            config.get_raw_value("core", None, "value"),
+            Ok(Cow::<[u8]>::Borrowed(b"base"))
+        );
+    }
+
+    #[test]
+    fn hasconfig_remote_url_includes_must_not_define_remote_urls() {
+        let dir = tempdir().unwrap();
+        let included = dir.path().join("included");
+        fs::write(&included, "[remote \"other\"]\n  url = https://example.com/other\n").unwrap();
+        let config_path = dir.path().join("config");
+        fs::write(
+            &config_path,
+            format!(
+                "[remote \"origin\"]\n  url = https://example.com/repo\n[includeIf \"hasconfig:remote.*.url:https://example.com/*\"]\n  path = {}\n",
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+
+        let err = GitConfig::from_paths(vec![config_path], &Default::default()).unwrap_err();
+        assert!(
+            matches!(err, from_paths::Error::RemoteUrlInConditionalInclude { path: Some(path) } if path == included)
+        );
+    }
+
+    #[test]
+    fn unknown_conditions_are_ignored() {
+        let dir = tempdir().unwrap();
+        let included = write_included_file(dir.path());
+        let config_path = dir.path().join("config");
+        fs::write(
+            &config_path,
+            format!(
+                "[core]\n  value = base\n[includeIf \"unknown:value\"]\n  path = {}\n",
+                escape_backslashes(&included)
+            ),
+        )
+        .unwrap();
+
+        let config = GitConfig::from_paths(vec![config_path], &Default::default()).unwrap();
+        assert_eq!(
+            config.get_raw_value("core", None, "value"),
+            Ok(Cow::<[u8]>::Borrowed(b"base"))
+        );
+    }
+}
+
 #[cfg(test)]
 mod from_env_tests {
     use std::{borrow::Cow, env, fs};
@@ -954,6 +1204,85 @@ mod get_raw_value {
     }
 }
 
+#[cfg(test)]
+mod get_raw_value_for_url {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::{
+        file::{GitConfig, GitConfigError},
+        values::Boolean,
+    };
+
+    #[test]
+    fn most_specific_url_wins() {
+        let config = GitConfig::try_from(
+            r#"[http "https://example.com/org"]
+  v = path
+[http "https://example.com"]
+  v = host
+[http]
+  v = plain
+[http "https://user@example.com"]
+  v = user
+[http "https://*.example.com"]
+  v = wildcard
+[http "https://sub.example.com"]
+  v = exact-sub
+"#,
+        )
+        .unwrap();
+
+        for (url, expected) in [
+            ("https://example.com/org/repo", "path"),
+            ("https://example.com/other", "host"),
+            ("https://user@example.com/other", "user"),
+            ("https://user@example.com/org/x", "path"),
+            ("https://sub.example.com/", "exact-sub"),
+            ("https://x.example.com/", "wildcard"),
+            ("https://other.com/", "plain"),
+            ("not a url", "plain"),
+        ] {
+            assert_eq!(
+                config.get_raw_value_for_url("http", url, "v"),
+                Ok(Cow::Borrowed(expected.as_bytes())),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn later_values_win_if_urls_match_equally_well() {
+        let config = GitConfig::try_from(
+            "[http \"https://example.com\"]\n  v = 1\n[http \"https://EXAMPLE.com/\"]\n  v = 2\n  v = 3\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.get_raw_value_for_url("http", "https://example.com/repo", "v"),
+            Ok(Cow::<[u8]>::Borrowed(b"3"))
+        );
+    }
+
+    #[test]
+    fn missing_values() {
+        let config = GitConfig::try_from("[http \"https://example.com\"]\n  sslVerify = false\n").unwrap();
+        assert_eq!(
+            config.get_raw_value_for_url("http", "https://example.org", "sslVerify"),
+            Err(GitConfigError::KeyDoesNotExist)
+        );
+        assert!(matches!(
+            config.get_raw_value_for_url("core", "https://example.com", "sslVerify"),
+            Err(GitConfigError::SectionDoesNotExist(_))
+        ));
+        assert_eq!(
+            config
+                .value_for_url::<Boolean<'_>>("http", "https://example.com/repo", "sslVerify")
+                .map(|value| value.to_bool()),
+            Ok(false)
+        );
+    }
+}
+
 #[cfg(test)]
 mod get_value {
     use std::{borrow::Cow, convert::TryFrom, error::Error};
@@ -1090,6 +1419,233 @@ mod get_raw_multi_value {
     }
 }
 
+#[cfg(test)]
+mod append_raw_value {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::file::GitConfig;
+
+    #[test]
+    fn adds_a_line_after_the_last_entry_of_the_last_matching_section() {
+        let mut config = GitConfig::try_from("[core]\n  a = b\n[core]\n\tc=d ; comment\n\n[other]\n").unwrap();
+        config.append_raw_value("core", None, "a", Cow::Borrowed(b"e"));
+        assert_eq!(
+            config.to_string(),
+            "[core]\n  a = b\n[core]\n\tc=d ; comment\n\ta=e\n\n[other]\n"
+        );
+        assert_eq!(
+            config.get_raw_multi_value("core", None, "a").unwrap(),
+            vec![Cow::<[u8]>::Borrowed(b"b"), Cow::Borrowed(b"e")]
+        );
+    }
+
+    #[test]
+    fn keeps_windows_line_endings() {
+        let mut config = GitConfig::try_from("[core]\r\n\ta = b\r\n").unwrap();
+        config.append_raw_value("core", None, "a", Cow::Borrowed(b"c"));
+        assert_eq!(config.to_string(), "[core]\r\n\ta = b\r\n\ta = c\r\n");
+    }
+
+    #[test]
+    fn adds_entries_right_after_the_header_of_sections_without_entries() {
+        let mut config = GitConfig::try_from("[core]\n\n# comment\n").unwrap();
+        config.append_raw_value("core", None, "a", Cow::Borrowed(b"b"));
+        assert_eq!(config.to_string(), "[core]\n\ta = b\n\n# comment\n");
+    }
+
+    #[test]
+    fn creates_missing_sections_on_a_new_line() {
+        let mut config = GitConfig::try_from("[core]\n\ta = b").unwrap();
+        config.append_raw_value("remote", Some("origin"), "url", Cow::Borrowed(b"https://example.com"));
+        assert_eq!(
+            config.to_string(),
+            "[core]\n\ta = b\n[remote \"origin\"]\n\turl = https://example.com\n"
+        );
+        assert_eq!(
+            config.get_raw_value("remote", Some("origin"), "url").unwrap(),
+            Cow::<[u8]>::Borrowed(b"https://example.com")
+        );
+    }
+}
+
+#[cfg(test)]
+mod remove_raw_value {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::file::{GitConfig, GitConfigError};
+
+    #[test]
+    fn removes_the_entire_line_of_the_last_value_only() {
+        let mut config = GitConfig::try_from("[core]\n\ta = b\n\n[core]\n    a = c # comment\n\n\td = e\n").unwrap();
+        config.remove_raw_value("core", None, "a").unwrap();
+        assert_eq!(config.to_string(), "[core]\n\ta = b\n\n[core]\n\n\td = e\n");
+        assert_eq!(
+            config.get_raw_value("core", None, "a").unwrap(),
+            Cow::<[u8]>::Borrowed(b"b")
+        );
+    }
+
+    #[test]
+    fn multi_value_removes_all_lines_in_all_sections() {
+        let mut config = GitConfig::try_from("[core]\n\ta = b\n\ta = c\\\n  d\n\te = f\n[core]\n\ta\n").unwrap();
+        config.remove_raw_multi_value("core", None, "a").unwrap();
+        assert_eq!(config.to_string(), "[core]\n\te = f\n[core]\n");
+        assert_eq!(
+            config.get_raw_value("core", None, "a"),
+            Err(GitConfigError::KeyDoesNotExist)
+        );
+    }
+
+    #[test]
+    fn missing_keys_are_an_error() {
+        let mut config = GitConfig::try_from("[core]\n\ta = b\n").unwrap();
+        assert_eq!(
+            config.remove_raw_value("core", None, "c"),
+            Err(GitConfigError::KeyDoesNotExist)
+        );
+        assert_eq!(
+            config.remove_raw_multi_value("core", None, "c"),
+            Err(GitConfigError::KeyDoesNotExist)
+        );
+        assert_eq!(config.to_string(), "[core]\n\ta = b\n");
+    }
+}
+
+#[cfg(test)]
+mod sections {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::{
+        file::{GitConfig, GitConfigError},
+        parser::SectionHeaderName,
+    };
+
+    #[test]
+    fn renamed_sections_keep_their_position_and_can_be_looked_up() {
+        let mut config = GitConfig::try_from("[a]\n\tx = 1\n[remote \"origin\"]\n\turl = b\n[c]\n").unwrap();
+        config
+            .rename_section("remote", "origin", "remote", Some(Cow::Borrowed("upstream")))
+            .unwrap();
+        assert_eq!(
+            config.to_string(),
+            "[a]\n\tx = 1\n[remote \"upstream\"]\n\turl = b\n[c]\n"
+        );
+        assert_eq!(
+            config.get_raw_value("remote", Some("upstream"), "url").unwrap(),
+            Cow::<[u8]>::Borrowed(b"b")
+        );
+        assert_eq!(
+            config.get_raw_value("remote", Some("origin"), "url"),
+            Err(GitConfigError::SubSectionDoesNotExist(Some("origin")))
+        );
+
+        config
+            .rename_section("a", None, "remote", Some(Cow::Borrowed("upstream")))
+            .unwrap();
+        assert_eq!(
+            config.get_raw_value("remote", Some("upstream"), "url").unwrap(),
+            Cow::<[u8]>::Borrowed(b"b"),
+            "precedence follows the order of sections in the file"
+        );
+        assert_eq!(
+            config.get_raw_multi_value("remote", Some("upstream"), "x").unwrap(),
+            vec![Cow::<[u8]>::Borrowed(b"1")]
+        );
+    }
+
+    #[test]
+    fn removed_sections_cannot_be_looked_up() {
+        let mut config = GitConfig::try_from("[core]\n\ta = b\n[other]\n").unwrap();
+        assert!(config.remove_section("core", None).is_some());
+        assert_eq!(config.to_string(), "[other]\n");
+        assert_eq!(
+            config.get_raw_value("core", None, "a"),
+            Err(GitConfigError::SectionDoesNotExist(SectionHeaderName("core".into())))
+        );
+        assert!(config.remove_section("core", None).is_none());
+    }
+}
+
+#[cfg(test)]
+mod entries {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::file::GitConfig;
+
+    #[test]
+    fn are_returned_in_order_with_normalized_values() {
+        let config = GitConfig::try_from(
+            "[core]\n\ta = b\n\tflag\n[remote \"origin\"]\n\turl = \"quoted\"\n[core]\n\ta = multi\\\nline\n",
+        )
+        .unwrap();
+        let entries: Vec<_> = config
+            .entries()
+            .into_iter()
+            .map(|(header, key, value)| {
+                (
+                    header.name.0.clone(),
+                    header.subsection_name.clone(),
+                    key.0.clone(),
+                    value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("core".into(), None, "a".into(), Some(Cow::<[u8]>::Borrowed(b"b"))),
+                ("core".into(), None, "flag".into(), None),
+                (
+                    "remote".into(),
+                    Some("origin".into()),
+                    "url".into(),
+                    Some(Cow::Borrowed(b"quoted"))
+                ),
+                ("core".into(), None, "a".into(), Some(Cow::Borrowed(b"multiline"))),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod write_to_path {
+    use std::{borrow::Cow, convert::TryFrom};
+
+    use git_config::file::GitConfig;
+
+    #[test]
+    fn creates_or_replaces_the_file_atomically() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config");
+        let input = "# a comment\n[core]\n\tbare = false ; keep me\n";
+        let mut config = GitConfig::try_from(input)?;
+        config.write_to_path(&path, git_lock::acquire::Fail::Immediately)?;
+        assert_eq!(std::fs::read_to_string(&path)?, input);
+
+        config.append_raw_value("core", None, "logAllRefUpdates", Cow::Borrowed(b"true"));
+        config.write_to_path(&path, git_lock::acquire::Fail::Immediately)?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "# a comment\n[core]\n\tbare = false ; keep me\n\tlogAllRefUpdates = true\n"
+        );
+        assert!(!dir.path().join("config.lock").exists(), "the lock is released");
+        Ok(())
+    }
+
+    #[test]
+    fn fails_if_the_file_is_locked() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config");
+        let _lock = git_lock::Marker::acquire_to_hold_resource(&path, git_lock::acquire::Fail::Immediately, None)?;
+        let config = GitConfig::try_from("[core]\n")?;
+        assert!(config
+            .write_to_path(&path, git_lock::acquire::Fail::Immediately)
+            .is_err());
+        assert!(!path.exists());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod display {
     use std::convert::TryFrom;
@@ -0,0 +1,80 @@
+//! Conversions between seconds since the unix epoch and calendar dates of the proleptic gregorian calendar.
+
+pub(crate) const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+pub(crate) const WEEKDAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+pub(crate) const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A date and time of day without timezone.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub(crate) struct DateTime {
+    pub year: i64,
+    /// The month from 1 to 12.
+    pub month: u32,
+    /// The day of the month starting at 1.
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    /// The day of the week with 0 being Sunday.
+    pub weekday: u32,
+}
+
+impl DateTime {
+    /// Return the date and time `seconds` after the unix epoch.
+    pub fn from_seconds(seconds: i64) -> Self {
+        let days = seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY) as u32;
+        let (year, month, day) = civil_from_days(days);
+        DateTime {
+            year,
+            month,
+            day,
+            hour: seconds_of_day / 3600,
+            minute: seconds_of_day / 60 % 60,
+            second: seconds_of_day % 60,
+            weekday: (days + 4).rem_euclid(7) as u32,
+        }
+    }
+}
+
+/// Return the seconds since the unix epoch of the given date and time, or `None` if they can't be represented.
+///
+/// Out-of-range values overflow into the next larger unit, so the 32nd of January is the 1st of February and
+/// the 13th month of a year is January of the next year.
+pub(crate) fn to_seconds(year: i64, month: i64, day: i64, hour: i64, minute: i64, second: i64) -> Option<i64> {
+    let months = year.checked_mul(12)?.checked_add(month - 1)?;
+    let days = days_from_civil(months.div_euclid(12), months.rem_euclid(12) as u32 + 1)?.checked_add(day - 1)?;
+    days.checked_mul(SECONDS_PER_DAY)?
+        .checked_add(hour * 3600 + minute * 60 + second)
+}
+
+/// Return the days since the unix epoch of the first day of `month` in `year`, or `None` if they can't be represented.
+fn days_from_civil(year: i64, month: u32) -> Option<i64> {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) as i64 + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era.checked_mul(146_097)?.checked_add(day_of_era - 719_468)
+}
+
+/// Return `(year, month, day)` of the date `days` after the unix epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
@@ -0,0 +1,203 @@
+use git_actor::Time;
+
+use crate::civil::{DateTime, MONTH_NAMES, WEEKDAY_NAMES};
+
+/// The formats git can display dates in, named after the values of `git log --date=<format>`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Format {
+    /// Like `Thu Apr 7 22:13:13 2005 +0200`.
+    Default,
+    /// Like `2005-04-07 22:13:13 +0200`.
+    Iso8601,
+    /// Like `2005-04-07T22:13:13+02:00`.
+    Iso8601Strict,
+    /// Like `Thu, 7 Apr 2005 22:13:13 +0200`.
+    Rfc2822,
+    /// Like `2005-04-07`.
+    Short,
+    /// Like `1112904793 +0200`.
+    Raw,
+    /// Like `1112904793`.
+    Unix,
+    /// Like `2 weeks ago`, relative to `now`.
+    Relative {
+        /// The time to compute the distance to.
+        now: Time,
+    },
+    /// Like [`Default`][Format::Default], but omits everything that is the same as in `now`, and is relative for dates
+    /// on the same day.
+    Human {
+        /// The time to compare to, whose timezone is the one considered local.
+        now: Time,
+    },
+}
+
+/// Format `time` in the given `format`, in the timezone of `time`.
+pub fn format(time: &Time, format: Format) -> String {
+    let date = local(time);
+    let tz = Tz(time.offset_in_seconds);
+    match format {
+        Format::Default => format!(
+            "{} {} {} {:02}:{:02}:{:02} {} {}",
+            WEEKDAY_NAMES[date.weekday as usize],
+            MONTH_NAMES[date.month as usize - 1],
+            date.day,
+            date.hour,
+            date.minute,
+            date.second,
+            date.year,
+            tz.compact()
+        ),
+        Format::Iso8601 => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}",
+            date.year,
+            date.month,
+            date.day,
+            date.hour,
+            date.minute,
+            date.second,
+            tz.compact()
+        ),
+        Format::Iso8601Strict => format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+            date.year,
+            date.month,
+            date.day,
+            date.hour,
+            date.minute,
+            date.second,
+            tz.with_colon()
+        ),
+        Format::Rfc2822 => format!(
+            "{}, {} {} {} {:02}:{:02}:{:02} {}",
+            WEEKDAY_NAMES[date.weekday as usize],
+            date.day,
+            MONTH_NAMES[date.month as usize - 1],
+            date.year,
+            date.hour,
+            date.minute,
+            date.second,
+            tz.compact()
+        ),
+        Format::Short => format!("{:04}-{:02}-{:02}", date.year, date.month, date.day),
+        Format::Raw => format!("{} {}", time.seconds_since_unix_epoch, tz.compact()),
+        Format::Unix => time.seconds_since_unix_epoch.to_string(),
+        Format::Relative { now } => relative(time, &now),
+        Format::Human { now } => human(time, &now),
+    }
+}
+
+fn local(time: &Time) -> DateTime {
+    DateTime::from_seconds(time.seconds_since_unix_epoch as i64 + time.offset_in_seconds as i64)
+}
+
+/// A timezone offset in seconds.
+struct Tz(i32);
+
+impl Tz {
+    fn sign_hours_minutes(&self) -> (char, i32, i32) {
+        let offset = self.0.abs();
+        (if self.0 < 0 { '-' } else { '+' }, offset / 3600, offset / 60 % 60)
+    }
+
+    /// Like `+0200`.
+    fn compact(&self) -> String {
+        let (sign, hours, minutes) = self.sign_hours_minutes();
+        format!("{}{:02}{:02}", sign, hours, minutes)
+    }
+
+    /// Like `+02:00`.
+    fn with_colon(&self) -> String {
+        let (sign, hours, minutes) = self.sign_hours_minutes();
+        format!("{}{:02}:{:02}", sign, hours, minutes)
+    }
+}
+
+/// Format `time` relative to `now` the same way as `show_date_relative()` in git.
+fn relative(time: &Time, now: &Time) -> String {
+    let (time, now) = (
+        time.seconds_since_unix_epoch as u64,
+        now.seconds_since_unix_epoch as u64,
+    );
+    if time > now {
+        return "in the future".into();
+    }
+    let plural = |amount: u64, unit: &str| format!("{} {}{}", amount, unit, if amount == 1 { "" } else { "s" });
+    let ago = |amount: u64, unit: &str| format!("{} ago", plural(amount, unit));
+
+    let seconds = now - time;
+    if seconds < 90 {
+        return ago(seconds, "second");
+    }
+    let minutes = (seconds + 30) / 60;
+    if minutes < 90 {
+        return ago(minutes, "minute");
+    }
+    let hours = (minutes + 30) / 60;
+    if hours < 36 {
+        return ago(hours, "hour");
+    }
+    let days = (hours + 12) / 24;
+    if days < 14 {
+        return ago(days, "day");
+    }
+    if days < 70 {
+        return ago((days + 3) / 7, "week");
+    }
+    if days < 365 {
+        return ago((days + 15) / 30, "month");
+    }
+    if days < 1825 {
+        let total_months = (days * 12 * 2 + 365) / (365 * 2);
+        let (years, months) = (total_months / 12, total_months % 12);
+        return if months == 0 {
+            ago(years, "year")
+        } else {
+            format!("{}, {}", plural(years, "year"), ago(months, "month"))
+        };
+    }
+    ago((days + 183) / 365, "year")
+}
+
+/// Format `time` like [`Format::Default`], but leave out what's the same as in `now`, the same way as
+/// `show_date_normal()` in git does for the `human` format.
+fn human(time: &Time, now: &Time) -> String {
+    let date = local(time);
+    let today = local(now);
+
+    let mut hide_tz = time.offset_in_seconds == now.offset_in_seconds;
+    let hide_year = date.year == today.year;
+    let (mut hide_date, mut hide_weekday) = (false, false);
+    if hide_year && date.month == today.month {
+        if date.day == today.day {
+            hide_date = true;
+            hide_weekday = true;
+        } else if date.day < today.day && date.day + 5 > today.day {
+            hide_date = true;
+        }
+    }
+    if hide_weekday {
+        return relative(time, now);
+    }
+    hide_tz |= !hide_date;
+    hide_weekday = !hide_year;
+    let hide_time = !hide_year;
+
+    let mut parts = Vec::new();
+    if !hide_weekday {
+        parts.push(WEEKDAY_NAMES[date.weekday as usize].to_string());
+    }
+    if !hide_date {
+        parts.push(format!("{} {}", MONTH_NAMES[date.month as usize - 1], date.day));
+    }
+    if !hide_time {
+        parts.push(format!("{:02}:{:02}", date.hour, date.minute));
+    }
+    if !hide_year {
+        parts.push(date.year.to_string());
+    }
+    if !hide_tz {
+        parts.push(Tz(time.offset_in_seconds).compact());
+    }
+    parts.join(" ")
+}
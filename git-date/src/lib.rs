@@ -1 +1,17 @@
-#![forbid(unsafe_code, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms, missing_docs)]
+//! [Parse][parse()] dates in all formats git accepts and [format][format()] them the way git displays them.
+//!
+//! Dates are represented as [`Time`], which is the time stamp found in signatures of commits and tags.
+
+pub use git_actor::Time;
+
+///
+pub mod parse;
+pub use parse::parse;
+
+///
+pub mod format;
+pub use format::{format, Format};
+
+mod civil;
@@ -0,0 +1,287 @@
+use std::{convert::TryInto, time::SystemTime};
+
+use git_actor::{Sign, Time};
+use quick_error::quick_error;
+
+use crate::civil::{self, MONTH_NAMES, SECONDS_PER_DAY, WEEKDAY_NAMES};
+
+quick_error! {
+    /// The error returned by [`parse()`][crate::parse()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        InvalidDateString { input: String } {
+            display("Date string can not be parsed: '{}'", input)
+        }
+        OutOfRange { input: String } {
+            display("The date '{}' is before the unix epoch or too far in the future to be represented", input)
+        }
+    }
+}
+
+/// Parse `input` as date in any of the formats git accepts, using `now` as reference for relative dates or the current
+/// system time if `None`.
+///
+/// The following formats are supported:
+///
+/// * `now`, `yesterday` and relative dates like `2.weeks.ago`, `2 weeks ago` or `1 year 2 months ago`.
+/// * `@<seconds since unix epoch> [<tz>]` and `<seconds since unix epoch> <tz>` as used in raw commit headers.
+/// * ISO 8601 like `2005-04-07T22:13:13+02:00`, `2005-04-07 22:13:13 +0200` or `2005-04-07 22:13`. A date without time,
+///   like `2005-04-07`, uses the time of day of `now`, similar to how git does it.
+/// * RFC 2822 like `Thu, 7 Apr 2005 22:13:13 +0200`.
+/// * The default format of git like `Thu Apr 7 22:13:13 2005 +0200`.
+///
+/// Timezones can be given as `+HHMM`, `+HH:MM`, `+HH`, `Z`, `UTC` or `GMT`. Dates without timezone are assumed to be
+/// in UTC, as are relative dates.
+pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
+    let now = now.unwrap_or_else(SystemTime::now);
+    let now = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .map_err(|_| Error::OutOfRange { input: input.into() })?;
+    let trimmed = input.trim();
+
+    let (seconds, offset, sign) = if let Some(seconds) = relative(trimmed, now)? {
+        (seconds, 0, Sign::Plus)
+    } else if let Some(time) = raw(trimmed)
+        .or_else(|| iso8601(trimmed, now))
+        .or_else(|| rfc2822_or_default(trimmed))
+    {
+        time
+    } else {
+        return Err(Error::InvalidDateString { input: input.into() });
+    };
+
+    let seconds = seconds
+        .try_into()
+        .map_err(|_| Error::OutOfRange { input: input.into() })?;
+    Ok(Time {
+        seconds_since_unix_epoch: seconds,
+        offset_in_seconds: offset,
+        sign,
+    })
+}
+
+/// The seconds since the unix epoch, the offset in seconds and its sign.
+type ParsedTime = (i64, i32, Sign);
+
+/// Parse `now`, `yesterday` and relative dates like `2.weeks.ago`, returning seconds since the unix epoch, or `None`
+/// if `input` isn't a relative date.
+///
+/// Relative dates too far in the past to be represented are an error.
+fn relative(input: &str, now: i64) -> Result<Option<i64>, Error> {
+    let out_of_range = || Error::OutOfRange { input: input.into() };
+    let lowercase_input = input.to_ascii_lowercase();
+    let mut tokens = lowercase_input
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .peekable();
+    match tokens.peek().copied() {
+        Some("now") => return Ok(tokens.nth(1).is_none().then(|| now)),
+        Some("yesterday") => return Ok(tokens.nth(1).is_none().then(|| now - SECONDS_PER_DAY)),
+        Some(_) => {}
+        None => return Ok(None),
+    }
+
+    let mut date = civil::DateTime::from_seconds(now);
+    let mut seconds_ago: i64 = 0;
+    let mut saw_unit = false;
+    while let Some(token) = tokens.next() {
+        if token == "ago" {
+            if tokens.next().is_some() {
+                return Ok(None);
+            }
+            break;
+        }
+        let (amount, unit) = match (token.parse::<i64>(), tokens.next()) {
+            (Ok(amount), Some(unit)) => (amount, unit),
+            _ => return Ok(None),
+        };
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        let (seconds_per_unit, months_per_unit) = match unit {
+            "second" | "sec" => (1, 0),
+            "minute" | "min" => (60, 0),
+            "hour" => (3600, 0),
+            "day" => (SECONDS_PER_DAY, 0),
+            "week" => (7 * SECONDS_PER_DAY, 0),
+            "month" => (0, 1),
+            "year" => (0, 12),
+            _ => return Ok(None),
+        };
+        seconds_ago = amount
+            .checked_mul(seconds_per_unit)
+            .and_then(|seconds| seconds_ago.checked_add(seconds))
+            .ok_or_else(out_of_range)?;
+        if months_per_unit != 0 {
+            date = amount
+                .checked_mul(months_per_unit)
+                .and_then(|months| add_months(date, -months))
+                .ok_or_else(out_of_range)?;
+        }
+        saw_unit = true;
+    }
+    if !saw_unit {
+        return Ok(None);
+    }
+    seconds_of(&date)
+        .and_then(|seconds| seconds.checked_sub(seconds_ago))
+        .map(Some)
+        .ok_or_else(out_of_range)
+}
+
+fn add_months(date: civil::DateTime, months: i64) -> Option<civil::DateTime> {
+    civil::to_seconds(
+        date.year,
+        (date.month as i64).checked_add(months)?,
+        date.day as i64,
+        date.hour as i64,
+        date.minute as i64,
+        date.second as i64,
+    )
+    .map(civil::DateTime::from_seconds)
+}
+
+fn seconds_of(date: &civil::DateTime) -> Option<i64> {
+    civil::to_seconds(
+        date.year,
+        date.month as i64,
+        date.day as i64,
+        date.hour as i64,
+        date.minute as i64,
+        date.second as i64,
+    )
+}
+
+/// Parse `@<seconds> [<tz>]` or `<seconds> <tz>`.
+fn raw(input: &str) -> Option<ParsedTime> {
+    let (input, needs_tz) = match input.strip_prefix('@') {
+        Some(input) => (input, false),
+        None => (input, true),
+    };
+    let mut tokens = input.split_ascii_whitespace();
+    let seconds = tokens.next().filter(|t| is_digits(t))?.parse().ok()?;
+    let (offset, sign) = match tokens.next() {
+        Some(tz) => timezone(tz)?,
+        None if needs_tz => return None,
+        None => (0, Sign::Plus),
+    };
+    tokens.next().is_none().then(|| (seconds, offset, sign))
+}
+
+/// Parse ISO 8601 dates like `2005-04-07T22:13:13.123+02:00`, with the time being optional.
+fn iso8601(input: &str, now: i64) -> Option<ParsedTime> {
+    let date = input.get(..10)?;
+    let mut parts = date.splitn(3, '-');
+    let (year, month, day) = (
+        fixed_number(parts.next()?, 4)?,
+        fixed_number(parts.next()?, 2)?,
+        fixed_number(parts.next()?, 2)?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let rest = &input[10..];
+    if rest.is_empty() {
+        let seconds_of_day = now.rem_euclid(SECONDS_PER_DAY);
+        return Some((
+            civil::to_seconds(year, month, day, 0, 0, 0)? + seconds_of_day,
+            0,
+            Sign::Plus,
+        ));
+    }
+    let rest = rest.strip_prefix('T').or_else(|| rest.strip_prefix(' '))?.trim_start();
+    let time_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':' || c == '.'))
+        .unwrap_or(rest.len());
+    let (hour, minute, second) = time_of_day(&rest[..time_len])?;
+    let (offset, sign) = match rest[time_len..].trim_start() {
+        "" => (0, Sign::Plus),
+        tz => timezone(tz)?,
+    };
+    Some((
+        civil::to_seconds(year, month, day, hour, minute, second)? - offset as i64,
+        offset,
+        sign,
+    ))
+}
+
+/// Parse dates like `Thu, 7 Apr 2005 22:13:13 +0200` (RFC 2822) or `Thu Apr 7 22:13:13 2005 +0200` (git default).
+fn rfc2822_or_default(input: &str) -> Option<ParsedTime> {
+    let mut tokens: Vec<_> = input.split_ascii_whitespace().collect();
+    if let Some(first) = tokens.first() {
+        if index_of(&WEEKDAY_NAMES, first.trim_end_matches(',')).is_some() {
+            tokens.remove(0);
+        }
+    }
+    let (day, month, year, time, tz) = match tokens.as_slice() {
+        [day, month, year, time, tz @ ..] if is_digits(day) => (*day, *month, *year, *time, tz),
+        [month, day, time, year, tz @ ..] => (*day, *month, *year, *time, tz),
+        _ => return None,
+    };
+    let day: i64 = day.parse().ok().filter(|d| (1..=31).contains(d))?;
+    let month = index_of(&MONTH_NAMES, month)? as i64 + 1;
+    let year = fixed_number(year, 4)?;
+    let (hour, minute, second) = time_of_day(time)?;
+    let (offset, sign) = match tz {
+        [] => (0, Sign::Plus),
+        [tz] => timezone(tz)?,
+        _ => return None,
+    };
+    Some((
+        civil::to_seconds(year, month, day, hour, minute, second)? - offset as i64,
+        offset,
+        sign,
+    ))
+}
+
+/// Parse `HH:MM[:SS[.fraction]]`, ignoring fractions of seconds.
+fn time_of_day(input: &str) -> Option<(i64, i64, i64)> {
+    let input = input.split('.').next()?;
+    let mut parts = input.split(':');
+    let hour = fixed_number(parts.next()?, 2).filter(|h| *h < 24)?;
+    let minute = fixed_number(parts.next()?, 2).filter(|m| *m < 60)?;
+    let second = match parts.next() {
+        Some(second) => fixed_number(second, 2).filter(|s| *s <= 60)?,
+        None => 0,
+    };
+    parts.next().is_none().then(|| (hour, minute, second))
+}
+
+/// Parse timezones like `+0200`, `+02:00`, `+02`, `-0000`, `Z`, `UTC` and `GMT` into an offset in seconds and its sign.
+fn timezone(input: &str) -> Option<(i32, Sign)> {
+    if input == "Z" || input.eq_ignore_ascii_case("utc") || input.eq_ignore_ascii_case("gmt") {
+        return Some((0, Sign::Plus));
+    }
+    let sign = match input.as_bytes().first()? {
+        b'+' => Sign::Plus,
+        b'-' => Sign::Minus,
+        _ => return None,
+    };
+    let digits = input[1..].replacen(':', "", 1);
+    let (hours, minutes) = match digits.len() {
+        2 => (fixed_number(&digits, 2)?, 0),
+        4 => (fixed_number(&digits[..2], 2)?, fixed_number(&digits[2..], 2)?),
+        _ => return None,
+    };
+    if hours > 24 || minutes > 59 {
+        return None;
+    }
+    let offset = (hours * 3600 + minutes * 60) as i32;
+    Some((if sign == Sign::Minus { -offset } else { offset }, sign))
+}
+
+fn fixed_number(input: &str, len: usize) -> Option<i64> {
+    (input.len() == len && is_digits(input))
+        .then(|| input.parse().ok())
+        .flatten()
+}
+
+fn is_digits(input: &str) -> bool {
+    !input.is_empty() && input.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn index_of(names: &[&str], name: &str) -> Option<usize> {
+    let name = name.get(..3).filter(|_| name.len() >= 3)?;
+    names.iter().position(|n| n.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,17 @@
+use std::time::{Duration, SystemTime};
+
+use git_date::Time;
+
+mod format;
+mod parse;
+
+/// The time at which all relative dates in tests are computed, `Fri Oct 16 00:47:03 2026 +0000`.
+const NOW: u32 = 1792111623;
+
+fn now() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(NOW.into())
+}
+
+fn time(seconds: u32, offset_in_seconds: i32) -> Time {
+    Time::new(seconds, offset_in_seconds)
+}
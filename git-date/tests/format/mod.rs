@@ -0,0 +1,76 @@
+use git_date::Format;
+
+use crate::{time, NOW};
+
+#[test]
+fn all_formats() {
+    let date = time(1112904793, 7200);
+    let now = time(NOW, 0);
+    for (format, expected) in &[
+        (Format::Default, "Thu Apr 7 22:13:13 2005 +0200"),
+        (Format::Iso8601, "2005-04-07 22:13:13 +0200"),
+        (Format::Iso8601Strict, "2005-04-07T22:13:13+02:00"),
+        (Format::Rfc2822, "Thu, 7 Apr 2005 22:13:13 +0200"),
+        (Format::Short, "2005-04-07"),
+        (Format::Raw, "1112904793 +0200"),
+        (Format::Unix, "1112904793"),
+        (Format::Relative { now }, "22 years ago"),
+        (Format::Human { now }, "Apr 7 2005"),
+    ] {
+        assert_eq!(git_date::format(&date, *format), *expected);
+    }
+}
+
+#[test]
+fn negative_timezones() {
+    let date = time(1791800000, -5400);
+    assert_eq!(git_date::format(&date, Format::Iso8601), "2026-10-12 08:43:20 -0130");
+    assert_eq!(
+        git_date::format(&date, Format::Iso8601Strict),
+        "2026-10-12T08:43:20-01:30"
+    );
+}
+
+#[test]
+fn relative() {
+    let now = time(NOW, 0);
+    for (seconds_ago, expected) in &[
+        (0, "0 seconds ago"),
+        (1, "1 second ago"),
+        (89, "89 seconds ago"),
+        (90, "2 minutes ago"),
+        (60 * 60, "60 minutes ago"),
+        (2 * 60 * 60, "2 hours ago"),
+        (3 * 86400, "3 days ago"),
+        (20 * 86400, "3 weeks ago"),
+        (100 * 86400, "3 months ago"),
+        (365 * 86400, "1 year ago"),
+        (500 * 86400, "1 year, 4 months ago"),
+        (3000 * 86400, "8 years ago"),
+    ] {
+        assert_eq!(
+            git_date::format(&time(NOW - seconds_ago, 0), Format::Relative { now }),
+            *expected,
+            "{}",
+            seconds_ago
+        );
+    }
+    assert_eq!(
+        git_date::format(&time(NOW + 1, 0), Format::Relative { now }),
+        "in the future"
+    );
+}
+
+#[test]
+fn human() {
+    let now = time(NOW, 0);
+    for (date, expected) in &[
+        (time(NOW - 23, 0), "23 seconds ago"),
+        (time(1791800000, -5400), "Mon 08:43 -0130"),
+        (time(1791800000, 0), "Mon 10:13"),
+        (time(1790000000, 0), "Mon Sep 21 14:13"),
+        (time(1700000000, 0), "Nov 14 2023"),
+    ] {
+        assert_eq!(git_date::format(date, Format::Human { now }), *expected);
+    }
+}
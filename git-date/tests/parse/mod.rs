@@ -0,0 +1,106 @@
+use git_actor::Sign;
+use git_date::Time;
+
+use crate::{now, time, NOW};
+
+fn parse(input: &str) -> Time {
+    git_date::parse(input, Some(now())).unwrap_or_else(|err| panic!("'{}' should parse: {}", input, err))
+}
+
+#[test]
+fn absolute_dates_in_all_formats() {
+    for (input, expected) in &[
+        ("2005-04-07 22:13:13 +0200", time(1112904793, 7200)),
+        ("2005-04-07T22:13:13+02:00", time(1112904793, 7200)),
+        ("2005-04-07T22:13:13.123+0530", time(1112892193, 19800)),
+        ("2005-04-07T22:13:13Z", time(1112911993, 0)),
+        ("2005-04-07 22:13:13", time(1112911993, 0)),
+        ("2005-04-07 22:13", time(1112911980, 0)),
+        ("Thu, 7 Apr 2005 22:13:13 +0200", time(1112904793, 7200)),
+        ("Thu Apr 7 22:13:13 2005 +0200", time(1112904793, 7200)),
+        ("Thu Apr 7 22:13:13 2005 -0130", time(1112917393, -5400)),
+        ("@1112904793 +0200", time(1112904793, 7200)),
+        ("@1112904793", time(1112904793, 0)),
+        ("1112904793 +0200", time(1112904793, 7200)),
+    ] {
+        assert_eq!(parse(input), *expected, "{}", input);
+    }
+}
+
+#[test]
+fn negative_zero_timezones_keep_their_sign() {
+    let time = parse("1112904793 -0000");
+    assert_eq!(time.offset_in_seconds, 0);
+    assert_eq!(time.sign, Sign::Minus);
+}
+
+#[test]
+fn dates_without_time_use_the_time_of_day_of_now() {
+    assert_eq!(parse("2005-04-07"), time(1112834823, 0));
+}
+
+#[test]
+fn relative_dates() {
+    for (input, expected) in &[
+        ("now", NOW),
+        ("yesterday", 1792025223),
+        ("5.hours.ago", 1792093623),
+        ("2.weeks.ago", 1790902023),
+        ("2 weeks ago", 1790902023),
+        ("3 months ago", 1784162823),
+        ("1 year 2 months ago", 1755305223),
+    ] {
+        assert_eq!(parse(input), time(*expected, 0), "{}", input);
+    }
+}
+
+#[test]
+fn invalid_dates() {
+    for input in &[
+        "",
+        "garbage",
+        "2.fortnights.ago",
+        "2005-13-07 22:13:13",
+        "2005-04-07 25:13:13",
+        "1112904793",
+        "@1112904793 +02000",
+        "Thu, 7 Foo 2005 22:13:13 +0200",
+    ] {
+        assert!(
+            matches!(
+                git_date::parse(input, Some(now())),
+                Err(git_date::parse::Error::InvalidDateString { .. })
+            ),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn dates_before_the_unix_epoch_are_out_of_range() {
+    assert!(matches!(
+        git_date::parse("1960-04-07 22:13:13 +0200", Some(now())),
+        Err(git_date::parse::Error::OutOfRange { .. })
+    ));
+}
+
+#[test]
+fn relative_dates_too_far_in_the_past_are_out_of_range() {
+    for input in &[
+        "9223372036854775807 weeks ago",
+        "9223372036854775807 seconds 1 second ago",
+        "768614336404564651 years ago",
+        "9223372036854775807 months ago",
+        "200 years ago",
+    ] {
+        assert!(
+            matches!(
+                git_date::parse(input, Some(now())),
+                Err(git_date::parse::Error::OutOfRange { .. })
+            ),
+            "{}",
+            input
+        );
+    }
+}
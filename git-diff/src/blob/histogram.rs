@@ -0,0 +1,95 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{myers, trim, Recorder};
+
+/// Tokens occurring more often than this on the `before` side are not used as anchors.
+const MAX_CHAIN_LEN: usize = 64;
+/// The recursion depth after which we fall back to Myers.
+const MAX_DEPTH: usize = 64;
+
+/// Diff `before[b]` with `after[a]` by anchoring at the longest common region containing the least frequent tokens,
+/// recursing into the regions before and after it.
+pub(crate) fn diff(
+    before: &[u32],
+    after: &[u32],
+    mut b: Range<usize>,
+    mut a: Range<usize>,
+    depth: usize,
+    out: &mut Recorder,
+) {
+    trim(before, after, &mut b, &mut a);
+    if b.is_empty() || a.is_empty() {
+        out.change(b, a);
+        return;
+    }
+    if depth > MAX_DEPTH {
+        myers::diff(before, after, b, a, false, out);
+        return;
+    }
+
+    match longest_rare_region(before, after, b.clone(), a.clone()) {
+        Some(region) => {
+            diff(before, after, b.start..region.before.start, a.start..region.after.start, depth + 1, out);
+            diff(before, after, region.before.end..b.end, region.after.end..a.end, depth + 1, out);
+        }
+        None => myers::diff(before, after, b, a, false, out),
+    }
+}
+
+struct Region {
+    before: Range<usize>,
+    after: Range<usize>,
+    /// The lowest amount of occurrences of any token in the region on the `before` side.
+    rarity: usize,
+}
+
+fn longest_rare_region(before: &[u32], after: &[u32], b: Range<usize>, a: Range<usize>) -> Option<Region> {
+    let mut positions = HashMap::<u32, Vec<usize>>::new();
+    for pos in b.clone() {
+        positions.entry(before[pos]).or_default().push(pos);
+    }
+
+    let mut best: Option<Region> = None;
+    let mut pos_a = a.start;
+    while pos_a < a.end {
+        let mut next_pos_a = pos_a + 1;
+        let candidates = match positions.get(&after[pos_a]) {
+            Some(candidates) if candidates.len() <= MAX_CHAIN_LEN => candidates,
+            _ => {
+                pos_a = next_pos_a;
+                continue;
+            }
+        };
+        if best.as_ref().map_or(false, |best| candidates.len() > best.rarity) {
+            pos_a = next_pos_a;
+            continue;
+        }
+        for &pos_b in candidates {
+            let (mut start_b, mut start_a) = (pos_b, pos_a);
+            while start_b > b.start && start_a > a.start && before[start_b - 1] == after[start_a - 1] {
+                start_b -= 1;
+                start_a -= 1;
+            }
+            let (mut end_b, mut end_a) = (pos_b + 1, pos_a + 1);
+            let mut rarity = candidates.len();
+            while end_b < b.end && end_a < a.end && before[end_b] == after[end_a] {
+                rarity = rarity.min(positions[&before[end_b]].len());
+                end_b += 1;
+                end_a += 1;
+            }
+            let is_better = best.as_ref().map_or(true, |best| {
+                rarity < best.rarity || (rarity == best.rarity && end_b - start_b > best.before.len())
+            });
+            if is_better {
+                best = Some(Region {
+                    before: start_b..end_b,
+                    after: start_a..end_a,
+                    rarity,
+                });
+            }
+            next_pos_a = next_pos_a.max(end_a);
+        }
+        pos_a = next_pos_a;
+    }
+    best
+}
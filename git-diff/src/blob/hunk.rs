@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+use git_object::bstr::BStr;
+
+use crate::blob::{Change, Diff};
+
+/// A line within a [`Hunk`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    /// A line present on both sides, shown for context.
+    Context(&'a BStr),
+    /// A line only present in the old version.
+    Remove(&'a BStr),
+    /// A line only present in the new version.
+    Add(&'a BStr),
+}
+
+/// A group of changes along with their surrounding context lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk<'a> {
+    /// The zero-based range of lines of the old version covered by this hunk.
+    pub before: Range<u32>,
+    /// The zero-based range of lines of the new version covered by this hunk.
+    pub after: Range<u32>,
+    /// All lines of this hunk in order.
+    pub lines: Vec<Line<'a>>,
+}
+
+impl<'a> Diff<'a> {
+    /// Group all changes into hunks, with `context_lines` unchanged lines around each change.
+    ///
+    /// Changes which are separated by no more than twice the amount of context lines are merged into one hunk.
+    pub fn hunks(&self, context_lines: u32) -> Vec<Hunk<'a>> {
+        let mut out = Vec::new();
+        let mut changes = self.changes.iter().peekable();
+        while let Some(first) = changes.next() {
+            let mut group: Vec<&Change> = vec![first];
+            while let Some(next) = changes.peek() {
+                let last = group.last().expect("non-empty");
+                if next.before.start - last.before.end > 2 * context_lines {
+                    break;
+                }
+                group.push(changes.next().expect("peeked"));
+            }
+            out.push(self.hunk_of(&group, context_lines));
+        }
+        out
+    }
+
+    fn hunk_of(&self, group: &[&Change], context_lines: u32) -> Hunk<'a> {
+        let first = group[0];
+        let last = group[group.len() - 1];
+        let leading = context_lines.min(first.before.start);
+        let trailing = context_lines.min(self.before.len() as u32 - last.before.end);
+
+        let before = first.before.start - leading..last.before.end + trailing;
+        let after = first.after.start - leading..last.after.end + trailing;
+
+        let mut lines = Vec::new();
+        let mut pos_before = before.start;
+        for change in group {
+            lines.extend((pos_before..change.before.start).map(|idx| Line::Context(self.before[idx as usize])));
+            lines.extend(change.before.clone().map(|idx| Line::Remove(self.before[idx as usize])));
+            lines.extend(change.after.clone().map(|idx| Line::Add(self.after[idx as usize])));
+            pos_before = change.before.end;
+        }
+        lines.extend((pos_before..before.end).map(|idx| Line::Context(self.before[idx as usize])));
+
+        Hunk { before, after, lines }
+    }
+}
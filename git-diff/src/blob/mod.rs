@@ -0,0 +1,162 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    ops::Range,
+};
+
+use git_object::bstr::{BStr, ByteSlice};
+
+/// The algorithm to use when diffing sequences of tokens, usually lines.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Algorithm {
+    /// The classic Myers algorithm, which gives up on finding the smallest diff if it gets too expensive to compute.
+    Myers,
+    /// The Myers algorithm without any heuristics, always producing the smallest possible diff.
+    MyersMinimal,
+    /// Like `Patience`, but uses the least frequent common tokens as anchors. Usually the fastest and produces
+    /// the most readable diffs.
+    Histogram,
+    /// Uses tokens unique to both sides as anchors and diffs the regions between them.
+    Patience,
+}
+
+impl Default for Algorithm {
+    fn default() -> Self {
+        Algorithm::Myers
+    }
+}
+
+/// A region of tokens which differs between the `before` and `after` sequence.
+///
+/// Either range may be empty to indicate a pure addition or deletion respectively.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The tokens in the `before` sequence that were removed.
+    pub before: Range<u32>,
+    /// The tokens in the `after` sequence that were added.
+    pub after: Range<u32>,
+}
+
+/// Diff the token sequences `before` and `after` using `algorithm`, returning all regions that differ in ascending order.
+pub fn diff_tokens<T: Hash + Eq>(algorithm: Algorithm, before: &[T], after: &[T]) -> Vec<Change> {
+    let (before, after) = intern(before, after);
+    let mut out = Recorder::default();
+    let (b, a) = (0..before.len(), 0..after.len());
+    match algorithm {
+        Algorithm::Myers => myers::diff(&before, &after, b, a, false, &mut out),
+        Algorithm::MyersMinimal => myers::diff(&before, &after, b, a, true, &mut out),
+        Algorithm::Histogram => histogram::diff(&before, &after, b, a, 0, &mut out),
+        Algorithm::Patience => patience::diff(&before, &after, b, a, &mut out),
+    }
+    out.changes
+}
+
+/// Split `data` into lines, each including its trailing newline if there is one.
+pub fn lines(data: &[u8]) -> Vec<&BStr> {
+    data.split_inclusive(|b| *b == b'\n').map(|l| l.as_bstr()).collect()
+}
+
+/// Return true if `data` is considered binary, which is the case if a null byte is contained in the first 8000 bytes, like `git` does.
+///
+/// Use [`is_binary_for()`] to let attributes and the size of `data` take part in the decision as well.
+pub fn is_binary(data: &[u8]) -> bool {
+    git_attributes::binary::is_binary(data)
+}
+
+/// The attributes of a path by name, as obtained with [`git_attributes::Stack::attributes()`].
+pub type Attributes<'a> = BTreeMap<&'a BStr, git_attributes::State<'a>>;
+
+/// Return true if `data` of a file with the given `attributes` is to be treated as binary for `purpose`, letting the
+/// attribute of `purpose` and the `text` attribute decide before its size and content do, as configured by `options`.
+/// See [`git_attributes::binary::classify()`] for details.
+///
+/// Without `attributes`, only the size and content of `data` are considered.
+pub fn is_binary_for(
+    data: &[u8],
+    attributes: Option<&Attributes<'_>>,
+    purpose: git_attributes::binary::Purpose,
+    options: git_attributes::binary::Options,
+) -> bool {
+    let no_attributes = Attributes::new();
+    git_attributes::binary::classify(data, attributes.unwrap_or(&no_attributes), purpose, options)
+        == git_attributes::binary::Class::Binary
+}
+
+/// The result of diffing two blobs line by line using [`diff()`].
+#[derive(Debug, Clone)]
+pub struct Diff<'a> {
+    /// The lines of the old blob.
+    pub before: Vec<&'a BStr>,
+    /// The lines of the new blob.
+    pub after: Vec<&'a BStr>,
+    /// The regions of lines that differ, in ascending order.
+    pub changes: Vec<Change>,
+}
+
+/// Diff the lines of the `before` and `after` blob using `algorithm`.
+pub fn diff<'a>(algorithm: Algorithm, before: &'a [u8], after: &'a [u8]) -> Diff<'a> {
+    let before = lines(before);
+    let after = lines(after);
+    let changes = diff_tokens(algorithm, &before, &after);
+    Diff { before, after, changes }
+}
+
+///
+pub mod hunk;
+pub use hunk::{Hunk, Line};
+
+///
+pub mod word;
+
+mod histogram;
+mod myers;
+mod patience;
+
+/// Map each token to a number that is the same for equal tokens, making comparisons cheap.
+fn intern<T: Hash + Eq>(before: &[T], after: &[T]) -> (Vec<u32>, Vec<u32>) {
+    let mut ids = HashMap::<&T, u32>::with_capacity(before.len());
+    let mut id_of = |token| {
+        let next_id = ids.len() as u32;
+        *ids.entry(token).or_insert(next_id)
+    };
+    let before = before.iter().map(&mut id_of).collect();
+    let after = after.iter().map(&mut id_of).collect();
+    (before, after)
+}
+
+#[derive(Default)]
+struct Recorder {
+    changes: Vec<Change>,
+}
+
+impl Recorder {
+    /// Record a change, merging it with the previous one if they touch.
+    fn change(&mut self, before: Range<usize>, after: Range<usize>) {
+        if before.is_empty() && after.is_empty() {
+            return;
+        }
+        let (before, after) = (
+            before.start as u32..before.end as u32,
+            after.start as u32..after.end as u32,
+        );
+        match self.changes.last_mut() {
+            Some(last) if last.before.end == before.start && last.after.end == after.start => {
+                last.before.end = before.end;
+                last.after.end = after.end;
+            }
+            _ => self.changes.push(Change { before, after }),
+        }
+    }
+}
+
+/// Shrink `before` and `after` by removing their common prefix and suffix.
+fn trim(before: &[u32], after: &[u32], b: &mut Range<usize>, a: &mut Range<usize>) {
+    while b.start < b.end && a.start < a.end && before[b.start] == after[a.start] {
+        b.start += 1;
+        a.start += 1;
+    }
+    while b.start < b.end && a.start < a.end && before[b.end - 1] == after[a.end - 1] {
+        b.end -= 1;
+        a.end -= 1;
+    }
+}
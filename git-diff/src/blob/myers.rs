@@ -0,0 +1,128 @@
+use std::ops::{Index, IndexMut, Range};
+
+use super::{trim, Recorder};
+
+/// Diff `before[b]` with `after[a]` using the linear-space variant of the Myers algorithm.
+///
+/// Unless `minimal` is set, we stop searching for the optimal path once it becomes too costly and record the remainder
+/// as a single change instead.
+pub(crate) fn diff(
+    before: &[u32],
+    after: &[u32],
+    mut b: Range<usize>,
+    mut a: Range<usize>,
+    minimal: bool,
+    out: &mut Recorder,
+) {
+    trim(before, after, &mut b, &mut a);
+    if b.is_empty() || a.is_empty() {
+        out.change(b, a);
+        return;
+    }
+
+    let max_cost = (!minimal).then(|| max_cost(b.len() + a.len()));
+    match middle_snake(before, after, b.clone(), a.clone(), max_cost) {
+        Some((x, y)) => {
+            diff(before, after, b.start..x, a.start..y, minimal, out);
+            diff(before, after, x..b.end, y..a.end, minimal, out);
+        }
+        None => out.change(b, a),
+    }
+}
+
+/// The amount of edits after which we consider a diff too expensive, similar to what `git` does.
+fn max_cost(num_tokens: usize) -> usize {
+    let mut cost = 1;
+    let mut i = num_tokens;
+    while i != 0 {
+        i >>= 2;
+        cost <<= 1;
+    }
+    cost.max(256)
+}
+
+/// A vector indexed by diagonals `k`, which may be negative.
+struct V {
+    offset: isize,
+    v: Vec<usize>,
+}
+
+impl V {
+    fn new(max_d: usize) -> Self {
+        V {
+            offset: max_d as isize,
+            v: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl Index<isize> for V {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl IndexMut<isize> for V {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+/// Find a point on the optimal path through the edit graph by searching from both ends at once,
+/// or `None` if the cost exceeds `max_cost`.
+fn middle_snake(
+    before: &[u32],
+    after: &[u32],
+    b: Range<usize>,
+    a: Range<usize>,
+    max_cost: Option<usize>,
+) -> Option<(usize, usize)> {
+    let (n, m) = (b.len(), a.len());
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+    let max_d = (n + m + 1) / 2 + 1;
+    let mut vf = V::new(max_d);
+    let mut vb = V::new(max_d);
+
+    for d in 0..max_d as isize {
+        if max_cost.map_or(false, |max| d as usize > max) {
+            return None;
+        }
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vf[k - 1] < vf[k + 1]) {
+                vf[k + 1]
+            } else {
+                vf[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            let (x0, y0) = (x, y);
+            while x < n && y < m && before[b.start + x] == after[a.start + y] {
+                x += 1;
+                y += 1;
+            }
+            vf[k] = x;
+            if odd && (k - delta).abs() <= d - 1 && vf[k] + vb[-(k - delta)] >= n {
+                return Some((b.start + x0, a.start + y0));
+            }
+        }
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && vb[k - 1] < vb[k + 1]) {
+                vb[k + 1]
+            } else {
+                vb[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && before[b.start + n - x - 1] == after[a.start + m - y - 1] {
+                x += 1;
+                y += 1;
+            }
+            vb[k] = x;
+            if !odd && (k - delta).abs() <= d && vb[k] + vf[-(k - delta)] >= n {
+                return Some((b.start + n - x, a.start + m - y));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,81 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{myers, trim, Recorder};
+
+/// Diff `before[b]` with `after[a]` by matching tokens that occur exactly once on each side, falling back to
+/// Myers if there are none.
+pub(crate) fn diff(before: &[u32], after: &[u32], mut b: Range<usize>, mut a: Range<usize>, out: &mut Recorder) {
+    trim(before, after, &mut b, &mut a);
+    if b.is_empty() || a.is_empty() {
+        out.change(b, a);
+        return;
+    }
+
+    let anchors = unique_common_subsequence(before, after, b.clone(), a.clone());
+    if anchors.is_empty() {
+        myers::diff(before, after, b, a, false, out);
+        return;
+    }
+
+    let (mut prev_b, mut prev_a) = (b.start, a.start);
+    for (anchor_b, anchor_a) in anchors {
+        diff(before, after, prev_b..anchor_b, prev_a..anchor_a, out);
+        prev_b = anchor_b + 1;
+        prev_a = anchor_a + 1;
+    }
+    diff(before, after, prev_b..b.end, prev_a..a.end, out);
+}
+
+/// Return the positions of tokens unique to both sides that form the longest sequence appearing in the same order on both sides.
+fn unique_common_subsequence(before: &[u32], after: &[u32], b: Range<usize>, a: Range<usize>) -> Vec<(usize, usize)> {
+    #[derive(Default)]
+    struct Occurrence {
+        count_before: usize,
+        pos_before: usize,
+        count_after: usize,
+        pos_after: usize,
+    }
+    let mut occurrences = HashMap::<u32, Occurrence>::new();
+    for pos in b.clone() {
+        let o = occurrences.entry(before[pos]).or_default();
+        o.count_before += 1;
+        o.pos_before = pos;
+    }
+    for pos in a {
+        if let Some(o) = occurrences.get_mut(&after[pos]) {
+            o.count_after += 1;
+            o.pos_after = pos;
+        }
+    }
+    let mut unique: Vec<(usize, usize)> = occurrences
+        .into_values()
+        .filter_map(|o| (o.count_before == 1 && o.count_after == 1).then(|| (o.pos_before, o.pos_after)))
+        .collect();
+    unique.sort_unstable();
+    longest_increasing_subsequence(&unique)
+}
+
+/// Patience sorting to find the longest subsequence of `pairs`, ordered by their first item, whose second item is increasing as well.
+fn longest_increasing_subsequence(pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // indices into `pairs` of the top of each pile
+    let mut piles: Vec<usize> = Vec::new();
+    let mut predecessor: Vec<Option<usize>> = vec![None; pairs.len()];
+    for (idx, &(_, pos_after)) in pairs.iter().enumerate() {
+        let pile = piles.partition_point(|&top| pairs[top].1 < pos_after);
+        predecessor[idx] = pile.checked_sub(1).map(|p| piles[p]);
+        if pile == piles.len() {
+            piles.push(idx);
+        } else {
+            piles[pile] = idx;
+        }
+    }
+
+    let mut out = Vec::with_capacity(piles.len());
+    let mut cursor = piles.last().copied();
+    while let Some(idx) = cursor {
+        out.push(pairs[idx]);
+        cursor = predecessor[idx];
+    }
+    out.reverse();
+    out
+}
@@ -0,0 +1,137 @@
+use std::ops::Range;
+
+use git_object::bstr::{BStr, ByteSlice};
+
+use crate::blob::{diff_tokens, Algorithm, Hunk, Line};
+
+/// A token of a [word diff][diff()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Word<'a> {
+    /// Text that is present in both versions.
+    Unchanged(&'a BStr),
+    /// Text that is only present in the old version.
+    Removed(&'a BStr),
+    /// Text that is only present in the new version.
+    Added(&'a BStr),
+}
+
+/// The byte ranges within an old and a new line that changed, for use in highlighting.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct IntraLine {
+    /// The ranges of bytes in the old line which were removed or replaced.
+    pub before: Vec<Range<usize>>,
+    /// The ranges of bytes in the new line which were added or are replacements.
+    pub after: Vec<Range<usize>>,
+}
+
+/// Split `text` into words, which are runs of alphanumeric characters or underscores, runs of whitespace,
+/// or single other characters. Bytes that are not ASCII are considered part of words.
+pub fn words(text: &[u8]) -> Vec<&BStr> {
+    #[derive(PartialEq, Eq, Clone, Copy)]
+    enum Class {
+        Word,
+        Space,
+        Other,
+    }
+    fn class_of(b: u8) -> Class {
+        if b.is_ascii_alphanumeric() || b == b'_' || !b.is_ascii() {
+            Class::Word
+        } else if b.is_ascii_whitespace() {
+            Class::Space
+        } else {
+            Class::Other
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut start = 0;
+    for pos in 1..=text.len() {
+        let boundary = pos == text.len() || {
+            let (prev, cur) = (class_of(text[pos - 1]), class_of(text[pos]));
+            prev != cur || prev == Class::Other
+        };
+        if boundary {
+            out.push(text[start..pos].as_bstr());
+            start = pos;
+        }
+    }
+    out
+}
+
+/// Diff `before` and `after` word by word using `algorithm`, similar to `git diff --word-diff`.
+///
+/// Concatenating all `Unchanged` and `Removed` words yields `before`, and all `Unchanged` and `Added` words yield `after`.
+pub fn diff<'a>(algorithm: Algorithm, before: &'a [u8], after: &'a [u8]) -> Vec<Word<'a>> {
+    let (before, after) = (words(before), words(after));
+    let mut out = Vec::new();
+    let mut pos = 0;
+    for change in diff_tokens(algorithm, &before, &after) {
+        out.extend(before[pos..change.before.start as usize].iter().map(|w| Word::Unchanged(*w)));
+        out.extend(change.before.clone().map(|idx| Word::Removed(before[idx as usize])));
+        out.extend(change.after.clone().map(|idx| Word::Added(after[idx as usize])));
+        pos = change.before.end as usize;
+    }
+    out.extend(before[pos..].iter().map(|w| Word::Unchanged(*w)));
+    out
+}
+
+/// Compute the byte ranges that changed between the `before` and the `after` line, comparing them word by word.
+pub fn intra_line(algorithm: Algorithm, before: &[u8], after: &[u8]) -> IntraLine {
+    let (before_words, after_words) = (words(before), words(after));
+    let to_bytes = |words: &[&BStr], range: Range<u32>| {
+        let start: usize = words[..range.start as usize].iter().map(|w| w.len()).sum();
+        let len: usize = words[range.start as usize..range.end as usize]
+            .iter()
+            .map(|w| w.len())
+            .sum();
+        start..start + len
+    };
+    let mut out = IntraLine::default();
+    for change in diff_tokens(algorithm, &before_words, &after_words) {
+        if !change.before.is_empty() {
+            out.before.push(to_bytes(&before_words, change.before));
+        }
+        if !change.after.is_empty() {
+            out.after.push(to_bytes(&after_words, change.after));
+        }
+    }
+    out
+}
+
+impl<'a> Hunk<'a> {
+    /// Compute intra-line changes for each line in this hunk, returning one entry per line in [`lines`][Hunk::lines].
+    ///
+    /// Like `diff-highlight`, only blocks of removed lines that are directly followed by the same amount of added lines
+    /// are compared pairwise. All other lines yield an empty [`IntraLine`].
+    pub fn intra_line_changes(&self, algorithm: Algorithm) -> Vec<IntraLine> {
+        let mut out = vec![IntraLine::default(); self.lines.len()];
+        let mut idx = 0;
+        while idx < self.lines.len() {
+            let removed = self.lines[idx..]
+                .iter()
+                .take_while(|l| matches!(l, Line::Remove(_)))
+                .count();
+            let added = self.lines[idx + removed..]
+                .iter()
+                .take_while(|l| matches!(l, Line::Add(_)))
+                .count();
+            if removed == 0 {
+                idx += 1;
+                continue;
+            }
+            if removed == added {
+                for offset in 0..removed {
+                    let (before, after) = match (self.lines[idx + offset], self.lines[idx + removed + offset]) {
+                        (Line::Remove(before), Line::Add(after)) => (before, after),
+                        _ => unreachable!("counted above"),
+                    };
+                    let changes = intra_line(algorithm, before, after);
+                    out[idx + offset].before = changes.before;
+                    out[idx + removed + offset].after = changes.after;
+                }
+            }
+            idx += removed + added;
+        }
+        out
+    }
+}
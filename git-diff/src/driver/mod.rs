@@ -0,0 +1,170 @@
+use std::{borrow::Cow, convert::TryFrom};
+
+use git_config::values::Boolean;
+use git_hash::oid;
+use git_object::bstr::{BStr, BString, ByteSlice};
+
+use crate::{blob, patch::Side};
+
+/// How a file should be diffed according to the value of its `diff` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute<'a> {
+    /// The attribute is unspecified, and the content decides whether the file is diffed as text or as binary.
+    Auto,
+    /// The attribute is set, and the file is always diffed as text.
+    Text,
+    /// The attribute is unset, and the file is always considered binary.
+    Binary,
+    /// The attribute names a driver, configured in the `diff.<driver>` section of the git configuration.
+    Driver(&'a BStr),
+}
+
+impl<'a> From<&git_attributes::State<'a>> for Attribute<'a> {
+    fn from(state: &git_attributes::State<'a>) -> Self {
+        use git_attributes::State;
+        match state {
+            State::Set => Attribute::Text,
+            State::Unset => Attribute::Binary,
+            State::Value(name) => Attribute::Driver(*name),
+            State::Unspecified => Attribute::Auto,
+        }
+    }
+}
+
+impl<'a> Attribute<'a> {
+    /// Determine how a file is diffed from all of its `attributes`, which lets the `text` attribute decide if the `diff`
+    /// attribute is unspecified, like [`git_attributes::binary::from_attributes()`] does.
+    pub fn from_attributes(attributes: &blob::Attributes<'a>) -> Self {
+        use git_attributes::binary::{from_attributes, Class, Purpose};
+        match attributes.get("diff".as_bytes().as_bstr()) {
+            Some(git_attributes::State::Value(name)) => Attribute::Driver(*name),
+            _ => match from_attributes(attributes, Purpose::Diff) {
+                Some(Class::Text) => Attribute::Text,
+                Some(Class::Binary) => Attribute::Binary,
+                None => Attribute::Auto,
+            },
+        }
+    }
+}
+
+/// A diff driver as configured in the `diff.<name>` section of the git configuration.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Driver {
+    /// The name of the driver, as used in the `diff` attribute.
+    pub name: BString,
+    /// The value of `diff.<name>.command`, a program to produce the diff instead of doing so ourselves.
+    pub command: Option<BString>,
+    /// The value of `diff.<name>.textconv`, a program to convert the content into text which is then diffed.
+    pub textconv: Option<BString>,
+    /// The value of `diff.<name>.cachetextconv`, if true, the results of the `textconv` conversion should be cached.
+    pub cache_textconv: bool,
+    /// The value of `diff.<name>.binary`, if set, it overrides the detection of binary files.
+    pub binary: Option<bool>,
+}
+
+impl Driver {
+    /// Create a new driver called `name` with its configuration obtained by calling `value(key)`, for keys like `command` or
+    /// `textconv` of the `diff.<name>` section, which returns the last value of that key, if present.
+    ///
+    /// Returns `None` if none of the keys of the driver are configured.
+    pub fn from_config<'a>(
+        name: impl Into<BString>,
+        mut value: impl FnMut(&str) -> Option<Cow<'a, BStr>>,
+    ) -> Option<Self> {
+        let driver = Driver {
+            name: name.into(),
+            command: value("command").map(Cow::into_owned),
+            textconv: value("textconv").map(Cow::into_owned),
+            cache_textconv: value("cachetextconv").and_then(|v| to_bool(&v)).unwrap_or(false),
+            binary: value("binary").and_then(|v| to_bool(&v)),
+        };
+        (driver.command.is_some() || driver.textconv.is_some() || driver.binary.is_some()).then(|| driver)
+    }
+
+    /// Return the name of the notes reference which caches the `textconv` conversions of this driver.
+    pub fn textconv_notes_ref(&self) -> BString {
+        let mut name = BString::from("refs/notes/textconv/");
+        name.extend_from_slice(&self.name);
+        name
+    }
+}
+
+/// Interpret `value` as git configuration boolean, or return `None` if it is no valid boolean.
+fn to_bool(value: &BStr) -> Option<bool> {
+    Boolean::try_from(value.as_bytes()).ok().map(|b| b.to_bool())
+}
+
+/// Convert the content of files into text before diffing them, as configured with `diff.<name>.textconv`.
+///
+/// Implementations may convert in-process, or run the configured program like [`process::TextConv`] does.
+pub trait TextConv {
+    /// The error produced when the conversion fails.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Convert `data` of the file at `path` into text using `driver`, which is known to have its
+    /// [`textconv`][Driver::textconv] set.
+    fn convert(&mut self, driver: &Driver, path: &BStr, data: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A cache for the results of [text conversions][TextConv], as used for drivers with [`cache_textconv`][Driver::cache_textconv] set.
+///
+/// Git stores the converted text as notes in the reference returned by [`Driver::textconv_notes_ref()`], with the
+/// converted blob as the annotated object, which is what the `TextConvCache` of `git-repository` does.
+/// Implementations are free to choose any storage though.
+pub trait Cache {
+    /// Return the converted text of the blob with `id` which was produced by `driver`, if present.
+    fn get(&mut self, driver: &Driver, id: &oid) -> Option<Vec<u8>>;
+    /// Store the `converted` text of the blob with `id` as produced by `driver`.
+    ///
+    /// Failures to store the result must not fail the conversion itself and are to be ignored.
+    fn put(&mut self, driver: &Driver, id: &oid, converted: &[u8]);
+}
+
+/// Convert the blob with `id` and `data` at `path` into text if `driver` has a `textconv` program configured, using `conv`,
+/// and return the converted text or `None` if no conversion is configured.
+///
+/// If the driver wants conversions to be cached and a `cache` is provided, it is queried first and updated with new conversions.
+pub fn convert_to_text<T: TextConv>(
+    driver: &Driver,
+    path: &BStr,
+    id: &oid,
+    data: &[u8],
+    conv: &mut T,
+    cache: Option<&mut dyn Cache>,
+) -> Result<Option<Vec<u8>>, T::Error> {
+    if driver.textconv.is_none() {
+        return Ok(None);
+    }
+    match cache.filter(|_| driver.cache_textconv) {
+        Some(cache) => {
+            if let Some(converted) = cache.get(driver, id) {
+                return Ok(Some(converted));
+            }
+            let converted = conv.convert(driver, path, data)?;
+            cache.put(driver, id, &converted);
+            Ok(Some(converted))
+        }
+        None => conv.convert(driver, path, data).map(Some),
+    }
+}
+
+/// Produce the diff of a file with an external program instead of diffing it ourselves, as configured with `diff.<name>.command`.
+///
+/// Implementations may diff in-process, or run the configured program like [`process::External`] does.
+pub trait External {
+    /// The error produced when the diff can't be produced.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Produce the diff output of the file at `path` which changed from `before` to `after` using `driver`,
+    /// which is known to have its [`command`][Driver::command] set.
+    fn diff(
+        &mut self,
+        driver: &Driver,
+        path: &BStr,
+        before: Option<Side<'_>>,
+        after: Option<Side<'_>>,
+    ) -> Result<Vec<u8>, Self::Error>;
+}
+
+///
+pub mod process;
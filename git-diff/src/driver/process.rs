@@ -0,0 +1,132 @@
+use std::{
+    ffi::OsString,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use git_object::bstr::{BStr, ByteSlice};
+use quick_error::quick_error;
+
+use crate::{driver::Driver, patch::Side};
+
+quick_error! {
+    /// The error returned by the process based implementations of [`driver::TextConv`][crate::driver::TextConv] and
+    /// [`driver::External`][crate::driver::External].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingProgram(driver: String) {
+            display("The driver '{}' has no program configured", driver)
+        }
+        Io(err: std::io::Error) {
+            display("Failed to prepare the input files or to run the program")
+            from()
+            source(err)
+        }
+        IllformedUtf8(program: String) {
+            display("The program '{}' could not be converted into a command line", program)
+        }
+        Failed { program: String, status: std::process::ExitStatus, stderr: String } {
+            display("The program '{}' failed with {}: {}", program, status, stderr)
+        }
+    }
+}
+
+/// A [`TextConv`][crate::driver::TextConv] implementation which runs the configured program with a temporary file containing
+/// the data to convert, reading the converted text from its standard output.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct TextConv;
+
+impl crate::driver::TextConv for TextConv {
+    type Error = Error;
+
+    fn convert(&mut self, driver: &Driver, path: &BStr, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        let program = driver
+            .textconv
+            .as_ref()
+            .ok_or_else(|| Error::MissingProgram(driver.name.to_string()))?;
+        let input = temp_file_with(path, data)?;
+        run(program.as_bstr(), vec![input.path().into()])
+    }
+}
+
+/// An [`External`][crate::driver::External] implementation which runs the configured program like git does, passing the path,
+/// followed by the temporary file, the hexadecimal id and the mode of the old and of the new version of the file.
+///
+/// Sides that don't exist are passed as `/dev/null` with `.` as id and mode.
+#[derive(Default, Debug, Clone, Copy)]
+pub struct External;
+
+impl crate::driver::External for External {
+    type Error = Error;
+
+    fn diff(
+        &mut self,
+        driver: &Driver,
+        path: &BStr,
+        before: Option<Side<'_>>,
+        after: Option<Side<'_>>,
+    ) -> Result<Vec<u8>, Self::Error> {
+        let program = driver
+            .command
+            .as_ref()
+            .ok_or_else(|| Error::MissingProgram(driver.name.to_string()))?;
+        let mut args = vec![to_os_string(path)?];
+        let mut files = Vec::new();
+        for side in [before, after] {
+            match side {
+                Some(side) => {
+                    let file = temp_file_with(side.path, side.data)?;
+                    args.push(file.path().into());
+                    args.push(side.id.to_string().into());
+                    args.push(format!("{:06o}", side.mode as u16).into());
+                    files.push(file);
+                }
+                None => args.extend(["/dev/null", ".", "."].iter().map(OsString::from)),
+            }
+        }
+        run(program.as_bstr(), args)
+    }
+}
+
+/// Run `program` through the shell with `args` and return its standard output.
+fn run(program: &BStr, args: Vec<OsString>) -> Result<Vec<u8>, Error> {
+    let program_str = program
+        .to_str()
+        .map_err(|_| Error::IllformedUtf8(program.to_string()))?;
+    let out = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", program_str))
+        .arg(program_str)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .stdout(Stdio::piped())
+        .output()?;
+    if !out.status.success() {
+        return Err(Error::Failed {
+            program: program_str.to_owned(),
+            status: out.status,
+            stderr: out.stderr.to_str_lossy().into_owned(),
+        });
+    }
+    Ok(out.stdout)
+}
+
+/// Write `data` into a new temporary file whose name ends with the file name of `path` so programs can detect its type.
+fn temp_file_with(path: &BStr, data: &[u8]) -> Result<tempfile::NamedTempFile, Error> {
+    let file_name = path.rsplit_str("/").next().unwrap_or_default();
+    let mut file = tempfile::Builder::new()
+        .prefix("")
+        .suffix(&format!("_{}", file_name.to_str_lossy()))
+        .tempfile()?;
+    file.write_all(data)?;
+    file.flush()?;
+    Ok(file)
+}
+
+fn to_os_string(path: &BStr) -> Result<OsString, Error> {
+    path.to_os_str()
+        .map(ToOwned::to_owned)
+        .map_err(|_| Error::IllformedUtf8(path.to_string()))
+}
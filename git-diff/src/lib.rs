@@ -4,3 +4,21 @@
 
 ///
 pub mod tree;
+
+///
+pub mod blob;
+
+///
+pub mod patch;
+
+///
+pub mod rewrites;
+
+///
+pub mod stats;
+
+///
+pub mod submodule;
+
+///
+pub mod driver;
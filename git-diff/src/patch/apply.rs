@@ -0,0 +1,287 @@
+use git_attributes::binary::Purpose;
+use git_object::bstr::BStr;
+use quick_error::quick_error;
+
+use crate::{
+    blob::{self, Hunk, Line},
+    patch::{
+        binary,
+        parse::{BinaryHunk, Content, File},
+    },
+};
+
+quick_error! {
+    /// The error returned by [`apply()`] and [`apply_three_way()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        HunkMismatch { hunk: usize } {
+            display("Hunk {} could not be applied as its context could not be found", hunk)
+        }
+        BinaryWithoutData {
+            display("The patch only states that binary files differ, but doesn't contain their data")
+        }
+        BinaryDelta {
+            display("The binary delta could not be applied to the given data")
+        }
+    }
+}
+
+/// Options for use in [`apply()`] and [`apply_three_way()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The maximum amount of leading and trailing context lines to ignore for a hunk if it doesn't apply otherwise.
+    pub fuzz: usize,
+    /// If true, apply the patch in reverse, undoing it.
+    pub reverse: bool,
+    /// Configure how files are classified as binary by [`apply_three_way()`] after their attributes had no say.
+    pub classification: git_attributes::binary::Options,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fuzz: 2,
+            reverse: false,
+            classification: Default::default(),
+        }
+    }
+}
+
+/// Apply the content changes of `file` to `data`, returning the patched data.
+///
+/// Hunks are searched for close to the location they state, and if they can't be found with all of their context,
+/// up to `fuzz` context lines at their beginning and end are ignored.
+pub fn apply(data: &[u8], file: &File<'_>, opts: Options) -> Result<Vec<u8>, Error> {
+    match &file.content {
+        Content::None => Ok(data.to_owned()),
+        Content::BinaryDiffers => Err(Error::BinaryWithoutData),
+        Content::Binary { forward, reverse } => {
+            let hunk = if opts.reverse {
+                reverse.as_ref().ok_or(Error::BinaryWithoutData)?
+            } else {
+                forward
+            };
+            match hunk {
+                BinaryHunk::Literal(data) => Ok(data.clone()),
+                BinaryHunk::Delta(delta) => binary::apply_delta(data, delta).ok_or(Error::BinaryDelta),
+            }
+        }
+        Content::Text(hunks) => apply_hunks(data, hunks, opts),
+    }
+}
+
+/// The outcome of [`apply_three_way()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The patched data, which may contain conflict markers.
+    pub data: Vec<u8>,
+    /// The amount of conflicts that were marked in `data`.
+    pub conflicts: usize,
+}
+
+/// Apply `file` to `ours`, and if that fails, apply it to `base` instead, the version the patch was created against,
+/// and merge the result with `ours`.
+///
+/// `base` is typically looked up using the pre-image id of the patch. Conflicting changes are marked in the output,
+/// like `git apply --3way` does. Binary files are never merged, just like text patches won't be merged into binary files.
+/// The `attributes` of the patched path decide whether a file is binary for merging before its content does,
+/// see [`blob::is_binary_for()`].
+pub fn apply_three_way(
+    ours: &[u8],
+    base: Option<&[u8]>,
+    file: &File<'_>,
+    attributes: Option<&blob::Attributes<'_>>,
+    opts: Options,
+) -> Result<Outcome, Error> {
+    let err = match apply(ours, file, opts) {
+        Ok(data) => return Ok(Outcome { data, conflicts: 0 }),
+        Err(err) => err,
+    };
+    let is_text = |data: &[u8]| !blob::is_binary_for(data, attributes, Purpose::Merge, opts.classification);
+    let base = match base {
+        Some(base) if matches!(file.content, Content::Text(_)) && is_text(base) && is_text(ours) => base,
+        _ => return Err(err),
+    };
+    let theirs = apply(base, file, opts)?;
+    Ok(merge(base, ours, &theirs))
+}
+
+fn apply_hunks(data: &[u8], hunks: &[Hunk<'_>], opts: Options) -> Result<Vec<u8>, Error> {
+    let lines = blob::lines(data);
+    let mut out = Vec::with_capacity(data.len());
+    // The first line in `lines` not yet written to `out`.
+    let mut cursor = 0;
+    // The difference between the position stated in the hunks and where they actually applied.
+    let mut offset = 0isize;
+
+    for (hunk_idx, hunk) in hunks.iter().enumerate() {
+        let (mut preimage, mut postimage) = images(hunk, opts.reverse);
+        let stated = if opts.reverse { &hunk.after } else { &hunk.before };
+        let (mut leading, mut trailing) = context_len(hunk);
+
+        let (mut fuzz, mut skipped_leading) = (0, 0);
+        let pos = loop {
+            let expected = (stated.start as isize + offset + skipped_leading as isize).max(cursor as isize) as usize;
+            if let Some(pos) = find(&lines, &preimage, expected, cursor) {
+                break pos;
+            }
+            if fuzz == opts.fuzz || (leading == 0 && trailing == 0) {
+                return Err(Error::HunkMismatch { hunk: hunk_idx + 1 });
+            }
+            fuzz += 1;
+            if leading > 0 {
+                leading -= 1;
+                skipped_leading += 1;
+                preimage.remove(0);
+                postimage.remove(0);
+            }
+            if trailing > 0 {
+                trailing -= 1;
+                preimage.pop();
+                postimage.pop();
+            }
+        };
+
+        for line in &lines[cursor..pos] {
+            out.extend_from_slice(line);
+        }
+        for line in &postimage {
+            out.extend_from_slice(line);
+        }
+        cursor = pos + preimage.len();
+        offset = pos as isize - stated.start as isize - skipped_leading as isize;
+    }
+    for line in &lines[cursor..] {
+        out.extend_from_slice(line);
+    }
+    Ok(out)
+}
+
+/// Return the lines to find and the lines to replace them with.
+fn images<'a>(hunk: &Hunk<'a>, reverse: bool) -> (Vec<&'a BStr>, Vec<&'a BStr>) {
+    let mut preimage = Vec::new();
+    let mut postimage = Vec::new();
+    for line in &hunk.lines {
+        match (*line, reverse) {
+            (Line::Context(l), _) => {
+                preimage.push(l);
+                postimage.push(l);
+            }
+            (Line::Remove(l), false) | (Line::Add(l), true) => preimage.push(l),
+            (Line::Add(l), false) | (Line::Remove(l), true) => postimage.push(l),
+        }
+    }
+    (preimage, postimage)
+}
+
+/// Return the amount of context lines at the beginning and at the end of `hunk`.
+fn context_len(hunk: &Hunk<'_>) -> (usize, usize) {
+    let is_context = |l: &&Line<'_>| matches!(l, Line::Context(_));
+    let leading = hunk.lines.iter().take_while(is_context).count();
+    if leading == hunk.lines.len() {
+        return (leading, 0);
+    }
+    (leading, hunk.lines.iter().rev().take_while(is_context).count())
+}
+
+/// Find `needle` in `lines` at or after `min`, preferring positions close to `expected`.
+fn find(lines: &[&BStr], needle: &[&BStr], expected: usize, min: usize) -> Option<usize> {
+    let matches_at = |pos: usize| pos + needle.len() <= lines.len() && lines[pos..pos + needle.len()] == *needle;
+    let expected = expected.min(lines.len());
+    for distance in 0..=lines.len() {
+        let after = expected + distance;
+        if matches_at(after) {
+            return Some(after);
+        }
+        match expected.checked_sub(distance) {
+            Some(before) if before >= min && distance > 0 => {
+                if matches_at(before) {
+                    return Some(before);
+                }
+            }
+            _ if after > lines.len() => break,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Merge the changes from `base` to `ours` and from `base` to `theirs` line by line, marking conflicts.
+fn merge(base: &[u8], ours: &[u8], theirs: &[u8]) -> Outcome {
+    let ours = blob::diff(blob::Algorithm::Histogram, base, ours);
+    let theirs = blob::diff(blob::Algorithm::Histogram, base, theirs);
+    let base = &ours.before;
+
+    let mut out = Vec::new();
+    let mut conflicts = 0;
+    let (mut next_ours, mut next_theirs) = (0, 0);
+    let (mut delta_ours, mut delta_theirs) = (0isize, 0isize);
+    let mut pos = 0u32;
+    loop {
+        let (o, t) = (ours.changes.get(next_ours), theirs.changes.get(next_theirs));
+        let mut region = match (o, t) {
+            (None, None) => break,
+            (Some(o), Some(t)) if t.before.start < o.before.start => t.before.clone(),
+            (Some(o), _) => o.before.clone(),
+            (None, Some(t)) => t.before.clone(),
+        };
+        let (first_ours, first_theirs) = (next_ours, next_theirs);
+        loop {
+            let mut grew = false;
+            for (changes, next) in [(&ours.changes, &mut next_ours), (&theirs.changes, &mut next_theirs)] {
+                while let Some(c) = changes.get(*next) {
+                    if c.before.start > region.end {
+                        break;
+                    }
+                    region.end = region.end.max(c.before.end);
+                    *next += 1;
+                    grew = true;
+                }
+            }
+            if !grew {
+                break;
+            }
+        }
+
+        for line in &base[pos as usize..region.start as usize] {
+            out.extend_from_slice(line);
+        }
+        let side = |diff: &blob::Diff<'_>, first: usize, last: usize, delta: &mut isize| -> Vec<u8> {
+            let start = (region.start as isize + *delta) as usize;
+            for c in &diff.changes[first..last] {
+                *delta += c.after.len() as isize - c.before.len() as isize;
+            }
+            let end = (region.end as isize + *delta) as usize;
+            diff.after[start..end].iter().flat_map(|l| l.iter().copied()).collect()
+        };
+        let ours_text = side(&ours, first_ours, next_ours, &mut delta_ours);
+        let theirs_text = side(&theirs, first_theirs, next_theirs, &mut delta_theirs);
+        if first_ours == next_ours {
+            out.extend_from_slice(&theirs_text);
+        } else if first_theirs == next_theirs || ours_text == theirs_text {
+            out.extend_from_slice(&ours_text);
+        } else {
+            conflicts += 1;
+            out.extend_from_slice(b"<<<<<<< ours\n");
+            out.extend_from_slice(&ours_text);
+            if !ours_text.is_empty() && !ours_text.ends_with(b"\n") {
+                out.push(b'\n');
+            }
+            out.extend_from_slice(b"=======\n");
+            out.extend_from_slice(&theirs_text);
+            if !theirs_text.is_empty() && !theirs_text.ends_with(b"\n") {
+                out.push(b'\n');
+            }
+            out.extend_from_slice(b">>>>>>> theirs\n");
+        }
+        pos = region.end;
+    }
+    for line in &base[pos as usize..] {
+        out.extend_from_slice(line);
+    }
+    Outcome { data: out, conflicts }
+}
+
+///
+pub mod worktree;
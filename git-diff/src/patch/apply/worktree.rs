@@ -0,0 +1,348 @@
+use std::path::{Path, PathBuf};
+
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+
+use crate::patch::parse::{File, Operation};
+
+quick_error! {
+    /// The error returned by [`apply()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Io { path: PathBuf, err: std::io::Error } {
+            display("Could not access '{}'", path.display())
+            source(err)
+        }
+        Apply { path: BString, err: super::Error } {
+            display("The patch could not be applied to '{}'", path)
+            source(err)
+        }
+        MissingPath { file: usize } {
+            display("The change to file number {} doesn't state the path of the file", file)
+        }
+        IllformedPath { path: BString } {
+            display("The path '{}' can't be represented on this platform or leaves the work tree", path)
+        }
+        AlreadyExists { path: BString } {
+            display("'{}' can't be created as it exists already", path)
+        }
+        NotInIndex { path: BString } {
+            display("'{}' isn't in the index", path)
+        }
+        BeyondSymlink { path: BString } {
+            display("'{}' is beyond a symbolic link", path)
+        }
+        Index(err: git_index::upsert::Error) {
+            display("The index could not be updated")
+            source(err)
+        }
+        WriteBlob(err: Box<dyn std::error::Error + Send + Sync>) {
+            display("The patched content could not be written to the object database")
+            source(&**err)
+        }
+    }
+}
+
+/// The outcome of [`apply()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of files that were created or changed.
+    pub files_written: usize,
+    /// The amount of files that were deleted, including the old locations of renamed files.
+    pub files_deleted: usize,
+}
+
+/// A change to perform on disk once all files were patched successfully.
+struct Change<'a> {
+    delete: Option<&'a BStr>,
+    write: Option<(&'a BStr, Vec<u8>, EntryMode)>,
+}
+
+/// Apply `files` as parsed from a patch to the work tree at `root`, and if set, to `index` as well, like `git apply --index`.
+///
+/// All files are patched in memory before anything is written, so nothing changes if one of them doesn't apply.
+/// Entries of new and changed files are added to `index` with the id returned by `write_blob(data)`, which is expected to
+/// write the patched `data` to the object database. As their stat information is empty, they will be checked thoroughly
+/// for changes next time.
+///
+/// Note that three-way merges aren't supported here, see [`apply_three_way()`][super::apply_three_way()] for that.
+pub fn apply<E>(
+    root: &Path,
+    files: &[File<'_>],
+    mut index: Option<&mut git_index::State>,
+    mut write_blob: impl FnMut(&[u8]) -> Result<git_hash::ObjectId, E>,
+    opts: super::Options,
+) -> Result<Outcome, Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut changes = Vec::with_capacity(files.len());
+    for (file_idx, file) in files.iter().enumerate() {
+        let (operation, old_path, new_path, old_mode, new_mode) = if opts.reverse {
+            let operation = match file.operation {
+                Operation::Add => Operation::Delete,
+                Operation::Delete => Operation::Add,
+                other => other,
+            };
+            (operation, file.new_path, file.old_path, file.new_mode, file.old_mode)
+        } else {
+            (
+                file.operation,
+                file.old_path,
+                file.new_path,
+                file.old_mode,
+                file.new_mode,
+            )
+        };
+        let missing_path = || Error::MissingPath { file: file_idx + 1 };
+        let patched = |path: &BStr| -> Result<Vec<u8>, Error> {
+            let preimage = read(&native_path(root, path)?)?;
+            super::apply(&preimage, file, opts).map_err(|err| Error::Apply {
+                path: path.to_owned(),
+                err,
+            })
+        };
+        let assert_new = |path: &BStr| -> Result<(), Error> {
+            match native_path(root, path)?.symlink_metadata() {
+                Ok(_) => Err(Error::AlreadyExists { path: path.to_owned() }),
+                Err(_) => Ok(()),
+            }
+        };
+        let mode_or_existing = |source: &BStr| -> Result<EntryMode, Error> {
+            Ok(new_mode
+                .or(old_mode)
+                .or(mode_of(&native_path(root, source)?))
+                .unwrap_or(EntryMode::Blob))
+        };
+
+        let (change, tracked_path) = match operation {
+            Operation::Add => {
+                let path = new_path.ok_or_else(missing_path)?;
+                assert_new(path)?;
+                let data = super::apply(&[], file, opts).map_err(|err| Error::Apply {
+                    path: path.to_owned(),
+                    err,
+                })?;
+                let change = Change {
+                    delete: None,
+                    write: Some((path, data, new_mode.unwrap_or(EntryMode::Blob))),
+                };
+                (change, None)
+            }
+            Operation::Delete => {
+                let path = old_path.ok_or_else(missing_path)?;
+                patched(path)?;
+                let change = Change {
+                    delete: Some(path),
+                    write: None,
+                };
+                (change, Some(path))
+            }
+            Operation::Modify => {
+                let path = old_path.or(new_path).ok_or_else(missing_path)?;
+                let change = Change {
+                    delete: None,
+                    write: Some((path, patched(path)?, mode_or_existing(path)?)),
+                };
+                (change, Some(path))
+            }
+            Operation::Rename => {
+                let (old_path, new_path) = (old_path.ok_or_else(missing_path)?, new_path.ok_or_else(missing_path)?);
+                if old_path != new_path {
+                    assert_new(new_path)?;
+                }
+                let change = Change {
+                    delete: if old_path != new_path { Some(old_path) } else { None },
+                    write: Some((new_path, patched(old_path)?, mode_or_existing(old_path)?)),
+                };
+                (change, Some(old_path))
+            }
+            // Undoing a copy only removes the copy, without affecting its source.
+            Operation::Copy if opts.reverse => {
+                let path = old_path.ok_or_else(missing_path)?;
+                let change = Change {
+                    delete: Some(path),
+                    write: None,
+                };
+                (change, Some(path))
+            }
+            Operation::Copy => {
+                let (old_path, new_path) = (old_path.ok_or_else(missing_path)?, new_path.ok_or_else(missing_path)?);
+                assert_new(new_path)?;
+                let change = Change {
+                    delete: None,
+                    write: Some((new_path, patched(old_path)?, mode_or_existing(old_path)?)),
+                };
+                (change, None)
+            }
+        };
+        if let (Some(index), Some(path)) = (index.as_deref(), tracked_path) {
+            if index.entry_index_by_path_and_stage(path, 0).is_none() {
+                return Err(Error::NotInIndex { path: path.to_owned() });
+            }
+        }
+        changes.push(change);
+    }
+
+    // Delete first so that files can be replaced by directories of the same name, and vice versa.
+    let mut out = Outcome::default();
+    for path in changes.iter().filter_map(|change| change.delete) {
+        let file_path = native_path(root, path)?;
+        std::fs::remove_file(&file_path).map_err(|err| Error::Io {
+            path: file_path.clone(),
+            err,
+        })?;
+        remove_empty_parent_dirs(root, &file_path);
+        if let Some(index) = index.as_mut() {
+            if let Some(idx) = index.entry_index_by_path_and_stage(path, 0) {
+                index.remove_entry_at_index(idx);
+            }
+        }
+        out.files_deleted += 1;
+    }
+    for (path, data, mode) in changes.into_iter().filter_map(|change| change.write) {
+        let file_path = native_path(root, path)?;
+        write_file(&file_path, &data, mode)?;
+        if let Some(index) = index.as_mut() {
+            let id = write_blob(&data).map_err(|err| Error::WriteBlob(Box::new(err)))?;
+            index
+                .upsert_entry(
+                    path,
+                    Default::default(),
+                    id,
+                    git_index::entry::Flags::empty(),
+                    index_mode(mode),
+                )
+                .map_err(Error::Index)?;
+        }
+        out.files_written += 1;
+    }
+    Ok(out)
+}
+
+/// Convert the slash-separated `path` relative to the work tree into a path within `root`.
+fn native_path(root: &Path, path: &BStr) -> Result<PathBuf, Error> {
+    let illformed = || Error::IllformedPath { path: path.to_owned() };
+    if path.is_empty() || path.split_str("/").any(|c| c.is_empty() || c == b"." || c == b"..") {
+        return Err(illformed());
+    }
+    let native = git_features::path::convert::to_native_separators(path.as_bytes());
+    let native = git_features::path::from_bytes(native).map_err(|_| illformed())?;
+    if native.is_absolute() {
+        return Err(illformed());
+    }
+    // Like git, refuse to follow symbolic links in leading directories which could point outside of the work tree.
+    let mut leading_dir = root.to_owned();
+    let mut components = native.components().peekable();
+    while let Some(component) = components.next() {
+        if components.peek().is_none() {
+            break;
+        }
+        leading_dir.push(component);
+        if leading_dir
+            .symlink_metadata()
+            .map_or(false, |meta| meta.file_type().is_symlink())
+        {
+            return Err(Error::BeyondSymlink { path: path.to_owned() });
+        }
+    }
+    Ok(root.join(native))
+}
+
+/// Read the content of the file at `path`, or the target of the symbolic link at `path`.
+fn read(path: &Path) -> Result<Vec<u8>, Error> {
+    let io_err = |err| Error::Io {
+        path: path.to_owned(),
+        err,
+    };
+    if path.symlink_metadata().map_err(io_err)?.file_type().is_symlink() {
+        let target = std::fs::read_link(path).map_err(io_err)?;
+        git_features::path::into_bytes(target)
+            .map(|target| git_features::path::convert::to_unix_separators(target).into_owned())
+            .map_err(|_| {
+                io_err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Illformed UTF-8 in link target",
+                ))
+            })
+    } else {
+        std::fs::read(path).map_err(io_err)
+    }
+}
+
+fn write_file(path: &Path, data: &[u8], mode: EntryMode) -> Result<(), Error> {
+    let io_err = |err| Error::Io {
+        path: path.to_owned(),
+        err,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io_err)?;
+    }
+    if path.symlink_metadata().is_ok() {
+        std::fs::remove_file(path).map_err(io_err)?;
+    }
+    match mode {
+        #[cfg(unix)]
+        EntryMode::Link => {
+            let target = git_features::path::from_byte_slice(data).map_err(|_| {
+                io_err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Illformed UTF-8 in link target",
+                ))
+            })?;
+            std::os::unix::fs::symlink(target, path).map_err(io_err)
+        }
+        _ => {
+            std::fs::write(path, data).map_err(io_err)?;
+            #[cfg(unix)]
+            if mode == EntryMode::BlobExecutable {
+                use std::os::unix::fs::PermissionsExt;
+                let mut permissions = std::fs::metadata(path).map_err(io_err)?.permissions();
+                permissions.set_mode(permissions.mode() | 0o111);
+                std::fs::set_permissions(path, permissions).map_err(io_err)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Return the mode of the file at `path` as it would be stored in a tree.
+fn mode_of(path: &Path) -> Option<EntryMode> {
+    let meta = path.symlink_metadata().ok()?;
+    if meta.file_type().is_symlink() {
+        return Some(EntryMode::Link);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o100 != 0 {
+            return Some(EntryMode::BlobExecutable);
+        }
+    }
+    Some(EntryMode::Blob)
+}
+
+/// Remove the directories leading to `path` as long as they are empty, but never `root` itself.
+fn remove_empty_parent_dirs(root: &Path, path: &Path) {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) || std::fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+fn index_mode(mode: EntryMode) -> git_index::entry::Mode {
+    match mode {
+        EntryMode::Tree => git_index::entry::Mode::DIR,
+        EntryMode::Blob => git_index::entry::Mode::FILE,
+        EntryMode::BlobExecutable => git_index::entry::Mode::FILE_EXECUTABLE,
+        EntryMode::Link => git_index::entry::Mode::SYMLINK,
+        EntryMode::Commit => git_index::entry::Mode::COMMIT,
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, io, io::Write};
+
+/// The alphabet used by git to encode binary patches with base85.
+pub(crate) const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// The maximum amount of decoded bytes per line of a binary patch.
+pub(crate) const MAX_BYTES_PER_LINE: usize = 52;
+
+/// Write a single hunk of a `GIT binary patch` to `out` which turns `from` into `to`, using a delta if it is smaller
+/// than the literal content.
+pub fn write_hunk(mut out: impl io::Write, from: &[u8], to: &[u8]) -> io::Result<()> {
+    let literal = deflate(to)?;
+    let delta = (!from.is_empty() && !to.is_empty())
+        .then(|| encode_delta(from, to))
+        .map(|delta| deflate(&delta).map(|compressed| (delta.len(), compressed)))
+        .transpose()?;
+    match delta {
+        Some((delta_len, compressed)) if compressed.len() < literal.len() => {
+            writeln!(out, "delta {}", delta_len)?;
+            write_base85_lines(&mut out, &compressed)?;
+        }
+        _ => {
+            writeln!(out, "literal {}", to.len())?;
+            write_base85_lines(&mut out, &literal)?;
+        }
+    }
+    writeln!(out)
+}
+
+/// Write `data` as base85 encoded lines, each prefixed with a character denoting the amount of decoded bytes it contains.
+pub fn write_base85_lines(mut out: impl io::Write, data: &[u8]) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(MAX_BYTES_PER_LINE / 4 * 5 + 2);
+    for line in data.chunks(MAX_BYTES_PER_LINE) {
+        buf.clear();
+        buf.push(match line.len() {
+            len @ 1..=26 => b'A' + len as u8 - 1,
+            len => b'a' + len as u8 - 27,
+        });
+        encode_base85(line, &mut buf);
+        buf.push(b'\n');
+        out.write_all(&buf)?;
+    }
+    Ok(())
+}
+
+/// Encode `data` with git's base85 alphabet into `out`, padding the last group of four bytes with zeroes.
+pub fn encode_base85(data: &[u8], out: &mut Vec<u8>) {
+    for group in data.chunks(4) {
+        let mut word = [0u8; 4];
+        word[..group.len()].copy_from_slice(group);
+        let mut value = u32::from_be_bytes(word);
+        let mut encoded = [0u8; 5];
+        for byte in encoded.iter_mut().rev() {
+            *byte = BASE85_ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+        out.extend_from_slice(&encoded);
+    }
+}
+
+/// Decode a single base85 encoded `line` of a binary patch, including its length prefix, and append the decoded bytes to `out`.
+///
+/// Returns `None` if the line is malformed.
+pub fn decode_base85_line(line: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    let (len, data) = line.split_first()?;
+    let len = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => return None,
+    } as usize;
+    if data.len() != (len + 3) / 4 * 5 {
+        return None;
+    }
+    let start = out.len();
+    for group in data.chunks(5) {
+        let mut value = 0u32;
+        for byte in group {
+            let digit = BASE85_ALPHABET.iter().position(|b| b == byte)? as u32;
+            value = value.checked_mul(85)?.checked_add(digit)?;
+        }
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.truncate(start + len);
+    Some(())
+}
+
+/// Apply `delta` as produced by [`encode_delta()`] to `base`, returning the resulting data or `None` if the delta is malformed
+/// or doesn't belong to `base`.
+pub fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let (base_len, mut delta) = read_varint(delta)?;
+    if base_len != base.len() {
+        return None;
+    }
+    let (target_len, rest) = read_varint(delta)?;
+    delta = rest;
+    let mut out = Vec::with_capacity(target_len);
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut offset = 0usize;
+            let mut size = 0usize;
+            for bit in 0..7 {
+                if cmd & (1 << bit) != 0 {
+                    let (&byte, rest) = delta.split_first()?;
+                    delta = rest;
+                    if bit < 4 {
+                        offset |= (byte as usize) << (8 * bit);
+                    } else {
+                        size |= (byte as usize) << (8 * (bit - 4));
+                    }
+                }
+            }
+            if size == 0 {
+                size = 0x10000;
+            }
+            out.extend_from_slice(base.get(offset..offset.checked_add(size)?)?);
+        } else if cmd != 0 {
+            let len = cmd as usize;
+            out.extend_from_slice(delta.get(..len)?);
+            delta = &delta[len..];
+        } else {
+            return None;
+        }
+    }
+    (out.len() == target_len).then(|| out)
+}
+
+fn read_varint(data: &[u8]) -> Option<(usize, &[u8])> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    for (idx, byte) in data.iter().enumerate() {
+        value |= ((byte & 0x7f) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Some((value, &data[idx + 1..]));
+        }
+    }
+    None
+}
+
+/// Produce a delta in the format used in packs and binary patches which turns `base` into `target`.
+///
+/// It finds copies from `base` by looking up blocks of bytes and falls back to inserting literal bytes otherwise.
+pub fn encode_delta(base: &[u8], target: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = 16;
+    const MAX_COPY: usize = 0x10000;
+    const MAX_INSERT: usize = 0x7f;
+
+    let mut index = HashMap::<&[u8], usize>::new();
+    for (block_idx, block) in base.chunks_exact(BLOCK).enumerate() {
+        index.entry(block).or_insert(block_idx * BLOCK);
+    }
+
+    let mut out = Vec::with_capacity(target.len() / 2);
+    write_varint(&mut out, base.len());
+    write_varint(&mut out, target.len());
+
+    let mut insert_start = 0;
+    let mut pos = 0;
+    while pos < target.len() {
+        let found = target
+            .get(pos..pos + BLOCK)
+            .and_then(|block| index.get(block))
+            .copied();
+        let base_pos = match found {
+            Some(base_pos) => base_pos,
+            None => {
+                pos += 1;
+                continue;
+            }
+        };
+        let len = base[base_pos..]
+            .iter()
+            .zip(&target[pos..])
+            .take_while(|(a, b)| a == b)
+            .count();
+        for chunk in target[insert_start..pos].chunks(MAX_INSERT) {
+            out.push(chunk.len() as u8);
+            out.extend_from_slice(chunk);
+        }
+        let mut copied = 0;
+        while copied < len {
+            let size = (len - copied).min(MAX_COPY);
+            write_copy(&mut out, base_pos + copied, size);
+            copied += size;
+        }
+        pos += len;
+        insert_start = pos;
+    }
+    for chunk in target[insert_start..].chunks(MAX_INSERT) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+    out
+}
+
+fn write_copy(out: &mut Vec<u8>, offset: usize, size: usize) {
+    let cmd_pos = out.len();
+    out.push(0x80);
+    let mut cmd = 0x80;
+    for (bit, byte) in (offset as u32).to_le_bytes().iter().enumerate() {
+        if *byte != 0 {
+            cmd |= 1 << bit;
+            out.push(*byte);
+        }
+    }
+    // a size of 0x10000 is encoded as zero
+    let size = if size == 0x10000 { 0 } else { size as u32 };
+    for (bit, byte) in size.to_le_bytes()[..3].iter().enumerate() {
+        if *byte != 0 {
+            cmd |= 0x10 << bit;
+            out.push(*byte);
+        }
+    }
+    out[cmd_pos] = cmd;
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let mut byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n != 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if n == 0 {
+            break;
+        }
+    }
+}
+
+fn deflate(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+    out.write_all(data)?;
+    out.flush()?;
+    Ok(out.into_inner())
+}
@@ -0,0 +1,84 @@
+use std::io;
+
+use git_hash::oid;
+use git_object::{bstr::ByteSlice, CommitRef};
+
+/// Write the mail header of a patch created from the commit with `id` and its decoded `commit` to `out`, similar to what
+/// `git format-patch` produces.
+///
+/// If `numbered` is `Some((n, total))`, the subject will be prefixed with `[PATCH n/total]` instead of `[PATCH]`.
+/// The caller is expected to write all file diffs afterwards, followed by [`write_footer()`].
+pub fn write_header(
+    mut out: impl io::Write,
+    id: &oid,
+    commit: &CommitRef<'_>,
+    numbered: Option<(usize, usize)>,
+) -> io::Result<()> {
+    writeln!(out, "From {} Mon Sep 17 00:00:00 2001", id.to_hex())?;
+    writeln!(out, "From: {} <{}>", commit.author.name, commit.author.email)?;
+    write!(out, "Date: ")?;
+    write_rfc2822(&mut out, commit.author.time)?;
+    writeln!(out)?;
+
+    let message = commit.message.trim_end();
+    let (subject, body) = match message.find(b"\n\n") {
+        Some(pos) => (&message[..pos], message[pos..].trim_start()),
+        None => (message, &[][..]),
+    };
+    let subject: Vec<_> = subject.lines().map(|l| l.trim()).collect();
+    let subject = subject.join(&b' ');
+    match numbered {
+        Some((n, total)) => write!(out, "Subject: [PATCH {}/{}] ", n, total)?,
+        None => write!(out, "Subject: [PATCH] ")?,
+    }
+    out.write_all(&subject)?;
+    out.write_all(b"\n\n")?;
+    if !body.is_empty() {
+        out.write_all(body)?;
+        out.write_all(b"\n")?;
+    }
+    out.write_all(b"---\n\n")
+}
+
+/// Write the signature separator and `version` to `out`, which concludes a patch mail.
+pub fn write_footer(mut out: impl io::Write, version: &str) -> io::Result<()> {
+    writeln!(out, "-- \n{}\n", version)
+}
+
+/// Write `time` like `Thu, 7 Apr 2005 15:14:13 -0700`.
+fn write_rfc2822(mut out: impl io::Write, time: git_actor::Time) -> io::Result<()> {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let offset = time.offset_in_seconds as i64;
+    let local = time.seconds_since_unix_epoch as i64 + offset;
+    let days = local.div_euclid(86400);
+    let secs_of_day = local.rem_euclid(86400);
+
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    write!(
+        out,
+        "{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+        WEEKDAYS[(days + 4).rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 3600,
+        offset.abs() / 60 % 60
+    )
+}
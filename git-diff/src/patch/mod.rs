@@ -0,0 +1,243 @@
+use std::io;
+
+use git_attributes::binary::Purpose;
+use git_hash::oid;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+};
+
+use crate::blob::{self, Algorithm, Line};
+
+/// Options to control how patches are written with [`write_file()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of unchanged lines to show around each change.
+    pub context_lines: u32,
+    /// The algorithm to use for diffing blobs.
+    pub algorithm: Algorithm,
+    /// The prefix to put in front of the path of the old version, like `a/`.
+    pub src_prefix: BString,
+    /// The prefix to put in front of the path of the new version, like `b/`.
+    pub dst_prefix: BString,
+    /// The amount of hexadecimal characters to show for object ids in the `index` line.
+    pub abbrev: usize,
+    /// If true, binary files will be written as `GIT binary patch` that can be applied, instead of just
+    /// stating that they differ.
+    pub binary: bool,
+    /// Configure how blobs are classified as binary after their attributes had no say.
+    pub classification: git_attributes::binary::Options,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context_lines: 3,
+            algorithm: Algorithm::default(),
+            src_prefix: "a/".into(),
+            dst_prefix: "b/".into(),
+            abbrev: 7,
+            binary: false,
+            classification: Default::default(),
+        }
+    }
+}
+
+/// One side of a [`FileChange`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Side<'a> {
+    /// The path of the file relative to the root of the repository.
+    pub path: &'a BStr,
+    /// The mode of the file.
+    pub mode: EntryMode,
+    /// The id of the blob.
+    pub id: &'a oid,
+    /// The content of the blob.
+    pub data: &'a [u8],
+    /// The attributes of `path`, which decide before `data` whether the file is diffed as binary.
+    pub attributes: Option<&'a blob::Attributes<'a>>,
+}
+
+/// A change to a single file, which is an addition if there is no `before`, a deletion if there is no `after` or a modification,
+/// which is also a rename if the paths differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileChange<'a> {
+    /// The old version of the file, if it existed.
+    pub before: Option<Side<'a>>,
+    /// The new version of the file, if it exists.
+    pub after: Option<Side<'a>>,
+}
+
+/// Write `change` as git-style unified diff to `out`, including all extended headers, configured by `opts`.
+///
+/// Changes to gitlinks are written as changes of the submodule commit, see [`crate::submodule::Change::write_patch()`].
+pub fn write_file(mut out: impl io::Write, change: &FileChange<'_>, opts: &Options) -> io::Result<()> {
+    let (before, after) = (change.before, change.after);
+    if before.is_none() && after.is_none() {
+        return Ok(());
+    }
+    let old_path = before.or(after).expect("at least one side").path;
+    let new_path = after.or(before).expect("at least one side").path;
+    if old_path == new_path && [before, after].iter().flatten().all(|s| s.mode == EntryMode::Commit) {
+        let side = |s: Option<Side<'_>>| s.map(|s| (s.mode, s.id.to_owned()));
+        return crate::submodule::Change::from_entries(side(before), side(after))
+            .expect("at least one gitlink")
+            .write_patch(out, new_path, opts);
+    }
+    writeln!(
+        out,
+        "diff --git {}{} {}{}",
+        opts.src_prefix, old_path, opts.dst_prefix, new_path
+    )?;
+
+    match (before, after) {
+        (None, Some(after)) => writeln!(out, "new file mode {:06o}", after.mode as u16)?,
+        (Some(before), None) => writeln!(out, "deleted file mode {:06o}", before.mode as u16)?,
+        (Some(before), Some(after)) => {
+            if before.mode != after.mode {
+                writeln!(out, "old mode {:06o}", before.mode as u16)?;
+                writeln!(out, "new mode {:06o}", after.mode as u16)?;
+            }
+            if before.path != after.path {
+                writeln!(out, "similarity index {}%", similarity(before, after, opts.algorithm))?;
+                writeln!(out, "rename from {}", before.path)?;
+                writeln!(out, "rename to {}", after.path)?;
+            }
+            if before.id == after.id {
+                return Ok(());
+            }
+        }
+        (None, None) => unreachable!("handled above"),
+    }
+
+    let data_before = before.map(|s| s.data).unwrap_or_default();
+    let data_after = after.map(|s| s.data).unwrap_or_default();
+    let is_binary = [before, after]
+        .iter()
+        .flatten()
+        .any(|side| blob::is_binary_for(side.data, side.attributes, Purpose::Diff, opts.classification));
+    let abbrev = if is_binary && opts.binary {
+        usize::MAX
+    } else {
+        opts.abbrev
+    };
+    let id_before = before.map(|s| s.id).unwrap_or_else(|| null_of(after));
+    let id_after = after.map(|s| s.id).unwrap_or_else(|| null_of(before));
+    write!(
+        out,
+        "index {}..{}",
+        id_before.to_hex_with_len(abbrev),
+        id_after.to_hex_with_len(abbrev)
+    )?;
+    match (before, after) {
+        (Some(before), Some(after)) if before.mode == after.mode => writeln!(out, " {:06o}", after.mode as u16)?,
+        _ => writeln!(out)?,
+    }
+
+    if is_binary {
+        if opts.binary {
+            writeln!(out, "GIT binary patch")?;
+            binary::write_hunk(&mut out, data_before, data_after)?;
+            binary::write_hunk(&mut out, data_after, data_before)?;
+        } else {
+            writeln!(
+                out,
+                "Binary files {} and {} differ",
+                side_path(before, opts.src_prefix.as_bstr()),
+                side_path(after, opts.dst_prefix.as_bstr())
+            )?;
+        }
+        return Ok(());
+    }
+
+    writeln!(out, "--- {}", side_path(before, opts.src_prefix.as_bstr()))?;
+    writeln!(out, "+++ {}", side_path(after, opts.dst_prefix.as_bstr()))?;
+    let diff = blob::diff(opts.algorithm, data_before, data_after);
+    write_hunks(out, &diff.hunks(opts.context_lines))
+}
+
+/// Write the given `hunks` in unified diff format, each with its `@@ … @@` header, to `out`.
+pub fn write_hunks(mut out: impl io::Write, hunks: &[blob::Hunk<'_>]) -> io::Result<()> {
+    for hunk in hunks {
+        write!(out, "@@ -")?;
+        write_range(&mut out, &hunk.before)?;
+        write!(out, " +")?;
+        write_range(&mut out, &hunk.after)?;
+        writeln!(out, " @@")?;
+        for line in &hunk.lines {
+            let (prefix, line) = match line {
+                Line::Context(line) => (b' ', line),
+                Line::Remove(line) => (b'-', line),
+                Line::Add(line) => (b'+', line),
+            };
+            out.write_all(&[prefix])?;
+            out.write_all(line)?;
+            if !line.ends_with(b"\n") {
+                out.write_all(b"\n\\ No newline at end of file\n")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_range(mut out: impl io::Write, range: &std::ops::Range<u32>) -> io::Result<()> {
+    match range.len() {
+        0 => write!(out, "{},0", range.start),
+        1 => write!(out, "{}", range.start + 1),
+        len => write!(out, "{},{}", range.start + 1, len),
+    }
+}
+
+fn side_path(side: Option<Side<'_>>, prefix: &BStr) -> BString {
+    match side {
+        Some(side) => {
+            let mut path = prefix.to_owned();
+            path.extend_from_slice(side.path);
+            path
+        }
+        None => "/dev/null".into(),
+    }
+}
+
+fn null_of(other: Option<Side<'_>>) -> &'static oid {
+    other.map_or(git_hash::Kind::Sha1, |s| s.id.kind()).null_ref()
+}
+
+/// Compute how similar both sides are in percent, based on the amount of unchanged bytes.
+fn similarity(before: Side<'_>, after: Side<'_>, algorithm: Algorithm) -> usize {
+    if before.id == after.id {
+        return 100;
+    }
+    let max = before.data.len().max(after.data.len());
+    if max == 0 {
+        return 100;
+    }
+    let diff = blob::diff(algorithm, before.data, after.data);
+    let removed: usize = diff
+        .changes
+        .iter()
+        .flat_map(|c| c.before.clone())
+        .map(|idx| diff.before[idx as usize].len())
+        .sum();
+    (before.data.len() - removed) * 100 / max
+}
+
+///
+pub mod binary;
+
+///
+pub mod parse;
+pub use parse::File;
+
+/// Parse all file changes contained in the unified diff or git patch `input`, ignoring all lines that don't belong to one,
+/// like the mail header of patches produced by `git format-patch`.
+pub fn parse(input: &[u8], opts: parse::Options) -> Result<Vec<File<'_>>, parse::Error> {
+    parse::parse(input, opts)
+}
+
+///
+pub mod apply;
+pub use apply::apply;
+
+///
+pub mod mbox;
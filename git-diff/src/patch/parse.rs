@@ -0,0 +1,425 @@
+use std::{io::Read, iter::Peekable, ops::Range};
+
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+
+use crate::blob::{Hunk, Line};
+
+quick_error! {
+    /// The error returned by [`parse()`][super::parse()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        HunkHeader { line: usize } {
+            display("Invalid hunk header in line {}", line)
+        }
+        HunkLine { line: usize } {
+            display("Line {} is not part of the hunk it should belong to", line)
+        }
+        Mode { line: usize } {
+            display("Invalid file mode in line {}", line)
+        }
+        BinaryHunk { line: usize } {
+            display("Invalid binary hunk in line {}", line)
+        }
+        Inflate(err: std::io::Error) {
+            display("Could not decompress binary hunk")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// Options for use in [`parse()`][super::parse()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of leading path components to remove from paths in `---` and `+++` lines and the `diff --git` header,
+    /// like `-p` in `git apply`.
+    pub strip: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip: 1 }
+    }
+}
+
+/// The kind of change to a file described in a patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// The file is modified in place.
+    Modify,
+    /// The file is newly created.
+    Add,
+    /// The file is deleted.
+    Delete,
+    /// The file is renamed, and possibly modified.
+    Rename,
+    /// The file is copied, and possibly modified.
+    Copy,
+}
+
+/// The content change of a [`File`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content<'a> {
+    /// There is no content change, only a change of metadata like the mode or the path.
+    None,
+    /// The text hunks to apply.
+    Text(Vec<Hunk<'a>>),
+    /// The patch states that binary files differ, but doesn't contain the data to apply it.
+    BinaryDiffers,
+    /// A `GIT binary patch` with a `forward` hunk to obtain the new version, and an optional `reverse` one to obtain
+    /// the old version from the new one.
+    Binary {
+        /// The hunk to produce the new version from the old one.
+        forward: BinaryHunk,
+        /// The hunk to produce the old version from the new one.
+        reverse: Option<BinaryHunk>,
+    },
+}
+
+/// A decoded and decompressed hunk of a binary patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryHunk {
+    /// The complete content of the resulting file.
+    Literal(Vec<u8>),
+    /// A delta to apply to the base file to obtain the resulting file.
+    Delta(Vec<u8>),
+}
+
+/// All information about the change of a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File<'a> {
+    /// The path of the file before the change, or `None` if it didn't exist.
+    pub old_path: Option<&'a BStr>,
+    /// The path of the file after the change, or `None` if it was deleted.
+    pub new_path: Option<&'a BStr>,
+    /// The mode of the file before the change, if known.
+    pub old_mode: Option<EntryMode>,
+    /// The mode of the file after the change, if known.
+    pub new_mode: Option<EntryMode>,
+    /// The kind of change.
+    pub operation: Operation,
+    /// The similarity in percent for renames and copies.
+    pub similarity: Option<u8>,
+    /// The possibly abbreviated hexadecimal id of the blob before the change.
+    pub old_id: Option<&'a BStr>,
+    /// The possibly abbreviated hexadecimal id of the blob after the change.
+    pub new_id: Option<&'a BStr>,
+    /// The change to the content.
+    pub content: Content<'a>,
+}
+
+impl<'a> File<'a> {
+    fn new() -> Self {
+        File {
+            old_path: None,
+            new_path: None,
+            old_mode: None,
+            new_mode: None,
+            operation: Operation::Modify,
+            similarity: None,
+            old_id: None,
+            new_id: None,
+            content: Content::None,
+        }
+    }
+}
+
+type Lines<'a> = Peekable<std::iter::Enumerate<std::slice::SplitInclusive<'a, u8, fn(&u8) -> bool>>>;
+
+pub(crate) fn parse(input: &[u8], opts: Options) -> Result<Vec<File<'_>>, Error> {
+    fn is_newline(b: &u8) -> bool {
+        *b == b'\n'
+    }
+    let mut lines: Lines<'_> = input
+        .split_inclusive(is_newline as fn(&u8) -> bool)
+        .enumerate()
+        .peekable();
+    let mut files = Vec::new();
+    while let Some((_, line)) = lines.peek().copied() {
+        if let Some(rest) = line.strip_prefix(b"diff --git ") {
+            lines.next();
+            let mut file = File::new();
+            if let Some((old, new)) = split_git_header_paths(trim_newline(rest).as_bstr(), opts.strip) {
+                file.old_path = Some(old);
+                file.new_path = Some(new);
+            }
+            parse_extended_headers(&mut lines, &mut file, opts)?;
+            files.push(file);
+        } else if line.starts_with(b"--- ") {
+            let mut file = File::new();
+            parse_extended_headers(&mut lines, &mut file, opts)?;
+            files.push(file);
+        } else {
+            lines.next();
+        }
+    }
+    Ok(files)
+}
+
+fn parse_extended_headers<'a>(lines: &mut Lines<'a>, file: &mut File<'a>, opts: Options) -> Result<(), Error> {
+    while let Some((line_no, line)) = lines.peek().copied() {
+        let line_no = line_no + 1;
+        let text = trim_newline(line);
+        if let Some(mode) = text.strip_prefix(b"old mode ") {
+            file.old_mode = Some(parse_mode(mode, line_no)?);
+        } else if let Some(mode) = text.strip_prefix(b"new mode ") {
+            file.new_mode = Some(parse_mode(mode, line_no)?);
+        } else if let Some(mode) = text.strip_prefix(b"new file mode ") {
+            file.operation = Operation::Add;
+            file.new_mode = Some(parse_mode(mode, line_no)?);
+            file.old_path = None;
+        } else if let Some(mode) = text.strip_prefix(b"deleted file mode ") {
+            file.operation = Operation::Delete;
+            file.old_mode = Some(parse_mode(mode, line_no)?);
+            file.new_path = None;
+        } else if let Some(value) = text
+            .strip_prefix(b"similarity index ")
+            .or_else(|| text.strip_prefix(b"dissimilarity index "))
+        {
+            file.similarity = value.strip_suffix(b"%").and_then(|v| v.to_str().ok()?.parse().ok());
+        } else if let Some(path) = text.strip_prefix(b"rename from ") {
+            file.operation = Operation::Rename;
+            file.old_path = Some(path.as_bstr());
+        } else if let Some(path) = text.strip_prefix(b"rename to ") {
+            file.operation = Operation::Rename;
+            file.new_path = Some(path.as_bstr());
+        } else if let Some(path) = text.strip_prefix(b"copy from ") {
+            file.operation = Operation::Copy;
+            file.old_path = Some(path.as_bstr());
+        } else if let Some(path) = text.strip_prefix(b"copy to ") {
+            file.operation = Operation::Copy;
+            file.new_path = Some(path.as_bstr());
+        } else if let Some(ids) = text.strip_prefix(b"index ") {
+            let (ids, mode) = match ids.find_byte(b' ') {
+                Some(pos) => (&ids[..pos], Some(&ids[pos + 1..])),
+                None => (ids, None),
+            };
+            if let Some(pos) = ids.find(b"..") {
+                file.old_id = Some(ids[..pos].as_bstr());
+                file.new_id = Some(ids[pos + 2..].as_bstr());
+            }
+            if let Some(mode) = mode {
+                let mode = parse_mode(mode, line_no)?;
+                file.old_mode = Some(mode);
+                file.new_mode = Some(mode);
+            }
+        } else if let Some(path) = text.strip_prefix(b"--- ") {
+            if let Some(path) = unified_path(path, opts.strip) {
+                file.old_path = Some(path);
+            }
+        } else if let Some(path) = text.strip_prefix(b"+++ ") {
+            if let Some(path) = unified_path(path, opts.strip) {
+                file.new_path = Some(path);
+            }
+        } else if text.starts_with(b"Binary files ") {
+            file.content = Content::BinaryDiffers;
+        } else if text == b"GIT binary patch" {
+            lines.next();
+            let forward = parse_binary_hunk(lines, line_no + 1)?.ok_or(Error::BinaryHunk { line: line_no + 1 })?;
+            let reverse = parse_binary_hunk(lines, line_no + 1)?;
+            file.content = Content::Binary { forward, reverse };
+            return Ok(());
+        } else if text.starts_with(b"@@ ") {
+            file.content = Content::Text(parse_hunks(lines)?);
+            return Ok(());
+        } else {
+            return Ok(());
+        }
+        lines.next();
+    }
+    Ok(())
+}
+
+fn parse_hunks<'a>(lines: &mut Lines<'a>) -> Result<Vec<Hunk<'a>>, Error> {
+    let mut hunks = Vec::new();
+    while let Some((line_no, line)) = lines.peek().copied() {
+        let line_no = line_no + 1;
+        if !line.starts_with(b"@@ ") {
+            break;
+        }
+        lines.next();
+        let (before, after) = parse_hunk_header(trim_newline(line)).ok_or(Error::HunkHeader { line: line_no })?;
+        let (mut remaining_before, mut remaining_after) = (before.len(), after.len());
+        let mut hunk_lines = Vec::<Line<'a>>::new();
+        while remaining_before > 0 || remaining_after > 0 {
+            let (line_no, line) = lines.next().ok_or(Error::HunkLine { line: line_no })?;
+            let line_no = line_no + 1;
+            let (kind, content) = match line.split_first() {
+                Some((b' ', content)) => (b' ', content),
+                // some tools strip the trailing whitespace of empty context lines
+                Some((b'\n', _)) => (b' ', line),
+                Some((b'-', content)) => (b'-', content),
+                Some((b'+', content)) => (b'+', content),
+                Some((b'\\', _)) => {
+                    strip_trailing_newline(hunk_lines.last_mut());
+                    continue;
+                }
+                _ => return Err(Error::HunkLine { line: line_no }),
+            };
+            let content = content.as_bstr();
+            hunk_lines.push(match kind {
+                b' ' if remaining_before > 0 && remaining_after > 0 => {
+                    remaining_before -= 1;
+                    remaining_after -= 1;
+                    Line::Context(content)
+                }
+                b'-' if remaining_before > 0 => {
+                    remaining_before -= 1;
+                    Line::Remove(content)
+                }
+                b'+' if remaining_after > 0 => {
+                    remaining_after -= 1;
+                    Line::Add(content)
+                }
+                _ => return Err(Error::HunkLine { line: line_no }),
+            });
+        }
+        if let Some((_, line)) = lines.peek() {
+            if line.starts_with(b"\\") {
+                strip_trailing_newline(hunk_lines.last_mut());
+                lines.next();
+            }
+        }
+        hunks.push(Hunk {
+            before,
+            after,
+            lines: hunk_lines,
+        });
+    }
+    Ok(hunks)
+}
+
+fn strip_trailing_newline(line: Option<&mut Line<'_>>) {
+    if let Some(Line::Context(l) | Line::Remove(l) | Line::Add(l)) = line {
+        if let Some(stripped) = l.strip_suffix(b"\n") {
+            *l = stripped.as_bstr();
+        }
+    }
+}
+
+/// Parse `@@ -a,b +c,d @@` into zero-based line ranges.
+fn parse_hunk_header(line: &[u8]) -> Option<(Range<u32>, Range<u32>)> {
+    let line = line.strip_prefix(b"@@ -")?;
+    let end = line.find(b" @@")?;
+    let mut ranges = line[..end].split_str(" +");
+    let before = parse_range(ranges.next()?)?;
+    let after = parse_range(ranges.next()?)?;
+    Some((before, after))
+}
+
+fn parse_range(range: &[u8]) -> Option<Range<u32>> {
+    let range = range.to_str().ok()?;
+    let (start, len) = match range.split_once(',') {
+        Some((start, len)) => (start.parse::<u32>().ok()?, len.parse::<u32>().ok()?),
+        None => (range.parse().ok()?, 1),
+    };
+    let start = if len == 0 { start } else { start.checked_sub(1)? };
+    Some(start..start.checked_add(len)?)
+}
+
+fn parse_binary_hunk(lines: &mut Lines<'_>, line_no: usize) -> Result<Option<BinaryHunk>, Error> {
+    let header = match lines.peek() {
+        Some((_, line)) => trim_newline(line),
+        None => return Ok(None),
+    };
+    let (is_literal, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+        (true, size)
+    } else if let Some(size) = header.strip_prefix(b"delta ") {
+        (false, size)
+    } else {
+        return Ok(None);
+    };
+    let size: usize = size
+        .to_str()
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or(Error::BinaryHunk { line: line_no })?;
+    lines.next();
+
+    let mut compressed = Vec::new();
+    for (line_no, line) in lines.by_ref() {
+        let line = trim_newline(line);
+        if line.is_empty() {
+            break;
+        }
+        super::binary::decode_base85_line(line, &mut compressed).ok_or(Error::BinaryHunk { line: line_no + 1 })?;
+    }
+    let mut data = Vec::with_capacity(size);
+    inflate(&compressed, &mut data)?;
+    if data.len() != size {
+        return Err(Error::BinaryHunk { line: line_no });
+    }
+    Ok(Some(if is_literal {
+        BinaryHunk::Literal(data)
+    } else {
+        BinaryHunk::Delta(data)
+    }))
+}
+
+fn inflate(compressed: &[u8], out: &mut Vec<u8>) -> std::io::Result<()> {
+    git_features::zlib::stream::inflate::ReadBoxed {
+        inner: compressed,
+        decompressor: Box::new(git_features::zlib::Decompress::new(true)),
+    }
+    .read_to_end(out)
+    .map(|_| ())
+}
+
+/// Split `a/path b/path` into both paths, which is ambiguous if paths contain spaces. Hence we only accept
+/// splits that yield the same path after stripping.
+fn split_git_header_paths(paths: &BStr, strip: usize) -> Option<(&BStr, &BStr)> {
+    let mid = paths.len() / 2;
+    if paths.get(mid) == Some(&b' ') {
+        let (old, new) = (
+            strip_components(&paths[..mid], strip)?,
+            strip_components(&paths[mid + 1..], strip)?,
+        );
+        if old == new {
+            return Some((old, new));
+        }
+    }
+    None
+}
+
+fn unified_path(path: &[u8], strip: usize) -> Option<&BStr> {
+    let path = match path.find_byte(b'\t') {
+        Some(pos) => &path[..pos],
+        None => path,
+    };
+    if path == b"/dev/null" {
+        return None;
+    }
+    strip_components(path, strip)
+}
+
+fn strip_components(mut path: &[u8], strip: usize) -> Option<&BStr> {
+    for _ in 0..strip {
+        path = &path[path.find_byte(b'/')? + 1..];
+    }
+    Some(path.as_bstr())
+}
+
+fn parse_mode(mode: &[u8], line: usize) -> Result<EntryMode, Error> {
+    let mode = mode
+        .to_str()
+        .ok()
+        .and_then(|m| u32::from_str_radix(m.trim(), 8).ok())
+        .ok_or(Error::Mode { line })?;
+    Ok(match mode {
+        0o040000 => EntryMode::Tree,
+        0o120000 => EntryMode::Link,
+        0o160000 => EntryMode::Commit,
+        m if m & 0o111 != 0 => EntryMode::BlobExecutable,
+        m if m & 0o170000 == 0o100000 => EntryMode::Blob,
+        _ => return Err(Error::Mode { line }),
+    })
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
@@ -0,0 +1,173 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use git_attributes::binary::Purpose;
+use git_hash::{oid, ObjectId};
+
+use crate::blob;
+
+/// Options for use in [`detect_renames()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The minimal similarity between a deleted and an added blob to be considered a rename, from `0.0` to `1.0`,
+    /// like the percentage passed to `git diff -M`. At `1.0` only renames of unchanged blobs are detected.
+    pub percentage: f32,
+    /// If the amount of deletions times the amount of additions exceeds the square of this limit, only renames of unchanged
+    /// blobs are detected, like `diff.renameLimit` does. `0` means there is no limit.
+    pub limit: usize,
+    /// The algorithm to use for computing the similarity of blobs.
+    pub algorithm: blob::Algorithm,
+    /// Configure how blobs are classified as binary after their attributes had no say.
+    pub classification: git_attributes::binary::Options,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            percentage: 0.5,
+            limit: 1000,
+            algorithm: blob::Algorithm::default(),
+            classification: Default::default(),
+        }
+    }
+}
+
+/// A deleted or added blob as passed to [`detect_renames()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blob<'a> {
+    /// The id of the blob.
+    pub id: ObjectId,
+    /// The attributes of the path of the blob, which decide before its content whether it is binary.
+    pub attributes: Option<&'a blob::Attributes<'a>>,
+}
+
+impl From<ObjectId> for Blob<'_> {
+    fn from(id: ObjectId) -> Self {
+        Blob { id, attributes: None }
+    }
+}
+
+/// A deleted and an added blob that are considered to be the same blob which was moved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rename {
+    /// The index of the deleted blob in the `deletions` passed to [`detect_renames()`].
+    pub deletion: usize,
+    /// The index of the added blob in the `additions` passed to [`detect_renames()`].
+    pub addition: usize,
+    /// The similarity of both blobs from `0.0` to `1.0`, with `1.0` meaning they are the same.
+    pub similarity: f32,
+}
+
+/// Pair each of the `deletions` with at most one of the `additions` if their blobs are similar enough
+/// according to `options`, and return the pairs ordered by the index of their addition.
+///
+/// * Blobs with the same id are paired first, in order, before the blobs of all remaining ids are compared with each other.
+/// * `find` is a function `f(object_id, &mut buffer) -> Option<BlobRef>` to return a blob for the given object id
+///   backing its data in the given buffer. Blobs that can't be found can only be renamed without changes.
+/// * The similarity is the amount of bytes in lines that both blobs have in common, divided by the size of the larger blob,
+///   with binary blobs only ever being similar if they are the same. The attributes of each blob decide whether it is binary
+///   before its content does, see [`blob::is_binary_for()`].
+/// * Each blob is paired with the most similar one, preferring the earliest deletion and addition if there is a tie.
+pub fn detect_renames<FindFn>(
+    deletions: &[Blob<'_>],
+    additions: &[Blob<'_>],
+    mut find: FindFn,
+    options: Options,
+) -> Vec<Rename>
+where
+    FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<git_object::BlobRef<'b>>,
+{
+    let mut out = Vec::new();
+    let mut deletions_by_id = HashMap::<_, Vec<_>>::new();
+    for (idx, blob) in deletions.iter().enumerate().rev() {
+        deletions_by_id.entry(blob.id).or_default().push(idx);
+    }
+    let mut deleted = vec![false; deletions.len()];
+    let mut added = vec![false; additions.len()];
+    for (addition, blob) in additions.iter().enumerate() {
+        if let Some(deletion) = deletions_by_id.get_mut(&blob.id).and_then(Vec::pop) {
+            deleted[deletion] = true;
+            added[addition] = true;
+            out.push(Rename {
+                deletion,
+                addition,
+                similarity: 1.0,
+            });
+        }
+    }
+
+    let remaining_deletions: Vec<_> = (0..deletions.len()).filter(|idx| !deleted[*idx]).collect();
+    let remaining_additions: Vec<_> = (0..additions.len()).filter(|idx| !added[*idx]).collect();
+    let num_pairs = remaining_deletions.len() * remaining_additions.len();
+    if options.percentage < 1.0 && num_pairs != 0 && (options.limit == 0 || num_pairs <= options.limit * options.limit)
+    {
+        let mut buf = Vec::new();
+        let mut load = |candidate: &Blob<'_>| {
+            find(&candidate.id, &mut buf).map(|data| {
+                let is_binary =
+                    blob::is_binary_for(data.data, candidate.attributes, Purpose::Diff, options.classification);
+                (data.data.to_owned(), is_binary)
+            })
+        };
+        let before: Vec<_> = remaining_deletions.iter().map(|idx| load(&deletions[*idx])).collect();
+        let after: Vec<_> = remaining_additions.iter().map(|idx| load(&additions[*idx])).collect();
+
+        let mut candidates = Vec::new();
+        for (d, before) in before.iter().enumerate() {
+            for (a, after) in after.iter().enumerate() {
+                if let (Some(before), Some(after)) = (before, after) {
+                    let similarity = similarity(before, after, options);
+                    if similarity >= options.percentage {
+                        candidates.push((similarity, remaining_deletions[d], remaining_additions[a]));
+                    }
+                }
+            }
+        }
+        candidates.sort_by(|(lhs, ld, la), (rhs, rd, ra)| {
+            rhs.partial_cmp(lhs)
+                .unwrap_or(Ordering::Equal)
+                .then(ld.cmp(rd))
+                .then(la.cmp(ra))
+        });
+        for (similarity, deletion, addition) in candidates {
+            if deleted[deletion] || added[addition] {
+                continue;
+            }
+            deleted[deletion] = true;
+            added[addition] = true;
+            out.push(Rename {
+                deletion,
+                addition,
+                similarity,
+            });
+        }
+    }
+    out.sort_by_key(|r| r.addition);
+    out
+}
+
+/// Return the similarity of `before` and `after` along with whether they are binary, or `0.0` if it's known to be smaller
+/// than the configured percentage.
+fn similarity(
+    (before, before_is_binary): &(Vec<u8>, bool),
+    (after, after_is_binary): &(Vec<u8>, bool),
+    options: Options,
+) -> f32 {
+    let max_len = before.len().max(after.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+    if (before.len().min(after.len()) as f32 / max_len as f32) < options.percentage
+        || *before_is_binary
+        || *after_is_binary
+    {
+        return 0.0;
+    }
+    let diff = blob::diff(options.algorithm, before, after);
+    let removed: usize = diff
+        .changes
+        .iter()
+        .flat_map(|c| &diff.before[c.before.start as usize..c.before.end as usize])
+        .map(|line| line.len())
+        .sum();
+    (before.len() - removed) as f32 / max_len as f32
+}
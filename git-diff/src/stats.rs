@@ -0,0 +1,235 @@
+use std::{fmt, io};
+
+use git_attributes::binary::Purpose;
+use git_object::bstr::{BStr, BString, ByteSlice};
+
+use crate::blob::{self, Algorithm};
+
+/// Statistics about the change of a single file, like shown by `git diff --numstat`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The path of the changed file.
+    pub path: BString,
+    /// The amount of added lines, always 0 for binary files.
+    pub insertions: u32,
+    /// The amount of removed lines, always 0 for binary files.
+    pub deletions: u32,
+    /// If set, the file is binary and the values are the size in bytes of the old and the new version respectively.
+    pub binary: Option<(u64, u64)>,
+}
+
+impl File {
+    /// Compute the statistics for the file at `path` which changed from `before` to `after`, using `algorithm` for text files.
+    ///
+    /// The `attributes` of `path` decide whether the file is binary before its content does, see [`blob::is_binary_for()`].
+    pub fn from_blobs(
+        path: impl Into<BString>,
+        before: &[u8],
+        after: &[u8],
+        attributes: Option<&blob::Attributes<'_>>,
+        algorithm: Algorithm,
+    ) -> Self {
+        let is_binary = |data| blob::is_binary_for(data, attributes, Purpose::Diff, Default::default());
+        if is_binary(before) || is_binary(after) {
+            return File {
+                path: path.into(),
+                insertions: 0,
+                deletions: 0,
+                binary: Some((before.len() as u64, after.len() as u64)),
+            };
+        }
+        Self::from_diff(path, &blob::diff(algorithm, before, after))
+    }
+
+    /// Obtain the statistics for the file at `path` from an existing line `diff`.
+    pub fn from_diff(path: impl Into<BString>, diff: &blob::Diff<'_>) -> Self {
+        let (insertions, deletions) = diff.changes.iter().fold((0, 0), |(ins, del), c| {
+            (ins + c.after.end - c.after.start, del + c.before.end - c.before.start)
+        });
+        File {
+            path: path.into(),
+            insertions,
+            deletions,
+            binary: None,
+        }
+    }
+
+    /// The amount of damage done to this file in the unit used by `mode`, for use in [`dirstat()`].
+    fn damage(&self, mode: DirstatMode) -> u64 {
+        match mode {
+            DirstatMode::Files => 1,
+            DirstatMode::Lines => match self.binary {
+                // binary files are counted in 64 byte chunks, like git does
+                Some((before, after)) => (before + after) / 64,
+                None => self.insertions as u64 + self.deletions as u64,
+            },
+        }
+    }
+}
+
+/// The totals of a set of file changes, like shown by `git diff --shortstat`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    /// The amount of changed files.
+    pub files_changed: usize,
+    /// The total amount of added lines.
+    pub insertions: u64,
+    /// The total amount of removed lines.
+    pub deletions: u64,
+}
+
+impl<'a> std::iter::FromIterator<&'a File> for Summary {
+    fn from_iter<T: IntoIterator<Item = &'a File>>(iter: T) -> Self {
+        iter.into_iter().fold(Summary::default(), |mut s, f| {
+            s.files_changed += 1;
+            s.insertions += f.insertions as u64;
+            s.deletions += f.deletions as u64;
+            s
+        })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn plural(n: u64) -> &'static str {
+            if n == 1 {
+                ""
+            } else {
+                "s"
+            }
+        }
+        if self.files_changed == 0 {
+            return f.write_str(" 0 files changed");
+        }
+        write!(
+            f,
+            " {} file{} changed",
+            self.files_changed,
+            plural(self.files_changed as u64)
+        )?;
+        if self.insertions > 0 || self.deletions == 0 {
+            write!(f, ", {} insertion{}(+)", self.insertions, plural(self.insertions))?;
+        }
+        if self.deletions > 0 || self.insertions == 0 {
+            write!(f, ", {} deletion{}(-)", self.deletions, plural(self.deletions))?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `files` in the format of `git diff --numstat` to `out`, showing `-` for insertions and deletions of binary files.
+pub fn write_numstat<'a>(mut out: impl io::Write, files: impl IntoIterator<Item = &'a File>) -> io::Result<()> {
+    for file in files {
+        match file.binary {
+            Some(_) => out.write_all(b"-\t-\t")?,
+            None => write!(out, "{}\t{}\t", file.insertions, file.deletions)?,
+        }
+        out.write_all(&file.path)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Determines how changes are counted when computing the [`dirstat()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirstatMode {
+    /// Count added and removed lines, with binary files counted in chunks of 64 bytes.
+    Lines,
+    /// Count the amount of changed files.
+    Files,
+}
+
+/// Options for use in [`dirstat()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirstatOptions {
+    /// How to count changes.
+    pub mode: DirstatMode,
+    /// Directories with less than this amount of changes in permille (1/1000th) are not shown.
+    pub limit_permille: u32,
+    /// If true, the changes of a directory also count towards all of its parent directories even if it is shown itself.
+    pub cumulative: bool,
+}
+
+impl Default for DirstatOptions {
+    fn default() -> Self {
+        DirstatOptions {
+            mode: DirstatMode::Lines,
+            limit_permille: 30,
+            cumulative: false,
+        }
+    }
+}
+
+/// A directory along with its share of all changes, as computed by [`dirstat()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirstat {
+    /// The path of the directory, including a trailing slash.
+    pub path: BString,
+    /// The share of this directory of all changes, in permille.
+    pub permille: u32,
+}
+
+impl fmt::Display for Dirstat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:4}.{}% {}", self.permille / 10, self.permille % 10, self.path)
+    }
+}
+
+/// Compute the distribution of changes across directories like `git diff --dirstat` does, ordered by path.
+pub fn dirstat<'a>(files: impl IntoIterator<Item = &'a File>, opts: DirstatOptions) -> Vec<Dirstat> {
+    let mut damage: Vec<(&BStr, u64)> = files
+        .into_iter()
+        .map(|f| (f.path.as_bstr(), f.damage(opts.mode)))
+        .filter(|(_, damage)| *damage > 0)
+        .collect();
+    damage.sort_by(|a, b| a.0.cmp(b.0));
+    let total: u64 = damage.iter().map(|(_, d)| d).sum();
+
+    let mut out = Vec::new();
+    if total > 0 {
+        gather(&damage, 0, total, opts, &mut out);
+    }
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    out
+}
+
+/// Accumulate the damage of all `entries` which share a directory prefix of `base_len` bytes, returning the damage
+/// that wasn't yet attributed to a shown subdirectory.
+fn gather(entries: &[(&BStr, u64)], base_len: usize, total: u64, opts: DirstatOptions, out: &mut Vec<Dirstat>) -> u64 {
+    let mut sum = 0;
+    let mut sources = 0;
+    let mut idx = 0;
+    while idx < entries.len() {
+        let (path, damage) = entries[idx];
+        match path[base_len..].find_byte(b'/') {
+            Some(slash) => {
+                let dir = &path[..base_len + slash + 1];
+                let end = idx + entries[idx..].iter().take_while(|(p, _)| p.starts_with(dir)).count();
+                sum += gather(&entries[idx..end], dir.len(), total, opts, out);
+                sources += 1;
+                idx = end;
+            }
+            None => {
+                sum += damage;
+                // files count twice so a directory with a single file is still shown
+                sources += 2;
+                idx += 1;
+            }
+        }
+    }
+
+    // Directories whose changes all come from a single subdirectory are represented by that subdirectory.
+    if base_len != 0 && sources != 1 && sum > 0 {
+        let permille = (sum * 1000 / total) as u32;
+        if permille >= opts.limit_permille {
+            out.push(Dirstat {
+                path: entries[0].0[..base_len].into(),
+                permille,
+            });
+            if !opts.cumulative {
+                return 0;
+            }
+        }
+    }
+    sum
+}
@@ -0,0 +1,249 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+};
+use quick_error::quick_error;
+
+use crate::{patch, tree};
+
+quick_error! {
+    /// The error returned by [`worktree_head()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Io(err: io::Error) {
+            display("Could not read the repository of the submodule worktree")
+            from()
+            source(err)
+        }
+        FindRef(err: git_ref::file::find::Error) {
+            display("A reference of the submodule could not be read")
+            from()
+            source(err)
+        }
+        SymbolicRefCycle(name: String) {
+            display("The symbolic reference '{}' could not be resolved as it is nested too deeply", name)
+        }
+    }
+}
+
+/// The state of the checked out worktree of a submodule.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Worktree {
+    /// The commit checked out in the worktree, or `None` if its `HEAD` is unborn.
+    pub head: Option<ObjectId>,
+    /// If true, tracked files in the worktree are modified.
+    pub modified_content: bool,
+    /// If true, there are untracked files in the worktree.
+    pub untracked_content: bool,
+}
+
+/// A change to a gitlink, the entry of a submodule in a tree, which records the submodule commits before and after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Change {
+    /// The submodule commit before the change, or `None` if the submodule was added or the entry was no gitlink before.
+    pub previous_commit: Option<ObjectId>,
+    /// The submodule commit after the change, or `None` if the submodule was removed or the entry is no gitlink anymore.
+    pub commit: Option<ObjectId>,
+    /// The state of the submodule worktree, if it is checked out.
+    pub worktree: Option<Worktree>,
+}
+
+impl Change {
+    /// Create a new instance from the modes and ids of both sides of a change, or `None` if neither side is a gitlink.
+    pub fn from_entries(
+        previous: Option<(EntryMode, ObjectId)>,
+        current: Option<(EntryMode, ObjectId)>,
+    ) -> Option<Self> {
+        let commit_of = |side: Option<(EntryMode, ObjectId)>| match side {
+            Some((EntryMode::Commit, id)) => Some(id),
+            _ => None,
+        };
+        let (previous_commit, commit) = (commit_of(previous), commit_of(current));
+        (previous_commit.is_some() || commit.is_some()).then(|| Change {
+            previous_commit,
+            commit,
+            worktree: None,
+        })
+    }
+
+    /// Set the state of the submodule `worktree` to take into account when describing the change.
+    pub fn with_worktree(mut self, worktree: Worktree) -> Self {
+        self.worktree = Some(worktree);
+        self
+    }
+
+    /// Return the commit the submodule is currently at, which is the commit checked out in its worktree if there is one,
+    /// or the recorded commit otherwise.
+    pub fn current_commit(&self) -> Option<ObjectId> {
+        match self.worktree {
+            Some(worktree) if self.commit.is_some() => worktree.head.or(self.commit),
+            _ => self.commit,
+        }
+    }
+
+    /// Return true if the worktree of the submodule contains modified or untracked files.
+    pub fn is_dirty(&self) -> bool {
+        self.worktree
+            .map_or(false, |wt| wt.modified_content || wt.untracked_content)
+    }
+
+    /// Return true if the worktree of the submodule has a different commit checked out than the one that is recorded.
+    pub fn has_new_commits(&self) -> bool {
+        self.current_commit() != self.commit
+    }
+
+    /// Return true if there is nothing to report, as the commits are the same and the worktree is clean.
+    pub fn is_unchanged(&self) -> bool {
+        self.previous_commit == self.current_commit() && !self.is_dirty()
+    }
+
+    /// Write this change of the submodule at `path` as a git-style diff to `out`, showing the submodule commits
+    /// as `Subproject commit <id>` lines and marking a dirty worktree with a `-dirty` suffix, like `git diff` does.
+    pub fn write_patch(&self, mut out: impl io::Write, path: &BStr, opts: &patch::Options) -> io::Result<()> {
+        if self.is_unchanged() {
+            return Ok(());
+        }
+        let current = self.current_commit();
+        writeln!(
+            out,
+            "diff --git {}{} {}{}",
+            opts.src_prefix, path, opts.dst_prefix, path
+        )?;
+        match (self.previous_commit, current) {
+            (None, Some(_)) => writeln!(out, "new file mode {:06o}", EntryMode::Commit as u16)?,
+            (Some(_), None) => writeln!(out, "deleted file mode {:06o}", EntryMode::Commit as u16)?,
+            _ => {}
+        }
+        let kind = self.previous_commit.or(current).map_or(git_hash::Kind::Sha1, |id| id.kind());
+        let null = ObjectId::null(kind);
+        write!(
+            out,
+            "index {}..{}",
+            self.previous_commit.unwrap_or(null).to_hex_with_len(opts.abbrev),
+            current.unwrap_or(null).to_hex_with_len(opts.abbrev)
+        )?;
+        match (self.previous_commit, current) {
+            (Some(_), Some(_)) => writeln!(out, " {:06o}", EntryMode::Commit as u16)?,
+            _ => writeln!(out)?,
+        }
+        let side_path = |id: Option<ObjectId>, prefix: &BStr| match id {
+            Some(_) => format!("{}{}", prefix, path),
+            None => "/dev/null".into(),
+        };
+        writeln!(out, "--- {}", side_path(self.previous_commit, opts.src_prefix.as_bstr()))?;
+        writeln!(out, "+++ {}", side_path(current, opts.dst_prefix.as_bstr()))?;
+        match (self.previous_commit, current) {
+            (Some(_), Some(_)) => writeln!(out, "@@ -1 +1 @@")?,
+            (None, Some(_)) => writeln!(out, "@@ -0,0 +1 @@")?,
+            (Some(_), None) => writeln!(out, "@@ -1 +0,0 @@")?,
+            (None, None) => writeln!(out, "@@ -0,0 +0,0 @@")?,
+        }
+        if let Some(previous) = self.previous_commit {
+            writeln!(out, "-Subproject commit {}", previous)?;
+        }
+        if let Some(current) = current {
+            writeln!(
+                out,
+                "+Subproject commit {}{}",
+                current,
+                if self.is_dirty() { "-dirty" } else { "" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl tree::visit::Change {
+    /// Return the submodule change if this change involves a gitlink on either side.
+    pub fn submodule(&self) -> Option<Change> {
+        use tree::visit::Change::*;
+        match self {
+            Addition { entry_mode, oid } => Change::from_entries(None, Some((*entry_mode, *oid))),
+            Deletion { entry_mode, oid } => Change::from_entries(Some((*entry_mode, *oid)), None),
+            Modification {
+                previous_entry_mode,
+                previous_oid,
+                entry_mode,
+                oid,
+            } => Change::from_entries(Some((*previous_entry_mode, *previous_oid)), Some((*entry_mode, *oid))),
+        }
+    }
+}
+
+impl tree::recorder::Change {
+    /// Return the submodule change if this change involves a gitlink on either side.
+    pub fn submodule(&self) -> Option<Change> {
+        use tree::recorder::Change::*;
+        match self {
+            Addition { entry_mode, oid, .. } => Change::from_entries(None, Some((*entry_mode, *oid))),
+            Deletion { entry_mode, oid, .. } => Change::from_entries(Some((*entry_mode, *oid)), None),
+            Modification {
+                previous_entry_mode,
+                previous_oid,
+                entry_mode,
+                oid,
+                ..
+            } => Change::from_entries(Some((*previous_entry_mode, *previous_oid)), Some((*entry_mode, *oid))),
+        }
+    }
+}
+
+/// Return the commit checked out in the submodule `worktree`, or `None` if it isn't checked out or its `HEAD` is unborn.
+///
+/// The repository of the worktree may be a `.git` directory or a `.git` file pointing to it, as used for submodules
+/// whose repository is stored in the `modules` directory of the superproject.
+pub fn worktree_head(worktree: impl AsRef<Path>) -> Result<Option<ObjectId>, Error> {
+    let worktree = worktree.as_ref();
+    let git_dir = match git_dir_of(worktree)? {
+        Some(git_dir) => git_dir,
+        None => return Ok(None),
+    };
+    let store = git_ref::file::Store::at(git_dir, git_ref::store::WriteReflog::Disable, git_hash::Kind::Sha1);
+    let mut reference = match store.try_find("HEAD")? {
+        Some(reference) => reference,
+        None => return Ok(None),
+    };
+    const MAX_REF_DEPTH: usize = 5;
+    for _ in 0..MAX_REF_DEPTH {
+        let name = match reference.target {
+            git_ref::Target::Peeled(id) => return Ok(Some(id)),
+            git_ref::Target::Symbolic(name) => name,
+        };
+        reference = match store.try_find(name.to_ref())? {
+            Some(reference) => reference,
+            None => return Ok(None),
+        };
+    }
+    Err(Error::SymbolicRefCycle(reference.name.as_bstr().to_string()))
+}
+
+fn git_dir_of(worktree: &Path) -> io::Result<Option<PathBuf>> {
+    let dot_git = worktree.join(".git");
+    let meta = match std::fs::metadata(&dot_git) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    if meta.is_dir() {
+        return Ok(Some(dot_git));
+    }
+    let content = std::fs::read(&dot_git)?;
+    match content.trim().strip_prefix(b"gitdir: ") {
+        Some(path) => Ok(Some(worktree.join(
+            path.trim()
+                .to_path()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+        ))),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} is not a valid git file", dot_git),
+        )),
+    }
+}
@@ -1,11 +1,15 @@
 use std::{borrow::BorrowMut, collections::VecDeque};
 
 use git_hash::{oid, ObjectId};
+use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
 use quick_error::quick_error;
 
 use crate::{
     tree,
-    tree::{visit::Change, TreeInfoPair},
+    tree::{
+        visit::{Action, Change},
+        TreeInfoPair, Visit,
+    },
 };
 
 quick_error! {
@@ -27,10 +31,48 @@ quick_error! {
     }
 }
 
+/// Options for use in [`tree::Changes::needed_to_obtain_with_options()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// If not empty, only changes to paths matching one of these pathspecs are reported, and trees that can't contain
+    /// matching paths are not traversed at all.
+    ///
+    /// A pathspec matches a path if it is equal to it or one of its leading directories, or if it matches it as glob pattern.
+    pub pathspecs: &'a [BString],
+    /// If `true`, changes to trees themselves are reported along with the changes to their entries.
+    pub emit_trees: bool,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options {
+            pathspecs: &[],
+            emit_trees: true,
+        }
+    }
+}
+
 impl<'a> tree::Changes<'a> {
+    /// Like [`needed_to_obtain_with_options()`][Self::needed_to_obtain_with_options()], but reports all changes including those to trees.
+    pub fn needed_to_obtain<FindFn, R, StateMut>(
+        self,
+        other: git_object::TreeRefIter<'_>,
+        state: StateMut,
+        find: FindFn,
+        delegate: &mut R,
+    ) -> Result<(), Error>
+    where
+        FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<git_object::TreeRefIter<'b>>,
+        R: tree::Visit,
+        StateMut: BorrowMut<tree::State>,
+    {
+        self.needed_to_obtain_with_options(other, state, find, delegate, Options::default())
+    }
+
     /// Calculate the changes that would need to be applied to `self` to get `other`.
     ///
     /// * The `state` maybe owned or mutably borrowed to allow reuses allocated data structures through multiple runs.
+    /// * `opts` allow to limit the changes to certain paths, pruning unrelated subtrees early, and to skip changes to trees.
     /// * `locate` is a function `f(object_id, &mut buffer) -> Option<TreeIter>` to return a `TreeIter` for the given object id backing
     ///   its data in the given buffer. Returning `None` is unexpected as these trees are obtained during iteration, and in a typical
     ///   database errors are not expected either which is why the error case is omitted. To allow proper error reporting, [`Error::NotFound`]
@@ -51,12 +93,13 @@ impl<'a> tree::Changes<'a> {
     ///
     /// [git_cmp_c]: https://github.com/git/git/blob/311531c9de557d25ac087c1637818bd2aad6eb3a/tree-diff.c#L49:L65
     /// [git_cmp_rs]: https://github.com/Byron/gitoxide/blob/a4d5f99c8dc99bf814790928a3bf9649cd99486b/git-object/src/mutable/tree.rs#L52-L55
-    pub fn needed_to_obtain<FindFn, R, StateMut>(
+    pub fn needed_to_obtain_with_options<FindFn, R, StateMut>(
         mut self,
         other: git_object::TreeRefIter<'_>,
         mut state: StateMut,
         mut find: FindFn,
         delegate: &mut R,
+        opts: Options<'_>,
     ) -> Result<(), Error>
     where
         FindFn: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Option<git_object::TreeRefIter<'b>>,
@@ -65,6 +108,7 @@ impl<'a> tree::Changes<'a> {
     {
         let state = state.borrow_mut();
         state.clear();
+        let delegate = &mut Delegate::new(delegate, opts);
         let mut lhs_entries = peekable(self.0.take().unwrap_or_default());
         let mut rhs_entries = peekable(other);
         let mut pop_path = false;
@@ -121,7 +165,7 @@ impl<'a> tree::Changes<'a> {
 fn delete_entry_schedule_recursion<R: tree::Visit>(
     entry: git_object::tree::EntryRef<'_>,
     queue: &mut VecDeque<TreeInfoPair>,
-    delegate: &mut R,
+    delegate: &mut Delegate<'_, R>,
 ) -> Result<(), Error> {
     delegate.push_path_component(entry.filename);
     if delegate
@@ -133,7 +177,7 @@ fn delete_entry_schedule_recursion<R: tree::Visit>(
     {
         return Err(Error::Cancelled);
     }
-    if entry.mode.is_tree() {
+    if entry.mode.is_tree() && delegate.descend() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
         queue.push_back((Some(entry.oid.to_owned()), None));
@@ -144,7 +188,7 @@ fn delete_entry_schedule_recursion<R: tree::Visit>(
 fn add_entry_schedule_recursion<R: tree::Visit>(
     entry: git_object::tree::EntryRef<'_>,
     queue: &mut VecDeque<TreeInfoPair>,
-    delegate: &mut R,
+    delegate: &mut Delegate<'_, R>,
 ) -> Result<(), Error> {
     delegate.push_path_component(entry.filename);
     if delegate
@@ -156,7 +200,7 @@ fn add_entry_schedule_recursion<R: tree::Visit>(
     {
         return Err(Error::Cancelled);
     }
-    if entry.mode.is_tree() {
+    if entry.mode.is_tree() && delegate.descend() {
         delegate.pop_path_component();
         delegate.push_back_tracked_path_component(entry.filename);
         queue.push_back((None, Some(entry.oid.to_owned())))
@@ -168,7 +212,7 @@ fn catchup_rhs_with_lhs<R: tree::Visit>(
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
     queue: &mut VecDeque<TreeInfoPair>,
-    delegate: &mut R,
+    delegate: &mut Delegate<'_, R>,
 ) -> Result<(), Error> {
     use std::cmp::Ordering::*;
     add_entry_schedule_recursion(rhs, queue, delegate)?;
@@ -208,7 +252,7 @@ fn catchup_lhs_with_rhs<R: tree::Visit>(
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
     queue: &mut VecDeque<TreeInfoPair>,
-    delegate: &mut R,
+    delegate: &mut Delegate<'_, R>,
 ) -> Result<(), Error> {
     use std::cmp::Ordering::*;
     delete_entry_schedule_recursion(lhs, queue, delegate)?;
@@ -247,12 +291,12 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
     lhs: git_object::tree::EntryRef<'_>,
     rhs: git_object::tree::EntryRef<'_>,
     queue: &mut VecDeque<TreeInfoPair>,
-    delegate: &mut R,
+    delegate: &mut Delegate<'_, R>,
 ) -> Result<(), Error> {
     use git_object::tree::EntryMode::*;
     match (lhs.mode, rhs.mode) {
         (Tree, Tree) => {
-            delegate.push_back_tracked_path_component(lhs.filename);
+            delegate.push_path_component(lhs.filename);
             if lhs.oid != rhs.oid
                 && delegate
                     .visit(Change::Modification {
@@ -265,10 +309,15 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             }
-            queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())));
+            // identical trees can't contain any changes, so there is no need to look at them
+            if lhs.oid != rhs.oid && delegate.descend() {
+                delegate.pop_path_component();
+                delegate.push_back_tracked_path_component(lhs.filename);
+                queue.push_back((Some(lhs.oid.to_owned()), Some(rhs.oid.to_owned())));
+            }
         }
         (lhs_mode, Tree) if lhs_mode.is_no_tree() => {
-            delegate.push_back_tracked_path_component(lhs.filename);
+            delegate.push_path_component(lhs.filename);
             if delegate
                 .visit(Change::Deletion {
                     entry_mode: lhs.mode,
@@ -287,10 +336,14 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             };
-            queue.push_back((None, Some(rhs.oid.to_owned())));
+            if delegate.descend() {
+                delegate.pop_path_component();
+                delegate.push_back_tracked_path_component(lhs.filename);
+                queue.push_back((None, Some(rhs.oid.to_owned())));
+            }
         }
         (Tree, rhs_mode) if rhs_mode.is_no_tree() => {
-            delegate.push_back_tracked_path_component(lhs.filename);
+            delegate.push_path_component(lhs.filename);
             if delegate
                 .visit(Change::Deletion {
                     entry_mode: lhs.mode,
@@ -309,7 +362,11 @@ fn handle_lhs_and_rhs_with_equal_filenames<R: tree::Visit>(
             {
                 return Err(Error::Cancelled);
             };
-            queue.push_back((Some(lhs.oid.to_owned()), None));
+            if delegate.descend() {
+                delegate.pop_path_component();
+                delegate.push_back_tracked_path_component(lhs.filename);
+                queue.push_back((Some(lhs.oid.to_owned()), None));
+            }
         }
         (lhs_non_tree, rhs_non_tree) => {
             delegate.push_path_component(lhs.filename);
@@ -336,3 +393,102 @@ type IteratorType<I> = std::mem::ManuallyDrop<std::iter::Peekable<I>>;
 fn peekable<I: Iterator>(iter: I) -> IteratorType<I> {
     std::mem::ManuallyDrop::new(iter.peekable())
 }
+
+/// A delegate wrapper which tracks the current path to apply the pathspec filter and to hide changes to trees if desired.
+pub(crate) struct Delegate<'a, R> {
+    inner: &'a mut R,
+    opts: Options<'a>,
+    path: BString,
+    path_deque: VecDeque<BString>,
+}
+
+impl<'a, R: tree::Visit> Delegate<'a, R> {
+    fn new(inner: &'a mut R, opts: Options<'a>) -> Self {
+        Delegate {
+            inner,
+            opts,
+            path: BString::default(),
+            path_deque: VecDeque::new(),
+        }
+    }
+
+    /// Return true if the tree at the current path may contain paths matching our pathspecs.
+    fn descend(&self) -> bool {
+        self.opts.pathspecs.is_empty()
+            || self
+                .opts
+                .pathspecs
+                .iter()
+                .any(|spec| git_pathspec::may_contain_matches(spec.as_ref(), self.path.as_ref()))
+    }
+
+    fn is_match(&self) -> bool {
+        self.opts.pathspecs.is_empty()
+            || self
+                .opts
+                .pathspecs
+                .iter()
+                .any(|spec| git_pathspec::matches(spec.as_ref(), self.path.as_ref()))
+    }
+}
+
+impl<R: tree::Visit> tree::Visit for Delegate<'_, R> {
+    fn pop_front_tracked_path_and_set_current(&mut self) {
+        if !self.opts.pathspecs.is_empty() {
+            self.path = self.path_deque.pop_front().expect("every parent is set only once");
+        }
+        self.inner.pop_front_tracked_path_and_set_current()
+    }
+
+    fn push_back_tracked_path_component(&mut self, component: &BStr) {
+        if !self.opts.pathspecs.is_empty() {
+            self.push_path(component);
+            self.path_deque.push_back(self.path.clone());
+        }
+        self.inner.push_back_tracked_path_component(component)
+    }
+
+    fn push_path_component(&mut self, component: &BStr) {
+        if !self.opts.pathspecs.is_empty() {
+            self.push_path(component);
+        }
+        self.inner.push_path_component(component)
+    }
+
+    fn pop_path_component(&mut self) {
+        if !self.opts.pathspecs.is_empty() {
+            match self.path.rfind_byte(b'/') {
+                Some(pos) => self.path.resize(pos, 0),
+                None => self.path.clear(),
+            }
+        }
+        self.inner.pop_path_component()
+    }
+
+    fn visit(&mut self, change: Change) -> Action {
+        let is_tree = match &change {
+            Change::Addition { entry_mode, .. } | Change::Deletion { entry_mode, .. } => entry_mode.is_tree(),
+            Change::Modification {
+                previous_entry_mode,
+                entry_mode,
+                ..
+            } => previous_entry_mode.is_tree() && entry_mode.is_tree(),
+        };
+        if is_tree && !self.opts.emit_trees {
+            return Action::Continue;
+        }
+        if !(self.is_match() || (is_tree && self.descend())) {
+            return Action::Continue;
+        }
+        self.inner.visit(change)
+    }
+}
+
+impl<R> Delegate<'_, R> {
+    fn push_path(&mut self, component: &BStr) {
+        if !self.path.is_empty() {
+            self.path.push(b'/');
+        }
+        self.path.push_str(component);
+    }
+}
@@ -0,0 +1,136 @@
+use git_diff::blob::{self, Algorithm, Change, Line};
+
+const ALGORITHMS: [Algorithm; 4] = [
+    Algorithm::Myers,
+    Algorithm::MyersMinimal,
+    Algorithm::Histogram,
+    Algorithm::Patience,
+];
+
+/// Apply `changes` to `before` to see if we obtain `after`.
+fn apply<'a>(before: &[&'a str], after: &[&'a str], changes: &[Change]) -> Vec<&'a str> {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    for change in changes {
+        out.extend_from_slice(&before[pos..change.before.start as usize]);
+        out.extend_from_slice(&after[change.after.start as usize..change.after.end as usize]);
+        pos = change.before.end as usize;
+    }
+    out.extend_from_slice(&before[pos..]);
+    out
+}
+
+#[test]
+fn all_algorithms_produce_changes_that_turn_before_into_after() {
+    let cases: &[(&str, &str)] = &[
+        ("", ""),
+        ("", "a b c"),
+        ("a b c", ""),
+        ("a b c", "a b c"),
+        ("a b c a b b a", "c b a b a c"),
+        ("x a b c y a b c z", "a b c q a b c"),
+        ("a b c d e f g h", "a x c d y f g z h"),
+        ("fn a x } fn b y }", "fn b y } fn a x }"),
+    ];
+    for (before, after) in cases {
+        let before: Vec<_> = before.split_whitespace().collect();
+        let after: Vec<_> = after.split_whitespace().collect();
+        for algorithm in ALGORITHMS {
+            let changes = blob::diff_tokens(algorithm, &before, &after);
+            assert_eq!(apply(&before, &after, &changes), after, "{:?}", algorithm);
+            assert_eq!(
+                before == after,
+                changes.is_empty(),
+                "equal inputs yield no changes for {:?}",
+                algorithm
+            );
+        }
+    }
+}
+
+#[test]
+fn myers_minimal_finds_the_shortest_edit_script() {
+    let before: Vec<_> = "a b c a b b a".split_whitespace().collect();
+    let after: Vec<_> = "c b a b a c".split_whitespace().collect();
+    let changes = blob::diff_tokens(Algorithm::MyersMinimal, &before, &after);
+    let cost: u32 = changes.iter().map(|c| c.before.len() as u32 + c.after.len() as u32).sum();
+    assert_eq!(cost, 5, "the classic example from the paper");
+}
+
+#[test]
+fn hunks_with_context() {
+    let before = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+    let after = "1\n2\nthree\n4\n5\n6\n7\n8\n9\nten\n";
+    let diff = blob::diff(Algorithm::Histogram, before.as_bytes(), after.as_bytes());
+
+    let hunks = diff.hunks(1);
+    assert_eq!(hunks.len(), 2, "changes are too far apart to be merged");
+    assert_eq!(hunks[0].before, 1..4);
+    assert_eq!(hunks[0].after, 1..4);
+    assert_eq!(
+        hunks[0].lines,
+        vec![
+            Line::Context("2\n".into()),
+            Line::Remove("3\n".into()),
+            Line::Add("three\n".into()),
+            Line::Context("4\n".into())
+        ]
+    );
+    assert_eq!(hunks[1].before, 8..10, "context is limited by the end of the file");
+
+    let hunks = diff.hunks(3);
+    assert_eq!(hunks.len(), 1, "with more context, both changes share a hunk");
+    assert_eq!(hunks[0].before, 0..10);
+}
+
+mod word {
+    use git_diff::blob::{self, word, word::Word, Algorithm};
+
+    #[test]
+    fn words_split_at_class_boundaries() {
+        assert_eq!(
+            word::words(b"fn foo_bar(x, y)  {"),
+            vec!["fn", " ", "foo_bar", "(", "x", ",", " ", "y", ")", "  ", "{"]
+        );
+    }
+
+    #[test]
+    fn word_diff_reproduces_both_sides() {
+        let words = word::diff(Algorithm::Histogram, b"let a = foo(1);", b"let b = foo(1, 2);");
+        assert_eq!(
+            words,
+            vec![
+                Word::Unchanged("let".into()),
+                Word::Unchanged(" ".into()),
+                Word::Removed("a".into()),
+                Word::Added("b".into()),
+                Word::Unchanged(" ".into()),
+                Word::Unchanged("=".into()),
+                Word::Unchanged(" ".into()),
+                Word::Unchanged("foo".into()),
+                Word::Unchanged("(".into()),
+                Word::Unchanged("1".into()),
+                Word::Added(",".into()),
+                Word::Added(" ".into()),
+                Word::Added("2".into()),
+                Word::Unchanged(")".into()),
+                Word::Unchanged(";".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn intra_line_ranges_of_hunk() {
+        let diff = blob::diff(Algorithm::Myers, b"a\nlet x = 1;\nb\n", b"a\nlet y = 1;\nb\nc\n");
+        let hunks = diff.hunks(1);
+        assert_eq!(hunks.len(), 1);
+        let changes = hunks[0].intra_line_changes(Algorithm::Myers);
+        assert_eq!(changes.len(), hunks[0].lines.len());
+        assert_eq!(changes[1].before, vec![4..5], "the removed line has 'x' changed");
+        assert_eq!(changes[2].after, vec![4..5], "the added line has 'y' changed");
+        assert!(
+            changes[4].after.is_empty(),
+            "lines without counterpart have no intra-line changes"
+        );
+    }
+}
@@ -2,4 +2,10 @@ pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 pub use git_testtools::hex_to_id;
 
+mod blob;
+mod driver;
+mod patch;
+mod rewrites;
+mod stats;
+mod submodule;
 mod visit;
@@ -0,0 +1,223 @@
+use std::{borrow::Cow, collections::HashMap, convert::Infallible};
+
+use git_diff::driver::{self, Attribute, Driver};
+use git_hash::{oid, ObjectId};
+use git_object::bstr::BStr;
+use git_testtools::hex_to_id;
+
+#[test]
+fn attribute_from_state() {
+    use git_attributes::State;
+    assert_eq!(Attribute::from(&State::Set), Attribute::Text);
+    assert_eq!(Attribute::from(&State::Unset), Attribute::Binary);
+    assert_eq!(Attribute::from(&State::Unspecified), Attribute::Auto);
+    assert_eq!(
+        Attribute::from(&State::Value("pdf".into())),
+        Attribute::Driver("pdf".into())
+    );
+}
+
+#[test]
+fn attribute_from_attributes() {
+    use git_attributes::State;
+    let attributes = |list: &[(&'static str, State<'static>)]| -> git_diff::blob::Attributes<'static> {
+        list.iter().map(|(name, state)| ((*name).into(), state.clone())).collect()
+    };
+    assert_eq!(Attribute::from_attributes(&attributes(&[])), Attribute::Auto);
+    assert_eq!(
+        Attribute::from_attributes(&attributes(&[
+            ("diff", State::Value("pdf".into())),
+            ("text", State::Unset)
+        ])),
+        Attribute::Driver("pdf".into())
+    );
+    assert_eq!(
+        Attribute::from_attributes(&attributes(&[("text", State::Unset)])),
+        Attribute::Binary,
+        "the text attribute decides if diff is unspecified"
+    );
+    assert_eq!(
+        Attribute::from_attributes(&attributes(&[("diff", State::Set), ("text", State::Unset)])),
+        Attribute::Text,
+        "but the diff attribute takes precedence"
+    );
+    assert_eq!(
+        Attribute::from_attributes(&attributes(&[("text", State::Value("auto".into()))])),
+        Attribute::Auto
+    );
+}
+
+#[test]
+fn driver_from_config() {
+    let config = |key: &str| -> Option<Cow<'static, BStr>> {
+        match key {
+            "textconv" => Some(Cow::Borrowed("pdftotext".into())),
+            "cachetextconv" => Some(Cow::Borrowed("yes".into())),
+            "binary" => Some(Cow::Borrowed("".into())),
+            _ => None,
+        }
+    };
+    let driver = Driver::from_config("pdf", config).expect("configured");
+    assert_eq!(
+        driver,
+        Driver {
+            name: "pdf".into(),
+            command: None,
+            textconv: Some("pdftotext".into()),
+            cache_textconv: true,
+            binary: Some(true),
+        }
+    );
+    assert_eq!(driver.textconv_notes_ref(), "refs/notes/textconv/pdf");
+    assert_eq!(
+        Driver::from_config("unknown", |_| None),
+        None,
+        "drivers without any configuration don't exist"
+    );
+}
+
+struct Upper {
+    calls: usize,
+}
+
+impl driver::TextConv for Upper {
+    type Error = Infallible;
+
+    fn convert(&mut self, _driver: &Driver, _path: &BStr, data: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        self.calls += 1;
+        Ok(data.to_ascii_uppercase())
+    }
+}
+
+#[derive(Default)]
+struct MemoryCache(HashMap<ObjectId, Vec<u8>>);
+
+impl driver::Cache for MemoryCache {
+    fn get(&mut self, _driver: &Driver, id: &oid) -> Option<Vec<u8>> {
+        self.0.get(id).cloned()
+    }
+
+    fn put(&mut self, _driver: &Driver, id: &oid, converted: &[u8]) {
+        self.0.insert(id.to_owned(), converted.to_owned());
+    }
+}
+
+#[test]
+fn in_process_conversion_with_cache() {
+    let id = hex_to_id("0000000000000000000000000000000000000001");
+    let mut driver = Driver {
+        name: "upper".into(),
+        ..Default::default()
+    };
+    let (mut conv, mut cache) = (Upper { calls: 0 }, MemoryCache::default());
+    assert_eq!(
+        driver::convert_to_text(&driver, "f".into(), &id, b"a", &mut conv, Some(&mut cache)).unwrap(),
+        None,
+        "drivers without textconv don't convert"
+    );
+
+    driver.textconv = Some("ignored".into());
+    for _ in 0..2 {
+        assert_eq!(
+            driver::convert_to_text(&driver, "f".into(), &id, b"a", &mut conv, Some(&mut cache)).unwrap(),
+            Some(b"A".to_vec())
+        );
+    }
+    assert_eq!(conv.calls, 2, "the cache is only used if the driver says so");
+
+    driver.cache_textconv = true;
+    for _ in 0..2 {
+        assert_eq!(
+            driver::convert_to_text(&driver, "f".into(), &id, b"a", &mut conv, Some(&mut cache)).unwrap(),
+            Some(b"A".to_vec())
+        );
+    }
+    assert_eq!(conv.calls, 3, "the second conversion is served from the cache");
+}
+
+#[cfg(unix)]
+mod process {
+    use git_diff::{
+        driver::{self, process, Driver, External as _, TextConv as _},
+        patch::Side,
+    };
+    use git_object::{bstr::ByteSlice, tree::EntryMode};
+    use git_testtools::hex_to_id;
+
+    #[test]
+    fn textconv_runs_program_with_file() {
+        let driver = Driver {
+            name: "cat".into(),
+            textconv: Some("cat".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            process::TextConv.convert(&driver, "dir/f".into(), b"content").unwrap(),
+            b"content"
+        );
+
+        let driver = Driver {
+            textconv: Some("false".into()),
+            ..driver
+        };
+        assert!(matches!(
+            process::TextConv.convert(&driver, "f".into(), b""),
+            Err(process::Error::Failed { .. })
+        ));
+    }
+
+    #[test]
+    fn external_receives_the_same_arguments_as_in_git() {
+        let driver = Driver {
+            name: "echo".into(),
+            command: Some("printf '%s\\n'".into()),
+            ..Default::default()
+        };
+        let id = hex_to_id("0000000000000000000000000000000000000001");
+        let out = process::External
+            .diff(
+                &driver,
+                "f".into(),
+                None,
+                Some(Side {
+                    path: "f".into(),
+                    mode: EntryMode::Blob,
+                    id: &id,
+                    data: b"",
+                    attributes: None,
+                }),
+            )
+            .unwrap();
+        let args: Vec<_> = out.lines().collect();
+        assert_eq!(args.len(), 7);
+        assert_eq!(&args[..4], &[&b"f"[..], b"/dev/null", b".", b"."]);
+        assert!(
+            args[4].ends_with(b"_f"),
+            "the temporary file name ends with the file name"
+        );
+        assert_eq!(
+            &args[5..],
+            &[&b"0000000000000000000000000000000000000001"[..], b"100644"]
+        );
+    }
+
+    #[test]
+    fn missing_program_is_an_error() {
+        let driver = Driver::default();
+        assert!(matches!(
+            driver::convert_to_text(
+                &driver,
+                "f".into(),
+                &hex_to_id("0000000000000000000000000000000000000001"),
+                b"",
+                &mut process::TextConv,
+                None
+            ),
+            Ok(None)
+        ));
+        assert!(matches!(
+            process::TextConv.convert(&driver, "f".into(), b""),
+            Err(process::Error::MissingProgram(_))
+        ));
+    }
+}
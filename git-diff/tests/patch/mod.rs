@@ -0,0 +1,614 @@
+use git_diff::patch::{self, FileChange, Side};
+use git_object::{bstr::ByteSlice, tree::EntryMode};
+use git_testtools::hex_to_id;
+
+fn write(change: FileChange<'_>, opts: &patch::Options) -> String {
+    let mut buf = Vec::new();
+    patch::write_file(&mut buf, &change, opts).unwrap();
+    buf.to_str().expect("valid UTF-8 in tests").to_owned()
+}
+
+#[test]
+fn modification_with_context_and_missing_newline() {
+    let before_id = hex_to_id("0000000000000000000000000000000000000001");
+    let after_id = hex_to_id("0000000000000000000000000000000000000002");
+    let change = FileChange {
+        before: Some(Side {
+            path: "f".into(),
+            mode: EntryMode::Blob,
+            id: &before_id,
+            data: b"1\n2\n3\n",
+            attributes: None,
+        }),
+        after: Some(Side {
+            path: "f".into(),
+            mode: EntryMode::Blob,
+            id: &after_id,
+            data: b"1\ntwo\n3",
+            attributes: None,
+        }),
+    };
+    assert_eq!(
+        write(change, &Default::default()),
+        "diff --git a/f b/f
+index 0000000..0000000 100644
+--- a/f
++++ b/f
+@@ -1,3 +1,3 @@
+ 1
+-2
+-3
++two
++3
+\\ No newline at end of file
+"
+    );
+}
+
+#[test]
+fn addition_deletion_and_prefixes() {
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let side = Side {
+        path: "dir/new".into(),
+        mode: EntryMode::BlobExecutable,
+        id: &id,
+        data: b"a\n",
+        attributes: None,
+    };
+    let opts = patch::Options {
+        src_prefix: "".into(),
+        dst_prefix: "".into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        write(
+            FileChange {
+                before: None,
+                after: Some(side)
+            },
+            &opts
+        ),
+        "diff --git dir/new dir/new
+new file mode 100755
+index 0000000..e69de29
+--- /dev/null
++++ dir/new
+@@ -0,0 +1 @@
++a
+"
+    );
+    assert_eq!(
+        write(
+            FileChange {
+                before: Some(side),
+                after: None
+            },
+            &Default::default()
+        ),
+        "diff --git a/dir/new b/dir/new
+deleted file mode 100755
+index e69de29..0000000
+--- a/dir/new
++++ /dev/null
+@@ -1 +0,0 @@
+-a
+"
+    );
+}
+
+#[test]
+fn pure_rename_with_mode_change() {
+    let id = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    let change = FileChange {
+        before: Some(Side {
+            path: "old".into(),
+            mode: EntryMode::Blob,
+            id: &id,
+            data: b"a\n",
+            attributes: None,
+        }),
+        after: Some(Side {
+            path: "new".into(),
+            mode: EntryMode::BlobExecutable,
+            id: &id,
+            data: b"a\n",
+            attributes: None,
+        }),
+    };
+    assert_eq!(
+        write(change, &Default::default()),
+        "diff --git a/old b/new
+old mode 100644
+new mode 100755
+similarity index 100%
+rename from old
+rename to new
+"
+    );
+}
+
+#[test]
+fn binary_files() {
+    let before_id = hex_to_id("0000000000000000000000000000000000000001");
+    let after_id = hex_to_id("0000000000000000000000000000000000000002");
+    let change = FileChange {
+        before: Some(Side {
+            path: "b".into(),
+            mode: EntryMode::Blob,
+            id: &before_id,
+            data: b"\0binary",
+            attributes: None,
+        }),
+        after: Some(Side {
+            path: "b".into(),
+            mode: EntryMode::Blob,
+            id: &after_id,
+            data: b"\0binary, changed",
+            attributes: None,
+        }),
+    };
+    assert!(write(change, &Default::default()).ends_with("Binary files a/b and b/b differ\n"));
+
+    let out = write(
+        change,
+        &patch::Options {
+            binary: true,
+            ..Default::default()
+        },
+    );
+    assert!(out.contains(
+        "index 0000000000000000000000000000000000000001..0000000000000000000000000000000000000002 100644\nGIT binary patch\n"
+    ));
+}
+
+#[test]
+fn attributes_decide_before_the_content() {
+    let before_id = hex_to_id("0000000000000000000000000000000000000001");
+    let after_id = hex_to_id("0000000000000000000000000000000000000002");
+    let binary: git_diff::blob::Attributes<'_> =
+        std::iter::once(("diff".into(), git_attributes::State::Unset)).collect();
+    let change = FileChange {
+        before: Some(Side {
+            path: "t".into(),
+            mode: EntryMode::Blob,
+            id: &before_id,
+            data: b"text\n",
+            attributes: None,
+        }),
+        after: Some(Side {
+            path: "t".into(),
+            mode: EntryMode::Blob,
+            id: &after_id,
+            data: b"changed text\n",
+            attributes: Some(&binary),
+        }),
+    };
+    assert!(
+        write(change, &Default::default()).ends_with("Binary files a/t and b/t differ\n"),
+        "a single side with binary attributes makes the whole file binary"
+    );
+}
+
+#[test]
+fn base85_matches_git() {
+    let mut buf = Vec::new();
+    patch::binary::encode_base85(b"\0\0\0\0\xff\xff\xff\xff", &mut buf);
+    assert_eq!(buf.as_bstr(), "00000|NsC0");
+}
+
+mod parse_and_apply {
+    use git_diff::patch::{self, apply, parse::Operation, FileChange, Side};
+    use git_object::tree::EntryMode;
+    use git_testtools::hex_to_id;
+
+    fn patch_of(before: &[u8], after: &[u8], binary: bool) -> Vec<u8> {
+        let (before_id, after_id) = (
+            hex_to_id("0000000000000000000000000000000000000001"),
+            hex_to_id("0000000000000000000000000000000000000002"),
+        );
+        let mut buf = Vec::new();
+        patch::write_file(
+            &mut buf,
+            &FileChange {
+                before: Some(Side {
+                    path: "f".into(),
+                    mode: EntryMode::Blob,
+                    id: &before_id,
+                    data: before,
+                    attributes: None,
+                }),
+                after: Some(Side {
+                    path: "f".into(),
+                    mode: EntryMode::Blob,
+                    id: &after_id,
+                    data: after,
+                    attributes: None,
+                }),
+            },
+            &patch::Options {
+                binary,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        buf
+    }
+
+    #[test]
+    fn roundtrip_text() {
+        let before = b"1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let after = b"1\ntwo\n3\n4\n5\n6\n7\n8\nnine";
+        let patch = patch_of(before, after, false);
+        let files = patch::parse(&patch, Default::default()).unwrap();
+        assert_eq!(files.len(), 1);
+        let file = &files[0];
+        assert_eq!(file.operation, Operation::Modify);
+        assert_eq!(file.old_path, Some("f".into()));
+        assert_eq!(file.new_path, Some("f".into()));
+        assert_eq!(file.old_mode, Some(EntryMode::Blob));
+
+        assert_eq!(patch::apply(before, file, Default::default()).unwrap(), after);
+        assert_eq!(
+            patch::apply(
+                after,
+                file,
+                apply::Options {
+                    reverse: true,
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            before
+        );
+    }
+
+    #[test]
+    fn roundtrip_binary() {
+        let before: Vec<u8> = (0..2000u32).map(|i| (i % 251) as u8).collect();
+        let mut after = before.clone();
+        after[1000] = 42;
+        let patch = patch_of(&before, &after, true);
+        let file = &patch::parse(&patch, Default::default()).unwrap()[0];
+        assert_eq!(patch::apply(&before, file, Default::default()).unwrap(), after);
+        assert_eq!(
+            patch::apply(
+                &after,
+                file,
+                apply::Options {
+                    reverse: true,
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            before
+        );
+    }
+
+    #[test]
+    fn offset_and_fuzz() {
+        let patch = patch_of(b"a\nb\nc\nd\ne\n", b"a\nb\nC\nd\ne\n", false);
+        let file = &patch::parse(&patch, Default::default()).unwrap()[0];
+
+        assert_eq!(
+            patch::apply(b"x\ny\na\nb\nc\nd\ne\n", file, Default::default()).unwrap(),
+            b"x\ny\na\nb\nC\nd\ne\n",
+            "hunks are found even if lines were inserted before"
+        );
+        assert_eq!(
+            patch::apply(b"A\nb\nc\nd\nE\n", file, Default::default()).unwrap(),
+            b"A\nb\nC\nd\nE\n",
+            "changed context is ignored within the fuzz limit"
+        );
+        assert!(patch::apply(
+            b"A\nb\nc\nd\nE\n",
+            file,
+            apply::Options {
+                fuzz: 0,
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn hunk_ranges_that_overflow_are_rejected() {
+        let patch = b"diff --git a/f b/f
+--- a/f
++++ b/f
+@@ -4294967295,2 +1,2 @@
+ a
+-b
++B
+";
+        assert!(patch::parse(patch, Default::default()).is_err());
+    }
+
+    #[test]
+    fn three_way_fallback_marks_conflicts() {
+        let base = b"1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let patch = patch_of(base, b"1\n2\nthree\n4\n5\n6\n7\n8\n9\n", false);
+        let file = &patch::parse(&patch, Default::default()).unwrap()[0];
+        let opts = apply::Options {
+            fuzz: 0,
+            ..Default::default()
+        };
+
+        let ours = b"1\n2\n3\n4\nfive\n6\n7\n8\nnine\n";
+        let outcome = apply::apply_three_way(ours, Some(base), file, None, opts).unwrap();
+        assert_eq!(outcome.conflicts, 0);
+        assert_eq!(outcome.data, b"1\n2\nthree\n4\nfive\n6\n7\n8\nnine\n");
+
+        let ours = b"1\n2\nTHREE\n4\n5\n6\n7\n8\n9\n";
+        let outcome = apply::apply_three_way(ours, Some(base), file, None, opts).unwrap();
+        assert_eq!(outcome.conflicts, 1);
+        assert_eq!(
+            outcome.data,
+            &b"1\n2\n<<<<<<< ours\nTHREE\n=======\nthree\n>>>>>>> theirs\n4\n5\n6\n7\n8\n9\n"[..]
+        );
+    }
+
+    #[test]
+    fn three_way_fallback_lets_the_merge_attribute_decide_what_is_binary() {
+        let base = b"1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let patch = patch_of(base, b"1\n2\nthree\n4\n5\n6\n7\n8\n9\n", false);
+        let file = &patch::parse(&patch, Default::default()).unwrap()[0];
+        let opts = apply::Options {
+            fuzz: 0,
+            ..Default::default()
+        };
+        let ours = b"1\n2\n3\n4\nfive\n6\n7\n8\nnine\n";
+
+        let mut attributes = git_diff::blob::Attributes::new();
+        attributes.insert("merge".into(), git_attributes::State::Unset);
+        assert!(
+            apply::apply_three_way(ours, Some(base), file, Some(&attributes), opts).is_err(),
+            "files that are binary for merging are never merged"
+        );
+
+        attributes.insert("merge".into(), git_attributes::State::Set);
+        attributes.insert("text".into(), git_attributes::State::Unset);
+        let outcome = apply::apply_three_way(ours, Some(base), file, Some(&attributes), opts).unwrap();
+        assert_eq!(
+            outcome.data, b"1\n2\nthree\n4\nfive\n6\n7\n8\nnine\n",
+            "the merge attribute takes precedence over the text attribute"
+        );
+    }
+
+    #[test]
+    fn extended_headers() {
+        let input = b"From 1234 Mon Sep 17 00:00:00 2001
+Subject: [PATCH] test
+
+diff --git a/old b/new
+similarity index 90%
+rename from old
+rename to new
+index 1234567..89abcde 100755
+--- a/old
++++ b/new
+@@ -1 +1 @@
+-a
++b
+diff --git a/gone b/gone
+deleted file mode 100644
+index 1234567..0000000
+--- a/gone
++++ /dev/null
+@@ -1 +0,0 @@
+-x
+";
+        let files = patch::parse(input, Default::default()).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].operation, Operation::Rename);
+        assert_eq!(files[0].similarity, Some(90));
+        assert_eq!(files[0].old_path, Some("old".into()));
+        assert_eq!(files[0].new_path, Some("new".into()));
+        assert_eq!(files[0].new_mode, Some(EntryMode::BlobExecutable));
+        assert_eq!(files[1].operation, Operation::Delete);
+        assert_eq!(files[1].new_path, None);
+        assert_eq!(patch::apply(b"x\n", &files[1], Default::default()).unwrap(), b"");
+    }
+}
+
+mod apply_to_worktree {
+    use std::convert::Infallible;
+
+    use git_diff::patch::{self, apply, apply::worktree};
+    use git_object::{bstr::ByteSlice, TreeRefIter};
+    use git_testtools::hex_to_id;
+
+    const PATCH: &[u8] = b"diff --git a/modified b/modified
+index 1234567..89abcde 100644
+--- a/modified
++++ b/modified
+@@ -1,2 +1,2 @@
+ a
+-b
++B
+diff --git a/gone b/gone
+deleted file mode 100644
+index 1234567..0000000
+--- a/gone
++++ /dev/null
+@@ -1 +0,0 @@
+-x
+diff --git a/new b/dir/new
+similarity index 100%
+rename from new
+rename to dir/new
+diff --git a/added b/added
+new file mode 100755
+index 0000000..1234567
+--- /dev/null
++++ b/added
+@@ -0,0 +1 @@
++fresh
+";
+
+    fn worktree_with(files: &[(&str, &[u8])]) -> (git_testtools::tempfile::TempDir, git_index::State) {
+        let dir = git_testtools::tempfile::TempDir::new().unwrap();
+        let mut index = git_index::State::from_tree(&git_hash::ObjectId::empty_tree(git_hash::Kind::Sha1), |_, _| {
+            Some(TreeRefIter::from_bytes(&[]))
+        })
+        .unwrap();
+        for (path, data) in files {
+            std::fs::write(dir.path().join(path), data).unwrap();
+            index
+                .upsert_entry(
+                    path.as_bytes().as_bstr(),
+                    Default::default(),
+                    hex_to_id("0000000000000000000000000000000000000001"),
+                    git_index::entry::Flags::empty(),
+                    git_index::entry::Mode::FILE,
+                )
+                .unwrap();
+        }
+        (dir, index)
+    }
+
+    fn index_paths(index: &git_index::State) -> Vec<String> {
+        index.entries().iter().map(|e| e.path(index).to_string()).collect()
+    }
+
+    fn write_blob(_data: &[u8]) -> Result<git_hash::ObjectId, Infallible> {
+        Ok(hex_to_id("0000000000000000000000000000000000000002"))
+    }
+
+    #[test]
+    fn changes_worktree_and_index_and_can_be_reversed() {
+        let (dir, mut index) = worktree_with(&[("modified", b"a\nb\n"), ("gone", b"x\n"), ("new", b"renamed\n")]);
+        let files = patch::parse(PATCH, Default::default()).unwrap();
+
+        let outcome = worktree::apply(dir.path(), &files, Some(&mut index), write_blob, Default::default()).unwrap();
+        assert_eq!(
+            outcome,
+            worktree::Outcome {
+                files_written: 3,
+                files_deleted: 2
+            }
+        );
+        assert_eq!(std::fs::read(dir.path().join("modified")).unwrap(), b"a\nB\n");
+        assert!(!dir.path().join("gone").exists());
+        assert!(!dir.path().join("new").exists());
+        assert_eq!(std::fs::read(dir.path().join("dir/new")).unwrap(), b"renamed\n");
+        assert_eq!(std::fs::read(dir.path().join("added")).unwrap(), b"fresh\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join("added"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_ne!(mode & 0o111, 0, "the executable bit is set for new executables");
+        }
+        assert_eq!(index_paths(&index), vec!["added", "dir/new", "modified"]);
+        let added = &index.entries()[0];
+        assert_eq!(added.mode, git_index::entry::Mode::FILE_EXECUTABLE);
+        assert_eq!(added.id, hex_to_id("0000000000000000000000000000000000000002"));
+
+        let reverse = apply::Options {
+            reverse: true,
+            ..Default::default()
+        };
+        worktree::apply(dir.path(), &files, Some(&mut index), write_blob, reverse).unwrap();
+        assert_eq!(std::fs::read(dir.path().join("modified")).unwrap(), b"a\nb\n");
+        assert_eq!(std::fs::read(dir.path().join("gone")).unwrap(), b"x\n");
+        assert_eq!(std::fs::read(dir.path().join("new")).unwrap(), b"renamed\n");
+        assert!(!dir.path().join("dir").exists(), "empty directories are removed");
+        assert!(!dir.path().join("added").exists());
+        assert_eq!(index_paths(&index), vec!["gone", "modified", "new"]);
+    }
+
+    #[test]
+    fn nothing_changes_if_one_file_does_not_apply() {
+        let (dir, mut index) = worktree_with(&[("modified", b"a\nb\n"), ("gone", b"y\n"), ("new", b"renamed\n")]);
+        let files = patch::parse(PATCH, Default::default()).unwrap();
+
+        let err = worktree::apply(dir.path(), &files, Some(&mut index), write_blob, Default::default()).unwrap_err();
+        assert!(matches!(err, worktree::Error::Apply { ref path, .. } if path == "gone"));
+        assert_eq!(std::fs::read(dir.path().join("modified")).unwrap(), b"a\nb\n");
+        assert!(dir.path().join("new").exists());
+        assert!(!dir.path().join("added").exists());
+        assert_eq!(index_paths(&index), vec!["gone", "modified", "new"]);
+    }
+
+    #[test]
+    fn files_to_change_must_be_in_the_index() {
+        let (dir, mut index) = worktree_with(&[("modified", b"a\nb\n"), ("gone", b"x\n")]);
+        std::fs::write(dir.path().join("new"), b"renamed\n").unwrap();
+        let files = patch::parse(PATCH, Default::default()).unwrap();
+
+        let err = worktree::apply(dir.path(), &files, Some(&mut index), write_blob, Default::default()).unwrap_err();
+        assert!(matches!(err, worktree::Error::NotInIndex { ref path } if path == "new"));
+        assert!(dir.path().join("gone").exists());
+
+        worktree::apply(dir.path(), &files, None, write_blob, Default::default())
+            .expect("without an index, only the work tree matters");
+        assert!(dir.path().join("dir/new").exists());
+    }
+
+    #[test]
+    fn paths_leaving_the_worktree_are_rejected() {
+        let dir = git_testtools::tempfile::TempDir::new().unwrap();
+        let files = patch::parse(
+            b"diff --git a/../escape b/../escape
+new file mode 100644
+--- /dev/null
++++ b/../escape
+@@ -0,0 +1 @@
++x
+",
+            Default::default(),
+        )
+        .unwrap();
+        let err = worktree::apply(dir.path(), &files, None, write_blob, Default::default()).unwrap_err();
+        assert!(matches!(err, worktree::Error::IllformedPath { .. }));
+    }
+
+    #[test]
+    fn files_can_be_replaced_by_directories_of_the_same_name() {
+        let (dir, mut index) = worktree_with(&[("a", b"x\n")]);
+        let files = patch::parse(
+            b"diff --git a/a b/a
+deleted file mode 100644
+--- a/a
++++ /dev/null
+@@ -1 +0,0 @@
+-x
+diff --git a/a/b b/a/b
+new file mode 100644
+--- /dev/null
++++ b/a/b
+@@ -0,0 +1 @@
++y
+",
+            Default::default(),
+        )
+        .unwrap();
+        worktree::apply(dir.path(), &files, Some(&mut index), write_blob, Default::default()).unwrap();
+        assert_eq!(std::fs::read(dir.path().join("a/b")).unwrap(), b"y\n");
+        assert_eq!(index_paths(&index), vec!["a/b"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn paths_beyond_symbolic_links_are_rejected() {
+        let outside = git_testtools::tempfile::TempDir::new().unwrap();
+        let dir = git_testtools::tempfile::TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).unwrap();
+        let files = patch::parse(
+            b"diff --git a/link/escape b/link/escape
+new file mode 100644
+--- /dev/null
++++ b/link/escape
+@@ -0,0 +1 @@
++x
+",
+            Default::default(),
+        )
+        .unwrap();
+        let err = worktree::apply(dir.path(), &files, None, write_blob, Default::default()).unwrap_err();
+        assert!(matches!(err, worktree::Error::BeyondSymlink { ref path } if path == "link/escape"));
+        assert!(!outside.path().join("escape").exists());
+    }
+}
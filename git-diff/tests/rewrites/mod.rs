@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use git_diff::{
+    blob::Attributes,
+    rewrites::{detect_renames, Blob, Options, Rename},
+};
+use git_hash::ObjectId;
+use git_object::BlobRef;
+
+fn id(n: u8) -> ObjectId {
+    let mut bytes = [0; 20];
+    bytes[19] = n;
+    ObjectId::from(bytes)
+}
+
+fn renames(deletions: &[u8], additions: &[u8], blobs: &[(u8, &str)], options: Options) -> Vec<Rename> {
+    let deletions: Vec<_> = deletions.iter().map(|n| Blob::from(id(*n))).collect();
+    let additions: Vec<_> = additions.iter().map(|n| Blob::from(id(*n))).collect();
+    renames_of(&deletions, &additions, blobs, options)
+}
+
+fn renames_of(deletions: &[Blob<'_>], additions: &[Blob<'_>], blobs: &[(u8, &str)], options: Options) -> Vec<Rename> {
+    let blobs: HashMap<_, _> = blobs.iter().map(|(n, data)| (id(*n), data.as_bytes())).collect();
+    detect_renames(
+        deletions,
+        additions,
+        |oid, buf| {
+            let data = blobs.get(oid)?;
+            buf.clear();
+            buf.extend_from_slice(data);
+            Some(BlobRef { data: buf })
+        },
+        options,
+    )
+}
+
+fn rename(deletion: usize, addition: usize, similarity: f32) -> Rename {
+    Rename {
+        deletion,
+        addition,
+        similarity,
+    }
+}
+
+#[test]
+fn identical_blobs_are_paired_in_order_without_being_loaded() {
+    assert_eq!(
+        renames(&[1, 2, 1], &[1, 3, 1, 1], &[], Options::default()),
+        vec![rename(0, 0, 1.0), rename(2, 2, 1.0)],
+        "each deletion is used only once, and blobs that can't be found are never similar"
+    );
+}
+
+#[test]
+fn similar_blobs_are_paired_by_highest_similarity() {
+    let blobs = [
+        (1, "a\nb\nc\nd\n"),
+        (2, "1\n2\n3\n4\n"),
+        (3, "1\n2\n3\n5\n"),
+        (4, "a\nb\nc\nx\n"),
+        (5, "a\nb\nc\nd\ne\n"),
+    ];
+    assert_eq!(
+        renames(&[1, 2], &[3, 4, 5], &blobs, Options::default()),
+        vec![rename(1, 0, 0.75), rename(0, 2, 0.8)],
+        "the best match wins, leaving the others unpaired"
+    );
+}
+
+#[test]
+fn similarity_must_reach_the_percentage() {
+    let blobs = [(1, "a\nb\nc\nd\n"), (2, "a\nb\nc\nx\n")];
+    assert_eq!(
+        renames(&[1], &[2], &blobs, Options::default()),
+        vec![rename(0, 0, 0.75)]
+    );
+    let options = Options {
+        percentage: 0.8,
+        ..Default::default()
+    };
+    assert_eq!(renames(&[1], &[2], &blobs, options), vec![]);
+}
+
+#[test]
+fn the_limit_disables_detection_of_similar_blobs() {
+    let blobs = [(1, "a\nb\nc\nd\n"), (2, "a\nb\nc\nx\n"), (3, "same\n")];
+    let options = Options {
+        limit: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        renames(&[1, 3, 1], &[2, 3], &blobs, options),
+        vec![rename(1, 1, 1.0)],
+        "exact renames are still found"
+    );
+}
+
+#[test]
+fn binary_blobs_are_only_renamed_if_unchanged() {
+    let blobs = [(1, "a\0b\nc\n"), (2, "a\0b\nd\n")];
+    assert_eq!(renames(&[1], &[2], &blobs, Options::default()), vec![]);
+}
+
+#[test]
+fn attributes_decide_if_blobs_are_binary() {
+    let blobs = [(1, "a\nb\nc\nd\n"), (2, "a\nb\nc\nx\n")];
+    let binary: Attributes<'_> = std::iter::once(("text".into(), git_attributes::State::Unset)).collect();
+    let with_attributes = |n, attributes| Blob { id: id(n), attributes };
+    assert_eq!(
+        renames_of(
+            &[with_attributes(1, None)],
+            &[with_attributes(2, Some(&binary))],
+            &blobs,
+            Options::default()
+        ),
+        vec![],
+        "text blobs are binary if their attributes say so"
+    );
+
+    let text: Attributes<'_> = std::iter::once(("diff".into(), git_attributes::State::Set)).collect();
+    let blobs = [(1, "\0\nb\nc\nd\n"), (2, "\0\nb\nc\nx\n")];
+    assert_eq!(
+        renames_of(
+            &[with_attributes(1, Some(&text))],
+            &[with_attributes(2, Some(&text))],
+            &blobs,
+            Options::default()
+        ),
+        vec![rename(0, 0, 0.75)],
+        "binary blobs are text if their attributes say so"
+    );
+}
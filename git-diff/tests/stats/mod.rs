@@ -0,0 +1,78 @@
+use git_diff::{
+    blob::Algorithm,
+    stats::{self, DirstatMode, DirstatOptions, File, Summary},
+};
+use git_object::bstr::ByteSlice;
+
+fn file(path: &str, insertions: u32, deletions: u32) -> File {
+    File {
+        path: path.into(),
+        insertions,
+        deletions,
+        binary: None,
+    }
+}
+
+#[test]
+fn numstat_and_shortstat() {
+    let files = vec![
+        File::from_blobs("a", b"1\n2\n3\n", b"1\ntwo\n3\n4\n", None, Algorithm::Myers),
+        File::from_blobs("img.png", b"\0\x01", b"\0\x02\x03", None, Algorithm::Myers),
+    ];
+    assert_eq!((files[0].insertions, files[0].deletions), (2, 1));
+    assert_eq!(files[1].binary, Some((2, 3)));
+
+    let mut buf = Vec::new();
+    stats::write_numstat(&mut buf, &files).unwrap();
+    assert_eq!(buf.as_bstr(), "2\t1\ta\n-\t-\timg.png\n");
+
+    let summary: Summary = files.iter().collect();
+    assert_eq!(summary.to_string(), " 2 files changed, 2 insertions(+), 1 deletion(-)");
+    assert_eq!(Summary::default().to_string(), " 0 files changed");
+}
+
+#[test]
+fn attributes_decide_before_the_content() {
+    let attributes: git_diff::blob::Attributes<'_> =
+        std::iter::once(("diff".into(), git_attributes::State::Unset)).collect();
+    let file = File::from_blobs("a.txt", b"1\n", b"2\n", Some(&attributes), Algorithm::Myers);
+    assert_eq!(file.binary, Some((2, 2)), "text can be forced to be binary");
+    assert_eq!((file.insertions, file.deletions), (0, 0));
+}
+
+#[test]
+fn dirstat_attributes_changes_to_the_deepest_directory() {
+    let files = vec![
+        file("README", 10, 0),
+        file("src/lib.rs", 20, 10),
+        file("src/a/one.rs", 25, 0),
+        file("src/a/two.rs", 25, 0),
+        file("docs/x/y/z.md", 10, 0),
+    ];
+    let stats = stats::dirstat(&files, DirstatOptions::default());
+    let rendered: Vec<_> = stats.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        rendered,
+        vec!["  10.0% docs/x/y/", "  30.0% src/", "  50.0% src/a/"],
+        "single-source parents are not shown, and shown directories don't count towards their parents"
+    );
+
+    let stats = stats::dirstat(
+        &files,
+        DirstatOptions {
+            cumulative: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(stats.iter().find(|s| s.path == "src/").map(|s| s.permille), Some(800));
+
+    let stats = stats::dirstat(
+        &files,
+        DirstatOptions {
+            mode: DirstatMode::Files,
+            limit_permille: 0,
+            cumulative: false,
+        },
+    );
+    assert_eq!(stats.iter().find(|s| s.path == "src/a/").map(|s| s.permille), Some(400));
+}
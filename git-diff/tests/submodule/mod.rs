@@ -0,0 +1,187 @@
+use git_diff::{
+    patch,
+    submodule::{self, Change, Worktree},
+    tree::recorder,
+};
+use git_object::{bstr::ByteSlice, tree::EntryMode};
+use git_testtools::hex_to_id;
+
+fn write(change: &Change) -> String {
+    let mut buf = Vec::new();
+    change
+        .write_patch(&mut buf, "sub".into(), &patch::Options::default())
+        .unwrap();
+    buf.to_str().expect("valid UTF-8 in tests").to_owned()
+}
+
+#[test]
+fn only_gitlinks_yield_submodule_changes() {
+    let id = hex_to_id("0000000000000000000000000000000000000001");
+    let blob = recorder::Change::Addition {
+        entry_mode: EntryMode::Blob,
+        oid: id,
+        path: "f".into(),
+    };
+    assert_eq!(blob.submodule(), None);
+
+    let gitlink_to_blob = recorder::Change::Modification {
+        previous_entry_mode: EntryMode::Commit,
+        previous_oid: id,
+        entry_mode: EntryMode::Blob,
+        oid: hex_to_id("0000000000000000000000000000000000000002"),
+        path: "f".into(),
+    };
+    assert_eq!(
+        gitlink_to_blob.submodule(),
+        Some(Change {
+            previous_commit: Some(id),
+            commit: None,
+            worktree: None
+        }),
+        "only the side that is a gitlink is recorded as commit"
+    );
+}
+
+#[test]
+fn commit_change_as_patch() {
+    let change = recorder::Change::Modification {
+        previous_entry_mode: EntryMode::Commit,
+        previous_oid: hex_to_id("0000000000000000000000000000000000000001"),
+        entry_mode: EntryMode::Commit,
+        oid: hex_to_id("0000000000000000000000000000000000000002"),
+        path: "sub".into(),
+    }
+    .submodule()
+    .expect("gitlink");
+    assert!(!change.is_dirty());
+    assert!(!change.has_new_commits());
+    assert_eq!(
+        write(&change),
+        "diff --git a/sub b/sub
+index 0000000..0000000 160000
+--- a/sub
++++ b/sub
+@@ -1 +1 @@
+-Subproject commit 0000000000000000000000000000000000000001
++Subproject commit 0000000000000000000000000000000000000002
+"
+    );
+}
+
+#[test]
+fn dirty_worktree_with_new_commits() {
+    let recorded = hex_to_id("0000000000000000000000000000000000000001");
+    let checked_out = hex_to_id("0000000000000000000000000000000000000002");
+    let change = Change {
+        previous_commit: Some(recorded),
+        commit: Some(recorded),
+        worktree: None,
+    };
+    assert!(change.is_unchanged());
+    assert_eq!(write(&change), "", "nothing to show without a worktree");
+
+    let change = change.with_worktree(Worktree {
+        head: Some(recorded),
+        modified_content: false,
+        untracked_content: true,
+    });
+    assert!(change.is_dirty());
+    assert!(!change.has_new_commits());
+    assert_eq!(
+        write(&change),
+        "diff --git a/sub b/sub
+index 0000000..0000000 160000
+--- a/sub
++++ b/sub
+@@ -1 +1 @@
+-Subproject commit 0000000000000000000000000000000000000001
++Subproject commit 0000000000000000000000000000000000000001-dirty
+"
+    );
+
+    let change = change.with_worktree(Worktree {
+        head: Some(checked_out),
+        ..Default::default()
+    });
+    assert!(change.has_new_commits());
+    assert_eq!(change.current_commit(), Some(checked_out));
+    assert!(write(&change).ends_with("+Subproject commit 0000000000000000000000000000000000000002\n"));
+}
+
+#[test]
+fn added_submodule_via_write_file() {
+    let id = hex_to_id("0000000000000000000000000000000000000002");
+    let mut buf = Vec::new();
+    patch::write_file(
+        &mut buf,
+        &patch::FileChange {
+            before: None,
+            after: Some(patch::Side {
+                path: "sub".into(),
+                mode: EntryMode::Commit,
+                id: &id,
+                data: &[],
+                attributes: None,
+            }),
+        },
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        buf.as_bstr(),
+        "diff --git a/sub b/sub
+new file mode 160000
+index 0000000..0000000
+--- /dev/null
++++ b/sub
+@@ -0,0 +1 @@
++Subproject commit 0000000000000000000000000000000000000002
+"
+    );
+}
+
+#[test]
+fn worktree_head_through_git_file_and_packed_refs() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let worktree = tmp.path().join("sub");
+    assert_eq!(submodule::worktree_head(&worktree)?, None, "not checked out");
+
+    let git_dir = tmp.path().join("modules").join("sub");
+    std::fs::create_dir_all(&git_dir)?;
+    std::fs::create_dir_all(&worktree)?;
+    std::fs::write(worktree.join(".git"), "gitdir: ../modules/sub\n")?;
+    std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n")?;
+    assert_eq!(submodule::worktree_head(&worktree)?, None, "unborn");
+
+    std::fs::write(
+        git_dir.join("packed-refs"),
+        "# pack-refs with: peeled fully-peeled sorted \n0000000000000000000000000000000000000003 refs/heads/main\n",
+    )?;
+    assert_eq!(
+        submodule::worktree_head(&worktree)?,
+        Some(hex_to_id("0000000000000000000000000000000000000003"))
+    );
+
+    std::fs::create_dir_all(git_dir.join("refs").join("heads"))?;
+    std::fs::write(
+        git_dir.join("refs").join("heads").join("main"),
+        "0000000000000000000000000000000000000004\n",
+    )?;
+    assert_eq!(
+        submodule::worktree_head(&worktree)?,
+        Some(hex_to_id("0000000000000000000000000000000000000004")),
+        "loose refs take precedence"
+    );
+
+    std::fs::write(git_dir.join("HEAD"), "ref: refs/heads/a\n")?;
+    std::fs::write(git_dir.join("refs").join("heads").join("a"), "ref: refs/heads/b\n")?;
+    std::fs::write(git_dir.join("refs").join("heads").join("b"), "ref: refs/heads/a\n")?;
+    assert!(
+        matches!(
+            submodule::worktree_head(&worktree),
+            Err(submodule::Error::SymbolicRefCycle(_))
+        ),
+        "cycles of symbolic refs are detected"
+    );
+    Ok(())
+}
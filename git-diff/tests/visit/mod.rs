@@ -63,6 +63,14 @@ mod changes {
         }
 
         fn diff_with_previous_commit_from(db: &git_odb::Handle, commit_id: &oid) -> crate::Result<Changes> {
+            diff_with_previous_commit_from_with_options(db, commit_id, Default::default())
+        }
+
+        fn diff_with_previous_commit_from_with_options(
+            db: &git_odb::Handle,
+            commit_id: &oid,
+            opts: git_diff::tree::changes::Options<'_>,
+        ) -> crate::Result<Changes> {
             let mut buf = Vec::new();
             let (main_tree_id, parent_commit_id) = {
                 let commit = db
@@ -96,7 +104,7 @@ mod changes {
             };
 
             let mut recorder = git_diff::tree::Recorder::default();
-            git_diff::tree::Changes::from(previous_tree).needed_to_obtain(
+            git_diff::tree::Changes::from(previous_tree).needed_to_obtain_with_options(
                 current_tree,
                 &mut git_diff::tree::State::default(),
                 |oid, buf| {
@@ -106,6 +114,7 @@ mod changes {
                         .and_then(|(obj, _)| obj.try_into_tree_iter())
                 },
                 &mut recorder,
+                opts,
             )?;
             Ok(recorder.records)
         }
@@ -143,6 +152,140 @@ mod changes {
             .expect("valid iteration")
         }
 
+        mod options {
+            use git_diff::tree::{changes::Options, recorder::Change::*};
+            use git_object::{bstr::BString, tree::EntryMode};
+
+            use super::{all_commits, db, diff_with_previous_commit_from_with_options, Changes};
+            use crate::hex_to_id;
+
+            fn paths(changes: &Changes) -> Vec<BString> {
+                changes
+                    .iter()
+                    .map(|c| match c {
+                        Addition { path, .. } | Deletion { path, .. } | Modification { path, .. } => path.clone(),
+                    })
+                    .collect()
+            }
+
+            #[test]
+            fn pathspecs_limit_changes_and_keep_leading_trees() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                let pathspecs = vec![BString::from("f/a")];
+                assert_eq!(
+                    diff_with_previous_commit_from_with_options(
+                        &db,
+                        &all_commits[10],
+                        Options {
+                            pathspecs: &pathspecs,
+                            ..Default::default()
+                        }
+                    )?,
+                    vec![
+                        Deletion {
+                            entry_mode: EntryMode::Tree,
+                            oid: hex_to_id(if cfg!(windows) {
+                                "3b287f8730c81d0b763c2d294618a5e32b67b4f8"
+                            } else {
+                                "7e26dba59b6336f87d1d4ae3505a2da302b91c76"
+                            }),
+                            path: "f".into()
+                        },
+                        Deletion {
+                            entry_mode: EntryMode::Blob,
+                            oid: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                            path: "f/a".into()
+                        },
+                    ],
+                    "the tree leading to the match is shown, but not the file replacing it"
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn pathspecs_without_trees() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                let pathspecs = vec![BString::from("f/a")];
+                assert_eq!(
+                    diff_with_previous_commit_from_with_options(
+                        &db,
+                        &all_commits[10],
+                        Options {
+                            pathspecs: &pathspecs,
+                            emit_trees: false
+                        }
+                    )?,
+                    vec![Deletion {
+                        entry_mode: EntryMode::Blob,
+                        oid: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                        path: "f/a".into()
+                    }]
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn glob_pathspecs() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                let pathspecs = vec![BString::from("f/[ab]")];
+                let changes = diff_with_previous_commit_from_with_options(
+                    &db,
+                    &all_commits[10],
+                    Options {
+                        pathspecs: &pathspecs,
+                        emit_trees: false,
+                    },
+                )?;
+                assert_eq!(
+                    paths(&changes),
+                    vec![BString::from("f/a"), "f/b".into()]
+                );
+
+                let pathspecs = vec![BString::from("c"), "e".into()];
+                let changes = diff_with_previous_commit_from_with_options(
+                    &db,
+                    &all_commits[15],
+                    Options {
+                        pathspecs: &pathspecs,
+                        emit_trees: false,
+                    },
+                )?;
+                assert_eq!(
+                    paths(&changes),
+                    vec![BString::from("c"), "e".into()],
+                    "literal pathspecs match exactly"
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn without_trees() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                assert_eq!(
+                    diff_with_previous_commit_from_with_options(
+                        &db,
+                        &all_commits[5],
+                        Options {
+                            emit_trees: false,
+                            ..Default::default()
+                        }
+                    )?,
+                    vec![Modification {
+                        previous_entry_mode: EntryMode::Blob,
+                        previous_oid: hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"),
+                        entry_mode: EntryMode::Blob,
+                        oid: hex_to_id("13c2aca72ab576cb5f22dc8e7f8ba8ddab553a8a"),
+                        path: "f/f".into()
+                    }]
+                );
+                Ok(())
+            }
+        }
+
         #[test]
         fn many_different_states() -> crate::Result {
             let db = db(None)?;
@@ -6,12 +6,48 @@
 //! For information on how to use the [`WalkDir`] type, have a look at
 //! * [`jwalk::WalkDir`](https://docs.rs/jwalk/0.5.1/jwalk/type.WalkDir.html) if `parallel` feature is enabled
 //! * [walkdir::WalkDir](https://docs.rs/walkdir/2.3.1/walkdir/struct.WalkDir.html) otherwise
+//!
+//! Use [`walkdir_with_options()`] to configure sorting, depth and file system boundaries in a way that works with both.
+
+/// Options to configure a directory walk created with [`walkdir_with_options()`].
+#[cfg(any(feature = "walkdir", feature = "jwalk"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// If true, entries of each directory are yielded sorted by file name, making the traversal order stable.
+    pub sorted: bool,
+    /// Entries with a depth lower than this are not yielded. The root has depth 0, its direct children depth 1.
+    pub min_depth: usize,
+    /// If set, directories at this depth won't be descended into, and no entries deeper than this are yielded.
+    pub max_depth: Option<usize>,
+    /// If true, symbolic links to directories are followed.
+    pub follow_links: bool,
+    /// If true, directories on a different file system than the root will be yielded, but not descended into.
+    ///
+    /// Note that with the `parallel` feature enabled, this is only effective on unix.
+    pub same_file_system: bool,
+}
+
+#[cfg(any(feature = "walkdir", feature = "jwalk"))]
+impl Default for WalkOptions {
+    fn default() -> Self {
+        WalkOptions {
+            sorted: false,
+            min_depth: 0,
+            max_depth: None,
+            follow_links: false,
+            same_file_system: false,
+        }
+    }
+}
+
 #[cfg(feature = "parallel")]
 ///
 pub mod walkdir {
     use std::path::Path;
 
-    pub use jwalk::{DirEntry as DirEntryGeneric, DirEntryIter as DirEntryIterGeneric, Error, WalkDir};
+    pub use jwalk::{
+        ClientState, DirEntry as DirEntryGeneric, DirEntryIter as DirEntryIterGeneric, Error, WalkDir, WalkDirGeneric,
+    };
 
     /// An alias for an uncustomized directory entry to match the one of the non-parallel version offered by `walkdir`.
     pub type DirEntry = DirEntryGeneric<((), ())>;
@@ -26,8 +62,84 @@ pub mod walkdir {
         WalkDir::new(root).sort(true)
     }
 
+    /// Instantiate a new directory iterator which will not skip hidden files and is configured with `options`.
+    ///
+    /// Directories are read in parallel.
+    pub fn walkdir_with_options(root: impl AsRef<Path>, options: super::WalkOptions) -> WalkDir {
+        configure(root.as_ref(), options, None)
+    }
+
+    /// Like [`walkdir_with_options()`], but also obtain the metadata of each entry that isn't a directory while its
+    /// directory is read, which happens in parallel. Use [`metadata()`] to access it.
+    pub fn walkdir_with_metadata(root: impl AsRef<Path>, options: super::WalkOptions) -> WalkDirGeneric<MetadataState> {
+        configure(
+            root.as_ref(),
+            options,
+            Some(|entry: &mut DirEntryWithMetadata| {
+                if !entry.file_type().is_dir() {
+                    entry.client_state = Some(entry.metadata());
+                }
+            }),
+        )
+    }
+
+    /// Return the metadata of `entry`, as obtained during the walk, or by querying it now if it's a directory.
+    pub fn metadata(entry: &mut DirEntryWithMetadata) -> Result<std::fs::Metadata, Error> {
+        entry.client_state.take().unwrap_or_else(|| entry.metadata())
+    }
+
+    fn configure<C: ClientState>(
+        root: &Path,
+        options: super::WalkOptions,
+        process_entry: Option<fn(&mut DirEntryGeneric<C>)>,
+    ) -> WalkDirGeneric<C> {
+        let walk = WalkDirGeneric::<C>::new(root)
+            .skip_hidden(false)
+            .sort(options.sorted)
+            .min_depth(options.min_depth)
+            .max_depth(options.max_depth.unwrap_or(usize::MAX))
+            .follow_links(options.follow_links);
+        #[cfg(unix)]
+        let root_device = {
+            use std::os::unix::fs::MetadataExt;
+            options
+                .same_file_system
+                .then(|| std::fs::metadata(root).map(|md| md.dev()).ok())
+                .flatten()
+        };
+        #[cfg(not(unix))]
+        let root_device = None::<u64>;
+        if process_entry.is_none() && root_device.is_none() {
+            return walk;
+        }
+        walk.process_read_dir(move |_depth, _path, _read_dir_state, siblings| {
+            for entry in siblings.iter_mut().flatten() {
+                if let Some(process_entry) = process_entry {
+                    process_entry(entry);
+                }
+                #[cfg(unix)]
+                if let Some(root_device) = root_device {
+                    use std::os::unix::fs::MetadataExt;
+                    // Directories we can't stat are still read to surface the error.
+                    if entry.read_children_path.is_some()
+                        && std::fs::metadata(entry.path()).map_or(false, |md| md.dev() != root_device)
+                    {
+                        entry.read_children_path = None;
+                    }
+                }
+            }
+        })
+    }
+
     /// The Iterator yielding directory items
     pub type DirEntryIter = DirEntryIterGeneric<((), ())>;
+
+    /// The state kept with each entry of a walk created with [`walkdir_with_metadata()`], which holds its metadata.
+    pub type MetadataState = ((), Option<Result<std::fs::Metadata, Error>>);
+    /// A directory entry of a walk created with [`walkdir_with_metadata()`].
+    pub type DirEntryWithMetadata = DirEntryGeneric<MetadataState>;
+    /// The Iterator yielding directory items along with their metadata.
+    pub type DirEntryWithMetadataIter = DirEntryIterGeneric<MetadataState>;
 }
 
 #[cfg(all(feature = "walkdir", not(feature = "parallel")))]
@@ -47,12 +159,40 @@ pub mod walkdir {
         WalkDir::new(root).sort_by_file_name()
     }
 
+    /// Instantiate a new directory iterator which will not skip hidden files and is configured with `options`.
+    pub fn walkdir_with_options(root: impl AsRef<Path>, options: super::WalkOptions) -> WalkDir {
+        let walk = WalkDir::new(root)
+            .min_depth(options.min_depth)
+            .max_depth(options.max_depth.unwrap_or(usize::MAX))
+            .follow_links(options.follow_links)
+            .same_file_system(options.same_file_system);
+        if options.sorted {
+            walk.sort_by_file_name()
+        } else {
+            walk
+        }
+    }
+
+    /// Like [`walkdir_with_options()`], which makes the metadata of each entry available with [`metadata()`].
+    pub fn walkdir_with_metadata(root: impl AsRef<Path>, options: super::WalkOptions) -> WalkDir {
+        walkdir_with_options(root, options)
+    }
+
+    /// Return the metadata of `entry`.
+    pub fn metadata(entry: &mut DirEntryWithMetadata) -> Result<std::fs::Metadata, Error> {
+        entry.metadata()
+    }
+
     /// The Iterator yielding directory items
     pub type DirEntryIter = walkdir::IntoIter;
+    /// A directory entry of a walk created with [`walkdir_with_metadata()`].
+    pub type DirEntryWithMetadata = DirEntry;
+    /// The Iterator yielding directory items along with their metadata.
+    pub type DirEntryWithMetadataIter = walkdir::IntoIter;
 }
 
 #[cfg(any(feature = "walkdir", feature = "jwalk"))]
-pub use self::walkdir::{walkdir_new, walkdir_sorted_new, WalkDir};
+pub use self::walkdir::{walkdir_new, walkdir_sorted_new, walkdir_with_metadata, walkdir_with_options, WalkDir};
 
 /// Prepare open options which won't follow symlinks when the file is opened.
 ///
@@ -3,15 +3,21 @@
 //! With the `fast-sha1` feature, the `Sha1` hash type will use a more elaborate implementation utilizing hardware support
 //! in case it is available. Otherwise the `rustsha1` feature should be set. `fast-sha1` will take precedence.
 //! Otherwise, a minimal yet performant implementation is used instead for a decent trade-off between compile times and run-time performance.
+//!
+//! With the `sha1-collision-detection` feature, the hardened implementation used by git itself becomes available as well and
+//! is used by default. It detects attempts to produce collisions, like the ones of the [SHAttered] attack, which matters when
+//! hashing objects of untrusted sources. Use [`set_sha1_backend()`] to select the implementation at runtime.
+//!
+//! [SHAttered]: https://shattered.io
 #[cfg(all(feature = "rustsha1", not(feature = "fast-sha1")))]
 mod _impl {
     use super::Sha1Digest;
 
     /// A implementation of the Sha1 hash, which can be used once.
     #[derive(Default, Clone)]
-    pub struct Sha1(sha1_smol::Sha1);
+    pub struct Fast(sha1_smol::Sha1);
 
-    impl Sha1 {
+    impl Fast {
         /// Digest the given `bytes`.
         pub fn update(&mut self, bytes: &[u8]) {
             self.0.update(bytes)
@@ -35,9 +41,9 @@ mod _impl {
 
     /// A implementation of the Sha1 hash, which can be used once.
     #[derive(Default, Clone)]
-    pub struct Sha1(sha1::Sha1);
+    pub struct Fast(sha1::Sha1);
 
-    impl Sha1 {
+    impl Fast {
         /// Digest the given `bytes`.
         pub fn update(&mut self, bytes: &[u8]) {
             self.0.update(bytes)
@@ -49,8 +55,179 @@ mod _impl {
     }
 }
 
+#[cfg(feature = "sha1-collision-detection")]
+mod detect {
+    use super::Sha1Digest;
+
+    /// A hardened implementation of the Sha1 hash which detects collision attacks, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct CollisionDetection(Box<sha1collisiondetection::Sha1CD>);
+
+    impl CollisionDetection {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest along with the outcome of the collision detection. If a collision attack
+        /// was detected, the digest is the 'safe hash' which differs from the one an implementation without collision
+        /// detection would produce.
+        pub fn digest(mut self) -> (Sha1Digest, Result<(), super::Collision>) {
+            let mut digest = sha1collisiondetection::Output::default();
+            let res = self.0.finalize_into_dirty_cd(&mut digest).map_err(|_| super::Collision);
+            (digest.into(), res)
+        }
+    }
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+mod backend {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    /// The implementation used by [`Sha1`][super::Sha1] instances.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Sha1Backend {
+        /// The implementation selected at compile time with the `fast-sha1` or `rustsha1` feature, which does not detect
+        /// collision attacks.
+        Fast,
+        /// The hardened implementation of git itself which detects collision attacks at the cost of performance.
+        #[cfg(feature = "sha1-collision-detection")]
+        CollisionDetection,
+    }
+
+    impl Default for Sha1Backend {
+        #[cfg(feature = "sha1-collision-detection")]
+        fn default() -> Self {
+            Sha1Backend::CollisionDetection
+        }
+        #[cfg(not(feature = "sha1-collision-detection"))]
+        fn default() -> Self {
+            Sha1Backend::Fast
+        }
+    }
+
+    const UNSET: u8 = 0;
+    const FAST: u8 = 1;
+    #[cfg(feature = "sha1-collision-detection")]
+    const COLLISION_DETECTION: u8 = 2;
+
+    static BACKEND: AtomicU8 = AtomicU8::new(UNSET);
+
+    /// Use `backend` for all [`Sha1`][super::Sha1] instances created with [`Sha1::default()`][super::Sha1::default()] from
+    /// now on, affecting all hashing in this process.
+    pub fn set_sha1_backend(backend: Sha1Backend) {
+        let value = match backend {
+            Sha1Backend::Fast => FAST,
+            #[cfg(feature = "sha1-collision-detection")]
+            Sha1Backend::CollisionDetection => COLLISION_DETECTION,
+        };
+        BACKEND.store(value, Ordering::Relaxed);
+    }
+
+    /// Return the backend used by [`Sha1::default()`][super::Sha1::default()], which is the
+    /// [default backend][Sha1Backend::default()] unless [`set_sha1_backend()`] was called.
+    pub fn sha1_backend() -> Sha1Backend {
+        match BACKEND.load(Ordering::Relaxed) {
+            FAST => Sha1Backend::Fast,
+            #[cfg(feature = "sha1-collision-detection")]
+            COLLISION_DETECTION => Sha1Backend::CollisionDetection,
+            _ => Sha1Backend::default(),
+        }
+    }
+}
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+pub use backend::{set_sha1_backend, sha1_backend, Sha1Backend};
+
+/// The error returned by [`Sha1::try_digest()`] if the hashed data was crafted to produce a collision.
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Collision;
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl std::fmt::Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("A SHA1 collision attack was detected in the hashed data")
+    }
+}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl std::error::Error for Collision {}
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Clone)]
+enum Inner {
+    Fast(_impl::Fast),
+    #[cfg(feature = "sha1-collision-detection")]
+    CollisionDetection(detect::CollisionDetection),
+}
+
+/// A implementation of the Sha1 hash, which can be used once.
+///
+/// The [`Default`] instance uses the backend returned by [`sha1_backend()`].
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+#[derive(Clone)]
+pub struct Sha1(Inner);
+
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
+impl Default for Sha1 {
+    fn default() -> Self {
+        Sha1::new(sha1_backend())
+    }
+}
+
 #[cfg(any(feature = "rustsha1", feature = "fast-sha1"))]
-pub use _impl::Sha1;
+impl Sha1 {
+    /// Create a new instance which hashes using the given `backend`.
+    pub fn new(backend: Sha1Backend) -> Self {
+        Sha1(match backend {
+            Sha1Backend::Fast => Inner::Fast(Default::default()),
+            #[cfg(feature = "sha1-collision-detection")]
+            Sha1Backend::CollisionDetection => Inner::CollisionDetection(Default::default()),
+        })
+    }
+
+    /// Return the backend this instance hashes with.
+    pub fn backend(&self) -> Sha1Backend {
+        match self.0 {
+            Inner::Fast(_) => Sha1Backend::Fast,
+            #[cfg(feature = "sha1-collision-detection")]
+            Inner::CollisionDetection(_) => Sha1Backend::CollisionDetection,
+        }
+    }
+
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        match &mut self.0 {
+            Inner::Fast(h) => h.update(bytes),
+            #[cfg(feature = "sha1-collision-detection")]
+            Inner::CollisionDetection(h) => h.update(bytes),
+        }
+    }
+
+    /// Finalize the hash and produce a digest.
+    ///
+    /// If collision detection is used and a collision attack was detected, the digest will differ from the one of other
+    /// implementations, making it impossible to pass off the crafted data as the object it collides with.
+    /// Use [`try_digest()`][Sha1::try_digest()] to learn about collisions.
+    pub fn digest(self) -> Sha1Digest {
+        match self.0 {
+            Inner::Fast(h) => h.digest(),
+            #[cfg(feature = "sha1-collision-detection")]
+            Inner::CollisionDetection(h) => h.digest().0,
+        }
+    }
+
+    /// Finalize the hash and produce a digest, or fail if collision detection is used and a collision attack was detected.
+    pub fn try_digest(self) -> Result<Sha1Digest, Collision> {
+        match self.0 {
+            Inner::Fast(h) => Ok(h.digest()),
+            #[cfg(feature = "sha1-collision-detection")]
+            Inner::CollisionDetection(h) => {
+                let (digest, res) = h.digest();
+                res.map(|()| digest)
+            }
+        }
+    }
+}
 
 /// Compute a CRC32 hash from the given `bytes`, returning the CRC32 hash.
 ///
@@ -4,6 +4,9 @@ use std::{
     sync::atomic::{AtomicBool, Ordering},
 };
 
+#[cfg(feature = "interrupt-handler")]
+pub mod handler;
+
 /// A wrapper for an inner iterator which will check for interruptions on each iteration, stopping the iteration when
 /// that is requested.
 pub struct Iter<'a, I> {
@@ -0,0 +1,201 @@
+//! Process-global signal handlers which request an interrupt on the first signal and terminate the process on the second one.
+//!
+//! Unlike handlers registered with `signal-hook` directly, these can be deregistered again, which restores the handling
+//! of signals as it was before, to allow using them in applications which don't control the whole process.
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use once_cell::sync::Lazy;
+
+/// The amount of [`Deregister`] handles that are still alive, each of which was returned by [`init()`].
+static DEREGISTER_COUNT: AtomicUsize = AtomicUsize::new(0);
+/// The signals and their hooks as registered by [`init()`].
+static REGISTERED_HOOKS: Lazy<Mutex<Vec<(i32, signal_hook::SigId)>>> = Lazy::new(Default::default);
+/// Hooks emulating the default behaviour of signals after our own hooks were deregistered.
+static DEFAULT_BEHAVIOUR_HOOKS: Lazy<Mutex<Vec<signal_hook::SigId>>> = Lazy::new(Default::default);
+/// The signals whose default action was in place before we registered hooks for the first time.
+///
+/// Other handlers don't need to be restored as the hooks we register call them as well, even after deregistration,
+/// and ignored signals stay ignored once our hooks are removed.
+static SIGNALS_WITH_DEFAULT_ACTION: Lazy<Vec<i32>> =
+    Lazy::new(|| signals().filter(|sig| has_default_action(*sig)).collect());
+
+/// The signals we handle, which are those which would terminate the process by default.
+fn signals() -> impl Iterator<Item = i32> {
+    let hangup: &[i32] = {
+        #[cfg(unix)]
+        {
+            &[signal_hook::consts::SIGHUP]
+        }
+        #[cfg(not(unix))]
+        {
+            &[]
+        }
+    };
+    signal_hook::consts::TERM_SIGNALS.iter().chain(hangup).copied()
+}
+
+/// Return true if `sig` is currently handled by its default action.
+#[cfg(unix)]
+fn has_default_action(sig: i32) -> bool {
+    let mut action = std::mem::MaybeUninit::<libc::sigaction>::uninit();
+    // # SAFETY
+    // * we only query the current action, which is written into memory we own
+    #[allow(unsafe_code)]
+    unsafe {
+        libc::sigaction(sig, std::ptr::null(), action.as_mut_ptr()) != 0
+            || action.assume_init().sa_sigaction == libc::SIG_DFL
+    }
+}
+
+/// Return true as we can't query the current action of `sig` on this platform.
+#[cfg(not(unix))]
+fn has_default_action(_sig: i32) -> bool {
+    true
+}
+
+/// A type to help deregistering hooks registered with [`init()`].
+#[derive(Debug)]
+pub struct Deregister {
+    do_reset: bool,
+    should_interrupt: &'static AtomicBool,
+    before_abort: fn(),
+}
+
+/// A guard which deregisters the hooks registered with [`init()`] when dropped.
+#[derive(Debug)]
+pub struct AutoDeregister(Option<Deregister>);
+
+impl Deregister {
+    /// Remove all previously registered handlers, and restore the handling of signals as it was before the first call
+    /// to [`init()`], if this is the last `Deregister` instance.
+    ///
+    /// Note that only the reinstatement of the default behaviour can fail.
+    pub fn deregister(self) -> io::Result<()> {
+        let mut hooks = REGISTERED_HOOKS.lock().expect("not poisoned");
+        let count = DEREGISTER_COUNT.fetch_sub(1, Ordering::SeqCst);
+        if count > 1 || hooks.is_empty() {
+            return Ok(());
+        }
+        if self.do_reset {
+            self.should_interrupt.store(false, Ordering::SeqCst);
+        }
+        for (_, hook_id) in hooks.iter() {
+            signal_hook::low_level::unregister(*hook_id);
+        }
+
+        let mut default_hooks = DEFAULT_BEHAVIOUR_HOOKS.lock().expect("not poisoned");
+        let before_abort = self.before_abort;
+        for (sig, _) in hooks.drain(..) {
+            if !SIGNALS_WITH_DEFAULT_ACTION.contains(&sig) {
+                continue;
+            }
+            // # SAFETY
+            // * we only call functions designed to be called from within a signal handler
+            #[allow(unsafe_code)]
+            unsafe {
+                default_hooks.push(signal_hook::low_level::register(sig, move || {
+                    before_abort();
+                    signal_hook::low_level::emulate_default_handler(sig).ok();
+                })?);
+            }
+        }
+        Ok(())
+    }
+
+    /// If called with `toggle` being `true`, when actually deregistering, we will also reset the interrupt flag
+    /// passed to [`init()`].
+    pub fn with_reset(mut self, toggle: bool) -> Self {
+        self.do_reset = toggle;
+        self
+    }
+
+    /// Return a type that deregisters all installed signal handlers on drop.
+    pub fn auto_deregister(self) -> AutoDeregister {
+        AutoDeregister(Some(self))
+    }
+}
+
+impl Drop for AutoDeregister {
+    fn drop(&mut self) {
+        if let Some(deregister) = self.0.take() {
+            deregister.deregister().ok();
+        }
+    }
+}
+
+/// Return true if the hooks registered with [`init()`] are currently in place, i.e. not all users deregistered yet.
+pub fn is_registered() -> bool {
+    !REGISTERED_HOOKS.lock().expect("not poisoned").is_empty()
+}
+
+/// Register hooks for SIGINT, SIGTERM, SIGQUIT and, on unix, SIGHUP which set `should_interrupt` and call `interrupt()`
+/// on the first signal. On the second signal, `before_abort()` is called right before the process is terminated.
+/// Both `interrupt()` and `before_abort()` must never use a Mutex, allocate or deallocate as they are called from
+/// within a signal handler.
+///
+/// The hooks stay registered for the lifetime of the process unless the returned [`Deregister`] is used to remove
+/// them, directly or by turning it into an [`AutoDeregister`] guard. As hooks are registered only once, calling
+/// this function again merely counts the additional user, and only the arguments of the first call are used.
+/// The hooks are removed once all returned instances were used to deregister, which restores the handling of the
+/// signals as it was before the first call, be it their default behaviour, them being ignored or previously installed handlers.
+pub fn init(
+    should_interrupt: &'static AtomicBool,
+    interrupt: impl Fn() + Send + Sync + Clone + 'static,
+    before_abort: fn(),
+) -> io::Result<Deregister> {
+    let deregister = Deregister {
+        do_reset: false,
+        should_interrupt,
+        before_abort,
+    };
+    let mut hooks = REGISTERED_HOOKS.lock().expect("not poisoned");
+    let prev_count = DEREGISTER_COUNT.fetch_add(1, Ordering::SeqCst);
+    if prev_count != 0 {
+        return Ok(deregister);
+    }
+
+    Lazy::force(&SIGNALS_WITH_DEFAULT_ACTION);
+    for hook_id in DEFAULT_BEHAVIOUR_HOOKS.lock().expect("not poisoned").drain(..) {
+        signal_hook::low_level::unregister(hook_id);
+    }
+
+    for sig in signals() {
+        // # SAFETY
+        // * we only set atomics or call functions that do
+        // * there is no use of the heap
+        let interrupt = interrupt.clone();
+        #[allow(unsafe_code)]
+        let hook_id = unsafe {
+            signal_hook::low_level::register(sig, move || {
+                static INTERRUPT_COUNT: AtomicUsize = AtomicUsize::new(0);
+                if !should_interrupt.load(Ordering::SeqCst) {
+                    INTERRUPT_COUNT.store(0, Ordering::SeqCst);
+                }
+                let msg_idx = INTERRUPT_COUNT.fetch_add(1, Ordering::SeqCst);
+                if msg_idx == 1 {
+                    before_abort();
+                    signal_hook::low_level::emulate_default_handler(sig).ok();
+                }
+                interrupt();
+                should_interrupt.store(true, Ordering::SeqCst);
+            })
+        };
+        match hook_id {
+            Ok(hook_id) => hooks.push((sig, hook_id)),
+            Err(err) => {
+                for (_, hook_id) in hooks.drain(..) {
+                    signal_hook::low_level::unregister(hook_id);
+                }
+                DEREGISTER_COUNT.fetch_sub(1, Ordering::SeqCst);
+                return Err(err);
+            }
+        }
+    }
+    Ok(deregister)
+}
@@ -14,6 +14,7 @@ pub mod pipe {
         /// The channel through which bytes are transferred. Useful for sending [`std::io::Error`]s instead.
         pub channel: std::sync::mpsc::SyncSender<io::Result<BytesMut>>,
         buf: BytesMut,
+        max_chunk_size: Option<usize>,
     }
 
     /// The read-end of the pipe, implementing the [`std::io::Read`] trait.
@@ -62,6 +63,10 @@ pub mod pipe {
 
     impl io::Write for Writer {
         fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let buf = match self.max_chunk_size {
+                Some(max) => &buf[..buf.len().min(max)],
+                None => buf,
+            };
             self.buf.put_slice(buf);
             self.channel
                 .send(Ok(self.buf.split()))
@@ -84,6 +89,29 @@ pub mod pipe {
             Writer {
                 channel: tx,
                 buf: BytesMut::with_capacity(4096),
+                max_chunk_size: None,
+            },
+            Reader {
+                channel: rx,
+                buf: BytesMut::new(),
+            },
+        )
+    }
+
+    /// Returns the _([`write`][Writer], [`read`][Reader])_ ends of a pipe which holds at most about `capacity` bytes in memory.
+    ///
+    /// Writes are split into chunks of at most `capacity` bytes, each of which is handed to the `read` end directly,
+    /// so the `write` end blocks until the `read` end has consumed all previously transferred bytes. This bounds the
+    /// memory used by the pipe no matter how fast the producer is compared to the consumer.
+    /// A `capacity` of `0` is treated as `1`.
+    pub fn bounded(capacity: usize) -> (Writer, Reader) {
+        let capacity = capacity.max(1);
+        let (tx, rx) = std::sync::mpsc::sync_channel(0);
+        (
+            Writer {
+                channel: tx,
+                buf: BytesMut::with_capacity(capacity.min(4096)),
+                max_chunk_size: Some(capacity),
             },
             Reader {
                 channel: rx,
@@ -91,4 +119,196 @@ pub mod pipe {
             },
         )
     }
+
+    /// An async version of the pipe, implementing the traits of the `futures-io` crate. Available with the `io-pipe-async` feature toggle.
+    #[cfg(feature = "io-pipe-async")]
+    pub mod async_io {
+        use std::{
+            io,
+            pin::Pin,
+            sync::{Arc, Mutex},
+            task::{Context, Poll, Waker},
+        };
+
+        use bytes::{Buf, BufMut, BytesMut};
+
+        struct State {
+            buf: BytesMut,
+            /// The amount of bytes taken from `buf` by the [`Reader`] which it didn't consume yet.
+            held_by_reader: usize,
+            capacity: usize,
+            error: Option<io::Error>,
+            writer_closed: bool,
+            reader_closed: bool,
+            read_waker: Option<Waker>,
+            write_waker: Option<Waker>,
+        }
+
+        impl State {
+            fn wake_reader(&mut self) {
+                if let Some(waker) = self.read_waker.take() {
+                    waker.wake();
+                }
+            }
+            fn wake_writer(&mut self) {
+                if let Some(waker) = self.write_waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+
+        type Shared = Arc<Mutex<State>>;
+
+        fn lock(state: &Shared) -> std::sync::MutexGuard<'_, State> {
+            state.lock().unwrap_or_else(|err| err.into_inner())
+        }
+
+        /// The write-end of the pipe, commonly used with the [`futures_io::AsyncWrite`] trait it implements.
+        ///
+        /// Writing is pending while the pipe holds as many bytes as its capacity allows, including those the [`Reader`] obtained
+        /// without consuming them yet, until the [`Reader`] consumes them.
+        pub struct Writer {
+            state: Shared,
+        }
+
+        /// The read-end of the pipe, implementing the [`futures_io::AsyncRead`] and [`futures_io::AsyncBufRead`] traits.
+        pub struct Reader {
+            state: Shared,
+            buf: BytesMut,
+        }
+
+        impl Writer {
+            /// Make `err` available to the [`Reader`] once it consumed all bytes written so far, instead of more bytes.
+            pub fn send_error(&self, err: io::Error) {
+                let mut state = lock(&self.state);
+                state.error = Some(err);
+                state.wake_reader();
+            }
+        }
+
+        impl Drop for Writer {
+            fn drop(&mut self) {
+                let mut state = lock(&self.state);
+                state.writer_closed = true;
+                state.wake_reader();
+            }
+        }
+
+        impl Drop for Reader {
+            fn drop(&mut self) {
+                let mut state = lock(&self.state);
+                state.reader_closed = true;
+                state.wake_writer();
+            }
+        }
+
+        impl futures_io::AsyncWrite for Writer {
+            fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+                let mut state = lock(&self.state);
+                if state.reader_closed {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "the read end of the pipe was dropped",
+                    )));
+                }
+                if buf.is_empty() {
+                    return Poll::Ready(Ok(0));
+                }
+                let available = state.capacity - state.buf.len() - state.held_by_reader;
+                if available == 0 {
+                    state.write_waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+                let amount = available.min(buf.len());
+                state.buf.put_slice(&buf[..amount]);
+                state.wake_reader();
+                Poll::Ready(Ok(amount))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                let mut state = lock(&self.state);
+                state.writer_closed = true;
+                state.wake_reader();
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        impl futures_io::AsyncBufRead for Reader {
+            fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+                let this = self.get_mut();
+                if this.buf.is_empty() {
+                    let mut state = lock(&this.state);
+                    if !state.buf.is_empty() {
+                        this.buf = state.buf.split();
+                        state.held_by_reader = this.buf.len();
+                    } else if let Some(err) = state.error.take() {
+                        return Poll::Ready(Err(err));
+                    } else if !state.writer_closed {
+                        state.read_waker = Some(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                }
+                Poll::Ready(Ok(&this.buf))
+            }
+
+            fn consume(self: Pin<&mut Self>, amt: usize) {
+                let this = self.get_mut();
+                let amt = amt.min(this.buf.len());
+                if amt == 0 {
+                    return;
+                }
+                this.buf.advance(amt);
+                let mut state = lock(&this.state);
+                state.held_by_reader = this.buf.len();
+                state.wake_writer();
+            }
+        }
+
+        impl futures_io::AsyncRead for Reader {
+            fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, out: &mut [u8]) -> Poll<io::Result<usize>> {
+                use futures_io::AsyncBufRead;
+                let amount = match self.as_mut().poll_fill_buf(cx) {
+                    Poll::Ready(Ok(buf)) => {
+                        let amount = buf.len().min(out.len());
+                        out[..amount].copy_from_slice(&buf[..amount]);
+                        amount
+                    }
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                };
+                self.consume(amount);
+                Poll::Ready(Ok(amount))
+            }
+        }
+
+        /// Returns the _([`write`][Writer], [`read`][Reader])_ ends of an async pipe which holds at most `capacity` bytes in memory.
+        ///
+        /// Once `capacity` bytes are buffered, writes are pending until the `read` end consumes them, applying backpressure to the producer.
+        /// Bytes which the `read` end obtained but didn't consume yet count towards the `capacity` as well.
+        /// A `capacity` of `0` is treated as `1`.
+        pub fn bounded(capacity: usize) -> (Writer, Reader) {
+            let capacity = capacity.max(1);
+            let state = Arc::new(Mutex::new(State {
+                buf: BytesMut::with_capacity(capacity.min(4096)),
+                held_by_reader: 0,
+                capacity,
+                error: None,
+                writer_closed: false,
+                reader_closed: false,
+                read_waker: None,
+                write_waker: None,
+            }));
+            (
+                Writer { state: state.clone() },
+                Reader {
+                    state,
+                    buf: BytesMut::new(),
+                },
+            )
+        }
+    }
 }
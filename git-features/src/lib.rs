@@ -28,6 +28,7 @@ pub mod path;
 #[cfg(feature = "progress")]
 pub mod progress;
 pub mod threading;
+pub mod trace;
 ///
 #[cfg(feature = "zlib")]
 pub mod zlib;
@@ -1,6 +1,6 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::parallel::{num_threads, Reduce};
+use crate::parallel::{num_threads, InOrderIter, Reduce};
 
 /// Runs `left` and `right` in parallel, returning their output when both are done.
 pub fn join<O1: Send, O2: Send>(left: impl FnOnce() -> O1 + Send, right: impl FnOnce() -> O2 + Send) -> (O1, O2) {
@@ -84,6 +84,79 @@ where
     .expect("no panic")
 }
 
+/// Read items from `input` and `consume` them in multiple threads like [`in_parallel()`], but feed their outputs to the `reducer`
+/// in the order of `input` while keeping at most `max_pending` items in flight.
+///
+/// An item is in flight from the moment it is taken from `input` until its output was fed to the `reducer`, which bounds the
+/// amount of memory used for inputs and outputs even if the `reducer` is slower than the producing threads.
+///
+/// * `max_pending` is the maximum amount of items in flight, and is at least as high as the amount of threads.
+/// * For all other parameters, see [`in_parallel()`].
+pub fn in_parallel_ordered_bounded<I, S, O, R>(
+    input: impl Iterator<Item = I> + Send,
+    thread_limit: Option<usize>,
+    max_pending: usize,
+    new_thread_state: impl Fn(usize) -> S + Send + Clone,
+    consume: impl Fn(I, &mut S) -> O + Send + Clone,
+    mut reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+    I: Send,
+    O: Send,
+{
+    let num_threads = num_threads(thread_limit);
+    let max_pending = max_pending.max(num_threads);
+    crossbeam_utils::thread::scope(move |s| {
+        let (send_permit, receive_permit) = crossbeam_channel::bounded::<()>(max_pending);
+        for _ in 0..max_pending {
+            send_permit.send(()).expect("capacity for all permits");
+        }
+        let receive_result = {
+            let (send_input, receive_input) = crossbeam_channel::bounded::<(usize, I)>(num_threads);
+            let (send_result, receive_result) = crossbeam_channel::bounded::<(usize, O)>(num_threads);
+            for thread_id in 0..num_threads {
+                s.spawn({
+                    let send_result = send_result.clone();
+                    let receive_input = receive_input.clone();
+                    let new_thread_state = new_thread_state.clone();
+                    let consume = consume.clone();
+                    move |_| {
+                        let mut state = new_thread_state(thread_id);
+                        for (sequence_id, item) in receive_input {
+                            if send_result.send((sequence_id, consume(item, &mut state))).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                });
+            }
+            s.spawn(move |_| {
+                let mut input = input.enumerate();
+                while receive_permit.recv().is_ok() {
+                    match input.next() {
+                        Some(item) => {
+                            if send_input.send(item).is_err() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    }
+                }
+            });
+            receive_result
+        };
+
+        for item in InOrderIter::from(receive_result.into_iter().map(Ok::<_, std::convert::Infallible>)) {
+            let item = item.expect("infallible");
+            drop(reducer.feed(item)?);
+            send_permit.send(()).ok();
+        }
+        reducer.finalize()
+    })
+    .expect("no panic")
+}
+
 /// An experiment to have fine-grained per-item parallelization with built-in aggregation via thread state.
 /// This is only good for operations where near-random access isn't detremental, so it's not usually great
 /// for file-io as it won't make use of sorted inputs well.
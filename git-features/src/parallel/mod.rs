@@ -15,6 +15,12 @@
 //! operation which runs as fast as possible, which is cancellable only by merit of stopping the input or stopping the output
 //! aggregation.
 //!
+//! ### in_parallel_ordered_bounded(…)
+//!
+//! The [`in_parallel_ordered_bounded(…)`][in_parallel_ordered_bounded()] works like [`in_parallel()`], but feeds outputs to the
+//! [`Reduce`] in the order of their inputs. It only takes new input once previous outputs were reduced, keeping a bounded amount of
+//! items in flight so that memory usage stays flat even if the reducer is slower than the threads producing its input.
+//!
 //! ### `reduce::Stepwise`
 //!
 //! The [`Stepwise`][reduce::Stepwise] iterator works exactly as [`in_parallel()`] except that the processing of the output produced by
@@ -35,11 +41,11 @@
 #[cfg(feature = "parallel")]
 mod in_parallel;
 #[cfg(feature = "parallel")]
-pub use in_parallel::{in_parallel, in_parallel_with_slice, join, threads};
+pub use in_parallel::{in_parallel, in_parallel_ordered_bounded, in_parallel_with_slice, join, threads};
 
 mod serial;
 #[cfg(not(feature = "parallel"))]
-pub use serial::{in_parallel, in_parallel_with_slice, join, threads};
+pub use serial::{in_parallel, in_parallel_ordered_bounded, in_parallel_with_slice, join, threads};
 
 mod in_order;
 pub use in_order::{InOrderIter, SequenceId};
@@ -175,6 +181,54 @@ where
     serial::in_parallel(input, thread_limit, new_thread_state, consume, reducer)
 }
 
+/// Run [`in_parallel_ordered_bounded()`] only if the given `condition()` returns true when eagerly evaluated.
+///
+/// For parameters, see the documentation of [`in_parallel_ordered_bounded()`]
+#[cfg(feature = "parallel")]
+pub fn in_parallel_ordered_bounded_if<I, S, O, R>(
+    condition: impl FnOnce() -> bool,
+    input: impl Iterator<Item = I> + Send,
+    thread_limit: Option<usize>,
+    max_pending: usize,
+    new_thread_state: impl Fn(usize) -> S + Send + Clone,
+    consume: impl Fn(I, &mut S) -> O + Send + Clone,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+    I: Send,
+    O: Send,
+{
+    if num_threads(thread_limit) > 1 && condition() {
+        in_parallel_ordered_bounded(input, thread_limit, max_pending, new_thread_state, consume, reducer)
+    } else {
+        serial::in_parallel_ordered_bounded(input, thread_limit, max_pending, new_thread_state, consume, reducer)
+    }
+}
+
+/// Run [`in_parallel_ordered_bounded()`] only if the given `condition()` returns true when eagerly evaluated.
+///
+/// For parameters, see the documentation of [`in_parallel_ordered_bounded()`]
+///
+/// Note that the non-parallel version is equivalent to [`in_parallel_ordered_bounded()`].
+#[cfg(not(feature = "parallel"))]
+pub fn in_parallel_ordered_bounded_if<I, S, O, R>(
+    _condition: impl FnOnce() -> bool,
+    input: impl Iterator<Item = I>,
+    thread_limit: Option<usize>,
+    max_pending: usize,
+    new_thread_state: impl Fn(usize) -> S,
+    consume: impl Fn(I, &mut S) -> O,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+    I: Send,
+    O: Send,
+{
+    serial::in_parallel_ordered_bounded(input, thread_limit, max_pending, new_thread_state, consume, reducer)
+}
+
 ///
 pub mod reduce;
 pub use reduce::Reduce;
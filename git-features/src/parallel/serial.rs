@@ -112,3 +112,23 @@ where
     }
     reducer.finalize()
 }
+
+/// Read items from `input` and `consume` them in a single thread, feeding their outputs to the `reducer` in order.
+///
+/// As each item is reduced right after it was consumed, there is only a single item in flight at any time, so `max_pending` has no effect.
+/// It's present to keep the signature similar to the parallel version.
+///
+/// **This serial version performing all calculations on the current thread.**
+pub fn in_parallel_ordered_bounded<I, S, O, R>(
+    input: impl Iterator<Item = I>,
+    thread_limit: Option<usize>,
+    _max_pending: usize,
+    new_thread_state: impl Fn(usize) -> S,
+    consume: impl Fn(I, &mut S) -> O,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+{
+    in_parallel(input, thread_limit, new_thread_state, consume, reducer)
+}
@@ -7,6 +7,13 @@ pub use prodash::{
     unit, Progress, Unit,
 };
 
+///
+#[cfg(feature = "progress-machine")]
+pub mod machine;
+///
+#[cfg(feature = "progress-summary")]
+pub mod summary;
+
 /// A unit for displaying bytes with throughput and progress percentage.
 pub fn bytes() -> Option<Unit> {
     Some(unit::dynamic_and_mode(
@@ -0,0 +1,343 @@
+//! Render a progress tree for consumption by other programs, one line per update, as JSON object or as `key=value` pairs.
+//!
+//! Each line describes one event, identified by its `event` field.
+//! * `progress` - a task was added or changed. It has a numeric `id` stable for the lifetime of the task, its `level` in the
+//!   tree, a `name`, a `state` which is one of `running`, `blocked` or `halted` along with an optional `reason`, and
+//!   optionally a `step` and `max`.
+//! * `done` - the task with `id` was removed from the tree.
+//! * `message` - a message with `level` being one of `info`, `success` or `failure`, sent by `origin`, the name of a task.
+use std::{
+    collections::HashMap,
+    io,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use prodash::{
+    messages::{Message, MessageCopyState, MessageLevel},
+    progress::{key::Level, Key, State, Task},
+    Root, WeakRoot,
+};
+
+/// The format of each line written by the [`Renderer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Write each event as JSON object on a single line.
+    Json,
+    /// Write each event as space separated `key=value` pairs, with values in double-quotes if needed.
+    KeyValue,
+}
+
+impl Format {
+    /// Return the names of all formats, as understood by [`FromStr`][std::str::FromStr].
+    pub fn variants() -> &'static [&'static str] {
+        &["json", "key-value"]
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "json" => Format::Json,
+            "key-value" => Format::KeyValue,
+            _ => return Err(format!("Invalid progress format: '{}'", s)),
+        })
+    }
+}
+
+/// Configure the [`Renderer`] and [`render()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The format of each line.
+    pub format: Format,
+    /// If set, only tasks within the given range of levels are rendered. Messages are always rendered.
+    pub level_filter: Option<RangeInclusive<Level>>,
+    /// The amount of times per second [`render()`] checks the progress tree for changes.
+    pub frames_per_second: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            format: Format::Json,
+            level_filter: None,
+            frames_per_second: 6.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Snapshot {
+    name: String,
+    step: Option<usize>,
+    max: Option<usize>,
+    state: &'static str,
+    reason: Option<&'static str>,
+}
+
+impl Snapshot {
+    fn from_task(task: &Task) -> Self {
+        let (step, max, state, reason) = match &task.progress {
+            Some(value) => {
+                let (state, reason) = match value.state {
+                    State::Running => ("running", None),
+                    State::Blocked(reason, _) => ("blocked", Some(reason)),
+                    State::Halted(reason, _) => ("halted", Some(reason)),
+                };
+                (Some(value.step.load(Ordering::SeqCst)), value.done_at, state, reason)
+            }
+            None => (None, None, "running", None),
+        };
+        Snapshot {
+            name: task.name.clone(),
+            step,
+            max,
+            state,
+            reason,
+        }
+    }
+}
+
+/// Renders the changes of a progress tree since the previous frame.
+pub struct Renderer {
+    options: Options,
+    tasks: Vec<(Key, Task)>,
+    previous: HashMap<Key, (usize, Snapshot)>,
+    next_id: usize,
+    messages: Vec<Message>,
+    message_state: Option<MessageCopyState>,
+}
+
+impl Renderer {
+    /// Create a new instance to render with the given `options`.
+    pub fn new(options: Options) -> Self {
+        Renderer {
+            options,
+            tasks: Vec::new(),
+            previous: HashMap::new(),
+            next_id: 0,
+            messages: Vec::new(),
+            message_state: None,
+        }
+    }
+
+    /// Write all messages and all changes to the tasks of `root` since the previous call to `out`.
+    pub fn render_changes(&mut self, root: &impl Root, mut out: impl io::Write) -> io::Result<()> {
+        self.messages.clear();
+        self.message_state = Some(root.copy_new_messages(&mut self.messages, self.message_state.take()));
+        for message in &self.messages {
+            let level = match message.level {
+                MessageLevel::Info => "info",
+                MessageLevel::Success => "success",
+                MessageLevel::Failure => "failure",
+            };
+            write_event(
+                &mut out,
+                self.options.format,
+                &[
+                    ("event", Value::Str("message")),
+                    ("level", Value::Str(level)),
+                    ("origin", Value::Str(&message.origin)),
+                    ("message", Value::Str(&message.message)),
+                ],
+            )?;
+        }
+
+        root.sorted_snapshot(&mut self.tasks);
+        let mut current = HashMap::with_capacity(self.tasks.len());
+        for (key, task) in &self.tasks {
+            let level = key.level();
+            if let Some(filter) = &self.options.level_filter {
+                if !filter.contains(&level) {
+                    continue;
+                }
+            }
+            let snapshot = Snapshot::from_task(task);
+            let id = match self.previous.remove(key) {
+                Some((id, previous)) if previous == snapshot => {
+                    current.insert(*key, (id, previous));
+                    continue;
+                }
+                Some((id, _)) => id,
+                None => {
+                    self.next_id += 1;
+                    self.next_id
+                }
+            };
+            {
+                let mut fields = vec![
+                    ("event", Value::Str("progress")),
+                    ("id", Value::Num(id)),
+                    ("level", Value::Num(level as usize)),
+                    ("name", Value::Str(&snapshot.name)),
+                    ("state", Value::Str(snapshot.state)),
+                ];
+                fields.extend(snapshot.reason.map(|reason| ("reason", Value::Str(reason))));
+                fields.extend(snapshot.step.map(|step| ("step", Value::Num(step))));
+                fields.extend(snapshot.max.map(|max| ("max", Value::Num(max))));
+                write_event(&mut out, self.options.format, &fields)?;
+            }
+            current.insert(*key, (id, snapshot));
+        }
+
+        let mut done: Vec<_> = self.previous.drain().map(|(_, (id, _))| id).collect();
+        done.sort_unstable();
+        for id in done {
+            write_event(
+                &mut out,
+                self.options.format,
+                &[("event", Value::Str("done")), ("id", Value::Num(id))],
+            )?;
+        }
+        self.previous = current;
+        out.flush()
+    }
+}
+
+enum Value<'a> {
+    Str(&'a str),
+    Num(usize),
+}
+
+fn write_event(out: &mut impl io::Write, format: Format, fields: &[(&str, Value<'_>)]) -> io::Result<()> {
+    let mut line = String::new();
+    match format {
+        Format::Json => {
+            line.push('{');
+            for (index, (key, value)) in fields.iter().enumerate() {
+                if index != 0 {
+                    line.push(',');
+                }
+                push_json_string(&mut line, key);
+                line.push(':');
+                match value {
+                    Value::Str(value) => push_json_string(&mut line, value),
+                    Value::Num(value) => line.push_str(&value.to_string()),
+                }
+            }
+            line.push('}');
+        }
+        Format::KeyValue => {
+            for (index, (key, value)) in fields.iter().enumerate() {
+                if index != 0 {
+                    line.push(' ');
+                }
+                line.push_str(key);
+                line.push('=');
+                match value {
+                    Value::Str(value) => push_key_value_string(&mut line, value),
+                    Value::Num(value) => line.push_str(&value.to_string()),
+                }
+            }
+        }
+    }
+    line.push('\n');
+    out.write_all(line.as_bytes())
+}
+
+fn push_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn push_key_value_string(out: &mut String, value: &str) {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '"' | '\\' | '='));
+    if !needs_quotes {
+        out.push_str(value);
+        return;
+    }
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// A handle to the thread spawned by [`render()`], which stops rendering when dropped.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<io::Result<()>>>,
+    should_stop: Arc<AtomicBool>,
+}
+
+impl JoinHandle {
+    /// Render the final changes and wait for the render thread to stop, returning the first IO error it encountered.
+    pub fn shutdown_and_wait(mut self) -> io::Result<()> {
+        self.should_stop.store(true, Ordering::SeqCst);
+        match self.inner.take().map(std::thread::JoinHandle::join) {
+            Some(Ok(res)) => res,
+            Some(Err(_)) => Err(io::Error::new(io::ErrorKind::Other, "the render thread panicked")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.inner.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Render all changes of the progress tree `progress` to `out` in a separate thread according to `options`, until the
+/// returned handle is dropped or the tree ceases to exist.
+pub fn render(
+    mut out: impl io::Write + Send + 'static,
+    progress: impl WeakRoot + Send + 'static,
+    options: Options,
+) -> JoinHandle {
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let interval = Duration::from_secs_f32(1.0 / options.frames_per_second.max(f32::EPSILON));
+    let inner = std::thread::Builder::new()
+        .name("machine-progress-renderer".into())
+        .spawn({
+            let should_stop = Arc::clone(&should_stop);
+            move || -> io::Result<()> {
+                let mut renderer = Renderer::new(options);
+                loop {
+                    let stop = should_stop.load(Ordering::SeqCst);
+                    match progress.upgrade() {
+                        Some(root) => renderer.render_changes(&root, &mut out)?,
+                        None => return Ok(()),
+                    }
+                    if stop {
+                        return Ok(());
+                    }
+                    std::thread::sleep(interval);
+                }
+            }
+        })
+        .expect("thread can be spawned");
+    JoinHandle {
+        inner: Some(inner),
+        should_stop,
+    }
+}
@@ -0,0 +1,287 @@
+//! Track all tasks of a progress tree to estimate when the entire operation is done, and to keep the throughput of tasks
+//! after they completed.
+//!
+//! Interactive renderers only show tasks while they exist, which is why [`render()`] adds a task to the tree that displays
+//! the progress and estimated time of arrival of all tasks combined, and which logs the throughput of each task
+//! as it completes.
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use prodash::{
+    progress::{Key, Task},
+    Progress, Root,
+};
+
+/// A task which disappeared from the progress tree, along with its throughput.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completed {
+    /// The name of the task.
+    pub name: String,
+    /// The last known step of the task.
+    pub step: usize,
+    /// The amount of steps made while the task was observed.
+    pub steps_observed: usize,
+    /// The time during which the task was observed.
+    pub elapsed: Duration,
+}
+
+impl Completed {
+    /// The average amount of steps per second, or `None` if the task was observed only once.
+    pub fn throughput_per_second(&self) -> Option<f64> {
+        (!self.elapsed.is_zero()).then(|| self.steps_observed as f64 / self.elapsed.as_secs_f64())
+    }
+}
+
+impl fmt::Display for Completed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} in {:.2}s", self.name, self.step, self.elapsed.as_secs_f64())?;
+        match self.throughput_per_second() {
+            Some(throughput) => write!(f, " ({:.0}/s)", throughput),
+            None => Ok(()),
+        }
+    }
+}
+
+struct Observed {
+    name: String,
+    first_seen: Instant,
+    last_seen: Instant,
+    first_step: usize,
+    step: usize,
+    max: Option<usize>,
+}
+
+impl Observed {
+    fn fraction(&self) -> Option<f64> {
+        self.max
+            .filter(|max| *max != 0)
+            .map(|max| (self.step as f64 / max as f64).min(1.0))
+    }
+}
+
+/// Observes a progress tree over time to compute the progress of all tasks combined, and the throughput of completed ones.
+pub struct Tracker {
+    start: Instant,
+    ignored: Option<Key>,
+    tasks: Vec<(Key, Task)>,
+    running: HashMap<Key, Observed>,
+    num_completed_with_max: usize,
+}
+
+impl Tracker {
+    /// Create a new instance, with `start` being the time the operation started.
+    pub fn new(start: Instant) -> Self {
+        Tracker {
+            start,
+            ignored: None,
+            tasks: Vec::new(),
+            running: HashMap::new(),
+            num_completed_with_max: 0,
+        }
+    }
+
+    /// Don't observe the task at `key`, typically because it displays the summary itself.
+    pub fn ignore(&mut self, key: Key) {
+        self.ignored = Some(key);
+    }
+
+    /// Observe all tasks of `root` at time `now`, and return all tasks which completed since the previous call.
+    pub fn update(&mut self, root: &impl Root, now: Instant) -> Vec<Completed> {
+        root.sorted_snapshot(&mut self.tasks);
+        let mut previous = std::mem::take(&mut self.running);
+        for (key, task) in &self.tasks {
+            if Some(*key) == self.ignored {
+                continue;
+            }
+            let (step, max) = task
+                .progress
+                .as_ref()
+                .map_or((0, None), |value| (value.step.load(Ordering::SeqCst), value.done_at));
+            let observed = match previous.remove(key) {
+                Some(mut observed) => {
+                    observed.name.clone_from(&task.name);
+                    observed.last_seen = now;
+                    observed.step = step;
+                    observed.max = max;
+                    observed
+                }
+                None => Observed {
+                    name: task.name.clone(),
+                    first_seen: now,
+                    last_seen: now,
+                    first_step: step,
+                    step,
+                    max,
+                },
+            };
+            self.running.insert(*key, observed);
+        }
+        self.complete(previous.into_iter().map(|(_, observed)| observed))
+    }
+
+    /// Consider all running tasks completed, for use once the operation is done, and return them.
+    pub fn finish(&mut self) -> Vec<Completed> {
+        let running = std::mem::take(&mut self.running);
+        self.complete(running.into_iter().map(|(_, observed)| observed))
+    }
+
+    /// The progress of all tasks with a known maximum combined, from 0.0 to 1.0, or `None` if there is no such task.
+    ///
+    /// Completed tasks count as done even if they didn't reach their maximum.
+    pub fn progress(&self) -> Option<f64> {
+        let (sum, count) = self.running.values().filter_map(Observed::fraction).fold(
+            (self.num_completed_with_max as f64, self.num_completed_with_max),
+            |(sum, count), f| (sum + f, count + 1),
+        );
+        (count != 0).then(|| sum / count as f64)
+    }
+
+    /// Estimate the time it takes until all tasks are done at `now`, based on the [`progress()`][Tracker::progress()] made
+    /// since the start, or `None` if there is no progress yet.
+    pub fn eta(&self, now: Instant) -> Option<Duration> {
+        let progress = self.progress().filter(|p| *p > 0.0)?;
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f64();
+        Some(Duration::from_secs_f64(elapsed * (1.0 - progress) / progress))
+    }
+
+    fn complete(&mut self, tasks: impl Iterator<Item = Observed>) -> Vec<Completed> {
+        let mut completed: Vec<_> = tasks
+            .map(|observed| {
+                if observed.max.is_some() {
+                    self.num_completed_with_max += 1;
+                }
+                (
+                    observed.first_seen,
+                    Completed {
+                        steps_observed: observed.step.saturating_sub(observed.first_step),
+                        elapsed: observed.last_seen.saturating_duration_since(observed.first_seen),
+                        name: observed.name,
+                        step: observed.step,
+                    },
+                )
+            })
+            .collect();
+        completed.sort_by_key(|(first_seen, _)| *first_seen);
+        completed.into_iter().map(|(_, completed)| completed).collect()
+    }
+}
+
+/// Format `duration` in a compact way like `1h 2m 3s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, s) => format!("{}h {}m {}s", h, m, s),
+    }
+}
+
+/// Configure [`render()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The name of the task displaying the summary, which is followed by the estimated time of arrival.
+    pub name: String,
+    /// The amount of times per second the progress tree is observed.
+    pub frames_per_second: f32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            name: "overall".into(),
+            frames_per_second: 6.0,
+        }
+    }
+}
+
+/// A handle to the thread spawned by [`render()`], which stops when dropped.
+pub struct JoinHandle {
+    inner: Option<std::thread::JoinHandle<()>>,
+    should_stop: Arc<AtomicBool>,
+}
+
+impl JoinHandle {
+    /// Log the throughput of all remaining tasks and the total duration, and wait for the thread to stop.
+    ///
+    /// Once this returns, the summary task is removed from the tree.
+    pub fn shutdown_and_wait(mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.inner.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+impl Drop for JoinHandle {
+    fn drop(&mut self) {
+        self.should_stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.inner.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Add a task to `root` which shows the progress of all other tasks combined along with the estimated time of arrival,
+/// and which logs the throughput of each task once it completes.
+///
+/// This should be called before other tasks are added to see them from the start.
+pub fn render(root: &Arc<prodash::Tree>, options: Options) -> JoinHandle {
+    let start = Instant::now();
+    let mut summary = root.add_child(options.name.clone());
+    summary.init(Some(100), Some(prodash::unit::label("%")));
+    let mut tracker = Tracker::new(start);
+    {
+        let mut tasks = Vec::new();
+        root.sorted_snapshot(&mut tasks);
+        if let Some((key, _)) = tasks.iter().find(|(_, task)| task.name == options.name) {
+            tracker.ignore(*key);
+        }
+    }
+
+    let should_stop = Arc::new(AtomicBool::new(false));
+    let interval = Duration::from_secs_f32(1.0 / options.frames_per_second.max(f32::EPSILON));
+    let root = Arc::downgrade(root);
+    let inner = std::thread::Builder::new()
+        .name("progress-summary".into())
+        .spawn({
+            let should_stop = Arc::clone(&should_stop);
+            move || loop {
+                let stop = should_stop.load(Ordering::SeqCst);
+                let root = match root.upgrade() {
+                    Some(root) => root,
+                    None => return,
+                };
+                let now = Instant::now();
+                for completed in tracker.update(&root, now) {
+                    summary.info(completed.to_string());
+                }
+                if stop {
+                    for completed in tracker.finish() {
+                        summary.info(completed.to_string());
+                    }
+                    summary.done(format!("done in {}", format_duration(now.duration_since(start))));
+                    return;
+                }
+                if let Some(progress) = tracker.progress() {
+                    summary.set((progress * 100.0) as usize);
+                }
+                summary.set_name(match tracker.eta(now) {
+                    Some(eta) => format!("{} (ETA {})", options.name, format_duration(eta)),
+                    None => options.name.clone(),
+                });
+                std::thread::sleep(interval);
+            }
+        })
+        .expect("thread can be spawned");
+    JoinHandle {
+        inner: Some(inner),
+        should_stop,
+    }
+}
@@ -0,0 +1,98 @@
+//! Instrumentation of key operations with spans and counters, powered by the [`tracing`](https://docs.rs/tracing) crate
+//! if the `tracing` feature toggle is enabled.
+//!
+//! Without it, the [`span!`][crate::span!] and [`count!`][crate::count!] macros expand to nothing and cost nothing at runtime.
+//! As the feature is unified across the dependency graph, enabling it once is enough to instrument all crates using these macros.
+//!
+//! Note that spans are entered as long as the returned [`Span`] guard is alive, hence it must not be held across `.await` points.
+//! Use [`count!`][crate::count!] in async code instead.
+
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing;
+
+/// A guard representing an entered span, which is exited when dropped.
+///
+/// Created by the [`span!`][crate::span!] macro.
+#[must_use = "the span is exited when the guard is dropped"]
+pub struct Span {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+impl Span {
+    /// Enter `span` and keep it entered until the returned guard is dropped.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    pub fn enter(span: tracing::Span) -> Self {
+        Span {
+            _entered: span.entered(),
+        }
+    }
+
+    /// Create a guard that does nothing.
+    #[cfg(not(feature = "tracing"))]
+    #[doc(hidden)]
+    pub fn disabled() -> Self {
+        Span {}
+    }
+}
+
+/// Create and enter a span named after the given string literal with optional `key = value` fields,
+/// returning a [`Span`][crate::trace::Span] guard which exits it when dropped.
+///
+/// Values must be numbers, booleans or strings. Without the `tracing` feature, they are not evaluated.
+///
+/// ```ignore
+/// let _span = git_features::span!("git_pack::index::traverse", objects = index.num_objects());
+/// ```
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::trace::Span::enter($crate::trace::tracing::info_span!($name $(, $key = $value)*))
+    };
+}
+
+/// Create and enter a span named after the given string literal with optional `key = value` fields,
+/// returning a [`Span`][crate::trace::Span] guard which exits it when dropped.
+///
+/// Values must be numbers, booleans or strings. Without the `tracing` feature, they are not evaluated.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        $(let _ = || {
+            let _ = &$value;
+        };)*
+        $crate::trace::Span::disabled()
+    }};
+}
+
+/// Record that `name` was counted `value` times, emitted as event with a `counter.<name>` field within the current span.
+///
+/// Without the `tracing` feature, `value` is not evaluated.
+///
+/// ```ignore
+/// git_features::count!(refs = refs.len());
+/// ```
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! count {
+    ($name:ident = $value:expr) => {
+        $crate::trace::tracing::debug!(counter.$name = $value as u64)
+    };
+}
+
+/// Record that `name` was counted `value` times, emitted as event with a `counter.<name>` field within the current span.
+///
+/// Without the `tracing` feature, `value` is not evaluated.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! count {
+    ($name:ident = $value:expr) => {{
+        let _ = || {
+            let _ = &$value;
+        };
+    }};
+}
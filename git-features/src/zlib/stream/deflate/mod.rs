@@ -2,6 +2,9 @@ use flate2::Compress;
 
 const BUF_SIZE: usize = 4096 * 8;
 
+/// The compression level used by [`Write::new()`], which favors speed over compression ratio.
+pub const DEFAULT_LEVEL: u32 = 1;
+
 /// A utility to zlib compress anything that is written via its [Write][std::io::Write] implementation.
 ///
 /// Be sure to call `flush()` when done to finalize the deflate stream.
@@ -22,10 +25,16 @@ mod impls {
     where
         W: io::Write,
     {
-        /// Create a new instance writing compressed bytes to `inner`.
+        /// Create a new instance writing compressed bytes to `inner`, favoring speed over compression ratio.
         pub fn new(inner: W) -> deflate::Write<W> {
+            Self::new_with_level(inner, deflate::DEFAULT_LEVEL)
+        }
+
+        /// Create a new instance writing bytes to `inner` compressed with the given `level`, ranging from 0 for no compression
+        /// to 9 for the best compression. Levels above 9 are treated like 9.
+        pub fn new_with_level(inner: W, level: u32) -> deflate::Write<W> {
             deflate::Write {
-                compressor: Compress::new(Compression::fast(), true),
+                compressor: Compress::new(Compression::new(level.min(9)), true),
                 inner,
                 buf: [0; deflate::BUF_SIZE],
             }
@@ -64,6 +64,23 @@ mod deflate_stream {
         assert_deflate_buffer(out, b"hello")
     }
 
+    #[test]
+    fn compression_levels_trade_speed_for_size() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes = b"hello world, ".repeat(1000);
+        let mut sizes = Vec::new();
+        for level in [0, 9, 42] {
+            let mut w = deflate::Write::new_with_level(Vec::new(), level);
+            w.write_all(&bytes)?;
+            w.flush()?;
+            sizes.push(w.inner.len());
+            assert_deflate_buffer(w.inner, &bytes)?;
+        }
+        assert!(sizes[0] > bytes.len(), "level 0 stores the data without compressing it");
+        assert!(sizes[1] < sizes[0]);
+        assert_eq!(sizes[2], sizes[1], "levels higher than 9 are the same as 9");
+        Ok(())
+    }
+
     fn assert_deflate_buffer(out: Vec<u8>, expected: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
         let mut actual = Vec::new();
         InflateReader::from_read(out.as_slice()).read_to_end(&mut actual)?;
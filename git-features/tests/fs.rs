@@ -0,0 +1,81 @@
+mod walkdir_with_options {
+    use std::path::{Path, PathBuf};
+
+    use git_features::fs::{walkdir_with_options, WalkOptions};
+
+    fn fixture() -> std::io::Result<tempfile::TempDir> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("b/c"))?;
+        std::fs::create_dir_all(dir.path().join("a"))?;
+        std::fs::write(dir.path().join("b/c/file"), b"")?;
+        std::fs::write(dir.path().join("a/.hidden"), b"")?;
+        std::fs::write(dir.path().join("top"), b"")?;
+        Ok(dir)
+    }
+
+    fn paths(root: &Path, options: WalkOptions) -> Vec<PathBuf> {
+        walkdir_with_options(root, options)
+            .into_iter()
+            .map(|entry| {
+                entry
+                    .expect("no error")
+                    .path()
+                    .strip_prefix(root)
+                    .expect("within root")
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorted_traversal_includes_hidden_files() -> std::io::Result<()> {
+        let dir = fixture()?;
+        assert_eq!(
+            paths(
+                dir.path(),
+                WalkOptions {
+                    sorted: true,
+                    min_depth: 1,
+                    ..Default::default()
+                }
+            ),
+            ["a", "a/.hidden", "b", "b/c", "b/c/file", "top"]
+                .iter()
+                .map(PathBuf::from)
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn depth_limits() -> std::io::Result<()> {
+        let dir = fixture()?;
+        assert_eq!(
+            paths(
+                dir.path(),
+                WalkOptions {
+                    sorted: true,
+                    min_depth: 1,
+                    max_depth: Some(1),
+                    ..Default::default()
+                }
+            ),
+            ["a", "b", "top"].iter().map(PathBuf::from).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            paths(
+                dir.path(),
+                WalkOptions {
+                    sorted: true,
+                    min_depth: 2,
+                    max_depth: Some(2),
+                    same_file_system: true,
+                    ..Default::default()
+                }
+            ),
+            ["a/.hidden", "b/c"].iter().map(PathBuf::from).collect::<Vec<_>>(),
+            "staying on the same file system doesn't affect traversal within it"
+        );
+        Ok(())
+    }
+}
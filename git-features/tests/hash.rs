@@ -1,13 +1,47 @@
 use git_features::hash::Sha1;
 
-#[cfg(not(feature = "fast-sha1"))]
+#[cfg(all(not(feature = "fast-sha1"), not(feature = "sha1-collision-detection")))]
 #[test]
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 96)
 }
 
-#[cfg(feature = "fast-sha1")]
+#[cfg(all(feature = "fast-sha1", not(feature = "sha1-collision-detection")))]
 #[test]
 fn size_of_sha1() {
     assert_eq!(std::mem::size_of::<Sha1>(), 104)
 }
+
+#[cfg(feature = "sha1-collision-detection")]
+mod backend {
+    use git_features::hash::{set_sha1_backend, sha1_backend, Sha1, Sha1Backend};
+
+    fn hex_digest(backend: Sha1Backend, data: &[u8]) -> String {
+        let mut hash = Sha1::new(backend);
+        hash.update(data);
+        hash.try_digest()
+            .expect("no collision")
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    #[test]
+    fn all_backends_produce_the_same_digest_for_regular_data() {
+        for backend in [Sha1Backend::Fast, Sha1Backend::CollisionDetection] {
+            assert_eq!(hex_digest(backend, b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        }
+    }
+
+    #[test]
+    fn collision_detection_is_the_default_and_can_be_changed_at_runtime() {
+        assert_eq!(Sha1Backend::default(), Sha1Backend::CollisionDetection);
+        assert_eq!(sha1_backend(), Sha1Backend::CollisionDetection);
+        assert_eq!(Sha1::default().backend(), Sha1Backend::CollisionDetection);
+
+        set_sha1_backend(Sha1Backend::Fast);
+        assert_eq!(sha1_backend(), Sha1Backend::Fast);
+        assert_eq!(Sha1::default().backend(), Sha1Backend::Fast);
+        set_sha1_backend(Sha1Backend::CollisionDetection);
+    }
+}
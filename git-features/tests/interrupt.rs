@@ -0,0 +1,38 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use git_features::interrupt::handler;
+
+static SHOULD_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn handlers_can_be_registered_multiple_times_and_are_deregistered_by_the_last_user() -> std::io::Result<()> {
+    assert!(!handler::is_registered());
+    let first = handler::init(&SHOULD_INTERRUPT, || {}, || {})?;
+    let second = handler::init(&SHOULD_INTERRUPT, || {}, || {})?.auto_deregister();
+    assert!(handler::is_registered());
+
+    SHOULD_INTERRUPT.store(true, Ordering::SeqCst);
+    first.with_reset(true).deregister()?;
+    assert!(
+        handler::is_registered(),
+        "the handlers are still registered as the second user didn't deregister yet"
+    );
+    assert!(
+        SHOULD_INTERRUPT.load(Ordering::SeqCst),
+        "the flag is only reset when actually deregistering"
+    );
+
+    drop(second);
+    assert!(!handler::is_registered(), "the last user removed the handlers");
+    assert!(
+        SHOULD_INTERRUPT.load(Ordering::SeqCst),
+        "only the deregistration of the first user would have reset the flag"
+    );
+
+    let third = handler::init(&SHOULD_INTERRUPT, || {}, || {})?;
+    assert!(handler::is_registered(), "handlers can be registered again");
+    third.with_reset(true).deregister()?;
+    assert!(!handler::is_registered());
+    assert!(!SHOULD_INTERRUPT.load(Ordering::SeqCst), "the last user reset the flag");
+    Ok(())
+}
@@ -37,6 +37,63 @@ fn in_parallel() {
     assert_eq!(res, 100);
 }
 
+#[test]
+fn in_parallel_ordered_bounded() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct InOrder<'a> {
+        taken: &'a AtomicUsize,
+        max_pending: usize,
+        items: Vec<usize>,
+    }
+
+    impl parallel::Reduce for InOrder<'_> {
+        type Input = usize;
+        type FeedProduce = ();
+        type Output = Vec<usize>;
+        type Error = ();
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            assert!(
+                self.taken.load(Ordering::SeqCst) - self.items.len() <= self.max_pending,
+                "no more than max_pending items are in flight"
+            );
+            self.items.push(item);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok(self.items)
+        }
+    }
+
+    let taken = AtomicUsize::default();
+    let max_pending = 4;
+    let res = parallel::in_parallel_ordered_bounded(
+        (0..100usize).inspect(|_| {
+            taken.fetch_add(1, Ordering::SeqCst);
+        }),
+        None,
+        max_pending.max(parallel::num_threads(None)),
+        |_n| (),
+        |input, _state| {
+            std::thread::sleep(std::time::Duration::from_micros(((input * 7) % 5) as u64 * 50));
+            input * 2
+        },
+        InOrder {
+            taken: &taken,
+            max_pending: max_pending.max(parallel::num_threads(None)),
+            items: Vec::new(),
+        },
+    )
+    .expect("successful computation");
+    assert_eq!(
+        res,
+        (0..100).map(|n| n * 2).collect::<Vec<_>>(),
+        "outputs are in input order"
+    );
+}
+
 #[test]
 fn in_parallel_with_mut_slice_in_chunks() {
     let num_items = 33;
@@ -114,4 +114,43 @@ mod io {
         }
         assert_eq!(block_count * BLOCK_SIZE, bytes_read);
     }
+
+    #[test]
+    fn bounded_pipe_transfers_chunks_no_larger_than_its_capacity() {
+        const CAPACITY: usize = 3;
+        let (mut writer, mut reader) = io::pipe::bounded(CAPACITY);
+        let message = b"hello, world";
+        std::thread::spawn(move || writer.write_all(message).expect("reader is present"));
+
+        let mut received = Vec::new();
+        loop {
+            let buf = reader.fill_buf().expect("no error");
+            if buf.is_empty() {
+                break;
+            }
+            assert!(buf.len() <= CAPACITY, "each chunk is bounded by the capacity");
+            let len = buf.len();
+            received.extend_from_slice(buf);
+            reader.consume(len);
+        }
+        assert_eq!(received, message);
+    }
+
+    #[test]
+    fn bounded_pipe_blocks_writer_until_reader_consumes() {
+        let (mut writer, mut reader) = io::pipe::bounded(4);
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            writer.write_all(b"abcdefgh").expect("reader is present");
+            tx.send(()).expect("receiver is present");
+        });
+        assert!(
+            rx.recv_timeout(std::time::Duration::from_millis(50)).is_err(),
+            "the writer cannot finish while nothing is read"
+        );
+        let mut received = String::new();
+        reader.read_to_string(&mut received).expect("no error");
+        assert_eq!(received, "abcdefgh");
+        rx.recv().expect("the writer finishes once everything was read");
+    }
 }
@@ -0,0 +1,88 @@
+mod io {
+    use std::io::ErrorKind;
+
+    use futures_lite::{future, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+    use git_features::io::pipe::async_io;
+
+    #[test]
+    fn threaded_read_to_end() {
+        let (mut writer, mut reader) = async_io::bounded(4);
+        let message = "Hello, world!";
+        std::thread::spawn(move || {
+            future::block_on(writer.write_all(message.as_bytes())).expect("writes to work if reader is present")
+        });
+
+        let mut received = String::new();
+        future::block_on(reader.read_to_string(&mut received)).unwrap();
+        assert_eq!(&received, message);
+    }
+
+    #[test]
+    fn writes_are_limited_by_capacity() {
+        let (mut writer, mut reader) = async_io::bounded(4);
+        future::block_on(async {
+            assert_eq!(
+                writer.write(b"abcdefgh").await.unwrap(),
+                4,
+                "only as much as fits is written"
+            );
+            assert!(
+                future::poll_once(writer.write(b"efgh")).await.is_none(),
+                "a full pipe makes writes pending"
+            );
+            let mut buf = [0u8; 2];
+            reader.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"ab");
+            assert_eq!(
+                writer.write(b"efgh").await.unwrap(),
+                2,
+                "consumed bytes make room, but bytes the reader holds still count"
+            );
+            assert!(
+                future::poll_once(writer.write(b"gh")).await.is_none(),
+                "the pipe is full again"
+            );
+            drop(writer);
+            let mut rest = String::new();
+            reader.read_to_string(&mut rest).await.unwrap();
+            assert_eq!(rest, "cdef");
+        });
+    }
+
+    #[test]
+    fn lack_of_reader_fails_with_broken_pipe() {
+        let (mut writer, _) = async_io::bounded(4);
+        assert_eq!(
+            future::block_on(writer.write_all(b"must fail")).unwrap_err().kind(),
+            ErrorKind::BrokenPipe
+        );
+    }
+
+    #[test]
+    fn line_reading() {
+        let (mut writer, reader) = async_io::bounded(16);
+        future::block_on(async {
+            writer.write_all(b"a\nb\nc\n").await.unwrap();
+            drop(writer);
+            let mut lines = Vec::new();
+            let mut reader = reader;
+            let mut line = String::new();
+            while reader.read_line(&mut line).await.unwrap() != 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            assert_eq!(lines, vec!["a\n", "b\n", "c\n"]);
+        });
+    }
+
+    #[test]
+    fn writer_can_inject_errors_after_written_bytes() {
+        let (mut writer, mut reader) = async_io::bounded(4);
+        future::block_on(async {
+            writer.write_all(b"ab").await.unwrap();
+            writer.send_error(std::io::Error::new(ErrorKind::Other, "the error"));
+            let mut buf = [0u8; 4];
+            assert_eq!(reader.read(&mut buf).await.unwrap(), 2, "bytes come first");
+            assert_eq!(reader.read(&mut buf).await.unwrap_err().to_string(), "the error");
+        });
+    }
+}
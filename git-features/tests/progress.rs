@@ -0,0 +1,76 @@
+use bstr::ByteSlice;
+use git_features::progress::{
+    machine::{Format, Options, Renderer},
+    Progress,
+};
+
+fn render(renderer: &mut Renderer, root: &std::sync::Arc<prodash::Tree>) -> String {
+    let mut out = Vec::new();
+    renderer
+        .render_changes(root, &mut out)
+        .expect("writing to memory never fails");
+    out.to_str().expect("valid UTF-8").to_owned()
+}
+
+#[test]
+fn key_value_lines_describe_changes_only() {
+    let root = prodash::Tree::new();
+    let mut renderer = Renderer::new(Options {
+        format: Format::KeyValue,
+        ..Default::default()
+    });
+    let mut task = root.add_child("receiving pack");
+    task.init(Some(10), None);
+    task.set(3);
+    task.info("hello world");
+
+    assert_eq!(
+        render(&mut renderer, &root),
+        "event=message level=info origin=\"receiving pack\" message=\"hello world\"\n\
+         event=progress id=1 level=1 name=\"receiving pack\" state=running step=3 max=10\n"
+    );
+    assert_eq!(render(&mut renderer, &root), "", "nothing changed");
+
+    task.set(5);
+    assert_eq!(
+        render(&mut renderer, &root),
+        "event=progress id=1 level=1 name=\"receiving pack\" state=running step=5 max=10\n"
+    );
+
+    drop(task);
+    assert_eq!(render(&mut renderer, &root), "event=done id=1\n");
+}
+
+#[test]
+fn json_objects_are_escaped() {
+    let root = prodash::Tree::new();
+    let mut renderer = Renderer::new(Options::default());
+    let mut task = root.add_child("a \"quoted\"\tname");
+    task.fail("line\nbreak");
+
+    assert_eq!(
+        render(&mut renderer, &root),
+        "{\"event\":\"message\",\"level\":\"failure\",\"origin\":\"a \\\"quoted\\\"\\tname\",\"message\":\"line\\nbreak\"}\n\
+         {\"event\":\"progress\",\"id\":1,\"level\":1,\"name\":\"a \\\"quoted\\\"\\tname\",\"state\":\"running\"}\n"
+    );
+}
+
+#[test]
+fn level_filter_excludes_tasks_but_not_messages() {
+    let root = prodash::Tree::new();
+    let mut renderer = Renderer::new(Options {
+        format: Format::KeyValue,
+        level_filter: Some(2..=2),
+        ..Default::default()
+    });
+    let mut parent = root.add_child("parent");
+    let mut child = parent.add_child("child");
+    child.init(None, None);
+    parent.done("finished");
+
+    assert_eq!(
+        render(&mut renderer, &root),
+        "event=message level=success origin=parent message=finished\n\
+         event=progress id=1 level=2 name=child state=running step=0\n"
+    );
+}
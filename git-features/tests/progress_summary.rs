@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+
+use git_features::progress::{
+    summary::{format_duration, Completed, Tracker},
+    Progress,
+};
+
+#[test]
+fn progress_and_eta_combine_all_tasks_with_known_maximum() {
+    let root = prodash::Tree::new();
+    let start = Instant::now();
+    let mut tracker = Tracker::new(start);
+    assert_eq!(tracker.progress(), None, "nothing to track yet");
+
+    let mut first = root.add_child("first");
+    first.init(Some(10), None);
+    first.set(5);
+    let mut second = root.add_child("second");
+    second.init(Some(4), None);
+    let mut unbounded = root.add_child("unbounded");
+    unbounded.init(None, None);
+    unbounded.set(1000);
+
+    assert!(tracker.update(&root, start).is_empty());
+    assert_eq!(tracker.progress(), Some(0.25), "tasks without maximum are ignored");
+    assert_eq!(
+        tracker.eta(start + Duration::from_secs(10)),
+        Some(Duration::from_secs(30)),
+        "a quarter took 10s, so the remaining three quarters take 30s"
+    );
+
+    second.set(8);
+    assert!(tracker.update(&root, start).is_empty());
+    assert_eq!(tracker.progress(), Some(0.75), "tasks beyond their maximum are considered done");
+}
+
+#[test]
+fn completed_tasks_keep_their_throughput_and_count_as_done() {
+    let root = prodash::Tree::new();
+    let start = Instant::now();
+    let mut tracker = Tracker::new(start);
+
+    let mut task = root.add_child("receiving");
+    task.init(Some(100), None);
+    task.set(10);
+    let mut other = root.add_child("resolving");
+    other.init(Some(10), None);
+    assert!(tracker.update(&root, start).is_empty());
+
+    task.set(50);
+    assert!(tracker.update(&root, start + Duration::from_secs(2)).is_empty());
+
+    drop(task);
+    let completed = tracker.update(&root, start + Duration::from_secs(3));
+    assert_eq!(
+        completed,
+        vec![Completed {
+            name: "receiving".into(),
+            step: 50,
+            steps_observed: 40,
+            elapsed: Duration::from_secs(2),
+        }],
+        "the time of completion is the last time the task was seen"
+    );
+    assert_eq!(completed[0].to_string(), "receiving: 50 in 2.00s (20/s)");
+    assert_eq!(tracker.progress(), Some(0.5), "the completed task counts as done");
+
+    let remaining = tracker.finish();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].to_string(), "resolving: 0 in 0.00s", "no throughput without elapsed time");
+    assert_eq!(tracker.progress(), Some(1.0));
+}
+
+#[test]
+fn ignored_tasks_are_not_tracked() {
+    let root = prodash::Tree::new();
+    let mut tracker = Tracker::new(Instant::now());
+    let mut summary = root.add_child("summary");
+    summary.init(Some(100), None);
+    let mut tasks = Vec::new();
+    prodash::Root::sorted_snapshot(&root, &mut tasks);
+    tracker.ignore(tasks[0].0);
+
+    assert!(tracker.update(&root, Instant::now()).is_empty());
+    assert_eq!(tracker.progress(), None);
+    drop(summary);
+    assert!(tracker.update(&root, Instant::now()).is_empty());
+}
+
+#[test]
+fn durations_are_formatted_compactly() {
+    assert_eq!(format_duration(Duration::from_millis(1500)), "1s");
+    assert_eq!(format_duration(Duration::from_secs(62)), "1m 2s");
+    assert_eq!(format_duration(Duration::from_secs(3723)), "1h 2m 3s");
+}
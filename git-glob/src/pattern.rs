@@ -159,7 +159,8 @@ impl Pattern {
                 } else if !value.starts_with(&self.text[..pos]) {
                     return false;
                 }
-                crate::wildmatch(self.text.as_bstr(), value, mode)
+                // Like git, only match what follows the literal prefix, which affects how `**` right after it is interpreted.
+                crate::wildmatch(self.text[pos..].as_bstr(), value[pos..].as_bstr(), mode)
             }
             None => {
                 if mode.contains(wildmatch::Mode::IGNORE_CASE) {
@@ -178,11 +178,13 @@ pub(crate) mod function {
                     let mut prev_p_ch = 0;
                     let mut matched = false;
                     loop {
-                        match next {
+                        // Like git, characters within brackets are compared as is, even when ignoring case.
+                        match next.map(|(p_idx, _)| (p_idx, pattern[p_idx])) {
                             None => return AbortAll,
                             Some((p_idx, mut p_ch)) => match p_ch {
                                 BACKSLASH => match p.next() {
-                                    Some((_, p_ch)) => {
+                                    Some((p_idx, _)) => {
+                                        let p_ch = pattern[p_idx];
                                         if p_ch == t_ch {
                                             matched = true
                                         } else {
@@ -195,10 +197,10 @@ pub(crate) mod function {
                                     && p.peek().is_some()
                                     && p.peek().map(|t| t.1) != Some(BRACKET_CLOSE) =>
                                 {
-                                    p_ch = p.next().expect("peeked").1;
+                                    p_ch = pattern[p.next().expect("peeked").0];
                                     if p_ch == BACKSLASH {
                                         p_ch = match p.next() {
-                                            Some(t) => t.1,
+                                            Some(t) => pattern[t.0],
                                             None => return AbortAll,
                                         };
                                     }
@@ -206,11 +208,7 @@ pub(crate) mod function {
                                         matched = true;
                                     } else if mode.contains(Mode::IGNORE_CASE) && t_ch.is_ascii_lowercase() {
                                         let t_ch_upper = t_ch.to_ascii_uppercase();
-                                        if (t_ch_upper <= p_ch.to_ascii_uppercase()
-                                            && t_ch_upper >= prev_p_ch.to_ascii_uppercase())
-                                            || (t_ch_upper <= prev_p_ch.to_ascii_uppercase()
-                                                && t_ch_upper >= p_ch.to_ascii_uppercase())
-                                        {
+                                        if t_ch_upper <= p_ch && t_ch_upper >= prev_p_ch {
                                             matched = true;
                                         }
                                     }
@@ -232,11 +230,9 @@ pub(crate) mod function {
                                         if t_ch == BRACKET_OPEN {
                                             matched = true
                                         }
-                                        p = pattern[p_idx + 1..]
-                                            .iter()
-                                            .map(possibly_lowercase)
-                                            .enumerate()
-                                            .peekable();
+                                        // Continue right after the opening bracket, keeping indices into `pattern`.
+                                        p = pattern.iter().map(possibly_lowercase).enumerate().peekable();
+                                        p.nth(p_idx);
                                     } else {
                                         let class = &pattern.as_ref()[p_idx + 2..closing_bracket_idx - 1];
                                         match class {
@@ -64,6 +64,9 @@ foo/** foo
 {**/src/**,foo} abc/src/bar
 {**/src/**,foo} foo
 abc[/]def abc/def
+ab**/x abcx
+[A] a
+[][:]*[a-b] ]]?[[A
 EOF
 
 while read -r pattern value; do
@@ -140,6 +143,11 @@ test/** test/one
 test/** test/one/two
 some/*/needle.txt some/one/needle.txt
 abc/def abc/def
+foo**/bar foox/bar
+ab**/x abx
+A**/x Ax
+[B-a]x \x
+[][:]*[a-b] ]]?[[a
 EOF
 
 git config core.ignorecase true
@@ -152,4 +160,23 @@ aBcDeFg  aBcDeFg
 aBcDeFg  abcdefg
 aBcDeFg  ABCDEFG
 aBcDeFg  AbCdEfG
+[a] A
+[Z-y] z
+[[:upper:]] a
+[[:lower:]] A
+[A-Z] a
+[B-a]x \x
+[][:]*[a-b] ]]?[[A
+A**/[[:] a[
+EOF
+
+while read -r pattern value; do
+  echo "$pattern" "$value"
+  echo "$pattern" > .gitignore
+  echo "$value" | git check-ignore -vn --stdin 2>&1 || :
+done <<EOF >git-baseline.nmatch-icase
+[A] A
+[A] a
+[Q] q
+ab**/x ABCX
 EOF
@@ -50,6 +50,7 @@ fn compare_baseline_with_ours() {
         ("git-baseline.match", true, pattern::Case::Sensitive),
         ("git-baseline.nmatch", false, pattern::Case::Sensitive),
         ("git-baseline.match-icase", true, pattern::Case::Fold),
+        ("git-baseline.nmatch-icase", false, pattern::Case::Fold),
     ] {
         let input = std::fs::read(dir.join(*input_file)).unwrap();
         let mut seen = BTreeSet::default();
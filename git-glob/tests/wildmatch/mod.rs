@@ -203,6 +203,19 @@ fn corpus() {
         (1,1,1,1, "a", "[B-a]"),
         (0,1,0,1, "z", "[Z-y]"),
         (1,1,1,1, "Z", "[Z-y]"),
+
+        // Malformed patterns that only match when invoked by `git ls-files`, which isn't what we do
+        (0,0,0,0, r"\", r"\"),
+        (0,0,0,0, "a[]b", "a[]b"),
+        (0,0,0,0, "ab[", "ab["),
+
+        // Like in git, characters within brackets are compared as is, even if case is ignored
+        (0,0,0,0, "a", "[A]"),
+        (1,0,1,0, "A", "[A]"),
+        (0,1,0,1, "A", "[a]"),
+        (1,1,1,1, r"\", "[B-a]"),
+        (1,1,1,1, "a-?ba-\\", "**[B-a]"),
+        (0,1,0,1, "]]?[[A", "[][:]*[a-b]"),
     ];
 
     let mut failures = Vec::new();
@@ -222,18 +235,6 @@ fn corpus() {
 
     assert_eq!(failures.len(), 0);
     assert_eq!(at_least_one_panic, 0, "not a single panic in any invocation");
-
-    // TODO: reproduce these
-    // (0 0 0 0 \
-    // 1 1 1 1 '\' '\'
-    // (0 0 0 0 \
-    // E E E E 'foo' ''
-    // (0 0 0 0 \
-    // 1 1 1 1 'a[]b' 'a[]b'
-    //     (0 0 0 0 \
-    //      1 1 1 1 'ab[' 'ab['
-    // (0 0 1 1 \
-    // 1 1 1 1 foo/bba/arr 'foo**'
 }
 
 #[test]
@@ -130,6 +130,38 @@ impl oid {
             hex_len: self.bytes.len() * 2,
         }
     }
+
+    /// Return the amount of leading hex characters `self` has in common with `other`.
+    ///
+    /// This is useful to determine how many hex characters are needed to tell both ids apart.
+    pub fn common_hex_len(&self, other: &oid) -> usize {
+        let mut hex_len = 0;
+        for (ours, theirs) in self.bytes.iter().zip(other.bytes.iter()) {
+            if ours == theirs {
+                hex_len += 2;
+                continue;
+            }
+            if ours >> 4 == theirs >> 4 {
+                hex_len += 1;
+            }
+            break;
+        }
+        hex_len
+    }
+
+    /// Return true if `other` is equal to this id, taking the same time no matter at which byte they differ.
+    ///
+    /// Use it instead of `==` when comparing ids provided by untrusted parties to secrets, for instance when validating
+    /// nonces or checksums in authentication-adjacent contexts, to not leak information about how many bytes matched.
+    pub fn eq_constant_time(&self, other: &oid) -> bool {
+        self.bytes.len() == other.bytes.len()
+            && self
+                .bytes
+                .iter()
+                .zip(other.bytes.iter())
+                .fold(0u8, |acc, (ours, theirs)| acc | (ours ^ theirs))
+                == 0
+    }
 }
 
 /// Sha1 specific methods
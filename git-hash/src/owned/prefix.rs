@@ -5,7 +5,7 @@ use quick_error::quick_error;
 use crate::{oid, ObjectId, Prefix};
 
 quick_error! {
-    /// The error returned by [Prefix::try_from_id()][super::Prefix::try_from_id()].
+    /// The error returned by [Prefix::new()][super::Prefix::new()].
     #[derive(Debug)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -75,9 +75,61 @@ impl Prefix {
             })
     }
 
+    /// Return the kind of hash this prefix belongs to.
+    pub fn kind(&self) -> crate::Kind {
+        self.bytes.kind()
+    }
+
+    /// Return true if `candidate`, a full hash, starts with this prefix.
+    pub fn contains(&self, candidate: impl AsRef<oid>) -> bool {
+        self.cmp_oid(candidate.as_ref()) == Ordering::Equal
+    }
+
+    /// Return true if this prefix starts with `other`, which is the case if all ids starting with this prefix also start
+    /// with `other`.
+    pub fn starts_with(&self, other: &Prefix) -> bool {
+        self.kind() == other.kind() && self.hex_len >= other.hex_len && other.cmp_oid(&self.bytes) == Ordering::Equal
+    }
+
     /// Create an instance from the given hexadecimal prefix, e.g. `35e77c16` would yield a `Prefix` with `hex_len()` = 8.
-    pub fn from_hex(_hex: &str) -> Self {
-        todo!("Prefix::from_hex()")
+    pub fn from_hex(value: &str) -> Result<Self, from_hex::Error> {
+        let hex_len = value.len();
+        let kind = crate::Kind::longest();
+        if hex_len > kind.len_in_hex() {
+            return Err(from_hex::Error::TooLong { hex_len });
+        } else if hex_len < 4 {
+            return Err(from_hex::Error::TooShort { hex_len });
+        }
+
+        let mut bytes = ObjectId::null(kind);
+        let buf = bytes.as_mut_slice();
+        for (index, c) in value.chars().enumerate() {
+            let nibble = c.to_digit(16).ok_or(from_hex::Error::Invalid { c, index })? as u8;
+            buf[index / 2] |= if index % 2 == 0 { nibble << 4 } else { nibble };
+        }
+        Ok(Prefix { bytes, hex_len })
+    }
+}
+
+///
+pub mod from_hex {
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [Prefix::from_hex][super::Prefix::from_hex()].
+        #[derive(Debug, PartialEq)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            TooShort { hex_len: usize } {
+                display("The minimum hex length of a short object id is 4, got {}", hex_len)
+            }
+            TooLong { hex_len: usize } {
+                display("An id cannot be larger than {} chars in hex, but {} was requested", crate::Kind::longest().len_in_hex(), hex_len)
+            }
+            Invalid { c: char, index: usize } {
+                display("Invalid character {} at position {}", c, index)
+            }
+        }
     }
 }
 
@@ -86,3 +138,11 @@ impl std::fmt::Display for Prefix {
         self.bytes.to_hex_with_len(self.hex_len).fmt(f)
     }
 }
+
+impl std::str::FromStr for Prefix {
+    type Err = from_hex::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
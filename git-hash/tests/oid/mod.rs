@@ -30,6 +30,75 @@ mod prefix {
             assert_eq!(prefix.to_string(), "a920bb");
         }
     }
+    mod contains {
+        use git_hash::Prefix;
+        use git_testtools::hex_to_id;
+
+        #[test]
+        fn full_ids_starting_with_the_prefix() {
+            let prefix = Prefix::from_hex("b920b").unwrap();
+            assert!(prefix.contains(hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3")));
+            assert!(prefix.contains(hex_to_id("b920b00000000000000000000000000000000000")));
+            assert!(!prefix.contains(hex_to_id("b920c00000000000000000000000000000000000")));
+            assert!(!prefix.contains(hex_to_id("a920bbb055e1efb9080592a409d3975738b6efb3")));
+        }
+
+        #[test]
+        fn shorter_prefixes() {
+            let prefix = Prefix::from_hex("b920bb").unwrap();
+            for other in ["b920", "b920b", "b920bb"] {
+                assert!(prefix.starts_with(&Prefix::from_hex(other).unwrap()), "{}", other);
+            }
+            for other in ["b920bbb", "b920c", "a920"] {
+                assert!(!prefix.starts_with(&Prefix::from_hex(other).unwrap()), "{}", other);
+            }
+        }
+    }
+
+    mod ordering {
+        use git_hash::Prefix;
+
+        #[test]
+        fn is_the_same_as_the_one_of_the_hexadecimal_representation() {
+            let mut hex = vec!["abcd1", "abcd", "abcd0", "abcc", "abcdef", "abce", "0000", "abcd00"];
+            let mut prefixes: Vec<_> = hex.iter().map(|hex| Prefix::from_hex(hex).unwrap()).collect();
+            hex.sort_unstable();
+            prefixes.sort();
+            assert_eq!(prefixes.iter().map(ToString::to_string).collect::<Vec<_>>(), hex);
+        }
+    }
+
+    mod from_hex {
+        use std::cmp::Ordering;
+
+        use git_hash::{prefix::from_hex::Error, Prefix};
+        use git_testtools::hex_to_id;
+
+        #[test]
+        fn various_valid_inputs() {
+            let oid_hex = "abcdefabcdefabcdefabcdefabcdefabcdefabcd";
+            let oid = hex_to_id(oid_hex);
+            for hex_len in 4..=oid.kind().len_in_hex() {
+                let prefix = Prefix::from_hex(&oid_hex[..hex_len]).unwrap();
+                assert_eq!(prefix, Prefix::new(oid, hex_len).unwrap(), "{}", hex_len);
+                assert_eq!(prefix.to_string(), oid_hex[..hex_len]);
+                assert_eq!(prefix.cmp_oid(&oid), Ordering::Equal);
+            }
+            assert_eq!(
+                "ABCDE".parse::<Prefix>().unwrap(),
+                Prefix::from_hex("abcde").unwrap(),
+                "case doesn't matter"
+            );
+        }
+
+        #[test]
+        fn errors_for_invalid_lengths_and_characters() {
+            assert_eq!(Prefix::from_hex("abc"), Err(Error::TooShort { hex_len: 3 }));
+            assert_eq!(Prefix::from_hex(&"a".repeat(41)), Err(Error::TooLong { hex_len: 41 }));
+            assert_eq!(Prefix::from_hex("abcx1"), Err(Error::Invalid { c: 'x', index: 3 }));
+        }
+    }
+
     mod new {
         use std::cmp::Ordering;
 
@@ -87,3 +156,46 @@ mod short_hex {
         );
     }
 }
+
+mod eq_constant_time {
+    use git_testtools::hex_to_id;
+
+    #[test]
+    fn is_the_same_as_regular_equality() {
+        let id = hex_to_id("0123456789abcdef123456789abcdef123456789");
+        assert!(id.eq_constant_time(&id));
+        for other in [
+            "1123456789abcdef123456789abcdef123456789",
+            "0123456789abcdef123456789abcdef12345678a",
+            "0000000000000000000000000000000000000000",
+        ] {
+            assert!(!id.eq_constant_time(&hex_to_id(other)), "{}", other);
+        }
+    }
+}
+
+mod common_hex_len {
+    use git_testtools::hex_to_id;
+
+    #[test]
+    fn counts_equal_leading_nibbles() {
+        let id = hex_to_id("0123456789abcdef123456789abcdef123456789");
+        assert_eq!(id.common_hex_len(&id), 40);
+        assert_eq!(
+            id.common_hex_len(&hex_to_id("1123456789abcdef123456789abcdef123456789")),
+            0
+        );
+        assert_eq!(
+            id.common_hex_len(&hex_to_id("0223456789abcdef123456789abcdef123456789")),
+            1
+        );
+        assert_eq!(
+            id.common_hex_len(&hex_to_id("0123466789abcdef123456789abcdef123456789")),
+            5
+        );
+        assert_eq!(
+            id.common_hex_len(&hex_to_id("0123456789abcdef123456789abcdef12345678a")),
+            39
+        );
+    }
+}
@@ -1,12 +1,20 @@
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
+use filetime::FileTime;
 
-use crate::{extension, Entry, State, Version};
+use crate::{entry, extension, Entry, State, Version};
 
 impl State {
     pub fn version(&self) -> Version {
         self.version
     }
 
+    /// The time at which the state was created, typically the modification time of the index file it was read from.
+    ///
+    /// Entries modified at or after this time can't be trusted to be unchanged based on their stat information alone.
+    pub fn timestamp(&self) -> FileTime {
+        self.timestamp
+    }
+
     pub fn entries(&self) -> &[Entry] {
         &self.entries
     }
@@ -20,6 +28,144 @@ impl State {
             (e, path)
         })
     }
+
+    /// Find the index of the entry in [`entries()`][State::entries()] with the given `path` relative to the root of the
+    /// repository and `stage`, which is `0` unless the entry is conflicted.
+    pub fn entry_index_by_path_and_stage(&self, path: &BStr, stage: u32) -> Option<usize> {
+        self.entries
+            .binary_search_by(|e| e.path(self).cmp(path).then_with(|| e.stage().cmp(&stage)))
+            .ok()
+    }
+
+    /// Remove the entry at `index` in [`entries()`][State::entries()] and return it.
+    ///
+    /// Unmerged entries are recorded in the resolve-undo extension, and all extensions referring to entries or paths are
+    /// dropped as they don't match the entries anymore.
+    pub fn remove_entry_at_index(&mut self, index: usize) -> Entry {
+        self.invalidate_extensions();
+        let entry = self.entries.remove(index);
+        self.record_resolve_undo(&entry);
+        entry
+    }
+
+    /// Add an entry for `path` with the given `stat`, `id`, `flags` and `mode` at the position matching its path and stage,
+    /// or replace the entry with the same path and stage, and return its index in [`entries()`][State::entries()].
+    ///
+    /// Adding a merged entry, i.e. one with stage `0`, resolves the conflict of `path` by removing its unmerged entries
+    /// and recording them in the resolve-undo extension, just like adding an unmerged entry removes its merged one.
+    /// Adding `path` fails if it would be both a file and a directory, i.e. if an entry is a leading directory of it
+    /// or lies within it.
+    ///
+    /// All extensions referring to entries or paths are dropped as they don't match the entries anymore.
+    pub fn upsert_entry(
+        &mut self,
+        path: &BStr,
+        stat: entry::Stat,
+        id: git_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+    ) -> Result<usize, crate::upsert::Error> {
+        let stage = flags.stage();
+        let same_path = self.entry_range_by_path(path);
+        if same_path.is_empty() {
+            if let Some(existing) = self.directory_file_conflict(path) {
+                return Err(crate::upsert::Error::DirectoryFileConflict {
+                    path: path.to_owned(),
+                    existing,
+                });
+            }
+        }
+        self.invalidate_extensions();
+        for idx in same_path.rev() {
+            if (self.entries[idx].stage() == 0) != (stage == 0) {
+                let entry = self.entries.remove(idx);
+                self.record_resolve_undo(&entry);
+            }
+        }
+
+        let pos = self
+            .entries
+            .binary_search_by(|e| e.path(self).cmp(path).then_with(|| e.stage().cmp(&stage)));
+        let (idx, path) = match pos {
+            Ok(idx) => (idx, self.entries[idx].path.clone()),
+            Err(idx) => {
+                let start = self.path_backing.len();
+                self.path_backing.extend_from_slice(path);
+                (idx, start..self.path_backing.len())
+            }
+        };
+        let entry = Entry {
+            stat,
+            id,
+            flags: flags & !entry::Flags::PATH_LEN,
+            mode,
+            path,
+        };
+        match pos {
+            Ok(_) => self.entries[idx] = entry,
+            Err(_) => self.entries.insert(idx, entry),
+        }
+        Ok(idx)
+    }
+
+    /// Return the range of indices into [`entries()`][State::entries()] of all entries with `path`, in any stage.
+    fn entry_range_by_path(&self, path: &BStr) -> std::ops::Range<usize> {
+        let start = self.entries.partition_point(|e| e.path(self) < path);
+        let end = start + self.entries[start..].partition_point(|e| e.path(self) == path);
+        start..end
+    }
+
+    /// Return the path of an entry that is a leading directory of `path`, or that lies in the directory `path`.
+    fn directory_file_conflict(&self, path: &BStr) -> Option<BString> {
+        let mut leading_dir = path;
+        while let Some(pos) = leading_dir.rfind_byte(b'/') {
+            leading_dir = leading_dir[..pos].as_bstr();
+            if !self.entry_range_by_path(leading_dir).is_empty() {
+                return Some(leading_dir.to_owned());
+            }
+        }
+        let mut dir = path.to_owned();
+        dir.push(b'/');
+        let start = self.entries.partition_point(|e| e.path(self) < dir.as_bstr());
+        self.entries
+            .get(start)
+            .map(|e| e.path(self))
+            .filter(|entry_path| entry_path.starts_with(&dir))
+            .map(ToOwned::to_owned)
+    }
+
+    /// Drop all extensions whose data refers to entries or paths, which are the cached trees, the untracked cache and the
+    /// file system monitor state. The resolve-undo extension is updated instead.
+    fn invalidate_extensions(&mut self) {
+        self.tree = None;
+        self.untracked = None;
+        self.fs_monitor = None;
+    }
+
+    /// Record the unmerged `entry` as one of the stages of its path in the resolve-undo extension.
+    fn record_resolve_undo(&mut self, entry: &Entry) {
+        let stage = entry.stage();
+        if stage == 0 {
+            return;
+        }
+        let name = entry.path(self).to_owned();
+        let paths = self.resolve_undo.get_or_insert_with(Vec::new);
+        let idx = match paths.iter().position(|p| p.name == name) {
+            Some(idx) => idx,
+            None => {
+                paths.push(extension::resolve_undo::ResolvePath {
+                    name,
+                    stages: [None, None, None],
+                });
+                paths.len() - 1
+            }
+        };
+        paths[idx].stages[stage as usize - 1] = Some(extension::resolve_undo::Stage {
+            mode: entry.mode.bits(),
+            id: entry.id,
+        });
+    }
+
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
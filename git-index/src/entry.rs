@@ -20,6 +20,19 @@ pub(crate) mod mode {
         pub fn is_sparse(&self) -> bool {
             *self == Self::DIR
         }
+
+        /// Return the mode of tree entries corresponding to this mode, or `None` if it isn't one of the known modes.
+        pub fn to_tree_entry_mode(&self) -> Option<git_object::tree::EntryMode> {
+            use git_object::tree::EntryMode;
+            Some(match *self {
+                Self::DIR => EntryMode::Tree,
+                Self::FILE => EntryMode::Blob,
+                Self::FILE_EXECUTABLE => EntryMode::BlobExecutable,
+                Self::SYMLINK => EntryMode::Link,
+                Self::COMMIT => EntryMode::Commit,
+                _ => return None,
+            })
+        }
     }
 }
 
@@ -51,6 +64,12 @@ pub(crate) mod at_rest {
         pub fn to_flags(self) -> Option<super::Flags> {
             super::Flags::from_bits((self.bits as u32) << 16)
         }
+
+        pub fn from_flags(flags: super::Flags) -> Self {
+            Self::from_bits_truncate(
+                ((flags & (super::Flags::INTENT_TO_ADD | super::Flags::SKIP_WORKTREE)).bits >> 16) as u16,
+            )
+        }
     }
 
     impl Flags {
@@ -58,6 +77,10 @@ pub(crate) mod at_rest {
             super::Flags::from_bits((self & (Flags::PATH_LEN | Flags::STAGE_MASK | Flags::ASSUME_VALID)).bits as u32)
                 .expect("PATHLEN is part of memory representation")
         }
+
+        pub fn from_memory(flags: super::Flags) -> Self {
+            Self::from_bits_truncate((flags & (super::Flags::STAGE_MASK | super::Flags::ASSUME_VALID)).bits as u16)
+        }
     }
 
     #[cfg(test)]
@@ -111,7 +134,7 @@ impl Flags {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[derive(Default, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
     /// The amount of seconds elapsed since EPOCH
@@ -120,7 +143,7 @@ pub struct Time {
     pub nsecs: u32,
 }
 
-#[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[derive(Default, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stat {
     pub mtime: Time,
@@ -5,11 +5,25 @@ use crate::{
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
+/// The token stored in the extension, identifying the point in time at which the file system monitor was last queried.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
+    /// Used with hook protocol version 1, the time of the last query.
     V1 { nanos_since_1970: u64 },
+    /// Used with hook protocol version 2, an opaque token provided by the file system monitor itself.
     V2 { token: BString },
 }
 
+impl Token {
+    /// The version of the hook protocol this token is meant to be used with.
+    pub fn version(&self) -> u8 {
+        match self {
+            Token::V1 { .. } => 1,
+            Token::V2 { .. } => 2,
+        }
+    }
+}
+
 pub const SIGNATURE: Signature = *b"FSMN";
 
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
@@ -35,3 +49,16 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
 
     FsMonitor { token, entry_dirty }.into()
 }
+
+impl FsMonitor {
+    /// The token at which the file system monitor was last queried.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Return the bitmap of entries which are not known to be unchanged according to the file system monitor,
+    /// with one bit per index entry.
+    pub fn entry_dirty(&self) -> &git_bitmap::ewah::Vec {
+        &self.entry_dirty
+    }
+}
@@ -46,7 +46,6 @@ pub struct UntrackedCache {
     directories: Vec<untracked_cache::Directory>,
 }
 
-#[allow(dead_code)]
 pub struct FsMonitor {
     token: fs_monitor::Token,
     /// if a bit is true, the resepctive entry is NOT valid as per the fs monitor.
@@ -55,7 +54,7 @@ pub struct FsMonitor {
 
 mod iter;
 
-pub(crate) mod fs_monitor;
+pub mod fs_monitor;
 
 pub(crate) mod decode;
 
@@ -8,19 +8,17 @@ use crate::{
 
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
-    name: BString,
+    pub name: BString,
 
     /// 0 = ancestor/common, 1 = ours, 2 = theirs
-    stages: [Option<Stage>; 3],
+    pub stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
 pub struct Stage {
-    mode: u32,
-    id: ObjectId,
+    pub mode: u32,
+    pub id: ObjectId,
 }
 
 pub const SIGNATURE: Signature = *b"REUC";
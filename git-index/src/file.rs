@@ -20,3 +20,4 @@ mod impls {
 
 pub mod init;
 pub mod verify;
+pub mod write;
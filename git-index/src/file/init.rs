@@ -43,3 +43,16 @@ impl File {
         Ok(File { state, path, checksum })
     }
 }
+
+impl File {
+    /// Consume `state` and pretend it was read from `path`, to be able to [write][File::write()] it there.
+    ///
+    /// The checksum is null until the file is written.
+    pub fn from_state(state: State, path: impl Into<PathBuf>) -> Self {
+        File {
+            state,
+            path: path.into(),
+            checksum: git_hash::Kind::Sha1.null(),
+        }
+    }
+}
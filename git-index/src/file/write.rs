@@ -0,0 +1,40 @@
+use std::io::Write;
+
+use crate::{write, File};
+
+mod error {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            Io(err: std::io::Error) {
+                display("An IO error occurred while writing the index")
+                source(err)
+                from()
+            }
+            AcquireLock(err: git_lock::acquire::Error) {
+                display("Could not acquire the lock for writing the index file")
+                source(err)
+                from()
+            }
+        }
+    }
+}
+pub use error::Error;
+
+impl File {
+    /// Write the index to the file at our [`path`][File::path] through a lock file, failing if the lock is held by
+    /// someone else, and update our checksum to match what was written.
+    ///
+    /// See [`State::write_to()`][crate::State::write_to()] for limitations.
+    pub fn write(&mut self, options: write::Options) -> Result<(), Error> {
+        let mut lock =
+            git_lock::File::acquire_to_update_resource(&self.path, git_lock::acquire::Fail::Immediately, None)?;
+        let (_version, checksum) = self.state.write_to(&mut lock, options)?;
+        lock.flush()?;
+        lock.commit().map_err(|err| err.error)?;
+        self.checksum = checksum;
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+//! A client for the `core.fsmonitor` hook protocol, which is also spoken by watchman integration scripts.
+//!
+//! It allows to learn which paths changed since a [token][crate::extension::fs_monitor::Token] obtained previously,
+//! avoiding a full traversal of the work tree.
+use std::{
+    ffi::OsStr,
+    process::{Command, Stdio},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{extension::fs_monitor::Token, State};
+
+pub mod query {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            Spawn(err: std::io::Error) {
+                display("The fsmonitor hook could not be launched")
+                source(err)
+                from()
+            }
+            ExitStatus(status: std::process::ExitStatus) {
+                display("The fsmonitor hook failed with {}", status)
+            }
+            MissingToken {
+                display("The fsmonitor hook did not provide a token in its response even though protocol version 2 was used")
+            }
+        }
+    }
+}
+
+pub mod validate {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            EntryCountMismatch { bitmap: usize, entries: usize } {
+                display("The fsmonitor extension tracks {} entries, but the index has only {}", bitmap, entries)
+            }
+        }
+    }
+}
+
+/// The paths that changed according to the file system monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Changes {
+    /// The monitor couldn't provide exact information and everything must be considered changed.
+    Everything,
+    /// Only the given paths changed, with paths ending in a slash indicating directories whose content changed recursively.
+    Paths(Vec<BString>),
+}
+
+/// The result of querying the file system monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The token to store for use in the next query.
+    pub token: Token,
+    /// The changes that happened since the token passed in the query.
+    pub changes: Changes,
+}
+
+/// Ask the fsmonitor hook `program`, typically configured in `core.fsmonitor`, for changes since `token`, with
+/// `now_nanos_since_1970` being the time at which the query starts which is used as new token for protocol version 1.
+///
+/// The hook is invoked using the protocol version the `token` was obtained with, and runs with the `cwd` set to the work tree.
+pub fn query(
+    program: impl AsRef<OsStr>,
+    work_tree: impl AsRef<std::path::Path>,
+    token: &Token,
+    now_nanos_since_1970: u64,
+) -> Result<Outcome, query::Error> {
+    let mut cmd = Command::new(program);
+    cmd.current_dir(work_tree)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .arg(token.version().to_string());
+    match token {
+        Token::V1 { nanos_since_1970 } => cmd.arg(nanos_since_1970.to_string()),
+        Token::V2 { token } => cmd.arg(&*token.to_os_str_lossy()),
+    };
+    let output = cmd.output()?;
+    if !output.status.success() {
+        return Err(query::Error::ExitStatus(output.status));
+    }
+    parse_response(token.version(), &output.stdout, now_nanos_since_1970)
+}
+
+/// Parse the `response` of a hook which was called with protocol `version`.
+///
+/// For version 1 no token is returned by the hook, which is why `now_nanos_since_1970` is used to create a new one.
+pub fn parse_response(version: u8, response: &[u8], now_nanos_since_1970: u64) -> Result<Outcome, query::Error> {
+    let mut tokens = response.split(|b| *b == 0);
+    let token = if version == 1 {
+        Token::V1 {
+            nanos_since_1970: now_nanos_since_1970,
+        }
+    } else {
+        match tokens.next() {
+            Some(token) if !token.is_empty() => Token::V2 { token: token.into() },
+            _ => return Err(query::Error::MissingToken),
+        }
+    };
+
+    let mut paths = Vec::new();
+    for path in tokens.filter(|p| !p.is_empty()) {
+        if path == b"/" {
+            return Ok(Outcome {
+                token,
+                changes: Changes::Everything,
+            });
+        }
+        paths.push(path.into());
+    }
+    Ok(Outcome {
+        token,
+        changes: Changes::Paths(paths),
+    })
+}
+
+impl Changes {
+    /// Return true if `path` of an index entry is affected by these changes.
+    pub fn contains(&self, path: &BStr) -> bool {
+        match self {
+            Changes::Everything => true,
+            Changes::Paths(paths) => paths.iter().any(|changed| {
+                if changed.ends_with(b"/") {
+                    path.starts_with(changed)
+                } else {
+                    path == changed || (path.starts_with(changed) && path.get(changed.len()) == Some(&b'/'))
+                }
+            }),
+        }
+    }
+}
+
+impl State {
+    /// Return the indices of all entries which need to be checked for changes on disk, as they are either marked
+    /// as dirty in the fsmonitor extension or are affected by `changes`.
+    ///
+    /// Without an fsmonitor extension, all entries are returned.
+    /// It's an error if the extension tracks more entries than this index has, as it doesn't belong to it. It may track less
+    /// though, as git only stores the bits up to the last dirty entry.
+    pub fn fs_monitor_dirty_entries(&self, changes: &Changes) -> Result<Vec<usize>, validate::Error> {
+        let ext = match self.fs_monitor() {
+            Some(ext) if !matches!(changes, Changes::Everything) => ext,
+            _ => return Ok((0..self.entries.len()).collect()),
+        };
+        let bitmap = ext.entry_dirty();
+        if bitmap.num_bits() > self.entries.len() {
+            return Err(validate::Error::EntryCountMismatch {
+                bitmap: bitmap.num_bits(),
+                entries: self.entries.len(),
+            });
+        }
+        let mut dirty = vec![false; self.entries.len()];
+        bitmap.for_each_set_bit(|idx| {
+            *dirty.get_mut(idx)? = true;
+            Some(())
+        });
+        Ok(self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| (dirty[idx] || changes.contains(entry.path(self))).then(|| idx))
+            .collect())
+    }
+}
@@ -0,0 +1,77 @@
+use filetime::FileTime;
+use git_object::{tree::EntryMode, TreeRefIter};
+use git_traverse::tree::{breadthfirst, recorder, Recorder};
+
+use crate::{entry, Entry, State, Version};
+
+impl State {
+    /// Create an index [`State`] with one entry for each blob, symlink and submodule reachable from the tree at `tree`,
+    /// similar to what `git read-tree` would produce, using `find` to obtain trees from an object database.
+    ///
+    /// Entries are sorted like they would be in an index file, and their stat information is empty as they don't exist on disk yet.
+    pub fn from_tree<Find>(tree: &git_hash::oid, mut find: Find) -> Result<Self, breadthfirst::Error>
+    where
+        Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+    {
+        let mut buf = Vec::new();
+        let root = find(tree, &mut buf).ok_or_else(|| breadthfirst::Error::NotFound { oid: tree.to_owned() })?;
+        let mut recorder = Recorder::default();
+        breadthfirst(root, breadthfirst::State::default(), &mut find, &mut recorder)?;
+
+        let mut records = recorder.records;
+        records.retain(|e| e.mode.is_no_tree());
+        records.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+
+        let mut path_backing = Vec::with_capacity(records.iter().map(|e| e.filepath.len()).sum());
+        let entries = records
+            .into_iter()
+            .map(|recorder::Entry { mode, filepath, oid }| {
+                let start = path_backing.len();
+                path_backing.extend_from_slice(&filepath);
+                Entry {
+                    stat: empty_stat(),
+                    id: oid,
+                    flags: entry::Flags::empty(),
+                    mode: mode_of(mode),
+                    path: start..path_backing.len(),
+                }
+            })
+            .collect();
+
+        Ok(State {
+            timestamp: FileTime::now(),
+            version: Version::V2,
+            entries,
+            path_backing,
+            is_sparse: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        })
+    }
+}
+
+fn mode_of(mode: EntryMode) -> entry::Mode {
+    match mode {
+        EntryMode::Tree => entry::Mode::DIR,
+        EntryMode::Blob => entry::Mode::FILE,
+        EntryMode::BlobExecutable => entry::Mode::FILE_EXECUTABLE,
+        EntryMode::Link => entry::Mode::SYMLINK,
+        EntryMode::Commit => entry::Mode::COMMIT,
+    }
+}
+
+fn empty_stat() -> entry::Stat {
+    let time = entry::Time { secs: 0, nsecs: 0 };
+    entry::Stat {
+        mtime: time,
+        ctime: time,
+        dev: 0,
+        ino: 0,
+        uid: 0,
+        gid: 0,
+        size: 0,
+    }
+}
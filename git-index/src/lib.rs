@@ -18,10 +18,33 @@ pub mod entry;
 
 mod access;
 
+///
+pub mod upsert {
+    use bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`State::upsert_entry()`][crate::State::upsert_entry()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            DirectoryFileConflict { path: BString, existing: BString } {
+                display("'{}' can't be added as it conflicts with '{}', one of which would have to be a directory", path, existing)
+            }
+        }
+    }
+}
+
+mod init;
+
 pub mod decode;
 
 pub mod verify;
 
+pub mod write;
+
+pub mod fs_monitor;
+
 /// All known versions of a git index file.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -60,7 +83,6 @@ pub struct State {
     ///
     /// Note that on platforms that only have a precisions of a second for this time, we will treat all entries with the
     /// same timestamp as this as potentially changed, checking more thoroughly if a change actually happened.
-    #[allow(dead_code)]
     timestamp: FileTime,
     version: Version,
     entries: Vec<Entry>,
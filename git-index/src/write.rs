@@ -0,0 +1,93 @@
+use std::{convert::TryInto, io::Write};
+
+use crate::{entry, State, Version};
+
+/// The options for use when [writing an index][State::write_to()].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// The kind of hash to use for the trailing checksum, which must match the one of the object ids of all entries.
+    pub object_hash: git_hash::Kind,
+}
+
+impl State {
+    /// Serialize this instance to `out` as index file with its trailing checksum, configured by `options`, and return
+    /// the version that was written along with the checksum.
+    ///
+    /// Entries are written in [version 2][Version::V2] unless some of them have extended flags, which requires
+    /// [version 3][Version::V3]. Paths are never delta-encoded, hence states read from [version 4][Version::V4] files are
+    /// written in one of the former versions as well.
+    ///
+    /// Note that extensions aren't written, which causes git to recompute their data as needed. States using a split index
+    /// as indicated by the [`link`][State::link()] extension can't be written faithfully for that reason.
+    pub fn write_to(
+        &self,
+        out: impl std::io::Write,
+        Options { object_hash }: Options,
+    ) -> std::io::Result<(Version, git_hash::ObjectId)> {
+        let version = if self.entries.iter().any(|e| {
+            e.flags
+                .intersects(entry::Flags::INTENT_TO_ADD | entry::Flags::SKIP_WORKTREE)
+        }) {
+            Version::V3
+        } else {
+            Version::V2
+        };
+        let mut out = git_features::hash::Write::new(out, object_hash);
+
+        out.write_all(b"DIRC")?;
+        out.write_all(&(version as u32).to_be_bytes())?;
+        out.write_all(&len_u32(self.entries.len())?.to_be_bytes())?;
+
+        for entry in &self.entries {
+            let path = &self.path_backing[entry.path.clone()];
+            let stat = &entry.stat;
+            for field in &[
+                stat.ctime.secs,
+                stat.ctime.nsecs,
+                stat.mtime.secs,
+                stat.mtime.nsecs,
+                stat.dev,
+                stat.ino,
+                entry.mode.bits(),
+                stat.uid,
+                stat.gid,
+                stat.size,
+            ] {
+                out.write_all(&field.to_be_bytes())?;
+            }
+            out.write_all(entry.id.as_bytes())?;
+
+            let extended = entry::at_rest::FlagsExtended::from_flags(entry.flags);
+            let mut flags = entry::at_rest::Flags::from_memory(entry.flags);
+            flags |= entry::at_rest::Flags::from_bits_truncate(
+                path.len().min(entry::at_rest::Flags::PATH_LEN.bits() as usize) as u16,
+            );
+            if !extended.is_empty() {
+                flags.insert(entry::at_rest::Flags::EXTENDED);
+            }
+            out.write_all(&flags.bits().to_be_bytes())?;
+            if !extended.is_empty() {
+                out.write_all(&extended.bits().to_be_bytes())?;
+            }
+            out.write_all(path)?;
+
+            let entry_len =
+                10 * 4 + entry.id.as_bytes().len() + 2 + if extended.is_empty() { 0 } else { 2 } + path.len();
+            let padded_len = (entry_len + 8) & !7;
+            out.write_all(&[0; 8][..padded_len - entry_len])?;
+        }
+
+        let checksum = git_hash::ObjectId::from(out.hash.digest());
+        out.inner.write_all(checksum.as_bytes())?;
+        Ok((version, checksum))
+    }
+}
+
+fn len_u32(len: usize) -> std::io::Result<u32> {
+    len.try_into().map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Index files can't hold more than 2^32 entries",
+        )
+    })
+}
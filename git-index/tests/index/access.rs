@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use bstr::ByteSlice;
+use git_index::{entry, State};
+use git_testtools::hex_to_id;
+
+fn loose_state(name: &str) -> State {
+    let path = git_testtools::fixture_path(Path::new("loose_index").join(name).with_extension("git-index"));
+    git_index::File::at(path, git_index::decode::Options::default())
+        .unwrap()
+        .state
+}
+
+fn upsert(state: &mut State, path: &str, flags: entry::Flags) -> Result<usize, git_index::upsert::Error> {
+    state.upsert_entry(
+        path.as_bytes().as_bstr(),
+        Default::default(),
+        hex_to_id("0000000000000000000000000000000000000001"),
+        flags,
+        entry::Mode::FILE,
+    )
+}
+
+#[test]
+fn changing_entries_drops_extensions_referring_to_them() {
+    for fixture in ["FSMN", "UNTR"] {
+        let mut state = loose_state(fixture);
+        assert!(state.fs_monitor().is_some() || state.untracked().is_some());
+        upsert(&mut state, "new-file", entry::Flags::empty()).unwrap();
+        assert!(state.fs_monitor().is_none(), "{}", fixture);
+        assert!(state.untracked().is_none(), "{}", fixture);
+        assert!(state.tree().is_none(), "{}", fixture);
+
+        let mut state = loose_state(fixture);
+        state.remove_entry_at_index(0);
+        assert!(state.fs_monitor().is_none() && state.untracked().is_none() && state.tree().is_none());
+    }
+}
+
+#[test]
+fn adding_a_merged_entry_resolves_the_conflict_and_records_it_for_undo() {
+    let mut state = loose_state("conflicting-file");
+    let path = state.entries()[0].path(&state).to_owned();
+    let unmerged: Vec<_> = state.entries().iter().map(|e| (e.stage(), e.id)).collect();
+    assert_eq!(
+        unmerged.iter().map(|(stage, _)| *stage).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!(state.resolve_undo().is_none());
+
+    let idx = upsert(&mut state, path.to_str().unwrap(), entry::Flags::empty()).unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(state.entries().len(), 1, "all unmerged entries are replaced");
+    assert_eq!(state.entries()[0].stage(), 0);
+    state.verify_entries().unwrap();
+
+    let resolve_undo = state.resolve_undo().expect("recorded");
+    assert_eq!(resolve_undo.len(), 1);
+    assert_eq!(resolve_undo[0].name, path);
+    for (stage, id) in unmerged {
+        assert_eq!(
+            resolve_undo[0].stages[stage as usize - 1].as_ref().map(|s| s.id),
+            Some(id)
+        );
+    }
+
+    upsert(
+        &mut state,
+        path.to_str().unwrap(),
+        entry::Flags::from_bits(2 << 12).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        state.entries().iter().map(|e| e.stage()).collect::<Vec<_>>(),
+        vec![2],
+        "adding an unmerged entry replaces the merged one"
+    );
+}
+
+#[test]
+fn paths_that_would_be_both_a_file_and_a_directory_are_rejected() {
+    let mut state = git_index::File::at(crate::fixture_path("v4_more_files_IEOT"), Default::default())
+        .unwrap()
+        .state;
+    for (path, existing) in [
+        ("d", "d/a"),
+        ("a/b", "a"),
+        ("d/last/6/x", "d/last/6"),
+        ("d/last", "d/last/123"),
+    ] {
+        match upsert(&mut state, path, entry::Flags::empty()) {
+            Err(git_index::upsert::Error::DirectoryFileConflict {
+                path: actual_path,
+                existing: actual_existing,
+            }) => {
+                assert_eq!(actual_path, path);
+                assert_eq!(actual_existing, existing);
+            }
+            Ok(_) => panic!("{} must not be added", path),
+        }
+    }
+    assert_eq!(state.entries().len(), 10, "nothing was added");
+    upsert(&mut state, "d/lastly", entry::Flags::empty()).expect("no conflict with 'd/last'");
+    upsert(&mut state, "b", entry::Flags::empty()).expect("replacing entries is fine");
+    assert_eq!(state.entries().len(), 11);
+    state.verify_entries().unwrap();
+}
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use git_index::{
+    extension::fs_monitor::Token,
+    fs_monitor::{parse_response, Changes},
+};
+
+#[test]
+fn parse_response_v2_with_paths() {
+    let outcome = parse_response(2, b"new-token\0a\0d/\0\0", 0).unwrap();
+    assert_eq!(
+        outcome.token,
+        Token::V2 {
+            token: "new-token".into()
+        }
+    );
+    assert_eq!(outcome.changes, Changes::Paths(vec!["a".into(), "d/".into()]));
+}
+
+#[test]
+fn parse_response_v2_without_token_is_an_error() {
+    assert!(parse_response(2, b"", 0).is_err());
+}
+
+#[test]
+fn parse_response_v1_uses_the_given_time_as_token() {
+    let outcome = parse_response(1, b"a\0/\0", 42).unwrap();
+    assert_eq!(outcome.token, Token::V1 { nanos_since_1970: 42 });
+    assert_eq!(
+        outcome.changes,
+        Changes::Everything,
+        "a single slash invalidates everything"
+    );
+}
+
+#[test]
+fn changes_match_files_and_directories() {
+    let changes = Changes::Paths(vec!["a".into(), "d/".into(), "e".into()]);
+    assert!(changes.contains("a".into()));
+    assert!(!changes.contains("ab".into()));
+    assert!(changes.contains("d/a".into()));
+    assert!(
+        changes.contains("e/f".into()),
+        "paths may also be directories without trailing slash"
+    );
+    assert!(!changes.contains("b".into()));
+}
+
+fn loose_file(name: &str) -> git_index::File {
+    let path = git_testtools::fixture_path(Path::new("loose_index").join(name).with_extension("git-index"));
+    git_index::File::at(path, git_index::decode::Options::default()).unwrap()
+}
+
+fn dirty_paths(file: &git_index::File, changes: &Changes) -> Vec<String> {
+    file.fs_monitor_dirty_entries(changes)
+        .unwrap()
+        .into_iter()
+        .map(|idx| file.entries()[idx].path(file).to_string())
+        .collect()
+}
+
+#[test]
+fn dirty_entries_are_validated_against_the_extension() {
+    let file = loose_file("FSMN");
+    let all = vec![
+        "dir1/modified",
+        "dir1/tracked",
+        "dir2/modified",
+        "dir2/tracked",
+        "modified",
+        "tracked",
+    ];
+    assert_eq!(dirty_paths(&file, &Changes::Everything), all);
+    assert_eq!(
+        dirty_paths(&file, &Changes::Paths(Vec::new())),
+        all,
+        "all entries are marked dirty in the extension"
+    );
+}
+
+#[test]
+fn dirty_entries_are_those_marked_in_the_extension_or_changed() {
+    let file = loose_file("FSMN-with-dirty-entries");
+    assert_eq!(
+        file.fs_monitor().expect("present").entry_dirty().num_bits(),
+        5,
+        "git only stores bits up to the last dirty entry"
+    );
+    assert_eq!(
+        dirty_paths(&file, &Changes::Paths(Vec::new())),
+        vec!["dir1/modified", "modified"]
+    );
+    assert_eq!(
+        dirty_paths(&file, &Changes::Paths(vec!["dir2/".into()])),
+        vec!["dir1/modified", "dir2/modified", "dir2/tracked", "modified"]
+    );
+}
@@ -0,0 +1,31 @@
+use git_index::State;
+use git_odb::FindExt;
+
+#[test]
+fn from_tree_produces_the_same_entries_as_git_add() {
+    for fixture in ["v2", "v2_more_files", "v4_more_files_IEOT"] {
+        let index_path = crate::fixture_path(fixture);
+        let expected = git_index::File::at(&index_path, Default::default()).unwrap();
+        let tree_id = expected.tree().expect("tree extension present").id;
+        let odb = git_odb::at(index_path.parent().unwrap().join("objects")).unwrap();
+
+        let actual = State::from_tree(&tree_id, |oid, buf| odb.find_tree_iter(oid, buf).ok()).unwrap();
+
+        assert_eq!(actual.entries().len(), expected.entries().len(), "{}", fixture);
+        for (actual_entry, expected_entry) in actual.entries().iter().zip(expected.entries()) {
+            assert_eq!(actual_entry.path(&actual), expected_entry.path(&expected.state));
+            assert_eq!(actual_entry.id, expected_entry.id);
+            assert_eq!(actual_entry.mode, expected_entry.mode);
+            assert_eq!(actual_entry.flags, expected_entry.flags);
+        }
+    }
+}
+
+#[test]
+fn from_tree_fails_if_the_tree_is_missing() {
+    let res = State::from_tree(&git_hash::ObjectId::null(git_hash::Kind::Sha1), |_, _| None);
+    assert!(matches!(
+        res,
+        Err(git_traverse::tree::breadthfirst::Error::NotFound { .. })
+    ));
+}
@@ -1,6 +1,10 @@
 use std::path::{Path, PathBuf};
 
+mod access;
 mod file;
+mod fs_monitor;
+mod init;
+mod write;
 
 pub fn fixture_path(name: &str) -> PathBuf {
     let dir = git_testtools::scripted_fixture_repo_read_only(Path::new("make_index").join(name).with_extension("sh"))
@@ -0,0 +1,69 @@
+use git_index::{entry, State, Version};
+
+fn assert_entries_eq(actual: &State, expected: &State) {
+    assert_eq!(actual.entries().len(), expected.entries().len());
+    for (actual_entry, expected_entry) in actual.entries().iter().zip(expected.entries()) {
+        assert_eq!(actual_entry.path(actual), expected_entry.path(expected));
+        assert_eq!(actual_entry.id, expected_entry.id);
+        assert_eq!(actual_entry.mode, expected_entry.mode);
+        assert_eq!(actual_entry.flags, expected_entry.flags);
+        assert!(actual_entry.stat == expected_entry.stat);
+    }
+}
+
+#[test]
+fn roundtrips_entries_of_all_versions() {
+    for (fixture, expected_version) in [
+        ("V2_empty", Version::V2),
+        ("v2", Version::V2),
+        ("v2_more_files", Version::V2),
+        ("v4_more_files_IEOT", Version::V2),
+    ] {
+        let expected = git_index::File::at(crate::fixture_path(fixture), Default::default()).unwrap();
+        let mut buf = Vec::new();
+        let (version, checksum) = expected.write_to(&mut buf, Default::default()).unwrap();
+        assert_eq!(version, expected_version, "{}", fixture);
+        assert_eq!(&buf[buf.len() - 20..], checksum.as_bytes());
+
+        let (actual, actual_checksum) =
+            State::from_bytes(&buf, expected.timestamp(), Default::default()).expect("valid index");
+        assert_eq!(actual_checksum, checksum);
+        assert_eq!(actual.version(), version);
+        assert!(actual.tree().is_none(), "extensions are not written");
+        actual.verify_entries().unwrap();
+        assert_entries_eq(&actual, &expected);
+    }
+}
+
+#[test]
+fn extended_flags_require_version_3() {
+    let mut state = git_index::File::at(crate::fixture_path("v2_more_files"), Default::default())
+        .unwrap()
+        .state;
+    state.entries_mut()[0].flags.insert(entry::Flags::SKIP_WORKTREE);
+
+    let mut buf = Vec::new();
+    let (version, _) = state.write_to(&mut buf, Default::default()).unwrap();
+    assert_eq!(version, Version::V3);
+
+    let (actual, _) = State::from_bytes(&buf, state.timestamp(), Default::default()).unwrap();
+    assert_eq!(actual.version(), Version::V3);
+    assert_entries_eq(&actual, &state);
+}
+
+#[test]
+fn file_write_creates_a_verifiable_index_file() {
+    let dir = git_testtools::tempfile::tempdir().unwrap();
+    let state = git_index::File::at(crate::fixture_path("v2_more_files"), Default::default())
+        .unwrap()
+        .state;
+    let path = dir.path().join("index");
+    let mut file = git_index::File::from_state(state, &path);
+    file.write(Default::default()).unwrap();
+
+    let actual = git_index::File::at(&path, Default::default()).unwrap();
+    actual.verify_integrity().unwrap();
+    assert_eq!(actual.checksum, file.checksum);
+    assert_entries_eq(&actual, &file);
+    assert!(!path.with_extension("lock").exists(), "the lock is released");
+}
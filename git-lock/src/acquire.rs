@@ -1,13 +1,14 @@
 use std::{
     fmt,
+    io::Write,
     path::{Path, PathBuf},
     time::Duration,
 };
 
-use git_tempfile::{AutoRemove, ContainingDirectory};
+use git_tempfile::{handle::Closed, AutoRemove, ContainingDirectory};
 use quick_error::quick_error;
 
-use crate::{backoff, File, Marker, DOT_LOCK_SUFFIX};
+use crate::{backoff, stale, File, Marker, DOT_LOCK_SUFFIX};
 
 /// Describe what to do if a lock cannot be obtained as it's already held elsewhere.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -62,10 +63,40 @@ impl File {
         mode: Fail,
         boundary_directory: Option<PathBuf>,
     ) -> Result<File, Error> {
-        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, |p, d, c| {
+        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, None, |p, d, c| {
             git_tempfile::writable_at(p, d, c)
         })?;
         Ok(File {
+            pid_file: None,
+            inner: handle,
+            lock_path,
+        })
+    }
+
+    /// Like [`acquire_to_update_resource()`][File::acquire_to_update_resource()], but remove the existing lock file if it
+    /// is stale according to `policy` to acquire the lock, after passing it to `audit`.
+    ///
+    /// As the lock file holds the new content of the resource, the id of the current process is written to the hidden file
+    /// `.<name>.lock.pid` next to it instead, allowing others to break the lock if it isn't running anymore.
+    pub fn acquire_to_update_resource_or_break_stale(
+        at_path: impl AsRef<Path>,
+        mode: Fail,
+        boundary_directory: Option<PathBuf>,
+        policy: stale::Policy,
+        mut audit: impl FnMut(&stale::Broken),
+    ) -> Result<File, Error> {
+        let (lock_path, handle) = lock_with_mode(
+            at_path.as_ref(),
+            mode,
+            boundary_directory,
+            Some(BreakStale {
+                policy,
+                audit: &mut audit,
+            }),
+            |p, d, c| git_tempfile::writable_at(p, d, c),
+        )?;
+        Ok(File {
+            pid_file: Some(write_pid_file(&lock_path)?),
             inner: handle,
             lock_path,
         })
@@ -83,10 +114,44 @@ impl Marker {
         mode: Fail,
         boundary_directory: Option<PathBuf>,
     ) -> Result<Marker, Error> {
-        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, |p, d, c| {
+        let (lock_path, handle) = lock_with_mode(at_path.as_ref(), mode, boundary_directory, None, |p, d, c| {
             git_tempfile::mark_at(p, d, c)
         })?;
         Ok(Marker {
+            pid_file: None,
+            created_from_file: false,
+            inner: handle,
+            lock_path,
+        })
+    }
+
+    /// Like [`acquire_to_hold_resource()`][Marker::acquire_to_hold_resource()], but remove the existing lock file if it
+    /// is stale according to `policy` to acquire the lock, after passing it to `audit`.
+    ///
+    /// The lock file contains the id of the current process, allowing others to break the lock if it isn't running anymore.
+    pub fn acquire_to_hold_resource_or_break_stale(
+        at_path: impl AsRef<Path>,
+        mode: Fail,
+        boundary_directory: Option<PathBuf>,
+        policy: stale::Policy,
+        mut audit: impl FnMut(&stale::Broken),
+    ) -> Result<Marker, Error> {
+        let (lock_path, handle) = lock_with_mode(
+            at_path.as_ref(),
+            mode,
+            boundary_directory,
+            Some(BreakStale {
+                policy,
+                audit: &mut audit,
+            }),
+            |p, d, c| {
+                let mut file = git_tempfile::writable_at(p, d, c)?;
+                writeln!(file, "{}", std::process::id())?;
+                file.close()
+            },
+        )?;
+        Ok(Marker {
+            pid_file: None,
             created_from_file: false,
             inner: handle,
             lock_path,
@@ -104,22 +169,58 @@ fn dir_cleanup(boundary: Option<PathBuf>) -> (ContainingDirectory, AutoRemove) {
     }
 }
 
+/// Write the id of the current process into the pid file of the lock at `lock_path`, which is held by us.
+fn write_pid_file(lock_path: &Path) -> std::io::Result<git_tempfile::Handle<Closed>> {
+    let pid_path = stale::pid_path(lock_path);
+    let create = || git_tempfile::writable_at(&pid_path, ContainingDirectory::Exists, AutoRemove::Tempfile);
+    let mut file = match create() {
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+            // As we hold the lock, the pid file was left behind by a previous owner.
+            std::fs::remove_file(&pid_path)?;
+            create()?
+        }
+        res => res?,
+    };
+    writeln!(file, "{}", std::process::id())?;
+    file.close()
+}
+
+struct BreakStale<'a> {
+    policy: stale::Policy,
+    audit: &'a mut dyn FnMut(&stale::Broken),
+}
+
 fn lock_with_mode<T>(
     resource: &Path,
     mode: Fail,
     boundary_directory: Option<PathBuf>,
+    mut break_stale: Option<BreakStale<'_>>,
     try_lock: impl Fn(&Path, ContainingDirectory, AutoRemove) -> std::io::Result<T>,
 ) -> Result<(PathBuf, T), Error> {
     use std::io::ErrorKind::*;
     let (directory, cleanup) = dir_cleanup(boundary_directory);
     let lock_path = add_lock_suffix(resource);
+    let mut try_lock_or_break_stale =
+        |directory: ContainingDirectory, cleanup: AutoRemove| match try_lock(&lock_path, directory, cleanup.clone()) {
+            Err(err) if err.kind() == AlreadyExists => match break_stale.as_mut() {
+                Some(BreakStale { policy, audit }) => {
+                    if stale::break_if_stale(&lock_path, policy, &mut **audit)? {
+                        try_lock(&lock_path, directory, cleanup)
+                    } else {
+                        Err(err)
+                    }
+                }
+                None => Err(err),
+            },
+            res => res,
+        };
     let mut attempts = 1;
     match mode {
-        Fail::Immediately => try_lock(&lock_path, directory, cleanup),
+        Fail::Immediately => try_lock_or_break_stale(directory, cleanup),
         Fail::AfterDurationWithBackoff(time) => {
             for wait in backoff::Exponential::default_with_random().until_no_remaining(time) {
                 attempts += 1;
-                match try_lock(&lock_path, directory, cleanup.clone()) {
+                match try_lock_or_break_stale(directory, cleanup.clone()) {
                     Ok(v) => return Ok((lock_path, v)),
                     #[cfg(windows)]
                     Err(err) if err.kind() == AlreadyExists || err.kind() == PermissionDenied => {
@@ -134,7 +235,7 @@ fn lock_with_mode<T>(
                     Err(err) => return Err(Error::from(err)),
                 }
             }
-            try_lock(&lock_path, directory, cleanup)
+            try_lock_or_break_stale(directory, cleanup)
         }
     }
     .map(|v| (lock_path, v))
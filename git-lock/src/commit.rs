@@ -47,6 +47,8 @@ impl Marker {
             });
         }
         let resource_path = self.resource_path();
+        // Remove it while the lock is held so it can't be mistaken for the one of the next owner of the lock.
+        self.pid_file.take();
         match self.inner.persist(&resource_path) {
             Ok(_) => Ok(resource_path),
             Err(err) => Err(Error {
@@ -65,6 +67,8 @@ impl File {
     /// and an open file handle on success.
     pub fn commit(mut self) -> Result<(PathBuf, Option<std::fs::File>), Error<Self>> {
         let resource_path = self.resource_path();
+        // Remove it while the lock is held so it can't be mistaken for the one of the next owner of the lock.
+        self.pid_file.take();
         match self.inner.persist(&resource_path) {
             Ok(possibly_file) => Ok((resource_path, possibly_file)),
             Err(err) => Err(Error {
@@ -20,6 +20,7 @@ impl File {
     /// A call to [Marker::commit()] is allowed on the [`Marker`] to write changes back to the resource.
     pub fn close(self) -> std::io::Result<Marker> {
         Ok(Marker {
+            pid_file: self.pid_file,
             inner: self.inner.close()?,
             created_from_file: true,
             lock_path: self.lock_path,
@@ -8,6 +8,7 @@
 //! * consistent naming of lock files
 //! * block the thread (with timeout) or fail immediately if a lock cannot be obtained right away
 //! * commit lock files to atomically put them into the location of the originally locked file
+//! * optionally break stale lock files left behind by crashed processes
 //!
 //! # Limitations
 //!
@@ -28,6 +29,8 @@ pub mod acquire;
 mod backoff;
 ///
 pub mod commit;
+///
+pub mod stale;
 
 /// Locks a resource to eventually be overwritten with the content of this file.
 ///
@@ -35,6 +38,8 @@ pub mod commit;
 #[must_use = "A File that is immediately dropped doesn't allow resource updates"]
 #[derive(Debug)]
 pub struct File {
+    /// Declared first to be removed before the lock file, which may remove its then empty parent directories.
+    pid_file: Option<git_tempfile::Handle<Closed>>,
     inner: git_tempfile::Handle<Writable>,
     lock_path: PathBuf,
 }
@@ -46,6 +51,8 @@ pub struct File {
 #[must_use = "A Marker that is immediately dropped doesn't lock a resource meaningfully"]
 #[derive(Debug)]
 pub struct Marker {
+    /// Declared first to be removed before the lock file, which may remove its then empty parent directories.
+    pid_file: Option<git_tempfile::Handle<Closed>>,
     inner: git_tempfile::Handle<Closed>,
     created_from_file: bool,
     lock_path: PathBuf,
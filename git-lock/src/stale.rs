@@ -0,0 +1,153 @@
+use std::{
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Lock files with more bytes than this can't contain just a process id.
+const MAX_PID_FILE_LEN: u64 = 32;
+
+/// Describe when a lock file held elsewhere is considered to be left behind by a crashed process, to remove it
+/// instead of failing to acquire the lock.
+///
+/// Note that breaking locks is inherently racy, as another process may acquire the lock right after it was considered
+/// stale but before it is removed. Thus the policy should be conservative enough to only match locks that are truly abandoned.
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Policy {
+    /// If set, lock files which weren't modified for longer than the given duration are considered stale.
+    pub older_than: Option<Duration>,
+    /// If true, lock files whose owning process isn't running anymore are considered stale. The id of that process is read
+    /// from the hidden file `.<name>.lock.pid` next to the lock file, or from the lock file itself if it contains nothing else.
+    ///
+    /// Locks acquired while breaking stale locks record the id of the current process to make this possible, with files
+    /// using the `.pid` file and markers the lock file itself.
+    /// Note that this isn't supported on Windows, where all processes are assumed to be alive.
+    pub owner_is_dead: bool,
+}
+
+/// The reason a lock file was considered stale.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Reason {
+    /// The lock file wasn't modified for the given duration, which is longer than [`Policy::older_than`].
+    Age(Duration),
+    /// The lock file contains the id of a process which isn't running anymore.
+    DeadOwner {
+        /// The id of the process that created the lock file.
+        pid: u32,
+    },
+}
+
+/// A stale lock file which was removed to acquire the lock, passed to audit callbacks.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Broken {
+    /// The path of the removed lock file.
+    pub lock_path: PathBuf,
+    /// The reason the lock file was removed.
+    pub reason: Reason,
+}
+
+/// Remove the lock file at `lock_path` if it is stale according to `policy`, after passing it to `audit`.
+///
+/// Return true if the lock file doesn't exist anymore and acquiring the lock should be attempted again.
+pub(crate) fn break_if_stale(lock_path: &Path, policy: &Policy, audit: &mut dyn FnMut(&Broken)) -> io::Result<bool> {
+    let reason = match reason(lock_path, policy) {
+        Ok(Some(reason)) => reason,
+        Ok(None) => return Ok(false),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(err) => return Err(err),
+    };
+    audit(&Broken {
+        lock_path: lock_path.to_owned(),
+        reason,
+    });
+    // The pid file goes first to not remove the one of whoever acquires the lock next.
+    match std::fs::remove_file(pid_path(lock_path)) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    match std::fs::remove_file(lock_path) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
+fn reason(lock_path: &Path, policy: &Policy) -> io::Result<Option<Reason>> {
+    let meta = std::fs::metadata(lock_path)?;
+    if let Some(older_than) = policy.older_than {
+        // Modification times in the future are treated as if the lock was just created.
+        let age = meta.modified()?.elapsed().unwrap_or_default();
+        if age > older_than {
+            return Ok(Some(Reason::Age(age)));
+        }
+    }
+    if policy.owner_is_dead {
+        let pid = match std::fs::read(pid_path(lock_path)) {
+            Ok(content) => parse_pid(&content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound && meta.len() <= MAX_PID_FILE_LEN => {
+                parse_pid(&std::fs::read(lock_path)?)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        if let Some(pid) = pid {
+            if pid != std::process::id() && !process_is_alive(pid) {
+                return Ok(Some(Reason::DeadOwner { pid }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Return the path of the file next to the lock file at `lock_path` which contains the id of its owning process.
+///
+/// It's hidden, which also makes it an invalid reference name that is never mistaken for a reference or its lock.
+pub(crate) fn pid_path(lock_path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(lock_path.file_name().expect("lock files have a name"));
+    name.push(".pid");
+    lock_path.with_file_name(name)
+}
+
+fn parse_pid(content: &[u8]) -> Option<u32> {
+    std::str::from_utf8(content)
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|pid| *pid != 0)
+}
+
+#[cfg(not(windows))]
+fn process_is_alive(pid: u32) -> bool {
+    let pid = match libc::pid_t::try_from(pid) {
+        Ok(pid) => pid,
+        Err(_) => return true,
+    };
+    // SAFETY: sending signal 0 only checks whether the process exists and whether we may signal it.
+    #[allow(unsafe_code)]
+    let res = unsafe { libc::kill(pid, 0) };
+    res == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(windows)]
+fn process_is_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_pid;
+
+    #[test]
+    fn parse_pid_accepts_a_single_non_zero_number() {
+        assert_eq!(parse_pid(b"1234\n"), Some(1234));
+        assert_eq!(parse_pid(b" 42 "), Some(42));
+        assert_eq!(parse_pid(b"0"), None);
+        assert_eq!(parse_pid(b""), None);
+        assert_eq!(parse_pid(b"12 host"), None);
+        assert_eq!(parse_pid(b"ref: refs/heads/main"), None);
+    }
+}
@@ -1,2 +1,3 @@
 mod file;
 mod marker;
+mod stale;
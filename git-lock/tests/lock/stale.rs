@@ -0,0 +1,183 @@
+use std::{io::Write, time::Duration};
+
+use git_lock::{
+    acquire::{Error, Fail},
+    stale::{Broken, Policy, Reason},
+};
+
+#[test]
+fn locks_older_than_the_threshold_are_broken_and_audited() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("packed-refs");
+    std::fs::write(dir.path().join("packed-refs.lock"), b"left behind")?;
+    std::thread::sleep(Duration::from_millis(10));
+
+    let mut broken = Vec::new();
+    let file = git_lock::File::acquire_to_update_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        Policy {
+            older_than: Some(Duration::from_millis(1)),
+            ..Default::default()
+        },
+        |b: &Broken| broken.push(b.clone()),
+    )?;
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].lock_path, file.lock_path());
+    assert!(matches!(broken[0].reason, Reason::Age(age) if age >= Duration::from_millis(10)));
+    Ok(())
+}
+
+#[test]
+fn recent_locks_are_kept() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("packed-refs");
+    let _guard = git_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)?;
+
+    let mut num_broken = 0;
+    let err = git_lock::File::acquire_to_update_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        Policy {
+            older_than: Some(Duration::from_secs(60 * 60)),
+            owner_is_dead: true,
+        },
+        |_: &Broken| num_broken += 1,
+    )
+    .expect_err("the lock is held and not stale");
+    assert!(matches!(err, Error::PermanentlyLocked { .. }));
+    assert_eq!(num_broken, 0, "nothing is audited if no lock was broken");
+    Ok(())
+}
+
+#[test]
+fn markers_record_the_owning_process_which_keeps_them_alive() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("HEAD");
+    let policy = Policy {
+        owner_is_dead: true,
+        ..Default::default()
+    };
+    let marker = git_lock::Marker::acquire_to_hold_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        policy,
+        |_: &Broken| unreachable!("there is no lock to break"),
+    )?;
+    assert_eq!(
+        std::fs::read_to_string(marker.lock_path())?,
+        format!("{}\n", std::process::id())
+    );
+
+    assert!(git_lock::Marker::acquire_to_hold_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        policy,
+        |_: &Broken| unreachable!("the owning process is alive"),
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn files_record_the_owning_process_next_to_the_lock_file_until_committed() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("packed-refs");
+    let pid_path = dir.path().join(".packed-refs.lock.pid");
+    let policy = Policy {
+        owner_is_dead: true,
+        ..Default::default()
+    };
+    let mut file = git_lock::File::acquire_to_update_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        policy,
+        |_: &Broken| unreachable!("there is no lock to break"),
+    )?;
+    assert_eq!(std::fs::read_to_string(&pid_path)?, format!("{}\n", std::process::id()));
+    assert!(git_lock::File::acquire_to_update_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        policy,
+        |_: &Broken| unreachable!("the owning process is alive"),
+    )
+    .is_err());
+
+    file.write_all(b"content")?;
+    file.commit()?;
+    assert_eq!(
+        std::fs::read(&resource)?,
+        b"content",
+        "the pid isn't part of the resource"
+    );
+    assert!(!pid_path.exists(), "the pid file is removed along with the lock");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn files_of_dead_processes_are_broken() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("packed-refs");
+    let mut child = std::process::Command::new("true").spawn()?;
+    let pid = child.id();
+    child.wait()?;
+    std::fs::write(dir.path().join("packed-refs.lock"), b"# pack-refs with: peeled\n")?;
+    std::fs::write(dir.path().join(".packed-refs.lock.pid"), format!("{}\n", pid))?;
+
+    let mut broken = Vec::new();
+    let file = git_lock::File::acquire_to_update_resource_or_break_stale(
+        &resource,
+        Fail::Immediately,
+        None,
+        Policy {
+            owner_is_dead: true,
+            ..Default::default()
+        },
+        |b: &Broken| broken.push(b.reason),
+    )?;
+    assert_eq!(broken, vec![Reason::DeadOwner { pid }]);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(".packed-refs.lock.pid"))?,
+        format!("{}\n", std::process::id()),
+        "the pid file now belongs to the current process"
+    );
+    drop(file);
+    assert_eq!(
+        std::fs::read_dir(dir.path())?.count(),
+        0,
+        "all files are removed on drop"
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn locks_of_dead_processes_are_broken() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let resource = dir.path().join("HEAD");
+    let mut child = std::process::Command::new("true").spawn()?;
+    let pid = child.id();
+    child.wait()?;
+    std::fs::write(dir.path().join("HEAD.lock"), format!("{}\n", pid))?;
+
+    let mut broken = Vec::new();
+    let _marker = git_lock::Marker::acquire_to_hold_resource_or_break_stale(
+        &resource,
+        Fail::AfterDurationWithBackoff(Duration::from_millis(50)),
+        None,
+        Policy {
+            owner_is_dead: true,
+            ..Default::default()
+        },
+        |b: &Broken| broken.push(b.reason),
+    )?;
+    assert_eq!(broken, vec![Reason::DeadOwner { pid }]);
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use bstr::BString;
+use git_hash::ObjectId;
+use quick_error::quick_error;
+use smallvec::SmallVec;
+
+use crate::{encode, trailer, Commit, CommitRef, Tag, WriteTo};
+
+quick_error! {
+    /// The error returned by [`Builder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingSignature(field: &'static str) {
+            display("The {} of the commit must be set", field)
+        }
+        InvalidSignature { field: &'static str, reason: &'static str } {
+            display("The {} of the commit is invalid: {}", field, reason)
+        }
+        HashKindMismatch { id: ObjectId } {
+            display("The parent {} uses a different hash kind than the tree", id)
+        }
+        InvalidEncoding(encoding: BString) {
+            display("The encoding {:?} must be a non-empty name without whitespace", encoding)
+        }
+        InvalidHeaderName(name: BString) {
+            display("Extra header name {:?} must be non-empty, without whitespace and not be a standard header", name)
+        }
+        EmptyHeaderValue(name: BString) {
+            display("The value of extra header {:?} must not be empty", name)
+        }
+        Reencode { encoding: BString, reason: String } {
+            display("The message could not be encoded in {:?}: {}", encoding, reason)
+        }
+        Trailer(err: crate::trailer::Error) {
+            display("A trailer of the commit message is invalid")
+            from()
+            source(err)
+        }
+        RoundTrip {
+            display("The commit would not be decoded into the same commit after serialization")
+        }
+    }
+}
+
+/// A builder for [`Commits`][Commit] which validates all fields so that the resulting commit serializes correctly and
+/// decodes into the same commit again.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    tree: ObjectId,
+    parents: SmallVec<[ObjectId; 1]>,
+    author: Option<git_actor::Signature>,
+    committer: Option<git_actor::Signature>,
+    encoding: Option<BString>,
+    #[cfg(feature = "encoding")]
+    reencode_message: bool,
+    message: BString,
+    extra_headers: Vec<(BString, BString)>,
+    trailers: Vec<trailer::Edit>,
+}
+
+/// The names of headers that are written before the extra headers.
+const STANDARD_HEADERS: &[&[u8]] = &[b"tree", b"parent", b"author", b"committer", b"encoding"];
+
+impl Builder {
+    /// Create a new builder for a commit recording the given `tree`, without parents and with an empty message.
+    pub fn new(tree: impl Into<ObjectId>) -> Self {
+        Builder {
+            tree: tree.into(),
+            parents: Default::default(),
+            author: None,
+            committer: None,
+            encoding: None,
+            #[cfg(feature = "encoding")]
+            reencode_message: false,
+            message: Default::default(),
+            extra_headers: Vec::new(),
+            trailers: Vec::new(),
+        }
+    }
+
+    /// Add `id` as the next parent of the commit.
+    pub fn parent(mut self, id: impl Into<ObjectId>) -> Self {
+        self.parents.push(id.into());
+        self
+    }
+
+    /// Add all `ids` as parents of the commit, in order.
+    pub fn parents(mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.parents.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the `author` of the commit, and the committer as well if it isn't set yet.
+    pub fn author(mut self, author: git_actor::Signature) -> Self {
+        if self.committer.is_none() {
+            self.committer = Some(author.clone());
+        }
+        self.author = Some(author);
+        self
+    }
+
+    /// Set the `committer` of the commit.
+    pub fn committer(mut self, committer: git_actor::Signature) -> Self {
+        self.committer = Some(committer);
+        self
+    }
+
+    /// Set the name of the `encoding` of the commit message, like `ISO-8859-1`.
+    pub fn encoding(mut self, encoding: impl Into<BString>) -> Self {
+        self.encoding = Some(encoding.into());
+        #[cfg(feature = "encoding")]
+        {
+            self.reencode_message = false;
+        }
+        self
+    }
+
+    /// Set the name of the `encoding` of the commit message and convert the message from UTF-8 into it when building
+    /// the commit, after trailers were applied.
+    ///
+    /// Use this to write commits with a legacy encoding, whereas [`encoding()`][Builder::encoding()] expects the message
+    /// to be encoded already.
+    #[cfg(feature = "encoding")]
+    pub fn reencode_message(mut self, encoding: impl Into<BString>) -> Self {
+        self.encoding = Some(encoding.into());
+        self.reencode_message = true;
+        self
+    }
+
+    /// Set the commit `message`.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Add a trailer like `Signed-off-by: Name <email>` to the end of the message unless it is the last trailer already,
+    /// like `git commit --trailer` would.
+    pub fn trailer(self, token: impl Into<BString>, value: impl Into<BString>) -> Self {
+        self.trailer_edit(trailer::Edit::new(token, value))
+    }
+
+    /// Change the trailers of the message as described by `edit`, after all previous edits were applied.
+    ///
+    /// Trailers are applied to the message when building the commit, so they can be added before the message is set.
+    pub fn trailer_edit(mut self, edit: trailer::Edit) -> Self {
+        self.trailers.push(edit);
+        self
+    }
+
+    /// Add an extra header with `name` and `value`, which may span multiple lines.
+    ///
+    /// Extra headers are always written after the standard headers, in the order they were added.
+    pub fn extra_header(mut self, name: impl Into<BString>, value: impl Into<BString>) -> Self {
+        self.extra_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add the serialized `tag` as `mergetag` header, as done by `git merge` when merging a signed tag.
+    pub fn mergetag(self, tag: &Tag) -> std::io::Result<Self> {
+        let mut buf = Vec::with_capacity(tag.size());
+        tag.write_to(&mut buf)?;
+        Ok(self.extra_header("mergetag", without_trailing_newline(buf)))
+    }
+
+    /// Add the ASCII-armored `signature` as `gpgsig` header.
+    ///
+    /// The signature is to be produced over the serialized commit without this header.
+    pub fn gpgsig(self, signature: impl Into<BString>) -> Self {
+        let signature: Vec<u8> = signature.into().into();
+        self.extra_header("gpgsig", without_trailing_newline(signature))
+    }
+
+    /// Validate all fields and produce the commit, or fail if it wouldn't decode into the same commit after serialization.
+    pub fn build(self) -> Result<Commit, Error> {
+        for (field, signature) in [("author", &self.author), ("committer", &self.committer)] {
+            let signature = signature.as_ref().ok_or(Error::MissingSignature(field))?;
+            if let Some(reason) = encode::signature_defect(signature) {
+                return Err(Error::InvalidSignature { field, reason });
+            }
+        }
+        if let Some(id) = self.parents.iter().find(|id| id.kind() != self.tree.kind()) {
+            return Err(Error::HashKindMismatch { id: *id });
+        }
+        if let Some(encoding) = &self.encoding {
+            if encoding.is_empty() || encoding.iter().any(|b| !b.is_ascii_graphic()) {
+                return Err(Error::InvalidEncoding(encoding.clone()));
+            }
+        }
+        for (name, value) in &self.extra_headers {
+            if name.is_empty()
+                || name.iter().any(|b| b.is_ascii_whitespace())
+                || STANDARD_HEADERS.contains(&name.as_slice())
+            {
+                return Err(Error::InvalidHeaderName(name.clone()));
+            }
+            if value.is_empty() {
+                return Err(Error::EmptyHeaderValue(name.clone()));
+            }
+        }
+
+        let message = if self.trailers.is_empty() {
+            self.message
+        } else {
+            trailer::apply(&self.message, &self.trailers)?
+        };
+        #[cfg(feature = "encoding")]
+        let message = match (&self.encoding, self.reencode_message) {
+            (Some(encoding), true) => crate::encoding::encode(message.as_ref(), encoding.as_ref())
+                .map_err(|err| Error::Reencode {
+                    encoding: encoding.clone(),
+                    reason: err.to_string(),
+                })?
+                .into_owned(),
+            _ => message,
+        };
+
+        let commit = Commit {
+            tree: self.tree,
+            parents: self.parents,
+            author: self.author.expect("validated"),
+            committer: self.committer.expect("validated"),
+            encoding: self.encoding,
+            message,
+            extra_headers: self.extra_headers,
+        };
+        let mut buf = Vec::with_capacity(commit.size());
+        commit.write_to(&mut buf).map_err(|_| Error::RoundTrip)?;
+        let round_trips = CommitRef::from_bytes(&buf).map_or(false, |decoded| Commit::from(decoded) == commit);
+        if round_trips {
+            Ok(commit)
+        } else {
+            Err(Error::RoundTrip)
+        }
+    }
+}
+
+fn without_trailing_newline(mut value: Vec<u8>) -> BString {
+    if value.ends_with(b"\n") {
+        value.pop();
+    }
+    value.into()
+}
+
+impl Commit {
+    /// Return a [`Builder`] for a commit recording `tree`.
+    pub fn builder(tree: impl Into<ObjectId>) -> Builder {
+        Builder::new(tree)
+    }
+}
@@ -10,7 +10,7 @@ use nom::{
 };
 use smallvec::SmallVec;
 
-use crate::{parse, parse::NL, BStr, ByteSlice, CommitRef};
+use crate::{commit::LenientCommitRef, parse, parse::NL, BStr, ByteSlice, CommitRef};
 
 pub fn message<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], &'a BStr, E> {
     if i.is_empty() {
@@ -28,6 +28,57 @@ pub fn message<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(i: &'a [u8]
 pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
     i: &'a [u8],
 ) -> IResult<&'a [u8], CommitRef<'_>, E> {
+    let (i, (tree, parents, author, committer, encoding, extra_headers, message)) = fields(i, parse::signature)?;
+    Ok((
+        i,
+        CommitRef {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        },
+    ))
+}
+
+pub fn commit_lenient<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], LenientCommitRef<'a>, E> {
+    let (i, (tree, parents, author, committer, encoding, extra_headers, message)) =
+        fields(i, parse::signature_lenient)?;
+    Ok((
+        i,
+        LenientCommitRef {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        },
+    ))
+}
+
+#[allow(clippy::type_complexity)]
+fn fields<'a, S, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+    i: &'a [u8],
+    signature: impl Fn(&'a [u8]) -> IResult<&'a [u8], S, E> + Copy,
+) -> IResult<
+    &'a [u8],
+    (
+        &'a BStr,
+        SmallVec<[&'a BStr; 2]>,
+        S,
+        S,
+        Option<&'a BStr>,
+        Vec<(&'a BStr, Cow<'a, BStr>)>,
+        &'a BStr,
+    ),
+    E,
+> {
     let (i, tree) = context("tree <40 lowercase hex char>", |i| {
         parse::header_field(i, b"tree", parse::hex_hash)
     })(i)?;
@@ -35,11 +86,9 @@ pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
         "zero or more 'parent <40 lowercase hex char>'",
         many0(|i| parse::header_field(i, b"parent", parse::hex_hash)),
     )(i)?;
-    let (i, author) = context("author <signature>", |i| {
-        parse::header_field(i, b"author", parse::signature)
-    })(i)?;
+    let (i, author) = context("author <signature>", |i| parse::header_field(i, b"author", signature))(i)?;
     let (i, committer) = context("committer <signature>", |i| {
-        parse::header_field(i, b"committer", parse::signature)
+        parse::header_field(i, b"committer", signature)
     })(i)?;
     let (i, encoding) = context(
         "encoding <encoding>",
@@ -58,14 +107,14 @@ pub fn commit<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
 
     Ok((
         i,
-        CommitRef {
+        (
             tree,
-            parents: SmallVec::from(parents),
+            SmallVec::from(parents),
             author,
             committer,
-            encoding: encoding.map(ByteSlice::as_bstr),
-            message,
+            encoding.map(ByteSlice::as_bstr),
             extra_headers,
-        },
+            message,
+        ),
     ))
 }
@@ -0,0 +1,114 @@
+use bstr::ByteSlice;
+use git_hash::ObjectId;
+
+/// The headers of a commit needed to traverse the commit graph, as parsed by [`Headers::from_bytes()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Headers<'a> {
+    /// The id of the tree the commit records.
+    pub tree: ObjectId,
+    /// The time at which the changes of the commit were authored, as recorded in the author signature.
+    pub author_time: git_actor::Time,
+    /// The time at which the commit was created, as recorded in the committer signature.
+    pub committer_time: git_actor::Time,
+    /// All `parent` header lines, each including its trailing newline.
+    parent_lines: &'a [u8],
+}
+
+impl<'a> Headers<'a> {
+    /// Parse only the tree, the parents and the author and committer times from the commit in `data`, without allocating.
+    ///
+    /// Parsing stops right after the committer line, which is why the message, the encoding and extra headers like
+    /// signatures are never looked at nor validated. Use [`CommitRefIter`][crate::CommitRefIter] for a complete parse.
+    pub fn from_bytes(data: &'a [u8]) -> Result<Headers<'a>, crate::decode::Error> {
+        let mut lines = Lines { data };
+        let tree = lines
+            .next_header(b"tree ")
+            .and_then(parse_id)
+            .ok_or_else(crate::decode::empty_error)?;
+
+        let parents_start = data.len() - lines.data.len();
+        while lines.next_header(b"parent ").is_some() {}
+        let parent_lines = &data[parents_start..data.len() - lines.data.len()];
+
+        let author_time = lines
+            .next_header(b"author ")
+            .and_then(parse_time)
+            .ok_or_else(crate::decode::empty_error)?;
+        let committer_time = lines
+            .next_header(b"committer ")
+            .and_then(parse_time)
+            .ok_or_else(crate::decode::empty_error)?;
+        Ok(Headers {
+            tree,
+            author_time,
+            committer_time,
+            parent_lines,
+        })
+    }
+
+    /// Return an iterator over the ids of all parents of the commit.
+    ///
+    /// Parents whose ids are malformed are skipped.
+    pub fn parents(&self) -> impl Iterator<Item = ObjectId> + 'a {
+        self.parent_lines
+            .lines()
+            .filter_map(|line| line.strip_prefix(b"parent ").and_then(parse_id))
+    }
+
+    /// Return the amount of parents of the commit.
+    pub fn parent_count(&self) -> usize {
+        self.parent_lines.iter().filter(|b| **b == b'\n').count()
+    }
+
+    /// Return the time at which the commit was created in seconds since the unix epoch, ignoring the timezone.
+    pub fn committer_seconds(&self) -> u32 {
+        self.committer_time.seconds_since_unix_epoch
+    }
+
+    /// Return the time at which the changes of the commit were authored in seconds since the unix epoch, ignoring the timezone.
+    pub fn author_seconds(&self) -> u32 {
+        self.author_time.seconds_since_unix_epoch
+    }
+}
+
+struct Lines<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Lines<'a> {
+    /// Return the value of the next line if it starts with `name`, and consume the line.
+    fn next_header(&mut self, name: &[u8]) -> Option<&'a [u8]> {
+        let value = self.data.strip_prefix(name)?;
+        let end = value.find_byte(b'\n')?;
+        self.data = &value[end + 1..];
+        Some(&value[..end])
+    }
+}
+
+fn parse_id(hex: &[u8]) -> Option<ObjectId> {
+    ObjectId::from_hex(hex).ok()
+}
+
+/// Parse the time from a signature like `name <email> 1234 +0100`.
+fn parse_time(signature: &[u8]) -> Option<git_actor::Time> {
+    let time = &signature[signature.rfind_byte(b'>')? + 1..];
+    let time = time.strip_prefix(b" ")?;
+    let (seconds, tz) = time.split_at(time.find_byte(b' ')?);
+    let tz = &tz[1..];
+    if tz.len() != 5 {
+        return None;
+    }
+    let sign = match tz[0] {
+        b'-' => git_actor::Sign::Minus,
+        b'+' => git_actor::Sign::Plus,
+        _ => return None,
+    };
+    let hours: i32 = btoi::btoi(&tz[1..3]).ok()?;
+    let minutes: i32 = btoi::btoi(&tz[3..5]).ok()?;
+    let offset = (hours * 3600 + minutes * 60) * if sign == git_actor::Sign::Minus { -1 } else { 1 };
+    Some(git_actor::Time {
+        seconds_since_unix_epoch: btoi::btoi(seconds).ok()?,
+        offset_in_seconds: offset,
+        sign,
+    })
+}
@@ -1,21 +1,18 @@
-use std::ops::Deref;
-
-use nom::{
-    bytes::complete::{tag, take_until1},
-    combinator::all_consuming,
-    error::{ErrorKind, ParseError},
-    sequence::terminated,
-    IResult,
-};
+use std::{borrow::Cow, ops::Deref};
 
 use crate::{
     bstr::{BStr, ByteSlice},
     commit::message::BodyRef,
 };
 
+/// Lines starting with this byte are comments.
+pub(crate) const COMMENT: u8 = b'#';
+const SEPARATOR: u8 = b':';
+
 /// An iterator over trailers as parsed from a commit message body.
 ///
-/// lines with parsing failures will be skipped
+/// Lines that aren't trailers as well as comments will be skipped, and lines starting with whitespace continue the
+/// value of the trailer before them, like in [git-interpret-trailers](https://git-scm.com/docs/git-interpret-trailers).
 pub struct Trailers<'a> {
     pub(crate) cursor: &'a [u8],
 }
@@ -24,20 +21,86 @@ pub struct Trailers<'a> {
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrailerRef<'a> {
-    /// The name of the trailer, like "Signed-off-by", up to the separator ": "
+    /// The name of the trailer, like "Signed-off-by", up to the separator ":" and without surrounding whitespace.
     #[cfg_attr(feature = "serde1", serde(borrow))]
     pub token: &'a BStr,
-    /// The value right after the separator ": ", with leading and trailing whitespace trimmed.
-    /// Note that multi-line values aren't currently supported.
+    /// The value right after the separator ":", with leading and trailing whitespace trimmed.
+    /// Values spanning multiple lines contain their continuation lines as they were, use
+    /// [`unfolded_value()`][TrailerRef::unfolded_value()] to obtain them as single line.
     pub value: &'a BStr,
 }
 
-fn parse_single_line_trailer<'a, E: ParseError<&'a [u8]>>(i: &'a [u8]) -> IResult<&'a [u8], (&'a BStr, &'a BStr), E> {
-    let (value, token) = terminated(take_until1(b":".as_ref()), tag(b": "))(i.trim_end())?;
-    if token.trim_end().len() != token.len() || value.trim_start().len() != value.len() {
-        Err(nom::Err::Failure(E::from_error_kind(i, ErrorKind::Fail)))
-    } else {
-        Ok((&[], (token.as_bstr(), value.as_bstr())))
+impl<'a> TrailerRef<'a> {
+    /// Return the value with continuation lines folded into a single line, like `git interpret-trailers --unfold` does.
+    pub fn unfolded_value(&self) -> Cow<'a, BStr> {
+        if !self.value.contains(&b'\n') {
+            return self.value.into();
+        }
+        let mut out = Vec::with_capacity(self.value.len());
+        let mut bytes = self.value.iter().copied().peekable();
+        while let Some(byte) = bytes.next() {
+            if byte == b'\n' {
+                while bytes.peek().map_or(false, u8::is_ascii_whitespace) {
+                    bytes.next();
+                }
+                out.push(b' ');
+            } else {
+                out.push(byte);
+            }
+        }
+        Cow::Owned(out.trim().as_bstr().to_owned())
+    }
+
+    /// Return true if our token is `token` when compared case-insensitively, like git does.
+    pub fn is(&self, token: impl AsRef<[u8]>) -> bool {
+        self.token.eq_ignore_ascii_case(token.as_ref())
+    }
+}
+
+/// Return the first line of `buf` including its newline, or `None` if `buf` is empty.
+pub(crate) fn first_line(buf: &[u8]) -> Option<&[u8]> {
+    (!buf.is_empty()).then(|| &buf[..buf.find_byte(b'\n').map_or(buf.len(), |pos| pos + 1)])
+}
+
+/// Return the position of the separator in `line` if it starts with a token, followed by optional whitespace and the separator.
+pub(crate) fn find_separator(line: &[u8]) -> Option<usize> {
+    let mut whitespace_found = false;
+    for (pos, byte) in line.iter().enumerate() {
+        match byte {
+            &SEPARATOR => return (pos > 0).then(|| pos),
+            b if !whitespace_found && (b.is_ascii_alphanumeric() || *b == b'-') => {}
+            b' ' | b'\t' if pos > 0 => whitespace_found = true,
+            _ => break,
+        }
+    }
+    None
+}
+
+impl<'a> Trailers<'a> {
+    /// Return the next line as trailer, including its continuation lines, or the line without its newline if it isn't
+    /// a trailer. Comments are skipped.
+    pub(crate) fn next_line(&mut self) -> Option<Result<TrailerRef<'a>, &'a BStr>> {
+        loop {
+            let lines = self.cursor;
+            let line = first_line(lines)?;
+            self.cursor = &lines[line.len()..];
+            if line[0] == COMMENT {
+                continue;
+            }
+            let separator_pos = match find_separator(line) {
+                Some(pos) => pos,
+                None => return Some(Err(line.strip_suffix(b"\n").unwrap_or(line).as_bstr())),
+            };
+            let mut trailer_len = line.len();
+            while let Some(continuation) = first_line(self.cursor).filter(|l| l[0].is_ascii_whitespace()) {
+                self.cursor = &self.cursor[continuation.len()..];
+                trailer_len += continuation.len();
+            }
+            return Some(Ok(TrailerRef {
+                token: line[..separator_pos].trim().as_bstr(),
+                value: lines[separator_pos + 1..trailer_len].trim().as_bstr(),
+            }));
+        }
     }
 }
 
@@ -45,26 +108,13 @@ impl<'a> Iterator for Trailers<'a> {
     type Item = TrailerRef<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cursor.is_empty() {
-            return None;
-        }
-        for line in self.cursor.lines_with_terminator() {
-            self.cursor = &self.cursor[line.len()..];
-            if let Some(trailer) =
-                all_consuming(parse_single_line_trailer::<()>)(line)
-                    .ok()
-                    .map(|(_, (token, value))| TrailerRef {
-                        token: token.trim().as_bstr(),
-                        value: value.trim().as_bstr(),
-                    })
-            {
+        loop {
+            if let Ok(trailer) = self.next_line()? {
                 return Some(trailer);
             }
         }
-        None
     }
 }
-
 impl<'a> BodyRef<'a> {
     /// Parse `body` bytes into the trailer and the actual body.
     pub fn from_bytes(body: &'a [u8]) -> Self {
@@ -113,40 +163,54 @@ impl<'a> Deref for BodyRef<'a> {
         self.body_without_trailer
     }
 }
+
 #[cfg(test)]
 mod test_parse_trailer {
     use super::*;
 
-    fn parse(input: &str) -> (&BStr, &BStr) {
-        parse_single_line_trailer::<()>(input.as_bytes()).unwrap().1
+    fn parse(input: &str) -> Option<(&BStr, &BStr)> {
+        Trailers {
+            cursor: input.as_bytes(),
+        }
+        .next()
+        .map(|t| (t.token, t.value))
     }
 
     #[test]
     fn simple_newline() {
-        assert_eq!(parse("foo: bar\n"), ("foo".into(), "bar".into()));
+        assert_eq!(parse("foo: bar\n"), Some(("foo".into(), "bar".into())));
     }
 
     #[test]
-    fn simple_non_ascii_no_newline() {
-        assert_eq!(parse("🤗: 🎉"), ("🤗".into(), "🎉".into()));
+    fn tokens_are_alphanumeric_or_dashes() {
+        assert_eq!(
+            parse("Signed-off-by2: 🎉"),
+            Some(("Signed-off-by2".into(), "🎉".into()))
+        );
+        assert_eq!(parse("🤗: 🎉"), None);
+        assert_eq!(parse("hello foo: bar there   \n"), None);
     }
 
     #[test]
-    fn with_lots_of_whitespace_newline() {
-        assert_eq!(
-            parse("hello foo: bar there   \n"),
-            ("hello foo".into(), "bar there".into())
-        );
+    fn whitespace_around_the_separator_is_ignored() {
+        assert_eq!(parse("foo : bar"), Some(("foo".into(), "bar".into())));
+        assert_eq!(parse("foo:  bar there   \n"), Some(("foo".into(), "bar there".into())));
+        assert_eq!(parse("foo:bar"), Some(("foo".into(), "bar".into())));
     }
 
     #[test]
-    fn extra_whitespace_before_token_or_value_is_error() {
-        assert!(parse_single_line_trailer::<()>(b"foo : bar").is_err());
-        assert!(parse_single_line_trailer::<()>(b"foo:  bar").is_err())
+    fn continuation_lines_are_part_of_the_value() {
+        let trailer = Trailers {
+            cursor: b"foo: bar\n  baz\n# comment\nc: d\n",
+        }
+        .next()
+        .expect("present");
+        assert_eq!(trailer.value, "bar\n  baz");
+        assert_eq!(trailer.unfolded_value().as_ref(), "bar baz");
     }
 
     #[test]
     fn simple_newline_windows() {
-        assert_eq!(parse("foo: bar\r\n"), ("foo".into(), "bar".into()));
+        assert_eq!(parse("foo: bar\r\n"), Some(("foo".into(), "bar".into())));
     }
 }
@@ -92,7 +92,8 @@ pub(crate) fn summary(message: &BStr) -> Cow<'_, BStr> {
 /// See [git-interpret-trailers](https://git-scm.com/docs/git-interpret-trailers) for more information
 /// on what constitutes trailers and not that this implementation is only good for typical sign-off footer or key-value parsing.
 ///
-/// Note that we only parse trailers from the bottom of the body.
+/// Note that we only parse trailers from the bottom of the body. Use the [`trailer`][crate::trailer] module to parse
+/// and edit trailers exactly like git does.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 pub struct BodyRef<'a> {
     body_without_trailer: &'a BStr,
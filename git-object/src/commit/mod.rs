@@ -1,4 +1,7 @@
+use std::borrow::Cow;
+
 use bstr::{BStr, ByteSlice};
+use smallvec::SmallVec;
 
 use crate::{Commit, CommitRef, TagRef};
 
@@ -21,16 +24,59 @@ pub struct MessageRef<'a> {
     pub body: Option<&'a BStr>,
 }
 
+/// A commit parsed using [`from_bytes_lenient()`][CommitRef::from_bytes_lenient()], which accepts broken signatures
+/// as found in real-world repositories, like missing emails or bogus timezones like `+051800`.
+///
+/// Signatures keep their raw bytes so that [serializing][crate::WriteTo::write_to()] it reproduces the original
+/// object exactly, and thus its id. Use [`to_strict()`][LenientCommitRef::to_strict()] to obtain a [`CommitRef`]
+/// if all signatures are valid.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct LenientCommitRef<'a> {
+    /// HEX hash of tree object we point to.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub tree: &'a BStr,
+    /// HEX hash of each parent commit. Empty for first commit in repository.
+    pub parents: SmallVec<[&'a BStr; 2]>,
+    /// Who wrote this commit, as far as it could be parsed.
+    pub author: git_actor::LenientSignatureRef<'a>,
+    /// Who committed this commit, as far as it could be parsed.
+    pub committer: git_actor::LenientSignatureRef<'a>,
+    /// The name of the message encoding, otherwise UTF-8 should be assumed.
+    pub encoding: Option<&'a BStr>,
+    /// The commit message documenting the change.
+    pub message: &'a BStr,
+    /// Extra header fields, in order of them being encountered.
+    pub extra_headers: Vec<(&'a BStr, Cow<'a, BStr>)>,
+}
+
 ///
 pub mod ref_iter;
 
 mod write;
 
+///
+pub mod builder;
+pub use builder::Builder;
+
+mod headers;
+pub use headers::Headers;
+
 impl<'a> CommitRef<'a> {
     /// Deserialize a commit from the given `data` bytes while avoiding most allocations.
     pub fn from_bytes(data: &'a [u8]) -> Result<CommitRef<'a>, crate::decode::Error> {
         decode::commit(data).map(|(_, t)| t).map_err(crate::decode::Error::from)
     }
+
+    /// Deserialize a commit from the given `data` bytes like [`from_bytes()`][CommitRef::from_bytes()], but accept
+    /// signatures that can't be parsed strictly while keeping their raw bytes.
+    ///
+    /// Use this to read objects from repositories with broken history, whose ids must not change when written back.
+    pub fn from_bytes_lenient(data: &'a [u8]) -> Result<LenientCommitRef<'a>, crate::decode::Error> {
+        decode::commit_lenient(data)
+            .map(|(_, t)| t)
+            .map_err(crate::decode::Error::from)
+    }
     /// Return the `tree` fields hash digest.
     pub fn tree(&self) -> git_hash::ObjectId {
         git_hash::ObjectId::from_hex(self.tree).expect("prior validation of tree hash during parsing")
@@ -67,17 +113,63 @@ impl<'a> CommitRef<'a> {
         MessageRef::from_bytes(self.message)
     }
 
+    /// Return the message decoded from the encoding declared in its `encoding` header into UTF-8, or the message as is
+    /// if there is no such header.
+    #[cfg(feature = "encoding")]
+    pub fn message_utf8(&self) -> Result<std::borrow::Cow<'a, BStr>, crate::encoding::Error> {
+        crate::encoding::decode(self.message, self.encoding)
+    }
+
     /// Returns the time at which this commit was created.
     pub fn time(&self) -> git_actor::Time {
         self.committer.time
     }
 }
 
+impl<'a> LenientCommitRef<'a> {
+    /// Return the `tree` fields hash digest.
+    pub fn tree(&self) -> git_hash::ObjectId {
+        git_hash::ObjectId::from_hex(self.tree).expect("prior validation of tree hash during parsing")
+    }
+
+    /// Returns an iterator of parent object ids
+    pub fn parents(&self) -> impl Iterator<Item = git_hash::ObjectId> + '_ {
+        self.parents
+            .iter()
+            .map(|hex_hash| git_hash::ObjectId::from_hex(hex_hash).expect("prior validation of hashes during parsing"))
+    }
+
+    /// Return true if all signatures of this commit would be accepted when parsing strictly.
+    pub fn is_valid(&self) -> bool {
+        self.author.is_valid() && self.committer.is_valid()
+    }
+
+    /// Return this commit as [`CommitRef`] if all of its signatures are valid, or `None` otherwise.
+    pub fn to_strict(&self) -> Option<CommitRef<'a>> {
+        Some(CommitRef {
+            tree: self.tree,
+            parents: self.parents.clone(),
+            author: self.author.to_strict()?,
+            committer: self.committer.to_strict()?,
+            encoding: self.encoding,
+            message: self.message,
+            extra_headers: self.extra_headers.clone(),
+        })
+    }
+}
+
 impl Commit {
     /// Returns a convenient iterator over all extra headers.
     pub fn extra_headers(&self) -> ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+
+    /// Return the message decoded from the encoding declared in its `encoding` header into UTF-8, or the message as is
+    /// if there is no such header.
+    #[cfg(feature = "encoding")]
+    pub fn message_utf8(&self) -> Result<std::borrow::Cow<'_, BStr>, crate::encoding::Error> {
+        crate::encoding::decode(self.message.as_bstr(), self.encoding.as_ref().map(|e| e.as_bstr()))
+    }
 }
 
 /// An iterator over extra headers in [owned][crate::Commit] and [borrowed][crate::CommitRef] commits.
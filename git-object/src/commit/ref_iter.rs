@@ -54,6 +54,17 @@ impl<'a> CommitRefIter<'a> {
         Token::try_into_id(tree_id).ok_or_else(missing_field)
     }
 
+    /// Parse only the headers needed for traversal, the tree, the parents and the committer time, without parsing the
+    /// remaining commit like the iterator would. This is considerably faster if only these are needed.
+    ///
+    /// This must be called before the first call to `next()`, which fails otherwise.
+    pub fn headers(&self) -> Result<crate::commit::Headers<'a>, crate::decode::Error> {
+        match self.state {
+            State::Tree => crate::commit::Headers::from_bytes(self.data),
+            _ => Err(missing_field()),
+        }
+    }
+
     /// Return all parent_ids as iterator.
     ///
     /// Parsing errors are ignored quietly.
@@ -85,3 +85,22 @@ pub(crate) fn header_field(name: &[u8], value: &[u8], out: impl io::Write) -> io
 
 pub(crate) const NL: &[u8; 1] = b"\n";
 pub(crate) const SPACE: &[u8; 1] = b" ";
+
+/// Return a description of what's wrong with `signature` if it can't be serialized in a way that round-trips.
+pub(crate) fn signature_defect(signature: &git_actor::Signature) -> Option<&'static str> {
+    if signature.name.trim().is_empty() {
+        return Some("the name must not be empty");
+    }
+    let invalid = |b: &u8| matches!(b, b'<' | b'>' | b'\n' | b'\0');
+    if signature.name.iter().any(invalid) {
+        return Some("the name must not contain '<', '>', null bytes or newlines");
+    }
+    if signature.email.iter().any(invalid) {
+        return Some("the email must not contain '<', '>', null bytes or newlines");
+    }
+    let is_space = |b: Option<&u8>| b.map_or(false, u8::is_ascii_whitespace);
+    if is_space(signature.name.first()) || is_space(signature.name.last()) {
+        return Some("the name must not start or end with whitespace");
+    }
+    None
+}
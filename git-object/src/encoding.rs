@@ -0,0 +1,73 @@
+//! Conversion of commit messages declared with an `encoding` header from and into UTF-8.
+//!
+//! Git stores commit messages verbatim and merely records the encoding they were written in, so messages of legacy
+//! repositories need to be decoded before they can be displayed or their trailers can be parsed.
+use std::borrow::Cow;
+
+use bstr::{BStr, BString, ByteSlice};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`decode()`] and [`encode()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Unknown(name: BString) {
+            display("The encoding {:?} is unknown", name)
+        }
+        Malformed(name: &'static str) {
+            display("The message is not valid {}", name)
+        }
+        Unmappable(name: &'static str) {
+            display("The message contains characters which can't be represented in {}", name)
+        }
+        Unsupported(name: &'static str) {
+            display("Messages can't be encoded in {}", name)
+        }
+    }
+}
+
+fn lookup(name: &BStr) -> Result<&'static encoding_rs::Encoding, Error> {
+    encoding_rs::Encoding::for_label(name.trim()).ok_or_else(|| Error::Unknown(name.to_owned()))
+}
+
+/// Decode `message` from the encoding with `name` into UTF-8, as stored in the `encoding` header of a commit.
+///
+/// If `name` is `None` or names UTF-8, the message is assumed to be UTF-8 already and is returned as is,
+/// just like git does. Otherwise the message must be valid in the given encoding.
+pub fn decode<'a>(message: &'a BStr, name: Option<&BStr>) -> Result<Cow<'a, BStr>, Error> {
+    let encoding = match name {
+        Some(name) => lookup(name)?,
+        None => return Ok(Cow::Borrowed(message)),
+    };
+    if encoding == encoding_rs::UTF_8 {
+        return Ok(Cow::Borrowed(message));
+    }
+    match encoding.decode_without_bom_handling_and_without_replacement(message) {
+        Some(Cow::Borrowed(decoded)) => Ok(Cow::Borrowed(decoded.as_bytes().as_bstr())),
+        Some(Cow::Owned(decoded)) => Ok(Cow::Owned(decoded.into())),
+        None => Err(Error::Malformed(encoding.name())),
+    }
+}
+
+/// Encode the UTF-8 `message` into the encoding with `name`, to be stored along with an `encoding` header.
+///
+/// Encodings like UTF-16 which can't be used for commit messages are rejected, as are messages with characters
+/// that can't be represented in the target encoding.
+pub fn encode<'a>(message: &'a BStr, name: &BStr) -> Result<Cow<'a, BStr>, Error> {
+    let encoding = lookup(name)?;
+    if encoding.output_encoding() != encoding {
+        return Err(Error::Unsupported(encoding.name()));
+    }
+    let message = message
+        .to_str()
+        .map_err(|_| Error::Malformed(encoding_rs::UTF_8.name()))?;
+    let (encoded, _, had_errors) = encoding.encode(message);
+    if had_errors {
+        return Err(Error::Unmappable(encoding.name()));
+    }
+    Ok(match encoded {
+        Cow::Borrowed(encoded) => Cow::Borrowed(encoded.as_bstr()),
+        Cow::Owned(encoded) => Cow::Owned(encoded.into()),
+    })
+}
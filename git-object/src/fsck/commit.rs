@@ -0,0 +1,51 @@
+use crate::fsck::{ident, skip_id_line, skip_line, verify_headers, Issue, Report, Stop};
+
+/// Check the serialized commit in `data`, stopping at the first error.
+pub(crate) fn check(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    verify_headers(data, report)?;
+    let object_hash = report.opts.object_hash;
+
+    let mut cursor = match data.strip_prefix(b"tree ") {
+        Some(tree) => tree,
+        None => return report.issue(Issue::MissingTree, "invalid format - expected 'tree' line"),
+    };
+    cursor = match skip_id_line(cursor, object_hash) {
+        Some(cursor) => cursor,
+        None => {
+            report.issue(Issue::BadTreeSha1, "invalid 'tree' line format - bad sha1")?;
+            skip_line(cursor)
+        }
+    };
+
+    while let Some(parent) = cursor.strip_prefix(b"parent ") {
+        cursor = match skip_id_line(parent, object_hash) {
+            Some(cursor) => cursor,
+            None => {
+                report.issue(Issue::BadParentSha1, "invalid 'parent' line format - bad sha1")?;
+                skip_line(parent)
+            }
+        };
+    }
+
+    let mut author_count = 0;
+    while let Some(author) = cursor.strip_prefix(b"author ") {
+        author_count += 1;
+        cursor = ident::check(author, report)?;
+    }
+    match author_count {
+        0 => report.issue(Issue::MissingAuthor, "invalid format - expected 'author' line")?,
+        1 => {}
+        _ => report.issue(Issue::MultipleAuthors, "invalid format - multiple 'author' lines")?,
+    }
+
+    cursor = match cursor.strip_prefix(b"committer ") {
+        Some(committer) => committer,
+        None => return report.issue(Issue::MissingCommitter, "invalid format - expected 'committer' line"),
+    };
+    ident::check(cursor, report)?;
+
+    if data.contains(&b'\0') {
+        report.issue(Issue::NulInCommit, "NUL byte in the commit object body")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::fsck::{skip_line, Issue, Report, Stop};
+
+/// Check the signature at the beginning of `data`, like `Name <email> 1234 +0100`, and return the data past its line.
+///
+/// Only the first issue of the signature is reported.
+pub(crate) fn check<'a>(data: &'a [u8], report: &mut Report<'_>) -> Result<&'a [u8], Stop> {
+    let remaining = skip_line(data);
+    let line = &data[..data.iter().position(|b| *b == b'\n').unwrap_or(data.len())];
+    issue_in(line).map_or(Ok(remaining), |(issue, message)| {
+        report.issue(issue, format!("invalid author/committer line - {}", message))?;
+        Ok(remaining)
+    })
+}
+
+fn issue_in(line: &[u8]) -> Option<(Issue, &'static str)> {
+    if line.first() == Some(&b'<') {
+        return Some((Issue::MissingNameBeforeEmail, "missing space before email"));
+    }
+    let email_start = match line.iter().position(|b| *b == b'<' || *b == b'>') {
+        Some(pos) if line[pos] == b'>' => return Some((Issue::BadName, "bad name")),
+        Some(pos) => pos,
+        None => return Some((Issue::MissingEmail, "missing email")),
+    };
+    if line[email_start - 1] != b' ' {
+        return Some((Issue::MissingSpaceBeforeEmail, "missing space before email"));
+    }
+    let email_end = match line[email_start + 1..].iter().position(|b| *b == b'<' || *b == b'>') {
+        Some(pos) if line[email_start + 1 + pos] == b'>' => email_start + 1 + pos,
+        _ => return Some((Issue::BadEmail, "bad email")),
+    };
+    let time = match line[email_end + 1..].strip_prefix(b" ") {
+        Some(time) => time,
+        None => return Some((Issue::MissingSpaceBeforeDate, "missing space before date")),
+    };
+
+    let seconds_len = time.iter().take_while(|b| b.is_ascii_digit()).count();
+    if time.first() == Some(&b'0') && time.get(1) != Some(&b' ') {
+        return Some((Issue::ZeroPaddedDate, "zero-padded date"));
+    }
+    if seconds_len > 0 && btoi::btou::<i64>(&time[..seconds_len]).is_err() {
+        return Some((Issue::BadDateOverflow, "date causes integer overflow"));
+    }
+    if seconds_len == 0 || time.get(seconds_len) != Some(&b' ') {
+        return Some((Issue::BadDate, "bad date"));
+    }
+    let tz = &time[seconds_len + 1..];
+    if tz.len() != 5 || !matches!(tz[0], b'+' | b'-') || !tz[1..].iter().all(u8::is_ascii_digit) {
+        return Some((Issue::BadTimezone, "bad time zone"));
+    }
+    None
+}
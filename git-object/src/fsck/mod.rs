@@ -0,0 +1,334 @@
+//! Validate objects semantically like `git fsck` does, beyond what is needed to decode them.
+//!
+//! Each problem is identified by an [`Issue`] whose [`Severity`] can be configured with [`Options`], using the same names
+//! as the `fsck.<msg-id>` and `receive.fsck.<msg-id>` configuration variables of git.
+use std::collections::BTreeMap;
+
+use quick_error::quick_error;
+
+mod commit;
+mod ident;
+mod tag;
+mod tree;
+
+/// How severe an [`Issue`] is considered.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// The object is invalid and should be rejected. Checking the object stops at the first error.
+    Error,
+    /// The object is questionable but may be accepted.
+    Warn,
+    /// The object is probably fine but the issue might be interesting to know about.
+    Info,
+    /// The issue isn't reported at all.
+    Ignore,
+}
+
+impl Severity {
+    /// Parse the severity from its name as used in git configuration, like `warn`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warn,
+            "info" => Severity::Info,
+            "ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+
+    /// The name of the severity as used in git configuration, like `warn`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warn => "warn",
+            Severity::Info => "info",
+            Severity::Ignore => "ignore",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Severity::from_name(s).ok_or_else(|| Error::UnknownSeverity(s.into()))
+    }
+}
+
+macro_rules! issues {
+    ($($(#[$doc:meta])* $variant:ident => $name:literal, $severity:ident;)*) => {
+        /// A problem found in an object, named after the message ids used by `git fsck`.
+        #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+        #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+        #[allow(missing_docs)]
+        pub enum Issue {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl Issue {
+            /// All issues that can be reported.
+            pub const ALL: &'static [Issue] = &[$(Issue::$variant,)*];
+
+            /// The name of the issue as used in git configuration, like `badDate`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Issue::$variant => $name,)*
+                }
+            }
+
+            /// The severity of the issue unless configured otherwise.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(Issue::$variant => Severity::$severity,)*
+                }
+            }
+        }
+    };
+}
+
+issues! {
+    BadDate => "badDate", Error;
+    BadDateOverflow => "badDateOverflow", Error;
+    BadEmail => "badEmail", Error;
+    BadName => "badName", Error;
+    BadObjectSha1 => "badObjectSha1", Error;
+    BadParentSha1 => "badParentSha1", Error;
+    BadTimezone => "badTimezone", Error;
+    BadTree => "badTree", Error;
+    BadTreeSha1 => "badTreeSha1", Error;
+    BadType => "badType", Error;
+    DuplicateEntries => "duplicateEntries", Error;
+    MissingAuthor => "missingAuthor", Error;
+    MissingCommitter => "missingCommitter", Error;
+    MissingEmail => "missingEmail", Error;
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error;
+    MissingObject => "missingObject", Error;
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error;
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error;
+    MissingTag => "missingTag", Error;
+    MissingTagEntry => "missingTagEntry", Error;
+    MissingTree => "missingTree", Error;
+    MissingType => "missingType", Error;
+    MissingTypeEntry => "missingTypeEntry", Error;
+    MultipleAuthors => "multipleAuthors", Error;
+    TreeNotSorted => "treeNotSorted", Error;
+    ZeroPaddedDate => "zeroPaddedDate", Error;
+    /// The header contains a NUL byte. It can't be demoted.
+    NulInHeader => "nulInHeader", Error;
+    /// The header isn't terminated by an empty line. It can't be demoted.
+    UnterminatedHeader => "unterminatedHeader", Error;
+    BadFilemode => "badFilemode", Warn;
+    EmptyName => "emptyName", Warn;
+    FullPathname => "fullPathname", Warn;
+    HasDot => "hasDot", Warn;
+    HasDotdot => "hasDotdot", Warn;
+    /// A tree contains an entry that would be treated as `.git` on case-insensitive, HFS+ or NTFS file systems.
+    HasDotgit => "hasDotgit", Warn;
+    NullSha1 => "nullSha1", Warn;
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warn;
+    NulInCommit => "nulInCommit", Warn;
+    BadTagName => "badTagName", Info;
+    MissingTaggerEntry => "missingTaggerEntry", Info;
+    ExtraHeaderEntry => "extraHeaderEntry", Ignore;
+}
+
+impl Issue {
+    /// Find the issue by its `name` as used in git configuration, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Issue::ALL
+            .iter()
+            .find(|issue| issue.name().eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// Return true if the severity of this issue can't be lowered as the object can't be processed any further.
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Issue::NulInHeader | Issue::UnterminatedHeader)
+    }
+}
+
+quick_error! {
+    /// The error returned by [`Options::set_severity()`] and [`Options::set_from_config()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        UnknownIssue(name: String) {
+            display("'{}' is not a known fsck message id", name)
+        }
+        UnknownSeverity(name: String) {
+            display("'{}' is not a valid severity, expected one of 'error', 'warn', 'info' or 'ignore'", name)
+        }
+        CannotDemote(issue: Issue) {
+            display("The severity of '{}' can't be lowered", issue.name())
+        }
+    }
+}
+
+/// Configure how objects are validated by [`check()`].
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Options {
+    /// The kind of hash used for object ids in the objects to check.
+    pub object_hash: git_hash::Kind,
+    /// If true, treat all warnings as errors and consider group-writable file modes as bad, like `git fsck --strict`.
+    pub strict: bool,
+    severities: BTreeMap<Issue, Severity>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: git_hash::Kind::Sha1,
+            strict: false,
+            severities: Default::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Set the `severity` of `issue`, overriding its default.
+    pub fn set_severity(&mut self, issue: Issue, severity: Severity) -> Result<(), Error> {
+        if issue.is_fatal() && severity != Severity::Error {
+            return Err(Error::CannotDemote(issue));
+        }
+        self.severities.insert(issue, severity);
+        Ok(())
+    }
+
+    /// Set the severity of the issue called `name` to the severity called `value`, as in the `fsck.<name> = <value>`
+    /// configuration variable.
+    pub fn set_from_config(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let issue = Issue::from_name(name).ok_or_else(|| Error::UnknownIssue(name.into()))?;
+        let severity = Severity::from_name(value).ok_or_else(|| Error::UnknownSeverity(value.into()))?;
+        self.set_severity(issue, severity)
+    }
+
+    /// Return the severity with which `issue` is reported.
+    pub fn severity(&self, issue: Issue) -> Severity {
+        let severity = self
+            .severities
+            .get(&issue)
+            .copied()
+            .unwrap_or_else(|| issue.default_severity());
+        if self.strict && severity == Severity::Warn {
+            Severity::Error
+        } else {
+            severity
+        }
+    }
+}
+
+/// An [`Issue`] found in an object along with the [`Severity`] it was reported with.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Finding {
+    /// The issue that was found.
+    pub issue: Issue,
+    /// The configured severity of the issue.
+    pub severity: Severity,
+    /// A description of the problem, like git would print it.
+    pub message: String,
+}
+
+/// The result of [`check()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// All issues that were found in the order they were found, omitting those that are ignored.
+    ///
+    /// There is at most one finding of [`Severity::Error`] as checking stops at the first error.
+    pub findings: Vec<Finding>,
+}
+
+impl Outcome {
+    /// Return true if the object should be rejected.
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+}
+
+/// Collects findings and signals when checking has to stop.
+pub(crate) struct Report<'a> {
+    opts: &'a Options,
+    out: Outcome,
+}
+
+/// Returned by [`Report::issue()`] if checking has to stop as an error was found.
+pub(crate) struct Stop;
+
+impl<'a> Report<'a> {
+    /// Record `issue` with `message` and return `Err(Stop)` if its severity is [`Severity::Error`].
+    pub(crate) fn issue(&mut self, issue: Issue, message: impl Into<String>) -> Result<(), Stop> {
+        let severity = self.opts.severity(issue);
+        if severity == Severity::Ignore {
+            return Ok(());
+        }
+        self.out.findings.push(Finding {
+            issue,
+            severity,
+            message: message.into(),
+        });
+        if severity == Severity::Error {
+            Err(Stop)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Check the serialized object of `kind` in `data` for issues according to `opts`.
+///
+/// This works on the raw object data as it would be received in a pack, which allows to find problems in objects that
+/// can't be decoded and is how `receive-pack` and `verify` operations can validate objects.
+pub fn check(kind: crate::Kind, data: &[u8], opts: &Options) -> Outcome {
+    let mut report = Report {
+        opts,
+        out: Outcome::default(),
+    };
+    let _stopped_early = match kind {
+        crate::Kind::Commit => commit::check(data, &mut report),
+        crate::Kind::Tag => tag::check(data, &mut report),
+        crate::Kind::Tree => tree::check(data, &mut report),
+        crate::Kind::Blob => Ok(()),
+    };
+    report.out
+}
+
+/// Return an error if the header in `data` contains a NUL byte or isn't terminated by an empty line.
+fn verify_headers(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    for (pos, byte) in data.iter().enumerate() {
+        match byte {
+            b'\0' => {
+                return report.issue(
+                    Issue::NulInHeader,
+                    format!("unterminated header: NUL at offset {}", pos),
+                )
+            }
+            b'\n' if data.get(pos + 1) == Some(&b'\n') => return Ok(()),
+            _ => {}
+        }
+    }
+    // Tags without message end right after the last header.
+    if data.last() == Some(&b'\n') {
+        return Ok(());
+    }
+    report.issue(Issue::UnterminatedHeader, "unterminated header")
+}
+
+/// Skip a hexadecimal object id followed by a newline at the beginning of `data` and return the remaining bytes,
+/// or `None` if there is no valid id.
+fn skip_id_line(data: &[u8], object_hash: git_hash::Kind) -> Option<&[u8]> {
+    let hex_len = object_hash.len_in_hex();
+    let hex = data.get(..hex_len)?;
+    if !hex.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    data[hex_len..].strip_prefix(b"\n")
+}
+
+/// Return `data` past the next newline, or an empty slice if there is none.
+fn skip_line(data: &[u8]) -> &[u8] {
+    data.iter()
+        .position(|b| *b == b'\n')
+        .map_or(&[], |pos| &data[pos + 1..])
+}
@@ -0,0 +1,62 @@
+use bstr::ByteSlice;
+
+use crate::fsck::{ident, skip_id_line, skip_line, verify_headers, Issue, Report, Stop};
+
+/// Check the serialized tag in `data`, stopping at the first error.
+pub(crate) fn check(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    verify_headers(data, report)?;
+
+    let mut cursor = match data.strip_prefix(b"object ") {
+        Some(object) => object,
+        None => return report.issue(Issue::MissingObject, "invalid format - expected 'object' line"),
+    };
+    cursor = match skip_id_line(cursor, report.opts.object_hash) {
+        Some(cursor) => cursor,
+        None => {
+            report.issue(Issue::BadObjectSha1, "invalid 'object' line format - bad sha1")?;
+            skip_line(cursor)
+        }
+    };
+
+    cursor = match cursor.strip_prefix(b"type ") {
+        Some(kind) => kind,
+        None => return report.issue(Issue::MissingTypeEntry, "invalid format - expected 'type' line"),
+    };
+    let kind = match cursor.find_byte(b'\n') {
+        Some(end) => &cursor[..end],
+        None => return report.issue(Issue::MissingType, "invalid format - unexpected end after 'type' line"),
+    };
+    if crate::Kind::from_bytes(kind).is_err() {
+        report.issue(Issue::BadType, "invalid 'type' value")?;
+    }
+    cursor = skip_line(cursor);
+
+    cursor = match cursor.strip_prefix(b"tag ") {
+        Some(name) => name,
+        None => return report.issue(Issue::MissingTagEntry, "invalid format - expected 'tag' line"),
+    };
+    let name = match cursor.find_byte(b'\n') {
+        Some(end) => &cursor[..end],
+        None => return report.issue(Issue::MissingTag, "invalid format - unexpected end after 'type' line"),
+    };
+    let mut ref_name = b"refs/tags/".to_vec();
+    ref_name.extend_from_slice(name);
+    if git_validate::reference::name(ref_name.as_bstr()).is_err() {
+        report.issue(Issue::BadTagName, format!("invalid 'tag' name: {}", name.as_bstr()))?;
+    }
+    cursor = skip_line(cursor);
+
+    match cursor.strip_prefix(b"tagger ") {
+        Some(tagger) => cursor = ident::check(tagger, report)?,
+        // Early tags do not contain 'tagger' lines.
+        None => report.issue(Issue::MissingTaggerEntry, "invalid format - expected 'tagger' line")?,
+    }
+
+    if !cursor.is_empty() && !cursor.starts_with(b"\n") {
+        report.issue(
+            Issue::ExtraHeaderEntry,
+            "invalid format - extra header(s) after 'tagger'",
+        )?;
+    }
+    Ok(())
+}
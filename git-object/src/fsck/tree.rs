@@ -0,0 +1,148 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use bstr::ByteSlice;
+
+use crate::fsck::{Issue, Report, Stop};
+
+/// A tree entry as parsed without validating its mode.
+struct Entry<'a> {
+    mode: &'a [u8],
+    name: &'a [u8],
+    id: &'a [u8],
+}
+
+impl Entry<'_> {
+    fn is_tree(&self) -> bool {
+        self.mode == b"40000" || self.mode == b"040000"
+    }
+}
+
+/// Check the serialized tree in `data`, reporting each kind of issue once.
+pub(crate) fn check(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    let hash_len = report.opts.object_hash.len_in_bytes();
+    let mut entries = Vec::new();
+    let mut cursor = data;
+    while !cursor.is_empty() {
+        match parse_entry(cursor, hash_len) {
+            Some((entry, remaining)) => {
+                entries.push(entry);
+                cursor = remaining;
+            }
+            None => return report.issue(Issue::BadTree, "cannot be parsed as a tree"),
+        }
+    }
+
+    let strict = report.opts.strict;
+    let mut seen = HashSet::new();
+    let (mut has_duplicates, mut is_unsorted) = (false, false);
+    for (idx, entry) in entries.iter().enumerate() {
+        has_duplicates |= !seen.insert(entry.name);
+        if let Some(previous) = idx.checked_sub(1).map(|idx| &entries[idx]) {
+            is_unsorted |= cmp_in_tree_order(previous, entry) == Ordering::Greater;
+        }
+    }
+
+    let any = |f: &dyn Fn(&Entry<'_>) -> bool| entries.iter().any(|e| f(e));
+    let checks: [(bool, Issue, &str); 10] = [
+        (
+            any(&|e| e.id.iter().all(|b| *b == 0)),
+            Issue::NullSha1,
+            "contains entries pointing to null sha1",
+        ),
+        (
+            any(&|e| e.name.contains(&b'/')),
+            Issue::FullPathname,
+            "contains full pathnames",
+        ),
+        (any(&|e| e.name.is_empty()), Issue::EmptyName, "contains empty pathname"),
+        (any(&|e| e.name == b"."), Issue::HasDot, "contains '.'"),
+        (any(&|e| e.name == b".."), Issue::HasDotdot, "contains '..'"),
+        (any(&|e| is_dotgit(e.name)), Issue::HasDotgit, "contains '.git'"),
+        (
+            any(&|e| e.mode.first() == Some(&b'0')),
+            Issue::ZeroPaddedFilemode,
+            "contains zero-padded file modes",
+        ),
+        (
+            any(&|e| !is_valid_mode(e.mode, strict)),
+            Issue::BadFilemode,
+            "contains bad file modes",
+        ),
+        (
+            has_duplicates,
+            Issue::DuplicateEntries,
+            "contains duplicate file entries",
+        ),
+        (is_unsorted, Issue::TreeNotSorted, "not properly sorted"),
+    ];
+    for (is_present, issue, message) in checks.iter() {
+        if *is_present {
+            report.issue(*issue, *message)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_entry(data: &[u8], hash_len: usize) -> Option<(Entry<'_>, &[u8])> {
+    let space = data.find_byte(b' ')?;
+    let mode = &data[..space];
+    if mode.is_empty() || !mode.iter().all(|b| (b'0'..=b'7').contains(b)) {
+        return None;
+    }
+    let data = &data[space + 1..];
+    let nul = data.find_byte(0)?;
+    let name = &data[..nul];
+    let id = data.get(nul + 1..nul + 1 + hash_len)?;
+    Some((Entry { mode, name, id }, &data[nul + 1 + hash_len..]))
+}
+
+fn is_valid_mode(mode: &[u8], strict: bool) -> bool {
+    let mode = mode.strip_prefix(b"0").unwrap_or(mode);
+    match mode {
+        b"100755" | b"100644" | b"120000" | b"40000" | b"160000" => true,
+        b"100664" => !strict,
+        _ => false,
+    }
+}
+
+/// Compare entries the way git sorts them, with trees sorting as if their name had a trailing slash.
+fn cmp_in_tree_order(a: &Entry<'_>, b: &Entry<'_>) -> Ordering {
+    let key = |e: &Entry<'_>| {
+        let slash: &[u8] = if e.is_tree() { b"/" } else { b"" };
+        e.name.iter().chain(slash.iter()).copied().collect::<Vec<_>>()
+    };
+    key(a).cmp(&key(b))
+}
+
+/// Return true if `name` would be treated as `.git` on case-insensitive file systems, as well as on HFS+ and NTFS,
+/// which ignore certain characters or support short names and alternate data streams.
+fn is_dotgit(name: &[u8]) -> bool {
+    is_hfs_dotgit(name) || is_ntfs_dotgit(name)
+}
+
+/// HFS+ ignores certain unicode code points in names, see `is_hfs_dotgit()` in git.
+fn is_hfs_dotgit(name: &[u8]) -> bool {
+    let is_ignorable = |c: char| {
+        matches!(c,
+            '\u{200c}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{206a}'..='\u{206f}' | '\u{feff}')
+    };
+    let mut chars = name.chars().filter(|c| !is_ignorable(*c));
+    ".git"
+        .chars()
+        .all(|expected| chars.next().map_or(false, |c| c.to_ascii_lowercase() == expected))
+        && chars.next().is_none()
+}
+
+/// NTFS ignores trailing spaces and periods, supports the short name `git~1` and alternate data streams after `:`,
+/// see `is_ntfs_dotgit()` in git.
+fn is_ntfs_dotgit(name: &[u8]) -> bool {
+    let end = name
+        .iter()
+        .position(|b| matches!(b, b'\\' | b'/' | b':'))
+        .unwrap_or(name.len());
+    let name = &name[..end];
+    let only_spaces_and_periods_after =
+        |skip: usize| name.len() >= skip && name[skip..].iter().all(|b| matches!(b, b' ' | b'.'));
+    (only_spaces_and_periods_after(4) && name[..4].eq_ignore_ascii_case(b".git"))
+        || (only_spaces_and_periods_after(5) && name[..5].eq_ignore_ascii_case(b"git~1"))
+}
@@ -31,7 +31,11 @@ mod traits;
 pub use traits::WriteTo;
 
 pub mod encode;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod fsck;
 pub(crate) mod parse;
+pub mod trailer;
 
 ///
 pub mod kind;
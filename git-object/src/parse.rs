@@ -79,3 +79,9 @@ pub(crate) fn signature<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
 ) -> IResult<&'a [u8], git_actor::SignatureRef<'a>, E> {
     git_actor::signature::decode(i)
 }
+
+pub(crate) fn signature_lenient<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], git_actor::LenientSignatureRef<'a>, E> {
+    git_actor::signature::decode_lenient(i)
+}
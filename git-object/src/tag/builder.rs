@@ -0,0 +1,108 @@
+use bstr::{BString, ByteSlice};
+use git_hash::ObjectId;
+use quick_error::quick_error;
+
+use crate::{encode, Kind, Tag, TagRef, WriteTo};
+
+quick_error! {
+    /// The error returned by [`Builder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        InvalidName(err: crate::tag::write::Error) {
+            display("The tag name is invalid")
+            from()
+            source(err)
+        }
+        InvalidTagger(reason: &'static str) {
+            display("The tagger of the tag is invalid: {}", reason)
+        }
+        InvalidSignature {
+            display("The signature must be ASCII-armored, starting with '-----BEGIN ' and ending with a newline")
+        }
+        RoundTrip {
+            display("The tag would not be decoded into the same tag after serialization")
+        }
+    }
+}
+
+/// A builder for [`Tags`][Tag] which validates all fields so that the resulting tag serializes correctly and
+/// decodes into the same tag again.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    target: ObjectId,
+    target_kind: Kind,
+    name: BString,
+    tagger: Option<git_actor::Signature>,
+    message: BString,
+    pgp_signature: Option<BString>,
+}
+
+impl Builder {
+    /// Create a new builder for a tag called `name` pointing to `target` of kind `target_kind`, without tagger and message.
+    pub fn new(target: impl Into<ObjectId>, target_kind: Kind, name: impl Into<BString>) -> Self {
+        Builder {
+            target: target.into(),
+            target_kind,
+            name: name.into(),
+            tagger: None,
+            message: Default::default(),
+            pgp_signature: None,
+        }
+    }
+
+    /// Set the `tagger` of the tag.
+    pub fn tagger(mut self, tagger: git_actor::Signature) -> Self {
+        self.tagger = Some(tagger);
+        self
+    }
+
+    /// Set the tag `message`.
+    pub fn message(mut self, message: impl Into<BString>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Set the ASCII-armored `signature`, produced over the serialized tag without it.
+    pub fn pgp_signature(mut self, signature: impl Into<BString>) -> Self {
+        self.pgp_signature = Some(signature.into());
+        self
+    }
+
+    /// Validate all fields and produce the tag, or fail if it wouldn't decode into the same tag after serialization.
+    pub fn build(self) -> Result<Tag, Error> {
+        crate::tag::write::validated_name(self.name.as_bstr())?;
+        if let Some(reason) = self.tagger.as_ref().and_then(encode::signature_defect) {
+            return Err(Error::InvalidTagger(reason));
+        }
+        if let Some(signature) = &self.pgp_signature {
+            if !signature.starts_with(b"-----BEGIN ") || !signature.ends_with(b"\n") {
+                return Err(Error::InvalidSignature);
+            }
+        }
+
+        let tag = Tag {
+            target: self.target,
+            target_kind: self.target_kind,
+            name: self.name,
+            tagger: self.tagger,
+            message: self.message,
+            pgp_signature: self.pgp_signature,
+        };
+        let mut buf = Vec::with_capacity(tag.size());
+        tag.write_to(&mut buf).map_err(|_| Error::RoundTrip)?;
+        let round_trips = TagRef::from_bytes(&buf).map_or(false, |decoded| Tag::from(decoded) == tag);
+        if round_trips {
+            Ok(tag)
+        } else {
+            Err(Error::RoundTrip)
+        }
+    }
+}
+
+impl Tag {
+    /// Return a [`Builder`] for a tag called `name` pointing to `target` of kind `target_kind`.
+    pub fn builder(target: impl Into<ObjectId>, target_kind: Kind, name: impl Into<BString>) -> Builder {
+        Builder::new(target, target_kind, name)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::TagRef;
+use crate::{Tag, TagRef};
 
 mod decode;
 
@@ -8,6 +8,10 @@ pub mod write;
 ///
 pub mod ref_iter;
 
+///
+pub mod builder;
+pub use builder::Builder;
+
 impl<'a> TagRef<'a> {
     /// Deserialize a tag from `data`.
     pub fn from_bytes(data: &'a [u8]) -> Result<TagRef<'a>, crate::decode::Error> {
@@ -19,4 +23,29 @@ impl<'a> TagRef<'a> {
     pub fn target(&self) -> git_hash::ObjectId {
         git_hash::ObjectId::from_hex(self.target).expect("prior validation")
     }
+
+    /// Return the message decoded from the encoding named `encoding` into UTF-8, or the message as is if it is `None`.
+    ///
+    /// Unlike commits, tags don't record the encoding of their message, so it has to be provided by the caller, for instance
+    /// from the `i18n.commitEncoding` configuration value.
+    #[cfg(feature = "encoding")]
+    pub fn message_utf8(
+        &self,
+        encoding: Option<&bstr::BStr>,
+    ) -> Result<std::borrow::Cow<'a, bstr::BStr>, crate::encoding::Error> {
+        crate::encoding::decode(self.message, encoding)
+    }
+}
+
+impl Tag {
+    /// Return the message decoded from the encoding named `encoding` into UTF-8, or the message as is if it is `None`,
+    /// just like [`TagRef::message_utf8()`].
+    #[cfg(feature = "encoding")]
+    pub fn message_utf8(
+        &self,
+        encoding: Option<&bstr::BStr>,
+    ) -> Result<std::borrow::Cow<'_, bstr::BStr>, crate::encoding::Error> {
+        use bstr::ByteSlice;
+        crate::encoding::decode(self.message.as_bstr(), encoding)
+    }
 }
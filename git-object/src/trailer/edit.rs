@@ -0,0 +1,237 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use quick_error::quick_error;
+
+use crate::trailer::{is_blank_line, last_line, locate, Item, Items, Trailers};
+
+quick_error! {
+    /// The error returned by [`apply()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        InvalidToken(token: BString) {
+            display("Trailer token {:?} must be non-empty and only contain alphanumeric characters or '-'", token)
+        }
+        InvalidValue(value: BString) {
+            display("Trailer value {:?} must not contain newlines", value)
+        }
+    }
+}
+
+/// Where to put a new trailer, the same as `trailer.where` in git.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Where {
+    /// After the last trailer.
+    End,
+    /// Before the first trailer.
+    Start,
+    /// After the last trailer with the same token, or at the end if there is none.
+    After,
+    /// Before the first trailer with the same token, or at the start if there is none.
+    Before,
+}
+
+impl Default for Where {
+    fn default() -> Self {
+        Where::End
+    }
+}
+
+impl Where {
+    fn is_after_or_end(&self) -> bool {
+        matches!(self, Where::After | Where::End)
+    }
+}
+
+/// What to do if a trailer with the same token exists already, the same as `trailer.ifExists` in git.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfExists {
+    /// Add the trailer unless the trailer next to where it would be added has the same token and value.
+    AddIfDifferentNeighbor,
+    /// Add the trailer unless a trailer with the same token and value exists.
+    AddIfDifferent,
+    /// Always add the trailer.
+    Add,
+    /// Remove the existing trailer and add the new one.
+    Replace,
+    /// Leave the existing trailer alone and don't add the new one.
+    DoNothing,
+}
+
+impl Default for IfExists {
+    fn default() -> Self {
+        IfExists::AddIfDifferentNeighbor
+    }
+}
+
+/// What to do if no trailer with the same token exists, the same as `trailer.ifMissing` in git.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfMissing {
+    /// Add the trailer.
+    Add,
+    /// Don't add the trailer.
+    DoNothing,
+}
+
+impl Default for IfMissing {
+    fn default() -> Self {
+        IfMissing::Add
+    }
+}
+
+/// A trailer to add to a message with [`apply()`], like `git interpret-trailers --trailer <token>: <value>` would.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edit {
+    /// The token of the trailer, like `Signed-off-by`.
+    pub token: BString,
+    /// The value of the trailer, like `Name <email>`.
+    pub value: BString,
+    /// Where to put the trailer.
+    pub location: Where,
+    /// What to do if a trailer with the same token exists already.
+    pub if_exists: IfExists,
+    /// What to do if no trailer with the same token exists.
+    pub if_missing: IfMissing,
+}
+
+impl Edit {
+    /// Create a new edit to add a trailer with `token` and `value` using the default configuration of git, which adds it
+    /// at the end unless the last trailer is the same.
+    pub fn new(token: impl Into<BString>, value: impl Into<BString>) -> Self {
+        Edit {
+            token: token.into(),
+            value: value.into(),
+            ..Default::default()
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.token.is_empty() || !self.token.iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-') {
+            return Err(Error::InvalidToken(self.token.clone()));
+        }
+        if self.value.contains(&b'\n') {
+            return Err(Error::InvalidValue(self.value.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// A line of the trailer block being edited.
+struct Line<'a> {
+    token: Option<&'a BStr>,
+    value: &'a BStr,
+}
+
+impl Line<'_> {
+    /// Like git, consider tokens the same if one is a case-insensitive prefix of the other, so `Ack` matches `Acked-by`.
+    fn has_token(&self, token: &BStr) -> bool {
+        self.token.map_or(false, |t| {
+            let len = t.len().min(token.len());
+            t[..len].eq_ignore_ascii_case(&token[..len])
+        })
+    }
+
+    fn is_same(&self, edit: &Line<'_>) -> bool {
+        edit.token.map_or(false, |token| self.has_token(token)) && self.value == edit.value
+    }
+}
+
+/// Apply all `edits` to the trailers of `message` in order and return the edited message, similar to what
+/// `git interpret-trailers` does.
+///
+/// Comments in the trailer block are removed and existing trailers are normalized to use `: ` as separator.
+/// Note that the message will always be followed by a blank line, even if there are no trailers.
+pub fn apply<'a>(message: &[u8], edits: impl IntoIterator<Item = &'a Edit>) -> Result<BString, Error> {
+    let edits: Vec<_> = edits.into_iter().collect();
+    for edit in &edits {
+        edit.validate()?;
+    }
+
+    let (start, end) = locate(message);
+    let mut lines: Vec<_> = Items {
+        lines: Trailers {
+            cursor: &message[start..end],
+        },
+    }
+    .map(|item| match item {
+        Item::Trailer(trailer) => Line {
+            token: Some(trailer.token),
+            value: trailer.value,
+        },
+        Item::Other(line) => Line {
+            token: None,
+            value: line,
+        },
+    })
+    .collect();
+    for edit in edits {
+        apply_edit(&mut lines, edit);
+    }
+
+    let mut out = BString::from(&message[..start]);
+    if !last_line(&message[..start]).map_or(false, |pos| is_blank_line(&message[pos..start])) {
+        out.push_byte(b'\n');
+    }
+    for line in lines {
+        if let Some(token) = line.token {
+            out.push_str(token);
+            out.push_str(": ");
+        }
+        out.push_str(line.value);
+        out.push_byte(b'\n');
+    }
+    out.push_str(&message[end..]);
+    Ok(out)
+}
+
+fn apply_edit<'a>(lines: &mut Vec<Line<'a>>, edit: &'a Edit) {
+    let new = Line {
+        token: Some(edit.token.as_bstr()),
+        value: edit.value.trim().as_bstr(),
+    };
+    let after_or_end = edit.location.is_after_or_end();
+    let mut indices: Box<dyn Iterator<Item = usize>> = if after_or_end {
+        Box::new((0..lines.len()).rev())
+    } else {
+        Box::new(0..lines.len())
+    };
+    let existing = match indices.find(|idx| lines[*idx].has_token(new.token.expect("set"))) {
+        Some(idx) => idx,
+        None => {
+            if edit.if_missing == IfMissing::Add {
+                let pos = if after_or_end { lines.len() } else { 0 };
+                lines.insert(pos, new);
+            }
+            return;
+        }
+    };
+
+    let neighbor = match edit.location {
+        Where::After | Where::Before => existing,
+        Where::End => lines.len() - 1,
+        Where::Start => 0,
+    };
+    let insert_at = if after_or_end { neighbor + 1 } else { neighbor };
+    let add = match edit.if_exists {
+        IfExists::DoNothing => false,
+        IfExists::Add | IfExists::Replace => true,
+        IfExists::AddIfDifferent => {
+            let mut candidates: Box<dyn Iterator<Item = usize>> = if after_or_end {
+                Box::new((0..=existing).rev())
+            } else {
+                Box::new(existing..lines.len())
+            };
+            !candidates.any(|idx| lines[idx].is_same(&new))
+        }
+        IfExists::AddIfDifferentNeighbor => !lines[neighbor].is_same(&new),
+    };
+    if add {
+        lines.insert(insert_at, new);
+        if edit.if_exists == IfExists::Replace {
+            lines.remove(if insert_at <= existing { existing + 1 } else { existing });
+        }
+    }
+}
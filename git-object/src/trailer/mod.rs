@@ -0,0 +1,192 @@
+//! Parse and edit trailers of commit and tag messages, like `Signed-off-by: Name <email>`, following the rules of
+//! [git-interpret-trailers](https://git-scm.com/docs/git-interpret-trailers).
+//!
+//! Trailers are only found in the last paragraph of a message, which isn't the title. It is considered a trailer block
+//! if all of its lines are trailers, or if at least a quarter of its lines are trailers and one of them was generated
+//! by git, like `Signed-off-by: ` or `(cherry picked from commit `. Lines starting with whitespace continue the value
+//! of the trailer before them, lines starting with `#` are comments and a patch after a `---` line isn't part of the message.
+//!
+//! The lines of the trailer block are parsed with the same [`Trailers`] iterator returned by
+//! [`BodyRef::trailers()`][crate::commit::message::BodyRef::trailers()], this module only adds finding the trailer block
+//! the way git does as well as editing it.
+use bstr::{BStr, ByteSlice};
+
+use crate::commit::message::body::{find_separator, first_line, COMMENT};
+pub use crate::commit::message::body::{TrailerRef, Trailers};
+
+mod edit;
+pub use edit::{apply, Edit, Error, IfExists, IfMissing, Where};
+
+/// Trailers that mark a trailer block even if it contains lines that aren't trailers.
+const GIT_GENERATED_PREFIXES: &[&[u8]] = &[b"Signed-off-by: ", b"(cherry picked from commit "];
+
+/// The block of trailers at the end of a message.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Block<'a> {
+    /// The lines of the trailer block including the last newline, if present.
+    pub lines: &'a BStr,
+    /// The offset of the first trailer line in the message.
+    pub start: usize,
+}
+
+/// A line in a [trailer block][Block].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Item<'a> {
+    /// A trailer, possibly spanning multiple lines.
+    Trailer(TrailerRef<'a>),
+    /// A line that isn't a trailer, without its newline.
+    Other(&'a BStr),
+}
+
+impl<'a> Item<'a> {
+    /// Return the trailer if this item is one.
+    pub fn trailer(self) -> Option<TrailerRef<'a>> {
+        match self {
+            Item::Trailer(trailer) => Some(trailer),
+            Item::Other(_) => None,
+        }
+    }
+}
+
+impl<'a> Block<'a> {
+    /// Find the trailer block in `message`, or return `None` if there is none.
+    pub fn from_message(message: &'a [u8]) -> Option<Self> {
+        let (start, end) = locate(message);
+        (start < end).then(|| Block {
+            lines: message[start..end].as_bstr(),
+            start,
+        })
+    }
+
+    /// Return an iterator over all trailers and other lines in the block, skipping comments.
+    pub fn items(&self) -> Items<'a> {
+        Items {
+            lines: Trailers { cursor: self.lines },
+        }
+    }
+
+    /// Return an iterator over all trailers in the block.
+    pub fn trailers(&self) -> Trailers<'a> {
+        Trailers { cursor: self.lines }
+    }
+}
+
+/// Return an iterator over all trailers in the trailer block of `message`, which may be empty.
+pub fn parse(message: &[u8]) -> impl Iterator<Item = TrailerRef<'_>> {
+    Block::from_message(message)
+        .into_iter()
+        .flat_map(|block| block.trailers())
+}
+
+/// An iterator over the [items][Item] of a trailer [`Block`].
+pub struct Items<'a> {
+    pub(crate) lines: Trailers<'a>,
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next_line().map(|line| match line {
+            Ok(trailer) => Item::Trailer(trailer),
+            Err(other) => Item::Other(other),
+        })
+    }
+}
+
+/// Return the start and end of the trailer block in `message`, which are equal if there is none.
+pub(crate) fn locate(message: &[u8]) -> (usize, usize) {
+    let end = end_of_log_message(message);
+    (find_trailer_start(&message[..end]), end)
+}
+
+/// Return the end of the actual message, excluding a patch after a `---` line as well as trailing comments and blank lines.
+fn end_of_log_message(message: &[u8]) -> usize {
+    let mut patch_start = 0;
+    while patch_start < message.len() {
+        let line = &message[patch_start..];
+        if line.starts_with(b"---") && line.get(3).map_or(false, u8::is_ascii_whitespace) {
+            break;
+        }
+        patch_start += first_line(line).map_or(line.len(), <[u8]>::len);
+    }
+    let message = &message[..patch_start];
+
+    let mut start_of_ignored = None;
+    let mut pos = 0;
+    while let Some(line) = first_line(&message[pos..]) {
+        if line[0] == COMMENT || line[0] == b'\n' {
+            start_of_ignored.get_or_insert(pos);
+        } else {
+            start_of_ignored = None;
+        }
+        pos += line.len();
+    }
+    start_of_ignored.unwrap_or(patch_start)
+}
+
+/// Return the start of the trailer block in `message`, or its length if there is none.
+fn find_trailer_start(message: &[u8]) -> usize {
+    let mut end_of_title = 0;
+    while let Some(line) = first_line(&message[end_of_title..]) {
+        if line[0] != COMMENT && is_blank_line(line) {
+            break;
+        }
+        end_of_title += line.len();
+    }
+
+    let mut only_spaces = true;
+    let mut recognized_prefix = false;
+    let (mut trailer_lines, mut non_trailer_lines, mut possible_continuation_lines) = (0, 0, 0);
+    let mut end = message.len();
+    while let Some(line_start) = last_line(&message[..end]).filter(|pos| *pos >= end_of_title) {
+        let line = &message[line_start..];
+        end = line_start;
+        if line[0] == COMMENT {
+            non_trailer_lines += possible_continuation_lines;
+            possible_continuation_lines = 0;
+            continue;
+        }
+        if is_blank_line(line) {
+            if only_spaces {
+                continue;
+            }
+            non_trailer_lines += possible_continuation_lines;
+            let next_line = line_start + first_line(line).map_or(0, <[u8]>::len);
+            if (recognized_prefix && trailer_lines * 3 >= non_trailer_lines)
+                || (trailer_lines > 0 && non_trailer_lines == 0)
+            {
+                return next_line;
+            }
+            return message.len();
+        }
+        only_spaces = false;
+
+        if GIT_GENERATED_PREFIXES.iter().any(|prefix| line.starts_with(prefix)) {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+            recognized_prefix = true;
+        } else if find_separator(line).is_some() && !line[0].is_ascii_whitespace() {
+            trailer_lines += 1;
+            possible_continuation_lines = 0;
+        } else if line[0].is_ascii_whitespace() {
+            possible_continuation_lines += 1;
+        } else {
+            non_trailer_lines += 1 + possible_continuation_lines;
+            possible_continuation_lines = 0;
+        }
+    }
+    message.len()
+}
+
+/// Return the start of the last line in `buf`, whose last byte is considered part of the last line even if it is a newline.
+fn last_line(buf: &[u8]) -> Option<usize> {
+    match buf.len() {
+        0 => None,
+        len => Some(buf[..len - 1].rfind_byte(b'\n').map_or(0, |pos| pos + 1)),
+    }
+}
+
+fn is_blank_line(line: &[u8]) -> bool {
+    line.iter().take_while(|b| **b != b'\n').all(u8::is_ascii_whitespace)
+}
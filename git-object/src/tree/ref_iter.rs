@@ -1,5 +1,6 @@
-use std::convert::TryFrom;
+use std::{cmp::Ordering, convert::TryFrom};
 
+use bstr::BStr;
 use nom::error::ParseError;
 
 use crate::{tree, tree::EntryRef, TreeRef, TreeRefIter};
@@ -23,6 +24,19 @@ impl<'a> TreeRef<'a> {
     pub const fn empty() -> TreeRef<'static> {
         TreeRef { entries: Vec::new() }
     }
+
+    /// Find the entry called `name` using binary search, as entries are sorted.
+    pub fn bisect_entry(&self, name: &BStr) -> Option<EntryRef<'a>> {
+        [false, true].iter().find_map(|&is_tree| {
+            self.entries
+                .binary_search_by(|e| cmp_sort_key(e, name, is_tree))
+                .ok()
+                .map(|idx| &self.entries[idx])
+                // The sort key of a tree matches the name of its entry with a trailing slash.
+                .filter(|e| e.filename == name)
+                .cloned()
+        })
+    }
 }
 
 impl<'a> TreeRefIter<'a> {
@@ -30,6 +44,70 @@ impl<'a> TreeRefIter<'a> {
     pub fn entries(self) -> Result<Vec<EntryRef<'a>>, crate::decode::Error> {
         self.collect()
     }
+
+    /// Find the entry called `name` by decoding entries one at a time, stopping as soon as all following entries
+    /// would sort after it.
+    pub fn lookup_entry_by_name(self, name: &BStr) -> Result<Option<EntryRef<'a>>, crate::decode::Error> {
+        for entry in self {
+            let entry = entry?;
+            if entry.filename == name {
+                return Ok(Some(entry));
+            }
+            // `name` might be a tree, which sorts as if it had a trailing slash.
+            if cmp_sort_key(&entry, name, true) == Ordering::Greater {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Follow `path`, a list of path components, through this tree and its subtrees and return the entry it points to,
+    /// or `None` if there is no such entry.
+    ///
+    /// `find` is used to obtain subtrees, using `buf` as storage. If a subtree can't be found, `None` is returned as well.
+    pub fn lookup_entry<I, P>(
+        self,
+        path: I,
+        buf: &mut Vec<u8>,
+        mut find: impl for<'b> FnMut(&git_hash::oid, &'b mut Vec<u8>) -> Option<TreeRefIter<'b>>,
+    ) -> Result<Option<tree::Entry>, crate::decode::Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut path = path.into_iter();
+        let mut entry: tree::Entry = match path.next() {
+            Some(name) => match self.lookup_entry_by_name(name.as_ref().into())? {
+                Some(entry) => entry.into(),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        for name in path {
+            if !entry.mode.is_tree() {
+                return Ok(None);
+            }
+            let tree = match find(&entry.oid, buf) {
+                Some(tree) => tree,
+                None => return Ok(None),
+            };
+            entry = match tree.lookup_entry_by_name(name.as_ref().into())? {
+                Some(next) => next.into(),
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(entry))
+    }
+}
+
+/// Compare `entry` to `name` in tree order, in which trees sort as if their name had a trailing slash.
+fn cmp_sort_key(entry: &EntryRef<'_>, name: &BStr, name_is_tree: bool) -> Ordering {
+    let slash = |is_tree: bool| if is_tree { &b"/"[..] } else { &[] };
+    entry
+        .filename
+        .iter()
+        .chain(slash(entry.mode.is_tree()))
+        .cmp(name.iter().chain(slash(name_is_tree)))
 }
 
 impl<'a> Iterator for TreeRefIter<'a> {
@@ -0,0 +1,187 @@
+use git_actor::{Sign, Signature, Time};
+use git_object::{bstr::ByteSlice, commit, tag, Commit, CommitRef, Kind, Tag, TagRef, WriteTo};
+
+use crate::hex_to_id;
+
+fn signature() -> Signature {
+    Signature {
+        name: "Sebastian Thiel".into(),
+        email: "sebastian.thiel@icloud.com".into(),
+        time: Time {
+            seconds_since_unix_epoch: 1528473343,
+            offset_in_seconds: 9000,
+            sign: Sign::Plus,
+        },
+    }
+}
+
+fn serialize(object: &impl WriteTo) -> Vec<u8> {
+    let mut buf = Vec::new();
+    object.write_to(&mut buf).expect("valid object");
+    buf
+}
+
+mod commit_builder {
+    use super::*;
+
+    #[test]
+    fn minimal_commit_uses_author_as_committer() -> crate::Result {
+        let commit = Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+            .author(signature())
+            .message("initial\n")
+            .build()?;
+        assert_eq!(commit.committer, commit.author);
+        assert_eq!(
+            serialize(&commit).as_bstr(),
+            "tree 9bed6275068a0575243ba8409253e61af81ab2ff
+author Sebastian Thiel <sebastian.thiel@icloud.com> 1528473343 +0230
+committer Sebastian Thiel <sebastian.thiel@icloud.com> 1528473343 +0230
+
+initial
+"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn extra_headers_round_trip() -> crate::Result {
+        let tag = Tag::builder(
+            hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"),
+            Kind::Commit,
+            "v1.0",
+        )
+        .tagger(signature())
+        .message("release\n")
+        .build()?;
+        let commit = Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+            .parents([
+                hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"),
+                hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ed"),
+            ])
+            .author(signature())
+            .encoding("ISO-8859-1")
+            .mergetag(&tag)?
+            .gpgsig("-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----\n")
+            .message("merge\n")
+            .build()?;
+
+        let buf = serialize(&commit);
+        assert_eq!(Commit::from(CommitRef::from_bytes(&buf)?), commit);
+        let extra = &commit.extra_headers;
+        assert_eq!(extra[0].0, "mergetag");
+        assert_eq!(
+            extra[0].1,
+            serialize(&tag).as_bstr().trim_end_with(|c| c == '\n'),
+            "the tag is stored without trailing newline"
+        );
+        assert_eq!(
+            commit.extra_headers().pgp_signature().map(|s| s.to_owned()),
+            Some("-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validation() {
+        let tree = hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff");
+        assert!(matches!(
+            Commit::builder(tree).build(),
+            Err(commit::builder::Error::MissingSignature("author"))
+        ));
+
+        let mut bad = signature();
+        bad.email = "a>b".into();
+        assert!(matches!(
+            Commit::builder(tree).author(signature()).committer(bad).build(),
+            Err(commit::builder::Error::InvalidSignature { field: "committer", .. })
+        ));
+
+        assert!(matches!(
+            Commit::builder(tree).author(signature()).encoding("UTF 8").build(),
+            Err(commit::builder::Error::InvalidEncoding(_))
+        ));
+
+        for name in ["parent", "", "with space"] {
+            assert!(
+                matches!(
+                    Commit::builder(tree)
+                        .author(signature())
+                        .extra_header(name, "value")
+                        .build(),
+                    Err(commit::builder::Error::InvalidHeaderName(_))
+                ),
+                "standard or malformed header names can't be used for extra headers"
+            );
+        }
+        assert!(matches!(
+            Commit::builder(tree).author(signature()).extra_header("x", "").build(),
+            Err(commit::builder::Error::EmptyHeaderValue(_))
+        ));
+    }
+
+    #[test]
+    fn trailers_are_added_to_the_message() -> crate::Result {
+        let commit = Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+            .author(signature())
+            .trailer("Signed-off-by", "Sebastian Thiel <sebastian.thiel@icloud.com>")
+            .message("subject\n\nbody\n")
+            .trailer("Co-authored-by", "Other <other@example.com>")
+            .trailer("Co-authored-by", "Other <other@example.com>")
+            .build()?;
+        assert_eq!(
+            commit.message,
+            "subject\n\nbody\n\nSigned-off-by: Sebastian Thiel <sebastian.thiel@icloud.com>\nCo-authored-by: Other <other@example.com>\n",
+            "the same trailer isn't added twice in a row"
+        );
+
+        assert!(matches!(
+            Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+                .author(signature())
+                .trailer("Not a token", "value")
+                .build(),
+            Err(commit::builder::Error::Trailer(_))
+        ));
+        Ok(())
+    }
+}
+
+mod tag_builder {
+    use super::*;
+
+    #[test]
+    fn signed_tag_round_trips() -> crate::Result {
+        let tag = Tag::builder(
+            hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"),
+            Kind::Commit,
+            "v1.0",
+        )
+        .tagger(signature())
+        .message("release\n")
+        .pgp_signature("-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----\n")
+        .build()?;
+        let buf = serialize(&tag);
+        assert_eq!(Tag::from(TagRef::from_bytes(&buf)?), tag);
+        Ok(())
+    }
+
+    #[test]
+    fn validation() {
+        let target = hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec");
+        assert!(matches!(
+            Tag::builder(target, Kind::Commit, "-v1").build(),
+            Err(tag::builder::Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            Tag::builder(target, Kind::Commit, "v1")
+                .pgp_signature("not armored")
+                .build(),
+            Err(tag::builder::Error::InvalidSignature)
+        ));
+        let mut bad = signature();
+        bad.name = " padded".into();
+        assert!(matches!(
+            Tag::builder(target, Kind::Commit, "v1").tagger(bad).build(),
+            Err(tag::builder::Error::InvalidTagger(_))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use git_actor::{Sign, Signature, Time};
+use git_object::{bstr::ByteSlice, commit, encoding, trailer, Commit, CommitRef, Tag, TagRef, WriteTo};
+
+use crate::hex_to_id;
+
+fn signature() -> Signature {
+    Signature {
+        name: "Sebastian Thiel".into(),
+        email: "sebastian.thiel@icloud.com".into(),
+        time: Time {
+            seconds_since_unix_epoch: 1528473343,
+            offset_in_seconds: 9000,
+            sign: Sign::Plus,
+        },
+    }
+}
+
+mod decode {
+    use super::*;
+
+    #[test]
+    fn messages_without_or_with_utf8_encoding_are_borrowed_as_is() -> crate::Result {
+        for name in [None, Some("UTF-8".into()), Some("utf8".into())] {
+            let message = encoding::decode(b"h\xc3\xa9\n".as_bstr(), name)?;
+            assert!(matches!(message, std::borrow::Cow::Borrowed(_)));
+            assert_eq!(message.as_ref(), "hé\n");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_encodings_are_decoded_into_utf8() -> crate::Result {
+        assert_eq!(
+            encoding::decode(b"caf\xe9\n".as_bstr(), Some("ISO-8859-1".into()))?.as_ref(),
+            "café\n"
+        );
+        assert_eq!(
+            encoding::decode(b"\x93\xfa\x96\x7b\x8c\xea".as_bstr(), Some("SJIS".into()))?.as_ref(),
+            "日本語"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_encodings_and_malformed_messages_fail() {
+        assert!(matches!(
+            encoding::decode(b"message".as_bstr(), Some("no-such-encoding".into())),
+            Err(encoding::Error::Unknown(_))
+        ));
+        assert!(matches!(
+            encoding::decode(b"\x82".as_bstr(), Some("SJIS".into())),
+            Err(encoding::Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn commit_messages_are_decoded_according_to_their_encoding_header() -> crate::Result {
+        let buf = b"tree 9bed6275068a0575243ba8409253e61af81ab2ff
+author Sebastian Thiel <sebastian.thiel@icloud.com> 1528473343 +0230
+committer Sebastian Thiel <sebastian.thiel@icloud.com> 1528473343 +0230
+encoding ISO-8859-1
+
+caf\xe9
+
+Signed-off-by: Andr\xe9 <andre@example.com>
+";
+        let commit = CommitRef::from_bytes(buf)?;
+        let message = commit.message_utf8()?;
+        assert_eq!(message.as_ref(), "café\n\nSigned-off-by: André <andre@example.com>\n");
+        assert_eq!(Commit::from(commit).message_utf8()?, message);
+
+        let trailer = trailer::parse(&message).next().expect("one trailer");
+        assert_eq!(trailer.unfolded_value().to_string(), "André <andre@example.com>");
+        Ok(())
+    }
+
+    #[test]
+    fn tag_messages_are_decoded_according_to_the_given_encoding() -> crate::Result {
+        let buf = b"object 01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc
+type commit
+tag v1.0
+tagger Sebastian Thiel <sebastian.thiel@icloud.com> 1528473343 +0230
+
+caf\xe9
+";
+        let tag = TagRef::from_bytes(buf)?;
+        assert_eq!(
+            tag.message_utf8(None)?.as_ref(),
+            b"caf\xe9\n".as_bstr(),
+            "as is without encoding"
+        );
+        let message = tag.message_utf8(Some("ISO-8859-1".into()))?;
+        assert_eq!(message.as_ref(), "café\n");
+        assert_eq!(Tag::from(tag).message_utf8(Some("ISO-8859-1".into()))?, message);
+        Ok(())
+    }
+}
+
+mod encode {
+    use super::*;
+
+    #[test]
+    fn utf8_messages_are_encoded_into_legacy_encodings() -> crate::Result {
+        assert_eq!(
+            encoding::encode("café\n".into(), "ISO-8859-1".into())?.as_ref(),
+            b"caf\xe9\n".as_bstr()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_encodings_and_unmappable_characters_fail() {
+        assert!(matches!(
+            encoding::encode("message".into(), "UTF-16LE".into()),
+            Err(encoding::Error::Unsupported(_))
+        ));
+        assert!(matches!(
+            encoding::encode("日本語".into(), "ISO-8859-1".into()),
+            Err(encoding::Error::Unmappable(_))
+        ));
+    }
+
+    #[test]
+    fn builder_reencodes_the_message_after_applying_trailers() -> crate::Result {
+        let commit = Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+            .author(signature())
+            .reencode_message("ISO-8859-1")
+            .message("café\n")
+            .trailer("Signed-off-by", "André <andre@example.com>")
+            .build()?;
+        assert_eq!(commit.encoding.as_ref().map(|e| e.as_bstr()), Some("ISO-8859-1".into()));
+        assert_eq!(
+            commit.message.as_bstr(),
+            b"caf\xe9\n\nSigned-off-by: Andr\xe9 <andre@example.com>\n".as_bstr()
+        );
+
+        let mut buf = Vec::new();
+        commit.write_to(&mut buf)?;
+        assert_eq!(
+            CommitRef::from_bytes(&buf)?.message_utf8()?.as_ref(),
+            "café\n\nSigned-off-by: André <andre@example.com>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn builder_fails_if_the_message_cannot_be_reencoded() {
+        let err = Commit::builder(hex_to_id("9bed6275068a0575243ba8409253e61af81ab2ff"))
+            .author(signature())
+            .reencode_message("ISO-8859-1")
+            .message("日本語\n")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, commit::builder::Error::Reencode { .. }));
+    }
+}
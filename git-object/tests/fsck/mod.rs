@@ -0,0 +1,293 @@
+use git_object::{
+    fsck::{self, Issue, Options, Severity},
+    Kind,
+};
+
+use crate::fixture_bytes;
+
+fn issues(kind: Kind, data: &[u8], opts: &Options) -> Vec<(Issue, Severity)> {
+    fsck::check(kind, data, opts)
+        .findings
+        .into_iter()
+        .map(|f| (f.issue, f.severity))
+        .collect()
+}
+
+fn commit_with_author(author: &str) -> Vec<u8> {
+    format!(
+        "tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor {}\ncommitter a <b> 1 +0000\n\nmessage\n",
+        author
+    )
+    .into_bytes()
+}
+
+fn tree(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for (idx, (mode, name)) in entries.iter().enumerate() {
+        buf.extend_from_slice(format!("{} {}\0", mode, name).as_bytes());
+        buf.extend_from_slice(&[idx as u8 + 1; 20]);
+    }
+    buf
+}
+
+#[test]
+fn fixtures_without_issues() {
+    for path in [
+        "commit/merge.txt",
+        "commit/mergetag.txt",
+        "commit/signed.txt",
+        "commit/unsigned.txt",
+        "commit/with-encoding.txt",
+    ] {
+        assert_eq!(
+            issues(Kind::Commit, &fixture_bytes(path), &Default::default()),
+            vec![],
+            "{}",
+            path
+        );
+    }
+    for path in ["tag/empty.txt", "tag/signed.txt", "tag/with-newlines.txt"] {
+        assert_eq!(
+            issues(Kind::Tag, &fixture_bytes(path), &Default::default()),
+            vec![],
+            "{}",
+            path
+        );
+    }
+    assert_eq!(
+        issues(Kind::Tree, &fixture_bytes("tree/everything.tree"), &Default::default()),
+        vec![]
+    );
+    assert_eq!(issues(Kind::Blob, b"anything\0", &Default::default()), vec![]);
+}
+
+#[test]
+fn tag_without_tagger_is_reported_as_info() {
+    assert_eq!(
+        issues(Kind::Tag, &fixture_bytes("tag/no-tagger.txt"), &Default::default()),
+        vec![(Issue::MissingTaggerEntry, Severity::Info)]
+    );
+}
+
+#[test]
+fn group_writable_file_modes_are_only_bad_in_strict_mode() {
+    let data = fixture_bytes("tree/maybe-special.tree");
+    assert_eq!(issues(Kind::Tree, &data, &Default::default()), vec![]);
+    let mut strict = Options::default();
+    strict.strict = true;
+    assert_eq!(
+        issues(Kind::Tree, &data, &strict),
+        vec![(Issue::BadFilemode, Severity::Error)],
+        "strict mode turns warnings into errors"
+    );
+    assert_eq!(
+        issues(
+            Kind::Tree,
+            &fixture_bytes("tree/definitely-special.tree"),
+            &Default::default()
+        ),
+        vec![(Issue::BadFilemode, Severity::Warn)]
+    );
+}
+
+#[test]
+fn signature_issues() {
+    for (author, expected) in [
+        ("<b> 1 +0000", Issue::MissingNameBeforeEmail),
+        ("a> 1 +0000", Issue::BadName),
+        ("a 1 +0000", Issue::MissingEmail),
+        ("a<b> 1 +0000", Issue::MissingSpaceBeforeEmail),
+        ("a <b 1 +0000", Issue::BadEmail),
+        ("a <b>1 +0000", Issue::MissingSpaceBeforeDate),
+        ("a <b> 01 +0000", Issue::ZeroPaddedDate),
+        ("a <b> 99999999999999999999 +0000", Issue::BadDateOverflow),
+        ("a <b> x +0000", Issue::BadDate),
+        ("a <b> 1 0000", Issue::BadTimezone),
+        ("a <b> 1 +00", Issue::BadTimezone),
+    ] {
+        assert_eq!(
+            issues(Kind::Commit, &commit_with_author(author), &Default::default()),
+            vec![(expected, Severity::Error)],
+            "{:?}",
+            author
+        );
+    }
+    assert_eq!(
+        issues(Kind::Commit, &commit_with_author("a <b> 0 +0000"), &Default::default()),
+        vec![],
+        "a zero date is fine"
+    );
+}
+
+#[test]
+fn commit_structure() {
+    let opts = Options::default();
+    assert_eq!(
+        issues(Kind::Commit, b"author a <b> 1 +0000\n\n", &opts),
+        vec![(Issue::MissingTree, Severity::Error)]
+    );
+    assert_eq!(
+        issues(Kind::Commit, b"tree abc\nauthor a <b> 1 +0000\n\n", &opts),
+        vec![(Issue::BadTreeSha1, Severity::Error)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Commit,
+            b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\ncommitter a <b> 1 +0000\n\n",
+            &opts
+        ),
+        vec![(Issue::MissingAuthor, Severity::Error)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Commit,
+            b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor a <b> 1 +0000\nauthor a <b> 1 +0000\ncommitter a <b> 1 +0000\n\n",
+            &opts
+        ),
+        vec![(Issue::MultipleAuthors, Severity::Error)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Commit,
+            b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor a <b> 1 +0000",
+            &opts
+        ),
+        vec![(Issue::UnterminatedHeader, Severity::Error)]
+    );
+
+    let mut with_nul = commit_with_author("a <b> 1 +0000");
+    with_nul.extend_from_slice(b"\0");
+    assert_eq!(
+        issues(Kind::Commit, &with_nul, &opts),
+        vec![(Issue::NulInCommit, Severity::Warn)]
+    );
+}
+
+#[test]
+fn tag_structure() {
+    let opts = Options::default();
+    let object = "object 01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc\n";
+    assert_eq!(
+        issues(Kind::Tag, b"type commit\ntag a\n\n", &opts),
+        vec![(Issue::MissingObject, Severity::Error)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Tag,
+            format!("{}type nothing\ntag a\n\n", object).as_bytes(),
+            &opts
+        ),
+        vec![(Issue::BadType, Severity::Error)]
+    );
+    assert_eq!(
+        issues(Kind::Tag, format!("{}type commit\n\n", object).as_bytes(), &opts),
+        vec![(Issue::MissingTagEntry, Severity::Error)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Tag,
+            format!("{}type commit\ntag a..b\ntagger a <b> 1 +0000\n\n", object).as_bytes(),
+            &opts
+        ),
+        vec![(Issue::BadTagName, Severity::Info)]
+    );
+    assert_eq!(
+        issues(
+            Kind::Tag,
+            format!("{}type commit\ntag a\ntagger a <b> 1 +0000\nextra header\n\n", object).as_bytes(),
+            &opts
+        ),
+        vec![],
+        "extra headers are ignored by default"
+    );
+}
+
+#[test]
+fn tree_entry_names() {
+    let opts = Options::default();
+    for (name, expected) in [
+        ("a/b", Issue::FullPathname),
+        ("", Issue::EmptyName),
+        (".", Issue::HasDot),
+        ("..", Issue::HasDotdot),
+        (".git", Issue::HasDotgit),
+        (".GIT", Issue::HasDotgit),
+        (".Git. . ", Issue::HasDotgit),
+        ("GIT~1", Issue::HasDotgit),
+        (".git::$INDEX_ALLOCATION", Issue::HasDotgit),
+        (".g\u{200c}it", Issue::HasDotgit),
+    ] {
+        assert_eq!(
+            issues(Kind::Tree, &tree(&[("100644", name)]), &opts),
+            vec![(expected, Severity::Warn)],
+            "{:?}",
+            name
+        );
+    }
+    for name in [".gitignore", "git~2", ".gi", "a.git"] {
+        assert_eq!(
+            issues(Kind::Tree, &tree(&[("100644", name)]), &opts),
+            vec![],
+            "{:?}",
+            name
+        );
+    }
+}
+
+#[test]
+fn tree_structure() {
+    let opts = Options::default();
+    assert_eq!(
+        issues(Kind::Tree, &tree(&[("100644", "b"), ("100644", "a")]), &opts),
+        vec![(Issue::TreeNotSorted, Severity::Error)]
+    );
+    assert_eq!(
+        issues(Kind::Tree, &tree(&[("100644", "a.b"), ("40000", "a")]), &opts),
+        vec![],
+        "trees sort as if they had a trailing slash"
+    );
+    assert_eq!(
+        issues(Kind::Tree, &tree(&[("100644", "a"), ("40000", "a")]), &opts),
+        vec![(Issue::DuplicateEntries, Severity::Error)]
+    );
+    assert_eq!(
+        issues(Kind::Tree, &tree(&[("040000", "a"), ("100600", "b")]), &opts),
+        vec![
+            (Issue::ZeroPaddedFilemode, Severity::Warn),
+            (Issue::BadFilemode, Severity::Warn)
+        ]
+    );
+    assert_eq!(
+        issues(Kind::Tree, b"100644 a\0too-short", &opts),
+        vec![(Issue::BadTree, Severity::Error)]
+    );
+
+    let mut null = tree(&[("100644", "a")]);
+    let len = null.len();
+    null[len - 20..].fill(0);
+    assert_eq!(
+        issues(Kind::Tree, &null, &opts),
+        vec![(Issue::NullSha1, Severity::Warn)]
+    );
+}
+
+#[test]
+fn configured_severities() -> crate::Result {
+    let mut opts = Options::default();
+    opts.set_from_config("hasDotgit", "error")?;
+    opts.set_from_config("MISSINGEMAIL", "ignore")?;
+    assert_eq!(
+        issues(Kind::Tree, &tree(&[("100644", ".git")]), &opts),
+        vec![(Issue::HasDotgit, Severity::Error)]
+    );
+    assert_eq!(issues(Kind::Commit, &commit_with_author("a 1 +0000"), &opts), vec![]);
+    assert!(fsck::check(Kind::Tree, &tree(&[("100644", ".git")]), &opts).has_errors());
+
+    assert!(matches!(
+        opts.set_severity(Issue::NulInHeader, Severity::Warn),
+        Err(fsck::Error::CannotDemote(Issue::NulInHeader))
+    ));
+    assert!(opts.set_from_config("notAnIssue", "warn").is_err());
+    assert!(opts.set_from_config("badDate", "fatal").is_err());
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use git_actor::{Sign, SignatureRef, Time};
-use git_object::{bstr::ByteSlice, commit::message::body::TrailerRef, CommitRef};
+use git_object::{bstr::ByteSlice, commit::message::body::TrailerRef, CommitRef, WriteTo};
 use smallvec::SmallVec;
 
 use crate::immutable::{
@@ -264,3 +264,42 @@ fn newline_right_after_signature_multiline_header() -> crate::Result {
     assert!(commit.message.starts_with(b"Rollup"));
     Ok(())
 }
+
+#[test]
+fn broken_signatures_are_parsed_leniently() -> crate::Result {
+    let input = b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d
+author Name <name@example.com> 1234 +051800
+committer Name without email 1234 +0000
+
+message";
+    assert!(
+        CommitRef::from_bytes(input).is_err(),
+        "strict parsing keeps rejecting broken signatures"
+    );
+
+    let commit = CommitRef::from_bytes_lenient(input)?;
+    assert_eq!(commit.author.raw, "Name <name@example.com> 1234 +051800");
+    assert_eq!(commit.author.name, "Name");
+    assert_eq!(commit.author.email.expect("present"), "name@example.com");
+    assert_eq!(commit.author.time, None, "the timezone is invalid");
+    assert_eq!(commit.committer.raw, "Name without email 1234 +0000");
+    assert_eq!(commit.committer.email, None);
+    assert_eq!(commit.message, "message");
+    assert!(!commit.is_valid());
+    assert_eq!(commit.to_strict(), None);
+
+    let mut buf = Vec::new();
+    commit.write_to(&mut buf)?;
+    assert_eq!(buf, input.as_ref(), "the raw bytes are written back as they were");
+    assert_eq!(commit.size(), input.len());
+    Ok(())
+}
+
+#[test]
+fn valid_signatures_parsed_leniently_convert_to_strict() -> crate::Result {
+    let fixture = fixture_bytes("commit", "unsigned.txt");
+    let commit = CommitRef::from_bytes_lenient(&fixture)?;
+    assert!(commit.is_valid());
+    assert_eq!(commit.to_strict(), Some(CommitRef::from_bytes(&fixture)?));
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use git_object::{commit::Headers, CommitRef};
+
+use crate::immutable::fixture_bytes;
+
+#[test]
+fn agrees_with_full_parse_for_all_fixtures() -> crate::Result {
+    for name in [
+        "merge.txt",
+        "mergetag.txt",
+        "message-with-footer.txt",
+        "signed-singleline.txt",
+        "signed-whitespace.txt",
+        "signed-with-encoding.txt",
+        "signed.txt",
+        "two-multiline-headers.txt",
+        "unsigned.txt",
+        "whitespace.txt",
+        "with-encoding.txt",
+    ] {
+        let data = fixture_bytes("commit", name);
+        let commit = CommitRef::from_bytes(&data)?;
+        let headers = Headers::from_bytes(&data)?;
+        assert_eq!(headers.tree, commit.tree(), "{}", name);
+        assert_eq!(
+            headers.parents().collect::<Vec<_>>(),
+            commit.parents().collect::<Vec<_>>(),
+            "{}",
+            name
+        );
+        assert_eq!(headers.parent_count(), commit.parents.len(), "{}", name);
+        assert_eq!(headers.author_time, commit.author.time, "{}", name);
+        assert_eq!(headers.committer_time, commit.committer.time, "{}", name);
+        assert_eq!(headers.committer_seconds(), commit.time().seconds_since_unix_epoch);
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_or_malformed_headers_are_errors() {
+    for input in [
+        &b""[..],
+        b"tree 123\n",
+        b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nparent 9bed6275068a0575243ba8409253e61af81ab2ff\n",
+        b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor a <b> 1 +0000\ncommitter a <b> 1 0000\n",
+    ] {
+        assert!(Headers::from_bytes(input).is_err());
+    }
+}
+
+#[test]
+fn message_and_signatures_are_not_parsed() -> crate::Result {
+    let headers = Headers::from_bytes(
+        b"tree 9bed6275068a0575243ba8409253e61af81ab2ff\nauthor a <b> 1 +0000\ncommitter a <b> 2 -0130\ngpgsig broken",
+    )?;
+    assert_eq!(headers.parent_count(), 0);
+    assert_eq!(headers.committer_time.offset_in_seconds, -5400);
+    assert_eq!(headers.committer_seconds(), 2);
+    assert_eq!(headers.author_seconds(), 1);
+    Ok(())
+}
+
+#[test]
+fn from_iter_before_iteration_only() -> crate::Result {
+    let data = fixture_bytes("commit", "unsigned.txt");
+    let mut iter = git_object::CommitRefIter::from_bytes(&data);
+    assert_eq!(iter.headers()?, Headers::from_bytes(&data)?);
+    iter.next();
+    assert!(iter.headers().is_err(), "the iterator was advanced already");
+    Ok(())
+}
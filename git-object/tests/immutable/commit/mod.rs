@@ -147,5 +147,6 @@ mod method {
 }
 
 mod from_bytes;
+mod headers;
 mod iter;
 mod message;
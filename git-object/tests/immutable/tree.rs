@@ -142,3 +142,98 @@ mod entry_mode {
         );
     }
 }
+
+mod lookup {
+    use git_object::{
+        tree::{Entry, EntryMode},
+        TreeRef, TreeRefIter,
+    };
+
+    use crate::hex_to_id;
+
+    fn entry(mode: EntryMode, name: &str, hex: &str) -> Entry {
+        Entry {
+            mode,
+            filename: name.into(),
+            oid: hex_to_id(hex),
+        }
+    }
+
+    const BLOB: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+    const SUBTREE: &str = "4d5fcadc293a348e88f777dc0920f11e7d71441c";
+
+    /// Serialize `entries` in the given order, which `Tree::write_to()` can't do as it doesn't sort trees like git.
+    fn serialize(entries: &[Entry]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for e in entries {
+            buf.extend_from_slice(e.mode.as_bytes());
+            buf.push(b' ');
+            buf.extend_from_slice(&e.filename);
+            buf.push(0);
+            buf.extend_from_slice(e.oid.as_bytes());
+        }
+        buf
+    }
+
+    /// Return the serialized root tree and the serialized `foo` subtree.
+    fn trees() -> (Vec<u8>, Vec<u8>) {
+        let root = serialize(&[
+            entry(EntryMode::Blob, "a", BLOB),
+            entry(EntryMode::Blob, "foo-bar", BLOB),
+            entry(EntryMode::Blob, "foo.txt", BLOB),
+            entry(EntryMode::Tree, "foo", SUBTREE),
+            entry(EntryMode::Blob, "zzz", BLOB),
+        ]);
+        let subtree = serialize(&[entry(EntryMode::BlobExecutable, "bar", BLOB)]);
+        (root, subtree)
+    }
+
+    #[test]
+    fn by_name_in_tree_order() -> crate::Result {
+        let (root, _) = trees();
+        let tree = TreeRef::from_bytes(&root)?;
+        for name in ["a", "foo-bar", "foo.txt", "foo", "zzz"] {
+            let expected = tree.entries.iter().find(|e| e.filename == name).cloned();
+            assert_eq!(tree.bisect_entry(name.into()), expected, "{}", name);
+            assert_eq!(
+                TreeRefIter::from_bytes(&root).lookup_entry_by_name(name.into())?,
+                expected,
+                "{}",
+                name
+            );
+        }
+        for name in ["", "b", "fo", "foo/", "foo0", "zzzz"] {
+            assert_eq!(tree.bisect_entry(name.into()), None, "{}", name);
+            assert_eq!(TreeRefIter::from_bytes(&root).lookup_entry_by_name(name.into())?, None);
+        }
+        Ok(())
+    }
+
+    fn lookup(root: &[u8], subtree: &[u8], path: &str) -> crate::Result<Option<Entry>> {
+        let mut buf = Vec::new();
+        Ok(
+            TreeRefIter::from_bytes(root).lookup_entry(path.split('/'), &mut buf, |id, buf| {
+                if id.to_owned() != hex_to_id(SUBTREE) {
+                    return None;
+                }
+                buf.clear();
+                buf.extend_from_slice(subtree);
+                Some(TreeRefIter::from_bytes(buf))
+            })?,
+        )
+    }
+
+    #[test]
+    fn by_path_through_subtrees() -> crate::Result {
+        let (root, subtree) = trees();
+        assert_eq!(
+            lookup(&root, &subtree, "foo/bar")?,
+            Some(entry(EntryMode::BlobExecutable, "bar", BLOB))
+        );
+        assert_eq!(lookup(&root, &subtree, "foo")?.map(|e| e.mode), Some(EntryMode::Tree));
+        assert_eq!(lookup(&root, &subtree, "foo/baz")?, None);
+        assert_eq!(lookup(&root, &subtree, "a/bar")?, None, "blobs have no entries");
+        assert_eq!(lookup(&root, &subtree, "missing/bar")?, None);
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
 use std::path::PathBuf;
 
+mod builder;
 mod encode;
+#[cfg(feature = "encoding")]
+mod encoding;
+mod fsck;
 mod immutable;
+mod trailer;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -0,0 +1,199 @@
+use git_object::{
+    bstr::ByteSlice,
+    trailer::{self, Block, Edit, IfExists, IfMissing, Item, Where},
+};
+
+fn trailers(message: &str) -> Vec<(String, String)> {
+    trailer::parse(message.as_bytes())
+        .map(|t| (t.token.to_string(), t.unfolded_value().to_string()))
+        .collect()
+}
+
+fn pairs(input: &[(&str, &str)]) -> Vec<(String, String)> {
+    input.iter().map(|(t, v)| (t.to_string(), v.to_string())).collect()
+}
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn the_last_paragraph_is_a_trailer_block_if_all_lines_are_trailers() {
+        assert_eq!(
+            trailers("subject\n\nbody\n\nA: b\nC: d\n"),
+            pairs(&[("A", "b"), ("C", "d")])
+        );
+        assert_eq!(trailers("subject\n\nbody\n\nA: b\nnot a trailer\n"), vec![]);
+    }
+
+    #[test]
+    fn the_title_is_never_a_trailer() {
+        assert_eq!(trailers("A: b\nC: d\n"), vec![]);
+        assert_eq!(trailers("subject\n\nA: b\n"), pairs(&[("A", "b")]));
+    }
+
+    #[test]
+    fn git_generated_trailers_allow_up_to_three_quarters_of_other_lines() {
+        assert_eq!(
+            trailers("subject\n\n(cherry picked from commit abc)\nfoo\nbar\nbaz\n"),
+            vec![],
+            "the prefix isn't a trailer with a separator"
+        );
+        let message = "subject\n\nbody\n\nSigned-off-by: me\nnot a trailer\nanother\nA: b\n";
+        assert_eq!(trailers(message), pairs(&[("Signed-off-by", "me"), ("A", "b")]));
+        let block = Block::from_message(message.as_bytes()).expect("present");
+        assert_eq!(block.start, 15);
+        assert_eq!(
+            block.items().collect::<Vec<_>>()[1],
+            Item::Other("not a trailer".into())
+        );
+        assert_eq!(
+            trailers("subject\n\nbody\n\nSigned-off-by: me\none\ntwo\nthree\nfour\n"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn continuation_lines_are_folded_and_whitespace_around_separators_is_ignored() {
+        let message = "subject\n\nbody\n\nAcked-by :  x\n   cont\n  more\n# comment\nC: d\n";
+        let trailer = trailer::parse(message.as_bytes()).next().expect("first trailer");
+        assert_eq!(trailer.token, "Acked-by");
+        assert_eq!(trailer.value, "x\n   cont\n  more");
+        assert_eq!(trailer.unfolded_value().as_ref(), "x cont more");
+        assert!(trailer.is("acked-BY"));
+        assert_eq!(trailers(message), pairs(&[("Acked-by", "x cont more"), ("C", "d")]));
+    }
+
+    #[test]
+    fn comments_blank_lines_and_patches_at_the_end_are_ignored() {
+        assert_eq!(trailers("subject\n\nA: b\n\n# comment\n#c2\n"), pairs(&[("A", "b")]));
+        assert_eq!(trailers("subject\n\nbody\n\nA: b\n---\npatch\n"), pairs(&[("A", "b")]));
+    }
+
+    #[test]
+    fn lines_starting_with_whitespace_are_no_trailers() {
+        assert_eq!(trailers("subject\n\n a: b\nC: d\n"), vec![]);
+    }
+}
+
+mod apply {
+    use super::*;
+
+    fn apply(message: &str, edits: &[Edit]) -> String {
+        trailer::apply(message.as_bytes(), edits)
+            .expect("valid edits")
+            .to_str_lossy()
+            .into_owned()
+    }
+
+    fn edit(token: &str, value: &str, location: Where, if_exists: IfExists, if_missing: IfMissing) -> Edit {
+        Edit {
+            token: token.into(),
+            value: value.into(),
+            location,
+            if_exists,
+            if_missing,
+        }
+    }
+
+    #[test]
+    fn a_blank_line_separates_new_trailers_from_the_message() {
+        let edits = [Edit::new("A", "b")];
+        assert_eq!(apply("subject\n", &edits), "subject\n\nA: b\n");
+        assert_eq!(apply("subject", &edits), "subject\nA: b\n", "like git does");
+        assert_eq!(apply("", &edits), "\nA: b\n");
+        assert_eq!(apply("subject\n", &[]), "subject\n\n");
+    }
+
+    #[test]
+    fn existing_trailers_are_normalized_and_trailing_comments_and_patches_are_kept() {
+        assert_eq!(
+            apply(
+                "subject\n\nbody\n\nAcked-by :  x\n   cont\n# comment\n---\npatch\n",
+                &[]
+            ),
+            "subject\n\nbody\n\nAcked-by: x\n   cont\n# comment\n---\npatch\n"
+        );
+        assert_eq!(
+            apply("subject\n\nA: b\n# comment\nC: d\n", &[]),
+            "subject\n\nA: b\nC: d\n",
+            "comments within the trailer block are removed"
+        );
+    }
+
+    #[test]
+    fn by_default_trailers_are_added_at_the_end_unless_the_last_one_is_the_same() {
+        let message = "subject\n\nbody\n\nA: b\nC: d\n";
+        assert_eq!(apply(message, &[Edit::new("A", "b")]), format!("{}A: b\n", message));
+        assert_eq!(apply(message, &[Edit::new("c", "d")]), message);
+    }
+
+    #[test]
+    fn tokens_match_if_one_is_a_prefix_of_the_other_like_in_git() {
+        let message = "subject\n\nAcked-by: x\nC: d\n";
+        assert_eq!(
+            apply(
+                message,
+                &[edit("A", "b", Where::End, IfExists::Replace, IfMissing::Add)]
+            ),
+            "subject\n\nC: d\nA: b\n"
+        );
+    }
+
+    #[test]
+    fn locations_and_conditions() {
+        let message = "subject\n\nbody\n\nA: 1\nB: 2\nA: 3\nB: 2\n";
+        for (edit, expected) in [
+            (
+                edit("B", "2", Where::End, IfExists::AddIfDifferent, IfMissing::Add),
+                "A: 1\nB: 2\nA: 3\nB: 2\n",
+            ),
+            (
+                edit("A", "1", Where::End, IfExists::AddIfDifferentNeighbor, IfMissing::Add),
+                "A: 1\nB: 2\nA: 3\nB: 2\nA: 1\n",
+            ),
+            (
+                edit("A", "4", Where::After, IfExists::Add, IfMissing::Add),
+                "A: 1\nB: 2\nA: 3\nA: 4\nB: 2\n",
+            ),
+            (
+                edit("B", "4", Where::Before, IfExists::Replace, IfMissing::Add),
+                "A: 1\nB: 4\nA: 3\nB: 2\n",
+            ),
+            (
+                edit("Z", "new", Where::Start, IfExists::Add, IfMissing::Add),
+                "Z: new\nA: 1\nB: 2\nA: 3\nB: 2\n",
+            ),
+            (
+                edit("Z", "new", Where::Start, IfExists::Add, IfMissing::DoNothing),
+                "A: 1\nB: 2\nA: 3\nB: 2\n",
+            ),
+            (
+                edit("A", "new", Where::End, IfExists::DoNothing, IfMissing::Add),
+                "A: 1\nB: 2\nA: 3\nB: 2\n",
+            ),
+        ] {
+            assert_eq!(
+                apply(message, &[edit.clone()]),
+                format!("subject\n\nbody\n\n{}", expected),
+                "{:?}",
+                edit
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_edits() {
+        assert!(matches!(
+            trailer::apply(b"subject\n", &[Edit::new("not a token", "b")]),
+            Err(trailer::Error::InvalidToken(_))
+        ));
+        assert!(matches!(
+            trailer::apply(b"subject\n", &[Edit::new("", "b")]),
+            Err(trailer::Error::InvalidToken(_))
+        ));
+        assert!(matches!(
+            trailer::apply(b"subject\n", &[Edit::new("A", "multi\nline")]),
+            Err(trailer::Error::InvalidValue(_))
+        ));
+    }
+}
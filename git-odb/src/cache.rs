@@ -17,6 +17,59 @@ pub type ObjectCache = dyn git_pack::cache::Object + Send + 'static;
 /// A constructor for boxed object caches.
 pub type NewObjectCacheFn = dyn Fn() -> Box<ObjectCache> + Send + Sync + 'static;
 
+/// The amount of hits and misses of a cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hits {
+    /// The amount of times an item was found in the cache.
+    pub hits: u64,
+    /// The amount of times an item wasn't found in the cache.
+    pub misses: u64,
+}
+
+impl Hits {
+    /// Return the ratio of hits to all cache accesses, or `None` if the cache wasn't accessed yet.
+    pub fn ratio(&self) -> Option<f32> {
+        let total = self.hits + self.misses;
+        (total != 0).then(|| self.hits as f32 / total as f32)
+    }
+}
+
+/// A snapshot of the metrics of a [`Cache`] along with the handle and store it uses, as obtained by [`Cache::metrics()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metrics {
+    /// The resource usage of the store shared by all handles.
+    pub store: crate::store::Metrics,
+    /// The files the store currently holds as memory map.
+    pub mapped_files: crate::store::metrics::MappedFiles,
+    /// The object lookups performed by our handle.
+    pub lookups: crate::store::metrics::Lookups,
+    /// Hits and misses of the cache for decoded pack entries, which are only counted if a pack cache is set.
+    pub pack_cache: Hits,
+    /// Hits and misses of the object cache, which are only counted if an object cache is set.
+    pub object_cache: Hits,
+}
+
+impl<S> Cache<crate::store::Handle<S>>
+where
+    S: Deref<Target = crate::Store> + Clone,
+{
+    /// Return a snapshot of all metrics collected by this instance, its handle and the store it belongs to.
+    ///
+    /// Note that cache metrics are specific to this instance, and are reset when it is cloned.
+    pub fn metrics(&self) -> Metrics {
+        let store = self.inner.store_ref();
+        Metrics {
+            store: store.metrics(),
+            mapped_files: store.mapped_files(),
+            lookups: self.inner.lookup_metrics(),
+            pack_cache: self.pack_cache_hits.get(),
+            object_cache: self.object_cache_hits.get(),
+        }
+    }
+}
+
 impl Cache<crate::store::Handle<Rc<crate::Store>>> {
     /// Convert this cache's handle into one that keeps its store in an arc. This creates an entirely new store,
     /// so should be done early to avoid unnecessary work (and mappings).
@@ -28,6 +81,8 @@ impl Cache<crate::store::Handle<Rc<crate::Store>>> {
             new_object_cache: self.new_object_cache,
             pack_cache: self.pack_cache,
             object_cache: self.object_cache,
+            pack_cache_hits: self.pack_cache_hits,
+            object_cache_hits: self.object_cache_hits,
         })
     }
 }
@@ -94,6 +149,8 @@ where
             new_pack_cache: None,
             object_cache: None,
             new_object_cache: None,
+            pack_cache_hits: Default::default(),
+            object_cache_hits: Default::default(),
         }
     }
 }
@@ -106,6 +163,8 @@ impl<S: Clone> Clone for Cache<S> {
             new_object_cache: self.new_object_cache.clone(),
             pack_cache: self.new_pack_cache.as_ref().map(|create| RefCell::new(create())),
             object_cache: self.new_object_cache.as_ref().map(|create| RefCell::new(create())),
+            pack_cache_hits: Default::default(),
+            object_cache_hits: Default::default(),
         }
     }
 }
@@ -125,12 +184,13 @@ impl<S> DerefMut for Cache<S> {
 }
 
 mod impls {
-    use std::{io::Read, ops::DerefMut};
+    use std::{cell::Cell, io::Read, ops::DerefMut};
 
     use git_hash::{oid, ObjectId};
-    use git_object::{Data, Kind};
+    use git_object::{Data, Kind, WriteTo};
     use git_pack::cache::Object;
 
+    use super::Hits;
     use crate::{pack::data::entry::Location, Cache};
 
     impl<S> crate::Write for Cache<S>
@@ -139,6 +199,14 @@ mod impls {
     {
         type Error = S::Error;
 
+        fn write(&self, object: impl WriteTo) -> Result<ObjectId, Self::Error> {
+            self.inner.write(object)
+        }
+
+        fn write_buf(&self, kind: Kind, from: &[u8]) -> Result<ObjectId, Self::Error> {
+            self.inner.write_buf(kind, from)
+        }
+
         fn write_stream(&self, kind: Kind, size: u64, from: impl Read) -> Result<ObjectId, Self::Error> {
             self.inner.write_stream(kind, size, from)
         }
@@ -175,7 +243,14 @@ mod impls {
             buffer: &'a mut Vec<u8>,
         ) -> Result<Option<(Data<'a>, Option<Location>)>, Self::Error> {
             match self.pack_cache.as_ref().map(|rc| rc.borrow_mut()) {
-                Some(mut pack_cache) => self.try_find_cached(id, buffer, pack_cache.deref_mut()),
+                Some(mut pack_cache) => self.try_find_cached(
+                    id,
+                    buffer,
+                    &mut CountHits {
+                        inner: pack_cache.deref_mut(),
+                        hits: &self.pack_cache_hits,
+                    },
+                ),
                 None => self.try_find_cached(id, buffer, &mut git_pack::cache::Never),
             }
         }
@@ -187,7 +262,9 @@ mod impls {
             pack_cache: &mut impl git_pack::cache::DecodeEntry,
         ) -> Result<Option<(Data<'a>, Option<git_pack::data::entry::Location>)>, Self::Error> {
             if let Some(mut obj_cache) = self.object_cache.as_ref().map(|rc| rc.borrow_mut()) {
-                if let Some(kind) = obj_cache.get(&id.as_ref().to_owned(), buffer) {
+                let kind = obj_cache.get(&id.as_ref().to_owned(), buffer);
+                record(&self.object_cache_hits, kind.is_some());
+                if let Some(kind) = kind {
                     return Ok(Some((Data::new(kind, buffer), None)));
                 }
             }
@@ -212,4 +289,35 @@ mod impls {
             self.inner.entry_by_location(location)
         }
     }
+
+    /// Counts hits and misses of the pack cache it wraps.
+    struct CountHits<'a, C> {
+        inner: &'a mut C,
+        hits: &'a Cell<Hits>,
+    }
+
+    impl<C> git_pack::cache::DecodeEntry for CountHits<'_, C>
+    where
+        C: git_pack::cache::DecodeEntry,
+    {
+        fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: Kind, compressed_size: usize) {
+            self.inner.put(pack_id, offset, data, kind, compressed_size)
+        }
+
+        fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(Kind, usize)> {
+            let res = self.inner.get(pack_id, offset, out);
+            record(self.hits, res.is_some());
+            res
+        }
+    }
+
+    fn record(hits: &Cell<Hits>, is_hit: bool) {
+        let mut current = hits.get();
+        if is_hit {
+            current.hits += 1;
+        } else {
+            current.misses += 1;
+        }
+        hits.set(current);
+    }
 }
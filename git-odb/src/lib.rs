@@ -11,7 +11,7 @@
 //! * multiple loose objects and pack locations as gathered from `alternates` files.
 
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     path::PathBuf,
     sync::{atomic::AtomicUsize, Arc},
 };
@@ -39,6 +39,8 @@ pub struct Cache<S> {
     new_object_cache: Option<Arc<cache::NewObjectCacheFn>>,
     pack_cache: Option<RefCell<Box<cache::PackCache>>>,
     object_cache: Option<RefCell<Box<cache::ObjectCache>>>,
+    pack_cache_hits: Cell<cache::Hits>,
+    object_cache_hits: Cell<cache::Hits>,
 }
 
 ///
@@ -63,9 +65,16 @@ pub fn sink(object_hash: git_hash::Kind) -> Sink {
 ///
 pub mod sink;
 
+///
+pub mod memory;
+
 ///
 pub mod find;
 
+///
+#[cfg(feature = "async")]
+pub mod nonblocking;
+
 /// An object database equivalent to `/dev/null`, dropping all objects stored into it.
 mod traits;
 
@@ -121,6 +130,8 @@ pub struct Store {
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: git_hash::Kind,
+    /// The zlib compression level to use when writing loose objects.
+    loose_compression_level: u32,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -0,0 +1,170 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    convert::TryInto,
+    io::Read,
+    ops::{Deref, DerefMut},
+};
+
+use git_hash::{oid, ObjectId};
+use git_object::{Data, Kind};
+
+/// The storage for objects held in memory, mapping their id to their kind and serialized data.
+pub type Storage = BTreeMap<ObjectId, (Kind, Vec<u8>)>;
+
+/// An object database which keeps all written objects in memory while reading through to an `inner` database
+/// for all objects it doesn't know itself.
+///
+/// This makes it possible to perform operations that create objects, like merges or rebases, without touching the
+/// `inner` database, which is useful for previewing their outcome or for tests.
+/// Objects can be written to the `inner` database using [`persist()`][Proxy::persist()].
+///
+/// For calling methods on `inner`, prefer to make use of auto-dereferencing, i.e. `proxy.inner_method()` instead of
+/// `proxy.inner.inner_method()`. Note that these won't see any objects held in memory.
+pub struct Proxy<T> {
+    inner: T,
+    memory: RefCell<Storage>,
+    object_hash: git_hash::Kind,
+}
+
+/// Lifecycle
+impl<T> Proxy<T> {
+    /// Create a new instance reading through to `inner` while holding all written objects in memory, hashing them with
+    /// `object_hash`.
+    pub fn new(inner: T, object_hash: git_hash::Kind) -> Self {
+        Proxy {
+            inner,
+            memory: Default::default(),
+            object_hash,
+        }
+    }
+
+    /// Return the `inner` database, discarding all objects held in memory.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Memory access
+impl<T> Proxy<T> {
+    /// Return the amount of objects currently held in memory.
+    pub fn num_objects_in_memory(&self) -> usize {
+        self.memory.borrow().len()
+    }
+
+    /// Remove all objects held in memory and return them, without writing them to the `inner` database.
+    pub fn take_object_memory(&mut self) -> Storage {
+        std::mem::take(self.memory.get_mut())
+    }
+
+    /// Write all objects held in memory into the `inner` database and return the amount of written objects.
+    ///
+    /// Objects are only removed from memory once all of them were written successfully.
+    pub fn persist(&self) -> Result<usize, T::Error>
+    where
+        T: crate::Write,
+    {
+        let mut memory = self.memory.borrow_mut();
+        for (kind, data) in memory.values() {
+            self.inner.write_buf(*kind, data)?;
+        }
+        let num_objects = memory.len();
+        memory.clear();
+        Ok(num_objects)
+    }
+}
+
+impl<T> Deref for Proxy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T> DerefMut for Proxy<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<T> crate::Write for Proxy<T> {
+    type Error = std::io::Error;
+
+    fn write_stream(&self, kind: Kind, size: u64, mut from: impl Read) -> Result<ObjectId, Self::Error> {
+        let mut buf = vec![0; size.try_into().expect("object size to fit into usize")];
+        from.read_exact(&mut buf)?;
+        let id = crate::Write::write_buf(&crate::sink(self.object_hash), kind, &buf)?;
+        self.memory.borrow_mut().insert(id, (kind, buf));
+        Ok(id)
+    }
+}
+
+impl<T> crate::Find for Proxy<T>
+where
+    T: crate::Find,
+{
+    type Error = T::Error;
+
+    fn contains(&self, id: impl AsRef<oid>) -> bool {
+        let id = id.as_ref();
+        self.memory.borrow().contains_key(id) || self.inner.contains(id)
+    }
+
+    fn try_find<'a>(&self, id: impl AsRef<oid>, buffer: &'a mut Vec<u8>) -> Result<Option<Data<'a>>, Self::Error> {
+        let id = id.as_ref();
+        match self.memory.borrow().get(id) {
+            Some((kind, data)) => {
+                buffer.clear();
+                buffer.extend_from_slice(data);
+                Ok(Some(Data::new(*kind, buffer)))
+            }
+            None => self.inner.try_find(id, buffer),
+        }
+    }
+}
+
+impl<T> git_pack::Find for Proxy<T>
+where
+    T: git_pack::Find,
+{
+    type Error = T::Error;
+
+    fn contains(&self, id: impl AsRef<oid>) -> bool {
+        let id = id.as_ref();
+        self.memory.borrow().contains_key(id) || self.inner.contains(id)
+    }
+
+    fn try_find_cached<'a>(
+        &self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl git_pack::cache::DecodeEntry,
+    ) -> Result<Option<(Data<'a>, Option<git_pack::data::entry::Location>)>, Self::Error> {
+        let id = id.as_ref();
+        match self.memory.borrow().get(id) {
+            Some((kind, data)) => {
+                buffer.clear();
+                buffer.extend_from_slice(data);
+                Ok(Some((Data::new(*kind, buffer), None)))
+            }
+            None => self.inner.try_find_cached(id, buffer, pack_cache),
+        }
+    }
+
+    fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<git_pack::data::entry::Location> {
+        let id = id.as_ref();
+        if self.memory.borrow().contains_key(id) {
+            return None;
+        }
+        self.inner.location_by_oid(id, buf)
+    }
+
+    fn pack_offsets_and_oid(&self, pack_id: u32) -> Option<Vec<(git_pack::data::Offset, ObjectId)>> {
+        self.inner.pack_offsets_and_oid(pack_id)
+    }
+
+    fn entry_by_location(&self, location: &git_pack::data::entry::Location) -> Option<git_pack::find::Entry> {
+        self.inner.entry_by_location(location)
+    }
+}
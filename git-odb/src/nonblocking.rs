@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use git_hash::ObjectId;
+use git_object::{Data, Kind, WriteTo};
+
+use crate::{store, Find, Write};
+
+/// An object as returned by [`Handle::try_find()`], owning its data as it is passed between threads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Object {
+    /// The kind of the object.
+    pub kind: Kind,
+    /// The decompressed and undeltified data of the object.
+    pub data: Vec<u8>,
+}
+
+impl Object {
+    /// Borrow the object's data for decoding it.
+    pub fn as_data(&self) -> Data<'_> {
+        Data::new(self.kind, &self.data)
+    }
+}
+
+/// The handles not currently in use, along with the one to clone if all of them are busy.
+struct Pool {
+    prototype: crate::HandleArc,
+    idle: Vec<crate::HandleArc>,
+}
+
+impl Pool {
+    fn checkout(&mut self) -> crate::HandleArc {
+        self.idle.pop().unwrap_or_else(|| self.prototype.clone())
+    }
+}
+
+/// A handle to access objects from `async` code without blocking the executor it runs on.
+///
+/// Lookups and writes involve IO as well as decompressing and undeltifying pack entries, which is why
+/// they are performed on the thread pool of the [`blocking`] crate, independently of the async runtime in use.
+/// Each operation takes an idle handle from a pool shared by all blocking threads, and creates a new one by cloning
+/// the handle this instance was created from if all of them are in use. Once done, the handle is returned to the pool
+/// for use by any thread. This way, concurrent operations don't block each other and reuse the caches of previous
+/// operations.
+///
+/// Clones of this instance share the same pool.
+#[derive(Clone)]
+pub struct Handle {
+    pool: Arc<parking_lot::Mutex<Pool>>,
+}
+
+impl From<crate::HandleArc> for Handle {
+    fn from(handle: crate::HandleArc) -> Self {
+        Handle::new(handle)
+    }
+}
+
+/// Lifecycle
+impl Handle {
+    /// Create a new instance to perform all operations with clones of `handle`, inheriting its configuration like
+    /// caches and the way missing objects are handled.
+    pub fn new(handle: crate::HandleArc) -> Self {
+        Handle {
+            pool: Arc::new(parking_lot::Mutex::new(Pool {
+                prototype: handle,
+                idle: Vec::new(),
+            })),
+        }
+    }
+}
+
+/// Access
+impl Handle {
+    /// Returns true if the object with `id` exists in the object database.
+    pub async fn contains(&self, id: impl Into<ObjectId>) -> bool {
+        let id = id.into();
+        self.with_handle(move |handle| handle.contains(id)).await
+    }
+
+    /// Find the object with `id`, or return `None` if it doesn't exist.
+    pub async fn try_find(&self, id: impl Into<ObjectId>) -> Result<Option<Object>, store::find::Error> {
+        let id = id.into();
+        self.with_handle(move |handle| {
+            let mut buf = Vec::new();
+            let kind = handle.try_find(id, &mut buf)?.map(|data| data.kind);
+            Ok(kind.map(|kind| Object { kind, data: buf }))
+        })
+        .await
+    }
+
+    /// Like [`try_find()`][Handle::try_find()], but fails if the object with `id` doesn't exist.
+    pub async fn find(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<Object, crate::find::existing::Error<store::find::Error>> {
+        let id = id.into();
+        self.try_find(id)
+            .await
+            .map_err(crate::find::existing::Error::Find)?
+            .ok_or(crate::find::existing::Error::NotFound { oid: id })
+    }
+
+    /// Write `object` to the object database and return its id.
+    pub async fn write(&self, object: impl WriteTo + Send + 'static) -> Result<ObjectId, store::write::Error> {
+        self.with_handle(move |handle| handle.write(object)).await
+    }
+
+    /// Write the serialized object of `kind` in `data` to the object database and return its id.
+    pub async fn write_buf(
+        &self,
+        kind: Kind,
+        data: impl AsRef<[u8]> + Send + 'static,
+    ) -> Result<ObjectId, store::write::Error> {
+        self.with_handle(move |handle| handle.write_buf(kind, data.as_ref()))
+            .await
+    }
+
+    /// Call `f` with a handle from our pool on the thread pool for blocking operations and return its result.
+    ///
+    /// This allows to perform multiple operations without hopping threads in between, or to use functionality not
+    /// provided by this type.
+    pub async fn with_handle<T>(&self, f: impl FnOnce(&crate::HandleArc) -> T + Send + 'static) -> T
+    where
+        T: Send + 'static,
+    {
+        let pool = Arc::clone(&self.pool);
+        blocking::unblock(move || {
+            let handle = pool.lock().checkout();
+            let res = f(&handle);
+            pool.lock().idle.push(handle);
+            res
+        })
+        .await
+    }
+}
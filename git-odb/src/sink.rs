@@ -4,7 +4,8 @@ use std::{
     io::{self, Write},
 };
 
-use git_features::zlib::stream::deflate;
+use git_features::{hash, zlib::stream::deflate};
+use git_object::WriteTo;
 
 use crate::Sink;
 
@@ -23,6 +24,25 @@ impl Sink {
 impl crate::traits::Write for Sink {
     type Error = io::Error;
 
+    /// Serialize `object` straight into the hasher and the compressor, if enabled, without buffering it first.
+    fn write(&self, object: impl WriteTo) -> Result<git_hash::ObjectId, Self::Error> {
+        let mut compressor = self
+            .compressor
+            .as_ref()
+            .map(|c| c.try_borrow_mut().expect("no recursion"));
+        let hash = {
+            let mut out = hash::Write::new(PossiblyCompress(compressor.as_deref_mut()), self.object_hash);
+            out.write_all(&object.loose_header())?;
+            object.write_to(&mut out)?;
+            out.hash
+        };
+        if let Some(mut c) = compressor {
+            c.flush()?;
+            c.reset();
+        }
+        Ok(hash.digest().into())
+    }
+
     fn write_stream(
         &self,
         kind: git_object::Kind,
@@ -64,3 +84,22 @@ impl crate::traits::Write for Sink {
         }
     }
 }
+
+/// Forwards all writes to the compressor if there is one, and discards them otherwise.
+struct PossiblyCompress<'a>(Option<&'a mut deflate::Write<io::Sink>>);
+
+impl io::Write for PossiblyCompress<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.0.as_mut() {
+            Some(compressor) => compressor.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.0.as_mut() {
+            Some(compressor) => compressor.flush(),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::store::{load_index::Either, types::IndexAndPacks};
+
+mod error {
+    use std::path::PathBuf;
+
+    /// Returned by [`Store::delete_expired_packs()`][crate::Store::delete_expired_packs()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Expected a path to a pack index or data file, got '{}'", .path.display())]
+        InvalidPackPath { path: PathBuf },
+        #[error(
+            "The pack at '{}' is still referenced by the multi-pack index at '{}' which has to be rewritten first",
+            .index_path.display(),
+            .multi_index_path.display()
+        )]
+        ReferencedByMultiIndex {
+            index_path: PathBuf,
+            multi_index_path: PathBuf,
+        },
+        #[error("Object {id} of the pack at '{}' isn't contained in any of the remaining packs or loose objects", .index_path.display())]
+        ObjectNotCovered {
+            id: git_hash::ObjectId,
+            index_path: PathBuf,
+        },
+        #[error(transparent)]
+        IndexOpen(#[from] git_pack::index::init::Error),
+        #[error(transparent)]
+        MultiIndexOpen(#[from] git_pack::multi_index::init::Error),
+        #[error(transparent)]
+        LoadIndex(#[from] crate::store::load_index::Error),
+    }
+}
+pub use error::Error;
+
+/// Options for use in [`Store::delete_expired_packs()`][crate::Store::delete_expired_packs()].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// If true, default true, fail if any object of the packs to delete isn't contained in a pack that remains or available as
+    /// loose object.
+    ///
+    /// Only disable this if the caller already knows that the packs were superseded, for instance after writing a pack
+    /// containing all of their objects.
+    pub verify_coverage: bool,
+    /// The amount of times to retry deleting a file after failing to do so, default 3.
+    ///
+    /// This is useful on Windows where files can't be deleted while other processes have them open.
+    pub retries: usize,
+    /// The time to wait before the first retry, which doubles with each additional retry.
+    pub retry_delay: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            verify_coverage: true,
+            retries: 3,
+            retry_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Returned by [`Store::delete_expired_packs()`][crate::Store::delete_expired_packs()].
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The paths to the index files of packs which were deleted.
+    pub deleted: Vec<PathBuf>,
+    /// The paths to the index files of packs which were left untouched as a `.keep` file exists for them.
+    pub kept: Vec<PathBuf>,
+    /// The paths to the index files of packs which could not be deleted, along with the error that prevented it.
+    ///
+    /// If the deletion of the index file succeeded, the pack is already invisible to readers and only some of
+    /// its other files remain. Otherwise the pack is still fully usable.
+    pub failed: Vec<(PathBuf, std::io::Error)>,
+}
+
+/// The extensions of files belonging to a pack, in the order in which they are deleted.
+///
+/// The index goes first so that the pack isn't discovered anymore even if deleting the remaining files fails.
+const PACK_FILE_EXTENSIONS: &[&str] = &["idx", "pack", "rev", "bitmap", "promisor", "mtimes"];
+
+impl super::Store {
+    /// Delete the packs identified by the paths to their index or data files in `pack_paths`, typically after they have been
+    /// superseded by a repack, and return information about which packs were actually deleted.
+    ///
+    /// Packs must be located in the `pack` directory of our objects directory. Packs for which a `.keep` file exists are
+    /// never deleted, and it's an error if a pack is still referenced by the multi-pack index.
+    /// Unless disabled in `options`, all objects of the packs to delete must be contained in the remaining packs
+    /// or in the loose object database, otherwise nothing is deleted.
+    ///
+    /// Before deleting, memory maps of the packs held by this store are released so that they don't keep files open,
+    /// unless handles require stable pack ids in which case they are kept alive until handles release them.
+    /// Afterwards the store is refreshed so handles learn about the removal and look for objects in other packs instead.
+    pub fn delete_expired_packs(
+        &self,
+        pack_paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let pack_dir = self.path.join("pack");
+        let mut outcome = Outcome::default();
+        let mut index_paths = Vec::new();
+        for path in pack_paths {
+            let path = path.as_ref();
+            let file_name = match path.file_name() {
+                Some(name) if matches!(path.extension().and_then(OsStr::to_str), Some("idx") | Some("pack")) => name,
+                _ => return Err(Error::InvalidPackPath { path: path.into() }),
+            };
+            let index_path = pack_dir.join(file_name).with_extension("idx");
+            if index_path.with_extension("keep").is_file() {
+                outcome.kept.push(index_path);
+            } else {
+                index_paths.push(index_path);
+            }
+        }
+        index_paths.sort();
+        index_paths.dedup();
+
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        if multi_index_path.is_file() {
+            let multi_index = git_pack::multi_index::File::at(&multi_index_path)?;
+            if let Some(index_path) = index_paths.iter().find(|path| {
+                let name = Path::new(path.file_name().expect("file name present"));
+                multi_index.index_names().iter().any(|n| n == name)
+            }) {
+                return Err(Error::ReferencedByMultiIndex {
+                    index_path: index_path.to_owned(),
+                    multi_index_path,
+                });
+            }
+        }
+
+        if options.verify_coverage {
+            self.verify_coverage(&index_paths)?;
+        }
+
+        self.unload_indices(&index_paths);
+        for index_path in index_paths {
+            match delete_pack_files(&index_path, options.retries, options.retry_delay) {
+                Ok(()) => outcome.deleted.push(index_path),
+                Err(err) => outcome.failed.push((index_path, err)),
+            }
+        }
+
+        if !outcome.deleted.is_empty() && self.index.load().is_initialized() {
+            self.consolidate_with_disk_state(false /* needs init */, false /*load one new index*/)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Assure all objects in the packs at `index_paths` can also be found elsewhere in our objects directory.
+    fn verify_coverage(&self, index_paths: &[PathBuf]) -> Result<(), Error> {
+        enum Remaining {
+            Single(Box<git_pack::index::File>),
+            Multi(Arc<git_pack::multi_index::File>),
+        }
+        let remaining =
+            Self::collect_indices_and_mtime_sorted_by_size(vec![self.path.clone()], None, Some(self.object_hash))?
+                .into_iter()
+                .filter_map(|(index, _, _)| match index {
+                    Either::IndexPath(path) => (index_paths.binary_search(&path).is_err()).then(|| {
+                        git_pack::index::File::at(&path, self.object_hash)
+                            .map(|index| Remaining::Single(Box::new(index)))
+                            .map_err(Error::from)
+                    }),
+                    Either::MultiIndexFile(multi_index) => Some(Ok(Remaining::Multi(multi_index))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+        let loose = crate::loose::Store::at(&self.path, self.object_hash);
+
+        for index_path in index_paths {
+            let index = git_pack::index::File::at(index_path, self.object_hash)?;
+            for id in index.iter().map(|entry| entry.oid) {
+                let is_covered = remaining.iter().any(|index| match index {
+                    Remaining::Single(index) => index.lookup(id).is_some(),
+                    Remaining::Multi(index) => index.lookup(id).is_some(),
+                }) || loose.contains(id);
+                if !is_covered {
+                    return Err(Error::ObjectNotCovered {
+                        id,
+                        index_path: index_path.to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Release our memory maps of the packs at `index_paths` so they don't prevent the deletion of their files,
+    /// which is only possible if no handle relies on stable pack ids.
+    ///
+    /// Handles that try to use them will notice they are gone once they try to load them again and refresh their view
+    /// of the object database.
+    fn unload_indices(&self, index_paths: &[PathBuf]) {
+        let write = self.write.lock();
+        if self.maintain_stable_indices(&write) {
+            return;
+        }
+        let index = self.index.load();
+        for slot in index.slot_indices.iter().map(|idx| &self.files[*idx]) {
+            let is_expired = match &**slot.files.load() {
+                Some(IndexAndPacks::Index(bundle)) => index_paths
+                    .binary_search_by(|p| p.as_path().cmp(bundle.index.path()))
+                    .is_ok(),
+                Some(IndexAndPacks::MultiIndex(_)) | None => false,
+            };
+            if !is_expired {
+                continue;
+            }
+            let _lock = slot.write.lock();
+            let mut files = slot.files.load_full();
+            if let Some(files_mut) = Arc::make_mut(&mut files).as_mut() {
+                files_mut.unload();
+            }
+            slot.files.store(files);
+        }
+    }
+}
+
+/// Delete all files belonging to the pack at `index_path`, stopping at the first file that can't be deleted.
+fn delete_pack_files(index_path: &Path, retries: usize, retry_delay: Duration) -> std::io::Result<()> {
+    for extension in PACK_FILE_EXTENSIONS {
+        remove_file_with_retries(&index_path.with_extension(extension), retries, retry_delay)?;
+    }
+    Ok(())
+}
+
+fn remove_file_with_retries(path: &Path, retries: usize, mut retry_delay: Duration) -> std::io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match std::fs::remove_file(path) {
+            Ok(()) => return Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(_) if attempt < retries => {
+                attempt += 1;
+                std::thread::sleep(retry_delay);
+                retry_delay *= 2;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
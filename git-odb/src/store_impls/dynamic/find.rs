@@ -187,6 +187,61 @@ where
         }
     }
 
+    /// Find the only object matching `prefix`, write its data into `buffer` and return it along with its id
+    /// as `Ok(Some(Ok((<ObjectId>, <Data>))))`, or return `Ok(Some(Err(())))` if multiple different objects with
+    /// the same prefix were found.
+    ///
+    /// Return `Ok(None)` if no object matched the `prefix`.
+    ///
+    /// This is useful to resolve abbreviated object ids, and has the same performance characteristics as
+    /// [`lookup_prefix()`][Self::lookup_prefix()].
+    pub fn find_by_prefix<'a>(
+        &self,
+        prefix: git_hash::Prefix,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<Result<(ObjectId, Data<'a>), ()>>, Error> {
+        let id = match self.lookup_prefix(prefix)? {
+            Some(Ok(id)) => id,
+            Some(Err(())) => return Ok(Some(Err(()))),
+            None => return Ok(None),
+        };
+        Ok(git_pack::Find::try_find(self, id, buffer)?.map(|(data, _location)| Ok((id, data))))
+    }
+
+    /// Return the amount of hex characters, but at least `min_hex_len`, that are needed for a prefix of `id` to not match
+    /// any other object in all packs, multi-pack indices and loose object databases.
+    ///
+    /// `min_hex_len` is typically the value of `core.abbrev`, or derived from the amount of objects if it is set to `auto`.
+    /// `id` doesn't have to exist in the object database.
+    ///
+    /// ### Performance Note
+    ///
+    /// Instead of trying prefixes of increasing length like [`disambiguate_prefix()`][Self::disambiguate_prefix()], only the
+    /// closest neighbours of `id` in each index are examined. As all objects need to be considered, all indices will be loaded
+    /// after calling this method.
+    pub fn shortest_unambiguous_hex_len(&self, id: impl AsRef<oid>, min_hex_len: usize) -> Result<usize, Error> {
+        let id = id.as_ref();
+        let mut hex_len = min_hex_len.min(id.kind().len_in_hex());
+        loop {
+            let snapshot = self.snapshot.borrow();
+            for index in snapshot.indices.iter() {
+                hex_len = hex_len.max(index.shortest_unambiguous_hex_len(id));
+            }
+
+            for lodb in snapshot.loose_dbs.iter() {
+                hex_len = hex_len.max(lodb.shortest_unambiguous_hex_len(id)?);
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                Some(new_snapshot) => {
+                    drop(snapshot);
+                    *self.snapshot.borrow_mut() = new_snapshot;
+                }
+                None => return Ok(hex_len),
+            }
+        }
+    }
+
     fn try_find_cached_inner<'a, 'b>(
         &'b self,
         mut id: &'b git_hash::oid,
@@ -367,6 +422,7 @@ where
             }
 
             for lodb in snapshot.loose_dbs.iter() {
+                self.record_lookup(|lookups| lookups.loose_lookups += 1);
                 // TODO: remove this double-lookup once the borrow checker allows it.
                 if lodb.contains(id) {
                     return lodb
@@ -436,7 +492,13 @@ where
     ) -> Result<Option<(Data<'a>, Option<Location>)>, Self::Error> {
         let id = id.as_ref();
         let mut snapshot = self.snapshot.borrow_mut();
-        self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None)
+        let res = self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None)?;
+        self.record_lookup(|lookups| match &res {
+            Some((_obj, Some(_location))) => lookups.pack_hits += 1,
+            Some((_obj, None)) => lookups.loose_hits += 1,
+            None => lookups.misses += 1,
+        });
+        Ok(res)
     }
 
     fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<Location> {
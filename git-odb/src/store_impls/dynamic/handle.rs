@@ -149,6 +149,14 @@ pub(crate) mod index_lookup {
             Some(res.map(|entry_index| self.oid_at_index(entry_index).to_owned()))
         }
 
+        /// Call `shortest_unambiguous_hex_len(…)` on either index or multi-index.
+        pub(crate) fn shortest_unambiguous_hex_len(&self, id: &oid) -> usize {
+            match &self.file {
+                handle::SingleOrMultiIndex::Single { index, .. } => index.shortest_unambiguous_hex_len(id),
+                handle::SingleOrMultiIndex::Multi { index, .. } => index.shortest_unambiguous_hex_len(id),
+            }
+        }
+
         /// See if the oid is contained in this index, and return its full id for lookup possibly alongside its data file if already
         /// loaded.
         /// Also return the index itself as it's needed to resolve intra-pack ref-delta objects. They are a possibility even though
@@ -251,6 +259,7 @@ impl super::Store {
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
             packed_object_count: Default::default(),
+            lookups: Default::default(),
         }
     }
 
@@ -267,6 +276,7 @@ impl super::Store {
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
             packed_object_count: Default::default(),
+            lookups: Default::default(),
         }
     }
 
@@ -341,6 +351,7 @@ impl TryFrom<&super::Store> for super::Store {
                 slots: crate::store::init::Slots::Given(s.files.len().try_into().expect("BUG: too many slots")),
                 object_hash: Default::default(),
                 use_multi_pack_index: false,
+                loose_compression_level: s.loose_compression_level,
             },
         )
     }
@@ -384,6 +395,7 @@ where
             snapshot: RefCell::new(self.store.collect_snapshot()),
             max_recursion_depth: self.max_recursion_depth,
             packed_object_count: Default::default(),
+            lookups: Default::default(),
         }
     }
 }
@@ -16,6 +16,9 @@ pub struct Options {
     pub object_hash: git_hash::Kind,
     /// If false, no multi-pack indices will be used. If true, they will be used if their hash matches `object_hash`.
     pub use_multi_pack_index: bool,
+    /// The zlib compression level to use when writing loose objects, from 0 for no compression to 9 for the best compression,
+    /// similar to `core.looseCompression`.
+    pub loose_compression_level: u32,
 }
 
 impl Default for Options {
@@ -24,6 +27,7 @@ impl Default for Options {
             slots: Default::default(),
             object_hash: Default::default(),
             use_multi_pack_index: true,
+            loose_compression_level: git_features::zlib::stream::deflate::DEFAULT_LEVEL,
         }
     }
 }
@@ -72,8 +76,10 @@ impl Store {
             slots,
             object_hash,
             use_multi_pack_index,
+            loose_compression_level,
         }: Options,
     ) -> std::io::Result<Self> {
+        let _span = git_features::span!("git_odb::Store::at");
         let objects_dir = objects_dir.into();
         if !objects_dir.is_dir() {
             return Err(std::io::Error::new(
@@ -111,6 +117,7 @@ impl Store {
             index: ArcSwap::new(Arc::new(SlotMapIndex::default())),
             use_multi_pack_index,
             object_hash,
+            loose_compression_level,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
@@ -171,6 +171,11 @@ impl super::Store {
         needs_init: bool,
         load_new_index: bool,
     ) -> Result<Option<Snapshot>, Error> {
+        let _span = git_features::span!(
+            "git_odb::Store::consolidate_with_disk_state",
+            needs_init = needs_init,
+            load_new_index = load_new_index
+        );
         let index = self.index.load();
         let previous_index_state = Arc::as_ptr(&index) as usize;
 
@@ -212,7 +217,10 @@ impl super::Store {
             Arc::new(
                 db_paths
                     .iter()
-                    .map(|path| crate::loose::Store::at(path, self.object_hash))
+                    .map(|path| {
+                        crate::loose::Store::at(path, self.object_hash)
+                            .with_compression_level(self.loose_compression_level)
+                    })
                     .collect::<Vec<_>>(),
             )
         } else {
@@ -419,14 +427,12 @@ impl super::Store {
         let mut indices_by_modification_time = Vec::with_capacity(initial_capacity.unwrap_or_default());
         for db_path in db_paths {
             let packs = db_path.join("pack");
-            let entries = match std::fs::read_dir(&packs) {
-                Ok(e) => e,
-                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(err) => return Err(err.into()),
+            let entries = match pack_directory_entries(&packs)? {
+                Some(e) => e,
+                None => continue,
             };
             let indices = entries
-                .filter_map(Result::ok)
-                .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
+                .into_iter()
                 .filter(|(_, md)| md.file_type().is_file())
                 .filter(|(p, _)| {
                     let ext = p.extension();
@@ -590,7 +596,7 @@ impl super::Store {
     ///
     /// Note that this must be called with a lock to the relevant state held to assure these values don't change while
     /// we are working on said index.
-    fn maintain_stable_indices(&self, _guard: &parking_lot::MutexGuard<'_, ()>) -> bool {
+    pub(crate) fn maintain_stable_indices(&self, _guard: &parking_lot::MutexGuard<'_, ()>) -> bool {
         self.num_handles_stable.load(Ordering::SeqCst) > 0
     }
 
@@ -696,3 +702,29 @@ impl Ord for Either {
         self.path().cmp(other.path())
     }
 }
+
+/// Return paths and metadata of all entries directly within the `packs` directory, or `None` if it doesn't exist.
+fn pack_directory_entries(packs: &Path) -> Result<Option<Vec<(PathBuf, std::fs::Metadata)>>, Error> {
+    let walk = git_features::fs::walkdir_with_options(
+        packs,
+        git_features::fs::WalkOptions {
+            min_depth: 1,
+            max_depth: Some(1),
+            ..Default::default()
+        },
+    );
+    let mut entries = Vec::new();
+    for entry in walk {
+        match entry {
+            Ok(entry) => match entry.metadata() {
+                Ok(md) => entries.push((entry.path().to_owned(), md)),
+                // The file was removed after listing the directory, which happens when packs are consolidated concurrently.
+                Err(err) if err.io_error().map(|err| err.kind()) == Some(std::io::ErrorKind::NotFound) => {}
+                Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::Other, err).into()),
+            },
+            Err(err) if err.io_error().map(|err| err.kind()) == Some(std::io::ErrorKind::NotFound) => return Ok(None),
+            Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::Other, err).into()),
+        }
+    }
+    Ok(Some(entries))
+}
@@ -1,7 +1,36 @@
-use std::sync::atomic::Ordering;
+use std::{
+    ops::Deref,
+    sync::{atomic::Ordering, Arc},
+};
 
 use crate::store::{types, types::IndexAndPacks};
 
+/// Counters for object lookups performed through a single [`Handle`][super::Handle].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lookups {
+    /// The amount of objects that were found in packs.
+    pub pack_hits: u64,
+    /// The amount of objects that were found in loose object databases.
+    pub loose_hits: u64,
+    /// The amount of objects that couldn't be found at all.
+    pub misses: u64,
+    /// The amount of times a loose object database was searched, which happens if an object isn't found in any pack.
+    pub loose_lookups: u64,
+}
+
+/// Information about the files a [`Store`][super::Store] currently holds as memory map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct MappedFiles {
+    /// The amount of mapped index and multi-index files.
+    pub num_indices: usize,
+    /// The amount of mapped pack data files.
+    pub num_packs: usize,
+    /// The total amount of bytes of all mapped files.
+    pub num_bytes: u64,
+}
+
 impl super::Store {
     /// Return metrics collected in a racy fashion, giving an idea of what's currently going on in the store.
     ///
@@ -84,3 +113,56 @@ impl super::Store {
         }
     }
 }
+
+impl super::Store {
+    /// Return information about all files that are currently memory mapped, including those that aren't reachable anymore,
+    /// collected in a racy fashion.
+    pub fn mapped_files(&self) -> MappedFiles {
+        fn add_pack(out: &mut MappedFiles, pack: &types::OnDiskFile<Arc<git_pack::data::File>>) {
+            if let Some(pack) = pack.loaded() {
+                out.num_packs += 1;
+                out.num_bytes += pack.data_len() as u64;
+            }
+        }
+
+        let mut out = MappedFiles::default();
+        for slot in &self.files {
+            match slot.files.load().as_ref() {
+                Some(IndexAndPacks::Index(bundle)) => {
+                    if let Some(index) = bundle.index.loaded() {
+                        out.num_indices += 1;
+                        out.num_bytes += index.data_len() as u64;
+                    }
+                    add_pack(&mut out, &bundle.data);
+                }
+                Some(IndexAndPacks::MultiIndex(multi)) => {
+                    if let Some(index) = multi.multi_index.loaded() {
+                        out.num_indices += 1;
+                        out.num_bytes += index.data_len() as u64;
+                    }
+                    for pack in multi.data.iter() {
+                        add_pack(&mut out, pack);
+                    }
+                }
+                None => {}
+            }
+        }
+        out
+    }
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return the counters of all object lookups performed through this handle so far.
+    pub fn lookup_metrics(&self) -> Lookups {
+        self.lookups.get()
+    }
+
+    pub(crate) fn record_lookup(&self, record: impl FnOnce(&mut Lookups)) {
+        let mut lookups = self.lookups.get();
+        record(&mut lookups);
+        self.lookups.set(lookups);
+    }
+}
@@ -1,5 +1,8 @@
 //! The standard object store which should fit all needs.
-use std::{cell::RefCell, ops::Deref};
+use std::{
+    cell::{Cell, RefCell},
+    ops::Deref,
+};
 
 use crate::Store;
 
@@ -25,6 +28,7 @@ where
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     packed_object_count: RefCell<Option<u64>>,
+    lookups: Cell<metrics::Lookups>,
 }
 
 /// Decide what happens when all indices are loaded.
@@ -75,9 +79,13 @@ pub mod load_index;
 ///
 pub mod verify;
 
+///
+pub mod delete;
+
 mod load_one;
 
-mod metrics;
+///
+pub mod metrics;
 
 mod access;
 
@@ -223,6 +223,13 @@ impl<T: Clone> OnDiskFile<T> {
             | other @ OnDiskFileState::Missing => self.state = other,
         }
     }
+
+    /// Drop our memory map of the file, if present, so it is loaded from disk again when needed.
+    pub fn unload(&mut self) {
+        if let OnDiskFileState::Loaded(_) | OnDiskFileState::Garbage(_) = self.state {
+            self.state = OnDiskFileState::Unloaded;
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -291,6 +298,22 @@ impl IndexAndPacks {
         }
     }
 
+    /// Drop all memory maps we hold, causing the files to be loaded from disk on next access, if they still exist.
+    pub(crate) fn unload(&mut self) {
+        match self {
+            IndexAndPacks::Index(bundle) => {
+                bundle.index.unload();
+                bundle.data.unload();
+            }
+            IndexAndPacks::MultiIndex(bundle) => {
+                bundle.multi_index.unload();
+                for data in &mut bundle.data {
+                    data.unload();
+                }
+            }
+        }
+    }
+
     pub(crate) fn index_is_loaded(&self) -> bool {
         match self {
             Self::Index(bundle) => bundle.index.is_loaded(),
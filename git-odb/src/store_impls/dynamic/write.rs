@@ -1,9 +1,9 @@
 use std::{io::Read, ops::Deref};
 
 use git_hash::ObjectId;
-use git_object::Kind;
+use git_object::{Kind, WriteTo};
 
-use crate::store;
+use crate::{loose, store, Write};
 
 mod error {
     use crate::{loose, store};
@@ -24,23 +24,44 @@ pub use error::Error;
 
 use crate::store_impls::dynamic;
 
-impl<S> crate::Write for store::Handle<S>
+impl<S> Write for store::Handle<S>
 where
     S: Deref<Target = dynamic::Store> + Clone,
 {
     type Error = Error;
 
+    fn write(&self, object: impl WriteTo) -> Result<ObjectId, Self::Error> {
+        self.with_loose_db(|ldb| ldb.write(object))
+    }
+
+    fn write_buf(&self, kind: Kind, from: &[u8]) -> Result<ObjectId, Self::Error> {
+        self.with_loose_db(|ldb| ldb.write_buf(kind, from))
+    }
+
     fn write_stream(&self, kind: Kind, size: u64, from: impl Read) -> Result<ObjectId, Self::Error> {
+        self.with_loose_db(|ldb| ldb.write_stream(kind, size, from))
+    }
+}
+
+impl<S> store::Handle<S>
+where
+    S: Deref<Target = dynamic::Store> + Clone,
+{
+    /// Call `write` with the loose object database all objects are written to, loading it first if needed.
+    fn with_loose_db(
+        &self,
+        write: impl FnOnce(&loose::Store) -> Result<ObjectId, loose::write::Error>,
+    ) -> Result<ObjectId, Error> {
         let mut snapshot = self.snapshot.borrow_mut();
         Ok(match snapshot.loose_dbs.get(0) {
-            Some(ldb) => ldb.write_stream(kind, size, from)?,
+            Some(ldb) => write(ldb)?,
             None => {
                 let new_snapshot = self
                     .store
                     .load_one_index(self.refresh, snapshot.marker)?
                     .expect("there is always at least one ODB, and this code runs only once for initialization");
                 *snapshot = new_snapshot;
-                snapshot.loose_dbs[0].write_stream(kind, size, from)?
+                write(&snapshot.loose_dbs[0])?
             }
         })
     }
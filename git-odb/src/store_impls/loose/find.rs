@@ -44,23 +44,12 @@ impl Store {
         &self,
         prefix: git_hash::Prefix,
     ) -> Result<Option<crate::find::PrefixLookupResult>, crate::loose::iter::Error> {
-        let single_directory_iter = crate::loose::Iter {
-            inner: git_features::fs::walkdir_new(&self.path.join(prefix.as_oid().to_hex_with_len(2).to_string()))
-                .min_depth(1)
-                .max_depth(1)
-                .follow_links(false)
-                .into_iter(),
-            hash_hex_len: prefix.as_oid().kind().len_in_hex(),
-        };
         let mut candidate = None;
-        for oid in single_directory_iter {
-            let oid = match oid {
-                Ok(oid) => oid,
-                Err(err) => match err.io_error() {
-                    Some(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
-                    None | Some(_) => return Err(err),
-                },
-            };
+        for oid in self.iter_with_options(crate::loose::iter::Options {
+            first_byte: Some(prefix.as_oid().first_byte()),
+            ..Default::default()
+        }) {
+            let oid = oid?;
             if prefix.cmp_oid(&oid) == Ordering::Equal {
                 if candidate.is_some() {
                     return Ok(Some(Err(())));
@@ -71,6 +60,31 @@ impl Store {
         Ok(candidate.map(Ok))
     }
 
+    /// Return the amount of hex characters a prefix of `id` needs to have to not match any other object in this
+    /// loose object database. `id` doesn't have to be contained in it.
+    ///
+    /// As objects are stored in directories named after the first byte of their id, only objects sharing it with `id`
+    /// are considered, which is why the returned length is at least 2.
+    ///
+    /// The `Result` is to indicate errors during file system traversal.
+    pub fn shortest_unambiguous_hex_len(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+    ) -> Result<usize, crate::loose::iter::Error> {
+        let id = id.as_ref();
+        let mut hex_len = 2;
+        for oid in self.iter_with_options(crate::loose::iter::Options {
+            first_byte: Some(id.first_byte()),
+            ..Default::default()
+        }) {
+            let oid = oid?;
+            if oid != id {
+                hex_len = hex_len.max(id.common_hex_len(&oid) + 1);
+            }
+        }
+        Ok(hex_len)
+    }
+
     /// Return the object identified by the given [`ObjectId`][git_hash::ObjectId] if present in this database,
     /// writing its raw data into the given `out` buffer.
     ///
@@ -1,3 +1,8 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
 use git_features::fs;
 
 use crate::store_impls::loose;
@@ -5,53 +10,133 @@ use crate::store_impls::loose;
 /// Returned by [`loose::Store::iter()`]
 pub type Error = git_features::fs::walkdir::Error;
 
-impl loose::Iter {
-    fn path_to_id(
-        &self,
-        res: Result<fs::walkdir::DirEntry, fs::walkdir::Error>,
-    ) -> Option<Result<git_hash::ObjectId, Error>> {
-        use std::path::Component::Normal;
-
-        match res {
-            Ok(e) => {
-                let p = e.path();
-                let mut ci = p.components();
-                let (c2, c1) = (ci.next_back(), ci.next_back());
-                if let (Some(Normal(c1)), Some(Normal(c2))) = (c1, c2) {
-                    if c1.len() == 2 && c2.len() == self.hash_hex_len - 2 {
-                        if let (Some(c1), Some(c2)) = (c1.to_str(), c2.to_str()) {
-                            let mut buf = git_hash::Kind::hex_buf();
-                            {
-                                let (first_byte, rest) = buf[..self.hash_hex_len].split_at_mut(2);
-                                first_byte.copy_from_slice(c1.as_bytes());
-                                rest.copy_from_slice(c2.as_bytes());
-                            }
-                            if let Ok(b) = git_hash::ObjectId::from_hex(&buf[..self.hash_hex_len]) {
-                                return Some(Ok(b));
-                            }
-                        }
-                    }
+/// Options for use in [`loose::Store::iter_with_options()`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// If true, objects are returned in the order of their ids, which is stable across runs.
+    pub sorted: bool,
+    /// If set, only objects whose ids start with this byte are returned, which only requires reading the one directory they
+    /// are stored in.
+    pub first_byte: Option<u8>,
+}
+
+/// A loose object along with information about the file it's stored in, as returned by [`WithMetadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// The id of the object.
+    pub id: git_hash::ObjectId,
+    /// The size of the compressed object file in bytes.
+    pub size: u64,
+    /// The time the object file was last modified, typically the time the object was written.
+    pub mtime: SystemTime,
+}
+
+///
+pub mod with_metadata {
+    /// The error returned by [`WithMetadata`][super::WithMetadata].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Walk(#[from] super::Error),
+        #[error("Could not obtain the modification time of loose object {id}")]
+        ModificationTime {
+            source: std::io::Error,
+            id: git_hash::ObjectId,
+        },
+    }
+}
+
+/// An iterator over loose objects along with their file's size and modification time, created with
+/// [`loose::Store::iter_with_metadata()`].
+pub struct WithMetadata {
+    inner: fs::walkdir::DirEntryWithMetadataIter,
+    hash_hex_len: usize,
+    root_may_be_missing: bool,
+}
+
+/// Return the id of the object stored at `path` if it is the path of a loose object with a hash of `hash_hex_len` hex
+/// characters.
+fn path_to_id(path: impl AsRef<Path>, hash_hex_len: usize) -> Option<git_hash::ObjectId> {
+    use std::path::Component::Normal;
+
+    let mut ci = path.as_ref().components();
+    let (c2, c1) = (ci.next_back(), ci.next_back());
+    if let (Some(Normal(c1)), Some(Normal(c2))) = (c1, c2) {
+        if c1.len() == 2 && c2.len() == hash_hex_len - 2 {
+            if let (Some(c1), Some(c2)) = (c1.to_str(), c2.to_str()) {
+                let mut buf = git_hash::Kind::hex_buf();
+                {
+                    let (first_byte, rest) = buf[..hash_hex_len].split_at_mut(2);
+                    first_byte.copy_from_slice(c1.as_bytes());
+                    rest.copy_from_slice(c2.as_bytes());
                 }
+                return git_hash::ObjectId::from_hex(&buf[..hash_hex_len]).ok();
             }
-            Err(err) => return Some(Err(err)),
-        };
-        None
+        }
     }
+    None
+}
+
+/// Return true if `err` indicates that the root of the traversal doesn't exist, which is fine if it `may_be_missing`.
+fn is_missing_root(err: &Error, may_be_missing: bool) -> bool {
+    may_be_missing && err.depth() == 0 && err.io_error().map(|err| err.kind()) == Some(std::io::ErrorKind::NotFound)
 }
 
 impl Iterator for loose::Iter {
     type Item = Result<git_hash::ObjectId, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(res) = self.inner.next() {
-            if let Some(res) = self.path_to_id(res) {
-                return Some(res);
+        for res in &mut self.inner {
+            match res {
+                Ok(entry) => {
+                    if let Some(id) = path_to_id(entry.path(), self.hash_hex_len) {
+                        return Some(Ok(id));
+                    }
+                }
+                Err(err) if is_missing_root(&err, self.root_may_be_missing) => {}
+                Err(err) => return Some(Err(err)),
             }
         }
         None
     }
 }
 
+impl WithMetadata {
+    fn next_entry(&mut self) -> Option<Result<(git_hash::ObjectId, fs::walkdir::DirEntryWithMetadata), Error>> {
+        for res in &mut self.inner {
+            match res {
+                Ok(entry) => {
+                    if let Some(id) = path_to_id(entry.path(), self.hash_hex_len) {
+                        return Some(Ok((id, entry)));
+                    }
+                }
+                Err(err) if is_missing_root(&err, self.root_may_be_missing) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for WithMetadata {
+    type Item = Result<Entry, with_metadata::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().map(|res| {
+            let (id, mut entry) = res?;
+            let metadata = fs::walkdir::metadata(&mut entry)?;
+            Ok(Entry {
+                id,
+                size: metadata.len(),
+                mtime: metadata
+                    .modified()
+                    .map_err(|source| with_metadata::Error::ModificationTime { source, id })?,
+            })
+        })
+    }
+}
+
 /// Iteration and traversal
 impl loose::Store {
     /// Return an iterator over all objects contained in the database.
@@ -64,13 +149,50 @@ impl loose::Store {
     /// [`loose::Iter`] is used instead of `impl Iterator<…>` to allow using this iterator in struct fields, as is currently
     /// needed if iterators need to be implemented by hand in the absence of generators.
     pub fn iter(&self) -> loose::Iter {
+        self.iter_with_options(Options::default())
+    }
+
+    /// Like [`iter()`][loose::Store::iter()], but allows to sort objects by id or to only return objects whose id starts with a
+    /// given byte, as configured by `options`.
+    ///
+    /// Use [`iter_with_metadata()`][loose::Store::iter_with_metadata()] to learn about the size and modification time of
+    /// each object file as well, for example to decide which objects to prune.
+    pub fn iter_with_options(&self, options: Options) -> loose::Iter {
+        let (root, walk_options) = self.walk_options(options);
         loose::Iter {
-            inner: fs::walkdir_new(&self.path)
-                .min_depth(2)
-                .max_depth(3)
-                .follow_links(false)
-                .into_iter(),
+            inner: fs::walkdir_with_options(root, walk_options).into_iter(),
+            hash_hex_len: self.object_hash.len_in_hex(),
+            root_may_be_missing: options.first_byte.is_some(),
+        }
+    }
+
+    /// Like [`iter_with_options()`][loose::Store::iter_with_options()], but also return the size and modification time of
+    /// each object file.
+    ///
+    /// With the `parallel` feature, the metadata of object files is obtained while their directories are read in parallel,
+    /// instead of by the consumer of the iterator.
+    pub fn iter_with_metadata(&self, options: Options) -> WithMetadata {
+        let (root, walk_options) = self.walk_options(options);
+        WithMetadata {
+            inner: fs::walkdir_with_metadata(root, walk_options).into_iter(),
             hash_hex_len: self.object_hash.len_in_hex(),
+            root_may_be_missing: options.first_byte.is_some(),
         }
     }
+
+    fn walk_options(&self, Options { sorted, first_byte }: Options) -> (PathBuf, fs::WalkOptions) {
+        let (root, min_depth, max_depth) = match first_byte {
+            Some(byte) => (self.path.join(format!("{:02x}", byte)), 1, 1),
+            None => (self.path.clone(), 2, 3),
+        };
+        (
+            root,
+            fs::WalkOptions {
+                sorted,
+                min_depth,
+                max_depth: Some(max_depth),
+                ..Default::default()
+            },
+        )
+    }
 }
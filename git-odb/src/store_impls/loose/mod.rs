@@ -11,6 +11,8 @@ pub struct Store {
     pub(crate) path: PathBuf,
     /// The kind of hash we should assume during iteration and when writing new objects.
     pub(crate) object_hash: git_hash::Kind,
+    /// The zlib compression level to use when writing new objects.
+    pub(crate) compression_level: u32,
 }
 
 /// Initialization
@@ -25,9 +27,17 @@ impl Store {
         Store {
             path: objects_directory.into(),
             object_hash,
+            compression_level: git_features::zlib::stream::deflate::DEFAULT_LEVEL,
         }
     }
 
+    /// Compress newly written objects with the given zlib compression `level`, from 0 for no compression to 9 for
+    /// the best compression.
+    pub fn with_compression_level(mut self, level: u32) -> Store {
+        self.compression_level = level;
+        self
+    }
+
     /// Return the path to our `objects` directory.
     pub fn path(&self) -> &Path {
         &self.path
@@ -37,6 +47,11 @@ impl Store {
     pub fn object_hash(&self) -> git_hash::Kind {
         self.object_hash
     }
+
+    /// Return the zlib compression level used when writing objects.
+    pub fn compression_level(&self) -> u32 {
+        self.compression_level
+    }
 }
 
 fn hash_path(id: &git_hash::oid, mut root: PathBuf) -> PathBuf {
@@ -59,6 +74,8 @@ pub mod verify;
 pub struct Iter {
     inner: fs::walkdir::DirEntryIter,
     hash_hex_len: usize,
+    /// If true, the directory we iterate may not exist, which is equivalent to it being empty.
+    root_may_be_missing: bool,
 }
 
 ///
@@ -0,0 +1,55 @@
+use git_odb::{memory, Find, FindExt, Write};
+use git_testtools::{fixture_path, hex_to_id};
+
+fn db_in_memory() -> crate::Result<(memory::Proxy<git_odb::Handle>, tempfile::TempDir)> {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    Ok((
+        memory::Proxy::new(git_odb::at(objects_dir.path())?, git_hash::Kind::Sha1),
+        objects_dir,
+    ))
+}
+
+#[test]
+fn objects_are_read_through_and_written_to_memory() -> crate::Result {
+    let (db, _tmp) = db_in_memory()?;
+    let mut buf = Vec::new();
+    let existing = hex_to_id("bd46bb3f5bb4ca5431770c4fde0735fb89d382f3");
+    assert!(db.contains(existing), "objects of the inner database are visible");
+    db.find_blob(existing, &mut buf)?;
+
+    let id = db.write_buf(git_object::Kind::Blob, b"hello")?;
+    assert_eq!(id, hex_to_id("b6fc4c620b67d95f953a5c1c1230aaab5db5a1b0"));
+    assert!(db.contains(id));
+    assert_eq!(db.find_blob(id, &mut buf)?.data, b"hello");
+    assert_eq!(db.num_objects_in_memory(), 1);
+    assert!(!(*db).contains(id), "nothing was written to disk");
+    Ok(())
+}
+
+#[test]
+fn persist_writes_objects_into_the_inner_database() -> crate::Result {
+    let (db, _tmp) = db_in_memory()?;
+    let id = db.write_buf(git_object::Kind::Blob, b"hello")?;
+    assert_eq!(db.persist()?, 1);
+    assert_eq!(db.num_objects_in_memory(), 0);
+    assert!((*db).contains(id));
+
+    let mut buf = Vec::new();
+    assert_eq!(db.find_blob(id, &mut buf)?.data, b"hello");
+    Ok(())
+}
+
+#[test]
+fn objects_in_memory_can_be_discarded() -> crate::Result {
+    let (mut db, _tmp) = db_in_memory()?;
+    let id = db.write_buf(git_object::Kind::Blob, b"hello")?;
+    let objects = db.take_object_memory();
+    assert_eq!(objects.len(), 1);
+    assert_eq!(objects[&id], (git_object::Kind::Blob, b"hello".to_vec()));
+    assert!(!db.contains(id));
+
+    let db = db.into_inner();
+    assert!(!db.contains(id), "the inner database never saw the object");
+    Ok(())
+}
@@ -4,6 +4,9 @@ pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 pub mod alternate;
 pub mod find;
+pub mod memory;
+#[cfg(feature = "async")]
+pub mod nonblocking;
 pub mod regression;
 pub mod sink;
 pub mod store;
@@ -0,0 +1,65 @@
+use futures_lite::future;
+use git_odb::{nonblocking, FindExt};
+use git_testtools::{fixture_path, hex_to_id};
+
+fn db() -> nonblocking::Handle {
+    git_odb::at(fixture_path("objects"))
+        .and_then(|handle| handle.into_arc())
+        .expect("valid object path")
+        .into()
+}
+
+#[test]
+fn find_packed_and_loose_objects() -> crate::Result {
+    let db = db();
+    future::block_on(async {
+        for id in [
+            hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"),
+            hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"),
+        ] {
+            assert!(db.contains(id).await);
+            let object = db.find(id).await?;
+            let expected = git_odb::at(fixture_path("objects"))?;
+            let mut buf = Vec::new();
+            assert_eq!(object.as_data(), expected.find(id, &mut buf)?);
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn missing_objects_are_not_found() {
+    let db = db();
+    let id = hex_to_id("0000000000000000000000000000000000000001");
+    future::block_on(async {
+        assert!(!db.contains(id).await);
+        assert!(db.try_find(id).await.expect("no error").is_none());
+        assert!(matches!(
+            db.find(id).await,
+            Err(git_odb::find::existing::Error::NotFound { oid }) if oid == id
+        ));
+    });
+}
+
+#[test]
+fn write_and_find_concurrently() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let db: nonblocking::Handle = git_odb::at(dir.path())?.into_arc()?.into();
+    let ids = (0..10u8)
+        .map(|n| {
+            let db = db.clone();
+            std::thread::spawn(move || future::block_on(db.write_buf(git_object::Kind::Blob, vec![n; 100])))
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|writer| writer.join().expect("no panic"))
+        .collect::<Result<Vec<_>, _>>()?;
+    future::block_on(async {
+        for (n, id) in ids.into_iter().enumerate() {
+            let object = db.find(id).await?;
+            assert_eq!(object.kind, git_object::Kind::Blob);
+            assert_eq!(object.data, vec![n as u8; 100]);
+        }
+        Ok(())
+    })
+}
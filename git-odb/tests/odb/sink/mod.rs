@@ -12,3 +12,20 @@ fn write() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn write_streams_objects_like_their_serialized_buffers() -> Result<(), Box<dyn std::error::Error>> {
+    use git_object::WriteTo;
+    let mut buf = Vec::new();
+    for compress in &[false, true] {
+        let sink = git_odb::sink(git_hash::Kind::Sha1).compress(*compress);
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf).decode()?;
+            let mut serialized = Vec::new();
+            obj.write_to(&mut serialized)?;
+            assert_eq!(sink.write(&obj)?, oid, "compress = {}", compress);
+            assert_eq!(sink.write_buf(obj.kind(), &serialized)?, oid);
+        }
+    }
+    Ok(())
+}
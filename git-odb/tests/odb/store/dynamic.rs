@@ -599,6 +599,136 @@ mod lookup_prefix {
     }
 }
 
+mod find_by_prefix {
+    use git_testtools::hex_to_id;
+
+    use crate::store::dynamic::db_with_all_object_sources;
+
+    #[test]
+    fn unambiguous_prefixes_yield_the_object_and_its_id() {
+        let (handle, _tmp) = db_with_all_object_sources().unwrap();
+        let id = hex_to_id("bd46bb3f5bb4ca5431770c4fde0735fb89d382f3");
+        let mut buf = Vec::new();
+        let (found_id, data) = handle
+            .find_by_prefix(git_hash::Prefix::new(id, 7).unwrap(), &mut buf)
+            .unwrap()
+            .expect("object exists")
+            .expect("unambiguous");
+        assert_eq!(found_id, id);
+        assert_eq!(data.kind, git_object::Kind::Blob);
+        data.verify_checksum(id).unwrap();
+    }
+
+    #[test]
+    fn ambiguous_and_missing_prefixes_yield_no_object() {
+        let (handle, _tmp) = db_with_all_object_sources().unwrap();
+        let mut buf = Vec::new();
+        let prefix = git_hash::Prefix::new(hex_to_id("a7065b5e971a6d8b55875d8cf634a3a37202ab23"), 4).unwrap();
+        assert!(
+            matches!(handle.find_by_prefix(prefix, &mut buf), Ok(Some(Err(())))),
+            "there are two objects with that prefix"
+        );
+
+        let prefix = git_hash::Prefix::new(git_hash::ObjectId::null(git_hash::Kind::Sha1), 7).unwrap();
+        assert!(matches!(handle.find_by_prefix(prefix, &mut buf), Ok(None)));
+    }
+}
+
+mod shortest_unambiguous_hex_len {
+    use git_testtools::hex_to_id;
+
+    use crate::store::dynamic::{assert_all_indices_loaded, db_with_all_object_sources};
+
+    #[test]
+    fn it_is_never_shorter_than_the_minimum() {
+        let (handle, _tmp) = db_with_all_object_sources().unwrap();
+        let id = hex_to_id("a7065b5e971a6d8b55875d8cf634a3a37202ab23");
+        assert_eq!(handle.shortest_unambiguous_hex_len(id, 4).unwrap(), 5);
+        assert_all_indices_loaded(&handle, 2, 2);
+        assert_eq!(handle.shortest_unambiguous_hex_len(id, 7).unwrap(), 7);
+    }
+
+    #[test]
+    fn it_matches_the_length_needed_for_unambiguous_prefix_lookups() {
+        let (mut handle, _tmp) = db_with_all_object_sources().unwrap();
+        handle.refresh.never();
+
+        for oid in handle.iter().unwrap().map(Result::unwrap) {
+            let hex_len = handle.shortest_unambiguous_hex_len(oid, 4).unwrap();
+            let prefix = git_hash::Prefix::new(oid, hex_len).unwrap();
+            assert_eq!(handle.lookup_prefix(prefix).unwrap(), Some(Ok(oid)));
+            if hex_len > 4 {
+                let prefix = git_hash::Prefix::new(oid, hex_len - 1).unwrap();
+                assert_eq!(
+                    handle.lookup_prefix(prefix).unwrap(),
+                    Some(Err(())),
+                    "one less is ambiguous"
+                );
+            }
+        }
+    }
+}
+
+mod metrics {
+    use git_odb::{store, Find, FindExt};
+    use git_testtools::hex_to_id;
+
+    use crate::store::dynamic::db;
+
+    #[test]
+    fn lookups_are_counted_per_handle_and_mapped_files_are_reported_by_the_store() {
+        let handle = db();
+        let metrics = handle.metrics();
+        assert_eq!(metrics.lookups, store::metrics::Lookups::default());
+        assert_eq!(metrics.mapped_files, store::metrics::MappedFiles::default());
+
+        let mut buf = Vec::new();
+        handle
+            .find(hex_to_id("4dac9989f96bc5b5b1263b582c08f0c5f0b58542"), &mut buf)
+            .expect("packed object exists");
+        handle
+            .find(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"), &mut buf)
+            .expect("loose object exists");
+        assert!(handle
+            .try_find(hex_to_id("0000000000000000000000000000000000000000"), &mut buf)
+            .unwrap()
+            .is_none());
+
+        let metrics = handle.metrics();
+        assert_eq!(
+            metrics.lookups,
+            store::metrics::Lookups {
+                pack_hits: 1,
+                loose_hits: 1,
+                misses: 1,
+                loose_lookups: 5,
+            },
+            "loose databases are searched whenever packs didn't have an object, also before and after refreshes"
+        );
+        assert_eq!(metrics.mapped_files.num_indices, 3, "all indices were loaded");
+        assert_eq!(metrics.mapped_files.num_packs, 1, "only one pack was needed");
+        assert!(metrics.mapped_files.num_bytes > 0);
+        assert_eq!(
+            metrics.pack_cache,
+            git_odb::cache::Hits::default(),
+            "without caches, nothing is counted"
+        );
+        assert_eq!(metrics.object_cache.ratio(), None);
+
+        assert_eq!(
+            handle.clone().metrics().lookups,
+            store::metrics::Lookups::default(),
+            "each handle counts its own lookups"
+        );
+    }
+
+    #[test]
+    fn hit_ratio() {
+        let hits = git_odb::cache::Hits { hits: 3, misses: 1 };
+        assert_eq!(hits.ratio(), Some(0.75));
+    }
+}
+
 #[test]
 fn missing_objects_triggers_everything_is_loaded() {
     let handle = db();
@@ -812,6 +942,129 @@ fn auto_refresh_with_and_without_id_stability() -> crate::Result {
     Ok(())
 }
 
+mod delete_expired_packs {
+    use git_odb::{store::delete, Find, FindExt};
+    use git_testtools::{fixture_path, hex_to_id};
+
+    const PACK: &str = "pack-11fdfa9e156ab73caae3b6da867192221f2089c2";
+
+    fn objects_dir() -> crate::Result<tempfile::TempDir> {
+        let objects_dir = git_testtools::tempfile::tempdir()?;
+        git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+        Ok(objects_dir)
+    }
+
+    fn copy_pack(objects_dir: &std::path::Path, from: &str, to: &str) -> crate::Result {
+        let pack_dir = objects_dir.join("pack");
+        for ext in &["idx", "pack"] {
+            std::fs::copy(
+                pack_dir.join(from).with_extension(ext),
+                pack_dir.join(to).with_extension(ext),
+            )?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn packs_with_objects_found_nowhere_else_are_not_deleted() -> crate::Result {
+        let objects_dir = objects_dir()?;
+        let handle = git_odb::at(objects_dir.path())?;
+        let index_path = objects_dir.path().join("pack").join(PACK).with_extension("idx");
+
+        let err = handle
+            .store_ref()
+            .delete_expired_packs(Some(&index_path), Default::default())
+            .expect_err("objects are unique to the pack");
+        assert!(matches!(err, delete::Error::ObjectNotCovered { .. }));
+        assert!(index_path.is_file(), "nothing was deleted");
+        Ok(())
+    }
+
+    #[test]
+    fn superseded_packs_are_deleted_and_handles_use_the_remaining_ones() -> crate::Result {
+        let objects_dir = objects_dir()?;
+        copy_pack(objects_dir.path(), PACK, "pack-superseding")?;
+        let handle = git_odb::at(objects_dir.path())?;
+        let id = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        let mut buf = Vec::new();
+        assert!(handle.find(id, &mut buf).is_ok(), "the object is in both packs");
+
+        let index_path = objects_dir.path().join("pack").join(PACK).with_extension("idx");
+        let outcome = handle
+            .store_ref()
+            .delete_expired_packs(Some(index_path.with_extension("pack")), Default::default())?;
+        assert_eq!(outcome.deleted, vec![index_path.clone()]);
+        assert!(outcome.kept.is_empty());
+        assert!(outcome.failed.is_empty());
+        assert!(!index_path.is_file());
+        assert!(!index_path.with_extension("pack").is_file());
+
+        assert!(handle.contains(id));
+        assert!(
+            handle.find(id, &mut buf).is_ok(),
+            "the handle picks up the change and finds the object in the superseding pack"
+        );
+        assert_eq!(
+            handle.store_ref().metrics().known_packs,
+            3,
+            "two original packs and the copy"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packs_with_keep_files_are_retained() -> crate::Result {
+        let objects_dir = objects_dir()?;
+        copy_pack(objects_dir.path(), PACK, "pack-superseding")?;
+        let index_path = objects_dir.path().join("pack").join(PACK).with_extension("idx");
+        std::fs::write(index_path.with_extension("keep"), b"")?;
+        let handle = git_odb::at(objects_dir.path())?;
+
+        let outcome = handle
+            .store_ref()
+            .delete_expired_packs(Some(&index_path), Default::default())?;
+        assert!(outcome.deleted.is_empty());
+        assert_eq!(outcome.kept, vec![index_path.clone()]);
+        assert!(index_path.is_file());
+        assert!(index_path.with_extension("pack").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn packs_referenced_by_the_multi_pack_index_are_not_deleted() -> crate::Result {
+        let (handle, objects_dir) = super::db_with_all_object_sources()?;
+        let index_path = objects_dir
+            .path()
+            .join("pack/pack-a2bf8e71d8c18879e499335762dd95119d93d9f1.idx");
+
+        let err = handle
+            .store_ref()
+            .delete_expired_packs(
+                Some(&index_path),
+                delete::Options {
+                    verify_coverage: false,
+                    ..Default::default()
+                },
+            )
+            .expect_err("the multi-index must be rewritten first");
+        assert!(matches!(err, delete::Error::ReferencedByMultiIndex { .. }));
+        assert!(index_path.is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn only_pack_files_are_accepted() -> crate::Result {
+        let objects_dir = objects_dir()?;
+        let handle = git_odb::at(objects_dir.path())?;
+        let err = handle
+            .store_ref()
+            .delete_expired_packs(Some("pack/multi-pack-index"), Default::default())
+            .expect_err("not a pack");
+        assert!(matches!(err, delete::Error::InvalidPackPath { .. }));
+        Ok(())
+    }
+}
+
 mod verify {
     use std::sync::atomic::AtomicBool;
 
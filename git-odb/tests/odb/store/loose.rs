@@ -30,6 +30,77 @@ fn iter() {
     oids.sort();
     assert_eq!(oids, object_ids());
 }
+
+mod iter_with_options {
+    use git_odb::loose::iter;
+    use pretty_assertions::assert_eq;
+
+    use crate::store::loose::{ldb, object_ids};
+
+    #[test]
+    fn sorted() {
+        let oids = ldb()
+            .iter_with_options(iter::Options {
+                sorted: true,
+                ..Default::default()
+            })
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(oids, object_ids(), "no additional sorting is needed");
+    }
+
+    #[test]
+    fn first_byte() {
+        let db = ldb();
+        for id in object_ids() {
+            let oids = db
+                .iter_with_options(iter::Options {
+                    first_byte: Some(id.first_byte()),
+                    ..Default::default()
+                })
+                .map(Result::unwrap)
+                .collect::<Vec<_>>();
+            assert_eq!(oids, vec![id], "each fixture object has its own directory");
+        }
+        assert_eq!(
+            db.iter_with_options(iter::Options {
+                first_byte: Some(0),
+                ..Default::default()
+            })
+            .count(),
+            0,
+            "directories that don't exist are empty"
+        );
+    }
+
+    #[test]
+    fn with_metadata() {
+        let db = ldb();
+        let entries = db
+            .iter_with_metadata(iter::Options {
+                sorted: true,
+                ..Default::default()
+            })
+            .map(Result::unwrap)
+            .collect::<Vec<_>>();
+        assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), object_ids());
+        for entry in entries {
+            let hex = entry.id.to_hex().to_string();
+            let metadata = std::fs::metadata(db.path().join(&hex[..2]).join(&hex[2..])).unwrap();
+            assert_eq!(entry.size, metadata.len());
+            assert_eq!(entry.mtime, metadata.modified().unwrap());
+        }
+        assert_eq!(
+            db.iter_with_metadata(iter::Options {
+                first_byte: Some(0),
+                ..Default::default()
+            })
+            .count(),
+            0,
+            "directories that don't exist are empty"
+        );
+    }
+}
 pub fn locate_oid(id: git_hash::ObjectId, buf: &mut Vec<u8>) -> git_object::Data<'_> {
     ldb().try_find(id, buf).expect("read success").expect("id present")
 }
@@ -70,6 +141,29 @@ mod write {
         }
         Ok(())
     }
+
+    #[test]
+    fn compression_level_affects_object_size_but_not_content() -> Result<(), Box<dyn std::error::Error>> {
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+        let big_blob = crate::hex_to_id("a706d7cd20fc8ce71489f34b50cf01011c104193");
+        let obj = locate_oid(big_blob, &mut buf);
+
+        let mut sizes = Vec::new();
+        for level in [0, 9] {
+            let dir = tempfile::tempdir()?;
+            let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1).with_compression_level(level);
+            assert_eq!(db.compression_level(), level);
+            let id = db.write_buf(obj.kind, obj.data)?;
+            assert_eq!(id, big_blob);
+            assert_eq!(db.try_find(id, &mut buf2)?.expect("id present").data, obj.data);
+
+            let hex = id.to_hex().to_string();
+            sizes.push(std::fs::metadata(dir.path().join(&hex[..2]).join(&hex[2..]))?.len());
+        }
+        assert!(sizes[0] > sizes[1], "level 0 doesn't compress, but level 9 does");
+        Ok(())
+    }
 }
 
 mod contains {
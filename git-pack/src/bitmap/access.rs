@@ -0,0 +1,66 @@
+use crate::bitmap::{Bitmap, File};
+
+///
+pub mod resolve {
+    /// The error returned by [`File::bitmap_at_index()`][crate::bitmap::File::bitmap_at_index()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no bitmap entry at index {index}")]
+        OutOfBounds { index: usize },
+        #[error("The bitmap entry at index {index} is XORed against an entry that doesn't exist")]
+        InvalidXorOffset { index: usize },
+    }
+}
+
+/// Access
+impl File {
+    /// Return the index of the entry for the commit at `object_position`, which is its position in the pack index or multi-index
+    /// this bitmap belongs to, or `None` if there is no bitmap for it.
+    pub fn lookup(&self, object_position: u32) -> Option<usize> {
+        self.sorted_by_position
+            .binary_search_by_key(&object_position, |idx| self.entries[*idx].object_position)
+            .ok()
+            .map(|pos| self.sorted_by_position[pos])
+    }
+
+    /// Return the fully resolved bitmap of all objects reachable from the commit of the entry at `index`.
+    ///
+    /// Bitmaps may be stored as XOR against a previous bitmap, which is resolved here.
+    pub fn bitmap_at_index(&self, index: usize) -> Result<Bitmap, resolve::Error> {
+        let mut chain = Vec::new();
+        let mut current = index;
+        loop {
+            let entry = self
+                .entries
+                .get(current)
+                .ok_or(resolve::Error::OutOfBounds { index: current })?;
+            chain.push(current);
+            match entry.xor_offset {
+                0 => break,
+                offset => {
+                    current = current
+                        .checked_sub(offset as usize)
+                        .ok_or(resolve::Error::InvalidXorOffset { index: current })?
+                }
+            }
+        }
+
+        let mut out = Bitmap::default();
+        for index in chain.into_iter().rev() {
+            out.xor(&Bitmap::from_ewah(&self.entries[index].bitmap));
+        }
+        Ok(out)
+    }
+
+    /// Return the bitmap with all objects of the given `kind`.
+    pub fn objects_of_kind(&self, kind: git_object::Kind) -> Bitmap {
+        use git_object::Kind::*;
+        Bitmap::from_ewah(match kind {
+            Commit => &self.commits,
+            Tree => &self.trees,
+            Blob => &self.blobs,
+            Tag => &self.tags,
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use crate::bitmap::Bitmap;
+
+const BITS_PER_WORD: usize = 64;
+
+/// Creation and mutation
+impl Bitmap {
+    /// Decompress the given `ewah` bitmap.
+    pub fn from_ewah(ewah: &git_bitmap::ewah::Vec) -> Self {
+        let mut out = Bitmap {
+            words: vec![0; (ewah.num_bits() + BITS_PER_WORD - 1) / BITS_PER_WORD],
+        };
+        ewah.for_each_set_bit(|position| {
+            out.insert(position);
+            Some(())
+        });
+        out
+    }
+
+    /// Set the bit at `position`, growing the bitmap as needed.
+    pub fn insert(&mut self, position: usize) {
+        let word = position / BITS_PER_WORD;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (position % BITS_PER_WORD);
+    }
+
+    /// Set all bits that are set in `other`.
+    pub fn or(&mut self, other: &Bitmap) {
+        self.combine(other, |ours, theirs| ours | theirs)
+    }
+
+    /// Unset all bits that are not set in `other`.
+    pub fn and(&mut self, other: &Bitmap) {
+        self.words.truncate(other.words.len());
+        self.combine(other, |ours, theirs| ours & theirs)
+    }
+
+    /// Unset all bits that are set in `other`.
+    pub fn and_not(&mut self, other: &Bitmap) {
+        self.combine(other, |ours, theirs| ours & !theirs)
+    }
+
+    /// Flip all bits that are set in `other`.
+    pub fn xor(&mut self, other: &Bitmap) {
+        self.combine(other, |ours, theirs| ours ^ theirs)
+    }
+
+    fn combine(&mut self, other: &Bitmap, op: impl Fn(u64, u64) -> u64) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (ours, theirs) in self.words.iter_mut().zip(other.words.iter()) {
+            *ours = op(*ours, *theirs);
+        }
+    }
+}
+
+/// Access
+impl Bitmap {
+    /// Return true if the bit at `position` is set.
+    pub fn contains(&self, position: usize) -> bool {
+        self.words
+            .get(position / BITS_PER_WORD)
+            .map_or(false, |word| word & (1 << (position % BITS_PER_WORD)) != 0)
+    }
+
+    /// Return the amount of set bits.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Return true if no bit is set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Return an iterator over the positions of all set bits, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(word_index, word)| {
+            let word = *word;
+            (0..BITS_PER_WORD)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| word_index * BITS_PER_WORD + bit)
+        })
+    }
+}
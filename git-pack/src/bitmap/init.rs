@@ -0,0 +1,137 @@
+use std::path::Path;
+
+use crate::bitmap::{Entry, File, Version, SIGNATURE};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported bitmap version: {version})")]
+        UnsupportedVersion { version: u16 },
+        #[error("Bitmaps which don't cover the full object graph are not supported")]
+        UnsupportedPartialGraph,
+        #[error("Could not decode {kind} bitmap")]
+        BitmapDecode {
+            source: git_bitmap::ewah::decode::Error,
+            kind: &'static str,
+        },
+    }
+}
+
+pub use error::Error;
+
+const OPTION_FULL_DAG: u16 = 0x1;
+const OPTION_HASH_CACHE: u16 = 0x4;
+
+/// Initialization
+impl File {
+    /// Open the bitmap file at the given `path`.
+    ///
+    /// The `object_hash` is needed as the hash kind isn't stored within the file format itself.
+    pub fn at(path: impl AsRef<Path>, object_hash: git_hash::Kind) -> Result<Self, Error> {
+        Self::at_inner(path.as_ref(), object_hash)
+    }
+
+    fn at_inner(path: &Path, object_hash: git_hash::Kind) -> Result<Self, Error> {
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+
+        let hash_len = object_hash.len_in_bytes();
+        let header_len = SIGNATURE.len() + 2 /* version */ + 2 /* options */ + 4 /* entry count */ + hash_len;
+        if data.len() < header_len + hash_len {
+            return Err(Error::Corrupt {
+                message: "bitmap file is truncated and too short",
+            });
+        }
+
+        let (signature, rest) = data.split_at(SIGNATURE.len());
+        if signature != SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "Invalid signature",
+            });
+        }
+        let (version, rest) = rest.split_at(2);
+        let version = match read_u16(version) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let (options, rest) = rest.split_at(2);
+        let options = read_u16(options);
+        if options & OPTION_FULL_DAG == 0 {
+            return Err(Error::UnsupportedPartialGraph);
+        }
+        let (num_entries, rest) = rest.split_at(4);
+        let num_entries = crate::read_u32(num_entries);
+        let (checksum, rest) = rest.split_at(hash_len);
+        let checksum = git_hash::ObjectId::from(checksum);
+
+        // The trailing checksum as well as the optional name-hash cache and lookup table follow the entries,
+        // which is why we don't have to know where they start.
+        let data_without_trailer = &rest[..rest.len() - hash_len];
+        let (commits, rest) = decode_bitmap(data_without_trailer, "commit")?;
+        let (trees, rest) = decode_bitmap(rest, "tree")?;
+        let (blobs, rest) = decode_bitmap(rest, "blob")?;
+        let (tags, mut rest) = decode_bitmap(rest, "tag")?;
+
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        for entry_index in 0..num_entries as usize {
+            if rest.len() < 4 + 1 + 1 {
+                return Err(Error::Corrupt {
+                    message: "bitmap entry is truncated",
+                });
+            }
+            let (object_position, tail) = rest.split_at(4);
+            let object_position = crate::read_u32(object_position);
+            let xor_offset = tail[0];
+            let flags = tail[1];
+            if xor_offset as usize > entry_index {
+                return Err(Error::Corrupt {
+                    message: "bitmap entry refers to an entry before the first one",
+                });
+            }
+            let (bitmap, tail) = decode_bitmap(&tail[2..], "commit entry")?;
+            entries.push(Entry {
+                object_position,
+                xor_offset,
+                flags,
+                bitmap,
+            });
+            rest = tail;
+        }
+
+        let mut sorted_by_position: Vec<_> = (0..entries.len()).collect();
+        sorted_by_position.sort_by_key(|idx| entries[*idx].object_position);
+
+        Ok(File {
+            path: path.to_owned(),
+            version,
+            object_hash,
+            checksum,
+            has_name_hash_cache: options & OPTION_HASH_CACHE != 0,
+            commits,
+            trees,
+            blobs,
+            tags,
+            entries,
+            sorted_by_position,
+        })
+    }
+}
+
+fn decode_bitmap<'a>(data: &'a [u8], kind: &'static str) -> Result<(git_bitmap::ewah::Vec, &'a [u8]), Error> {
+    git_bitmap::ewah::decode(data).map_err(|source| Error::BitmapDecode { source, kind })
+}
+
+fn read_u16(b: &[u8]) -> u16 {
+    u16::from_be_bytes([b[0], b[1]])
+}
@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+/// The version of a bitmap file
+#[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    V1 = 1,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V1
+    }
+}
+
+/// A representation of a reachability bitmap file, typically named `pack-<hash>.bitmap` if it belongs to a single pack,
+/// or `multi-pack-index-<hash>.bitmap` if it belongs to a [multi-index][crate::multi_index::File].
+///
+/// Each bit in a bitmap represents an object in _pack order_, which is the order of objects by offset in a single pack.
+/// For bitmaps of multi-pack indices it's the _pseudo-pack order_, as obtained by
+/// [`multi_index::File::pseudo_pack_order()`][crate::multi_index::File::pseudo_pack_order()].
+pub struct File {
+    path: PathBuf,
+    version: Version,
+    object_hash: git_hash::Kind,
+    checksum: git_hash::ObjectId,
+    has_name_hash_cache: bool,
+    commits: git_bitmap::ewah::Vec,
+    trees: git_bitmap::ewah::Vec,
+    blobs: git_bitmap::ewah::Vec,
+    tags: git_bitmap::ewah::Vec,
+    entries: Vec<Entry>,
+    /// Entry indices sorted by the object position of the entry, for lookup by position.
+    sorted_by_position: Vec<usize>,
+}
+
+/// A bitmap of all objects reachable from a commit, as stored in a bitmap [file][File].
+pub struct Entry {
+    /// The position of the commit in the index the bitmap file belongs to, i.e. its [pack index entry][crate::index::EntryIndex]
+    /// or its [multi-index entry][crate::multi_index::EntryIndex].
+    pub object_position: u32,
+    /// If not 0, our bitmap is stored as XOR against the bitmap of the entry this many entries before us.
+    pub xor_offset: u8,
+    /// Flags as written by git, with the only known one signalling that this bitmap should be reused when writing.
+    pub flags: u8,
+    bitmap: git_bitmap::ewah::Vec,
+}
+
+/// A decompressed bitmap with one bit per object, in pack or pseudo-pack order.
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct Bitmap {
+    words: Vec<u64>,
+}
+
+/// Basic file information
+impl File {
+    /// The version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// The path of the opened bitmap file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The kind of hash we assume.
+    pub fn object_hash(&self) -> git_hash::Kind {
+        self.object_hash
+    }
+    /// The checksum of the pack or multi-index file this bitmap belongs to.
+    ///
+    /// If it doesn't match, the bitmap must not be used.
+    pub fn checksum(&self) -> &git_hash::oid {
+        &self.checksum
+    }
+    /// Returns true if the file contains a cache of name hashes, which is currently ignored.
+    pub fn has_name_hash_cache(&self) -> bool {
+        self.has_name_hash_cache
+    }
+    /// All commit bitmaps stored in the file, in order.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+}
+
+const SIGNATURE: &[u8] = b"BITM";
+
+///
+pub mod init;
+
+mod access;
+pub use access::resolve;
+
+mod bits;
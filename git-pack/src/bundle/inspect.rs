@@ -0,0 +1,215 @@
+use git_hash::ObjectId;
+
+use crate::{data, index, Bundle};
+
+mod error {
+    use crate::data;
+
+    /// The error returned by the iterator of [`Bundle::inspect_entries()`][crate::Bundle::inspect_entries()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Entry {id} at pack offset {pack_offset} lies outside of the pack data")]
+        OffsetOutOfBounds {
+            id: git_hash::ObjectId,
+            pack_offset: data::Offset,
+        },
+        #[error("The delta base of entry {id} at pack offset {base_pack_offset} is not listed in the index")]
+        MissingOfsDeltaBase {
+            id: git_hash::ObjectId,
+            base_pack_offset: data::Offset,
+        },
+        #[error("The delta chain of entry {id} is longer than the amount of objects in the pack, it must be cyclic")]
+        DeltaChainCycle { id: git_hash::ObjectId },
+    }
+}
+pub use error::Error;
+
+/// The base object of a delta entry as seen by [`Entry::base`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeltaBase {
+    /// The id of the base object.
+    pub id: ObjectId,
+    /// The offset of the base object in this pack, or `None` if a [`RefDelta`][data::entry::Header::RefDelta]
+    /// refers to an object outside of this pack.
+    pub pack_offset: Option<data::Offset>,
+}
+
+/// Information about a single entry of a pack obtained without decompressing it.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// The id of the object stored in the entry.
+    pub id: ObjectId,
+    /// The offset at which the entry's header starts in the pack.
+    pub pack_offset: data::Offset,
+    /// The header of the entry which tells the kind of object or delta.
+    pub header: data::entry::Header,
+    /// The size of the header in bytes.
+    pub header_size: usize,
+    /// The size of the object once decompressed, or the size of the decompressed delta instructions if this is a delta.
+    pub decompressed_size: u64,
+    /// The amount of bytes of compressed data following the header.
+    pub compressed_size: u64,
+    /// The CRC32 of the entry as stored in the index, if the index is of version 2 or higher.
+    pub crc32: Option<u32>,
+    /// The kind of object this entry resolves to once all deltas are applied, or `None` if the delta chain
+    /// ends in an object outside of this pack.
+    pub object_kind: Option<git_object::Kind>,
+    /// The amount of deltas that need to be applied to obtain the object, which is 0 for non-delta entries.
+    pub chain_length: u32,
+    /// The immediate base object if this entry is a delta.
+    pub base: Option<DeltaBase>,
+}
+
+impl Entry {
+    /// The amount of bytes the entry occupies in the pack, including the header.
+    pub fn entry_size(&self) -> u64 {
+        self.header_size as u64 + self.compressed_size
+    }
+
+    /// The offset at which the compressed data of this entry starts.
+    pub fn data_offset(&self) -> data::Offset {
+        self.pack_offset + self.header_size as u64
+    }
+}
+
+/// An iterator over all entries of a pack in the order of their pack offset, created by [`Bundle::inspect_entries()`].
+pub struct Iter<'a> {
+    bundle: &'a Bundle,
+    entries_by_offset: Vec<index::Entry>,
+    next: usize,
+}
+
+impl<'a> Iter<'a> {
+    fn entry_at(&self, id: &ObjectId, pack_offset: data::Offset) -> Result<data::Entry, Error> {
+        if pack_offset >= self.bundle.pack.pack_end() as u64 {
+            return Err(Error::OffsetOutOfBounds { id: *id, pack_offset });
+        }
+        Ok(self.bundle.pack.entry(pack_offset))
+    }
+
+    fn id_at_offset(&self, pack_offset: data::Offset) -> Option<ObjectId> {
+        self.entries_by_offset
+            .binary_search_by_key(&pack_offset, |e| e.pack_offset)
+            .ok()
+            .map(|idx| self.entries_by_offset[idx].oid)
+    }
+
+    fn base_of(&self, id: &ObjectId, entry: &data::Entry) -> Result<Option<DeltaBase>, Error> {
+        Ok(match entry.header {
+            data::entry::Header::OfsDelta { base_distance } => {
+                let base_pack_offset = entry.base_pack_offset(base_distance);
+                let base_id = self.id_at_offset(base_pack_offset).ok_or(Error::MissingOfsDeltaBase {
+                    id: *id,
+                    base_pack_offset,
+                })?;
+                Some(DeltaBase {
+                    id: base_id,
+                    pack_offset: Some(base_pack_offset),
+                })
+            }
+            data::entry::Header::RefDelta { base_id } => Some(DeltaBase {
+                id: base_id,
+                pack_offset: self
+                    .bundle
+                    .index
+                    .lookup(base_id)
+                    .map(|idx| self.bundle.index.pack_offset_at_index(idx)),
+            }),
+            _ => None,
+        })
+    }
+
+    fn resolve_chain(
+        &self,
+        id: &ObjectId,
+        mut base: Option<DeltaBase>,
+    ) -> Result<(Option<git_object::Kind>, u32), Error> {
+        let mut chain_length = 0;
+        while let Some(delta_base) = base {
+            chain_length += 1;
+            if chain_length > self.entries_by_offset.len() as u32 {
+                return Err(Error::DeltaChainCycle { id: *id });
+            }
+            let pack_offset = match delta_base.pack_offset {
+                Some(ofs) => ofs,
+                None => return Ok((None, chain_length)),
+            };
+            let entry = self.entry_at(&delta_base.id, pack_offset)?;
+            match entry.header.as_kind() {
+                Some(kind) => return Ok((Some(kind), chain_length)),
+                None => base = self.base_of(&delta_base.id, &entry)?,
+            }
+        }
+        unreachable!("the loop is only entered for deltas and returns once a base object is found")
+    }
+
+    fn entry_at_sorted_index(&self, idx: usize) -> Result<Entry, Error> {
+        let index_entry = &self.entries_by_offset[idx];
+        let id = index_entry.oid;
+        let entry = self.entry_at(&id, index_entry.pack_offset)?;
+        let next_pack_offset = self
+            .entries_by_offset
+            .get(idx + 1)
+            .map(|e| e.pack_offset)
+            .unwrap_or(self.bundle.pack.pack_end() as u64);
+
+        let base = self.base_of(&id, &entry)?;
+        let (object_kind, chain_length) = match entry.header.as_kind() {
+            Some(kind) => (Some(kind), 0),
+            None => self.resolve_chain(&id, base)?,
+        };
+        Ok(Entry {
+            id,
+            pack_offset: index_entry.pack_offset,
+            header: entry.header,
+            header_size: entry.header_size(),
+            decompressed_size: entry.decompressed_size,
+            compressed_size: next_pack_offset.saturating_sub(entry.data_offset),
+            crc32: index_entry.crc32,
+            object_kind,
+            chain_length,
+            base,
+        })
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.entries_by_offset.len() {
+            return None;
+        }
+        let res = self.entry_at_sorted_index(self.next);
+        self.next += 1;
+        Some(res)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.entries_by_offset.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+/// Inspection
+impl Bundle {
+    /// Return an iterator over all entries of the pack in the order they are stored, providing information about their
+    /// sizes and delta bases without decompressing any object data.
+    ///
+    /// This is the information needed to produce output similar to `git verify-pack -v` or to analyze the
+    /// composition of a pack.
+    pub fn inspect_entries(&self) -> Iter<'_> {
+        let mut entries_by_offset: Vec<_> = self.index.iter().collect();
+        entries_by_offset.sort_by_key(|e| e.pack_offset);
+        Iter {
+            bundle: self,
+            entries_by_offset,
+            next: 0,
+        }
+    }
+}
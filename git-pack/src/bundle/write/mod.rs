@@ -10,7 +10,7 @@ use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
 use crate::data;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 use types::{LockWriter, PassThrough};
@@ -47,6 +47,7 @@ impl crate::Bundle {
     where
         P: Progress,
     {
+        let _span = git_features::span!("git_pack::bundle::write_to_directory");
         let mut read_progress = progress.add_child("read pack");
         read_progress.init(None, progress::bytes());
         let pack = progress::Read {
@@ -146,6 +147,7 @@ impl crate::Bundle {
         thin_pack_base_object_lookup_fn: Option<ThinPackLookupFnSend>,
         options: Options,
     ) -> Result<Outcome, Error> {
+        let _span = git_features::span!("git_pack::bundle::write_to_directory_eagerly");
         let mut read_progress = progress.add_child("read pack");
         read_progress.init(pack_size.map(|s| s as usize), progress::bytes());
         let pack = progress::Read {
@@ -0,0 +1,131 @@
+use std::io;
+
+use git_hash::ObjectId;
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::bundle_file::{Header, Prerequisite, Ref, Version};
+
+/// Returned by [`Header::from_read()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the bundle header")]
+    Io(#[from] io::Error),
+    #[error("Expected a bundle signature like '# v2 git bundle', but got '{signature}'")]
+    Signature { signature: BString },
+    #[error("The capability '{capability}' is only allowed in version 3 bundles")]
+    CapabilityInV2 { capability: BString },
+    #[error("The capability '{capability}' is not supported")]
+    UnsupportedCapability { capability: BString },
+    #[error("Could not decode the object id in line '{line}'")]
+    Id {
+        line: BString,
+        source: git_hash::decode::Error,
+    },
+    #[error("The line '{line}' is neither a prerequisite nor a reference")]
+    InvalidLine { line: BString },
+    #[error("The bundle header ended before the empty line separating it from the pack")]
+    UnexpectedEof,
+}
+
+impl Header {
+    /// Read a bundle header from `read`, which is left positioned at the beginning of the pack, and return it along with
+    /// the amount of bytes it occupied.
+    pub fn from_read(mut read: impl io::BufRead) -> Result<(Self, u64), Error> {
+        let mut line = Vec::new();
+        let mut bytes_read = read_line(&mut read, &mut line)?;
+        let version = if line == Version::V2.signature() {
+            Version::V2
+        } else if line == Version::V3.signature() {
+            Version::V3
+        } else {
+            return Err(Error::Signature { signature: line.into() });
+        };
+        let mut header = Header {
+            version,
+            ..Default::default()
+        };
+
+        loop {
+            bytes_read += read_line(&mut read, &mut line)?;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(capability) = line.strip_prefix(b"@") {
+                let unsupported = || Error::UnsupportedCapability {
+                    capability: capability.into(),
+                };
+                if version == Version::V2 {
+                    return Err(Error::CapabilityInV2 {
+                        capability: capability.into(),
+                    });
+                }
+                match capability
+                    .find_byte(b'=')
+                    .map(|pos| (&capability[..pos], &capability[pos + 1..]))
+                {
+                    Some((b"object-format", name)) => {
+                        header.object_hash = name
+                            .to_str()
+                            .ok()
+                            .and_then(|name| name.parse().ok())
+                            .ok_or_else(unsupported)?
+                    }
+                    Some((b"filter", filter)) => header.filter = Some(filter.into()),
+                    _ => return Err(unsupported()),
+                }
+                continue;
+            }
+
+            let (is_prerequisite, rest) = match line.strip_prefix(b"-") {
+                Some(rest) => (true, rest),
+                None => (false, line.as_slice()),
+            };
+            let (hex, rest) = rest.split_at(header.object_hash.len_in_hex().min(rest.len()));
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(Error::InvalidLine {
+                    line: line.as_bstr().to_owned(),
+                });
+            }
+            let id = ObjectId::from_hex(hex).map_err(|source| Error::Id {
+                line: line.as_bstr().to_owned(),
+                source,
+            })?;
+            let text = match rest {
+                [] => b"".as_bstr(),
+                [b' ', text @ ..] => text.as_bstr(),
+                _ => {
+                    return Err(Error::InvalidLine {
+                        line: line.as_bstr().to_owned(),
+                    })
+                }
+            };
+            if is_prerequisite {
+                header.prerequisites.push(Prerequisite {
+                    id,
+                    comment: text.to_owned(),
+                });
+            } else if text.is_empty() {
+                return Err(Error::InvalidLine {
+                    line: line.as_bstr().to_owned(),
+                });
+            } else {
+                header.refs.push(Ref {
+                    id,
+                    name: text.to_owned(),
+                });
+            }
+        }
+        Ok((header, bytes_read))
+    }
+}
+
+/// Read the next line into `line` without its trailing newline, and return the amount of bytes read.
+fn read_line(read: &mut impl io::BufRead, line: &mut Vec<u8>) -> Result<u64, Error> {
+    line.clear();
+    let bytes_read = read.read_until(b'\n', line)?;
+    if line.pop() != Some(b'\n') {
+        return Err(Error::UnexpectedEof);
+    }
+    Ok(bytes_read as u64)
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs, io,
+    io::{Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use git_features::progress::Progress;
+
+use crate::bundle_file::{decode, File, Header};
+
+/// Returned by [`File::at()`]
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open bundle file at '{}'", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error(transparent)]
+    Decode(#[from] decode::Error),
+}
+
+/// Initialization
+impl File {
+    /// Open the bundle at `path` and decode its header.
+    pub fn at(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = fs::File::open(path).map_err(|source| Error::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        let (header, pack_offset) = Header::from_read(io::BufReader::new(file))?;
+        Ok(File {
+            header,
+            path: path.to_owned(),
+            pack_offset,
+        })
+    }
+
+    /// Open the pack contained in the bundle for reading.
+    pub fn pack(&self) -> io::Result<io::BufReader<fs::File>> {
+        let mut file = fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pack_offset))?;
+        Ok(io::BufReader::new(file))
+    }
+
+    /// Write the pack of this bundle into `directory` along with a newly created index, similar to
+    /// [`Bundle::write_to_directory()`][crate::Bundle::write_to_directory()].
+    ///
+    /// Packs in bundles created by git are thin, hence `thin_pack_base_object_lookup_fn` should be set to find base objects
+    /// among the prerequisites.
+    #[allow(clippy::type_complexity)]
+    pub fn write_pack_to_directory(
+        &self,
+        directory: Option<impl AsRef<Path>>,
+        progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        thin_pack_base_object_lookup_fn: Option<
+            Box<dyn for<'a> FnMut(git_hash::ObjectId, &'a mut Vec<u8>) -> Option<git_object::Data<'a>>>,
+        >,
+        options: crate::bundle::write::Options,
+    ) -> Result<crate::bundle::write::Outcome, crate::bundle::write::Error> {
+        crate::Bundle::write_to_directory(
+            self.pack()?,
+            directory,
+            progress,
+            should_interrupt,
+            thin_pack_base_object_lookup_fn,
+            crate::bundle::write::Options {
+                object_hash: self.header.object_hash,
+                ..options
+            },
+        )
+    }
+}
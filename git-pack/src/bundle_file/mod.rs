@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use git_hash::ObjectId;
+use git_object::bstr::BString;
+
+/// The version of a bundle file, which determines its signature and whether it may have capabilities.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Version {
+    /// The original version which always uses SHA1 object ids.
+    V2,
+    /// The version supporting capabilities to indicate the object format and whether the pack is filtered.
+    V3,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V2
+    }
+}
+
+impl Version {
+    /// The line at the beginning of each bundle file of this version, without the trailing newline.
+    pub fn signature(&self) -> &'static [u8] {
+        match self {
+            Version::V2 => b"# v2 git bundle",
+            Version::V3 => b"# v3 git bundle",
+        }
+    }
+}
+
+impl std::str::FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "2" | "v2" => Version::V2,
+            "3" | "v3" => Version::V3,
+            _ => return Err(format!("Unknown bundle version: '{}'", s)),
+        })
+    }
+}
+
+/// A commit the receiver of a bundle must have for the objects in the bundle to be complete.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prerequisite {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// Typically the subject of the commit, which is informational only and may be empty.
+    pub comment: BString,
+}
+
+/// A reference stored in a bundle.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ref {
+    /// The object the reference points to.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// The header of a bundle file, which is followed by a pack with all objects reachable from [`refs`][Header::refs]
+/// but not from [`prerequisites`][Header::prerequisites].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used for all object ids, which is always SHA1 in version 2 bundles.
+    pub object_hash: git_hash::Kind,
+    /// A filter specification like `blob:none` if the pack omits objects, which is only supported in version 3 bundles.
+    pub filter: Option<BString>,
+    /// The commits the receiver must have.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references contained in the bundle.
+    pub refs: Vec<Ref>,
+}
+
+impl Header {
+    /// Return all prerequisites which aren't contained in `db`, which must be none for the bundle to be applied to it.
+    pub fn missing_prerequisites(&self, db: &impl crate::Find) -> Vec<&Prerequisite> {
+        self.prerequisites.iter().filter(|p| !db.contains(p.id)).collect()
+    }
+}
+
+/// A bundle file as created by `git bundle create`, consisting of a [`Header`] and a pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct File {
+    /// The decoded header.
+    pub header: Header,
+    /// The path at which the bundle is located.
+    pub path: PathBuf,
+    /// The offset at which the pack starts in the file.
+    pub pack_offset: u64,
+}
+
+///
+pub mod decode;
+///
+pub mod init;
+///
+pub mod write;
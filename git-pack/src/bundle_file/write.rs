@@ -0,0 +1,242 @@
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    io,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::{
+    parallel::{reduce::Finalize, InOrderIter},
+    progress::{self, Progress},
+};
+use git_hash::ObjectId;
+
+use crate::{
+    bundle_file::{File, Header, Prerequisite, Ref, Version},
+    data::output,
+    FindExt,
+};
+
+/// The error returned by [`File::write_to()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    TraverseCommits(#[from] git_traverse::commit::ancestors::Error),
+    #[error(transparent)]
+    FindPrerequisite(#[from] crate::find::existing_object::Error<E>),
+    #[error(transparent)]
+    CountObjects(#[from] output::count::objects::Error<crate::find::existing::Error<E>, Infallible>),
+    #[error(transparent)]
+    CreateEntries(#[from] output::entry::iter_from_counts::Error<E>),
+    #[error(transparent)]
+    WritePack(#[from] output::bytes::Error<output::entry::iter_from_counts::Error<E>>),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`File::write_to()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Options {
+    /// The version of the bundle to write.
+    pub version: Version,
+    /// The kind of hash used by all objects, which must be SHA1 for version 2 bundles.
+    pub object_hash: git_hash::Kind,
+    /// The amount of threads to use at most when creating pack entries. If `None`, all logical cores are used.
+    pub thread_limit: Option<usize>,
+    /// The zlib compression level for objects that have to be compressed, similar to `pack.compression`.
+    pub compression_level: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            version: Default::default(),
+            object_hash: Default::default(),
+            thread_limit: None,
+            compression_level: git_features::zlib::stream::deflate::DEFAULT_LEVEL,
+        }
+    }
+}
+
+/// The outcome of [`File::write_to()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The header that was written.
+    pub header: Header,
+    /// The amount of objects in the pack following the header.
+    pub num_objects: usize,
+}
+
+impl Header {
+    /// Write this header to `out`, which is to be followed by the pack.
+    ///
+    /// Note that the [`object_hash`][Header::object_hash] and [`filter`][Header::filter] are only written in version 3
+    /// bundles, which should be used if they differ from their defaults.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        out.write_all(self.version.signature())?;
+        out.write_all(b"\n")?;
+        if self.version == Version::V3 {
+            writeln!(
+                out,
+                "@object-format={}",
+                self.object_hash.to_string().to_ascii_lowercase()
+            )?;
+            if let Some(filter) = &self.filter {
+                out.write_all(b"@filter=")?;
+                out.write_all(filter)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.id)?;
+            out.write_all(&r.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")
+    }
+}
+
+impl File {
+    /// Write a bundle to `out` containing `refs` and a pack with all objects reachable from the commits `tips` and the objects
+    /// `refs` point to, but not from the commits `hidden`, looking up objects in `db`, similar to `git bundle create`.
+    ///
+    /// All parents of included commits which aren't included themselves become prerequisites the receiver must have.
+    /// As deltas may only refer to objects in the pack, no thin pack is written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_to<Find>(
+        mut out: impl io::Write,
+        refs: Vec<Ref>,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        hidden: impl IntoIterator<Item = impl Into<ObjectId>>,
+        db: Find,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        Options {
+            version,
+            object_hash,
+            thread_limit,
+            compression_level,
+        }: Options,
+    ) -> Result<Outcome, Error<Find::Error>>
+    where
+        Find: crate::Find + Clone + Send + 'static,
+        Find::Error: Send + Sync,
+    {
+        let commits = {
+            let mut progress = progress.add_child("collecting commits");
+            progress.init(None, progress::count("commits"));
+            let mut commits = Vec::new();
+            for id in git_traverse::commit::Ancestors::new(
+                tips,
+                git_traverse::commit::ancestors::State::default(),
+                |oid, buf| db.find_commit_iter(oid, buf).map(|(iter, _location)| iter),
+            )
+            .with_hidden(hidden)
+            {
+                commits.push(id?);
+                progress.inc();
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
+            commits
+        };
+        let header = Header {
+            version,
+            object_hash,
+            filter: None,
+            prerequisites: prerequisites_of(&db, &commits)?,
+            refs,
+        };
+        header.write_to(&mut out)?;
+
+        // Annotated tags aren't part of the commit traversal but have to be part of the pack.
+        let input = commits
+            .into_iter()
+            .chain(header.refs.iter().map(|r| r.id))
+            .map(Ok::<_, Infallible>);
+        let (counts, _) = {
+            let mut progress = progress.add_child("counting");
+            progress.init(None, progress::count("objects"));
+            output::count::objects_unthreaded(
+                db.clone(),
+                input,
+                progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            )?
+        };
+
+        let num_objects = counts.len();
+        let mut entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            progress.add_child("creating entries"),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+                compression_level,
+            },
+        ));
+        {
+            let mut progress = progress.add_child("writing");
+            progress.init(None, progress::bytes());
+            let mut pack_bytes = output::bytes::FromEntriesIter::new(
+                entries.by_ref(),
+                &mut out,
+                num_objects as u32,
+                crate::data::Version::default(),
+                object_hash,
+            );
+            for written in pack_bytes.by_ref() {
+                progress.inc_by(written? as usize);
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
+            pack_bytes.digest().expect("iteration is done");
+        }
+        entries.inner.finalize()?;
+        out.flush()?;
+        Ok(Outcome { header, num_objects })
+    }
+}
+
+/// Return all parents of `commits` which aren't among them along with their subject, in order of occurrence.
+fn prerequisites_of<Find>(db: &Find, commits: &[ObjectId]) -> Result<Vec<Prerequisite>, Error<Find::Error>>
+where
+    Find: crate::Find,
+{
+    let included: HashSet<_> = commits.iter().collect();
+    let mut seen = HashSet::new();
+    let mut prerequisites = Vec::new();
+    let mut buf = Vec::new();
+    for id in commits {
+        let parents: Vec<_> = db.find_commit(id, &mut buf)?.0.parents().collect();
+        for parent in parents {
+            if included.contains(&parent) || !seen.insert(parent) {
+                continue;
+            }
+            let comment = db.find_commit(parent, &mut buf)?.0.message_summary().into_owned();
+            prerequisites.push(Prerequisite { id: parent, comment });
+        }
+    }
+    Ok(prerequisites)
+}
@@ -7,17 +7,183 @@ impl<T> Tree<T> {
         let roots = self.root_items.as_mut_slice();
         let children = self.child_items.as_mut_slice();
 
+        self.is_shard.clear();
+        let is_shard = self.is_shard.as_slice();
         roots.chunks_mut(chunk_size).map(move |c| Chunk {
             inner: c.iter_mut(),
             children: children as *mut [Item<T>],
+            is_shard,
         })
     }
+
+    /// Return units of work with about `chunk_size` objects each, which are either chunks of roots or [`Shard`]s.
+    ///
+    /// Roots, which are not children themselves as they have no parents, are grouped into chunks until the objects of their
+    /// subtrees add up to `chunk_size`. Subtrees larger than that are split into shards which can be resolved by other
+    /// threads, at the cost of having to resolve the bases of each shard once more.
+    ///
+    /// Shards only start at branch points, that is at children of bases with more than one child. Chains of deltas
+    /// without branches are never split as each delta needs the previous one, so splitting them wouldn't allow
+    /// resolving them in parallel but only cause their bases to be resolved multiple times.
+    pub(crate) fn iter_units(&mut self, chunk_size: usize) -> Vec<Unit<'_, T>> {
+        let chunk_size = chunk_size.max(1);
+        let min_shard_size = (chunk_size / 2).max(2);
+        let mut is_shard = vec![false; self.child_items.len()];
+        let mut shards = Vec::new();
+        let mut root_sizes = Vec::with_capacity(self.root_items.len());
+        {
+            let roots = &self.root_items;
+            let children = &self.child_items;
+            let mut sizes = vec![1; children.len()];
+            let mut parents = vec![None; children.len()];
+            let mut root_of = vec![0; children.len()];
+            let mut order = Vec::with_capacity(children.len());
+            let mut stack: Vec<_> = roots
+                .iter()
+                .enumerate()
+                .flat_map(|(root, item)| item.children.iter().map(move |child| (*child, root)))
+                .collect();
+            while let Some((idx, root)) = stack.pop() {
+                order.push(idx);
+                root_of[idx] = root;
+                for child in &children[idx].children {
+                    parents[*child] = Some(idx);
+                    stack.push((*child, root));
+                }
+            }
+            for idx in order.into_iter().rev() {
+                sizes[idx] += children[idx].children.iter().map(|child| sizes[*child]).sum::<usize>();
+            }
+
+            // Split off subtrees below `start_children` as shards while more than `chunk_size` objects remain, but only
+            // at branch points and if both the shard and what remains are large enough to be worth resolving the bases
+            // of the shard again.
+            let mut split_off = |start_children: &[usize], remaining: &mut usize, new_shards: &mut Vec<usize>| {
+                let mut stack = vec![start_children];
+                while let Some(siblings) = stack.pop() {
+                    let is_branch_point = siblings.len() > 1;
+                    for &idx in siblings {
+                        if *remaining <= chunk_size {
+                            return;
+                        }
+                        let size = sizes[idx];
+                        if size < min_shard_size {
+                            continue;
+                        }
+                        if is_branch_point && *remaining - size >= min_shard_size {
+                            *remaining -= size;
+                            is_shard[idx] = true;
+                            new_shards.push(idx);
+                        } else {
+                            stack.push(&children[idx].children);
+                        }
+                    }
+                }
+            };
+
+            let mut new_shards = Vec::new();
+            for root in roots {
+                let mut remaining = 1 + root.children.iter().map(|child| sizes[*child]).sum::<usize>();
+                split_off(&root.children, &mut remaining, &mut new_shards);
+                root_sizes.push(remaining);
+            }
+            while let Some(idx) = new_shards.pop() {
+                let mut remaining = sizes[idx];
+                split_off(&children[idx].children, &mut remaining, &mut new_shards);
+                let mut bases = Vec::new();
+                let mut parent = parents[idx];
+                while let Some(parent_idx) = parent {
+                    bases.push(children[parent_idx].entry_range());
+                    parent = parents[parent_idx];
+                }
+                bases.push(roots[root_of[idx]].entry_range());
+                bases.reverse();
+                shards.push((idx, bases));
+            }
+        }
+
+        self.is_shard = is_shard;
+        let is_shard = self.is_shard.as_slice();
+        let children = self.child_items.as_mut_slice() as *mut [Item<T>];
+        let mut units: Vec<_> = shards
+            .into_iter()
+            .map(|(idx, bases)| {
+                // SAFETY: The children array is alive by the 'a lifetime.
+                // SAFETY: The index is a valid index into the children array.
+                // SAFETY: Shards are skipped when iterating the children of their base, so no other node yields them.
+                #[allow(unsafe_code)]
+                let item = unsafe { &mut *(children as *mut Item<T>).add(idx) };
+                Unit::Shard(Shard {
+                    node: Node {
+                        item,
+                        children,
+                        is_shard,
+                    },
+                    bases,
+                })
+            })
+            .collect();
+
+        let mut roots = self.root_items.as_mut_slice();
+        let mut chunk_len = 0;
+        let mut num_objects = 0;
+        for size in root_sizes {
+            chunk_len += 1;
+            num_objects += size;
+            if num_objects >= chunk_size {
+                let (chunk, rest) = std::mem::take(&mut roots).split_at_mut(chunk_len);
+                roots = rest;
+                units.push(Unit::Roots(Chunk {
+                    inner: chunk.iter_mut(),
+                    children,
+                    is_shard,
+                }));
+                chunk_len = 0;
+                num_objects = 0;
+            }
+        }
+        if !roots.is_empty() {
+            units.push(Unit::Roots(Chunk {
+                inner: roots.iter_mut(),
+                children,
+                is_shard,
+            }));
+        }
+        units
+    }
+}
+
+impl<T> Item<T> {
+    fn entry_range(&self) -> crate::data::EntryRange {
+        self.offset..self.next_offset
+    }
 }
 
-/// A chunk returned by `iter_root_chunks`, which can be iterated over to get [`Node`]s.
+/// A unit of work returned by `iter_units`.
+pub(crate) enum Unit<'a, T> {
+    /// Roots to resolve along with all of their children which aren't shards.
+    Roots(Chunk<'a, T>),
+    /// A part of the subtree of a root.
+    Shard(Shard<'a, T>),
+}
+
+// SAFETY: The raw pointers are uniquely materialized in `Node::into_child_iter()`, or when creating shards.
+#[allow(unsafe_code)]
+unsafe impl<'a, T> Send for Unit<'a, T> where T: Send {}
+
+/// A delta and its children which are resolved separately from the other children of its base.
+pub(crate) struct Shard<'a, T> {
+    /// The first node of the shard, which is a delta.
+    pub node: Node<'a, T>,
+    /// The pack entries of the root and all deltas leading to `node`, in the order in which they need to be resolved.
+    pub bases: Vec<crate::data::EntryRange>,
+}
+
+/// A chunk of roots returned by `iter_root_chunks` or `iter_units`, which can be iterated over to get [`Node`]s.
 pub struct Chunk<'a, T> {
     inner: std::slice::IterMut<'a, Item<T>>,
     children: *mut [Item<T>],
+    is_shard: &'a [bool],
 }
 
 // SAFETY: The raw pointer is uniquely materialized in `Node::into_child_iter`.
@@ -31,14 +197,16 @@ impl<'a, T> Iterator for Chunk<'a, T> {
         self.inner.next().map(|item| Node {
             item,
             children: self.children,
+            is_shard: self.is_shard,
         })
     }
 }
 
-/// An item returned by `iter_root_chunks`, allowing access to the `data` stored alongside nodes in a [`Tree`].
+/// An item returned by `iter_root_chunks` or `iter_units`, allowing access to the `data` stored alongside nodes in a [`Tree`].
 pub struct Node<'a, T> {
     item: &'a mut Item<T>,
     children: *mut [Item<T>],
+    is_shard: &'a [bool],
 }
 
 impl<'a, T> Node<'a, T> {
@@ -49,7 +217,7 @@ impl<'a, T> Node<'a, T> {
 
     /// Returns the slice into the data pack at which the pack entry is located.
     pub fn entry_slice(&self) -> crate::data::EntryRange {
-        self.item.offset..self.item.next_offset
+        self.item.entry_range()
     }
 
     /// Returns the node data associated with this node.
@@ -59,18 +227,24 @@ impl<'a, T> Node<'a, T> {
 
     /// Transform this `Node` into an iterator over its children.
     ///
-    /// Children are `Node`s referring to pack entries whose base object is this pack entry.
+    /// Children are `Node`s referring to pack entries whose base object is this pack entry, without those which are resolved
+    /// separately as part of a shard.
     pub fn into_child_iter(self) -> impl Iterator<Item = Node<'a, T>> + 'a {
-        let children = self.children;
-        self.item.children.iter().map(move |&index| {
-            // SAFETY: The children array is alive by the 'a lifetime.
-            // SAFETY: The index is a valid index into the children array.
-            // SAFETY: The resulting mutable pointer cannot be yielded by any other node.
-            #[allow(unsafe_code)]
-            Node {
-                item: unsafe { &mut *(children as *mut Item<T>).add(index) },
-                children,
-            }
-        })
+        let (children, is_shard) = (self.children, self.is_shard);
+        self.item
+            .children
+            .iter()
+            .filter(move |&&index| !is_shard.get(index).copied().unwrap_or(false))
+            .map(move |&index| {
+                // SAFETY: The children array is alive by the 'a lifetime.
+                // SAFETY: The index is a valid index into the children array.
+                // SAFETY: The resulting mutable pointer cannot be yielded by any other node, nor by a shard.
+                #[allow(unsafe_code)]
+                Node {
+                    item: unsafe { &mut *(children as *mut Item<T>).add(index) },
+                    children,
+                    is_shard,
+                }
+            })
     }
 }
@@ -51,6 +51,8 @@ pub struct Tree<T> {
     /// Future child offsets, associating their offset into the pack with their index in the items array.
     /// (parent_offset, child_index)
     future_child_offsets: Vec<(crate::data::Offset, usize)>,
+    /// A flag for each child item which is set if it is resolved as part of a shard instead of along with its base.
+    is_shard: Vec<bool>,
 }
 
 impl<T> Tree<T> {
@@ -61,6 +63,7 @@ impl<T> Tree<T> {
             child_items: Vec::with_capacity(num_objects / 2),
             last_seen: None,
             future_child_offsets: Vec::new(),
+            is_shard: Vec::new(),
         })
     }
 
@@ -191,6 +194,178 @@ mod tests {
                 Ok(())
             }
         }
+
+        mod traverse {
+            use std::sync::atomic::AtomicBool;
+
+            use git_features::progress;
+            use git_odb::pack;
+            use git_testtools::fixture_path;
+
+            use crate::cache::delta::{iter::Unit, traverse, Tree};
+
+            const INDEX_V2: &str = "objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx";
+            const PACK_FOR_INDEX_V2: &str = "objects/pack/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack";
+
+            struct Entry {
+                pack_offset: crate::data::Offset,
+                id: git_hash::ObjectId,
+                num_visits: usize,
+            }
+
+            fn tree(idx: &pack::index::File) -> Result<Tree<Entry>, Box<dyn std::error::Error>> {
+                let mut entries: Vec<_> = idx
+                    .iter()
+                    .map(|e| Entry {
+                        pack_offset: e.pack_offset,
+                        id: e.oid,
+                        num_visits: 0,
+                    })
+                    .collect();
+                entries.sort_by_key(|e| e.pack_offset);
+                Ok(Tree::from_offsets_in_pack(
+                    fixture_path(PACK_FOR_INDEX_V2),
+                    entries.into_iter(),
+                    |e| e.pack_offset,
+                    |id| idx.lookup(id).map(|index| idx.pack_offset_at_index(index)),
+                    progress::Discard,
+                    &AtomicBool::new(false),
+                    git_hash::Kind::Sha1,
+                )?)
+            }
+
+            #[test]
+            fn subtrees_larger_than_the_chunk_size_are_split_into_shards() -> Result<(), Box<dyn std::error::Error>> {
+                let idx = pack::index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+                let mut tree = tree(&idx)?;
+                let num_roots = tree.root_items.len();
+                for (chunk_size, expect_shards) in [(4, true), (idx.num_objects() as usize, false)] {
+                    let units = tree.iter_units(chunk_size);
+                    let (mut roots, mut shards) = (0, 0);
+                    for unit in units {
+                        match unit {
+                            Unit::Roots(chunk) => roots += chunk.count(),
+                            Unit::Shard(shard) => {
+                                assert!(!shard.bases.is_empty(), "shards always have a root as base");
+                                shards += 1
+                            }
+                        }
+                    }
+                    assert_eq!(roots, num_roots, "each root is part of exactly one chunk");
+                    assert_eq!(shards, tree.is_shard.iter().filter(|s| **s).count());
+                    assert_eq!(shards != 0, expect_shards);
+                }
+                Ok(())
+            }
+
+            fn objects_per_unit(units: Vec<Unit<'_, ()>>) -> Vec<usize> {
+                units
+                    .into_iter()
+                    .map(|unit| {
+                        let mut nodes: Vec<_> = match unit {
+                            Unit::Roots(chunk) => chunk.collect(),
+                            Unit::Shard(shard) => vec![shard.node],
+                        };
+                        let mut num_objects = 0;
+                        while let Some(node) = nodes.pop() {
+                            num_objects += 1;
+                            nodes.extend(node.into_child_iter());
+                        }
+                        num_objects
+                    })
+                    .collect()
+            }
+
+            #[test]
+            fn a_deep_chain_of_deltas_is_never_split() -> Result<(), Box<dyn std::error::Error>> {
+                let mut tree = Tree::with_capacity(10_000)?;
+                tree.add_root(0, ())?;
+                for offset in 1..10_000 {
+                    tree.add_child(offset - 1, offset, ())?;
+                }
+                assert_eq!(
+                    objects_per_unit(tree.iter_units(10)),
+                    vec![10_000],
+                    "chains can only be resolved one delta after another, so splitting them wouldn't help"
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn chains_are_split_at_branch_points_only() -> Result<(), Box<dyn std::error::Error>> {
+                let mut tree = Tree::with_capacity(151)?;
+                tree.add_root(0, ())?;
+                for offset in 1..=50 {
+                    tree.add_child(offset - 1, offset, ())?;
+                }
+                for (first, last) in [(51, 100), (101, 150)] {
+                    tree.add_child(50, first, ())?;
+                    for offset in first + 1..=last {
+                        tree.add_child(offset - 1, offset, ())?;
+                    }
+                }
+
+                let units = tree.iter_units(10);
+                let shards: Vec<_> = units
+                    .iter()
+                    .filter_map(|unit| match unit {
+                        Unit::Shard(shard) => Some((shard.node.offset(), shard.bases.len())),
+                        Unit::Roots(_) => None,
+                    })
+                    .collect();
+                assert_eq!(
+                    shards,
+                    vec![(101, 51), (51, 51)],
+                    "both branches start a shard whose bases are the root and the chain leading up to the branch point"
+                );
+                assert_eq!(objects_per_unit(units), vec![50, 50, 51]);
+                Ok(())
+            }
+
+            #[test]
+            fn all_objects_are_resolved_exactly_once_with_any_chunk_size() -> Result<(), Box<dyn std::error::Error>> {
+                let idx = pack::index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+                let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+                for chunk_size in [Some(1), Some(2), Some(3), Some(5), None] {
+                    let traverse::Outcome { roots, children } = tree(&idx)?.traverse(
+                        || true,
+                        |slice, out| pack.entry_slice(slice).map(|entry| out.copy_from_slice(entry)),
+                        pack.pack_end() as u64,
+                        || (),
+                        |data,
+                         _progress,
+                         traverse::Context {
+                             entry, decompressed, ..
+                         }| {
+                            let kind = entry.header.as_kind().expect("delta objects are resolved");
+                            git_object::Data::new(kind, decompressed)
+                                .verify_checksum(data.id)
+                                .expect("the object is resolved correctly");
+                            data.num_visits += 1;
+                            Ok::<_, std::io::Error>(())
+                        },
+                        traverse::Options {
+                            object_progress: progress::Discard,
+                            size_progress: progress::Discard,
+                            thread_limit: Some(4),
+                            chunk_size,
+                            should_interrupt: &AtomicBool::new(false),
+                            object_hash: git_hash::Kind::Sha1,
+                        },
+                    )?;
+                    assert_eq!(roots.len() + children.len(), idx.num_objects() as usize);
+                    assert!(
+                        roots
+                            .iter()
+                            .chain(children.iter())
+                            .all(|item| item.data.num_visits == 1),
+                        "chunk size {:?}: every object is inspected once, even if its subtree was split",
+                        chunk_size
+                    );
+                }
+                Ok(())
+            }
+        }
     }
 
     struct TreeItem<D> {
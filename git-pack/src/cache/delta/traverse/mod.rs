@@ -8,7 +8,7 @@ use git_features::{
 };
 
 use crate::{
-    cache::delta::{Item, Tree},
+    cache::delta::{iter::Unit, Chunk, Item, Tree},
     data::EntryRange,
 };
 
@@ -62,6 +62,13 @@ pub struct Options<'a, P1, P2> {
     /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
     /// the amount of available logical cores.
     pub thread_limit: Option<usize>,
+    /// If `Some`, the amount of objects to resolve in one unit of work when running in parallel, or the amount of bases
+    /// otherwise. If `None`, it will be selected based on the amount of objects and threads.
+    ///
+    /// When running in parallel, bases with more objects than that in their subtree are split into shards at branch points
+    /// to be resolved by multiple threads, which comes at the cost of resolving the bases of each shard once more.
+    /// Chains of deltas without branches are never split.
+    pub chunk_size: Option<usize>,
     /// Abort the operation if the value is `true`.
     pub should_interrupt: &'a AtomicBool,
     /// specifies what kind of hashes we expect to be stored in oid-delta entries, which is viable to decoding them
@@ -107,6 +114,7 @@ where
         inspect_object: MBFN,
         Options {
             thread_limit,
+            chunk_size,
             object_progress,
             size_progress,
             should_interrupt,
@@ -121,16 +129,23 @@ where
         E: std::error::Error + Send + Sync + 'static,
     {
         self.set_pack_entries_end_and_resolve_ref_offsets(pack_entries_end)?;
-        let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(1, None, thread_limit, None);
+        let num_objects = self.num_items();
+        let (default_chunk_size, thread_limit, _) =
+            parallel::optimize_chunk_size_and_thread_limit(50, Some(num_objects), thread_limit, None);
         let object_progress = OwnShared::new(Mutable::new(object_progress));
 
-        // TODO: this could be faster using the `in_parallel_with_slice()` as it will a root item per thread,
-        //       allowing threads to be more busy overall. This, however, needs some refactorings to allow operation
-        //       on a single item efficiently while providing real-time feedback.
-        let num_objects = self.num_items();
+        let chunk_size = chunk_size.unwrap_or(default_chunk_size);
+        let run_in_parallel = parallel::num_threads(thread_limit) > 1 && should_run_in_parallel();
+        let units = if run_in_parallel {
+            self.iter_units(chunk_size)
+        } else {
+            // Splitting the subtrees of bases into shards only pays off if other threads can pick them up.
+            let chunks: Vec<Chunk<'_, T>> = self.iter_root_chunks(chunk_size).collect();
+            chunks.into_iter().map(Unit::Roots).collect()
+        };
         in_parallel_if(
-            should_run_in_parallel,
-            self.iter_root_chunks(chunk_size),
+            || run_in_parallel,
+            units.into_iter(),
             thread_limit,
             {
                 let object_progress = object_progress.clone();
@@ -144,7 +159,7 @@ where
                     )
                 }
             },
-            move |root_nodes, state| resolve::deltas(root_nodes, state, object_hash.len_in_bytes()),
+            move |unit, state| resolve::deltas(unit, state, object_hash.len_in_bytes()),
             Reducer::new(num_objects, object_progress, size_progress, should_interrupt),
         )?;
         Ok(Outcome {
@@ -6,12 +6,16 @@ use git_features::{
 };
 
 use crate::{
-    cache::delta::traverse::{Context, Error},
+    cache::delta::{
+        iter::{Shard, Unit},
+        traverse::{Context, Error},
+        Node,
+    },
     data::EntryRange,
 };
 
 pub(crate) fn deltas<T, F, P, MBFN, S, E>(
-    nodes: crate::cache::delta::Chunk<'_, T>,
+    unit: Unit<'_, T>,
     (bytes_buf, ref mut progress, state, resolve, modify_base): &mut (Vec<u8>, P, S, F, MBFN),
     hash_len: usize,
 ) -> Result<(usize, u64), Error>
@@ -36,6 +40,30 @@ where
         let decompressed_len = entry.decompressed_size as usize;
         Ok((entry, slice.end, decompress_all_at_once(compressed, decompressed_len)?))
     };
+    let resolve_delta_from_resolver = |base_entry: &crate::data::Entry,
+                                       base_bytes: &[u8],
+                                       slice: EntryRange|
+     -> Result<(crate::data::Entry, u64, Vec<u8>), Error> {
+        let (mut child_entry, entry_end, delta_bytes) = decompress_from_resolver(slice)?;
+        let (base_size, consumed) = crate::data::delta::decode_header_size(&delta_bytes);
+        let mut header_ofs = consumed;
+        assert_eq!(
+            base_bytes.len(),
+            base_size as usize,
+            "recorded base size in delta does not match"
+        );
+        let (result_size, consumed) = crate::data::delta::decode_header_size(&delta_bytes[consumed..]);
+        header_ofs += consumed;
+
+        let mut fully_resolved_delta_bytes = bytes_buf.borrow_mut();
+        fully_resolved_delta_bytes.resize(result_size as usize, 0);
+        crate::data::delta::apply(base_bytes, &mut fully_resolved_delta_bytes, &delta_bytes[header_ofs..]);
+
+        // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
+        // at all
+        child_entry.header = base_entry.header;
+        Ok((child_entry, entry_end, fully_resolved_delta_bytes.to_owned()))
+    };
 
     // Traverse the tree breadth first and loose the data produced for the base as it won't be needed anymore.
     progress.init(
@@ -49,7 +77,24 @@ where
     // each node is a base, and its children always start out as deltas which become a base after applying them.
     // These will be pushed onto our stack until all are processed
     let root_level = 0;
-    let mut nodes: Vec<_> = nodes.into_iter().map(|n| (root_level, n)).collect();
+    let mut nodes: Vec<(u16, Node<'_, T>)> = match unit {
+        Unit::Roots(roots) => roots.into_iter().map(|n| (root_level, n)).collect(),
+        Unit::Shard(Shard { node, bases }) => {
+            // Resolve the bases of the shard once more to obtain the base of its first node, without inspecting them
+            // as they are handled by the unit they belong to.
+            let level = bases.len() as u16;
+            let mut bases = bases.into_iter();
+            let mut base = decompress_from_resolver(bases.next().expect("a shard always has a root as base"))?;
+            for slice in bases {
+                base = resolve_delta_from_resolver(&base.0, &base.2, slice)?;
+            }
+            decompressed_bytes_by_pack_offset.insert(
+                node.offset(),
+                resolve_delta_from_resolver(&base.0, &base.2, node.entry_slice())?,
+            );
+            vec![(level, node)]
+        }
+    };
     while let Some((level, mut base)) = nodes.pop() {
         let (base_entry, entry_end, base_bytes) = if level == root_level {
             decompress_from_resolver(base.entry_slice())?
@@ -75,27 +120,9 @@ where
         decompressed_bytes += base_bytes.len() as u64;
         progress.inc();
         for child in base.into_child_iter() {
-            let (mut child_entry, entry_end, delta_bytes) = decompress_from_resolver(child.entry_slice())?;
-            let (base_size, consumed) = crate::data::delta::decode_header_size(&delta_bytes);
-            let mut header_ofs = consumed;
-            assert_eq!(
-                base_bytes.len(),
-                base_size as usize,
-                "recorded base size in delta does not match"
-            );
-            let (result_size, consumed) = crate::data::delta::decode_header_size(&delta_bytes[consumed..]);
-            header_ofs += consumed;
-
-            let mut fully_resolved_delta_bytes = bytes_buf.borrow_mut();
-            fully_resolved_delta_bytes.resize(result_size as usize, 0);
-            crate::data::delta::apply(&base_bytes, &mut fully_resolved_delta_bytes, &delta_bytes[header_ofs..]);
-
-            // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
-            // at all
-            child_entry.header = base_entry.header;
             decompressed_bytes_by_pack_offset.insert(
                 child.offset(),
-                (child_entry, entry_end, fully_resolved_delta_bytes.to_owned()),
+                resolve_delta_from_resolver(&base_entry, &base_bytes, child.entry_slice())?,
             );
             nodes.push((level + 1, child));
         }
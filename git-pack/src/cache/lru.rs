@@ -7,6 +7,7 @@ mod memory {
     use clru::WeightScale;
 
     use super::DecodeEntry;
+    use crate::cache::Statistics;
 
     struct Entry {
         data: Vec<u8>,
@@ -23,10 +24,46 @@ mod memory {
         }
     }
 
+    /// A policy to grow or shrink the memory cap of a [`MemoryCappedHashmap`] depending on its observed hit rate,
+    /// for use with [`MemoryCappedHashmap::with_adaptive_memory_cap()`].
+    ///
+    /// The hit rate is evaluated after every [`lookups_per_adjustment`][AdaptiveMemoryCap::lookups_per_adjustment] lookups,
+    /// and the memory cap is halved or doubled within the bounds of this policy.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct AdaptiveMemoryCap {
+        /// The memory cap will never be lowered below this amount of bytes.
+        pub min_memory_cap_in_bytes: usize,
+        /// The memory cap will never be raised above this amount of bytes.
+        pub max_memory_cap_in_bytes: usize,
+        /// The amount of lookups after which the hit rate is evaluated.
+        pub lookups_per_adjustment: usize,
+        /// If the hit rate is below this ratio and entries had to be evicted in the meantime, the memory cap is doubled
+        /// as more memory is likely to turn misses into hits.
+        pub grow_below_hit_rate: f32,
+        /// If the hit rate is below this ratio, the memory cap is halved as the access pattern doesn't benefit from caching.
+        /// This takes precedence over growing the memory cap.
+        pub shrink_below_hit_rate: f32,
+    }
+
+    impl Default for AdaptiveMemoryCap {
+        fn default() -> Self {
+            AdaptiveMemoryCap {
+                min_memory_cap_in_bytes: 1024 * 1024,
+                max_memory_cap_in_bytes: 256 * 1024 * 1024,
+                lookups_per_adjustment: 1000,
+                grow_below_hit_rate: 0.8,
+                shrink_below_hit_rate: 0.1,
+            }
+        }
+    }
+
     /// An LRU cache with hash map backing and an eviction rule based on the memory usage for object data in bytes.
     pub struct MemoryCappedHashmap {
         inner: clru::CLruCache<Key, Entry, std::collections::hash_map::RandomState, CustomScale>,
         free_list: Vec<Vec<u8>>,
+        stats: Statistics,
+        /// The policy to adjust our memory cap with, along with the statistics at the time of the last adjustment.
+        adaptive: Option<(AdaptiveMemoryCap, Statistics)>,
         debug: git_features::cache::Debug,
     }
 
@@ -40,15 +77,64 @@ mod memory {
                         .with_scale(CustomScale),
                 ),
                 free_list: Vec::new(),
+                stats: Statistics::default(),
+                adaptive: None,
                 debug: git_features::cache::Debug::new(format!("MemoryCappedHashmap({}B)", memory_cap_in_bytes)),
             }
         }
+
+        /// Return a new instance which starts out with `memory_cap_in_bytes`, clamped to the bounds of `policy`,
+        /// and adjusts it according to `policy` while it's used.
+        pub fn with_adaptive_memory_cap(memory_cap_in_bytes: usize, policy: AdaptiveMemoryCap) -> MemoryCappedHashmap {
+            let min = policy.min_memory_cap_in_bytes.max(1);
+            let mut cache = Self::new(memory_cap_in_bytes.min(policy.max_memory_cap_in_bytes).max(min));
+            cache.adaptive = Some((policy, Statistics::default()));
+            cache
+        }
+
+        /// Return the amount of bytes of object data we may currently hold before evicting entries.
+        pub fn memory_cap_in_bytes(&self) -> usize {
+            self.inner.capacity()
+        }
+
+        fn adjust_memory_cap(&mut self) {
+            let (policy, last) = match self.adaptive.as_mut() {
+                Some((policy, last)) => (*policy, last),
+                None => return,
+            };
+            let window = self.stats - *last;
+            if window.hits + window.misses < policy.lookups_per_adjustment {
+                return;
+            }
+            let hit_rate = window.hit_ratio().unwrap_or(1.0);
+            let cap = self.inner.capacity();
+            let new_cap = if hit_rate < policy.shrink_below_hit_rate {
+                (cap / 2).max(policy.min_memory_cap_in_bytes).max(1)
+            } else if hit_rate < policy.grow_below_hit_rate && window.evictions != 0 {
+                cap.saturating_mul(2).min(policy.max_memory_cap_in_bytes).max(1)
+            } else {
+                cap
+            };
+            if new_cap < cap {
+                self.stats.shrunk += 1;
+            } else if new_cap > cap {
+                self.stats.grown += 1;
+            }
+            if new_cap != cap {
+                let len_before = self.inner.len();
+                self.inner.resize(NonZeroUsize::new(new_cap).expect("non zero"));
+                self.stats.evictions += len_before - self.inner.len();
+            }
+            *last = self.stats;
+        }
     }
 
     impl DecodeEntry for MemoryCappedHashmap {
         fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
             self.debug.put();
-            if let Ok(Some(previous_entry)) = self.inner.put_with_weight(
+            self.stats.puts += 1;
+            let len_before = self.inner.len();
+            let res = self.inner.put_with_weight(
                 (pack_id, offset),
                 Entry {
                     data: self
@@ -64,9 +150,16 @@ mod memory {
                     kind,
                     compressed_size,
                 },
-            ) {
-                self.free_list.push(previous_entry.data)
-            }
+            );
+            let len_without_evictions = match res {
+                Ok(Some(previous_entry)) => {
+                    self.free_list.push(previous_entry.data);
+                    len_before
+                }
+                Ok(None) => len_before + 1,
+                Err(_) => len_before,
+            };
+            self.stats.evictions += len_without_evictions.saturating_sub(self.inner.len());
         }
 
         fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
@@ -76,21 +169,29 @@ mod memory {
                 (e.kind, e.compressed_size)
             });
             if res.is_some() {
-                self.debug.hit()
+                self.debug.hit();
+                self.stats.hits += 1;
             } else {
-                self.debug.miss()
+                self.debug.miss();
+                self.stats.misses += 1;
             }
+            self.adjust_memory_cap();
             res
         }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.stats)
+        }
     }
 }
 
 #[cfg(feature = "pack-cache-lru-dynamic")]
-pub use memory::MemoryCappedHashmap;
+pub use memory::{AdaptiveMemoryCap, MemoryCappedHashmap};
 
 #[cfg(feature = "pack-cache-lru-static")]
 mod _static {
     use super::DecodeEntry;
+    use crate::cache::Statistics;
     struct Entry {
         pack_id: u32,
         offset: u64,
@@ -105,6 +206,7 @@ mod _static {
     pub struct StaticLinkedList<const SIZE: usize> {
         inner: uluru::LRUCache<Entry, SIZE>,
         free_list: Vec<Vec<u8>>,
+        stats: Statistics,
         debug: git_features::cache::Debug,
     }
 
@@ -113,6 +215,7 @@ mod _static {
             StaticLinkedList {
                 inner: Default::default(),
                 free_list: Vec::new(),
+                stats: Statistics::default(),
                 debug: git_features::cache::Debug::new(format!("StaticLinkedList<{}>", SIZE)),
             }
         }
@@ -121,6 +224,7 @@ mod _static {
     impl<const SIZE: usize> DecodeEntry for StaticLinkedList<SIZE> {
         fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
             self.debug.put();
+            self.stats.puts += 1;
             if let Some(previous) = self.inner.insert(Entry {
                 offset,
                 pack_id,
@@ -137,6 +241,7 @@ mod _static {
                 kind,
                 compressed_size,
             }) {
+                self.stats.evictions += 1;
                 self.free_list.push(previous.data)
             }
         }
@@ -152,12 +257,18 @@ mod _static {
                 }
             });
             if res.is_some() {
-                self.debug.hit()
+                self.debug.hit();
+                self.stats.hits += 1;
             } else {
-                self.debug.miss()
+                self.debug.miss();
+                self.stats.misses += 1;
             }
             res
         }
+
+        fn statistics(&self) -> Option<Statistics> {
+            Some(self.stats)
+        }
     }
 }
 
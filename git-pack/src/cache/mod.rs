@@ -1,4 +1,4 @@
-use std::ops::DerefMut;
+use std::ops::{Deref, DerefMut};
 
 use git_object::Kind;
 
@@ -13,6 +13,65 @@ pub trait DecodeEntry {
     /// Attempt to fetch the object at `offset` and store its decoded bytes in `out`, as previously stored with [`DecodeEntry::put()`], and return
     /// its (object `kind`, `decompressed_size`)
     fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)>;
+    /// Return statistics about the usage of this cache so far, or `None` if the implementation doesn't collect them.
+    fn statistics(&self) -> Option<Statistics> {
+        None
+    }
+}
+
+/// Statistics about the usage of a [`DecodeEntry`] cache, as returned by [`DecodeEntry::statistics()`].
+///
+/// They accumulate over the lifetime of a cache and can be summed up across caches.
+#[derive(Default, Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statistics {
+    /// The amount of times an entry was found in the cache.
+    pub hits: usize,
+    /// The amount of times an entry wasn't found in the cache.
+    pub misses: usize,
+    /// The amount of entries put into the cache.
+    pub puts: usize,
+    /// The amount of entries that were removed from the cache to make room for new ones.
+    pub evictions: usize,
+    /// The amount of times the capacity of the cache was increased by its adaptive sizing policy.
+    pub grown: usize,
+    /// The amount of times the capacity of the cache was decreased by its adaptive sizing policy.
+    pub shrunk: usize,
+}
+
+impl Statistics {
+    /// Return the ratio of hits to all lookups, or `None` if there was no lookup yet.
+    pub fn hit_ratio(&self) -> Option<f32> {
+        let lookups = self.hits + self.misses;
+        (lookups != 0).then(|| self.hits as f32 / lookups as f32)
+    }
+}
+
+impl std::ops::AddAssign for Statistics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.hits += rhs.hits;
+        self.misses += rhs.misses;
+        self.puts += rhs.puts;
+        self.evictions += rhs.evictions;
+        self.grown += rhs.grown;
+        self.shrunk += rhs.shrunk;
+    }
+}
+
+impl std::ops::Sub for Statistics {
+    type Output = Statistics;
+
+    /// Return the usage between the `rhs` snapshot and this later snapshot of the same cache.
+    fn sub(self, rhs: Self) -> Self::Output {
+        Statistics {
+            hits: self.hits - rhs.hits,
+            misses: self.misses - rhs.misses,
+            puts: self.puts - rhs.puts,
+            evictions: self.evictions - rhs.evictions,
+            grown: self.grown - rhs.grown,
+            shrunk: self.shrunk - rhs.shrunk,
+        }
+    }
 }
 
 /// A cache that stores nothing and retrieves nothing, thus it _never_ caches.
@@ -34,6 +93,10 @@ impl<T: DecodeEntry + ?Sized> DecodeEntry for Box<T> {
     fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(Kind, usize)> {
         self.deref_mut().get(pack_id, offset, out)
     }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.deref().statistics()
+    }
 }
 
 /// A way of storing and retrieving entire objects to and from a cache.
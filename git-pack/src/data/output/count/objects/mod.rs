@@ -54,6 +54,7 @@ where
     Oid: Into<ObjectId> + Send,
     IterErr: std::error::Error + Send,
 {
+    let _span = git_features::span!("git_pack::data::output::count::objects");
     let lower_bound = objects_ids.size_hint().0;
     let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(
         chunk_size,
@@ -118,6 +119,7 @@ where
     Oid: Into<ObjectId>,
     IterErr: std::error::Error,
 {
+    let _span = git_features::span!("git_pack::data::output::count::objects_unthreaded");
     let seen_objs = RefCell::new(HashedSet::<ObjectId>::default());
 
     let (mut buf1, mut buf2) = (Vec::new(), Vec::new());
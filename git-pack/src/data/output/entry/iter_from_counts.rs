@@ -43,6 +43,7 @@ pub fn iter_from_counts<Find>(
         allow_thin_pack,
         thread_limit,
         chunk_size,
+        compression_level,
     }: Options,
 ) -> impl Iterator<Item = Result<(SequenceId, Vec<output::Entry>), Error<Find::Error>>>
        + parallel::reduce::Finalize<Reduce = reduce::Statistics<Error<Find::Error>>>
@@ -201,7 +202,7 @@ where
                                 None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
                                     Some((obj, _location)) => {
                                         stats.decoded_and_recompressed_objects += 1;
-                                        output::Entry::from_data(count, &obj)
+                                        output::Entry::from_data(count, &obj, compression_level)
                                     }
                                     None => {
                                         stats.missing_objects += 1;
@@ -213,7 +214,7 @@ where
                         None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
                             Some((obj, _location)) => {
                                 stats.decoded_and_recompressed_objects += 1;
-                                output::Entry::from_data(count, &obj)
+                                output::Entry::from_data(count, &obj, compression_level)
                             }
                             None => {
                                 stats.missing_objects += 1;
@@ -371,6 +372,9 @@ mod types {
         pub chunk_size: usize,
         /// The pack data version to produce for each entry
         pub version: crate::data::Version,
+        /// The zlib compression level for objects that have to be compressed, from 0 for no compression to 9 for the best
+        /// compression, similar to `pack.compression`. Objects copied from existing packs are not recompressed.
+        pub compression_level: u32,
     }
 
     impl Default for Options {
@@ -381,6 +385,7 @@ mod types {
                 allow_thin_pack: false,
                 chunk_size: 10,
                 version: Default::default(),
+                compression_level: git_features::zlib::stream::deflate::DEFAULT_LEVEL,
             }
         }
     }
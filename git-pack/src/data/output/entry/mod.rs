@@ -126,14 +126,15 @@ impl output::Entry {
         })
     }
 
-    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data.
-    pub fn from_data(count: &output::Count, obj: &git_object::Data<'_>) -> Result<Self, Error> {
+    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data, compressed with the zlib
+    /// `compression_level` from 0 for no compression to 9 for the best compression.
+    pub fn from_data(count: &output::Count, obj: &git_object::Data<'_>, compression_level: u32) -> Result<Self, Error> {
         Ok(output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
             compressed_data: {
-                let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+                let mut out = git_features::zlib::stream::deflate::Write::new_with_level(Vec::new(), compression_level);
                 if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
                     match err.kind() {
                         std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
@@ -39,3 +39,6 @@ pub mod entry;
 
 ///
 pub mod bytes;
+
+/// Write packs of commit ranges for sending them to another repository.
+pub mod range;
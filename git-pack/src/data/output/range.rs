@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use git_features::{
+    parallel::{reduce::Finalize, InOrderIter},
+    progress::Progress,
+};
+use git_hash::ObjectId;
+use git_object::{CommitRefIter, TagRefIter};
+
+use crate::{
+    data::output,
+    data::output::{count, entry},
+    find, FindExt,
+};
+
+/// Configuration options for [`write()`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The amount of threads to use at most when creating pack entries. If `None`, all logical cores are used.
+    pub thread_limit: Option<usize>,
+    /// The amount of objects per chunk or unit of work to be sent to threads for processing.
+    pub chunk_size: usize,
+    /// The zlib compression level for objects that have to be compressed, from 0 for no compression to 9 for the best
+    /// compression, similar to `pack.compression`. Objects copied from existing packs are not recompressed.
+    pub compression_level: u32,
+    /// The kind of hash to use for the pack checksum.
+    pub object_hash: git_hash::Kind,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            thread_limit: None,
+            chunk_size: 1000,
+            compression_level: git_features::zlib::stream::deflate::DEFAULT_LEVEL,
+            object_hash: git_hash::Kind::Sha1,
+        }
+    }
+}
+
+/// The outcome of [`write()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The amount of objects in the pack.
+    pub num_objects: u32,
+    /// The amount of bytes written, including the header and the trailing checksum.
+    pub bytes_written: u64,
+    /// The checksum of the pack, which is also its trailer.
+    pub pack_hash: ObjectId,
+    /// Information about counting the objects to send.
+    pub counts: count::objects::Outcome,
+    /// Information about creating the pack entries.
+    pub entries: entry::iter_from_counts::Outcome,
+}
+
+/// The error returned by [`write()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<FindErr>
+where
+    FindErr: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    FindExisting(#[from] find::existing::Error<FindErr>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error("The wanted object {id} is a {kind}, but only commits and tags pointing to commits can be sent")]
+    UnsupportedWant { id: ObjectId, kind: git_object::Kind },
+    #[error(transparent)]
+    Count(#[from] count::objects::Error<find::existing::Error<FindErr>, git_traverse::commit::ancestors::Error>),
+    #[error(transparent)]
+    Entries(#[from] entry::iter_from_counts::Error<FindErr>),
+    #[error(transparent)]
+    Write(#[from] output::bytes::Error<entry::iter_from_counts::Error<FindErr>>),
+    #[error("Operation interrupted")]
+    Interrupted,
+}
+
+/// Write a pack with all objects reachable from `wants` but not from `haves` to `out`, streaming it while it is created,
+/// which is what both `git upload-pack` and `git send-pack` need to send packs to the other side.
+///
+/// * `db` - the object store to read objects from, whose packed objects are copied as is, including deltas whose base is part of the pack.
+/// * `wants` - commits or annotated tags pointing to commits, the latter of which are sent as well.
+/// * `haves` - commits or annotated tags pointing to commits the receiver has, which are assumed to be present in `db`.
+///   Neither these nor their ancestors are sent.
+/// * `progress` - a way to learn about the progress of counting objects, creating entries and writing them.
+/// * `should_interrupt` - a flag that is set to true if the operation should stop.
+pub fn write<Find, W>(
+    db: Find,
+    wants: impl IntoIterator<Item = impl Into<ObjectId>>,
+    haves: impl IntoIterator<Item = impl Into<ObjectId>>,
+    out: W,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    Options {
+        thread_limit,
+        chunk_size,
+        compression_level,
+        object_hash,
+    }: Options,
+) -> Result<Outcome, Error<Find::Error>>
+where
+    Find: crate::Find + Send + Clone + 'static,
+    <Find as crate::Find>::Error: Send + Sync,
+    W: std::io::Write,
+{
+    let mut buf = Vec::new();
+    let mut tips = Vec::new();
+    let mut tags = Vec::new();
+    for want in wants {
+        let want = want.into();
+        match peel_to_commit(&db, want, &mut buf, &mut tags)? {
+            Ok(commit) => tips.push(commit),
+            Err(kind) => return Err(Error::UnsupportedWant { id: want, kind }),
+        }
+    }
+    let mut hidden = Vec::new();
+    for have in haves {
+        if let Ok(commit) = peel_to_commit(&db, have.into(), &mut buf, &mut Vec::new())? {
+            hidden.push(commit);
+        }
+    }
+
+    let mut state = git_traverse::commit::ancestors::State::default();
+    let (mut counts, counts_outcome) = {
+        let commits = git_traverse::commit::Ancestors::new(tips, &mut state, |oid, buf| {
+            db.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .with_hidden(hidden);
+        let mut progress = progress.add_child("counting");
+        progress.init(None, git_features::progress::count("objects"));
+        count::objects_unthreaded(
+            db.clone(),
+            commits.chain(tags.into_iter().map(Ok)),
+            progress,
+            should_interrupt,
+            count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+        )?
+    };
+    // Commits at the boundary are parents the receiver has, which are counted along with their trees only to find the
+    // objects that changed.
+    let mut boundary_objects = state.boundary().to_vec();
+    for commit in state.boundary() {
+        let (object, _location) = db.find(commit, &mut buf)?;
+        boundary_objects.push(CommitRefIter::from_bytes(object.data).tree_id()?);
+    }
+    counts.retain(|count| !boundary_objects.contains(&count.id));
+
+    let num_objects = counts.len() as u32;
+    let mut entries = InOrderIter::from(entry::iter_from_counts(
+        counts,
+        db,
+        progress.add_child("creating entries"),
+        entry::iter_from_counts::Options {
+            thread_limit,
+            mode: entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+            allow_thin_pack: false,
+            chunk_size,
+            version: Default::default(),
+            compression_level,
+        },
+    ));
+
+    let mut write_progress = progress.add_child("writing");
+    write_progress.init(None, git_features::progress::bytes());
+    let mut pack = output::bytes::FromEntriesIter::new(
+        entries.by_ref(),
+        out,
+        num_objects,
+        crate::data::Version::default(),
+        object_hash,
+    );
+    let mut bytes_written = 0;
+    for written in pack.by_ref() {
+        let written = written?;
+        bytes_written += written;
+        write_progress.inc_by(written as usize);
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+    }
+    let pack_hash = pack.digest().expect("iteration is done");
+    Ok(Outcome {
+        num_objects,
+        bytes_written,
+        pack_hash,
+        counts: counts_outcome,
+        entries: entries.inner.finalize()?,
+    })
+}
+
+/// Peel `id` to the commit it points to, collecting all tags on the way in `tags`, or return the kind of object it
+/// points to if it's not a commit.
+fn peel_to_commit<Find>(
+    db: &Find,
+    mut id: ObjectId,
+    buf: &mut Vec<u8>,
+    tags: &mut Vec<ObjectId>,
+) -> Result<Result<ObjectId, git_object::Kind>, Error<Find::Error>>
+where
+    Find: crate::Find,
+{
+    loop {
+        let (object, _location) = db.find(id, buf)?;
+        match object.kind {
+            git_object::Kind::Commit => return Ok(Ok(id)),
+            git_object::Kind::Tag => {
+                tags.push(id);
+                id = TagRefIter::from_bytes(object.data).target_id()?;
+            }
+            kind => return Ok(Err(kind)),
+        }
+    }
+}
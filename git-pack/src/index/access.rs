@@ -173,6 +173,39 @@ impl index::File {
         None
     }
 
+    /// Return the amount of hex characters a prefix of `id` needs to have to not match any other object in this index,
+    /// which is the length of the shortest unambiguous abbreviation of `id` if only objects of this index are considered.
+    ///
+    /// `id` doesn't have to be contained in this index.
+    // NOTE: pretty much the same things as in `multi_index::File::shortest_unambiguous_hex_len`, change things there
+    //       as well.
+    pub fn shortest_unambiguous_hex_len(&self, id: impl AsRef<git_hash::oid>) -> usize {
+        let id = id.as_ref();
+        let mut lower_bound = 0;
+        let mut upper_bound = self.num_objects;
+        while lower_bound < upper_bound {
+            let mid = (lower_bound + upper_bound) / 2;
+            if self.oid_at_index(mid) < id {
+                lower_bound = mid + 1;
+            } else {
+                upper_bound = mid;
+            }
+        }
+
+        let previous = lower_bound.checked_sub(1);
+        let mut next = lower_bound;
+        if next < self.num_objects && self.oid_at_index(next) == id {
+            next += 1;
+        }
+        let next = (next < self.num_objects).then(|| next);
+        previous
+            .into_iter()
+            .chain(next)
+            .map(|neighbour| id.common_hex_len(self.oid_at_index(neighbour)) + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
     /// An iterator over all [`Entries`][Entry] of this index file.
     pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry> + 'a> {
         match self.version {
@@ -137,6 +137,10 @@ impl File {
     pub fn object_hash(&self) -> git_hash::Kind {
         self.object_hash
     }
+    /// The length of all mapped data, including the header and the trailer
+    pub fn data_len(&self) -> usize {
+        self.data.len()
+    }
 }
 
 const V2_SIGNATURE: &[u8] = b"\xfftOc";
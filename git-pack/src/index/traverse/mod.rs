@@ -25,6 +25,12 @@ pub struct Options<F> {
     /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
     /// the amount of available logical cores.
     pub thread_limit: Option<usize>,
+    /// If `Some`, the amount of objects to handle in one unit of work. Otherwise, it will be selected based on the amount
+    /// of objects and threads.
+    ///
+    /// Note that with [`Algorithm::DeltaTreeLookup`], bases with more objects than that in their subtree are split up
+    /// at branch points so that multiple threads can resolve them.
+    pub chunk_size: Option<usize>,
     /// The kinds of safety checks to perform.
     pub check: SafetyCheck,
     /// A function to create a pack cache
@@ -37,6 +43,7 @@ impl Default for Options<fn() -> crate::cache::Never> {
             check: Default::default(),
             traversal: Default::default(),
             thread_limit: None,
+            chunk_size: None,
             make_pack_lookup_cache: || crate::cache::Never,
         }
     }
@@ -72,7 +79,7 @@ impl index::File {
     ///   decoding objects.
     ///   One could also call [`traverse_with_lookup()`][index::File::traverse_with_lookup()] directly.
     ///
-    /// Use [`thread_limit`][Options::thread_limit] to further control parallelism and [`check`][SafetyCheck] to define how much the passed
+    /// Use [`thread_limit`][Options::thread_limit] and [`chunk_size`][Options::chunk_size] to further control parallelism and [`check`][SafetyCheck] to define how much the passed
     /// objects shall be verified beforehand.
     pub fn traverse<P, C, Processor, E, F>(
         &self,
@@ -83,6 +90,7 @@ impl index::File {
         Options {
             traversal,
             thread_limit,
+            chunk_size,
             check,
             make_pack_lookup_cache,
         }: Options<F>,
@@ -99,6 +107,7 @@ impl index::File {
         ) -> Result<(), E>,
         F: Fn() -> C + Send + Clone,
     {
+        let _span = git_features::span!("git_pack::index::traverse", objects = self.num_objects());
         match traversal {
             Algorithm::Lookup => self.traverse_with_lookup(
                 new_processor,
@@ -107,6 +116,7 @@ impl index::File {
                 should_interrupt,
                 with_lookup::Options {
                     thread_limit,
+                    chunk_size,
                     check,
                     make_pack_lookup_cache,
                 },
@@ -116,7 +126,11 @@ impl index::File {
                 new_processor,
                 progress,
                 should_interrupt,
-                crate::index::traverse::with_index::Options { check, thread_limit },
+                crate::index::traverse::with_index::Options {
+                    check,
+                    thread_limit,
+                    chunk_size,
+                },
             ),
         }
     }
@@ -132,19 +146,23 @@ impl index::File {
     where
         E: std::error::Error + Send + Sync + 'static,
     {
-        Ok(if check.file_checksum() {
+        Ok(if check.index_checksum() {
             if self.pack_checksum() != pack.checksum() {
                 return Err(Error::PackMismatch {
                     actual: pack.checksum(),
                     expected: self.pack_checksum(),
                 });
             }
-            let (pack_res, id) = parallel::join(
-                move || pack.verify_checksum(pack_progress, should_interrupt),
-                move || self.verify_checksum(index_progress, should_interrupt),
-            );
-            pack_res?;
-            id?
+            if check.file_checksum() {
+                let (pack_res, id) = parallel::join(
+                    move || pack.verify_checksum(pack_progress, should_interrupt),
+                    move || self.verify_checksum(index_progress, should_interrupt),
+                );
+                pack_res?;
+                id?
+            } else {
+                self.verify_checksum(index_progress, should_interrupt)?
+            }
         } else {
             self.index_checksum()
         })
@@ -214,7 +232,7 @@ where
     P: Progress,
     E: std::error::Error + Send + Sync + 'static,
 {
-    if check.object_checksum() {
+    if check.object_checksum(&index_entry.oid) {
         let mut hasher = git_features::hash::hasher(index_entry.oid.kind());
         hasher.update(&git_object::encode::loose_header(object_kind, decompressed.len()));
         hasher.update(decompressed);
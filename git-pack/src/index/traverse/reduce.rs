@@ -68,13 +68,13 @@ where
     P: Progress,
     E: std::error::Error + Send + Sync + 'static,
 {
-    type Input = Result<Vec<data::decode_entry::Outcome>, traverse::Error<E>>;
+    type Input = Result<(Vec<data::decode_entry::Outcome>, Option<crate::cache::Statistics>), traverse::Error<E>>;
     type FeedProduce = ();
     type Output = traverse::Statistics;
     type Error = traverse::Error<E>;
 
     fn feed(&mut self, input: Self::Input) -> Result<(), Self::Error> {
-        let chunk_stats: Vec<_> = match input {
+        let (chunk_stats, cache_stats) = match input {
             Err(err @ traverse::Error::PackDecode { .. }) if !self.check.fatal_decode_error() => {
                 lock(&self.progress).info(format!("Ignoring decode error: {}", err));
                 return Ok(());
@@ -82,6 +82,9 @@ where
             res => res,
         }?;
         self.entries_seen += chunk_stats.len();
+        if let Some(cache_stats) = cache_stats {
+            *self.stats.pack_cache.get_or_insert_with(Default::default) += cache_stats;
+        }
 
         let chunk_total = chunk_stats.into_iter().fold(
             data::decode_entry::Outcome::default_from_kind(git_object::Kind::Tree),
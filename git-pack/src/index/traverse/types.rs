@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, num::NonZeroU32};
 
 /// Statistics regarding object encountered during execution of the [`traverse()`][crate::index::File::traverse()] method.
 #[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
@@ -27,6 +27,11 @@ pub struct Statistics {
     pub num_tags: u32,
     /// The amount of objects encountered that where blobs
     pub num_blobs: u32,
+    /// The usage of the pack caches of all threads combined, if these collect [statistics][crate::cache::DecodeEntry::statistics()].
+    ///
+    /// It's only set when traversing with [`Algorithm::Lookup`][crate::index::traverse::Algorithm::Lookup] as the
+    /// delta tree traversal doesn't need a pack cache.
+    pub pack_cache: Option<crate::cache::Statistics>,
 }
 
 impl Default for Statistics {
@@ -42,6 +47,7 @@ impl Default for Statistics {
             num_commits: 0,
             num_trees: 0,
             num_tags: 0,
+            pack_cache: None,
         }
     }
 }
@@ -61,6 +67,15 @@ pub enum SafetyCheck {
     /// Useful if there is a damaged pack and you would like to traverse as many objects as possible.
     SkipFileAndObjectChecksumVerificationAndNoAbortOnDecodeError,
 
+    /// Only verify the checksum of the index file but not the one of the much larger pack file, and verify the object
+    /// checksums of about every `every_nth` object only, selected by their id.
+    ///
+    /// Useful for routine checks of very large packs which would take too long to verify entirely.
+    SampleObjectChecksumVerification {
+        /// Verify about one in this amount of objects, with `1` verifying all of them.
+        every_nth: NonZeroU32,
+    },
+
     /// Perform all available safety checks before operating on the pack and
     /// abort if any of them fails
     All,
@@ -70,13 +85,29 @@ impl SafetyCheck {
     pub(crate) fn file_checksum(&self) -> bool {
         matches!(self, SafetyCheck::All)
     }
-    pub(crate) fn object_checksum(&self) -> bool {
-        matches!(self, SafetyCheck::All | SafetyCheck::SkipFileChecksumVerification)
+    pub(crate) fn index_checksum(&self) -> bool {
+        matches!(
+            self,
+            SafetyCheck::All | SafetyCheck::SampleObjectChecksumVerification { .. }
+        )
+    }
+    pub(crate) fn object_checksum(&self, id: &git_hash::oid) -> bool {
+        match self {
+            SafetyCheck::All | SafetyCheck::SkipFileChecksumVerification => true,
+            SafetyCheck::SampleObjectChecksumVerification { every_nth } => {
+                let mut prefix = [0u8; 4];
+                prefix.copy_from_slice(&id.as_bytes()[..4]);
+                u32::from_be_bytes(prefix) % every_nth.get() == 0
+            }
+            SafetyCheck::SkipFileAndObjectChecksumVerification
+            | SafetyCheck::SkipFileAndObjectChecksumVerificationAndNoAbortOnDecodeError => false,
+        }
     }
     pub(crate) fn fatal_decode_error(&self) -> bool {
         match self {
             SafetyCheck::All
             | SafetyCheck::SkipFileChecksumVerification
+            | SafetyCheck::SampleObjectChecksumVerification { .. }
             | SafetyCheck::SkipFileAndObjectChecksumVerification => true,
             SafetyCheck::SkipFileAndObjectChecksumVerificationAndNoAbortOnDecodeError => false,
         }
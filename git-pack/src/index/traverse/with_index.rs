@@ -14,6 +14,10 @@ pub struct Options {
     /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
     /// the amount of available logical cores.
     pub thread_limit: Option<usize>,
+    /// If `Some`, the amount of objects to resolve in one unit of work, see
+    /// [`chunk_size`][crate::cache::delta::traverse::Options::chunk_size] for details.
+    /// Otherwise, it will be selected based on the amount of objects and threads.
+    pub chunk_size: Option<usize>,
     /// The kinds of safety checks to perform.
     pub check: crate::index::traverse::SafetyCheck,
 }
@@ -30,7 +34,11 @@ impl index::File {
         new_processor: impl Fn() -> Processor + Send + Clone,
         mut progress: P,
         should_interrupt: &AtomicBool,
-        Options { check, thread_limit }: Options,
+        Options {
+            check,
+            thread_limit,
+            chunk_size,
+        }: Options,
     ) -> Result<Outcome<P>, Error<E>>
     where
         P: Progress,
@@ -122,6 +130,7 @@ impl index::File {
                         object_progress: progress.add_child("Resolving"),
                         size_progress: progress.add_child("Decoding"),
                         thread_limit,
+                        chunk_size,
                         should_interrupt,
                         object_hash: self.object_hash,
                     },
@@ -1,7 +1,7 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use git_features::{
-    parallel::{self, in_parallel_if},
+    parallel::{self, in_parallel_ordered_bounded_if},
     progress::{self, unit, Progress},
 };
 
@@ -13,6 +13,9 @@ pub struct Options<F> {
     /// If `Some`, only use the given amount of threads. Otherwise, the amount of threads to use will be selected based on
     /// the amount of available logical cores.
     pub thread_limit: Option<usize>,
+    /// If `Some`, the amount of objects to decode in one unit of work. Otherwise, it will be selected based on the amount
+    /// of objects and threads.
+    pub chunk_size: Option<usize>,
     /// The kinds of safety checks to perform.
     pub check: crate::index::traverse::SafetyCheck,
     /// A function to create a pack cache
@@ -24,6 +27,7 @@ impl Default for Options<fn() -> crate::cache::Never> {
         Options {
             check: Default::default(),
             thread_limit: None,
+            chunk_size: None,
             make_pack_lookup_cache: || crate::cache::Never,
         }
     }
@@ -47,6 +51,7 @@ impl index::File {
         should_interrupt: &AtomicBool,
         Options {
             thread_limit,
+            chunk_size,
             check,
             make_pack_lookup_cache,
         }: Options<F>,
@@ -85,10 +90,11 @@ impl index::File {
                 let index_entries =
                     util::index_entries_sorted_by_offset_ascending(self, progress.add_child("collecting sorted index"));
 
-                let (chunk_size, thread_limit, available_cores) =
+                let (default_chunk_size, thread_limit, available_cores) =
                     parallel::optimize_chunk_size_and_thread_limit(1000, Some(index_entries.len()), thread_limit, None);
+                let chunk_size = chunk_size.unwrap_or(default_chunk_size).max(1);
                 let there_are_enough_entries_to_process = || index_entries.len() > chunk_size * available_cores;
-                let input_chunks = index_entries.chunks(chunk_size.max(chunk_size));
+                let input_chunks = index_entries.chunks(chunk_size);
                 let reduce_progress = OwnShared::new(Mutable::new({
                     let mut p = progress.add_child("Traversing");
                     p.init(Some(self.num_objects() as usize), progress::count("objects"));
@@ -106,14 +112,15 @@ impl index::File {
                     }
                 };
 
-                in_parallel_if(
+                in_parallel_ordered_bounded_if(
                     there_are_enough_entries_to_process,
                     input_chunks,
                     thread_limit,
+                    available_cores * 2,
                     state_per_thread,
                     |entries: &[index::Entry],
                      (cache, ref mut processor, buf, progress)|
-                     -> Result<Vec<data::decode_entry::Outcome>, Error<_>> {
+                     -> Result<(Vec<data::decode_entry::Outcome>, Option<crate::cache::Statistics>), Error<_>> {
                         progress.init(
                             Some(entries.len()),
                             Some(unit::dynamic(unit::Human::new(
@@ -122,6 +129,7 @@ impl index::File {
                             ))),
                         );
                         let mut stats = Vec::with_capacity(entries.len());
+                        let cache_stats_before = cache.statistics();
                         progress.set(0);
                         for index_entry in entries.iter() {
                             let result = self.decode_and_process_entry(
@@ -143,7 +151,10 @@ impl index::File {
                             }?;
                             stats.push(stat);
                         }
-                        Ok(stats)
+                        let cache_stats = cache
+                            .statistics()
+                            .map(|after| after - cache_stats_before.unwrap_or_default());
+                        Ok((stats, cache_stats))
                     },
                     Reducer::from_progress(reduce_progress, pack.data_len(), check, should_interrupt),
                 )
@@ -1,4 +1,4 @@
-use std::sync::atomic::AtomicBool;
+use std::{num::NonZeroU32, sync::atomic::AtomicBool};
 
 use git_features::progress::Progress;
 use git_object::{bstr::ByteSlice, WriteTo};
@@ -84,6 +84,37 @@ pub enum Mode {
     /// Validate hash and CRC32, and decode and encode each non-Blob object.
     /// Each object should yield exactly the same hash when re-encoded.
     HashCrc32DecodeEncode,
+    /// Validate hash and CRC32 of a sample of objects only, and skip computing the checksum of the pack data file.
+    ///
+    /// This is useful for routine checks of very large packs which would take too long to verify entirely.
+    /// Note that all objects are still decoded.
+    HashCrc32Sampled(Sample),
+}
+
+/// The objects to verify in [`Mode::HashCrc32Sampled`].
+///
+/// Objects are selected by their id, which spreads them evenly across the pack and selects the same objects in each run.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Sample {
+    /// Verify about every `n`-th object, with `1` verifying all of them.
+    EveryNth(NonZeroU32),
+    /// Verify as many objects as it takes to hash about the given amount of bytes of pack data.
+    Bytes(u64),
+}
+
+impl Sample {
+    /// Return `n` so that verifying about every `n`-th object of a pack with `pack_data_len` bytes
+    /// satisfies this sample.
+    pub fn every_nth(&self, pack_data_len: u64) -> NonZeroU32 {
+        match self {
+            Sample::EveryNth(n) => *n,
+            Sample::Bytes(bytes) => {
+                let bytes = (*bytes).max(1);
+                let n = pack_data_len.saturating_add(bytes - 1) / bytes;
+                NonZeroU32::new(n.min(u32::MAX as u64) as u32).unwrap_or_else(|| NonZeroU32::new(1).expect("non-zero"))
+            }
+        }
+    }
 }
 
 impl Default for Mode {
@@ -195,7 +226,17 @@ impl index::File {
                     index::traverse::Options {
                         traversal,
                         thread_limit,
-                        check: index::traverse::SafetyCheck::All,
+                        chunk_size: None,
+                        check: match verify_mode {
+                            Mode::HashCrc32Sampled(sample) => {
+                                index::traverse::SafetyCheck::SampleObjectChecksumVerification {
+                                    every_nth: sample.every_nth(pack.data_len() as u64),
+                                }
+                            }
+                            Mode::HashCrc32 | Mode::HashCrc32Decode | Mode::HashCrc32DecodeEncode => {
+                                index::traverse::SafetyCheck::All
+                            }
+                        },
                         make_pack_lookup_cache,
                     },
                 )
@@ -62,6 +62,7 @@ impl crate::index::File {
         F: FnOnce() -> io::Result<F2>,
         F2: for<'r> Fn(crate::data::EntryRange, &'r mut Vec<u8>) -> Option<()> + Send + Clone,
     {
+        let _span = git_features::span!("git_pack::index::write");
         if kind != crate::index::Version::default() {
             return Err(Error::Unsupported(kind));
         }
@@ -175,6 +176,7 @@ impl crate::index::File {
                     object_progress: root_progress.add_child("Resolving"),
                     size_progress: root_progress.add_child("Decoding"),
                     thread_limit,
+                    chunk_size: None,
                     should_interrupt,
                     object_hash,
                 },
@@ -206,6 +208,7 @@ impl crate::index::File {
             progress::count("objects").expect("unit always set"),
             progress::MessageLevel::Success,
         );
+        git_features::count!(objects = num_objects);
         Ok(Outcome {
             index_kind: kind,
             index_hash,
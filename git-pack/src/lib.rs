@@ -10,6 +10,9 @@
 //! Objects returned by `.find(…)` are [objects][git_object::Data] which know their pack location in order to speed up
 //! various common operations like creating new packs from existing ones.
 //!
+//! Not to be confused with the former, [bundle files][bundle_file::File] as created by `git bundle` contain references
+//! along with a pack to transfer objects without a connection to a remote.
+//!
 //! When traversing all objects in a pack, a _delta tree acceleration structure_ can be built from pack data or an index
 //! in order to decompress packs in parallel and without any waste.
 //! ## Feature Flags
@@ -28,6 +31,12 @@ pub struct Bundle {
     pub index: crate::index::File,
 }
 
+///
+pub mod bundle_file;
+
+///
+pub mod bitmap;
+
 ///
 pub mod find;
 
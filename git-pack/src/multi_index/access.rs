@@ -47,6 +47,10 @@ impl File {
     pub fn object_hash(&self) -> git_hash::Kind {
         self.object_hash
     }
+    /// Returns the length of all mapped data, including the header and the trailer.
+    pub fn data_len(&self) -> usize {
+        self.data.len()
+    }
     /// Returns the checksum over the entire content of the file (excluding the checksum itself).
     ///
     /// It can be used to validate it didn't change after creation.
@@ -105,6 +109,39 @@ impl File {
         None
     }
 
+    /// Return the amount of hex characters a prefix of `id` needs to have to not match any other object in this index,
+    /// which is the length of the shortest unambiguous abbreviation of `id` if only objects of this index are considered.
+    ///
+    /// `id` doesn't have to be contained in this index.
+    // NOTE: pretty much the same things as in `index::File::shortest_unambiguous_hex_len`, change things there
+    //       as well.
+    pub fn shortest_unambiguous_hex_len(&self, id: impl AsRef<git_hash::oid>) -> usize {
+        let id = id.as_ref();
+        let mut lower_bound = 0;
+        let mut upper_bound = self.num_objects;
+        while lower_bound < upper_bound {
+            let mid = (lower_bound + upper_bound) / 2;
+            if self.oid_at_index(mid) < id {
+                lower_bound = mid + 1;
+            } else {
+                upper_bound = mid;
+            }
+        }
+
+        let previous = lower_bound.checked_sub(1);
+        let mut next = lower_bound;
+        if next < self.num_objects && self.oid_at_index(next) == id {
+            next += 1;
+        }
+        let next = (next < self.num_objects).then(|| next);
+        previous
+            .into_iter()
+            .chain(next)
+            .map(|neighbour| id.common_hex_len(self.oid_at_index(neighbour)) + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
     /// Find the index ranging from 0 to [File::num_objects()] that belongs to data associated with `id`, or `None` if it wasn't found.
     ///
     /// Use this index for finding additional information via [`File::pack_id_and_pack_offset_at_index()`].
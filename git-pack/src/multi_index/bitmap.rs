@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+
+use crate::multi_index::{EntryIndex, File, PackIndex};
+
+mod error {
+    /// The error returned by [File::pseudo_pack_order()][super::File::pseudo_pack_order()] and [File::bitmap()][super::File::bitmap()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read reverse index at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(
+            "The multi-index has no pseudo-pack order as neither the RIDX chunk nor a reverse index file is present"
+        )]
+        MissingPseudoPackOrder,
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("The bitmap belongs to multi-index {actual}, but we are {expected}")]
+        ChecksumMismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+        #[error(transparent)]
+        Bitmap(#[from] crate::bitmap::init::Error),
+    }
+}
+
+pub use error::Error;
+
+const REVERSE_INDEX_SIGNATURE: &[u8] = b"RIDX";
+
+/// Bitmap support
+impl File {
+    /// Return the path at which a bitmap for this multi-index is expected, which is named after our [checksum][File::checksum()].
+    pub fn bitmap_path(&self) -> PathBuf {
+        self.path
+            .with_file_name(format!("multi-pack-index-{}.bitmap", self.checksum()))
+    }
+
+    /// Return the path at which a reverse index file with our pseudo-pack order is expected if we don't have one built in.
+    pub fn reverse_index_path(&self) -> PathBuf {
+        self.path
+            .with_file_name(format!("multi-pack-index-{}.rev", self.checksum()))
+    }
+
+    /// Open the bitmap belonging to this multi-index, or return `None` if there is none.
+    ///
+    /// Positions of the bits within the bitmaps refer to the [pseudo-pack order][File::pseudo_pack_order()].
+    pub fn bitmap(&self) -> Result<Option<crate::bitmap::File>, Error> {
+        let path = self.bitmap_path();
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let bitmap = crate::bitmap::File::at(path, self.object_hash)?;
+        let expected = self.checksum();
+        if bitmap.checksum() != expected {
+            return Err(Error::ChecksumMismatch {
+                expected,
+                actual: bitmap.checksum().to_owned(),
+            });
+        }
+        Ok(Some(bitmap))
+    }
+
+    /// Return all entry indices in pseudo-pack order, which is the order in which they would appear if all our packs
+    /// were concatenated, starting with the preferred pack, and with each object appearing only in the pack it's
+    /// assigned to. The position of an entry index in the returned list is its position in the pseudo-pack.
+    ///
+    /// The order is read from the `RIDX` chunk if present, or from the [reverse index file][File::reverse_index_path()].
+    pub fn pseudo_pack_order(&self) -> Result<Vec<EntryIndex>, Error> {
+        let num_objects = self.num_objects as usize;
+        let positions = match self.reverse_index_ofs {
+            Some(ofs) => &self.data[ofs..][..num_objects * 4],
+            None => return self.pseudo_pack_order_from_reverse_index_file(),
+        };
+        decode_positions(positions, self.num_objects)
+    }
+
+    /// Return the pack which is the first in the pseudo-pack order, or `None` if there are no objects or the order is unknown.
+    pub fn preferred_pack(&self) -> Option<PackIndex> {
+        let ofs = self.reverse_index_ofs?;
+        (self.num_objects != 0)
+            .then(|| crate::read_u32(&self.data[ofs..][..4]))
+            .filter(|entry_index| *entry_index < self.num_objects)
+            .map(|entry_index| self.pack_id_and_pack_offset_at_index(entry_index).0)
+    }
+
+    /// Compute the pseudo-pack order with `preferred_pack` placed first, followed by all other packs in order,
+    /// with the objects of each pack ordered by their offset.
+    ///
+    /// This is useful when writing bitmaps or reverse indices, while readers should use [`pseudo_pack_order()`][File::pseudo_pack_order()]
+    /// as only the writer of the multi-index knows which pack is preferred.
+    pub fn compute_pseudo_pack_order(&self, preferred_pack: PackIndex) -> Vec<EntryIndex> {
+        let mut order: Vec<_> = (0..self.num_objects).collect();
+        order.sort_by_cached_key(|entry_index| {
+            let (pack_index, pack_offset) = self.pack_id_and_pack_offset_at_index(*entry_index);
+            (pack_index != preferred_pack, pack_index, pack_offset)
+        });
+        order
+    }
+
+    fn pseudo_pack_order_from_reverse_index_file(&self) -> Result<Vec<EntryIndex>, Error> {
+        let path = self.reverse_index_path();
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(Error::MissingPseudoPackOrder),
+            Err(source) => return Err(Error::Io { source, path }),
+        };
+
+        let header_len = REVERSE_INDEX_SIGNATURE.len() + 4 /* version */ + 4 /* hash kind */;
+        let hash_len = self.hash_len;
+        if data.len() != header_len + self.num_objects as usize * 4 + hash_len * 2 {
+            return Err(Error::Corrupt {
+                message: "reverse index doesn't have the expected size",
+            });
+        }
+        if &data[..REVERSE_INDEX_SIGNATURE.len()] != REVERSE_INDEX_SIGNATURE {
+            return Err(Error::Corrupt {
+                message: "reverse index has an invalid signature",
+            });
+        }
+        if crate::read_u32(&data[4..][..4]) != 1 {
+            return Err(Error::Corrupt {
+                message: "reverse index has an unsupported version",
+            });
+        }
+        let trailer = &data[data.len() - hash_len * 2..];
+        if git_hash::ObjectId::from(&trailer[..hash_len]) != self.checksum() {
+            return Err(Error::Corrupt {
+                message: "reverse index belongs to a different multi-index",
+            });
+        }
+        decode_positions(&data[header_len..data.len() - hash_len * 2], self.num_objects)
+    }
+}
+
+fn decode_positions(positions: &[u8], num_objects: u32) -> Result<Vec<EntryIndex>, Error> {
+    positions
+        .chunks(4)
+        .map(|entry_index| {
+            let entry_index = crate::read_u32(entry_index);
+            if entry_index < num_objects {
+                Ok(entry_index)
+            } else {
+                Err(Error::Corrupt {
+                    message: "pseudo-pack order refers to an entry which doesn't exist",
+                })
+            }
+        })
+        .collect()
+}
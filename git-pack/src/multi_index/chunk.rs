@@ -274,3 +274,16 @@ pub mod large_offsets {
         8 * large_offsets as u64
     }
 }
+
+/// Information about the reverse index, which stores the pseudo-pack order of all objects.
+pub mod reverse_index {
+    use std::ops::Range;
+
+    /// The id uniquely identifying the reverse index, mapping positions in the pseudo-pack order to entry indices.
+    pub const ID: git_chunk::Id = *b"RIDX";
+
+    /// Returns true if the `offset` range seems to match the size required for `num_objects`.
+    pub fn is_valid(offset: &Range<usize>, num_objects: u32) -> bool {
+        offset.end - offset.start == num_objects as usize * 4
+    }
+}
@@ -39,6 +39,7 @@ pub struct File {
     lookup_ofs: usize,
     offsets_ofs: usize,
     large_offsets_ofs: Option<usize>,
+    reverse_index_ofs: Option<usize>,
 }
 
 ///
@@ -55,3 +56,6 @@ pub mod chunk;
 
 ///
 pub mod init;
+
+///
+pub mod bitmap;
@@ -1,6 +1,6 @@
 use std::{
     convert::TryInto,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::atomic::{AtomicBool, Ordering},
     time::{Instant, SystemTime},
 };
@@ -10,7 +10,8 @@ use git_features::progress::Progress;
 use crate::multi_index;
 
 mod error {
-    /// The error returned by [multi_index::File::write_from_index_paths()][super::multi_index::File::write_from_index_paths()]..
+    /// The error returned by [multi_index::File::write_from_index_paths()][super::multi_index::File::write_from_index_paths()]
+    /// and [multi_index::File::write_with_additional_index_paths()][super::multi_index::File::write_with_additional_index_paths()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
@@ -20,6 +21,11 @@ mod error {
         Interrupted,
         #[error(transparent)]
         OpenIndex(#[from] crate::index::init::Error),
+        #[error("The multi-index uses object hash {actual}, but {expected} was requested")]
+        ObjectHashMismatch {
+            expected: git_hash::Kind,
+            actual: git_hash::Kind,
+        },
     }
 }
 pub use error::Error;
@@ -33,13 +39,15 @@ pub(crate) struct Entry {
     index_mtime: SystemTime,
 }
 
-/// Options for use in [`multi_index::File::write_from_index_paths()`].
+/// Options for use in [`multi_index::File::write_from_index_paths()`] and
+/// [`multi_index::File::write_with_additional_index_paths()`].
 pub struct Options {
     /// The kind of hash to use for objects and to expect in the input files.
     pub object_hash: git_hash::Kind,
 }
 
-/// The result of [`multi_index::File::write_from_index_paths()`].
+/// The result of [`multi_index::File::write_from_index_paths()`] and
+/// [`multi_index::File::write_with_additional_index_paths()`].
 pub struct Outcome<P> {
     /// The calculated multi-index checksum of the file at `multi_index_path`.
     pub multi_index_checksum: git_hash::ObjectId,
@@ -69,7 +77,6 @@ impl multi_index::File {
     where
         P: Progress,
     {
-        let out = git_features::hash::Write::new(out, object_hash);
         let (index_paths_sorted, index_filenames_sorted) = {
             index_paths.sort();
             let file_names = index_paths
@@ -87,19 +94,7 @@ impl multi_index::File {
 
             // This could be parallelized… but it's probably not worth it unless you have 500mio objects.
             for (index_id, index) in index_paths_sorted.iter().enumerate() {
-                let mtime = index
-                    .metadata()
-                    .and_then(|m| m.modified())
-                    .unwrap_or(SystemTime::UNIX_EPOCH);
-                let index = crate::index::File::at(index, object_hash)?;
-
-                entries.reserve(index.num_objects() as usize);
-                entries.extend(index.iter().map(|e| Entry {
-                    id: e.oid,
-                    pack_index: index_id as u32,
-                    pack_offset: e.pack_offset,
-                    index_mtime: mtime,
-                }));
+                Self::collect_index_entries(index, index_id as u32, object_hash, &mut entries)?;
                 progress.inc();
                 if should_interrupt.load(Ordering::Relaxed) {
                     return Err(Error::Interrupted);
@@ -107,27 +102,191 @@ impl multi_index::File {
             }
             progress.show_throughput(start);
 
-            let start = Instant::now();
-            progress.set_name("Deduplicate");
-            progress.init(Some(entries.len()), git_features::progress::count("entries"));
-            entries.sort_by(|l, r| {
-                l.id.cmp(&r.id)
-                    .then_with(|| l.index_mtime.cmp(&r.index_mtime).reverse())
-                    .then_with(|| l.pack_index.cmp(&r.pack_index))
+            Self::sort_and_deduplicate(&mut entries, &mut progress);
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            entries
+        };
+
+        Self::write_entries(
+            &index_filenames_sorted,
+            &entries,
+            out,
+            progress,
+            should_interrupt,
+            object_hash,
+        )
+    }
+
+    /// Write a new multi-index file to `out` which contains all entries of this multi-index along with the ones of
+    /// the pack index files at `index_paths`.
+    ///
+    /// Only the pack index files that are new are read, while the entries of all packs already known to us are
+    /// taken from this file, which makes adding a few small packs to a multi-index with many objects much cheaper
+    /// than [rebuilding it from all index files][multi_index::File::write_from_index_paths()].
+    /// Index files whose names are already contained in this multi-index are ignored.
+    ///
+    /// Note that `out` must not be the file this multi-index was loaded from, as it is still used while writing.
+    /// Instead, write to a lock file and move it into place once done.
+    ///
+    /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
+    pub fn write_with_additional_index_paths<P>(
+        &self,
+        index_paths: Vec<PathBuf>,
+        out: impl std::io::Write,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+        Options { object_hash }: Options,
+    ) -> Result<Outcome<P>, Error>
+    where
+        P: Progress,
+    {
+        if object_hash != self.object_hash {
+            return Err(Error::ObjectHashMismatch {
+                expected: object_hash,
+                actual: self.object_hash,
             });
-            entries.dedup_by_key(|e| e.id);
-            progress.inc_by(entries.len());
+        }
+        let existing_names = self.index_names();
+        let new_index_paths = {
+            let mut paths: Vec<_> = index_paths
+                .into_iter()
+                .filter(|p| {
+                    let name = Path::new(p.file_name().expect("file name present"));
+                    existing_names.binary_search_by(|n| n.as_path().cmp(name)).is_err()
+                })
+                .collect();
+            paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+            paths.dedup_by(|a, b| a.file_name() == b.file_name());
+            paths
+        };
+        let index_filenames_sorted = {
+            let mut names = existing_names.to_vec();
+            names.extend(
+                new_index_paths
+                    .iter()
+                    .map(|p| PathBuf::from(p.file_name().expect("file name present"))),
+            );
+            names.sort();
+            names
+        };
+        let pack_index_of = |name: &Path| -> u32 {
+            index_filenames_sorted
+                .binary_search_by(|n| n.as_path().cmp(name))
+                .expect("all names are present") as u32
+        };
+
+        let entries = {
+            let mut entries = Vec::with_capacity(self.num_objects() as usize);
+            let start = Instant::now();
+            let mut progress = progress.add_child("Collecting entries");
+            progress.init(
+                Some(1 + new_index_paths.len()),
+                git_features::progress::count("indices"),
+            );
+
+            let index_dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+            let (existing_pack_indices, existing_mtimes): (Vec<_>, Vec<_>) = existing_names
+                .iter()
+                .map(|name| {
+                    let mtime = index_dir
+                        .join(name)
+                        .metadata()
+                        .and_then(|m| m.modified())
+                        .unwrap_or(SystemTime::UNIX_EPOCH);
+                    (pack_index_of(name), mtime)
+                })
+                .unzip();
+            entries.extend(self.iter().map(|e| Entry {
+                id: e.oid,
+                pack_index: existing_pack_indices[e.pack_index as usize],
+                pack_offset: e.pack_offset,
+                index_mtime: existing_mtimes[e.pack_index as usize],
+            }));
+            progress.inc();
+
+            for index in &new_index_paths {
+                let pack_index = pack_index_of(Path::new(index.file_name().expect("file name present")));
+                Self::collect_index_entries(index, pack_index, object_hash, &mut entries)?;
+                progress.inc();
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
             progress.show_throughput(start);
+
+            // Our own entries are already sorted and form a single run, so sorting costs are dominated by the new entries.
+            Self::sort_and_deduplicate(&mut entries, &mut progress);
             if should_interrupt.load(Ordering::Relaxed) {
                 return Err(Error::Interrupted);
             }
             entries
         };
 
+        Self::write_entries(
+            &index_filenames_sorted,
+            &entries,
+            out,
+            progress,
+            should_interrupt,
+            object_hash,
+        )
+    }
+
+    fn collect_index_entries(
+        index_path: &Path,
+        pack_index: u32,
+        object_hash: git_hash::Kind,
+        entries: &mut Vec<Entry>,
+    ) -> Result<(), Error> {
+        let mtime = index_path
+            .metadata()
+            .and_then(|m| m.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let index = crate::index::File::at(index_path, object_hash)?;
+
+        entries.reserve(index.num_objects() as usize);
+        entries.extend(index.iter().map(|e| Entry {
+            id: e.oid,
+            pack_index,
+            pack_offset: e.pack_offset,
+            index_mtime: mtime,
+        }));
+        Ok(())
+    }
+
+    /// Sort `entries` by id and keep only one entry per object, preferring the ones from the most recent index.
+    fn sort_and_deduplicate(entries: &mut Vec<Entry>, progress: &mut impl Progress) {
+        let start = Instant::now();
+        progress.set_name("Deduplicate");
+        progress.init(Some(entries.len()), git_features::progress::count("entries"));
+        entries.sort_by(|l, r| {
+            l.id.cmp(&r.id)
+                .then_with(|| l.index_mtime.cmp(&r.index_mtime).reverse())
+                .then_with(|| l.pack_index.cmp(&r.pack_index))
+        });
+        entries.dedup_by_key(|e| e.id);
+        progress.inc_by(entries.len());
+        progress.show_throughput(start);
+    }
+
+    fn write_entries<P>(
+        index_filenames_sorted: &[PathBuf],
+        entries: &[Entry],
+        out: impl std::io::Write,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+        object_hash: git_hash::Kind,
+    ) -> Result<Outcome<P>, Error>
+    where
+        P: Progress,
+    {
+        let out = git_features::hash::Write::new(out, object_hash);
         let mut cf = git_chunk::file::Index::for_writing();
         cf.plan_chunk(
             multi_index::chunk::index_names::ID,
-            multi_index::chunk::index_names::storage_size(&index_filenames_sorted),
+            multi_index::chunk::index_names::storage_size(index_filenames_sorted),
         );
         cf.plan_chunk(multi_index::chunk::fanout::ID, multi_index::chunk::fanout::SIZE as u64);
         cf.plan_chunk(
@@ -139,7 +298,7 @@ impl multi_index::File {
             multi_index::chunk::offsets::storage_size(entries.len()),
         );
 
-        let num_large_offsets = multi_index::chunk::large_offsets::num_large_offsets(&entries);
+        let num_large_offsets = multi_index::chunk::large_offsets::num_large_offsets(entries);
         if let Some(num_large_offsets) = num_large_offsets {
             cf.plan_chunk(
                 multi_index::chunk::large_offsets::ID,
@@ -161,7 +320,7 @@ impl multi_index::File {
         let bytes_written = Self::write_header(
             &mut out,
             cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
-            index_paths_sorted.len() as u32,
+            index_filenames_sorted.len() as u32,
             object_hash,
         )?;
 
@@ -173,15 +332,15 @@ impl multi_index::File {
             while let Some(chunk_to_write) = chunk_write.next_chunk() {
                 match chunk_to_write {
                     multi_index::chunk::index_names::ID => {
-                        multi_index::chunk::index_names::write(&index_filenames_sorted, &mut chunk_write)?
+                        multi_index::chunk::index_names::write(index_filenames_sorted, &mut chunk_write)?
                     }
-                    multi_index::chunk::fanout::ID => multi_index::chunk::fanout::write(&entries, &mut chunk_write)?,
-                    multi_index::chunk::lookup::ID => multi_index::chunk::lookup::write(&entries, &mut chunk_write)?,
+                    multi_index::chunk::fanout::ID => multi_index::chunk::fanout::write(entries, &mut chunk_write)?,
+                    multi_index::chunk::lookup::ID => multi_index::chunk::lookup::write(entries, &mut chunk_write)?,
                     multi_index::chunk::offsets::ID => {
-                        multi_index::chunk::offsets::write(&entries, num_large_offsets.is_some(), &mut chunk_write)?
+                        multi_index::chunk::offsets::write(entries, num_large_offsets.is_some(), &mut chunk_write)?
                     }
                     multi_index::chunk::large_offsets::ID => multi_index::chunk::large_offsets::write(
-                        &entries,
+                        entries,
                         num_large_offsets.expect("available if planned"),
                         &mut chunk_write,
                     )?,
@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+for round in $(seq 60); do
+  echo "$round" > "file$(( round % 7 ))"
+  git add .
+  git commit -qm "$round"
+  if [ $(( round % 20 )) = 0 ]; then
+    git repack -q
+  fi
+done
+git tag -m "a tag object" tagged
+
+git repack -q
+git multi-pack-index write --bitmap
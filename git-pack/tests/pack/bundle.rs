@@ -75,6 +75,75 @@ mod locate {
     }
 }
 
+mod inspect_entries {
+    use git_object::Kind;
+    use git_odb::pack;
+
+    use crate::{
+        fixture_path, hex_to_id,
+        pack::{PACKS_AND_INDICES, SMALL_PACK_INDEX},
+    };
+
+    #[test]
+    fn entries_cover_the_whole_pack_in_order() -> Result<(), Box<dyn std::error::Error>> {
+        for (index_path, _data_path) in PACKS_AND_INDICES {
+            let bundle = pack::Bundle::at(fixture_path(index_path), git_hash::Kind::Sha1)?;
+            let entries = bundle.inspect_entries().collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(entries.len(), bundle.index.num_objects() as usize);
+
+            let mut expected_offset = 12;
+            for entry in &entries {
+                assert_eq!(entry.pack_offset, expected_offset, "entries are contiguous");
+                expected_offset += entry.entry_size();
+                assert!(entry.object_kind.is_some(), "all bases are contained in the pack");
+                assert_eq!(entry.header.is_delta(), entry.chain_length != 0);
+            }
+            assert_eq!(expected_offset, bundle.pack.pack_end() as u64);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn entries_match_git_verify_pack() -> Result<(), Box<dyn std::error::Error>> {
+        let bundle = pack::Bundle::at(fixture_path(SMALL_PACK_INDEX), git_hash::Kind::Sha1)?;
+        let entries = bundle.inspect_entries().collect::<Result<Vec<_>, _>>()?;
+
+        let base = &entries[0];
+        assert_eq!(base.id, hex_to_id("779c5451ba9fe210ffd1f55db202e55f51acecac"));
+        assert_eq!(base.object_kind, Some(Kind::Commit));
+        assert_eq!(
+            (base.decompressed_size, base.entry_size(), base.pack_offset),
+            (223, 149, 12)
+        );
+        assert_eq!(base.base, None);
+
+        let delta = entries
+            .iter()
+            .find(|e| e.id == hex_to_id("b8aa61be84b78d7fcff788e8d844406cc97132bf"))
+            .expect("present");
+        assert_eq!(delta.object_kind, Some(Kind::Blob));
+        assert_eq!(
+            (delta.decompressed_size, delta.entry_size(), delta.pack_offset),
+            (6, 16, 3033)
+        );
+        assert_eq!(delta.chain_length, 2);
+        assert_eq!(
+            delta.base,
+            Some(pack::bundle::inspect::DeltaBase {
+                id: hex_to_id("e0dc2c78d3bd3d14d20906ad7905a32f0f0918a8"),
+                pack_offset: Some(3009),
+            })
+        );
+
+        let chain_lengths = entries.iter().fold([0; 3], |mut acc, e| {
+            acc[e.chain_length as usize] += 1;
+            acc
+        });
+        assert_eq!(chain_lengths, [30, 6, 6]);
+        Ok(())
+    }
+}
+
 mod write_to_directory {
     use std::{fs, path::Path, sync::atomic::AtomicBool};
 
@@ -0,0 +1,222 @@
+use git_odb::pack::bundle_file::{decode, Header, Prerequisite, Ref, Version};
+
+use crate::hex_to_id;
+
+fn header(version: Version) -> Header {
+    Header {
+        version,
+        object_hash: git_hash::Kind::Sha1,
+        filter: (version == Version::V3).then(|| "blob:none".into()),
+        prerequisites: vec![
+            Prerequisite {
+                id: hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                comment: "a commit subject".into(),
+            },
+            Prerequisite {
+                id: hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+                comment: "".into(),
+            },
+        ],
+        refs: vec![
+            Ref {
+                id: hex_to_id("0f3ea84cd1bba10c2a03d736a460635082833e59"),
+                name: "refs/heads/main".into(),
+            },
+            Ref {
+                id: hex_to_id("0f3ea84cd1bba10c2a03d736a460635082833e59"),
+                name: "HEAD".into(),
+            },
+        ],
+    }
+}
+
+mod decode_and_write {
+    use git_odb::pack::bundle_file::{Header, Version};
+
+    use super::header;
+
+    #[test]
+    fn roundtrip_for_all_versions() -> crate::Result {
+        for version in [Version::V2, Version::V3] {
+            let expected = header(version);
+            let mut buf = Vec::new();
+            expected.write_to(&mut buf)?;
+            buf.extend_from_slice(b"PACK");
+
+            let (actual, bytes_read) = Header::from_read(buf.as_slice())?;
+            assert_eq!(actual, expected);
+            assert_eq!(
+                &buf[bytes_read as usize..],
+                b"PACK",
+                "the header ends right before the pack"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn v2_as_written_by_git() -> crate::Result {
+        let input = b"# v2 git bundle\n-e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 a commit subject\n-4b825dc642cb6eb9a060e54bf8d69288fbee4904\n0f3ea84cd1bba10c2a03d736a460635082833e59 refs/heads/main\n0f3ea84cd1bba10c2a03d736a460635082833e59 HEAD\n\n";
+        let (actual, bytes_read) = Header::from_read(&input[..])?;
+        assert_eq!(actual, header(Version::V2));
+        assert_eq!(bytes_read as usize, input.len());
+        Ok(())
+    }
+}
+
+mod from_read {
+    use git_odb::pack::bundle_file::Header;
+
+    use super::decode::Error;
+
+    fn decode_err(input: &[u8]) -> Error {
+        Header::from_read(input).expect_err("invalid input")
+    }
+
+    #[test]
+    fn invalid_signature() {
+        assert!(matches!(decode_err(b"# v4 git bundle\n\n"), Error::Signature { .. }));
+    }
+
+    #[test]
+    fn capabilities_in_v2() {
+        assert!(matches!(
+            decode_err(b"# v2 git bundle\n@object-format=sha1\n\n"),
+            Error::CapabilityInV2 { .. }
+        ));
+    }
+
+    #[test]
+    fn unsupported_capabilities() {
+        for input in [
+            &b"# v3 git bundle\n@object-format=sha256\n\n"[..],
+            b"# v3 git bundle\n@unknown=value\n\n",
+            b"# v3 git bundle\n@unknown\n\n",
+        ] {
+            assert!(matches!(decode_err(input), Error::UnsupportedCapability { .. }));
+        }
+    }
+
+    #[test]
+    fn invalid_object_ids() {
+        assert!(matches!(
+            decode_err(b"# v2 git bundle\n-e69de29bb2d1d6434b8b29ae775ad8c2e48c53\n\n"),
+            Error::Id { .. }
+        ));
+        assert!(matches!(
+            decode_err(b"# v2 git bundle\n-e69de29bb2d1d6434b8b29ae775ad8c2e48c539x\n\n"),
+            Error::InvalidLine { .. }
+        ));
+    }
+
+    #[test]
+    fn refs_without_name_and_trailing_garbage() {
+        for input in [
+            &b"# v2 git bundle\ne69de29bb2d1d6434b8b29ae775ad8c2e48c5391\n\n"[..],
+            b"# v2 git bundle\ne69de29bb2d1d6434b8b29ae775ad8c2e48c5391x HEAD\n\n",
+        ] {
+            assert!(matches!(decode_err(input), Error::InvalidLine { .. }));
+        }
+    }
+
+    #[test]
+    fn missing_separator_line() {
+        assert!(matches!(
+            decode_err(b"# v2 git bundle\ne69de29bb2d1d6434b8b29ae775ad8c2e48c5391 HEAD\n"),
+            Error::UnexpectedEof
+        ));
+    }
+}
+
+mod file {
+    use std::{io::Write, sync::atomic::AtomicBool};
+
+    use bstr::ByteSlice;
+    use git_features::progress;
+    use git_odb::pack::{self, bundle_file::File};
+
+    use crate::{fixture_path, pack::SMALL_PACK};
+
+    #[test]
+    fn write_pack_to_directory() -> crate::Result {
+        let dir = tempfile::TempDir::new()?;
+        let bundle_path = dir.path().join("repo.bundle");
+        {
+            let mut out = std::fs::File::create(&bundle_path)?;
+            super::header(pack::bundle_file::Version::V2).write_to(&mut out)?;
+            out.write_all(&std::fs::read(fixture_path(SMALL_PACK))?)?;
+        }
+
+        let bundle = File::at(&bundle_path)?;
+        assert_eq!(bundle.header, super::header(pack::bundle_file::Version::V2));
+        let outcome = bundle.write_pack_to_directory(
+            Some(dir.path()),
+            progress::Discard,
+            &AtomicBool::new(false),
+            None,
+            pack::bundle::write::Options {
+                thread_limit: None,
+                iteration_mode: pack::data::input::Mode::Verify,
+                index_kind: pack::index::Version::V2,
+                object_hash: git_hash::Kind::Sha1,
+            },
+        )?;
+        assert_eq!(outcome.index.num_objects, 42);
+        assert!(outcome.to_bundle().transpose()?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn write_to_creates_bundles_that_git_can_verify() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_pack_gen_repo.sh")?;
+        let rev_parse = |rev: &str| -> crate::Result<git_hash::ObjectId> {
+            let out = std::process::Command::new("git")
+                .args(["rev-parse", rev])
+                .current_dir(&repo_dir)
+                .output()?;
+            Ok(git_hash::ObjectId::from_hex(out.stdout.trim_end())?)
+        };
+        let (tip, parent) = (rev_parse("main")?, rev_parse("main~1")?);
+        let mut db = std::sync::Arc::new(git_odb::Store::at_opts(
+            repo_dir.join(".git").join("objects"),
+            Vec::new(),
+            git_odb::store::init::Options::default(),
+        )?)
+        .to_cache_arc();
+        db.prevent_pack_unload();
+
+        let dir = tempfile::TempDir::new()?;
+        let bundle_path = dir.path().join("repo.bundle");
+        let outcome = File::write_to(
+            std::fs::File::create(&bundle_path)?,
+            vec![pack::bundle_file::Ref {
+                id: tip,
+                name: "refs/heads/main".into(),
+            }],
+            Some(tip),
+            Some(parent),
+            db,
+            progress::Discard,
+            &AtomicBool::new(false),
+            Default::default(),
+        )?;
+        assert_eq!(
+            outcome.header.prerequisites.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![parent],
+            "the parent of the only included commit is a prerequisite"
+        );
+        assert!(
+            outcome.num_objects > 1,
+            "the commit along with its changed trees and blobs"
+        );
+        assert_eq!(File::at(&bundle_path)?.header, outcome.header);
+
+        let out = std::process::Command::new("git")
+            .args(["bundle", "verify"])
+            .arg(&bundle_path)
+            .current_dir(&repo_dir)
+            .output()?;
+        assert!(out.status.success(), "git considers the bundle valid");
+        Ok(())
+    }
+}
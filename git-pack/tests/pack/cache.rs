@@ -0,0 +1,156 @@
+#[cfg(feature = "pack-cache-lru-static")]
+mod static_linked_list {
+    use git_pack::cache::{lru::StaticLinkedList, DecodeEntry, Statistics};
+
+    #[test]
+    fn statistics_count_hits_misses_puts_and_evictions() {
+        let mut cache = StaticLinkedList::<2>::default();
+        let mut buf = Vec::new();
+        for offset in 0..3 {
+            cache.put(0, offset, b"data", git_object::Kind::Blob, 4);
+        }
+        assert!(
+            cache.get(0, 0, &mut buf).is_none(),
+            "the least recently used entry was evicted"
+        );
+        assert!(cache.get(0, 2, &mut buf).is_some());
+        assert_eq!(
+            cache.statistics(),
+            Some(Statistics {
+                hits: 1,
+                misses: 1,
+                puts: 3,
+                evictions: 1,
+                ..Default::default()
+            })
+        );
+    }
+}
+
+#[cfg(feature = "pack-cache-lru-dynamic")]
+mod memory_capped_hashmap {
+    use git_pack::cache::{
+        lru::{AdaptiveMemoryCap, MemoryCappedHashmap},
+        DecodeEntry, Statistics,
+    };
+
+    const DATA: &[u8] = &[0; 30];
+
+    fn policy() -> AdaptiveMemoryCap {
+        AdaptiveMemoryCap {
+            min_memory_cap_in_bytes: 64,
+            max_memory_cap_in_bytes: 128,
+            lookups_per_adjustment: 4,
+            grow_below_hit_rate: 0.8,
+            shrink_below_hit_rate: 0.1,
+        }
+    }
+
+    fn put_four_entries(cache: &mut MemoryCappedHashmap) {
+        for offset in 0..4 {
+            cache.put(0, offset, DATA, git_object::Kind::Blob, DATA.len());
+        }
+    }
+
+    fn lookup_all_four_entries(cache: &mut MemoryCappedHashmap) {
+        let mut buf = Vec::new();
+        for offset in 0..4 {
+            cache.get(0, offset, &mut buf);
+        }
+    }
+
+    #[test]
+    fn statistics_count_hits_misses_puts_and_evictions() {
+        let mut cache = MemoryCappedHashmap::new(64);
+        put_four_entries(&mut cache);
+        lookup_all_four_entries(&mut cache);
+        assert_eq!(
+            cache.statistics(),
+            Some(Statistics {
+                hits: 2,
+                misses: 2,
+                puts: 4,
+                evictions: 2,
+                ..Default::default()
+            }),
+            "only two entries fit into the cache"
+        );
+        assert_eq!(
+            cache.memory_cap_in_bytes(),
+            64,
+            "without policy, the memory cap stays the same"
+        );
+    }
+
+    #[test]
+    fn adaptive_memory_cap_grows_if_entries_are_evicted_and_the_hit_rate_is_low() {
+        let mut cache = MemoryCappedHashmap::with_adaptive_memory_cap(64, policy());
+        put_four_entries(&mut cache);
+        lookup_all_four_entries(&mut cache);
+        assert_eq!(
+            cache.memory_cap_in_bytes(),
+            128,
+            "a hit rate of 50% is below the threshold"
+        );
+        assert_eq!(cache.statistics().expect("present").grown, 1);
+
+        put_four_entries(&mut cache);
+        lookup_all_four_entries(&mut cache);
+        assert_eq!(
+            cache.statistics().expect("present"),
+            Statistics {
+                hits: 6,
+                misses: 2,
+                puts: 8,
+                evictions: 2,
+                grown: 1,
+                shrunk: 0
+            },
+            "all entries fit now, so the hit rate is perfect and the memory cap stays at its maximum"
+        );
+        assert_eq!(cache.memory_cap_in_bytes(), 128);
+    }
+
+    #[test]
+    fn adaptive_memory_cap_shrinks_if_the_cache_is_not_hit() {
+        let mut cache = MemoryCappedHashmap::with_adaptive_memory_cap(1024, policy());
+        assert_eq!(
+            cache.memory_cap_in_bytes(),
+            128,
+            "the initial memory cap is clamped to the bounds of the policy"
+        );
+
+        lookup_all_four_entries(&mut cache);
+        assert_eq!(cache.memory_cap_in_bytes(), 64, "nothing was found");
+        lookup_all_four_entries(&mut cache);
+        assert_eq!(
+            cache.memory_cap_in_bytes(),
+            64,
+            "the memory cap doesn't drop below the minimum"
+        );
+        assert_eq!(cache.statistics().expect("present").shrunk, 1);
+    }
+
+    #[test]
+    fn entries_evicted_when_shrinking_the_memory_cap_are_counted() {
+        let mut cache = MemoryCappedHashmap::with_adaptive_memory_cap(128, policy());
+        put_four_entries(&mut cache);
+        let mut buf = Vec::new();
+        for offset in 4..8 {
+            cache.get(0, offset, &mut buf);
+        }
+        assert_eq!(cache.memory_cap_in_bytes(), 64, "nothing was found");
+        assert_eq!(
+            cache.statistics().expect("present"),
+            Statistics {
+                hits: 0,
+                misses: 4,
+                puts: 4,
+                evictions: 2,
+                grown: 0,
+                shrunk: 1
+            },
+            "only two of the four entries fit into the smaller cache"
+        );
+    }
+}
@@ -44,3 +44,4 @@ fn db(kind: DbKind) -> crate::Result<git_odb::HandleArc> {
 }
 
 mod count_and_entries;
+mod range;
@@ -0,0 +1,84 @@
+use std::sync::atomic::AtomicBool;
+
+use git_features::progress;
+use git_odb::{pack, pack::FindExt};
+use git_pack::data::output::range;
+
+use crate::pack::{
+    data::output::{db, DbKind},
+    hex_to_id,
+};
+
+#[test]
+fn wants_not_reachable_from_haves_are_written_with_their_tags() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let referrer_tag = hex_to_id("e3fb53cbb4c346d48732a24f09cf445e49bc63d6");
+    let forgotten_commit = hex_to_id("ea25b241c5c31803ca72588762e36847c14d5a91");
+    let main = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+
+    let should_interrupt = AtomicBool::new(false);
+    let mut pack = Vec::new();
+    let outcome = range::write(
+        db.clone(),
+        Some(referrer_tag),
+        Some(main),
+        &mut pack,
+        progress::Discard,
+        &should_interrupt,
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.num_objects, 4,
+        "the tag, the commit it points to, its tree and the only blob it adds"
+    );
+    assert_eq!(outcome.bytes_written, pack.len() as u64);
+    assert_eq!(outcome.counts.input_objects, 2, "the tag and the commit it points to");
+
+    let tmp_dir = tempfile::TempDir::new()?;
+    let bundle_outcome = pack::Bundle::write_to_directory(
+        pack.as_slice(),
+        Some(tmp_dir.path()),
+        progress::Discard,
+        &should_interrupt,
+        None,
+        pack::bundle::write::Options::default(),
+    )?;
+    assert_eq!(bundle_outcome.pack_kind, pack::data::Version::V2);
+    assert_eq!(
+        bundle_outcome.index.data_hash, outcome.pack_hash,
+        "the pack is complete and the trailer is correct"
+    );
+    assert_eq!(bundle_outcome.index.num_objects, 4);
+
+    let bundle = pack::Bundle::at(bundle_outcome.data_path.expect("directory set"), git_hash::Kind::Sha1)?;
+    for id in [referrer_tag, forgotten_commit] {
+        assert!(bundle.index.lookup(id).is_some(), "{} is part of the pack", id);
+    }
+    assert!(bundle.index.lookup(main).is_none(), "haves are never sent");
+    Ok(())
+}
+
+#[test]
+fn wants_that_are_neither_commits_nor_tags_are_rejected() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let main = hex_to_id("dfcb5e39ac6eb30179808bbab721e8a28ce1b52e");
+    let mut buf = Vec::new();
+    let tree = db.find_commit(main, &mut buf)?.0.tree();
+
+    let err = range::write(
+        db,
+        Some(tree),
+        None::<git_hash::ObjectId>,
+        Vec::new(),
+        progress::Discard,
+        &AtomicBool::new(false),
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, range::Error::UnsupportedWant { id, kind: git_object::Kind::Tree } if id == tree),
+        "{:?}",
+        err
+    );
+    Ok(())
+}
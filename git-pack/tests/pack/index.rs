@@ -49,6 +49,27 @@ mod version {
             }
             Ok(())
         }
+
+        #[test]
+        fn shortest_unambiguous_hex_len() -> Result<(), Box<dyn std::error::Error>> {
+            let file = index::File::at(&fixture_path(INDEX_V1), git_hash::Kind::Sha1)?;
+            for entry in file.iter() {
+                let hex_len = file.shortest_unambiguous_hex_len(entry.oid);
+                let prefix = git_hash::Prefix::new(entry.oid, hex_len.max(4))?;
+                assert!(matches!(file.lookup_prefix(prefix), Some(Ok(_))));
+                if hex_len > 4 {
+                    let prefix = git_hash::Prefix::new(entry.oid, hex_len - 1)?;
+                    assert_eq!(file.lookup_prefix(prefix), Some(Err(())));
+                }
+            }
+            let missing = git_hash::ObjectId::from_hex(b"036bd66fe9b6591e959e6df51160e636ab1a682f")?;
+            assert_eq!(
+                file.shortest_unambiguous_hex_len(missing),
+                40,
+                "ids which aren't contained can be abbreviated as well"
+            );
+            Ok(())
+        }
     }
 
     mod v2 {
@@ -295,6 +316,7 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 15,
                 pack_size: 51875,
+                pack_cache: None,
             },
         ),
         (
@@ -320,6 +342,7 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 2,
                 pack_size: 49113,
+                pack_cache: None,
             },
         ),
         (
@@ -346,6 +369,7 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
                 num_tags: 0,
                 num_trees: 14,
                 pack_size: 3732,
+                pack_cache: None,
             },
         ),
     ] {
@@ -435,6 +459,134 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn pack_lookup_with_sampled_verification() -> Result<(), Box<dyn std::error::Error>> {
+    use index::verify::Sample;
+    let every_nth = |n| Sample::EveryNth(std::num::NonZeroU32::new(n).expect("non-zero"));
+    for (index_path, pack_path) in &[
+        (INDEX_V2, PACK_FOR_INDEX_V2),
+        (INDEX_V1, PACK_FOR_INDEX_V1),
+        (SMALL_PACK_INDEX, SMALL_PACK),
+    ] {
+        let idx = index::File::at(fixture_path(index_path), git_hash::Kind::Sha1)?;
+        let pack = pack::data::File::at(fixture_path(pack_path), git_hash::Kind::Sha1)?;
+        for algo in ALGORITHMS {
+            for sample in &[every_nth(1), every_nth(3), Sample::Bytes(1024), Sample::Bytes(0)] {
+                let outcome = idx.verify_integrity(
+                    Some(git_pack::index::verify::PackContext {
+                        data: &pack,
+                        options: git_pack::index::verify::integrity::Options {
+                            verify_mode: index::verify::Mode::HashCrc32Sampled(*sample),
+                            traversal: *algo,
+                            make_pack_lookup_cache: || cache::Never,
+                            thread_limit: None,
+                        },
+                    }),
+                    progress::Discard,
+                    &AtomicBool::new(false),
+                )?;
+                assert_eq!(outcome.actual_index_checksum, idx.index_checksum());
+                assert_eq!(
+                    outcome
+                        .pack_traverse_statistics
+                        .map(|stats| stats.objects_per_chain_length.values().sum::<u32>()),
+                    Some(idx.num_objects()),
+                    "{:?} {:?}: all objects are still traversed",
+                    algo,
+                    sample
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn sample_spreads_byte_budget_across_the_pack() {
+    use index::verify::Sample;
+    assert_eq!(Sample::Bytes(100).every_nth(1000).get(), 10);
+    assert_eq!(
+        Sample::Bytes(300).every_nth(1000).get(),
+        4,
+        "rounding up to stay within the budget"
+    );
+    assert_eq!(Sample::Bytes(2000).every_nth(1000).get(), 1, "a large budget verifies everything");
+    assert_eq!(
+        Sample::Bytes(0).every_nth(1000).get(),
+        1000,
+        "an empty budget still verifies a few objects"
+    );
+    assert_eq!(
+        Sample::EveryNth(std::num::NonZeroU32::new(7).expect("non-zero"))
+            .every_nth(1000)
+            .get(),
+        7
+    );
+}
+
+#[test]
+fn pack_lookup_collects_pack_cache_statistics() -> Result<(), Box<dyn std::error::Error>> {
+    /// A cache which keeps all objects and counts its usage.
+    #[derive(Default)]
+    struct Unbounded {
+        objects: std::collections::HashMap<(u32, u64), (object::Kind, Vec<u8>, usize)>,
+        stats: cache::Statistics,
+    }
+
+    impl cache::DecodeEntry for Unbounded {
+        fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: object::Kind, compressed_size: usize) {
+            self.stats.puts += 1;
+            self.objects.insert((pack_id, offset), (kind, data.to_owned(), compressed_size));
+        }
+
+        fn get(&mut self, pack_id: u32, offset: u64, out: &mut Vec<u8>) -> Option<(object::Kind, usize)> {
+            match self.objects.get(&(pack_id, offset)) {
+                Some((kind, data, compressed_size)) => {
+                    self.stats.hits += 1;
+                    out.clear();
+                    out.extend_from_slice(data);
+                    Some((*kind, *compressed_size))
+                }
+                None => {
+                    self.stats.misses += 1;
+                    None
+                }
+            }
+        }
+
+        fn statistics(&self) -> Option<cache::Statistics> {
+            Some(self.stats)
+        }
+    }
+
+    let idx = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2), git_hash::Kind::Sha1)?;
+    let outcome = idx.verify_integrity(
+        Some(git_pack::index::verify::PackContext {
+            data: &pack,
+            options: git_pack::index::verify::integrity::Options {
+                verify_mode: index::verify::Mode::HashCrc32,
+                traversal: index::traverse::Algorithm::Lookup,
+                make_pack_lookup_cache: Unbounded::default,
+                thread_limit: Some(1),
+            },
+        }),
+        progress::Discard,
+        &AtomicBool::new(false),
+    )?;
+    assert_eq!(
+        outcome.pack_traverse_statistics.expect("pack was traversed").pack_cache,
+        Some(cache::Statistics {
+            hits: 8,
+            misses: 12,
+            puts: 12,
+            ..Default::default()
+        }),
+        "all 12 delta objects are put into the cache once resolved, and some of them serve as base of later objects"
+    );
+    Ok(())
+}
+
 #[test]
 fn iter() -> Result<(), Box<dyn std::error::Error>> {
     for (path, kind, num_objects, index_checksum, pack_checksum) in &[
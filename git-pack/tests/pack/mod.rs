@@ -31,6 +31,8 @@ pub fn fixup(v: Vec<u8>) -> Vec<u8> {
 }
 
 mod bundle;
+mod bundle_file;
+mod cache;
 mod data;
 mod index;
 mod iter;
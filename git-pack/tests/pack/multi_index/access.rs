@@ -29,6 +29,28 @@ fn lookup_prefix() {
     }
 }
 
+#[test]
+fn shortest_unambiguous_hex_len() {
+    let (file, _path) = multi_index();
+
+    for entry in file.iter() {
+        let hex_len = file.shortest_unambiguous_hex_len(entry.oid);
+        let prefix = git_hash::Prefix::new(&entry.oid, hex_len.max(4)).unwrap();
+        assert!(
+            matches!(file.lookup_prefix(prefix), Some(Ok(_))),
+            "the prefix is unambiguous"
+        );
+        if hex_len > 4 {
+            let prefix = git_hash::Prefix::new(&entry.oid, hex_len - 1).unwrap();
+            assert_eq!(file.lookup_prefix(prefix), Some(Err(())), "one less is ambiguous");
+        }
+    }
+    assert_eq!(
+        file.shortest_unambiguous_hex_len(hex_to_id("cfc33fc40413fb3e30ff6b44d03fd8d071cb633b")),
+        5
+    );
+}
+
 #[test]
 fn lookup_missing() {
     let (file, _path) = multi_index();
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use git_pack::multi_index::File;
+use git_testtools::hex_to_id;
+
+fn multi_index_with_bitmap() -> (File, PathBuf) {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_pack_gen_repo_multi_index_bitmap.sh")
+        .expect("test fixture exists");
+    let file = File::at(repo.join(".git/objects/pack/multi-pack-index")).unwrap();
+    (file, repo)
+}
+
+#[test]
+fn pseudo_pack_order_is_a_permutation_starting_with_the_preferred_pack() {
+    let (file, _repo) = multi_index_with_bitmap();
+    let order = file.pseudo_pack_order().expect("RIDX chunk is present");
+    let preferred_pack = file.preferred_pack().expect("there are objects");
+    assert_eq!(
+        order,
+        file.compute_pseudo_pack_order(preferred_pack),
+        "we compute the same order as git"
+    );
+
+    let mut sorted = order.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..file.num_objects()).collect::<Vec<_>>());
+}
+
+#[test]
+fn bitmaps_resolve_to_all_reachable_objects() {
+    let (file, repo) = multi_index_with_bitmap();
+    let bitmap = file.bitmap().expect("valid bitmap").expect("bitmap is present");
+    assert_eq!(bitmap.checksum(), file.checksum());
+
+    let num_objects = file.num_objects() as usize;
+    let counts: Vec<_> = [
+        git_object::Kind::Commit,
+        git_object::Kind::Tree,
+        git_object::Kind::Blob,
+        git_object::Kind::Tag,
+    ]
+    .iter()
+    .map(|kind| bitmap.objects_of_kind(*kind).count())
+    .collect();
+    assert_eq!(counts[0], 60, "one bit per commit");
+    assert_eq!(counts[3], 1, "one bit per tag");
+    assert_eq!(
+        counts.iter().sum::<usize>(),
+        num_objects,
+        "each object has exactly one type"
+    );
+
+    let order = file.pseudo_pack_order().unwrap();
+    let position_of = |entry_index: u32| order.iter().position(|idx| *idx == entry_index).unwrap();
+    assert!(
+        bitmap.entries().iter().any(|e| e.xor_offset != 0),
+        "the fixture should have XORed bitmaps"
+    );
+    for (index, entry) in bitmap.entries().iter().enumerate() {
+        assert_eq!(bitmap.lookup(entry.object_position), Some(index));
+        let reachable = bitmap.bitmap_at_index(index).expect("valid xor chain");
+        assert!(
+            reachable.contains(position_of(entry.object_position)),
+            "each commit reaches itself"
+        );
+        assert!(reachable.count() <= num_objects);
+    }
+
+    let tip = std::fs::read_to_string(repo.join(".git/refs/heads/main")).unwrap();
+    let tip = file.lookup(hex_to_id(tip.trim())).expect("tip is in the multi-index");
+    let reachable = bitmap
+        .bitmap_at_index(bitmap.lookup(tip).expect("tips always have a bitmap"))
+        .unwrap();
+    assert_eq!(
+        reachable.count(),
+        num_objects - 1,
+        "everything but the tag object is reachable from the tip"
+    );
+}
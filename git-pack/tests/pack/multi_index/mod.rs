@@ -12,6 +12,8 @@ fn multi_index() -> (File, PathBuf) {
 
 mod access;
 
+mod bitmap;
+
 mod verify;
 
 mod write;
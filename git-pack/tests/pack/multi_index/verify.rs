@@ -46,7 +46,8 @@ fn integrity() {
             num_commits: 16,
             num_trees: 40,
             num_tags: 1,
-            num_blobs: 811
+            num_blobs: 811,
+            pack_cache: None
         }]
     );
 }
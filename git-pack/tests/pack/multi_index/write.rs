@@ -64,3 +64,72 @@ fn from_paths() -> crate::Result {
     assert_eq!(outcome.0, file.checksum());
     Ok(())
 }
+
+#[test]
+fn with_additional_index_paths() -> crate::Result {
+    let dir = tempfile::TempDir::new()?;
+    let mut input_indices = std::fs::read_dir(fixture_path("objects/pack"))?
+        .filter_map(|r| {
+            r.ok()
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|e| e.to_str()).unwrap_or("") == "idx")
+        })
+        .map(|index| -> std::io::Result<_> {
+            let dest = dir.path().join(index.file_name().expect("present"));
+            std::fs::copy(&index, &dest)?;
+            let pack = index.with_extension("pack");
+            std::fs::copy(&pack, dir.path().join(pack.file_name().expect("present")))?;
+            Ok(dest)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    input_indices.sort();
+    assert_eq!(input_indices.len(), 3);
+
+    let write = |index_paths: Vec<PathBuf>, name: &str| -> crate::Result<PathBuf> {
+        let path = dir.path().join(name);
+        let out = std::fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+        git_pack::multi_index::File::write_from_index_paths(
+            index_paths,
+            out,
+            progress::Discard,
+            &AtomicBool::new(false),
+            git_pack::multi_index::write::Options {
+                object_hash: git_hash::Kind::Sha1,
+            },
+        )?;
+        Ok(path)
+    };
+    let full = git_pack::multi_index::File::at(write(input_indices.clone(), "full")?)?;
+    let partial = git_pack::multi_index::File::at(write(input_indices[..1].to_vec(), "partial")?)?;
+    assert_eq!(partial.num_indices(), 1);
+
+    let output_path = dir.path().join("multi-pack-index");
+    let out = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&output_path)?;
+    let outcome = partial.write_with_additional_index_paths(
+        input_indices.iter().rev().cloned().collect(),
+        out,
+        progress::Discard,
+        &AtomicBool::new(false),
+        git_pack::multi_index::write::Options {
+            object_hash: git_hash::Kind::Sha1,
+        },
+    )?;
+
+    assert_eq!(
+        outcome.multi_index_checksum,
+        full.checksum(),
+        "known indices are ignored and the result is the same as if it was written from scratch"
+    );
+    let file = git_pack::multi_index::File::at(output_path)?;
+    assert_eq!(file.index_names(), full.index_names());
+    assert_eq!(file.num_objects(), 139);
+    assert_eq!(
+        file.verify_integrity(progress::Discard, &AtomicBool::new(false), Default::default())?
+            .actual_index_checksum,
+        outcome.multi_index_checksum
+    );
+    Ok(())
+}
@@ -7,7 +7,7 @@ use std::{
 use futures_io::{AsyncBufRead, AsyncRead};
 use futures_lite::ready;
 
-use crate::{decode, BandRef, PacketLineRef, StreamingPeekableIter, TextRef, U16_HEX_BYTES};
+use crate::{decode, BandRef, PacketLineRef, StreamingPeekableIter, TextRef, MAX_LINE_LEN, U16_HEX_BYTES};
 
 type ReadLineResult<'a> = Option<std::io::Result<Result<PacketLineRef<'a>, decode::Error>>>;
 /// An implementor of [`AsyncBufRead`] yielding packet lines on each call to [`read_line()`][AsyncBufRead::read_line()].
@@ -21,6 +21,7 @@ where
     handle_progress: Option<F>,
     pos: usize,
     cap: usize,
+    raw: bool,
 }
 
 impl<'a, T, F> Drop for WithSidebands<'a, T, F>
@@ -45,6 +46,7 @@ where
             handle_progress: None,
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 }
@@ -102,6 +104,7 @@ where
             handle_progress: Some(handle_progress),
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 
@@ -112,6 +115,7 @@ where
             handle_progress: None,
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 
@@ -135,6 +139,14 @@ where
         self.handle_progress = handle_progress;
     }
 
+    /// Stop decoding packet lines and pass all remaining bytes of the underlying reader through as they are.
+    ///
+    /// This is needed to receive a pack from servers which send it without packet line encoding, which is what they do if no
+    /// side-band was negotiated.
+    pub fn pass_through_raw_data(&mut self) {
+        self.raw = true;
+    }
+
     /// Effectively forwards to the parent [StreamingPeekableIter::peek_line()], allowing to see what would be returned
     /// next on a call to [`read_line()`][io::BufRead::read_line()].
     pub async fn peek_data_line(&mut self) -> Option<std::io::Result<Result<&[u8], crate::decode::Error>>> {
@@ -195,7 +207,17 @@ where
         use futures_lite::FutureExt;
         {
             let this = self.as_mut().get_mut();
-            if this.pos >= this.cap {
+            if this.raw && this.pos >= this.cap {
+                if let State::Idle {
+                    parent: Some(ref mut parent),
+                } = this.state
+                {
+                    let parent = &mut **parent;
+                    parent.buf.resize(MAX_LINE_LEN, 0);
+                    this.cap = ready!(Pin::new(&mut parent.read).poll_read(cx, &mut parent.buf))?;
+                    this.pos = 0;
+                }
+            } else if this.pos >= this.cap {
                 let (ofs, cap) = loop {
                     match this.state {
                         State::Idle { ref mut parent } => {
@@ -1,6 +1,6 @@
 use std::{io, io::BufRead};
 
-use crate::{BandRef, PacketLineRef, StreamingPeekableIter, TextRef, U16_HEX_BYTES};
+use crate::{BandRef, PacketLineRef, StreamingPeekableIter, TextRef, MAX_LINE_LEN, U16_HEX_BYTES};
 
 /// An implementor of [`BufRead`][io::BufRead] yielding packet lines on each call to [`read_line()`][io::BufRead::read_line()].
 /// It's also possible to hide the underlying packet lines using the [`Read`][io::Read] implementation which is useful
@@ -13,6 +13,7 @@ where
     handle_progress: Option<F>,
     pos: usize,
     cap: usize,
+    raw: bool,
 }
 
 impl<'a, T, F> Drop for WithSidebands<'a, T, F>
@@ -35,6 +36,7 @@ where
             handle_progress: None,
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 }
@@ -54,6 +56,7 @@ where
             handle_progress: Some(handle_progress),
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 
@@ -64,6 +67,7 @@ where
             handle_progress: None,
             pos: 0,
             cap: 0,
+            raw: false,
         }
     }
 
@@ -82,6 +86,14 @@ where
         self.handle_progress = handle_progress;
     }
 
+    /// Stop decoding packet lines and pass all remaining bytes of the underlying reader through as they are.
+    ///
+    /// This is needed to receive a pack from servers which send it without packet line encoding, which is what they do if no
+    /// side-band was negotiated.
+    pub fn pass_through_raw_data(&mut self) {
+        self.raw = true;
+    }
+
     /// Effectively forwards to the parent [StreamingPeekableIter::peek_line()], allowing to see what would be returned
     /// next on a call to [`read_line()`][io::BufRead::read_line()].
     pub fn peek_data_line(&mut self) -> Option<io::Result<Result<&[u8], crate::decode::Error>>> {
@@ -100,6 +112,15 @@ where
     F: FnMut(bool, &[u8]),
 {
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.raw {
+            if self.pos >= self.cap {
+                let buf = &mut self.parent.buf;
+                buf.resize(MAX_LINE_LEN, 0);
+                self.cap = self.parent.read.read(buf)?;
+                self.pos = 0;
+            }
+            return Ok(&self.parent.buf[self.pos..self.cap]);
+        }
         if self.pos >= self.cap {
             let (ofs, cap) = loop {
                 let line = match self.parent.read_line() {
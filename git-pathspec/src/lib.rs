@@ -1 +1,34 @@
-#![forbid(unsafe_code, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms, missing_docs)]
+//! Match paths against simple pathspecs, which are either leading paths or glob patterns.
+
+use bstr::{BStr, ByteSlice};
+
+/// Return true if `spec` matches `path`, either as a leading portion of it or as glob pattern.
+///
+/// A trailing slash in `spec` is ignored, and both are expected to be relative to the root of the repository.
+pub fn matches(spec: &BStr, path: &BStr) -> bool {
+    let spec = spec.strip_suffix(b"/").unwrap_or(spec).as_bstr();
+    if path.starts_with(spec) && (path.len() == spec.len() || path[spec.len()] == b'/') {
+        return true;
+    }
+    is_glob(spec) && git_glob::wildmatch(spec, path, git_glob::wildmatch::Mode::empty())
+}
+
+/// Return true if the directory at `path` could contain entries matched by `spec`, which allows to skip
+/// directories that can't contain any matches.
+pub fn may_contain_matches(spec: &BStr, path: &BStr) -> bool {
+    let literal_prefix = match spec.iter().position(|b| GLOB_CHARS.contains(b)) {
+        Some(pos) => &spec[..pos],
+        None => spec.as_ref(),
+    };
+    let is_parent_of_prefix = literal_prefix.starts_with(path) && literal_prefix.get(path.len()) == Some(&b'/');
+    let is_within_prefix = is_glob(spec) && path.starts_with(literal_prefix);
+    is_parent_of_prefix || is_within_prefix || matches(spec, path)
+}
+
+const GLOB_CHARS: &[u8] = b"*?[";
+
+fn is_glob(spec: &BStr) -> bool {
+    spec.iter().any(|b| GLOB_CHARS.contains(b))
+}
@@ -14,7 +14,7 @@ impl Arguments {
             assert!(add_done_argument, "If there are no haves, is_done must be true.");
         }
         match self.version {
-            git_transport::Protocol::V1 => {
+            git_transport::Protocol::V0 | git_transport::Protocol::V1 => {
                 let (on_into_read, retained_state) = self.prepare_v1(
                     transport.connection_persists_across_multiple_requests(),
                     add_done_argument,
@@ -42,10 +42,11 @@ impl Arguments {
                 if add_done_argument {
                     self.args.push("done".into());
                 }
+                let args = std::mem::replace(&mut self.args, retained_state);
                 transport.invoke(
                     Command::Fetch.as_str(),
-                    self.features.iter().filter(|(_, v)| v.is_some()).cloned(),
-                    Some(std::mem::replace(&mut self.args, retained_state).into_iter()),
+                    self.effective_features().filter(|(_, v)| v.is_some()),
+                    Some(args.into_iter()),
                 )
             }
         }
@@ -21,6 +21,9 @@ pub struct Arguments {
     features_for_first_want: Option<Vec<String>>,
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
     version: git_transport::Protocol,
+    /// The value to use for the 'agent' feature instead of the default one.
+    #[cfg(any(feature = "async-client", feature = "blocking-client"))]
+    agent: Option<String>,
 }
 
 impl Arguments {
@@ -71,8 +74,10 @@ impl Arguments {
     /// As such it should be included in the server response as it's not present on the client.
     pub fn want(&mut self, id: impl AsRef<git_hash::oid>) {
         match self.features_for_first_want.take() {
-            Some(features) => self.prefixed("want ", format!("{} {}", id.as_ref(), features.join(" "))),
-            None => self.prefixed("want ", id.as_ref()),
+            Some(features) if !features.is_empty() => {
+                self.prefixed("want ", format!("{} {}", id.as_ref(), features.join(" ")))
+            }
+            _ => self.prefixed("want ", id.as_ref()),
         }
     }
     /// Add the given ref to the 'want-ref' list.
@@ -126,7 +131,11 @@ impl Arguments {
         self.args.push(format!("{}{}", prefix, value).into());
     }
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
-    pub(crate) fn new(version: git_transport::Protocol, features: Vec<crate::fetch::command::Feature>) -> Self {
+    pub(crate) fn new(
+        version: git_transport::Protocol,
+        features: Vec<crate::fetch::command::Feature>,
+        agent: Option<String>,
+    ) -> Self {
         use crate::fetch::Command;
         let has = |name: &str| features.iter().any(|f| f.0 == name);
         let filter = has("filter");
@@ -135,24 +144,17 @@ impl Arguments {
         let mut deepen_since = shallow;
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
-        let (initial_arguments, features_for_first_want) = match version {
-            git_transport::Protocol::V1 => {
+        let initial_arguments = match version {
+            git_transport::Protocol::V0 | git_transport::Protocol::V1 => {
                 deepen_since = has("deepen-since");
                 deepen_not = has("deepen-not");
                 deepen_relative = has("deepen-relative");
-                let baked_features = features
-                    .iter()
-                    .map(|(n, v)| match v {
-                        Some(v) => format!("{}={}", n, v),
-                        None => n.to_string(),
-                    })
-                    .collect::<Vec<_>>();
-                (Vec::new(), Some(baked_features))
+                Vec::new()
             }
-            git_transport::Protocol::V2 => (Command::Fetch.initial_arguments(&features), None),
+            git_transport::Protocol::V2 => Command::Fetch.initial_arguments(&features),
         };
 
-        Arguments {
+        let mut arguments = Arguments {
             features,
             version,
             args: initial_arguments,
@@ -163,8 +165,30 @@ impl Arguments {
             deepen_relative,
             ref_in_want,
             deepen_since,
-            features_for_first_want,
+            features_for_first_want: None,
+            agent,
+        };
+        if matches!(version, git_transport::Protocol::V0 | git_transport::Protocol::V1) {
+            let baked_features = arguments
+                .effective_features()
+                .map(|(n, v)| match v {
+                    Some(v) => format!("{}={}", n, v),
+                    None => n.to_string(),
+                })
+                .collect::<Vec<_>>();
+            arguments.features_for_first_want = Some(baked_features);
         }
+        arguments
+    }
+
+    /// Return all features, with the value of the 'agent' feature replaced by our custom agent, if set.
+    #[cfg(any(feature = "async-client", feature = "blocking-client"))]
+    fn effective_features(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        let agent = self.agent.as_deref();
+        self.features.iter().map(move |(name, value)| match (*name, agent) {
+            ("agent", Some(agent)) if value.is_some() => (*name, Some(agent)),
+            _ => (*name, *value),
+        })
     }
 }
 
@@ -61,7 +61,7 @@ mod with_io {
             match self {
                 Command::LsRefs => &[],
                 Command::Fetch => match version {
-                    git_transport::Protocol::V1 => &[
+                    git_transport::Protocol::V0 | git_transport::Protocol::V1 => &[
                         "multi_ack",
                         "thin-pack",
                         "side-band",
@@ -111,20 +111,23 @@ mod with_io {
         ) -> Vec<Feature> {
             match self {
                 Command::Fetch => match version {
-                    git_transport::Protocol::V1 => {
+                    git_transport::Protocol::V0 | git_transport::Protocol::V1 => {
                         let has_multi_ack_detailed = server_capabilities.contains("multi_ack_detailed");
                         let has_sideband_64k = server_capabilities.contains("side-band-64k");
+                        let has_sideband = has_sideband_64k || server_capabilities.contains("side-band");
                         self.all_features(version)
                             .iter()
                             .copied()
                             .filter(|feature| match *feature {
                                 "side-band" if has_sideband_64k => false,
                                 "multi_ack" if has_multi_ack_detailed => false,
+                                // without side-band, the pack can't be told apart from acknowledgements that may follow
+                                "no-done" if !has_sideband => false,
                                 "include-tag" | "no-progress" => false,
                                 feature => server_capabilities.contains(feature),
                             })
                             .map(|s| (s, None))
-                            .chain(Some(agent()))
+                            .chain(server_capabilities.contains("agent").then(agent))
                             .collect()
                     }
                     git_transport::Protocol::V2 => {
@@ -166,7 +169,7 @@ mod with_io {
                 panic!("{}: argument {} is not known or allowed", self.as_str(), arg);
             }
             match version {
-                git_transport::Protocol::V1 => {
+                git_transport::Protocol::V0 | git_transport::Protocol::V1 => {
                     for (feature, _) in features {
                         if server
                             .iter()
@@ -6,7 +6,7 @@ use std::{
 use bstr::BString;
 use git_transport::client::Capabilities;
 
-use crate::fetch::{Arguments, Ref, Response};
+use crate::fetch::{refs, Arguments, Ref, Response};
 
 /// Defines what to do next after certain [`Delegate`] operations.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -42,6 +42,20 @@ pub trait DelegateBlocking {
     fn handshake_extra_parameters(&self) -> Vec<(String, Option<String>)> {
         Vec::new()
     }
+
+    /// Return the value of the `agent` feature to identify ourselves to the server, or `None` to use the default
+    /// of [`agent()`][crate::fetch::agent()].
+    fn agent(&self) -> Option<String> {
+        None
+    }
+
+    /// Called right after the handshake to allow adjusting the `server` capabilities, for instance to remove
+    /// capabilities known to be broken on the server side so they are never used, or to add ones the server supports
+    /// without advertising them.
+    ///
+    /// All following interactions are based on the adjusted capabilities, which is also what the preset `features`
+    /// are derived from.
+    fn adjust_capabilities(&mut self, _version: git_transport::Protocol, _server: &mut Capabilities) {}
     /// Called before invoking 'ls-refs' on the server to allow providing it with additional `arguments` and to enable `features`.
     /// If the server `capabilities` don't match the requirements abort with an error to abort the entire fetch operation.
     ///
@@ -50,7 +64,9 @@ pub trait DelegateBlocking {
     ///
     /// If the delegate returns [`LsRefsAction::Skip`], no 'ls-refs` command is sent to the server.
     ///
-    /// Note that this is called only if we are using protocol version 2.
+    /// Note that this is called only if we are using protocol version 2. If a [refs cache][Self::refs_cache()] knows
+    /// the capabilities of the remote, it's called before connecting to look up cached refs, and once more after
+    /// the handshake if they weren't cached.
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
@@ -60,6 +76,15 @@ pub trait DelegateBlocking {
         Ok(LsRefsAction::Continue)
     }
 
+    /// Return a cache for the refs listed with 'ls-refs' to skip connecting to the remote if refs were received recently
+    /// from it with the same arguments, or `None` to always list refs.
+    ///
+    /// If cached refs are used and [`prepare_fetch()`][Self::prepare_fetch()] returns [`Action::Cancel`], the remote isn't
+    /// contacted at all. Otherwise the handshake is performed just before fetching.
+    fn refs_cache(&mut self) -> Option<&mut refs::Cache> {
+        None
+    }
+
     /// Called before invoking the 'fetch' interaction with `features` pre-filled for typical use
     /// and to maximize capabilities to allow aborting an interaction early.
     ///
@@ -106,6 +131,9 @@ pub trait DelegateBlocking {
     /// enough state to be able to walk down from your tips on each call, if they are not in common, and keep setting `have`
     /// for those which are in common if that helps teaching the server about our state and to acknowledge their existence on _their_ end.
     /// This method is called until the other side signals they are ready to send a pack.
+    /// All acknowledgement lines as sent by the server are available through
+    /// [`Response::acknowledgement_details()`][crate::fetch::Response::acknowledgement_details()], along with its
+    /// [shallow updates][crate::fetch::Response::shallow_updates()].
     /// Return `Action::Close` if you want to give up before finding a common base. This can happen if the remote repository
     /// has radically changed so there are no bases, or they are very far in the past, causing all objects to be sent.
     fn negotiate(
@@ -121,6 +149,14 @@ impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
         self.deref().handshake_extra_parameters()
     }
 
+    fn agent(&self) -> Option<String> {
+        self.deref().agent()
+    }
+
+    fn adjust_capabilities(&mut self, version: git_transport::Protocol, server: &mut Capabilities) {
+        self.deref_mut().adjust_capabilities(version, server)
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
@@ -130,6 +166,10 @@ impl<T: DelegateBlocking> DelegateBlocking for Box<T> {
         self.deref_mut().prepare_ls_refs(_server, _arguments, _features)
     }
 
+    fn refs_cache(&mut self) -> Option<&mut refs::Cache> {
+        self.deref_mut().refs_cache()
+    }
+
     fn prepare_fetch(
         &mut self,
         _version: git_transport::Protocol,
@@ -155,6 +195,14 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
         self.deref().handshake_extra_parameters()
     }
 
+    fn agent(&self) -> Option<String> {
+        self.deref().agent()
+    }
+
+    fn adjust_capabilities(&mut self, version: git_transport::Protocol, server: &mut Capabilities) {
+        self.deref_mut().adjust_capabilities(version, server)
+    }
+
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
@@ -164,6 +212,10 @@ impl<T: DelegateBlocking> DelegateBlocking for &mut T {
         self.deref_mut().prepare_ls_refs(_server, _arguments, _features)
     }
 
+    fn refs_cache(&mut self) -> Option<&mut refs::Cache> {
+        self.deref_mut().refs_cache()
+    }
+
     fn prepare_fetch(
         &mut self,
         _version: git_transport::Protocol,
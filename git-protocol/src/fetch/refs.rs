@@ -3,6 +3,10 @@ use std::io;
 use bstr::BString;
 use quick_error::quick_error;
 
+///
+pub mod cache;
+pub use cache::Cache;
+
 quick_error! {
     /// The error returned when parsing References/refs from the server response.
     #[derive(Debug)]
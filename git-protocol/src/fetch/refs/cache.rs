@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use git_transport::client::Capabilities;
+
+use crate::fetch::Ref;
+
+/// A cache for the refs advertised by remotes, keyed by their url and all arguments used to list them with `ls-refs`,
+/// to avoid connecting to remotes again if their refs were received recently, which is useful for tools that poll
+/// many remotes.
+///
+/// It's used by [`fetch()`][crate::fetch()] if the delegate provides it with
+/// [`DelegateBlocking::refs_cache()`][crate::fetch::DelegateBlocking::refs_cache()]. Along with the refs, the cache keeps
+/// the capabilities the remote advertised, which allows to prepare the `ls-refs` arguments and look up fresh refs
+/// before connecting, and to skip the handshake entirely if the delegate doesn't continue to fetch.
+/// Note that only refs listed with `ls-refs` in protocol V2 are cached, as V1 servers advertise their refs as part of
+/// the handshake.
+///
+/// Urls are stored without user information like passwords, so remotes which differ only in their credentials share
+/// the same refs.
+///
+/// Refs are stale once they are older than the time-to-live passed to [`new()`][Cache::new()]. Stale refs are never
+/// returned, and they are removed with [`prune()`][Cache::prune()].
+#[derive(Debug, Clone)]
+pub struct Cache {
+    ttl: Duration,
+    entries: HashMap<BString, Remote>,
+}
+
+/// Everything known about a single remote.
+#[derive(Debug, Clone)]
+struct Remote {
+    /// The capabilities the remote advertised most recently, along with the time they were received at.
+    capabilities: (Instant, Capabilities),
+    /// The refs along with the time they were received at, keyed by the sorted `ls-refs` arguments they were listed with.
+    refs: HashMap<Vec<BString>, (Instant, Vec<Ref>)>,
+}
+
+/// Return `url` without user information, like `user:password@`.
+fn without_userinfo(url: &BStr) -> BString {
+    let (scheme, rest) = match url.find(b"://") {
+        Some(pos) => url.split_at(pos + 3),
+        None => (&b""[..], url.as_bytes()),
+    };
+    let host_end = if scheme.is_empty() {
+        // scp-like urls, i.e. `user@host:path`, are the only ones without scheme that carry user information.
+        rest.find_byteset(b":/").filter(|pos| rest[*pos] == b':').unwrap_or(0)
+    } else {
+        rest.find_byte(b'/').unwrap_or(rest.len())
+    };
+    match rest[..host_end].rfind_byte(b'@') {
+        Some(at) => {
+            let mut url = BString::from(scheme);
+            url.extend_from_slice(&rest[at + 1..]);
+            url
+        }
+        None => url.to_owned(),
+    }
+}
+
+/// Return the given `ls-refs` `arguments` in sorted order, as they are part of the key of cached refs.
+fn sorted(arguments: &[BString]) -> Vec<BString> {
+    let mut arguments = arguments.to_owned();
+    arguments.sort();
+    arguments
+}
+
+impl Cache {
+    /// Create a new empty cache whose refs are stale once they are older than `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Cache {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Return the refs advertised by the remote at `url` when listed with the given `ls-refs` `arguments` if they aren't
+    /// stale. The order of `arguments` doesn't matter.
+    pub fn get(&self, url: &BStr, arguments: &[BString]) -> Option<&[Ref]> {
+        self.entries
+            .get(&without_userinfo(url))
+            .and_then(|remote| remote.refs.get(&sorted(arguments)))
+            .filter(|(received_at, _)| received_at.elapsed() < self.ttl)
+            .map(|(_, refs)| refs.as_slice())
+    }
+
+    /// Return the capabilities most recently advertised by the remote at `url` if they aren't stale.
+    pub fn capabilities(&self, url: &BStr) -> Option<&Capabilities> {
+        self.entries
+            .get(&without_userinfo(url))
+            .map(|remote| &remote.capabilities)
+            .filter(|(received_at, _)| received_at.elapsed() < self.ttl)
+            .map(|(_, capabilities)| capabilities)
+    }
+
+    /// Store the `refs` just received from the remote at `url` when listed with the given `ls-refs` `arguments`, along
+    /// with the `capabilities` it advertised, replacing those received previously.
+    pub fn insert(&mut self, url: &BStr, arguments: &[BString], capabilities: Capabilities, refs: Vec<Ref>) {
+        let now = Instant::now();
+        let arguments = sorted(arguments);
+        match self.entries.get_mut(&without_userinfo(url)) {
+            Some(remote) => {
+                remote.capabilities = (now, capabilities);
+                remote.refs.insert(arguments, (now, refs));
+            }
+            None => {
+                self.entries.insert(
+                    without_userinfo(url),
+                    Remote {
+                        capabilities: (now, capabilities),
+                        refs: std::iter::once((arguments, (now, refs))).collect(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Return the refs advertised by the remote at `url` when listed with the given `ls-refs` `arguments` if they aren't
+    /// stale, or call `receive` to connect to the remote and store the capabilities and refs it advertises otherwise.
+    pub fn get_or_try_insert_with<E>(
+        &mut self,
+        url: &BStr,
+        arguments: &[BString],
+        receive: impl FnOnce() -> Result<(Capabilities, Vec<Ref>), E>,
+    ) -> Result<&[Ref], E> {
+        if self.get(url, arguments).is_none() {
+            let (capabilities, refs) = receive()?;
+            self.insert(url, arguments, capabilities, refs);
+        }
+        Ok(self
+            .entries
+            .get(&without_userinfo(url))
+            .and_then(|remote| remote.refs.get(&sorted(arguments)))
+            .map(|(_, refs)| refs.as_slice())
+            .expect("just inserted"))
+    }
+
+    /// Remove all refs of the remote at `url`, no matter which arguments they were listed with, for instance after
+    /// pushing to it, and return true if any of them were present and not stale.
+    pub fn remove(&mut self, url: &BStr) -> bool {
+        let ttl = self.ttl;
+        self.entries.remove(&without_userinfo(url)).map_or(false, |remote| {
+            remote.refs.values().any(|(received_at, _)| received_at.elapsed() < ttl)
+        })
+    }
+
+    /// Remove all stale refs and capabilities to release their memory.
+    pub fn prune(&mut self) {
+        let ttl = self.ttl;
+        self.entries.retain(|_, remote| {
+            remote.refs.retain(|_, (received_at, _)| received_at.elapsed() < ttl);
+            !remote.refs.is_empty()
+        });
+    }
+}
@@ -5,7 +5,7 @@ use git_transport::{client, Protocol};
 
 use crate::fetch::{
     response,
-    response::{Acknowledgement, ShallowUpdate, WantedRef},
+    response::{Acknowledgement, AcknowledgementDetail, ShallowUpdate, WantedRef},
     Response,
 };
 
@@ -38,9 +38,10 @@ impl Response {
         reader: &mut (impl client::ExtendedBufRead + Unpin),
     ) -> Result<Response, response::Error> {
         match version {
-            Protocol::V1 => {
+            Protocol::V0 | Protocol::V1 => {
                 let mut line = String::new();
                 let mut acks = Vec::<Acknowledgement>::new();
+                let mut ack_details = Vec::<AcknowledgementDetail>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let has_pack = 'lines: loop {
                     line.clear();
@@ -73,13 +74,19 @@ impl Response {
                         }
                     };
 
-                    if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut shallows, &peeked_line) {
+                    if Response::parse_v1_ack_or_shallow_or_assume_pack(
+                        &mut acks,
+                        &mut ack_details,
+                        &mut shallows,
+                        &peeked_line,
+                    ) {
                         break 'lines true;
                     }
                     assert_ne!(reader.read_line(&mut line).await?, 0, "consuming a peeked line works");
                 };
                 Ok(Response {
                     acks,
+                    ack_details,
                     shallows,
                     wanted_refs: vec![],
                     has_pack,
@@ -89,7 +96,7 @@ impl Response {
                 // NOTE: We only read acknowledgements and scrub to the pack file, until we have use for the other features
                 let mut line = String::new();
                 reader.reset(Protocol::V2);
-                let mut acks = Vec::<Acknowledgement>::new();
+                let mut ack_details = Vec::<AcknowledgementDetail>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let mut wanted_refs = Vec::<WantedRef>::new();
                 let has_pack = 'section: loop {
@@ -103,7 +110,9 @@ impl Response {
 
                     match line.trim_end() {
                         "acknowledgments" => {
-                            if parse_v2_section(&mut line, reader, &mut acks, Acknowledgement::from_line).await? {
+                            if parse_v2_section(&mut line, reader, &mut ack_details, AcknowledgementDetail::from_line)
+                                .await?
+                            {
                                 break 'section false;
                             }
                         }
@@ -125,7 +134,8 @@ impl Response {
                     }
                 };
                 Ok(Response {
-                    acks,
+                    acks: ack_details.iter().copied().map(Into::into).collect(),
+                    ack_details,
                     shallows,
                     wanted_refs,
                     has_pack,
@@ -133,4 +143,46 @@ impl Response {
             }
         }
     }
+
+    /// Parse the response to the final request of a `V0` or `V1` negotiation, the one with `done`, from `reader` if no
+    /// side-band was negotiated.
+    ///
+    /// Such servers send the pack right after their last acknowledgement without packet line encoding, which is why we stop
+    /// at the final `ACK` or `NAK` instead of peeking at the next line. Call
+    /// [`pass_through_raw_data()`][client::ExtendedBufRead::pass_through_raw_data()] on `reader` to read the pack afterwards.
+    pub async fn from_line_reader_before_raw_pack(
+        reader: &mut (impl client::ExtendedBufRead + Unpin),
+    ) -> Result<Response, response::Error> {
+        let mut line = String::new();
+        let mut acks = Vec::<Acknowledgement>::new();
+        let mut ack_details = Vec::<AcknowledgementDetail>::new();
+        let mut shallows = Vec::<ShallowUpdate>::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await? == 0 {
+                // shallow updates are terminated by a flush packet
+                if reader.stopped_at() != Some(client::MessageKind::Flush) {
+                    return Err(response::Error::Io(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Could not read the final acknowledgement",
+                    )));
+                }
+                reader.reset(Protocol::V1);
+                continue;
+            }
+            if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut ack_details, &mut shallows, &line) {
+                return Err(response::Error::UnknownLineType(line));
+            }
+            if let Some(AcknowledgementDetail::Ack(_) | AcknowledgementDetail::Nak) = ack_details.last() {
+                break;
+            }
+        }
+        Ok(Response {
+            acks,
+            ack_details,
+            shallows,
+            wanted_refs: vec![],
+            has_pack: true,
+        })
+    }
 }
@@ -64,6 +64,24 @@ pub enum Acknowledgement {
     Nak,
 }
 
+/// An 'ACK', 'NAK' or 'ready' line received from the server, in full detail and without interpretation.
+///
+/// Useful to implement negotiation strategies outside of this crate, or to debug them.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum AcknowledgementDetail {
+    /// `ACK <id>`, which is the final acknowledgement right before the pack in V1, or a common object in V2.
+    Ack(git_hash::ObjectId),
+    /// `ACK <id> common`, the `id` is in common (V1, `multi_ack_detailed`).
+    Common(git_hash::ObjectId),
+    /// `ACK <id> continue`, the `id` is in common and the server wants more (V1, `multi_ack`).
+    Continue(git_hash::ObjectId),
+    /// `ACK <id> ready` in V1 or `ready` in V2, the server found a cut point and is ready to send a pack.
+    Ready(Option<git_hash::ObjectId>),
+    /// `NAK`, nothing is in common yet (V1) or no ACK was sent (V2).
+    Nak,
+}
+
 /// A shallow line received from the server.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -102,34 +120,62 @@ impl ShallowUpdate {
     }
 }
 
-impl Acknowledgement {
-    /// Parse an `Acknowledgement` from a `line` as received to the server.
-    pub fn from_line(line: &str) -> Result<Acknowledgement, Error> {
+impl AcknowledgementDetail {
+    /// Parse an `AcknowledgementDetail` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<AcknowledgementDetail, Error> {
         let mut tokens = line.trim_end().splitn(3, ' ');
         match (tokens.next(), tokens.next(), tokens.next()) {
             (Some(first), id, description) => Ok(match first {
-                "ready" => Acknowledgement::Ready, // V2
-                "NAK" => Acknowledgement::Nak,     // V1
+                "ready" => AcknowledgementDetail::Ready(None), // V2
+                "NAK" => AcknowledgementDetail::Nak,           // V1
                 "ACK" => {
                     let id = match id {
                         Some(id) => git_hash::ObjectId::from_hex(id.as_bytes())
                             .map_err(|_| Error::UnknownLineType(line.to_owned()))?,
                         None => return Err(Error::UnknownLineType(line.to_owned())),
                     };
-                    if let Some(description) = description {
-                        match description {
-                            "common" => {}
-                            "ready" => return Ok(Acknowledgement::Ready),
-                            _ => return Err(Error::UnknownLineType(line.to_owned())),
-                        }
+                    match description {
+                        None => AcknowledgementDetail::Ack(id),
+                        Some("common") => AcknowledgementDetail::Common(id),
+                        Some("continue") => AcknowledgementDetail::Continue(id),
+                        Some("ready") => AcknowledgementDetail::Ready(Some(id)),
+                        Some(_) => return Err(Error::UnknownLineType(line.to_owned())),
                     }
-                    Acknowledgement::Common(id)
                 }
                 _ => return Err(Error::UnknownLineType(line.to_owned())),
             }),
             (None, _, _) => Err(Error::UnknownLineType(line.to_owned())),
         }
     }
+    /// Returns the hash of the object mentioned in the line, if there is one.
+    pub fn id(&self) -> Option<&git_hash::ObjectId> {
+        match self {
+            AcknowledgementDetail::Ack(id)
+            | AcknowledgementDetail::Common(id)
+            | AcknowledgementDetail::Continue(id)
+            | AcknowledgementDetail::Ready(Some(id)) => Some(id),
+            AcknowledgementDetail::Ready(None) | AcknowledgementDetail::Nak => None,
+        }
+    }
+}
+
+impl From<AcknowledgementDetail> for Acknowledgement {
+    fn from(detail: AcknowledgementDetail) -> Self {
+        match detail {
+            AcknowledgementDetail::Ack(id)
+            | AcknowledgementDetail::Common(id)
+            | AcknowledgementDetail::Continue(id) => Acknowledgement::Common(id),
+            AcknowledgementDetail::Ready(_) => Acknowledgement::Ready,
+            AcknowledgementDetail::Nak => Acknowledgement::Nak,
+        }
+    }
+}
+
+impl Acknowledgement {
+    /// Parse an `Acknowledgement` from a `line` as received to the server.
+    pub fn from_line(line: &str) -> Result<Acknowledgement, Error> {
+        AcknowledgementDetail::from_line(line).map(Into::into)
+    }
     /// Returns the hash of the acknowledged object if this instance acknowledges a common one.
     pub fn id(&self) -> Option<&git_hash::ObjectId> {
         match self {
@@ -156,6 +202,7 @@ impl WantedRef {
 /// A representation of a complete fetch response
 pub struct Response {
     acks: Vec<Acknowledgement>,
+    ack_details: Vec<AcknowledgementDetail>,
     shallows: Vec<ShallowUpdate>,
     wanted_refs: Vec<WantedRef>,
     has_pack: bool,
@@ -169,34 +216,31 @@ impl Response {
 
     /// Return an error if the given `features` don't contain the required ones for the given `version` of the protocol.
     ///
-    /// Even though technically any set of features supported by the server could work, we only implement the ones that
-    /// make it easy to maintain all versions with a single code base that aims to be and remain maintainable.
-    pub fn check_required_features(version: Protocol, features: &[Feature]) -> Result<(), Error> {
-        match version {
-            Protocol::V1 => {
-                let has = |name: &str| features.iter().any(|f| f.0 == name);
-                // Let's focus on V2 standards, and simply not support old servers to keep our code simpler
-                if !has("multi_ack_detailed") {
-                    return Err(Error::MissingServerCapability("multi_ack_detailed"));
-                }
-                // It's easy to NOT do sideband for us, but then again, everyone supports it.
-                // CORRECTION: If side-band is off, it would send the packfile without packet line encoding,
-                // which is nothing we ever want to deal with (despite it being more efficient). In V2, this
-                // is not even an option anymore, sidebands are always present.
-                if !has("side-band") && !has("side-band-64k") {
-                    return Err(Error::MissingServerCapability("side-band OR side-band-64k"));
-                }
-            }
-            Protocol::V2 => {}
-        }
+    /// There are no required features anymore as `V0` and `V1` servers without `multi_ack_detailed` or side-bands are supported,
+    /// the latter by reading their pack with [`from_line_reader_before_raw_pack()`][Response::from_line_reader_before_raw_pack()].
+    #[deprecated(
+        since = "0.15.1",
+        note = "all features are optional now, this function will be removed in the next major release"
+    )]
+    #[doc(hidden)]
+    pub fn check_required_features(_version: Protocol, _features: &[Feature]) -> Result<(), Error> {
         Ok(())
     }
 
     /// Return all acknowledgements [parsed previously][Response::from_line_reader()].
+    ///
+    /// Note that these are condensed, with each common object listed only once. See
+    /// [`acknowledgement_details()`][Response::acknowledgement_details()] for all lines as sent by the server.
     pub fn acknowledgements(&self) -> &[Acknowledgement] {
         &self.acks
     }
 
+    /// Return all acknowledgement lines in the order they were [received][Response::from_line_reader()], without
+    /// any deduplication or interpretation.
+    pub fn acknowledgement_details(&self) -> &[AcknowledgementDetail] {
+        &self.ack_details
+    }
+
     /// Return all shallow update lines [parsed previously][Response::from_line_reader()].
     pub fn shallow_updates(&self) -> &[ShallowUpdate] {
         &self.shallows
@@ -214,18 +258,23 @@ impl Response {
     /// which is our hint to stop here.
     fn parse_v1_ack_or_shallow_or_assume_pack(
         acks: &mut Vec<Acknowledgement>,
+        ack_details: &mut Vec<AcknowledgementDetail>,
         shallows: &mut Vec<ShallowUpdate>,
         peeked_line: &str,
     ) -> bool {
-        match Acknowledgement::from_line(peeked_line) {
-            Ok(ack) => match ack.id() {
-                Some(id) => {
-                    if !acks.iter().any(|a| a.id() == Some(id)) {
-                        acks.push(ack);
+        match AcknowledgementDetail::from_line(peeked_line) {
+            Ok(detail) => {
+                ack_details.push(detail);
+                let ack = Acknowledgement::from(detail);
+                match ack.id() {
+                    Some(id) => {
+                        if !acks.iter().any(|a| a.id() == Some(id)) {
+                            acks.push(ack);
+                        }
                     }
+                    None => acks.push(ack),
                 }
-                None => acks.push(ack),
-            },
+            }
             Err(_) => match ShallowUpdate::from_line(peeked_line) {
                 Ok(shallow) => {
                     shallows.push(shallow);
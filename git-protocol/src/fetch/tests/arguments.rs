@@ -4,11 +4,11 @@ use git_transport::Protocol;
 use crate::fetch;
 
 fn arguments_v1(features: impl IntoIterator<Item = &'static str>) -> fetch::Arguments {
-    fetch::Arguments::new(Protocol::V1, features.into_iter().map(|n| (n, None)).collect())
+    fetch::Arguments::new(Protocol::V1, features.into_iter().map(|n| (n, None)).collect(), None)
 }
 
 fn arguments_v2(features: impl IntoIterator<Item = &'static str>) -> fetch::Arguments {
-    fetch::Arguments::new(Protocol::V2, features.into_iter().map(|n| (n, None)).collect())
+    fetch::Arguments::new(Protocol::V2, features.into_iter().map(|n| (n, None)).collect(), None)
 }
 
 struct Transport<T> {
@@ -126,8 +126,12 @@ fn id(hex: &str) -> git_hash::ObjectId {
 
 mod v1 {
     use bstr::ByteSlice;
+    use git_transport::Protocol;
 
-    use crate::fetch::tests::arguments::{arguments_v1, id, transport};
+    use crate::fetch::{
+        self,
+        tests::arguments::{arguments_v1, id, transport},
+    };
 
     #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
     async fn haves_and_wants_for_clone() {
@@ -148,6 +152,27 @@ mod v1 {
         );
     }
 
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn custom_agent_replaces_the_default_one() {
+        let mut out = Vec::new();
+        let mut t = transport(&mut out, true);
+        let mut arguments = fetch::Arguments::new(
+            Protocol::V1,
+            vec![("feature-a", None), ("agent", Some("git/oxide-0.0.0"))],
+            Some("custom/1.0".into()),
+        );
+
+        arguments.want(id("7b333369de1221f9bfbbe03a3a13e9a09bc1c907"));
+        arguments.send(&mut t, true).await.expect("sending to buffer to work");
+        assert_eq!(
+            out.as_bstr(),
+            b"004dwant 7b333369de1221f9bfbbe03a3a13e9a09bc1c907 feature-a agent=custom/1.0
+00000009done
+"
+            .as_bstr()
+        );
+    }
+
     #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
     async fn haves_and_wants_for_fetch_stateless() {
         let mut out = Vec::new();
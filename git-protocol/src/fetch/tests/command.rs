@@ -21,7 +21,8 @@ mod v1 {
                         git_transport::Protocol::V1,
                         &capabilities("multi_ack side-band side-band-64k multi_ack_detailed")
                     ),
-                    &[("side-band-64k", None), ("multi_ack_detailed", None), fetch::agent()]
+                    &[("side-band-64k", None), ("multi_ack_detailed", None)],
+                    "the agent is only sent if the server advertises one"
                 );
             }
 
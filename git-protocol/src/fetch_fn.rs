@@ -1,16 +1,17 @@
 use std::io;
 
+use bstr::BString;
 use git_features::{progress, progress::Progress};
 use git_transport::{
     client,
-    client::{SetServiceResponse, TransportV2Ext},
+    client::{Capabilities, SetServiceResponse, TransportV2Ext},
     Service,
 };
 use maybe_async::maybe_async;
 
 use crate::{
     credentials,
-    fetch::{refs, Action, Arguments, Command, Delegate, Error, LsRefsAction, Response},
+    fetch::{command::Feature, refs, Action, Arguments, Command, Delegate, Error, LsRefsAction, Ref, Response},
 };
 
 /// A way to indicate how to treat the connection underlying the transport, potentially allowing to reuse it.
@@ -62,126 +63,93 @@ where
     D: Delegate,
     T: client::Transport,
 {
-    let (protocol_version, parsed_refs, capabilities) = {
-        progress.init(None, progress::steps());
-        progress.set_name("handshake");
-        progress.step();
-
-        let extra_parameters = delegate.handshake_extra_parameters();
-        let extra_parameters: Vec<_> = extra_parameters
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_ref().map(|s| s.as_str())))
-            .collect();
-        let supported_versions: Vec<_> = transport.supported_protocol_versions().into();
-
-        let result = transport.handshake(Service::UploadPack, &extra_parameters).await;
-        let SetServiceResponse {
-            actual_protocol,
-            capabilities,
-            refs,
-        } = match result {
-            Ok(v) => Ok(v),
-            Err(client::Error::Io { ref err }) if err.kind() == io::ErrorKind::PermissionDenied => {
-                drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
-                let url = transport.to_url();
-                progress.set_name("authentication");
-                let credentials::Outcome { identity, next } =
-                    authenticate(credentials::Action::Fill(&url))?.expect("FILL provides an identity");
-                transport.set_identity(identity)?;
-                progress.step();
-                progress.set_name("handshake (authenticated)");
-                match transport.handshake(Service::UploadPack, &extra_parameters).await {
-                    Ok(v) => {
-                        authenticate(next.approve())?;
-                        Ok(v)
-                    }
-                    // Still no permission? Reject the credentials.
-                    Err(client::Error::Io { err }) if err.kind() == io::ErrorKind::PermissionDenied => {
-                        authenticate(next.reject())?;
-                        Err(client::Error::Io { err })
-                    }
-                    // Otherwise, do nothing, as we don't know if it actually got to try the credentials.
-                    // If they were previously stored, they remain. In the worst case, the user has to enter them again
-                    // next time they try.
-                    Err(err) => Err(err),
-                }
-            }
-            Err(err) => Err(err),
-        }?;
-
-        if !supported_versions.is_empty() && !supported_versions.contains(&actual_protocol) {
-            return Err(Error::TransportProtocolPolicyViolation {
-                actual_version: actual_protocol,
-            });
-        }
-
-        let parsed_refs = match refs {
-            Some(mut refs) => {
-                assert_eq!(
-                    actual_protocol,
-                    git_transport::Protocol::V1,
-                    "Only V1 auto-responds with refs"
-                );
-                Some(
-                    refs::from_v1_refs_received_as_part_of_handshake_and_capabilities(&mut refs, capabilities.iter())
-                        .await?,
-                )
-            }
+    progress.init(None, progress::steps());
+    let url = transport.to_url();
+    // With fresh capabilities of the remote in the cache, we can find out which refs to list and skip connecting
+    // if they are cached as well.
+    let cached = match delegate
+        .refs_cache()
+        .and_then(|cache| cache.capabilities(url.as_str().into()))
+        .cloned()
+    {
+        Some(capabilities) => match prepare_ls_refs(&mut delegate, &capabilities)? {
+            Some((ls_args, _ls_features)) => delegate
+                .refs_cache()
+                .and_then(|cache| cache.get(url.as_str().into(), &ls_args))
+                .map(|refs| (capabilities, refs.to_owned())),
             None => None,
-        };
-        (actual_protocol, parsed_refs, capabilities)
-    }; // this scope is needed, see https://github.com/rust-lang/rust/issues/76149
+        },
+        None => None,
+    };
 
-    let parsed_refs = match parsed_refs {
-        Some(refs) => refs,
+    let agent = delegate.agent();
+    let (protocol_version, parsed_refs, capabilities, is_connected) = match cached {
+        Some((capabilities, refs)) => (git_transport::Protocol::V2, refs, capabilities, false),
         None => {
-            assert_eq!(
-                protocol_version,
-                git_transport::Protocol::V2,
-                "Only V2 needs a separate request to get specific refs"
-            );
-
-            let ls_refs = Command::LsRefs;
-            let mut ls_features = ls_refs.default_features(protocol_version, &capabilities);
-            let mut ls_args = ls_refs.initial_arguments(&ls_features);
-            match delegate.prepare_ls_refs(&capabilities, &mut ls_args, &mut ls_features) {
-                Ok(LsRefsAction::Skip) => Vec::new(),
-                Ok(LsRefsAction::Continue) => {
-                    ls_refs.validate_argument_prefixes_or_panic(
+            let (protocol_version, parsed_refs, capabilities) =
+                handshake(&mut transport, &mut delegate, &mut authenticate, &mut progress).await?;
+            let parsed_refs = match parsed_refs {
+                Some(refs) => refs,
+                None => {
+                    assert_eq!(
                         protocol_version,
-                        &capabilities,
-                        &ls_args,
-                        &ls_features,
+                        git_transport::Protocol::V2,
+                        "Only V2 needs a separate request to get specific refs"
                     );
 
-                    progress.step();
-                    progress.set_name("list refs");
-                    let mut remote_refs = transport
-                        .invoke(
-                            ls_refs.as_str(),
-                            ls_features.into_iter(),
-                            if ls_args.is_empty() {
-                                None
-                            } else {
-                                Some(ls_args.into_iter())
-                            },
-                        )
-                        .await?;
-                    refs::from_v2_refs(&mut remote_refs).await?
-                }
-                Err(err) => {
-                    indicate_end_of_interaction(transport).await?;
-                    return Err(err.into());
+                    let ls_refs = Command::LsRefs;
+                    match prepare_ls_refs(&mut delegate, &capabilities) {
+                        Ok(None) => Vec::new(),
+                        Ok(Some((ls_args, ls_features))) => {
+                            ls_refs.validate_argument_prefixes_or_panic(
+                                protocol_version,
+                                &capabilities,
+                                &ls_args,
+                                &ls_features,
+                            );
+
+                            progress.step();
+                            progress.set_name("list refs");
+                            let mut remote_refs = transport
+                                .invoke(
+                                    ls_refs.as_str(),
+                                    ls_features.into_iter().map(|(name, value)| match name {
+                                        "agent" => (name, agent.as_deref().or(value)),
+                                        _ => (name, value),
+                                    }),
+                                    if ls_args.is_empty() {
+                                        None
+                                    } else {
+                                        Some(ls_args.clone().into_iter())
+                                    },
+                                )
+                                .await?;
+                            let parsed_refs = refs::from_v2_refs(&mut remote_refs).await?;
+                            if let Some(cache) = delegate.refs_cache() {
+                                cache.insert(url.as_str().into(), &ls_args, capabilities.clone(), parsed_refs.clone());
+                            }
+                            parsed_refs
+                        }
+                        Err(err) => {
+                            indicate_end_of_interaction(transport).await?;
+                            return Err(err.into());
+                        }
+                    }
                 }
-            }
+            };
+            (protocol_version, parsed_refs, capabilities, true)
         }
     };
 
+    git_features::count!(refs = parsed_refs.len());
+
     let fetch = Command::Fetch;
     let mut fetch_features = fetch.default_features(protocol_version, &capabilities);
     match delegate.prepare_fetch(protocol_version, &capabilities, &mut fetch_features, &parsed_refs) {
         Ok(Action::Cancel) => {
-            return if matches!(protocol_version, git_transport::Protocol::V1)
+            return if !is_connected {
+                Ok(())
+            } else if matches!(protocol_version, git_transport::Protocol::V1)
                 || matches!(fetch_mode, FetchConnection::TerminateOnSuccessfulCompletion)
             {
                 indicate_end_of_interaction(transport).await
@@ -190,33 +158,54 @@ where
             };
         }
         Ok(Action::Continue) => {
+            if !is_connected {
+                // The refs were cached, but fetching needs a connection to the remote.
+                let (actual_version, _refs, _capabilities) =
+                    handshake(&mut transport, &mut delegate, &mut authenticate, &mut progress).await?;
+                if actual_version != protocol_version {
+                    indicate_end_of_interaction(transport).await?;
+                    return Err(Error::TransportProtocolPolicyViolation { actual_version });
+                }
+            }
             fetch.validate_argument_prefixes_or_panic(protocol_version, &capabilities, &[], &fetch_features);
         }
         Err(err) => {
-            indicate_end_of_interaction(transport).await?;
+            if is_connected {
+                indicate_end_of_interaction(transport).await?;
+            }
             return Err(err.into());
         }
     }
 
-    Response::check_required_features(protocol_version, &fetch_features)?;
     let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
-    let mut arguments = Arguments::new(protocol_version, fetch_features);
+    let raw_pack = protocol_version != git_transport::Protocol::V2
+        && !fetch_features
+            .iter()
+            .any(|(n, _)| *n == "side-band" || *n == "side-band-64k");
+    let mut arguments = Arguments::new(protocol_version, fetch_features, agent);
     let mut previous_response = None::<Response>;
     let mut round = 1;
     'negotiation: loop {
         progress.step();
         progress.set_name(format!("negotiate (round {})", round));
+        git_features::count!(negotiation_rounds = 1);
         round += 1;
         let action = delegate.negotiate(&parsed_refs, &mut arguments, previous_response.as_ref())?;
         let mut reader = arguments.send(&mut transport, action == Action::Cancel).await?;
         if sideband_all {
             setup_remote_progress(&mut progress, &mut reader);
         }
-        let response = Response::from_line_reader(protocol_version, &mut reader).await?;
+        let response = if raw_pack && action == Action::Cancel {
+            Response::from_line_reader_before_raw_pack(&mut reader).await?
+        } else {
+            Response::from_line_reader(protocol_version, &mut reader).await?
+        };
         previous_response = if response.has_pack() {
             progress.step();
             progress.set_name("receiving pack");
-            if !sideband_all {
+            if raw_pack {
+                reader.pass_through_raw_data();
+            } else if !sideband_all {
                 setup_remote_progress(&mut progress, &mut reader);
             }
             delegate.receive_pack(reader, progress, &parsed_refs, &response).await?;
@@ -236,6 +225,106 @@ where
     Ok(())
 }
 
+/// Perform the handshake with the remote at the other end of `transport`, obtaining credentials with `authenticate` if needed,
+/// and return the protocol version it speaks, the refs it advertised if the protocol version requires it, and its capabilities.
+#[maybe_async]
+async fn handshake<T, D, F>(
+    transport: &mut T,
+    delegate: &mut D,
+    authenticate: &mut F,
+    progress: &mut impl Progress,
+) -> Result<(git_transport::Protocol, Option<Vec<Ref>>, Capabilities), Error>
+where
+    F: FnMut(credentials::Action<'_>) -> credentials::Result,
+    D: Delegate,
+    T: client::Transport,
+{
+    progress.set_name("handshake");
+    progress.step();
+
+    let extra_parameters = delegate.handshake_extra_parameters();
+    let extra_parameters: Vec<_> = extra_parameters
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_ref().map(|s| s.as_str())))
+        .collect();
+    let supported_versions: Vec<_> = transport.supported_protocol_versions().into();
+
+    let result = transport.handshake(Service::UploadPack, &extra_parameters).await;
+    let SetServiceResponse {
+        actual_protocol,
+        mut capabilities,
+        refs,
+    } = match result {
+        Ok(v) => Ok(v),
+        Err(client::Error::Io { ref err }) if err.kind() == io::ErrorKind::PermissionDenied => {
+            drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
+            let url = transport.to_url();
+            progress.set_name("authentication");
+            let credentials::Outcome { identity, next } =
+                authenticate(credentials::Action::Fill(&url))?.expect("FILL provides an identity");
+            transport.set_identity(identity)?;
+            progress.step();
+            progress.set_name("handshake (authenticated)");
+            match transport.handshake(Service::UploadPack, &extra_parameters).await {
+                Ok(v) => {
+                    authenticate(next.approve())?;
+                    Ok(v)
+                }
+                // Still no permission? Reject the credentials.
+                Err(client::Error::Io { err }) if err.kind() == io::ErrorKind::PermissionDenied => {
+                    authenticate(next.reject())?;
+                    Err(client::Error::Io { err })
+                }
+                // Otherwise, do nothing, as we don't know if it actually got to try the credentials.
+                // If they were previously stored, they remain. In the worst case, the user has to enter them again
+                // next time they try.
+                Err(err) => Err(err),
+            }
+        }
+        Err(err) => Err(err),
+    }?;
+
+    if !supported_versions.is_empty() && !supported_versions.contains(&actual_protocol) {
+        return Err(Error::TransportProtocolPolicyViolation {
+            actual_version: actual_protocol,
+        });
+    }
+    delegate.adjust_capabilities(actual_protocol, &mut capabilities);
+
+    let parsed_refs = match refs {
+        Some(mut refs) => {
+            assert_eq!(
+                actual_protocol,
+                git_transport::Protocol::V1,
+                "Only V1 auto-responds with refs"
+            );
+            Some(
+                refs::from_v1_refs_received_as_part_of_handshake_and_capabilities(&mut refs, capabilities.iter())
+                    .await?,
+            )
+        }
+        None => None,
+    };
+    Ok((actual_protocol, parsed_refs, capabilities))
+}
+
+/// Return the arguments and features of the `ls-refs` command as prepared by `delegate` for a remote with the given
+/// `capabilities`, or `None` if the delegate skips listing refs.
+fn prepare_ls_refs(
+    delegate: &mut impl Delegate,
+    capabilities: &Capabilities,
+) -> io::Result<Option<(Vec<BString>, Vec<Feature>)>> {
+    let ls_refs = Command::LsRefs;
+    let mut ls_features = ls_refs.default_features(git_transport::Protocol::V2, capabilities);
+    let mut ls_args = ls_refs.initial_arguments(&ls_features);
+    Ok(
+        match delegate.prepare_ls_refs(capabilities, &mut ls_args, &mut ls_features)? {
+            LsRefsAction::Skip => None,
+            LsRefsAction::Continue => Some((ls_args, ls_features)),
+        },
+    )
+}
+
 #[maybe_async]
 async fn indicate_end_of_interaction(mut transport: impl client::Transport) -> Result<(), Error> {
     // An empty request marks the (early) end of the interaction. Only relevant in stateful transports though.
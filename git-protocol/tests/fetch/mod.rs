@@ -93,6 +93,8 @@ impl fetch::DelegateBlocking for CloneRefInWantDelegate {
 pub struct LsRemoteDelegate {
     refs: Vec<fetch::Ref>,
     abort_with: Option<std::io::Error>,
+    ref_prefixes: Vec<BString>,
+    refs_cache: Option<fetch::refs::Cache>,
 }
 
 impl fetch::DelegateBlocking for LsRemoteDelegate {
@@ -102,14 +104,24 @@ impl fetch::DelegateBlocking for LsRemoteDelegate {
     fn prepare_ls_refs(
         &mut self,
         _server: &Capabilities,
-        _arguments: &mut Vec<BString>,
+        arguments: &mut Vec<BString>,
         _features: &mut Vec<(&str, Option<&str>)>,
     ) -> std::io::Result<LsRefsAction> {
         match self.abort_with.take() {
             Some(err) => Err(err),
-            None => Ok(LsRefsAction::Continue),
+            None => {
+                for prefix in &self.ref_prefixes {
+                    let mut arg = BString::from("ref-prefix ");
+                    arg.extend_from_slice(prefix);
+                    arguments.push(arg);
+                }
+                Ok(LsRefsAction::Continue)
+            }
         }
     }
+    fn refs_cache(&mut self) -> Option<&mut fetch::refs::Cache> {
+        self.refs_cache.as_mut()
+    }
     fn prepare_fetch(
         &mut self,
         _version: git_transport::Protocol,
@@ -289,6 +301,7 @@ pub fn transport<W: std::io::Write>(
     )
 }
 
+mod refs_cache;
 pub mod response;
 mod v1;
 mod v2;
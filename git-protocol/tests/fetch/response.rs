@@ -19,7 +19,7 @@ mod v1 {
         use futures_lite::io::AsyncReadExt;
         use git_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, AcknowledgementDetail, ShallowUpdate},
         };
         use git_transport::Protocol;
 
@@ -99,6 +99,18 @@ mod v1 {
                     Acknowledgement::Nak,
                 ]
             );
+            assert_eq!(
+                r.acknowledgement_details(),
+                &[
+                    AcknowledgementDetail::Common(id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
+                    AcknowledgementDetail::Common(id("fe17165c392110d1305674c06e4aec35728bfab7")),
+                    AcknowledgementDetail::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    AcknowledgementDetail::Ready(Some(id("f22743895a3024bb0c958335981439f1fa747d57"))),
+                    AcknowledgementDetail::Nak,
+                    AcknowledgementDetail::Ack(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                ],
+                "details are neither deduplicated nor interpreted"
+            );
             assert!(r.has_pack());
             let mut buf = Vec::new();
             let bytes_read = reader.read_to_end(&mut buf).await?;
@@ -116,7 +128,7 @@ mod v2 {
         use futures_lite::io::AsyncReadExt;
         use git_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, AcknowledgementDetail, ShallowUpdate},
         };
         use git_transport::Protocol;
 
@@ -225,6 +237,14 @@ mod v2 {
                     Acknowledgement::Ready,
                 ]
             );
+            assert_eq!(
+                r.acknowledgement_details(),
+                &[
+                    AcknowledgementDetail::Ack(id("190c3f6b2319c1f4ec854215533caf8623f8f870")),
+                    AcknowledgementDetail::Ack(id("97c5a932b3940a09683e924ef6a92b31a6f7c6de")),
+                    AcknowledgementDetail::Ready(None),
+                ]
+            );
             assert!(r.has_pack());
             let mut buf = Vec::new();
             reader.set_progress_handler(Some(Box::new(|a: bool, b: &[u8]| {
@@ -26,6 +26,35 @@ async fn clone() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn clone_from_v0_server_without_capabilities_and_side_bands() -> crate::Result {
+    let mut out = Vec::new();
+    let mut dlg = CloneDelegate::default();
+    git_protocol::fetch(
+        transport(
+            &mut out,
+            "v1/clone-without-capabilities.response",
+            Protocol::V0,
+            git_transport::client::git::ConnectMode::Daemon,
+        ),
+        &mut dlg,
+        git_protocol::credentials::helper,
+        progress::Discard,
+        FetchConnection::TerminateOnSuccessfulCompletion,
+    )
+    .await?;
+    assert_eq!(
+        dlg.pack_bytes, 876,
+        "the pack is read as is without packet line decoding"
+    );
+    assert_eq!(
+        out.as_bstr(),
+        b"0024git-upload-pack does/not/matter\00032want 808e50d724f604f69ab93c6da2919c014667bedb\n0032want 808e50d724f604f69ab93c6da2919c014667bedb\n00000009done\n".as_bstr(),
+        "no capabilities are requested from servers that don't advertise any"
+    );
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn ls_remote() -> crate::Result {
     let out = Vec::new();
@@ -102,6 +102,48 @@ async fn ls_remote() -> crate::Result {
     Ok(())
 }
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn ls_remote_with_refs_cache() -> crate::Result {
+    let mut delegate = LsRemoteDelegate {
+        refs_cache: Some(fetch::refs::Cache::new(std::time::Duration::from_secs(60 * 60))),
+        ..Default::default()
+    };
+    for (ref_prefixes, expect_ls_refs, message) in &[
+        (&[][..], true, "nothing is cached yet"),
+        (&[], false, "refs are taken from the cache"),
+        (
+            &["refs/heads/"],
+            true,
+            "refs listed with other prefixes are cached separately",
+        ),
+        (&["refs/heads/"], false, "these are cached now as well"),
+    ] {
+        delegate.ref_prefixes = ref_prefixes.iter().map(|p| (*p).into()).collect();
+        let mut transport = transport(
+            Vec::new(),
+            "v2/clone.response",
+            Protocol::V2,
+            git_transport::client::git::ConnectMode::Daemon,
+        );
+        git_protocol::fetch(
+            &mut transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress::Discard,
+            FetchConnection::AllowReuse,
+        )
+        .await?;
+        assert_eq!(delegate.refs.len(), 2, "refs are the same whether cached or not");
+        let sent = transport.into_inner().1;
+        if *expect_ls_refs {
+            assert!(sent.find("command=ls-refs").is_some(), "{}", message);
+        } else {
+            assert!(sent.is_empty(), "{}, without even connecting to the remote", message);
+        }
+    }
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn ls_remote_abort_in_prep_ls_refs() -> crate::Result {
     let out = Vec::new();
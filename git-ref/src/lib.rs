@@ -60,6 +60,23 @@ pub mod store {
         Disable,
     }
 
+    /// The way a file store handles names of loose references which are aliased by the filesystem, like `refs/heads/Feature`
+    /// and `refs/heads/feature` on case-insensitive filesystems, or differently normalized unicode names on filesystems which
+    /// normalize them.
+    ///
+    /// Use [`NameCollisions::probe()`] to determine the policy suitable for a filesystem.
+    #[derive(Debug, PartialOrd, PartialEq, Ord, Eq, Hash, Clone, Copy)]
+    pub enum NameCollisions {
+        /// Trust the filesystem to only find loose references by their exact name, which is correct for case-sensitive
+        /// filesystems that don't normalize names.
+        Ignore,
+        /// Verify that loose references found on disk have exactly the requested name and report a collision otherwise,
+        /// instead of silently treating a differently named reference as the requested one.
+        ///
+        /// This costs a directory listing for each component of the name of each loose reference that is read or changed.
+        Report,
+    }
+
     /// A thread-local handle for interacting with a [`Store`][crate::Store] to find and iterate references.
     #[derive(Clone)]
     pub struct Handle {
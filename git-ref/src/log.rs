@@ -14,3 +14,51 @@ pub struct Line {
     /// The message providing details about the operation performed in this log line.
     pub message: BString,
 }
+
+/// Functions to compose reflog messages the way `git` does for common operations.
+pub mod message {
+    use git_object::{
+        bstr::{BStr, BString, ByteVec},
+        commit::MessageRef,
+    };
+
+    /// Generate a message typical for git commit logs based on the given `operation`, commit `message` and `num_parents` of the commit,
+    /// like `commit (initial): the subject`.
+    pub fn commit(operation: &str, message: &BStr, num_parents: usize) -> BString {
+        let mut out = BString::from(operation);
+        if let Some(commit_type) = commit_type_by_parents(num_parents) {
+            out.push_str(b" (");
+            out.extend_from_slice(commit_type.as_bytes());
+            out.push_byte(b')');
+        }
+        out.push_str(b": ");
+        out.extend_from_slice(&MessageRef::from_bytes(message).summary());
+        out
+    }
+
+    /// Generate the message for a newly created branch which starts at `start_point`, like `branch: Created from main`.
+    pub fn branch_created_from(start_point: &BStr) -> BString {
+        let mut out = BString::from("branch: Created from ");
+        out.extend_from_slice(start_point);
+        out
+    }
+
+    /// Generate the message for moving `HEAD` from `from` to `to` as done by `git checkout`, like `checkout: moving from main to feature`.
+    ///
+    /// `from` and `to` are typically short branch names, or hexadecimal object ids if `HEAD` is or becomes detached.
+    pub fn checkout(from: &BStr, to: &BStr) -> BString {
+        let mut out = BString::from("checkout: moving from ");
+        out.extend_from_slice(from);
+        out.push_str(b" to ");
+        out.extend_from_slice(to);
+        out
+    }
+
+    fn commit_type_by_parents(count: usize) -> Option<&'static str> {
+        Some(match count {
+            0 => "initial",
+            1 => return None,
+            _two_or_more => "merge",
+        })
+    }
+}
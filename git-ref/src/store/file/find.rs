@@ -8,6 +8,7 @@ pub use error::Error;
 
 use crate::{
     file,
+    store::NameCollisions,
     store_impl::{
         file::{loose, path_to_name},
         packed,
@@ -152,6 +153,16 @@ impl file::Store {
             }
             Some(c) => c,
         };
+        if self.name_collisions == NameCollisions::Report
+            && !self
+                .is_stored_with_exact_name(&relative_path)
+                .map_err(|err| Error::ReadFileContents {
+                    err,
+                    path: relative_path.clone(),
+                })?
+        {
+            return Err(Error::NameCollision { relative_path });
+        }
         Ok(Some({
             let full_name = path_to_name(&relative_path);
             loose::Reference::try_from_path(FullName(full_name.into_owned()), &contents)
@@ -167,15 +178,41 @@ impl file::Store {
     }
 }
 
+fn is_per_worktree(name: &Path) -> bool {
+    !name.starts_with("refs")
+        || name.starts_with("refs/bisect")
+        || name.starts_with("refs/worktree")
+        || name.starts_with("refs/rewritten")
+}
+
 impl file::Store {
     /// Implements the logic required to transform a fully qualified refname into a filesystem path
     pub(crate) fn reference_path(&self, name: &Path) -> PathBuf {
         match &self.namespace {
-            None => self.base.join(name),
-            Some(namespace) => self.base.join(namespace.to_path()).join(name),
+            None => self.base_for(name).join(name),
+            Some(namespace) => self.base_for(name).join(namespace.to_path()).join(name),
         }
     }
 
+    /// Return the directory in which the reference or reflog of the fully qualified refname `name` is stored.
+    ///
+    /// Linked work trees keep `HEAD`, pseudo-refs and references in `refs/bisect/`, `refs/worktree/` and `refs/rewritten/`
+    /// in their own directory, whereas all other references are shared through the common directory.
+    pub(crate) fn base_for(&self, name: &Path) -> &Path {
+        match &self.common_dir {
+            Some(common_dir) if self.namespace.is_some() || !is_per_worktree(name) => common_dir,
+            _ => &self.base,
+        }
+    }
+
+    /// Return `path` relative to the directory it is stored in as obtained by [`reference_path()`][Self::reference_path()].
+    pub(crate) fn relative_to_base<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(&self.base)
+            .ok()
+            .filter(|name| !name.starts_with("..") && self.base_for(name) == self.base)
+            .or_else(|| path.strip_prefix(self.common_dir_resolved()).ok())
+    }
+
     /// Read the file contents with a verified full reference path and return it in the given vector if possible.
     pub(crate) fn ref_contents(&self, relative_path: &Path) -> std::io::Result<Option<Vec<u8>>> {
         let mut buf = Vec::new();
@@ -316,6 +353,9 @@ mod error {
                 display("The reference at '{}' could not be instantiated", relative_path.display())
                 source(err)
             }
+            NameCollision{ relative_path: PathBuf } {
+                display("The reference at '{}' was found under a different name on a case-insensitive or unicode-normalizing filesystem", relative_path.display())
+            }
             PackedRef(err: packed::find::Error) {
                 display("A packed ref lookup failed")
                 from()
@@ -24,7 +24,14 @@ impl SortedLoosePaths {
         base: impl Into<PathBuf>,
         filename_prefix: Option<BString>,
     ) -> Self {
-        let file_walk = git_features::fs::walkdir_sorted_new(path).into_iter();
+        let file_walk = git_features::fs::walkdir_with_options(
+            path,
+            git_features::fs::WalkOptions {
+                sorted: true,
+                ..Default::default()
+            },
+        )
+        .into_iter();
         SortedLoosePaths {
             base: base.into(),
             filename_prefix,
@@ -160,19 +167,23 @@ impl file::Store {
         if !refs.is_dir() {
             return Err(std::io::ErrorKind::NotFound.into());
         }
-        Ok(Loose::at_root(refs, self.base.clone()))
+        Ok(Loose::at_root(refs, self.common_dir_resolved().to_owned()))
     }
 
     /// Return an iterator over all loose references that start with the given `prefix`.
     ///
     /// Otherwise it's similar to [`loose_iter()`][file::Store::loose_iter()].
     pub fn loose_iter_prefixed(&self, prefix: impl AsRef<Path>) -> std::io::Result<Loose> {
-        let (root, remainder) = self.validate_prefix(&self.base, prefix.as_ref())?;
-        Ok(Loose::at_root_with_filename_prefix(root, self.base.clone(), remainder))
+        let (root, remainder) = self.validate_prefix(self.common_dir_resolved(), prefix.as_ref())?;
+        Ok(Loose::at_root_with_filename_prefix(
+            root,
+            self.common_dir_resolved().to_owned(),
+            remainder,
+        ))
     }
 
     pub(in crate::store_impl::file) fn refs_dir(&self) -> PathBuf {
-        self.base.join("refs")
+        self.common_dir_resolved().join("refs")
     }
     pub(in crate::store_impl::file) fn validate_prefix(
         &self,
@@ -33,6 +33,9 @@ mod init {
         /// Create a new instance at the given `git_dir`, which commonly is a standard git repository with a
         /// `refs/` subdirectory.
         /// The `object_hash` defines which kind of hash we should recognize.
+        ///
+        /// [Name collisions][crate::store::NameCollisions] are ignored, which should be changed on filesystems that
+        /// are case-insensitive or normalize unicode.
         pub fn at(
             git_dir: impl Into<PathBuf>,
             write_reflog: crate::file::WriteReflog,
@@ -40,11 +43,30 @@ mod init {
         ) -> Self {
             file::Store {
                 base: git_dir.into(),
+                common_dir: None,
                 write_reflog,
                 namespace: None,
+                name_collisions: crate::store::NameCollisions::Ignore,
                 packed: Default::default(),
                 object_hash,
             }
         }
+
+        /// Like [`at()`][file::Store::at()], but for the `git_dir` of a linked work tree whose shared references, including
+        /// packed references, are stored in `common_dir`.
+        ///
+        /// Only `HEAD` and other per-worktree references, like those in `refs/bisect/` and `refs/worktree/`,
+        /// as well as their reflogs, are stored in `git_dir`. Note that iterators only see the shared references.
+        pub fn for_linked_worktree(
+            git_dir: impl Into<PathBuf>,
+            common_dir: impl Into<PathBuf>,
+            write_reflog: crate::file::WriteReflog,
+            object_hash: git_hash::Kind,
+        ) -> Self {
+            file::Store {
+                common_dir: Some(common_dir.into()),
+                ..file::Store::at(git_dir, write_reflog, object_hash)
+            }
+        }
     }
 }
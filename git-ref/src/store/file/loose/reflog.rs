@@ -173,30 +173,26 @@ pub mod create_or_update {
         }
 
         fn reflock_resource_full_name(&self, reflock: &git_lock::Marker) -> PathBuf {
-            reflock
-                .resource_path()
-                .strip_prefix(&self.base)
+            self.relative_to_base(&reflock.resource_path())
                 .expect("lock must be held within this store")
                 .to_owned()
         }
 
         fn reflock_resource_to_log_path(&self, reflock: &git_lock::Marker) -> PathBuf {
             self.reflog_path_inner(
-                reflock
-                    .resource_path()
-                    .strip_prefix(&self.base)
+                self.relative_to_base(&reflock.resource_path())
                     .expect("lock must be held within this store"),
             )
         }
 
         /// Returns the base and a full path (including the base) to the reflog for a ref of the given `full_name`
         pub(in crate::store_impl::file::loose::reflog) fn reflog_path_inner(&self, full_name: &Path) -> PathBuf {
-            self.reflog_root().join(full_name)
+            self.reflog_root(full_name).join(full_name)
         }
 
-        /// Returns the base paths for all reflogs
-        pub(in crate::store_impl::file) fn reflog_root(&self) -> PathBuf {
-            self.base.join("logs")
+        /// Returns the base path for the reflog of `full_name`, which is shared by all reflogs stored alongside it
+        pub(in crate::store_impl::file) fn reflog_root(&self, full_name: &Path) -> PathBuf {
+            self.base_for(full_name).join("logs")
         }
     }
 
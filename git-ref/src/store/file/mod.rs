@@ -5,7 +5,11 @@ use std::{
 
 use git_features::threading::{MutableOnDemand, OwnShared};
 
-use crate::{bstr::BStr, store::WriteReflog, Namespace};
+use crate::{
+    bstr::BStr,
+    store::{NameCollisions, WriteReflog},
+    Namespace,
+};
 
 /// A store for reference which uses plain files.
 ///
@@ -17,6 +21,10 @@ pub struct Store {
     ///
     /// Typical base paths are `.git` repository folders.
     base: PathBuf,
+    /// The directory with references shared among all work trees, if `base` is the `git` directory of a linked work tree.
+    ///
+    /// If set, only `HEAD` and other per-worktree references are stored in `base`.
+    common_dir: Option<PathBuf>,
     /// The kind of hash to assume in a couple of situations. Note that currently we are able to read any valid hash from files
     /// which might want to change one day.
     object_hash: git_hash::Kind,
@@ -25,6 +33,8 @@ pub struct Store {
     pub write_reflog: WriteReflog,
     /// The namespace to use for edits and reads
     pub namespace: Option<Namespace>,
+    /// The way to handle loose references which are found under a name differing from the requested one.
+    pub name_collisions: NameCollisions,
     /// A packed buffer which can be mapped in one version and shared as such.
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
@@ -41,6 +51,17 @@ mod access {
         pub fn base(&self) -> &Path {
             &self.base
         }
+
+        /// Return the directory with references shared among all work trees, or `None` if this isn't a linked work tree.
+        pub fn common_dir(&self) -> Option<&Path> {
+            self.common_dir.as_deref()
+        }
+
+        /// Return the directory with references shared among all work trees, which is our [`base()`][Self::base()]
+        /// unless this is a linked work tree.
+        pub fn common_dir_resolved(&self) -> &Path {
+            self.common_dir.as_deref().unwrap_or(&self.base)
+        }
     }
 }
 
@@ -50,6 +71,8 @@ pub struct Transaction<'s> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs,
+    hook: Option<Box<transaction::HookFn>>,
+    break_stale_locks: Option<transaction::BreakStaleLocks>,
 }
 
 pub(in crate::store_impl::file) fn path_to_name<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
@@ -63,6 +86,7 @@ pub(in crate::store_impl::file) fn path_to_name<'a>(path: impl Into<Cow<'a, Path
 
 ///
 pub mod loose;
+mod name_collisions;
 mod overlay_iter;
 
 ///
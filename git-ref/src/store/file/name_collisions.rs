@@ -0,0 +1,56 @@
+use std::{
+    fs,
+    path::{Component, Path},
+};
+
+use crate::{file, store::NameCollisions};
+
+impl NameCollisions {
+    /// Determine the policy suitable for the filesystem containing the existing and writable directory `dir`, typically
+    /// the `.git` directory, by writing probe files and checking if they can be accessed under aliased names.
+    ///
+    /// This returns [`NameCollisions::Report`] if the filesystem is case-insensitive or normalizes unicode.
+    pub fn probe(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let dir = dir.as_ref();
+        for (name, alias) in &[("Case", "cASE"), ("\u{e9}", "e\u{301}")] {
+            let name_with_prefix = |name: &str| format!(".git-ref-probe-{}-{}", std::process::id(), name);
+            let probe = dir.join(name_with_prefix(name));
+            fs::OpenOptions::new().write(true).create_new(true).open(&probe)?;
+            let is_aliased = dir.join(name_with_prefix(alias)).symlink_metadata().is_ok();
+            fs::remove_file(&probe)?;
+            if is_aliased {
+                return Ok(NameCollisions::Report);
+            }
+        }
+        Ok(NameCollisions::Ignore)
+    }
+}
+
+impl file::Store {
+    /// Return true if the existing loose reference at `relative_path` is stored on disk with exactly the given name,
+    /// and not under a name the filesystem considers equal.
+    pub(in crate::store_impl::file) fn is_stored_with_exact_name(&self, relative_path: &Path) -> std::io::Result<bool> {
+        let mut dir = self.base_for(relative_path).to_owned();
+        if let Some(namespace) = &self.namespace {
+            dir.push(namespace.to_path());
+        }
+        for component in relative_path.components() {
+            let component = match component {
+                Component::Normal(component) => component,
+                _ => continue,
+            };
+            let mut has_exact_name = false;
+            for entry in fs::read_dir(&dir)? {
+                if entry?.file_name() == component {
+                    has_exact_name = true;
+                    break;
+                }
+            }
+            if !has_exact_name {
+                return Ok(false);
+            }
+            dir.push(component);
+        }
+        Ok(true)
+    }
+}
@@ -10,8 +10,9 @@ impl file::Store {
     pub(crate) fn packed_transaction(
         &self,
         lock_mode: git_lock::acquire::Fail,
+        break_stale: Option<&mut file::transaction::BreakStaleLocks>,
     ) -> Result<packed::Transaction, transaction::Error> {
-        let lock = git_lock::File::acquire_to_update_resource(self.packed_refs_path(), lock_mode, None)?;
+        let lock = file::transaction::lock_file(self.packed_refs_path(), lock_mode, None, break_stale)?;
         // We 'steal' the possibly existing packed buffer which may safe time if it's already there and fresh.
         // If nothing else is happening, nobody will get to see the soon stale buffer either, but if so, they will pay
         // for reloading it. That seems preferred over always loading up a new one.
@@ -42,7 +43,7 @@ impl file::Store {
 
     /// Return the path at which packed-refs would usually be stored
     pub fn packed_refs_path(&self) -> PathBuf {
-        self.base.join("packed-refs")
+        self.common_dir_resolved().join("packed-refs")
     }
 }
 
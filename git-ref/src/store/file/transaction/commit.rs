@@ -1,5 +1,10 @@
+use std::collections::BTreeSet;
+
 use crate::{
-    store_impl::file::{transaction::PackedRefs, Transaction},
+    store_impl::file::{
+        transaction::{HookStage, PackedRefs},
+        Transaction,
+    },
     transaction::{Change, LogChange, RefEdit, RefLog},
     Target,
 };
@@ -30,8 +35,10 @@ impl<'s> Transaction<'s> {
             PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(_)
         );
 
+        let split_parents: BTreeSet<_> = updates.iter().filter_map(|edit| edit.parent_index).collect();
+
         // Perform updates first so live commits remain referenced
-        for change in updates.iter_mut() {
+        for (cid, change) in updates.iter_mut().enumerate() {
             assert!(!change.update.deref, "Deref mode is turned into splits and turned off");
             match &change.update.change {
                 // reflog first, then reference
@@ -50,7 +57,14 @@ impl<'s> Transaction<'s> {
                                     _ => None,
                                 }
                                 .or(change.leaf_referent_previous_oid);
-                                let do_update = previous.as_ref().map_or(true, |previous| previous != new_oid);
+                                // Reflog-only edits made by the caller are always logged, even if the object doesn't change,
+                                // just like `git` does when switching to a new branch created at the current commit.
+                                // This doesn't apply to symbolic refs whose referents receive the actual update.
+                                let is_reflog_only_edit = log.mode == RefLog::Only
+                                    && change.parent_index.is_none()
+                                    && !split_parents.contains(&cid);
+                                let do_update = is_reflog_only_edit
+                                    || previous.as_ref().map_or(true, |previous| previous != new_oid);
                                 if do_update {
                                     self.store.reflog_create_or_append(
                                         &lock,
@@ -96,7 +110,6 @@ impl<'s> Transaction<'s> {
             }
         }
 
-        let reflog_root = self.store.reflog_root();
         for change in updates.iter_mut() {
             match &change.update.change {
                 Change::Update { .. } => {}
@@ -114,7 +127,7 @@ impl<'s> Transaction<'s> {
                     } else {
                         git_tempfile::remove_dir::empty_upward_until_boundary(
                             reflog_path.parent().expect("never without parent"),
-                            &reflog_root,
+                            &self.store.reflog_root(change.update.name.to_path()),
                         )
                         .ok();
                     }
@@ -151,14 +164,23 @@ impl<'s> Transaction<'s> {
                 drop(lock)
             }
         }
-        Ok(updates.into_iter().map(|edit| edit.update).collect())
+        let edits: Vec<_> = updates.into_iter().map(|edit| edit.update).collect();
+        if let Some(mut hook) = self.hook {
+            if let Err(err) = hook(HookStage::Committed, &edits) {
+                return Err(Error::Hook { err, edits });
+            }
+        }
+        Ok(edits)
     }
 }
 mod error {
     use git_object::bstr::BString;
     use quick_error::quick_error;
 
-    use crate::store_impl::{file, packed};
+    use crate::{
+        store_impl::{file, packed},
+        transaction::RefEdit,
+    };
 
     quick_error! {
         /// The error returned by various [`Transaction`][super::Transaction] methods.
@@ -190,6 +212,10 @@ mod error {
                 from()
                 source(err)
             }
+            Hook{ err: Box<dyn std::error::Error + Send + Sync + 'static>, edits: Vec<RefEdit> } {
+                display("The transaction hook failed after all edits were committed")
+                source(&**err)
+            }
         }
     }
 }
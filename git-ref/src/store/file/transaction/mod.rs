@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use git_hash::ObjectId;
 use git_object::bstr::BString;
 
@@ -15,6 +17,33 @@ pub type FindObjectFn =
         &mut Vec<u8>,
     ) -> Result<Option<git_object::Kind>, Box<dyn std::error::Error + Send + Sync + 'static>>;
 
+/// A function called with the [stage][HookStage] a transaction is in along with all of its edits, similar to the
+/// `reference-transaction` hook of `git`.
+///
+/// Returning an error in the [`Prepared`][HookStage::Prepared] stage aborts the transaction, and in the
+/// [`Committed`][HookStage::Committed] stage it's returned by [`commit()`][Transaction::commit()] along with the performed edits.
+pub type HookFn = dyn FnMut(HookStage, &[RefEdit]) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+/// A function called with each stale lock file that is removed to acquire a lock during a transaction, before its removal.
+pub type AuditStaleLockFn = dyn FnMut(&git_lock::stale::Broken);
+
+/// The stage of a transaction at which a [`HookFn`] is called.
+///
+/// Unlike `git`, there is no `aborted` stage. If preparing a transaction fails or a prepared transaction is dropped, the hook
+/// isn't called again and all locks are released without any reference being changed.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum HookStage {
+    /// All references are locked and the edits are validated, but nothing was changed yet.
+    ///
+    /// The edits include those derived from symbolic references, and can still be rejected by returning an error.
+    Prepared,
+    /// All edits were applied, with their expected values set to the values the references had previously.
+    ///
+    /// Errors returned at this stage are passed on by [`commit()`][Transaction::commit()] along with the performed edits,
+    /// as these can't be undone anymore.
+    Committed,
+}
+
 /// How to handle packed refs during a transaction
 pub enum PackedRefs {
     /// Only propagate deletions of references. This is the default
@@ -76,6 +105,8 @@ impl file::Store {
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            hook: None,
+            break_stale_locks: None,
         }
     }
 }
@@ -86,6 +117,67 @@ impl<'s> Transaction<'s> {
         self.packed_refs = packed_refs;
         self
     }
+
+    /// Call `hook` once the transaction was prepared and once it was committed, to allow auditing edits or rejecting
+    /// them before they are applied.
+    pub fn hook(mut self, hook: Box<HookFn>) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    /// Remove lock files of references and of `packed-refs` which are stale according to `policy` instead of failing to
+    /// acquire them, passing each one to `audit` before it is removed.
+    ///
+    /// The locks acquired by this transaction then record the id of the current process, to be breakable by others if it
+    /// crashes.
+    pub fn break_stale_locks(mut self, policy: git_lock::stale::Policy, audit: Box<AuditStaleLockFn>) -> Self {
+        self.break_stale_locks = Some(BreakStaleLocks { policy, audit });
+        self
+    }
+}
+
+/// The way stale locks are broken during a transaction.
+pub(crate) struct BreakStaleLocks {
+    policy: git_lock::stale::Policy,
+    audit: Box<AuditStaleLockFn>,
+}
+
+/// Acquire a lock to update the resource at `path`, breaking it if it's stale according to `break_stale`.
+pub(crate) fn lock_file(
+    path: impl AsRef<Path>,
+    mode: git_lock::acquire::Fail,
+    boundary_directory: Option<PathBuf>,
+    break_stale: Option<&mut BreakStaleLocks>,
+) -> Result<git_lock::File, git_lock::acquire::Error> {
+    match break_stale {
+        Some(BreakStaleLocks { policy, audit }) => git_lock::File::acquire_to_update_resource_or_break_stale(
+            path,
+            mode,
+            boundary_directory,
+            *policy,
+            |broken| audit(broken),
+        ),
+        None => git_lock::File::acquire_to_update_resource(path, mode, boundary_directory),
+    }
+}
+
+/// Acquire a lock to hold the resource at `path`, breaking it if it's stale according to `break_stale`.
+pub(crate) fn lock_marker(
+    path: impl AsRef<Path>,
+    mode: git_lock::acquire::Fail,
+    boundary_directory: Option<PathBuf>,
+    break_stale: Option<&mut BreakStaleLocks>,
+) -> Result<git_lock::Marker, git_lock::acquire::Error> {
+    match break_stale {
+        Some(BreakStaleLocks { policy, audit }) => git_lock::Marker::acquire_to_hold_resource_or_break_stale(
+            path,
+            mode,
+            boundary_directory,
+            *policy,
+            |broken| audit(broken),
+        ),
+        None => git_lock::Marker::acquire_to_hold_resource(path, mode, boundary_directory),
+    }
 }
 
 ///
@@ -1,21 +1,23 @@
 use crate::{
     packed,
+    store::NameCollisions,
     store_impl::{
         file,
         file::{
             loose,
-            transaction::{Edit, PackedRefs},
+            transaction::{lock_file, lock_marker, BreakStaleLocks, Edit, HookStage, PackedRefs},
             Transaction,
         },
     },
     transaction::{Change, LogChange, RefEdit, RefEditsExt, RefLog},
-    Reference, Target,
+    FullName, Reference, Target,
 };
 
 impl<'s> Transaction<'s> {
     fn lock_ref_and_apply_change(
         store: &file::Store,
         lock_fail_mode: git_lock::acquire::Fail,
+        break_stale: Option<&mut BreakStaleLocks>,
         packed: Option<&packed::Buffer>,
         change: &mut Edit,
     ) -> Result<(), Error> {
@@ -29,6 +31,17 @@ impl<'s> Transaction<'s> {
         let existing_ref = store
             .ref_contents(relative_path.as_ref())
             .map_err(Error::from)
+            .and_then(|maybe_loose| match maybe_loose {
+                Some(_)
+                    if store.name_collisions == NameCollisions::Report
+                        && !store.is_stored_with_exact_name(relative_path.as_ref())? =>
+                {
+                    Err(Error::NameCollision {
+                        full_name: change.name(),
+                    })
+                }
+                _ => Ok(maybe_loose),
+            })
             .and_then(|maybe_loose| {
                 maybe_loose
                     .map(|buf| {
@@ -50,12 +63,14 @@ impl<'s> Transaction<'s> {
                 (None, None) => Ok(None),
                 (maybe_loose, _) => Ok(maybe_loose),
             });
+        let mut leaf_referent_previous_oid = None;
         let lock = match &mut change.update.change {
             Change::Delete { expected, .. } => {
-                let lock = git_lock::Marker::acquire_to_hold_resource(
+                let lock = lock_marker(
                     store.reference_path(relative_path),
                     lock_fail_mode,
-                    Some(store.base.to_owned()),
+                    Some(store.base_for(relative_path).to_owned()),
+                    break_stale,
                 )
                 .map_err(|err| Error::LockAcquire {
                     err,
@@ -96,11 +111,12 @@ impl<'s> Transaction<'s> {
 
                 lock
             }
-            Change::Update { expected, new, .. } => {
-                let mut lock = git_lock::File::acquire_to_update_resource(
+            Change::Update { expected, new, log } => {
+                let mut lock = lock_file(
                     store.reference_path(relative_path),
                     lock_fail_mode,
-                    Some(store.base.to_owned()),
+                    Some(store.base_for(relative_path).to_owned()),
+                    break_stale,
                 )
                 .map_err(|err| Error::LockAcquire {
                     err,
@@ -150,6 +166,11 @@ impl<'s> Transaction<'s> {
                 };
 
                 if let Some(existing) = existing_ref {
+                    // Reflog-only updates of symbolic refs, like `HEAD` when switching branches, log the movement of
+                    // the object they point to.
+                    if let (RefLog::Only, Target::Symbolic(referent)) = (log.mode, &existing.target) {
+                        leaf_referent_previous_oid = Self::peel_symbolic_ref(store, packed, referent.clone());
+                    }
                     *expected = PreviousValue::MustExistAndMatch(existing.target);
                 };
 
@@ -162,8 +183,27 @@ impl<'s> Transaction<'s> {
             }
         };
         change.lock = Some(lock);
+        if change.leaf_referent_previous_oid.is_none() {
+            change.leaf_referent_previous_oid = leaf_referent_previous_oid;
+        }
         Ok(())
     }
+
+    /// Follow the symbolic ref `name` until it points to an object, or return `None` if any ref along the way doesn't exist.
+    fn peel_symbolic_ref(
+        store: &file::Store,
+        packed: Option<&packed::Buffer>,
+        mut name: FullName,
+    ) -> Option<git_hash::ObjectId> {
+        const MAX_DEPTH: usize = 5;
+        for _ in 0..MAX_DEPTH {
+            match store.find_existing_inner(name.to_partial(), packed).ok()?.target {
+                Target::Peeled(oid) => return Some(oid),
+                Target::Symbolic(referent) => name = referent,
+            }
+        }
+        None
+    }
 }
 
 impl<'s> Transaction<'s> {
@@ -261,7 +301,7 @@ impl<'s> Transaction<'s> {
                 let packed_transaction: Option<_> = if maybe_updates_for_packed_refs.unwrap_or(0) > 0 {
                     // We have to create a packed-ref even if it doesn't exist
                     self.store
-                        .packed_transaction(lock_fail_mode)
+                        .packed_transaction(lock_fail_mode, self.break_stale_locks.as_mut())
                         .map_err(|err| match err {
                             file::packed::transaction::Error::BufferOpen(err) => Error::from(err),
                             file::packed::transaction::Error::TransactionLock(err) => {
@@ -275,7 +315,7 @@ impl<'s> Transaction<'s> {
                     self.store
                         .assure_packed_refs_uptodate()?
                         .map(|p| {
-                            p.into_transaction(lock_fail_mode)
+                            p.into_transaction(lock_fail_mode, self.break_stale_locks.as_mut())
                                 .map_err(Error::PackedTransactionAcquire)
                         })
                         .transpose()?
@@ -300,6 +340,7 @@ impl<'s> Transaction<'s> {
             if let Err(err) = Self::lock_ref_and_apply_change(
                 self.store,
                 lock_fail_mode,
+                self.break_stale_locks.as_mut(),
                 self.packed_transaction.as_ref().and_then(|t| t.buffer()),
                 change,
             ) {
@@ -338,6 +379,10 @@ impl<'s> Transaction<'s> {
                 }
             }
         }
+        if let Some(hook) = self.hook.as_mut() {
+            let edits: Vec<_> = updates.iter().map(|edit| edit.update.clone()).collect();
+            hook(HookStage::Prepared, &edits).map_err(Error::Hook)?;
+        }
         self.updates = Some(updates);
         Ok(self)
     }
@@ -401,6 +446,13 @@ mod error {
             ReferenceOutOfDate { full_name: BString, expected: Target, actual: Target } {
                 display("The reference '{}' should have content {}, actual content was {}", full_name, expected, actual)
             }
+            Hook(err: Box<dyn std::error::Error + Send + Sync + 'static>) {
+                display("The transaction hook rejected the prepared edits")
+                source(&**err)
+            }
+            NameCollision { full_name: BString } {
+                display("The reference '{}' collides with a differently named loose reference on a case-insensitive or unicode-normalizing filesystem", full_name)
+            }
             ReferenceDecode(err: file::loose::reference::decode::Error) {
                 display("Could not read reference")
                 from()
@@ -216,8 +216,9 @@ impl packed::Buffer {
     pub(crate) fn into_transaction(
         self: OwnShared<Self>,
         lock_mode: git_lock::acquire::Fail,
+        break_stale: Option<&mut crate::file::transaction::BreakStaleLocks>,
     ) -> Result<packed::Transaction, git_lock::acquire::Error> {
-        let lock = git_lock::File::acquire_to_update_resource(&self.path, lock_mode, None)?;
+        let lock = crate::file::transaction::lock_file(&self.path, lock_mode, None, break_stale)?;
         Ok(packed::Transaction {
             buffer: Some(self),
             lock: Some(lock),
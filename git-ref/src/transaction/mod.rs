@@ -5,6 +5,7 @@
 //!
 //! * create or update reference
 //! * delete references
+//! * append to the reflog of references without changing them
 //!
 //! The following guarantees are made:
 //!
@@ -127,7 +128,10 @@ pub struct RefEdit {
 pub enum RefLog {
     /// Delete or update the reference and the log
     AndReference,
-    /// Delete or update only the reflog
+    /// Delete or update only the reflog, leaving the reference itself unchanged.
+    ///
+    /// Updates of symbolic references log the movement from the object they currently point to to the new one,
+    /// as is needed to record switching `HEAD` from one branch to another.
     Only,
 }
 
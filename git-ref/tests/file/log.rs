@@ -191,3 +191,33 @@ mod iter {
         }
     }
 }
+
+mod message {
+    use git_ref::log::message;
+
+    #[test]
+    fn commit() {
+        assert_eq!(
+            message::commit("commit", "the subject\n\nthe body".into(), 0),
+            "commit (initial): the subject"
+        );
+        assert_eq!(message::commit("other", "the subject".into(), 1), "other: the subject");
+        assert_eq!(
+            message::commit("rebase", "the subject".into(), 2),
+            "rebase (merge): the subject"
+        );
+    }
+
+    #[test]
+    fn branch_created_from() {
+        assert_eq!(message::branch_created_from("main".into()), "branch: Created from main");
+    }
+
+    #[test]
+    fn checkout() {
+        assert_eq!(
+            message::checkout("main".into(), "feature".into()),
+            "checkout: moving from main to feature"
+        );
+    }
+}
@@ -155,3 +155,60 @@ mod loose {
         Ok(())
     }
 }
+
+mod name_collisions {
+    use git_ref::store::NameCollisions;
+
+    use crate::file::store;
+
+    fn filesystem_aliases_names() -> crate::Result<bool> {
+        let dir = git_testtools::tempfile::tempdir()?;
+        Ok(NameCollisions::probe(dir.path())? == NameCollisions::Report)
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn probing_detects_case_sensitive_filesystems() -> crate::Result {
+        assert!(!filesystem_aliases_names()?);
+        Ok(())
+    }
+
+    #[test]
+    fn reporting_them_does_not_affect_exactly_named_references() -> crate::Result {
+        let mut store = store()?;
+        store.name_collisions = NameCollisions::Report;
+        for partial_name in &["dt1", "heads/dt1", "HEAD", "origin", "origin/main", "refs/heads/main"] {
+            assert!(
+                store.try_find_loose(*partial_name)?.is_some(),
+                "{} exists",
+                partial_name
+            );
+        }
+        assert!(store.try_find_loose("does-not-exist")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn they_are_reported_if_a_reference_is_found_under_a_different_name() -> crate::Result {
+        if !filesystem_aliases_names()? {
+            return Ok(());
+        }
+        let mut store = store()?;
+        assert_eq!(
+            store.find_loose("refs/heads/MAIN")?.name.as_bstr(),
+            "refs/heads/MAIN",
+            "by default, we trust the filesystem and see the aliased reference under the requested name"
+        );
+
+        store.name_collisions = NameCollisions::Report;
+        assert!(matches!(
+            store.try_find_loose("refs/heads/MAIN"),
+            Err(git_ref::file::find::Error::NameCollision { .. })
+        ));
+        assert!(matches!(
+            store.try_find_loose("refs/Heads/main"),
+            Err(git_ref::file::find::Error::NameCollision { .. })
+        ));
+        Ok(())
+    }
+}
@@ -604,6 +604,55 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
     Ok(())
 }
 
+#[test]
+fn reflog_only_updates_of_symbolic_refs_log_the_movement_of_their_referent() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let head = store.find_loose("HEAD")?;
+    let previous_head_id = hex_to_id("02a7a22d90d7c02fb494ed25551850b868e634f0");
+    let new_id = hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc");
+
+    for (new, message) in &[
+        (new_id, git_ref::log::message::checkout("main".into(), "feature".into())),
+        (
+            previous_head_id,
+            git_ref::log::message::checkout("main".into(), "new-branch".into()),
+        ),
+    ] {
+        let edits = store
+            .transaction()
+            .prepare(
+                Some(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::Only,
+                            force_create_reflog: false,
+                            message: message.clone(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(head.target.clone()),
+                        new: Target::Peeled(*new),
+                    },
+                    name: "HEAD".try_into()?,
+                    deref: false,
+                }),
+                Fail::Immediately,
+            )?
+            .commit(&committer())?;
+        assert_eq!(edits.len(), 1, "no split was performed");
+
+        assert_eq!(
+            store.find_loose("HEAD")?.target,
+            head.target,
+            "HEAD still points to the same branch"
+        );
+        assert_eq!(
+            reflog_lines(&store, "HEAD")?.last().expect("at least one line"),
+            &log_line(previous_head_id, *new, message.clone()),
+            "the previous value is the object HEAD points to, and the line is written even if the object doesn't change"
+        );
+    }
+    Ok(())
+}
+
 #[test]
 fn packed_refs_are_looked_up_when_checking_existing_values() -> crate::Result {
     let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
@@ -772,3 +821,199 @@ fn packed_refs_creation_with_packed_refs_mode_leave_keeps_original_loose_refs()
     );
     Ok(())
 }
+
+#[test]
+fn reference_names_aliased_by_the_filesystem_cause_failure_if_collisions_are_reported() -> crate::Result {
+    let (_keep, mut store) = store_writable("make_repo_for_reflog.sh")?;
+    store.name_collisions = git_ref::store::NameCollisions::Report;
+    let edit = |name: &str| -> crate::Result<RefEdit> {
+        Ok(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                new: Target::Peeled(hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc")),
+                expected: PreviousValue::Any,
+            },
+            name: name.try_into()?,
+            deref: false,
+        })
+    };
+
+    store
+        .transaction()
+        .prepare(Some(edit("refs/heads/main")?), Fail::Immediately)?
+        .commit(&committer())?;
+
+    if git_ref::store::NameCollisions::probe(store.base())? == git_ref::store::NameCollisions::Report {
+        match store
+            .transaction()
+            .prepare(Some(edit("refs/heads/Main")?), Fail::Immediately)
+        {
+            Err(transaction::prepare::Error::NameCollision { full_name }) => assert_eq!(full_name, "refs/heads/Main"),
+            _ => unreachable!("the aliased reference is detected"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn hook_is_called_with_all_edits_when_prepared_and_committed() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let calls = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let new = Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242"));
+
+    let edits = store
+        .transaction()
+        .hook(Box::new({
+            let calls = calls.clone();
+            move |stage, edits| {
+                calls.lock().unwrap().push((stage, edits.to_vec()));
+                Ok(())
+            }
+        }))
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    new: new.clone(),
+                    expected: PreviousValue::MustNotExist,
+                },
+                name: "refs/heads/main".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+        )?
+        .commit(&committer())?;
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            (transaction::HookStage::Prepared, edits.clone()),
+            (transaction::HookStage::Committed, edits)
+        ],
+        "the edits don't change between the stages if there was no previous value"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_can_reject_prepared_edits_to_abort_the_transaction() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let res = store
+        .transaction()
+        .hook(Box::new(|stage, _edits| {
+            assert_eq!(stage, transaction::HookStage::Prepared, "commit is never reached");
+            Err("policy forbids it".into())
+        }))
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    new: Target::Peeled(git_hash::Kind::Sha1.null()),
+                    expected: PreviousValue::Any,
+                },
+                name: "refs/heads/main".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+        );
+
+    match res {
+        Err(err @ transaction::prepare::Error::Hook(_)) => {
+            assert_eq!(
+                std::error::Error::source(&err).map(ToString::to_string),
+                Some("policy forbids it".into())
+            );
+        }
+        _ => unreachable!("the hook error is passed on"),
+    }
+    assert!(
+        store.try_find_loose("refs/heads/main")?.is_none(),
+        "the reference wasn't created"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_errors_after_commit_are_passed_on() -> crate::Result {
+    let (_keep, store) = empty_store()?;
+    let res = store
+        .transaction()
+        .hook(Box::new(|stage, _edits| match stage {
+            transaction::HookStage::Prepared => Ok(()),
+            transaction::HookStage::Committed => Err("audit log unavailable".into()),
+        }))
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    new: Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242")),
+                    expected: PreviousValue::Any,
+                },
+                name: "refs/heads/main".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+        )?
+        .commit(&committer());
+
+    match res {
+        Err(transaction::commit::Error::Hook { err, edits }) => {
+            assert_eq!(err.to_string(), "audit log unavailable");
+            assert_eq!(edits.len(), 1, "the performed edits are returned with the error");
+            assert_eq!(edits[0].name.as_bstr(), "refs/heads/main");
+        }
+        _ => unreachable!("the hook error is passed on"),
+    }
+    assert!(
+        store.try_find_loose("refs/heads/main")?.is_some(),
+        "the edits were applied nonetheless"
+    );
+    Ok(())
+}
+
+#[test]
+fn stale_locks_can_be_broken_and_are_audited() -> crate::Result {
+    let (dir, store) = empty_store()?;
+    let lock_path = dir.path().join("refs").join("heads").join("main.lock");
+    std::fs::create_dir_all(lock_path.parent().expect("parent"))?;
+    std::fs::write(&lock_path, b"left behind")?;
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    let edit = RefEdit {
+        change: Change::Update {
+            log: LogChange::default(),
+            new: Target::Peeled(hex_to_id("28ce6a8b26aa170e1de65536fe8abe1832bd3242")),
+            expected: PreviousValue::MustNotExist,
+        },
+        name: "refs/heads/main".try_into()?,
+        deref: false,
+    };
+    assert!(
+        store
+            .transaction()
+            .prepare(Some(edit.clone()), Fail::Immediately)
+            .is_err(),
+        "locks aren't broken by default"
+    );
+
+    let broken = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    store
+        .transaction()
+        .break_stale_locks(
+            git_lock::stale::Policy {
+                older_than: Some(std::time::Duration::from_millis(1)),
+                ..Default::default()
+            },
+            Box::new({
+                let broken = broken.clone();
+                move |b| broken.lock().unwrap().push(b.lock_path.clone())
+            }),
+        )
+        .prepare(Some(edit), Fail::Immediately)?
+        .commit(&committer())?;
+    assert_eq!(*broken.lock().unwrap(), vec![lock_path]);
+    assert!(
+        store.try_find_loose("refs/heads/main")?.is_some(),
+        "the reference was created"
+    );
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use std::{convert::TryInto, path::Path};
+
+use git_lock::acquire::Fail;
+use git_ref::{
+    file,
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+    Target,
+};
+
+fn worktree_store(root: &Path) -> file::Store {
+    let git_dir = root.join("main").join(".git").join("worktrees").join("wt");
+    file::Store::for_linked_worktree(
+        &git_dir,
+        git_dir.join("..").join(".."),
+        git_ref::store::WriteReflog::Normal,
+        git_hash::Kind::Sha1,
+    )
+}
+
+#[test]
+fn per_worktree_references_are_read_from_the_worktree_and_all_others_from_the_common_dir() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_worktree_repository.sh")?;
+    let store = worktree_store(&dir);
+
+    let head = store.find_loose("HEAD")?;
+    assert_eq!(
+        head.target,
+        Target::Symbolic("refs/heads/wt".try_into()?),
+        "HEAD is the one of the worktree"
+    );
+    let main = store.find_loose("main")?;
+    let bad = store.find_loose("refs/bisect/bad")?;
+    assert_eq!(main.target, bad.target, "both point to the only commit");
+    assert!(store.reflog_exists("refs/heads/wt")?, "shared reflogs are found");
+
+    let names = store
+        .iter()?
+        .all()?
+        .map(|r| r.map(|r| r.name.as_bstr().to_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        names,
+        vec!["refs/heads/main", "refs/heads/wt"],
+        "only shared references are iterated"
+    );
+    Ok(())
+}
+
+#[test]
+fn reporting_name_collisions_does_not_affect_exactly_named_shared_or_per_worktree_references() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_writable("make_worktree_repository.sh")?;
+    let mut store = worktree_store(dir.path());
+    store.name_collisions = git_ref::store::NameCollisions::Report;
+
+    for name in &["HEAD", "main", "refs/heads/wt", "refs/bisect/bad"] {
+        assert!(store.try_find_loose(*name)?.is_some(), "{} exists", name);
+    }
+
+    let id = store.find_loose("main")?.target.into_id();
+    let edits = ["refs/heads/main", "refs/bisect/bad"].iter().map(|name| RefEdit {
+        change: Change::Update {
+            log: LogChange::default(),
+            expected: PreviousValue::MustExistAndMatch(Target::Peeled(id)),
+            new: Target::Peeled(id),
+        },
+        name: (*name).try_into().expect("valid"),
+        deref: false,
+    });
+    store
+        .transaction()
+        .prepare(edits, Fail::Immediately)?
+        .commit(&git_actor::Signature {
+            name: "committer".into(),
+            email: "committer@example.com".into(),
+            time: git_actor::Time::new(1234, 0),
+        })?;
+    Ok(())
+}
+
+#[test]
+fn edits_are_written_to_the_directory_the_reference_belongs_to() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_writable("make_worktree_repository.sh")?;
+    let store = worktree_store(dir.path());
+    let id = store.find_loose("main")?.target.into_id();
+
+    let edits = ["refs/heads/new", "refs/bisect/good"].iter().map(|name| RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                force_create_reflog: true,
+                ..Default::default()
+            },
+            expected: PreviousValue::MustNotExist,
+            new: Target::Peeled(id),
+        },
+        name: (*name).try_into().expect("valid"),
+        deref: false,
+    });
+    store
+        .transaction()
+        .prepare(edits, Fail::Immediately)?
+        .commit(&git_actor::Signature {
+            name: "committer".into(),
+            email: "committer@example.com".into(),
+            time: git_actor::Time::new(1234, 0),
+        })?;
+
+    let common_dir = dir.path().join("main").join(".git");
+    let git_dir = common_dir.join("worktrees").join("wt");
+    for (base, name) in &[(&common_dir, "refs/heads/new"), (&git_dir, "refs/bisect/good")] {
+        assert!(base.join(name).is_file(), "{} is stored in {:?}", name, base);
+        assert!(base.join("logs").join(name).is_file(), "so is its reflog");
+    }
+    assert!(!git_dir.join("refs/heads/new").exists());
+    assert!(!common_dir.join("refs/bisect/good").exists());
+    Ok(())
+}
@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q main
+(cd main
+  git checkout -q -b main
+  git commit -q --allow-empty -m c1
+  git worktree add -q -b wt ../wt
+)
+
+(cd wt
+  git update-ref refs/bisect/bad HEAD
+)
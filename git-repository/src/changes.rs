@@ -0,0 +1,260 @@
+//! Types for the changes between trees, the index and the worktree as produced by
+//! [`Repository::diff_tree_to_tree()`][crate::Repository::diff_tree_to_tree()] and its siblings.
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+};
+
+/// A change to a single path, which is the same no matter which two of trees, the index or the worktree were compared.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum Change {
+    /// The entry at `path` was added.
+    Addition {
+        entry_mode: EntryMode,
+        id: ObjectId,
+        path: BString,
+    },
+    /// The entry at `path` was deleted.
+    Deletion {
+        entry_mode: EntryMode,
+        id: ObjectId,
+        path: BString,
+    },
+    /// The entry at `path` changed its mode or content.
+    ///
+    /// When comparing the index with the worktree, `id` is the null id if the content of the file changed
+    /// as files in the worktree aren't hashed, just like `git diff` does.
+    Modification {
+        previous_entry_mode: EntryMode,
+        previous_id: ObjectId,
+        entry_mode: EntryMode,
+        id: ObjectId,
+        path: BString,
+    },
+    /// The entry at `source_path` was deleted and added as `path` with the same or similar content.
+    Rename {
+        source_entry_mode: EntryMode,
+        source_id: ObjectId,
+        source_path: BString,
+        entry_mode: EntryMode,
+        id: ObjectId,
+        path: BString,
+        /// The similarity of the content from `0.0` to `1.0`, with `1.0` meaning it is unchanged.
+        similarity: f32,
+    },
+}
+
+impl Change {
+    /// The path of the entry after the change.
+    pub fn path(&self) -> &BStr {
+        match self {
+            Change::Addition { path, .. }
+            | Change::Deletion { path, .. }
+            | Change::Modification { path, .. }
+            | Change::Rename { path, .. } => path.as_bstr(),
+        }
+    }
+}
+
+/// Options for computing [changes][Change].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// If set, deletions and additions of similar blobs are turned into [renames][Change::Rename], like `git diff -M` does.
+    ///
+    /// Note that there are no renames between the index and the worktree as untracked files are never considered.
+    pub renames: Option<git_diff::rewrites::Options>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            renames: Some(Default::default()),
+        }
+    }
+}
+
+///
+pub mod tree_to_tree {
+    /// The error returned by [`Repository::diff_tree_to_tree()`][crate::Repository::diff_tree_to_tree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Diff(#[from] git_diff::tree::changes::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "git-index", feature = "git-worktree"))]
+pub mod tree_to_index {
+    /// The error returned by [`Repository::diff_tree_to_index()`][crate::Repository::diff_tree_to_index()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] git_index::file::init::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "git-index", feature = "git-worktree"))]
+pub mod index_to_worktree {
+    /// The error returned by [`Repository::diff_index_to_worktree()`][crate::Repository::diff_index_to_worktree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Changes of the worktree can only be obtained in repositories with a worktree")]
+        MissingWorkTree,
+        #[error(transparent)]
+        OpenIndex(#[from] git_index::file::init::Error),
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+        #[error(transparent)]
+        Status(#[from] git_worktree::status::Error),
+    }
+}
+
+/// A [cache for text conversions][git_diff::driver::Cache] which stores them as notes in the repository like git does for
+/// drivers with `diff.<driver>.cachetextconv` set, so that both can reuse each others conversions.
+///
+/// The notes of a driver are kept in the reference returned by
+/// [`Driver::textconv_notes_ref()`][git_diff::driver::Driver::textconv_notes_ref()], pointing to a commit whose message is the
+/// `textconv` program that produced them. If the program changes, all previous conversions are discarded.
+///
+/// Obtain it with [`Repository::textconv_cache()`][crate::Repository::textconv_cache()].
+pub struct TextConvCache<'repo> {
+    pub(crate) repo: &'repo crate::Repository,
+}
+
+mod textconv_cache {
+    use std::convert::TryInto;
+
+    use git_diff::driver::{Cache, Driver};
+    use git_hash::{oid, ObjectId};
+    use git_object::{bstr::ByteSlice, tree, TreeRef};
+    use git_ref::transaction::{Change, LogChange, PreviousValue, RefEdit};
+
+    use super::TextConvCache;
+
+    impl TextConvCache<'_> {
+        /// Return the id of the tree with the notes of `driver` if they were produced by its current `textconv` program.
+        fn notes_tree(&self, driver: &Driver) -> Option<ObjectId> {
+            let program = driver.textconv.as_ref()?;
+            let mut reference = self
+                .repo
+                .try_find_reference(driver.textconv_notes_ref().as_bstr())
+                .ok()??;
+            let commit = reference
+                .peel_to_id_in_place()
+                .ok()?
+                .object()
+                .ok()?
+                .try_into_commit()
+                .ok()?;
+            let is_valid = commit.message_raw().ok()?.trim() == program.trim();
+            is_valid.then(|| commit.tree_id().ok()).flatten()
+        }
+
+        /// Return all entries of the tree with `id`.
+        fn entries(&self, id: ObjectId) -> Option<Vec<tree::Entry>> {
+            let tree = self.repo.find_object(id).ok()?;
+            TreeRef::from_bytes(&tree.data)
+                .ok()
+                .map(|tree| git_object::Tree::from(tree).entries)
+        }
+    }
+
+    impl Cache for TextConvCache<'_> {
+        fn get(&mut self, driver: &Driver, id: &oid) -> Option<Vec<u8>> {
+            let hex = id.to_hex().to_string();
+            let mut tree_id = self.notes_tree(driver)?;
+            // Notes may be spread over subtrees named after the leading bytes of the annotated object, like `ab/cdef…`.
+            let mut consumed = 0;
+            loop {
+                let entries = self.entries(tree_id)?;
+                let remaining = &hex[consumed..];
+                if let Some(note) = entries.iter().find(|e| !e.mode.is_tree() && e.filename == remaining) {
+                    return self.repo.find_object(note.oid).ok().map(|note| note.data.clone());
+                }
+                let fanout = remaining.get(..2)?;
+                tree_id = entries
+                    .iter()
+                    .find(|e| e.mode == tree::EntryMode::Tree && e.filename == fanout)?
+                    .oid;
+                consumed += 2;
+            }
+        }
+
+        fn put(&mut self, driver: &Driver, id: &oid, converted: &[u8]) {
+            let program = match driver.textconv.as_ref() {
+                Some(program) => program,
+                None => return,
+            };
+            let mut entries = self
+                .notes_tree(driver)
+                .and_then(|tree_id| self.entries(tree_id))
+                .unwrap_or_default();
+            let note = match self.repo.write_blob(converted) {
+                Ok(note) => note.detach(),
+                Err(_) => return,
+            };
+            let filename = id.to_hex().to_string();
+            entries.retain(|e| e.filename != filename);
+            entries.push(tree::Entry {
+                mode: tree::EntryMode::Blob,
+                filename: filename.into(),
+                oid: note,
+            });
+            entries.sort();
+            let tree = match self.repo.write_object(&git_object::Tree { entries }) {
+                Ok(tree) => tree.detach(),
+                Err(_) => return,
+            };
+            // Like git, the cache has no history and each update replaces the previous commit.
+            let signature = self.repo.committer_or_default();
+            let commit = git_object::Commit {
+                message: program.clone(),
+                tree,
+                author: signature.clone(),
+                committer: signature.clone(),
+                encoding: None,
+                parents: Default::default(),
+                extra_headers: Default::default(),
+            };
+            let commit = match self.repo.write_object(&commit) {
+                Ok(commit) => commit.detach(),
+                Err(_) => return,
+            };
+            let name = match driver.textconv_notes_ref().try_into() {
+                Ok(name) => name,
+                Err(_) => return,
+            };
+            self.repo
+                .edit_reference(
+                    RefEdit {
+                        change: Change::Update {
+                            log: LogChange {
+                                message: "notes cache".into(),
+                                ..Default::default()
+                            },
+                            expected: PreviousValue::Any,
+                            new: git_ref::Target::Peeled(commit),
+                        },
+                        name,
+                        deref: false,
+                    },
+                    git_lock::acquire::Fail::Immediately,
+                    Some(&signature),
+                )
+                .ok();
+        }
+    }
+}
@@ -1,51 +1,123 @@
 use crate::bstr::BString;
 
+/// The error returned when reading the configuration of a repository.
 #[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
 pub enum Error {
     #[error("Could not open repository conifguration file")]
-    Open(#[from] git_config::parser::ParserOrIoError<'static>),
+    Open(#[from] git_config::file::from_paths::Error),
     #[error("Cannot handle objects formatted as {:?}", .name)]
     UnsupportedObjectFormat { name: crate::bstr::BString },
     #[error("The value for '{}' cannot be empty", .key)]
     EmptyValue { key: &'static str },
     #[error("Invalid value for 'core.abbrev' = '{}'. It must be between 4 and {}", .value, .max)]
     CoreAbbrev { value: BString, max: u8 },
+    #[error("Invalid value for '{}' = '{}'. It must be a zlib compression level between -1 and 9", .key, .value)]
+    CompressionLevel { key: &'static str, value: BString },
 }
 
 /// Utility type to keep pre-obtained configuration values.
 #[derive(Debug, Clone)]
 pub(crate) struct Cache {
-    // TODO: remove this once resolved is used without a feature dependency
-    #[cfg_attr(not(feature = "git-mailmap"), allow(dead_code))]
     pub resolved: crate::Config,
     /// The hex-length to assume when shortening object ids. If `None`, it should be computed based on the approximate object count.
     pub hex_len: Option<usize>,
     /// true if the repository is designated as 'bare', without work tree
     pub is_bare: bool,
+    /// The work tree configured in `core.worktree`, joined with the git directory if it is relative.
+    pub work_tree: Option<std::path::PathBuf>,
     /// The type of hash to use
     pub object_hash: git_hash::Kind,
     /// If true, multi-pack indices, whether present or not, may be used by the object database.
     pub use_multi_pack_index: bool,
+    /// If true, paths are matched case-insensitively, as configured by `core.ignoreCase`.
+    pub ignore_case: bool,
+    /// The zlib compression level for loose objects, from `core.looseCompression` or `core.compression`.
+    pub loose_compression_level: u32,
+    /// The zlib compression level for objects written to packs, from `pack.compression` or `core.compression`.
+    pub pack_compression_level: u32,
+    /// The rewrite rules for remote urls, as defined by `url.<base>.insteadOf` and `url.<base>.pushInsteadOf`.
+    #[cfg(feature = "git-url")]
+    pub url_rewrite: crate::remote::url::Rewrite,
     // TODO: make core.precomposeUnicode available as well.
+    /// The stamps of the configuration file, `HEAD` and all included files at the time the configuration was read.
+    stamps: Vec<Option<FileStamp>>,
+    /// The paths of all files included by the configuration file, whether they existed or not.
+    included_paths: Vec<std::path::PathBuf>,
+}
+
+/// Cheaply obtainable information about a file to learn whether it changed since it was read.
+///
+/// Note that changes which neither alter the size nor the modification time as seen by the filesystem can't be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: std::time::SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &std::path::Path) -> Option<Self> {
+        let meta = std::fs::metadata(path).ok()?;
+        Some(FileStamp {
+            modified: meta.modified().ok()?,
+            len: meta.len(),
+        })
+    }
+
+    /// Obtain the stamps of the files which affect the configuration, the `HEAD` file of `git_dir` being relevant to
+    /// `includeIf.onbranch:` while the configuration file is shared by all work trees in `common_dir`.
+    /// The `included_paths` are stamped as well, as their creation, removal or change affects the configuration too.
+    fn of_tracked_files(
+        git_dir: &std::path::Path,
+        common_dir: &std::path::Path,
+        included_paths: &[std::path::PathBuf],
+    ) -> Vec<Option<Self>> {
+        [common_dir.join("config"), git_dir.join("HEAD")]
+            .iter()
+            .chain(included_paths)
+            .map(|path| FileStamp::of(path))
+            .collect()
+    }
 }
 
 mod cache {
     use std::{borrow::Cow, convert::TryFrom};
 
     use git_config::{
-        file::GitConfig,
+        file::{from_paths, GitConfig},
         values,
         values::{Boolean, Integer},
     };
 
-    use super::{Cache, Error};
+    use super::{Cache, Error, FileStamp};
     use crate::bstr::ByteSlice;
 
     impl Cache {
-        pub fn new(git_dir: &std::path::Path) -> Result<Self, Error> {
-            let config = GitConfig::open(git_dir.join("config"))?;
+        /// Read the configuration of the repository at `git_dir`, which is stored in `common_dir` as it's shared by all its
+        /// work trees. Both are the same unless `git_dir` belongs to a linked work tree.
+        pub fn new(git_dir: &std::path::Path, common_dir: &std::path::Path) -> Result<Self, Error> {
+            // Obtain stamps before reading so that changes made while reading are detected later.
+            let mut stamps = FileStamp::of_tracked_files(git_dir, common_dir, &[]);
+            let branch_name = head_branch_name(git_dir);
+            let config = GitConfig::from_paths(
+                vec![common_dir.join("config")],
+                &from_paths::Options {
+                    git_dir: Some(git_dir),
+                    branch_name: branch_name.as_ref().map(|name| name.as_bstr()),
+                    ..Default::default()
+                },
+            )?;
+            // Included files are only known once they were read.
+            let included_paths = config.included_paths().to_owned();
+            stamps.extend(included_paths.iter().map(|path| FileStamp::of(path)));
             let is_bare = config_bool(&config, "core.bare", false);
+            let work_tree = config
+                .value::<Cow<'_, [u8]>>("core", None, "worktree")
+                .ok()
+                .and_then(|path| git_features::path::from_byte_vec(path.into_owned()).ok())
+                .map(|path| git_dir.join(path));
             let use_multi_pack_index = config_bool(&config, "core.multiPackIndex", true);
+            let ignore_case = config_bool(&config, "core.ignoreCase", false);
             let repo_format_version = config
                 .value::<Integer>("core", None, "repositoryFormatVersion")
                 .map_or(0, |v| v.value);
@@ -66,6 +138,14 @@ mod cache {
                 git_hash::Kind::Sha1
             };
 
+            let core_compression_level = compression_level(&config, "core.compression")?;
+            let loose_compression_level = compression_level(&config, "core.looseCompression")?
+                .or(core_compression_level)
+                .unwrap_or(ZLIB_BEST_SPEED_COMPRESSION_LEVEL);
+            let pack_compression_level = compression_level(&config, "pack.compression")?
+                .or(core_compression_level)
+                .unwrap_or(ZLIB_DEFAULT_COMPRESSION_LEVEL);
+
             let mut hex_len = None;
             if let Ok(hex_len_str) = config.value::<values::String<'_>>("core", None, "abbrev") {
                 if hex_len_str.value.trim().is_empty() {
@@ -98,13 +178,82 @@ mod cache {
             }
 
             Ok(Cache {
+                #[cfg(feature = "git-url")]
+                url_rewrite: crate::remote::url::Rewrite::from_config(&config),
                 resolved: config.into(),
                 use_multi_pack_index,
+                ignore_case,
+                loose_compression_level,
+                pack_compression_level,
                 object_hash,
                 is_bare,
+                work_tree,
                 hex_len,
+                stamps,
+                included_paths,
             })
         }
+
+        /// Return true if the files affecting the configuration of `git_dir` and `common_dir` changed since this instance
+        /// was created from them.
+        pub fn is_stale(&self, git_dir: &std::path::Path, common_dir: &std::path::Path) -> bool {
+            FileStamp::of_tracked_files(git_dir, common_dir, &self.included_paths) != self.stamps
+        }
+
+        /// Re-read the configuration from `common_dir` if [it changed][Cache::is_stale()], and return true if it was re-read.
+        ///
+        /// Values that shaped the repository when it was opened, like the object hash or whether it is bare, are retained.
+        pub fn refresh(&mut self, git_dir: &std::path::Path, common_dir: &std::path::Path) -> Result<bool, Error> {
+            if !self.is_stale(git_dir, common_dir) {
+                return Ok(false);
+            }
+            let mut cache = Cache::new(git_dir, common_dir)?;
+            cache.is_bare = self.is_bare;
+            cache.work_tree = self.work_tree.take();
+            cache.object_hash = self.object_hash;
+            cache.use_multi_pack_index = self.use_multi_pack_index;
+            cache.loose_compression_level = self.loose_compression_level;
+            *self = cache;
+            Ok(true)
+        }
+    }
+
+    /// Return the full name of the branch `HEAD` of `git_dir` points to for use in `includeIf.onbranch:` conditions,
+    /// or `None` if `HEAD` is detached or can't be read.
+    fn head_branch_name(git_dir: &std::path::Path) -> Option<git_ref::FullName> {
+        // The object hash isn't known yet, but it's irrelevant for reading symbolic refs.
+        let store = git_ref::file::Store::at(git_dir, git_ref::store::WriteReflog::Disable, git_hash::Kind::Sha1);
+        match store.try_find("HEAD").ok()??.target {
+            git_ref::Target::Symbolic(name) => Some(name),
+            git_ref::Target::Peeled(_) => None,
+        }
+    }
+
+    /// The level zlib uses if `-1` is configured as compression level.
+    const ZLIB_DEFAULT_COMPRESSION_LEVEL: u32 = 6;
+    /// The level git uses for loose objects if no compression level is configured.
+    const ZLIB_BEST_SPEED_COMPRESSION_LEVEL: u32 = 1;
+
+    /// Read the zlib compression level at `key`, which is between -1 for the zlib default and 9, if it is set.
+    fn compression_level(config: &GitConfig<'_>, key: &'static str) -> Result<Option<u32>, Error> {
+        let (section, name) = key.split_once('.').expect("valid section.key format");
+        let value = match config.value::<Cow<'_, [u8]>>(section, None, name) {
+            Ok(value) => value,
+            Err(_) => return Ok(None),
+        };
+        let level = Integer::try_from(value.as_ref())
+            .ok()
+            .and_then(|level| level.to_decimal())
+            .filter(|level| (-1..=9).contains(level))
+            .ok_or_else(|| Error::CompressionLevel {
+                key,
+                value: value.as_ref().into(),
+            })?;
+        Ok(Some(if level == -1 {
+            ZLIB_DEFAULT_COMPRESSION_LEVEL
+        } else {
+            level as u32
+        }))
     }
 
     fn config_bool(config: &GitConfig<'_>, key: &str, default: bool) -> bool {
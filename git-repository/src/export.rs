@@ -0,0 +1,314 @@
+//!
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use git_hash::ObjectId;
+use git_object::tree::EntryMode;
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// What to do with annotated tags that carry a signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignedTags {
+    /// Fail the export, like `git fast-export --signed-tags=abort`.
+    Abort,
+    /// Export the signature as part of the message, which keeps it valid if the tag is imported unchanged.
+    Verbatim,
+    /// Drop the signature.
+    Strip,
+}
+
+impl Default for SignedTags {
+    fn default() -> Self {
+        SignedTags::Abort
+    }
+}
+
+/// Options for use in [`Repository::fast_export()`][crate::Repository::fast_export()].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// What to do with signed annotated tags.
+    pub signed_tags: SignedTags,
+}
+
+/// The outcome of [`Repository::fast_export()`][crate::Repository::fast_export()].
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// The ids of all objects that received a mark, with the object with mark `:n` being at index `n - 1`,
+    /// which is all blobs and commits.
+    pub marks: Vec<ObjectId>,
+    /// The amount of exported blobs.
+    pub blobs: usize,
+    /// The amount of exported commits.
+    pub commits: usize,
+    /// The amount of exported annotated tags.
+    pub tags: usize,
+}
+
+/// The error returned by [`Repository::fast_export()`][crate::Repository::fast_export()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not write the export stream")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    FindObjectWithConversion(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    TraverseTree(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    ShallowCommits(#[from] crate::shallow::read::Error),
+    #[error("Tag '{name}' is signed - choose to export its signature verbatim or to strip it")]
+    SignedTag { name: BString },
+    #[error("Tag '{name}' points to a {kind}, but only tags pointing to commits can be exported")]
+    UnsupportedTagTarget { name: BString, kind: git_object::Kind },
+}
+
+type Files = BTreeMap<BString, (EntryMode, ObjectId)>;
+
+pub(crate) struct Exporter<'repo, W> {
+    repo: &'repo crate::Repository,
+    out: W,
+    options: Options,
+    shallow: Vec<ObjectId>,
+    marks: HashMap<ObjectId, usize>,
+    last_commit_by_ref: HashMap<BString, ObjectId>,
+    /// The files of the most recently exported commit, which is commonly the first parent of the next one.
+    last_files: Option<(ObjectId, Files)>,
+    outcome: Outcome,
+}
+
+impl<'repo, W: io::Write> Exporter<'repo, W> {
+    pub(crate) fn new(repo: &'repo crate::Repository, out: W, options: Options) -> Result<Self, Error> {
+        Ok(Exporter {
+            repo,
+            out,
+            options,
+            shallow: repo.shallow_commits()?.unwrap_or_default(),
+            marks: HashMap::new(),
+            last_commit_by_ref: HashMap::new(),
+            last_files: None,
+            outcome: Outcome::default(),
+        })
+    }
+
+    pub(crate) fn export_reference(&mut self, name: &str) -> Result<(), Error> {
+        let mut reference = self.repo.find_reference(name)?;
+        while let git_ref::TargetRef::Symbolic(target) = reference.target() {
+            let target = target.as_bstr().to_owned();
+            reference = self.repo.find_reference(target.as_bstr())?;
+        }
+        let name = reference.name().as_bstr().to_owned();
+        let id = reference.id().detach();
+
+        let object = self.repo.find_object(id)?;
+        match object.kind {
+            git_object::Kind::Commit => {
+                drop(object);
+                self.export_history(id, name.as_ref())?;
+                if self.last_commit_by_ref.get(&name) != Some(&id) {
+                    writeln!(self.out, "reset {}\nfrom :{}\n", name, self.marks[&id])?;
+                    self.last_commit_by_ref.insert(name, id);
+                }
+            }
+            git_object::Kind::Tag => {
+                let tag = git_object::TagRef::from_bytes(&object.data)?;
+                if tag.target_kind != git_object::Kind::Commit {
+                    return Err(Error::UnsupportedTagTarget {
+                        name,
+                        kind: tag.target_kind,
+                    });
+                }
+                let tag_name = tag.name.to_owned();
+                let target = tag.target();
+                let tagger = tag.tagger.map(|tagger| tagger.to_owned());
+                let mut data = tag.message.to_owned();
+                match (tag.pgp_signature, self.options.signed_tags) {
+                    (None, _) | (Some(_), SignedTags::Strip) => {}
+                    (Some(_), SignedTags::Abort) => return Err(Error::SignedTag { name }),
+                    (Some(signature), SignedTags::Verbatim) => {
+                        if !data.is_empty() {
+                            data.push(b'\n');
+                        }
+                        data.extend_from_slice(signature);
+                    }
+                }
+                drop(object);
+
+                self.export_history(target, name.as_ref())?;
+                writeln!(self.out, "tag {}\nfrom :{}", tag_name, self.marks[&target])?;
+                if let Some(tagger) = tagger {
+                    self.out.write_all(b"tagger ")?;
+                    tagger.write_to(&mut self.out)?;
+                    self.out.write_all(b"\n")?;
+                }
+                self.write_data(&data)?;
+                self.outcome.tags += 1;
+            }
+            kind => return Err(Error::UnsupportedTagTarget { name, kind }),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(mut self) -> Result<Outcome, Error> {
+        self.out.flush()?;
+        Ok(self.outcome)
+    }
+
+    /// Export all commits reachable from `tip` that weren't exported yet, parents first, on the reference `ref_name`.
+    fn export_history(&mut self, tip: ObjectId, ref_name: &BStr) -> Result<(), Error> {
+        let mut stack = vec![(tip, false)];
+        while let Some((id, parents_are_exported)) = stack.pop() {
+            if self.marks.contains_key(&id) {
+                continue;
+            }
+            if parents_are_exported {
+                self.export_commit(id, ref_name)?;
+                continue;
+            }
+            stack.push((id, true));
+            let parents = self.parents(&self.repo.find_commit(id)?)?;
+            stack.extend(
+                parents
+                    .into_iter()
+                    .rev()
+                    .filter(|id| !self.marks.contains_key(id))
+                    .map(|id| (id, false)),
+            );
+        }
+        Ok(())
+    }
+
+    fn parents(&self, commit: &crate::Commit<'_>) -> Result<Vec<ObjectId>, Error> {
+        if self.shallow.binary_search(&commit.id).is_ok() {
+            return Ok(Vec::new());
+        }
+        Ok(commit.decode()?.parents().collect())
+    }
+
+    fn export_commit(&mut self, id: ObjectId, ref_name: &BStr) -> Result<(), Error> {
+        let commit = self.repo.find_commit(id)?;
+        let parents = self.parents(&commit)?;
+        let files = self.files(commit.tree_id()?)?;
+        let parent_files = match parents.first() {
+            Some(parent) => {
+                let tree_id = self.repo.find_commit(*parent)?.tree_id()?;
+                match self.last_files.take() {
+                    Some((last_tree_id, files)) if last_tree_id == tree_id => files,
+                    _ => self.files(tree_id)?,
+                }
+            }
+            None => Files::new(),
+        };
+
+        let deletions: Vec<_> = parent_files.keys().filter(|path| !files.contains_key(*path)).collect();
+        let modifications: Vec<_> = files
+            .iter()
+            .filter(|(path, entry)| parent_files.get(*path) != Some(entry))
+            .collect();
+        for (_, (mode, oid)) in &modifications {
+            if *mode != EntryMode::Commit && !self.marks.contains_key(oid) {
+                self.export_blob(*oid)?;
+            }
+        }
+
+        let commit = commit.decode()?;
+        let mark = self.mark(id);
+        writeln!(self.out, "commit {}\nmark :{}", ref_name, mark)?;
+        self.out.write_all(b"author ")?;
+        commit.author.write_to(&mut self.out)?;
+        self.out.write_all(b"\ncommitter ")?;
+        commit.committer.write_to(&mut self.out)?;
+        self.out.write_all(b"\n")?;
+        if let Some(encoding) = commit.encoding {
+            writeln!(self.out, "encoding {}", encoding)?;
+        }
+        self.write_data(commit.message)?;
+        for (idx, parent) in parents.iter().enumerate() {
+            let kind = if idx == 0 { "from" } else { "merge" };
+            writeln!(self.out, "{} :{}", kind, self.marks[parent])?;
+        }
+        for path in deletions {
+            self.out.write_all(b"D ")?;
+            write_path(&mut self.out, path.as_ref())?;
+            self.out.write_all(b"\n")?;
+        }
+        for (path, (mode, oid)) in modifications {
+            write!(self.out, "M {:06o} ", *mode as u16)?;
+            match mode {
+                EntryMode::Commit => write!(self.out, "{} ", oid)?,
+                _ => write!(self.out, ":{} ", self.marks[oid])?,
+            }
+            write_path(&mut self.out, path.as_ref())?;
+            self.out.write_all(b"\n")?;
+        }
+        self.out.write_all(b"\n")?;
+
+        self.last_files = Some((commit.tree(), files));
+        self.last_commit_by_ref.insert(ref_name.to_owned(), id);
+        self.outcome.commits += 1;
+        Ok(())
+    }
+
+    fn export_blob(&mut self, id: ObjectId) -> Result<(), Error> {
+        let blob = self.repo.find_object(id)?;
+        let mark = self.mark(id);
+        writeln!(self.out, "blob\nmark :{}", mark)?;
+        self.write_data(&blob.data)?;
+        self.outcome.blobs += 1;
+        Ok(())
+    }
+
+    /// Return all files in the tree with `id`, recursively, along with their mode and id.
+    fn files(&self, id: ObjectId) -> Result<Files, Error> {
+        Ok(self
+            .repo
+            .find_tree(id)?
+            .traverse()
+            .breadthfirst
+            .files()?
+            .into_iter()
+            .filter(|entry| entry.mode != EntryMode::Tree)
+            .map(|entry| (entry.filepath, (entry.mode, entry.oid)))
+            .collect())
+    }
+
+    fn mark(&mut self, id: ObjectId) -> usize {
+        self.outcome.marks.push(id);
+        let mark = self.outcome.marks.len();
+        self.marks.insert(id, mark);
+        mark
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> io::Result<()> {
+        writeln!(self.out, "data {}", data.len())?;
+        self.out.write_all(data)?;
+        self.out.write_all(b"\n")
+    }
+}
+
+/// Write `path`, quoting it like `git` does if it contains characters that would otherwise change its meaning.
+fn write_path(out: &mut impl io::Write, path: &BStr) -> io::Result<()> {
+    if !path.starts_with(b"\"") && !path.iter().any(|b| *b < 0x20 || *b == b'\\') {
+        return out.write_all(path);
+    }
+    out.write_all(b"\"")?;
+    for b in path.iter().copied() {
+        match b {
+            b'"' => out.write_all(b"\\\"")?,
+            b'\\' => out.write_all(b"\\\\")?,
+            b'\n' => out.write_all(b"\\n")?,
+            b'\t' => out.write_all(b"\\t")?,
+            b if b < 0x20 => write!(out, "\\{:03o}", b)?,
+            b => out.write_all(&[b])?,
+        }
+    }
+    out.write_all(b"\"")
+}
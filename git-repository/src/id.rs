@@ -12,7 +12,7 @@ impl<'repo> Id<'repo> {
     /// # Note
     ///
     /// There can only be one `ObjectRef` per `Easy`. To increase that limit, clone the `Easy`.
-    pub fn object(&self) -> Result<Object<'repo>, find::existing::OdbError> {
+    pub fn object(&self) -> Result<Object<'repo>, find::existing::Error> {
         self.repo.find_object(self.inner)
     }
 
@@ -21,7 +21,7 @@ impl<'repo> Id<'repo> {
     /// # Note
     ///
     /// There can only be one `ObjectRef` per `Easy`. To increase that limit, clone the `Easy`.
-    pub fn try_object(&self) -> Result<Option<Object<'repo>>, find::OdbError> {
+    pub fn try_object(&self) -> Result<Option<Object<'repo>>, find::Error> {
         self.repo.try_find_object(self.inner)
     }
 
@@ -35,15 +35,15 @@ impl<'repo> Id<'repo> {
                 || self.repo.objects.packed_object_count().map(calculate_auto_hex_len),
                 Ok,
             )
-            .map_err(shorten::Error::Find)?;
+            .map_err(|err| shorten::Error::Find(err.into()))?;
 
         let prefix = git_odb::find::PotentialPrefix::new(self.inner, hex_len)
             .expect("BUG: internal hex-len must always be valid");
         self.repo
             .objects
             .disambiguate_prefix(prefix)
-            .map_err(crate::object::find::existing::OdbError::Find)?
-            .ok_or(crate::object::find::existing::OdbError::NotFound { oid: self.inner })
+            .map_err(|err| crate::object::find::existing::Error::Find(err.into()))?
+            .ok_or(crate::object::find::existing::Error::NotFound { oid: self.inner })
     }
 }
 
@@ -56,7 +56,7 @@ fn calculate_auto_hex_len(num_packed_objects: u64) -> usize {
 ///
 pub mod shorten {
     /// Returned by [`Id::prefix()`][super::Id::shorten()].
-    pub type Error = crate::object::find::existing::OdbError;
+    pub type Error = crate::object::find::existing::Error;
 }
 
 impl<'repo> Deref for Id<'repo> {
@@ -118,11 +118,15 @@ pub mod ancestors {
         }
 
         /// Return an iterator to traverse all commits in the history of the commit the parent [Id] is pointing to.
-        pub fn all(&mut self) -> Result<Iter<'repo>, git_traverse::commit::ancestors::Error> {
+        ///
+        /// If the repository is a shallow clone, the [shallow commits][crate::Repository::shallow_commits()] are treated
+        /// as if they had no parents.
+        pub fn all(&mut self) -> Result<Iter<'repo>, Error> {
             let tips = std::mem::replace(&mut self.tips, Box::new(None.into_iter()));
             let parents = self.parents;
             let sorting = self.sorting;
             let repo = self.repo;
+            let shallow = repo.shallow_commits()?.unwrap_or_default();
             Ok(Iter {
                 repo,
                 inner: Box::new(
@@ -132,21 +136,214 @@ pub mod ancestors {
                         move |oid, buf| repo.objects.find_commit_iter(oid, buf),
                     )
                     .sorting(sorting)?
-                    .parents(parents),
+                    .parents(parents)
+                    .with_shallow(shallow.iter().cloned()),
                 ),
+                shallow,
+                saw_shallow_commit: false,
+                is_shallow: None,
+                error_on_missing_commit: false,
+            })
+        }
+
+        /// Like [`all()`][Ancestors::all()], but decode each commit in the history ahead of the consumer on a pool of threads,
+        /// which is useful if the consumer needs the commit information and would otherwise be bound by decoding commits.
+        ///
+        /// The traversal runs on a thread of its own and passes the ids of the commits it encounters on in chunks, which the
+        /// threads of the pool look up and decode on their own. This keeps the amount of commits decoded ahead of the consumer
+        /// bounded to a few chunks per thread.
+        /// Without the `max-performance` feature toggle, commits are decoded on demand on the calling thread instead.
+        pub fn all_prefetched(
+            &mut self,
+            prefetch::Options {
+                chunk_size,
+                thread_limit,
+            }: prefetch::Options,
+        ) -> Result<prefetch::Iter<'repo>, Error> {
+            use git_features::parallel::{self, SequenceId};
+            use git_hash::ObjectId;
+            use git_odb::{Find, FindExt};
+
+            let tips: Vec<_> = std::mem::replace(&mut self.tips, Box::new(None.into_iter())).collect();
+            let shallow = self.repo.shallow_commits()?.unwrap_or_default();
+            let mut traversal = {
+                let objects = self.repo.objects.clone();
+                git_traverse::commit::Ancestors::new(
+                    tips,
+                    git_traverse::commit::ancestors::State::default(),
+                    move |oid, buf| objects.find_commit_iter(oid, buf),
+                )
+                .sorting(self.sorting)?
+                .parents(self.parents)
+                .with_shallow(shallow.iter().cloned())
+            };
+            let chunk_size = chunk_size.max(1);
+            let mut is_done = false;
+            let chunks = std::iter::from_fn(move || {
+                let mut chunk = Vec::with_capacity(chunk_size);
+                while !is_done && chunk.len() < chunk_size {
+                    match traversal.next() {
+                        Some(res) => {
+                            is_done = res.is_err();
+                            chunk.push(res);
+                        }
+                        None => is_done = true,
+                    }
+                }
+                (!chunk.is_empty()).then(|| chunk)
+            })
+            .enumerate();
+
+            let objects = self.repo.objects.clone();
+            let commits = parallel::reduce::Stepwise::new(
+                chunks,
+                thread_limit,
+                move |_n| (objects.clone(), Vec::new()),
+                |(chunk_id, chunk): (SequenceId, Vec<_>), (objects, buf): &mut (crate::OdbHandle, Vec<u8>)| {
+                    let commits = chunk
+                        .into_iter()
+                        .map(|res| -> Result<(ObjectId, git_object::Commit), prefetch::Error> {
+                            let id = res?;
+                            let object = objects
+                                .try_find(id, buf)
+                                .map_err(|err| crate::object::find::existing::Error::Find(err.into()))?
+                                .ok_or(crate::object::find::existing::Error::NotFound { oid: id })?;
+                            let commit = git_object::CommitRef::from_bytes(object.data)?.into();
+                            Ok((id, commit))
+                        })
+                        .collect::<Vec<_>>();
+                    Ok((chunk_id, commits))
+                },
+                parallel::reduce::IdentityWithResult::<_, std::convert::Infallible>::default(),
+            );
+            Ok(prefetch::Iter {
+                repo: self.repo,
+                inner: Box::new(parallel::InOrderIter::from(commits).filter_map(Result::ok).flatten()),
+                shallow,
+                saw_shallow_commit: false,
                 is_shallow: None,
                 error_on_missing_commit: false,
             })
         }
     }
 
+    /// The error returned by [`Ancestors::all()`] and [`Ancestors::all_prefetched()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        ShallowCommits(#[from] crate::shallow::read::Error),
+    }
+
+    ///
+    pub mod prefetch {
+        use git_hash::ObjectId;
+
+        use crate::{ext::ObjectIdExt, Id};
+
+        /// Configure how commits are decoded ahead of the consumer in [`Ancestors::all_prefetched()`][crate::id::Ancestors::all_prefetched()].
+        #[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+        pub struct Options {
+            /// The amount of commits to pass to a thread for decoding at a time.
+            pub chunk_size: usize,
+            /// The amount of threads to use for decoding commits, or all logical cores if `None`.
+            pub thread_limit: Option<usize>,
+        }
+
+        impl Default for Options {
+            fn default() -> Self {
+                Options {
+                    chunk_size: 50,
+                    thread_limit: None,
+                }
+            }
+        }
+
+        /// The error returned by the iterator of [`Ancestors::all_prefetched()`][crate::id::Ancestors::all_prefetched()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            Traverse(#[from] git_traverse::commit::ancestors::Error),
+            #[error(transparent)]
+            FindExistingObject(#[from] crate::object::find::existing::Error),
+            #[error(transparent)]
+            DecodeCommit(#[from] git_object::decode::Error),
+        }
+
+        /// A commit in the history along with its decoded information.
+        pub struct Info<'repo> {
+            /// The id of the commit.
+            pub id: Id<'repo>,
+            /// The decoded commit.
+            pub commit: git_object::Commit,
+        }
+
+        /// The iterator returned by [`Ancestors::all_prefetched()`][crate::id::Ancestors::all_prefetched()].
+        pub struct Iter<'repo> {
+            pub(crate) repo: &'repo crate::Repository,
+            pub(crate) inner: Box<dyn Iterator<Item = Result<(ObjectId, git_object::Commit), Error>>>,
+            pub(crate) error_on_missing_commit: bool,
+            pub(crate) shallow: Vec<ObjectId>,
+            pub(crate) saw_shallow_commit: bool,
+            /// After iteration this flag is true if a shallow commit was encountered or if the iteration was stopped
+            /// prematurely due to missing parent commits, see [`ancestors::Iter::is_shallow`][super::Iter::is_shallow] for details.
+            pub is_shallow: Option<bool>,
+        }
+
+        impl<'repo> Iter<'repo> {
+            /// Once invoked, the iteration will return an error if a commit cannot be found in the object database,
+            /// see [`ancestors::Iter::error_on_missing_commit()`][super::Iter::error_on_missing_commit()] for details.
+            pub fn error_on_missing_commit(mut self) -> Self {
+                self.error_on_missing_commit = true;
+                self
+            }
+        }
+
+        impl<'repo> Iterator for Iter<'repo> {
+            type Item = Result<Info<'repo>, Error>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.is_shallow.is_some() {
+                    return None;
+                }
+                match self.inner.next() {
+                    None => {
+                        self.is_shallow = Some(self.saw_shallow_commit);
+                        None
+                    }
+                    Some(Ok((id, commit))) => {
+                        self.saw_shallow_commit |= self.shallow.binary_search(&id).is_ok();
+                        Some(Ok(Info {
+                            id: id.attach(self.repo),
+                            commit,
+                        }))
+                    }
+                    Some(Err(Error::Traverse(err @ git_traverse::commit::ancestors::Error::FindExisting { .. }))) => {
+                        if self.error_on_missing_commit {
+                            Some(Err(err.into()))
+                        } else {
+                            self.is_shallow = Some(true);
+                            None
+                        }
+                    }
+                    Some(Err(err)) => Some(Err(err)),
+                }
+            }
+        }
+    }
+
     /// The iterator returned by [`Ancestors::all()`].
     pub struct Iter<'repo> {
         repo: &'repo crate::Repository,
         inner: Box<dyn Iterator<Item = Result<git_hash::ObjectId, git_traverse::commit::ancestors::Error>> + 'repo>,
         error_on_missing_commit: bool,
-        // TODO: tests
-        /// After iteration this flag is true if the iteration was stopped prematurely due to missing parent commits.
+        shallow: Vec<git_hash::ObjectId>,
+        saw_shallow_commit: bool,
+        /// After iteration this flag is true if one of the [shallow commits][crate::Repository::shallow_commits()] was
+        /// returned, or if the iteration was stopped prematurely due to missing parent commits.
         /// Note that this flag won't be `Some` if any iteration error occours, which is the case if
         /// [`error_on_missing_commit()`][Iter::error_on_missing_commit()] was called.
         ///
@@ -174,10 +371,13 @@ pub mod ancestors {
         fn next(&mut self) -> Option<Self::Item> {
             match self.inner.next() {
                 None => {
-                    self.is_shallow = Some(false);
+                    self.is_shallow = Some(self.saw_shallow_commit);
                     None
                 }
-                Some(Ok(oid)) => Some(Ok(oid.attach(self.repo))),
+                Some(Ok(oid)) => {
+                    self.saw_shallow_commit |= self.shallow.binary_search(&oid).is_ok();
+                    Some(Ok(oid.attach(self.repo)))
+                }
                 Some(Err(err @ git_traverse::commit::ancestors::Error::FindExisting { .. })) => {
                     if self.error_on_missing_commit {
                         Some(Err(err))
@@ -0,0 +1,216 @@
+//!
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    io::{Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::atomic::AtomicBool,
+};
+
+use git_features::{progress::Progress, zlib::stream::deflate};
+use git_hash::ObjectId;
+use git_odb::Find;
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
+
+use crate::{ext::ObjectIdExt, Id};
+
+/// The error returned by [`Repository::import()`][crate::Repository::import()] and the methods of [`Pack`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not write to the temporary pack")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    ReferenceNameValidation(#[from] git_ref::name::Error),
+    #[error("Packs can't hold more than {} objects", u32::MAX)]
+    TooManyObjects,
+    #[error("Could not write the pack and its index into the object database")]
+    WritePack(#[from] git_pack::bundle::write::Error),
+    #[error(transparent)]
+    ReferenceEdit(#[from] crate::reference::edit::Error),
+}
+
+/// The outcome of [`Pack::commit()`].
+pub struct Outcome {
+    /// Information about the written pack and its index, or `None` if no new object was imported.
+    pub pack: Option<git_pack::bundle::write::Outcome>,
+    /// The reference edits that were performed, which might be more than were queued due to the splitting of
+    /// symbolic references.
+    pub ref_edits: Vec<RefEdit>,
+}
+
+/// A way to write many objects into a single new pack instead of writing them as loose objects, similar to `git fast-import`,
+/// and to update references to them once all objects are written.
+///
+/// Objects are streamed into a temporary pack in the `objects/pack` directory. They become visible only once the
+/// import is [committed][Pack::commit()], which is when the pack is indexed and moved into place, and queued reference
+/// edits are performed. Dropping it discards all written objects instead.
+pub struct Pack<'repo> {
+    repo: &'repo crate::Repository,
+    pack_dir: PathBuf,
+    file: git_tempfile::Handle<Writable>,
+    compression_level: u32,
+    buf: Vec<u8>,
+    ids: HashSet<ObjectId>,
+    num_objects: u32,
+    ref_edits: Vec<RefEdit>,
+}
+
+impl<'repo> Pack<'repo> {
+    pub(crate) fn new(repo: &'repo crate::Repository) -> Result<Self, Error> {
+        let pack_dir = repo.objects.store_ref().path().join("pack");
+        let mut file = git_tempfile::new(&pack_dir, ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+        file.write_all(&git_pack::data::header::encode(git_pack::data::Version::V2, 0))?;
+        Ok(Pack {
+            repo,
+            pack_dir,
+            file,
+            compression_level: repo.pack_compression_level(),
+            buf: Vec::new(),
+            ids: HashSet::new(),
+            num_objects: 0,
+            ref_edits: Vec::new(),
+        })
+    }
+
+    /// Write `object` into the pack and return its id.
+    ///
+    /// Objects which were imported already or which exist in the repository are skipped.
+    pub fn write_object(&mut self, object: impl git_object::WriteTo) -> Result<Id<'repo>, Error> {
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.clear();
+        let res = object
+            .write_to(&mut buf)
+            .map_err(Error::from)
+            .and_then(|_| self.write_object_inner(object.kind(), &buf));
+        self.buf = buf;
+        res
+    }
+
+    /// Write a blob with `bytes` as content into the pack, unless it exists already, and return its id.
+    pub fn write_blob(&mut self, bytes: impl AsRef<[u8]>) -> Result<Id<'repo>, Error> {
+        self.write_object_inner(git_object::Kind::Blob, bytes.as_ref())
+    }
+
+    /// Queue an edit to set the reference `name` to `target` once all objects are written, creating it if it doesn't
+    /// exist yet or overwriting it otherwise.
+    ///
+    /// Use [`edit_reference()`][Pack::edit_reference()] for more control over the edit.
+    pub fn set_reference<Name, E>(&mut self, name: Name, target: impl Into<ObjectId>) -> Result<(), Error>
+    where
+        Name: TryInto<FullName, Error = E>,
+        Error: From<E>,
+    {
+        self.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "import".into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Peeled(target.into()),
+            },
+            name: name.try_into()?,
+            deref: false,
+        });
+        Ok(())
+    }
+
+    /// Queue `edit` to be performed once all objects are written.
+    pub fn edit_reference(&mut self, edit: RefEdit) {
+        self.ref_edits.push(edit);
+    }
+
+    /// The amount of objects written into the pack so far.
+    pub fn num_objects(&self) -> u32 {
+        self.num_objects
+    }
+
+    /// Finish the pack, index it and move it into the object database while providing `progress` and checking
+    /// `should_interrupt`, then perform all queued reference edits and return information about both.
+    ///
+    /// No pack is written if no new object was imported.
+    pub fn commit<P>(mut self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
+    where
+        P: Progress,
+        <P as Progress>::SubProgress: 'static,
+    {
+        let pack = if self.num_objects == 0 {
+            None
+        } else {
+            let object_hash = self.repo.object_hash();
+            let num_objects = self.num_objects;
+            let reader = self.file.with_mut(|file| -> std::io::Result<_> {
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(&git_pack::data::header::encode(
+                    git_pack::data::Version::V2,
+                    num_objects,
+                ))?;
+                let len = file.seek(SeekFrom::End(0))?;
+                file.seek(SeekFrom::Start(0))?;
+                let checksum = git_features::hash::bytes(
+                    &mut *file,
+                    len as usize,
+                    object_hash,
+                    &mut progress.add_child("hash pack"),
+                    should_interrupt,
+                )?;
+                file.write_all(checksum.as_bytes())?;
+                file.flush()?;
+                file.reopen()
+            })??;
+
+            Some(git_pack::Bundle::write_to_directory(
+                std::io::BufReader::new(reader),
+                Some(&self.pack_dir),
+                progress,
+                should_interrupt,
+                None,
+                git_pack::bundle::write::Options {
+                    thread_limit: None,
+                    iteration_mode: git_pack::data::input::Mode::Verify,
+                    index_kind: git_pack::index::Version::V2,
+                    object_hash,
+                },
+            )?)
+        };
+
+        let ref_edits = if self.ref_edits.is_empty() {
+            Vec::new()
+        } else {
+            self.repo.edit_references(
+                std::mem::take(&mut self.ref_edits),
+                git_lock::acquire::Fail::Immediately,
+                None,
+            )?
+        };
+        Ok(Outcome { pack, ref_edits })
+    }
+
+    fn write_object_inner(&mut self, kind: git_object::Kind, data: &[u8]) -> Result<Id<'repo>, Error> {
+        let id = git_odb::Write::write_buf(&git_odb::sink(self.repo.object_hash()), kind, data)?;
+        if self.ids.contains(&id) || self.repo.objects.contains(&id) {
+            return Ok(id.attach(self.repo));
+        }
+        self.num_objects = self.num_objects.checked_add(1).ok_or(Error::TooManyObjects)?;
+
+        let header = match kind {
+            git_object::Kind::Tree => git_pack::data::entry::Header::Tree,
+            git_object::Kind::Blob => git_pack::data::entry::Header::Blob,
+            git_object::Kind::Commit => git_pack::data::entry::Header::Commit,
+            git_object::Kind::Tag => git_pack::data::entry::Header::Tag,
+        };
+        header.write_to(data.len() as u64, &mut self.file)?;
+        let mut out = deflate::Write::new_with_level(&mut self.file, self.compression_level);
+        out.write_all(data)?;
+        out.flush()?;
+
+        self.ids.insert(id);
+        Ok(id.attach(self.repo))
+    }
+}
@@ -6,58 +6,46 @@
 //! support to methods who wouldn't otherwise by injecting it. see [`Read`].
 
 mod init {
-    use std::{
-        io,
-        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
-    };
+    use std::io;
 
-    /// Initialize a signal handler to listen to SIGINT and SIGTERM and trigger our [`trigger()`][super::trigger()] that way.
-    /// Also trigger `interrupt()` which promises to never use a Mutex, allocate or deallocate.
+    /// Initialize a signal handler to listen to SIGINT, SIGTERM, SIGQUIT and, on unix, SIGHUP and trigger our
+    /// [`trigger()`][super::trigger()] that way. Also trigger `interrupt()` which promises to never use a Mutex, allocate
+    /// or deallocate.
+    ///
+    /// The handlers stay registered for the lifetime of the process, use [`init_scoped_handler()`] to be able to remove them.
     ///
     /// # Note
     ///
     /// It will abort the process on second press and won't inform the user about this behaviour either as we are unable to do so without
     /// deadlocking even when trying to write to stderr directly.
     pub fn init_handler(interrupt: impl Fn() + Send + Sync + Clone + 'static) -> io::Result<()> {
-        static IS_INITIALIZED: AtomicBool = AtomicBool::new(false);
-        if IS_INITIALIZED.load(Ordering::SeqCst) {
-            return Err(io::Error::new(io::ErrorKind::Other, "Already initialized"));
-        }
-        for sig in signal_hook::consts::TERM_SIGNALS {
-            // # SAFETY
-            // * we only set atomics or call functions that do
-            // * there is no use of the heap
-            let interrupt = interrupt.clone();
-            #[allow(unsafe_code)]
-            unsafe {
-                signal_hook::low_level::register(*sig, move || {
-                    static INTERRUPT_COUNT: AtomicUsize = AtomicUsize::new(0);
-                    if !super::is_triggered() {
-                        INTERRUPT_COUNT.store(0, Ordering::SeqCst);
-                    }
-                    let msg_idx = INTERRUPT_COUNT.fetch_add(1, Ordering::SeqCst);
-                    if msg_idx == 1 {
-                        git_tempfile::handler::cleanup_tempfiles();
-                        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTERM).ok();
-                    }
-                    interrupt();
-                    super::trigger();
-                })?;
-            }
-        }
+        init_scoped_handler(interrupt).map(|_handlers_stay_registered| ())
+    }
+
+    /// Like [`init_handler()`], but return a [`Deregister`] to remove the handlers again, which restores the handling
+    /// of signals as it was before. Handlers are registered only once, and removed when all users deregistered.
+    /// See [`git_features::interrupt::handler::init()`] for details.
+    pub fn init_scoped_handler(interrupt: impl Fn() + Send + Sync + Clone + 'static) -> io::Result<Deregister> {
+        let deregister = git_features::interrupt::handler::init(
+            &super::IS_INTERRUPTED,
+            interrupt,
+            git_tempfile::handler::cleanup_tempfiles,
+        )?;
 
         // This means that they won't setup a handler allowing us to call them right before we actually abort.
         git_tempfile::setup(git_tempfile::SignalHandlerMode::None);
 
-        Ok(())
+        Ok(deregister)
     }
+
+    pub use git_features::interrupt::handler::{AutoDeregister, Deregister};
 }
 use std::{
     io,
     sync::atomic::{AtomicBool, Ordering},
 };
 
-pub use init::init_handler;
+pub use init::{init_handler, init_scoped_handler, AutoDeregister, Deregister};
 
 /// A wrapper for an inner iterator which will check for interruptions on each iteration.
 pub struct Iter<I, EFN> {
@@ -41,7 +41,7 @@
 //!
 //! - Only a single `crate::object` or derivatives can be held in memory at a time, _per `Easy*`_.
 //! - Changes made to the configuration, packs, and alternates aren't picked up automatically, but the current object store
-//!   needs a manual refresh.
+//!   needs a manual refresh. Configuration can be re-read if it changed using [`refresh_config()`][crate::Repository::refresh_config()].
 //!
 //! ### Design Sketch
 //!
@@ -189,9 +189,13 @@ pub enum Path {
 mod types;
 pub use types::{Commit, DetachedObject, Head, Id, Object, Reference, Repository, Tag, ThreadSafeRepository, Tree};
 
+#[cfg(feature = "git-diff")]
+pub mod changes;
 pub mod commit;
+pub mod export;
 pub mod head;
 pub mod id;
+pub mod import;
 pub mod object;
 pub mod reference;
 mod repository;
@@ -204,6 +208,12 @@ pub enum Kind {
     Bare,
     /// A `git` repository along with a checked out files in a work tree.
     WorkTree,
+    /// A work tree added with `git worktree add`, whose `git` directory is located in the `worktrees` directory of
+    /// another repository whose objects, references and configuration it shares.
+    LinkedWorkTree,
+    /// The `git` directory of a submodule stored in the `modules` directory of its super-project's `git` directory,
+    /// with its work tree configured in `core.worktree`.
+    Submodule,
 }
 
 impl Kind {
@@ -220,12 +230,12 @@ pub fn discover(directory: impl AsRef<std::path::Path>) -> Result<crate::Reposit
 
 /// See [ThreadSafeRepository::init()], but returns a [`Repository`] instead.
 pub fn init(directory: impl AsRef<std::path::Path>) -> Result<crate::Repository, init::Error> {
-    ThreadSafeRepository::init(directory, Kind::WorkTree).map(Into::into)
+    ThreadSafeRepository::init(directory, path::create::Kind::WithWorkTree).map(Into::into)
 }
 
 /// See [ThreadSafeRepository::init()], but returns a [`Repository`] instead.
 pub fn init_bare(directory: impl AsRef<std::path::Path>) -> Result<crate::Repository, init::Error> {
-    ThreadSafeRepository::init(directory, Kind::Bare).map(Into::into)
+    ThreadSafeRepository::init(directory, path::create::Kind::Bare).map(Into::into)
 }
 
 /// See [ThreadSafeRepository::open()], but returns a [`Repository`] instead.
@@ -237,7 +247,14 @@ pub fn open(directory: impl Into<std::path::PathBuf>) -> Result<crate::Repositor
 pub mod open;
 
 ///
-mod config;
+pub mod linked_worktree;
+
+///
+pub mod config;
+
+///
+#[cfg(feature = "git-url")]
+pub mod remote;
 
 ///
 pub mod mailmap {
@@ -257,11 +274,34 @@ pub mod mailmap {
             #[error(transparent)]
             PathInterpolate(#[from] git_config::values::path::interpolate::Error),
             #[error("Could not find object configured in `mailmap.blob`")]
-            FindExisting(#[from] crate::object::find::existing::OdbError),
+            FindExisting(#[from] crate::object::find::existing::Error),
         }
     }
 }
 
+///
+pub mod identity {
+    /// The error returned by [`Repository::committer()`][crate::Repository::committer()] and
+    /// [`Repository::author()`][crate::Repository::author()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(
+            "No {field} was configured and auto-detection is disabled by 'user.useConfigOnly' - set 'user.{field}'"
+        )]
+        ConfigOnly { field: &'static str },
+        #[error("The {field} could not be auto-detected - set 'user.{field}'")]
+        Undetermined { field: &'static str },
+        #[error("An empty name is not allowed")]
+        EmptyName,
+        #[error("The date in {key} could not be parsed")]
+        Date {
+            key: &'static str,
+            source: git_date::parse::Error,
+        },
+    }
+}
+
 ///
 pub mod rev_parse {
     /// The error returned by [`crate::Repository::rev_parse()`].
@@ -271,7 +311,7 @@ pub mod rev_parse {
         #[error(transparent)]
         IdFromHex(#[from] git_hash::decode::Error),
         #[error(transparent)]
-        Find(#[from] crate::object::find::existing::OdbError),
+        Find(#[from] crate::object::find::existing::Error),
     }
 }
 
@@ -294,13 +334,92 @@ pub mod init {
         ///
         /// Fails without action if there is already a `.git` repository inside of `directory`, but
         /// won't mind if the `directory` otherwise is non-empty.
-        pub fn init(directory: impl AsRef<Path>, kind: crate::Kind) -> Result<Self, Error> {
+        pub fn init(directory: impl AsRef<Path>, kind: crate::path::create::Kind) -> Result<Self, Error> {
             let path = crate::path::create::into(directory.as_ref(), kind)?;
             Ok(path.try_into()?)
         }
     }
 }
 
+///
+pub mod convert {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::convert_to_bare()`][crate::Repository::convert_to_bare()] and
+    /// [`Repository::convert_to_work_tree()`][crate::Repository::convert_to_work_tree()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The submodule at '{}' can't be converted as its work tree is managed by its super-project", .git_dir.display())]
+        Submodule { git_dir: PathBuf },
+        #[error("The linked work tree at '{}' can't be converted as it shares its configuration with the main work tree", .git_dir.display())]
+        LinkedWorkTree { git_dir: PathBuf },
+        #[error("The work tree at '{}' isn't an existing directory", .path.display())]
+        MissingWorkTree { path: PathBuf },
+        #[error("The work tree at '{}' can't be stored in the configuration as its path isn't valid UTF-8", .path.display())]
+        IllformedUtf8 { path: PathBuf },
+        #[error("Could not read the repository configuration")]
+        ReadConfig(#[from] git_config::parser::ParserOrIoError<'static>),
+        #[error("Could not write the repository configuration")]
+        WriteConfig(#[from] git_config::file::write_to_path::Error),
+        #[error(transparent)]
+        RefreshConfig(#[from] crate::config::Error),
+    }
+}
+
+///
+pub mod shallow {
+    ///
+    pub mod read {
+        /// The error returned by [`Repository::shallow_commits()`][crate::Repository::shallow_commits()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not read the shallow file")]
+            Io(#[from] std::io::Error),
+            #[error("The shallow file contained an invalid commit id")]
+            DecodeHash(#[from] git_hash::decode::Error),
+        }
+    }
+
+    ///
+    pub mod write {
+        /// The error returned by [`Repository::update_shallow_commits()`][crate::Repository::update_shallow_commits()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            Read(#[from] super::read::Error),
+            #[error("Could not lock the shallow file for writing")]
+            AcquireLock(#[from] git_lock::acquire::Error),
+            #[error("Could not write or remove the shallow file")]
+            Io(#[from] std::io::Error),
+            #[error("Could not commit the lock of the shallow file")]
+            Commit(#[from] git_lock::commit::Error<git_lock::File>),
+        }
+    }
+}
+
+///
+pub mod worktree_path {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::prefix()`][crate::Repository::prefix()] and
+    /// [`Repository::to_git_path()`][crate::Repository::to_git_path()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Bare repositories have no work tree to which paths could be relative")]
+        MissingWorkTree,
+        #[error("Could not obtain the current working directory")]
+        CurrentDir(#[from] std::io::Error),
+        #[error("The path '{}' is outside of the work tree at '{}'", path.display(), work_dir.display())]
+        OutsideWorkTree { path: PathBuf, work_dir: PathBuf },
+        #[error("The path '{}' isn't valid UTF-8 and can't be stored in git", path.display())]
+        IllformedUtf8 { path: PathBuf },
+    }
+}
+
 ///
 pub mod discover {
     use std::{convert::TryInto, path::Path};
@@ -320,6 +439,7 @@ pub mod discover {
     impl crate::ThreadSafeRepository {
         /// Try to open a git repository in `directory` and search upwards through its parents until one is found.
         pub fn discover(directory: impl AsRef<Path>) -> Result<Self, Error> {
+            let _span = git_features::span!("git_repository::discover");
             let path = discover::existing(directory)?;
             Ok(path.try_into()?)
         }
@@ -0,0 +1,120 @@
+//!
+use std::{
+    convert::TryInto,
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+};
+
+use git_ref::Target;
+
+/// A stand-in for a linked worktree, which is represented by its administrative directory in `$GIT_DIR/worktrees/<id>`.
+///
+/// It provides information about the worktree without having to open it as repository.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Proxy {
+    pub(crate) git_dir: PathBuf,
+}
+
+///
+pub mod proxy {
+    ///
+    pub mod head {
+        /// The error returned by [`Proxy::head()`][super::super::Proxy::head()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not read the HEAD file of the worktree")]
+            Io(#[from] std::io::Error),
+            #[error(transparent)]
+            Decode(#[from] git_ref::file::loose::reference::decode::Error),
+        }
+    }
+}
+
+impl Proxy {
+    /// The name of the worktree, which is the name of its administrative directory in `$GIT_DIR/worktrees`.
+    pub fn id(&self) -> &OsStr {
+        self.git_dir
+            .file_name()
+            .expect("administrative directories have a name")
+    }
+
+    /// The administrative directory of the worktree, which serves as its `git` directory.
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Return the directory with the checked out files of the worktree, as recorded in its `gitdir` file.
+    ///
+    /// Note that it might not exist anymore, see [`prunable()`][Proxy::prunable()].
+    pub fn base(&self) -> io::Result<PathBuf> {
+        let git_file = self.read("gitdir")?.map(PathBuf::from).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "The gitdir file of the worktree does not exist",
+            )
+        })?;
+        Ok(match git_file.file_name() {
+            Some(name) if name == ".git" => git_file
+                .parent()
+                .expect("a file name means there is a parent")
+                .to_owned(),
+            _ => git_file,
+        })
+    }
+
+    /// Open the worktree as repository of kind [`LinkedWorkTree`][crate::Kind::LinkedWorkTree], using the work tree recorded
+    /// in its `gitdir` file.
+    pub fn into_repo(self) -> Result<crate::Repository, crate::open::Error> {
+        crate::ThreadSafeRepository::open_from_paths(self.git_dir, None, Default::default()).map(Into::into)
+    }
+
+    /// Return true if the worktree is locked, which prevents it from being pruned.
+    pub fn is_locked(&self) -> bool {
+        self.git_dir.join("locked").is_file()
+    }
+
+    /// Return the reason for which the worktree is locked, which may be empty, or `None` if it's not locked.
+    pub fn lock_reason(&self) -> io::Result<Option<String>> {
+        self.read("locked")
+    }
+
+    /// Return the reason for which the worktree is stale and could be pruned, or `None` if it still exists.
+    ///
+    /// Note that locked worktrees shouldn't be pruned even if they are stale.
+    pub fn prunable(&self) -> io::Result<Option<&'static str>> {
+        if !self.git_dir.is_dir() {
+            return Ok(Some("not a valid directory"));
+        }
+        Ok(match self.read("gitdir")?.map(PathBuf::from) {
+            None => Some("gitdir file does not exist"),
+            Some(git_file) if git_file.as_os_str().is_empty() => Some("invalid gitdir file"),
+            Some(git_file) if !git_file.exists() => Some("gitdir file points to non-existent location"),
+            Some(_) => None,
+        })
+    }
+
+    /// Return the target of the `HEAD` of the worktree, which is the checked out branch or a detached commit, or `None`
+    /// if it doesn't exist.
+    pub fn head(&self) -> Result<Option<Target>, proxy::head::Error> {
+        let content = match std::fs::read(self.git_dir.join("HEAD")) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let name = "HEAD".try_into().expect("HEAD is a valid name");
+        Ok(Some(
+            git_ref::file::loose::Reference::try_from_path(name, &content)?.target,
+        ))
+    }
+
+    /// Read the file `name` in the administrative directory without its trailing newline, or `None` if it doesn't exist.
+    fn read(&self, name: &str) -> io::Result<Option<String>> {
+        match std::fs::read_to_string(self.git_dir.join(name)) {
+            Ok(content) => Ok(Some(content.trim_end_matches(&['\n', '\r'][..]).to_owned())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
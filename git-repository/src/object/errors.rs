@@ -17,11 +17,44 @@ pub mod conversion {
 
 ///
 pub mod find {
-    pub(crate) type OdbError = git_odb::store::find::Error;
+    /// Indicate that an error occurred when trying to find an object, which is never the case if it merely doesn't exist.
+    #[derive(Debug, thiserror::Error)]
+    #[error(transparent)]
+    pub struct Error(#[from] pub git_odb::store::find::Error);
 
     ///
     pub mod existing {
-        pub(crate) type OdbError = git_odb::find::existing::Error<git_odb::store::find::Error>;
+        use git_hash::ObjectId;
+
+        /// An object could not be found in the database, or an error occurred when trying to obtain it.
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            Find(#[from] super::Error),
+            #[error("An object with id {} could not be found", .oid)]
+            NotFound { oid: ObjectId },
+        }
+
+        impl Error {
+            /// Return true if the object doesn't exist, as opposed to it not being readable or decodable.
+            pub fn is_not_found(&self) -> bool {
+                matches!(self, Error::NotFound { .. })
+            }
+        }
+
+        ///
+        pub mod with_conversion {
+            /// An object could not be found in the database, or it had an unexpected kind.
+            #[derive(Debug, thiserror::Error)]
+            #[allow(missing_docs)]
+            pub enum Error {
+                #[error(transparent)]
+                FindExisting(#[from] super::Error),
+                #[error(transparent)]
+                Convert(#[from] crate::object::try_into::Error),
+            }
+        }
     }
 }
 
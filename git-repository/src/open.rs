@@ -94,6 +94,8 @@ pub enum Error {
     NotARepository(#[from] crate::path::is::Error),
     #[error(transparent)]
     ObjectStoreInitialization(#[from] std::io::Error),
+    #[error("Could not read the location of the work tree of the linked work tree at '{}'", .git_dir.display())]
+    LinkedWorkTreeDirectory { source: std::io::Error, git_dir: PathBuf },
 }
 
 impl crate::ThreadSafeRepository {
@@ -104,6 +106,7 @@ impl crate::ThreadSafeRepository {
 
     /// Open a git repository at the given `path`, possibly expanding it to `path/.git` if `path` is a work tree dir.
     fn open_opts(path: impl Into<std::path::PathBuf>, options: Options) -> Result<Self, Error> {
+        let _span = git_features::span!("git_repository::open");
         let path = path.into();
         let (path, kind) = match crate::path::is::git(&path) {
             Ok(kind) => (path, kind),
@@ -125,10 +128,30 @@ impl crate::ThreadSafeRepository {
             replacement_objects,
         }: Options,
     ) -> Result<Self, Error> {
-        let mut config = crate::config::Cache::new(&git_dir)?;
+        let common_dir = crate::path::is::common_dir(&git_dir).map_err(|source| crate::path::is::Error::CommonDir {
+            source,
+            path: git_dir.join("commondir"),
+        })?;
+        let is_linked_work_tree = common_dir != git_dir;
+        let mut config = crate::config::Cache::new(&git_dir, &common_dir)?;
         match worktree_dir {
+            None if is_linked_work_tree => {
+                let proxy = crate::linked_worktree::Proxy {
+                    git_dir: git_dir.clone(),
+                };
+                worktree_dir = Some(proxy.base().map_err(|source| Error::LinkedWorkTreeDirectory {
+                    source,
+                    git_dir: git_dir.clone(),
+                })?);
+                config.is_bare = false;
+            }
             None if !config.is_bare => {
-                worktree_dir = Some(git_dir.parent().expect("parent is always available").to_owned());
+                worktree_dir = Some(
+                    config
+                        .work_tree
+                        .clone()
+                        .unwrap_or_else(|| git_dir.parent().expect("parent is always available").to_owned()),
+                );
             }
             Some(_) => {
                 config.is_bare = false;
@@ -136,15 +159,16 @@ impl crate::ThreadSafeRepository {
             None => {}
         }
 
-        let refs = crate::RefStore::at(
-            &git_dir,
-            if worktree_dir.is_none() {
-                git_ref::store::WriteReflog::Disable
-            } else {
-                git_ref::store::WriteReflog::Normal
-            },
-            config.object_hash,
-        );
+        let write_reflog = if worktree_dir.is_none() {
+            git_ref::store::WriteReflog::Disable
+        } else {
+            git_ref::store::WriteReflog::Normal
+        };
+        let refs = if is_linked_work_tree {
+            crate::RefStore::for_linked_worktree(&git_dir, &common_dir, write_reflog, config.object_hash)
+        } else {
+            crate::RefStore::at(&git_dir, write_reflog, config.object_hash)
+        };
 
         let replacements = replacement_objects
             .refs_prefix()
@@ -167,12 +191,13 @@ impl crate::ThreadSafeRepository {
 
         Ok(crate::ThreadSafeRepository {
             objects: OwnShared::new(git_odb::Store::at_opts(
-                git_dir.join("objects"),
+                common_dir.join("objects"),
                 replacements,
                 git_odb::store::init::Options {
                     slots: object_store_slots,
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
+                    loose_compression_level: config.loose_compression_level,
                 },
             )?),
             refs,
@@ -22,6 +22,15 @@ pub enum Error {
     CreateDirectory { source: std::io::Error, path: PathBuf },
 }
 
+/// The kind of repository to create with [`into()`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Kind {
+    /// Create a bare repository without a work tree.
+    Bare,
+    /// Create a `.git` directory within a work tree.
+    WithWorkTree,
+}
+
 const GIT_DIR_NAME: &str = ".git";
 
 const TPL_INFO_EXCLUDE: &[u8] = include_bytes!("../assets/baseline-init/info/exclude");
@@ -100,11 +109,11 @@ fn create_dir(p: &Path) -> Result<(), Error> {
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
 /// and return its path.
-pub fn into(directory: impl Into<PathBuf>, kind: crate::Kind) -> Result<crate::Path, Error> {
+pub fn into(directory: impl Into<PathBuf>, kind: Kind) -> Result<crate::Path, Error> {
     let mut dot_git = directory.into();
 
     match kind {
-        crate::Kind::Bare => {
+        Kind::Bare => {
             if std::fs::read_dir(&dot_git)
                 .map_err(|err| Error::IoOpen {
                     source: err,
@@ -116,7 +125,7 @@ pub fn into(directory: impl Into<PathBuf>, kind: crate::Kind) -> Result<crate::P
                 return Err(Error::DirectoryNotEmpty { path: dot_git });
             }
         }
-        crate::Kind::WorkTree => {
+        Kind::WithWorkTree => {
             dot_git.push(GIT_DIR_NAME);
 
             if dot_git.is_dir() {
@@ -173,8 +182,8 @@ pub fn into(directory: impl Into<PathBuf>, kind: crate::Kind) -> Result<crate::P
                 &tpl.replace(
                     "{bare-value}",
                     match kind {
-                        crate::Kind::Bare => "true",
-                        crate::Kind::WorkTree => "false",
+                        Kind::Bare => "true",
+                        Kind::WithWorkTree => "false",
                     },
                 ),
                 PathCursor(&mut dot_git).at(filename),
@@ -184,5 +193,11 @@ pub fn into(directory: impl Into<PathBuf>, kind: crate::Kind) -> Result<crate::P
         }
     }
 
-    Ok(crate::Path::from_dot_git_dir(dot_git, kind))
+    Ok(crate::Path::from_dot_git_dir(
+        dot_git,
+        match kind {
+            Kind::Bare => crate::Kind::Bare,
+            Kind::WithWorkTree => crate::Kind::WorkTree,
+        },
+    ))
 }
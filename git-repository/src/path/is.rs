@@ -1,9 +1,11 @@
 //!
 use std::{
     ffi::OsStr,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
+use crate::bstr::ByteSlice;
+
 /// The error returned by [`git()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -16,6 +18,48 @@ pub enum Error {
     MissingObjectsDirectory { missing: PathBuf },
     #[error("Expected a refs directory at '{}'", .missing.display())]
     MissingRefsDirectory { missing: PathBuf },
+    #[error("Could not read the common directory from '{}'", .path.display())]
+    CommonDir { source: std::io::Error, path: PathBuf },
+}
+
+/// Returns true if `git_dir` is the `git` directory of a linked work tree, as created by `git worktree add`.
+///
+/// These contain a `commondir` file pointing to the `git` directory whose objects and references they share.
+pub fn linked_work_tree_git_dir(git_dir: impl AsRef<Path>) -> bool {
+    git_dir.as_ref().join("commondir").is_file()
+}
+
+/// Returns true if `git_dir` is the `git` directory of a submodule, which git places into the `modules` directory
+/// of the `git` directory of its super-project, like `.git/modules/name`.
+pub fn submodule_git_dir(git_dir: impl AsRef<Path>) -> bool {
+    let git_dir = git_dir.as_ref();
+    if git_dir.file_name() == Some(OsStr::new(".git")) {
+        return false;
+    }
+    let mut last_component = None;
+    git_dir.components().rev().skip(1).any(|c| {
+        if c == Component::Normal(OsStr::new(".git")) {
+            true
+        } else {
+            last_component = Some(c);
+            false
+        }
+    }) && last_component == Some(Component::Normal(OsStr::new("modules")))
+}
+
+/// Returns the directory whose objects and references are used by `git_dir`, which is the `git` directory of the main
+/// work tree for linked work trees or `git_dir` itself otherwise.
+pub fn common_dir(git_dir: impl AsRef<Path>) -> std::io::Result<PathBuf> {
+    let git_dir = git_dir.as_ref();
+    match std::fs::read(git_dir.join("commondir")) {
+        Ok(content) => {
+            let common_dir = git_features::path::from_byte_vec(content.trim_end())
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            Ok(git_dir.join(common_dir))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(git_dir.to_owned()),
+        Err(err) => Err(err),
+    }
 }
 
 /// Returns true if the given `git_dir` seems to be a bare repository.
@@ -30,11 +74,14 @@ pub fn bare(git_dir_candidate: impl AsRef<Path>) -> bool {
 /// purely based on the presence of files. Note that the git-config ultimately decides what's bare.
 ///
 /// * [x] a valid head
-/// * [ ] git common directory
+/// * [x] git common directory
 ///   * [ ] respect GIT_COMMON_DIR
 /// * [x] an objects directory
 ///   * [x] respect GIT_OBJECT_DIRECTORY
 /// * [x] a refs directory
+///
+/// The `git` directories of [linked work trees][crate::Kind::LinkedWorkTree] and [submodules][crate::Kind::Submodule]
+/// are detected by their location and files.
 pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
     let dot_git = git_dir.as_ref();
 
@@ -56,22 +103,30 @@ pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::Kind, Error> {
         }
     }
 
+    let common_dir = common_dir(dot_git).map_err(|source| Error::CommonDir {
+        source,
+        path: dot_git.join("commondir"),
+    })?;
     {
         let objects_path = std::env::var("GIT_OBJECT_DIRECTORY")
             .map(PathBuf::from)
-            .unwrap_or_else(|_| dot_git.join("objects"));
+            .unwrap_or_else(|_| common_dir.join("objects"));
         if !objects_path.is_dir() {
             return Err(Error::MissingObjectsDirectory { missing: objects_path });
         }
     }
     {
-        let refs_path = dot_git.join("refs");
+        let refs_path = common_dir.join("refs");
         if !refs_path.is_dir() {
             return Err(Error::MissingRefsDirectory { missing: refs_path });
         }
     }
 
-    Ok(if bare(git_dir) {
+    Ok(if linked_work_tree_git_dir(dot_git) {
+        crate::Kind::LinkedWorkTree
+    } else if submodule_git_dir(dot_git) {
+        crate::Kind::Submodule
+    } else if bare(dot_git) {
         crate::Kind::Bare
     } else {
         crate::Kind::WorkTree
@@ -18,14 +18,19 @@ impl AsRef<std::path::Path> for Path {
 impl Path {
     /// Instantiate a new path from `dir` which is expected to be the `.git` directory, with `kind` indicating
     /// whether it's a bare repository or not.
+    ///
+    /// The `git` directories of linked work trees and submodules are kept as is as their work tree isn't
+    /// their parent directory.
     pub fn from_dot_git_dir(dir: impl Into<PathBuf>, kind: Kind) -> Self {
         let dir = dir.into();
         match kind {
             Kind::WorkTree => Path::WorkTree(dir.parent().expect("this is a sub-directory").to_owned()),
-            Kind::Bare => Path::Repository(dir),
+            Kind::Bare | Kind::LinkedWorkTree | Kind::Submodule => Path::Repository(dir),
         }
     }
     /// Returns the [kind][Kind] of this repository path.
+    ///
+    /// Note that `git` directories of linked work trees and submodules appear [bare][Kind::Bare] here.
     pub fn kind(&self) -> Kind {
         match self {
             Path::WorkTree(_) => Kind::WorkTree,
@@ -0,0 +1,37 @@
+/// The direction of an operation carried out (or to be carried out) through a remote.
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+pub enum Direction {
+    /// Push local changes to the remote.
+    Push,
+    /// Fetch changes from the remote to the local repository.
+    Fetch,
+}
+
+impl Direction {
+    /// Return ourselves as string suitable for use as verb in an english sentence.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Push => "push",
+            Direction::Fetch => "fetch",
+        }
+    }
+}
+
+///
+pub mod url;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod connect {
+    /// The error returned by [`Repository::connect_remote()`][crate::Repository::connect_remote()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Remote '{}' has no url to {}", .name, .direction)]
+        MissingUrl { name: String, direction: &'static str },
+        #[error(transparent)]
+        Url(#[from] super::url::Error),
+        #[error(transparent)]
+        Connect(#[from] git_protocol::transport::client::connect::Error),
+    }
+}
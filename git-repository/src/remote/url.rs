@@ -0,0 +1,95 @@
+use git_config::{file::GitConfig, parser::Key};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    remote::Direction,
+};
+
+/// The error returned by [`Repository::remote_url()`][crate::Repository::remote_url()].
+#[derive(Debug, thiserror::Error)]
+#[error("The url {url:?} of remote '{name}' could not be parsed")]
+pub struct Error {
+    /// The name of the remote.
+    pub name: String,
+    /// The url after rewriting it.
+    pub url: BString,
+    /// The reason for the url being invalid.
+    pub source: git_url::parse::Error,
+}
+
+/// A url prefix to replace, along with all the prefixes it replaces.
+#[derive(Debug, Clone)]
+struct Replace {
+    with: BString,
+    find: Vec<BString>,
+}
+
+/// Rewrite urls according to the `url.<base>.insteadOf` and `url.<base>.pushInsteadOf` configuration.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Rewrite {
+    url: Vec<Replace>,
+    push_url: Vec<Replace>,
+}
+
+impl Rewrite {
+    /// Obtain all `insteadOf` and `pushInsteadOf` values from the `url` sections in `config`.
+    pub fn from_config(config: &GitConfig<'_>) -> Self {
+        let mut rewrite = Rewrite::default();
+        for (header, body) in config.sections_by_name_with_header("url") {
+            let with = match header.subsection_name.as_ref() {
+                Some(base) => base.as_bytes().as_bstr(),
+                None => continue,
+            };
+            for (key, replacements) in [
+                ("insteadOf", &mut rewrite.url),
+                ("pushInsteadOf", &mut rewrite.push_url),
+            ] {
+                let find = body.values(&Key::from(key));
+                if find.is_empty() {
+                    continue;
+                }
+                // Like git, group prefixes by their base in the order bases first appear to settle ties.
+                let replace = match replacements.iter_mut().position(|r| r.with == with) {
+                    Some(pos) => &mut replacements[pos],
+                    None => {
+                        replacements.push(Replace {
+                            with: with.to_owned(),
+                            find: Vec::new(),
+                        });
+                        replacements.last_mut().expect("just pushed")
+                    }
+                };
+                replace.find.extend(
+                    find.into_iter()
+                        .filter(|prefix| !prefix.is_empty())
+                        .map(|prefix| prefix.into_owned().into()),
+                );
+            }
+        }
+        rewrite
+    }
+
+    /// Rewrite `url` by replacing the longest matching prefix configured for `direction`, or return `None` if no prefix
+    /// matched.
+    ///
+    /// Note that `pushInsteadOf` is used for [`Direction::Push`], and `insteadOf` for [`Direction::Fetch`].
+    pub fn rewrite_url(&self, url: &BStr, direction: Direction) -> Option<BString> {
+        let replacements = match direction {
+            Direction::Fetch => &self.url,
+            Direction::Push => &self.push_url,
+        };
+        let mut best: Option<(&BStr, usize)> = None;
+        for replace in replacements {
+            for prefix in &replace.find {
+                if url.starts_with(prefix) && best.map_or(true, |(_, len)| prefix.len() > len) {
+                    best = Some((replace.with.as_bstr(), prefix.len()));
+                }
+            }
+        }
+        best.map(|(with, prefix_len)| {
+            let mut rewritten = with.to_owned();
+            rewritten.push_str(&url[prefix_len..]);
+            rewritten
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use git_hash::ObjectId;
+use git_odb::FindExt;
+use git_traverse::commit::{merge_base, topo, Flags};
+
+use crate::{ext::ObjectIdExt, Id};
+
+/// Ancestry of commits
+///
+/// All queries use the commit-graph of the object database if it can be read, which avoids decoding commits and allows to
+/// stop traversals early.
+impl crate::Repository {
+    /// Return true if `ancestor` is reachable from `descendant` by following parents, or if both are the same commit,
+    /// like `git merge-base --is-ancestor` does.
+    pub fn is_ancestor(
+        &self,
+        ancestor: impl Into<ObjectId>,
+        descendant: impl Into<ObjectId>,
+    ) -> Result<bool, topo::Error> {
+        let graph = self.commit_graph();
+        topo::is_ancestor(
+            &ancestor.into(),
+            &descendant.into(),
+            &mut Flags::new(graph.as_ref()),
+            |oid, buf| self.objects.find_commit_iter(oid, buf),
+        )
+    }
+
+    /// Return the best common ancestor of `one` and `two`, like `git merge-base` does, or `None` if they don't have
+    /// a common ancestor.
+    ///
+    /// If there is more than one best common ancestor due to criss-cross merges, the most recent one is returned.
+    pub fn merge_base(
+        &self,
+        one: impl Into<ObjectId>,
+        two: impl Into<ObjectId>,
+    ) -> Result<Option<Id<'_>>, topo::Error> {
+        let graph = self.commit_graph();
+        let bases = merge_base::merge_bases(
+            &one.into(),
+            &[two.into()],
+            &mut Flags::new(graph.as_ref()),
+            |oid, buf| self.objects.find_commit_iter(oid, buf),
+        )?;
+        Ok(bases.into_iter().next().map(|id| id.attach(self)))
+    }
+
+    /// Return all best common ancestors of `one` and `two` with the most recent ones first, like `git merge-base --all`
+    /// does, or nothing if they don't have a common ancestor.
+    pub fn merge_bases(&self, one: impl Into<ObjectId>, two: impl Into<ObjectId>) -> Result<Vec<Id<'_>>, topo::Error> {
+        let graph = self.commit_graph();
+        let bases = merge_base::merge_bases(
+            &one.into(),
+            &[two.into()],
+            &mut Flags::new(graph.as_ref()),
+            |oid, buf| self.objects.find_commit_iter(oid, buf),
+        )?;
+        Ok(bases.into_iter().map(|id| id.attach(self)).collect())
+    }
+
+    /// Return the best common ancestor of all `commits`, like `git merge-base --octopus` does, which is the commit
+    /// a merge of all `commits` would be based on, or `None` if they don't have a common ancestor.
+    ///
+    /// If there is more than one best common ancestor due to criss-cross merges, the most recent one is returned.
+    pub fn merge_base_octopus(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Option<Id<'_>>, topo::Error> {
+        let commits: Vec<_> = commits.into_iter().map(Into::into).collect();
+        let graph = self.commit_graph();
+        let bases = merge_base::octopus(&commits, &mut Flags::new(graph.as_ref()), |oid, buf| {
+            self.objects.find_commit_iter(oid, buf)
+        })?;
+        Ok(bases.into_iter().next().map(|id| id.attach(self)))
+    }
+
+    /// Return all commits reachable from `tips` by following parents, including the `tips` themselves, in topological
+    /// order like `git rev-list --topo-order` does.
+    pub fn reachable_from(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Vec<Id<'_>>, topo::Error> {
+        let mut walk = git_traverse::commit::Topo::new(tips, |oid, buf| self.objects.find_commit_iter(oid, buf));
+        if let Some(graph) = self.commit_graph() {
+            walk = walk.with_commit_graph(graph);
+        }
+        walk.map(|res| res.map(|id| id.attach(self))).collect()
+    }
+
+    fn commit_graph(&self) -> Option<git_commitgraph::Graph> {
+        git_commitgraph::Graph::from_info_dir(self.objects.store_ref().path().join("info")).ok()
+    }
+}
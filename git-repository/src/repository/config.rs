@@ -0,0 +1,32 @@
+impl crate::Repository {
+    /// Return true if the configuration of this repository changed on disk since it was read.
+    ///
+    /// Changes are detected by comparing the modification time and size of the configuration file and `HEAD`,
+    /// which is cheap enough to do before handling each request in long-running processes.
+    pub fn config_is_stale(&self) -> bool {
+        self.config.is_stale(self.refs.base(), self.refs.common_dir_resolved())
+    }
+
+    /// Re-read the configuration of this repository if [it changed][Self::config_is_stale()] on disk, and return
+    /// true if it was re-read.
+    ///
+    /// Values that shaped the repository when it was opened, like its object hash, are not changed.
+    pub fn refresh_config(&mut self) -> Result<bool, crate::config::Error> {
+        self.config.refresh(self.refs.base(), self.refs.common_dir_resolved())
+    }
+
+    /// The zlib compression level to use for objects written to packs, from 0 for no compression to 9 for the best compression.
+    ///
+    /// It's configured by `pack.compression`, falling back to `core.compression` and the zlib default.
+    pub fn pack_compression_level(&self) -> u32 {
+        self.config.pack_compression_level
+    }
+
+    /// Return true if paths should be matched case-insensitively, for example when matching them against patterns in
+    /// `.gitignore` files.
+    ///
+    /// It's configured by `core.ignoreCase`, which `git` sets when creating repositories on case-insensitive file systems.
+    pub fn ignore_case(&self) -> bool {
+        self.config.ignore_case
+    }
+}
@@ -0,0 +1,88 @@
+use std::{borrow::Cow, ffi::OsStr, path::Path};
+
+use crate::convert::Error;
+
+/// Conversion between bare repositories and those with a work tree
+impl crate::Repository {
+    /// Turn this repository into a bare one by setting `core.bare` to `true` and removing `core.worktree` in its configuration
+    /// file, leaving all files in its work tree untouched.
+    ///
+    /// Note that other instances of this repository won't notice the change until they are opened again.
+    pub fn convert_to_bare(&mut self) -> Result<(), Error> {
+        self.assure_convertible()?;
+        if self.work_tree.is_none() {
+            return Ok(());
+        }
+        self.write_core_config(true, None)?;
+        self.work_tree = None;
+        self.refs.write_reflog = git_ref::store::WriteReflog::Disable;
+        Ok(())
+    }
+
+    /// Turn this repository into one with a work tree at the existing `work_tree` directory by setting `core.bare` to `false`
+    /// in its configuration file. `core.worktree` is set as well unless `work_tree` is the parent of a `.git` directory,
+    /// which is where git expects it by default. Just like `core.worktree`, a relative `work_tree` is relative to the
+    /// git directory.
+    ///
+    /// No files are checked out into the work tree, and other instances of this repository won't notice the change until
+    /// they are opened again.
+    pub fn convert_to_work_tree(&mut self, work_tree: impl AsRef<Path>) -> Result<(), Error> {
+        self.assure_convertible()?;
+        let git_dir = self.git_dir();
+        let work_tree_path = git_dir.join(work_tree.as_ref());
+        if !work_tree_path.is_dir() {
+            return Err(Error::MissingWorkTree { path: work_tree_path });
+        }
+        let is_default_location = git_dir.file_name() == Some(OsStr::new(".git"))
+            && git_dir.parent().and_then(|dir| dir.canonicalize().ok()) == work_tree_path.canonicalize().ok();
+        self.write_core_config(false, (!is_default_location).then(|| work_tree.as_ref()))?;
+        self.work_tree = Some(work_tree_path);
+        self.refs.write_reflog = git_ref::store::WriteReflog::Normal;
+        Ok(())
+    }
+
+    fn assure_convertible(&self) -> Result<(), Error> {
+        if self.refs.common_dir().is_some() {
+            return Err(Error::LinkedWorkTree {
+                git_dir: self.git_dir().to_owned(),
+            });
+        }
+        if crate::path::is::submodule_git_dir(self.git_dir()) {
+            return Err(Error::Submodule {
+                git_dir: self.git_dir().to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Write `core.bare` and `core.worktree` to the configuration file of this repository, and re-read it.
+    fn write_core_config(&mut self, is_bare: bool, work_tree: Option<&Path>) -> Result<(), Error> {
+        let path = self.git_dir().join("config");
+        let mut config = git_config::file::GitConfig::open(&path)?;
+        let bare: &[u8] = if is_bare { b"true" } else { b"false" };
+        if config.set_raw_value("core", None, "bare", bare.to_vec()).is_err() {
+            config.append_raw_value("core", None, "bare", Cow::Borrowed(bare));
+        }
+        match work_tree {
+            Some(work_tree) => {
+                let value = git_features::path::into_bytes(work_tree)
+                    .map_err(|_| Error::IllformedUtf8 {
+                        path: work_tree.to_owned(),
+                    })?
+                    .into_owned();
+                if config.set_raw_value("core", None, "worktree", value.clone()).is_err() {
+                    config.append_raw_value("core", None, "worktree", Cow::Owned(value));
+                }
+            }
+            None => {
+                config.remove_raw_multi_value("core", None, "worktree").ok();
+            }
+        }
+        config.write_to_path(&path, git_lock::acquire::Fail::Immediately)?;
+
+        self.config.is_bare = is_bare;
+        self.config.work_tree = work_tree.map(|work_tree| self.git_dir().join(work_tree));
+        self.refresh_config()?;
+        Ok(())
+    }
+}
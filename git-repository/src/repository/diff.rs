@@ -0,0 +1,266 @@
+use git_hash::ObjectId;
+use git_object::{tree::EntryMode, TreeRefIter};
+use git_odb::FindExt;
+
+use crate::changes::{self, Change};
+
+/// Diffing
+///
+/// All comparisons produce the same kind of [changes][Change] ordered by path, with renames detected as configured
+/// in [`Options`][changes::Options].
+impl crate::Repository {
+    /// Return the changes needed to turn the tree of `old` into the tree of `new`, like `git diff <old> <new>` does.
+    ///
+    /// Both may be anything that can be peeled into a tree, like commits, or `None` to compare with an empty tree.
+    /// Changes to submodules are reported, but trees themselves never appear as changed entry.
+    pub fn diff_tree_to_tree(
+        &self,
+        old: impl Into<Option<ObjectId>>,
+        new: impl Into<Option<ObjectId>>,
+        options: changes::Options,
+    ) -> Result<Vec<Change>, changes::tree_to_tree::Error> {
+        let old = self.tree_data(old.into())?;
+        let new = self.tree_data(new.into())?;
+        let mut recorder = git_diff::tree::Recorder::default();
+        git_diff::tree::Changes::from(TreeRefIter::from_bytes(&old)).needed_to_obtain_with_options(
+            TreeRefIter::from_bytes(&new),
+            git_diff::tree::State::default(),
+            |oid, buf| self.objects.find_tree_iter(oid, buf).ok(),
+            &mut recorder,
+            git_diff::tree::changes::Options {
+                emit_trees: false,
+                ..Default::default()
+            },
+        )?;
+        let mut changes: Vec<_> = recorder
+            .records
+            .into_iter()
+            .map(|change| match change {
+                git_diff::tree::recorder::Change::Addition { entry_mode, oid, path } => Change::Addition {
+                    entry_mode,
+                    id: oid,
+                    path,
+                },
+                git_diff::tree::recorder::Change::Deletion { entry_mode, oid, path } => Change::Deletion {
+                    entry_mode,
+                    id: oid,
+                    path,
+                },
+                git_diff::tree::recorder::Change::Modification {
+                    previous_entry_mode,
+                    previous_oid,
+                    entry_mode,
+                    oid,
+                    path,
+                } => Change::Modification {
+                    previous_entry_mode,
+                    previous_id: previous_oid,
+                    entry_mode,
+                    id: oid,
+                    path,
+                },
+            })
+            .collect();
+        // Changes are recorded breadth-first, but are expected in the order of their paths like the index has them.
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(self.apply_diff_options(changes, options))
+    }
+
+    /// Return the changes needed to turn the tree of `tree` into the index, like `git diff --cached <tree>` does, or
+    /// compare with an empty tree if `tree` is `None`.
+    ///
+    /// A missing index is treated like an empty one, and conflicted entries in the index are ignored.
+    #[cfg(all(feature = "git-index", feature = "git-worktree"))]
+    pub fn diff_tree_to_index(
+        &self,
+        tree: impl Into<Option<ObjectId>>,
+        options: changes::Options,
+    ) -> Result<Vec<Change>, changes::tree_to_index::Error> {
+        let tree = match tree.into() {
+            Some(id) => {
+                let tree_id = self.peel_to_tree(id)?.id;
+                git_index::State::from_tree(&tree_id, |oid, buf| self.objects.find_tree_iter(oid, buf).ok())?
+            }
+            None => self.empty_index()?,
+        };
+        let index = match self.load_index().transpose()? {
+            Some(index) => index.state,
+            None => self.empty_index()?,
+        };
+        let changes = git_worktree::status::tree_index(&tree, &index)
+            .into_iter()
+            .filter_map(|change| {
+                use git_worktree::status::Staged;
+                Some(match change {
+                    Staged::Added { path, mode, id } => Change::Addition {
+                        entry_mode: mode.to_tree_entry_mode()?,
+                        id,
+                        path,
+                    },
+                    Staged::Deleted { path, mode, id } => Change::Deletion {
+                        entry_mode: mode.to_tree_entry_mode()?,
+                        id,
+                        path,
+                    },
+                    Staged::Modified {
+                        path,
+                        previous_mode,
+                        previous_id,
+                        mode,
+                        id,
+                    } => Change::Modification {
+                        previous_entry_mode: previous_mode.to_tree_entry_mode()?,
+                        previous_id,
+                        entry_mode: mode.to_tree_entry_mode()?,
+                        id,
+                        path,
+                    },
+                })
+            })
+            .collect();
+        Ok(self.apply_diff_options(changes, options))
+    }
+
+    /// Return the changes needed to turn the index into the files in the worktree, like `git diff` does.
+    ///
+    /// Only files tracked by the index are compared, which is why untracked files are never reported.
+    /// A missing index is treated like an empty one, and conflicted entries in the index are ignored.
+    #[cfg(all(feature = "git-index", feature = "git-worktree"))]
+    pub fn diff_index_to_worktree(
+        &self,
+        options: changes::Options,
+    ) -> Result<Vec<Change>, changes::index_to_worktree::Error> {
+        use changes::index_to_worktree::Error;
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        let index = match self.load_index().transpose()? {
+            Some(index) => index.state,
+            None => self.empty_index()?,
+        };
+        let outcome = git_worktree::status::index_worktree(
+            &index,
+            work_dir,
+            &[],
+            git_worktree::status::Options {
+                fs: git_worktree::fs::Capabilities {
+                    ignore_case: self.ignore_case(),
+                    ..git_worktree::fs::Capabilities::probe(self.git_dir())
+                },
+                untracked: git_worktree::status::Untracked::No,
+            },
+        )?;
+        let null = ObjectId::null(self.object_hash());
+        let changes = outcome
+            .changes
+            .into_iter()
+            .filter_map(|(idx, change)| {
+                use git_worktree::status::Change as Status;
+                let entry = &index.entries()[idx];
+                let path = entry.path(&index).to_owned();
+                let previous_entry_mode = entry.mode.to_tree_entry_mode()?;
+                Some(match change {
+                    Status::Removed => Change::Deletion {
+                        entry_mode: previous_entry_mode,
+                        id: entry.id,
+                        path,
+                    },
+                    Status::Modified { mode } | Status::TypeChange { mode } => Change::Modification {
+                        previous_entry_mode,
+                        previous_id: entry.id,
+                        entry_mode: mode.to_tree_entry_mode()?,
+                        id: null,
+                        path,
+                    },
+                })
+            })
+            .collect();
+        Ok(self.apply_diff_options(changes, options))
+    }
+
+    /// Return a cache for the conversions of diff drivers with `textconv` programs, which stores them as notes like git does.
+    pub fn textconv_cache(&self) -> changes::TextConvCache<'_> {
+        changes::TextConvCache { repo: self }
+    }
+}
+
+/// Utilities
+impl crate::Repository {
+    fn peel_to_tree(&self, id: ObjectId) -> Result<crate::Object<'_>, crate::object::peel::to_kind::Error> {
+        self.find_object(id)?.peel_to_kind(git_object::Kind::Tree)
+    }
+
+    /// Return the data of the tree `id` peels to, or no data for an empty tree if it is `None`.
+    fn tree_data(&self, id: Option<ObjectId>) -> Result<Vec<u8>, crate::object::peel::to_kind::Error> {
+        Ok(match id {
+            Some(id) => self.peel_to_tree(id)?.detach().data,
+            None => Vec::new(),
+        })
+    }
+
+    #[cfg(all(feature = "git-index", feature = "git-worktree"))]
+    fn empty_index(&self) -> Result<git_index::State, git_traverse::tree::breadthfirst::Error> {
+        git_index::State::from_tree(&ObjectId::empty_tree(self.object_hash()), |_, buf| {
+            buf.clear();
+            Some(TreeRefIter::from_bytes(buf))
+        })
+    }
+
+    /// Turn deletions and additions of blobs into renames if configured in `options`, keeping the order by path.
+    fn apply_diff_options(&self, mut changes: Vec<Change>, options: changes::Options) -> Vec<Change> {
+        let rename_options = match options.renames {
+            Some(options) => options,
+            None => return changes,
+        };
+        let is_blob = |mode: &EntryMode| matches!(mode, EntryMode::Blob | EntryMode::BlobExecutable);
+        let (mut deletions, mut deleted_ids) = (Vec::new(), Vec::new());
+        let (mut additions, mut added_ids) = (Vec::new(), Vec::new());
+        for (idx, change) in changes.iter().enumerate() {
+            match change {
+                Change::Deletion { entry_mode, id, .. } if is_blob(entry_mode) => {
+                    deletions.push(idx);
+                    deleted_ids.push(git_diff::rewrites::Blob::from(*id));
+                }
+                Change::Addition { entry_mode, id, .. } if is_blob(entry_mode) => {
+                    additions.push(idx);
+                    added_ids.push(git_diff::rewrites::Blob::from(*id));
+                }
+                _ => {}
+            }
+        }
+        let renames = git_diff::rewrites::detect_renames(
+            &deleted_ids,
+            &added_ids,
+            |oid, buf| self.objects.find_blob(oid, buf).ok(),
+            rename_options,
+        );
+        if renames.is_empty() {
+            return changes;
+        }
+
+        let mut renamed_sources = vec![false; changes.len()];
+        for rename in renames {
+            let source = deletions[rename.deletion];
+            let (source_entry_mode, source_id, source_path) = match &changes[source] {
+                Change::Deletion { entry_mode, id, path } => (*entry_mode, *id, path.clone()),
+                _ => unreachable!("only deletions are rename sources"),
+            };
+            let destination = &mut changes[additions[rename.addition]];
+            if let Change::Addition { entry_mode, id, path } = destination {
+                *destination = Change::Rename {
+                    source_entry_mode,
+                    source_id,
+                    source_path,
+                    entry_mode: *entry_mode,
+                    id: *id,
+                    path: std::mem::take(path),
+                    similarity: rename.similarity,
+                };
+            }
+            renamed_sources[source] = true;
+        }
+        changes
+            .into_iter()
+            .zip(renamed_sources)
+            .filter_map(|(change, is_renamed)| (!is_renamed).then(|| change))
+            .collect()
+    }
+}
@@ -0,0 +1,187 @@
+use std::borrow::Cow;
+
+use git_actor::{Signature, Time};
+use git_config::values::Boolean;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    identity::Error,
+};
+
+/// Identity handling
+impl crate::Repository {
+    /// Return the committer as configured by this repository, which is determined by…
+    ///
+    /// * …the `GIT_COMMITTER_NAME`, `GIT_COMMITTER_EMAIL` and `GIT_COMMITTER_DATE` environment variables…
+    /// * …the `committer.name` and `committer.email` configuration, followed by `user.name` and `user.email`…
+    /// * …the `EMAIL` environment variable for the email address…
+    /// * …a name and email address guessed from the current user and the host name…
+    ///
+    /// …and in that order. Guessing is disabled if `user.useConfigOnly` is true, in which case name and email address must
+    /// be set in the environment or the configuration.
+    /// The time is the current time unless set in the environment.
+    pub fn committer(&self) -> Result<Signature, Error> {
+        self.identity(Role::Committer, true)
+    }
+
+    /// Return the author as configured by this repository, which is determined just like the
+    /// [`committer()`][crate::Repository::committer()], but using the `GIT_AUTHOR_*` environment variables and the
+    /// `author.*` configuration instead.
+    pub fn author(&self) -> Result<Signature, Error> {
+        self.identity(Role::Author, true)
+    }
+
+    /// Like [`committer()`][crate::Repository::committer()], but never fails and leaves name or email address empty
+    /// instead, which is how `git` obtains the committer for reference logs.
+    pub(crate) fn committer_or_default(&self) -> Signature {
+        self.identity(Role::Committer, false)
+            .expect("identities that aren't strict don't fail")
+    }
+
+    fn identity(&self, role: Role, strict: bool) -> Result<Signature, Error> {
+        let use_config_only = strict
+            && self
+                .config
+                .resolved
+                .value::<Boolean<'_>>("user", None, "useConfigOnly")
+                .map_or(false, |b| b.to_bool());
+        let unset = |field| {
+            if use_config_only {
+                Err(Error::ConfigOnly { field })
+            } else if strict {
+                Err(Error::Undetermined { field })
+            } else {
+                Ok(BString::default())
+            }
+        };
+
+        let name = match self.explicit_value(role, "name") {
+            Some(name) => name,
+            None => match (!use_config_only).then(guessed_user_name).flatten() {
+                Some(name) => name,
+                None => unset("name")?,
+            },
+        };
+        if strict && name.trim().is_empty() {
+            return Err(Error::EmptyName);
+        }
+        let email = match self.explicit_value(role, "email") {
+            Some(email) => email,
+            None => match (!use_config_only)
+                .then(|| env_value("EMAIL").or_else(guessed_email))
+                .flatten()
+            {
+                Some(email) => email,
+                None => unset("email")?,
+            },
+        };
+        let time = match env_value(role.date_var()) {
+            Some(date) => match git_date::parse(&date.to_str_lossy(), None) {
+                Ok(time) => time,
+                Err(err) if strict => {
+                    return Err(Error::Date {
+                        key: role.date_var(),
+                        source: err,
+                    })
+                }
+                Err(_) => now(),
+            },
+            None => now(),
+        };
+        Ok(Signature { name, email, time })
+    }
+
+    /// Return `field` of `role` as set in the environment, or in the configuration of `role` or the user.
+    fn explicit_value(&self, role: Role, field: &str) -> Option<BString> {
+        env_value(match field {
+            "name" => role.name_var(),
+            _ => role.email_var(),
+        })
+        .or_else(|| {
+            [role.section(), "user"].iter().find_map(|section| {
+                self.config
+                    .resolved
+                    .value::<Cow<'_, [u8]>>(section, None, field)
+                    .ok()
+                    .map(|value| value.into_owned().into())
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Author,
+    Committer,
+}
+
+impl Role {
+    fn section(self) -> &'static str {
+        match self {
+            Role::Author => "author",
+            Role::Committer => "committer",
+        }
+    }
+
+    fn name_var(self) -> &'static str {
+        match self {
+            Role::Author => "GIT_AUTHOR_NAME",
+            Role::Committer => "GIT_COMMITTER_NAME",
+        }
+    }
+
+    fn email_var(self) -> &'static str {
+        match self {
+            Role::Author => "GIT_AUTHOR_EMAIL",
+            Role::Committer => "GIT_COMMITTER_EMAIL",
+        }
+    }
+
+    fn date_var(self) -> &'static str {
+        match self {
+            Role::Author => "GIT_AUTHOR_DATE",
+            Role::Committer => "GIT_COMMITTER_DATE",
+        }
+    }
+}
+
+fn env_value(name: &str) -> Option<BString> {
+    let value = std::env::var_os(name)?;
+    git_features::path::os_str_into_bytes(&value).ok().map(Into::into)
+}
+
+/// The login name of the current user, which `git` would also use as name if it isn't set otherwise.
+fn guessed_user_name() -> Option<BString> {
+    ["USER", "USERNAME", "LOGNAME"]
+        .iter()
+        .find_map(|name| env_value(name))
+        .filter(|name| !name.is_empty())
+}
+
+/// An email address made from the login name and the fully qualified host name. Like `git`, we don't consider it valid
+/// if the host name has no domain.
+fn guessed_email() -> Option<BString> {
+    let host = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())?;
+    let host = host.trim();
+    if !host.contains('.') {
+        return None;
+    }
+    let mut email = guessed_user_name()?;
+    email.push(b'@');
+    email.extend_from_slice(host.as_bytes());
+    Some(email)
+}
+
+fn now() -> Time {
+    #[cfg(feature = "local-time-support")]
+    {
+        Signature::now_local_or_utc("", "").time
+    }
+    #[cfg(not(feature = "local-time-support"))]
+    {
+        Signature::now_utc("", "").time
+    }
+}
@@ -19,12 +19,10 @@ impl crate::Repository {
         })
     }
 
-    /// Return the kind of repository, either bare or one with a work tree.
+    /// Return the kind of repository, either bare, one with a work tree, a linked work tree or the `git` directory of
+    /// a submodule.
     pub fn kind(&self) -> crate::Kind {
-        match self.work_tree {
-            Some(_) => crate::Kind::WorkTree,
-            None => crate::Kind::Bare,
-        }
+        kind(self.git_dir(), self.work_tree.is_some())
     }
 
     /// Return the path to the repository itself, containing objects, references, configuration, and more.
@@ -33,4 +31,23 @@ impl crate::Repository {
     pub fn git_dir(&self) -> &std::path::Path {
         self.refs.base()
     }
+
+    /// Return the directory with the objects, references and configuration shared by all work trees, which is the
+    /// [`git_dir()`][crate::Repository::git_dir()] unless this is a linked work tree.
+    pub fn common_dir(&self) -> &std::path::Path {
+        self.refs.common_dir_resolved()
+    }
+}
+
+/// Determine the kind of an opened repository at `git_dir`.
+pub(crate) fn kind(git_dir: &std::path::Path, has_work_tree: bool) -> crate::Kind {
+    if !has_work_tree {
+        crate::Kind::Bare
+    } else if crate::path::is::linked_work_tree_git_dir(git_dir) {
+        crate::Kind::LinkedWorkTree
+    } else if crate::path::is::submodule_git_dir(git_dir) {
+        crate::Kind::Submodule
+    } else {
+        crate::Kind::WorkTree
+    }
 }
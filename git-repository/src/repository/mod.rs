@@ -21,18 +21,6 @@ impl crate::Repository {
 
 /// Everything else
 impl crate::Repository {
-    // TODO: actual implementation
-    /// Return the committer as configured by this repository, which is determined by…
-    ///
-    /// * …the git configuration…
-    /// * …the GIT_(AUTHOR|COMMITTER)_(NAME|EMAIL|DATE) environment variables…
-    ///
-    /// …and in that order.
-    pub fn committer(&self) -> git_actor::Signature {
-        // TODO: actually do the work, probably that should be cached and be refreshable
-        git_actor::Signature::empty()
-    }
-
     /// The kind of object hash the repository is configured to use.
     pub fn object_hash(&self) -> git_hash::Kind {
         self.config.object_hash
@@ -76,12 +64,32 @@ mod init {
 
 mod location;
 
+mod convert;
+
+mod shallow;
+
+mod ancestry;
+
+#[cfg(feature = "git-diff")]
+mod diff;
+
+mod identity;
+
+mod worktree_path;
+
+mod worktree;
+
+#[cfg(feature = "git-url")]
+mod remote;
+
 mod snapshots;
 
 mod impls;
 
 mod cache;
 
+mod config;
+
 mod reference;
 
 mod object;
@@ -1,13 +1,13 @@
 use std::convert::TryInto;
 
 use git_hash::{oid, ObjectId};
-use git_odb::{Find, FindExt};
+use git_odb::Find;
 use git_ref::{
     transaction::{LogChange, PreviousValue, RefLog},
     FullName,
 };
 
-use crate::{commit, ext::ObjectIdExt, object, tag, Id, Object, Reference};
+use crate::{commit, ext::ObjectIdExt, object, tag, Commit, Id, Object, Reference, Tag, Tree};
 
 /// Methods related to object creation.
 impl crate::Repository {
@@ -33,11 +33,10 @@ impl crate::Repository {
     ///
     /// In order to get the kind of the object, is must be fully decoded from storage if it is packed with deltas.
     /// Loose object could be partially decoded, even though that's not implemented.
-    pub fn find_object(&self, id: impl Into<ObjectId>) -> Result<Object<'_>, object::find::existing::OdbError> {
+    pub fn find_object(&self, id: impl Into<ObjectId>) -> Result<Object<'_>, object::find::existing::Error> {
         let id = id.into();
-        let mut buf = self.free_buf();
-        let kind = self.objects.find(&id, &mut buf)?.kind;
-        Ok(Object::from_data(id, kind, buf, self))
+        self.try_find_object(id)?
+            .ok_or(object::find::existing::Error::NotFound { oid: id })
     }
 
     /// Try to find the object with `id` or return `None` it it wasn't found.
@@ -47,31 +46,125 @@ impl crate::Repository {
     /// As a shared buffer is written to back the object data, the returned `ObjectRef` will prevent other
     /// `try_find_object()` operations from succeeding while alive.
     /// To bypass this limit, clone this `sync::Handle` instance.
-    pub fn try_find_object(&self, id: impl Into<ObjectId>) -> Result<Option<Object<'_>>, object::find::OdbError> {
-        let state = self;
+    pub fn try_find_object(&self, id: impl Into<ObjectId>) -> Result<Option<Object<'_>>, object::find::Error> {
         let id = id.into();
-
-        let mut buf = state.free_buf();
+        let mut buf = self.free_buf();
         match self.objects.try_find(&id, &mut buf)? {
             Some(obj) => {
                 let kind = obj.kind;
                 drop(obj);
                 Ok(Some(Object::from_data(id, kind, buf, self)))
             }
-            None => Ok(None),
+            None => {
+                self.reuse_buffer(&mut buf);
+                Ok(None)
+            }
         }
     }
 
+    /// Find the commit with `id` or return an error if it doesn't exist or isn't a commit.
+    pub fn find_commit(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<Commit<'_>, object::find::existing::with_conversion::Error> {
+        Ok(self.find_object(id)?.try_into_commit()?)
+    }
+
+    /// Find the tree with `id` or return an error if it doesn't exist or isn't a tree.
+    pub fn find_tree(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<Tree<'_>, object::find::existing::with_conversion::Error> {
+        Ok(self.find_object(id)?.try_into_tree()?)
+    }
+
+    /// Find the annotated tag with `id` or return an error if it doesn't exist or isn't a tag.
+    pub fn find_tag(&self, id: impl Into<ObjectId>) -> Result<Tag<'_>, object::find::existing::with_conversion::Error> {
+        Ok(self.find_object(id)?.try_into_tag()?)
+    }
+
     /// Write the given object into the object database and return its object id.
+    ///
+    /// The object is hashed in memory first and only written if it doesn't exist yet, which avoids writing
+    /// duplicate objects to slow disks only to have them garbage collected later.
     pub fn write_object(&self, object: impl git_object::WriteTo) -> Result<Id<'_>, object::write::Error> {
-        use git_odb::Write;
+        let mut buf = self.free_buf();
+        buf.clear();
+        let res = object
+            .write_to(&mut buf)
+            .map_err(Into::into)
+            .and_then(|_| self.write_object_inner(object.kind(), &buf));
+        self.reuse_buffer(&mut buf);
+        res
+    }
 
-        let state = self;
-        state
-            .objects
-            .write(object)
-            .map(|oid| oid.attach(self))
+    /// Write a blob with `bytes` as content into the object database, unless it exists already, and return its id.
+    pub fn write_blob(&self, bytes: impl AsRef<[u8]>) -> Result<Id<'_>, object::write::Error> {
+        self.write_object_inner(git_object::Kind::Blob, bytes.as_ref())
+    }
+
+    /// Write a blob with all bytes read from `bytes` as content into the object database, unless it exists already,
+    /// and return its id.
+    ///
+    /// Note that all bytes are held in memory to be able to hash them before writing.
+    pub fn write_blob_stream(&self, mut bytes: impl std::io::Read) -> Result<Id<'_>, object::write::Error> {
+        let mut buf = self.free_buf();
+        buf.clear();
+        let res = bytes
+            .read_to_end(&mut buf)
             .map_err(Into::into)
+            .and_then(|_| self.write_object_inner(git_object::Kind::Blob, &buf));
+        self.reuse_buffer(&mut buf);
+        res
+    }
+
+    /// Write `tree` into the object database, unless it exists already, and return its id.
+    pub fn write_tree(&self, tree: &git_object::Tree) -> Result<Id<'_>, object::write::Error> {
+        self.write_object(tree)
+    }
+
+    /// Write `commit` into the object database, unless it exists already, and return its id.
+    ///
+    /// Use [`commit()`][crate::Repository::commit()] to also update a reference to point to the new commit.
+    pub fn write_commit(&self, commit: &git_object::Commit) -> Result<Id<'_>, object::write::Error> {
+        self.write_object(commit)
+    }
+
+    fn write_object_inner(&self, kind: git_object::Kind, data: &[u8]) -> Result<Id<'_>, object::write::Error> {
+        use git_odb::Write;
+
+        let id = git_odb::sink(self.object_hash()).write_buf(kind, data)?;
+        if self.objects.contains(&id) {
+            return Ok(id.attach(self));
+        }
+        self.objects.write_buf(kind, data).map(|oid| oid.attach(self))
+    }
+
+    /// Start writing objects into a single new pack instead of writing them as loose objects, similar to `git fast-import`,
+    /// which is much more efficient when importing many objects at once.
+    ///
+    /// See [`import::Pack`][crate::import::Pack] for details.
+    pub fn import(&self) -> Result<crate::import::Pack<'_>, crate::import::Error> {
+        crate::import::Pack::new(self)
+    }
+
+    /// Write the history of all `references`, like `main` or `refs/tags/v1.0`, as a stream understood by `git fast-import`
+    /// to `out`, similar to `git fast-export`.
+    ///
+    /// Blobs and commits are exported once and receive marks in the order they are written, while symbolic references
+    /// are followed. Each commit only lists the changes compared to its first parent, and commits of shallow
+    /// clones whose parents are missing are exported without parents.
+    pub fn fast_export(
+        &self,
+        references: impl IntoIterator<Item = impl AsRef<str>>,
+        out: impl std::io::Write,
+        options: crate::export::Options,
+    ) -> Result<crate::export::Outcome, crate::export::Error> {
+        let mut exporter = crate::export::Exporter::new(self, out, options)?;
+        for name in references {
+            exporter.export_reference(name.as_ref())?;
+        }
+        exporter.finish()
     }
 
     /// Create a tag reference named `name` (without `refs/tags/` prefix) pointing to a newly created tag object
@@ -154,7 +154,7 @@ impl crate::Repository {
         let committer = match log_committer {
             Some(c) => c,
             None => {
-                committer_storage = self.committer();
+                committer_storage = self.committer_or_default();
                 &committer_storage
             }
         };
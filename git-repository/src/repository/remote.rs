@@ -0,0 +1,76 @@
+use std::borrow::Cow;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    remote,
+};
+
+impl crate::Repository {
+    /// Return the url of the remote named `name` to use for `direction` with `url.<base>.insteadOf` and
+    /// `url.<base>.pushInsteadOf` applied, or `None` if there is no such url.
+    ///
+    /// Like git, urls to push to are taken from `remote.<name>.pushUrl` and rewritten using `insteadOf`.
+    /// If unset, `remote.<name>.url` is used instead, with `pushInsteadOf` taking precedence over `insteadOf`.
+    pub fn remote_url(
+        &self,
+        name: &str,
+        direction: remote::Direction,
+    ) -> Result<Option<git_url::Url>, remote::url::Error> {
+        self.rewritten_remote_url(name, direction)
+            .map(|url| {
+                git_url::parse(&url).map_err(|source| remote::url::Error {
+                    name: name.into(),
+                    url,
+                    source,
+                })
+            })
+            .transpose()
+    }
+
+    /// Connect to the remote named `name` for the given `direction` using its [rewritten url][Self::remote_url()],
+    /// and ask for the `desired_version` of the protocol.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn connect_remote(
+        &self,
+        name: &str,
+        direction: remote::Direction,
+        desired_version: git_protocol::transport::Protocol,
+    ) -> Result<Box<dyn git_protocol::transport::client::Transport + Send>, remote::connect::Error> {
+        let url = self
+            .rewritten_remote_url(name, direction)
+            .ok_or_else(|| remote::connect::Error::MissingUrl {
+                name: name.into(),
+                direction: direction.as_str(),
+            })?;
+        Ok(git_protocol::transport::connect(&url, desired_version)?)
+    }
+
+    fn rewritten_remote_url(&self, name: &str, direction: remote::Direction) -> Option<BString> {
+        let config = &self.config.resolved;
+        let rewrite = &self.config.url_rewrite;
+        let first_value = |key: &str| {
+            config
+                .get_raw_multi_value("remote", Some(name), key)
+                .ok()
+                .and_then(|values| values.into_iter().next())
+        };
+        let url: Cow<'_, [u8]> = match direction {
+            remote::Direction::Fetch => first_value("url")?,
+            remote::Direction::Push => match first_value("pushUrl") {
+                Some(url) => url,
+                None => {
+                    let url = first_value("url")?;
+                    if let Some(url) = rewrite.rewrite_url(url.as_bstr(), remote::Direction::Push) {
+                        return Some(url);
+                    }
+                    url
+                }
+            },
+        };
+        Some(
+            rewrite
+                .rewrite_url(url.as_bstr(), remote::Direction::Fetch)
+                .unwrap_or_else(|| url.into_owned().into()),
+        )
+    }
+}
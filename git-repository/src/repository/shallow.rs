@@ -0,0 +1,88 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+
+use crate::bstr::ByteSlice;
+
+/// Shallow clones
+impl crate::Repository {
+    /// Return the path to the `shallow` file which lists the commits whose parents are missing in a shallow clone.
+    pub fn shallow_file(&self) -> PathBuf {
+        self.common_dir().join("shallow")
+    }
+
+    /// Return true if this repository is a shallow clone, i.e. it contains commits whose parents weren't fetched.
+    pub fn is_shallow(&self) -> bool {
+        self.shallow_file()
+            .metadata()
+            .map_or(false, |metadata| metadata.is_file() && metadata.len() > 0)
+    }
+
+    /// Return the sorted list of commits whose parents are missing as this repository is a shallow clone, or `None` if
+    /// the repository isn't shallow.
+    ///
+    /// Traversals started with [`Ancestors::all()`][crate::id::Ancestors::all()] treat these commits as if they had no parents.
+    pub fn shallow_commits(&self) -> Result<Option<Vec<ObjectId>>, crate::shallow::read::Error> {
+        read(&self.shallow_file())
+    }
+
+    /// Add all `shallow` commits to and remove all `unshallow` commits from the list of shallow commits, as sent by
+    /// the remote during shallow fetches or when deepening the history, and return the updated list just like
+    /// [`shallow_commits()`][crate::Repository::shallow_commits()].
+    ///
+    /// The `shallow` file is locked according to `lock_mode` while it is read and written, and removed once no shallow
+    /// commit remains.
+    pub fn update_shallow_commits(
+        &self,
+        shallow: impl IntoIterator<Item = ObjectId>,
+        unshallow: impl IntoIterator<Item = ObjectId>,
+        lock_mode: git_lock::acquire::Fail,
+    ) -> Result<Option<Vec<ObjectId>>, crate::shallow::write::Error> {
+        let path = self.shallow_file();
+        let mut lock = git_lock::File::acquire_to_update_resource(&path, lock_mode, None)?;
+        let mut commits = read(&path)?.unwrap_or_default();
+        commits.extend(shallow);
+        commits.sort();
+        commits.dedup();
+        for id in unshallow {
+            if let Ok(pos) = commits.binary_search(&id) {
+                commits.remove(pos);
+            }
+        }
+
+        if commits.is_empty() {
+            if let Err(err) = std::fs::remove_file(&path) {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    return Err(err.into());
+                }
+            }
+            return Ok(None);
+        }
+        for id in &commits {
+            writeln!(lock, "{}", id)?;
+        }
+        lock.commit()?;
+        Ok(Some(commits))
+    }
+}
+
+fn read(path: &Path) -> Result<Option<Vec<ObjectId>>, crate::shallow::read::Error> {
+    let buf = match std::fs::read(path) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut commits = buf
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(ObjectId::from_hex)
+        .collect::<Result<Vec<_>, _>>()?;
+    if commits.is_empty() {
+        return Ok(None);
+    }
+    commits.sort();
+    Ok(Some(commits))
+}
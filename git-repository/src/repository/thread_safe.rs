@@ -2,18 +2,30 @@ mod access {
     use crate::Kind;
 
     impl crate::ThreadSafeRepository {
-        /// Return the kind of repository, either bare or one with a work tree.
+        /// Return the kind of repository, either bare, one with a work tree, a linked work tree or the `git` directory of
+        /// a submodule.
         pub fn kind(&self) -> Kind {
-            match self.work_tree {
-                Some(_) => Kind::WorkTree,
-                None => Kind::Bare,
-            }
+            crate::repository::location::kind(self.refs.base(), self.work_tree.is_some())
         }
 
         /// Add thread-local state to an easy-to-use thread-local repository for the most convenient API.
         pub fn to_thread_local(&self) -> crate::Repository {
             self.into()
         }
+
+        /// Return true if the configuration of this repository changed on disk since it was read.
+        ///
+        /// Changes are detected by comparing the modification time and size of the configuration file and `HEAD`,
+        /// which is cheap enough to do before handling each request in long-running processes.
+        pub fn config_is_stale(&self) -> bool {
+            self.config.is_stale(self.refs.base(), self.refs.common_dir_resolved())
+        }
+
+        /// Re-read the configuration of this repository if [it changed][Self::config_is_stale()] on disk, and return
+        /// true if it was re-read. Thread-local repositories obtained afterwards will use the new configuration.
+        pub fn refresh_config(&mut self) -> Result<bool, crate::config::Error> {
+            self.config.refresh(self.refs.base(), self.refs.common_dir_resolved())
+        }
     }
 }
 
@@ -0,0 +1,21 @@
+use std::io;
+
+use crate::linked_worktree::Proxy;
+
+/// Linked worktrees
+impl crate::Repository {
+    /// Return all linked worktrees of this repository, ordered by their name, which are created with `git worktree add`.
+    ///
+    /// Note that the main worktree isn't included, and that stale worktrees are returned as well.
+    pub fn worktrees(&self) -> io::Result<Vec<Proxy>> {
+        let mut worktrees = match self.common_dir().join("worktrees").read_dir() {
+            Ok(entries) => entries
+                .map(|e| e.map(|e| Proxy { git_dir: e.path() }))
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        worktrees.sort();
+        Ok(worktrees)
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    worktree_path::Error,
+};
+
+/// Paths within the work tree
+impl crate::Repository {
+    /// Return the path of the current working directory relative to the root of the work tree, similar to
+    /// `git rev-parse --show-prefix`, which is empty if the current directory is the work tree itself.
+    ///
+    /// Symbolic links in the path of the current directory or the work tree are resolved if needed, and it's an error
+    /// if the current directory isn't inside of the work tree or if this repository is bare.
+    pub fn prefix(&self) -> Result<PathBuf, Error> {
+        let current_dir = std::env::current_dir()?;
+        let work_dir = self.absolute_work_dir(&current_dir)?;
+        relative_to(&current_dir, &work_dir)
+    }
+
+    /// Convert `path` into a path as stored in the index and in trees, relative to the root of the work tree and with
+    /// slashes as separators on all platforms.
+    ///
+    /// Absolute paths must point into the work tree, even though they may point to it via symbolic links, whereas relative
+    /// paths are relative to the root of the work tree. To convert paths relative to the current working directory, join
+    /// them with the [`prefix()`][crate::Repository::prefix()] first.
+    /// `.` and `..` components are resolved, but the resulting path must not leave the work tree.
+    pub fn to_git_path(&self, path: impl AsRef<Path>) -> Result<BString, Error> {
+        let path = path.as_ref();
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        let relative = if path.is_absolute() {
+            relative_to(path, &self.absolute_work_dir(&std::env::current_dir()?)?)?
+        } else {
+            normalize(path).ok_or_else(|| Error::OutsideWorkTree {
+                path: path.to_owned(),
+                work_dir: work_dir.to_owned(),
+            })?
+        };
+
+        let mut out = BString::default();
+        for component in relative.components() {
+            let component = git_features::path::os_str_into_bytes(component.as_os_str())
+                .map_err(|_| Error::IllformedUtf8 { path: relative.clone() })?;
+            if !out.is_empty() {
+                out.push(b'/');
+            }
+            out.extend_from_slice(component);
+        }
+        Ok(out)
+    }
+
+    /// Convert the `path` as stored in the index and in trees into a path to the respective file in the work tree,
+    /// using the native path separator, or `None` if this repository is bare or if `path` can't be represented
+    /// on this platform.
+    pub fn to_workdir_path(&self, path: &BStr) -> Option<PathBuf> {
+        let work_dir = self.work_dir()?;
+        let path = git_features::path::convert::to_native_separators(path.as_bytes());
+        let path = git_features::path::from_bytes(path).ok()?;
+        Some(work_dir.join(path))
+    }
+
+    fn absolute_work_dir(&self, current_dir: &Path) -> Result<PathBuf, Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        Ok(current_dir.join(work_dir))
+    }
+}
+
+/// Return `path` relative to `work_dir`, both of which are absolute, and resolve symbolic links only if `path` doesn't
+/// lexically start with `work_dir`.
+fn relative_to(path: &Path, work_dir: &Path) -> Result<PathBuf, Error> {
+    let outside = || Error::OutsideWorkTree {
+        path: path.to_owned(),
+        work_dir: work_dir.to_owned(),
+    };
+    let (normalized_path, normalized_work_dir) = match (normalize(path), normalize(work_dir)) {
+        (Some(path), Some(work_dir)) => (path, work_dir),
+        _ => return Err(outside()),
+    };
+    if let Ok(relative) = normalized_path.strip_prefix(&normalized_work_dir) {
+        return Ok(relative.to_owned());
+    }
+    canonicalize(&normalized_path)
+        .strip_prefix(canonicalize(&normalized_work_dir).as_ref())
+        .map(ToOwned::to_owned)
+        .map_err(|_| outside())
+}
+
+/// Remove all `.` components and resolve `..` components lexically, or return `None` if `path` would leave its root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            Component::Prefix(_) | Component::RootDir | Component::Normal(_) => out.push(component),
+        }
+    }
+    Some(out)
+}
+
+/// Resolve symbolic links in the longest existing leading portion of `path`, keeping the remaining components as is
+/// as they may not exist yet.
+fn canonicalize(path: &Path) -> Cow<'_, Path> {
+    let mut existing = path;
+    loop {
+        if let Ok(canonicalized) = existing.canonicalize() {
+            let rest = path.strip_prefix(existing).expect("ancestors are prefixes");
+            return if rest.as_os_str().is_empty() {
+                canonicalized.into()
+            } else {
+                canonicalized.join(rest).into()
+            };
+        }
+        match existing.parent() {
+            Some(parent) => existing = parent,
+            None => return path.into(),
+        }
+    }
+}
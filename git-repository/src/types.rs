@@ -142,7 +142,6 @@ pub struct ThreadSafeRepository {
     /// The path to the worktree at which to find checked out files
     pub work_tree: Option<PathBuf>,
     // TODO: git-config should be here - it's read a lot but not written much in must applications, so shouldn't be in `State`.
-    //       Probably it's best reload it on signal (in servers) or refresh it when it's known to have been changed similar to how
-    //       packs are refreshed. This would be `git_config::fs::Config` when ready.
+    //       This would be `git_config::fs::Config` when ready, which is refreshed with `refresh_config()` when it changed on disk.
     pub(crate) config: crate::config::Cache,
 }
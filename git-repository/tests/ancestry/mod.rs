@@ -0,0 +1,57 @@
+use git_testtools::hex_to_id;
+
+fn repo() -> crate::Result<git_repository::Repository> {
+    crate::repo("make_repo_with_fork_and_dates.sh").map(|r| r.to_thread_local())
+}
+
+#[test]
+fn is_ancestor() -> crate::Result {
+    let repo = repo()?;
+    let c1 = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    let b1c1 = hex_to_id("bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac");
+    let c2 = hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7");
+    let m1b1 = hex_to_id("288e509293165cb5630d08f4185bdf2445bf6170");
+
+    assert!(repo.is_ancestor(c1, m1b1)?);
+    assert!(repo.is_ancestor(b1c1, m1b1)?);
+    assert!(repo.is_ancestor(c2, c2)?, "commits are their own ancestors");
+    assert!(!repo.is_ancestor(b1c1, c2)?);
+    assert!(!repo.is_ancestor(m1b1, c1)?);
+    Ok(())
+}
+
+#[test]
+fn merge_base() -> crate::Result {
+    let repo = repo()?;
+    let c1 = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    let b1c1 = hex_to_id("bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac");
+    let c2 = hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7");
+    let m1b1 = hex_to_id("288e509293165cb5630d08f4185bdf2445bf6170");
+
+    assert_eq!(repo.merge_base(c2, b1c1)?.expect("common ancestor"), c1);
+    assert_eq!(
+        repo.merge_base(m1b1, b1c1)?.expect("common ancestor"),
+        b1c1,
+        "ancestors are their own merge-base with their descendants"
+    );
+    assert_eq!(repo.merge_bases(c2, b1c1)?, vec![c1]);
+    assert_eq!(repo.merge_base_octopus([c2, b1c1, m1b1])?.expect("common ancestor"), c1);
+    assert_eq!(repo.merge_base_octopus([m1b1, c2])?.expect("common ancestor"), c2);
+    Ok(())
+}
+
+#[test]
+fn reachable_from() -> crate::Result {
+    let repo = repo()?;
+    let c1 = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+    let b1c1 = hex_to_id("bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac");
+    let c2 = hex_to_id("9902e3c3e8f0c569b4ab295ddf473e6de763e1e7");
+    let m1b1 = hex_to_id("288e509293165cb5630d08f4185bdf2445bf6170");
+
+    assert_eq!(repo.reachable_from(Some(c2))?, [c2, c1]);
+    let commits = repo.reachable_from([b1c1, m1b1])?;
+    assert_eq!(commits.len(), 4, "each commit is returned once");
+    assert_eq!(commits[0], m1b1, "children come before their parents");
+    assert_eq!(commits[3], c1);
+    Ok(())
+}
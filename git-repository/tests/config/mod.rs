@@ -0,0 +1,205 @@
+use std::io::Write;
+
+#[test]
+fn refresh_config_only_rereads_changed_configuration() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    assert!(
+        !repo.config_is_stale(),
+        "nothing changed since the repository was opened"
+    );
+    assert!(!repo.refresh_config()?, "unchanged configuration isn't read again");
+
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(b"[core]\n\tabbrev = 8\n")?;
+    assert!(repo.config_is_stale(), "the configuration file changed in size");
+
+    let mut thread_safe_repo = repo.clone().into_sync();
+    assert!(
+        thread_safe_repo.config_is_stale(),
+        "the configuration is shared by all handles"
+    );
+
+    assert!(repo.refresh_config()?, "changed configuration is read again");
+    assert!(!repo.config_is_stale());
+    assert!(thread_safe_repo.refresh_config()?);
+    assert!(!thread_safe_repo.to_thread_local().config_is_stale());
+    Ok(())
+}
+
+#[test]
+fn refresh_config_rereads_changed_included_files() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(b"[include]\n\tpath = included\n")?;
+    assert!(repo.refresh_config()?);
+    assert!(!repo.ignore_case());
+
+    let included_path = repo.git_dir().join("included");
+    std::fs::write(&included_path, b"[core]\n\tignoreCase = true\n")?;
+    assert!(repo.config_is_stale(), "the included file was created");
+    assert!(repo.refresh_config()?);
+    assert!(repo.ignore_case());
+
+    std::fs::write(&included_path, b"[core]\n\tignoreCase = false\n")?;
+    assert!(repo.config_is_stale(), "the included file changed in size");
+    assert!(repo.refresh_config()?);
+    assert!(!repo.ignore_case());
+    Ok(())
+}
+
+#[test]
+fn onbranch_includes_follow_the_branch_head_points_to() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    std::fs::write(repo.git_dir().join("included"), b"[core]\n\tignoreCase = true\n")?;
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(b"[includeIf \"onbranch:feature\"]\n\tpath = included\n")?;
+    assert!(repo.refresh_config()?);
+    assert!(!repo.ignore_case(), "HEAD doesn't point to the feature branch yet");
+
+    std::fs::write(repo.git_dir().join("HEAD"), b"ref: refs/heads/feature\n")?;
+    assert!(repo.refresh_config()?);
+    assert!(repo.ignore_case());
+    Ok(())
+}
+
+#[test]
+fn compression_levels_are_configured_like_git() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    assert_eq!(repo.pack_compression_level(), 6, "the zlib default");
+    assert_eq!(repo.objects.store_ref().loose_compression_level(), 1, "fast by default");
+
+    let config_path = repo.git_dir().join("config");
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&config_path)?
+        .write_all(b"[core]\n\tcompression = 9\n")?;
+    assert!(repo.refresh_config()?);
+    assert_eq!(
+        repo.pack_compression_level(),
+        9,
+        "core.compression is used if pack.compression is unset"
+    );
+
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&config_path)?
+        .write_all(b"[pack]\n\tcompression = 0\n[core]\n\tlooseCompression = -1\n")?;
+    assert!(repo.refresh_config()?);
+    assert_eq!(repo.pack_compression_level(), 0, "pack.compression takes precedence");
+
+    let repo = git_repository::open(tmp.path())?;
+    assert_eq!(
+        repo.objects.store_ref().loose_compression_level(),
+        6,
+        "core.looseCompression is used when opening the object database, -1 is the zlib default"
+    );
+
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&config_path)?
+        .write_all(b"[pack]\n\tcompression = 10\n")?;
+    assert!(
+        git_repository::open(tmp.path()).is_err(),
+        "levels out of range are an error"
+    );
+    Ok(())
+}
+
+#[test]
+fn ignore_case_is_read_from_core_ignore_case() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    assert!(!repo.ignore_case(), "case matters by default");
+
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(b"[core]\n\tignoreCase = true\n")?;
+    assert!(repo.refresh_config()?);
+    assert!(repo.ignore_case());
+    Ok(())
+}
+
+#[test]
+fn identity_is_obtained_from_the_environment_and_the_configuration() -> crate::Result {
+    for name in &[
+        "GIT_AUTHOR_NAME",
+        "GIT_AUTHOR_EMAIL",
+        "GIT_AUTHOR_DATE",
+        "GIT_COMMITTER_NAME",
+        "GIT_COMMITTER_EMAIL",
+        "GIT_COMMITTER_DATE",
+    ] {
+        std::env::remove_var(name);
+    }
+
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    let config_path = repo.git_dir().join("config");
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&config_path)?
+        .write_all(b"[user]\n\tname = User\n\temail = user@example.com\n[author]\n\tname = Author\n")?;
+    assert!(repo.refresh_config()?);
+
+    let committer = repo.committer()?;
+    assert_eq!(committer.name, "User");
+    assert_eq!(committer.email, "user@example.com");
+    let author = repo.author()?;
+    assert_eq!(author.name, "Author", "author.name takes precedence over user.name");
+    assert_eq!(author.email, "user@example.com");
+
+    std::env::set_var("GIT_AUTHOR_NAME", "Env");
+    std::env::set_var("GIT_AUTHOR_EMAIL", "env@example.com");
+    std::env::set_var("GIT_AUTHOR_DATE", "1234567890 +0200");
+    let author = repo.author()?;
+    assert_eq!(
+        author.name, "Env",
+        "the environment takes precedence over the configuration"
+    );
+    assert_eq!(author.email, "env@example.com");
+    assert_eq!(author.time, git_repository::actor::Time::new(1234567890, 2 * 60 * 60));
+
+    std::env::set_var("GIT_AUTHOR_DATE", "not a date");
+    assert!(matches!(
+        repo.author(),
+        Err(git_repository::identity::Error::Date { .. })
+    ));
+    for name in &["GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE"] {
+        std::env::remove_var(name);
+    }
+
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&config_path)?
+        .write_all(b"[user]\n\tuseConfigOnly = true\n\temail = \n")?;
+    assert!(repo.refresh_config()?);
+    assert_eq!(
+        repo.committer()?.email,
+        "",
+        "explicitly configured empty email addresses are fine"
+    );
+
+    let tmp = tempfile::tempdir()?;
+    let mut repo = git_repository::init(tmp.path())?;
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(b"[user]\n\tname = User\n\tuseConfigOnly = true\n")?;
+    assert!(repo.refresh_config()?);
+    assert!(matches!(
+        repo.committer(),
+        Err(git_repository::identity::Error::ConfigOnly { field: "email" })
+    ));
+    Ok(())
+}
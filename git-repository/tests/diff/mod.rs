@@ -0,0 +1,243 @@
+use git_repository::{
+    changes::{Change, Options},
+    ObjectId,
+};
+
+fn repo() -> crate::Result<git_repository::Repository> {
+    crate::repo("make_diff_repo.sh").map(|r| r.to_thread_local())
+}
+
+fn tag(repo: &git_repository::Repository, name: &str) -> crate::Result<ObjectId> {
+    Ok(repo.find_reference(name)?.peel_to_id_in_place()?.detach())
+}
+
+/// Show changes like `git diff --name-status` does.
+fn name_status(changes: &[Change]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::Addition { path, .. } => format!("A {}", path),
+            Change::Deletion { path, .. } => format!("D {}", path),
+            Change::Modification { path, .. } => format!("M {}", path),
+            Change::Rename {
+                source_path,
+                path,
+                similarity,
+                ..
+            } => format!("R{:.0} {} -> {}", similarity * 100.0, source_path, path),
+        })
+        .collect()
+}
+
+fn no_renames() -> Options {
+    Options { renames: None }
+}
+
+#[test]
+fn tree_to_tree() -> crate::Result {
+    let repo = repo()?;
+    let (c1, c2) = (tag(&repo, "c1")?, tag(&repo, "c2")?);
+    assert_eq!(
+        name_status(&repo.diff_tree_to_tree(c1, c2, Options::default())?),
+        [
+            "A added",
+            "D deleted",
+            "R100 exact-source -> exact-target",
+            "M modified",
+            "R80 dir/moved -> renamed"
+        ],
+        "commits are peeled to their trees, and changes are ordered by path with renames detected by default"
+    );
+    assert_eq!(
+        name_status(&repo.diff_tree_to_tree(c1, c2, no_renames())?),
+        [
+            "A added",
+            "D deleted",
+            "D dir/moved",
+            "D exact-source",
+            "A exact-target",
+            "M modified",
+            "A renamed"
+        ]
+    );
+    assert_eq!(
+        name_status(&repo.diff_tree_to_tree(None, c1, Options::default())?),
+        [
+            "A deleted",
+            "A dir/moved",
+            "A exact-source",
+            "A modified",
+            "A unchanged"
+        ],
+        "a missing tree is empty"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "git-index", feature = "git-worktree"))]
+fn tree_to_index() -> crate::Result {
+    use git_repository::objs::tree::EntryMode;
+    let repo = repo()?;
+    let changes = repo.diff_tree_to_index(tag(&repo, "c2")?, Options::default())?;
+    assert_eq!(name_status(&changes), ["M modified", "A staged-new"]);
+    assert!(matches!(
+        &changes[0],
+        Change::Modification {
+            previous_entry_mode: EntryMode::Blob,
+            entry_mode: EntryMode::Blob,
+            id,
+            ..
+        } if !id.is_null()
+    ));
+
+    assert_eq!(
+        name_status(&repo.diff_tree_to_index(tag(&repo, "c1")?, Options::default())?),
+        [
+            "A added",
+            "D deleted",
+            "R100 exact-source -> exact-target",
+            "M modified",
+            "R80 dir/moved -> renamed",
+            "A staged-new"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "git-index", feature = "git-worktree"))]
+fn index_to_worktree() -> crate::Result {
+    use git_repository::objs::tree::EntryMode;
+    let repo = repo()?;
+    let changes = repo.diff_index_to_worktree(Options::default())?;
+    assert_eq!(
+        name_status(&changes),
+        ["M exact-target", "M modified", "D unchanged"],
+        "untracked files are never listed"
+    );
+    assert!(matches!(
+        &changes[0],
+        Change::Modification {
+            previous_entry_mode: EntryMode::Blob,
+            entry_mode: EntryMode::BlobExecutable,
+            id,
+            ..
+        } if id.is_null()
+    ));
+    assert!(
+        matches!(&changes[1], Change::Modification { id, .. } if id.is_null()),
+        "files in the worktree aren't hashed"
+    );
+    Ok(())
+}
+
+mod textconv_cache {
+    use git_diff::driver::{Cache, Driver};
+    use git_repository as git;
+    use git_testtools::hex_to_id;
+
+    fn driver(textconv: &str) -> Driver {
+        Driver {
+            name: "pdf".into(),
+            textconv: Some(textconv.into()),
+            cache_textconv: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn conversions_are_stored_as_notes_of_the_program_that_produced_them() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init(&tmp)?;
+        let mut cache = repo.textconv_cache();
+        let a = hex_to_id("0000000000000000000000000000000000000001");
+        let b = hex_to_id("0000000000000000000000000000000000000002");
+
+        assert_eq!(cache.get(&driver("pdftotext"), &a), None, "nothing is cached initially");
+        cache.put(&driver("pdftotext"), &a, b"a as text");
+        cache.put(&driver("pdftotext"), &b, b"b as text");
+        assert_eq!(cache.get(&driver("pdftotext"), &a).as_deref(), Some(&b"a as text"[..]));
+        assert_eq!(cache.get(&driver("pdftotext"), &b).as_deref(), Some(&b"b as text"[..]));
+
+        let notes = repo
+            .find_reference("refs/notes/textconv/pdf")?
+            .peel_to_id_in_place()?
+            .object()?
+            .try_into_commit()?;
+        assert_eq!(
+            notes.message_raw()?,
+            "pdftotext",
+            "like git, the program is the commit message"
+        );
+        assert_eq!(notes.parent_ids().count(), 0, "like git, the cache has no history");
+
+        assert_eq!(
+            cache.get(&driver("pdftotext -layout"), &a),
+            None,
+            "conversions of other programs are ignored"
+        );
+        cache.put(&driver("pdftotext -layout"), &b, b"b as layouted text");
+        assert_eq!(
+            cache.get(&driver("pdftotext -layout"), &b).as_deref(),
+            Some(&b"b as layouted text"[..])
+        );
+        assert_eq!(
+            cache.get(&driver("pdftotext -layout"), &a),
+            None,
+            "conversions of other programs are discarded when storing new ones"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn notes_in_fanout_directories_are_found() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init(&tmp)?;
+        let note = repo.write_blob("converted")?.detach();
+        let fanout = repo
+            .write_object(&git::objs::Tree {
+                entries: vec![git::objs::tree::Entry {
+                    mode: git::objs::tree::EntryMode::Blob,
+                    filename: "00000000000000000000000000000000000001".into(),
+                    oid: note,
+                }],
+            })?
+            .detach();
+        let root = repo.write_object(&git::objs::Tree {
+            entries: vec![git::objs::tree::Entry {
+                mode: git::objs::tree::EntryMode::Tree,
+                filename: "00".into(),
+                oid: fanout,
+            }],
+        })?;
+        let signature = git::actor::Signature::empty();
+        repo.commit(
+            "refs/notes/textconv/pdf",
+            signature.to_ref(),
+            signature.to_ref(),
+            "pdftotext",
+            root,
+            git::commit::NO_PARENT_IDS,
+        )?;
+
+        let mut cache = repo.textconv_cache();
+        assert_eq!(
+            cache
+                .get(
+                    &driver("pdftotext"),
+                    &hex_to_id("0000000000000000000000000000000000000001")
+                )
+                .as_deref(),
+            Some(&b"converted"[..])
+        );
+        assert_eq!(
+            cache.get(
+                &driver("pdftotext"),
+                &hex_to_id("0000000000000000000000000000000000000002")
+            ),
+            None
+        );
+        Ok(())
+    }
+}
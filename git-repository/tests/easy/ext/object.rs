@@ -13,7 +13,80 @@ mod write_object {
     }
 }
 
+mod write_blob {
+    use std::process::Command;
+
+    use git_repository::prelude::FindExt;
+
+    use crate::basic_rw_repo;
+
+    fn loose_object_path(repo: &git_repository::Repository, id: git_repository::Id<'_>) -> std::path::PathBuf {
+        let hex = id.to_hex().to_string();
+        repo.git_dir().join("objects").join(&hex[..2]).join(&hex[2..])
+    }
+
+    #[test]
+    fn existing_objects_are_not_written_again() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let status = Command::new("git")
+            .args(&["repack", "-adq"])
+            .current_dir(repo.git_dir())
+            .status()?;
+        assert!(status.success(), "objects can be packed");
+
+        let packed_blob = "hello\n";
+        let id = repo.write_blob(packed_blob)?;
+        assert_eq!(repo.find_object(id)?.data, packed_blob.as_bytes());
+        assert!(
+            !loose_object_path(&repo, id).exists(),
+            "the blob exists in a pack and isn't written as loose object"
+        );
+
+        let new_blob = "new content";
+        let id = repo.write_blob(new_blob)?;
+        assert!(loose_object_path(&repo, id).is_file(), "new objects are written");
+        assert_eq!(
+            repo.write_blob_stream(new_blob.as_bytes())?,
+            id,
+            "streamed blobs are the same"
+        );
+        let mut buf = Vec::new();
+        assert_eq!(repo.objects.find_blob(id, &mut buf)?.data, new_blob.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn trees_and_commits_can_be_written() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git_repository::init_bare(&tmp)?;
+        let tree_id = repo.write_tree(&git_repository::objs::Tree::empty())?;
+        assert_eq!(tree_id, git_repository::hash::ObjectId::empty_tree(repo.object_hash()));
+
+        let signature = git_repository::actor::Signature::empty();
+        let commit = git_repository::objs::Commit {
+            tree: tree_id.detach(),
+            parents: Default::default(),
+            author: signature.clone(),
+            committer: signature,
+            encoding: None,
+            message: "initial".into(),
+            extra_headers: Default::default(),
+        };
+        let commit_id = repo.write_commit(&commit)?;
+        assert_eq!(
+            repo.write_commit(&commit)?,
+            commit_id,
+            "writing it again yields the same id"
+        );
+        assert_eq!(repo.find_object(commit_id)?.kind, git_repository::objs::Kind::Commit);
+        Ok(())
+    }
+}
+
 mod find {
+    use git_repository::object::find::existing;
+
+    use crate::basic_rw_repo;
 
     #[test]
     fn find_and_try_find_with_and_without_object_cache() -> crate::Result {
@@ -39,6 +112,47 @@ mod find {
         }
         Ok(())
     }
+
+    #[test]
+    fn missing_objects_are_distinguishable_from_unreadable_ones() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let missing = git_repository::hash::ObjectId::from_hex(b"0000000000000000000000000000000000000001")?;
+        assert!(repo.try_find_object(missing)?.is_none());
+        assert!(matches!(
+            repo.find_object(missing),
+            Err(existing::Error::NotFound { oid }) if oid == missing
+        ));
+        assert!(matches!(
+            repo.find_commit(missing),
+            Err(existing::with_conversion::Error::FindExisting(err)) if err.is_not_found()
+        ));
+
+        let id = repo.write_blob("will be corrupted")?.detach();
+        let hex = id.to_hex().to_string();
+        let path = repo.git_dir().join("objects").join(&hex[..2]).join(&hex[2..]);
+        std::fs::remove_file(&path)?;
+        std::fs::write(&path, b"not a zlib stream")?;
+        assert!(repo.try_find_object(id).is_err(), "corrupt objects aren't missing");
+        assert!(matches!(repo.find_object(id), Err(existing::Error::Find(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn objects_of_unexpected_kind_fail_to_convert() -> crate::Result {
+        let (repo, _tmp) = basic_rw_repo()?;
+        let commit = repo.head_commit()?;
+        assert_eq!(repo.find_commit(commit.id)?.id, commit.id);
+        assert_eq!(repo.find_tree(commit.tree_id()?)?.id, commit.tree_id()?);
+        assert!(matches!(
+            repo.find_tree(commit.id),
+            Err(existing::with_conversion::Error::Convert(_))
+        ));
+        assert!(matches!(
+            repo.find_tag(commit.id),
+            Err(existing::with_conversion::Error::Convert(_))
+        ));
+        Ok(())
+    }
 }
 
 mod tag {
@@ -47,11 +161,12 @@ mod tag {
         let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
         let current_head_id = repo.head_id()?;
         let message = "a multi\nline message";
+        let tagger = git_repository::actor::Signature::now_utc("tagger", "tagger@example.com");
         let tag_ref = repo.tag(
             "v1.0.0",
             &current_head_id,
             git_object::Kind::Commit,
-            Some(repo.committer().to_ref()),
+            Some(tagger.to_ref()),
             message,
             git_ref::transaction::PreviousValue::MustNotExist,
         )?;
@@ -62,7 +177,7 @@ mod tag {
         assert_eq!(tag.name, "v1.0.0");
         assert_eq!(current_head_id, tag.target(), "the tag points to the commit");
         assert_eq!(tag.target_kind, git_object::Kind::Commit);
-        assert_eq!(*tag.tagger.as_ref().expect("tagger"), repo.committer().to_ref());
+        assert_eq!(*tag.tagger.as_ref().expect("tagger"), tagger.to_ref());
         assert_eq!(tag.message, message);
         Ok(())
     }
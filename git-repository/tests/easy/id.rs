@@ -57,4 +57,37 @@ mod ancestors {
         );
         Ok(())
     }
+
+    #[test]
+    fn all_prefetched() -> crate::Result {
+        let repo = crate::repo("make_repo_with_fork_and_dates.sh")?.to_thread_local();
+        let head = repo.head()?.into_fully_peeled_id().expect("born")?;
+        for sorting in &[commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+            let expected = head
+                .ancestors()
+                .sorting(*sorting)
+                .all()?
+                .collect::<Result<Vec<_>, _>>()?;
+            for chunk_size in &[1, 3, 50] {
+                let mut iter = head.ancestors().sorting(*sorting).all_prefetched(
+                    git_repository::id::ancestors::prefetch::Options {
+                        chunk_size: *chunk_size,
+                        thread_limit: Some(2),
+                    },
+                )?;
+                let infos = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+                assert_eq!(
+                    infos.iter().map(|info| info.id).collect::<Vec<_>>(),
+                    expected,
+                    "commits are returned in traversal order no matter how they are decoded"
+                );
+                for info in &infos {
+                    let commit = info.id.object()?.into_commit();
+                    assert_eq!(info.commit, git_repository::objs::Commit::from(commit.decode()?));
+                }
+                assert_eq!(iter.is_shallow, Some(false));
+            }
+        }
+        Ok(())
+    }
 }
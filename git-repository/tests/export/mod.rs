@@ -0,0 +1,120 @@
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use git_repository as git;
+use git_repository::bstr::ByteSlice;
+use git_repository::export::{Error, Options, SignedTags};
+
+/// Import `stream` with `git fast-import` into a new repository and return the ids `refs` point to there.
+fn import_with_git(stream: &[u8], refs: &[&str]) -> crate::Result<Vec<String>> {
+    let tmp = tempfile::tempdir()?;
+    git::init_bare(tmp.path())?;
+    let mut child = Command::new("git")
+        .args(&["fast-import", "--quiet"])
+        .current_dir(tmp.path())
+        .stdin(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().expect("piped").write_all(stream)?;
+    assert!(child.wait()?.success(), "git can import the stream");
+    rev_parse(tmp.path(), refs)
+}
+
+fn rev_parse(git_dir: &Path, refs: &[&str]) -> crate::Result<Vec<String>> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .args(refs)
+        .current_dir(git_dir)
+        .output()?;
+    assert!(output.status.success(), "all references exist");
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect())
+}
+
+#[test]
+fn exported_histories_are_imported_by_git_without_changes() -> crate::Result {
+    let repo = crate::repo("make_fast_export_repo.sh")?.to_thread_local();
+    let refs = [
+        "refs/heads/main",
+        "refs/heads/other",
+        "refs/tags/lightweight",
+        "refs/tags/annotated",
+    ];
+    let mut stream = Vec::new();
+    let outcome = repo.fast_export(&refs, &mut stream, Options::default())?;
+    assert_eq!(outcome.commits, 5);
+    assert_eq!(outcome.tags, 1);
+    assert_eq!(outcome.blobs, 11, "each distinct blob is exported once");
+    assert_eq!(outcome.marks.len(), outcome.commits + outcome.blobs);
+
+    assert_eq!(import_with_git(&stream, &refs)?, rev_parse(repo.git_dir(), &refs)?);
+    Ok(())
+}
+
+#[test]
+fn symbolic_references_are_followed() -> crate::Result {
+    let repo = crate::repo("make_fast_export_repo.sh")?.to_thread_local();
+    let mut stream = Vec::new();
+    repo.fast_export(Some("HEAD"), &mut stream, Options::default())?;
+    assert!(stream.starts_with(b"blob\nmark :1\n"));
+    assert!(
+        stream.contains_str("commit refs/heads/main"),
+        "commits are exported on the branch HEAD points to"
+    );
+    assert_eq!(
+        import_with_git(&stream, &["refs/heads/main"])?,
+        rev_parse(repo.git_dir(), &["refs/heads/main"])?
+    );
+    Ok(())
+}
+
+#[test]
+fn signed_tags_abort_the_export_unless_their_signature_is_kept_or_stripped() -> crate::Result {
+    let (repo, _tmp) = crate::repo_rw("make_fast_export_repo.sh")?;
+    let target = repo.head_id()?.detach();
+    let tag_id = repo.write_object(&git::objs::Tag {
+        target,
+        target_kind: git::objs::Kind::Commit,
+        name: "signed".into(),
+        tagger: Some(git::actor::Signature::now_utc("tagger", "tagger@example.com")),
+        message: "a signed tag\n".into(),
+        pgp_signature: Some("-----BEGIN PGP SIGNATURE-----\n\nsignature\n-----END PGP SIGNATURE-----\n".into()),
+    })?;
+    repo.tag_reference("signed", tag_id, git_ref::transaction::PreviousValue::MustNotExist)?;
+
+    let refs = ["refs/tags/signed"];
+    assert!(matches!(
+        repo.fast_export(&refs, Vec::new(), Options::default()),
+        Err(Error::SignedTag { name }) if name == refs[0]
+    ));
+
+    let mut stream = Vec::new();
+    repo.fast_export(
+        &refs,
+        &mut stream,
+        Options {
+            signed_tags: SignedTags::Verbatim,
+        },
+    )?;
+    assert_eq!(
+        import_with_git(&stream, &refs)?,
+        vec![tag_id.detach().to_string()],
+        "the tag remains unchanged"
+    );
+
+    let mut stream = Vec::new();
+    repo.fast_export(
+        &refs,
+        &mut stream,
+        Options {
+            signed_tags: SignedTags::Strip,
+        },
+    )?;
+    assert_ne!(import_with_git(&stream, &refs)?, vec![tag_id.detach().to_string()]);
+    assert!(!stream.contains_str("PGP"), "the signature is removed");
+    Ok(())
+}
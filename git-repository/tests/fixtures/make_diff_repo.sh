@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.fileMode true
+
+mkdir dir
+printf 'a\nb\nc\nd\ne\n' > dir/moved
+echo unchanged > unchanged
+echo one > modified
+echo gone > deleted
+echo same > exact-source
+git add .
+git commit -q -m c1
+git tag c1
+
+git mv dir/moved renamed
+printf 'a\nb\nc\nd\nE\n' > renamed
+echo two > modified
+git rm -q deleted
+git mv exact-source exact-target
+echo new > added
+git add .
+git commit -q -m c2
+git tag c2
+
+echo three > modified
+echo staged > staged-new
+git add modified staged-new
+
+echo four > modified
+rm unchanged
+chmod +x exact-target
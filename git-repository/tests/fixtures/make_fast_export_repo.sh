@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo a > a
+mkdir dir && echo b > dir/b
+echo c > "with space"
+echo q > '"quoted'
+printf 'n\n' > 'new
+line'
+ln -s a link
+echo x > exe && chmod +x exe
+git add -A && git commit -q -m "first"
+
+git rm -q dir/b
+echo a2 > a
+mkdir sub && echo deep > sub/deep
+git add -A && git commit -q -m "second"
+
+git checkout -q -b other HEAD~1
+echo other > other
+git add other && git commit -q -m "other"
+
+git checkout -q main
+git merge -q --no-ff -m "merge" other
+git tag lightweight HEAD~1
+git tag -a -m "annotated" annotated main
+
+git rm -q a
+mkdir a && echo nested > a/file
+git add -A && git commit -q -m "file becomes directory"
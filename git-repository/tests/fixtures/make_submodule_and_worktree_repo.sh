@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q module1
+(cd module1
+  touch this
+  git add this
+  git commit -q -m c1
+)
+
+git init -q super
+(cd super
+  git -c protocol.file.allow=always submodule add -q ../module1 m1
+  git commit -q -m "add submodule"
+  git worktree add -q ../linked
+)
@@ -0,0 +1,108 @@
+use std::{process::Command, sync::atomic::AtomicBool};
+
+use git_repository as git;
+
+fn loose_and_packed_files(repo: &git::Repository) -> crate::Result<(usize, Vec<String>)> {
+    let objects = repo.git_dir().join("objects");
+    let loose = std::fs::read_dir(&objects)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().len() == 2)
+        .count();
+    let mut packed = std::fs::read_dir(objects.join("pack"))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    packed.sort();
+    Ok((loose, packed))
+}
+
+#[test]
+fn objects_are_written_into_a_single_pack_and_references_are_updated_last() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(tmp.path())?;
+    let mut import = repo.import()?;
+
+    let blob = import.write_blob("content\n")?.detach();
+    let mut tree = git::objs::Tree::empty();
+    tree.entries.push(git::objs::tree::Entry {
+        mode: git::objs::tree::EntryMode::Blob,
+        filename: "file".into(),
+        oid: blob,
+    });
+    let tree = import.write_object(&tree)?.detach();
+    let signature = git::actor::Signature::now_utc("importer", "importer@example.com");
+    let commit = import
+        .write_object(&git::objs::Commit {
+            tree,
+            parents: Default::default(),
+            author: signature.clone(),
+            committer: signature,
+            encoding: None,
+            message: "imported\n".into(),
+            extra_headers: Default::default(),
+        })?
+        .detach();
+    import.set_reference("refs/heads/main", commit)?;
+    assert_eq!(import.num_objects(), 3);
+    assert!(
+        repo.try_find_object(commit)?.is_none(),
+        "objects aren't visible before the import is committed"
+    );
+
+    let outcome = import.commit(git_features::progress::Discard, &AtomicBool::default())?;
+    let pack = outcome.pack.expect("a pack was written");
+    assert_eq!(pack.index.num_objects, 3);
+    assert_eq!(outcome.ref_edits.len(), 1);
+
+    let (loose, packed) = loose_and_packed_files(&repo)?;
+    assert_eq!(loose, 0, "no loose object was written");
+    assert_eq!(packed.len(), 2, "only the pack and its index remain: {:?}", packed);
+
+    assert_eq!(repo.find_commit(commit)?.tree_id()?, tree);
+    assert_eq!(repo.find_object(blob)?.data, b"content\n");
+    assert_eq!(repo.find_reference("refs/heads/main")?.id(), commit);
+
+    let status = Command::new("git")
+        .args(&["fsck", "--strict", "--no-dangling"])
+        .current_dir(repo.git_dir())
+        .status()?;
+    assert!(status.success(), "git considers the pack valid");
+    Ok(())
+}
+
+#[test]
+fn known_objects_are_skipped() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(tmp.path())?;
+    let existing = repo.write_blob("existing")?.detach();
+
+    let mut import = repo.import()?;
+    assert_eq!(import.write_blob("existing")?, existing);
+    let new = import.write_blob("new")?;
+    assert_eq!(import.write_blob("new")?, new);
+    assert_eq!(import.num_objects(), 1, "only the new object is written, once");
+
+    let outcome = import.commit(git_features::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(outcome.pack.expect("written").index.num_objects, 1);
+    Ok(())
+}
+
+#[test]
+fn imports_without_new_objects_write_no_pack_and_dropped_imports_leave_nothing_behind() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(tmp.path())?;
+    let mut import = repo.import()?;
+    import.write_blob("discarded")?;
+    drop(import);
+    assert_eq!(loose_and_packed_files(&repo)?, (0, Vec::new()));
+
+    let existing = repo.write_blob("existing")?.detach();
+    let mut import = repo.import()?;
+    import.write_blob("existing")?;
+    import.set_reference("refs/heads/blob", existing)?;
+    let outcome = import.commit(git_features::progress::Discard, &AtomicBool::default())?;
+    assert!(outcome.pack.is_none());
+    assert_eq!(loose_and_packed_files(&repo)?.1, Vec::<String>::new());
+    assert_eq!(repo.find_reference("refs/heads/blob")?.id(), existing);
+    Ok(())
+}
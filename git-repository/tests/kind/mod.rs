@@ -0,0 +1,175 @@
+use std::convert::TryFrom;
+
+use git_repository::Kind;
+
+fn submodule_and_worktree_repo() -> crate::Result<std::path::PathBuf> {
+    git_testtools::scripted_fixture_repo_read_only("make_submodule_and_worktree_repo.sh")
+}
+
+#[test]
+fn submodule_git_dirs_are_detected_and_use_the_configured_work_tree() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let git_dir = dir.join("super").join(".git").join("modules").join("m1");
+    assert_eq!(git_repository::path::is::git(&git_dir)?, Kind::Submodule);
+
+    let repo = git_repository::open(&git_dir)?;
+    assert_eq!(repo.kind(), Kind::Submodule);
+    assert_eq!(
+        repo.work_dir().expect("submodules have a work tree").canonicalize()?,
+        dir.join("super").join("m1").canonicalize()?,
+        "the work tree is obtained from core.worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn linked_work_trees_share_objects_references_and_configuration_with_their_main_work_tree() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let common_dir = dir.join("super").join(".git");
+    let git_dir = common_dir.join("worktrees").join("linked");
+    assert_eq!(git_repository::path::is::git(&git_dir)?, Kind::LinkedWorkTree);
+
+    let repo = git_repository::open(&git_dir)?;
+    assert_eq!(repo.kind(), Kind::LinkedWorkTree);
+    assert_eq!(repo.git_dir(), git_dir);
+    assert_eq!(repo.common_dir().canonicalize()?, common_dir.canonicalize()?);
+    assert_eq!(
+        repo.work_dir()
+            .expect("linked work trees have a work tree")
+            .canonicalize()?,
+        dir.join("linked").canonicalize()?,
+        "the work tree is obtained from the gitdir file"
+    );
+    assert_eq!(
+        repo.head()?.referent_name().expect("a branch is checked out").as_bstr(),
+        "refs/heads/linked",
+        "HEAD is the one of the linked work tree"
+    );
+
+    let main = git_repository::open(dir.join("super"))?;
+    assert_eq!(
+        repo.head_id()?.detach(),
+        main.head_id()?.detach(),
+        "the branch of the linked work tree is read from the main repository and points to the same commit"
+    );
+    assert!(repo.config_is_stale() == main.config_is_stale() && !repo.config_is_stale());
+    Ok(())
+}
+
+#[test]
+fn explicit_work_trees_take_precedence_over_core_worktree() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let elsewhere = tmp.path().join("elsewhere");
+    std::fs::create_dir(&elsewhere)?;
+    let mut repo = git_repository::init(tmp.path())?;
+    repo.convert_to_work_tree(&elsewhere)?;
+    assert_eq!(
+        git_repository::open(repo.git_dir())?.work_dir(),
+        Some(elsewhere.as_path())
+    );
+
+    let repo: git_repository::Repository =
+        git_repository::ThreadSafeRepository::try_from(git_repository::Path::WorkTree(tmp.path().into()))?.into();
+    assert_eq!(
+        repo.work_dir(),
+        Some(tmp.path()),
+        "the given work tree wins over the configured one"
+    );
+    Ok(())
+}
+
+#[test]
+fn super_projects_are_regular_work_trees() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let repo = git_repository::open(dir.join("super"))?;
+    assert_eq!(repo.kind(), Kind::WorkTree);
+    Ok(())
+}
+
+mod convert {
+    use git_repository::Kind;
+
+    #[test]
+    fn work_tree_to_bare_and_back() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let mut repo = git_repository::init(tmp.path())?;
+
+        repo.convert_to_bare()?;
+        assert_eq!(repo.kind(), Kind::Bare);
+        assert!(repo.work_dir().is_none());
+        let reopened = git_repository::open(repo.git_dir())?;
+        assert_eq!(
+            reopened.kind(),
+            Kind::Bare,
+            "the change is persisted in the configuration"
+        );
+
+        repo.convert_to_work_tree(tmp.path())?;
+        assert_eq!(repo.kind(), Kind::WorkTree);
+        assert_eq!(repo.work_dir(), Some(tmp.path()));
+        let config = std::fs::read_to_string(repo.git_dir().join("config"))?;
+        assert!(
+            !config.contains("worktree"),
+            "the default location of the work tree doesn't need to be configured"
+        );
+        let reopened = git_repository::open(repo.git_dir())?;
+        assert_eq!(reopened.kind(), Kind::WorkTree);
+        assert_eq!(reopened.work_dir(), Some(tmp.path()));
+        Ok(())
+    }
+
+    #[test]
+    fn bare_to_work_tree_in_another_location() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let git_dir = tmp.path().join("repo.git");
+        let work_tree = tmp.path().join("work-tree");
+        std::fs::create_dir(&git_dir)?;
+        let mut repo = git_repository::init_bare(&git_dir)?;
+
+        assert!(matches!(
+            repo.convert_to_work_tree(&work_tree),
+            Err(git_repository::convert::Error::MissingWorkTree { .. })
+        ));
+        assert_eq!(repo.kind(), Kind::Bare, "nothing changes on error");
+
+        std::fs::create_dir(&work_tree)?;
+        repo.convert_to_work_tree(&work_tree)?;
+        assert_eq!(repo.work_dir(), Some(work_tree.as_path()));
+
+        let reopened = git_repository::open(&git_dir)?;
+        assert_eq!(reopened.kind(), Kind::WorkTree);
+        assert_eq!(
+            reopened.work_dir(),
+            Some(work_tree.as_path()),
+            "the work tree is read from core.worktree"
+        );
+
+        repo.convert_to_bare()?;
+        let config = std::fs::read_to_string(git_dir.join("config"))?;
+        assert!(!config.contains("worktree"), "core.worktree is removed");
+        assert_eq!(git_repository::open(&git_dir)?.kind(), Kind::Bare);
+        Ok(())
+    }
+
+    #[test]
+    fn submodules_cannot_be_converted() -> crate::Result {
+        let dir = super::submodule_and_worktree_repo()?;
+        let mut repo = git_repository::open(dir.join("super").join(".git").join("modules").join("m1"))?;
+        assert!(matches!(
+            repo.convert_to_bare(),
+            Err(git_repository::convert::Error::Submodule { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn linked_work_trees_cannot_be_converted() -> crate::Result {
+        let dir = super::submodule_and_worktree_repo()?;
+        let mut repo = git_repository::open(dir.join("super").join(".git").join("worktrees").join("linked"))?;
+        assert!(matches!(
+            repo.convert_to_bare(),
+            Err(git_repository::convert::Error::LinkedWorkTree { .. })
+        ));
+        Ok(())
+    }
+}
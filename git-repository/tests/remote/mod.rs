@@ -0,0 +1,96 @@
+use std::io::Write;
+
+use git_repository::remote::Direction;
+
+fn repo_with_config(config: &str) -> crate::Result<(git_repository::Repository, tempfile::TempDir)> {
+    let tmp = tempfile::tempdir()?;
+    let repo = git_repository::init(tmp.path())?;
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?
+        .write_all(config.as_bytes())?;
+    Ok((git_repository::open(repo.git_dir())?, tmp))
+}
+
+fn url(repo: &git_repository::Repository, name: &str, direction: Direction) -> crate::Result<Option<String>> {
+    Ok(repo.remote_url(name, direction)?.map(|url| url.to_string()))
+}
+
+#[test]
+fn remote_urls_are_rewritten_with_the_longest_matching_prefix() -> crate::Result {
+    let (repo, _tmp) = repo_with_config(
+        r#"[url "ssh://a/"]
+    insteadOf = https://github.com/
+[url "ssh://b/"]
+    insteadOf = https://github.com/
+    insteadOf = https://github.com/org/
+[url "ssh://c/"]
+    pushInsteadOf = https://github.com/
+[remote "org"]
+    url = https://github.com/org/repo
+[remote "other"]
+    url = https://github.com/other/repo
+[remote "explicit-push"]
+    url = https://github.com/other/repo
+    pushUrl = https://github.com/org/push
+[remote "unrelated"]
+    url = https://example.com/repo
+[url "ssh://a/"]
+    insteadOf = https://github.co
+"#,
+    )?;
+
+    // The expectations match `git remote get-url [--push] <name>`.
+    for (name, fetch, push) in [
+        ("org", "ssh://b/repo", "ssh://c/org/repo"),
+        ("other", "ssh://a/other/repo", "ssh://c/other/repo"),
+        ("explicit-push", "ssh://a/other/repo", "ssh://b/push"),
+        ("unrelated", "https://example.com/repo", "https://example.com/repo"),
+    ] {
+        assert_eq!(url(&repo, name, Direction::Fetch)?.as_deref(), Some(fetch), "{}", name);
+        assert_eq!(url(&repo, name, Direction::Push)?.as_deref(), Some(push), "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn push_urls_fall_back_to_insteadof_without_matching_pushinsteadof() -> crate::Result {
+    let (repo, _tmp) = repo_with_config(
+        r#"[url "ssh://git@host/"]
+    insteadOf = gh:
+[remote "origin"]
+    url = gh:repo
+"#,
+    )?;
+    assert_eq!(
+        url(&repo, "origin", Direction::Fetch)?.as_deref(),
+        Some("ssh://git@host/repo")
+    );
+    assert_eq!(
+        url(&repo, "origin", Direction::Push)?.as_deref(),
+        Some("ssh://git@host/repo")
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_remotes_have_no_url() -> crate::Result {
+    let (repo, _tmp) = repo_with_config("")?;
+    assert_eq!(url(&repo, "origin", Direction::Fetch)?, None);
+    assert_eq!(url(&repo, "origin", Direction::Push)?, None);
+    Ok(())
+}
+
+#[test]
+fn invalid_rewritten_urls_are_errors() -> crate::Result {
+    let (repo, _tmp) = repo_with_config(
+        r#"[url "unknown://"]
+    insteadOf = https://
+[remote "origin"]
+    url = https://example.com/repo
+"#,
+    )?;
+    let err = repo.remote_url("origin", Direction::Fetch).unwrap_err();
+    assert_eq!(err.url, "unknown://example.com/repo");
+    Ok(())
+}
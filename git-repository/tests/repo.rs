@@ -23,7 +23,17 @@ fn basic_rw_repo() -> crate::Result<(Repository, tempfile::TempDir)> {
     repo_rw("make_basic_repo.sh")
 }
 
+mod ancestry;
+mod config;
+mod diff;
 mod discover;
 mod easy;
+mod export;
+mod import;
 mod init;
+mod kind;
 mod reference;
+mod remote;
+mod shallow;
+mod worktree;
+mod worktree_path;
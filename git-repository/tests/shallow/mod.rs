@@ -0,0 +1,70 @@
+use git_repository::lock::acquire::Fail;
+use git_testtools::hex_to_id;
+
+fn shallow_repo() -> crate::Result<git_repository::Repository> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?;
+    Ok(git_repository::open(dir.join("shallow"))?)
+}
+
+#[test]
+fn shallow_commits_are_read_from_the_shallow_file() -> crate::Result {
+    let repo = shallow_repo()?;
+    assert!(repo.is_shallow());
+    let commits = repo.shallow_commits()?.expect("shallow");
+    assert_eq!(commits.len(), 2, "both parents of the merge commit are shallow");
+    assert!(commits.windows(2).all(|w| w[0] < w[1]), "commits are sorted");
+
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?;
+    let remote = git_repository::open(dir.join("remote"))?;
+    assert!(!remote.is_shallow());
+    assert_eq!(remote.shallow_commits()?, None);
+    Ok(())
+}
+
+#[test]
+fn traversals_stop_at_shallow_commits() -> crate::Result {
+    let repo = shallow_repo()?;
+    let head = repo.head()?.into_fully_peeled_id().expect("born")?;
+
+    let mut iter = head.ancestors().all()?;
+    let commits = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(commits.len(), 3, "the merge commit and its two shallow parents");
+    assert_eq!(iter.is_shallow, Some(true));
+
+    let mut iter = head.ancestors().all_prefetched(Default::default())?;
+    let infos = iter.by_ref().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(infos.iter().map(|info| info.id).collect::<Vec<_>>(), commits);
+    assert_eq!(iter.is_shallow, Some(true));
+    Ok(())
+}
+
+#[test]
+fn update_shallow_commits_adds_and_removes_commits() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_writable("make_shallow_repo.sh")?;
+    let repo = git_repository::open(dir.path().join("shallow"))?;
+    let previous = repo.shallow_commits()?.expect("shallow");
+
+    let new_shallow = hex_to_id("0000000000000000000000000000000000000001");
+    let commits = repo
+        .update_shallow_commits(Some(new_shallow), Some(previous[0]), Fail::Immediately)?
+        .expect("still shallow");
+    assert_eq!(commits, vec![new_shallow, previous[1]]);
+    assert_eq!(
+        repo.shallow_commits()?,
+        Some(commits.clone()),
+        "the change is persisted"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.shallow_file())?,
+        format!("{}\n{}\n", commits[0], commits[1])
+    );
+
+    assert_eq!(
+        repo.update_shallow_commits(None, commits, Fail::Immediately)?,
+        None,
+        "no shallow commit is left"
+    );
+    assert!(!repo.shallow_file().exists(), "the shallow file is removed");
+    assert!(!repo.is_shallow());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::convert::TryInto;
+
+use git_repository::refs::Target;
+
+fn submodule_and_worktree_repo() -> crate::Result<std::path::PathBuf> {
+    git_testtools::scripted_fixture_repo_read_only("make_submodule_and_worktree_repo.sh")
+}
+
+#[test]
+fn linked_worktrees_provide_information_without_being_opened() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let repo = git_repository::open(dir.join("super"))?;
+    let worktrees = repo.worktrees()?;
+    assert_eq!(worktrees.len(), 1);
+
+    let linked = &worktrees[0];
+    assert_eq!(linked.id(), "linked");
+    assert_eq!(linked.git_dir(), repo.git_dir().join("worktrees").join("linked"));
+    assert_eq!(linked.base()?.canonicalize()?, dir.join("linked").canonicalize()?);
+    assert!(!linked.is_locked());
+    assert_eq!(linked.lock_reason()?, None);
+    assert_eq!(linked.prunable()?, None);
+    assert_eq!(
+        linked.head()?,
+        Some(Target::Symbolic("refs/heads/linked".try_into()?)),
+        "the branch named after the worktree is checked out"
+    );
+    Ok(())
+}
+
+#[test]
+fn linked_worktrees_can_be_opened_as_repository() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let main = git_repository::open(dir.join("super"))?;
+    let linked = main.worktrees()?.pop().expect("one linked worktree");
+    let base = linked.base()?;
+
+    let repo = linked.into_repo()?;
+    assert_eq!(repo.kind(), git_repository::Kind::LinkedWorkTree);
+    assert_eq!(repo.work_dir(), Some(base.as_path()));
+    assert_eq!(repo.common_dir().canonicalize()?, main.git_dir().canonicalize()?);
+    assert_eq!(
+        repo.worktrees()?.len(),
+        1,
+        "worktrees are listed from the common directory"
+    );
+    Ok(())
+}
+
+#[test]
+fn repositories_without_linked_worktrees_have_none() -> crate::Result {
+    let dir = submodule_and_worktree_repo()?;
+    let repo = git_repository::open(dir.join("module1"))?;
+    assert!(repo.worktrees()?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use git_repository::worktree_path::Error;
+
+#[test]
+fn relative_paths_are_relative_to_the_work_tree() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git_repository::init(tmp.path())?;
+    assert_eq!(repo.to_git_path("dir/./sub/../file")?, "dir/file");
+    assert_eq!(repo.to_git_path(".")?, "", "the root of the work tree is empty");
+    assert!(matches!(
+        repo.to_git_path("dir/../../file"),
+        Err(Error::OutsideWorkTree { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn absolute_paths_must_be_inside_of_the_work_tree() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let work_dir = tmp.path().join("repo");
+    std::fs::create_dir(&work_dir)?;
+    let repo = git_repository::init(&work_dir)?;
+
+    assert_eq!(repo.to_git_path(work_dir.join("dir").join("file"))?, "dir/file");
+    assert_eq!(repo.to_git_path(&work_dir)?, "");
+    assert_eq!(
+        repo.to_git_path(work_dir.join("dir").join("..").join("file"))?,
+        "file",
+        "the path is normalized first"
+    );
+    assert!(matches!(
+        repo.to_git_path(tmp.path().join("file")),
+        Err(Error::OutsideWorkTree { .. })
+    ));
+    assert!(matches!(
+        repo.to_git_path(work_dir.join("..").join("file")),
+        Err(Error::OutsideWorkTree { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn symbolic_links_to_the_work_tree_are_resolved() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let work_dir = tmp.path().join("repo");
+    let link = tmp.path().join("link");
+    std::fs::create_dir_all(work_dir.join("dir"))?;
+    std::os::unix::fs::symlink(&work_dir, &link)?;
+
+    let repo = git_repository::init(&work_dir)?;
+    assert_eq!(
+        repo.to_git_path(link.join("dir").join("new-file"))?,
+        "dir/new-file",
+        "paths don't have to exist"
+    );
+
+    let repo = git_repository::open(&link)?;
+    assert_eq!(repo.to_git_path(work_dir.join("dir").join("file"))?, "dir/file");
+    Ok(())
+}
+
+#[test]
+fn git_paths_are_converted_to_paths_in_the_work_tree() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git_repository::init(tmp.path())?;
+    assert_eq!(
+        repo.to_workdir_path("dir/file".into()),
+        Some(tmp.path().join("dir").join("file"))
+    );
+    assert_eq!(repo.to_workdir_path("".into()).as_deref(), Some(tmp.path()));
+    Ok(())
+}
+
+#[test]
+fn prefix_is_relative_to_the_current_directory() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git_repository::init(tmp.path())?;
+    assert!(
+        matches!(repo.prefix(), Err(Error::OutsideWorkTree { .. })),
+        "the current directory is outside of the work tree"
+    );
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_have_no_paths_relative_to_a_work_tree() -> crate::Result {
+    let tmp = tempfile::tempdir()?;
+    let repo = git_repository::init_bare(tmp.path())?;
+    assert!(matches!(repo.prefix(), Err(Error::MissingWorkTree)));
+    assert!(matches!(repo.to_git_path("file"), Err(Error::MissingWorkTree)));
+    assert!(matches!(
+        repo.to_git_path(Path::new("/file")),
+        Err(Error::MissingWorkTree)
+    ));
+    assert_eq!(repo.to_workdir_path("file".into()), None);
+    Ok(())
+}
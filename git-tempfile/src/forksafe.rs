@@ -1,4 +1,8 @@
-use std::{io::Write, path::Path};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use tempfile::{NamedTempFile, TempPath};
 
@@ -12,19 +16,27 @@ enum TempfileOrTemppath {
 pub(crate) struct ForksafeTempfile {
     inner: TempfileOrTemppath,
     cleanup: AutoRemove,
+    /// If true, the tempfile was created at a path chosen by the caller, like a lock file, instead of a generated one.
+    pub at_fixed_path: bool,
     pub owning_process_id: u32,
+    pub created_at: Instant,
+    pub last_used: Instant,
 }
 
 impl ForksafeTempfile {
-    pub fn new(tempfile: NamedTempFile, cleanup: AutoRemove, mode: handle::Mode) -> Self {
+    pub fn new(tempfile: NamedTempFile, cleanup: AutoRemove, mode: handle::Mode, at_fixed_path: bool) -> Self {
         use handle::Mode::*;
+        let now = Instant::now();
         ForksafeTempfile {
             inner: match mode {
                 Closed => TempfileOrTemppath::Temppath(tempfile.into_temp_path()),
                 Writable => TempfileOrTemppath::Tempfile(tempfile),
             },
             cleanup,
+            at_fixed_path,
             owning_process_id: std::process::id(),
+            created_at: now,
+            last_used: now,
         }
     }
 }
@@ -36,12 +48,24 @@ impl ForksafeTempfile {
             TempfileOrTemppath::Temppath(_) => None,
         }
     }
+    pub fn path(&self) -> PathBuf {
+        match &self.inner {
+            TempfileOrTemppath::Tempfile(file) => file.path().to_owned(),
+            TempfileOrTemppath::Temppath(path) => path.to_path_buf(),
+        }
+    }
+    pub fn is_writable(&self) -> bool {
+        matches!(self.inner, TempfileOrTemppath::Tempfile(_))
+    }
     pub fn close(self) -> Self {
         if let TempfileOrTemppath::Tempfile(file) = self.inner {
             ForksafeTempfile {
                 inner: TempfileOrTemppath::Temppath(file.into_temp_path()),
                 cleanup: self.cleanup,
+                at_fixed_path: self.at_fixed_path,
                 owning_process_id: self.owning_process_id,
+                created_at: self.created_at,
+                last_used: Instant::now(),
             }
         } else {
             self
@@ -42,10 +42,11 @@ impl Handle<()> {
             }
             let parent_dir = path.parent().expect("parent directory is present");
             let parent_dir = directory.resolve(parent_dir)?;
-            ForksafeTempfile::new(builder.rand_bytes(0).tempfile_in(parent_dir)?, cleanup, mode)
+            ForksafeTempfile::new(builder.rand_bytes(0).tempfile_in(parent_dir)?, cleanup, mode, true)
         };
         let id = NEXT_MAP_INDEX.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         expect_none(REGISTER.insert(id, Some(tempfile)));
+        crate::registry::enforce_limit(id);
         Ok(id)
     }
 
@@ -63,8 +64,10 @@ impl Handle<()> {
                 NamedTempFile::new_in(containing_directory)?,
                 cleanup,
                 mode,
+                false,
             )),
         ));
+        crate::registry::enforce_limit(id);
         Ok(id)
     }
 }
@@ -165,6 +168,7 @@ impl Handle<Writable> {
         match REGISTER.remove(&self.id) {
             Some((id, Some(mut t))) => {
                 let res = once(t.as_mut_tempfile().expect("correct runtime typing"));
+                t.last_used = std::time::Instant::now();
                 expect_none(REGISTER.insert(id, Some(t)));
                 Ok(res)
             }
@@ -206,7 +210,7 @@ mod io_impls {
 
 ///
 pub mod persist {
-    use std::path::Path;
+    use std::{io, path::Path};
 
     use crate::{
         handle::{expect_none, Closed, Writable},
@@ -241,15 +245,35 @@ pub mod persist {
     }
     pub use error::Error;
 
+    fn evicted(id: usize) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "The tempfile with id {} was removed as the registry limit was exceeded",
+                id
+            ),
+        )
+    }
+
     impl Handle<Writable> {
         /// Persist this tempfile to replace the file at the given `path` if necessary, in a way that recovers the original instance
         /// on error or returns the open now persisted former tempfile.
         /// Note that it might not exist anymore if an interrupt handler managed to steal it and allowed the program to return to
         /// its normal flow.
+        ///
+        /// If the tempfile was removed as the [registry limit][crate::registry::set_limit()] was exceeded, an error is returned.
         pub fn persist(self, path: impl AsRef<Path>) -> Result<Option<std::fs::File>, Error<Writable>> {
-            let res = REGISTER.remove(&self.id);
+            let res = match REGISTER.remove(&self.id) {
+                Some(res) => res,
+                None => {
+                    return Err(Error::<Writable> {
+                        error: evicted(self.id),
+                        handle: self,
+                    })
+                }
+            };
 
-            match res.and_then(|(_k, v)| v.map(|v| v.persist(path))) {
+            match res.1.map(|v| v.persist(path)) {
                 Some(Ok(Some(file))) => {
                     std::mem::forget(self);
                     Ok(Some(file))
@@ -273,10 +297,20 @@ pub mod persist {
     impl Handle<Closed> {
         /// Persist this tempfile to replace the file at the given `path` if necessary, in a way that recovers the original instance
         /// on error.
+        ///
+        /// If the tempfile was removed as the [registry limit][crate::registry::set_limit()] was exceeded, an error is returned.
         pub fn persist(self, path: impl AsRef<Path>) -> Result<(), Error<Closed>> {
-            let res = REGISTER.remove(&self.id);
+            let res = match REGISTER.remove(&self.id) {
+                Some(res) => res,
+                None => {
+                    return Err(Error::<Closed> {
+                        error: evicted(self.id),
+                        handle: self,
+                    })
+                }
+            };
 
-            match res.and_then(|(_k, v)| v.map(|v| v.persist(path))) {
+            match res.1.map(|v| v.persist(path)) {
                 None | Some(Ok(None)) => {
                     std::mem::forget(self);
                     Ok(())
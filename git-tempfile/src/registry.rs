@@ -0,0 +1,114 @@
+//! Inspect the global registry of tempfiles and optionally bound its size.
+//!
+//! Long-running processes can use [`list()`] to monitor tempfiles which are never removed, for instance because the thread
+//! owning their [`Handle`][crate::Handle] panicked while its destructor couldn't run, and [`set_limit()`] to remove the least
+//! recently used tempfiles once there are too many of them.
+use std::{
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::REGISTER;
+
+/// The maximum amount of tempfiles of the current process, with 0 meaning there is no limit.
+static LIMIT: AtomicUsize = AtomicUsize::new(0);
+
+/// Information about a registered tempfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// The path at which the tempfile is located.
+    pub path: PathBuf,
+    /// The time since the tempfile was created.
+    pub age: Duration,
+    /// The time since the tempfile was last written to, read from or closed.
+    pub idle: Duration,
+    /// If true, the tempfile is still open, otherwise it was closed and merely marks a path.
+    pub writable: bool,
+    /// The id of the process that created the tempfile, which differs from the current process in forks.
+    pub owning_process_id: u32,
+}
+
+/// Return information about all currently registered tempfiles, the oldest one first.
+///
+/// Tempfiles which are currently in use, like while [`Handle::with_mut()`][crate::Handle::with_mut()] is running, are
+/// temporarily removed from the registry and thus not listed.
+pub fn list() -> Vec<Info> {
+    let now = Instant::now();
+    let mut tempfiles: Vec<_> = REGISTER
+        .iter()
+        .filter_map(|entry| {
+            entry.value().as_ref().map(|tempfile| Info {
+                path: tempfile.path(),
+                age: now.saturating_duration_since(tempfile.created_at),
+                idle: now.saturating_duration_since(tempfile.last_used),
+                writable: tempfile.is_writable(),
+                owning_process_id: tempfile.owning_process_id,
+            })
+        })
+        .collect();
+    tempfiles.sort_by_key(|info| std::cmp::Reverse(info.age));
+    tempfiles
+}
+
+/// Return the amount of currently registered tempfiles.
+pub fn len() -> usize {
+    REGISTER.iter().filter(|entry| entry.value().is_some()).count()
+}
+
+/// Set the maximum amount of tempfiles the current process may have registered at the same time, or remove the limit
+/// with `None`, which is the default.
+///
+/// Whenever a new tempfile would exceed the limit, the least recently used tempfiles of the current process with a generated
+/// name, as created by [`new()`][crate::new()], are removed along with their empty parent directories, just like when their
+/// [`Handle`][crate::Handle] is dropped. Handles to removed tempfiles behave as if the tempfile was removed by a signal
+/// handler, and fail to persist them.
+///
+/// Tempfiles created at a given path like lock files and markers are never removed as others rely on their presence, and
+/// neither are those currently in use, so the limit may be exceeded if there aren't enough tempfiles which can be removed.
+/// Note that the limit is checked when creating new tempfiles only, so setting it doesn't remove any tempfile by itself.
+pub fn set_limit(limit: Option<NonZeroUsize>) {
+    LIMIT.store(limit.map_or(0, NonZeroUsize::get), Ordering::SeqCst);
+}
+
+/// Return the limit previously set with [`set_limit()`], or `None` if there is no limit.
+pub fn limit() -> Option<NonZeroUsize> {
+    NonZeroUsize::new(LIMIT.load(Ordering::SeqCst))
+}
+
+/// Remove the least recently used tempfiles of the current process with a generated name other than the one with `new_id`
+/// until the limit is respected or there are no such tempfiles left.
+pub(crate) fn enforce_limit(new_id: usize) {
+    let limit = match limit() {
+        Some(limit) => limit.get(),
+        None => return,
+    };
+    let current_pid = std::process::id();
+    let tempfiles: Vec<_> = REGISTER
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .value()
+                .as_ref()
+                .filter(|tempfile| tempfile.owning_process_id == current_pid)
+                .map(|tempfile| (tempfile.last_used, *entry.key(), tempfile.at_fixed_path))
+        })
+        .collect();
+    let num_to_remove = tempfiles.len().saturating_sub(limit);
+    if num_to_remove == 0 {
+        return;
+    }
+    // The new tempfile is counted but never removed, and neither are tempfiles at fixed paths like lock files.
+    let mut candidates: Vec<_> = tempfiles
+        .into_iter()
+        .filter(|(_, id, at_fixed_path)| !at_fixed_path && *id != new_id)
+        .map(|(last_used, id, _)| (last_used, id))
+        .collect();
+    candidates.sort();
+    for (_, id) in candidates.into_iter().take(num_to_remove) {
+        if let Some((_id, Some(tempfile))) = REGISTER.remove(&id) {
+            tempfile.drop_impl();
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::{io::Write, num::NonZeroUsize};
+
+use git_tempfile::{registry, AutoRemove, ContainingDirectory};
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[test]
+fn least_recently_used_tempfiles_are_removed_once_the_limit_is_exceeded() -> Result {
+    assert_eq!(registry::limit(), None, "there is no limit by default");
+    let dir = tempfile::tempdir()?;
+    let new_tempfile = || git_tempfile::new(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile);
+    let lock_path = dir.path().join("file.lock");
+    let _lock = git_tempfile::mark_at(&lock_path, ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+    let mut first = new_tempfile()?;
+    let second = new_tempfile()?;
+    let first_path = first.with_mut(|f| f.path().to_owned())?;
+    let second_path = registry::list()
+        .into_iter()
+        .map(|info| info.path)
+        .find(|path| path != &first_path && path != &lock_path)
+        .expect("two tempfiles");
+
+    registry::set_limit(NonZeroUsize::new(3));
+    assert_eq!(registry::len(), 3, "setting the limit doesn't remove tempfiles");
+    first.write_all(b"used recently")?;
+
+    let _third = new_tempfile()?;
+    assert_eq!(registry::len(), 3);
+    assert!(
+        lock_path.is_file(),
+        "tempfiles at fixed paths like lock files are never removed"
+    );
+    assert!(first_path.is_file(), "the recently used tempfile is kept");
+    assert!(!second_path.exists(), "the least recently used tempfile is removed");
+    let err = second
+        .persist(dir.path().join("persisted"))
+        .expect_err("removed tempfiles can't be persisted");
+    assert_eq!(err.error.kind(), std::io::ErrorKind::NotFound);
+
+    registry::set_limit(None);
+    let _fourth = new_tempfile()?;
+    assert_eq!(registry::len(), 4, "without limit tempfiles are kept");
+    Ok(())
+}
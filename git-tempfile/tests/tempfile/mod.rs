@@ -1,5 +1,6 @@
 mod fs;
 mod handle;
+mod registry;
 
 mod setup {
     #[test]
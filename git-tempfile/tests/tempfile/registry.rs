@@ -0,0 +1,34 @@
+use git_tempfile::{registry, AutoRemove, ContainingDirectory};
+
+#[test]
+fn list_shows_registered_tempfiles_until_they_are_dropped() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    let in_dir = |infos: Vec<registry::Info>| -> Vec<registry::Info> {
+        infos
+            .into_iter()
+            .filter(|info| info.path.starts_with(dir.path()))
+            .collect()
+    };
+    let writable = git_tempfile::new(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+    let marker = git_tempfile::mark_at(
+        dir.path().join("marker"),
+        ContainingDirectory::Exists,
+        AutoRemove::Tempfile,
+    )?;
+
+    let infos = in_dir(registry::list());
+    assert_eq!(infos.len(), 2);
+    assert!(infos[0].age >= infos[1].age, "the oldest tempfile comes first");
+    assert!(infos[0].writable);
+    assert_eq!(infos[1].path, dir.path().join("marker"));
+    assert!(!infos[1].writable, "markers are closed");
+    assert!(infos
+        .iter()
+        .all(|info| info.owning_process_id == std::process::id() && info.idle <= info.age));
+    assert!(registry::len() >= 2, "other tests may register tempfiles concurrently");
+
+    drop(writable);
+    drop(marker);
+    assert!(in_dir(registry::list()).is_empty());
+    Ok(())
+}
@@ -26,6 +26,10 @@ pub trait ExtendedBufRead: io::BufRead {
     fn reset(&mut self, version: Protocol);
     /// Return the kind of message at which the reader stopped.
     fn stopped_at(&self) -> Option<MessageKind>;
+    /// Stop decoding packet lines and pass all remaining bytes through as they are received.
+    ///
+    /// This is how packs have to be read if no side-band was negotiated with a `V0` or `V1` server.
+    fn pass_through_raw_data(&mut self);
 }
 
 impl<'a, T: ExtendedBufRead + ?Sized + 'a> ExtendedBufRead for Box<T> {
@@ -44,6 +48,10 @@ impl<'a, T: ExtendedBufRead + ?Sized + 'a> ExtendedBufRead for Box<T> {
     fn stopped_at(&self) -> Option<MessageKind> {
         self.deref().stopped_at()
     }
+
+    fn pass_through_raw_data(&mut self) {
+        self.deref_mut().pass_through_raw_data()
+    }
 }
 
 impl<'a, T: io::Read> ExtendedBufRead for git_packetline::read::WithSidebands<'a, T, HandleProgress> {
@@ -60,7 +68,7 @@ impl<'a, T: io::Read> ExtendedBufRead for git_packetline::read::WithSidebands<'a
     }
     fn reset(&mut self, version: Protocol) {
         match version {
-            Protocol::V1 => self.reset_with(&[git_packetline::PacketLineRef::Flush]),
+            Protocol::V0 | Protocol::V1 => self.reset_with(&[git_packetline::PacketLineRef::Flush]),
             Protocol::V2 => self.reset_with(&[
                 git_packetline::PacketLineRef::Delimiter,
                 git_packetline::PacketLineRef::Flush,
@@ -75,4 +83,7 @@ impl<'a, T: io::Read> ExtendedBufRead for git_packetline::read::WithSidebands<'a
             git_packetline::PacketLineRef::Data(_) => unreachable!("data cannot be a delimiter"),
         })
     }
+    fn pass_through_raw_data(&mut self) {
+        self.pass_through_raw_data()
+    }
 }
@@ -16,7 +16,8 @@ pub fn connect(url: &[u8], desired_version: crate::Protocol) -> Result<Box<dyn T
     Ok(match url.scheme {
         git_url::Scheme::Radicle => return Err(Error::UnsupportedScheme(url.scheme)),
         git_url::Scheme::File => {
-            if url.user.is_some() || url.host.is_some() || url.port.is_some() {
+            // like git, ignore the host of `file://host/path` URLs
+            if url.user.is_some() || url.port.is_some() {
                 return Err(Error::UnsupportedUrlTokens(urlb.into(), url.scheme));
             }
             Box::new(
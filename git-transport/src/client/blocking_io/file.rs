@@ -76,6 +76,7 @@ impl SpawnProcessOnDemand {
                 host: None,
                 port: None,
                 path: path.clone(),
+                serialize_alternative_form: false,
             },
             path,
             ssh_program: None,
@@ -33,9 +33,7 @@ impl Handler {
     }
     fn parse_status(data: &[u8]) -> Option<(usize, Box<dyn std::error::Error + Send + Sync>)> {
         match Self::parse_status_inner(data) {
-            Ok(status) if !(200..=299).contains(&status) => {
-                Some((status, format!("Received HTTP status {}", status).into()))
-            }
+            Ok(status) if !(200..=299).contains(&status) => Some((status, http::StatusError { status }.into())),
             Ok(_) => None,
             Err(err) => Some((500, err)),
         }
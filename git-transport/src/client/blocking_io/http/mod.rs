@@ -5,7 +5,7 @@ use std::{
 };
 
 use git_packetline::PacketLineRef;
-pub use traits::{Error, GetResponse, Http, PostResponse};
+pub use traits::{Error, GetResponse, Http, PostResponse, StatusError};
 
 use crate::{
     client::{self, capabilities, Capabilities, ExtendedBufRead, HandleProgress, MessageKind, RequestWriter},
@@ -25,7 +25,7 @@ pub type Impl = curl::Curl;
 /// A transport for supporting arbitrary http clients by abstracting interactions with them into the [Http] trait.
 pub struct Transport<H: Http> {
     url: String,
-    user_agent_header: &'static str,
+    user_agent_header: Cow<'static, str>,
     desired_version: crate::Protocol,
     supported_versions: [crate::Protocol; 1],
     actual_version: crate::Protocol,
@@ -40,10 +40,10 @@ impl Transport<Impl> {
     pub fn new(url: &str, desired_version: crate::Protocol) -> Self {
         Transport {
             url: url.to_owned(),
-            user_agent_header: concat!("User-Agent: git/oxide-", env!("CARGO_PKG_VERSION")),
+            user_agent_header: concat!("User-Agent: git/oxide-", env!("CARGO_PKG_VERSION")).into(),
             desired_version,
             actual_version: desired_version,
-            supported_versions: [desired_version],
+            supported_versions: [supported_version(desired_version)],
             service: None,
             http: Impl::default(),
             line_provider: None,
@@ -53,22 +53,101 @@ impl Transport<Impl> {
 }
 
 impl<H: Http> Transport<H> {
-    fn check_content_type(service: Service, kind: &str, headers: <H as Http>::Headers) -> Result<(), client::Error> {
-        let wanted_content_type = format!("Content-Type: application/x-{}-{}", service.as_str(), kind);
-        if !headers
+    fn content_type(service: Service, kind: &str) -> String {
+        format!("Content-Type: application/x-{}-{}", service.as_str(), kind)
+    }
+
+    fn has_header(headers: <H as Http>::Headers, wanted: &str) -> io::Result<bool> {
+        Ok(headers
             .lines()
             .collect::<Result<Vec<_>, _>>()?
             .iter()
-            .any(|l| l == &wanted_content_type)
-        {
+            .any(|l| l == wanted))
+    }
+
+    fn check_content_type(service: Service, kind: &str, headers: <H as Http>::Headers) -> Result<(), client::Error> {
+        let wanted_content_type = Self::content_type(service, kind);
+        if !Self::has_header(headers, &wanted_content_type)? {
             return Err(client::Error::Http(Error::Detail(format!(
-                "Didn't find '{}' header to indicate 'smart' protocol, and 'dumb' protocol is not supported.",
+                "Didn't find '{}' header to indicate 'smart' protocol",
                 wanted_content_type
             ))));
         }
         Ok(())
     }
 
+    /// Identify ourselves as `agent`, like `git/2.36.0`, in the `User-Agent` header of all subsequent requests.
+    ///
+    /// This is useful to let the server know which product it is talking to, and should match the value of
+    /// the `agent` capability used in the protocol itself.
+    pub fn set_user_agent(&mut self, agent: &str) {
+        self.user_agent_header = format!("User-Agent: {}", agent).into();
+    }
+
+    /// Retrieve the file at `path` relative to the repository URL, which is all servers supporting only the 'dumb' HTTP protocol
+    /// can do, typically after [`handshake()`][client::Transport::handshake()] failed with [`client::Error::DumbHttpServer`].
+    ///
+    /// Note that the identity set with [`set_identity()`][client::TransportWithoutIO::set_identity()] is used here as well.
+    pub fn get_file(&mut self, path: &str) -> Result<H::ResponseBody, client::Error> {
+        let url = append_url(&self.url, path);
+        let static_headers = [Cow::Borrowed(self.user_agent_header.as_ref())];
+        let mut dynamic_headers = Vec::<Cow<'_, str>>::new();
+        self.add_basic_auth_if_present(&mut dynamic_headers)?;
+        let GetResponse { headers, body } = self.http.get(&url, static_headers.iter().chain(&dynamic_headers))?;
+        // Errors like unexpected status codes are reported when reading headers.
+        for line in headers.lines() {
+            line?;
+        }
+        Ok(body)
+    }
+
+    /// Return true if a `Git-Protocol` header is sent along with the advertisement request.
+    fn sends_protocol_header(&self, extra_parameters: &[(&str, Option<&str>)]) -> bool {
+        match self.desired_version {
+            Protocol::V0 => false,
+            Protocol::V1 => !extra_parameters.is_empty(),
+            Protocol::V2 => true,
+        }
+    }
+
+    fn get_advertisement(
+        &mut self,
+        service: Service,
+        extra_parameters: &[(&str, Option<&str>)],
+    ) -> Result<H::ResponseBody, client::Error> {
+        let url = append_url(&self.url, &format!("info/refs?service={}", service.as_str()));
+        let static_headers = [Cow::Borrowed(self.user_agent_header.as_ref())];
+        let mut dynamic_headers = Vec::<Cow<'_, str>>::new();
+        if self.sends_protocol_header(extra_parameters) {
+            let mut parameters = if self.desired_version == Protocol::V2 {
+                let mut p = format!("version={}", self.desired_version as usize);
+                if !extra_parameters.is_empty() {
+                    p.push(':');
+                }
+                p
+            } else {
+                String::new()
+            };
+            parameters.push_str(
+                &extra_parameters
+                    .iter()
+                    .map(|(key, value)| match value {
+                        Some(value) => format!("{}={}", key, value),
+                        None => key.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(":"),
+            );
+            dynamic_headers.push(format!("Git-Protocol: {}", parameters).into());
+        }
+        self.add_basic_auth_if_present(&mut dynamic_headers)?;
+        let GetResponse { headers, body } = self.http.get(&url, static_headers.iter().chain(&dynamic_headers))?;
+        if !Self::has_header(headers, &Self::content_type(service, "advertisement"))? {
+            return Err(client::Error::DumbHttpServer);
+        }
+        Ok(body)
+    }
+
     #[allow(clippy::unnecessary_wraps, unknown_lints)]
     fn add_basic_auth_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
         if let Some(identity) = &self.identity {
@@ -113,14 +192,14 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
         let service = self.service.expect("handshake() must have been called first");
         let url = append_url(&self.url, service.as_str());
         let static_headers = &[
-            Cow::Borrowed(self.user_agent_header),
+            Cow::Borrowed(self.user_agent_header.as_ref()),
             Cow::Owned(format!("Content-Type: application/x-{}-request", service.as_str())),
             format!("Accept: application/x-{}-result", service.as_str()).into(),
             "Expect:".into(), // needed to avoid sending Expect: 100-continue, which adds another response and only CURL wants that
         ];
         let mut dynamic_headers = Vec::new();
         self.add_basic_auth_if_present(&mut dynamic_headers)?;
-        if self.actual_version != Protocol::V1 {
+        if self.actual_version == Protocol::V2 {
             dynamic_headers.push(Cow::Owned(format!(
                 "Git-Protocol: version={}",
                 self.actual_version as usize
@@ -168,34 +247,17 @@ impl<H: Http> client::Transport for Transport<H> {
         service: Service,
         extra_parameters: &'a [(&'a str, Option<&'a str>)],
     ) -> Result<client::SetServiceResponse<'_>, client::Error> {
-        let url = append_url(&self.url, &format!("info/refs?service={}", service.as_str()));
-        let static_headers = [Cow::Borrowed(self.user_agent_header)];
-        let mut dynamic_headers = Vec::<Cow<'_, str>>::new();
-        if self.desired_version != Protocol::V1 || !extra_parameters.is_empty() {
-            let mut parameters = if self.desired_version != Protocol::V1 {
-                let mut p = format!("version={}", self.desired_version as usize);
-                if !extra_parameters.is_empty() {
-                    p.push(':');
-                }
-                p
-            } else {
-                String::new()
-            };
-            parameters.push_str(
-                &extra_parameters
-                    .iter()
-                    .map(|(key, value)| match value {
-                        Some(value) => format!("{}={}", key, value),
-                        None => key.to_string(),
-                    })
-                    .collect::<Vec<_>>()
-                    .join(":"),
-            );
-            dynamic_headers.push(format!("Git-Protocol: {}", parameters).into());
-        }
-        self.add_basic_auth_if_present(&mut dynamic_headers)?;
-        let GetResponse { headers, body } = self.http.get(&url, static_headers.iter().chain(&dynamic_headers))?;
-        <Transport<H>>::check_content_type(service, "advertisement", headers)?;
+        let body = match self.get_advertisement(service, extra_parameters) {
+            // Some servers and proxies reject requests for a protocol version or extra parameters as malformed,
+            // so try again without them.
+            Err(client::Error::Io { err }) if is_bad_request(&err) && self.sends_protocol_header(extra_parameters) => {
+                self.desired_version = Protocol::V0;
+                self.actual_version = Protocol::V0;
+                self.supported_versions = [supported_version(Protocol::V0)];
+                self.get_advertisement(service, extra_parameters)?
+            }
+            res => res?,
+        };
 
         let line_reader = self
             .line_provider
@@ -280,6 +342,26 @@ impl<H: Http, B: ExtendedBufRead + Unpin> ExtendedBufRead for HeadersThenBody<H,
     fn stopped_at(&self) -> Option<MessageKind> {
         self.body.stopped_at()
     }
+
+    fn pass_through_raw_data(&mut self) {
+        self.body.pass_through_raw_data()
+    }
+}
+
+/// Return true if `err` indicates that the server didn't understand our request, which is what servers unable to handle
+/// the `Git-Protocol` header respond with.
+fn is_bad_request(err: &io::Error) -> bool {
+    err.get_ref()
+        .and_then(|err| err.downcast_ref::<StatusError>())
+        .map_or(false, |err| err.status == 400)
+}
+
+/// The version to accept from servers if `desired_version` is requested.
+fn supported_version(desired_version: Protocol) -> Protocol {
+    match desired_version {
+        Protocol::V0 => Protocol::V1,
+        version => version,
+    }
 }
 
 /// Connect to the given `url` via HTTP/S using the `desired_version` of the `git` protocol.
@@ -18,6 +18,21 @@ quick_error! {
     }
 }
 
+/// The error indicating that the server responded with an unsuccessful HTTP `status`, as wrapped into an `std::io::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusError {
+    /// The HTTP status code, like 404.
+    pub status: usize,
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Received HTTP status {}", self.status)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
 /// The return value of [Http::get()].
 pub struct GetResponse<H, B> {
     /// The response headers.
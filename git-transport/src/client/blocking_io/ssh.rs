@@ -42,7 +42,7 @@ pub fn connect(
     type EnvVar = (&'static str, String);
     let args_and_env: Option<(Vec<Cow<'_, str>>, Vec<EnvVar>)> = match ssh_cmd {
         "ssh" | "ssh.exe" => {
-            if desired_version != Protocol::V1 {
+            if desired_version == Protocol::V2 {
                 let mut args = vec![Cow::from("-o"), "SendEnv=GIT_PROTOCOL".into()];
                 if let Some(port) = port {
                     args.push(format!("-p={}", port).into());
@@ -70,6 +70,7 @@ pub fn connect(
         host: Some(host.clone()),
         port,
         path: path.clone(),
+        serialize_alternative_form: false,
     };
     Ok(match args_and_env {
         Some((args, envs)) => blocking_io::file::SpawnProcessOnDemand::new_ssh(
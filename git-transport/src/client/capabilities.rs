@@ -1,6 +1,6 @@
 use std::io;
 
-use bstr::{BStr, BString, ByteSlice};
+use bstr::{BStr, BString, ByteSlice, ByteVec};
 use quick_error::quick_error;
 
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
@@ -121,6 +121,13 @@ impl Capabilities {
         })
     }
 
+    fn empty() -> Self {
+        Capabilities {
+            data: BString::default(),
+            value_sep: b' ',
+        }
+    }
+
     /// Returns true of the given `feature` is mentioned in this list of capabilities.
     pub fn contains(&self, feature: &str) -> bool {
         self.capability(feature).is_some()
@@ -135,8 +142,42 @@ impl Capabilities {
     pub fn iter(&self) -> impl Iterator<Item = Capability<'_>> {
         self.data
             .split(move |b| *b == self.value_sep)
+            .filter(|c| !c.is_empty())
             .map(|c| Capability(c.as_bstr()))
     }
+
+    /// Add the capability `name` with an optional `value`, replacing an existing capability of the same name.
+    ///
+    /// This is useful to pretend the server advertised a capability it supports without saying so.
+    pub fn set(&mut self, name: &str, value: Option<&str>) {
+        self.remove(name);
+        if !self.data.is_empty() {
+            self.data.push(self.value_sep);
+        }
+        self.data.push_str(name);
+        if let Some(value) = value {
+            self.data.push(b'=');
+            self.data.push_str(value);
+        }
+    }
+
+    /// Remove the capability `name` and return true if it was present.
+    ///
+    /// This is useful to pretend the server never advertised a capability, for instance to avoid using it if it is known to be broken.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let value_sep = self.value_sep;
+        let num_capabilities = self.iter().count();
+        let retained: Vec<_> = self
+            .iter()
+            .filter(|c| c.name() != name.as_bytes().as_bstr())
+            .map(|c| c.0)
+            .collect();
+        if retained.len() == num_capabilities {
+            return false;
+        }
+        self.data = bstr::join([value_sep], retained).into();
+        true
+    }
 }
 
 /// internal use
@@ -206,9 +247,16 @@ pub mod recv {
 
             let (first_line, version) = Capabilities::extract_protocol(&capabilities_or_version)?;
             match version {
-                Protocol::V1 => {
-                    let (capabilities, delimiter_position) = Capabilities::from_bytes(first_line.0)?;
-                    rd.peek_buffer_replace_and_truncate(delimiter_position, b'\n');
+                Protocol::V0 | Protocol::V1 => {
+                    let capabilities = match Capabilities::from_bytes(first_line.0) {
+                        Ok((capabilities, delimiter_position)) => {
+                            rd.peek_buffer_replace_and_truncate(delimiter_position, b'\n');
+                            capabilities
+                        }
+                        // Ancient servers don't advertise any capabilities.
+                        Err(super::Error::MissingDelimitingNullByte) => Capabilities::empty(),
+                        Err(err) => return Err(err.into()),
+                    };
                     Ok(Outcome {
                         capabilities,
                         refs: Some(Box::new(rd.as_read())),
@@ -272,9 +320,16 @@ pub mod recv {
 
             let (first_line, version) = Capabilities::extract_protocol(&capabilities_or_version)?;
             match version {
-                Protocol::V1 => {
-                    let (capabilities, delimiter_position) = Capabilities::from_bytes(first_line.0)?;
-                    rd.peek_buffer_replace_and_truncate(delimiter_position, b'\n');
+                Protocol::V0 | Protocol::V1 => {
+                    let capabilities = match Capabilities::from_bytes(first_line.0) {
+                        Ok((capabilities, delimiter_position)) => {
+                            rd.peek_buffer_replace_and_truncate(delimiter_position, b'\n');
+                            capabilities
+                        }
+                        // Ancient servers don't advertise any capabilities.
+                        Err(super::Error::MissingDelimitingNullByte) => Capabilities::empty(),
+                        Err(err) => return Err(err.into()),
+                    };
                     Ok(Outcome {
                         capabilities,
                         refs: Some(Box::new(rd.as_read())),
@@ -35,6 +35,7 @@ where
                     host: None,
                     port: None,
                     path: self.path.clone(),
+                    serialize_alternative_form: false,
                 }
                 .to_string()
             },
@@ -45,7 +46,7 @@ where
     /// We implement this in a paranoid and safe way, not allowing downgrade to V1 which
     /// could send large amounts of refs in case we didn't want to support V1.
     fn supported_protocol_versions(&self) -> &[Protocol] {
-        if self.desired_version == Protocol::V1 {
+        if matches!(self.desired_version, Protocol::V0 | Protocol::V1) {
             // allow any version
             &[]
         } else {
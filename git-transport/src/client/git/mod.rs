@@ -73,13 +73,15 @@ mod message {
         // as extra lines in the reply, which we don't want to handle. Especially since an old server will not respond with that
         // line (is what I assume, at least), so it's an optional part in the response to understand and handle. There is no value
         // in that, so let's help V2 servers to respond in a way that assumes V1.
-        let extra_params_need_null_prefix = if version != Protocol::V1 {
-            out.push(0);
-            out.push_str(format!("version={}", version as usize));
-            out.push(0);
-            false
-        } else {
-            true
+        let extra_params_need_null_prefix = match version {
+            Protocol::V0 => return out,
+            Protocol::V1 => true,
+            Protocol::V2 => {
+                out.push(0);
+                out.push_str(format!("version={}", version as usize));
+                out.push(0);
+                false
+            }
         };
 
         if !extra_parameters.is_empty() {
@@ -166,6 +168,19 @@ mod message {
                 "git-upload-pack hello\\world\0host=host:404\0"
             )
         }
+        #[test]
+        fn version_0_with_host_ignores_extra_parameters() {
+            assert_eq!(
+                git::message::connect(
+                    Service::UploadPack,
+                    Protocol::V0,
+                    b"hello\\world",
+                    Some(&("host".into(), None)),
+                    &[("key", Some("value"))]
+                ),
+                "git-upload-pack hello\\world\0host=host\0"
+            )
+        }
     }
 }
 
@@ -119,6 +119,8 @@ mod error {
         AuthenticationRefused(&'static str),
         #[error("The protocol version indicated by {:?} is unsupported", {0})]
         UnsupportedProtocolVersion(BString),
+        #[error("The server only supports the 'dumb' HTTP protocol, which can't be used with this transport")]
+        DumbHttpServer,
         #[error(transparent)]
         Http(#[from] HttpError),
     }
@@ -17,6 +17,11 @@ pub use git_packetline as packetline;
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 #[allow(missing_docs)]
 pub enum Protocol {
+    /// The original protocol, which is [`V1`][Protocol::V1] without asking the server for a version or sending extra
+    /// parameters, for servers that misbehave when receiving these.
+    ///
+    /// Servers never respond with this version, as their response is indistinguishable from `V1`.
+    V0 = 0,
     V1 = 1,
     V2 = 2,
 }
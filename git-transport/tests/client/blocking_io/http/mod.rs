@@ -99,6 +99,91 @@ fn http_error_results_in_observable_error() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn servers_rejecting_the_protocol_version_are_retried_with_v0() -> crate::Result {
+    let (server, mut client) = mock::serve_and_connect("http-400.response", "path/not-important", Protocol::V2)?;
+    let server = std::thread::spawn(move || {
+        let rejected = server.received_as_string();
+        server.next_read_and_respond_with(fixture_bytes("v1/http-handshake.response"));
+        (rejected, server.received_as_string())
+    });
+    let SetServiceResponse { actual_protocol, .. } = client.handshake(Service::UploadPack, &[])?;
+    assert_eq!(actual_protocol, Protocol::V1, "V0 is the same as V1 on the wire");
+
+    let (rejected, retried) = server.join().expect("no panic");
+    assert!(rejected.contains("Git-Protocol: version=2"));
+    assert!(
+        !retried.contains("Git-Protocol"),
+        "the retry doesn't request any protocol version"
+    );
+    Ok(())
+}
+
+#[test]
+fn only_bad_requests_cause_a_retry_with_v0() -> crate::Result {
+    let (server, mut client) = mock::serve_and_connect("http-404.response", "path/not-important", Protocol::V2)?;
+    let error = client
+        .handshake(Service::UploadPack, &[])
+        .err()
+        .expect("non-200 status causes error");
+    assert!(matches!(error, client::Error::Io { .. }));
+    assert!(server.received_as_string().contains("Git-Protocol: version=2"));
+    Ok(())
+}
+
+#[test]
+fn dumb_server_is_detected_and_files_can_be_retrieved() -> crate::Result {
+    let (server, mut client) =
+        mock::serve_and_connect("http-dumb-info-refs.response", "path/not-important", Protocol::V2)?;
+    let error = client
+        .handshake(Service::UploadPack, &[])
+        .err()
+        .expect("the advertisement isn't from a smart server");
+    assert!(matches!(error, client::Error::DumbHttpServer));
+    drop(server.received());
+
+    server.next_read_and_respond_with(fixture_bytes("http-dumb-info-refs.response"));
+    let mut refs = String::new();
+    client.get_file("info/refs")?.read_to_string(&mut refs)?;
+    assert_eq!(
+        refs,
+        "808e50d724f604f69ab93c6da2919c014667bedb\trefs/heads/master\n808e50d724f604f69ab93c6da2919c014667bedb\trefs/tags/v1.0\n"
+    );
+    assert_eq!(
+        server.received_as_string().lines().collect::<Vec<_>>(),
+        format!(
+            "GET /path/not-important/info/refs HTTP/1.1
+Host: 127.0.0.1:{}
+Accept: */*
+User-Agent: git/oxide-{}
+
+",
+            server.addr.port(),
+            env!("CARGO_PKG_VERSION")
+        )
+        .lines()
+        .collect::<Vec<_>>(),
+        "no protocol version is requested when retrieving files"
+    );
+    Ok(())
+}
+
+#[test]
+fn user_agent_can_be_customized() -> crate::Result {
+    let (server, mut client) =
+        mock::serve_and_connect("v1/http-handshake.response", "path/not-important", Protocol::V1)?;
+    client.set_user_agent("product/1.0");
+    client.handshake(Service::UploadPack, &[])?;
+    assert!(
+        server
+            .received_as_string()
+            .lines()
+            .any(|line| line == "User-Agent: product/1.0"),
+        "the custom agent is used instead of the default one"
+    );
+    Ok(())
+}
+
 #[test]
 fn handshake_v1() -> crate::Result {
     let (server, mut c) = mock::serve_and_connect(
@@ -49,3 +49,35 @@ fn from_bytes() -> crate::Result {
     );
     Ok(())
 }
+
+#[test]
+fn set_and_remove() -> crate::Result {
+    let (mut caps, _) = Capabilities::from_bytes(&b"\0multi_ack thin-pack agent=git/2.28.0"[..])?;
+    assert!(caps.remove("thin-pack"));
+    assert!(!caps.remove("thin-pack"), "it's gone after the first removal");
+    caps.set("agent", Some("custom/1.0"));
+    caps.set("no-done", None);
+    assert_eq!(
+        caps.iter()
+            .map(|c| (c.name().to_owned(), c.value().map(ToOwned::to_owned)))
+            .collect::<Vec<_>>(),
+        vec![
+            ("multi_ack".into(), None),
+            ("agent".into(), Some("custom/1.0".into())),
+            ("no-done".into(), None)
+        ]
+    );
+
+    let mut caps = Capabilities::from_lines(
+        Some(Ok("version 2".into())),
+        "agent=git/2.28.0\nls-refs\nfetch=shallow filter",
+    )?;
+    assert!(caps.remove("ls-refs"));
+    caps.set("fetch", Some("shallow"));
+    assert_eq!(caps.iter().count(), 2, "capabilities are still separated by newlines");
+    assert_eq!(
+        caps.capability("fetch").and_then(|c| c.value().map(ToOwned::to_owned)),
+        Some("shallow".into())
+    );
+    Ok(())
+}
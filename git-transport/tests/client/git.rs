@@ -16,6 +16,51 @@ use git_transport::{
 
 use crate::fixture_bytes;
 
+#[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+async fn handshake_v0_with_server_not_advertising_capabilities() -> crate::Result {
+    let mut out = Vec::new();
+    let server_response = b"0032808e50d724f604f69ab93c6da2919c014667bedb HEAD\n003f808e50d724f604f69ab93c6da2919c014667bedb refs/heads/master\n0000";
+    let mut c = git::Connection::new(
+        &server_response[..],
+        &mut out,
+        Protocol::V0,
+        "/foo.git",
+        Some(("example.org", None)),
+        git::ConnectMode::Daemon,
+    );
+    let mut res = c.handshake(Service::UploadPack, &[("key", Some("value"))]).await?;
+    assert_eq!(res.actual_protocol, Protocol::V1, "V0 is the same as V1 on the wire");
+    assert_eq!(
+        res.capabilities.iter().count(),
+        0,
+        "ancient servers don't send capabilities"
+    );
+
+    let mut lines = res.refs.as_mut().expect("v0 protocol provides refs").lines();
+    let mut refs = Vec::new();
+    #[allow(clippy::while_let_on_iterator)] // needed in async version of test
+    while let Some(line) = lines.next().await {
+        refs.push(line?)
+    }
+    drop(lines);
+    assert_eq!(
+        refs,
+        vec![
+            "808e50d724f604f69ab93c6da2919c014667bedb HEAD",
+            "808e50d724f604f69ab93c6da2919c014667bedb refs/heads/master"
+        ],
+        "the first ref isn't lost even though there is no capabilities delimiter"
+    );
+    drop(res);
+    drop(c);
+    assert_eq!(
+        out.as_bstr(),
+        b"002egit-upload-pack /foo.git\0host=example.org\0".as_bstr(),
+        "neither the version nor extra parameters are sent"
+    );
+    Ok(())
+}
+
 #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
 async fn handshake_v1_and_request() -> crate::Result {
     let mut out = Vec::new();
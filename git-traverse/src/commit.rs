@@ -5,6 +5,7 @@ pub struct Ancestors<Find, Predicate, StateMut> {
     state: StateMut,
     parents: Parents,
     sorting: Sorting,
+    since: Option<u32>,
 }
 
 /// Specify how to handle commit parents during traversal.
@@ -31,6 +32,10 @@ pub enum Sorting {
     ///
     /// The sorting applies to all currently queued commit ids and thus is full.
     ByCommitTimeNewestFirst,
+    /// Commits are sorted by their author time in decending order, that is newest first.
+    ///
+    /// The sorting applies to all currently queued commit ids and thus is full.
+    ByAuthorTimeNewestFirst,
 }
 
 impl Default for Sorting {
@@ -41,7 +46,11 @@ impl Default for Sorting {
 
 ///
 pub mod ancestors {
-    use std::{borrow::BorrowMut, collections::VecDeque};
+    use std::{
+        borrow::BorrowMut,
+        cmp::Reverse,
+        collections::{hash_map::Entry, BinaryHeap, VecDeque},
+    };
 
     use git_hash::{oid, ObjectId};
     use git_object::CommitRefIter;
@@ -68,6 +77,11 @@ pub mod ancestors {
 
     type TimeInSeconds = u32;
 
+    /// The amount of uninteresting commits to traverse before stopping to find more recent commits behind them in case of
+    /// clock skew, like `git` does. Commits are uninteresting if they are older than the cutoff date set with
+    /// [`Ancestors::since()`], or if they are hidden.
+    pub const SLOP: usize = 5;
+
     /// The state used and potentially shared by multiple graph traversals.
     #[derive(Default, Clone)]
     pub struct State {
@@ -75,6 +89,17 @@ pub mod ancestors {
         buf: Vec<u8>,
         seen: hash_hasher::HashedSet<ObjectId>,
         parents_buf: Vec<u8>,
+        /// Commits to return next when sorting by time.
+        queue: TimeQueue,
+        /// The amount of commits older than the cutoff date that may still be traversed.
+        slop: usize,
+        /// Tips whose ancestors should be hidden, which are yet to be processed.
+        hidden_tips: Vec<ObjectId>,
+        /// Commits reachable from hidden tips, as far as they are relevant to the traversal.
+        hidden: hash_hasher::HashedSet<ObjectId>,
+        boundary: Vec<ObjectId>,
+        /// Commits whose parents are treated as missing, like the ones listed in the `shallow` file of a shallow clone.
+        shallow: hash_hasher::HashedSet<ObjectId>,
     }
 
     impl State {
@@ -82,6 +107,41 @@ pub mod ancestors {
             self.next.clear();
             self.buf.clear();
             self.seen.clear();
+            self.queue.clear();
+            self.slop = SLOP;
+            self.hidden_tips.clear();
+            self.hidden.clear();
+            self.boundary.clear();
+            self.shallow.clear();
+        }
+
+        /// Return all hidden commits that are parents of commits returned so far, in the order they were encountered,
+        /// similar to the boundary commits shown by `git rev-list --boundary`.
+        pub fn boundary(&self) -> &[ObjectId] {
+            &self.boundary
+        }
+    }
+
+    /// Commits ordered by time, newest first, and in the order they were pushed if their times are equal.
+    #[derive(Default, Clone)]
+    struct TimeQueue {
+        heap: BinaryHeap<(TimeInSeconds, Reverse<usize>, ObjectId)>,
+        num_pushed: usize,
+    }
+
+    impl TimeQueue {
+        fn push(&mut self, id: ObjectId, time: TimeInSeconds) {
+            self.heap.push((time, Reverse(self.num_pushed), id));
+            self.num_pushed += 1;
+        }
+
+        fn pop(&mut self) -> Option<(ObjectId, TimeInSeconds)> {
+            self.heap.pop().map(|(time, _, id)| (id, time))
+        }
+
+        fn clear(&mut self) {
+            self.heap.clear();
+            self.num_pushed = 0;
         }
     }
 
@@ -91,6 +151,52 @@ pub mod ancestors {
             self.parents = mode;
             self
         }
+
+        /// Only return commits whose time is at least `cutoff` in seconds since the unix epoch, similar to `--since` on the
+        /// git command-line. The time used is the one commits are sorted by, or the commit time when sorting topologically.
+        ///
+        /// Parents of commits older than `cutoff` are still traversed for up to [`SLOP`] commits in a row, so that more recent
+        /// commits are still found if the history contains commits with skewed clocks.
+        pub fn since(mut self, cutoff: u32) -> Self {
+            self.since = Some(cutoff);
+            self
+        }
+    }
+
+    impl<Find, Predicate, StateMut> Ancestors<Find, Predicate, StateMut>
+    where
+        StateMut: BorrowMut<State>,
+    {
+        /// Hide the given `tips` along with all of their ancestors, similar to `^tip` or `--not tip` on the git command-line.
+        /// This allows to traverse ranges like `A..B` by passing `B` as tip and hiding `A`.
+        ///
+        /// Hidden commits are never returned and their ancestors aren't traversed. Only as many commits as needed are visited
+        /// to know which ones are hidden, instead of the entire history of the hidden tips.
+        pub fn with_hidden(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+            self.state
+                .borrow_mut()
+                .hidden_tips
+                .extend(tips.into_iter().map(Into::into));
+            self
+        }
+
+        /// Return all hidden commits that are parents of commits returned so far, in the order they were encountered,
+        /// similar to the boundary commits shown by `git rev-list --boundary`.
+        pub fn boundary(&mut self) -> &[ObjectId] {
+            self.state.borrow_mut().boundary()
+        }
+
+        /// Treat the given `commits` as if they had no parents, which is what `git` does for the commits listed in the
+        /// `shallow` file of a shallow clone as their parents aren't present in the object database.
+        ///
+        /// This allows to traverse shallow histories without running into errors due to missing commits.
+        pub fn with_shallow(mut self, commits: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+            self.state
+                .borrow_mut()
+                .shallow
+                .extend(commits.into_iter().map(Into::into));
+            self
+        }
     }
 
     impl<Find, Predicate, StateMut, E> Ancestors<Find, Predicate, StateMut>
@@ -99,7 +205,10 @@ pub mod ancestors {
         StateMut: BorrowMut<State>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        /// Set the sorting method, either topological or by author date
+        /// Set the sorting method, either topological or by commit or author date.
+        ///
+        /// When sorting by date, the tips are sorted as well so that the most recent one is returned first.
+        /// This also applies when only traversing first parents with [`parents()`][Ancestors::parents()].
         pub fn sorting(mut self, sorting: Sorting) -> Result<Self, Error> {
             self.sorting = sorting;
             if !matches!(self.sorting, Sorting::Topological) {
@@ -109,7 +218,7 @@ pub mod ancestors {
                         oid: *commit_id,
                         err: err.into(),
                     })?;
-                    *commit_time = commit_iter.committer()?.time.seconds_since_unix_epoch;
+                    *commit_time = time_of(commit_iter.headers()?, sorting);
                 }
             }
             Ok(self)
@@ -180,6 +289,7 @@ pub mod ancestors {
                 state,
                 parents: Default::default(),
                 sorting: Default::default(),
+                since: None,
             }
         }
     }
@@ -194,14 +304,15 @@ pub mod ancestors {
         type Item = Result<ObjectId, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
-            if matches!(self.parents, Parents::First) {
-                self.next_by_topology()
-            } else {
-                match self.sorting {
-                    Sorting::Topological => self.next_by_topology(),
-                    Sorting::ByCommitTimeNewestFirst => self.next_by_commit_date(),
+            if !self.state.borrow_mut().hidden_tips.is_empty() {
+                if let Err(err) = self.hide() {
+                    return Some(Err(err));
                 }
             }
+            match self.sorting {
+                Sorting::Topological => self.next_by_topology(),
+                Sorting::ByCommitTimeNewestFirst | Sorting::ByAuthorTimeNewestFirst => self.next_by_time(),
+            }
         }
     }
 
@@ -212,44 +323,40 @@ pub mod ancestors {
         StateMut: BorrowMut<State>,
         E: std::error::Error + Send + Sync + 'static,
     {
-        fn next_by_commit_date(&mut self) -> Option<Result<ObjectId, Error>> {
+        fn next_by_time(&mut self) -> Option<Result<ObjectId, Error>> {
             let state = self.state.borrow_mut();
+            while let Some((id, commit_time)) = state.next.pop_front() {
+                state.queue.push(id, commit_time);
+            }
 
-            let (oid, _commit_time) = state.next.pop_front()?;
-            match (self.find)(&oid, &mut state.buf) {
-                Ok(commit_iter) => {
-                    let mut count = 0;
+            loop {
+                let (oid, commit_time) = state.queue.pop()?;
+                let too_old = self.since.map_or(false, |cutoff| commit_time < cutoff);
+                if may_traverse_parents(&mut state.slop, too_old) && !state.shallow.contains(&oid) {
+                    let commit_iter = match (self.find)(&oid, &mut state.buf) {
+                        Ok(commit_iter) => commit_iter,
+                        Err(err) => return Some(Err(Error::FindExisting { oid, err: err.into() })),
+                    };
                     for token in commit_iter {
-                        count += 1;
-                        let is_first = count == 1;
                         match token {
                             Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
                             Ok(git_object::commit::ref_iter::Token::Parent { id }) => {
                                 let was_inserted = state.seen.insert(id);
-                                if !(was_inserted && (self.predicate)(&id)) {
-                                    if is_first && matches!(self.parents, Parents::First) {
-                                        break;
-                                    } else {
-                                        continue;
-                                    }
+                                let is_hidden = state.hidden.contains(&id);
+                                if was_inserted && is_hidden {
+                                    state.boundary.push(id);
                                 }
-
-                                let parent = (self.find)(id.as_ref(), &mut state.parents_buf).ok();
-                                let parent_commit_time = parent
-                                    .and_then(|parent| {
-                                        parent
-                                            .committer()
-                                            .ok()
-                                            .map(|committer| committer.time.seconds_since_unix_epoch)
-                                    })
-                                    .unwrap_or_default();
-
-                                match state.next.binary_search_by(|c| c.1.cmp(&parent_commit_time).reverse()) {
-                                    Ok(_) => state.next.push_back((id, parent_commit_time)), // collision => topo-sort
-                                    Err(pos) => state.next.insert(pos, (id, parent_commit_time)), // otherwise insert by commit-time
+                                if was_inserted && !is_hidden && (self.predicate)(&id) {
+                                    let parent = (self.find)(id.as_ref(), &mut state.parents_buf).ok();
+                                    let sorting = self.sorting;
+                                    let parent_commit_time = parent
+                                        .and_then(|parent| {
+                                            parent.headers().ok().map(|headers| time_of(headers, sorting))
+                                        })
+                                        .unwrap_or_default();
+                                    state.queue.push(id, parent_commit_time);
                                 }
-
-                                if is_first && matches!(self.parents, Parents::First) {
+                                if matches!(self.parents, Parents::First) {
                                     break;
                                 }
                             }
@@ -258,9 +365,10 @@ pub mod ancestors {
                         }
                     }
                 }
-                Err(err) => return Some(Err(Error::FindExisting { oid, err: err.into() })),
+                if !too_old {
+                    return Some(Ok(oid));
+                }
             }
-            Some(Ok(oid))
         }
     }
 
@@ -273,15 +381,28 @@ pub mod ancestors {
     {
         fn next_by_topology(&mut self) -> Option<Result<ObjectId, Error>> {
             let state = self.state.borrow_mut();
-            let (oid, _commit_time) = state.next.pop_front()?;
-            match (self.find)(&oid, &mut state.buf) {
-                Ok(commit_iter) => {
+            loop {
+                let (oid, _commit_time) = state.next.pop_front()?;
+                let commit_iter = match (self.find)(&oid, &mut state.buf) {
+                    Ok(commit_iter) => commit_iter,
+                    Err(err) => return Some(Err(Error::FindExisting { oid, err: err.into() })),
+                };
+                let too_old = match self.since {
+                    Some(cutoff) => match commit_iter.headers() {
+                        Ok(headers) => headers.committer_seconds() < cutoff,
+                        Err(err) => return Some(Err(err.into())),
+                    },
+                    None => false,
+                };
+                if may_traverse_parents(&mut state.slop, too_old) && !state.shallow.contains(&oid) {
                     for token in commit_iter {
                         match token {
                             Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
                             Ok(git_object::commit::ref_iter::Token::Parent { id }) => {
                                 let was_inserted = state.seen.insert(id);
-                                if was_inserted && (self.predicate)(&id) {
+                                if was_inserted && state.hidden.contains(&id) {
+                                    state.boundary.push(id);
+                                } else if was_inserted && (self.predicate)(&id) {
                                     state.next.push_back((id, 0));
                                 }
                                 if matches!(self.parents, Parents::First) {
@@ -293,9 +414,173 @@ pub mod ancestors {
                         }
                     }
                 }
-                Err(err) => return Some(Err(Error::FindExisting { oid, err: err.into() })),
+                if !too_old {
+                    return Some(Ok(oid));
+                }
+            }
+        }
+    }
+
+    impl<Find, Predicate, StateMut, E> Ancestors<Find, Predicate, StateMut>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        StateMut: BorrowMut<State>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        /// Walk interesting and hidden commits by commit date, newest first, and mark all commits reachable from hidden tips
+        /// as hidden until only hidden commits are left to be walked, like `git` does to limit its traversal.
+        ///
+        /// To compensate for clock skew, a few more hidden commits are walked before stopping.
+        fn hide(&mut self) -> Result<(), Error> {
+            let state = self.state.borrow_mut();
+            let find = &mut self.find;
+            let shallow = &state.shallow;
+            let mut read = |id: ObjectId, buf: &mut Vec<u8>| -> Result<WalkedCommit, Error> {
+                let is_shallow = shallow.contains(&id);
+                let mut parents = Vec::new();
+                let mut time = 0;
+                let commit_iter = find(&id, buf).map_err(|err| Error::FindExisting {
+                    oid: id,
+                    err: err.into(),
+                })?;
+                for token in commit_iter {
+                    match token? {
+                        git_object::commit::ref_iter::Token::Tree { .. } => continue,
+                        git_object::commit::ref_iter::Token::Parent { .. } if is_shallow => continue,
+                        git_object::commit::ref_iter::Token::Parent { id } => parents.push(id),
+                        git_object::commit::ref_iter::Token::Author { .. } => continue,
+                        git_object::commit::ref_iter::Token::Committer { signature } => {
+                            time = signature.time.seconds_since_unix_epoch;
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                Ok(WalkedCommit { id, time, parents })
+            };
+
+            // Whether or not a commit that was queued is hidden.
+            let mut is_hidden = hash_hasher::HashedMap::<ObjectId, bool>::default();
+            let mut parents_of_walked = hash_hasher::HashedMap::<ObjectId, Vec<ObjectId>>::default();
+            let mut queue = VecDeque::new();
+            for (id, _) in state.next.iter() {
+                if is_hidden.insert(*id, false).is_none() {
+                    insert_by_date(&mut queue, read(*id, &mut state.buf)?);
+                }
+            }
+            for id in std::mem::take(&mut state.hidden_tips) {
+                if is_hidden.insert(id, true) != Some(true) {
+                    insert_by_date(&mut queue, read(id, &mut state.buf)?);
+                }
+            }
+
+            let mut last_interesting_time = TimeInSeconds::MAX;
+            let mut slop = SLOP;
+            while let Some(commit) = queue.pop_front() {
+                if is_hidden[&commit.id] {
+                    let mut to_hide = commit.parents.clone();
+                    while let Some(id) = to_hide.pop() {
+                        match is_hidden.insert(id, true) {
+                            Some(true) => {}
+                            Some(false) => {
+                                if let Some(parents) = parents_of_walked.get(&id) {
+                                    to_hide.extend(parents.iter().cloned());
+                                }
+                            }
+                            None => insert_by_date(&mut queue, read(id, &mut state.buf)?),
+                        }
+                    }
+                    parents_of_walked.insert(commit.id, commit.parents);
+
+                    slop = match queue.front() {
+                        None => 0,
+                        Some(next) if last_interesting_time <= next.time => SLOP,
+                        Some(_) if queue.iter().any(|c| !is_hidden[&c.id]) => SLOP,
+                        Some(_) => slop - 1,
+                    };
+                    if slop == 0 {
+                        break;
+                    }
+                } else {
+                    last_interesting_time = commit.time;
+                    let num_parents = match self.parents {
+                        Parents::All => commit.parents.len(),
+                        Parents::First => 1,
+                    };
+                    for id in commit.parents.iter().take(num_parents) {
+                        if let Entry::Vacant(entry) = is_hidden.entry(*id) {
+                            entry.insert(false);
+                            insert_by_date(&mut queue, read(*id, &mut state.buf)?);
+                        }
+                    }
+                    parents_of_walked.insert(commit.id, commit.parents);
+                }
             }
-            Some(Ok(oid))
+
+            state.hidden.extend(
+                is_hidden
+                    .into_iter()
+                    .filter_map(|(id, hidden)| if hidden { Some(id) } else { None }),
+            );
+            let hidden = &state.hidden;
+            state.next.retain(|(id, _)| !hidden.contains(id));
+            Ok(())
+        }
+    }
+
+    /// Return true if the parents of a commit should be traversed, which is the case for all commits that aren't `too_old`,
+    /// and for up to [`SLOP`] commits in a row that are.
+    fn may_traverse_parents(slop: &mut usize, too_old: bool) -> bool {
+        if !too_old {
+            *slop = SLOP;
+            true
+        } else if *slop > 0 {
+            *slop -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn time_of(headers: git_object::commit::Headers<'_>, sorting: Sorting) -> TimeInSeconds {
+        match sorting {
+            Sorting::ByAuthorTimeNewestFirst => headers.author_seconds(),
+            Sorting::ByCommitTimeNewestFirst | Sorting::Topological => headers.committer_seconds(),
         }
     }
+
+    /// A commit seen while finding hidden commits.
+    struct WalkedCommit {
+        id: ObjectId,
+        time: TimeInSeconds,
+        parents: Vec<ObjectId>,
+    }
+
+    /// Insert `commit` into `queue` so that newer commits come first, placing it after commits with the same time.
+    fn insert_by_date(queue: &mut VecDeque<WalkedCommit>, commit: WalkedCommit) {
+        let pos = queue
+            .binary_search_by(|c| {
+                if c.time >= commit.time {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Greater
+                }
+            })
+            .unwrap_or_else(|pos| pos);
+        queue.insert(pos, commit);
+    }
 }
+
+///
+pub mod flags;
+pub use flags::Flags;
+
+///
+pub mod merge_base;
+
+///
+pub mod path;
+
+///
+pub mod topo;
+pub use topo::Topo;
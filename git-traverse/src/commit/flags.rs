@@ -0,0 +1,126 @@
+use std::ops::{BitAnd, BitOr, Not};
+
+use git_commitgraph::graph;
+use git_hash::{oid, ObjectId};
+
+/// A compact store of flags for commits to mark them while walking the commit graph, as needed for negotiation, finding
+/// merge-bases and checking reachability, without allocating a set of commits per query.
+///
+/// The flags of commits contained in the [commit-graph][git_commitgraph::Graph] are stored in a vector addressed by their
+/// position in the graph, using only the size of `T` per commit. The flags of all other commits are stored in a hash map.
+/// Algorithms take a `Flags` instance and [`clear()`][Flags::clear()] it before use, so callers can pass the same instance
+/// to many queries and allocate only once, while clearing only touches the commits marked by the previous query.
+///
+/// `T` is typically an integer or a type created with the `bitflags` crate, with each bit being a flag, or `bool` for
+/// a single flag.
+#[derive(Clone)]
+pub struct Flags<'graph, T> {
+    graph: Option<&'graph git_commitgraph::Graph>,
+    /// The flags of commits in the graph by their position, allocated when the first of them is marked.
+    by_position: Vec<T>,
+    /// The positions of all commits in the graph that were marked since the last time we were cleared.
+    touched: Vec<graph::Position>,
+    by_id: hash_hasher::HashedMap<ObjectId, T>,
+}
+
+impl<'graph, T> Flags<'graph, T>
+where
+    T: Copy + Default + PartialEq + BitOr<Output = T> + BitAnd<Output = T> + Not<Output = T>,
+{
+    /// Create a new instance which stores flags of commits contained in `graph` by their position, if it is set.
+    pub fn new(graph: Option<&'graph git_commitgraph::Graph>) -> Self {
+        Flags {
+            graph,
+            by_position: Vec::new(),
+            touched: Vec::new(),
+            by_id: Default::default(),
+        }
+    }
+
+    /// Return the commit-graph passed to [`new()`][Flags::new()], if any.
+    pub fn graph(&self) -> Option<&'graph git_commitgraph::Graph> {
+        self.graph
+    }
+
+    /// Return the flags of the commit `id`, which are empty if it wasn't marked yet.
+    pub fn get(&self, id: &oid) -> T {
+        match self.position(id) {
+            Some(pos) => self.get_at(pos),
+            None => self.by_id.get(id).copied().unwrap_or_default(),
+        }
+    }
+
+    /// Return the flags of the commit at `pos` in the commit-graph passed to [`new()`][Flags::new()].
+    pub fn get_at(&self, pos: graph::Position) -> T {
+        self.by_position.get(pos.0 as usize).copied().unwrap_or_default()
+    }
+
+    /// Return true if all of the given `flags` are set for the commit `id`.
+    pub fn contains(&self, id: &oid, flags: T) -> bool {
+        self.get(id) & flags == flags
+    }
+
+    /// Add `flags` to the flags of the commit `id` and return the flags it had before.
+    pub fn insert(&mut self, id: &oid, flags: T) -> T {
+        match self.position(id) {
+            Some(pos) => self.insert_at(pos, flags),
+            None => {
+                let current = self.by_id.entry(id.to_owned()).or_default();
+                let previous = *current;
+                *current = previous | flags;
+                previous
+            }
+        }
+    }
+
+    /// Add `flags` to the flags of the commit at `pos` in the commit-graph passed to [`new()`][Flags::new()] and return
+    /// the flags it had before, which avoids looking up the position of commits whose parents are read from the graph.
+    ///
+    /// # Panics
+    ///
+    /// If no commit-graph was passed to [`new()`][Flags::new()], or if `pos` isn't contained in it.
+    pub fn insert_at(&mut self, pos: graph::Position, flags: T) -> T {
+        if self.by_position.is_empty() {
+            let num_commits = self
+                .graph
+                .expect("positions are only known with a commit-graph")
+                .num_commits();
+            self.by_position = vec![T::default(); num_commits as usize];
+        }
+        let current = &mut self.by_position[pos.0 as usize];
+        let previous = *current;
+        *current = previous | flags;
+        if previous == T::default() {
+            self.touched.push(pos);
+        }
+        previous
+    }
+
+    /// Remove `flags` from the flags of the commit `id` and return the flags it had before.
+    pub fn remove(&mut self, id: &oid, flags: T) -> T {
+        let current = match self.position(id) {
+            Some(pos) => self.by_position.get_mut(pos.0 as usize),
+            None => self.by_id.get_mut(id),
+        };
+        match current {
+            Some(current) => {
+                let previous = *current;
+                *current = previous & !flags;
+                previous
+            }
+            None => T::default(),
+        }
+    }
+
+    /// Remove all flags of all commits, but keep the allocations to mark commits in the next query.
+    pub fn clear(&mut self) {
+        for pos in self.touched.drain(..) {
+            self.by_position[pos.0 as usize] = T::default();
+        }
+        self.by_id.clear();
+    }
+
+    fn position(&self, id: &oid) -> Option<graph::Position> {
+        self.graph.and_then(|graph| graph.lookup(id))
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::BinaryHeap;
+
+use git_commitgraph::GENERATION_NUMBER_INFINITY;
+use git_hash::{oid, ObjectId};
+use git_object::CommitRefIter;
+
+use crate::commit::{
+    topo::{collect_parents, find_commit, is_ancestor, Error, Generation},
+    Flags,
+};
+
+/// Reachable from the first commit.
+const PARENT1: u8 = 1 << 0;
+/// Reachable from one of the other commits.
+const PARENT2: u8 = 1 << 1;
+/// Reachable from a common ancestor, and thus not a best common ancestor.
+const STALE: u8 = 1 << 2;
+/// Already recorded as common ancestor.
+const RESULT: u8 = 1 << 3;
+
+/// Return the best common ancestors of `first` and any of `others`, like `git merge-base --all` does, with the most
+/// recent ones first, or nothing if there is no common ancestor.
+///
+/// Common ancestors are reachable from `first` and from one of `others`, and the best ones aren't reachable from any
+/// other common ancestor. There can be more than one if there are criss-cross merges.
+///
+/// * `flags` - the store to mark visited commits in, which is cleared before use and may be reused across queries.
+///    If it was created with a commit-graph, generation numbers and parents are read from it instead of decoding commits.
+/// * `find` - a way to lookup commits by their ObjectId, writing their data into buffer.
+pub fn merge_bases<Find, E>(
+    first: &oid,
+    others: &[ObjectId],
+    flags: &mut Flags<'_, u8>,
+    mut find: Find,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if others.iter().any(|other| *other == first) {
+        return Ok(vec![first.to_owned()]);
+    }
+
+    let graph = flags.graph();
+    let mut buf = Vec::new();
+    flags.clear();
+    let mut queue = BinaryHeap::new();
+    flags.insert(first, PARENT1);
+    queue.push(queue_item(first, graph, &mut find, &mut buf)?);
+    for other in others {
+        if flags.insert(other, PARENT2) == 0 {
+            queue.push(queue_item(other, graph, &mut find, &mut buf)?);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    let mut parent_ids = Vec::new();
+    while queue.iter().any(|(_, _, id)| !flags.contains(id, STALE)) {
+        let (_, _, id) = queue.pop().expect("non-empty queue");
+        let mut commit_flags = flags.get(&id) & (PARENT1 | PARENT2 | STALE);
+        if commit_flags == PARENT1 | PARENT2 {
+            if flags.insert(&id, RESULT) & RESULT == 0 {
+                candidates.push(id);
+            }
+            // Ancestors of common ancestors can't be the best common ancestors.
+            commit_flags |= STALE;
+        }
+        collect_parents(graph, &mut find, &id, &mut buf, &mut parent_ids)?;
+        for parent_id in parent_ids.drain(..) {
+            if flags.get(&parent_id) & commit_flags == commit_flags {
+                continue;
+            }
+            flags.insert(&parent_id, commit_flags);
+            queue.push(queue_item(&parent_id, graph, &mut find, &mut buf)?);
+        }
+    }
+    candidates.retain(|id| !flags.contains(id, STALE));
+    remove_redundant(candidates, flags, find)
+}
+
+/// Return the best common ancestors of all `commits`, like `git merge-base --octopus --all` does, which are the
+/// commits a merge of all `commits` would be based on.
+///
+/// `flags` and `find` are used just like in [`merge_bases()`].
+pub fn octopus<Find, E>(commits: &[ObjectId], flags: &mut Flags<'_, u8>, mut find: Find) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let (first, rest) = match commits.split_first() {
+        Some(split) => split,
+        None => return Ok(Vec::new()),
+    };
+    let mut bases = vec![*first];
+    for commit in rest {
+        let mut next_bases = Vec::new();
+        for base in &bases {
+            for id in merge_bases(base, std::slice::from_ref(commit), flags, &mut find)? {
+                if !next_bases.contains(&id) {
+                    next_bases.push(id);
+                }
+            }
+        }
+        bases = next_bases;
+    }
+    remove_redundant(bases, flags, find)
+}
+
+/// Commits are popped by highest generation number first, and by most recent commit time if the generation is the same.
+fn queue_item<Find, E>(
+    id: &oid,
+    graph: Option<&git_commitgraph::Graph>,
+    find: &mut Find,
+    buf: &mut Vec<u8>,
+) -> Result<(Generation, u64, ObjectId), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Ok(match graph.and_then(|graph| graph.commit_by_id(id)) {
+        Some(commit) => (commit.generation(), commit.committer_timestamp(), id.to_owned()),
+        None => (
+            GENERATION_NUMBER_INFINITY,
+            find_commit(find, id, buf)?.headers()?.committer_seconds() as u64,
+            id.to_owned(),
+        ),
+    })
+}
+
+/// Remove all `candidates` which are ancestors of other candidates.
+fn remove_redundant<Find, E>(
+    candidates: Vec<ObjectId>,
+    flags: &mut Flags<'_, u8>,
+    mut find: Find,
+) -> Result<Vec<ObjectId>, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if candidates.len() < 2 {
+        return Ok(candidates);
+    }
+    let mut is_redundant = vec![false; candidates.len()];
+    for (idx, candidate) in candidates.iter().enumerate() {
+        for (other_idx, other) in candidates.iter().enumerate() {
+            if idx != other_idx && !is_redundant[other_idx] && is_ancestor(candidate, other, flags, &mut find)? {
+                is_redundant[idx] = true;
+                break;
+            }
+        }
+    }
+    Ok(candidates
+        .into_iter()
+        .zip(is_redundant)
+        .filter(|(_, is_redundant)| !is_redundant)
+        .map(|(id, _)| id)
+        .collect())
+}
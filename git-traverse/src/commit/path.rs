@@ -0,0 +1,157 @@
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BString, ByteSlice},
+    tree, Kind,
+};
+use quick_error::quick_error;
+
+quick_error! {
+    /// The error returned by [`Filter::is_changed_by()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        FindExisting{oid: ObjectId, err: Box<dyn std::error::Error + Send + Sync + 'static> } {
+            display("The object {} could not be found", oid)
+            source(&**err)
+        }
+        ObjectKind{oid: ObjectId, actual: Kind, expected: Kind} {
+            display("The object {} was expected to be a {} but is a {}", oid, expected, actual)
+        }
+        ObjectDecode(err: git_object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+    }
+}
+
+/// Determine if commits changed a path compared to their first parent, which allows to only keep commits touching
+/// the path during a traversal, like `git log -- <path>` does.
+///
+/// If a [commit-graph][git_commitgraph::Graph] with changed-path Bloom filters is available, most commits that didn't change
+/// the path are skipped without reading a single tree.
+pub struct Filter {
+    path: BString,
+    query: Option<git_commitgraph::bloom::Query>,
+    graph: Option<git_commitgraph::Graph>,
+    commit_buf: Vec<u8>,
+    tree_buf: Vec<u8>,
+    subtree_buf: Vec<u8>,
+}
+
+impl Filter {
+    /// Create a new filter for `path`, which is relative to the root of the repository and uses slashes as separators.
+    ///
+    /// An empty `path` refers to the root of the repository, which is changed by every commit changing the tree.
+    pub fn new(path: impl Into<BString>) -> Self {
+        let path = path.into();
+        Filter {
+            query: git_commitgraph::bloom::Query::new(&path),
+            path,
+            graph: None,
+            commit_buf: Vec::new(),
+            tree_buf: Vec::new(),
+            subtree_buf: Vec::new(),
+        }
+    }
+
+    /// Use the changed-path Bloom filters in `graph` to avoid comparing trees of commits that didn't change our path.
+    ///
+    /// Commits that aren't part of `graph` or have no Bloom filter are handled by comparing trees.
+    pub fn with_commit_graph(mut self, graph: git_commitgraph::Graph) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Return `true` if the commit with `id` changed our path compared to its first parent, or if the path exists
+    /// in a commit without parents.
+    ///
+    /// * `find` - a way to lookup commits and trees by their ObjectId, writing their data into buffer.
+    pub fn is_changed_by<Find, E>(&mut self, id: &oid, mut find: Find) -> Result<bool, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some((graph, query)) = self.graph.as_ref().zip(self.query.as_ref()) {
+            if graph.maybe_changed_path(id, query) == Some(false) {
+                return Ok(false);
+            }
+        }
+
+        let (tree, parent) = tree_and_first_parent(id, &mut self.commit_buf, &mut find)?;
+        let parent_tree = match parent {
+            Some(parent) => Some(tree_and_first_parent(&parent, &mut self.commit_buf, &mut find)?.0),
+            None => None,
+        };
+        if parent_tree == Some(tree) {
+            return Ok(false);
+        }
+        if self.query.is_none() {
+            return Ok(true);
+        }
+
+        let entry = self.entry_at(&tree, &mut find)?;
+        Ok(match parent_tree {
+            Some(parent_tree) => {
+                let parent_entry = self.entry_at(&parent_tree, &mut find)?;
+                entry.map(|e| (e.mode, e.oid)) != parent_entry.map(|e| (e.mode, e.oid))
+            }
+            None => entry.is_some(),
+        })
+    }
+
+    fn entry_at<Find, E>(&mut self, tree: &oid, find: &mut Find) -> Result<Option<tree::Entry>, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let tree_iter = find_existing(tree, &mut self.tree_buf, find, Kind::Tree)?
+            .try_into_tree_iter()
+            .expect("kind was checked");
+        let components = self.path.split_str("/").filter(|c| !c.is_empty());
+        Ok(tree_iter.lookup_entry(components, &mut self.subtree_buf, |id, buf| {
+            find(id, buf).ok()?.try_into_tree_iter()
+        })?)
+    }
+}
+
+fn tree_and_first_parent<Find, E>(
+    id: &oid,
+    buf: &mut Vec<u8>,
+    find: &mut Find,
+) -> Result<(ObjectId, Option<ObjectId>), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let commit = find_existing(id, buf, find, Kind::Commit)?
+        .try_into_commit_iter()
+        .expect("kind was checked");
+    let headers = commit.headers()?;
+    let first_parent = headers.parents().next();
+    Ok((headers.tree, first_parent))
+}
+
+fn find_existing<'a, Find, E>(
+    id: &oid,
+    buf: &'a mut Vec<u8>,
+    find: &mut Find,
+    expected: Kind,
+) -> Result<git_object::Data<'a>, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::Data<'b>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let data = find(id, buf).map_err(|err| Error::FindExisting {
+        oid: id.to_owned(),
+        err: err.into(),
+    })?;
+    if data.kind != expected {
+        return Err(Error::ObjectKind {
+            oid: id.to_owned(),
+            actual: data.kind,
+            expected,
+        });
+    }
+    Ok(data)
+}
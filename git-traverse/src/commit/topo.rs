@@ -0,0 +1,328 @@
+use std::collections::{hash_map::Entry, BinaryHeap};
+
+use git_commitgraph::GENERATION_NUMBER_INFINITY;
+use git_hash::{oid, ObjectId};
+use git_object::CommitRefIter;
+use quick_error::quick_error;
+
+use crate::commit::{Flags, Parents};
+
+quick_error! {
+    /// The error is part of the item returned by the [Topo] iterator and by [`is_ancestor()`], and returned when finding
+    /// [merge-bases][crate::commit::merge_base].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        FindExisting{oid: ObjectId, err: Box<dyn std::error::Error + Send + Sync + 'static> } {
+            display("The commit {} could not be found", oid)
+            source(&**err)
+        }
+        ObjectDecode(err: git_object::decode::Error) {
+            display("An object could not be decoded")
+            source(err)
+            from()
+        }
+        CommitGraph(err: git_commitgraph::file::commit::Error) {
+            display("A commit could not be read from the commit-graph")
+            source(err)
+            from()
+        }
+    }
+}
+
+pub(crate) type Generation = u32;
+
+/// An iterator over the ancestors of one or more starting commits in topological order, like `git rev-list --topo-order` does.
+///
+/// No commit is returned before all of its children are returned, and lines of history are not intermixed.
+///
+/// If a [commit-graph][git_commitgraph::Graph] is provided with [`with_commit_graph()`][Topo::with_commit_graph()], its generation
+/// numbers are used to only look at as many commits as needed to know which commit to return next, instead of walking the entire
+/// history before returning the first commit. This makes it cheap to obtain only the most recent commits.
+/// Without it, or for commits not contained in the graph, all ancestors are visited before the first commit is returned.
+pub struct Topo<Find> {
+    find: Find,
+    graph: Option<git_commitgraph::Graph>,
+    parents: Parents,
+    tips: Option<Vec<ObjectId>>,
+    /// One more than the amount of children not yet returned for each commit seen by the in-degree walk.
+    indegree: hash_hasher::HashedMap<ObjectId, u32>,
+    /// Commits whose parents still have to be counted, highest generation first.
+    indegree_queue: BinaryHeap<(Generation, ObjectId)>,
+    /// Commits without children that are yet to be returned, the last one is returned next.
+    topo_queue: Vec<ObjectId>,
+    /// The lowest generation down to which the in-degree walk is complete.
+    min_generation: Generation,
+    buf: Vec<u8>,
+    parent_ids: Vec<ObjectId>,
+}
+
+impl<Find, E> Topo<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Create a new instance.
+    ///
+    /// * `tips`
+    ///   * the starting points of the iteration, usually commits
+    ///   * each commit they lead to will only be returned once, including the tip that started it
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///    an iterator over commit tokens if the object is present and is a commit. Caching should be implemented within this function
+    ///    as needed.
+    pub fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, find: Find) -> Self {
+        Topo {
+            find,
+            graph: None,
+            parents: Default::default(),
+            tips: Some(tips.into_iter().map(Into::into).collect()),
+            indegree: Default::default(),
+            indegree_queue: Default::default(),
+            topo_queue: Vec::new(),
+            min_generation: GENERATION_NUMBER_INFINITY,
+            buf: Vec::new(),
+            parent_ids: Vec::new(),
+        }
+    }
+
+    /// Use the generation numbers in `graph` to avoid walking the entire history before returning the first commit,
+    /// and to read parents without decoding commits.
+    pub fn with_commit_graph(mut self, graph: git_commitgraph::Graph) -> Self {
+        self.graph = Some(graph);
+        self
+    }
+
+    /// Change our commit parent handling mode to the given one.
+    pub fn parents(mut self, mode: Parents) -> Self {
+        self.parents = mode;
+        self
+    }
+
+    fn initialize(&mut self, tips: Vec<ObjectId>) -> Result<(), Error> {
+        let mut tips_with_time = Vec::with_capacity(tips.len());
+        for tip in tips {
+            if self.indegree.insert(tip, 1).is_some() {
+                continue;
+            }
+            let generation = generation(self.graph.as_ref(), &tip);
+            self.min_generation = self.min_generation.min(generation);
+            self.indegree_queue.push((generation, tip));
+            let time = match self.graph.as_ref().and_then(|graph| graph.commit_by_id(&tip)) {
+                Some(commit) => commit.committer_timestamp(),
+                None => find_commit(&mut self.find, &tip, &mut self.buf)?
+                    .headers()?
+                    .committer_seconds() as u64,
+            };
+            tips_with_time.push((tip, time));
+        }
+        self.compute_indegrees_to_depth(self.min_generation)?;
+
+        // Like git, return the most recent tips first unless they are reachable from other tips.
+        tips_with_time.sort_by_key(|(_tip, time)| std::cmp::Reverse(*time));
+        for (tip, _time) in tips_with_time.into_iter().rev() {
+            if self.indegree[&tip] == 1 {
+                self.topo_queue.push(tip);
+            }
+        }
+        Ok(())
+    }
+
+    /// Count the children of all commits with a generation of at least `min_generation`.
+    fn compute_indegrees_to_depth(&mut self, min_generation: Generation) -> Result<(), Error> {
+        while self
+            .indegree_queue
+            .peek()
+            .map_or(false, |(generation, _)| *generation >= min_generation)
+        {
+            let (_, id) = self.indegree_queue.pop().expect("peeked");
+            let mut parent_ids = std::mem::take(&mut self.parent_ids);
+            let res = self.collect_parents(&id, &mut parent_ids);
+            for parent_id in parent_ids.drain(..) {
+                match self.indegree.entry(parent_id) {
+                    Entry::Occupied(mut entry) => *entry.get_mut() += 1,
+                    Entry::Vacant(entry) => {
+                        entry.insert(2);
+                        self.indegree_queue
+                            .push((generation(self.graph.as_ref(), &parent_id), parent_id));
+                    }
+                }
+            }
+            self.parent_ids = parent_ids;
+            res?;
+        }
+        Ok(())
+    }
+
+    fn collect_parents(&mut self, id: &oid, out: &mut Vec<ObjectId>) -> Result<(), Error> {
+        match self
+            .graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(id).map(|commit| (graph, commit)))
+        {
+            Some((graph, commit)) => {
+                for pos in commit.iter_parents() {
+                    out.push(graph.id_at(pos?).to_owned());
+                    if matches!(self.parents, Parents::First) {
+                        break;
+                    }
+                }
+            }
+            None => collect_parents_from_object(&mut self.find, id, &mut self.buf, self.parents, out)?,
+        }
+        Ok(())
+    }
+
+    fn next_commit(&mut self) -> Result<Option<ObjectId>, Error> {
+        if let Some(tips) = self.tips.take() {
+            self.initialize(tips)?;
+        }
+        let id = match self.topo_queue.pop() {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let mut parent_ids = std::mem::take(&mut self.parent_ids);
+        let res = self.collect_parents(&id, &mut parent_ids).and_then(|_| {
+            for parent_id in parent_ids.iter() {
+                let generation = generation(self.graph.as_ref(), parent_id);
+                if generation < self.min_generation {
+                    self.min_generation = generation;
+                    self.compute_indegrees_to_depth(generation)?;
+                }
+                let indegree = self
+                    .indegree
+                    .get_mut(parent_id)
+                    .expect("parents of returned commits are seen by the in-degree walk");
+                *indegree -= 1;
+                if *indegree == 1 {
+                    self.topo_queue.push(*parent_id);
+                }
+            }
+            Ok(())
+        });
+        parent_ids.clear();
+        self.parent_ids = parent_ids;
+        res.map(|_| Some(id))
+    }
+}
+
+impl<Find, E> Iterator for Topo<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    type Item = Result<ObjectId, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_commit().transpose()
+    }
+}
+
+/// Return `true` if `ancestor` is reachable from `descendant` by following parents, or if both are the same commit,
+/// like `git merge-base --is-ancestor` does.
+///
+/// * `flags` - the store to mark visited commits in, which is cleared before use and may be reused across queries.
+///    If it was created with a commit-graph, commits with a generation number lower than the one of `ancestor` are not
+///    traversed as they can't lead to it, which typically stops the traversal early.
+/// * `find` - a way to lookup commits by their ObjectId, writing their data into buffer.
+pub fn is_ancestor<Find, E>(
+    ancestor: &oid,
+    descendant: &oid,
+    flags: &mut Flags<'_, u8>,
+    mut find: Find,
+) -> Result<bool, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    const SEEN: u8 = 1 << 0;
+    let graph = flags.graph();
+    let min_generation = generation(graph, ancestor);
+    let mut buf = Vec::new();
+    let mut queue = vec![descendant.to_owned()];
+    let mut parent_ids = Vec::new();
+    flags.clear();
+    flags.insert(descendant, SEEN);
+    while let Some(id) = queue.pop() {
+        if &*id == ancestor {
+            return Ok(true);
+        }
+        collect_parents(graph, &mut find, &id, &mut buf, &mut parent_ids)?;
+        for parent_id in parent_ids.drain(..) {
+            if generation(graph, &parent_id) >= min_generation && flags.insert(&parent_id, SEEN) & SEEN == 0 {
+                queue.push(parent_id);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Put all parents of the commit `id` into `out`, reading them from `graph` if it contains the commit.
+pub(crate) fn collect_parents<Find, E>(
+    graph: Option<&git_commitgraph::Graph>,
+    find: &mut Find,
+    id: &oid,
+    buf: &mut Vec<u8>,
+    out: &mut Vec<ObjectId>,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match graph.and_then(|graph| graph.commit_by_id(id).map(|commit| (graph, commit))) {
+        Some((graph, commit)) => {
+            for pos in commit.iter_parents() {
+                out.push(graph.id_at(pos?).to_owned());
+            }
+        }
+        None => collect_parents_from_object(find, id, buf, Parents::All, out)?,
+    }
+    Ok(())
+}
+
+pub(crate) fn generation(graph: Option<&git_commitgraph::Graph>, id: &oid) -> Generation {
+    graph
+        .and_then(|graph| graph.commit_by_id(id))
+        .map_or(GENERATION_NUMBER_INFINITY, |commit| commit.generation())
+}
+
+pub(crate) fn find_commit<'a, Find, E>(
+    find: &mut Find,
+    id: &oid,
+    buf: &'a mut Vec<u8>,
+) -> Result<CommitRefIter<'a>, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<CommitRefIter<'b>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    find(id, buf).map_err(|err| Error::FindExisting {
+        oid: id.to_owned(),
+        err: err.into(),
+    })
+}
+
+fn collect_parents_from_object<Find, E>(
+    find: &mut Find,
+    id: &oid,
+    buf: &mut Vec<u8>,
+    parents: Parents,
+    out: &mut Vec<ObjectId>,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    for token in find_commit(find, id, buf)? {
+        match token? {
+            git_object::commit::ref_iter::Token::Tree { .. } => continue,
+            git_object::commit::ref_iter::Token::Parent { id } => {
+                out.push(id);
+                if matches!(parents, Parents::First) {
+                    break;
+                }
+            }
+            _a_token_past_the_parents => break,
+        }
+    }
+    Ok(())
+}
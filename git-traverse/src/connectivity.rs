@@ -0,0 +1,199 @@
+//! Check that all objects reachable from a set of tips are present, like git does after receiving a pack.
+use git_hash::{oid, ObjectId};
+use git_object::{tree::EntryMode, Kind};
+
+/// An object that is referenced by another object or by a tip, but which isn't present in the object database.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Missing {
+    /// The id of the missing object.
+    pub id: ObjectId,
+    /// The object referencing the missing object, or `None` if it was one of the tips.
+    pub referenced_by: Option<ObjectId>,
+}
+
+/// An object that is present, but isn't of the kind the object referencing it expects it to be.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct UnexpectedKind {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The kind of object the referencing object expects.
+    pub expected: Kind,
+    /// The actual kind of the object.
+    pub actual: Kind,
+    /// The object referencing this object.
+    pub referenced_by: ObjectId,
+}
+
+/// The outcome of [`Check::objects_reachable_from()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+pub struct Outcome {
+    /// All objects that are referenced but not present, in the order they were encountered.
+    pub missing: Vec<Missing>,
+    /// All objects that are present but of an unexpected kind, in the order they were encountered.
+    ///
+    /// Objects reachable from them aren't visited.
+    pub unexpected_kind: Vec<UnexpectedKind>,
+    /// All objects that are present but couldn't be decoded, in the order they were encountered.
+    ///
+    /// Objects reachable from them aren't visited.
+    pub undecodable: Vec<ObjectId>,
+    /// The amount of objects that were found to be present, including blobs whose data wasn't read.
+    pub objects: usize,
+}
+
+impl Outcome {
+    /// Return true if no object is missing, of an unexpected kind or undecodable.
+    pub fn is_connected(&self) -> bool {
+        self.missing.is_empty() && self.unexpected_kind.is_empty() && self.undecodable.is_empty()
+    }
+}
+
+/// A utility to verify that all objects reachable from given tips are present, reporting those that are missing along with the object
+/// referring to them.
+///
+/// Each object is visited only once, even across multiple calls to [`objects_reachable_from()`][Check::objects_reachable_from()].
+pub struct Check<Find, Contains> {
+    find: Find,
+    contains: Contains,
+    seen: hash_hasher::HashedSet<ObjectId>,
+    buf: Vec<u8>,
+}
+
+impl<Find, Contains> Check<Find, Contains>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<git_object::Data<'a>>,
+    Contains: FnMut(&oid) -> bool,
+{
+    /// Create a new instance.
+    ///
+    /// * `find` - a way to lookup object data by id, writing it into buffer and returning it, or `None` if the object doesn't exist.
+    /// * `contains` - return true if the object with the given id exists, used for blobs as their data isn't needed.
+    pub fn new(find: Find, contains: Contains) -> Self {
+        Check {
+            find,
+            contains,
+            seen: Default::default(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Assume all objects reachable from `ids` to be present without checking them, which is useful to only check objects
+    /// that were newly received, as objects reachable from existing references are known to be connected already.
+    pub fn assume_connected(mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.seen.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Return true if the object with `id` was visited by [`objects_reachable_from()`][Check::objects_reachable_from()],
+    /// or if it was [assumed to be connected][Check::assume_connected()].
+    pub fn has_visited(&self, id: &oid) -> bool {
+        self.seen.contains(id)
+    }
+
+    /// Visit all objects reachable from `tips` and report those which are missing, aren't of the kind that their
+    /// referencing object expects them to be, or can't be decoded.
+    ///
+    /// Such objects don't cause the traversal to stop, which allows to report all problems at once.
+    /// Submodule commits referenced by trees are not expected to be present and are not checked.
+    pub fn objects_reachable_from(&mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Outcome {
+        let mut out = Outcome::default();
+        let mut children = Vec::new();
+        let mut queue: Vec<(ObjectId, Option<(Kind, ObjectId)>)> = Vec::new();
+        for tip in tips.into_iter().map(Into::into) {
+            if self.seen.insert(tip) {
+                queue.push((tip, None));
+            }
+        }
+        queue.reverse();
+
+        while let Some((id, expected)) = queue.pop() {
+            if let Some((Kind::Blob, referenced_by)) = expected {
+                if (self.contains)(&id) {
+                    out.objects += 1;
+                } else {
+                    out.missing.push(Missing {
+                        id,
+                        referenced_by: Some(referenced_by),
+                    });
+                }
+                continue;
+            }
+            let obj = match (self.find)(&id, &mut self.buf) {
+                Some(obj) => obj,
+                None => {
+                    out.missing.push(Missing {
+                        id,
+                        referenced_by: expected.map(|(_kind, referenced_by)| referenced_by),
+                    });
+                    continue;
+                }
+            };
+            out.objects += 1;
+            if let Some((expected, referenced_by)) = expected {
+                if expected != obj.kind {
+                    out.unexpected_kind.push(UnexpectedKind {
+                        id,
+                        expected,
+                        actual: obj.kind,
+                        referenced_by,
+                    });
+                    continue;
+                }
+            }
+
+            children.clear();
+            match references_of(obj, &mut children) {
+                Ok(()) => {
+                    // Visit children in the order they are mentioned.
+                    for (child, kind) in children.drain(..).rev() {
+                        if self.seen.insert(child) {
+                            queue.push((child, Some((kind, id))));
+                        }
+                    }
+                }
+                Err(_) => out.undecodable.push(id),
+            }
+        }
+        out
+    }
+}
+
+/// Put the ids of all objects referenced by `obj` along with their expected kind into `out`.
+fn references_of(obj: git_object::Data<'_>, out: &mut Vec<(ObjectId, Kind)>) -> Result<(), git_object::decode::Error> {
+    match obj.kind {
+        Kind::Commit => {
+            let headers = git_object::commit::Headers::from_bytes(obj.data)?;
+            out.push((headers.tree, Kind::Tree));
+            out.extend(headers.parents().map(|parent| (parent, Kind::Commit)));
+        }
+        Kind::Tree => {
+            for entry in git_object::TreeRefIter::from_bytes(obj.data) {
+                let entry = entry?;
+                match entry.mode {
+                    EntryMode::Tree => out.push((entry.oid.to_owned(), Kind::Tree)),
+                    EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link => {
+                        out.push((entry.oid.to_owned(), Kind::Blob))
+                    }
+                    EntryMode::Commit => {}
+                }
+            }
+        }
+        Kind::Tag => {
+            let mut target = None;
+            for token in git_object::TagRefIter::from_bytes(obj.data) {
+                match token? {
+                    git_object::tag::ref_iter::Token::Target { id } => target = Some(id),
+                    git_object::tag::ref_iter::Token::TargetKind(kind) => {
+                        if let Some(target) = target.take() {
+                            out.push((target, kind));
+                        }
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        Kind::Blob => {}
+    }
+    Ok(())
+}
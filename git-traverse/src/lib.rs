@@ -7,3 +7,6 @@ pub mod commit;
 
 /// Tree traversal
 pub mod tree;
+
+/// Connectivity checks
+pub mod connectivity;
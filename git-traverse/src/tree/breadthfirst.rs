@@ -43,7 +43,7 @@ pub(crate) mod impl_ {
     use git_object::{tree::EntryMode, TreeRefIter};
 
     use super::{Error, State};
-    use crate::tree::Visit;
+    use crate::tree::{prune, Options, Visit};
 
     /// Start a breadth-first iteration over the `root` trees entries.
     ///
@@ -58,16 +58,33 @@ pub(crate) mod impl_ {
     ///    be escalated into a more specific error if its encountered by the caller.
     /// * `delegate` - A way to observe entries and control the iteration while allowing the optimizer to let you pay only for what you use.
     pub fn traverse<StateMut, Find, V>(
+        root: TreeRefIter<'_>,
+        state: StateMut,
+        find: Find,
+        delegate: &mut V,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+        StateMut: BorrowMut<State>,
+        V: Visit,
+    {
+        traverse_with_options(root, state, find, delegate, Options::default())
+    }
+
+    /// Like [`traverse()`], but only visits entries selected by `options`, without traversing trees that can't contain them.
+    pub fn traverse_with_options<StateMut, Find, V>(
         root: TreeRefIter<'_>,
         mut state: StateMut,
         mut find: Find,
         delegate: &mut V,
+        options: Options<'_>,
     ) -> Result<(), Error>
     where
         Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
         StateMut: BorrowMut<State>,
         V: Visit,
     {
+        let delegate = &mut prune::Delegate::new(delegate, options);
         let state = state.borrow_mut();
         state.clear();
         let mut tree = root;
@@ -0,0 +1,106 @@
+pub use crate::tree::breadthfirst::Error;
+
+/// The state used and potentially shared by multiple tree traversals.
+#[derive(Default, Clone)]
+pub struct State {
+    /// Buffers for the trees currently being traversed, reused as the traversal moves along.
+    bufs: Vec<Vec<u8>>,
+}
+
+pub(crate) mod impl_ {
+    use std::borrow::BorrowMut;
+
+    use git_hash::oid;
+    use git_object::{tree::EntryMode, TreeRefIter};
+
+    use super::{Error, State};
+    use crate::tree::{prune, Options, Visit};
+
+    /// Start a depth-first iteration over the `root` trees entries, which visits all entries in the order git sorts them by
+    /// their full path, just like `git ls-tree -r -t` does.
+    ///
+    /// * `root`
+    ///   * the tree to iterate in a nested fashion.
+    /// * `state` - all state used for the iteration. If multiple iterations are performed, allocations can be minimized by reusing
+    ///   this state.
+    /// * `find` - a way to lookup new object data during traversal by their ObjectId, writing their data into buffer and returning
+    ///    an iterator over entries if the object is present and is a tree. Caching should be implemented within this function
+    ///    as needed. The return value is `Option<TreeIter>` which degenerates all error information. Not finding a commit should also
+    ///    be considered an errors as all objects in the tree DAG should be present in the database. Hence [`Error::NotFound`] should
+    ///    be escalated into a more specific error if its encountered by the caller.
+    /// * `delegate` - A way to observe entries and control the iteration while allowing the optimizer to let you pay only for what you use.
+    pub fn traverse<StateMut, Find, V>(
+        root: TreeRefIter<'_>,
+        state: StateMut,
+        find: Find,
+        delegate: &mut V,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+        StateMut: BorrowMut<State>,
+        V: Visit,
+    {
+        traverse_with_options(root, state, find, delegate, Options::default())
+    }
+
+    /// Like [`traverse()`], but only visits entries selected by `options`, without traversing trees that can't contain them.
+    pub fn traverse_with_options<StateMut, Find, V>(
+        root: TreeRefIter<'_>,
+        mut state: StateMut,
+        mut find: Find,
+        delegate: &mut V,
+        options: Options<'_>,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+        StateMut: BorrowMut<State>,
+        V: Visit,
+    {
+        walk(
+            root,
+            state.borrow_mut(),
+            &mut find,
+            &mut prune::Delegate::new(delegate, options),
+        )
+    }
+
+    fn walk<Find, V>(tree: TreeRefIter<'_>, state: &mut State, find: &mut Find, delegate: &mut V) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
+        V: Visit,
+    {
+        for entry in tree {
+            let entry = entry?;
+            delegate.push_path_component(entry.filename);
+            match entry.mode {
+                EntryMode::Tree => {
+                    use crate::tree::visit::Action::*;
+                    match delegate.visit_tree(&entry) {
+                        Skip => {}
+                        Continue => {
+                            let mut buf = state.bufs.pop().unwrap_or_default();
+                            let res = match find(entry.oid, &mut buf) {
+                                Some(tree) => walk(tree, state, find, delegate),
+                                None => Err(Error::NotFound {
+                                    oid: entry.oid.to_owned(),
+                                }),
+                            };
+                            state.bufs.push(buf);
+                            res?;
+                        }
+                        Cancel => {
+                            return Err(Error::Cancelled);
+                        }
+                    }
+                }
+                _non_tree => {
+                    if delegate.visit_nontree(&entry).cancelled() {
+                        return Err(Error::Cancelled);
+                    }
+                }
+            }
+            delegate.pop_path_component();
+        }
+        Ok(())
+    }
+}
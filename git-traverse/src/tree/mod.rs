@@ -2,6 +2,18 @@ use std::collections::VecDeque;
 
 use git_object::bstr::{BStr, BString};
 
+/// Options for use in [`breadthfirst_with_options()`] and [`depthfirst_with_options()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options<'a> {
+    /// If not empty, only entries at paths matching one of these pathspecs are visited, along with the trees leading to them,
+    /// and trees that can't contain matching paths are not traversed at all.
+    ///
+    /// A pathspec matches a path if it is equal to it or one of its leading directories, or if it matches it as glob pattern.
+    pub pathspecs: &'a [BString],
+    /// If set, trees are not traversed if their entries would be deeper than this, with `0` only visiting the entries of the root tree.
+    pub max_depth: Option<usize>,
+}
+
 /// A trait to allow responding to a traversal designed to observe all entries in a tree, recursively while keeping track of
 /// paths if desired.
 pub trait Visit {
@@ -62,4 +74,10 @@ pub mod recorder;
 
 ///
 pub mod breadthfirst;
-pub use breadthfirst::impl_::traverse as breadthfirst;
+pub use breadthfirst::impl_::{traverse as breadthfirst, traverse_with_options as breadthfirst_with_options};
+
+///
+pub mod depthfirst;
+pub use depthfirst::impl_::{traverse as depthfirst, traverse_with_options as depthfirst_with_options};
+
+mod prune;
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::tree::{visit::Action, Options, Visit};
+
+/// A [Visit] implementation which only passes entries selected by [Options] on to the delegate it wraps, and which
+/// prevents trees from being traversed if none of their entries would be passed on.
+pub(crate) struct Delegate<'a, V> {
+    inner: &'a mut V,
+    opts: Options<'a>,
+    path: BString,
+    path_deque: VecDeque<BString>,
+}
+
+impl<'a, V> Delegate<'a, V> {
+    pub fn new(inner: &'a mut V, opts: Options<'a>) -> Self {
+        Delegate {
+            inner,
+            opts,
+            path: BString::default(),
+            path_deque: VecDeque::new(),
+        }
+    }
+
+    fn tracks_path(&self) -> bool {
+        !self.opts.pathspecs.is_empty() || self.opts.max_depth.is_some()
+    }
+
+    fn push_path(&mut self, component: &BStr) {
+        if !self.path.is_empty() {
+            self.path.push(b'/');
+        }
+        self.path.push_str(component);
+    }
+
+    /// Return true if the tree at the current path may contain paths matching our pathspecs.
+    fn may_contain_matches(&self) -> bool {
+        self.opts.pathspecs.is_empty()
+            || self
+                .opts
+                .pathspecs
+                .iter()
+                .any(|spec| git_pathspec::may_contain_matches(spec.as_ref(), self.path.as_ref()))
+    }
+
+    fn is_match(&self) -> bool {
+        self.opts.pathspecs.is_empty()
+            || self
+                .opts
+                .pathspecs
+                .iter()
+                .any(|spec| git_pathspec::matches(spec.as_ref(), self.path.as_ref()))
+    }
+
+    /// Return true if the entries of the tree at the current path are not deeper than allowed.
+    fn may_descend(&self) -> bool {
+        self.opts
+            .max_depth
+            .map_or(true, |max_depth| self.path.find_iter(b"/").count() < max_depth)
+    }
+}
+
+impl<V: Visit> Visit for Delegate<'_, V> {
+    fn pop_front_tracked_path_and_set_current(&mut self) {
+        if self.tracks_path() {
+            self.path = self.path_deque.pop_front().expect("every parent is set only once");
+        }
+        self.inner.pop_front_tracked_path_and_set_current()
+    }
+
+    fn push_back_tracked_path_component(&mut self, component: &BStr) {
+        if self.tracks_path() {
+            self.push_path(component);
+            self.path_deque.push_back(self.path.clone());
+        }
+        self.inner.push_back_tracked_path_component(component)
+    }
+
+    fn push_path_component(&mut self, component: &BStr) {
+        if self.tracks_path() {
+            self.push_path(component);
+        }
+        self.inner.push_path_component(component)
+    }
+
+    fn pop_path_component(&mut self) {
+        if self.tracks_path() {
+            match self.path.rfind_byte(b'/') {
+                Some(pos) => self.path.resize(pos, 0),
+                None => self.path.clear(),
+            }
+        }
+        self.inner.pop_path_component()
+    }
+
+    fn visit_tree(&mut self, entry: &git_object::tree::EntryRef<'_>) -> Action {
+        if !self.may_contain_matches() {
+            return Action::Skip;
+        }
+        match self.inner.visit_tree(entry) {
+            Action::Continue if !self.may_descend() => Action::Skip,
+            action => action,
+        }
+    }
+
+    fn visit_nontree(&mut self, entry: &git_object::tree::EntryRef<'_>) -> Action {
+        if !self.is_match() {
+            return Action::Continue;
+        }
+        self.inner.visit_nontree(entry)
+    }
+}
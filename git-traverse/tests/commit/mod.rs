@@ -236,4 +236,639 @@ mod ancestor {
         .with_parents(commit::Parents::First)
         .check()
     }
+
+    #[test]
+    fn author_date_sorted_commits() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_with_author_dates.sh",
+            &["b85a41cfd3f94b5e84518ec18259dc1b4402a85d"],
+            &[
+                "3a0a7bbe8d5f3f37029644eef0477d79d0223ab0",
+                "9c8d3bbac213aa2cfc80547ed895df7e3b1da493",
+                "d1d7449e7e990e24b235ae4c8592a129df69fc24",
+            ],
+        )
+        .with_sorting(commit::Sorting::ByAuthorTimeNewestFirst)
+        .check()
+    }
+
+    #[test]
+    fn committer_date_sorted_commits_ignore_author_dates() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_with_author_dates.sh",
+            &["b85a41cfd3f94b5e84518ec18259dc1b4402a85d"],
+            &[
+                "9c8d3bbac213aa2cfc80547ed895df7e3b1da493",
+                "3a0a7bbe8d5f3f37029644eef0477d79d0223ab0",
+                "d1d7449e7e990e24b235ae4c8592a129df69fc24",
+            ],
+        )
+        .with_sorting(commit::Sorting::ByCommitTimeNewestFirst)
+        .check()
+    }
+
+    #[test]
+    fn since_tolerates_clock_skew_without_traversing_all_older_commits() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_with_clock_skew.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let tip = hex_to_id("c936a9de2c4a78d4dfb28bab2272511f1062b86e");
+        let expected: Vec<_> = [
+            "c936a9de2c4a78d4dfb28bab2272511f1062b86e",
+            "686818357dcc3030e04fded1789d5d81617bbfa4",
+            "0122f77a8668134cf3305140052abe08c3c57411",
+            "4b2accc438b319fb889e7fbb2d6a07f8fa5eb5b6",
+            "fca6156f8557c6b4e8e4273908be64c634c5463b",
+        ]
+        .iter()
+        .map(|hex| hex_to_id(hex))
+        .collect();
+        let c3 = hex_to_id("1cf74b0ac859e8cfdcd8c868f616cb21bd3501cd");
+        let cutoff_at_c10 = 947462400;
+
+        for sorting in [commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+            let mut looked_up = Vec::new();
+            let oids: Result<Vec<_>, _> =
+                commit::Ancestors::new(Some(tip), commit::ancestors::State::default(), |oid, buf| {
+                    looked_up.push(oid.to_owned());
+                    store.find_commit_iter(oid, buf).map(|t| t.0)
+                })
+                .sorting(sorting)?
+                .since(cutoff_at_c10)
+                .collect();
+            assert_eq!(
+                oids?, expected,
+                "commits behind the commit with a skewed clock are found, but not the old ones"
+            );
+            assert!(
+                !looked_up.contains(&c3),
+                "only up to {} commits older than the cutoff are traversed",
+                commit::ancestors::SLOP
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn date_sorted_first_parent_commits_of_multiple_tips() -> crate::Result {
+        let dir =
+            git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_author_dates.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let (c2, b1c1, c1) = (
+            hex_to_id("3a0a7bbe8d5f3f37029644eef0477d79d0223ab0"),
+            hex_to_id("9c8d3bbac213aa2cfc80547ed895df7e3b1da493"),
+            hex_to_id("d1d7449e7e990e24b235ae4c8592a129df69fc24"),
+        );
+        for (sorting, expected) in [
+            (commit::Sorting::ByCommitTimeNewestFirst, [b1c1, c2, c1]),
+            (commit::Sorting::ByAuthorTimeNewestFirst, [c2, b1c1, c1]),
+        ] {
+            let oids: Result<Vec<_>, _> =
+                commit::Ancestors::new([c2, b1c1], commit::ancestors::State::default(), |oid, buf| {
+                    store.find_commit_iter(oid, buf).map(|t| t.0)
+                })
+                .sorting(sorting)?
+                .parents(commit::Parents::First)
+                .collect();
+            assert_eq!(oids?, expected);
+        }
+        Ok(())
+    }
+}
+
+mod path_filter {
+    use std::{path::Path, process::Command};
+
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    fn git(repo_dir: &Path, args: &[&str]) -> Vec<ObjectId> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        out.stdout
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|hex| ObjectId::from_hex(hex).expect("valid hex"))
+            .collect()
+    }
+
+    /// Return all commits along the first-parent history of `HEAD` which are changed according to `filter`,
+    /// along with the amount of trees that were read.
+    fn first_parent_commits_changing(
+        repo_dir: &Path,
+        mut filter: commit::path::Filter,
+    ) -> crate::Result<(Vec<ObjectId>, usize)> {
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let tips = git(repo_dir, &["rev-parse", "HEAD"]);
+        let mut trees_read = 0;
+        let mut commits = Vec::new();
+        for id in commit::Ancestors::new(tips, commit::ancestors::State::default(), |oid, buf| {
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .parents(commit::Parents::First)
+        {
+            let id = id?;
+            let is_changed = filter.is_changed_by(&id, |oid, buf| {
+                store.find(oid, buf).map(|(data, _location)| {
+                    if data.kind == git_object::Kind::Tree {
+                        trees_read += 1;
+                    }
+                    data
+                })
+            })?;
+            if is_changed {
+                commits.push(id);
+            }
+        }
+        Ok((commits, trees_read))
+    }
+
+    #[test]
+    fn yields_the_same_commits_as_git_log_with_and_without_commit_graph() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_paths.sh")?;
+        for path in ["top", "top/file", "dir", "dir/sub/file", "dir/other", "missing", ""] {
+            let pathspec = if path.is_empty() { "." } else { path };
+            let expected = git(&repo_dir, &["log", "--first-parent", "--format=%H", "--", pathspec]);
+            let (without_graph, trees_read_without_graph) =
+                first_parent_commits_changing(&repo_dir, commit::path::Filter::new(path))?;
+            assert_eq!(without_graph, expected, "{:?} without commit-graph", path);
+
+            let graph = git_commitgraph::Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+            let (with_graph, trees_read_with_graph) =
+                first_parent_commits_changing(&repo_dir, commit::path::Filter::new(path).with_commit_graph(graph))?;
+            assert_eq!(with_graph, expected, "{:?} with commit-graph", path);
+            if !path.is_empty() {
+                assert!(
+                    trees_read_with_graph < trees_read_without_graph,
+                    "{:?}: Bloom filters avoid reading trees of unchanged commits",
+                    path
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+mod topo {
+    use std::{cell::Cell, path::Path, process::Command};
+
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    fn git(repo_dir: &Path, args: &[&str]) -> Vec<ObjectId> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        out.stdout
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|hex| ObjectId::from_hex(hex).expect("valid hex"))
+            .collect()
+    }
+
+    fn graph(repo_dir: &Path) -> crate::Result<git_commitgraph::Graph> {
+        Ok(git_commitgraph::Graph::from_info_dir(
+            repo_dir.join(".git").join("objects").join("info"),
+        )?)
+    }
+
+    /// Return the commits produced by a topological traversal from `tips`, along with the amount of commits read from the object database.
+    fn topo(
+        repo_dir: &Path,
+        tips: &[ObjectId],
+        parents: commit::Parents,
+        graph: Option<git_commitgraph::Graph>,
+        limit: usize,
+    ) -> crate::Result<(Vec<ObjectId>, usize)> {
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let commits_read = Cell::new(0);
+        let mut walk = commit::Topo::new(tips.iter().cloned(), |oid, buf| {
+            commits_read.set(commits_read.get() + 1);
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .parents(parents);
+        if let Some(graph) = graph {
+            walk = walk.with_commit_graph(graph);
+        }
+        let commits = walk.take(limit).collect::<Result<Vec<_>, _>>()?;
+        Ok((commits, commits_read.get()))
+    }
+
+    #[test]
+    fn yields_the_same_commits_as_git_rev_list_with_and_without_commit_graph() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        for tips in [
+            &["main"][..],
+            &["main", "branch3"],
+            &["branch3", "main", "branch1"],
+            &["branch1", "branch2", "branch3"],
+            &["main~2", "branch3", "main"],
+        ] {
+            let tip_ids: Vec<_> = tips.iter().map(|tip| git(&repo_dir, &["rev-parse", tip])[0]).collect();
+            for (parents, extra_args) in [
+                (commit::Parents::All, &[][..]),
+                (commit::Parents::First, &["--first-parent"]),
+            ] {
+                let expected = git(
+                    &repo_dir,
+                    &[&["rev-list", "--topo-order"][..], extra_args, tips].concat(),
+                );
+                let (without_graph, _) = topo(&repo_dir, &tip_ids, parents, None, usize::MAX)?;
+                assert_eq!(
+                    without_graph, expected,
+                    "{:?} {:?} without commit-graph",
+                    tips, extra_args
+                );
+                let (with_graph, _) = topo(&repo_dir, &tip_ids, parents, Some(graph(&repo_dir)?), usize::MAX)?;
+                assert_eq!(with_graph, expected, "{:?} {:?} with commit-graph", tips, extra_args);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn commit_graph_is_used_instead_of_reading_all_commits_upfront() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let tips = git(&repo_dir, &["rev-parse", "main"]);
+        let expected: Vec<_> = git(&repo_dir, &["rev-list", "--topo-order", "main"])
+            .into_iter()
+            .take(3)
+            .collect();
+
+        let (without_graph, commits_read_without_graph) = topo(&repo_dir, &tips, commit::Parents::All, None, 3)?;
+        assert_eq!(without_graph, expected);
+        assert!(
+            commits_read_without_graph > git(&repo_dir, &["rev-list", "main"]).len(),
+            "all commits are read before the first one is returned"
+        );
+
+        let (with_graph, commits_read_with_graph) =
+            topo(&repo_dir, &tips, commit::Parents::All, Some(graph(&repo_dir)?), 3)?;
+        assert_eq!(with_graph, expected);
+        assert_eq!(commits_read_with_graph, 0, "everything is read from the commit-graph");
+        Ok(())
+    }
+
+    #[test]
+    fn is_ancestor_matches_git_merge_base() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let graph = graph(&repo_dir)?;
+        let commits = git(&repo_dir, &["rev-list", "--all"]);
+        let mut flags = [commit::Flags::new(None), commit::Flags::new(Some(&graph))];
+        for ancestor in &commits {
+            for descendant in &commits {
+                let expected = Command::new("git")
+                    .args(&["merge-base", "--is-ancestor"])
+                    .arg(ancestor.to_string())
+                    .arg(descendant.to_string())
+                    .current_dir(&repo_dir)
+                    .status()?
+                    .success();
+                for flags in &mut flags {
+                    assert_eq!(
+                        commit::topo::is_ancestor(ancestor, descendant, flags, |oid, buf| store
+                            .find_commit_iter(oid, buf)
+                            .map(|t| t.0))?,
+                        expected,
+                        "{} is ancestor of {}, with graph: {}",
+                        ancestor,
+                        descendant,
+                        flags.graph().is_some()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+mod hidden {
+    use std::{path::Path, process::Command};
+
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    fn git(repo_dir: &Path, args: &[&str]) -> Vec<String> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        String::from_utf8(out.stdout)
+            .expect("utf8")
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    fn rev_parse(repo_dir: &Path, rev: &str) -> ObjectId {
+        ObjectId::from_hex(git(repo_dir, &["rev-parse", rev])[0].as_bytes()).expect("valid hex")
+    }
+
+    /// Return the sorted commits and boundary commits of `git rev-list --boundary hidden..tip`.
+    fn rev_list(repo_dir: &Path, hidden: &str, tip: &str, mode: commit::Parents) -> (Vec<ObjectId>, Vec<ObjectId>) {
+        let range = format!("{}..{}", hidden, tip);
+        let mut args = vec!["rev-list", "--boundary", range.as_str()];
+        if let commit::Parents::First = mode {
+            args.push("--first-parent");
+        }
+        let (mut commits, mut boundary) = (Vec::new(), Vec::new());
+        for line in git(repo_dir, &args) {
+            match line.strip_prefix('-') {
+                Some(hex) => boundary.push(ObjectId::from_hex(hex.as_bytes()).expect("valid hex")),
+                None => commits.push(ObjectId::from_hex(line.as_bytes()).expect("valid hex")),
+            }
+        }
+        commits.sort();
+        boundary.sort();
+        (commits, boundary)
+    }
+
+    #[test]
+    fn yields_the_same_commits_and_boundary_as_git_rev_list() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let refs = ["main", "branch1", "branch2", "branch3", "main~4", "branch2~1"];
+        for mode in [commit::Parents::All, commit::Parents::First] {
+            for sorting in [commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+                for hidden in refs {
+                    for tip in refs {
+                        let (expected, expected_boundary) = rev_list(&repo_dir, hidden, tip, mode);
+                        let mut traversal = commit::Ancestors::new(
+                            Some(rev_parse(&repo_dir, tip)),
+                            commit::ancestors::State::default(),
+                            |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0),
+                        )
+                        .sorting(sorting)?
+                        .parents(mode)
+                        .with_hidden(Some(rev_parse(&repo_dir, hidden)));
+                        let mut actual = traversal.by_ref().collect::<Result<Vec<_>, _>>()?;
+                        actual.sort();
+                        assert_eq!(actual, expected, "{}..{}", hidden, tip);
+
+                        if let commit::Parents::All = mode {
+                            let mut boundary = traversal.boundary().to_vec();
+                            boundary.sort();
+                            assert_eq!(boundary, expected_boundary, "boundary of {}..{}", hidden, tip);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hiding_a_tip_hides_it_and_all_its_ancestors() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let main = rev_parse(&repo_dir, "main");
+        let mut traversal = commit::Ancestors::new(Some(main), commit::ancestors::State::default(), |oid, buf| {
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .with_hidden(Some(main));
+        assert_eq!(traversal.next().transpose()?, None, "nothing is left to traverse");
+        assert!(traversal.boundary().is_empty());
+        Ok(())
+    }
+}
+
+mod shallow {
+    use std::{path::Path, process::Command};
+
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    fn git(repo_dir: &Path, args: &[&str]) -> Vec<ObjectId> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        String::from_utf8(out.stdout)
+            .expect("utf8")
+            .lines()
+            .map(|hex| ObjectId::from_hex(hex.as_bytes()).expect("valid hex"))
+            .collect()
+    }
+
+    fn shallow_commits(repo_dir: &Path) -> crate::Result<Vec<ObjectId>> {
+        Ok(std::fs::read_to_string(repo_dir.join(".git").join("shallow"))?
+            .lines()
+            .map(|hex| ObjectId::from_hex(hex.as_bytes()).expect("valid hex"))
+            .collect())
+    }
+
+    #[test]
+    fn yields_the_same_commits_as_git_rev_list() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?.join("shallow");
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let head = git(&repo_dir, &["rev-parse", "HEAD"]);
+        let shallow = shallow_commits(&repo_dir)?;
+        assert_eq!(shallow.len(), 2, "both parents of the merge commit are shallow");
+
+        for sorting in [commit::Sorting::Topological, commit::Sorting::ByCommitTimeNewestFirst] {
+            let mut expected = git(&repo_dir, &["rev-list", "HEAD"]);
+            let mut actual = commit::Ancestors::new(head.clone(), commit::ancestors::State::default(), |oid, buf| {
+                store.find_commit_iter(oid, buf).map(|t| t.0)
+            })
+            .sorting(sorting)?
+            .with_shallow(shallow.iter().cloned())
+            .collect::<Result<Vec<_>, _>>()?;
+            expected.sort();
+            actual.sort();
+            assert_eq!(actual, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_commits_stop_at_shallow_commits() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?.join("shallow");
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let head = git(&repo_dir, &["rev-parse", "HEAD"]);
+        let hidden = git(&repo_dir, &["rev-parse", "HEAD^2"]);
+        let mut traversal = commit::Ancestors::new(head, commit::ancestors::State::default(), |oid, buf| {
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .with_shallow(shallow_commits(&repo_dir)?)
+        .with_hidden(hidden);
+        let actual = traversal.by_ref().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(actual, git(&repo_dir, &["rev-list", "HEAD^2..HEAD"]));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_parents_of_shallow_commits_are_an_error_if_they_are_not_known() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_shallow_repo.sh")?.join("shallow");
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let head = git(&repo_dir, &["rev-parse", "HEAD"]);
+        let res = commit::Ancestors::new(head, commit::ancestors::State::default(), |oid, buf| {
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .collect::<Result<Vec<_>, _>>();
+        assert!(matches!(res, Err(commit::ancestors::Error::FindExisting { .. })));
+        Ok(())
+    }
+}
+
+mod flags {
+    use std::process::Command;
+
+    use git_hash::ObjectId;
+    use git_traverse::commit::Flags;
+
+    const A: u8 = 1 << 0;
+    const B: u8 = 1 << 1;
+
+    #[test]
+    fn are_stored_by_position_in_the_commit_graph_or_by_id() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let graph = git_commitgraph::Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+        let out = Command::new("git")
+            .args(&["rev-list", "--all"])
+            .current_dir(&repo_dir)
+            .output()?;
+        let not_in_graph = ObjectId::from_hex(b"0123456789012345678901234567890123456789")?;
+        let commits: Vec<_> = out
+            .stdout
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(ObjectId::from_hex)
+            .chain(Some(Ok(not_in_graph)))
+            .collect::<Result<_, _>>()?;
+
+        for graph in [None, Some(&graph)] {
+            let mut flags = Flags::<u8>::new(graph);
+            for (idx, id) in commits.iter().enumerate() {
+                assert_eq!(
+                    flags.insert(id, if idx % 2 == 0 { A } else { B }),
+                    0,
+                    "nothing is marked yet"
+                );
+            }
+            for (idx, id) in commits.iter().enumerate() {
+                let previous = if idx % 2 == 0 { A } else { B };
+                assert_eq!(flags.insert(id, A), previous);
+                assert!(flags.contains(id, A));
+                assert_eq!(flags.contains(id, A | B), idx % 2 == 1);
+            }
+            for id in &commits {
+                flags.remove(id, A);
+                assert!(!flags.contains(id, A));
+            }
+            assert_eq!(flags.get(&commits[1]), B, "other flags are kept when removing flags");
+
+            flags.clear();
+            assert!(commits.iter().all(|id| flags.get(id) == 0), "all flags are removed");
+        }
+
+        let mut flags = Flags::<bool>::new(Some(&graph));
+        let pos = graph.lookup(&commits[0]).expect("commit is in graph");
+        assert!(!flags.insert_at(pos, true));
+        assert!(flags.get(&commits[0]), "flags set by position can be read by id");
+        assert!(flags.get_at(pos));
+        assert!(!flags.get(&not_in_graph));
+
+        flags.clear();
+        assert!(!flags.get_at(pos), "flags set by position are cleared as well");
+        assert!(!flags.insert_at(pos, true), "and can be set again after clearing");
+        Ok(())
+    }
+}
+
+mod merge_base {
+    use std::{path::Path, process::Command};
+
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit::{merge_base, Flags};
+
+    fn git(repo_dir: &Path, args: &[String]) -> Vec<ObjectId> {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        let mut ids: Vec<_> = out
+            .stdout
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|hex| ObjectId::from_hex(hex).expect("valid hex"))
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn args(first: &[&str], commits: &[ObjectId]) -> Vec<String> {
+        first
+            .iter()
+            .map(ToString::to_string)
+            .chain(commits.iter().map(ToString::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn merge_bases_and_octopus_match_git_merge_base() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_topo.sh")?;
+        let store = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let graph = git_commitgraph::Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info"))?;
+        let commits = git(&repo_dir, &args(&["rev-list", "--all"], &[]));
+
+        let mut flags = [Flags::new(None), Flags::new(Some(&graph))];
+        for (idx, first) in commits.iter().enumerate() {
+            for second in &commits {
+                let expected = git(&repo_dir, &args(&["merge-base", "--all"], &[*first, *second]));
+                let third = commits[(idx * 7) % commits.len()];
+                let expected_octopus = git(
+                    &repo_dir,
+                    &args(&["merge-base", "--octopus", "--all"], &[*first, *second, third]),
+                );
+                for flags in &mut flags {
+                    let mut actual =
+                        merge_base::merge_bases(first, std::slice::from_ref(second), flags, |oid, buf| {
+                            store.find_commit_iter(oid, buf).map(|t| t.0)
+                        })?;
+                    actual.sort();
+                    assert_eq!(
+                        actual,
+                        expected,
+                        "merge-bases of {} and {}, with graph: {}",
+                        first,
+                        second,
+                        flags.graph().is_some()
+                    );
+
+                    let mut actual = merge_base::octopus(&[*first, *second, third], flags, |oid, buf| {
+                        store.find_commit_iter(oid, buf).map(|t| t.0)
+                    })?;
+                    actual.sort();
+                    assert_eq!(
+                        actual,
+                        expected_octopus,
+                        "octopus merge-bases of {}, {} and {}, with graph: {}",
+                        first,
+                        second,
+                        third,
+                        flags.graph().is_some()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,173 @@
+use std::{collections::HashMap, path::Path, process::Command};
+
+use git_hash::ObjectId;
+use git_object::bstr::ByteSlice;
+use git_odb::pack::Find;
+use git_traverse::connectivity::{self, Missing, UnexpectedKind};
+
+fn git(repo_dir: &Path, args: &[&str]) -> ObjectId {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(repo_dir)
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("git is available");
+    ObjectId::from_hex(out.stdout.trim()).expect("valid hex")
+}
+
+fn rev_parse(repo_dir: &Path, rev: &str) -> ObjectId {
+    git(repo_dir, &["rev-parse", rev])
+}
+
+/// Return the id of the tree of `commit` without accessing the tree, which newer versions of `git rev-parse` insist on.
+fn tree_of(repo_dir: &Path, commit: &str) -> ObjectId {
+    git(repo_dir, &["log", "-1", "--format=%T", commit])
+}
+
+fn fixture() -> crate::Result<(std::path::PathBuf, git_odb::Handle)> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_missing_objects.sh")?;
+    let db = git_odb::at(dir.join(".git").join("objects"))?;
+    Ok((dir, db))
+}
+
+#[test]
+fn missing_objects_are_reported_along_with_the_object_referring_to_them() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let outcome = connectivity::Check::new(
+        |oid, buf| db.try_find(oid, buf).ok().flatten().map(|t| t.0),
+        |oid| db.contains(oid),
+    )
+    .objects_reachable_from(Some(rev_parse(&dir, "main")));
+    assert!(!outcome.is_connected());
+    assert_eq!(
+        outcome.missing,
+        vec![
+            Missing {
+                id: rev_parse(&dir, "main:dir/c"),
+                referenced_by: Some(rev_parse(&dir, "main:dir"))
+            },
+            Missing {
+                id: tree_of(&dir, "main~1"),
+                referenced_by: Some(rev_parse(&dir, "main~1"))
+            }
+        ],
+        "objects are visited depth-first in the order they are mentioned"
+    );
+    assert_eq!(
+        outcome.objects, 6,
+        "two commits, one tree and a blob, and the 'dir' tree with a blob"
+    );
+    Ok(())
+}
+
+#[test]
+fn tags_are_followed_and_missing_tips_have_no_referrer() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let missing_tip = rev_parse(&dir, "main:dir/c");
+    let outcome = connectivity::Check::new(
+        |oid, buf| db.try_find(oid, buf).ok().flatten().map(|t| t.0),
+        |oid| db.contains(oid),
+    )
+    .objects_reachable_from([rev_parse(&dir, "t1"), missing_tip]);
+    assert_eq!(
+        outcome.missing,
+        vec![
+            Missing {
+                id: tree_of(&dir, "main~1"),
+                referenced_by: Some(rev_parse(&dir, "main~1"))
+            },
+            Missing {
+                id: missing_tip,
+                referenced_by: None
+            }
+        ]
+    );
+    assert_eq!(outcome.objects, 2, "the tag and the commit it points to");
+    Ok(())
+}
+
+#[test]
+fn objects_assumed_to_be_connected_are_not_checked() -> crate::Result {
+    let (dir, db) = fixture()?;
+    let mut check = connectivity::Check::new(
+        |oid, buf| db.try_find(oid, buf).ok().flatten().map(|t| t.0),
+        |oid| db.contains(oid),
+    )
+    .assume_connected(Some(rev_parse(&dir, "main~1")));
+    let outcome = check.objects_reachable_from(Some(rev_parse(&dir, "main")));
+    assert_eq!(
+        outcome.missing,
+        vec![Missing {
+            id: rev_parse(&dir, "main:dir/c"),
+            referenced_by: Some(rev_parse(&dir, "main:dir"))
+        }]
+    );
+
+    let outcome = check.objects_reachable_from(Some(rev_parse(&dir, "main")));
+    assert!(
+        outcome.is_connected() && outcome.objects == 0,
+        "objects are only visited once per instance"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_of_unexpected_kind_and_undecodable_objects_are_reported_without_stopping() {
+    let id = |hex: &str| ObjectId::from_hex(hex.repeat(40).as_bytes()).expect("valid hex");
+    let (commit, blob_as_tree, parent, broken_tree) = (id("1"), id("2"), id("3"), id("4"));
+    let objects: HashMap<_, _> = vec![
+        (
+            commit,
+            (
+                git_object::Kind::Commit,
+                format!(
+                    "tree {}\nparent {}\nauthor a <a@example.com> 0 +0000\ncommitter a <a@example.com> 0 +0000\n\nmessage",
+                    blob_as_tree, parent
+                ),
+            ),
+        ),
+        (blob_as_tree, (git_object::Kind::Blob, "content".into())),
+        (
+            parent,
+            (
+                git_object::Kind::Commit,
+                format!(
+                    "tree {}\nauthor a <a@example.com> 0 +0000\ncommitter a <a@example.com> 0 +0000\n\nmessage",
+                    broken_tree
+                ),
+            ),
+        ),
+        (broken_tree, (git_object::Kind::Tree, "garbage".into())),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut check = connectivity::Check::new(
+        |oid, buf| {
+            let (kind, data) = objects.get(oid)?;
+            buf.clear();
+            buf.extend_from_slice(data.as_bytes());
+            Some(git_object::Data::new(*kind, buf))
+        },
+        |oid| objects.contains_key(oid),
+    );
+    let outcome = check.objects_reachable_from(Some(commit));
+    assert!(!outcome.is_connected());
+    assert!(outcome.missing.is_empty());
+    assert_eq!(
+        outcome.unexpected_kind,
+        vec![UnexpectedKind {
+            id: blob_as_tree,
+            expected: git_object::Kind::Tree,
+            actual: git_object::Kind::Blob,
+            referenced_by: commit
+        }]
+    );
+    assert_eq!(
+        outcome.undecodable,
+        vec![broken_tree],
+        "the traversal continues past objects of the wrong kind"
+    );
+    assert_eq!(outcome.objects, 4);
+    assert!(check.has_visited(&broken_tree));
+}
@@ -0,0 +1,24 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+mkdir dir
+echo a > a
+echo b > dir/b
+git add .
+git commit -q -m c1
+
+echo c > dir/c
+git add .
+git commit -q -m c2
+git tag -a -m "annotated" t1 main~1
+
+function remove_object() {
+  local id=${1:?the id of the object to remove}
+  rm ".git/objects/${id:0:2}/${id:2}"
+}
+
+remove_object "$(git rev-parse main:dir/c)"
+remove_object "$(git rev-parse main~1^{tree})"
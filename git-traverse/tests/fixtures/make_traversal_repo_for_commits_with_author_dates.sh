@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+# Authored and committed in year 2000
+git checkout -q -b main
+GIT_AUTHOR_DATE="2000-01-02 00:00:00 +0000" GIT_COMMITTER_DATE="2000-01-02 00:00:00 +0000" git commit -q --allow-empty -m c1
+
+# Authored in 2000, committed in 2001
+git checkout -q -b branch1
+GIT_AUTHOR_DATE="2000-06-02 00:00:00 +0000" GIT_COMMITTER_DATE="2001-01-02 00:00:00 +0000" git commit -q --allow-empty -m b1c1
+
+# Authored in 2001, committed in 2000
+git checkout -q main
+GIT_AUTHOR_DATE="2001-06-02 00:00:00 +0000" GIT_COMMITTER_DATE="2000-06-02 00:00:00 +0000" git commit -q --allow-empty -m c2
+
+# Merge of branch1 authored and committed in 2002
+GIT_AUTHOR_DATE="2002-01-02 00:00:00 +0000" GIT_COMMITTER_DATE="2002-01-02 00:00:00 +0000" git merge branch1 -m m1b1
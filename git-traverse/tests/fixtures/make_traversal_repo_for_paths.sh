@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+mkdir -p dir/sub
+echo c1 > dir/sub/file
+echo c1 > top
+git add -A
+git commit -q -m c1
+
+echo c2 > top
+git commit -q -am c2
+
+echo c3 > dir/sub/file
+git commit -q -am c3
+
+git checkout -q -b branch1
+echo b1c1 > dir/other
+git add dir/other
+git commit -q -m b1c1
+
+git checkout -q main
+git rm -q top
+git commit -q -m c4
+git merge -q branch1 -m m1b1 >/dev/null
+
+mkdir top
+echo c5 > top/file
+git add top
+git commit -q -m c5
+
+git commit-graph write --no-progress --reachable --changed-paths
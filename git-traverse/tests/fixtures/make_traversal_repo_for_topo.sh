@@ -0,0 +1,50 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the commit date in seconds since epoch}
+  GIT_COMMITTER_DATE="@$date +0000" GIT_AUTHOR_DATE="@$date +0000" git commit -q --allow-empty -m "$message"
+}
+
+function merge() {
+  local branch=${1:?first argument is the branch to merge}
+  local date=${2:?second argument is the commit date in seconds since epoch}
+  GIT_COMMITTER_DATE="@$date +0000" GIT_AUTHOR_DATE="@$date +0000" git merge -q --no-edit "$branch" -m "merge $branch"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+for i in $(seq 1 10); do
+  commit "c$i" $((100000 + i))
+done
+
+git checkout -q -b branch1 HEAD~6
+commit b1c1 200001
+commit b1c2 100020
+
+git checkout -q -b branch2 main~3
+commit b2c1 100030
+git checkout -q main
+commit c11 100040
+merge branch1 100050
+
+git checkout -q branch2
+commit b2c2 100060
+merge branch1 100070
+
+git checkout -q main
+commit c12 100080
+merge branch2 100090
+
+git checkout -q -b branch3 branch1~1
+commit b3c1 100100
+commit b3c2 100100
+
+git checkout -q main
+commit c13 100110
+commit c14 100110
+
+git commit-graph write --no-progress --reachable
@@ -0,0 +1,10 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+mkdir -p a/c b
+touch a-b a.txt a/b a/c/d a/c.txt ab b/x b.c
+git add .
+git commit -q -m c1
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+for i in $(seq 1 15); do
+  date="2000-01-$(printf '%02d' "$i") 00:00:00 +0000"
+  if [ "$i" = 14 ]; then
+    # a commit made on a machine with a broken clock
+    date="1990-01-01 00:00:00 +0000"
+  fi
+  GIT_COMMITTER_DATE="$date" git commit -q --allow-empty -m "c$i"
+done
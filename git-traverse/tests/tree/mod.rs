@@ -95,3 +95,133 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+mod options {
+    use std::{path::Path, process::Command};
+
+    use git_object::bstr::{BString, ByteSlice};
+    use git_odb::pack::FindExt;
+    use git_traverse::tree;
+
+    fn git_ls_tree(repo_dir: &Path, pathspecs: &[&str]) -> Vec<BString> {
+        let out = Command::new("git")
+            .args(["ls-tree", "-r", "-t", "--name-only", "HEAD", "--"])
+            .args(pathspecs)
+            .current_dir(repo_dir)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git is available");
+        String::from_utf8(out.stdout)
+            .expect("utf8")
+            .lines()
+            .map(Into::into)
+            .collect()
+    }
+
+    /// Return the paths of all entries as visited by a depth-first or a breadth-first traversal.
+    fn paths(repo_dir: &Path, depth_first: bool, options: tree::Options<'_>) -> crate::Result<Vec<BString>> {
+        let db = git_odb::at(repo_dir.join(".git").join("objects"))?;
+        let head = Command::new("git")
+            .args(["rev-parse", "HEAD^{tree}"])
+            .current_dir(repo_dir)
+            .output()?
+            .stdout;
+        let head = git_hash::ObjectId::from_hex(head.trim())?;
+        let mut buf = Vec::new();
+        let root = db.find_tree_iter(head, &mut buf)?.0;
+        let mut recorder = tree::Recorder::default();
+        if depth_first {
+            tree::depthfirst_with_options(
+                root,
+                tree::depthfirst::State::default(),
+                |oid, buf| db.find_tree_iter(oid, buf).ok().map(|t| t.0),
+                &mut recorder,
+                options,
+            )?;
+        } else {
+            tree::breadthfirst_with_options(
+                root,
+                tree::breadthfirst::State::default(),
+                |oid, buf| db.find_tree_iter(oid, buf).ok().map(|t| t.0),
+                &mut recorder,
+                options,
+            )?;
+        }
+        Ok(recorder.records.into_iter().map(|entry| entry.filepath).collect())
+    }
+
+    #[test]
+    fn depthfirst_visits_entries_in_git_sort_order() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_tree_order.sh")?;
+        assert_eq!(
+            paths(&repo_dir, true, Default::default())?,
+            git_ls_tree(&repo_dir, &[]),
+            "trees sort as if their name ended with a slash"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pathspecs_prune_trees_that_cannot_contain_matches() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_tree_order.sh")?;
+        for pathspecs in [
+            &["a"][..],
+            &["a/"],
+            &["a/c"],
+            &["a/c/d"],
+            &["b/x"],
+            &["missing"],
+            &["a/c", "b.c"],
+        ] {
+            let specs: Vec<BString> = pathspecs.iter().map(|spec| (*spec).into()).collect();
+            let options = tree::Options {
+                pathspecs: &specs,
+                ..Default::default()
+            };
+            let expected = git_ls_tree(&repo_dir, pathspecs);
+            assert_eq!(paths(&repo_dir, true, options)?, expected, "{:?}", pathspecs);
+
+            let mut actual = paths(&repo_dir, false, options)?;
+            actual.sort();
+            let mut expected = expected;
+            expected.sort();
+            assert_eq!(
+                actual, expected,
+                "breadth-first visits the same entries for {:?}",
+                pathspecs
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn max_depth_prevents_traversing_deeper_trees() -> crate::Result {
+        let repo_dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_tree_order.sh")?;
+        for (max_depth, expected) in [
+            (0, &["a-b", "a.txt", "a", "ab", "b.c", "b"][..]),
+            (
+                1,
+                &["a-b", "a.txt", "a", "a/b", "a/c.txt", "a/c", "ab", "b.c", "b", "b/x"],
+            ),
+            (
+                2,
+                &[
+                    "a-b", "a.txt", "a", "a/b", "a/c.txt", "a/c", "a/c/d", "ab", "b.c", "b", "b/x",
+                ],
+            ),
+        ] {
+            let options = tree::Options {
+                max_depth: Some(max_depth),
+                ..Default::default()
+            };
+            assert_eq!(paths(&repo_dir, true, options)?, expected, "depth {}", max_depth);
+            assert_eq!(
+                paths(&repo_dir, false, options)?.len(),
+                expected.len(),
+                "breadth-first visits as many entries for depth {}",
+                max_depth
+            );
+        }
+        Ok(())
+    }
+}
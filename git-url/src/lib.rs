@@ -2,12 +2,9 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
 
-use std::{
-    convert::TryFrom,
-    fmt::{self, Write},
-};
+use std::{convert::TryFrom, fmt, io};
 
-use bstr::ByteSlice;
+use bstr::{BString, ByteSlice};
 
 ///
 pub mod parse;
@@ -47,8 +44,8 @@ impl fmt::Display for Scheme {
 
 /// A URL with support for specialized git related capabilities.
 ///
-/// Additionally there is support for [deserialization][Url::from_bytes()] and serialization
-/// (_see the `Display::fmt()` implementation_).
+/// Additionally there is support for [deserialization][Url::from_bytes()] and [serialization][Url::to_bstring()],
+/// which round-trip as `parse()` retains whether or not the URL was written with a scheme.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Url {
@@ -62,6 +59,9 @@ pub struct Url {
     pub port: Option<u16>,
     /// The path portion of the URL, usually the location of the git repository.
     pub path: bstr::BString,
+    /// If true, the URL is serialized without scheme, as scp-like `[user@]host:path` for [`Scheme::Ssh`] or as plain
+    /// path for [`Scheme::File`]. It has no effect for other schemes.
+    pub serialize_alternative_form: bool,
 }
 
 impl Default for Url {
@@ -72,28 +72,100 @@ impl Default for Url {
             host: None,
             port: None,
             path: bstr::BString::default(),
+            serialize_alternative_form: false,
         }
     }
 }
 
 impl fmt::Display for Url {
+    /// Display the URL as by [`to_bstring()`][Url::to_bstring()], with illformed UTF-8 made unicode safe.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.scheme.fmt(f)?;
-        f.write_str("://")?;
+        f.write_str(self.to_bstring().map_err(|_| fmt::Error)?.to_str_lossy().as_ref())
+    }
+}
+
+/// Serialization
+impl Url {
+    /// Write this URL losslessly to `out`, ready to be parsed again by [`parse()`], percent-encoding the parts of URLs
+    /// with scheme where needed.
+    ///
+    /// Fails if a user is set without host.
+    pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        if self.serialize_alternative_form && matches!(self.scheme, Scheme::File | Scheme::Ssh) {
+            return self.write_alternative_form_to(out);
+        }
+        write!(out, "{}://", self.scheme)?;
         match (&self.user, &self.host) {
-            (Some(user), Some(host)) => f.write_fmt(format_args!("{}@{}", user, host)),
-            (None, Some(host)) => f.write_str(host),
-            (None, None) => Ok(()),
-            _ => return Err(fmt::Error),
-        }?;
+            (Some(user), Some(host)) => {
+                out.write_all(&percent_encode(user.as_bytes(), b"@/"))?;
+                out.write_all(b"@")?;
+                write_host(&mut out, host)?;
+            }
+            (None, Some(host)) => write_host(&mut out, host)?,
+            (None, None) => {}
+            (Some(_), None) => return Err(user_without_host()),
+        }
         if let Some(port) = &self.port {
-            f.write_char(':')?;
-            f.write_fmt(format_args!("{}", port))?;
+            write!(out, ":{}", port)?;
+        }
+        out.write_all(&percent_encode(&self.path, b""))
+    }
+
+    fn write_alternative_form_to(&self, mut out: impl io::Write) -> io::Result<()> {
+        if self.scheme == Scheme::File {
+            return out.write_all(&self.path);
+        }
+        if let Some(user) = &self.user {
+            write!(out, "{}@", user)?;
+        }
+        match (&self.host, self.port) {
+            (Some(host), Some(port)) => write!(out, "[{}:{}]", host, port)?,
+            (Some(host), None) if host.contains(':') => write!(out, "[{}]", host)?,
+            (Some(host), None) => out.write_all(host.as_bytes())?,
+            (None, _) => return Err(user_without_host()),
+        }
+        out.write_all(b":")?;
+        out.write_all(&self.path)
+    }
+
+    /// Transform ourselves into a binary string losslessly, or fail if a user is set without host.
+    pub fn to_bstring(&self) -> io::Result<BString> {
+        let mut buf = Vec::with_capacity(self.path.len() + 32);
+        self.write_to(&mut buf)?;
+        Ok(buf.into())
+    }
+}
+
+fn user_without_host() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "a user requires a host to be set")
+}
+
+fn write_host(mut out: impl io::Write, host: &str) -> io::Result<()> {
+    let host = percent_encode(host.as_bytes(), b"@/");
+    if host.contains(&b':') {
+        out.write_all(b"[")?;
+        out.write_all(&host)?;
+        out.write_all(b"]")
+    } else {
+        out.write_all(&host)
+    }
+}
+
+/// Percent-encode `%`, whitespace and control characters along with all bytes in `extra`, so that [`parse()`] reproduces
+/// the original after decoding.
+fn percent_encode(input: &[u8], extra: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    for &byte in input {
+        if byte == b'%' || byte <= b' ' || byte == 0x7f || extra.contains(&byte) {
+            out.extend_from_slice(format!("%{:02X}", byte).as_bytes());
+        } else {
+            out.push(byte);
         }
-        f.write_str(self.path.to_str_lossy().as_ref())
     }
+    out
 }
 
+/// Deserialization
 impl Url {
     /// Parse a URL from `bytes`
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, parse::Error> {
@@ -1,6 +1,4 @@
-use std::borrow::Cow;
-
-use bstr::ByteSlice;
+use bstr::{BString, ByteSlice};
 use quick_error::quick_error;
 
 use crate::Scheme;
@@ -15,17 +13,20 @@ quick_error! {
             from()
             source(err)
         }
-        Url(err: String) {
-            display("the URL could not be parsed: {}", err)
-        }
         UnsupportedProtocol(protocol: String) {
             display("Protocol '{}' is not supported", protocol)
         }
         EmptyPath {
             display("Paths cannot be empty")
         }
-        RelativeUrl(url: String) {
-            display("Relative URLs are not permitted: '{}'", url)
+        MissingHost(url: BString) {
+            display("URL '{}' does not specify a host", url)
+        }
+        InvalidPort(port: BString) {
+            display("Port '{}' is not a valid port number", port)
+        }
+        UnterminatedBracket(url: BString) {
+            display("The host in URL '{}' lacks its closing bracket", url)
         }
     }
 }
@@ -42,95 +43,220 @@ fn str_to_protocol(s: &str) -> Result<Scheme, Error> {
     })
 }
 
-fn guess_protocol(url: &[u8]) -> &str {
+/// Split `url` into its scheme and the portion past `://` if it looks like a URL with scheme, like git's `is_url()`.
+fn split_scheme(url: &[u8]) -> Option<(&str, &[u8])> {
+    let pos = url.find(b"://")?;
+    let scheme = &url[..pos];
+    let is_valid = matches!(scheme.first(), Some(b) if b.is_ascii_alphabetic())
+        && scheme
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'));
+    if is_valid {
+        Some((std::str::from_utf8(scheme).expect("ascii"), &url[pos + 3..]))
+    } else {
+        None
+    }
+}
+
+/// Returns true if `path` starts with a drive letter like `c:`.
+fn has_drive_letter_prefix(path: &[u8]) -> bool {
+    path.len() >= 2 && path[0].is_ascii_alphabetic() && path[1] == b':'
+}
+
+/// Returns true if `url` without scheme is a path on the local file system instead of a scp-like ssh URL,
+/// which is the case if it has no colon or a slash before the first colon, or if it starts with a drive letter.
+fn is_local_path(url: &[u8]) -> bool {
     match url.find_byte(b':') {
-        Some(colon_pos) => {
-            if url[..colon_pos].find_byte(b'.').is_some() {
-                "ssh"
-            } else {
-                "file"
+        Some(colon) => url[..colon].find_byte(b'/').is_some() || has_drive_letter_prefix(url),
+        None => true,
+    }
+}
+
+/// Decode all valid percent-encoded bytes in `input`, leaving invalid escapes as is, similar to git's `url_decode()`.
+fn percent_decode(input: &[u8]) -> BString {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        let decoded = if input[i] == b'%' {
+            input
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(input[i]);
+                i += 1;
             }
         }
-        None => "file",
     }
+    out.into()
 }
 
-fn sanitize_for_protocol<'a>(protocol: &str, url: &'a str) -> Cow<'a, str> {
-    match protocol {
-        "ssh" => url.replacen(':', "/", 1).into(),
-        _ => url.into(),
+fn decode_to_string(input: &[u8]) -> Result<String, Error> {
+    let decoded = percent_decode(input);
+    Ok(std::str::from_utf8(decoded.as_ref())?.to_owned())
+}
+
+fn parse_port(port: &[u8]) -> Result<Option<u16>, Error> {
+    if port.is_empty() {
+        return Ok(None);
+    }
+    std::str::from_utf8(port)
+        .ok()
+        .filter(|port| port.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|port| port.parse().ok())
+        .map(Some)
+        .ok_or_else(|| Error::InvalidPort(port.into()))
+}
+
+/// Split `user_and_host` at its last `@` into the user name and the rest.
+fn split_user(user_and_host: &[u8]) -> (Option<&[u8]>, &[u8]) {
+    match user_and_host.rfind_byte(b'@') {
+        Some(pos) => (Some(&user_and_host[..pos]), &user_and_host[pos + 1..]),
+        None => (None, user_and_host),
     }
 }
 
-fn has_no_explicit_protocol(url: &[u8]) -> bool {
-    url.find(b"://").is_none()
+/// Parse `[host]` or `[host:port]` at the beginning of `input`, returning `(host, port, position past closing bracket)`.
+///
+/// Only bracket contents with a single colon are considered to have a port, everything else is an IPv6 address.
+fn parse_bracketed_host<'a>(input: &'a [u8], url: &[u8]) -> Result<(&'a [u8], Option<u16>, usize), Error> {
+    let end = input
+        .find_byte(b']')
+        .ok_or_else(|| Error::UnterminatedBracket(url.into()))?;
+    let content = &input[1..end];
+    Ok(match content.find_byte(b':') {
+        Some(colon) if content[colon + 1..].find_byte(b':').is_none() => {
+            (&content[..colon], parse_port(&content[colon + 1..])?, end + 1)
+        }
+        _ => (content, None, end + 1),
+    })
 }
 
-fn possibly_strip_file_protocol(url: &[u8]) -> &[u8] {
-    if url.starts_with(b"file://") {
-        &url[b"file://".len()..]
+fn parse_with_scheme(scheme: Scheme, rest: &[u8], url: &[u8]) -> Result<crate::Url, Error> {
+    if scheme == Scheme::File {
+        let (host, path) = if rest.starts_with(b"/") || has_drive_letter_prefix(rest) {
+            (None, rest)
+        } else {
+            let slash = rest.find_byte(b'/').unwrap_or(rest.len());
+            (Some(decode_to_string(&rest[..slash])?), &rest[slash..])
+        };
+        if path.is_empty() {
+            return Err(Error::EmptyPath);
+        }
+        return Ok(crate::Url {
+            scheme,
+            host,
+            path: percent_decode(path),
+            ..Default::default()
+        });
+    }
+
+    let slash = rest.find_byte(b'/').unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(slash);
+    let (user, host_and_port) = split_user(authority);
+    let (host, port) = if host_and_port.starts_with(b"[") {
+        let (host, port, end) = parse_bracketed_host(host_and_port, url)?;
+        let rest = &host_and_port[end..];
+        match (port, rest.strip_prefix(b":")) {
+            (port, _) if rest.is_empty() => (host, port),
+            (None, Some(port)) => (host, parse_port(port)?),
+            _ => return Err(Error::InvalidPort(rest.into())),
+        }
     } else {
-        url
+        match host_and_port.find_byte(b':') {
+            Some(colon) => (&host_and_port[..colon], parse_port(&host_and_port[colon + 1..])?),
+            None => (host_and_port, None),
+        }
+    };
+    if host.is_empty() {
+        return Err(Error::MissingHost(url.into()));
+    }
+    if scheme != Scheme::Radicle && path.is_empty() {
+        return Err(Error::EmptyPath);
     }
+    Ok(crate::Url {
+        scheme,
+        user: user.map(decode_to_string).transpose()?,
+        host: Some(decode_to_string(host)?),
+        port,
+        path: percent_decode(path),
+        serialize_alternative_form: false,
+    })
 }
 
-fn to_owned_url(url: url::Url) -> Result<crate::Url, Error> {
+/// Parse the scp-like syntax `[user@]host:path`, with `host` optionally in brackets which may also contain a port.
+fn parse_scp_like(url: &[u8]) -> Result<crate::Url, Error> {
+    let colon = url.find_byte(b':').expect("scp-like URLs have a colon");
+    let bracket = if url.starts_with(b"[") {
+        Some(0)
+    } else {
+        url[..colon].find(b"@[").map(|pos| pos + 1)
+    };
+    let (user, host, port, path) = match bracket {
+        Some(bracket) => {
+            let user = if bracket == 0 { None } else { Some(&url[..bracket - 1]) };
+            let (host, port, end) = parse_bracketed_host(&url[bracket..], url)?;
+            let path = url[bracket + end..].strip_prefix(b":").ok_or(Error::EmptyPath)?;
+            (user, host, port, path)
+        }
+        None => {
+            let (user, host) = split_user(&url[..colon]);
+            (user, host, None, &url[colon + 1..])
+        }
+    };
+    if host.is_empty() {
+        return Err(Error::MissingHost(url.into()));
+    }
+    if path.is_empty() {
+        return Err(Error::EmptyPath);
+    }
     Ok(crate::Url {
-        scheme: str_to_protocol(url.scheme())?,
-        user: if url.username().is_empty() {
-            None
-        } else {
-            Some(url.username().into())
-        },
-        host: url.host_str().map(Into::into),
-        port: url.port(),
-        path: url.path().into(),
+        scheme: Scheme::Ssh,
+        user: user
+            .map(|user| std::str::from_utf8(user).map(ToOwned::to_owned))
+            .transpose()?,
+        host: Some(std::str::from_utf8(host)?.to_owned()),
+        port,
+        path: path.into(),
+        serialize_alternative_form: true,
     })
 }
 
 /// Parse the given `bytes` as git url.
 ///
+/// The syntax and semantics follow those of git, which supports…
+/// * URLs with scheme like `ssh://user@[::1]:22/path`, whose user, host and path are percent-decoded,
+/// * scp-like URLs like `user@host:path` or `[host:port]:path` whose path is kept exactly as given,
+/// * and local paths like `/path`, `relative/path` or `c:/path`.
+///
+/// Whether or not a scheme was given is retained, so that [`Url::to_bstring()`][crate::Url::to_bstring()] reproduces
+/// the original form.
+///
 /// # Note
 ///
 /// We cannot and should never have to deal with UTF-16 encoded windows strings, so bytes input is acceptable.
 /// For file-paths, we don't expect UTF8 encoding either.
 pub fn parse(bytes: &[u8]) -> Result<crate::Url, Error> {
-    let guessed_protocol = guess_protocol(bytes);
-    if possibly_strip_file_protocol(bytes) != bytes || (has_no_explicit_protocol(bytes) && guessed_protocol == "file") {
-        return Ok(crate::Url {
-            scheme: Scheme::File,
-            path: possibly_strip_file_protocol(bytes).into(),
-            ..Default::default()
-        });
-    }
-
-    let url_str = std::str::from_utf8(bytes)?;
-    let mut url = match url::Url::parse(url_str) {
-        Ok(url) => url,
-        Err(::url::ParseError::RelativeUrlWithoutBase) => {
-            // happens with bare paths as well as scp like paths. The latter contain a ':' past the host portion,
-            // which we are trying to detect.
-            url::Url::parse(&format!(
-                "{}://{}",
-                guessed_protocol,
-                sanitize_for_protocol(guessed_protocol, url_str)
-            ))
-            .map_err(|err| Error::Url(err.to_string()))?
-        }
-        Err(err) => return Err(Error::Url(err.to_string())),
-    };
-    // SCP like URLs without user parse as 'something' with the scheme being the 'host'. Hosts always have dots.
-    if url.scheme().find('.').is_some() {
-        // try again with prefixed protocol
-        url = url::Url::parse(&format!("ssh://{}", sanitize_for_protocol("ssh", url_str)))
-            .map_err(|err| Error::Url(err.to_string()))?;
-    }
-    if url.scheme() != "rad" && url.path().is_empty() {
+    if bytes.is_empty() {
         return Err(Error::EmptyPath);
     }
-    if url.cannot_be_a_base() {
-        return Err(Error::RelativeUrl(url.into()));
+    match split_scheme(bytes) {
+        Some((scheme, rest)) => parse_with_scheme(str_to_protocol(scheme)?, rest, bytes),
+        None if is_local_path(bytes) => Ok(crate::Url {
+            scheme: Scheme::File,
+            path: bytes.into(),
+            serialize_alternative_form: true,
+            ..Default::default()
+        }),
+        None => parse_scp_like(bytes),
     }
-
-    to_owned_url(url)
 }
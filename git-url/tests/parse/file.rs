@@ -1,6 +1,7 @@
+use bstr::ByteSlice;
 use git_url::Scheme;
 
-use crate::parse::{assert_url_and, assert_url_roundtrip, url};
+use crate::parse::{assert_url_and, assert_url_roundtrip, url, url_alternate};
 
 #[test]
 fn file_path_with_protocol() -> crate::Result {
@@ -12,82 +13,130 @@ fn file_path_with_protocol() -> crate::Result {
 
 #[test]
 fn file_path_without_protocol() -> crate::Result {
-    let url = assert_url_and("/path/to/git", url(Scheme::File, None, None, None, b"/path/to/git"))?.to_string();
-    assert_eq!(url, "file:///path/to/git");
-    Ok(())
+    assert_url_roundtrip(
+        "/path/to/git",
+        url_alternate(Scheme::File, None, None, None, b"/path/to/git"),
+    )
 }
 
 #[test]
 fn no_username_expansion_for_file_paths_without_protocol() -> crate::Result {
-    let url = assert_url_and("~/path/to/git", url(Scheme::File, None, None, None, b"~/path/to/git"))?.to_string();
-    assert_eq!(url, "file://~/path/to/git");
-    Ok(())
+    assert_url_roundtrip(
+        "~/path/to/git",
+        url_alternate(Scheme::File, None, None, None, b"~/path/to/git"),
+    )
 }
+
 #[test]
-fn no_username_expansion_for_file_paths_with_protocol() -> crate::Result {
+fn file_path_with_host_keeps_host_separate_from_path() -> crate::Result {
     assert_url_roundtrip(
         "file://~username/path/to/git",
-        url(Scheme::File, None, None, None, b"~username/path/to/git"),
+        url(Scheme::File, None, "~username", None, b"/path/to/git"),
+    )?;
+    assert_url_roundtrip(
+        "file://host.xz/path/to/git",
+        url(Scheme::File, None, "host.xz", None, b"/path/to/git"),
+    )
+}
+
+#[test]
+fn file_path_with_protocol_is_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "file:///path/with%20space/%25/git",
+        url(Scheme::File, None, None, None, b"/path/with space/%/git"),
+    )?;
+    let url = assert_url_and(
+        "file:///p%61th/to/git",
+        url(Scheme::File, None, None, None, b"/path/to/git"),
+    )?;
+    assert_eq!(
+        url.to_bstring()?,
+        "file:///path/to/git",
+        "only what needs it is encoded"
+    );
+    Ok(())
+}
+
+#[test]
+fn file_path_without_protocol_is_not_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "/path/with%20space",
+        url_alternate(Scheme::File, None, None, None, b"/path/with%20space"),
+    )
+}
+
+#[test]
+fn colon_after_slash_is_a_local_path() -> crate::Result {
+    assert_url_roundtrip(
+        "./host.xz:path",
+        url_alternate(Scheme::File, None, None, None, b"./host.xz:path"),
     )
 }
 
 #[test]
 fn non_utf8_file_path_without_protocol() -> crate::Result {
     let parsed = git_url::parse(b"/path/to\xff/git")?;
-    assert_eq!(parsed, url(Scheme::File, None, None, None, b"/path/to\xff/git",));
     assert_eq!(
-        parsed.to_string(),
-        "file:///path/to�/git",
-        "non-unicode is made unicode safe"
+        parsed,
+        url_alternate(Scheme::File, None, None, None, b"/path/to\xff/git",)
+    );
+    assert_eq!(
+        parsed.to_bstring()?,
+        b"/path/to\xff/git".as_bstr(),
+        "serialization is lossless"
     );
+    assert_eq!(parsed.to_string(), "/path/to�/git", "non-unicode is made unicode safe");
     Ok(())
 }
 
 #[test]
 fn relative_file_path_without_protocol() -> crate::Result {
-    let parsed = assert_url_and(
+    assert_url_roundtrip(
         "../../path/to/git",
-        url(Scheme::File, None, None, None, b"../../path/to/git"),
-    )?
-    .to_string();
-    assert_eq!(parsed, "file://../../path/to/git");
-    let url = assert_url_and("path/to/git", url(Scheme::File, None, None, None, b"path/to/git"))?.to_string();
-    assert_eq!(url, "file://path/to/git");
-    Ok(())
+        url_alternate(Scheme::File, None, None, None, b"../../path/to/git"),
+    )?;
+    assert_url_roundtrip(
+        "path/to/git",
+        url_alternate(Scheme::File, None, None, None, b"path/to/git"),
+    )
 }
 
 #[test]
 fn interior_relative_file_path_without_protocol() -> crate::Result {
-    let url = assert_url_and(
+    assert_url_roundtrip(
         "/abs/path/../../path/to/git",
-        url(Scheme::File, None, None, None, b"/abs/path/../../path/to/git"),
-    )?
-    .to_string();
-    assert_eq!(url, "file:///abs/path/../../path/to/git");
+        url_alternate(Scheme::File, None, None, None, b"/abs/path/../../path/to/git"),
+    )
+}
+
+#[test]
+fn file_url_without_alternative_form_is_serialized_with_protocol() -> crate::Result {
+    assert_eq!(
+        url(Scheme::File, None, None, None, b"/path/to/git").to_bstring()?,
+        "file:///path/to/git"
+    );
     Ok(())
 }
 
 mod windows {
     use git_url::Scheme;
 
-    use crate::parse::{assert_url_and, assert_url_roundtrip, url};
+    use crate::parse::{assert_url_roundtrip, url, url_alternate};
 
     #[test]
     fn file_path_without_protocol() -> crate::Result {
-        let url = assert_url_and("x:/path/to/git", url(Scheme::File, None, None, None, b"x:/path/to/git"))?.to_string();
-        assert_eq!(url, "file://x:/path/to/git");
-        Ok(())
+        assert_url_roundtrip(
+            "x:/path/to/git",
+            url_alternate(Scheme::File, None, None, None, b"x:/path/to/git"),
+        )
     }
 
     #[test]
     fn file_path_with_backslashes_without_protocol() -> crate::Result {
-        let url = assert_url_and(
+        assert_url_roundtrip(
             "x:\\path\\to\\git",
-            url(Scheme::File, None, None, None, b"x:\\path\\to\\git"),
-        )?
-        .to_string();
-        assert_eq!(url, "file://x:\\path\\to\\git");
-        Ok(())
+            url_alternate(Scheme::File, None, None, None, b"x:\\path\\to\\git"),
+        )
     }
 
     #[test]
@@ -14,3 +14,35 @@ fn missing_path() {
 fn missing_port_despite_indication() {
     assert_failure("ssh://host.xz:", "Paths cannot be empty")
 }
+
+#[test]
+fn empty_input() {
+    assert_failure("", "Paths cannot be empty")
+}
+
+#[test]
+fn scp_like_without_path() {
+    assert_failure("host.xz:", "Paths cannot be empty")
+}
+
+#[test]
+fn missing_host() {
+    assert_failure(
+        "ssh:///path/to/repo",
+        "URL 'ssh:///path/to/repo' does not specify a host",
+    )
+}
+
+#[test]
+fn invalid_port() {
+    assert_failure("ssh://host.xz:port/repo", "Port 'port' is not a valid port number");
+    assert_failure("ssh://host.xz:65536/repo", "Port '65536' is not a valid port number");
+}
+
+#[test]
+fn unterminated_ipv6_host() {
+    assert_failure(
+        "ssh://[::1/repo",
+        "The host in URL 'ssh://[::1/repo' lacks its closing bracket",
+    )
+}
@@ -6,7 +6,13 @@ fn assert_url_and(url: &str, expected: git_url::Url) -> Result<git_url::Url, cra
 }
 
 fn assert_url_roundtrip(url: &str, expected: git_url::Url) -> crate::Result {
-    assert_eq!(assert_url_and(url, expected)?.to_string(), url);
+    let serialized = assert_url_and(url, expected)?.to_bstring()?;
+    assert_eq!(serialized, url);
+    assert_eq!(
+        git_url::parse(&serialized)?.to_bstring()?,
+        serialized,
+        "serialization is stable"
+    );
     Ok(())
 }
 
@@ -27,6 +33,20 @@ fn url(
         host: host.into().map(Into::into),
         port: port.into(),
         path: path.into(),
+        serialize_alternative_form: false,
+    }
+}
+
+fn url_alternate(
+    protocol: Scheme,
+    user: impl Into<Option<&'static str>>,
+    host: impl Into<Option<&'static str>>,
+    port: impl Into<Option<u16>>,
+    path: &'static [u8],
+) -> git_url::Url {
+    git_url::Url {
+        serialize_alternative_form: true,
+        ..url(protocol, user, host, port, path)
     }
 }
 
@@ -1,6 +1,6 @@
 use git_url::Scheme;
 
-use crate::parse::{assert_url_and, assert_url_roundtrip, url};
+use crate::parse::{assert_url_and, assert_url_roundtrip, url, url_alternate};
 
 #[test]
 fn without_user_and_without_port() -> crate::Result {
@@ -47,46 +47,125 @@ fn with_user_and_without_port() -> crate::Result {
     )
 }
 
+#[test]
+fn with_user_containing_at_sign() -> crate::Result {
+    assert_url_roundtrip(
+        "ssh://us%40er@host.xz/repo",
+        url(Scheme::Ssh, "us@er", "host.xz", None, b"/repo"),
+    )
+}
+
+#[test]
+fn host_is_ipv6() -> crate::Result {
+    assert_url_roundtrip("ssh://[::1]:22/repo", url(Scheme::Ssh, None, "::1", 22, b"/repo"))?;
+    assert_url_roundtrip(
+        "ssh://user@[2001:db8::1]/repo",
+        url(Scheme::Ssh, "user", "2001:db8::1", None, b"/repo"),
+    )
+}
+
+#[test]
+fn path_is_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "ssh://host.xz/path%20with%20spaces/%25",
+        url(Scheme::Ssh, None, "host.xz", None, b"/path with spaces/%"),
+    )
+}
+
+#[test]
+fn empty_port_is_ignored() -> crate::Result {
+    let url = assert_url_and("ssh://host.xz:/repo", url(Scheme::Ssh, None, "host.xz", None, b"/repo"))?;
+    assert_eq!(url.to_bstring()?, "ssh://host.xz/repo");
+    Ok(())
+}
+
 #[test]
 fn scp_like_without_user() -> crate::Result {
-    let url = assert_url_and(
+    assert_url_roundtrip(
         "host.xz:path/to/git",
-        url(Scheme::Ssh, None, "host.xz", None, b"/path/to/git"),
-    )?
-    .to_string();
-    assert_eq!(url, "ssh://host.xz/path/to/git");
-    Ok(())
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"path/to/git"),
+    )
+}
+
+#[test]
+fn scp_like_with_host_without_dot() -> crate::Result {
+    assert_url_roundtrip("host:path", url_alternate(Scheme::Ssh, None, "host", None, b"path"))
+}
+
+#[test]
+fn scp_like_with_absolute_path() -> crate::Result {
+    assert_url_roundtrip(
+        "host.xz:/path/to/git",
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"/path/to/git"),
+    )
 }
 
 #[test]
 fn scp_like_without_user_and_username_expansion_without_username() -> crate::Result {
-    let url = assert_url_and(
+    assert_url_roundtrip(
         "host.xz:~/to/git",
-        url(Scheme::Ssh, None, "host.xz", None, b"/~/to/git"),
-    )?
-    .to_string();
-    assert_eq!(url, "ssh://host.xz/~/to/git");
-    Ok(())
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"~/to/git"),
+    )
 }
 
 #[test]
 fn scp_like_without_user_and_username_expansion_with_username() -> crate::Result {
-    let url = assert_url_and(
+    assert_url_roundtrip(
         "host.xz:~byron/to/git",
-        url(Scheme::Ssh, None, "host.xz", None, b"/~byron/to/git"),
-    )?
-    .to_string();
-    assert_eq!(url, "ssh://host.xz/~byron/to/git");
-    Ok(())
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"~byron/to/git"),
+    )
 }
 
 #[test]
-fn scp_like_with_user_and_relative_path_turns_into_absolute_path() -> crate::Result {
-    let url = assert_url_and(
+fn scp_like_with_user_and_relative_path_keeps_it_relative() -> crate::Result {
+    assert_url_roundtrip(
         "user@host.xz:./relative",
-        url(Scheme::Ssh, "user", "host.xz", None, b"/relative"),
-    )?
-    .to_string();
-    assert_eq!(url, "ssh://user@host.xz/relative");
+        url_alternate(Scheme::Ssh, "user", "host.xz", None, b"./relative"),
+    )
+}
+
+#[test]
+fn scp_like_with_user_containing_at_sign() -> crate::Result {
+    assert_url_roundtrip(
+        "us@er@host.xz:repo",
+        url_alternate(Scheme::Ssh, "us@er", "host.xz", None, b"repo"),
+    )
+}
+
+#[test]
+fn scp_like_path_is_not_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "host.xz:path%20to/git",
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"path%20to/git"),
+    )
+}
+
+#[test]
+fn scp_like_with_ipv6_host() -> crate::Result {
+    assert_url_roundtrip("[::1]:repo", url_alternate(Scheme::Ssh, None, "::1", None, b"repo"))?;
+    assert_url_roundtrip(
+        "user@[::1]:repo",
+        url_alternate(Scheme::Ssh, "user", "::1", None, b"repo"),
+    )
+}
+
+#[test]
+fn scp_like_with_port_in_brackets() -> crate::Result {
+    assert_url_roundtrip(
+        "[host.xz:22]:repo",
+        url_alternate(Scheme::Ssh, None, "host.xz", 22, b"repo"),
+    )?;
+    assert_url_roundtrip(
+        "user@[host.xz:2222]:repo",
+        url_alternate(Scheme::Ssh, "user", "host.xz", 2222, b"repo"),
+    )
+}
+
+#[test]
+fn ssh_url_without_alternative_form_is_serialized_with_protocol() -> crate::Result {
+    assert_eq!(
+        url(Scheme::Ssh, "user", "host.xz", None, b"/path").to_bstring()?,
+        "ssh://user@host.xz/path"
+    );
     Ok(())
 }
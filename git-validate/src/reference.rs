@@ -26,6 +26,18 @@ pub mod name {
             SingleDot {
                 display("Names must not be a single '.', but may contain it.")
             }
+            EndsWithDot {
+                display("A reference name must not end with a '.'")
+            }
+            ComponentStartsWithDot {
+                display("A component of a reference name must not start with a '.'")
+            }
+            ComponentLockFileSuffix {
+                display("A component of a reference name must not end with '.lock'")
+            }
+            SingleAt {
+                display("A reference name must not be '@' as it is an alias for 'HEAD'")
+            }
         }
     }
 
@@ -36,14 +48,37 @@ pub mod name {
     }
 }
 
-use bstr::BStr;
+use std::borrow::Cow;
+
+use bstr::{BStr, BString, ByteSlice};
 
-/// Validate a reference name running all the tests in the book. This disallows lower-case references, but allows
-/// ones like `HEAD`.
+/// Validate a reference name running all the tests in the book, which are the rules of `git check-ref-format`.
+/// This disallows lower-case references, but allows ones like `HEAD`.
 pub fn name(path: &BStr) -> Result<&BStr, name::Error> {
     validate(path, Mode::Complete)
 }
 
+/// Like [`name()`], but normalize `path` first by removing leading slashes and collapsing repeated slashes into one,
+/// similar to `git check-ref-format --normalize`.
+///
+/// The normalized name is returned on success, which is `path` itself if it didn't need normalization.
+pub fn name_normalized(path: &BStr) -> Result<Cow<'_, BStr>, name::Error> {
+    let path = if path.starts_with(b"/") || path.find(b"//").is_some() {
+        let mut normalized = BString::from(Vec::with_capacity(path.len()));
+        for byte in path.iter().skip_while(|b| **b == b'/') {
+            if *byte == b'/' && normalized.last() == Some(&b'/') {
+                continue;
+            }
+            normalized.push(*byte);
+        }
+        Cow::Owned(normalized)
+    } else {
+        Cow::Borrowed(path)
+    };
+    validate(path.as_ref(), Mode::Complete)?;
+    Ok(path)
+}
+
 /// Validate a partial reference name. As it is assumed to be partial, names like `some-name` is allowed
 /// even though these would be disallowed with when using [`name()`].
 pub fn name_partial(path: &BStr) -> Result<&BStr, name::Error> {
@@ -77,6 +112,21 @@ fn validate(path: &BStr, mode: Mode) -> Result<&BStr, name::Error> {
         previous = *byte;
     }
 
+    if path.ends_with(b".") {
+        return Err(name::Error::EndsWithDot);
+    }
+    if path == "@" {
+        return Err(name::Error::SingleAt);
+    }
+    for component in path.split(|b| *b == b'/') {
+        if component.starts_with(b".") {
+            return Err(name::Error::ComponentStartsWithDot);
+        }
+        if component.ends_with(b".lock") {
+            return Err(name::Error::ComponentLockFileSuffix);
+        }
+    }
+
     if let Mode::Complete = mode {
         if !saw_slash && !path.iter().all(|c| c.is_ascii_uppercase() || *c == b'_') {
             return Err(name::Error::SomeLowercase);
@@ -75,6 +75,18 @@ mod name_partial {
             b"refs\\heads/name with spaces",
             RefError::Tag(TagError::InvalidByte(_))
         );
+        mktest!(ends_with_dot, b"heads/main.", RefError::EndsWithDot);
+        mktest!(
+            component_starts_with_dot,
+            b"heads/.hidden/main",
+            RefError::ComponentStartsWithDot
+        );
+        mktest!(
+            component_ends_with_dot_lock,
+            b"heads/main.lock/other",
+            RefError::ComponentLockFileSuffix
+        );
+        mktest!(single_at, b"@", RefError::SingleAt);
     }
 }
 
@@ -146,5 +158,84 @@ mod name {
             b"refs//heads/main",
             RefError::RepeatedSlash
         );
+        mktest!(ends_with_dot, b"refs/heads/main.", RefError::EndsWithDot);
+        mktest!(ends_with_single_dot_component, b"refs/heads/.", RefError::EndsWithDot);
+        mktest!(
+            component_starts_with_dot,
+            b"refs/heads/.hidden",
+            RefError::ComponentStartsWithDot
+        );
+        mktest!(
+            component_ends_with_dot_lock,
+            b"refs/heads.lock/main",
+            RefError::ComponentLockFileSuffix
+        );
+        mktest!(
+            ends_with_dot_lock,
+            b"refs/heads/main.lock",
+            RefError::Tag(TagError::LockFileSuffix)
+        );
+        mktest!(
+            contains_reflog_portion,
+            b"refs/heads/main@{1}",
+            RefError::Tag(TagError::ReflogPortion)
+        );
+        mktest!(
+            contains_del,
+            b"refs/heads/ma\x7Fin",
+            RefError::Tag(TagError::InvalidByte(_))
+        );
+        mktest!(
+            contains_control_character,
+            b"refs/heads/ma\x01in",
+            RefError::Tag(TagError::InvalidByte(_))
+        );
+        mktest!(single_at, b"@", RefError::SingleAt);
+    }
+}
+
+mod name_normalized {
+    use bstr::ByteSlice;
+    use git_validate::reference::name::Error as RefError;
+
+    #[test]
+    fn leading_and_repeated_slashes_are_removed() {
+        for (input, expected) in &[
+            ("refs/heads/main", "refs/heads/main"),
+            ("/refs/heads/main", "refs/heads/main"),
+            ("//refs//heads///main", "refs/heads/main"),
+            ("HEAD", "HEAD"),
+        ] {
+            assert_eq!(
+                git_validate::reference::name_normalized(input.as_bytes().as_bstr())
+                    .expect("valid after normalization")
+                    .as_ref(),
+                *expected
+            );
+        }
+    }
+
+    #[test]
+    fn already_normalized_names_are_borrowed() {
+        assert!(matches!(
+            git_validate::reference::name_normalized(b"refs/heads/main".as_bstr()),
+            Ok(std::borrow::Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn normalized_names_are_validated() {
+        assert!(matches!(
+            git_validate::reference::name_normalized(b"/main".as_bstr()),
+            Err(RefError::SomeLowercase)
+        ));
+        assert!(matches!(
+            git_validate::reference::name_normalized(b"//refs/heads/./main".as_bstr()),
+            Err(RefError::SingleDot)
+        ));
+        assert!(matches!(
+            git_validate::reference::name_normalized(b"refs//heads/main/".as_bstr()),
+            Err(RefError::Tag(_))
+        ));
     }
 }
@@ -150,6 +150,7 @@ pub struct Collision {
     pub error_kind: std::io::ErrorKind,
 }
 
+#[derive(Debug)]
 pub struct ErrorRecord {
     /// the path that encountered the error.
     pub path: BString,
@@ -157,12 +158,21 @@ pub struct ErrorRecord {
     pub error: Box<dyn std::error::Error + Send + Sync + 'static>,
 }
 
+/// The outcome of a [checkout][crate::index::checkout()], which succeeded for all entries only if there are
+/// neither `collisions` nor `errors`.
+#[derive(Debug)]
 pub struct Outcome {
-    /// The amount of files updated, or created.
+    /// The amount of files updated, or created, without those that collided or failed.
     pub files_updated: usize,
+    /// The amount of entries that weren't checked out as they are excluded from the worktree, typically by a sparse checkout.
+    pub files_skipped: usize,
     /// The amount of bytes written to disk,
     pub bytes_written: u64,
+    /// The paths that collided with something already present on disk, in the order of the index
+    /// with symbolic links coming last.
     pub collisions: Vec<Collision>,
+    /// The paths that couldn't be checked out along with the reason, only populated if
+    /// [`keep_going`][Options::keep_going] is set as the checkout fails on the first error otherwise.
     pub errors: Vec<ErrorRecord>,
 }
 
@@ -183,5 +183,7 @@ where
         .try_into()
         .expect("by 2038 we found a solution for this");
     stat.ctime.nsecs = ctime.subsec_nanos();
+    // Like git, only keep the lower 32 bits of the size.
+    stat.size = meta.len() as u32;
     Ok(())
 }
@@ -1,6 +1,6 @@
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 
-use git_features::{interrupt, parallel::in_parallel, progress, progress::Progress};
+use git_features::{interrupt, parallel::in_parallel_ordered_bounded, progress, progress::Progress};
 use git_hash::oid;
 
 use crate::index::checkout::PathCache;
@@ -8,6 +8,12 @@ use crate::index::checkout::PathCache;
 pub mod checkout;
 pub(crate) mod entry;
 
+/// Checkout all entries of `index` into `dir`, obtaining their content using `find`, and return an [outcome][checkout::Outcome]
+/// listing what was written along with all collisions.
+///
+/// Errors other than collisions abort the checkout unless [`keep_going`][checkout::Options::keep_going] is set,
+/// in which case they are recorded along with their path in the outcome as well.
+///
 /// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
 pub fn checkout<Find, E>(
     index: &mut git_index::State,
@@ -47,17 +53,20 @@ where
     let chunk::Outcome {
         mut collisions,
         mut errors,
+        mut files_updated,
+        files_skipped,
         mut bytes_written,
         delayed,
     } = if num_threads == 1 {
         chunk::process(entries_with_paths, files, bytes, &mut ctx)?
     } else {
-        in_parallel(
+        in_parallel_ordered_bounded(
             git_features::iter::Chunks {
                 inner: entries_with_paths,
                 size: chunk_size,
             },
             thread_limit,
+            num_threads * 2,
             {
                 let num_files = &num_files;
                 move |_| {
@@ -90,7 +99,7 @@ where
     };
 
     for (entry, entry_path) in delayed {
-        bytes_written += chunk::checkout_entry_handle_result(
+        if let Some(object_size) = chunk::checkout_entry_handle_result(
             entry,
             entry_path,
             &mut errors,
@@ -98,14 +107,18 @@ where
             files,
             bytes,
             &mut ctx,
-        )? as u64;
+        )? {
+            files_updated += 1;
+            bytes_written += object_size as u64;
+        }
     }
 
     Ok(checkout::Outcome {
-        files_updated: ctx.num_files.load(Ordering::Relaxed),
+        files_updated,
+        files_skipped,
+        bytes_written,
         collisions,
         errors,
-        bytes_written,
     })
 }
 
@@ -155,11 +168,15 @@ mod chunk {
                 let item = item?;
                 let super::Outcome {
                     bytes_written,
+                    files_updated,
+                    files_skipped,
                     delayed,
                     errors,
                     collisions,
                 } = item;
                 self.aggregate.bytes_written += bytes_written;
+                self.aggregate.files_updated += files_updated;
+                self.aggregate.files_skipped += files_skipped;
                 self.aggregate.delayed.extend(delayed);
                 self.aggregate.errors.extend(errors);
                 self.aggregate.collisions.extend(collisions);
@@ -182,6 +199,8 @@ mod chunk {
         pub collisions: Vec<checkout::Collision>,
         pub errors: Vec<checkout::ErrorRecord>,
         pub delayed: Vec<(&'a mut git_index::Entry, &'a BStr)>,
+        pub files_updated: usize,
+        pub files_skipped: usize,
         pub bytes_written: u64,
     }
 
@@ -208,12 +227,14 @@ mod chunk {
         let mut delayed = Vec::new();
         let mut collisions = Vec::new();
         let mut errors = Vec::new();
+        let mut files_updated = 0;
+        let mut files_skipped = 0;
         let mut bytes_written = 0;
 
         for (entry, entry_path) in entries_with_paths {
-            // TODO: write test for that
             if entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE) {
                 files.inc();
+                files_skipped += 1;
                 continue;
             }
 
@@ -228,12 +249,17 @@ mod chunk {
                 continue;
             }
 
-            bytes_written +=
+            if let Some(object_size) =
                 checkout_entry_handle_result(entry, entry_path, &mut errors, &mut collisions, files, bytes, ctx)?
-                    as u64;
+            {
+                files_updated += 1;
+                bytes_written += object_size as u64;
+            }
         }
 
         Ok(Outcome {
+            files_updated,
+            files_skipped,
             bytes_written,
             errors,
             collisions,
@@ -241,6 +267,8 @@ mod chunk {
         })
     }
 
+    /// Return the amount of bytes written if the entry was checked out, or `None` if it collided or failed and
+    /// its error was recorded.
     pub fn checkout_entry_handle_result<Find, E>(
         entry: &mut git_index::Entry,
         entry_path: &BStr,
@@ -255,7 +283,7 @@ mod chunk {
             options,
             num_files,
         }: &mut Context<'_, Find>,
-    ) -> Result<usize, checkout::Error<E>>
+    ) -> Result<Option<usize>, checkout::Error<E>>
     where
         Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
         E: std::error::Error + Send + Sync + 'static,
@@ -266,7 +294,7 @@ mod chunk {
         match res {
             Ok(object_size) => {
                 bytes.inc_by(object_size);
-                Ok(object_size)
+                Ok(Some(object_size))
             }
             Err(index::checkout::Error::Io(err)) if os::indicates_collision(&err) => {
                 // We are here because a file existed or was blocked by a directory which shouldn't be possible unless
@@ -276,7 +304,7 @@ mod chunk {
                     path: entry_path.into(),
                     error_kind: err.kind(),
                 });
-                Ok(0)
+                Ok(None)
             }
             Err(err) => {
                 if options.keep_going {
@@ -284,7 +312,7 @@ mod chunk {
                         path: entry_path.into(),
                         error: Box::new(err),
                     });
-                    Ok(0)
+                    Ok(None)
                 } else {
                     Err(err)
                 }
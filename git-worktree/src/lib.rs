@@ -11,4 +11,10 @@ pub mod fs;
 
 pub mod index;
 
+/// A stack of ignore patterns and attributes of paths in the work tree.
+pub mod stack;
+
+///
+pub mod status;
+
 pub(crate) mod os;
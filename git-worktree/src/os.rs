@@ -48,3 +48,14 @@ pub fn indicates_collision(err: &std::io::Error) -> bool {
 pub fn indicates_collision(err: &std::io::Error) -> bool {
     err.kind() == AlreadyExists || err.kind() == std::io::ErrorKind::PermissionDenied
 }
+
+#[cfg(not(windows))]
+pub fn indicates_missing(err: &std::io::Error) -> bool {
+    // TODO: use ::NotADirectory as well when stabilized instead of raw_os_error()
+    err.kind() == std::io::ErrorKind::NotFound || err.raw_os_error() == Some(20)
+}
+
+#[cfg(windows)]
+pub fn indicates_missing(err: &std::io::Error) -> bool {
+    err.kind() == std::io::ErrorKind::NotFound
+}
@@ -0,0 +1,355 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_glob::pattern::Case;
+use git_hash::{oid, ObjectId};
+
+use crate::os;
+
+/// Where to read `.gitignore` and `.gitattributes` files from.
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// Read them from the work tree at the given directory.
+    WorkTree(PathBuf),
+    /// Read them from the blobs of index entries at the respective paths, sorted by path, which is useful if there is no
+    /// work tree like in bare repositories, or if it doesn't contain the files yet like during checkout.
+    ///
+    /// Use [`Source::from_index()`] to create it.
+    Index(Vec<(BString, ObjectId)>),
+}
+
+impl Source {
+    /// Collect the paths and ids of all `.gitignore` and `.gitattributes` files tracked in `index`.
+    ///
+    /// This allows to keep reading them while the index is modified, as during checkout.
+    pub fn from_index(index: &git_index::State) -> Self {
+        Source::Index(
+            index
+                .entries()
+                .iter()
+                .filter(|entry| entry.stage() == 0 && entry.mode == git_index::entry::Mode::FILE)
+                .map(|entry| (entry.path(index), entry.id))
+                .filter(|(path, _)| {
+                    let file_name = path.rfind_byte(b'/').map_or(*path, |pos| &path[pos + 1..]);
+                    file_name == ".gitignore" || file_name == ".gitattributes"
+                })
+                .map(|(path, id)| (path.to_owned(), id))
+                .collect(),
+        )
+    }
+
+    fn find_id(&self, path: &BStr) -> Option<&oid> {
+        match self {
+            Source::WorkTree(_) => None,
+            Source::Index(ids) => ids
+                .binary_search_by(|(p, _)| p.as_bstr().cmp(path))
+                .ok()
+                .map(|pos| ids[pos].1.as_ref()),
+        }
+    }
+}
+
+/// A way to learn how much work was performed by a [`Stack`], useful for benchmarking.
+#[derive(PartialEq, Eq, Debug, Hash, Default, Clone, Copy)]
+pub struct Statistics {
+    /// The amount of directories whose files were pushed onto the stack.
+    pub directories_pushed: usize,
+    /// The amount of `.gitignore` files that were read and parsed.
+    pub ignore_files_read: usize,
+    /// The amount of `.gitattributes` files that were read and parsed.
+    pub attribute_files_read: usize,
+    /// The amount of files read from the index whose parsed content was taken from the cache instead.
+    pub cache_hits: usize,
+}
+
+/// The error returned by [`Stack::at_path()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E: std::error::Error + Send + Sync + 'static> {
+    #[error("Could not convert path to UTF8: {}", .path)]
+    IllformedUtf8 { path: BString },
+    #[error("IO error while reading an ignore or attribute file")]
+    Io(#[from] std::io::Error),
+    #[error("The attribute file at '{path}' could not be parsed")]
+    ParseAttributes {
+        source: git_attributes::parse::Error,
+        path: BString,
+    },
+    #[error("object {} for the file at {} could not be retrieved from object database", .oid.to_hex(), .path)]
+    Find {
+        #[source]
+        err: E,
+        oid: ObjectId,
+        path: BString,
+    },
+}
+
+/// A stack of ignore patterns and attributes that follows a walk through the directories of a work tree, loading the
+/// `.gitignore` and `.gitattributes` files of each directory only when a path inside of it is queried.
+///
+/// The walk is assumed to visit paths in order, as only the files of the directories leading to the most recently
+/// queried path are kept. Files read from the [index][Source::Index] are parsed only once for each blob id.
+pub struct Stack {
+    source: Source,
+    case: Case,
+    /// Ignore patterns that take the lowest precedence, or `None` if ignore patterns aren't needed.
+    excludes: Option<Vec<git_glob::Pattern>>,
+    /// The ignore patterns of each directory in `dirs`.
+    ignore: Vec<Vec<git_glob::Pattern>>,
+    /// The attributes of each directory in `dirs`, or `None` if attributes aren't needed.
+    attributes: Option<git_attributes::Stack>,
+    /// The directories whose files are on the stack, starting with the root of the work tree as empty path, with
+    /// all others having a trailing slash.
+    dirs: Vec<BString>,
+    ignore_cache: HashMap<ObjectId, Vec<git_glob::Pattern>>,
+    attribute_cache: HashMap<ObjectId, git_attributes::List>,
+    buf: Vec<u8>,
+    /// Information about the work performed so far.
+    pub statistics: Statistics,
+}
+
+impl Stack {
+    /// Create a new instance which reads files from `source` and matches paths according to `case`, neither
+    /// loading ignore patterns nor attributes until configured with [`with_excludes()`][Stack::with_excludes()]
+    /// or [`with_attributes()`][Stack::with_attributes()].
+    pub fn new(source: Source, case: Case) -> Self {
+        Stack {
+            source,
+            case,
+            excludes: None,
+            ignore: Vec::new(),
+            attributes: None,
+            dirs: Vec::new(),
+            ignore_cache: HashMap::new(),
+            attribute_cache: HashMap::new(),
+            buf: Vec::new(),
+            statistics: Statistics::default(),
+        }
+    }
+
+    /// Load `.gitignore` files, with the ignore patterns in `excludes` taking the lowest precedence, which
+    /// typically are read from `.git/info/exclude` and the file configured in `core.excludesFile`.
+    pub fn with_excludes(mut self, excludes: &[u8]) -> Self {
+        self.excludes = Some(git_attributes::parse::ignore(excludes).map(|(p, _)| p).collect());
+        self
+    }
+
+    /// Load `.gitattributes` files, with the attributes in `attributes` taking the lowest precedence, which
+    /// typically are read from the file configured in `core.attributesFile`.
+    pub fn with_attributes(mut self, attributes: &[u8]) -> Result<Self, git_attributes::parse::Error> {
+        let mut stack = git_attributes::Stack::default().with_case(self.case);
+        stack.push(attributes, "")?;
+        self.attributes = Some(stack);
+        Ok(self)
+    }
+
+    /// Prepare the stack for querying the file or directory at `relative_path`, depending on `is_dir`, which is
+    /// relative to the root of the work tree and uses slashes as separators, by loading the files of all directories
+    /// leading to it.
+    ///
+    /// `find` is used to read blobs if the source is the [index][Source::Index], and [`no_find()`] can be used
+    /// otherwise.
+    pub fn at_path<'a, Find, E>(
+        &'a mut self,
+        relative_path: &'a BStr,
+        is_dir: bool,
+        mut find: Find,
+    ) -> Result<Platform<'a>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let dir = relative_path
+            .rfind_byte(b'/')
+            .map_or(&relative_path[..0], |pos| &relative_path[..=pos]);
+        while self.dirs.last().map_or(false, |last| !dir.starts_with(last)) {
+            self.pop_directory();
+        }
+        if self.dirs.is_empty() {
+            self.push_directory(BString::default(), &mut find)?;
+        }
+        loop {
+            let known = self.dirs.last().expect("root is present").len();
+            match dir[known..].find_byte(b'/') {
+                Some(pos) => self.push_directory(dir[..known + pos + 1].into(), &mut find)?,
+                None => break,
+            }
+        }
+        Ok(Platform {
+            stack: self,
+            relative_path,
+            is_dir,
+        })
+    }
+
+    fn push_directory<Find, E>(&mut self, dir: BString, find: &mut Find) -> Result<(), Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.statistics.directories_pushed += 1;
+        if self.excludes.is_some() {
+            let mut path = dir.clone();
+            path.push_str(".gitignore");
+            let patterns = match self.source.find_id(path.as_bstr()).map(ToOwned::to_owned) {
+                Some(id) => match self.ignore_cache.get(&id) {
+                    Some(patterns) => {
+                        self.statistics.cache_hits += 1;
+                        patterns.clone()
+                    }
+                    None => {
+                        let blob = find(&id, &mut self.buf).map_err(|err| Error::Find {
+                            err,
+                            oid: id,
+                            path: path.clone(),
+                        })?;
+                        let patterns: Vec<_> = git_attributes::parse::ignore(blob.data).map(|(p, _)| p).collect();
+                        self.statistics.ignore_files_read += 1;
+                        self.ignore_cache.insert(id, patterns.clone());
+                        patterns
+                    }
+                },
+                None => {
+                    if self.read_from_worktree(path.as_bstr())? {
+                        self.statistics.ignore_files_read += 1;
+                    }
+                    git_attributes::parse::ignore(&self.buf).map(|(p, _)| p).collect()
+                }
+            };
+            self.ignore.push(
+                patterns
+                    .into_iter()
+                    .map(|pattern| {
+                        if dir.is_empty() {
+                            pattern
+                        } else {
+                            pattern.with_base(dir.clone())
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        if self.attributes.is_some() {
+            let mut path = dir.clone();
+            path.push_str(".gitattributes");
+            let list = match self.source.find_id(path.as_bstr()).map(ToOwned::to_owned) {
+                Some(id) => match self.attribute_cache.get(&id) {
+                    Some(list) => {
+                        self.statistics.cache_hits += 1;
+                        list.clone()
+                    }
+                    None => {
+                        let blob = find(&id, &mut self.buf).map_err(|err| Error::Find {
+                            err,
+                            oid: id,
+                            path: path.clone(),
+                        })?;
+                        let list = git_attributes::List::from_bytes(blob.data)
+                            .map_err(|source| Error::ParseAttributes { source, path })?;
+                        self.statistics.attribute_files_read += 1;
+                        self.attribute_cache.insert(id, list.clone());
+                        list
+                    }
+                },
+                None => {
+                    if self.read_from_worktree(path.as_bstr())? {
+                        self.statistics.attribute_files_read += 1;
+                    }
+                    git_attributes::List::from_bytes(&self.buf)
+                        .map_err(|source| Error::ParseAttributes { source, path })?
+                }
+            };
+            self.attributes
+                .as_mut()
+                .expect("checked above")
+                .push_list(list, dir.clone());
+        }
+        self.dirs.push(dir);
+        Ok(())
+    }
+
+    fn pop_directory(&mut self) {
+        self.dirs.pop();
+        self.ignore.pop();
+        if let Some(attributes) = self.attributes.as_mut() {
+            attributes.pop();
+        }
+    }
+
+    /// Read the file at `relative_path` into our buffer if the source is the work tree and return true if it exists,
+    /// or clear the buffer otherwise.
+    fn read_from_worktree<E>(&mut self, relative_path: &BStr) -> Result<bool, Error<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.buf.clear();
+        let root = match &self.source {
+            Source::WorkTree(root) => root,
+            Source::Index(_) => return Ok(false),
+        };
+        let path = root.join(
+            git_features::path::from_byte_slice(relative_path).map_err(|_| Error::IllformedUtf8 {
+                path: relative_path.to_owned(),
+            })?,
+        );
+        match std::fs::File::open(&path) {
+            Ok(mut file) => {
+                use std::io::Read;
+                file.read_to_end(&mut self.buf)?;
+                Ok(true)
+            }
+            Err(err) if os::indicates_missing(&err) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// A `find` implementation for use with [`Stack::at_path()`] if the source is the [work tree][Source::WorkTree], which
+/// never has to read blobs.
+pub fn no_find<'a>(_id: &oid, _buf: &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, std::convert::Infallible> {
+    unreachable!("blobs are only read if the source is the index")
+}
+
+/// The ignore patterns and attributes applying to a path, as returned by [`Stack::at_path()`].
+pub struct Platform<'a> {
+    stack: &'a Stack,
+    relative_path: &'a BStr,
+    is_dir: bool,
+}
+
+impl<'a> Platform<'a> {
+    /// Return true if the path is ignored, which is always false if the stack wasn't created
+    /// [with excludes][Stack::with_excludes()].
+    ///
+    /// Note that the path is not ignored just because one of its leading directories is ignored, which is up to the caller to check.
+    pub fn is_ignored(&self) -> bool {
+        let excludes = match &self.stack.excludes {
+            Some(excludes) => excludes,
+            None => return false,
+        };
+        let basename_start_pos = self.relative_path.rfind_byte(b'/').map(|p| p + 1);
+        self.stack
+            .ignore
+            .iter()
+            .rev()
+            .chain(std::iter::once(excludes))
+            .flat_map(|patterns| patterns.iter().rev())
+            .find(|pattern| {
+                pattern.matches_repo_relative_path(self.relative_path, basename_start_pos, self.is_dir, self.stack.case)
+            })
+            .map_or(false, |pattern| !pattern.is_negative())
+    }
+
+    /// Return all attributes of the path that aren't unspecified, which are always empty if the stack wasn't created
+    /// [with attributes][Stack::with_attributes()].
+    pub fn attributes(&self) -> BTreeMap<&'a BStr, git_attributes::State<'a>> {
+        self.stack
+            .attributes
+            .as_ref()
+            .map(|attributes| attributes.attributes(self.relative_path, self.is_dir))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,433 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_index::{entry::Mode, State};
+
+use crate::{os, stack, stack::Stack};
+
+/// How to handle files in the worktree that are not tracked by the index, similar to `git status --untracked-files`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Untracked {
+    /// Don't look for untracked files at all.
+    No,
+    /// Show untracked files, but collapse directories without any tracked file into a single entry with trailing slash.
+    Normal,
+    /// Show each untracked file individually.
+    All,
+}
+
+impl Default for Untracked {
+    fn default() -> Self {
+        Untracked::Normal
+    }
+}
+
+/// Options for use in [`index_worktree()`].
+#[derive(Default, Clone, Copy)]
+pub struct Options {
+    /// Capabilities of the file system the worktree is on, affecting how symlinks and executable bits are interpreted, and
+    /// whether ignore patterns match paths case-insensitively.
+    pub fs: crate::fs::Capabilities,
+    /// How to look for untracked files.
+    pub untracked: Untracked,
+}
+
+/// The way an index entry differs from the file in the worktree.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub enum Change {
+    /// The file doesn't exist in the worktree anymore.
+    Removed,
+    /// The content of the file or its executable bit changed, with `mode` being the mode of the file in the worktree.
+    Modified { mode: Mode },
+    /// The file changed its type, for example from a file to a symlink, with `mode` being the mode of the file in the worktree.
+    TypeChange { mode: Mode },
+}
+
+/// A way to learn how much work was necessary to produce an [`Outcome`], useful for benchmarking.
+#[derive(PartialEq, Eq, Debug, Hash, Default, Clone, Copy)]
+pub struct Statistics {
+    /// The amount of index entries that were compared to the worktree.
+    pub entries: usize,
+    /// The amount of index entries that were not compared as they are conflicted, submodules or marked to skip the worktree.
+    pub entries_skipped: usize,
+    /// The amount of entries which were deemed unchanged just by comparing their stat information.
+    pub entries_unchanged_by_stat: usize,
+    /// The amount of files whose content had to be hashed to know if they changed.
+    pub files_hashed: usize,
+    /// The amount of directories whose entries were read when looking for untracked files.
+    pub directories_read: usize,
+    /// The amount of `.gitignore` files that were read.
+    pub ignore_files_read: usize,
+    /// The amount of untracked files and directories which were excluded as they were ignored.
+    pub ignored: usize,
+}
+
+/// The outcome of [`index_worktree()`].
+#[derive(Default, Clone)]
+pub struct Outcome {
+    /// Entries that differ from the worktree, by their position in the index' entries, sorted by that position.
+    pub changes: Vec<(usize, Change)>,
+    /// Sorted paths to untracked files, relative to the worktree root, with directories having a trailing slash.
+    pub untracked: Vec<BString>,
+    /// Information about the work performed.
+    pub statistics: Statistics,
+}
+
+/// The error returned by [`index_worktree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not convert path to UTF8: {}", .path)]
+    IllformedUtf8 { path: BString },
+    #[error("IO error while reading file metadata, file contents or directories")]
+    Io(#[from] std::io::Error),
+    #[error("The clock was off when reading file modification times")]
+    Time(#[from] std::time::SystemTimeError),
+    #[error(transparent)]
+    Ignore(#[from] stack::Error<std::convert::Infallible>),
+}
+
+/// Compare all entries in `index` to the files in the `worktree` directory and find untracked files as configured in `options`,
+/// using ignore patterns from `.gitignore` files in the worktree as well as `excludes`, which typically are read from
+/// `.git/info/exclude`.
+///
+/// Entries whose stat information matches the file on disk are assumed to be unchanged, unless they were modified too closely to
+/// the [`index timestamp`][State::timestamp()], in which case the file is hashed just like files whose stat information changed.
+/// Note that no content filters are applied before hashing.
+pub fn index_worktree(
+    index: &State,
+    worktree: impl AsRef<Path>,
+    excludes: &[u8],
+    options: Options,
+) -> Result<Outcome, Error> {
+    let worktree = worktree.as_ref();
+    let mut out = Outcome::default();
+    let mut buf = Vec::new();
+    for (idx, entry) in index.entries().iter().enumerate() {
+        if entry.stage() != 0
+            || entry.mode == Mode::COMMIT
+            || entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE)
+        {
+            out.statistics.entries_skipped += 1;
+            continue;
+        }
+        out.statistics.entries += 1;
+        if let Some(change) = compare_entry(index, entry, worktree, options.fs, &mut buf, &mut out.statistics)? {
+            out.changes.push((idx, change));
+        }
+    }
+
+    if options.untracked != Untracked::No {
+        let case = if options.fs.ignore_case {
+            git_glob::pattern::Case::Fold
+        } else {
+            git_glob::pattern::Case::Sensitive
+        };
+        let mut ignore = Stack::new(stack::Source::WorkTree(worktree.to_owned()), case).with_excludes(excludes);
+        let mut walk = Walk {
+            index,
+            mode: options.untracked,
+            ignore: &mut ignore,
+            stats: &mut out.statistics,
+            untracked: &mut out.untracked,
+        };
+        let mut rela_path = BString::default();
+        walk.directory(worktree.to_owned(), &mut rela_path)?;
+        out.statistics.ignore_files_read = ignore.statistics.ignore_files_read;
+        out.untracked.sort();
+    }
+    Ok(out)
+}
+
+fn compare_entry(
+    index: &State,
+    entry: &git_index::Entry,
+    worktree: &Path,
+    fs: crate::fs::Capabilities,
+    buf: &mut Vec<u8>,
+    stats: &mut Statistics,
+) -> Result<Option<Change>, Error> {
+    let rela_path = entry.path(index);
+    let path = worktree.join(
+        git_features::path::from_byte_slice(rela_path).map_err(|_| Error::IllformedUtf8 {
+            path: rela_path.to_owned(),
+        })?,
+    );
+    let meta = match std::fs::symlink_metadata(&path) {
+        Ok(meta) if meta.is_dir() => return Ok(Some(Change::Removed)),
+        Ok(meta) => meta,
+        Err(err) if os::indicates_missing(&err) => return Ok(Some(Change::Removed)),
+        Err(err) => return Err(err.into()),
+    };
+
+    let is_symlink = meta.file_type().is_symlink() || (!fs.symlink && entry.mode == Mode::SYMLINK);
+    let mode = if is_symlink {
+        Mode::SYMLINK
+    } else if fs.executable_bit {
+        if is_executable(&meta) {
+            Mode::FILE_EXECUTABLE
+        } else {
+            Mode::FILE
+        }
+    } else if entry.mode == Mode::SYMLINK {
+        Mode::FILE
+    } else {
+        entry.mode
+    };
+    if (mode == Mode::SYMLINK) != (entry.mode == Mode::SYMLINK) {
+        return Ok(Some(Change::TypeChange { mode }));
+    }
+
+    let mtime = meta.modified().map_or(Ok(std::time::Duration::default()), |t| {
+        t.duration_since(std::time::UNIX_EPOCH)
+    })?;
+    let size = meta.len() as u32;
+    let is_racy = {
+        let timestamp = index.timestamp();
+        (entry.stat.mtime.secs as i64, entry.stat.mtime.nsecs) >= (timestamp.unix_seconds(), timestamp.nanoseconds())
+    };
+    let content_changed = if !is_racy
+        && entry.stat.size == size
+        && entry.stat.mtime.secs as u64 == mtime.as_secs()
+        && entry.stat.mtime.nsecs == mtime.subsec_nanos()
+    {
+        stats.entries_unchanged_by_stat += 1;
+        false
+    } else if entry.stat.size != 0 && entry.stat.size != size && !is_symlink {
+        true
+    } else {
+        stats.files_hashed += 1;
+        buf.clear();
+        if meta.file_type().is_symlink() {
+            buf.extend_from_slice(&git_features::path::into_bytes_or_panic_on_windows(std::fs::read_link(
+                &path,
+            )?));
+        } else {
+            use std::io::Read;
+            std::fs::File::open(&path)?.read_to_end(buf)?;
+        }
+        hash_blob(buf, entry.id.kind()) != entry.id
+    };
+
+    Ok(if content_changed || mode != entry.mode {
+        Some(Change::Modified { mode })
+    } else {
+        None
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.mode() & 0o100 == 0o100
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+fn hash_blob(data: &[u8], kind: git_hash::Kind) -> git_hash::ObjectId {
+    let mut hasher = git_features::hash::hasher(kind);
+    hasher.update(&git_object::encode::loose_header(git_object::Kind::Blob, data.len()));
+    hasher.update(data);
+    git_hash::ObjectId::from(hasher.digest())
+}
+
+/// Changes between a tree and the index, typically the tree of `HEAD`, as produced by [`tree_index()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub enum Staged {
+    /// The entry at `path` was added to the index.
+    Added {
+        path: BString,
+        mode: Mode,
+        id: git_hash::ObjectId,
+    },
+    /// The entry at `path` was removed from the index.
+    Deleted {
+        path: BString,
+        mode: Mode,
+        id: git_hash::ObjectId,
+    },
+    /// The entry at `path` changed its mode or content.
+    Modified {
+        path: BString,
+        previous_mode: Mode,
+        previous_id: git_hash::ObjectId,
+        mode: Mode,
+        id: git_hash::ObjectId,
+    },
+}
+
+impl Staged {
+    /// The path of the changed entry.
+    pub fn path(&self) -> &BStr {
+        match self {
+            Staged::Added { path, .. } | Staged::Deleted { path, .. } | Staged::Modified { path, .. } => path.as_bstr(),
+        }
+    }
+}
+
+/// Return all changes between `tree`, typically [created][State::from_tree()] from the tree of `HEAD`, and the `index`, in path order.
+///
+/// Conflicted entries in `index` are ignored, along with the corresponding entry in `tree`.
+pub fn tree_index(tree: &State, index: &State) -> Vec<Staged> {
+    let mut out = Vec::new();
+    let mut lhs = tree.entries().iter().peekable();
+    let mut rhs = index.entries().iter().peekable();
+    loop {
+        let order = match (lhs.peek(), rhs.peek()) {
+            (None, None) => break,
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (Some(l), Some(r)) => l.path(tree).cmp(r.path(index)),
+        };
+        match order {
+            std::cmp::Ordering::Less => {
+                let entry = lhs.next().expect("peeked");
+                out.push(Staged::Deleted {
+                    path: entry.path(tree).to_owned(),
+                    mode: entry.mode,
+                    id: entry.id,
+                });
+            }
+            std::cmp::Ordering::Greater => {
+                let entry = rhs.next().expect("peeked");
+                if entry.stage() == 0 {
+                    out.push(Staged::Added {
+                        path: entry.path(index).to_owned(),
+                        mode: entry.mode,
+                        id: entry.id,
+                    });
+                }
+            }
+            std::cmp::Ordering::Equal => {
+                let previous = lhs.next().expect("peeked");
+                let entry = rhs.next().expect("peeked");
+                if entry.stage() != 0 {
+                    let path = entry.path(index);
+                    while rhs.peek().map_or(false, |e| e.path(index) == path) {
+                        rhs.next();
+                    }
+                    continue;
+                }
+                if previous.mode != entry.mode || previous.id != entry.id {
+                    out.push(Staged::Modified {
+                        path: entry.path(index).to_owned(),
+                        previous_mode: previous.mode,
+                        previous_id: previous.id,
+                        mode: entry.mode,
+                        id: entry.id,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+struct Walk<'a> {
+    index: &'a State,
+    mode: Untracked,
+    ignore: &'a mut Stack,
+    stats: &'a mut Statistics,
+    untracked: &'a mut Vec<BString>,
+}
+
+impl Walk<'_> {
+    /// Visit the directory at `path`, which is at `rela_path` in the worktree, recording all untracked files within.
+    fn directory(&mut self, path: PathBuf, rela_path: &mut BString) -> Result<(), Error> {
+        self.stats.directories_read += 1;
+        self.entries(&path, rela_path)
+    }
+
+    fn entries(&mut self, path: &Path, rela_path: &mut BString) -> Result<(), Error> {
+        let base_len = rela_path.len();
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let name = git_features::path::into_bytes_or_panic_on_windows(Path::new(&file_name));
+            if rela_path.is_empty() && name.as_ref() == b".git" {
+                continue;
+            }
+            rela_path.truncate(base_len);
+            rela_path.push_str(name.as_ref());
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if self.is_tracked(rela_path.as_bstr()) {
+                    // submodules are tracked as directories, and we don't look into them
+                    continue;
+                }
+                rela_path.push(b'/');
+                let is_tracked = self.has_tracked_entries_in(rela_path.as_bstr());
+                rela_path.pop();
+                if self.is_ignored(rela_path.as_bstr(), true)? {
+                    self.stats.ignored += 1;
+                    continue;
+                }
+                let dir = entry.path();
+                if !is_tracked && dir.join(".git").exists() {
+                    self.push_untracked_dir(rela_path);
+                    continue;
+                }
+                if is_tracked || self.mode == Untracked::All {
+                    rela_path.push(b'/');
+                    self.directory(dir, rela_path)?;
+                } else if self.contains_untracked_files(dir, rela_path)? {
+                    self.push_untracked_dir(rela_path);
+                }
+            } else if !self.is_tracked(rela_path.as_bstr()) {
+                if self.is_ignored(rela_path.as_bstr(), false)? {
+                    self.stats.ignored += 1;
+                } else {
+                    self.untracked.push(rela_path.clone());
+                }
+            }
+        }
+        rela_path.truncate(base_len);
+        Ok(())
+    }
+
+    /// Return true if the untracked directory at `path` contains at least one file that isn't ignored.
+    fn contains_untracked_files(&mut self, path: PathBuf, rela_path: &BString) -> Result<bool, Error> {
+        let mut untracked = Vec::new();
+        let mut walk = Walk {
+            index: self.index,
+            mode: Untracked::All,
+            ignore: &mut *self.ignore,
+            stats: &mut *self.stats,
+            untracked: &mut untracked,
+        };
+        let mut rela_path = rela_path.clone();
+        rela_path.push(b'/');
+        walk.directory(path, &mut rela_path)?;
+        Ok(!untracked.is_empty())
+    }
+
+    fn is_ignored(&mut self, rela_path: &BStr, is_dir: bool) -> Result<bool, Error> {
+        Ok(self.ignore.at_path(rela_path, is_dir, stack::no_find)?.is_ignored())
+    }
+
+    fn push_untracked_dir(&mut self, rela_path: &BString) {
+        let mut dir = rela_path.clone();
+        dir.push(b'/');
+        self.untracked.push(dir);
+    }
+
+    fn is_tracked(&self, rela_path: &BStr) -> bool {
+        self.index
+            .entries()
+            .binary_search_by(|e| e.path(self.index).cmp(rela_path))
+            .is_ok()
+    }
+
+    /// `dir` is expected to have a trailing slash.
+    fn has_tracked_entries_in(&self, dir: &BStr) -> bool {
+        let entries = self.index.entries();
+        let pos = entries.partition_point(|e| e.path(self.index) < dir);
+        entries
+            .get(pos)
+            .map_or(false, |e| e.path(self.index).starts_with(dir.as_bytes()))
+    }
+}
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p a b/c/d
+printf '*.txt text\n[attr]custom diff=custom -merge\n*.o custom\n' > .gitattributes
+printf '*.o\n*.log\n' > .gitignore
+printf '*.txt -text\n*.bin binary\n' > a/.gitattributes
+printf '!keep.o\n' > a/.gitignore
+cp a/.gitattributes b/.gitattributes
+printf '*.log -diff\n' > b/c/.gitattributes
+touch a/x b/c/d/x
+
+git add .
+git commit -q -m "init"
+git rev-parse @^{tree} > .git/head-tree-id
@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir dir tracked-ignored
+echo a > a
+echo b > b
+echo c > dir/c
+echo d > dir/d
+echo exe > exe && chmod +x exe
+echo kept > tracked-ignored/kept
+ln -s a link
+echo "*.ignored" > .gitignore
+echo "!keep.ignored" >> .gitignore
+
+git add .
+git commit -q -m "init"
+git rev-parse @^{tree} > .git/head-tree-id
+
+echo "changed" >> a
+rm b
+chmod -x exe
+rm link && echo "no link" > link
+git rm -q --cached dir/d
+echo staged > staged && git add staged
+echo "staged and changed" > dir/c && git add dir/c && echo "changed again" > dir/c
+
+touch x.ignored keep.ignored untracked
+mkdir -p untracked-dir/nested ignored-dir empty-dir
+touch untracked-dir/1 untracked-dir/nested/2 ignored-dir/x.ignored tracked-ignored/y.ignored
+echo "ignored-dir/" > .git/info/exclude
+mkdir nested-repo && (cd nested-repo && git init -q)
@@ -207,6 +207,7 @@ fn overwriting_files_and_lone_directories_works() {
             symlink::symlink_dir(empty, dir.join("symlink"))?; // 'symlink' is a symlink to another file
             Ok(())
         },
+        |_| {},
     )
     .unwrap();
 
@@ -261,15 +262,59 @@ fn allow_or_disallow_symlinks() -> crate::Result {
     let mut opts = opts_from_probe();
     for allowed in &[false, true] {
         opts.fs.symlink = *allowed;
-        let (source_tree, destination, _index, outcome) =
+        let (source_tree, destination, index, outcome) =
             checkout_index_in_tmp_dir(opts, "make_mixed_without_submodules")?;
 
         assert_equality(&source_tree, &destination, opts.fs.symlink)?;
         assert!(outcome.collisions.is_empty());
+        assert!(outcome.errors.is_empty());
+        assert_eq!(outcome.files_updated, index.entries().len());
+        assert_eq!(outcome.files_skipped, 0);
+        assert_eq!(
+            outcome.bytes_written,
+            dir_structure(&source_tree)
+                .iter()
+                .map(|path| fs::symlink_metadata(path).map(|m| m.len()))
+                .sum::<std::io::Result<u64>>()?,
+            "symlinks are written as their destination"
+        );
     }
     Ok(())
 }
 
+#[test]
+fn entries_excluded_from_the_worktree_are_skipped() -> crate::Result {
+    let opts = opts_from_probe();
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir_opts(
+        opts,
+        "make_mixed_without_submodules",
+        |_| true,
+        |_| Ok(()),
+        |index| {
+            for entry in index.entries_mut() {
+                if entry.mode == git_index::entry::Mode::FILE {
+                    entry.flags.insert(git_index::entry::Flags::SKIP_WORKTREE);
+                }
+            }
+        },
+    )?;
+
+    let num_skipped = index
+        .entries()
+        .iter()
+        .filter(|e| e.mode == git_index::entry::Mode::FILE)
+        .count();
+    assert_ne!(num_skipped, 0, "the fixture has regular files");
+    assert_eq!(outcome.files_skipped, num_skipped);
+    assert_eq!(outcome.files_updated, index.entries().len() - num_skipped);
+    assert_eq!(
+        dir_structure(&destination).len(),
+        outcome.files_updated,
+        "only the remaining files are written"
+    );
+    Ok(())
+}
+
 #[test]
 fn keep_going_collects_results() {
     let mut opts = opts_from_probe();
@@ -288,9 +333,12 @@ fn keep_going_collects_results() {
             }
         },
         |_| Ok(()),
+        |_| {},
     )
     .unwrap();
 
+    assert_eq!(outcome.files_updated, 2, "failed files aren't counted as updated");
+
     if multi_threaded() {
         assert_eq!(
             outcome.errors.len(),
@@ -473,7 +521,7 @@ fn checkout_index_in_tmp_dir(
     git_index::File,
     git_worktree::index::checkout::Outcome,
 )> {
-    checkout_index_in_tmp_dir_opts(opts, name, |_d| true, |_| Ok(()))
+    checkout_index_in_tmp_dir_opts(opts, name, |_d| true, |_| Ok(()), |_| {})
 }
 
 fn checkout_index_in_tmp_dir_opts(
@@ -481,6 +529,7 @@ fn checkout_index_in_tmp_dir_opts(
     name: &str,
     mut allow_return_object: impl FnMut(&git_hash::oid) -> bool + Send + Clone,
     prep_dest: impl Fn(&Path) -> std::io::Result<()>,
+    prep_index: impl FnOnce(&mut git_index::File),
 ) -> crate::Result<(
     PathBuf,
     TempDir,
@@ -490,6 +539,7 @@ fn checkout_index_in_tmp_dir_opts(
     let source_tree = fixture_path(name);
     let git_dir = source_tree.join(".git");
     let mut index = git_index::File::at(git_dir.join("index"), Default::default())?;
+    prep_index(&mut index);
     let odb = git_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
     let destination = tempfile::tempdir_in(std::env::current_dir()?)?;
     prep_dest(destination.path())?;
@@ -0,0 +1,128 @@
+use bstr::ByteSlice;
+use git_attributes::State;
+use git_glob::pattern::Case;
+use git_odb::FindExt;
+use git_testtools::hex_to_id;
+use git_worktree::stack::{self, Source, Stack, Statistics};
+
+use crate::fixture_path;
+
+/// A path, whether it is a directory, whether it is expected to be ignored and its expected attributes.
+type Query<'a> = (&'a str, bool, bool, &'a [&'a str]);
+
+fn assert_queries<Find, E>(stack: &mut Stack, queries: &[Query<'_>], mut find: Find)
+where
+    Find: for<'b> FnMut(&git_hash::oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    for (path, is_dir, is_ignored, attributes) in queries {
+        let platform = stack.at_path(path.as_bytes().as_bstr(), *is_dir, &mut find).unwrap();
+        assert_eq!(platform.is_ignored(), *is_ignored, "{}", path);
+        let actual: Vec<_> = platform
+            .attributes()
+            .into_iter()
+            .map(|(name, state)| match state {
+                State::Set => name.to_string(),
+                State::Unset => format!("-{}", name),
+                State::Value(value) => format!("{}={}", name, value),
+                State::Unspecified => unreachable!("unspecified attributes aren't returned"),
+            })
+            .collect();
+        assert_eq!(actual, *attributes, "{}", path);
+    }
+}
+
+/// Like `git check-attr -a` and `git check-ignore`, in an order which revisits directories.
+const QUERIES: &[Query<'static>] = &[
+    ("f.txt", false, false, &["text"]),
+    ("f.o", false, true, &["custom", "diff=custom", "-merge"]),
+    ("a", true, false, &[]),
+    ("a/f.txt", false, false, &["-text"]),
+    ("a/f.bin", false, false, &["binary", "-diff", "-merge", "-text"]),
+    ("a/keep.o", false, false, &["custom", "diff=custom", "-merge"]),
+    ("b/c/d/f.log", false, true, &["-diff"]),
+    ("b/c/f.txt", false, false, &["-text"]),
+    ("b/keep.o", false, true, &["custom", "diff=custom", "-merge"]),
+    ("a/f.o", false, true, &["custom", "diff=custom", "-merge"]),
+];
+
+#[test]
+fn files_in_the_worktree_are_loaded_lazily_as_the_walk_descends() {
+    let worktree = fixture_path("make_attributes_repo");
+    let mut stack = Stack::new(Source::WorkTree(worktree), Case::Sensitive)
+        .with_excludes(b"")
+        .with_attributes(b"")
+        .unwrap();
+
+    assert_queries(&mut stack, &QUERIES[..2], stack::no_find);
+    assert_eq!(
+        stack.statistics,
+        Statistics {
+            directories_pushed: 1,
+            ignore_files_read: 1,
+            attribute_files_read: 1,
+            cache_hits: 0,
+        },
+        "only the files of the root directory are needed so far"
+    );
+
+    assert_queries(&mut stack, &QUERIES[2..], stack::no_find);
+    assert_eq!(
+        stack.statistics,
+        Statistics {
+            directories_pushed: 6,
+            ignore_files_read: 3,
+            attribute_files_read: 5,
+            cache_hits: 0,
+        },
+        "files in the work tree are read each time their directory is entered"
+    );
+}
+
+#[test]
+fn files_in_the_index_are_parsed_only_once_per_blob() {
+    let git_dir = fixture_path("make_attributes_repo").join(".git");
+    let odb = git_odb::at(git_dir.join("objects")).unwrap();
+    let head_tree_id = hex_to_id(
+        std::fs::read(git_dir.join("head-tree-id"))
+            .unwrap()
+            .trim()
+            .to_str()
+            .unwrap(),
+    );
+    let index = git_index::State::from_tree(&head_tree_id, |oid, buf| odb.find_tree_iter(oid, buf).ok()).unwrap();
+    let mut stack = Stack::new(Source::from_index(&index), Case::Sensitive)
+        .with_excludes(b"")
+        .with_attributes(b"")
+        .unwrap();
+
+    assert_queries(&mut stack, QUERIES, |oid, buf| odb.find_blob(oid, buf));
+    assert_eq!(
+        stack.statistics,
+        Statistics {
+            directories_pushed: 6,
+            ignore_files_read: 2,
+            attribute_files_read: 3,
+            cache_hits: 3,
+        },
+        "a/.gitattributes and b/.gitattributes are the same blob, and a is entered twice"
+    );
+}
+
+#[test]
+fn excludes_and_global_attributes_take_the_lowest_precedence() {
+    let worktree = fixture_path("make_attributes_repo");
+    let mut stack = Stack::new(Source::WorkTree(worktree), Case::Fold)
+        .with_excludes(b"*.TXT\n!*.log")
+        .with_attributes(b"*.txt eol=lf -text\n")
+        .unwrap();
+
+    assert_queries(
+        &mut stack,
+        &[
+            ("F.txt", false, true, &["eol=lf", "text"]),
+            ("b/c/d/f.log", false, true, &["-diff"]),
+        ],
+        stack::no_find,
+    );
+}
@@ -0,0 +1,146 @@
+use bstr::{BString, ByteSlice};
+use git_index::entry::Mode;
+use git_odb::FindExt;
+use git_testtools::hex_to_id;
+use git_worktree::status::{self, Change, Staged, Untracked};
+
+use crate::fixture_path;
+
+fn index_worktree(untracked: Untracked) -> (Vec<(BString, Change)>, Vec<BString>) {
+    let worktree = fixture_path("make_status_repo");
+    let git_dir = worktree.join(".git");
+    let index = git_index::File::at(git_dir.join("index"), Default::default()).unwrap();
+    let excludes = std::fs::read(git_dir.join("info").join("exclude")).unwrap();
+    let outcome = status::index_worktree(
+        &index,
+        &worktree,
+        &excludes,
+        status::Options {
+            fs: git_worktree::fs::Capabilities::probe(&git_dir),
+            untracked,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        outcome.statistics.entries + outcome.statistics.entries_skipped,
+        index.entries().len()
+    );
+    (
+        outcome
+            .changes
+            .into_iter()
+            .map(|(idx, change)| (index.entries()[idx].path(&index).to_owned(), change))
+            .collect(),
+        outcome.untracked,
+    )
+}
+
+fn paths(paths: &[&str]) -> Vec<BString> {
+    paths.iter().map(|p| (*p).into()).collect()
+}
+
+#[test]
+fn index_worktree_finds_the_same_changes_and_untracked_files_as_git_status() {
+    let (changes, untracked) = index_worktree(Untracked::Normal);
+    let mut expected = vec![
+        ("a".into(), Change::Modified { mode: Mode::FILE }),
+        ("b".into(), Change::Removed),
+        ("dir/c".into(), Change::Modified { mode: Mode::FILE }),
+    ];
+    if git_worktree::fs::Capabilities::probe(fixture_path("make_status_repo").join(".git")).executable_bit {
+        expected.push(("exe".into(), Change::Modified { mode: Mode::FILE }));
+    }
+    expected.push(("link".into(), Change::TypeChange { mode: Mode::FILE }));
+    assert_eq!(changes, expected);
+    assert_eq!(
+        untracked,
+        paths(&["dir/d", "keep.ignored", "nested-repo/", "untracked", "untracked-dir/"]),
+        "directories without tracked files are collapsed, and ignored files and empty directories are not listed"
+    );
+}
+
+#[test]
+fn index_worktree_can_list_all_untracked_files_or_none() {
+    let (_, untracked) = index_worktree(Untracked::All);
+    assert_eq!(
+        untracked,
+        paths(&[
+            "dir/d",
+            "keep.ignored",
+            "nested-repo/",
+            "untracked",
+            "untracked-dir/1",
+            "untracked-dir/nested/2"
+        ]),
+        "nested repositories are never traversed"
+    );
+
+    let (changes, untracked) = index_worktree(Untracked::No);
+    assert!(untracked.is_empty());
+    assert_eq!(changes.len(), index_worktree(Untracked::All).0.len());
+}
+
+#[test]
+fn index_worktree_matches_ignore_patterns_case_insensitively_if_the_file_system_ignores_case() {
+    let worktree = fixture_path("make_status_repo");
+    let git_dir = worktree.join(".git");
+    let index = git_index::File::at(git_dir.join("index"), Default::default()).unwrap();
+    for ignore_case in [false, true] {
+        let outcome = status::index_worktree(
+            &index,
+            &worktree,
+            b"ignored-dir/\nUNTRACKED\n",
+            status::Options {
+                fs: git_worktree::fs::Capabilities {
+                    ignore_case,
+                    ..git_worktree::fs::Capabilities::probe(&git_dir)
+                },
+                untracked: Untracked::Normal,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            outcome.untracked.contains(&"untracked".into()),
+            !ignore_case,
+            "the pattern only matches if case is ignored"
+        );
+    }
+}
+
+#[test]
+fn tree_index_finds_the_same_staged_changes_as_git_status() {
+    let git_dir = fixture_path("make_status_repo").join(".git");
+    let index = git_index::File::at(git_dir.join("index"), Default::default()).unwrap();
+    let odb = git_odb::at(git_dir.join("objects")).unwrap();
+    let head_tree_id = hex_to_id(
+        std::fs::read(git_dir.join("head-tree-id"))
+            .unwrap()
+            .trim()
+            .to_str()
+            .unwrap(),
+    );
+    let head = git_index::State::from_tree(&head_tree_id, |oid, buf| odb.find_tree_iter(oid, buf).ok()).unwrap();
+
+    assert_eq!(
+        status::tree_index(&head, &index),
+        vec![
+            Staged::Modified {
+                path: "dir/c".into(),
+                previous_mode: Mode::FILE,
+                previous_id: hex_to_id("f2ad6c76f0115a6ba5b00456a849810e7ec0af20"),
+                mode: Mode::FILE,
+                id: hex_to_id("d676b6ed2a39f5794ff054abc16eabeabc39b537"),
+            },
+            Staged::Deleted {
+                path: "dir/d".into(),
+                mode: Mode::FILE,
+                id: hex_to_id("4bcfe98e640c8284511312660fb8709b0afa888e"),
+            },
+            Staged::Added {
+                path: "staged".into(),
+                mode: Mode::FILE,
+                id: hex_to_id("19d9cc8584ac2c7dcf57d2680375e80f099dc481"),
+            },
+        ]
+    );
+}
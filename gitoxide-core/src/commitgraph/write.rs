@@ -0,0 +1,283 @@
+use std::{
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{bail, Context as AnyhowContext};
+use git_commitgraph::{
+    file::{self, write},
+    Graph,
+};
+use git_repository as git;
+use git_repository::{
+    objs::{bstr::BString, TreeRefIter},
+    odb::FindExt,
+    traverse::commit::{ancestors, Ancestors},
+    Progress,
+};
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, start from all references in addition to the given revisions.
+    pub reachable: bool,
+    /// If true, compute changed-path Bloom filters for all written commits.
+    pub changed_paths: bool,
+    /// If true, only write commits not yet in the graph into a new file appended to the chain at `objects/info/commit-graphs`,
+    /// instead of rewriting everything into `objects/info/commit-graph`.
+    pub split: bool,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Statistics {
+    /// The file written, or `None` if there were no new commits to write.
+    pub path: Option<PathBuf>,
+    pub num_commits: u32,
+    pub num_bloom_filters: u32,
+    /// The amount of files in the chain the written file is a part of, which is 1 if it's not part of a chain.
+    pub num_files_in_chain: usize,
+}
+
+/// Write a commit-graph for the repository at `repository` which contains all commits reachable from `revisions`.
+///
+/// Each revision is a commit hash or reference name. Excluding commits with `^` or ranges like `from..to` is an error,
+/// as the commit-graph must contain all ancestors of its commits. If no commit to start from is given and `reachable`
+/// isn't set, `HEAD` is used.
+pub fn write(
+    repository: PathBuf,
+    revisions: Vec<String>,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    out: impl io::Write,
+    Options {
+        format,
+        reachable,
+        changed_paths,
+        split,
+    }: Options,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?.apply_environment();
+    let info_dir = repo.git_dir().join("objects").join("info");
+    let chain_dir = info_dir.join("commit-graphs");
+    let (mut tips, hidden) = crate::pack::create::parse_tips(&repo, &revisions)?;
+    if !hidden.is_empty() {
+        bail!("A commit-graph must contain all ancestors of its commits, hence revisions can't be excluded with '^' or ranges")
+    }
+    if reachable {
+        let mut buf = Vec::new();
+        for reference in repo.references()?.all()?.peeled() {
+            let id = reference.map_err(|err| anyhow::anyhow!(err))?.id().detach();
+            if repo.objects.find(id, &mut buf)?.kind == git::objs::Kind::Commit {
+                tips.push(id);
+            }
+        }
+    } else if tips.is_empty() {
+        tips = crate::pack::create::parse_tips(&repo, Some("HEAD"))?.0;
+    }
+
+    let base = if split && chain_dir.join("commit-graph-chain").is_file() {
+        Some(Graph::from_commit_graphs_dir(&chain_dir).context("Could not open existing commit-graph chain")?)
+    } else {
+        None
+    };
+
+    let commits = {
+        let mut commits = Vec::new();
+        let mut progress = progress.add_child("collecting commits");
+        progress.init(None, git::progress::count("commits"));
+        let mut buf = Vec::new();
+        let mut diff_state = git::diff::tree::State::default();
+        for id in Ancestors::new(tips, ancestors::State::default(), |oid, buf| {
+            repo.objects.find_commit_iter(oid, buf)
+        }) {
+            let id = id?;
+            if base.as_ref().map_or(false, |base| base.lookup(id).is_some()) {
+                continue;
+            }
+            let commit = repo.objects.find_commit(id, &mut buf)?;
+            let parent_ids: Vec<_> = commit.parents().collect();
+            commits.push(write::Commit {
+                id,
+                root_tree_id: commit.tree(),
+                committer_timestamp: commit.committer().time.seconds_since_unix_epoch.into(),
+                changed_paths: changed_paths
+                    .then(|| changed_paths_of(&repo, commit.tree(), parent_ids.first().copied(), &mut diff_state))
+                    .transpose()?,
+                parent_ids,
+            });
+            progress.inc();
+            if should_interrupt.load(Ordering::Relaxed) {
+                bail!("Interrupted");
+            }
+        }
+        commits
+    };
+
+    let options = write::Options {
+        object_hash: repo.object_hash(),
+        bloom_filter_settings: changed_paths.then(Default::default),
+    };
+    progress.set_name("writing commit-graph");
+    let stats = if split && commits.is_empty() {
+        Statistics {
+            path: None,
+            num_commits: 0,
+            num_bloom_filters: 0,
+            num_files_in_chain: base.map_or(0, |base| base.files().len()),
+        }
+    } else if split {
+        let (path, outcome) = write_chain_file(&chain_dir, commits, base.as_ref(), options)?;
+        let mut chain: Vec<_> = base
+            .iter()
+            .flat_map(|base| base.files())
+            .map(|f| f.checksum().to_owned())
+            .collect();
+        chain.push(outcome.checksum);
+        write_chain(&chain_dir, &chain)?;
+        remove_if_present(&info_dir.join("commit-graph"))?;
+        Statistics {
+            path: Some(path),
+            num_commits: outcome.num_commits,
+            num_bloom_filters: outcome.num_bloom_filters,
+            num_files_in_chain: chain.len(),
+        }
+    } else {
+        let path = info_dir.join("commit-graph");
+        std::fs::create_dir_all(&info_dir)?;
+        let mut out = BufWriter::new(git::lock::File::acquire_to_update_resource(
+            &path,
+            git::lock::acquire::Fail::Immediately,
+            None,
+        )?);
+        let outcome = file::File::write_to(commits, None, &mut out, options)?;
+        out.into_inner()?.commit()?;
+        remove_chain(&chain_dir)?;
+        Statistics {
+            path: Some(path),
+            num_commits: outcome.num_commits,
+            num_bloom_filters: outcome.num_bloom_filters,
+            num_files_in_chain: 1,
+        }
+    };
+    progress.done(format!("Wrote {} commits", stats.num_commits));
+
+    match format {
+        OutputFormat::Human => print_human_output(out, &stats)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "commit-graph-write", &stats)?,
+    }
+    Ok(())
+}
+
+/// Return all paths changed in the tree `tree_id` compared to the tree of `first_parent`, or all paths if there is no parent.
+fn changed_paths_of(
+    repo: &git::Repository,
+    tree_id: git::ObjectId,
+    first_parent: Option<git::ObjectId>,
+    state: &mut git::diff::tree::State,
+) -> anyhow::Result<Vec<BString>> {
+    use git::diff::tree::recorder::Change::*;
+
+    let mut buf = Vec::new();
+    let mut parent_buf = Vec::new();
+    let parent_tree = match first_parent {
+        Some(parent) => {
+            let parent_tree_id = repo.objects.find_commit_iter(parent, &mut parent_buf)?.tree_id()?;
+            repo.objects.find_tree_iter(parent_tree_id, &mut parent_buf)?
+        }
+        None => TreeRefIter::from_bytes(&[]),
+    };
+    let tree = repo.objects.find_tree_iter(tree_id, &mut buf)?;
+    let mut recorder = git::diff::tree::Recorder::default();
+    git::diff::tree::Changes::from(parent_tree).needed_to_obtain_with_options(
+        tree,
+        state,
+        |oid, buf| repo.objects.find_tree_iter(oid, buf).ok(),
+        &mut recorder,
+        git::diff::tree::changes::Options {
+            pathspecs: &[],
+            emit_trees: false,
+        },
+    )?;
+    Ok(recorder
+        .records
+        .into_iter()
+        .map(|change| match change {
+            Addition { path, .. } | Deletion { path, .. } | Modification { path, .. } => path,
+        })
+        .collect())
+}
+
+/// Write a new file of a commit-graph chain into `chain_dir`, named after its checksum.
+fn write_chain_file(
+    chain_dir: &Path,
+    commits: Vec<write::Commit>,
+    base: Option<&Graph>,
+    options: write::Options,
+) -> anyhow::Result<(PathBuf, write::Outcome)> {
+    std::fs::create_dir_all(chain_dir)?;
+    let mut out = BufWriter::new(tempfile::NamedTempFile::new_in(chain_dir)?);
+    let outcome = file::File::write_to(commits, base, &mut out, options)?;
+    let path = chain_dir.join(format!("graph-{}.graph", outcome.checksum));
+    out.into_inner()?.persist(&path)?;
+    Ok((path, outcome))
+}
+
+/// Write the list of files making up a chain of commit-graph files, with the base of all other files first.
+fn write_chain(chain_dir: &Path, checksums: &[git::ObjectId]) -> anyhow::Result<()> {
+    let mut out = BufWriter::new(git::lock::File::acquire_to_update_resource(
+        chain_dir.join("commit-graph-chain"),
+        git::lock::acquire::Fail::Immediately,
+        None,
+    )?);
+    for checksum in checksums {
+        writeln!(out, "{}", checksum)?;
+    }
+    out.into_inner()?.commit()?;
+    Ok(())
+}
+
+/// Remove the chain of commit-graph files in `chain_dir` as it was superseded by a single file.
+fn remove_chain(chain_dir: &Path) -> anyhow::Result<()> {
+    let graph_files: Vec<_> = Graph::from_commit_graphs_dir(chain_dir)
+        .map(|graph| graph.files().iter().map(|f| f.path().to_owned()).collect())
+        .unwrap_or_default();
+    for path in graph_files {
+        remove_if_present(&path)?;
+    }
+    remove_if_present(&chain_dir.join("commit-graph-chain"))
+}
+
+fn remove_if_present(path: &Path) -> anyhow::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            Err(err).with_context(|| format!("Could not remove superseded commit-graph file at '{}'", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn print_human_output(mut out: impl io::Write, stats: &Statistics) -> io::Result<()> {
+    match &stats.path {
+        Some(path) => writeln!(
+            out,
+            "Wrote {} commits ({} with changed-path Bloom filters) to '{}'",
+            stats.num_commits,
+            stats.num_bloom_filters,
+            path.display()
+        )?,
+        None => writeln!(out, "No new commits to write")?,
+    }
+    if stats.num_files_in_chain > 1 {
+        writeln!(
+            out,
+            "The commit-graph chain now consists of {} files",
+            stats.num_files_in_chain
+        )?;
+    }
+    Ok(())
+}
@@ -1,19 +1,32 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    borrow::Cow,
+    collections::{hash_map::Entry, BTreeMap, HashMap},
     ffi::OsStr,
     fmt,
     fmt::{Display, Formatter},
     io,
-    path::Path,
+    path::{Path, PathBuf},
+    str::FromStr,
     time::Instant,
 };
 
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, Context as AnyhowContext};
 use git_repository as git;
-use git_repository::{actor, bstr::BString, interrupt, objs, prelude::*, progress, refs::file::ReferenceExt, Progress};
+use git_repository::{
+    actor,
+    bstr::{BString, ByteSlice},
+    interrupt, objs,
+    prelude::*,
+    progress,
+    refs::file::ReferenceExt,
+    Progress,
+};
 use itertools::Itertools;
 use rayon::prelude::*;
 
+/// The longest pause between two commits of an author in minutes which is still considered working on them, by default.
+pub const DEFAULT_INACTIVITY_THRESHOLD_IN_MINUTES: u32 = 120;
+
 /// Additional configuration for the hours estimation functionality.
 pub struct Context<W> {
     /// Show personally identifiable information before the summary. Includes names and email addresses.
@@ -21,10 +34,127 @@ pub struct Context<W> {
     /// Omit unifying identities by name and email which can lead to the same author appear multiple times
     /// due to using different names or email addresses.
     pub omit_unify_identities: bool,
+    /// Don't use the mailmap of the repository to map names and email addresses of authors to their canonical identity.
+    pub ignore_mailmap: bool,
+    /// A mailmap file whose entries are used in addition to the ones of the repository, taking precedence over them.
+    pub mailmap_file: Option<PathBuf>,
+    /// If set, estimate the hours for each time bucket of this size separately instead of for the entire history.
+    pub bucket: Option<Bucket>,
+    /// The longest pause between two commits of an author in minutes which is still considered working on them.
+    ///
+    /// Longer pauses are considered the start of a new session, to which the same amount of time is added as for the
+    /// first commit of an author.
+    pub inactivity_threshold_in_minutes: u32,
+    /// The way to present the results.
+    pub format: Format,
     /// Where to write our output to
     pub out: W,
 }
 
+/// The way to present the estimated hours.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Format {
+    /// A summary for humans, preceded by the work of each author if personally identifiable information is shown.
+    Human,
+    /// A JSON document following the `estimate-hours` schema.
+    #[cfg(feature = "serde1")]
+    Json,
+    /// Comma separated values with a header line, with a line for each time bucket, or for each author of a time
+    /// bucket if personally identifiable information is shown.
+    Csv,
+}
+
+impl Format {
+    pub fn variants() -> &'static [&'static str] {
+        &[
+            "human",
+            #[cfg(feature = "serde1")]
+            "json",
+            "csv",
+        ]
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "human" => Format::Human,
+            #[cfg(feature = "serde1")]
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            _ => return Err(format!("Invalid output format: '{}'", s)),
+        })
+    }
+}
+
+/// A span of time to aggregate commits by, in the timezone of their author.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Bucket {
+    /// Named like `2022-04-07`.
+    Day,
+    /// Weeks start on monday and are named after it, like `2022-04-04`.
+    Week,
+    /// Named like `2022-04`.
+    Month,
+    /// Named like `2022`.
+    Year,
+}
+
+impl Bucket {
+    pub fn variants() -> &'static [&'static str] {
+        &["day", "week", "month", "year"]
+    }
+
+    /// Return the name of the bucket containing `time`.
+    fn name(&self, time: &actor::Time) -> String {
+        let mut date = match self {
+            Bucket::Week => {
+                const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+                let days =
+                    (time.seconds_since_unix_epoch as i64 + time.offset_in_seconds as i64).div_euclid(SECONDS_PER_DAY);
+                // The unix epoch was a thursday.
+                let monday = (days - (days + 3).rem_euclid(7)) * SECONDS_PER_DAY;
+                // Mondays before the unix epoch can't be represented as time stamp, but as offset from it.
+                let seconds_since_unix_epoch = monday.max(0);
+                let offset_in_seconds = (monday - seconds_since_unix_epoch) as i32;
+                let monday = actor::Time {
+                    seconds_since_unix_epoch: seconds_since_unix_epoch as u32,
+                    offset_in_seconds,
+                    sign: if offset_in_seconds < 0 {
+                        actor::Sign::Minus
+                    } else {
+                        actor::Sign::Plus
+                    },
+                };
+                git_date::format(&monday, git_date::Format::Short)
+            }
+            Bucket::Day | Bucket::Month | Bucket::Year => git_date::format(time, git_date::Format::Short),
+        };
+        date.truncate(match self {
+            Bucket::Day | Bucket::Week => "YYYY-MM-DD".len(),
+            Bucket::Month => "YYYY-MM".len(),
+            Bucket::Year => "YYYY".len(),
+        });
+        date
+    }
+}
+
+impl FromStr for Bucket {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "day" => Bucket::Day,
+            "week" => Bucket::Week,
+            "month" => Bucket::Month,
+            "year" => Bucket::Year,
+            _ => return Err(format!("Invalid time bucket: '{}'", s)),
+        })
+    }
+}
+
 /// Estimate the hours it takes to produce the content of the repository in `_working_dir_`, with `_refname_` for
 /// the start of the commit graph traversal.
 ///
@@ -38,6 +168,11 @@ pub fn estimate<W, P>(
     Context {
         show_pii,
         omit_unify_identities,
+        ignore_mailmap,
+        mailmap_file,
+        bucket,
+        inactivity_threshold_in_minutes,
+        format,
         mut out,
     }: Context<W>,
 ) -> anyhow::Result<()>
@@ -61,17 +196,20 @@ where
         let mut progress = progress.add_child("Traverse commit graph");
         progress.init(None, progress::count("commits"));
         let mut commits: Vec<Vec<u8>> = Vec::new();
+        let shallow_commits = repo.shallow_commits()?.unwrap_or_default();
+        let mut is_shallow = !shallow_commits.is_empty();
         let commit_iter = interrupt::Iter::new(
-            commit_id.ancestors(|oid, buf| {
-                progress.inc();
-                repo.objects.find(oid, buf).map(|o| {
-                    commits.push(o.data.to_owned());
-                    objs::CommitRefIter::from_bytes(o.data)
+            commit_id
+                .ancestors(|oid, buf| {
+                    progress.inc();
+                    repo.objects.find(oid, buf).map(|o| {
+                        commits.push(o.data.to_owned());
+                        objs::CommitRefIter::from_bytes(o.data)
+                    })
                 })
-            }),
+                .with_shallow(shallow_commits),
             || anyhow!("Cancelled by user"),
         );
-        let mut is_shallow = false;
         for c in commit_iter {
             match c? {
                 Ok(c) => c,
@@ -86,74 +224,145 @@ where
         (commits, is_shallow)
     };
 
-    let mailmap = repo.load_mailmap();
+    let mailmap = if ignore_mailmap {
+        git::mailmap::Snapshot::default()
+    } else {
+        let mut mailmap = repo.load_mailmap();
+        if let Some(path) = mailmap_file {
+            let buf =
+                std::fs::read(&path).with_context(|| format!("Failed to read mailmap file at '{}'", path.display()))?;
+            mailmap.merge(git::mailmap::parse_ignore_errors(&buf));
+        }
+        mailmap
+    };
     let start = Instant::now();
-    #[allow(clippy::redundant_closure)]
-    let mut all_commits: Vec<actor::Signature> = all_commits
-        .into_par_iter()
-        .filter_map(|commit_data: Vec<u8>| {
-            objs::CommitRefIter::from_bytes(&commit_data)
-                .author()
-                .map(|author| mailmap.resolve(author.trim()))
-                .ok()
-        })
-        .collect::<Vec<_>>();
-    all_commits.sort_by(|a, b| {
-        a.email.cmp(&b.email).then(
-            a.time
-                .seconds_since_unix_epoch
-                .cmp(&b.time.seconds_since_unix_epoch)
-                .reverse(),
-        )
-    });
+    let mut all_commits = authors(all_commits, &mailmap);
     if all_commits.is_empty() {
         bail!("No commits to process");
     }
-    let mut current_email = &all_commits[0].email;
-    let mut slice_start = 0;
-    let mut results_by_hours = Vec::new();
-    for (idx, elm) in all_commits.iter().enumerate() {
-        if elm.email != *current_email {
-            results_by_hours.push(estimate_hours(&all_commits[slice_start..idx]));
-            slice_start = idx;
-            current_email = &elm.email;
-        }
-    }
-    if let Some(commits) = all_commits.get(slice_start..) {
-        results_by_hours.push(estimate_hours(commits));
-    }
+    let num_commits = all_commits.len();
+    let inactivity_threshold_in_minutes = inactivity_threshold_in_minutes as f32;
 
-    let num_authors = results_by_hours.len();
-    let mut results_by_hours = if !omit_unify_identities {
-        deduplicate_identities(&results_by_hours)
-    } else {
-        results_by_hours
-            .iter()
-            .fold(Vec::with_capacity(results_by_hours.len()), |mut acc, e| {
-                acc.push(e.into());
-                acc
-            })
+    let work_by_email = estimate_hours_by_email(&mut all_commits, inactivity_threshold_in_minutes);
+    let num_authors = work_by_email.len();
+    let num_unique_authors = (!omit_unify_identities).then(|| deduplicate_identities(&work_by_email).len());
+    let work_by_email_and_bucket = match bucket {
+        None => vec![(None, work_by_email)],
+        Some(bucket) => {
+            let mut commits_by_bucket = BTreeMap::<_, Vec<_>>::new();
+            for commit in all_commits {
+                commits_by_bucket
+                    .entry(bucket.name(&commit.time))
+                    .or_default()
+                    .push(commit);
+            }
+            commits_by_bucket
+                .into_iter()
+                .map(|(name, mut commits)| {
+                    (
+                        Some(name),
+                        estimate_hours_by_email(&mut commits, inactivity_threshold_in_minutes),
+                    )
+                })
+                .collect()
+        }
     };
+    let work_by_bucket: Vec<_> = work_by_email_and_bucket
+        .iter()
+        .map(|(bucket, work_by_email)| {
+            let mut work_by_person = if !omit_unify_identities {
+                deduplicate_identities(work_by_email)
+            } else {
+                work_by_email.iter().map(WorkByPerson::from).collect()
+            };
+            work_by_person.sort_by(|a, b| a.hours.partial_cmp(&b.hours).unwrap_or(std::cmp::Ordering::Equal));
+            (bucket.as_deref(), work_by_person)
+        })
+        .collect();
     let elapsed = start.elapsed();
     progress.done(format!(
         "Extracted and organized data from {} commits in {:?} ({:0.0} commits/s)",
-        all_commits.len(),
+        num_commits,
         elapsed,
-        all_commits.len() as f32 / elapsed.as_secs_f32()
+        num_commits as f32 / elapsed.as_secs_f32()
     ));
 
-    let num_unique_authors = results_by_hours.len();
-    if show_pii {
-        results_by_hours.sort_by(|a, b| a.hours.partial_cmp(&b.hours).unwrap_or(std::cmp::Ordering::Equal));
-        for entry in results_by_hours.iter() {
-            writeln!(out, "{}\n", entry)?;
-        }
-    }
-    let (total_hours, total_commits) = results_by_hours
+    let (total_hours, total_commits) = work_by_bucket
         .iter()
-        .map(|e| (e.hours, e.num_commits))
+        .flat_map(|(_bucket, work_by_person)| work_by_person.iter().map(|w| (w.hours, w.num_commits)))
         .reduce(|a, b| (a.0 + b.0, a.1 + b.1))
         .expect("at least one commit at this point");
+    assert_eq!(total_commits, num_commits as u32, "need to get all commits");
+    let summary = Summary {
+        total_hours,
+        total_commits,
+        is_shallow,
+        num_authors,
+        num_unique_authors,
+    };
+    match format {
+        Format::Human => write_human(&mut out, &work_by_bucket, &summary, show_pii)?,
+        #[cfg(feature = "serde1")]
+        Format::Json => write_json(&mut out, &work_by_bucket, &summary, show_pii)?,
+        Format::Csv => write_csv(&mut out, &work_by_bucket, show_pii)?,
+    }
+    Ok(())
+}
+
+/// Return the authors of all `commits`, resolved to their canonical identity using `mailmap`.
+fn authors(commits: Vec<Vec<u8>>, mailmap: &git::mailmap::Snapshot) -> Vec<actor::Signature> {
+    #[allow(clippy::redundant_closure)]
+    commits
+        .into_par_iter()
+        .filter_map(|commit_data: Vec<u8>| {
+            objs::CommitRefIter::from_bytes(&commit_data)
+                .author()
+                .map(|author| mailmap.resolve(author.trim()))
+                .ok()
+        })
+        .collect()
+}
+
+/// Totals across all time buckets and authors.
+struct Summary {
+    total_hours: f32,
+    total_commits: u32,
+    is_shallow: bool,
+    num_authors: usize,
+    /// The amount of authors after unifying identities, if that wasn't omitted.
+    num_unique_authors: Option<usize>,
+}
+
+fn write_human(
+    mut out: impl io::Write,
+    work_by_bucket: &[(Option<&str>, Vec<WorkByPerson<'_>>)],
+    Summary {
+        total_hours,
+        total_commits,
+        is_shallow,
+        num_authors,
+        num_unique_authors,
+    }: &Summary,
+    show_pii: bool,
+) -> io::Result<()> {
+    for (bucket, work_by_person) in work_by_bucket {
+        if let Some(bucket) = bucket {
+            let (hours, num_commits) = totals(work_by_person);
+            writeln!(
+                out,
+                "{}: {:.02}h in {} commits by {} authors",
+                bucket,
+                hours,
+                num_commits,
+                work_by_person.len()
+            )?;
+        }
+        if show_pii {
+            for entry in work_by_person {
+                writeln!(out, "{}\n", entry)?;
+            }
+        }
+    }
     writeln!(
         out,
         "total hours: {:.02}\ntotal 8h days: {:.02}\ntotal commits = {}{}\ntotal authors: {}",
@@ -163,24 +372,177 @@ where
         is_shallow.then(|| " (shallow)").unwrap_or_default(),
         num_authors
     )?;
-    if !omit_unify_identities {
+    if let Some(num_unique_authors) = num_unique_authors {
         writeln!(
             out,
             "total unique authors: {} ({:.02}% duplication)",
             num_unique_authors,
-            (1.0 - (num_unique_authors as f32 / num_authors as f32)) * 100.0
+            (1.0 - (*num_unique_authors as f32 / *num_authors as f32)) * 100.0
         )?;
     }
-    assert_eq!(total_commits, all_commits.len() as u32, "need to get all commits");
     Ok(())
 }
 
+#[cfg(feature = "serde1")]
+fn write_json(
+    out: impl io::Write,
+    work_by_bucket: &[(Option<&str>, Vec<WorkByPerson<'_>>)],
+    summary: &Summary,
+    show_pii: bool,
+) -> anyhow::Result<()> {
+    #[derive(serde::Serialize)]
+    struct Bucket<'a> {
+        name: Option<&'a str>,
+        hours: f32,
+        num_commits: u32,
+        num_authors: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        authors: Option<Vec<Author>>,
+    }
+    #[derive(serde::Serialize)]
+    struct Author {
+        names: Vec<String>,
+        emails: Vec<String>,
+        hours: f32,
+        num_commits: u32,
+    }
+    #[derive(serde::Serialize)]
+    struct Estimate<'a> {
+        total_hours: f32,
+        total_commits: u32,
+        is_shallow: bool,
+        num_authors: usize,
+        num_unique_authors: Option<usize>,
+        buckets: Vec<Bucket<'a>>,
+    }
+
+    let strings = |values: &[&BString]| values.iter().map(|v| v.to_str_lossy().into_owned()).collect();
+    let buckets = work_by_bucket
+        .iter()
+        .map(|(name, work_by_person)| {
+            let (hours, num_commits) = totals(work_by_person);
+            Bucket {
+                name: *name,
+                hours,
+                num_commits,
+                num_authors: work_by_person.len(),
+                authors: show_pii.then(|| {
+                    work_by_person
+                        .iter()
+                        .map(|w| Author {
+                            names: strings(&w.name),
+                            emails: strings(&w.email),
+                            hours: w.hours,
+                            num_commits: w.num_commits,
+                        })
+                        .collect()
+                }),
+            }
+        })
+        .collect();
+    crate::json::write(
+        out,
+        "estimate-hours",
+        &Estimate {
+            total_hours: summary.total_hours,
+            total_commits: summary.total_commits,
+            is_shallow: summary.is_shallow,
+            num_authors: summary.num_authors,
+            num_unique_authors: summary.num_unique_authors,
+            buckets,
+        },
+    )?;
+    Ok(())
+}
+
+fn write_csv(
+    mut out: impl io::Write,
+    work_by_bucket: &[(Option<&str>, Vec<WorkByPerson<'_>>)],
+    show_pii: bool,
+) -> io::Result<()> {
+    if show_pii {
+        writeln!(out, "bucket,names,emails,hours,commits")?;
+        for (bucket, work_by_person) in work_by_bucket {
+            for w in work_by_person {
+                writeln!(
+                    out,
+                    "{},{},{},{:.02},{}",
+                    csv_field(bucket.unwrap_or_default()),
+                    csv_field(&w.name.iter().join(", ")),
+                    csv_field(&w.email.iter().join(", ")),
+                    w.hours,
+                    w.num_commits
+                )?;
+            }
+        }
+    } else {
+        writeln!(out, "bucket,hours,commits,authors")?;
+        for (bucket, work_by_person) in work_by_bucket {
+            let (hours, num_commits) = totals(work_by_person);
+            writeln!(
+                out,
+                "{},{:.02},{},{}",
+                csv_field(bucket.unwrap_or_default()),
+                hours,
+                num_commits,
+                work_by_person.len()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Quote `value` if it contains characters with special meaning in CSV.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Return the hours and the amount of commits of all `work_by_person`.
+fn totals(work_by_person: &[WorkByPerson<'_>]) -> (f32, u32) {
+    work_by_person.iter().fold((0.0, 0), |(hours, num_commits), w| {
+        (hours + w.hours, num_commits + w.num_commits)
+    })
+}
+
 const MINUTES_PER_HOUR: f32 = 60.0;
 const HOURS_PER_WORKDAY: f32 = 8.0;
 
-fn estimate_hours(commits: &[actor::Signature]) -> WorkByEmail {
+/// Sort `commits` by author and estimate the hours of each of them, considering pauses longer than
+/// `inactivity_threshold_in_minutes` the start of a new session.
+fn estimate_hours_by_email(commits: &mut [actor::Signature], inactivity_threshold_in_minutes: f32) -> Vec<WorkByEmail> {
+    commits.sort_by(|a, b| {
+        a.email.cmp(&b.email).then(
+            a.time
+                .seconds_since_unix_epoch
+                .cmp(&b.time.seconds_since_unix_epoch)
+                .reverse(),
+        )
+    });
+    let mut current_email = &commits[0].email;
+    let mut slice_start = 0;
+    let mut results_by_hours = Vec::new();
+    for (idx, elm) in commits.iter().enumerate() {
+        if elm.email != *current_email {
+            results_by_hours.push(estimate_hours(
+                &commits[slice_start..idx],
+                inactivity_threshold_in_minutes,
+            ));
+            slice_start = idx;
+            current_email = &elm.email;
+        }
+    }
+    if let Some(commits) = commits.get(slice_start..) {
+        results_by_hours.push(estimate_hours(commits, inactivity_threshold_in_minutes));
+    }
+    results_by_hours
+}
+
+fn estimate_hours(commits: &[actor::Signature], inactivity_threshold_in_minutes: f32) -> WorkByEmail {
     assert!(!commits.is_empty());
-    const MAX_COMMIT_DIFFERENCE_IN_MINUTES: f32 = 2.0 * MINUTES_PER_HOUR;
     const FIRST_COMMIT_ADDITION_IN_MINUTES: f32 = 2.0 * MINUTES_PER_HOUR;
 
     let hours = FIRST_COMMIT_ADDITION_IN_MINUTES / 60.0
@@ -189,7 +551,7 @@ fn estimate_hours(commits: &[actor::Signature]) -> WorkByEmail {
             |hours, (cur, next): (&actor::Signature, &actor::Signature)| {
                 let change_in_minutes =
                     (next.time.seconds_since_unix_epoch - cur.time.seconds_since_unix_epoch) as f32 / MINUTES_PER_HOUR;
-                if change_in_minutes < MAX_COMMIT_DIFFERENCE_IN_MINUTES {
+                if change_in_minutes < inactivity_threshold_in_minutes {
                     hours + change_in_minutes as f32 / MINUTES_PER_HOUR
                 } else {
                     hours + (FIRST_COMMIT_ADDITION_IN_MINUTES / MINUTES_PER_HOUR)
@@ -284,3 +646,280 @@ struct WorkByEmail {
     hours: f32,
     num_commits: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use git_repository::{actor, bstr::ByteSlice};
+
+    use super::*;
+
+    fn time(seconds_since_unix_epoch: u32, offset_in_seconds: i32) -> actor::Time {
+        actor::Time {
+            seconds_since_unix_epoch,
+            offset_in_seconds,
+            sign: if offset_in_seconds < 0 {
+                actor::Sign::Minus
+            } else {
+                actor::Sign::Plus
+            },
+        }
+    }
+
+    fn signature(name: &str, email: &str, seconds_since_unix_epoch: u32) -> actor::Signature {
+        actor::Signature {
+            name: name.into(),
+            email: email.into(),
+            time: time(seconds_since_unix_epoch, 0),
+        }
+    }
+
+    fn work(name: &str, email: &str, hours: f32, num_commits: u32) -> WorkByEmail {
+        WorkByEmail {
+            name: name.into(),
+            email: email.into(),
+            hours,
+            num_commits,
+        }
+    }
+
+    /// 2022-04-07, a thursday, at midnight UTC.
+    const THURSDAY: u32 = 1649289600;
+    const HOUR: u32 = 60 * 60;
+    const DAY: u32 = 24 * HOUR;
+
+    mod bucket {
+        use super::{time, Bucket, DAY, HOUR, THURSDAY};
+
+        #[test]
+        fn week_is_named_after_its_monday() {
+            assert_eq!(Bucket::Week.name(&time(THURSDAY, 0)), "2022-04-04");
+            assert_eq!(
+                Bucket::Week.name(&time(THURSDAY - 3 * DAY, 0)),
+                "2022-04-04",
+                "mondays are their own week"
+            );
+            assert_eq!(
+                Bucket::Week.name(&time(THURSDAY + 3 * DAY + 23 * HOUR, 0)),
+                "2022-04-04",
+                "sundays belong to the previous monday"
+            );
+        }
+
+        #[test]
+        fn week_is_determined_in_the_timezone_of_the_author() {
+            let late_sunday_in_utc = time(THURSDAY + 3 * DAY + 23 * HOUR, 2 * HOUR as i32);
+            assert_eq!(Bucket::Week.name(&late_sunday_in_utc), "2022-04-11");
+            assert_eq!(Bucket::Day.name(&late_sunday_in_utc), "2022-04-11");
+        }
+
+        #[test]
+        fn week_may_start_before_the_unix_epoch() {
+            assert_eq!(
+                Bucket::Week.name(&time(3 * DAY, 0)),
+                "1969-12-29",
+                "1970-01-04 was a sunday"
+            );
+            assert_eq!(
+                Bucket::Week.name(&time(0, -(HOUR as i32))),
+                "1969-12-29",
+                "the epoch is on 1969-12-31 west of UTC"
+            );
+        }
+
+        #[test]
+        fn day_month_and_year() {
+            let t = time(THURSDAY, 0);
+            assert_eq!(Bucket::Day.name(&t), "2022-04-07");
+            assert_eq!(Bucket::Month.name(&t), "2022-04");
+            assert_eq!(Bucket::Year.name(&t), "2022");
+        }
+    }
+
+    #[test]
+    fn pauses_longer_than_the_inactivity_threshold_start_a_new_session() {
+        let mut commits = vec![
+            signature("a", "a@example.com", THURSDAY),
+            signature("a", "a@example.com", THURSDAY + HOUR / 2),
+            signature("a", "a@example.com", THURSDAY + 3 * HOUR),
+        ];
+        let work = estimate_hours_by_email(&mut commits, 120.0);
+        assert_eq!(work.len(), 1);
+        assert_eq!(work[0].num_commits, 3);
+        assert_eq!(
+            work[0].hours, 4.5,
+            "2h for the first commit and the 2.5h pause, and half an hour in between"
+        );
+
+        let work = estimate_hours_by_email(&mut commits, 180.0);
+        assert_eq!(work[0].hours, 5.0, "the 2.5h pause is now considered work");
+    }
+
+    #[test]
+    fn authors_are_estimated_separately() {
+        let mut commits = vec![
+            signature("b", "b@example.com", THURSDAY),
+            signature("a", "a@example.com", THURSDAY + HOUR / 2),
+            signature("b", "b@example.com", THURSDAY + HOUR),
+        ];
+        let work = estimate_hours_by_email(&mut commits, 120.0);
+        assert_eq!(
+            work.iter()
+                .map(|w| (w.email.as_bstr(), w.hours, w.num_commits))
+                .collect::<Vec<_>>(),
+            vec![("a@example.com".into(), 2.0, 1), ("b@example.com".into(), 3.0, 2)]
+        );
+    }
+
+    #[test]
+    fn authors_are_unified_by_the_mailmap() {
+        let commit = |author: &str| {
+            format!(
+                "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\nauthor {} {} +0000\ncommitter c <c@example.com> {} +0000\n\nmessage\n",
+                author, THURSDAY, THURSDAY
+            )
+            .into_bytes()
+        };
+        let mailmap = git::mailmap::Snapshot::from_bytes(b"Jane Doe <jane@example.com> <jane@old.example.com>");
+        let mut authors = authors(
+            vec![
+                commit("Jane Doe <jane@example.com>"),
+                commit("Jane <jane@old.example.com>"),
+            ],
+            &mailmap,
+        );
+        assert!(authors
+            .iter()
+            .all(|a| a.name == "Jane Doe" && a.email == "jane@example.com"));
+
+        let work = estimate_hours_by_email(&mut authors, 120.0);
+        assert_eq!(work.len(), 1, "both commits are attributed to the same author");
+        assert_eq!(work[0].num_commits, 2);
+    }
+
+    #[test]
+    fn identities_are_unified_by_name_and_email() {
+        let work = vec![
+            work("Jane Doe", "jane@example.com", 2.0, 1),
+            work("Jane", "jane@example.com", 2.0, 1),
+            work("Jane", "jane@work.example.com", 2.0, 1),
+            work("Bob", "bob@example.com", 2.0, 1),
+        ];
+        let persons = deduplicate_identities(&work);
+        assert_eq!(persons.len(), 2);
+        assert_eq!(persons[0].name, vec!["Jane Doe", "Jane"]);
+        assert_eq!(persons[0].email, vec!["jane@example.com", "jane@work.example.com"]);
+        assert_eq!((persons[0].hours, persons[0].num_commits), (6.0, 3));
+        assert_eq!(persons[1].name, vec!["Bob"]);
+    }
+
+    mod csv {
+        use super::{csv_field, deduplicate_identities, work, write_csv};
+
+        #[test]
+        fn fields_are_quoted_if_needed() {
+            assert_eq!(csv_field("plain value"), "plain value");
+            assert_eq!(csv_field(""), "");
+            assert_eq!(csv_field("a, b"), "\"a, b\"");
+            assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+            assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+            assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+        }
+
+        #[test]
+        fn with_and_without_pii() -> std::io::Result<()> {
+            let work = vec![
+                work("Jane Doe", "jane@example.com", 2.5, 2),
+                work("Jane Doe", "jane@work.example.com", 2.0, 1),
+                work("Bob \"The Builder\"", "bob@example.com", 2.0, 1),
+            ];
+            let work_by_bucket = vec![(Some("2022-04-04"), deduplicate_identities(&work))];
+
+            let mut out = Vec::new();
+            write_csv(&mut out, &work_by_bucket, true)?;
+            assert_eq!(
+                String::from_utf8(out).expect("valid UTF-8"),
+                "bucket,names,emails,hours,commits\n\
+                 2022-04-04,Jane Doe,\"jane@example.com, jane@work.example.com\",4.50,3\n\
+                 2022-04-04,\"Bob \"\"The Builder\"\"\",bob@example.com,2.00,1\n"
+            );
+
+            let mut out = Vec::new();
+            write_csv(&mut out, &work_by_bucket, false)?;
+            assert_eq!(
+                String::from_utf8(out).expect("valid UTF-8"),
+                "bucket,hours,commits,authors\n2022-04-04,6.50,4,2\n"
+            );
+
+            let mut out = Vec::new();
+            write_csv(&mut out, &[(None, deduplicate_identities(&work))], false)?;
+            assert_eq!(
+                String::from_utf8(out).expect("valid UTF-8"),
+                "bucket,hours,commits,authors\n,6.50,4,2\n",
+                "the bucket is empty if the entire history is estimated at once"
+            );
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "serde1")]
+    mod json {
+        use super::{deduplicate_identities, work, write_json, Summary};
+
+        #[test]
+        fn with_and_without_pii() -> anyhow::Result<()> {
+            let work = vec![
+                work("Jane Doe", "jane@example.com", 2.5, 2),
+                work("Jane", "jane@example.com", 2.0, 1),
+            ];
+            let work_by_bucket = vec![
+                (Some("2022-04-04"), deduplicate_identities(&work[..1])),
+                (Some("2022-04-11"), deduplicate_identities(&work)),
+            ];
+            let summary = Summary {
+                total_hours: 7.0,
+                total_commits: 5,
+                is_shallow: false,
+                num_authors: 2,
+                num_unique_authors: Some(1),
+            };
+
+            let mut out = Vec::new();
+            write_json(&mut out, &work_by_bucket, &summary, true)?;
+            let doc: serde_json::Value = serde_json::from_slice(&out)?;
+            assert_eq!(doc["schema"], "estimate-hours");
+            let data = &doc["data"];
+            assert_eq!(data["total_hours"], 7.0);
+            assert_eq!(data["total_commits"], 5);
+            assert_eq!(data["is_shallow"], false);
+            assert_eq!(data["num_authors"], 2);
+            assert_eq!(data["num_unique_authors"], 1);
+            assert_eq!(
+                data["buckets"][1],
+                serde_json::json!({
+                    "name": "2022-04-11",
+                    "hours": 4.5,
+                    "num_commits": 3,
+                    "num_authors": 1,
+                    "authors": [{
+                        "names": ["Jane Doe", "Jane"],
+                        "emails": ["jane@example.com"],
+                        "hours": 4.5,
+                        "num_commits": 3
+                    }]
+                })
+            );
+
+            let mut out = Vec::new();
+            write_json(&mut out, &work_by_bucket, &summary, false)?;
+            let doc: serde_json::Value = serde_json::from_slice(&out)?;
+            let buckets = doc["data"]["buckets"].as_array().expect("buckets are an array");
+            assert_eq!(buckets.len(), 2);
+            assert!(
+                buckets.iter().all(|b| b.get("authors").is_none()),
+                "authors are personally identifiable information"
+            );
+            assert_eq!(buckets[0]["name"], "2022-04-04");
+            Ok(())
+        }
+    }
+}
@@ -82,6 +82,7 @@ pub fn checkout_exclusive(
         collisions,
         files_updated,
         bytes_written,
+        ..
     } = match repo {
         Some(repo) => git::worktree::index::checkout(
             &mut index,
@@ -14,7 +14,7 @@ pub fn entries(
 
     #[cfg(feature = "serde1")]
     if let Json = format {
-        out.write_all(b"[\n")?;
+        crate::json::begin_array(&mut out, "index-entries")?;
     }
 
     let mut entries = file.entries().iter().peekable();
@@ -28,7 +28,69 @@ pub fn entries(
 
     #[cfg(feature = "serde1")]
     if let Json = format {
-        out.write_all(b"]\n")?;
+        crate::json::end_array(&mut out)?;
+    }
+    Ok(())
+}
+
+/// Print all entries which are in a conflicted state, that is with a stage other than 0, similar to `git ls-files -u`.
+pub fn conflicts(
+    index_path: impl AsRef<Path>,
+    mut out: impl std::io::Write,
+    Options { object_hash, format }: Options,
+) -> anyhow::Result<()> {
+    use crate::OutputFormat::*;
+    let file = parse_file(index_path, object_hash)?;
+    let unmerged = file.entries().iter().filter(|e| e.flags.stage() != 0);
+
+    match format {
+        Human => {
+            for entry in unmerged {
+                writeln!(
+                    out,
+                    "{:06o} {} {}\t{}",
+                    entry.mode.bits(),
+                    entry.id,
+                    entry.flags.stage(),
+                    entry.path(&file.state)
+                )?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        Json => {
+            use git_repository::bstr::ByteSlice;
+
+            #[derive(serde::Serialize)]
+            struct Stage {
+                stage: u32,
+                mode: u32,
+                hex_id: String,
+            }
+            #[derive(serde::Serialize)]
+            struct Conflict<'a> {
+                path: std::borrow::Cow<'a, str>,
+                stages: Vec<Stage>,
+            }
+
+            let mut conflicts = Vec::<Conflict<'_>>::new();
+            for entry in unmerged {
+                let path = entry.path(&file.state).to_str_lossy();
+                let stage = Stage {
+                    stage: entry.flags.stage(),
+                    mode: entry.mode.bits(),
+                    hex_id: entry.id.to_hex().to_string(),
+                };
+                match conflicts.last_mut() {
+                    Some(prev) if prev.path == path => prev.stages.push(stage),
+                    _ => conflicts.push(Conflict {
+                        path,
+                        stages: vec![stage],
+                    }),
+                }
+            }
+            crate::json::write(&mut out, "index-conflicts", &conflicts)?;
+            writeln!(out)?;
+        }
     }
     Ok(())
 }
@@ -68,6 +68,10 @@ mod serde_only {
     pub struct Extensions {
         names: Vec<&'static str>,
         tree: Option<ext::Tree>,
+        /// The checksum of the shared index this split index refers to.
+        shared_index_checksum: Option<String>,
+        /// The amount of paths whose conflicts were resolved and can be restored.
+        num_resolve_undo_paths: Option<usize>,
     }
 
     #[derive(serde::Serialize)]
@@ -89,19 +93,26 @@ mod serde_only {
                         names.push("tree (TREE)");
                         extension_details.then(|| tree.into())
                     });
-                    if f.link().is_some() {
+                    let shared_index_checksum = f.link().and_then(|link| {
                         names.push("link");
-                    };
-                    if f.resolve_undo().is_some() {
+                        extension_details.then(|| link.shared_index_checksum.to_hex().to_string())
+                    });
+                    let num_resolve_undo_paths = f.resolve_undo().and_then(|paths| {
                         names.push("resolve-undo (REUC)");
-                    };
+                        extension_details.then(|| paths.len())
+                    });
                     if f.untracked().is_some() {
                         names.push("untracked (UNTR)");
                     };
                     if f.fs_monitor().is_some() {
                         names.push("fs-monitor (FSMN)");
                     };
-                    Extensions { names, tree }
+                    Extensions {
+                        names,
+                        tree,
+                        shared_index_checksum,
+                        num_resolve_undo_paths,
+                    }
                 },
                 entries: {
                     let (mut stage_0_merged, mut stage_1_base, mut stage_2_ours, mut stage_3_theirs) = (0, 0, 0, 0);
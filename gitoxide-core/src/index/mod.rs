@@ -8,7 +8,7 @@ pub struct Options {
 }
 
 mod entries;
-pub use entries::entries;
+pub use entries::{conflicts, entries};
 
 pub mod information;
 
@@ -45,12 +45,49 @@ pub fn verify(
     Options { object_hash, format }: Options,
 ) -> anyhow::Result<()> {
     let file = parse_file(index_path, object_hash)?;
-    file.verify_integrity()?;
-    file.verify_entries()?;
-    file.verify_extensions(false, git::index::verify::extensions::no_find)?;
-    #[cfg_attr(not(feature = "serde1"), allow(irrefutable_let_patterns))]
-    if let crate::OutputFormat::Human = format {
-        writeln!(out, "OK").ok();
+    let checks = [
+        ("checksum", file.verify_integrity().err().map(|err| err.to_string())),
+        ("entry-order", file.verify_entries().err().map(|err| err.to_string())),
+        (
+            "extensions",
+            file.verify_extensions(false, git::index::verify::extensions::no_find)
+                .err()
+                .map(|err| err.to_string()),
+        ),
+    ];
+    match format {
+        crate::OutputFormat::Human => {
+            for (name, err) in &checks {
+                match err {
+                    Some(err) => writeln!(out, "{}: FAILED: {}", name, err)?,
+                    None => writeln!(out, "{}: OK", name)?,
+                }
+            }
+        }
+        #[cfg(feature = "serde1")]
+        crate::OutputFormat::Json => {
+            #[derive(serde::Serialize)]
+            struct Check<'a> {
+                name: &'a str,
+                error: Option<&'a str>,
+            }
+            crate::json::write(
+                &mut out,
+                "index-verify",
+                &checks
+                    .iter()
+                    .map(|(name, err)| Check {
+                        name,
+                        error: err.as_deref(),
+                    })
+                    .collect::<Vec<_>>(),
+            )?;
+            writeln!(out)?;
+        }
+    }
+    let num_failed = checks.iter().filter(|(_, err)| err.is_some()).count();
+    if num_failed != 0 {
+        anyhow::bail!("{} of {} index checks failed", num_failed, checks.len());
     }
     Ok(())
 }
@@ -81,7 +118,7 @@ pub fn information(
         #[cfg(feature = "serde1")]
         Json => {
             let info = information::Collection::try_from_file(parse_file(index_path, object_hash)?, extension_details)?;
-            serde_json::to_writer_pretty(out, &info)?;
+            crate::json::write(out, "index-info", &info)?;
             Ok(())
         }
     }
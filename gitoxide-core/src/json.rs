@@ -0,0 +1,82 @@
+//! Versioned JSON output shared by all commands supporting `--format json`.
+//!
+//! Every document is an object with the `schema` it follows, the `version` of that schema and the actual `data`, like
+//!
+//! ```json
+//! {
+//!   "schema": "commit-graph-verify",
+//!   "version": 1,
+//!   "data": { "longest_path_length": 2, "num_commits": 3, "parent_counts": { "0": 1, "1": 2 } }
+//! }
+//! ```
+//!
+//! This allows tools to parse output reliably and to detect incompatible changes, which will increment [`SCHEMA_VERSION`].
+//! Fields may be added to `data` without changing the version.
+//!
+//! The following schemas exist, along with the type serialized as `data`:
+//!
+//! * `bundle-create` - the `path` of the bundle, its `num_objects` and its `header`
+//! * `bundle-unbundle` - the written pack and index along with the `refs` and how they were updated
+//! * `bundle-verify` - the `header` of the bundle and its `missing_prerequisites`
+//! * `commit-graph-verify` - [`git_commitgraph::graph::verify::Outcome`]
+//! * `commit-graph-write` - [`crate::commitgraph::write::Statistics`]
+//! * `estimate-hours` - the totals of the estimate along with the hours, commits and authors of each time bucket
+//! * `fsck` - the amount of objects, errors and warnings along with a list of [`crate::repository::fsck::Finding`]s
+//! * `config-entries` - a list of configuration values with their `key`, `value`, and optionally `scope` and `origin`
+//! * `index-entries` - a list of index entries with their `stat`, `hex_id`, `flags`, `mode` and `path`
+//! * `index-conflicts` - a list of conflicting paths along with their `stages`
+//! * `index-verify` - a list of checks with their `name` and an `error` if they failed
+//! * `index-info` - the entries and extensions of the index
+//! * `mailmap-entries` - a list of mailmap entries
+//! * `multi-index-info` - the `path`, `num_objects`, `index_names` and `object_hash` of a multi-pack index
+//! * `odb-stats` - the structure and metrics of the object database
+//! * `pack-create` - the statistics of counting and entry generation
+//! * `pack-index-create` - [`git_repository::odb::pack::bundle::write::Outcome`]
+//! * `pack-receive` - the written pack and index along with the received `refs`
+//! * `pack-verify` - [`git_repository::odb::pack::index::traverse::Statistics`]
+//! * `multi-index-verify` - a list of pairs of index names and their [`git_repository::odb::pack::index::traverse::Statistics`]
+//! * `remote-refs` - a list of references advertised by the remote
+//! * `repository-verify` - the `index_statistics` of all packs and the `loose_object-stores`
+//! * `tree-stats` - the amount of trees, blobs and bytes in a tree
+//! * `worktree-list` - a list of [`crate::repository::worktree::Worktree`]s
+//! * `worktree-prune` - a list of pruned worktrees with their `id` and the `reason` for pruning them
+use std::io;
+
+/// The version of all schemas, incremented whenever a field is removed or changes its meaning.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(serde::Serialize)]
+struct Document<'a, T: ?Sized> {
+    schema: &'a str,
+    version: u32,
+    data: &'a T,
+}
+
+/// Write `data` as pretty-printed JSON document following `schema` to `out`.
+pub fn write<T: serde::Serialize + ?Sized>(out: impl io::Write, schema: &str, data: &T) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(
+        out,
+        &Document {
+            schema,
+            version: SCHEMA_VERSION,
+            data,
+        },
+    )
+}
+
+/// Start a document following `schema` whose `data` is an array of items, for use when items should be streamed one by one.
+///
+/// It must be followed by [`end_array()`] once all items were written, separated by `,`.
+pub fn begin_array(mut out: impl io::Write, schema: &str) -> io::Result<()> {
+    writeln!(
+        out,
+        "{{\n  \"schema\": {},\n  \"version\": {},\n  \"data\": [",
+        serde_json::Value::from(schema),
+        SCHEMA_VERSION
+    )
+}
+
+/// Finish a document started with [`begin_array()`].
+pub fn end_array(mut out: impl io::Write) -> io::Result<()> {
+    writeln!(out, "  ]\n}}")
+}
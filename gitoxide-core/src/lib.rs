@@ -46,6 +46,8 @@ pub mod commitgraph;
 #[cfg(feature = "estimate-hours")]
 pub mod hours;
 pub mod index;
+#[cfg(feature = "serde1")]
+pub mod json;
 pub mod mailmap;
 #[cfg(feature = "organize")]
 pub mod organize;
@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 #[derive(PartialEq, Debug)]
 pub enum Protocol {
+    V0,
     V1,
     V2,
 }
@@ -11,9 +12,10 @@ impl FromStr for Protocol {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
+            "0" => Protocol::V0,
             "1" => Protocol::V1,
             "2" => Protocol::V2,
-            _ => return Err(format!("Unsupported protocol version '{}', choose '1' or '2'", s)),
+            _ => return Err(format!("Unsupported protocol version '{}', choose '0', '1' or '2'", s)),
         })
     }
 }
@@ -27,6 +29,7 @@ mod impls {
     impl From<Protocol> for transport::Protocol {
         fn from(v: Protocol) -> Self {
             match v {
+                Protocol::V0 => transport::Protocol::V0,
                 Protocol::V1 => transport::Protocol::V1,
                 Protocol::V2 => transport::Protocol::V2,
             }
@@ -6,6 +6,9 @@ use std::{
 use git_config::file::GitConfig;
 use git_repository::{objs::bstr::ByteSlice, progress, Progress};
 
+#[cfg(feature = "blocking-client")]
+pub mod mirror;
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Mode {
     Execute,
@@ -18,6 +21,14 @@ impl Default for Mode {
     }
 }
 
+#[derive(Default)]
+pub struct Options {
+    pub mode: Mode,
+    /// If set, each repository will be fetched from its `origin` remote once it is in place.
+    #[cfg(feature = "blocking-client")]
+    pub mirror: Option<mirror::Options>,
+}
+
 enum RepoKind {
     Bare,
     WorkingTree,
@@ -126,7 +137,7 @@ fn handle(
     git_workdir: &Path,
     canonicalized_destination: &Path,
     progress: &mut impl Progress,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<PathBuf>> {
     fn to_relative(path: PathBuf) -> PathBuf {
         path.components()
             .skip_while(|c| c == &std::path::Component::RootDir)
@@ -158,7 +169,7 @@ fn handle(
             git_workdir.display(),
             parent_repo_path
         ));
-        return Ok(());
+        return Ok(None);
     }
 
     let url = match find_origin_remote(git_workdir)? {
@@ -167,7 +178,7 @@ fn handle(
                 "Skipping repository {:?} without 'origin' remote",
                 git_workdir.display()
             ));
-            return Ok(());
+            return Ok(None);
         }
         Some(url) => url,
     };
@@ -177,7 +188,7 @@ fn handle(
             git_workdir.display(),
             url.to_string()
         ));
-        return Ok(());
+        return Ok(None);
     }
 
     let destination = canonicalized_destination
@@ -203,7 +214,7 @@ fn handle(
 
     if let Ok(destination) = destination.canonicalize() {
         if git_workdir.canonicalize()? == destination {
-            return Ok(());
+            return Ok(Some(destination));
         }
     }
     match mode {
@@ -218,7 +229,7 @@ fn handle(
             std::fs::rename(git_workdir, &destination)?;
         }
     }
-    Ok(())
+    Ok(Some(destination))
 }
 
 /// Find all working directories in the given `source_dir` and print them to `out` while providing `progress`.
@@ -239,8 +250,10 @@ where
     Ok(())
 }
 
+/// Move all repositories found in `source_dir` into a directory structure within `destination` that matches their
+/// `origin` remote, and fetch them afterwards if `options` ask for it.
 pub fn run<P: Progress>(
-    mode: Mode,
+    options: Options,
     source_dir: impl AsRef<Path>,
     destination: impl AsRef<Path>,
     mut progress: P,
@@ -248,18 +261,36 @@ pub fn run<P: Progress>(
 where
     <<P as Progress>::SubProgress as Progress>::SubProgress: Sync,
 {
+    let mode = options.mode;
     let mut num_errors = 0usize;
+    let mut repositories = Vec::new();
     let destination = destination.as_ref().canonicalize()?;
     for (path_to_move, kind) in
         find_git_repository_workdirs(source_dir, progress.add_child("Searching repositories"), false)
     {
-        if let Err(err) = handle(mode, kind, &path_to_move, &destination, &mut progress) {
-            progress.fail(format!(
-                "Error when handling directory {:?}: {}",
-                path_to_move.display(),
-                err.to_string()
-            ));
-            num_errors += 1;
+        match handle(mode, kind, &path_to_move, &destination, &mut progress) {
+            Ok(Some(repository)) => repositories.push(repository),
+            Ok(None) => {}
+            Err(err) => {
+                progress.fail(format!(
+                    "Error when handling directory {:?}: {}",
+                    path_to_move.display(),
+                    err.to_string()
+                ));
+                num_errors += 1;
+            }
+        }
+    }
+
+    #[cfg(feature = "blocking-client")]
+    if let Some(mirror) = options.mirror {
+        match mode {
+            Mode::Simulate => {
+                for repository in &repositories {
+                    progress.info(format!("WOULD fetch {} from its origin", repository.display()));
+                }
+            }
+            Mode::Execute => num_errors += mirror::fetch_all(repositories, progress.add_child("Fetching"), mirror),
         }
     }
 
@@ -0,0 +1,398 @@
+//! Keep organized repositories up to date by fetching all refs of their `origin` remote, turning `organize` into a tool
+//! to mirror many repositories at once.
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::{anyhow, bail};
+use git::{
+    bstr::{BStr, BString, ByteSlice},
+    odb::pack,
+    prelude::Find,
+    protocol,
+    protocol::{
+        fetch::{Action, Arguments, LsRefsAction, Ref, Response},
+        transport,
+        transport::client::Capabilities,
+    },
+    refs::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        FullName, Target,
+    },
+    ObjectId, Progress,
+};
+use git_features::parallel::Reduce;
+use git_repository as git;
+
+use crate::net;
+
+/// The name of the remote whose refs are mirrored.
+const REMOTE_NAME: &str = "origin";
+
+pub struct Options {
+    /// The protocol version to use when talking to remotes.
+    pub protocol: Option<net::Protocol>,
+    /// If true, refs which were deleted on the remote will be deleted locally as well.
+    pub prune: bool,
+    /// The amount of repositories to fetch in parallel.
+    /// If unset, as many repositories as there are logical cores are fetched at the same time.
+    pub thread_limit: Option<usize>,
+    pub should_interrupt: Arc<AtomicBool>,
+}
+
+/// The outcome of fetching a single repository.
+pub struct Outcome {
+    /// The amount of objects received, which is zero if the repository was up to date.
+    pub num_objects: usize,
+    /// The amount of refs that were created or moved to match the remote.
+    pub updated_refs: usize,
+    /// The amount of refs that were deleted as they don't exist on the remote anymore.
+    pub deleted_refs: usize,
+}
+
+/// Fetch all `repositories` from their `origin` remote in parallel, with each repository receiving its own child of
+/// `progress`, and return the amount of repositories that failed to fetch.
+pub fn fetch_all(repositories: Vec<PathBuf>, mut progress: impl Progress, options: Options) -> usize {
+    let Options {
+        protocol,
+        prune,
+        thread_limit,
+        should_interrupt,
+    } = options;
+    let version: transport::Protocol = protocol.unwrap_or_default().into();
+    progress.init(Some(repositories.len()), git::progress::count("repositories"));
+    let num_repositories = repositories.len();
+    let progress = Arc::new(Mutex::new(progress));
+    let reducer = Reducer {
+        progress: Arc::clone(&progress),
+        num_errors: 0,
+    };
+    let result = git_features::parallel::in_parallel_if(
+        || num_repositories > 1,
+        repositories.into_iter(),
+        thread_limit,
+        |_thread_id| (),
+        move |repo_path, _state| {
+            let mut progress = progress
+                .lock()
+                .expect("no panic while holding the lock")
+                .add_child(repo_path.display().to_string());
+            if should_interrupt.load(Ordering::Relaxed) {
+                progress.fail("Interrupted before fetching".into());
+                return false;
+            }
+            match fetch(&repo_path, &mut progress, &should_interrupt, version, prune) {
+                Ok(Outcome {
+                    num_objects,
+                    updated_refs,
+                    deleted_refs,
+                }) => {
+                    progress.done(format!(
+                        "Received {} objects, updated {} and deleted {} refs",
+                        num_objects, updated_refs, deleted_refs
+                    ));
+                    true
+                }
+                Err(err) => {
+                    progress.fail(format!("Failed to fetch {:?}: {:#}", repo_path.display(), err));
+                    false
+                }
+            }
+        },
+        reducer,
+    );
+    match result {
+        Ok(num_errors) => num_errors,
+        Err(never) => match never {},
+    }
+}
+
+struct Reducer<P> {
+    progress: Arc<Mutex<P>>,
+    num_errors: usize,
+}
+
+impl<P: Progress> Reduce for Reducer<P> {
+    type Input = bool;
+    type FeedProduce = ();
+    type Output = usize;
+    type Error = std::convert::Infallible;
+
+    fn feed(&mut self, succeeded: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        if !succeeded {
+            self.num_errors += 1;
+        }
+        self.progress.lock().expect("no panic while holding the lock").inc();
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.num_errors)
+    }
+}
+
+/// Fetch all refs of the `origin` remote into the repository at `repo_path` using the protocol `version`, and delete
+/// refs that were deleted on the remote if `prune` is true.
+///
+/// Bare repositories mirror all refs of the remote like `git clone --mirror` does, i.e. as if the refspec was `+refs/*:refs/*`.
+/// Repositories with a worktree keep their local branches and only update their remote-tracking branches and tags.
+pub fn fetch(
+    repo_path: &Path,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    version: transport::Protocol,
+    prune: bool,
+) -> anyhow::Result<Outcome> {
+    let repo = git::open(repo_path)?.apply_environment();
+    let url = repo
+        .remote_url(REMOTE_NAME, git::remote::Direction::Fetch)?
+        .ok_or_else(|| anyhow!("No url configured for remote '{}'", REMOTE_NAME))?
+        .to_bstring()?;
+    let bare = repo.work_dir().is_none();
+
+    let mut existing = HashMap::new();
+    for r in repo.references()?.all()? {
+        let r = r.map_err(|err| anyhow!(err))?.detach();
+        existing.insert(r.name.as_bstr().to_owned(), r.target);
+    }
+    let mut haves: Vec<_> = existing
+        .values()
+        .filter_map(|target| target.try_id().map(ToOwned::to_owned))
+        .filter(|id| repo.objects.contains(id))
+        .collect();
+    haves.sort();
+    haves.dedup();
+
+    let mut delegate = MirrorDelegate {
+        repo: &repo,
+        bare,
+        haves,
+        should_interrupt,
+        refs: Vec::new(),
+        pack: None,
+    };
+    let transport = net::connect(url.as_slice(), version)?;
+    protocol::fetch(
+        transport,
+        &mut delegate,
+        protocol::credentials::helper,
+        progress.add_child("fetch"),
+        protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+    )?;
+    let MirrorDelegate { refs, pack, .. } = delegate;
+    if should_interrupt.load(Ordering::Relaxed) {
+        bail!("Interrupted after receiving objects")
+    }
+
+    let message = format!("fetch: mirror from {}", url);
+    let log = LogChange {
+        mode: RefLog::AndReference,
+        force_create_reflog: false,
+        message: message.as_str().into(),
+    };
+    let mut edits = Vec::new();
+    let mut updated_refs = 0;
+    for (name, new, _id) in &refs {
+        match existing.get(name) {
+            Some(existing) if existing == new => continue,
+            // Local symbolic refs stay symbolic, as the remote only advertises the id of their target.
+            Some(Target::Symbolic(_)) if new.try_id().is_some() => continue,
+            _ => {}
+        }
+        edits.push(RefEdit {
+            change: Change::Update {
+                log: log.clone(),
+                expected: PreviousValue::Any,
+                new: new.clone(),
+            },
+            name: FullName::try_from(name.as_bstr())?,
+            deref: false,
+        });
+        updated_refs += 1;
+    }
+
+    let mut deleted_refs = 0;
+    if prune {
+        let mirrored: HashSet<_> = refs.iter().map(|(name, _target, _id)| name.as_bstr()).collect();
+        for (name, target) in &existing {
+            // Symbolic refs like `refs/remotes/origin/HEAD` are never advertised, but still point to mirrored refs.
+            if target.try_id().is_none() || !is_mirrored(name.as_bstr(), bare) || mirrored.contains(&name.as_bstr()) {
+                continue;
+            }
+            edits.push(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(target.clone()),
+                    log: RefLog::AndReference,
+                },
+                name: FullName::try_from(name.as_bstr())?,
+                deref: false,
+            });
+            deleted_refs += 1;
+        }
+    }
+    if !edits.is_empty() {
+        repo.edit_references(edits, git::lock::acquire::Fail::Immediately, None)?;
+    }
+
+    Ok(Outcome {
+        num_objects: pack.map_or(0, |pack| pack.index.num_objects as usize),
+        updated_refs,
+        deleted_refs,
+    })
+}
+
+/// Return the name of the local ref mirroring the remote ref at `path`, or `None` if it isn't mirrored.
+fn local_name(path: &BStr, bare: bool) -> Option<BString> {
+    if bare {
+        path.starts_with(b"refs/").then(|| path.to_owned())
+    } else if let Some(branch) = path.strip_prefix(b"refs/heads/") {
+        Some(format!("refs/remotes/{}/{}", REMOTE_NAME, branch.as_bstr()).into())
+    } else if path.starts_with(b"refs/tags/") {
+        Some(path.to_owned())
+    } else {
+        None
+    }
+}
+
+/// Return true if the local ref `name` is a mirror of a remote ref, making it subject to pruning.
+///
+/// Tags are only ever added in repositories with a worktree, just like `git fetch` does.
+fn is_mirrored(name: &BStr, bare: bool) -> bool {
+    if bare {
+        name.starts_with(b"refs/")
+    } else {
+        name.starts_with(format!("refs/remotes/{}/", REMOTE_NAME).as_bytes())
+    }
+}
+
+struct MirrorDelegate<'a> {
+    repo: &'a git::Repository,
+    bare: bool,
+    haves: Vec<ObjectId>,
+    should_interrupt: &'a AtomicBool,
+
+    /// The names of the local refs along with the target they should point to and the id of the object it resolves to,
+    /// as advertised by the remote.
+    refs: Vec<(BString, Target, ObjectId)>,
+    pack: Option<pack::bundle::write::Outcome>,
+}
+
+impl MirrorDelegate<'_> {
+    fn ref_prefixes(&self) -> &'static [&'static str] {
+        if self.bare {
+            &["refs/"]
+        } else {
+            &["refs/heads/", "refs/tags/"]
+        }
+    }
+
+    /// Return the ids of all mirrored refs whose objects are not yet present in the repository.
+    fn wants(&self) -> Vec<ObjectId> {
+        let mut wants: Vec<_> = self
+            .refs
+            .iter()
+            .map(|(_name, _target, id)| *id)
+            .filter(|id| !self.repo.objects.contains(id))
+            .collect();
+        wants.sort();
+        wants.dedup();
+        wants
+    }
+}
+
+impl protocol::fetch::DelegateBlocking for MirrorDelegate<'_> {
+    fn prepare_ls_refs(
+        &mut self,
+        server: &Capabilities,
+        arguments: &mut Vec<BString>,
+        _features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<LsRefsAction> {
+        if server.contains("ls-refs") {
+            arguments.extend(self.ref_prefixes().iter().map(|r| format!("ref-prefix {}", r).into()));
+        }
+        Ok(LsRefsAction::Continue)
+    }
+
+    fn prepare_fetch(
+        &mut self,
+        _version: transport::Protocol,
+        _server: &Capabilities,
+        features: &mut Vec<(&str, Option<&str>)>,
+        refs: &[Ref],
+    ) -> io::Result<Action> {
+        let bare = self.bare;
+        self.refs = refs
+            .iter()
+            .filter_map(|r| {
+                let (path, id) = r.unpack();
+                let name = local_name(path.as_bstr(), bare)?;
+                let target = match r {
+                    Ref::Symbolic { target, .. } => local_name(target.as_bstr(), bare)
+                        .and_then(|target| FullName::try_from(target).ok())
+                        .map(Target::Symbolic),
+                    _ => None,
+                }
+                .unwrap_or(Target::Peeled(*id));
+                Some((name, target, *id))
+            })
+            .collect();
+        // Thin packs would need their base objects to be looked up in the repository while indexing the pack.
+        features.retain(|(name, _value)| *name != "thin-pack");
+        Ok(if self.wants().is_empty() {
+            Action::Cancel
+        } else {
+            Action::Continue
+        })
+    }
+
+    fn negotiate(
+        &mut self,
+        _refs: &[Ref],
+        arguments: &mut Arguments,
+        _previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        for id in self.wants() {
+            arguments.want(id);
+        }
+        for id in &self.haves {
+            arguments.have(id);
+        }
+        Ok(Action::Cancel)
+    }
+}
+
+impl protocol::fetch::Delegate for MirrorDelegate<'_> {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        _previous_response: &Response,
+    ) -> io::Result<()> {
+        let options = pack::bundle::write::Options {
+            // Repositories are already fetched in parallel.
+            thread_limit: Some(1),
+            index_kind: pack::index::Version::V2,
+            iteration_mode: pack::data::input::Mode::Verify,
+            object_hash: self.repo.object_hash(),
+        };
+        let outcome = pack::Bundle::write_to_directory(
+            input,
+            Some(self.repo.objects.store_ref().path().join("pack")),
+            progress,
+            self.should_interrupt,
+            None,
+            options,
+        )
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        self.pack = Some(outcome);
+        Ok(())
+    }
+}
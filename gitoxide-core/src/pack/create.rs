@@ -6,7 +6,7 @@ use git_repository::{
     hash,
     hash::ObjectId,
     interrupt,
-    objs::bstr::ByteVec,
+    objs::bstr::{ByteSlice, ByteVec},
     odb::{pack, pack::FindExt},
     parallel::InOrderIter,
     prelude::Finalize,
@@ -92,8 +92,13 @@ pub struct Context<W> {
     /// This is a total, shared among all threads if `thread_limit` permits.
     /// Only used when known to be effective, namely when `expansion == ObjectExpansion::TreeDiff`.
     pub object_cache_size_in_bytes: usize,
+    /// If true, the pack will be written to `out` instead of `output_directory`, for example to pipe it into `git index-pack`.
+    /// Additional information is then written to `err` instead.
+    pub pack_to_out: bool,
     /// The output stream for use of additional information
     pub out: W,
+    /// The output stream for additional information if `out` receives the pack.
+    pub err: W,
 }
 
 pub fn create<W>(
@@ -110,13 +115,17 @@ pub fn create<W>(
         statistics,
         pack_cache_size_in_bytes,
         object_cache_size_in_bytes,
+        pack_to_out,
         mut out,
+        err,
     }: Context<W>,
 ) -> anyhow::Result<()>
 where
     W: std::io::Write,
 {
-    let repo = git::discover(repository_path)?.into_sync();
+    let repo = git::discover(repository_path)?;
+    let compression_level = repo.pack_compression_level();
+    let repo = repo.into_sync();
     progress.init(Some(2), progress::steps());
     let tips = tips.into_iter();
     let make_cancellation_err = || anyhow!("Cancelled by user");
@@ -127,24 +136,14 @@ where
         None => {
             let mut progress = progress.add_child("traversing");
             progress.init(None, progress::count("commits"));
-            let tips = tips
-                .map({
-                    let easy = repo.to_thread_local();
-                    move |tip| {
-                        ObjectId::from_hex(&Vec::from_os_str_lossy(tip.as_ref())).or_else(|_| {
-                            easy.find_reference(tip.as_ref())
-                                .map_err(anyhow::Error::from)
-                                .and_then(|r| r.into_fully_peeled_id().map(|oid| oid.detach()).map_err(Into::into))
-                        })
-                    }
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+            let (tips, hidden) = parse_tips(&repo.to_thread_local(), tips)?;
             let handle = repo.objects.into_shared_arc().to_cache_arc();
             let iter = Box::new(
                 traverse::commit::Ancestors::new(tips, traverse::commit::ancestors::State::default(), {
                     let handle = handle.clone();
                     move |oid, buf| handle.find_commit_iter(oid, buf).map(|t| t.0)
                 })
+                .with_hidden(hidden)
                 .map(|res| res.map_err(Into::into))
                 .inspect(move |_| progress.inc()),
             );
@@ -247,6 +246,7 @@ where
                 allow_thin_pack: thin,
                 chunk_size,
                 version: Default::default(),
+                compression_level,
             },
         ))
     };
@@ -260,6 +260,7 @@ where
     let mut named_tempfile_store: Option<tempfile::NamedTempFile> = None;
     let mut sink_store: std::io::Sink;
     let (mut pack_file, output_directory): (&mut dyn std::io::Write, Option<_>) = match output_directory {
+        _ if pack_to_out => (&mut out, None),
         Some(dir) => {
             named_tempfile_store = Some(tempfile::NamedTempFile::new_in(dir.as_ref())?);
             (named_tempfile_store.as_mut().expect("packfile just set"), Some(dir))
@@ -293,7 +294,9 @@ where
         .digest()
         .expect("iteration is done");
     let pack_name = format!("{}.pack", hash);
-    if let (Some(pack_file), Some(dir)) = (named_tempfile_store.take(), output_directory) {
+    if pack_to_out {
+        out.flush()?;
+    } else if let (Some(pack_file), Some(dir)) = (named_tempfile_store.take(), output_directory) {
         pack_file.persist(dir.as_ref().join(pack_name))?;
     } else {
         writeln!(out, "{}", pack_name)?;
@@ -304,17 +307,54 @@ where
     entries_progress.show_throughput(start);
 
     if let Some(format) = statistics {
-        print(stats, format, out)?;
+        print(stats, format, if pack_to_out { err } else { out })?;
     }
     progress.inc();
     Ok(())
 }
 
+/// Resolve `tips` into the commits to traverse and the ones to hide along with their ancestors.
+///
+/// Each tip is a commit hash or reference name, optionally prefixed with `^` to hide it, or a range like `from..to`,
+/// where `from` is hidden and an empty side refers to `HEAD`, or a symmetric difference like `one...two` which hides
+/// the merge-bases of both sides.
+pub(crate) fn parse_tips(
+    repo: &git::Repository,
+    tips: impl IntoIterator<Item = impl AsRef<OsStr>>,
+) -> anyhow::Result<(Vec<ObjectId>, Vec<ObjectId>)> {
+    let resolve = |name: &[u8]| -> anyhow::Result<ObjectId> {
+        let name = if name.is_empty() { &b"HEAD"[..] } else { name };
+        ObjectId::from_hex(name).or_else(|_| Ok(repo.find_reference(name.as_bstr())?.into_fully_peeled_id()?.detach()))
+    };
+    let mut included = Vec::new();
+    let mut hidden = Vec::new();
+    for tip in tips {
+        let tip = Vec::from_os_str_lossy(tip.as_ref());
+        if let Some(pos) = tip.find("...") {
+            let (one, two) = (resolve(&tip[..pos])?, resolve(&tip[pos + 3..])?);
+            hidden.extend(repo.merge_bases(one, two)?.into_iter().map(|id| id.detach()));
+            included.extend([one, two]);
+            continue;
+        }
+        match tip.find("..") {
+            Some(pos) => {
+                hidden.push(resolve(&tip[..pos])?);
+                included.push(resolve(&tip[pos + 2..])?);
+            }
+            None => match tip.strip_prefix(b"^") {
+                Some(tip) => hidden.push(resolve(tip)?),
+                None => included.push(resolve(&tip)?),
+            },
+        }
+    }
+    Ok((included, hidden))
+}
+
 fn print(stats: Statistics, format: OutputFormat, out: impl std::io::Write) -> anyhow::Result<()> {
     match format {
         OutputFormat::Human => human_output(stats, out).map_err(Into::into),
         #[cfg(feature = "serde1")]
-        OutputFormat::Json => serde_json::to_writer_pretty(out, &stats).map_err(Into::into),
+        OutputFormat::Json => crate::json::write(out, "pack-create", &stats).map_err(Into::into),
     }
 }
 
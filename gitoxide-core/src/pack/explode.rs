@@ -236,6 +236,7 @@ pub fn pack_or_pack_index(
             pack::index::traverse::Options {
                 traversal: algorithm,
                 thread_limit,
+                chunk_size: None,
                 check: check.into(),
                 make_pack_lookup_cache:             pack::cache::lru::StaticLinkedList::<64>::default,
             },
@@ -118,7 +118,7 @@ pub fn from_pack(
     match format {
         OutputFormat::Human => drop(human_output(out, res)),
         #[cfg(feature = "serde1")]
-        OutputFormat::Json => serde_json::to_writer_pretty(out, &res)?,
+        OutputFormat::Json => crate::json::write(out, "pack-index-create", &res)?,
     };
     Ok(())
 }
@@ -36,6 +36,30 @@ pub fn create(
     Ok(())
 }
 
+pub fn add(
+    index_paths: Vec<PathBuf>,
+    multi_index_path: PathBuf,
+    progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    object_hash: git::hash::Kind,
+) -> anyhow::Result<()> {
+    let file = git::odb::pack::multi_index::File::at(&multi_index_path)?;
+    let mut out = BufWriter::new(git::lock::File::acquire_to_update_resource(
+        multi_index_path,
+        git::lock::acquire::Fail::Immediately,
+        None,
+    )?);
+    file.write_with_additional_index_paths(
+        index_paths,
+        &mut out,
+        progress,
+        should_interrupt,
+        git::odb::pack::multi_index::write::Options { object_hash },
+    )?;
+    out.into_inner()?.commit()?;
+    Ok(())
+}
+
 #[cfg(feature = "serde1")]
 mod info {
     use std::path::PathBuf;
@@ -62,8 +86,9 @@ pub fn info(
     #[cfg(feature = "serde1")]
     {
         let file = git::odb::pack::multi_index::File::at(&multi_index_path)?;
-        serde_json::to_writer_pretty(
+        crate::json::write(
             out,
+            "multi-index-info",
             &info::Statistics {
                 path: multi_index_path,
                 num_objects: file.num_objects(),
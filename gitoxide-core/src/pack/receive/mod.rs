@@ -1,9 +1,10 @@
 use std::{
     io,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{atomic::AtomicBool, Arc},
 };
 
+use anyhow::anyhow;
 use git_repository::{
     hash::ObjectId,
     objs::bstr::{BString, ByteSlice},
@@ -19,6 +20,8 @@ use git_repository::{
 
 use crate::{remote::refs::JsonRef, OutputFormat};
 
+mod resume;
+
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
 pub struct Context<W> {
@@ -27,6 +30,9 @@ pub struct Context<W> {
     pub should_interrupt: Arc<AtomicBool>,
     pub out: W,
     pub object_hash: git_repository::hash::Kind,
+    /// If true, keep the partially received pack and the negotiation state in the output directory if receiving the pack
+    /// is interrupted, and use them to avoid receiving objects again that were received by previous attempts.
+    pub resume: bool,
 }
 
 struct CloneDelegate<W> {
@@ -35,6 +41,9 @@ struct CloneDelegate<W> {
     refs_directory: Option<PathBuf>,
     ref_filter: Option<&'static [&'static str]>,
     wanted_refs: Vec<BString>,
+    resume: Option<resume::Resume>,
+    /// The advertised refs if all objects they point to were received by previous attempts, so no pack had to be received.
+    refs_received_previously: Option<Vec<Ref>>,
 }
 static FILTER: &[&str] = &["HEAD", "refs/tags", "refs/heads"];
 
@@ -67,7 +76,7 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         version: transport::Protocol,
         server: &Capabilities,
         _features: &mut Vec<(&str, Option<&str>)>,
-        _refs: &[Ref],
+        refs: &[Ref],
     ) -> io::Result<Action> {
         if !self.wanted_refs.is_empty() && !remote_supports_ref_in_want(server) {
             return Err(io::Error::new(
@@ -78,6 +87,12 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         if version == transport::Protocol::V1 {
             self.ref_filter = Some(FILTER);
         }
+        if let Some(resume) = self.resume.as_ref() {
+            if self.wanted_refs.is_empty() && resume.has_all(&self.wanted_ids(refs)) {
+                self.refs_received_previously = Some(refs.to_owned());
+                return Ok(Action::Cancel);
+            }
+        }
         Ok(Action::Continue)
     }
 
@@ -88,26 +103,40 @@ impl<W> protocol::fetch::DelegateBlocking for CloneDelegate<W> {
         _previous_response: Option<&Response>,
     ) -> io::Result<Action> {
         if self.wanted_refs.is_empty() {
-            for r in refs {
-                let (path, id) = r.unpack();
-                match self.ref_filter {
-                    Some(ref_prefixes) => {
-                        if ref_prefixes.iter().any(|prefix| path.starts_with_str(prefix)) {
-                            arguments.want(id);
-                        }
-                    }
-                    None => arguments.want(id),
-                }
+            for id in &self.wanted_ids(refs) {
+                arguments.want(id);
             }
         } else {
             for r in &self.wanted_refs {
                 arguments.want_ref(r.as_ref())
             }
         }
+        if let Some(resume) = self.resume.as_ref() {
+            for id in &resume.haves {
+                arguments.have(id);
+            }
+        }
         Ok(Action::Cancel)
     }
 }
 
+impl<W> CloneDelegate<W> {
+    /// Return the ids of all `refs` we want to receive, unless specific refs are wanted by name.
+    fn wanted_ids(&self, refs: &[Ref]) -> Vec<ObjectId> {
+        if !self.wanted_refs.is_empty() {
+            return Vec::new();
+        }
+        refs.iter()
+            .map(Ref::unpack)
+            .filter(|(path, _id)| match self.ref_filter {
+                Some(ref_prefixes) => ref_prefixes.iter().any(|prefix| path.starts_with_str(prefix)),
+                None => true,
+            })
+            .map(|(_path, id)| *id)
+            .collect()
+    }
+}
+
 #[cfg(feature = "blocking-client")]
 mod blocking_io {
     use std::{io, io::BufRead, path::PathBuf};
@@ -119,7 +148,7 @@ mod blocking_io {
         Progress,
     };
 
-    use super::{receive_pack_blocking, CloneDelegate, Context};
+    use super::{finish_if_received_previously, prepare_resume, receive_pack_blocking, CloneDelegate, Context};
     use crate::net;
 
     impl<W: io::Write> protocol::fetch::Delegate for CloneDelegate<W> {
@@ -133,6 +162,7 @@ mod blocking_io {
             receive_pack_blocking(
                 self.directory.take(),
                 self.refs_directory.take(),
+                self.resume.as_ref(),
                 &mut self.ctx,
                 input,
                 progress,
@@ -147,25 +177,28 @@ mod blocking_io {
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
         wanted_refs: Vec<BString>,
-        progress: P,
+        mut progress: P,
         ctx: Context<W>,
     ) -> anyhow::Result<()> {
+        let resume = prepare_resume(url, directory.as_deref(), &mut progress, &ctx)?;
         let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
-        let delegate = CloneDelegate {
+        let mut delegate = CloneDelegate {
             ctx,
             directory,
             refs_directory,
             ref_filter: None,
             wanted_refs,
+            resume,
+            refs_received_previously: None,
         };
         protocol::fetch(
             transport,
-            delegate,
+            &mut delegate,
             protocol::credentials::helper,
             progress,
             protocol::FetchConnection::TerminateOnSuccessfulCompletion,
         )?;
-        Ok(())
+        finish_if_received_previously(delegate)
     }
 }
 
@@ -186,7 +219,10 @@ mod async_io {
         Progress,
     };
 
-    use super::{print, receive_pack_blocking, write_raw_refs, CloneDelegate, Context};
+    use super::{
+        finish_if_received_previously, prepare_resume, print, receive_pack_blocking, write_raw_refs, CloneDelegate,
+        Context,
+    };
     use crate::{net, OutputFormat};
 
     #[async_trait(?Send)]
@@ -201,6 +237,7 @@ mod async_io {
             receive_pack_blocking(
                 self.directory.take(),
                 self.refs_directory.take(),
+                self.resume.as_ref(),
                 &mut self.ctx,
                 futures_lite::io::BlockOn::new(input),
                 progress,
@@ -215,9 +252,10 @@ mod async_io {
         directory: Option<PathBuf>,
         refs_directory: Option<PathBuf>,
         wanted_refs: Vec<BString>,
-        progress: P,
+        mut progress: P,
         ctx: Context<W>,
     ) -> anyhow::Result<()> {
+        let resume = prepare_resume(url, directory.as_deref(), &mut progress, &ctx)?;
         let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into()).await?;
         let mut delegate = CloneDelegate {
             ctx,
@@ -225,18 +263,21 @@ mod async_io {
             refs_directory,
             ref_filter: None,
             wanted_refs,
+            resume,
+            refs_received_previously: None,
         };
-        blocking::unblock(move || {
+        let delegate = blocking::unblock(move || {
             futures_lite::future::block_on(protocol::fetch(
                 transport,
-                delegate,
+                &mut delegate,
                 protocol::credentials::helper,
                 progress,
                 protocol::FetchConnection::TerminateOnSuccessfulCompletion,
             ))
+            .map(|()| delegate)
         })
         .await?;
-        Ok(())
+        finish_if_received_previously(delegate)
     }
 }
 
@@ -319,34 +360,100 @@ fn write_raw_refs(refs: &[Ref], directory: PathBuf) -> std::io::Result<()> {
     Ok(())
 }
 
+fn write_options<W>(ctx: &Context<W>) -> pack::bundle::write::Options {
+    pack::bundle::write::Options {
+        thread_limit: ctx.thread_limit,
+        index_kind: pack::index::Version::V2,
+        iteration_mode: pack::data::input::Mode::Verify,
+        object_hash: ctx.object_hash,
+    }
+}
+
+/// Salvage what previous attempts to receive a pack into `directory` received, if resuming is enabled.
+fn prepare_resume<W>(
+    url: &str,
+    directory: Option<&Path>,
+    progress: &mut impl Progress,
+    ctx: &Context<W>,
+) -> anyhow::Result<Option<resume::Resume>> {
+    if !ctx.resume {
+        return Ok(None);
+    }
+    let directory = directory.ok_or_else(|| anyhow!("Resuming requires a directory to write the pack to"))?;
+    resume::Resume::prepare(
+        directory,
+        url,
+        progress.add_child("resume"),
+        &ctx.should_interrupt,
+        write_options(ctx),
+    )
+    .map(Some)
+}
+
+/// Write refs and print the pack received by previous attempts if nothing had to be received anymore.
+fn finish_if_received_previously<W: io::Write>(mut delegate: CloneDelegate<W>) -> anyhow::Result<()> {
+    let (refs, resume) = match (delegate.refs_received_previously.take(), delegate.resume.take()) {
+        (Some(refs), Some(resume)) => (refs, resume),
+        _ => return Ok(()),
+    };
+    let outcome = resume
+        .last_pack()
+        .expect("a pack was salvaged if all objects were received");
+    resume::State::remove(&resume.directory)?;
+    if let Some(directory) = delegate.refs_directory.take() {
+        write_raw_refs(&refs, directory)?;
+    }
+    print_outcome(&mut delegate.ctx, outcome, &refs)?;
+    Ok(())
+}
+
 fn receive_pack_blocking<W: io::Write>(
     mut directory: Option<PathBuf>,
     mut refs_directory: Option<PathBuf>,
+    resume: Option<&resume::Resume>,
     ctx: &mut Context<W>,
     input: impl io::BufRead,
     progress: impl Progress,
     refs: &[Ref],
 ) -> io::Result<()> {
-    let options = pack::bundle::write::Options {
-        thread_limit: ctx.thread_limit,
-        index_kind: pack::index::Version::V2,
-        iteration_mode: pack::data::input::Mode::Verify,
-        object_hash: ctx.object_hash,
-    };
-    let outcome =
-        pack::Bundle::write_to_directory(input, directory.take(), progress, &ctx.should_interrupt, None, options)
-            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    let options = write_options(ctx);
+    let outcome = match resume {
+        Some(resume) => pack::Bundle::write_to_directory(
+            io::BufReader::new(resume::Tee::new(&resume.directory, input)?),
+            directory.take(),
+            progress,
+            &ctx.should_interrupt,
+            resume.thin_pack_lookup()?,
+            options,
+        ),
+        None => {
+            pack::Bundle::write_to_directory(input, directory.take(), progress, &ctx.should_interrupt, None, options)
+        }
+    }
+    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    if let Some(resume) = resume {
+        resume::State::remove(&resume.directory)?;
+    }
 
     if let Some(directory) = refs_directory.take() {
         write_raw_refs(refs, directory)?;
     }
+    print_outcome(ctx, outcome, refs)
+}
 
+fn print_outcome<W: io::Write>(
+    ctx: &mut Context<W>,
+    outcome: pack::bundle::write::Outcome,
+    refs: &[Ref],
+) -> io::Result<()> {
     match ctx.format {
         OutputFormat::Human => drop(print(&mut ctx.out, outcome, refs)),
         #[cfg(feature = "serde1")]
-        OutputFormat::Json => {
-            serde_json::to_writer_pretty(&mut ctx.out, &JsonOutcome::from_outcome_and_refs(outcome, refs))?
-        }
+        OutputFormat::Json => crate::json::write(
+            &mut ctx.out,
+            "pack-receive",
+            &JsonOutcome::from_outcome_and_refs(outcome, refs),
+        )?,
     };
     Ok(())
 }
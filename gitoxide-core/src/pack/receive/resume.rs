@@ -0,0 +1,373 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use anyhow::{bail, Context as AnyhowContext};
+use git_repository::{
+    hash::ObjectId,
+    objs::{tree::EntryMode, CommitRefIter, Kind, TagRefIter, TreeRefIter},
+    odb::pack,
+    Progress,
+};
+
+type LookupFn = Box<dyn for<'a> FnMut(ObjectId, &'a mut Vec<u8>) -> Option<git_repository::objs::Data<'a>>>;
+
+const STATE_FILE: &str = "pack-receive.state";
+const PARTIAL_PACK_FILE: &str = "pack-receive.partial";
+
+/// The negotiation state of a previous attempt to receive a pack into a directory, kept until the pack was received completely.
+#[derive(Default, Debug, PartialEq, Eq)]
+pub struct State {
+    /// The URL the pack is received from.
+    pub url: String,
+    /// The file names of the indices of packs salvaged from partially received packs.
+    pub packs: Vec<String>,
+}
+
+impl State {
+    /// Read the state from `directory`, or return `None` if there is none.
+    pub fn read(directory: &Path) -> anyhow::Result<Option<Self>> {
+        let path = directory.join(STATE_FILE);
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut state = State::default();
+        for line in content.lines() {
+            match line.split_once(' ') {
+                Some(("url", url)) => state.url = url.into(),
+                Some(("pack", name)) => state.packs.push(name.into()),
+                _ => bail!("Invalid line in '{}': '{}'", path.display(), line),
+            }
+        }
+        Ok(Some(state))
+    }
+
+    /// Write this state to `directory`, replacing any previous state.
+    pub fn write(&self, directory: &Path) -> io::Result<()> {
+        let mut out = io::BufWriter::new(fs::File::create(directory.join(STATE_FILE))?);
+        writeln!(out, "url {}", self.url)?;
+        for name in &self.packs {
+            writeln!(out, "pack {}", name)?;
+        }
+        out.into_inner().map_err(|err| err.into_error())?.sync_all()
+    }
+
+    /// Remove the state and the partially received pack from `directory` once the pack was received completely.
+    pub fn remove(directory: &Path) -> io::Result<()> {
+        remove_if_present(&directory.join(STATE_FILE))?;
+        remove_if_present(&directory.join(PARTIAL_PACK_FILE))
+    }
+}
+
+/// Everything received by previous attempts to receive a pack into a directory, used to avoid receiving it again.
+pub struct Resume {
+    pub directory: PathBuf,
+    pub state: State,
+    /// The commits whose trees and ancestors are entirely contained in salvaged packs, without those which are ancestors of
+    /// other such commits. These can be sent as `have`s to the server.
+    pub haves: Vec<ObjectId>,
+    /// All commits whose trees and ancestors are entirely contained in salvaged packs.
+    complete: HashSet<ObjectId>,
+    bundles: Vec<pack::Bundle>,
+}
+
+impl Resume {
+    /// Salvage the pack partially received by a previous attempt to receive a pack from `url` into `directory`, and determine
+    /// which commits don't have to be received again.
+    pub fn prepare(
+        directory: &Path,
+        url: &str,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        options: pack::bundle::write::Options,
+    ) -> anyhow::Result<Self> {
+        let object_hash = options.object_hash;
+        let mut state = match State::read(directory)? {
+            Some(state) if state.url != url => bail!(
+                "The directory '{}' contains a partially received pack from '{}', not from '{}'",
+                directory.display(),
+                state.url,
+                url
+            ),
+            Some(state) => state,
+            None => State {
+                url: url.into(),
+                ..Default::default()
+            },
+        };
+        if let Some(outcome) = salvage_partial_pack(
+            directory,
+            &state.packs,
+            progress.add_child("salvage"),
+            should_interrupt,
+            options,
+        )? {
+            progress.info(format!(
+                "Salvaged {} objects received by a previous attempt",
+                outcome.index.num_objects
+            ));
+            state.packs.extend(
+                outcome
+                    .index_path
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|name| name.to_string_lossy().into_owned()),
+            );
+        }
+        state.write(directory)?;
+        remove_if_present(&directory.join(PARTIAL_PACK_FILE))?;
+
+        let bundles = state
+            .packs
+            .iter()
+            .map(|name| pack::Bundle::at(directory.join(name), object_hash))
+            .collect::<Result<Vec<_>, _>>()
+            .context("Could not open pack salvaged by a previous attempt")?;
+        let complete = complete_commits(&bundles)?;
+        let parents: HashSet<_> = complete.values().flatten().collect();
+        let haves = complete.keys().filter(|id| !parents.contains(id)).copied().collect();
+        Ok(Resume {
+            directory: directory.to_owned(),
+            state,
+            haves,
+            complete: complete.keys().copied().collect(),
+            bundles,
+        })
+    }
+
+    /// Return true if all objects in `ids` along with everything they reference were received by previous attempts.
+    pub fn has_all(&self, ids: &[ObjectId]) -> bool {
+        let mut buf = Vec::new();
+        !ids.is_empty()
+            && ids.iter().all(|id| {
+                let mut id = *id;
+                loop {
+                    if self.complete.contains(&id) {
+                        break true;
+                    }
+                    match find(&self.bundles, id, &mut buf) {
+                        Some(data) if data.kind == Kind::Tag => match TagRefIter::from_bytes(data.data).target_id() {
+                            Ok(target) => id = target,
+                            Err(_) => break false,
+                        },
+                        Some(data) if data.kind == Kind::Blob => break true,
+                        _ => break false,
+                    }
+                }
+            })
+    }
+
+    /// Return a way to lookup objects received by previous attempts, which the server may use as base objects when sending
+    /// a thin pack, or `None` if nothing was received.
+    pub fn thin_pack_lookup(&self) -> io::Result<Option<LookupFn>> {
+        let bundles = self
+            .bundles
+            .iter()
+            .map(|bundle| pack::Bundle::at(bundle.index.path(), bundle.index.object_hash()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Ok(lookup_in(bundles))
+    }
+
+    /// Return a description of the pack most recently salvaged, to be used if nothing had to be received anymore.
+    pub fn last_pack(&self) -> Option<pack::bundle::write::Outcome> {
+        self.bundles.last().map(|bundle| pack::bundle::write::Outcome {
+            index: pack::index::write::Outcome {
+                index_kind: bundle.index.version(),
+                index_hash: bundle.index.index_checksum(),
+                data_hash: bundle.index.pack_checksum(),
+                num_objects: bundle.index.num_objects(),
+            },
+            pack_kind: bundle.pack.version(),
+            object_hash: bundle.index.object_hash(),
+            index_path: Some(bundle.index.path().to_owned()),
+            data_path: Some(bundle.pack.path().to_owned()),
+        })
+    }
+}
+
+/// A reader which writes everything it reads into the partially received pack of a directory as well, so it can be salvaged
+/// if receiving the pack is interrupted.
+pub struct Tee<R> {
+    inner: R,
+    out: io::BufWriter<fs::File>,
+}
+
+impl<R> Tee<R> {
+    pub fn new(directory: &Path, inner: R) -> io::Result<Self> {
+        Ok(Tee {
+            inner,
+            out: io::BufWriter::new(fs::File::create(directory.join(PARTIAL_PACK_FILE))?),
+        })
+    }
+}
+
+impl<R: io::Read> io::Read for Tee<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.out.write_all(&buf[..bytes_read])?;
+        Ok(bytes_read)
+    }
+}
+
+/// Turn the partially received pack in `directory` into a valid pack with all objects that were received completely.
+///
+/// As the partial pack may be a thin pack whose base objects are in the `packs` salvaged previously, these are used to
+/// resolve them. If it still can't be salvaged, it's discarded and its objects will be received again.
+fn salvage_partial_pack(
+    directory: &Path,
+    packs: &[String],
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    options: pack::bundle::write::Options,
+) -> anyhow::Result<Option<pack::bundle::write::Outcome>> {
+    let file = match fs::File::open(directory.join(PARTIAL_PACK_FILE)) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    // Nothing but the header was received.
+    if file.metadata()?.len() <= 12 {
+        return Ok(None);
+    }
+    let bundles = packs
+        .iter()
+        .map(|name| pack::Bundle::at(directory.join(name), options.object_hash))
+        .collect::<Result<Vec<_>, _>>()
+        .context("Could not open pack salvaged by a previous attempt")?;
+    let outcome = match pack::Bundle::write_to_directory(
+        io::BufReader::new(file),
+        Some(directory),
+        progress.add_child("restore"),
+        should_interrupt,
+        lookup_in(bundles),
+        pack::bundle::write::Options {
+            iteration_mode: pack::data::input::Mode::Restore,
+            ..options
+        },
+    ) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            progress.fail(format!(
+                "Could not salvage the partially received pack and will receive it again: {}",
+                err
+            ));
+            return Ok(None);
+        }
+    };
+    Ok((outcome.index.num_objects != 0).then(|| outcome))
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Return a way to lookup objects in `bundles`, or `None` if there are none.
+fn lookup_in(bundles: Vec<pack::Bundle>) -> Option<LookupFn> {
+    if bundles.is_empty() {
+        return None;
+    }
+    Some(Box::new(move |id, buf| find(&bundles, id, buf)))
+}
+
+fn find<'a>(bundles: &[pack::Bundle], id: ObjectId, buf: &'a mut Vec<u8>) -> Option<git_repository::objs::Data<'a>> {
+    let bundle = bundles.iter().find(|bundle| bundle.index.lookup(id).is_some())?;
+    bundle
+        .find(id, buf, &mut pack::cache::Never)
+        .ok()
+        .flatten()
+        .map(|(data, _location)| data)
+}
+
+/// Return all commits in `bundles` along with their parents whose trees and ancestors are entirely contained in `bundles`.
+fn complete_commits(bundles: &[pack::Bundle]) -> anyhow::Result<HashMap<ObjectId, Vec<ObjectId>>> {
+    let mut commits = HashMap::new();
+    let mut buf = Vec::new();
+    for bundle in bundles {
+        for entry in bundle.index.iter() {
+            let data = match find(bundles, entry.oid, &mut buf) {
+                Some(data) if data.kind == Kind::Commit => data,
+                _ => continue,
+            };
+            let mut iter = CommitRefIter::from_bytes(data.data);
+            let tree = iter.tree_id()?;
+            commits.insert(entry.oid, (tree, iter.parent_ids().collect::<Vec<_>>()));
+        }
+    }
+
+    let mut complete = HashMap::<ObjectId, bool>::new();
+    let mut complete_trees = HashMap::new();
+    for id in commits.keys() {
+        let mut stack = vec![(*id, false)];
+        while let Some((id, parents_are_known)) = stack.pop() {
+            if complete.contains_key(&id) {
+                continue;
+            }
+            let (tree, parents) = match commits.get(&id) {
+                Some(commit) => commit,
+                None => {
+                    complete.insert(id, false);
+                    continue;
+                }
+            };
+            if parents_are_known {
+                let is_complete = parents.iter().all(|parent| complete[parent])
+                    && tree_is_complete(bundles, *tree, &mut complete_trees)?;
+                complete.insert(id, is_complete);
+            } else {
+                stack.push((id, true));
+                stack.extend(parents.iter().map(|parent| (*parent, false)));
+            }
+        }
+    }
+    Ok(commits
+        .into_iter()
+        .filter(|(id, _)| complete[id])
+        .map(|(id, (_tree, parents))| (id, parents))
+        .collect())
+}
+
+fn tree_is_complete(
+    bundles: &[pack::Bundle],
+    id: ObjectId,
+    complete_trees: &mut HashMap<ObjectId, bool>,
+) -> anyhow::Result<bool> {
+    if let Some(is_complete) = complete_trees.get(&id) {
+        return Ok(*is_complete);
+    }
+    let mut buf = Vec::new();
+    let children = match find(bundles, id, &mut buf) {
+        Some(data) if data.kind == Kind::Tree => TreeRefIter::from_bytes(data.data)
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.mode != EntryMode::Commit)
+            .map(|entry| (entry.oid.to_owned(), entry.mode == EntryMode::Tree))
+            .collect::<Vec<_>>(),
+        _ => {
+            complete_trees.insert(id, false);
+            return Ok(false);
+        }
+    };
+    let mut is_complete = true;
+    for (child, is_tree) in children {
+        is_complete = if is_tree {
+            tree_is_complete(bundles, child, complete_trees)?
+        } else {
+            bundles.iter().any(|bundle| bundle.index.lookup(child).is_some())
+        };
+        if !is_complete {
+            break;
+        }
+    }
+    complete_trees.insert(id, is_complete);
+    Ok(is_complete)
+}
@@ -8,7 +8,7 @@ use git_repository::{
     odb::{pack, pack::index},
     Progress,
 };
-pub use index::verify::Mode;
+pub use index::verify::{Mode, Sample};
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
 
 use crate::OutputFormat;
@@ -65,12 +65,12 @@ pub struct Context<'a, W1: io::Write, W2: io::Write> {
     pub object_hash: git::hash::Kind,
 }
 
-enum EitherCache<const SIZE: usize> {
+enum EitherCache {
     Left(pack::cache::Never),
-    Right(pack::cache::lru::StaticLinkedList<SIZE>),
+    Right(pack::cache::lru::MemoryCappedHashmap),
 }
 
-impl<const SIZE: usize> pack::cache::DecodeEntry for EitherCache<SIZE> {
+impl pack::cache::DecodeEntry for EitherCache {
     fn put(&mut self, pack_id: u32, offset: u64, data: &[u8], kind: object::Kind, compressed_size: usize) {
         match self {
             EitherCache::Left(v) => v.put(pack_id, offset, data, kind, compressed_size),
@@ -84,6 +84,13 @@ impl<const SIZE: usize> pack::cache::DecodeEntry for EitherCache<SIZE> {
             EitherCache::Right(v) => v.get(pack_id, offset, out),
         }
     }
+
+    fn statistics(&self) -> Option<pack::cache::Statistics> {
+        match self {
+            EitherCache::Left(v) => v.statistics(),
+            EitherCache::Right(v) => v.statistics(),
+        }
+    }
 }
 
 pub fn pack_or_pack_index<W1, W2>(
@@ -106,14 +113,18 @@ where
 {
     let path = path.as_ref();
     let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
-    const CACHE_SIZE: usize = 64;
-    let cache = || -> EitherCache<CACHE_SIZE> {
+    let cache = || -> EitherCache {
         if matches!(algorithm, Algorithm::LessMemory) {
             if output_statistics.is_some() {
                 // turn off acceleration as we need to see entire chains all the time
                 EitherCache::Left(pack::cache::Never)
             } else {
-                EitherCache::Right(pack::cache::lru::StaticLinkedList::<CACHE_SIZE>::default())
+                // start small and let the cache grow only if the pack benefits from it
+                let policy = pack::cache::lru::AdaptiveMemoryCap::default();
+                EitherCache::Right(pack::cache::lru::MemoryCappedHashmap::with_adaptive_memory_cap(
+                    policy.min_memory_cap_in_bytes,
+                    policy,
+                ))
             }
         } else {
             EitherCache::Left(pack::cache::Never)
@@ -176,7 +187,7 @@ where
                             }
                         },
                         #[cfg(feature = "serde1")]
-                        Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, &multi_index.index_names().iter().zip(res.pack_traverse_statistics).collect::<Vec<_>>())?,
+                        Some(OutputFormat::Json) => crate::json::write(out, "multi-index-verify", &multi_index.index_names().iter().zip(res.pack_traverse_statistics).collect::<Vec<_>>())?,
                         _ => {}
                     };
                     return Ok(())
@@ -194,7 +205,7 @@ where
         match output_statistics {
             Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
             #[cfg(feature = "serde1")]
-            Some(OutputFormat::Json) => serde_json::to_writer_pretty(out, stats)?,
+            Some(OutputFormat::Json) => crate::json::write(out, "pack-verify", stats)?,
             _ => {}
         };
     }
@@ -265,5 +276,18 @@ fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Statistic
         "pack overhead", (1.0 - (stats.total_compressed_entries_size as f64 / stats.pack_size as f64)) * 100.0,
         width = width
     )?;
+    if let Some(cache) = stats.pack_cache {
+        writeln!(out, "\npack cache")?;
+        #[rustfmt::skip]
+        writeln!(
+            out,
+            "\t{:<width$}: {}\n\t{:<width$}: {}\n\t{:<width$}: {:.2}%\n\t{:<width$}: {}",
+            "hits", cache.hits,
+            "misses", cache.misses,
+            "hit rate", cache.hit_ratio().unwrap_or_default() * 100.0,
+            "evictions", cache.evictions,
+            width = width
+        )?;
+    }
     Ok(())
 }
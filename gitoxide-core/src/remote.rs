@@ -1,8 +1,9 @@
 pub mod refs {
     use git_repository::{
+        bstr::{BString, ByteSlice},
         protocol,
         protocol::{
-            fetch::{Action, Arguments, Ref, Response},
+            fetch::{Action, Arguments, LsRefsAction, Ref, Response},
             transport,
         },
     };
@@ -13,12 +14,35 @@ pub mod refs {
 
     use std::io;
 
-    #[derive(Default)]
     struct LsRemotes {
+        ref_prefixes: Vec<String>,
         refs: Vec<Ref>,
     }
 
+    impl LsRemotes {
+        fn new(ref_prefixes: Vec<String>) -> Self {
+            LsRemotes {
+                ref_prefixes,
+                refs: Vec::new(),
+            }
+        }
+    }
+
     impl protocol::fetch::DelegateBlocking for LsRemotes {
+        fn prepare_ls_refs(
+            &mut self,
+            _server: &transport::client::Capabilities,
+            arguments: &mut Vec<BString>,
+            _features: &mut Vec<(&str, Option<&str>)>,
+        ) -> io::Result<LsRefsAction> {
+            arguments.extend(
+                self.ref_prefixes
+                    .iter()
+                    .map(|prefix| format!("ref-prefix {}", prefix).into()),
+            );
+            Ok(LsRefsAction::Continue)
+        }
+
         fn prepare_fetch(
             &mut self,
             _version: transport::Protocol,
@@ -26,7 +50,18 @@ pub mod refs {
             _features: &mut Vec<(&str, Option<&str>)>,
             refs: &[Ref],
         ) -> io::Result<Action> {
-            self.refs = refs.into();
+            // Servers speaking protocol V1 always advertise all refs, so we filter them ourselves.
+            self.refs = refs
+                .iter()
+                .filter(|r| {
+                    self.ref_prefixes.is_empty()
+                        || self
+                            .ref_prefixes
+                            .iter()
+                            .any(|prefix| r.unpack().0.starts_with_str(prefix))
+                })
+                .cloned()
+                .collect();
             Ok(Action::Cancel)
         }
 
@@ -70,19 +105,18 @@ pub mod refs {
 
         pub async fn list(
             protocol: Option<net::Protocol>,
-            url: &str,
+            url: &[u8],
             progress: impl Progress,
             ctx: Context<impl io::Write + Send + 'static>,
         ) -> anyhow::Result<()> {
-            let url = url.to_owned();
-            let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into()).await?;
+            let transport = net::connect(url, protocol.unwrap_or_default().into()).await?;
             blocking::unblock(
                 // `blocking` really needs a way to unblock futures, which is what it does internally anyway.
                 // Both fetch() needs unblocking as it executes blocking code within the future, and the other
                 // block does blocking IO because it's primarily a blocking codebase.
                 move || {
                     futures_lite::future::block_on(async move {
-                        let mut delegate = LsRemotes::default();
+                        let mut delegate = LsRemotes::new(ctx.ref_prefixes);
                         protocol::fetch(
                             transport,
                             &mut delegate,
@@ -95,8 +129,9 @@ pub mod refs {
                         match ctx.format {
                             OutputFormat::Human => drop(print(ctx.out, &delegate.refs)),
                             #[cfg(feature = "serde1")]
-                            OutputFormat::Json => serde_json::to_writer_pretty(
+                            OutputFormat::Json => crate::json::write(
                                 ctx.out,
+                                "remote-refs",
                                 &delegate.refs.into_iter().map(super::JsonRef::from).collect::<Vec<_>>(),
                             )?,
                         }
@@ -139,12 +174,12 @@ pub mod refs {
 
         pub fn list(
             protocol: Option<net::Protocol>,
-            url: &str,
+            url: &[u8],
             progress: impl Progress,
             ctx: Context<impl io::Write>,
         ) -> anyhow::Result<()> {
-            let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
-            let mut delegate = LsRemotes::default();
+            let transport = net::connect(url, protocol.unwrap_or_default().into())?;
+            let mut delegate = LsRemotes::new(ctx.ref_prefixes);
             protocol::fetch(
                 transport,
                 &mut delegate,
@@ -156,8 +191,9 @@ pub mod refs {
             match ctx.format {
                 OutputFormat::Human => drop(print(ctx.out, &delegate.refs)),
                 #[cfg(feature = "serde1")]
-                OutputFormat::Json => serde_json::to_writer_pretty(
+                OutputFormat::Json => crate::json::write(
                     ctx.out,
+                    "remote-refs",
                     &delegate.refs.into_iter().map(JsonRef::from).collect::<Vec<_>>(),
                 )?,
             };
@@ -170,6 +206,8 @@ pub mod refs {
     pub struct Context<W: io::Write> {
         pub thread_limit: Option<usize>,
         pub format: OutputFormat,
+        /// If not empty, only list references whose full name starts with one of these prefixes, like `refs/heads/`.
+        pub ref_prefixes: Vec<String>,
         pub out: W,
     }
 
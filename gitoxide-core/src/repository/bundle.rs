@@ -0,0 +1,356 @@
+use std::{
+    convert::TryInto,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use anyhow::{bail, Context as AnyhowContext};
+use git_repository as git;
+use git_repository::{
+    bstr::{BString, ByteSlice},
+    odb::{
+        pack,
+        pack::bundle_file::{self, Header, Prerequisite, Ref},
+    },
+    prelude::FindExt,
+    refs::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        Target,
+    },
+    ObjectId, Progress,
+};
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+}
+
+pub struct CreateOptions {
+    /// The version of the bundle file to write.
+    pub version: bundle_file::Version,
+    pub format: OutputFormat,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct CreateOutcome {
+    path: PathBuf,
+    num_objects: usize,
+    header: Header,
+}
+
+/// Create a bundle file at `path` from the repository at `repository` with all objects reachable from `revisions`, similar
+/// to `git bundle create`.
+///
+/// Each revision is a commit hash or reference name, optionally prefixed with `^` to exclude it along with its ancestors,
+/// or a range like `from..to`. All references among the revisions are stored in the bundle, and all excluded commits
+/// which are parents of included ones become prerequisites the receiver must have.
+pub fn create(
+    repository: PathBuf,
+    path: PathBuf,
+    revisions: Vec<String>,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    out: impl io::Write,
+    CreateOptions {
+        version,
+        format,
+        thread_limit,
+    }: CreateOptions,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let refs = revisions
+        .iter()
+        .flat_map(|revision| included_ref_names(revision))
+        .filter_map(|name| find_ref(&repo, name).transpose())
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    if refs.is_empty() {
+        bail!("Refusing to create a bundle without references, specify at least one reference name to include");
+    }
+    let (tips, hidden) = crate::pack::create::parse_tips(&repo, &revisions)?;
+
+    let options = bundle_file::write::Options {
+        version,
+        object_hash: repo.object_hash(),
+        thread_limit,
+        compression_level: repo.pack_compression_level(),
+    };
+    let mut handle = repo.into_sync().objects.into_shared_arc().to_cache_arc();
+    handle.prevent_pack_unload();
+    handle.ignore_replacements = true;
+    let mut file = BufWriter::new(git::lock::File::acquire_to_update_resource(
+        &path,
+        git::lock::acquire::Fail::Immediately,
+        None,
+    )?);
+    let bundle_file::write::Outcome { header, num_objects } = bundle_file::File::write_to(
+        &mut file,
+        refs,
+        tips,
+        hidden,
+        handle,
+        progress.add_child("creating bundle"),
+        should_interrupt,
+        options,
+    )?;
+    file.into_inner()?.commit()?;
+    progress.done(format!("Wrote {} objects to '{}'", num_objects, path.display()));
+
+    let outcome = CreateOutcome {
+        path,
+        num_objects,
+        header,
+    };
+    match format {
+        OutputFormat::Human => print_header(out, &outcome.header)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "bundle-create", &outcome)?,
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct VerifyOutcome<'a> {
+    header: &'a Header,
+    missing_prerequisites: Vec<&'a Prerequisite>,
+}
+
+/// Check that the bundle at `path` can be applied to the repository at `repository`, which is the case if all of its
+/// prerequisites are present, similar to `git bundle verify`.
+pub fn verify(repository: PathBuf, path: PathBuf, out: impl io::Write, format: OutputFormat) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let bundle = open(&path)?;
+    let outcome = VerifyOutcome {
+        header: &bundle.header,
+        missing_prerequisites: bundle.header.missing_prerequisites(&repo.objects),
+    };
+    match format {
+        OutputFormat::Human => print_verify(out, &outcome, &path)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "bundle-verify", &outcome)?,
+    }
+    if !outcome.missing_prerequisites.is_empty() {
+        bail!(
+            "The repository lacks {} prerequisite commit(s) of the bundle",
+            outcome.missing_prerequisites.len()
+        );
+    }
+    Ok(())
+}
+
+/// What happened to a reference stored in a bundle during [`unbundle()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+enum RefStatus {
+    /// The reference didn't exist and was created.
+    Created,
+    /// The reference existed with a different value and was overwritten.
+    Updated,
+    /// The reference already pointed to the same object.
+    Unchanged,
+    /// The reference isn't created as it's not below `refs/`, like `HEAD`.
+    Skipped,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct RefUpdate {
+    name: BString,
+    id: ObjectId,
+    status: RefStatus,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct UnbundleOutcome {
+    index: pack::bundle::write::Outcome,
+    refs: Vec<RefUpdate>,
+}
+
+/// Write the pack of the bundle at `path` into the repository at `repository` along with an index, and create all references
+/// stored in it.
+///
+/// Existing references are only overwritten if `force` is true.
+pub fn unbundle(
+    repository: PathBuf,
+    path: PathBuf,
+    force: bool,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    out: impl io::Write,
+    Options { format, thread_limit }: Options,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let bundle = open(&path)?;
+    let missing = bundle.header.missing_prerequisites(&repo.objects);
+    if !missing.is_empty() {
+        bail!(
+            "The repository lacks {} prerequisite commit(s) of the bundle, the first being {}",
+            missing.len(),
+            missing[0].id
+        );
+    }
+    if bundle.header.filter.is_some() {
+        bail!("Filtered bundles aren't supported as they would leave the repository incomplete");
+    }
+
+    let index = {
+        let objects = repo.objects.clone();
+        bundle
+            .write_pack_to_directory(
+                Some(repo.git_dir().join("objects").join("pack")),
+                progress.add_child("writing pack"),
+                should_interrupt,
+                Some(Box::new(move |oid, buf| objects.find(oid, buf).ok())),
+                pack::bundle::write::Options {
+                    thread_limit,
+                    iteration_mode: pack::data::input::Mode::Verify,
+                    index_kind: pack::index::Version::V2,
+                    object_hash: bundle.header.object_hash,
+                },
+            )
+            .with_context(|| format!("Failed to write the pack of bundle at '{}'", path.display()))?
+    };
+
+    // All references are updated in a single transaction so none of them is changed if one of them can't be.
+    let mut refs = Vec::new();
+    let mut edits = Vec::new();
+    let log_message = format!("unbundle: {}", path.display());
+    for Ref { id, name } in &bundle.header.refs {
+        let existing = repo
+            .try_find_reference(name.as_bstr())?
+            .and_then(|r| r.try_id().map(|id| id.detach()));
+        let status = if !name.starts_with(b"refs/") {
+            RefStatus::Skipped
+        } else if existing == Some(*id) {
+            RefStatus::Unchanged
+        } else {
+            if existing.is_some() && !force {
+                bail!(
+                    "Reference '{}' exists already and points to a different object, use --force to overwrite it",
+                    name
+                );
+            }
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: log_message.as_str().into(),
+                    },
+                    expected: match existing {
+                        Some(existing) => PreviousValue::MustExistAndMatch(Target::Peeled(existing)),
+                        None => PreviousValue::MustNotExist,
+                    },
+                    new: Target::Peeled(*id),
+                },
+                name: name.as_bstr().try_into()?,
+                deref: false,
+            });
+            if existing.is_some() {
+                RefStatus::Updated
+            } else {
+                RefStatus::Created
+            }
+        };
+        refs.push(RefUpdate {
+            name: name.clone(),
+            id: *id,
+            status,
+        });
+    }
+    repo.edit_references(edits, git::lock::acquire::Fail::Immediately, None)?;
+
+    let outcome = UnbundleOutcome { index, refs };
+    match format {
+        OutputFormat::Human => print_unbundle(out, &outcome)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "bundle-unbundle", &outcome)?,
+    }
+    Ok(())
+}
+
+fn open(path: &Path) -> anyhow::Result<bundle_file::File> {
+    bundle_file::File::at(path).with_context(|| format!("Could not read bundle at '{}'", path.display()))
+}
+
+/// Return the names of the revisions to include in `revision`, which are both sides of a symmetric difference, the end of
+/// a range, or nothing if it's excluded.
+fn included_ref_names(revision: &str) -> Vec<&str> {
+    fn or_head(name: &str) -> &str {
+        if name.is_empty() {
+            "HEAD"
+        } else {
+            name
+        }
+    }
+    if let Some(pos) = revision.find("...") {
+        return vec![or_head(&revision[..pos]), or_head(&revision[pos + 3..])];
+    }
+    match revision.find("..") {
+        Some(pos) => vec![or_head(&revision[pos + 2..])],
+        None if revision.starts_with('^') => Vec::new(),
+        None => vec![revision],
+    }
+}
+
+/// Find the reference `name` and return it with its full name, or `None` if `name` is an object id.
+fn find_ref(repo: &git::Repository, name: &str) -> anyhow::Result<Option<Ref>> {
+    if ObjectId::from_hex(name.as_bytes()).is_ok() {
+        return Ok(None);
+    }
+    let reference = repo.find_reference(name)?;
+    let name = reference.name().as_bstr().to_owned();
+    let id = match reference.target() {
+        // Keep annotated tags instead of peeling them to the commit they point to.
+        git::refs::TargetRef::Peeled(id) => id.to_owned(),
+        git::refs::TargetRef::Symbolic(_) => reference.into_fully_peeled_id()?.detach(),
+    };
+    Ok(Some(Ref { id, name }))
+}
+
+fn print_header(mut out: impl io::Write, header: &Header) -> io::Result<()> {
+    for prerequisite in &header.prerequisites {
+        writeln!(out, "-{} {}", prerequisite.id, prerequisite.comment)?;
+    }
+    for r in &header.refs {
+        writeln!(out, "{} {}", r.id, r.name)?;
+    }
+    Ok(())
+}
+
+fn print_verify(mut out: impl io::Write, outcome: &VerifyOutcome<'_>, path: &Path) -> io::Result<()> {
+    print_header(&mut out, outcome.header)?;
+    if outcome.missing_prerequisites.is_empty() {
+        writeln!(out, "The bundle at '{}' is okay", path.display())
+    } else {
+        writeln!(out, "The repository lacks these prerequisite commits:")?;
+        for prerequisite in &outcome.missing_prerequisites {
+            writeln!(out, "\t{} {}", prerequisite.id, prerequisite.comment)?;
+        }
+        Ok(())
+    }
+}
+
+fn print_unbundle(mut out: impl io::Write, outcome: &UnbundleOutcome) -> io::Result<()> {
+    writeln!(out, "pack: {}", outcome.index.index.data_hash)?;
+    for update in &outcome.refs {
+        let status = match update.status {
+            RefStatus::Created => "created",
+            RefStatus::Updated => "updated",
+            RefStatus::Unchanged => "unchanged",
+            RefStatus::Skipped => "skipped",
+        };
+        writeln!(out, "{} {} ({})", update.id, update.name, status)?;
+    }
+    Ok(())
+}
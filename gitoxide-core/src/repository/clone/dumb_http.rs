@@ -0,0 +1,268 @@
+//! Support for servers which only understand the 'dumb' HTTP protocol, that is they merely serve the files of a repository.
+//!
+//! Objects are retrieved by walking the object graph from the wanted tips, fetching each missing object as loose object
+//! or, if that fails, by downloading the pack containing it as advertised in `objects/info/packs`.
+use std::{
+    collections::{HashSet, VecDeque},
+    io::{BufRead, Read},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext};
+use git::{
+    objs,
+    odb::pack,
+    prelude::{Find, FindExt, Write},
+    protocol::{
+        fetch::Ref,
+        transport::{client::http, Protocol},
+    },
+    ObjectId, Progress,
+};
+use git_features::zlib;
+use git_repository as git;
+
+/// A remote repository served by a web server without support for the 'smart' HTTP protocol.
+pub struct Remote {
+    transport: http::Transport<http::Impl>,
+    /// Packs of the remote which weren't downloaded yet, or `None` if the list wasn't retrieved yet.
+    packs: Option<Vec<RemotePack>>,
+    /// The directory to store downloaded pack indices in.
+    index_directory: tempfile::TempDir,
+}
+
+struct RemotePack {
+    /// The name of the pack without extension, like `pack-<hex>`.
+    name: String,
+    /// The index of the pack, once it was downloaded.
+    index: Option<pack::index::File>,
+}
+
+impl Remote {
+    /// Prepare to access the repository at `url`, which is expected to be an `http` or `https` URL.
+    pub fn new(url: &str) -> anyhow::Result<Self> {
+        Ok(Remote {
+            transport: http::Transport::new(url, Protocol::V0),
+            packs: None,
+            index_directory: tempfile::tempdir()?,
+        })
+    }
+
+    /// Retrieve all references of the remote from `info/refs` along with its `HEAD`, if it can be resolved.
+    pub fn refs(&mut self) -> anyhow::Result<Vec<Ref>> {
+        let mut refs = Vec::<Ref>::new();
+        for line in self.file("info/refs")?.lines() {
+            let line = line?;
+            let (hex, path) = line
+                .split_once('\t')
+                .ok_or_else(|| anyhow!("Invalid line in 'info/refs': {:?}", line))?;
+            let object = ObjectId::from_hex(hex.as_bytes())?;
+            match path.strip_suffix("^{}") {
+                Some(peeled_path) => match refs.pop() {
+                    Some(Ref::Direct { path, object: tag }) if path == peeled_path => {
+                        refs.push(Ref::Peeled { path, tag, object })
+                    }
+                    _ => bail!("Peeled ref '{}' doesn't follow the ref it belongs to", path),
+                },
+                None => refs.push(Ref::Direct {
+                    path: path.into(),
+                    object,
+                }),
+            }
+        }
+
+        // A missing HEAD isn't fatal, it only means there is nothing to check out.
+        if let Ok(head) = self.file("HEAD") {
+            let head = head.lines().next().transpose()?.unwrap_or_default();
+            match head.strip_prefix("ref: ") {
+                Some(target) => {
+                    let object = refs.iter().find_map(|r| match r {
+                        Ref::Direct { path, object } if path == target => Some(*object),
+                        _ => None,
+                    });
+                    if let Some(object) = object {
+                        refs.insert(
+                            0,
+                            Ref::Symbolic {
+                                path: "HEAD".into(),
+                                target: target.into(),
+                                object,
+                            },
+                        );
+                    }
+                }
+                None => refs.insert(
+                    0,
+                    Ref::Direct {
+                        path: "HEAD".into(),
+                        object: ObjectId::from_hex(head.trim().as_bytes())?,
+                    },
+                ),
+            }
+        }
+        Ok(refs)
+    }
+
+    /// Download all objects reachable from `tips` which aren't yet in `repo`, returning the amount of reachable objects.
+    pub fn fetch(
+        &mut self,
+        repo: &git::Repository,
+        tips: impl IntoIterator<Item = ObjectId>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+    ) -> anyhow::Result<usize> {
+        let mut queue: VecDeque<_> = tips.into_iter().collect();
+        let mut seen = HashSet::new();
+        let mut buf = Vec::new();
+        progress.init(None, git::progress::count("objects"));
+        while let Some(id) = queue.pop_front() {
+            if !seen.insert(id) {
+                continue;
+            }
+            if should_interrupt.load(Ordering::Relaxed) {
+                bail!("Interrupted while receiving objects");
+            }
+            if !repo.objects.contains(id) {
+                self.download(repo, id, &mut progress, should_interrupt, thread_limit)?;
+            }
+            progress.inc();
+
+            let object = repo.objects.find(id, &mut buf)?;
+            match object.kind {
+                objs::Kind::Blob => {}
+                _ => match object.decode()? {
+                    objs::ObjectRef::Commit(commit) => {
+                        queue.push_back(commit.tree());
+                        queue.extend(commit.parents());
+                    }
+                    objs::ObjectRef::Tree(tree) => queue.extend(
+                        tree.entries
+                            .iter()
+                            // Submodule commits are part of other repositories.
+                            .filter(|entry| entry.mode != objs::tree::EntryMode::Commit)
+                            .map(|entry| entry.oid.to_owned()),
+                    ),
+                    objs::ObjectRef::Tag(tag) => queue.push_back(tag.target()),
+                    objs::ObjectRef::Blob(_) => unreachable!("blobs are skipped"),
+                },
+            }
+        }
+        Ok(seen.len())
+    }
+
+    fn download(
+        &mut self,
+        repo: &git::Repository,
+        id: ObjectId,
+        progress: &mut impl Progress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+    ) -> anyhow::Result<()> {
+        let hex = id.to_hex().to_string();
+        let loose_err = match self.loose_object(repo, id, &format!("objects/{}/{}", &hex[..2], &hex[2..])) {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+
+        let object_hash = repo.object_hash();
+        let pack_name = match self.find_pack(id, object_hash)? {
+            Some(name) => name,
+            None => {
+                return Err(loose_err.context(format!(
+                    "Object {} was neither available as loose object nor in any pack of the remote",
+                    id
+                )))
+            }
+        };
+        progress.info(format!("Downloading '{}'", pack_name));
+        let pack = self.file(&format!("objects/pack/{}.pack", pack_name))?;
+        pack::Bundle::write_to_directory(
+            pack,
+            Some(repo.git_dir().join("objects").join("pack")),
+            progress.add_child(pack_name),
+            should_interrupt,
+            None,
+            pack::bundle::write::Options {
+                thread_limit,
+                index_kind: pack::index::Version::V2,
+                iteration_mode: pack::data::input::Mode::Verify,
+                object_hash,
+            },
+        )?;
+        Ok(())
+    }
+
+    fn loose_object(&mut self, repo: &git::Repository, id: ObjectId, path: &str) -> anyhow::Result<()> {
+        let mut data = Vec::new();
+        zlib::stream::inflate::ReadBoxed {
+            inner: self.file(path)?,
+            decompressor: Box::new(zlib::Decompress::new(true)),
+        }
+        .read_to_end(&mut data)
+        .with_context(|| format!("Could not decompress loose object at '{}'", path))?;
+        let (kind, size, header_len) = objs::decode::loose_header(&data)?;
+        let object = &data[header_len..];
+        if object.len() != size {
+            bail!(
+                "Loose object {} was expected to have {} bytes, but got {}",
+                id,
+                size,
+                object.len()
+            );
+        }
+        let actual_id = repo.objects.write_buf(kind, object)?;
+        if actual_id != id {
+            bail!("Loose object {} was actually {} and may be corrupt", id, actual_id);
+        }
+        Ok(())
+    }
+
+    /// Return the name of the remote pack containing `id`, downloading pack indices as needed.
+    fn find_pack(&mut self, id: ObjectId, object_hash: git::hash::Kind) -> anyhow::Result<Option<String>> {
+        if self.packs.is_none() {
+            let mut packs = Vec::new();
+            for line in self.file("objects/info/packs")?.lines() {
+                let line = line?;
+                if let Some(name) = line.strip_prefix("P ").and_then(|file| file.strip_suffix(".pack")) {
+                    packs.push(RemotePack {
+                        name: name.to_owned(),
+                        index: None,
+                    });
+                }
+            }
+            self.packs = Some(packs);
+        }
+
+        let Remote {
+            transport,
+            packs,
+            index_directory,
+        } = self;
+        let packs = packs.as_mut().expect("initialized above");
+        let mut found = None;
+        for (pack_idx, pack) in packs.iter_mut().enumerate() {
+            if pack.index.is_none() {
+                let path = index_directory.path().join(format!("{}.idx", pack.name));
+                let mut index = file(transport, &format!("objects/pack/{}.idx", pack.name))?;
+                std::io::copy(&mut index, &mut std::fs::File::create(&path)?)?;
+                pack.index = Some(pack::index::File::at(&path, object_hash)?);
+            }
+            if pack.index.as_ref().map_or(false, |index| index.lookup(id).is_some()) {
+                found = Some(pack_idx);
+                break;
+            }
+        }
+        Ok(found.map(|pack_idx| packs.remove(pack_idx).name))
+    }
+
+    fn file(&mut self, path: &str) -> anyhow::Result<impl BufRead> {
+        file(&mut self.transport, path)
+    }
+}
+
+fn file(transport: &mut http::Transport<http::Impl>, path: &str) -> anyhow::Result<impl BufRead> {
+    transport
+        .get_file(path)
+        .with_context(|| format!("Could not retrieve '{}' from remote", path))
+}
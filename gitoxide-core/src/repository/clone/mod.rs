@@ -0,0 +1,396 @@
+use std::{
+    borrow::Cow,
+    convert::TryInto,
+    io,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use anyhow::{bail, Context as AnyhowContext};
+use git::{
+    bstr::ByteSlice,
+    odb::pack,
+    protocol,
+    protocol::{
+        fetch::{response::ShallowUpdate, Action, Arguments, Ref, Response},
+        transport,
+        transport::client::Capabilities,
+    },
+    refs::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+        FullName, Target,
+    },
+    ObjectId, Progress,
+};
+use git_config::file::GitConfig;
+use git_repository as git;
+
+use crate::net;
+
+#[cfg(feature = "blocking-http-transport")]
+mod dumb_http;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+pub struct Options {
+    /// If set, the repository will be created without a worktree, with all branches of the remote becoming local branches.
+    pub bare: bool,
+    /// If set, only this many commits of history will be fetched from each tip, creating a shallow repository.
+    pub depth: Option<usize>,
+    /// If true, tags will not be fetched.
+    pub no_tags: bool,
+    /// The protocol version to use when talking to the remote.
+    pub protocol: Option<net::Protocol>,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+}
+
+/// The name of the remote configured for the repository we clone from.
+const REMOTE_NAME: &str = "origin";
+
+/// Clone the repository at `url` into `directory`, or into a directory named after the last component of `url` if unset,
+/// and check out the remote's `HEAD` unless the clone is bare.
+pub fn clone(
+    url: &str,
+    directory: Option<PathBuf>,
+    mut progress: impl Progress,
+    mut out: impl io::Write,
+    should_interrupt: &AtomicBool,
+    Options {
+        bare,
+        depth,
+        no_tags,
+        protocol,
+        thread_limit,
+    }: Options,
+) -> anyhow::Result<()> {
+    let directory = match directory {
+        Some(directory) => directory,
+        None => directory_from_url(url, bare)
+            .with_context(|| format!("Could not derive a directory name from '{}' - please provide one", url))?,
+    };
+    if directory
+        .read_dir()
+        .map_or(false, |mut entries| entries.next().is_some())
+    {
+        bail!(
+            "Refusing to clone into existing non-empty directory '{}'",
+            directory.display()
+        )
+    }
+
+    let repo = if bare {
+        git::init_bare(&directory)
+    } else {
+        git::init(&directory)
+    }
+    .with_context(|| format!("Could not initialize repository at '{}'", directory.display()))?;
+
+    let mut delegate = CloneDelegate {
+        no_tags,
+        depth,
+        pack_directory: repo.git_dir().join("objects").join("pack"),
+        object_hash: repo.object_hash(),
+        thread_limit,
+        should_interrupt,
+        refs: Vec::new(),
+        shallows: Vec::new(),
+        pack: None,
+    };
+    let transport = net::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
+    let num_objects = match protocol::fetch(
+        transport,
+        &mut delegate,
+        protocol::credentials::helper,
+        progress.add_child("fetch"),
+        protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+    ) {
+        Ok(()) => delegate.pack.as_ref().map(|pack| pack.index.num_objects as usize),
+        #[cfg(feature = "blocking-http-transport")]
+        Err(protocol::fetch::Error::Transport(transport::client::Error::DumbHttpServer)) => {
+            if depth.is_some() {
+                bail!("The remote only supports the 'dumb' HTTP protocol, which doesn't support shallow clones")
+            }
+            progress.info("The remote only supports the 'dumb' HTTP protocol, retrieving objects one by one".into());
+            let mut remote = dumb_http::Remote::new(url)?;
+            delegate.select_refs(&remote.refs()?);
+            let tips: Vec<_> = delegate.refs.iter().map(|r| *r.unpack().1).collect();
+            Some(remote.fetch(&repo, tips, progress.add_child("fetch"), should_interrupt, thread_limit)?)
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let CloneDelegate { refs, shallows, .. } = delegate;
+
+    write_config(repo.git_dir(), url, bare, head_branch(&refs))?;
+    if !shallows.is_empty() {
+        repo.update_shallow_commits(shallows, None, git::lock::acquire::Fail::Immediately)?;
+    }
+
+    if refs.is_empty() {
+        progress.info("You appear to have cloned an empty repository.".into());
+        writeln!(out, "Cloned empty repository into '{}'", directory.display())?;
+        return Ok(());
+    }
+    repo.edit_references(
+        ref_edits(&refs, bare, url)?,
+        git::lock::acquire::Fail::Immediately,
+        None,
+    )?;
+
+    if let (Some(work_dir), Some(head_id)) = (repo.work_dir().map(ToOwned::to_owned), head_id(&refs)) {
+        let index_path = repo.git_dir().join("index");
+        super::worktree::checkout(
+            repo,
+            head_id,
+            &work_dir,
+            &index_path,
+            progress.add_child("checkout"),
+            should_interrupt,
+            thread_limit,
+        )?;
+    }
+
+    match num_objects {
+        Some(num_objects) => writeln!(out, "Cloned {} objects into '{}'", num_objects, directory.display())?,
+        None => writeln!(out, "Cloned into '{}'", directory.display())?,
+    }
+    Ok(())
+}
+
+struct CloneDelegate<'a> {
+    no_tags: bool,
+    depth: Option<usize>,
+    pack_directory: PathBuf,
+    object_hash: git::hash::Kind,
+    thread_limit: Option<usize>,
+    should_interrupt: &'a AtomicBool,
+
+    refs: Vec<Ref>,
+    shallows: Vec<ObjectId>,
+    pack: Option<pack::bundle::write::Outcome>,
+}
+
+impl CloneDelegate<'_> {
+    fn ref_prefixes(&self) -> &'static [&'static str] {
+        if self.no_tags {
+            &["HEAD", "refs/heads/"]
+        } else {
+            &["HEAD", "refs/heads/", "refs/tags/"]
+        }
+    }
+
+    /// Remember all `refs` we are interested in.
+    fn select_refs(&mut self, refs: &[Ref]) {
+        let prefixes = self.ref_prefixes();
+        self.refs = refs
+            .iter()
+            .filter(|r| {
+                let path = r.unpack().0;
+                prefixes.iter().any(|prefix| path.starts_with(prefix.as_bytes()))
+            })
+            .cloned()
+            .collect();
+    }
+}
+
+impl protocol::fetch::DelegateBlocking for CloneDelegate<'_> {
+    fn prepare_ls_refs(
+        &mut self,
+        server: &Capabilities,
+        arguments: &mut Vec<git::bstr::BString>,
+        _features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<protocol::fetch::LsRefsAction> {
+        if server.contains("ls-refs") {
+            arguments.extend(self.ref_prefixes().iter().map(|r| format!("ref-prefix {}", r).into()));
+        }
+        Ok(protocol::fetch::LsRefsAction::Continue)
+    }
+
+    fn prepare_fetch(
+        &mut self,
+        _version: transport::Protocol,
+        _server: &Capabilities,
+        _features: &mut Vec<(&str, Option<&str>)>,
+        refs: &[Ref],
+    ) -> io::Result<Action> {
+        self.select_refs(refs);
+        Ok(if self.refs.is_empty() {
+            Action::Cancel
+        } else {
+            Action::Continue
+        })
+    }
+
+    fn negotiate(
+        &mut self,
+        _refs: &[Ref],
+        arguments: &mut Arguments,
+        _previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        let mut wants: Vec<_> = self.refs.iter().map(|r| *r.unpack().1).collect();
+        wants.sort();
+        wants.dedup();
+        for id in wants {
+            arguments.want(id);
+        }
+        if let Some(depth) = self.depth {
+            if !arguments.can_use_deepen() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "The remote doesn't support shallow clones",
+                ));
+            }
+            arguments.deepen(depth);
+        }
+        Ok(Action::Cancel)
+    }
+}
+
+impl protocol::fetch::Delegate for CloneDelegate<'_> {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        previous_response: &Response,
+    ) -> io::Result<()> {
+        self.shallows = previous_response
+            .shallow_updates()
+            .iter()
+            .filter_map(|update| match update {
+                ShallowUpdate::Shallow(id) => Some(*id),
+                ShallowUpdate::Unshallow(_) => None,
+            })
+            .collect();
+        self.shallows.sort();
+
+        let options = pack::bundle::write::Options {
+            thread_limit: self.thread_limit,
+            index_kind: pack::index::Version::V2,
+            iteration_mode: pack::data::input::Mode::Verify,
+            object_hash: self.object_hash,
+        };
+        let outcome = pack::Bundle::write_to_directory(
+            input,
+            Some(self.pack_directory.clone()),
+            progress,
+            self.should_interrupt,
+            None,
+            options,
+        )
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        self.pack = Some(outcome);
+        Ok(())
+    }
+}
+
+/// Derive the directory to clone into from the last path component of `url`, similar to what `git clone` does.
+fn directory_from_url(url: &str, bare: bool) -> Option<PathBuf> {
+    let path = url.trim_end_matches('/');
+    let path = path.strip_suffix("/.git").unwrap_or(path);
+    let name = path.rsplit(|c: char| c == '/' || c == ':').next()?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    if name.is_empty() {
+        return None;
+    }
+    Some(if bare {
+        format!("{}.git", name).into()
+    } else {
+        name.into()
+    })
+}
+
+fn head_id(refs: &[Ref]) -> Option<ObjectId> {
+    refs.iter().find(|r| r.unpack().0 == "HEAD").map(|r| *r.unpack().1)
+}
+
+/// Return the full name of the branch the remote `HEAD` points to, if it's not detached.
+fn head_branch(refs: &[Ref]) -> Option<&str> {
+    refs.iter().find_map(|r| match r {
+        Ref::Symbolic { path, target, .. } if path == "HEAD" => target.to_str().ok(),
+        _ => None,
+    })
+}
+
+fn ref_edits(refs: &[Ref], bare: bool, url: &str) -> anyhow::Result<Vec<RefEdit>> {
+    let message = format!("clone: from {}", url);
+    let edit = |name: String, new: Target, expected: PreviousValue| -> anyhow::Result<RefEdit> {
+        let name: FullName = name.try_into()?;
+        Ok(RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.as_str().into(),
+                },
+                expected,
+                new,
+            },
+            name,
+            deref: false,
+        })
+    };
+    let remote_tracking_name = |branch: &str| -> String {
+        match branch.strip_prefix("refs/heads/") {
+            Some(name) if !bare => format!("refs/remotes/{}/{}", REMOTE_NAME, name),
+            _ => branch.to_owned(),
+        }
+    };
+
+    let mut edits = Vec::new();
+    for r in refs {
+        let (path, id) = r.unpack();
+        if path == "HEAD" {
+            continue;
+        }
+        let path = path.to_str()?;
+        edits.push(edit(
+            remote_tracking_name(path),
+            Target::Peeled(*id),
+            PreviousValue::MustNotExist,
+        )?);
+    }
+
+    match (head_branch(refs), head_id(refs)) {
+        (Some(branch), Some(id)) => {
+            if !bare {
+                edits.push(edit(branch.into(), Target::Peeled(id), PreviousValue::MustNotExist)?);
+                edits.push(edit(
+                    format!("refs/remotes/{}/HEAD", REMOTE_NAME),
+                    Target::Symbolic(remote_tracking_name(branch).try_into()?),
+                    PreviousValue::MustNotExist,
+                )?);
+            }
+            edits.push(edit(
+                "HEAD".into(),
+                Target::Symbolic(branch.try_into()?),
+                PreviousValue::Any,
+            )?);
+        }
+        (None, Some(id)) => edits.push(edit("HEAD".into(), Target::Peeled(id), PreviousValue::Any)?),
+        (_, None) => {}
+    }
+    Ok(edits)
+}
+
+fn write_config(git_dir: &Path, url: &str, bare: bool, head_branch: Option<&str>) -> anyhow::Result<()> {
+    let path = git_dir.join("config");
+    let mut config =
+        GitConfig::open(&path).with_context(|| format!("Could not read configuration at '{}'", path.display()))?;
+    let remote = Some(REMOTE_NAME);
+    config.append_raw_value("remote", remote, "url", Cow::Owned(url.as_bytes().to_owned()));
+    if !bare {
+        let refspec = format!("+refs/heads/*:refs/remotes/{}/*", REMOTE_NAME);
+        config.append_raw_value("remote", remote, "fetch", Cow::Owned(refspec.into_bytes()));
+        if let Some(branch) = head_branch {
+            let name = Some(branch.strip_prefix("refs/heads/").unwrap_or(branch));
+            config.append_raw_value("branch", name, "remote", Cow::Borrowed(REMOTE_NAME.as_bytes()));
+            config.append_raw_value("branch", name, "merge", Cow::Owned(branch.as_bytes().to_owned()));
+        }
+    }
+    config.write_to_path(&path, git::lock::acquire::Fail::Immediately)?;
+    Ok(())
+}
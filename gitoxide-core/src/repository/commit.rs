@@ -20,7 +20,7 @@ pub fn describe(
 ) -> Result<()> {
     let repo = git::open(repo)?.apply_environment();
     let commit = match rev_spec {
-        Some(spec) => repo.rev_parse(spec)?.object()?.try_into_commit()?,
+        Some(spec) => repo.find_commit(repo.rev_parse(spec)?)?,
         None => repo.head_commit()?,
     };
     use git::commit::describe::SelectRef::*;
@@ -0,0 +1,332 @@
+use std::{borrow::Cow, convert::TryFrom, io, path::PathBuf, str::FromStr};
+
+use anyhow::{anyhow, bail, Context};
+use git_config::{
+    file::GitConfig,
+    fs::{Config, ConfigSource},
+    values::{Boolean, Integer, Path},
+};
+use git_repository as git;
+
+use crate::OutputFormat;
+
+/// The configuration file to write values to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The system-wide configuration, usually `/etc/gitconfig`.
+    System,
+    /// The configuration of the current user, usually `~/.gitconfig`.
+    Global,
+    /// The configuration of the repository, `.git/config`.
+    Local,
+    /// The configuration of the current worktree, which is the repository configuration unless `extensions.worktreeConfig`
+    /// is enabled.
+    Worktree,
+}
+
+impl Scope {
+    pub fn variants() -> &'static [&'static str] {
+        &["system", "global", "local", "worktree"]
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "system" => Scope::System,
+            "global" => Scope::Global,
+            "local" => Scope::Local,
+            "worktree" => Scope::Worktree,
+            _ => return Err(format!("Invalid scope: '{}'", s)),
+        })
+    }
+}
+
+/// The type to interpret values as, which also normalizes them like `git config --type` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Either `true` or `false`.
+    Bool,
+    /// A decimal number, with suffixes like `k` or `m` being expanded.
+    Int,
+    /// An integer if the value is numeric, or a boolean otherwise.
+    BoolOrInt,
+    /// A path with `~/` and `~user/` being expanded.
+    Path,
+}
+
+impl ValueType {
+    pub fn variants() -> &'static [&'static str] {
+        &["bool", "int", "bool-or-int", "path"]
+    }
+}
+
+impl FromStr for ValueType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bool" => ValueType::Bool,
+            "int" => ValueType::Int,
+            "bool-or-int" => ValueType::BoolOrInt,
+            "path" => ValueType::Path,
+            _ => return Err(format!("Invalid value type: '{}'", s)),
+        })
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, show the file each value was read from, which is the included file for values defined in one, or
+    /// `command line:` for values set in the environment or by arguments.
+    pub show_origin: bool,
+    /// If true, show the scope of each value, like `global` or `local`.
+    pub show_scope: bool,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct Entry {
+    /// The name of the value like `remote.origin.url`, or `None` if only the value was asked for.
+    #[cfg_attr(feature = "serde1", serde(skip_serializing_if = "Option::is_none"))]
+    key: Option<String>,
+    /// The value, or `None` if the key is given without `=`, which is an implicit boolean `true`.
+    value: Option<String>,
+    #[cfg_attr(feature = "serde1", serde(skip_serializing_if = "Option::is_none"))]
+    scope: Option<&'static str>,
+    #[cfg_attr(feature = "serde1", serde(skip_serializing_if = "Option::is_none"))]
+    origin: Option<String>,
+}
+
+/// List all values of all configuration files git would read for the repository at `repository`, those with the lowest
+/// precedence first, like `git config --list` does.
+pub fn list(repository: PathBuf, out: impl io::Write, options: Options) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let config = Config::from_environment_and_repo(Some(repo.git_dir()), &[])?;
+    let entries = config
+        .entries_with_source()
+        .into_iter()
+        .map(|(header, key, value, source, path)| {
+            let key = format!(
+                "{}.{}{}",
+                header.name.0.to_ascii_lowercase(),
+                header
+                    .subsection_name
+                    .as_ref()
+                    .map(|name| format!("{}.", name))
+                    .unwrap_or_default(),
+                key.0.to_ascii_lowercase()
+            );
+            entry(Some(key), value.as_deref(), source, path, &options)
+        })
+        .collect();
+    write_entries(out, entries, options.format)
+}
+
+/// Print the value of `key`, like `remote.origin.url`, with the highest precedence, or all of its values if `all` is true.
+///
+/// If `value_type` is set, values are converted to the given type and fail if they can't be.
+pub fn get(
+    repository: PathBuf,
+    key: &str,
+    value_type: Option<ValueType>,
+    all: bool,
+    out: impl io::Write,
+    options: Options,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let config = Config::from_environment_and_repo(Some(repo.git_dir()), &[])?;
+    let (section, subsection, name) = parse_key(key)?;
+    let mut values = config.raw_multi_value_with_source(section, subsection, name);
+    if values.is_empty() {
+        bail!("The key '{}' is not set", key);
+    }
+    if !all {
+        values.drain(..values.len() - 1);
+    }
+    let entries = values
+        .into_iter()
+        .map(|(value, source, path)| {
+            let value = match value_type {
+                Some(value_type) => {
+                    normalize(value, value_type).with_context(|| format!("Invalid value for '{}'", key))?
+                }
+                None => value,
+            };
+            Ok(entry(None, Some(&value), source, path, &options))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    write_entries(out, entries, options.format)
+}
+
+/// Set `key`, like `remote.origin.url`, to `value` in the configuration file of `scope`, replacing its value
+/// if it exists already, like `git config` does.
+///
+/// Keys with multiple values in that file are only replaced if `replace_all` is true, which replaces all of them
+/// with `value`, like `git config --replace-all` does.
+///
+/// If `value_type` is set, the value is normalized according to the given type first, and fails if it can't be converted.
+/// Paths are stored as given though, as they are expanded when read.
+pub fn set(
+    repository: PathBuf,
+    key: &str,
+    value: &str,
+    scope: Scope,
+    value_type: Option<ValueType>,
+    replace_all: bool,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let (section, subsection, name) = parse_key(key)?;
+    let value = match value_type {
+        // Like git, store paths as given to expand `~` when reading them, not when writing them.
+        Some(ValueType::Path) | None => value.as_bytes().to_owned(),
+        Some(value_type) => normalize(Cow::Borrowed(value.as_bytes()), value_type)
+            .with_context(|| format!("Invalid value for '{}'", key))?
+            .into_owned(),
+    };
+
+    let source = match scope {
+        Scope::System => ConfigSource::System,
+        Scope::Global => ConfigSource::Global,
+        Scope::Local => ConfigSource::Repository,
+        Scope::Worktree => {
+            let config = Config::from_environment_and_repo(Some(repo.git_dir()), &[])?;
+            if config
+                .value::<Boolean<'_>>("extensions", None, "worktreeConfig")
+                .map_or(false, |v| v.to_bool())
+            {
+                ConfigSource::Worktree
+            } else {
+                ConfigSource::Repository
+            }
+        }
+    };
+    let path = source
+        .storage_location(Some(repo.git_dir()))
+        .ok_or_else(|| anyhow!("Could not determine the location of the {:?} configuration", scope))?;
+    let mut config = if path.is_file() {
+        GitConfig::open(&path).with_context(|| format!("Could not read configuration at '{}'", path.display()))?
+    } else {
+        GitConfig::new()
+    };
+    let num_values = config
+        .get_raw_multi_value(section, subsection, name)
+        .map_or(0, |values| values.len());
+    if num_values > 1 {
+        if !replace_all {
+            bail!(
+                "Cannot overwrite the {} values of '{}' with a single value, use --replace-all to replace all of them",
+                num_values,
+                key
+            );
+        }
+        config
+            .remove_raw_multi_value(section, subsection, name)
+            .expect("the values were just found");
+    }
+    if config.set_raw_value(section, subsection, name, value.clone()).is_err() {
+        config.append_raw_value(section, subsection, name, Cow::Owned(value));
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    config.write_to_path(&path, git::lock::acquire::Fail::Immediately)?;
+    Ok(())
+}
+
+/// Split `key` like `remote.origin.url` into its section, optional subsection and name.
+fn parse_key(key: &str) -> anyhow::Result<(&str, Option<&str>, &str)> {
+    let (section, rest) = key
+        .split_once('.')
+        .ok_or_else(|| anyhow!("Key '{}' does not contain a section", key))?;
+    let (subsection, name) = match rest.rsplit_once('.') {
+        Some((subsection, name)) => (Some(subsection), name),
+        None => (None, rest),
+    };
+    if section.is_empty() || name.is_empty() {
+        bail!("Key '{}' does not contain a section and a name", key);
+    }
+    Ok((section, subsection, name))
+}
+
+/// Convert `value` to `value_type` and return it in its canonical form.
+fn normalize(value: Cow<'_, [u8]>, value_type: ValueType) -> anyhow::Result<Cow<'_, [u8]>> {
+    let as_bool = |value: &[u8]| {
+        Boolean::try_from(value)
+            .map(|b| Cow::Borrowed(if b.to_bool() { &b"true"[..] } else { &b"false"[..] }))
+            .map_err(|_| anyhow!("'{}' is not a boolean", String::from_utf8_lossy(value)))
+    };
+    let as_int = |value: &[u8]| {
+        Integer::try_from(value)
+            .ok()
+            .and_then(|i| i.to_decimal())
+            .map(|i| Cow::Owned(i.to_string().into_bytes()))
+            .ok_or_else(|| anyhow!("'{}' is not an integer", String::from_utf8_lossy(value)))
+    };
+    Ok(match value_type {
+        ValueType::Bool => as_bool(value.as_ref())?,
+        ValueType::Int => as_int(value.as_ref())?,
+        ValueType::BoolOrInt => match as_int(value.as_ref()) {
+            Ok(int) => int,
+            Err(_) => as_bool(value.as_ref())?,
+        },
+        ValueType::Path => {
+            let path = Path::from(value).interpolate(None)?;
+            Cow::Owned(path.to_string_lossy().into_owned().into_bytes())
+        }
+    })
+}
+
+/// Create the entry for `value` of `key`, which was read from the file at `path` if it was read from a file at all.
+fn entry(
+    key: Option<String>,
+    value: Option<&[u8]>,
+    source: ConfigSource,
+    path: Option<&std::path::Path>,
+    options: &Options,
+) -> Entry {
+    Entry {
+        key,
+        value: value.map(|value| String::from_utf8_lossy(value).into_owned()),
+        scope: options.show_scope.then(|| scope_name(source)),
+        origin: options.show_origin.then(|| match path {
+            Some(path) => format!("file:{}", path.display()),
+            None => "command line:".into(),
+        }),
+    }
+}
+
+/// The name of the scope of `source` as shown by `git config --show-scope`.
+fn scope_name(source: ConfigSource) -> &'static str {
+    match source {
+        ConfigSource::System => "system",
+        ConfigSource::Global | ConfigSource::User => "global",
+        ConfigSource::Repository => "local",
+        ConfigSource::Worktree => "worktree",
+        ConfigSource::Env | ConfigSource::Cli => "command",
+    }
+}
+
+fn write_entries(mut out: impl io::Write, entries: Vec<Entry>, format: OutputFormat) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Human => {
+            for entry in entries {
+                if let Some(scope) = entry.scope {
+                    write!(out, "{}\t", scope)?;
+                }
+                if let Some(origin) = entry.origin {
+                    write!(out, "{}\t", origin)?;
+                }
+                match (entry.key, entry.value) {
+                    (Some(key), Some(value)) => writeln!(out, "{}={}", key, value)?,
+                    (Some(key), None) => writeln!(out, "{}", key)?,
+                    (None, value) => writeln!(out, "{}", value.unwrap_or_default())?,
+                }
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "config-entries", &entries)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,351 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::atomic::{self, AtomicBool},
+};
+
+use anyhow::bail;
+use git_repository as git;
+use git_repository::{
+    objs::{
+        fsck::{self, Severity},
+        Kind, ObjectRef,
+    },
+    odb::FindExt,
+    ObjectId, Progress,
+};
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+/// A problem found in the repository.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Finding {
+    pub severity: Severity,
+    /// A short identifier for the kind of problem, like `missing` or the name of an fsck issue like `treeNotSorted`.
+    pub kind: &'static str,
+    /// The hex id of the object the problem was found in, if it concerns a single object.
+    pub hex_id: Option<String>,
+    pub message: String,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct Outcome {
+    num_objects: usize,
+    num_reachable_objects: usize,
+    num_errors: usize,
+    num_warnings: usize,
+    findings: Vec<Finding>,
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// Findings less severe than this aren't reported.
+    pub min_severity: Severity,
+    /// If true, treat all warnings as errors like `git fsck --strict`.
+    pub strict: bool,
+    /// If false, don't check that all objects reachable from references and their reflogs are present.
+    pub connectivity: bool,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    pub algorithm: crate::pack::verify::Algorithm,
+}
+
+/// Check the object database of the repository at `repository`, similar to `git fsck`.
+///
+/// This verifies the integrity of all packs, their indices and multi-indices as well as loose objects, checks all objects
+/// semantically with severities configured by `fsck.<msg-id>`, and assures all objects reachable from references, their reflogs
+/// and the index are present. Objects which aren't reachable are reported with `Info` severity.
+pub fn fsck(
+    repository: PathBuf,
+    mut out: impl io::Write,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    Options {
+        format,
+        min_severity,
+        strict,
+        connectivity,
+        thread_limit,
+        algorithm,
+    }: Options,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let fsck_options = fsck_options(&repo, strict)?;
+    let mut findings = Vec::new();
+
+    if let Err(err) = repo.objects.store_ref().verify_integrity(
+        progress.add_child("integrity"),
+        should_interrupt,
+        git::odb::pack::index::verify::integrity::Options {
+            verify_mode: git::odb::pack::index::verify::Mode::HashCrc32,
+            traversal: algorithm.into(),
+            thread_limit,
+            make_pack_lookup_cache: || git::odb::pack::cache::Never,
+        },
+    ) {
+        findings.push(Finding::new(Severity::Error, "integrity", None, err.to_string()));
+    }
+
+    let mut kinds = HashMap::new();
+    {
+        let mut progress = progress.add_child("checking objects");
+        progress.init(None, git::progress::count("objects"));
+        let mut buf = Vec::new();
+        for id in repo.objects.iter()? {
+            let id = match id {
+                Ok(id) => id,
+                Err(err) => {
+                    findings.push(Finding::new(Severity::Error, "unreadable", None, err.to_string()));
+                    continue;
+                }
+            };
+            match repo.objects.find(id, &mut buf) {
+                Ok(data) => {
+                    kinds.insert(id, data.kind);
+                    check_object(id, data.kind, data.data, &fsck_options, &mut findings);
+                }
+                Err(err) => findings.push(Finding::new(Severity::Error, "unreadable", Some(id), err.to_string())),
+            }
+            progress.inc();
+            if should_interrupt.load(atomic::Ordering::Relaxed) {
+                bail!("Interrupted");
+            }
+        }
+    }
+
+    let num_reachable_objects = if connectivity {
+        let mut progress = progress.add_child("checking connectivity");
+        progress.init(Some(kinds.len()), git::progress::count("objects"));
+        check_connectivity(&repo, &kinds, &mut findings, &mut progress, should_interrupt)?
+    } else {
+        0
+    };
+
+    let outcome = Outcome {
+        num_objects: kinds.len(),
+        num_reachable_objects,
+        num_errors: findings.iter().filter(|f| f.severity == Severity::Error).count(),
+        num_warnings: findings.iter().filter(|f| f.severity == Severity::Warn).count(),
+        findings: findings.into_iter().filter(|f| f.severity <= min_severity).collect(),
+    };
+    match format {
+        OutputFormat::Human => {
+            for finding in &outcome.findings {
+                writeln!(
+                    out,
+                    "{} {}{}: {}",
+                    finding.severity.name(),
+                    finding.kind,
+                    finding.hex_id.as_ref().map(|id| format!(" {}", id)).unwrap_or_default(),
+                    finding.message
+                )?;
+            }
+            progress.info(format!(
+                "Checked {} objects ({} reachable) with {} error(s) and {} warning(s)",
+                outcome.num_objects, outcome.num_reachable_objects, outcome.num_errors, outcome.num_warnings
+            ));
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(&mut out, "fsck", &outcome)?,
+    }
+
+    if outcome.num_errors != 0 {
+        bail!("Found {} error(s) in the object database", outcome.num_errors);
+    }
+    Ok(())
+}
+
+impl Finding {
+    fn new(severity: Severity, kind: &'static str, id: Option<ObjectId>, message: String) -> Self {
+        Finding {
+            severity,
+            kind,
+            hex_id: id.map(|id| id.to_hex().to_string()),
+            message,
+        }
+    }
+}
+
+/// Read the severities of fsck issues from the `fsck.<msg-id>` configuration of `repo`.
+fn fsck_options(repo: &git::Repository, strict: bool) -> anyhow::Result<fsck::Options> {
+    let config = git_config::fs::Config::from_environment_and_repo(Some(repo.git_dir()), &[])?;
+    let mut opts = fsck::Options::default();
+    opts.object_hash = repo.object_hash();
+    opts.strict = strict;
+    for issue in fsck::Issue::ALL {
+        if let Some(severity) = config.value::<Cow<'_, [u8]>>("fsck", None, issue.name()) {
+            opts.set_from_config(issue.name(), &String::from_utf8_lossy(&severity))?;
+        }
+    }
+    Ok(opts)
+}
+
+/// Check the object `id` of `kind` with `data` semantically like `git fsck` does, and assure it can be decoded.
+fn check_object(id: ObjectId, kind: Kind, data: &[u8], opts: &fsck::Options, findings: &mut Vec<Finding>) {
+    let outcome = fsck::check(kind, data, opts);
+    let has_errors = outcome.has_errors();
+    findings.extend(
+        outcome
+            .findings
+            .into_iter()
+            .map(|f| Finding::new(f.severity, f.issue.name(), Some(id), f.message)),
+    );
+    if !has_errors {
+        if let Err(err) = ObjectRef::from_bytes(kind, data) {
+            findings.push(Finding::new(
+                Severity::Error,
+                "malformed",
+                Some(id),
+                format!("{} could not be decoded: {}", kind, err),
+            ));
+        }
+    }
+}
+
+/// Traverse all objects reachable from references, their reflogs and the index, reporting missing objects, those of
+/// an unexpected kind and those that weren't reached, and return the amount of objects that were reached.
+fn check_connectivity(
+    repo: &git::Repository,
+    kinds: &HashMap<ObjectId, Kind>,
+    findings: &mut Vec<Finding>,
+    progress: &mut impl Progress,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<usize> {
+    let mut tips = Vec::new();
+    let mut tip_names = HashMap::<_, Vec<_>>::new();
+    let mut add_tip = |id: ObjectId, name: String| {
+        tip_names.entry(id).or_default().push(name);
+        tips.push(id);
+    };
+    // Like git, consider objects mentioned in reflogs to be reachable as well.
+    for reference in repo.references()?.all()? {
+        let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+        if let git::refs::TargetRef::Peeled(id) = reference.target() {
+            add_tip(id.to_owned(), format!("reference '{}'", reference.name().as_bstr()));
+        }
+        for (id, name) in reflog_tips(&reference, findings)? {
+            add_tip(id, name);
+        }
+    }
+    if let Ok(head) = repo.find_reference("HEAD") {
+        if let git::refs::TargetRef::Peeled(id) = head.target() {
+            add_tip(id.to_owned(), "detached HEAD".into());
+        }
+        for (id, name) in reflog_tips(&head, findings)? {
+            add_tip(id, name);
+        }
+    }
+    if let Some(index) = repo.load_index().transpose()? {
+        for entry in index.entries() {
+            if entry.mode == git::index::entry::Mode::COMMIT {
+                continue;
+            }
+            let name = format!("index entry '{}'", entry.path(&index.state));
+            match kinds.get(&entry.id) {
+                Some(kind) if *kind != Kind::Blob => findings.push(Finding::new(
+                    Severity::Error,
+                    "wrong-kind",
+                    Some(entry.id),
+                    format!("{} referenced by {} should be a blob", kind, name),
+                )),
+                _ => add_tip(entry.id, name),
+            }
+        }
+    }
+
+    // Objects that exist but can't be decoded were reported already and are listed in `outcome.undecodable`.
+    // Once interrupted, objects aren't looked up anymore to drain the traversal quickly, and its outcome is discarded.
+    let progress = RefCell::new(progress);
+    let is_interrupted = || should_interrupt.load(atomic::Ordering::Relaxed);
+    let mut check = git::traverse::connectivity::Check::new(
+        |id, buf| {
+            if is_interrupted() {
+                return None;
+            }
+            progress.borrow_mut().inc();
+            repo.objects.find(id, buf).ok()
+        },
+        |id| {
+            if is_interrupted() {
+                return false;
+            }
+            progress.borrow_mut().inc();
+            kinds.contains_key(id)
+        },
+    );
+    let outcome = check.objects_reachable_from(tips);
+    if is_interrupted() {
+        bail!("Interrupted");
+    }
+    for missing in outcome.missing {
+        let referrers = match missing.referenced_by {
+            Some(id) => vec![format!("{} {}", kinds[&id], id)],
+            None => tip_names.remove(&missing.id).unwrap_or_default(),
+        };
+        for referrer in referrers {
+            findings.push(Finding::new(
+                Severity::Error,
+                "missing",
+                Some(missing.id),
+                format!("object referenced by {} is missing", referrer),
+            ));
+        }
+    }
+    for unexpected in outcome.unexpected_kind {
+        findings.push(Finding::new(
+            Severity::Error,
+            "wrong-kind",
+            Some(unexpected.id),
+            format!(
+                "{} referenced by {} {} should be a {}",
+                unexpected.actual, kinds[&unexpected.referenced_by], unexpected.referenced_by, unexpected.expected
+            ),
+        ));
+    }
+    let mut unreachable: Vec<_> = kinds.keys().filter(|id| !check.has_visited(id)).collect();
+    unreachable.sort();
+    for id in unreachable {
+        findings.push(Finding::new(
+            Severity::Info,
+            "unreachable",
+            Some(*id),
+            format!("{} is not reachable from any reference, reflog or the index", kinds[id]),
+        ));
+    }
+    Ok(outcome.objects)
+}
+
+/// Return all objects mentioned in the reflog of `reference` along with a description of the log entry,
+/// reporting log entries that can't be decoded.
+fn reflog_tips(reference: &git::Reference<'_>, findings: &mut Vec<Finding>) -> anyhow::Result<Vec<(ObjectId, String)>> {
+    let mut tips = Vec::new();
+    let name = reference.name().as_bstr();
+    let mut log_iter = reference.log_iter();
+    if let Some(log) = log_iter.all()? {
+        for (line_number, line) in log.enumerate() {
+            match line {
+                Ok(line) => {
+                    for id in [line.previous_oid(), line.new_oid()] {
+                        if !id.is_null() {
+                            tips.push((id, format!("reflog entry {} of '{}'", line_number + 1, name)));
+                        }
+                    }
+                }
+                Err(err) => findings.push(Finding::new(
+                    Severity::Warn,
+                    "invalid-reflog",
+                    None,
+                    format!("Reflog of '{}' could not be decoded: {}", name, err),
+                )),
+            }
+        }
+    }
+    Ok(tips)
+}
@@ -0,0 +1,262 @@
+use std::{borrow::Cow, io, path::PathBuf};
+
+use anyhow::bail;
+use git::{
+    actor::{Signature, Time},
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    objs::{commit::MessageRef, CommitRef},
+    odb::FindExt,
+    traverse::commit::{ancestors, path::Filter, Ancestors, Sorting},
+    ObjectId,
+};
+use git_repository as git;
+
+use crate::OutputFormat;
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, print only the abbreviated commit hash and the subject of each commit.
+    pub oneline: bool,
+    /// If set, print each commit according to this template using placeholders like `%H` or `%an`, similar to `git log --format`.
+    pub template: Option<String>,
+    /// If set, stop after printing this amount of commits.
+    pub max_count: Option<usize>,
+    /// If not empty, only print commits which change any of these paths compared to their first parent, relative to the
+    /// current directory or to the root of the repository if it is bare.
+    pub pathspecs: Vec<PathBuf>,
+}
+
+/// Print the commits reachable from `revisions` in the repository at `repository`, newest first.
+///
+/// Each revision is a commit hash or reference name, optionally prefixed with `^` to exclude it along with its ancestors,
+/// or a range like `from..to`. If no commit to start from is given, `HEAD` is used.
+pub fn log(
+    repository: PathBuf,
+    revisions: Vec<String>,
+    mut out: impl io::Write,
+    Options {
+        format,
+        oneline,
+        template,
+        max_count,
+        pathspecs,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output format is supported at the moment");
+    }
+
+    let repo = git::open(repository)?.apply_environment();
+    let (mut tips, hidden) = crate::pack::create::parse_tips(&repo, &revisions)?;
+    if tips.is_empty() {
+        tips = crate::pack::create::parse_tips(&repo, Some("HEAD"))?.0;
+    }
+    let commit_graph_dir = repo.objects.store_ref().path().join("info");
+    let mut filters = pathspecs
+        .into_iter()
+        .map(|path| {
+            let filter = Filter::new(to_git_path(&repo, path)?);
+            Ok(match git_commitgraph::Graph::from_info_dir(&commit_graph_dir) {
+                Ok(graph) => filter.with_commit_graph(graph),
+                Err(_) => filter,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mailmap = repo.load_mailmap();
+    #[cfg(feature = "local-time-support")]
+    let now = Signature::now_local_or_utc("", "").time;
+    #[cfg(not(feature = "local-time-support"))]
+    let now = Signature::now_utc("", "").time;
+
+    let commits = Ancestors::new(tips, ancestors::State::default(), |oid, buf| {
+        repo.objects.find_commit_iter(oid, buf)
+    })
+    .sorting(Sorting::ByCommitTimeNewestFirst)?
+    .with_hidden(hidden);
+
+    let mut buf = Vec::new();
+    let mut num_printed = 0;
+    for id in commits {
+        if max_count.map_or(false, |max| num_printed >= max) {
+            break;
+        }
+        let id = id?;
+        if !filters.is_empty() && !changes_paths(&repo, &id, &mut filters)? {
+            continue;
+        }
+        let commit = repo.objects.find_commit(id, &mut buf)?;
+
+        let commit = Commit {
+            id,
+            // Like git, show the message as is if it can't be decoded from its declared encoding.
+            message: commit.message_utf8().unwrap_or(Cow::Borrowed(commit.message)),
+            author: mailmap.resolve(commit.author()),
+            committer: mailmap.resolve(commit.committer()),
+            now,
+            commit,
+        };
+        match (&template, oneline) {
+            (Some(template), _) => {
+                write_formatted(&mut out, template, &commit)?;
+                writeln!(out)?;
+            }
+            (None, true) => writeln!(
+                out,
+                "{} {}",
+                commit.id.to_hex_with_len(7),
+                MessageRef::from_bytes(&commit.message).summary()
+            )?,
+            (None, false) => {
+                if num_printed != 0 {
+                    writeln!(out)?;
+                }
+                write_medium(&mut out, &commit)?;
+            }
+        }
+        num_printed += 1;
+    }
+    Ok(())
+}
+
+struct Commit<'a> {
+    id: ObjectId,
+    commit: CommitRef<'a>,
+    /// The message decoded into UTF-8.
+    message: Cow<'a, BStr>,
+    /// The author after applying the mailmap.
+    author: Signature,
+    /// The committer after applying the mailmap.
+    committer: Signature,
+    /// The time to compute relative dates to.
+    now: Time,
+}
+
+/// Write `commit` like `git log` does by default.
+fn write_medium(out: &mut impl io::Write, commit: &Commit<'_>) -> io::Result<()> {
+    writeln!(out, "commit {}", commit.id)?;
+    if commit.commit.parents.len() > 1 {
+        write!(out, "Merge:")?;
+        for parent in commit.commit.parents() {
+            write!(out, " {}", parent.to_hex_with_len(7))?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "Author: {} <{}>", commit.author.name, commit.author.email)?;
+    writeln!(
+        out,
+        "Date:   {}\n",
+        git_date::format(&commit.author.time, git_date::Format::Default)
+    )?;
+    for line in commit.message.trim_end().lines() {
+        writeln!(out, "    {}", line.as_bstr())?;
+    }
+    Ok(())
+}
+
+/// Write `commit` according to `template`, substituting all known placeholders and leaving all others as is.
+fn write_formatted(out: &mut impl io::Write, template: &str, commit: &Commit<'_>) -> io::Result<()> {
+    let mut rest = template;
+    while let Some(pos) = rest.find('%') {
+        out.write_all(rest[..pos].as_bytes())?;
+        rest = &rest[pos + 1..];
+        match placeholder(rest, commit) {
+            Some((len, value)) => {
+                out.write_all(&value)?;
+                rest = &rest[len..];
+            }
+            None => out.write_all(b"%")?,
+        }
+    }
+    out.write_all(rest.as_bytes())
+}
+
+/// Return the length of the placeholder at the beginning of `spec` along with its value for `commit`,
+/// or `None` if there is no known placeholder.
+fn placeholder(spec: &str, commit: &Commit<'_>) -> Option<(usize, BString)> {
+    let c = &commit.commit;
+    let value: BString = match spec.get(..1)? {
+        "H" => commit.id.to_string().into(),
+        "h" => commit.id.to_hex_with_len(7).to_string().into(),
+        "T" => c.tree().to_string().into(),
+        "t" => c.tree().to_hex_with_len(7).to_string().into(),
+        "P" => join(c.parents().map(|id| id.to_string())),
+        "p" => join(c.parents().map(|id| id.to_hex_with_len(7).to_string())),
+        "s" => MessageRef::from_bytes(&commit.message).summary().into_owned(),
+        "b" => MessageRef::from_bytes(&commit.message)
+            .body
+            .unwrap_or_default()
+            .to_owned(),
+        "B" => commit.message.clone().into_owned(),
+        "n" => "\n".into(),
+        "%" => "%".into(),
+        "a" => return signature_placeholder(spec, c.author(), &commit.author, &commit.now),
+        "c" => return signature_placeholder(spec, c.committer(), &commit.committer, &commit.now),
+        _ => return None,
+    };
+    Some((1, value))
+}
+
+/// Handle placeholders like `%an` for the `raw` signature as stored in the commit, or its `mapped` version after
+/// applying the mailmap.
+fn signature_placeholder(
+    spec: &str,
+    raw: git::actor::SignatureRef<'_>,
+    mapped: &Signature,
+    now: &Time,
+) -> Option<(usize, BString)> {
+    let date = |format| -> BString { git_date::format(&raw.time, format).into() };
+    let value = match spec.get(1..2)? {
+        "n" => raw.name.to_owned(),
+        "e" => raw.email.to_owned(),
+        "N" => mapped.name.clone(),
+        "E" => mapped.email.clone(),
+        "d" => date(git_date::Format::Default),
+        "i" => date(git_date::Format::Iso8601),
+        "I" => date(git_date::Format::Iso8601Strict),
+        "D" => date(git_date::Format::Rfc2822),
+        "s" => date(git_date::Format::Short),
+        "t" => date(git_date::Format::Unix),
+        "r" => date(git_date::Format::Relative { now: *now }),
+        _ => return None,
+    };
+    Some((2, value))
+}
+
+fn join(items: impl Iterator<Item = String>) -> BString {
+    let mut out = BString::default();
+    for (idx, item) in items.enumerate() {
+        if idx != 0 {
+            out.push_byte(b' ');
+        }
+        out.push_str(item);
+    }
+    out
+}
+
+/// Convert `path` as given on the command-line into a path as stored in trees, relative to the current directory if it is
+/// inside of the work tree, or relative to the root of the repository otherwise.
+fn to_git_path(repo: &git::Repository, path: PathBuf) -> anyhow::Result<BString> {
+    if repo.work_dir().is_some() {
+        let path = match repo.prefix() {
+            Ok(prefix) => prefix.join(path),
+            Err(git::worktree_path::Error::OutsideWorkTree { .. }) => path,
+            Err(err) => return Err(err.into()),
+        };
+        return Ok(repo.to_git_path(path)?);
+    }
+    let path = git_features::path::into_bytes(path).map_err(|_| anyhow::anyhow!("Path is not valid UTF-8"))?;
+    Ok(git_features::path::convert::to_unix_separators(path)
+        .into_owned()
+        .into())
+}
+
+/// Return true if the commit with `id` changes any of the paths the `filters` were created for compared to its first
+/// parent, or if it has no parents and any of these paths exist.
+fn changes_paths(repo: &git::Repository, id: &git::hash::oid, filters: &mut [Filter]) -> anyhow::Result<bool> {
+    for filter in filters {
+        if filter.is_changed_by(id, |oid, buf| repo.objects.find(oid, buf))? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -45,8 +45,9 @@ pub fn entries(
     }
 
     #[cfg(feature = "serde1")]
-    serde_json::to_writer_pretty(
+    crate::json::write(
         out,
+        "mailmap-entries",
         &mailmap.entries().into_iter().map(JsonEntry::from).collect::<Vec<_>>(),
     )?;
 
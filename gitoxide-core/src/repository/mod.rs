@@ -3,16 +3,41 @@ use std::path::PathBuf;
 use anyhow::{Context as AnyhowContext, Result};
 
 pub fn init(directory: Option<PathBuf>) -> Result<git_repository::Path> {
-    git_repository::path::create::into(directory.unwrap_or_default(), git_repository::Kind::WorkTree)
-        .with_context(|| "Repository initialization failed")
+    git_repository::path::create::into(
+        directory.unwrap_or_default(),
+        git_repository::path::create::Kind::WithWorkTree,
+    )
+    .with_context(|| "Repository initialization failed")
 }
 
+#[cfg(feature = "blocking-client")]
+pub mod clone;
+#[cfg(feature = "blocking-client")]
+pub use clone::clone;
+
+pub mod status;
+pub use status::show as status;
+
 pub mod tree;
 
 pub mod commit;
 
+pub mod log;
+pub use log::log;
+
 pub mod verify;
 
+pub mod fsck;
+pub use fsck::fsck;
+
 pub mod odb;
 
 pub mod mailmap;
+
+pub mod remote;
+
+pub mod config;
+
+pub mod bundle;
+
+pub mod worktree;
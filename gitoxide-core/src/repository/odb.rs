@@ -1,7 +1,8 @@
-use std::{io, path::PathBuf};
+use std::{io, io::BufRead, path::PathBuf};
 
 use anyhow::bail;
 use git_repository as git;
+use git_repository::{bstr::ByteSlice, odb::Find, refs::file::ReferenceExt};
 
 use crate::OutputFormat;
 
@@ -43,7 +44,7 @@ pub fn info(
 
     #[cfg(feature = "serde1")]
     {
-        serde_json::to_writer_pretty(out, &stats)?;
+        crate::json::write(out, "odb-stats", &stats)?;
     }
 
     Ok(())
@@ -63,3 +64,81 @@ pub fn entries(repository: PathBuf, format: OutputFormat, mut out: impl io::Writ
 
     Ok(())
 }
+
+pub mod cat {
+    /// What to print for each object.
+    #[derive(Debug, Eq, PartialEq, Clone, Copy)]
+    pub enum Mode {
+        /// Print `<id> <kind> <size>` followed by the object data, like `git cat-file --batch`.
+        Contents,
+        /// Print only `<id> <kind> <size>`, like `git cat-file --batch-check`.
+        Headers,
+    }
+}
+
+/// Read object names or references from `input`, one per line, and write each object to `out` framed like `git cat-file --batch`
+/// does, or only its header if `mode` is [`cat::Mode::Headers`].
+///
+/// Names that can't be resolved to an existing object are reported as `<name> missing`.
+pub fn cat(
+    repository: PathBuf,
+    format: OutputFormat,
+    mode: cat::Mode,
+    mut input: impl BufRead,
+    mut out: impl io::Write,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output format is supported at the moment");
+    }
+
+    let repo = git::open(repository)?.apply_environment();
+    let mut line = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let name = line.trim_end_with(|c| c == '\n' || c == '\r').as_bstr();
+        let data = match resolve(&repo, name)? {
+            Some(id) => repo.objects.try_find(id, &mut buf)?.map(|data| (id, data)),
+            None => None,
+        };
+        match data {
+            Some((id, data)) => {
+                writeln!(out, "{} {} {}", id, data.kind, data.data.len())?;
+                if mode == cat::Mode::Contents {
+                    out.write_all(data.data)?;
+                    writeln!(out)?;
+                }
+            }
+            None => writeln!(out, "{} missing", name)?,
+        }
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn resolve(repo: &git::Repository, name: &git::bstr::BStr) -> anyhow::Result<Option<git::ObjectId>> {
+    if let Ok(id) = git::ObjectId::from_hex(name) {
+        return Ok(Some(id));
+    }
+    match repo.try_find_reference(name) {
+        Ok(Some(reference)) => {
+            // Like `git cat-file`, follow symbolic refs but don't peel tags, as the tag object itself is requested.
+            const MAX_REF_DEPTH: usize = 5;
+            let mut reference = reference.detach();
+            for _ in 0..MAX_REF_DEPTH {
+                match reference.follow(&repo.refs) {
+                    Some(next) => reference = next?,
+                    None => return Ok(reference.target.try_id().map(ToOwned::to_owned)),
+                }
+            }
+            bail!("Reference '{}' is nested more than {} levels deep", name, MAX_REF_DEPTH)
+        }
+        Ok(None) | Err(git::reference::find::Error::Find(git::refs::file::find::Error::RefnameValidation(_))) => {
+            Ok(None)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,118 @@
+use std::{borrow::Cow, convert::TryFrom, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context};
+use git_config::file::GitConfig;
+use git_repository as git;
+use git_repository::{
+    bstr::BString,
+    refs::transaction::{Change, PreviousValue, RefEdit, RefLog},
+};
+
+/// Return the url to fetch from for the remote named `name` in the repository at `repository`, with all `insteadOf`
+/// rewrites applied.
+pub fn fetch_url(repository: PathBuf, name: &str) -> anyhow::Result<BString> {
+    let repo = git::open(repository)?.apply_environment();
+    let url = repo
+        .remote_url(name, git::remote::Direction::Fetch)?
+        .ok_or_else(|| anyhow!("No url configured for remote '{}'", name))?;
+    Ok(url.to_bstring()?)
+}
+
+/// Add a remote named `name` fetching all branches from `url` into `refs/remotes/<name>/`, like `git remote add` does.
+pub fn add(repository: PathBuf, name: &str, url: &str) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let (path, mut config) = open_config(&repo)?;
+    validate_name(name)?;
+    if config.section_mut("remote", Some(name)).is_ok() {
+        bail!("Remote '{}' already exists", name);
+    }
+    let mut section = config.new_section("remote", Some(Cow::Owned(name.to_owned())));
+    section.push("url".into(), Cow::Owned(url.as_bytes().to_owned()));
+    section.push(
+        "fetch".into(),
+        Cow::Owned(format!("+refs/heads/*:refs/remotes/{}/*", name).into_bytes()),
+    );
+    config.write_to_path(path, git::lock::acquire::Fail::Immediately)?;
+    Ok(())
+}
+
+/// Remove the remote named `name` along with its remote-tracking branches and the upstream configuration of all branches
+/// tracking it, like `git remote remove` does.
+pub fn remove(repository: PathBuf, name: &str) -> anyhow::Result<()> {
+    let repo = git::open(repository)?.apply_environment();
+    let (path, mut config) = open_config(&repo)?;
+    if config.remove_section("remote", Some(name)).is_none() {
+        bail!("No such remote: '{}'", name);
+    }
+    while config.remove_section("remote", Some(name)).is_some() {}
+
+    let tracking_branches: Vec<String> = config
+        .sections_by_name_with_header("branch")
+        .into_iter()
+        .filter_map(|(header, body)| {
+            let branch = header.subsection_name.as_ref()?;
+            if body.value(&"remote".into())?.as_ref() == name.as_bytes() {
+                Some(branch.to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+    for branch in &tracking_branches {
+        for key in &["remote", "merge"] {
+            config.remove_raw_value("branch", Some(branch.as_str()), key).ok();
+        }
+    }
+    config.write_to_path(path, git::lock::acquire::Fail::Immediately)?;
+
+    let edits = repo
+        .references()?
+        .prefixed(format!("refs/remotes/{}/", name))?
+        .map(|r| {
+            r.map(|r| RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(r.inner.target),
+                    log: RefLog::AndReference,
+                },
+                name: r.inner.name,
+                deref: false,
+            })
+            .map_err(|err| anyhow!(err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    repo.edit_references(edits, git::lock::acquire::Fail::Immediately, None)?;
+    Ok(())
+}
+
+/// Set the url of the remote named `name` to `url`, or its url to push to if `push` is true, like `git remote set-url` does.
+/// All previously configured urls of the same kind are replaced.
+pub fn set_url(repository: PathBuf, name: &str, url: &str, push: bool) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let (path, mut config) = open_config(&repo)?;
+    let key = if push { "pushurl" } else { "url" };
+    if config.section_mut("remote", Some(name)).is_err() {
+        bail!("No such remote: '{}'", name);
+    }
+    config.remove_raw_multi_value("remote", Some(name), key).ok();
+    config
+        .section_mut("remote", Some(name))
+        .expect("checked above")
+        .push(key.into(), Cow::Owned(url.as_bytes().to_owned()));
+    config.write_to_path(path, git::lock::acquire::Fail::Immediately)?;
+    Ok(())
+}
+
+/// Open the configuration file of `repo` for modification.
+fn open_config(repo: &git::Repository) -> anyhow::Result<(PathBuf, GitConfig<'static>)> {
+    let path = repo.git_dir().join("config");
+    let config =
+        GitConfig::open(&path).with_context(|| format!("Could not read configuration at '{}'", path.display()))?;
+    Ok((path, config))
+}
+
+/// Assure the remote `name` can be used in the names of its remote-tracking branches, just like git does.
+fn validate_name(name: &str) -> anyhow::Result<()> {
+    git::refs::FullName::try_from(format!("refs/remotes/{}/HEAD", name))
+        .map(|_| ())
+        .map_err(|_| anyhow!("'{}' is not a valid remote name", name))
+}
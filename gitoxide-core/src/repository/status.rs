@@ -0,0 +1,377 @@
+use std::{collections::BTreeMap, io, path::PathBuf, str::FromStr, time::Instant};
+
+use anyhow::{bail, Context as AnyhowContext};
+use git::{
+    bstr::BStr,
+    index::entry::Mode,
+    odb::FindExt,
+    worktree::status::{Change, Staged},
+    ObjectId,
+};
+use git_repository as git;
+
+use crate::OutputFormat;
+
+/// How to find untracked files, mirroring `git status --untracked-files`.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum UntrackedFiles {
+    No,
+    Normal,
+    All,
+}
+
+impl UntrackedFiles {
+    pub fn variants() -> &'static [&'static str] {
+        &["no", "normal", "all"]
+    }
+}
+
+impl FromStr for UntrackedFiles {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "no" => UntrackedFiles::No,
+            "normal" => UntrackedFiles::Normal,
+            "all" => UntrackedFiles::All,
+            _ => return Err(format!("Invalid untracked files mode: '{}'", s)),
+        })
+    }
+}
+
+impl From<UntrackedFiles> for git::worktree::status::Untracked {
+    fn from(v: UntrackedFiles) -> Self {
+        use git::worktree::status::Untracked;
+        match v {
+            UntrackedFiles::No => Untracked::No,
+            UntrackedFiles::Normal => Untracked::Normal,
+            UntrackedFiles::All => Untracked::All,
+        }
+    }
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// If true, print the same format as `git status --porcelain=v2`, instead of a human readable one.
+    pub porcelain: bool,
+    pub untracked: UntrackedFiles,
+    /// If true, timings and performance counters will be written to `err`.
+    pub statistics: bool,
+}
+
+/// Print the differences between `HEAD` and the index, the index and the worktree, and untracked files of the repository
+/// at `repository`.
+pub fn show(
+    repository: PathBuf,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    Options {
+        format,
+        porcelain,
+        untracked,
+        statistics,
+    }: Options,
+) -> anyhow::Result<()> {
+    if format != OutputFormat::Human {
+        bail!("Only human output format is supported at the moment");
+    }
+
+    let repo = git::open(repository)?.apply_environment();
+    let work_dir = repo
+        .work_dir()
+        .context("Status can only be obtained for repositories with a worktree")?
+        .to_owned();
+    let git_dir = repo.git_dir().to_owned();
+
+    let start = Instant::now();
+    let index_path = git_dir.join("index");
+    let index = if index_path.is_file() {
+        git::index::File::at(
+            index_path,
+            git::index::decode::Options {
+                object_hash: repo.object_hash(),
+                ..Default::default()
+            },
+        )?
+        .state
+    } else {
+        empty_index(repo.object_hash())?
+    };
+    let read_index = start.elapsed();
+
+    let start = Instant::now();
+    let mut head = repo.head()?;
+    let head_name = head.referent_name().map(|name| name.shorten().to_owned());
+    let head_id = head.peel_to_id_in_place().transpose()?.map(|id| id.detach());
+    let head_tree = match head_id {
+        Some(id) => {
+            let mut buf = Vec::new();
+            let tree_id = repo.objects.find_commit_iter(id, &mut buf)?.tree_id()?;
+            git::index::State::from_tree(&tree_id, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?
+        }
+        None => empty_index(repo.object_hash())?,
+    };
+    let read_head_tree = start.elapsed();
+
+    let start = Instant::now();
+    let staged = git::worktree::status::tree_index(&head_tree, &index);
+    let compare_head_tree = start.elapsed();
+
+    let start = Instant::now();
+    let excludes = match std::fs::read(git_dir.join("info").join("exclude")) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let worktree = git::worktree::status::index_worktree(
+        &index,
+        &work_dir,
+        &excludes,
+        git::worktree::status::Options {
+            fs: git::worktree::fs::Capabilities {
+                ignore_case: repo.ignore_case(),
+                ..git::worktree::fs::Capabilities::probe(&git_dir)
+            },
+            untracked: untracked.into(),
+        },
+    )?;
+    let compare_worktree = start.elapsed();
+
+    let mut paths = BTreeMap::<&BStr, Item<'_>>::new();
+    for change in &staged {
+        paths.entry(change.path()).or_default().staged = Some(change);
+    }
+    for (idx, change) in &worktree.changes {
+        let entry = &index.entries()[*idx];
+        paths.entry(entry.path(&index)).or_default().worktree = Some((entry, *change));
+    }
+    let mut conflicts = BTreeMap::<&BStr, [Option<&git::index::Entry>; 3]>::new();
+    for entry in index.entries().iter().filter(|e| e.stage() != 0) {
+        conflicts.entry(entry.path(&index)).or_default()[entry.stage() as usize - 1] = Some(entry);
+    }
+
+    if porcelain {
+        let null = ObjectId::null(repo.object_hash());
+        for (path, item) in &paths {
+            write_porcelain_v2_change(&mut out, path, item, null)?;
+        }
+        for (path, stages) in &conflicts {
+            let mode_and_id = |entry: &Option<&git::index::Entry>| match entry {
+                Some(entry) => (entry.mode, entry.id),
+                None => (Mode::empty(), null),
+            };
+            let (m1, h1) = mode_and_id(&stages[0]);
+            let (m2, h2) = mode_and_id(&stages[1]);
+            let (m3, h3) = mode_and_id(&stages[2]);
+            writeln!(
+                out,
+                "u {} N... {:06o} {:06o} {:06o} {:06o} {} {} {} {}",
+                conflict_status(stages).0,
+                m1.bits(),
+                m2.bits(),
+                m3.bits(),
+                worktree_mode(&repo, path).bits(),
+                h1,
+                h2,
+                h3,
+                path
+            )?;
+        }
+        for path in &worktree.untracked {
+            writeln!(out, "? {}", path)?;
+        }
+    } else {
+        match (head_name, head_id) {
+            (Some(name), Some(_)) => writeln!(out, "On branch {}", name)?,
+            (Some(name), None) => writeln!(out, "On branch {}\n\nNo commits yet", name)?,
+            (None, Some(id)) => writeln!(out, "HEAD detached at {}", id.to_hex_with_len(7))?,
+            (None, None) => {}
+        }
+        let mut section = |title: &str, lines: Vec<String>| -> io::Result<()> {
+            if !lines.is_empty() {
+                writeln!(out, "\n{}", title)?;
+                for line in lines {
+                    writeln!(out, "\t{}", line)?;
+                }
+            }
+            Ok(())
+        };
+        section(
+            "Changes to be committed:",
+            staged
+                .iter()
+                .map(|change| {
+                    let status = match change {
+                        Staged::Added { .. } => "new file:",
+                        Staged::Deleted { .. } => "deleted:",
+                        Staged::Modified {
+                            previous_mode, mode, ..
+                        } if is_type_change(*previous_mode, *mode) => "typechange:",
+                        Staged::Modified { .. } => "modified:",
+                    };
+                    format!("{:<12}{}", status, change.path())
+                })
+                .collect(),
+        )?;
+        section(
+            "Unmerged paths:",
+            conflicts
+                .iter()
+                .map(|(path, stages)| format!("{:<17}{}", conflict_status(stages).1, path))
+                .collect(),
+        )?;
+        section(
+            "Changes not staged for commit:",
+            worktree
+                .changes
+                .iter()
+                .map(|(idx, change)| {
+                    let status = match change {
+                        Change::Removed => "deleted:",
+                        Change::Modified { .. } => "modified:",
+                        Change::TypeChange { .. } => "typechange:",
+                    };
+                    format!("{:<12}{}", status, index.entries()[*idx].path(&index))
+                })
+                .collect(),
+        )?;
+        section(
+            "Untracked files:",
+            worktree.untracked.iter().map(ToString::to_string).collect(),
+        )?;
+        if paths.is_empty() && conflicts.is_empty() && worktree.untracked.is_empty() {
+            writeln!(out, "nothing to commit, working tree clean")?;
+        }
+    }
+
+    if statistics {
+        let stats = worktree.statistics;
+        writeln!(
+            err,
+            "read index with {} entries in {:?}, HEAD tree with {} entries in {:?}",
+            index.entries().len(),
+            read_index,
+            head_tree.entries().len(),
+            read_head_tree
+        )?;
+        writeln!(err, "compared HEAD tree to index in {:?}", compare_head_tree)?;
+        writeln!(err, "compared index to worktree in {:?}", compare_worktree)?;
+        writeln!(
+            err,
+            "entries: {} compared, {} skipped, {} unchanged by stat, {} hashed",
+            stats.entries, stats.entries_skipped, stats.entries_unchanged_by_stat, stats.files_hashed
+        )?;
+        writeln!(
+            err,
+            "untracked: {} directories read, {} ignore files read, {} paths ignored",
+            stats.directories_read, stats.ignore_files_read, stats.ignored
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Item<'a> {
+    staged: Option<&'a Staged>,
+    worktree: Option<(&'a git::index::Entry, Change)>,
+}
+
+fn write_porcelain_v2_change(
+    out: &mut impl io::Write,
+    path: &BStr,
+    Item { staged, worktree }: &Item<'_>,
+    null: ObjectId,
+) -> io::Result<()> {
+    let (x, head_mode, head_id, index_mode, index_id) = match staged {
+        Some(Staged::Added { mode, id, .. }) => ('A', Mode::empty(), null, *mode, *id),
+        Some(Staged::Deleted { mode, id, .. }) => ('D', *mode, *id, Mode::empty(), null),
+        Some(Staged::Modified {
+            previous_mode,
+            previous_id,
+            mode,
+            id,
+            ..
+        }) => (
+            if is_type_change(*previous_mode, *mode) {
+                'T'
+            } else {
+                'M'
+            },
+            *previous_mode,
+            *previous_id,
+            *mode,
+            *id,
+        ),
+        None => {
+            let (entry, _) = worktree.expect("either staged or worktree changes are present");
+            ('.', entry.mode, entry.id, entry.mode, entry.id)
+        }
+    };
+    let (y, worktree_mode) = match worktree {
+        Some((_, Change::Removed)) => ('D', Mode::empty()),
+        Some((_, Change::Modified { mode })) => ('M', *mode),
+        Some((_, Change::TypeChange { mode })) => ('T', *mode),
+        None => ('.', index_mode),
+    };
+    writeln!(
+        out,
+        "1 {}{} N... {:06o} {:06o} {:06o} {} {} {}",
+        x,
+        y,
+        head_mode.bits(),
+        index_mode.bits(),
+        worktree_mode.bits(),
+        head_id,
+        index_id,
+        path
+    )
+}
+
+fn is_type_change(previous: Mode, current: Mode) -> bool {
+    (previous == Mode::SYMLINK) != (current == Mode::SYMLINK)
+}
+
+/// Return the short and long status of a conflict with the given `stages`, like `git status` does.
+fn conflict_status(stages: &[Option<&git::index::Entry>; 3]) -> (&'static str, &'static str) {
+    match (stages[0].is_some(), stages[1].is_some(), stages[2].is_some()) {
+        (true, true, true) => ("UU", "both modified:"),
+        (false, true, true) => ("AA", "both added:"),
+        (true, true, false) => ("UD", "deleted by them:"),
+        (true, false, true) => ("DU", "deleted by us:"),
+        (false, true, false) => ("AU", "added by us:"),
+        (false, false, true) => ("UA", "added by them:"),
+        (true, false, false) | (false, false, false) => ("DD", "both deleted:"),
+    }
+}
+
+fn worktree_mode(repo: &git::Repository, path: &BStr) -> Mode {
+    let meta = match repo
+        .to_workdir_path(path)
+        .and_then(|path| std::fs::symlink_metadata(path).ok())
+    {
+        Some(meta) => meta,
+        None => return Mode::empty(),
+    };
+    if meta.file_type().is_symlink() {
+        return Mode::SYMLINK;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if meta.mode() & 0o100 == 0o100 {
+            return Mode::FILE_EXECUTABLE;
+        }
+    }
+    Mode::FILE
+}
+
+/// An index without entries, for use when there is no index file or no `HEAD` commit yet.
+fn empty_index(object_hash: git::hash::Kind) -> anyhow::Result<git::index::State> {
+    Ok(git::index::State::from_tree(
+        &ObjectId::empty_tree(object_hash),
+        |_, buf| {
+            buf.clear();
+            Some(git::objs::TreeRefIter::from_bytes(buf))
+        },
+    )?)
+}
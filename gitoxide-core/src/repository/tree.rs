@@ -137,7 +137,7 @@ pub fn info(
     #[cfg(feature = "serde1")]
     {
         delegate.stats.bytes = extended.then(|| delegate.stats.num_bytes);
-        serde_json::to_writer_pretty(out, &delegate.stats)?;
+        crate::json::write(out, "tree-stats", &delegate.stats)?;
     }
 
     Ok(())
@@ -59,8 +59,9 @@ pub fn integrity(
         Some(OutputFormat::Human) => writeln!(out, "Human output is currently unsupported, use JSON instead")?,
         #[cfg(feature = "serde1")]
         Some(OutputFormat::Json) => {
-            serde_json::to_writer_pretty(
+            crate::json::write(
                 out,
+                "repository-verify",
                 &serde_json::json!({
                     "index_statistics" : outcome.index_statistics,
                     "loose_object-stores" : outcome.loose_object_stores
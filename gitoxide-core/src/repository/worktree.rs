@@ -0,0 +1,579 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use anyhow::{anyhow, bail, Context as AnyhowContext};
+use git_repository as git;
+use git_repository::{
+    bstr::{BString, ByteSlice},
+    linked_worktree::Proxy,
+    odb::FindExt,
+    refs::{transaction::PreviousValue, Target},
+    ObjectId, Progress,
+};
+
+use crate::OutputFormat;
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+/// What a worktree has checked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub enum Head {
+    /// A branch with its full name, which points to `id` unless it wasn't born yet.
+    Branch { name: BString, id: Option<ObjectId> },
+    /// A commit which isn't associated with a branch.
+    Detached { id: ObjectId },
+}
+
+/// The main worktree or a linked worktree of a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Worktree {
+    /// The directory with the checked out files, or the repository itself if it's bare.
+    pub path: PathBuf,
+    /// The name of the administrative directory in `$GIT_DIR/worktrees/`, or `None` for the main worktree.
+    pub id: Option<String>,
+    pub bare: bool,
+    /// The checked out commit, or `None` if `HEAD` couldn't be read.
+    pub head: Option<Head>,
+    /// If set, the worktree is locked for the given reason which may be empty, and won't be pruned.
+    pub locked: Option<String>,
+    /// If set, the worktree is stale for the given reason and would be removed when pruning.
+    pub prunable: Option<String>,
+}
+
+/// List the main worktree of the repository at `repository` followed by all of its linked worktrees, like
+/// `git worktree list` does.
+pub fn list(repository: PathBuf, mut out: impl io::Write, format: OutputFormat) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let worktrees = worktrees(&repo)?;
+    match format {
+        OutputFormat::Human => {
+            let width = worktrees
+                .iter()
+                .map(|wt| wt.path.display().to_string().len())
+                .max()
+                .unwrap_or_default();
+            for wt in &worktrees {
+                write!(out, "{:<width$} ", wt.path.display().to_string(), width = width)?;
+                match &wt.head {
+                    _ if wt.bare => write!(out, "(bare)")?,
+                    Some(Head::Branch { name, id }) => write!(
+                        out,
+                        "{} [{}]",
+                        id.map(|id| id.to_hex_with_len(7).to_string())
+                            .unwrap_or_else(|| "0000000".into()),
+                        name.strip_prefix(b"refs/heads/").unwrap_or(name).as_bstr()
+                    )?,
+                    Some(Head::Detached { id }) => write!(out, "{} (detached HEAD)", id.to_hex_with_len(7))?,
+                    None => write!(out, "(error)")?,
+                }
+                if wt.locked.is_some() {
+                    write!(out, " locked")?;
+                }
+                if wt.prunable.is_some() {
+                    write!(out, " prunable")?;
+                }
+                writeln!(out)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "worktree-list", &worktrees)?,
+    }
+    Ok(())
+}
+
+pub struct AddOptions {
+    /// If true, check out the commit without associating it with a branch.
+    pub detach: bool,
+    /// If set, create a branch of this name at the commit to check out, which must not exist yet.
+    pub new_branch: Option<String>,
+    /// If set, lock the new worktree with the given reason, which may be empty, to prevent it from being pruned.
+    pub lock: Option<String>,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+}
+
+/// Create a linked worktree of the repository at `repository` in the new or empty directory `path`, and check out
+/// `commit_ish` into it, similar to `git worktree add`.
+///
+/// If `commit_ish` is the name of a local branch, that branch is checked out unless it's checked out in another worktree
+/// already, and any other commit is checked out as detached `HEAD`. Without `commit_ish`, a branch named after the last
+/// component of `path` is checked out, and created from `HEAD` if it doesn't exist yet.
+pub fn add(
+    repository: PathBuf,
+    path: PathBuf,
+    commit_ish: Option<String>,
+    progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    AddOptions {
+        detach,
+        new_branch,
+        lock,
+        thread_limit,
+    }: AddOptions,
+) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    if path.read_dir().map_or(false, |mut entries| entries.next().is_some()) {
+        bail!(
+            "Refusing to add a worktree in existing non-empty directory '{}'",
+            path.display()
+        );
+    }
+    let path = if path.is_absolute() {
+        path
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let basename = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Could not derive a worktree name from '{}'", path.display()))?
+        .to_owned();
+
+    let resolve = |name: Option<&str>| -> anyhow::Result<ObjectId> {
+        let name = name.unwrap_or("HEAD");
+        crate::pack::create::parse_tips(&repo, Some(name))?
+            .0
+            .pop()
+            .ok_or_else(|| anyhow!("'{}' is not a commit", name))
+    };
+    let existing_branch = |name: &str| -> anyhow::Result<Option<ObjectId>> {
+        Ok(repo
+            .try_find_reference(format!("refs/heads/{}", name).as_str())?
+            .map(|r| r.into_fully_peeled_id())
+            .transpose()?
+            .map(|id| id.detach()))
+    };
+    let (head, branch_to_create) = match (new_branch, commit_ish.as_deref()) {
+        (Some(branch), commit_ish) => {
+            let id = resolve(commit_ish)?;
+            (branch_head(branch.as_str(), id), Some(branch))
+        }
+        (None, commit_ish) if detach => (
+            Head::Detached {
+                id: resolve(commit_ish)?,
+            },
+            None,
+        ),
+        (None, Some(commit_ish)) => match existing_branch(commit_ish)? {
+            Some(id) => (branch_head(commit_ish, id), None),
+            None => (
+                Head::Detached {
+                    id: resolve(Some(commit_ish))?,
+                },
+                None,
+            ),
+        },
+        (None, None) => match existing_branch(&basename)? {
+            Some(id) => (branch_head(&basename, id), None),
+            None => (branch_head(&basename, resolve(None)?), Some(basename.clone())),
+        },
+    };
+    let id = match &head {
+        Head::Branch { name, id } => {
+            if let Some(wt) = worktrees(&repo)?
+                .into_iter()
+                .find(|wt| matches!(&wt.head, Some(Head::Branch { name: checked_out, .. }) if checked_out == name))
+            {
+                bail!(
+                    "Branch '{}' is checked out at '{}' already, use --detach to check out its commit instead",
+                    name,
+                    wt.path.display()
+                );
+            }
+            id.expect("branches to check out always exist")
+        }
+        Head::Detached { id } => *id,
+    };
+
+    let admin_dir = {
+        let worktrees_dir = repo.common_dir().join("worktrees");
+        let mut admin_dir = worktrees_dir.join(&basename);
+        let mut count = 0;
+        while admin_dir.exists() {
+            count += 1;
+            admin_dir = worktrees_dir.join(format!("{}{}", basename, count));
+        }
+        admin_dir
+    };
+    let path_existed = path.is_dir();
+    let res = populate(
+        repo,
+        &path,
+        &admin_dir,
+        head,
+        branch_to_create.map(|branch| (branch, commit_ish)),
+        id,
+        lock.as_deref(),
+        progress,
+        should_interrupt,
+        thread_limit,
+    );
+    if res.is_err() {
+        // Like git, don't leave a half-initialized worktree behind, which would be locked and thus never pruned.
+        fs::remove_dir_all(&admin_dir).ok();
+        // The directory was empty if it existed, so it's restored by recreating it.
+        fs::remove_dir_all(&path).ok();
+        if path_existed {
+            fs::create_dir(&path).ok();
+        }
+    }
+    res
+}
+
+/// Set up the administrative directory at `admin_dir` for the worktree at `path` and check out `id` into it.
+#[allow(clippy::too_many_arguments)]
+fn populate(
+    repo: git::Repository,
+    path: &Path,
+    admin_dir: &Path,
+    head: Head,
+    branch_to_create: Option<(String, Option<String>)>,
+    id: ObjectId,
+    lock: Option<&str>,
+    progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    thread_limit: Option<usize>,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(&admin_dir)?;
+    // Like git, lock the worktree while it's initialized to prevent it from being pruned.
+    fs::write(admin_dir.join("locked"), lock.unwrap_or("initializing"))?;
+    fs::create_dir_all(&path)?;
+    let path = path.canonicalize()?;
+    let admin_dir = admin_dir.canonicalize()?;
+    fs::write(admin_dir.join("gitdir"), format!("{}\n", path.join(".git").display()))?;
+    fs::write(admin_dir.join("commondir"), "../..\n")?;
+    fs::write(
+        admin_dir.join("HEAD"),
+        match &head {
+            Head::Branch { name, .. } => format!("ref: {}\n", name),
+            Head::Detached { id } => format!("{}\n", id),
+        },
+    )?;
+    fs::write(path.join(".git"), format!("gitdir: {}\n", admin_dir.display()))?;
+
+    if let Some((branch, commit_ish)) = branch_to_create {
+        repo.reference(
+            format!("refs/heads/{}", branch).as_str(),
+            id,
+            PreviousValue::MustNotExist,
+            format!("branch: Created from {}", commit_ish.as_deref().unwrap_or("HEAD")),
+        )?;
+    }
+    checkout(
+        repo,
+        id,
+        &path,
+        &admin_dir.join("index"),
+        progress,
+        should_interrupt,
+        thread_limit,
+    )?;
+    if lock.is_none() {
+        fs::remove_file(admin_dir.join("locked"))?;
+    }
+    Ok(())
+}
+
+/// Remove the linked worktree at `path` of the repository at `repository` along with its administrative files.
+///
+/// Like `git worktree remove`, only clean worktrees without local modifications, staged changes or untracked files that
+/// aren't ignored can be removed unless `force` is set, which also allows locked worktrees to be removed.
+pub fn remove(repository: PathBuf, path: PathBuf, force: bool) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Could not find worktree at '{}'", path.display()))?;
+    if repo.work_dir().and_then(|dir| dir.canonicalize().ok()).as_ref() == Some(&path) {
+        bail!("'{}' is the main worktree and can't be removed", path.display());
+    }
+    let proxy = repo
+        .worktrees()?
+        .into_iter()
+        .find(|proxy| {
+            proxy
+                .base()
+                .and_then(|base| base.canonicalize())
+                .map_or(false, |p| p == path)
+        })
+        .ok_or_else(|| anyhow!("'{}' is not a worktree of the repository", path.display()))?;
+    if !force {
+        if let Some(reason) = proxy.lock_reason()? {
+            bail!(
+                "Worktree '{}' is locked{}, use --force to remove it anyway",
+                path.display(),
+                if reason.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", reason)
+                }
+            );
+        }
+        if let Some(reason) = modifications(&repo, &proxy, &path)? {
+            bail!(
+                "Worktree '{}' has {}, use --force to remove it along with all of its files",
+                path.display(),
+                reason
+            );
+        }
+    }
+    fs::remove_dir_all(&path).with_context(|| format!("Could not remove worktree at '{}'", path.display()))?;
+    fs::remove_dir_all(proxy.git_dir())?;
+    Ok(())
+}
+
+/// Return a description of the first kind of change found in the linked worktree of `proxy` checked out at `work_dir`,
+/// or `None` if it's clean.
+fn modifications(repo: &git::Repository, proxy: &Proxy, work_dir: &Path) -> anyhow::Result<Option<&'static str>> {
+    let index_path = proxy.git_dir().join("index");
+    if !index_path.is_file() {
+        return Ok(Some("no index"));
+    }
+    let index = git::index::File::at(
+        index_path,
+        git::index::decode::Options {
+            object_hash: repo.object_hash(),
+            ..Default::default()
+        },
+    )?
+    .state;
+    if index
+        .entries()
+        .iter()
+        .any(|e| e.mode == git::index::entry::Mode::COMMIT)
+    {
+        return Ok(Some("submodules"));
+    }
+    let head_id = match proxy.head()?.map(|target| to_head(repo, target)).transpose()? {
+        Some(Head::Branch { id, .. }) => id,
+        Some(Head::Detached { id }) => Some(id),
+        None => None,
+    };
+    let head_tree = match head_id {
+        Some(id) => {
+            let mut buf = Vec::new();
+            let tree_id = repo.objects.find_commit_iter(id, &mut buf)?.tree_id()?;
+            Some(git::index::State::from_tree(&tree_id, |oid, buf| {
+                repo.objects.find_tree_iter(oid, buf).ok()
+            })?)
+        }
+        None => None,
+    };
+    let has_staged_changes = match head_tree {
+        Some(head_tree) => !git::worktree::status::tree_index(&head_tree, &index).is_empty(),
+        None => !index.entries().is_empty(),
+    };
+    if has_staged_changes {
+        return Ok(Some("staged changes"));
+    }
+
+    let excludes = match fs::read(repo.common_dir().join("info").join("exclude")) {
+        Ok(buf) => buf,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    let status = git::worktree::status::index_worktree(
+        &index,
+        work_dir,
+        &excludes,
+        git::worktree::status::Options {
+            fs: git::worktree::fs::Capabilities {
+                ignore_case: repo.ignore_case(),
+                ..git::worktree::fs::Capabilities::probe(work_dir)
+            },
+            untracked: git::worktree::status::Untracked::Normal,
+        },
+    )?;
+    Ok(if !status.changes.is_empty() {
+        Some("local modifications")
+    } else if !status.untracked.is_empty() {
+        Some("untracked files")
+    } else {
+        None
+    })
+}
+
+/// A stale worktree whose administrative files were removed.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct Pruned {
+    id: String,
+    reason: String,
+}
+
+/// Remove the administrative files of all linked worktrees of the repository at `repository` whose directory doesn't exist
+/// anymore, unless they are locked, like `git worktree prune` does.
+///
+/// If `dry_run` is true, only print what would be removed.
+pub fn prune(repository: PathBuf, dry_run: bool, mut out: impl io::Write, format: OutputFormat) -> anyhow::Result<()> {
+    let repo = git::open(repository)?;
+    let mut pruned = Vec::new();
+    for proxy in repo.worktrees()? {
+        if let (Some(reason), false) = (proxy.prunable()?, proxy.is_locked()) {
+            if !dry_run {
+                fs::remove_dir_all(proxy.git_dir())?;
+            }
+            pruned.push(Pruned {
+                id: proxy.id().to_string_lossy().into_owned(),
+                reason: reason.into(),
+            });
+        }
+    }
+    let worktrees_dir = repo.common_dir().join("worktrees");
+    if !dry_run
+        && worktrees_dir
+            .read_dir()
+            .map_or(false, |mut entries| entries.next().is_none())
+    {
+        fs::remove_dir(&worktrees_dir)?;
+    }
+    match format {
+        OutputFormat::Human => {
+            for Pruned { id, reason } in &pruned {
+                writeln!(out, "Removing worktrees/{}: {}", id, reason)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => crate::json::write(out, "worktree-prune", &pruned)?,
+    }
+    Ok(())
+}
+
+/// Check out the tree of the commit `id` into `work_dir`, skipping submodules, and write the matching index to `index_path`.
+pub(crate) fn checkout(
+    repo: git::Repository,
+    id: ObjectId,
+    work_dir: &Path,
+    index_path: &Path,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    thread_limit: Option<usize>,
+) -> anyhow::Result<()> {
+    let object_hash = repo.object_hash();
+    let mut buf = Vec::new();
+    let tree_id = repo.objects.find_commit_iter(id, &mut buf)?.tree_id()?;
+    let mut index = git::index::State::from_tree(&tree_id, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())?;
+    let mut num_submodules = 0;
+    for entry in index
+        .entries_mut()
+        .iter_mut()
+        .filter(|e| e.mode.contains(git::index::entry::Mode::COMMIT))
+    {
+        entry.flags.insert(git::index::entry::Flags::SKIP_WORKTREE);
+        num_submodules += 1;
+    }
+
+    let mut files = progress.add_child("checkout");
+    let mut bytes = progress.add_child("writing");
+    files.init(
+        Some(index.entries().len() - num_submodules),
+        git::progress::count("files"),
+    );
+    bytes.init(None, git::progress::bytes());
+
+    let start = std::time::Instant::now();
+    let objects = repo.objects.into_arc()?;
+    let git::worktree::index::checkout::Outcome { errors, collisions, .. } = git::worktree::index::checkout(
+        &mut index,
+        work_dir,
+        move |oid, buf| objects.find_blob(oid, buf),
+        &mut files,
+        &mut bytes,
+        should_interrupt,
+        git::worktree::index::checkout::Options {
+            fs: git::worktree::fs::Capabilities::probe(work_dir),
+            destination_is_initially_empty: true,
+            thread_limit,
+            ..Default::default()
+        },
+    )?;
+    files.show_throughput(start);
+    bytes.show_throughput(start);
+
+    if !(errors.is_empty() && collisions.is_empty()) {
+        bail!(
+            "Checkout of '{}' is incomplete with {} error(s) and {} collision(s)",
+            work_dir.display(),
+            errors.len(),
+            collisions.len()
+        )
+    }
+    git::index::File::from_state(index, index_path)
+        .write(git::index::write::Options { object_hash })
+        .with_context(|| format!("Could not write index at '{}'", index_path.display()))?;
+    Ok(())
+}
+
+fn branch_head(short_name: &str, id: ObjectId) -> Head {
+    Head::Branch {
+        name: format!("refs/heads/{}", short_name).into(),
+        id: Some(id),
+    }
+}
+
+/// Return the main worktree of `repo` followed by all linked worktrees, ordered by their name.
+fn worktrees(repo: &git::Repository) -> anyhow::Result<Vec<Worktree>> {
+    let head = match repo.head()?.kind {
+        git::head::Kind::Symbolic(r) => Target::Symbolic(r.name),
+        git::head::Kind::Unborn(name) => Target::Symbolic(name),
+        git::head::Kind::Detached { target, .. } => Target::Peeled(target),
+    };
+    let mut worktrees = vec![Worktree {
+        path: repo.work_dir().unwrap_or_else(|| repo.git_dir()).to_owned(),
+        id: None,
+        bare: repo.work_dir().is_none(),
+        head: Some(to_head(repo, head)?),
+        locked: None,
+        prunable: None,
+    }];
+    for proxy in repo.worktrees()? {
+        worktrees.push(linked_worktree(repo, &proxy)?);
+    }
+    Ok(worktrees)
+}
+
+fn linked_worktree(repo: &git::Repository, proxy: &Proxy) -> anyhow::Result<Worktree> {
+    let id = Some(proxy.id().to_string_lossy().into_owned());
+    let prunable = proxy.prunable()?;
+    if !proxy.git_dir().is_dir() {
+        return Ok(Worktree {
+            path: proxy.git_dir().to_owned(),
+            id,
+            bare: false,
+            head: None,
+            locked: None,
+            prunable: prunable.map(Into::into),
+        });
+    }
+    Ok(Worktree {
+        path: match proxy.base() {
+            Ok(path) => path,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => proxy.git_dir().to_owned(),
+            Err(err) => return Err(err.into()),
+        },
+        id,
+        bare: false,
+        head: proxy.head()?.map(|target| to_head(repo, target)).transpose()?,
+        locked: proxy.lock_reason()?,
+        prunable: prunable.map(Into::into),
+    })
+}
+
+/// Turn the `target` of a `HEAD` reference into the checked out branch and commit.
+fn to_head(repo: &git::Repository, target: Target) -> anyhow::Result<Head> {
+    Ok(match target {
+        Target::Symbolic(name) => Head::Branch {
+            id: repo
+                .try_find_reference(name.as_bstr())?
+                .and_then(|r| r.into_fully_peeled_id().ok())
+                .map(|id| id.detach()),
+            name: name.into_inner(),
+        },
+        Target::Peeled(id) => Head::Detached { id },
+    })
+}
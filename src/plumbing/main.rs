@@ -12,11 +12,11 @@ use clap::Parser;
 use gitoxide_core as core;
 use gitoxide_core::pack::verify;
 
-#[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-use crate::plumbing::options::remote;
 use crate::{
-    plumbing::options::{commitgraph, index, mailmap, pack, pack::multi_index, repo, Args, Subcommands},
-    shared::pretty::prepare_and_run,
+    plumbing::options::{
+        bundle, commitgraph, config, index, mailmap, pack, pack::multi_index, remote, repo, worktree, Args, Subcommands,
+    },
+    shared::pretty::{prepare_and_run, ProgressMode},
 };
 
 #[cfg(feature = "gitoxide-core-async-client")]
@@ -53,18 +53,23 @@ pub fn main() -> Result<()> {
     let cmd = args.cmd;
     let object_hash = args.object_hash;
 
-    let progress;
+    let tui;
     let progress_keep_open;
     #[cfg(feature = "prodash-render-tui")]
     {
-        progress = args.progress;
+        tui = args.progress;
         progress_keep_open = args.progress_keep_open;
     }
     #[cfg(not(feature = "prodash-render-tui"))]
     {
-        progress = false;
+        tui = false;
         progress_keep_open = false;
     }
+    let progress = match args.progress_format {
+        Some(format) => ProgressMode::Machine(format),
+        None if tui => ProgressMode::Tui,
+        None => ProgressMode::Lines,
+    };
 
     let should_interrupt = Arc::new(AtomicBool::new(false));
     git_repository::interrupt::init_handler({
@@ -144,6 +149,16 @@ pub fn main() -> Result<()> {
                     core::index::entries(index_path, out, core::index::Options { object_hash, format })
                 },
             ),
+            index::Subcommands::Conflicts => prepare_and_run(
+                "index-conflicts",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::index::conflicts(index_path, out, core::index::Options { object_hash, format })
+                },
+            ),
             index::Subcommands::Verify => prepare_and_run(
                 "index-verify",
                 verbose,
@@ -218,6 +233,26 @@ pub fn main() -> Result<()> {
                     None,
                     move |_progress, out, err| core::repository::odb::info(repository, format, out, err),
                 ),
+                repo::odb::Subcommands::Cat { batch_check, .. } => prepare_and_run(
+                    "repository-odb-cat",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    None,
+                    move |_progress, out, _err| {
+                        core::repository::odb::cat(
+                            repository,
+                            format,
+                            if batch_check {
+                                core::repository::odb::cat::Mode::Headers
+                            } else {
+                                core::repository::odb::cat::Mode::Contents
+                            },
+                            BufReader::new(stdin()),
+                            out,
+                        )
+                    },
+                ),
             },
             repo::Subcommands::Tree { cmd } => match cmd {
                 repo::tree::Subcommands::Entries {
@@ -259,6 +294,8 @@ pub fn main() -> Result<()> {
                         algorithm,
                         decode,
                         re_encode,
+                        sample,
+                        sample_bytes,
                     },
             } => prepare_and_run(
                 "repository-verify",
@@ -275,7 +312,7 @@ pub fn main() -> Result<()> {
                         core::repository::verify::Context {
                             output_statistics: statistics.then(|| format),
                             algorithm,
-                            verify_mode: verify_mode(decode, re_encode),
+                            verify_mode: verify_mode(decode, re_encode, sample, sample_bytes),
                             thread_limit,
                         },
                     )
@@ -294,6 +331,7 @@ pub fn main() -> Result<()> {
                 counting_threads,
                 object_cache_size_mb,
                 output_directory,
+                stdout: pack_to_stdout,
             } => {
                 let has_tips = !tips.is_empty();
                 prepare_and_run(
@@ -302,7 +340,7 @@ pub fn main() -> Result<()> {
                     progress,
                     progress_keep_open,
                     core::pack::create::PROGRESS_RANGE,
-                    move |progress, out, _err| {
+                    move |progress, out, err| {
                         let input = if has_tips {
                             None
                         } else {
@@ -321,7 +359,9 @@ pub fn main() -> Result<()> {
                             pack_cache_size_in_bytes: pack_cache_size_mb.unwrap_or(0) * 1_000_000,
                             object_cache_size_in_bytes: object_cache_size_mb.unwrap_or(0) * 1_000_000,
                             statistics: if statistics { Some(format) } else { None },
+                            pack_to_out: pack_to_stdout,
                             out,
+                            err,
                             expansion: expansion.unwrap_or(if has_tips {
                                 core::pack::create::ObjectExpansion::TreeTraversal
                             } else {
@@ -339,6 +379,7 @@ pub fn main() -> Result<()> {
                 directory,
                 refs,
                 refs_directory,
+                resume,
             } => {
                 let (_handle, progress) =
                     async_util::prepare(verbose, "pack-receive", core::pack::receive::PROGRESS_RANGE);
@@ -355,6 +396,7 @@ pub fn main() -> Result<()> {
                         out: std::io::stdout(),
                         should_interrupt,
                         object_hash,
+                        resume,
                     },
                 );
                 return futures_lite::future::block_on(fut);
@@ -366,6 +408,7 @@ pub fn main() -> Result<()> {
                 directory,
                 refs,
                 refs_directory,
+                resume,
             } => prepare_and_run(
                 "pack-receive",
                 verbose,
@@ -386,6 +429,7 @@ pub fn main() -> Result<()> {
                             should_interrupt,
                             out,
                             object_hash,
+                            resume,
                         },
                     )
                 },
@@ -427,6 +471,8 @@ pub fn main() -> Result<()> {
                         decode,
                         re_encode,
                         statistics,
+                        sample,
+                        sample_bytes,
                     },
                 path,
             } => prepare_and_run(
@@ -436,7 +482,7 @@ pub fn main() -> Result<()> {
                 progress_keep_open,
                 verify::PROGRESS_RANGE,
                 move |progress, out, err| {
-                    let mode = verify_mode(decode, re_encode);
+                    let mode = verify_mode(decode, re_encode, sample, sample_bytes);
                     let output_statistics = if statistics { Some(format) } else { None };
                     verify::pack_or_pack_index(
                         path,
@@ -498,6 +544,22 @@ pub fn main() -> Result<()> {
                         )
                     },
                 ),
+                pack::multi_index::Subcommands::Add { index_paths } => prepare_and_run(
+                    "pack-multi-index-add",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    core::pack::multi_index::PROGRESS_RANGE,
+                    move |progress, _out, _err| {
+                        core::pack::multi_index::add(
+                            index_paths,
+                            multi_index_path,
+                            progress,
+                            &should_interrupt,
+                            object_hash,
+                        )
+                    },
+                ),
             },
             pack::Subcommands::Index(subcommands) => match subcommands {
                 pack::index::Subcommands::Create {
@@ -539,12 +601,237 @@ pub fn main() -> Result<()> {
                 ),
             },
         },
-        #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-        Subcommands::Remote(subcommands) => match subcommands {
+        #[cfg(feature = "gitoxide-core-async-client")]
+        Subcommands::LsRemote {
+            protocol,
+            refs_prefixes,
+            url,
+        } => {
+            let (_handle, progress) =
+                async_util::prepare(verbose, "ls-remote", Some(core::remote::refs::PROGRESS_RANGE));
+            let fut = core::remote::refs::list(
+                protocol,
+                url.as_bytes(),
+                git_features::progress::DoOrDiscard::from(progress),
+                core::remote::refs::Context {
+                    thread_limit,
+                    format,
+                    ref_prefixes: refs_prefixes,
+                    out: std::io::stdout(),
+                },
+            );
+            return futures_lite::future::block_on(fut);
+        }
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::LsRemote {
+            protocol,
+            refs_prefixes,
+            url,
+        } => prepare_and_run(
+            "ls-remote",
+            verbose,
+            progress,
+            progress_keep_open,
+            core::remote::refs::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::remote::refs::list(
+                    protocol,
+                    url.as_bytes(),
+                    git_features::progress::DoOrDiscard::from(progress),
+                    core::remote::refs::Context {
+                        thread_limit,
+                        format,
+                        ref_prefixes: refs_prefixes,
+                        out,
+                    },
+                )
+            },
+        ),
+        Subcommands::Config(config::Platform { repository, cmd }) => match cmd {
+            config::Subcommands::List {
+                show_origin,
+                show_scope,
+            } => prepare_and_run(
+                "config-list",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::config::list(
+                        repository,
+                        out,
+                        core::repository::config::Options {
+                            format,
+                            show_origin,
+                            show_scope,
+                        },
+                    )
+                },
+            ),
+            config::Subcommands::Get {
+                all,
+                value_type,
+                show_origin,
+                show_scope,
+                key,
+            } => prepare_and_run(
+                "config-get",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::config::get(
+                        repository,
+                        &key,
+                        value_type,
+                        all,
+                        out,
+                        core::repository::config::Options {
+                            format,
+                            show_origin,
+                            show_scope,
+                        },
+                    )
+                },
+            ),
+            config::Subcommands::Set {
+                scope,
+                value_type,
+                replace_all,
+                key,
+                value,
+            } => prepare_and_run(
+                "config-set",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::config::set(repository, &key, &value, scope, value_type, replace_all)
+                },
+            ),
+        },
+        Subcommands::Bundle(bundle::Platform { repository, cmd }) => match cmd {
+            bundle::Subcommands::Create {
+                bundle_version,
+                path,
+                revisions,
+            } => prepare_and_run(
+                "bundle-create",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::bundle::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::bundle::create(
+                        repository,
+                        path,
+                        revisions,
+                        progress,
+                        &should_interrupt,
+                        out,
+                        core::repository::bundle::CreateOptions {
+                            version: bundle_version,
+                            format,
+                            thread_limit,
+                        },
+                    )
+                },
+            ),
+            bundle::Subcommands::Verify { path } => prepare_and_run(
+                "bundle-verify",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::bundle::verify(repository, path, out, format),
+            ),
+            bundle::Subcommands::Unbundle { force, path } => prepare_and_run(
+                "bundle-unbundle",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::bundle::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::bundle::unbundle(
+                        repository,
+                        path,
+                        force,
+                        progress,
+                        &should_interrupt,
+                        out,
+                        core::repository::bundle::Options { format, thread_limit },
+                    )
+                },
+            ),
+        },
+        Subcommands::Worktree(worktree::Platform { repository, cmd }) => match cmd {
+            worktree::Subcommands::List => prepare_and_run(
+                "worktree-list",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::worktree::list(repository, out, format),
+            ),
+            worktree::Subcommands::Add {
+                detach,
+                new_branch,
+                lock,
+                reason,
+                path,
+                commit_ish,
+            } => prepare_and_run(
+                "worktree-add",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::repository::worktree::PROGRESS_RANGE,
+                move |progress, _out, _err| {
+                    core::repository::worktree::add(
+                        repository,
+                        path,
+                        commit_ish,
+                        progress,
+                        &should_interrupt,
+                        core::repository::worktree::AddOptions {
+                            detach,
+                            new_branch,
+                            lock: lock.then(|| reason.unwrap_or_default()),
+                            thread_limit,
+                        },
+                    )
+                },
+            ),
+            worktree::Subcommands::Remove { force, path } => prepare_and_run(
+                "worktree-remove",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::worktree::remove(repository, path, force),
+            ),
+            worktree::Subcommands::Prune { dry_run } => prepare_and_run(
+                "worktree-prune",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::worktree::prune(repository, dry_run, out, format),
+            ),
+        },
+        Subcommands::Remote(remote::Platform { repository, cmd }) => match cmd {
             #[cfg(feature = "gitoxide-core-async-client")]
-            remote::Subcommands::RefList { protocol, url } => {
+            remote::Subcommands::Ls {
+                protocol,
+                refs_prefixes,
+                name,
+            } => {
+                let url = core::repository::remote::fetch_url(repository, &name)?;
                 let (_handle, progress) =
-                    async_util::prepare(verbose, "remote-ref-list", Some(core::remote::refs::PROGRESS_RANGE));
+                    async_util::prepare(verbose, "remote-ls", Some(core::remote::refs::PROGRESS_RANGE));
                 let fut = core::remote::refs::list(
                     protocol,
                     &url,
@@ -552,32 +839,177 @@ pub fn main() -> Result<()> {
                     core::remote::refs::Context {
                         thread_limit,
                         format,
+                        ref_prefixes: refs_prefixes,
                         out: std::io::stdout(),
                     },
                 );
                 return futures_lite::future::block_on(fut);
             }
             #[cfg(feature = "gitoxide-core-blocking-client")]
-            remote::Subcommands::RefList { protocol, url } => prepare_and_run(
-                "remote-ref-list",
+            remote::Subcommands::Ls {
+                protocol,
+                refs_prefixes,
+                name,
+            } => {
+                let url = core::repository::remote::fetch_url(repository, &name)?;
+                prepare_and_run(
+                    "remote-ls",
+                    verbose,
+                    progress,
+                    progress_keep_open,
+                    core::remote::refs::PROGRESS_RANGE,
+                    move |progress, out, _err| {
+                        core::remote::refs::list(
+                            protocol,
+                            &url,
+                            git_features::progress::DoOrDiscard::from(progress),
+                            core::remote::refs::Context {
+                                thread_limit,
+                                format,
+                                ref_prefixes: refs_prefixes,
+                                out,
+                            },
+                        )
+                    },
+                )
+            }
+            remote::Subcommands::Add { name, url } => prepare_and_run(
+                "remote-add",
                 verbose,
                 progress,
                 progress_keep_open,
-                core::remote::refs::PROGRESS_RANGE,
-                move |progress, out, _err| {
-                    core::remote::refs::list(
-                        protocol,
-                        &url,
-                        git_features::progress::DoOrDiscard::from(progress),
-                        core::remote::refs::Context {
-                            thread_limit,
-                            format,
-                            out,
-                        },
-                    )
-                },
+                None,
+                move |_progress, _out, _err| core::repository::remote::add(repository, &name, &url),
+            ),
+            remote::Subcommands::Remove { name } => prepare_and_run(
+                "remote-remove",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::remote::remove(repository, &name),
+            ),
+            remote::Subcommands::SetUrl { push, name, url } => prepare_and_run(
+                "remote-set-url",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::remote::set_url(repository, &name, &url, push),
             ),
         },
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Subcommands::Clone {
+            protocol,
+            bare,
+            depth,
+            no_tags,
+            url,
+            directory,
+        } => prepare_and_run(
+            "clone",
+            verbose,
+            progress,
+            progress_keep_open,
+            core::repository::clone::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::clone(
+                    &url,
+                    directory,
+                    progress,
+                    out,
+                    &should_interrupt,
+                    core::repository::clone::Options {
+                        bare,
+                        depth,
+                        no_tags,
+                        protocol,
+                        thread_limit,
+                    },
+                )
+            },
+        ),
+        Subcommands::Status {
+            repository,
+            porcelain,
+            untracked_files,
+        } => prepare_and_run(
+            "status",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::status(
+                    repository,
+                    out,
+                    err,
+                    core::repository::status::Options {
+                        format,
+                        porcelain,
+                        untracked: untracked_files,
+                        statistics: verbose,
+                    },
+                )
+            },
+        ),
+        Subcommands::Log {
+            repository,
+            oneline,
+            template,
+            max_count,
+            revisions,
+            paths,
+        } => prepare_and_run(
+            "log",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::log(
+                    repository,
+                    revisions,
+                    out,
+                    core::repository::log::Options {
+                        format,
+                        oneline,
+                        template,
+                        max_count,
+                        pathspecs: paths,
+                    },
+                )
+            },
+        ),
+        Subcommands::Fsck {
+            repository,
+            min_severity,
+            strict,
+            no_connectivity,
+            algorithm,
+        } => prepare_and_run(
+            "fsck",
+            verbose,
+            progress,
+            progress_keep_open,
+            core::repository::fsck::PROGRESS_RANGE,
+            move |progress, out, _err| {
+                core::repository::fsck(
+                    repository,
+                    out,
+                    progress,
+                    &should_interrupt,
+                    core::repository::fsck::Options {
+                        format,
+                        min_severity,
+                        strict,
+                        connectivity: !no_connectivity,
+                        thread_limit,
+                        algorithm,
+                    },
+                )
+            },
+        ),
         Subcommands::CommitGraph(subcommands) => match subcommands {
             commitgraph::Subcommands::Verify { path, statistics } => prepare_and_run(
                 "commitgraph-verify",
@@ -598,12 +1030,51 @@ pub fn main() -> Result<()> {
                 },
             )
             .map(|_| ()),
+            commitgraph::Subcommands::Write {
+                repository,
+                reachable,
+                changed_paths,
+                split,
+                revisions,
+            } => prepare_and_run(
+                "commitgraph-write",
+                verbose,
+                progress,
+                progress_keep_open,
+                core::commitgraph::write::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::commitgraph::write::write(
+                        repository,
+                        revisions,
+                        progress,
+                        &should_interrupt,
+                        out,
+                        core::commitgraph::write::Options {
+                            format,
+                            reachable,
+                            changed_paths,
+                            split,
+                        },
+                    )
+                },
+            ),
         },
     }?;
     Ok(())
 }
 
-fn verify_mode(decode: bool, re_encode: bool) -> verify::Mode {
+fn verify_mode(
+    decode: bool,
+    re_encode: bool,
+    sample: Option<std::num::NonZeroU32>,
+    sample_bytes: Option<u64>,
+) -> verify::Mode {
+    if let Some(sample) = sample
+        .map(verify::Sample::EveryNth)
+        .or_else(|| sample_bytes.map(verify::Sample::Bytes))
+    {
+        return verify::Mode::HashCrc32Sampled(sample);
+    }
     match (decode, re_encode) {
         (true, false) => verify::Mode::HashCrc32Decode,
         (true, true) | (false, true) => verify::Mode::HashCrc32DecodeEncode,
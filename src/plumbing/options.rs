@@ -22,12 +22,20 @@ pub struct Args {
 
     /// The progress TUI will stay up even though the work is already completed.
     ///
-    /// Use this to be able to read progress messages or additional information visible in the TUI log pane.
+    /// Use this to be able to read progress messages or additional information visible in the TUI log pane, like the
+    /// throughput of each completed task and the total duration.
     #[cfg(feature = "prodash-render-tui")]
     #[clap(long, conflicts_with("verbose"), requires("progress"))]
     pub progress_keep_open: bool,
 
+    /// Write progress to stderr as one line per update in the given format, for consumption by other programs.
+    #[clap(long, possible_values(git_features::progress::machine::Format::variants()))]
+    #[cfg_attr(feature = "prodash-render-tui", clap(conflicts_with("progress")))]
+    pub progress_format: Option<git_features::progress::machine::Format>,
+
     /// Determine the format to use when outputting statistics.
+    ///
+    /// JSON documents contain the name of their `schema` and its `version` along with the actual `data`.
     #[clap(
         long,
         short = 'f',
@@ -49,10 +57,145 @@ pub enum Subcommands {
     /// Subcommands for interacting with packs and their indices.
     #[clap(subcommand)]
     Pack(pack::Subcommands),
-    /// Subcommands for interacting with git remotes, e.g. git repositories hosted on servers.
-    #[clap(subcommand)]
+    /// Subcommands for interacting with the remotes of a repository.
+    Remote(remote::Platform),
+    /// List remote references from a remote identified by a url.
+    ///
+    /// This is the plumbing equivalent of `git ls-remote`.
+    /// Supported URLs are documented here: <https://www.git-scm.com/docs/git-clone#_git_urls>
     #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
-    Remote(remote::Subcommands),
+    LsRemote {
+        /// The protocol version to use. Valid values are 0, 1 and 2, with 0 being for servers that fail on version requests
+        #[clap(long, short = 'p')]
+        protocol: Option<core::net::Protocol>,
+
+        /// Only list references whose full name starts with the given prefix, like `refs/heads/`.
+        ///
+        /// Can be specified multiple times to list references matching any of the prefixes.
+        #[clap(long = "refs-prefix")]
+        refs_prefixes: Vec<String>,
+
+        /// the URLs or path from which to receive references
+        ///
+        /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+        url: String,
+    },
+    /// Clone a repository from a url into a new directory, checking out the remote's HEAD unless the clone is bare.
+    ///
+    /// This is the plumbing equivalent of `git clone`.
+    #[cfg(feature = "gitoxide-core-blocking-client")]
+    Clone {
+        /// The protocol version to use. Valid values are 0, 1 and 2, with 0 being for servers that fail on version requests
+        #[clap(long, short = 'p')]
+        protocol: Option<core::net::Protocol>,
+
+        /// Create a repository without worktree, with the remote's branches becoming local branches.
+        #[clap(long)]
+        bare: bool,
+
+        /// Only fetch the given amount of commits from each tip, creating a shallow repository.
+        #[clap(long)]
+        depth: Option<usize>,
+
+        /// Do not fetch any tags.
+        #[clap(long)]
+        no_tags: bool,
+
+        /// The URL or path of the repository to clone.
+        ///
+        /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+        url: String,
+
+        /// The directory to clone into, which must not exist or be empty.
+        ///
+        /// If unset, it's derived from the last component of the url.
+        directory: Option<std::path::PathBuf>,
+    },
+    /// Show changes between `HEAD` and the index, the index and the worktree, as well as untracked files.
+    ///
+    /// This is the plumbing equivalent of `git status`.
+    Status {
+        /// The repository to show the status of.
+        #[clap(short = 'r', long, default_value = ".")]
+        repository: std::path::PathBuf,
+
+        /// Print the same machine-readable format as `git status --porcelain=v2`.
+        #[clap(long)]
+        porcelain: bool,
+
+        /// How to show untracked files, with `normal` collapsing directories without tracked files into a single entry.
+        #[clap(
+            long,
+            short = 'u',
+            default_value = "normal",
+            possible_values(core::repository::status::UntrackedFiles::variants())
+        )]
+        untracked_files: core::repository::status::UntrackedFiles,
+    },
+    /// Show the commits reachable from the given revisions, newest first.
+    ///
+    /// This is the plumbing equivalent of `git log`.
+    Log {
+        /// The repository to show the history of.
+        #[clap(short = 'r', long, default_value = ".")]
+        repository: std::path::PathBuf,
+
+        /// Print only the abbreviated hash and the subject of each commit.
+        #[clap(long)]
+        oneline: bool,
+
+        /// Print each commit using the given template, like `%H %s %an`.
+        ///
+        /// Supported placeholders are %H, %h, %T, %t, %P, %p, %s, %b, %B, %n and %%, as well as
+        /// %an, %ae, %aN, %aE, %ad, %ai, %aI, %aD, %as, %at and %ar along with their committer equivalents starting with %c.
+        #[clap(long = "format", alias = "pretty", conflicts_with("oneline"))]
+        template: Option<String>,
+
+        /// Stop after showing this amount of commits.
+        #[clap(long, short = 'n')]
+        max_count: Option<usize>,
+
+        /// The commits to start from as hashes or reference names, defaulting to `HEAD`.
+        ///
+        /// Commits prefixed with `^` are excluded along with their ancestors, and ranges like `from..to` show
+        /// all commits reachable from `to` but not from `from`. Symmetric differences like `one...two` show commits
+        /// reachable from either side, but not from both.
+        revisions: Vec<String>,
+
+        /// Only show commits changing any of these paths compared to their first parent, relative to the current directory
+        /// if it is inside of the work tree, or relative to the root of the repository otherwise.
+        #[clap(last = true)]
+        paths: Vec<std::path::PathBuf>,
+    },
+    /// Check the connectivity and validity of all objects in the object database.
+    ///
+    /// This is the plumbing equivalent of `git fsck`.
+    Fsck {
+        /// The repository whose objects to check.
+        #[clap(short = 'r', long, default_value = ".")]
+        repository: std::path::PathBuf,
+
+        /// Only report findings of this severity or higher.
+        #[clap(long, default_value = "warn", possible_values(&["error", "warn", "info"]))]
+        min_severity: git_repository::objs::fsck::Severity,
+
+        /// Treat all warnings as errors, like `git fsck --strict`.
+        #[clap(long)]
+        strict: bool,
+
+        /// Don't check that all objects reachable from references, their reflogs and the index are present.
+        #[clap(long)]
+        no_connectivity: bool,
+
+        /// The algorithm used to verify packs. They differ in costs.
+        #[clap(
+            long,
+            short = 'a',
+            default_value = "less-time",
+            possible_values(core::pack::verify::Algorithm::variants())
+        )]
+        algorithm: core::pack::verify::Algorithm,
+    },
     /// Subcommands for interacting with commit-graphs
     #[clap(subcommand)]
     CommitGraph(commitgraph::Subcommands),
@@ -62,6 +205,12 @@ pub enum Subcommands {
     Repository(repo::Platform),
     /// Subcommands for interacting with mailmaps
     Mailmap(mailmap::Platform),
+    /// Subcommands for reading and writing configuration values.
+    Config(config::Platform),
+    /// Subcommands for creating, verifying and applying bundle files.
+    Bundle(bundle::Platform),
+    /// Subcommands for managing linked worktrees.
+    Worktree(worktree::Platform),
 }
 
 ///
@@ -137,9 +286,20 @@ pub mod pack {
             #[clap(long, short = 'o')]
             output_directory: Option<PathBuf>,
 
+            /// Write the pack to standard output instead, for example to pipe it into `git index-pack --stdin`.
+            ///
+            /// Statistics are written to standard error in this case.
+            #[clap(long, conflicts_with("output-directory"))]
+            stdout: bool,
+
             /// The tips from which to start the commit graph iteration, either as fully qualified commit hashes
             /// or as branch names.
             ///
+            /// Tips prefixed with `^` are excluded along with their ancestors, and ranges like `from..to` include
+            /// all commits reachable from `to` but not from `from`. Symmetric differences like `one...two`
+            /// include commits reachable from either side, but not from both.
+            /// An empty side of a range refers to `HEAD`.
+            ///
             /// If empty, we expect to read objects on stdin and default to 'none' as expansion mode.
             /// Otherwise the expansion mode is 'tree-traversal' by default.
             tips: Vec<OsString>,
@@ -147,7 +307,7 @@ pub mod pack {
         /// Use the git-protocol to receive a pack, emulating a clone.
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
         Receive {
-            /// The protocol version to use. Valid values are 1 and 2
+            /// The protocol version to use. Valid values are 0, 1 and 2, with 0 being for servers that fail on version requests
             #[clap(long, short = 'p')]
             protocol: Option<core::net::Protocol>,
 
@@ -168,6 +328,14 @@ pub mod pack {
             #[clap(long = "reference", short = 'r')]
             refs: Vec<String>,
 
+            /// Keep the partially received pack and the negotiation state in the output directory if receiving is interrupted,
+            /// and continue from them when invoked again with the same directory.
+            ///
+            /// Objects received previously are kept in their own pack, and commits whose history is entirely contained in it
+            /// are sent as haves to the server so it doesn't send them again.
+            #[clap(long, requires("directory"))]
+            resume: bool,
+
             /// The directory into which to write the received pack and index.
             ///
             /// If unset, they will be discarded.
@@ -250,6 +418,16 @@ pub mod pack {
         /// This will reduce overall performance even more, as re-encoding requires to transform zero-copy objects into
         /// owned objects, causing plenty of allocation to occour.
         pub re_encode: bool,
+
+        #[clap(long, conflicts_with_all(&["decode", "re-encode", "sample-bytes"]))]
+        /// Only verify the hash and CRC32 of about every n-th object, and don't compute the checksum of pack data files.
+        ///
+        /// This is useful for routine checks of very large repositories which would take too long to verify entirely.
+        pub sample: Option<std::num::NonZeroU32>,
+
+        #[clap(long, conflicts_with_all(&["decode", "re-encode"]))]
+        /// Like --sample, but verify only as many objects as it takes to hash about the given amount of bytes per pack.
+        pub sample_bytes: Option<u64>,
     }
 
     ///
@@ -283,6 +461,14 @@ pub mod pack {
                 #[clap(required = true)]
                 index_paths: Vec<PathBuf>,
             },
+            /// Add one or more pack index files to an existing multi-pack index without re-reading the ones it already contains.
+            Add {
+                /// Paths to the pack index files to add (with .idx extension).
+                ///
+                /// Index files already contained in the multi-index are ignored.
+                #[clap(required = true)]
+                index_paths: Vec<PathBuf>,
+            },
         }
     }
 
@@ -387,6 +573,16 @@ pub mod repo {
             Entries,
             /// Provide general information about the object database.
             Info,
+            /// Read object names or references from stdin, one per line, and print information about each object
+            /// in the same format as `git cat-file --batch`.
+            Cat {
+                /// Print `<id> <kind> <size>` followed by the object data for each object.
+                #[clap(long, conflicts_with("batch-check"), required_unless_present("batch-check"))]
+                batch: bool,
+                /// Print only `<id> <kind> <size>` for each object, without its data.
+                #[clap(long)]
+                batch_check: bool,
+            },
         }
     }
 
@@ -479,9 +675,13 @@ pub mod index {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
         /// Validate constraints and assumptions of an index along with its integrity.
+        ///
+        /// Checks the trailing checksum, the order of all entries and the validity of extensions, reporting each outcome.
         Verify,
         /// Print all entries to standard output
         Entries,
+        /// Print all entries in a conflicted state, i.e. those with a stage other than 0.
+        Conflicts,
         /// Print information about the index structure
         Info {
             /// Do not extract specific extension information to gain only a superficial idea of the index's composition.
@@ -543,28 +743,287 @@ pub mod commitgraph {
             #[clap(long, short = 's')]
             statistics: bool,
         },
+        /// Write a commit-graph containing the commits reachable from the given revisions.
+        ///
+        /// This is the plumbing equivalent of `git commit-graph write`.
+        Write {
+            /// The repository to write the commit-graph for.
+            #[clap(short = 'r', long, default_value = ".")]
+            repository: PathBuf,
+            /// Start from all references in addition to the given revisions.
+            #[clap(long)]
+            reachable: bool,
+            /// Compute and write changed-path Bloom filters to speed up path-limited traversals.
+            #[clap(long)]
+            changed_paths: bool,
+            /// Only write commits that aren't yet in the graph into a new file, which is appended to the chain of files
+            /// in 'objects/info/commit-graphs' instead of rewriting 'objects/info/commit-graph'.
+            #[clap(long)]
+            split: bool,
+            /// The commits to start from as hashes or reference names, defaulting to `HEAD` unless --reachable is set.
+            ///
+            /// As the commit-graph contains all of their ancestors, commits can't be excluded with `^` or ranges.
+            revisions: Vec<String>,
+        },
+    }
+}
+
+///
+pub mod bundle {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The repository to create bundles from or apply them to.
+        #[clap(short = 'r', long, default_value = ".")]
+        pub repository: PathBuf,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Create a bundle file with all objects reachable from the given revisions.
+        ///
+        /// This is the plumbing equivalent of `git bundle create`.
+        Create {
+            /// The version of the bundle file, with version 3 being able to indicate the object format.
+            #[clap(long, default_value = "2", possible_values(&["2", "3"]))]
+            bundle_version: git_repository::odb::pack::bundle_file::Version,
+            /// The path of the bundle file to write.
+            path: PathBuf,
+            /// The revisions to include as reference names or hashes, with at least one reference name.
+            ///
+            /// Commits prefixed with `^` are excluded along with their ancestors, and ranges like `from..to` include
+            /// all commits reachable from `to` but not from `from`, while `one...two` includes commits reachable from
+            /// either side, but not from both. Excluded commits become prerequisites of the bundle.
+            #[clap(required = true)]
+            revisions: Vec<String>,
+        },
+        /// Check that the given bundle file can be applied to the repository as all of its prerequisites are present.
+        ///
+        /// This is the plumbing equivalent of `git bundle verify`.
+        Verify {
+            /// The path of the bundle file to verify.
+            path: PathBuf,
+        },
+        /// Write the pack of the given bundle file into the repository along with an index, and create its references.
+        ///
+        /// This is the plumbing equivalent of `git bundle unbundle` followed by updating references.
+        Unbundle {
+            /// Overwrite existing references which point to different objects.
+            #[clap(long)]
+            force: bool,
+            /// The path of the bundle file to apply.
+            path: PathBuf,
+        },
+    }
+}
+
+///
+pub mod worktree {
+    use std::path::PathBuf;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The repository whose worktrees to manage.
+        #[clap(short = 'r', long, default_value = ".")]
+        pub repository: PathBuf,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// List the main worktree followed by all linked worktrees along with what they have checked out.
+        ///
+        /// This is the plumbing equivalent of `git worktree list`.
+        #[clap(visible_alias = "ls")]
+        List,
+        /// Create a linked worktree at the given path and check out a commit into it.
+        ///
+        /// This is the plumbing equivalent of `git worktree add`.
+        Add {
+            /// Check out the commit as detached `HEAD`, even if a branch name was given.
+            #[clap(long, conflicts_with("new-branch"))]
+            detach: bool,
+            /// Create a branch of the given name at the commit to check out it in the new worktree.
+            #[clap(short = 'b', long)]
+            new_branch: Option<String>,
+            /// Lock the new worktree to prevent it from being pruned, for instance if it is on removable media.
+            #[clap(long)]
+            lock: bool,
+            /// The reason for locking the worktree.
+            #[clap(long, requires("lock"))]
+            reason: Option<String>,
+            /// The new or empty directory to create the worktree in.
+            path: PathBuf,
+            /// The branch or commit to check out, defaulting to a branch named after the last component of `path`.
+            commit_ish: Option<String>,
+        },
+        /// Remove the linked worktree at the given path along with its administrative files.
+        ///
+        /// This is the plumbing equivalent of `git worktree remove`.
+        Remove {
+            /// Remove the worktree even if it is locked, contains modifications or untracked files.
+            #[clap(long)]
+            force: bool,
+            /// The path of the worktree to remove.
+            path: PathBuf,
+        },
+        /// Remove the administrative files of linked worktrees whose directory doesn't exist anymore, unless they are locked.
+        ///
+        /// This is the plumbing equivalent of `git worktree prune`.
+        Prune {
+            /// Only show which worktrees would be pruned.
+            #[clap(long, short = 'n')]
+            dry_run: bool,
+        },
+    }
+}
+
+///
+pub mod config {
+    use std::path::PathBuf;
+
+    use gitoxide_core as core;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The repository whose configuration to interact with.
+        #[clap(short = 'r', long, default_value = ".")]
+        pub repository: PathBuf,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// List all values of all configuration files, with those of the lowest precedence first.
+        ///
+        /// This is the plumbing equivalent of `git config --list`.
+        #[clap(visible_alias = "ls")]
+        List {
+            /// Show the file each value was read from, or `command line:` if it was set otherwise.
+            #[clap(long)]
+            show_origin: bool,
+            /// Show the scope of each value, like `global` or `local`.
+            #[clap(long)]
+            show_scope: bool,
+        },
+        /// Print the value of a key with the highest precedence.
+        ///
+        /// This is the plumbing equivalent of `git config --get`.
+        Get {
+            /// Print all values of the key instead of only the one with the highest precedence.
+            #[clap(long)]
+            all: bool,
+            /// Convert the values to the given type, failing if they can't be converted.
+            #[clap(long = "type", possible_values(core::repository::config::ValueType::variants()))]
+            value_type: Option<core::repository::config::ValueType>,
+            /// Show the file each value was read from, or `command line:` if it was set otherwise.
+            #[clap(long)]
+            show_origin: bool,
+            /// Show the scope of each value, like `global` or `local`.
+            #[clap(long)]
+            show_scope: bool,
+            /// The key to look up, like `remote.origin.url`.
+            key: String,
+        },
+        /// Set a key to the given value in the configuration file of the given scope.
+        ///
+        /// This is the plumbing equivalent of `git config <key> <value>`.
+        Set {
+            /// The configuration file to write to.
+            #[clap(
+                long,
+                default_value = "local",
+                possible_values(core::repository::config::Scope::variants())
+            )]
+            scope: core::repository::config::Scope,
+            /// Normalize the value according to the given type, failing if it can't be converted.
+            ///
+            /// Paths are stored as given, as `~` is expanded when they are read.
+            #[clap(long = "type", possible_values(core::repository::config::ValueType::variants()))]
+            value_type: Option<core::repository::config::ValueType>,
+            /// Replace all values of a key that has multiple values, which fails otherwise.
+            #[clap(long)]
+            replace_all: bool,
+            /// The key to set, like `remote.origin.url`.
+            key: String,
+            /// The new value.
+            value: String,
+        },
     }
 }
 
 ///
 #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
 pub mod remote {
+    use std::path::PathBuf;
+
+    #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
     use gitoxide_core as core;
 
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// The repository whose remotes to interact with.
+        #[clap(short = 'r', long, default_value = ".")]
+        pub repository: PathBuf,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Subcommands,
+    }
+
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
-        /// List remote references from a remote identified by a url.
+        /// List the references of a remote configured in the repository.
         ///
-        /// This is the plumbing equivalent of `git ls-remote`.
-        /// Supported URLs are documented here: <https://www.git-scm.com/docs/git-clone#_git_urls>
-        RefList {
-            /// The protocol version to use. Valid values are 1 and 2
+        /// This is the plumbing equivalent of `git ls-remote <name>`.
+        #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
+        Ls {
+            /// The protocol version to use. Valid values are 0, 1 and 2, with 0 being for servers that fail on version requests
             #[clap(long, short = 'p')]
             protocol: Option<core::net::Protocol>,
 
-            /// the URLs or path from which to receive references
+            /// Only list references whose full name starts with the given prefix, like `refs/heads/`.
             ///
-            /// See here for a list of supported URLs: <https://www.git-scm.com/docs/git-clone#_git_urls>
+            /// Can be specified multiple times to list references matching any of the prefixes.
+            #[clap(long = "refs-prefix")]
+            refs_prefixes: Vec<String>,
+
+            /// The name of the remote to list references of.
+            #[clap(default_value = "origin")]
+            name: String,
+        },
+        /// Add a remote which fetches all branches into `refs/remotes/<name>/`.
+        Add {
+            /// The name of the new remote.
+            name: String,
+            /// The url of the new remote.
+            url: String,
+        },
+        /// Remove a remote along with its remote-tracking branches and the upstream configuration of branches tracking it.
+        #[clap(alias = "rm")]
+        Remove {
+            /// The name of the remote to remove.
+            name: String,
+        },
+        /// Change the url of a remote.
+        SetUrl {
+            /// Change the url to push to instead, which is otherwise the same as the url to fetch from.
+            #[clap(long)]
+            push: bool,
+            /// The name of the remote to change.
+            name: String,
+            /// The new url.
             url: String,
         },
     }
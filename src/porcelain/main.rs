@@ -9,7 +9,7 @@ use gitoxide_core as core;
 
 use crate::{
     porcelain::options::{Args, Subcommands},
-    shared::pretty::prepare_and_run,
+    shared::pretty::{prepare_and_run, ProgressMode},
 };
 
 pub fn main() -> Result<()> {
@@ -20,7 +20,11 @@ pub fn main() -> Result<()> {
         move || should_interrupt.store(true, Ordering::SeqCst)
     })?;
     let verbose = !args.quiet;
-    let progress = args.progress;
+    let progress = match args.progress_format {
+        Some(format) => ProgressMode::Machine(format),
+        None if args.progress => ProgressMode::Tui,
+        None => ProgressMode::Lines,
+    };
     let progress_keep_open = args.progress_keep_open;
 
     match args.cmd {
@@ -41,6 +45,11 @@ pub fn main() -> Result<()> {
                 refname,
                 show_pii,
                 omit_unify_identities,
+                ignore_mailmap,
+                mailmap_file,
+                bucket,
+                inactivity_threshold,
+                format,
             }) => {
                 use gitoxide_core::hours;
                 prepare_and_run(
@@ -57,6 +66,11 @@ pub fn main() -> Result<()> {
                             hours::Context {
                                 show_pii,
                                 omit_unify_identities,
+                                ignore_mailmap,
+                                mailmap_file,
+                                bucket,
+                                inactivity_threshold_in_minutes: inactivity_threshold,
+                                format,
                                 out,
                             },
                         )
@@ -85,6 +99,12 @@ pub fn main() -> Result<()> {
                 destination_directory,
                 execute,
                 repository_source,
+                #[cfg(feature = "gitoxide-core-blocking-client")]
+                fetch,
+                #[cfg(feature = "gitoxide-core-blocking-client")]
+                no_prune,
+                #[cfg(feature = "gitoxide-core-blocking-client")]
+                jobs,
             } => {
                 use gitoxide_core::organize;
                 prepare_and_run(
@@ -95,10 +115,19 @@ pub fn main() -> Result<()> {
                     crate::shared::STANDARD_RANGE,
                     move |progress, _out, _err| {
                         organize::run(
-                            if execute {
-                                organize::Mode::Execute
-                            } else {
-                                organize::Mode::Simulate
+                            organize::Options {
+                                mode: if execute {
+                                    organize::Mode::Execute
+                                } else {
+                                    organize::Mode::Simulate
+                                },
+                                #[cfg(feature = "gitoxide-core-blocking-client")]
+                                mirror: fetch.then(|| organize::mirror::Options {
+                                    protocol: None,
+                                    prune: !no_prune,
+                                    thread_limit: jobs,
+                                    should_interrupt,
+                                }),
                             },
                             repository_source.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
                             destination_directory.unwrap_or_else(|| [std::path::Component::CurDir].iter().collect()),
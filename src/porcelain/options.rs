@@ -14,10 +14,19 @@ pub struct Args {
 
     /// The progress TUI will stay up even though the work is already completed.
     ///
-    /// Use this to be able to read progress messages or additional information visible in the TUI log pane.
+    /// Use this to be able to read progress messages or additional information visible in the TUI log pane, like the
+    /// throughput of each completed task and the total duration.
     #[clap(long, conflicts_with("quiet"), requires("progress"))]
     pub progress_keep_open: bool,
 
+    /// Write progress to stderr as one line per update in the given format, for consumption by other programs.
+    #[clap(
+        long,
+        conflicts_with("progress"),
+        possible_values(git_features::progress::machine::Format::variants())
+    )]
+    pub progress_format: Option<git_features::progress::machine::Format>,
+
     #[clap(subcommand)]
     pub cmd: Subcommands,
 }
@@ -75,6 +84,26 @@ pub enum ToolCommands {
         ///
         /// Defaults to the current working directory.
         destination_directory: Option<PathBuf>,
+
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        #[clap(long)]
+        /// Fetch all refs of each repository's 'origin' remote once it is in place, deleting refs that were deleted there.
+        ///
+        /// Bare repositories mirror all refs of their remote, whereas repositories with a worktree only update
+        /// their remote-tracking branches and tags.
+        fetch: bool,
+
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        #[clap(long, requires("fetch"))]
+        /// Keep refs that were deleted on the remote when fetching.
+        no_prune: bool,
+
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        #[clap(long, short = 'j', requires("fetch"))]
+        /// The amount of repositories to fetch in parallel.
+        ///
+        /// Defaults to the amount of logical cores.
+        jobs: Option<usize>,
     },
     EstimateHours(EstimateHours),
 }
@@ -102,6 +131,26 @@ pub struct EstimateHours {
     /// due to using different names or email addresses.
     #[clap(short = 'i', long)]
     pub omit_unify_identities: bool,
+    /// Don't use the mailmap of the repository to map names and email addresses of authors to their canonical identity.
+    #[clap(long, conflicts_with("mailmap_file"))]
+    pub ignore_mailmap: bool,
+    /// A mailmap file to use in addition to the one of the repository, whose entries take precedence.
+    #[clap(long, parse(from_os_str))]
+    pub mailmap_file: Option<PathBuf>,
+    /// Estimate the hours for each time bucket of the given size separately, in the timezone of each author.
+    #[clap(long, short = 'b', possible_values(gitoxide_core::hours::Bucket::variants()))]
+    pub bucket: Option<gitoxide_core::hours::Bucket>,
+    /// The longest pause between two commits of an author in minutes which is still considered working on them.
+    #[clap(long, default_value_t = gitoxide_core::hours::DEFAULT_INACTIVITY_THRESHOLD_IN_MINUTES)]
+    pub inactivity_threshold: u32,
+    /// The format to write the estimate in.
+    #[clap(
+        long,
+        short = 'f',
+        default_value = "human",
+        possible_values(gitoxide_core::hours::Format::variants())
+    )]
+    pub format: gitoxide_core::hours::Format,
 }
 
 mod validator {
@@ -52,12 +52,23 @@ pub mod pretty {
 
     use crate::shared::ProgressRange;
 
+    /// How to present progress, if at all.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ProgressMode {
+        /// Display progress as lines if `verbose` is set, or show no progress.
+        Lines,
+        /// Bring up a terminal user interface.
+        Tui,
+        /// Write each progress update to stderr as line in the given machine readable format, whether `verbose` is set or not.
+        Machine(progress::machine::Format),
+    }
+
     pub fn prepare_and_run<T: Send + 'static>(
         name: &str,
         verbose: bool,
-        progress: bool,
+        progress: ProgressMode,
         #[cfg_attr(not(feature = "prodash-render-tui"), allow(unused_variables))] progress_keep_open: bool,
-        #[cfg_attr(not(feature = "prodash-render-line"), allow(unused_variables))] range: impl Into<Option<ProgressRange>>,
+        range: impl Into<Option<ProgressRange>>,
         run: impl FnOnce(
                 progress::DoOrDiscard<prodash::tree::Item>,
                 &mut dyn std::io::Write,
@@ -70,14 +81,33 @@ pub mod pretty {
         crate::shared::init_env_logger();
 
         match (verbose, progress) {
-            (false, false) => {
+            (_, ProgressMode::Machine(format)) => {
+                let progress = prodash::Tree::new();
+                let sub_progress = progress.add_child(name);
+                let handle = progress::machine::render(
+                    stderr(),
+                    std::sync::Arc::downgrade(&progress),
+                    progress::machine::Options {
+                        format,
+                        level_filter: range.into(),
+                        ..Default::default()
+                    },
+                );
+
+                let mut out = Vec::<u8>::new();
+                let res = run(progress::DoOrDiscard::from(Some(sub_progress)), &mut out, &mut stderr());
+                handle.shutdown_and_wait()?;
+                std::io::Write::write_all(&mut stdout(), &out)?;
+                res
+            }
+            (false, ProgressMode::Lines) => {
                 let stdout = stdout();
                 let mut stdout_lock = stdout.lock();
                 let stderr = stderr();
                 let mut stderr_lock = stderr.lock();
                 run(progress::DoOrDiscard::from(None), &mut stdout_lock, &mut stderr_lock)
             }
-            (true, false) => {
+            (true, ProgressMode::Lines) => {
                 let progress = crate::shared::progress_tree();
                 let sub_progress = progress.add_child(name);
 
@@ -91,11 +121,11 @@ pub mod pretty {
                 res
             }
             #[cfg(not(feature = "prodash-render-tui"))]
-            (true, true) | (false, true) => {
+            (_, ProgressMode::Tui) => {
                 unreachable!("BUG: This branch can't be run without a TUI built-in")
             }
             #[cfg(feature = "prodash-render-tui")]
-            (true, true) | (false, true) => {
+            (_, ProgressMode::Tui) => {
                 use std::io::Write;
 
                 use crate::shared;
@@ -105,6 +135,13 @@ pub mod pretty {
                     ComputationDone(Result<T>, Vec<u8>),
                 }
                 let progress = prodash::Tree::new();
+                let summary = progress::summary::render(
+                    &progress,
+                    progress::summary::Options {
+                        frames_per_second: shared::DEFAULT_FRAME_RATE,
+                        ..Default::default()
+                    },
+                );
                 let sub_progress = progress.add_child(name);
                 let render_tui = prodash::render::tui(
                     stdout(),
@@ -142,6 +179,9 @@ pub mod pretty {
                             continue;
                         }
                         Event::ComputationDone(res, out) => {
+                            // Log the throughput of the remaining tasks, and remove the summary to let the UI stop
+                            // unless it should be kept open.
+                            summary.shutdown_and_wait();
                             ui_handle.join().ok();
                             stdout().write_all(&out)?;
                             break res;
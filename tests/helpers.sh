@@ -14,6 +14,10 @@ function remove-paths() {
   sed -E 's#/.*#"#g'
 }
 
+function remove-hashes() {
+  sed -E 's/[0-9a-f]{40}/<hash>/g'
+}
+
 function repo-with-remotes() {
   if [[ $((($# - 1) % 2)) != 0 ]] || [[ $# = 0 ]]; then
     echo "need <path> (<remote> <url>)[,...] tuples"
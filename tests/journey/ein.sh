@@ -130,6 +130,77 @@ title "Porcelain ${kind}"
           )
         )
       )
+      (with "a bare repository whose origin is a local repository"
+        (sandbox
+          {
+            git init remote
+            (cd remote
+              git checkout -b main
+              git config commit.gpgsign false
+              touch a
+              git add a
+              git commit -m "first"
+              git tag unannotated
+              git branch dev
+              git symbolic-ref refs/heads/alias refs/heads/main
+            )
+            git init --bare mirror.git
+            git -C mirror.git remote add origin https://example.com/mirror.git
+            git -C mirror.git config url."file://$PWD/remote".insteadOf https://example.com/mirror.git
+          } &>/dev/null
+          snapshot="$snapshot/tools/organize/fetch"
+
+          title "gix tools organize --fetch"
+          (when "running 'organize --execute --fetch' for the first time"
+            it "succeeds" && {
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --execute --fetch 2>/dev/null"
+            }
+
+            it "mirrors all refs of the remote" && {
+              WITH_SNAPSHOT="$snapshot/refs-after-first-fetch" \
+              expect_run $SUCCESSFULLY git -C example.com/mirror.git for-each-ref
+            }
+
+            it "keeps symbolic refs symbolic" && {
+              WITH_SNAPSHOT="$snapshot/symbolic-ref" \
+              expect_run $SUCCESSFULLY git -C example.com/mirror.git symbolic-ref refs/heads/alias
+            }
+          )
+
+          (cd remote
+            touch b
+            git add b
+            git commit -m "second"
+            git branch feature
+          ) &>/dev/null
+          (when "running 'organize --execute --fetch' after the remote changed"
+            it "succeeds" && {
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --execute --fetch 2>/dev/null"
+            }
+
+            it "updates moved refs and adds new ones" && {
+              WITH_SNAPSHOT="$snapshot/refs-after-incremental-fetch" \
+              expect_run $SUCCESSFULLY git -C example.com/mirror.git for-each-ref
+            }
+
+            it "receives all objects needed by the refs" && {
+              expect_run $SUCCESSFULLY git -C example.com/mirror.git fsck --connectivity-only
+            }
+          )
+
+          git -C remote branch -D dev &>/dev/null
+          (when "running 'organize --execute --fetch' after a branch was deleted on the remote"
+            it "succeeds" && {
+              expect_run_sh $SUCCESSFULLY "$exe tools organize --execute --fetch 2>/dev/null"
+            }
+
+            it "deletes the ref locally as well" && {
+              WITH_SNAPSHOT="$snapshot/refs-after-pruning-fetch" \
+              expect_run $SUCCESSFULLY git -C example.com/mirror.git for-each-ref
+            }
+          )
+        )
+      )
     )
   )
 
@@ -67,6 +67,50 @@ title "gix repository"
       )
       fi
     )
+    (with "the 'odb cat' sub-command"
+      snapshot="$snapshot/odb/cat"
+      (with "--batch"
+        it "prints the header and data of each object named on stdin, and reports missing ones" && {
+          WITH_SNAPSHOT="$snapshot/batch-success" \
+          expect_run_sh $SUCCESSFULLY "printf 'HEAD\nannotated\n45b983be36b73c0788dc9cbcb76cbb80fc7bb057\n0000000000000000000000000000000000000001\nnot-a-ref\n' | $exe_plumbing repository odb cat --batch"
+        }
+      )
+      (with "--batch-check"
+        it "prints only the header of each object named on stdin" && {
+          WITH_SNAPSHOT="$snapshot/batch-check-success" \
+          expect_run_sh $SUCCESSFULLY "printf '68550577632771ffcf05859e6267fac5e026334d\nefa596d621559707b2d221f10490959b2decbc6c\nHEAD\nannotated\nnot-a-ref\n' | $exe_plumbing repository odb cat --batch-check"
+        }
+      )
+    )
+  )
+)
+
+title "gix clone"
+(when "running 'clone'"
+  snapshot="$snapshot/clone"
+  (small-repo-in-sandbox
+    if [[ "$kind" != 'small' && "$kind" != 'async' ]]; then
+    (with "a local repository"
+      it "generates the correct output" && {
+        WITH_SNAPSHOT="$snapshot/file-output" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" clone .git cloned
+      }
+      (cd cloned
+        it "writes an index matching the checked out worktree" && {
+          WITH_SNAPSHOT="$snapshot/status-after-clone" \
+          expect_run $SUCCESSFULLY git status --porcelain
+        }
+        it "configures the remote and the upstream branch" && {
+          WITH_SNAPSHOT="$snapshot/remote-and-branch-config" \
+          expect_run $SUCCESSFULLY git config --get-regexp '^(remote|branch)\.'
+        }
+        it "creates remote tracking branches and tags" && {
+          WITH_SNAPSHOT="$snapshot/refs" \
+          expect_run $SUCCESSFULLY git for-each-ref --format '%(objectname) %(refname)'
+        }
+      )
+    )
+    fi
   )
 )
 
@@ -138,6 +182,19 @@ title "gix pack"
             }
             rm -Rf out
           )
+          (with "--resume and a partially received pack in the output directory"
+            mkdir out/
+            git rev-list --objects --all | git pack-objects --stdout > full.pack
+            head -c $(($(wc -c < full.pack) / 2)) full.pack > out/pack-receive.partial
+            echo "url .git" > out/pack-receive.state
+            it "salvages the partial pack and receives the remaining objects" && {
+              expect_run $SUCCESSFULLY "$exe_plumbing" pack receive --resume .git out/
+            }
+            it "removes the partial pack and the state once everything was received" && {
+              expect_run $WITH_FAILURE test -e out/pack-receive.partial -o -e out/pack-receive.state
+            }
+            rm -Rf out full.pack
+          )
           if test "$kind" = "max"; then
           (with "--format json"
             it "generates the correct output in JSON format" && {
@@ -308,6 +365,28 @@ title "gix pack"
             )
           )
       )
+      title "gix pack multi-index add"
+      (with "the 'add' sub-command"
+          snapshot="$snapshot/add"
+          (with 'a multi-index of a single pack'
+            (sandbox
+              cp $fixtures/packs/pack-* .
+              $exe_plumbing pack multi-index -i multi-pack-index create pack-11fdfa9e156ab73caae3b6da867192221f2089c2.idx
+              it "adds another pack index successfully" && {
+                expect_run $SUCCESSFULLY "$exe_plumbing" pack multi-index -i multi-pack-index add pack-c0438c19fb16422b6bbcce24387b3264416d485b.idx
+              }
+              it "writes a multi-index which verifies successfully" && {
+                expect_run $SUCCESSFULLY "$exe_plumbing" pack multi-index -i multi-pack-index verify
+              }
+              it "writes the same entries as if the multi-index was created from both pack indices" && {
+                $exe_plumbing pack multi-index -i multi-pack-index entries > added-entries
+                $exe_plumbing pack multi-index -i created-multi-pack-index create *.idx
+                $exe_plumbing pack multi-index -i created-multi-pack-index entries > created-entries
+                expect_run $SUCCESSFULLY cmp added-entries created-entries
+              }
+            )
+          )
+      )
   )
 
   title "gix pack explode"
@@ -525,12 +604,10 @@ title "gix pack"
   )
 )
 
-title "gix remote"
-(when "running 'remote'"
-  snapshot="$snapshot/remote"
-  title "gix remote ref-list"
-  (with "the 'ref-list' subcommand"
-    snapshot="$snapshot/ref-list"
+title "gix ls-remote"
+(when "running 'ls-remote'"
+  snapshot="$snapshot/ls-remote"
+  (with "a local repository"
     (small-repo-in-sandbox
       if [[ "$kind" != "small" ]]; then
 
@@ -539,20 +616,26 @@ title "gix remote"
         (with "version 1"
           it "generates the correct output" && {
             WITH_SNAPSHOT="$snapshot/file-v-any" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list -p 1 .git
+            expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote -p 1 .git
           }
         )
         (with "version 2"
           it "generates the correct output" && {
             WITH_SNAPSHOT="$snapshot/file-v-any" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list --protocol 2 "$PWD/.git"
+            expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote --protocol 2 "$PWD/.git"
+          }
+        )
+        (with "--refs-prefix"
+          it "generates the correct output" && {
+            WITH_SNAPSHOT="$snapshot/file-v-any-refs-prefix" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote --refs-prefix refs/heads/ .git
           }
         )
         if test "$kind" = "max"; then
         (with "--format json"
           it "generates the correct output in JSON format" && {
             WITH_SNAPSHOT="$snapshot/file-v-any-json" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" --format json remote ref-list .git
+            expect_run $SUCCESSFULLY "$exe_plumbing" --format json ls-remote .git
           }
         )
         fi
@@ -564,13 +647,13 @@ title "gix remote"
         (with "version 1"
           it "generates the correct output" && {
             WITH_SNAPSHOT="$snapshot/file-v-any" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list -p 1 git://localhost/
+            expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote -p 1 git://localhost/
           }
         )
         (with "version 2"
           it "generates the correct output" && {
             WITH_SNAPSHOT="$snapshot/file-v-any" \
-            expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list -p 2 git://localhost/
+            expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote -p 2 git://localhost/
           }
         )
       )
@@ -578,7 +661,7 @@ title "gix remote"
       (with "https:// protocol (in small builds)"
         it "fails as http is not compiled in" && {
           WITH_SNAPSHOT="$snapshot/fail-http-in-small" \
-          expect_run $WITH_FAILURE "$exe_plumbing" remote ref-list -p 1 https://github.com/byron/gitoxide
+          expect_run $WITH_FAILURE "$exe_plumbing" ls-remote -p 1 https://github.com/byron/gitoxide
         }
       )
       fi
@@ -587,12 +670,12 @@ title "gix remote"
         (with "https:// protocol"
           (with "version 1"
             it "generates the correct output" && {
-              expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list -p 1 https://github.com/byron/gitoxide
+              expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote -p 1 https://github.com/byron/gitoxide
             }
           )
           (with "version 2"
             it "generates the correct output" && {
-              expect_run $SUCCESSFULLY "$exe_plumbing" remote ref-list -p 2 https://github.com/byron/gitoxide
+              expect_run $SUCCESSFULLY "$exe_plumbing" ls-remote -p 2 https://github.com/byron/gitoxide
             }
           )
         )
@@ -600,8 +683,8 @@ title "gix remote"
       )
       else
         it "fails as the CLI doesn't include networking in 'small' mode" && {
-          WITH_SNAPSHOT="$snapshot/remote ref-list-no-networking-in-small-failure" \
-          expect_run 2 "$exe_plumbing" remote ref-list -p 1 .git
+          WITH_SNAPSHOT="$snapshot/ls-remote-no-networking-in-small-failure" \
+          expect_run 2 "$exe_plumbing" ls-remote -p 1 .git
         }
       fi
     )
@@ -609,6 +692,243 @@ title "gix remote"
 )
 
 
+title "gix remote"
+(when "running 'remote'"
+  snapshot="$snapshot/remote"
+
+  (small-repo-in-sandbox
+    (with "the 'add' sub-command"
+      it "configures the url and the fetch refspec" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" remote add origin https://example.com/repo.git
+        WITH_SNAPSHOT="$snapshot/config-after-add" \
+        expect_run $SUCCESSFULLY git config --get-regexp '^remote\.'
+      }
+      it "refuses to add a remote with the same name" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" remote add origin https://example.com/other.git
+      }
+    )
+    (with "the 'set-url' sub-command"
+      it "replaces the url" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" remote set-url origin https://example.com/other.git
+        WITH_SNAPSHOT="$snapshot/config-after-set-url" \
+        expect_run $SUCCESSFULLY git config --get-regexp '^remote\.'
+      }
+      it "adds a url to push to with --push" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" remote set-url --push origin https://example.com/push.git
+        WITH_SNAPSHOT="$snapshot/config-after-set-push-url" \
+        expect_run $SUCCESSFULLY git config --get-regexp '^remote\.'
+      }
+      it "fails for unknown remotes" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" remote set-url unknown https://example.com/repo.git
+      }
+    )
+    (with "the 'remove' sub-command"
+      {
+        git config branch.main.remote origin
+        git config branch.main.merge refs/heads/main
+        git update-ref refs/remotes/origin/main HEAD
+      } &>/dev/null
+      it "removes the remote and the upstream configuration of branches tracking it" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" remote remove origin
+        expect_run $WITH_FAILURE git config --get-regexp '^(remote|branch)\.'
+      }
+      it "removes its remote-tracking branches" && {
+        expect_run_sh $SUCCESSFULLY 'test -z "$(git for-each-ref refs/remotes/)"'
+      }
+      it "fails for unknown remotes" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" remote remove origin
+      }
+    )
+  )
+)
+
+
+
+title "gix config"
+(when "running 'config'"
+  snapshot="$snapshot/config"
+  title "gix config set"
+  (with "the 'set' sub-command"
+    snapshot="$snapshot/set"
+
+    (small-repo-in-sandbox
+      (with "--type bool"
+        it "stores the normalized value" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" config set --type bool section.bool yes
+          WITH_SNAPSHOT="$snapshot/bool-is-normalized" \
+          expect_run $SUCCESSFULLY git config section.bool
+        }
+      )
+      (with "--type path"
+        it "stores the path as given to expand it when reading" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" config set --type path section.path '~/file'
+          WITH_SNAPSHOT="$snapshot/path-is-stored-as-given" \
+          expect_run $SUCCESSFULLY git config section.path
+        }
+      )
+      (with "a key with multiple values"
+        git config section.multi one
+        git config --add section.multi two
+        it "refuses to overwrite them" && {
+          expect_run $WITH_FAILURE "$exe_plumbing" config set section.multi three
+        }
+        it "replaces all of them with --replace-all" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" config set --replace-all section.multi three
+          WITH_SNAPSHOT="$snapshot/all-values-are-replaced" \
+          expect_run $SUCCESSFULLY git config --get-all section.multi
+        }
+      )
+    )
+  )
+  title "gix config list"
+  (with "the 'list' sub-command"
+    snapshot="$snapshot/list"
+
+    (small-repo-in-sandbox
+      git config include.path included
+      printf '[section]\n\tbare\n\tvalue = included\n' > .git/included
+      (with "--show-origin"
+        it "shows the included file as origin of its values and keys without value without '='" && {
+          WITH_SNAPSHOT="$snapshot/show-origin-of-included-values" \
+          expect_run_sh $SUCCESSFULLY "$exe_plumbing config list --show-origin | grep section. | sed -E 's|file:.*\.git/|file:.git/|'"
+        }
+      )
+    )
+  )
+)
+
+title "gix log"
+(when "running 'log'"
+  snapshot="$snapshot/log"
+
+  (small-repo-in-sandbox
+    (with "a path changed by two commits"
+      it "shows only these commits" && {
+        WITH_SNAPSHOT="$snapshot/path-changed-by-two-commits-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" log --oneline -- b
+      }
+    )
+    (with "a path only added by the root commit"
+      it "shows only the root commit" && {
+        WITH_SNAPSHOT="$snapshot/path-added-by-root-commit-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" log --oneline -- a
+      }
+    )
+    (with "a commit-graph with changed-path Bloom filters"
+      git commit-graph write --reachable --changed-paths &>/dev/null
+      it "shows the same commits" && {
+        WITH_SNAPSHOT="$snapshot/path-changed-by-two-commits-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" log --oneline -- b
+      }
+    )
+  )
+)
+
+title "gix index"
+(when "running 'index'"
+  snapshot="$snapshot/index"
+
+  (small-repo-in-sandbox
+    {
+      git checkout -b other dev
+      echo other > b
+      git commit -am other
+      git checkout main
+      git merge other
+    } &>/dev/null
+    (with "the 'conflicts' sub-command"
+      (with "an index with a conflicting path"
+        it "lists all of its stages" && {
+          WITH_SNAPSHOT="$snapshot/conflicts-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" index conflicts
+        }
+        if test "$kind" = "max"; then
+        (with "--format json"
+          it "groups the stages by path" && {
+            WITH_SNAPSHOT="$snapshot/conflicts-json-success" \
+            expect_run $SUCCESSFULLY "$exe_plumbing" --format json index conflicts
+          }
+        )
+        fi
+      )
+    )
+    (with "the 'verify' sub-command"
+      (with "a valid index"
+        it "reports each check as OK" && {
+          WITH_SNAPSHOT="$snapshot/verify-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" index verify
+        }
+      )
+      (with "an index with a corrupted checksum"
+        cp .git/index corrupted-index
+        printf '%020d' 0 | dd of=corrupted-index bs=1 seek=$(( $(wc -c < corrupted-index) - 20 )) conv=notrunc &>/dev/null
+        it "reports the failed check and fails" && {
+          SNAPSHOT_FILTER=remove-hashes \
+          WITH_SNAPSHOT="$snapshot/verify-corrupted-checksum-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" index -i corrupted-index verify
+        }
+      )
+    )
+  )
+)
+
+title "gix fsck"
+(when "running 'fsck'"
+  snapshot="$snapshot/fsck"
+
+  (small-repo-in-sandbox
+    git reset --hard HEAD~1 &>/dev/null
+    (with "a commit that is only reachable through reflogs"
+      it "considers it reachable" && {
+        WITH_SNAPSHOT="$snapshot/reachable-through-reflog-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" fsck --min-severity info
+      }
+    )
+    (with "expired reflogs"
+      git reflog expire --expire=now --all
+      it "reports the commit and the objects only it refers to as unreachable" && {
+        WITH_SNAPSHOT="$snapshot/unreachable-after-reflog-expiry-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" fsck --min-severity info
+      }
+    )
+  )
+)
+
+title "gix worktree"
+(when "running 'worktree'"
+  (small-repo-in-sandbox
+    (with "a clean linked worktree"
+      "$exe_plumbing" worktree add linked &>/dev/null
+      it "removes it without --force" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" worktree remove linked
+      }
+      it "removes its administrative files as well" && {
+        expect_run $WITH_FAILURE test -e .git/worktrees/linked
+      }
+    )
+    (with "a linked worktree with local modifications"
+      "$exe_plumbing" worktree add --detach modified &>/dev/null
+      echo "change" > modified/a
+      it "refuses to remove it" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" worktree remove modified
+      }
+      it "removes it with --force" && {
+        expect_run $SUCCESSFULLY "$exe_plumbing" worktree remove --force modified
+      }
+    )
+    (with "an invalid name for the new branch"
+      it "fails" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" worktree add -b "invalid..name" failed
+      }
+      it "leaves no worktree directory behind" && {
+        expect_run $WITH_FAILURE test -e failed
+      }
+      it "leaves no administrative files behind" && {
+        expect_run $WITH_FAILURE test -e .git/worktrees/failed
+      }
+    )
+  )
+)
 
 title "gix commit-graph"
 (when "running 'commit-graph'"
@@ -637,4 +957,29 @@ title "gix commit-graph"
       )
     )
   )
+  title "gix commit-graph write"
+  (with "the 'write' sub-command"
+    snapshot="$snapshot/write"
+
+    (small-repo-in-sandbox
+      (with "a revision to start from"
+        it "writes a commit-graph that git considers valid" && {
+          expect_run $SUCCESSFULLY "$exe_plumbing" commit-graph write main
+          expect_run $SUCCESSFULLY git commit-graph verify
+        }
+      )
+      (with "a negated revision"
+        it "fails as the commit-graph would lack ancestors" && {
+          WITH_SNAPSHOT="$snapshot/excluded-revision-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" commit-graph write main ^main~1
+        }
+      )
+      (with "a range"
+        it "fails as the commit-graph would lack ancestors" && {
+          WITH_SNAPSHOT="$snapshot/excluded-revision-failure" \
+          expect_run $WITH_FAILURE "$exe_plumbing" commit-graph write main~1..main
+        }
+      )
+    )
+  )
 )